@@ -0,0 +1,1229 @@
+//! Availability domain model shared by the server fns that answer "when can
+//! this artist be booked" and the artist's calendar. Callers load an artist's
+//! business hours, overrides, recurring rules, bookings and busy time from
+//! other calendars into a [`Schedule`]; everything here is pure and knows
+//! nothing about the database.
+//!
+//! Days of the week are numbered from Sunday = 0, matching the
+//! `day_of_week` columns.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+
+/// Session length for artists who haven't set one
+pub const DEFAULT_BOOKING_MINUTES: i64 = 60;
+/// Bounds on an artist's [`SlotSettings`], in minutes
+pub const SESSION_MINUTES_RANGE: (i64, i64) = (15, 12 * 60);
+pub const SLOT_MINUTES_RANGE: (i64, i64) = (5, 4 * 60);
+pub const BUFFER_MINUTES_RANGE: (i64, i64) = (0, 4 * 60);
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// 23:59:59, the end of ranges that run to the end of the day
+fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN)
+}
+
+/// Parses `HH:MM` or `HH:MM:SS`; empty or malformed times are `None`
+pub fn parse_time(time: &str) -> Option<NaiveTime> {
+    let time = time.trim();
+    NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()
+}
+
+/// Parses a `YYYY-MM-DD` date
+pub fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()
+}
+
+/// Sunday = 0 … Saturday = 6
+pub fn day_of_week(date: NaiveDate) -> u32 {
+    date.weekday().num_days_from_sunday()
+}
+
+/// Opening hours for one day of the week
+#[derive(Clone, Debug, PartialEq)]
+pub struct BusinessHours {
+    pub day_of_week: u32,
+    pub start: Option<NaiveTime>,
+    pub end: Option<NaiveTime>,
+    pub is_closed: bool,
+}
+
+impl BusinessHours {
+    /// Open hours as `(start, end)`, if the day is open with both times set
+    /// and closing after opening. Hours can't run past midnight.
+    pub fn open_hours(&self) -> Option<(NaiveTime, NaiveTime)> {
+        match (self.is_closed, self.start, self.end) {
+            (false, Some(start), Some(end)) if end > start => Some((start, end)),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let Some(day) = WEEKDAY_NAMES.get(self.day_of_week as usize) else {
+            return Err("Days of the week run from 0 (Sunday) to 6".to_string());
+        };
+        if self.is_closed {
+            return Ok(());
+        }
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if end > start => Ok(()),
+            (Some(_), Some(_)) => Err(format!("{} closes before it opens", day)),
+            _ => Err(format!(
+                "Give {} an opening and a closing time, or mark it closed",
+                day
+            )),
+        }
+    }
+}
+
+/// Checks a week of business hours: every day valid and given only once, so
+/// no two sets of hours overlap on the same day
+pub fn validate_week(hours: &[BusinessHours]) -> Result<(), String> {
+    let mut seen = [false; 7];
+    for day in hours {
+        day.validate()?;
+        let seen = &mut seen[day.day_of_week as usize];
+        if *seen {
+            return Err(format!(
+                "{} has more than one set of hours",
+                WEEKDAY_NAMES[day.day_of_week as usize]
+            ));
+        }
+        *seen = true;
+    }
+    Ok(())
+}
+
+/// An artist marking one date as available or not
+#[derive(Clone, Debug, PartialEq)]
+pub struct Override {
+    pub date: NaiveDate,
+    pub available: bool,
+}
+
+/// How long [`RecurringRule::next_occurrence`] looks ahead; long enough for a
+/// February 29 rule to come round
+const OCCURRENCE_SEARCH_DAYS: i64 = 366 * 8;
+
+/// The Sunday starting `date`'s week
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(i64::from(day_of_week(date)))
+}
+
+fn ordinal(nth: i32) -> &'static str {
+    match nth {
+        1 => "1st",
+        2 => "2nd",
+        3 => "3rd",
+        4 => "4th",
+        5 => "5th",
+        _ => "Last",
+    }
+}
+
+/// How often a recurring rule comes round
+#[derive(Clone, Debug, PartialEq)]
+pub enum Recurrence {
+    /// The listed weekdays, every `interval` weeks counted from the week the
+    /// rule starts
+    Weekly { weekdays: Vec<u32>, interval: u32 },
+    /// One weekday a month: `nth` is 1 to 5, or -1 for the last
+    MonthlyWeekday { weekday: u32, nth: i32 },
+    /// The same dates every year, as `(month, day)`
+    Yearly { dates: Vec<(u32, u32)> },
+}
+
+impl Recurrence {
+    fn matches(&self, date: NaiveDate, starts_on: Option<NaiveDate>) -> bool {
+        match self {
+            Recurrence::Weekly { weekdays, interval } => {
+                if !weekdays.contains(&day_of_week(date)) {
+                    return false;
+                }
+                match starts_on {
+                    Some(starts_on) if *interval > 1 => {
+                        let weeks = (week_start(date) - week_start(starts_on)).num_weeks();
+                        weeks >= 0 && weeks % i64::from(*interval) == 0
+                    }
+                    _ => true,
+                }
+            }
+            Recurrence::MonthlyWeekday { weekday, nth } => {
+                if day_of_week(date) != *weekday {
+                    return false;
+                }
+                if *nth == -1 {
+                    (date + Duration::days(7)).month() != date.month()
+                } else {
+                    ((date.day() - 1) / 7 + 1) as i32 == *nth
+                }
+            }
+            Recurrence::Yearly { dates } => dates.contains(&(date.month(), date.day())),
+        }
+    }
+
+    /// How narrowly the recurrence picks out days; when rules disagree about
+    /// a date the narrower one decides it
+    fn specificity(&self) -> u8 {
+        match self {
+            Recurrence::Weekly { .. } => 0,
+            Recurrence::MonthlyWeekday { .. } => 1,
+            Recurrence::Yearly { .. } => 2,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Recurrence::Weekly { weekdays, interval } => {
+                if weekdays.is_empty() {
+                    return Err("Pick at least one day of the week".to_string());
+                }
+                if weekdays.iter().any(|day| *day > 6) {
+                    return Err("Days of the week run from 0 (Sunday) to 6".to_string());
+                }
+                if !(1..=52).contains(interval) {
+                    return Err("A weekly rule repeats every 1 to 52 weeks".to_string());
+                }
+            }
+            Recurrence::MonthlyWeekday { weekday, nth } => {
+                if *weekday > 6 {
+                    return Err("Days of the week run from 0 (Sunday) to 6".to_string());
+                }
+                if !(1..=5).contains(nth) && *nth != -1 {
+                    return Err("Pick the 1st to 5th or the last week of the month".to_string());
+                }
+            }
+            Recurrence::Yearly { dates } => {
+                if dates.is_empty() {
+                    return Err("Pick at least one date".to_string());
+                }
+                // 2000 was a leap year, so February 29 is allowed
+                if let Some((month, day)) = dates
+                    .iter()
+                    .find(|(month, day)| NaiveDate::from_ymd_opt(2000, *month, *day).is_none())
+                {
+                    return Err(format!("{}/{} isn't a date", month, day));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// e.g. "Every Saturday, Sunday", "Last Friday of the month" or
+    /// "Every year on December 25"
+    pub fn describe(&self) -> String {
+        match self {
+            Recurrence::Weekly { weekdays, interval } => {
+                let mut days = weekdays.clone();
+                days.sort_unstable();
+                days.dedup();
+                let days = if days.len() == 7 {
+                    "day".to_string()
+                } else {
+                    days.iter()
+                        .filter_map(|day| WEEKDAY_NAMES.get(*day as usize))
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                if *interval > 1 {
+                    format!("Every {} weeks on {}", interval, days)
+                } else {
+                    format!("Every {}", days)
+                }
+            }
+            Recurrence::MonthlyWeekday { weekday, nth } => format!(
+                "{} {} of the month",
+                ordinal(*nth),
+                WEEKDAY_NAMES.get(*weekday as usize).unwrap_or(&"day")
+            ),
+            Recurrence::Yearly { dates } => {
+                let dates = dates
+                    .iter()
+                    .filter_map(|(month, day)| NaiveDate::from_ymd_opt(2000, *month, *day))
+                    .map(|date| format!("{} {}", date.format("%B"), date.day()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("Every year on {}", dates)
+            }
+        }
+    }
+}
+
+/// A rule from the artist's recurring rules list
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurringRule {
+    pub recurrence: Recurrence,
+    /// First and last dates the rule applies on, inclusive
+    pub starts_on: Option<NaiveDate>,
+    pub ends_on: Option<NaiveDate>,
+    /// Dates the rule would match but skips
+    pub exceptions: Vec<NaiveDate>,
+    /// The part of the day the rule covers; neither set means all day
+    pub start: Option<NaiveTime>,
+    pub end: Option<NaiveTime>,
+    /// Whether matching time becomes available (`action = 'available'`) or blocked
+    pub available: bool,
+}
+
+impl RecurringRule {
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        self.starts_on.is_none_or(|starts_on| date >= starts_on)
+            && self.ends_on.is_none_or(|ends_on| date <= ends_on)
+            && !self.exceptions.contains(&date)
+            && self.recurrence.matches(date, self.starts_on)
+    }
+
+    /// The hours the rule covers, or `None` for an all-day rule
+    pub fn hours(&self) -> Option<(NaiveTime, NaiveTime)> {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if end > start => Some((start, end)),
+            _ => None,
+        }
+    }
+
+    pub fn is_all_day(&self) -> bool {
+        self.hours().is_none()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.recurrence.validate()?;
+        if let Recurrence::Weekly { interval, .. } = self.recurrence {
+            if interval > 1 && self.starts_on.is_none() {
+                return Err("A rule that skips weeks needs a start date".to_string());
+            }
+        }
+        if let (Some(starts_on), Some(ends_on)) = (self.starts_on, self.ends_on) {
+            if ends_on < starts_on {
+                return Err("The rule can't end before it starts".to_string());
+            }
+        }
+        match (self.start, self.end) {
+            (None, None) => Ok(()),
+            (Some(start), Some(end)) if end > start => Ok(()),
+            (Some(_), Some(_)) => Err("The end time must be after the start time".to_string()),
+            _ => Err("Give both a start and an end time, or neither for all day".to_string()),
+        }
+    }
+
+    /// Dates in `start..=end` the rule applies on
+    pub fn occurrences(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = NaiveDate> + '_ {
+        start
+            .iter_days()
+            .take_while(move |date| *date <= end)
+            .filter(|date| self.matches(*date))
+    }
+
+    /// The first date on or after `from` the rule applies on
+    pub fn next_occurrence(&self, from: NaiveDate) -> Option<NaiveDate> {
+        let from = self.starts_on.map_or(from, |starts_on| from.max(starts_on));
+        self.occurrences(from, from + Duration::days(OCCURRENCE_SEARCH_DAYS))
+            .next()
+    }
+
+    /// The rule split so each part is a single RRULE series: a yearly rule
+    /// with several dates becomes one rule per date
+    pub fn series(&self) -> Vec<RecurringRule> {
+        match &self.recurrence {
+            Recurrence::Yearly { dates } if dates.len() > 1 => dates
+                .iter()
+                .map(|date| RecurringRule {
+                    recurrence: Recurrence::Yearly { dates: vec![*date] },
+                    ..self.clone()
+                })
+                .collect(),
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// RFC 5545 RRULE value, e.g. `FREQ=WEEKLY;BYDAY=SA,SU`. Only exact for
+    /// rules from [`RecurringRule::series`]; exceptions go in EXDATEs.
+    pub fn rrule(&self) -> String {
+        const BYDAY: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+        let byday = |day: &u32| BYDAY.get(*day as usize).copied().unwrap_or("SU");
+
+        let mut rrule = match &self.recurrence {
+            Recurrence::Weekly { weekdays, interval } => format!(
+                "FREQ=WEEKLY;INTERVAL={};WKST=SU;BYDAY={}",
+                interval,
+                weekdays.iter().map(byday).collect::<Vec<_>>().join(",")
+            ),
+            Recurrence::MonthlyWeekday { weekday, nth } => {
+                format!("FREQ=MONTHLY;BYDAY={}{}", nth, byday(weekday))
+            }
+            Recurrence::Yearly { dates } => {
+                let (months, days): (Vec<_>, Vec<_>) = dates
+                    .iter()
+                    .map(|(month, day)| (month.to_string(), day.to_string()))
+                    .unzip();
+                format!(
+                    "FREQ=YEARLY;BYMONTH={};BYMONTHDAY={}",
+                    months.join(","),
+                    days.join(",")
+                )
+            }
+        };
+        if let Some(ends_on) = self.ends_on {
+            rrule.push_str(&format!(";UNTIL={}", ends_on.format("%Y%m%d")));
+        }
+        rrule
+    }
+}
+
+/// How an artist's days are divided into appointments
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlotSettings {
+    /// Length of a standard session
+    pub session_minutes: i64,
+    /// Offered start times are this far apart
+    pub slot_minutes: i64,
+    /// Kept free before and after every booking
+    pub buffer_minutes: i64,
+}
+
+impl Default for SlotSettings {
+    fn default() -> Self {
+        SlotSettings {
+            session_minutes: DEFAULT_BOOKING_MINUTES,
+            slot_minutes: DEFAULT_BOOKING_MINUTES,
+            buffer_minutes: 0,
+        }
+    }
+}
+
+impl SlotSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, value: i64, (min, max): (i64, i64)| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{} must be between {} and {} minutes",
+                    name, min, max
+                ))
+            }
+        };
+        check(
+            "Session length",
+            self.session_minutes,
+            SESSION_MINUTES_RANGE,
+        )?;
+        check(
+            "Time between start times",
+            self.slot_minutes,
+            SLOT_MINUTES_RANGE,
+        )?;
+        check("Buffer", self.buffer_minutes, BUFFER_MINUTES_RANGE)
+    }
+
+    /// Minutes to set aside for a tattoo `size_inches` across: a standard
+    /// session up to 3", two up to 6", three up to 10" and four beyond. An
+    /// unknown size gets a standard session.
+    pub fn session_minutes_for(&self, size_inches: Option<f64>) -> i64 {
+        let sessions = match size_inches.filter(|size| *size > 0.0) {
+            None => 1,
+            Some(size) if size <= 3.0 => 1,
+            Some(size) if size <= 6.0 => 2,
+            Some(size) if size <= 10.0 => 3,
+            Some(_) => 4,
+        };
+        self.session_minutes * sessions
+    }
+}
+
+/// `time` moved by `minutes`, kept within the day
+fn shift(time: NaiveTime, minutes: i64) -> NaiveTime {
+    let (shifted, wrapped) = time.overflowing_add_signed(Duration::minutes(minutes));
+    match wrapped {
+        0 => shifted,
+        w if w < 0 => NaiveTime::MIN,
+        _ => end_of_day(),
+    }
+}
+
+/// A pending or approved booking occupying part of a day
+#[derive(Clone, Debug, PartialEq)]
+pub struct Booking {
+    pub date: NaiveDate,
+    pub start: Option<NaiveTime>,
+    pub end: Option<NaiveTime>,
+}
+
+impl Booking {
+    /// Occupied time range; a missing end means `session_minutes`, and a
+    /// booking without a start time takes the whole day
+    fn range(&self, session_minutes: i64) -> (NaiveTime, NaiveTime) {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if end > start => (start, end),
+            (Some(start), _) => (start, shift(start, session_minutes)),
+            (None, _) => (NaiveTime::MIN, end_of_day()),
+        }
+    }
+
+    /// Whether `start..end` falls within the booking or the buffer around it
+    fn overlaps(&self, start: NaiveTime, end: NaiveTime, settings: &SlotSettings) -> bool {
+        let (booked_start, booked_end) = self.range(settings.session_minutes);
+        let booked_start = shift(booked_start, -settings.buffer_minutes);
+        let booked_end = shift(booked_end, settings.buffer_minutes);
+        start < booked_end && booked_start < end
+    }
+}
+
+/// Time the artist is busy elsewhere, from an event on a calendar they
+/// imported. Unlike a booking it blocks exactly its own time, with no
+/// buffer around it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BusyBlock {
+    pub date: NaiveDate,
+    /// `(start, end)` on the date, `None` for the whole day
+    pub hours: Option<(NaiveTime, NaiveTime)>,
+}
+
+impl BusyBlock {
+    fn range(&self) -> (NaiveTime, NaiveTime) {
+        self.hours.unwrap_or((NaiveTime::MIN, end_of_day()))
+    }
+}
+
+/// One bookable appointment on a day
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slot {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub available: bool,
+}
+
+/// Everything that decides an artist's availability over a range of dates
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schedule {
+    pub business_hours: Vec<BusinessHours>,
+    pub overrides: Vec<Override>,
+    pub rules: Vec<RecurringRule>,
+    pub bookings: Vec<Booking>,
+    pub busy: Vec<BusyBlock>,
+    pub slot_settings: SlotSettings,
+}
+
+impl Schedule {
+    pub fn hours_for(&self, date: NaiveDate) -> Option<&BusinessHours> {
+        let day = day_of_week(date);
+        self.business_hours.iter().find(|h| h.day_of_week == day)
+    }
+
+    /// An explicit override for the date
+    pub fn override_for(&self, date: NaiveDate) -> Option<bool> {
+        self.overrides
+            .iter()
+            .find(|o| o.date == date)
+            .map(|o| o.available)
+    }
+
+    /// The all-day rule that decides `date`: the narrowest recurrence among
+    /// the rules matching it, with blocking winning a tie
+    pub fn deciding_rule(&self, date: NaiveDate) -> Option<&RecurringRule> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.is_all_day() && rule.matches(date))
+            .max_by_key(|rule| (rule.recurrence.specificity(), !rule.available))
+    }
+
+    /// Hours on `date` blocked by rules that cover part of the day, or by
+    /// busy time from other calendars
+    pub fn blocked_hours(&self, date: NaiveDate) -> Vec<(NaiveTime, NaiveTime)> {
+        self.rules
+            .iter()
+            .filter(|rule| !rule.available && rule.matches(date))
+            .filter_map(RecurringRule::hours)
+            .chain(
+                self.busy
+                    .iter()
+                    .filter(|block| block.date == date)
+                    .map(BusyBlock::range),
+            )
+            .collect()
+    }
+
+    /// Whether another calendar has the artist busy all of `date`
+    pub fn is_busy_all_day(&self, date: NaiveDate) -> bool {
+        self.busy
+            .iter()
+            .any(|block| block.date == date && block.hours.is_none())
+    }
+
+    pub fn is_booked(&self, date: NaiveDate) -> bool {
+        self.bookings.iter().any(|b| b.date == date)
+    }
+
+    /// Whether the artist works on `date`: an override decides if there is
+    /// one, then the deciding all-day rule, otherwise available.
+    pub fn effective_availability(&self, date: NaiveDate) -> bool {
+        if let Some(available) = self.override_for(date) {
+            return available;
+        }
+        self.deciding_rule(date).is_none_or(|rule| rule.available)
+    }
+
+    /// Dates in `start..=end` (none before `today`) a client can request: not
+    /// already booked or busy all day elsewhere, and either explicitly made
+    /// available or within open business hours without anything blocking
+    /// them.
+    pub fn available_dates(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        today: NaiveDate,
+    ) -> Vec<NaiveDate> {
+        start
+            .max(today)
+            .iter_days()
+            .take_while(|date| *date <= end)
+            .filter(|date| !self.is_booked(*date) && !self.is_busy_all_day(*date))
+            .filter(|date| match self.override_for(*date) {
+                Some(available) => available,
+                None => {
+                    self.hours_for(*date)
+                        .and_then(BusinessHours::open_hours)
+                        .is_some()
+                        && self.effective_availability(*date)
+                }
+            })
+            .collect()
+    }
+
+    /// Whether `start..end` on `date` is clear of bookings (and the buffer
+    /// around them) and of time blocked by rules or busy elsewhere
+    fn is_free(&self, date: NaiveDate, start: NaiveTime, end: NaiveTime) -> bool {
+        !self
+            .bookings
+            .iter()
+            .any(|b| b.date == date && b.overlaps(start, end, &self.slot_settings))
+            && !self
+                .blocked_hours(date)
+                .iter()
+                .any(|(from, to)| start < *to && *from < end)
+    }
+
+    /// Appointments `minutes` long that fit in the day's business hours,
+    /// starting every `slot_minutes` from opening, each marked unavailable
+    /// when a booking, its buffer or a rule blocking part of the day overlaps
+    /// it, or the artist is busy elsewhere then. Closed or blocked days have
+    /// no slots.
+    pub fn time_slots(&self, date: NaiveDate, minutes: i64) -> Vec<Slot> {
+        if !self.effective_availability(date) {
+            return Vec::new();
+        }
+        let Some((open, close)) = self.hours_for(date).and_then(BusinessHours::open_hours) else {
+            return Vec::new();
+        };
+        let step = Duration::minutes(self.slot_settings.slot_minutes.max(1));
+        let length = Duration::minutes(minutes.max(1));
+
+        let mut slots = Vec::new();
+        let mut start = open;
+        loop {
+            let (end, wrapped) = start.overflowing_add_signed(length);
+            if wrapped != 0 || end > close {
+                break;
+            }
+            slots.push(Slot {
+                start,
+                end,
+                available: self.is_free(date, start, end),
+            });
+            let (next, wrapped) = start.overflowing_add_signed(step);
+            if wrapped != 0 {
+                break;
+            }
+            start = next;
+        }
+        slots
+    }
+
+    /// Whether a new booking at `start` on `date`, lasting a standard
+    /// session, would clash with an existing booking, the buffer around it
+    /// or time the artist has blocked
+    pub fn has_conflict(&self, date: NaiveDate, start: Option<NaiveTime>) -> bool {
+        if !self.effective_availability(date) {
+            return true;
+        }
+        let requested = Booking {
+            date,
+            start,
+            end: None,
+        };
+        let (from, to) = requested.range(self.slot_settings.session_minutes);
+        !self.is_free(date, from, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).expect("test date")
+    }
+
+    fn time(value: &str) -> NaiveTime {
+        parse_time(value).expect("test time")
+    }
+
+    fn hours(day_of_week: u32, start: &str, end: &str) -> BusinessHours {
+        BusinessHours {
+            day_of_week,
+            start: Some(time(start)),
+            end: Some(time(end)),
+            is_closed: false,
+        }
+    }
+
+    fn closed(day_of_week: u32) -> BusinessHours {
+        BusinessHours {
+            day_of_week,
+            start: None,
+            end: None,
+            is_closed: true,
+        }
+    }
+
+    fn rule(recurrence: Recurrence, available: bool) -> RecurringRule {
+        RecurringRule {
+            recurrence,
+            starts_on: None,
+            ends_on: None,
+            exceptions: Vec::new(),
+            start: None,
+            end: None,
+            available,
+        }
+    }
+
+    fn weekly(weekdays: &[u32]) -> Recurrence {
+        Recurrence::Weekly {
+            weekdays: weekdays.to_vec(),
+            interval: 1,
+        }
+    }
+
+    /// Open 10:00 to 18:00 every day of the week
+    fn open_week() -> Vec<BusinessHours> {
+        (0..7).map(|day| hours(day, "10:00", "18:00")).collect()
+    }
+
+    #[test]
+    fn parse_time_accepts_minutes_and_seconds() {
+        assert_eq!(parse_time("09:30"), NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(
+            parse_time(" 17:45:10 "),
+            NaiveTime::from_hms_opt(17, 45, 10)
+        );
+    }
+
+    #[test]
+    fn parse_time_rejects_bad_input() {
+        for bad in ["", "  ", "25:00", "12:60", "noon", "9", "12:30pm", "12-30"] {
+            assert_eq!(parse_time(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn parse_date_rejects_bad_input() {
+        assert_eq!(
+            parse_date("2025-03-01"),
+            NaiveDate::from_ymd_opt(2025, 3, 1)
+        );
+        for bad in [
+            "",
+            "2025-02-29",
+            "2025-13-01",
+            "2025-3",
+            "03/01/2025",
+            "tomorrow",
+        ] {
+            assert_eq!(parse_date(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn day_of_week_counts_from_sunday() {
+        assert_eq!(day_of_week(date("2025-03-02")), 0);
+        assert_eq!(day_of_week(date("2025-03-01")), 6);
+    }
+
+    #[test]
+    fn business_hours_validate_rejects_inverted_hours() {
+        assert!(hours(1, "10:00", "18:00").validate().is_ok());
+        assert!(hours(1, "18:00", "10:00").validate().is_err());
+        assert!(hours(1, "10:00", "10:00").validate().is_err());
+        // Across midnight
+        assert!(hours(5, "22:00", "02:00").validate().is_err());
+    }
+
+    #[test]
+    fn business_hours_validate_needs_both_times_unless_closed() {
+        let mut day = hours(1, "10:00", "18:00");
+        day.end = None;
+        assert!(day.validate().is_err());
+        assert!(closed(1).validate().is_ok());
+        assert!(hours(7, "10:00", "18:00").validate().is_err());
+    }
+
+    #[test]
+    fn validate_week_rejects_overlapping_days() {
+        assert!(validate_week(&open_week()).is_ok());
+        assert!(validate_week(&[]).is_ok());
+
+        let mut week = open_week();
+        week.push(hours(3, "12:00", "20:00"));
+        assert_eq!(
+            validate_week(&week),
+            Err("Wednesday has more than one set of hours".to_string())
+        );
+
+        let mut week = open_week();
+        week[2] = hours(2, "20:00", "09:00");
+        assert!(validate_week(&week).is_err());
+    }
+
+    #[test]
+    fn open_hours_when_closed_or_across_midnight() {
+        assert_eq!(
+            hours(1, "10:00", "18:00").open_hours(),
+            Some((time("10:00"), time("18:00")))
+        );
+        assert_eq!(closed(1).open_hours(), None);
+
+        let mut closed_with_times = hours(1, "10:00", "18:00");
+        closed_with_times.is_closed = true;
+        assert_eq!(closed_with_times.open_hours(), None);
+
+        assert_eq!(hours(5, "22:00", "02:00").open_hours(), None);
+    }
+
+    #[test]
+    fn rule_hours_across_midnight_are_not_a_range() {
+        let mut evening = rule(weekly(&[5]), false);
+        evening.start = Some(time("18:00"));
+        evening.end = Some(time("21:00"));
+        assert_eq!(evening.hours(), Some((time("18:00"), time("21:00"))));
+        assert!(!evening.is_all_day());
+
+        evening.end = Some(time("02:00"));
+        assert_eq!(evening.hours(), None);
+        assert!(evening.validate().is_err());
+    }
+
+    #[test]
+    fn rule_validate_rejects_inverted_and_partial_hours() {
+        let mut blocked = rule(weekly(&[1]), false);
+        assert!(blocked.validate().is_ok());
+
+        blocked.start = Some(time("14:00"));
+        blocked.end = Some(time("12:00"));
+        assert_eq!(
+            blocked.validate(),
+            Err("The end time must be after the start time".to_string())
+        );
+
+        blocked.end = None;
+        assert!(blocked.validate().is_err());
+    }
+
+    #[test]
+    fn rule_validate_rejects_inverted_dates_and_bad_recurrences() {
+        let mut range = rule(weekly(&[1]), true);
+        range.starts_on = Some(date("2025-03-10"));
+        range.ends_on = Some(date("2025-03-01"));
+        assert!(range.validate().is_err());
+
+        let skipping = rule(
+            Recurrence::Weekly {
+                weekdays: vec![1],
+                interval: 2,
+            },
+            true,
+        );
+        assert!(skipping.validate().is_err());
+
+        for recurrence in [
+            weekly(&[]),
+            weekly(&[7]),
+            Recurrence::MonthlyWeekday { weekday: 1, nth: 0 },
+            Recurrence::MonthlyWeekday { weekday: 1, nth: 6 },
+            Recurrence::Yearly { dates: vec![] },
+            Recurrence::Yearly {
+                dates: vec![(2, 30)],
+            },
+        ] {
+            assert!(recurrence.validate().is_err(), "{:?}", recurrence);
+        }
+        assert!(Recurrence::Yearly {
+            dates: vec![(2, 29)]
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn weekly_rule_matches_its_weekdays() {
+        let weekends = rule(weekly(&[0, 6]), false);
+        assert!(weekends.matches(date("2025-03-01")));
+        assert!(weekends.matches(date("2025-03-02")));
+        assert!(!weekends.matches(date("2025-03-03")));
+    }
+
+    #[test]
+    fn weekly_rule_with_interval_counts_from_its_start() {
+        let mut fortnightly = rule(
+            Recurrence::Weekly {
+                weekdays: vec![1],
+                interval: 2,
+            },
+            true,
+        );
+        fortnightly.starts_on = Some(date("2025-03-05"));
+        assert!(!fortnightly.matches(date("2025-03-03")));
+        assert!(!fortnightly.matches(date("2025-03-10")));
+        assert!(fortnightly.matches(date("2025-03-17")));
+        assert!(!fortnightly.matches(date("2025-03-24")));
+        assert!(fortnightly.matches(date("2025-03-31")));
+    }
+
+    #[test]
+    fn date_range_rule_matches_inclusive_bounds() {
+        let mut vacation = rule(weekly(&[0, 1, 2, 3, 4, 5, 6]), false);
+        vacation.starts_on = Some(date("2025-03-10"));
+        vacation.ends_on = Some(date("2025-03-14"));
+        vacation.exceptions = vec![date("2025-03-12")];
+
+        assert!(!vacation.matches(date("2025-03-09")));
+        assert!(vacation.matches(date("2025-03-10")));
+        assert!(!vacation.matches(date("2025-03-12")));
+        assert!(vacation.matches(date("2025-03-14")));
+        assert!(!vacation.matches(date("2025-03-15")));
+        assert_eq!(
+            vacation
+                .occurrences(date("2025-03-01"), date("2025-03-31"))
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn single_date_rules_match_only_that_date() {
+        let mut one_day = rule(weekly(&[0, 1, 2, 3, 4, 5, 6]), false);
+        one_day.starts_on = Some(date("2025-03-12"));
+        one_day.ends_on = Some(date("2025-03-12"));
+        assert!(one_day.matches(date("2025-03-12")));
+        assert!(!one_day.matches(date("2025-03-11")));
+        assert!(!one_day.matches(date("2025-03-13")));
+
+        let christmas = rule(
+            Recurrence::Yearly {
+                dates: vec![(12, 25)],
+            },
+            false,
+        );
+        assert!(christmas.matches(date("2025-12-25")));
+        assert!(christmas.matches(date("2030-12-25")));
+        assert!(!christmas.matches(date("2025-12-24")));
+    }
+
+    #[test]
+    fn monthly_rule_matches_nth_and_last_weekday() {
+        let fourth_monday = rule(Recurrence::MonthlyWeekday { weekday: 1, nth: 4 }, true);
+        assert!(fourth_monday.matches(date("2025-03-24")));
+        assert!(!fourth_monday.matches(date("2025-03-31")));
+
+        let last_monday = rule(
+            Recurrence::MonthlyWeekday {
+                weekday: 1,
+                nth: -1,
+            },
+            true,
+        );
+        assert!(last_monday.matches(date("2025-03-31")));
+        assert!(!last_monday.matches(date("2025-03-24")));
+
+        let last_friday = rule(
+            Recurrence::MonthlyWeekday {
+                weekday: 5,
+                nth: -1,
+            },
+            true,
+        );
+        assert!(last_friday.matches(date("2025-03-28")));
+    }
+
+    #[test]
+    fn next_occurrence_finds_leap_days() {
+        let leap_day = rule(
+            Recurrence::Yearly {
+                dates: vec![(2, 29)],
+            },
+            false,
+        );
+        assert_eq!(
+            leap_day.next_occurrence(date("2025-03-01")),
+            Some(date("2028-02-29"))
+        );
+    }
+
+    #[test]
+    fn narrower_rule_decides_the_date() {
+        let schedule = Schedule {
+            business_hours: open_week(),
+            rules: vec![
+                // Saturdays off, but the last Saturday of the month open and
+                // Christmas off whatever day it falls on
+                rule(weekly(&[6]), false),
+                rule(
+                    Recurrence::MonthlyWeekday {
+                        weekday: 6,
+                        nth: -1,
+                    },
+                    true,
+                ),
+                rule(
+                    Recurrence::Yearly {
+                        dates: vec![(12, 25)],
+                    },
+                    false,
+                ),
+                rule(weekly(&[4]), true),
+            ],
+            ..Schedule::default()
+        };
+
+        assert!(!schedule.effective_availability(date("2025-03-22")));
+        assert!(schedule.effective_availability(date("2025-03-29")));
+        assert!(!schedule.effective_availability(date("2025-12-25")));
+        assert!(schedule.effective_availability(date("2025-12-18")));
+    }
+
+    #[test]
+    fn blocking_wins_between_equally_narrow_rules() {
+        let schedule = Schedule {
+            rules: vec![rule(weekly(&[1]), true), rule(weekly(&[1, 2]), false)],
+            ..Schedule::default()
+        };
+        let deciding = schedule
+            .deciding_rule(date("2025-03-03"))
+            .expect("a rule matches");
+        assert!(!deciding.available);
+        assert!(!schedule.effective_availability(date("2025-03-03")));
+    }
+
+    #[test]
+    fn override_beats_every_rule() {
+        let schedule = Schedule {
+            business_hours: open_week(),
+            overrides: vec![
+                Override {
+                    date: date("2025-12-25"),
+                    available: true,
+                },
+                Override {
+                    date: date("2025-03-04"),
+                    available: false,
+                },
+            ],
+            rules: vec![rule(
+                Recurrence::Yearly {
+                    dates: vec![(12, 25)],
+                },
+                false,
+            )],
+            ..Schedule::default()
+        };
+        assert!(schedule.effective_availability(date("2025-12-25")));
+        assert!(!schedule.effective_availability(date("2025-03-04")));
+        assert!(schedule.effective_availability(date("2025-03-05")));
+    }
+
+    #[test]
+    fn rules_covering_part_of_the_day_block_only_those_hours() {
+        let mut lunch = rule(weekly(&[1]), false);
+        lunch.start = Some(time("12:00"));
+        lunch.end = Some(time("13:00"));
+        let schedule = Schedule {
+            business_hours: open_week(),
+            rules: vec![lunch],
+            ..Schedule::default()
+        };
+        let monday = date("2025-03-03");
+
+        assert!(schedule.deciding_rule(monday).is_none());
+        assert!(schedule.effective_availability(monday));
+        assert_eq!(
+            schedule.blocked_hours(monday),
+            vec![(time("12:00"), time("13:00"))]
+        );
+
+        let slots = schedule.time_slots(monday, 60);
+        assert_eq!(slots.len(), 8);
+        let unavailable = slots
+            .iter()
+            .filter(|slot| !slot.available)
+            .map(|slot| slot.start)
+            .collect::<Vec<_>>();
+        assert_eq!(unavailable, vec![time("12:00")]);
+        assert!(schedule.has_conflict(monday, Some(time("12:30"))));
+        assert!(!schedule.has_conflict(monday, Some(time("13:00"))));
+    }
+
+    #[test]
+    fn available_dates_skip_closed_booked_and_busy_days() {
+        let mut business_hours = open_week();
+        business_hours[0] = closed(0);
+        let schedule = Schedule {
+            business_hours,
+            overrides: vec![Override {
+                date: date("2025-03-09"),
+                available: true,
+            }],
+            bookings: vec![Booking {
+                date: date("2025-03-04"),
+                start: None,
+                end: None,
+            }],
+            busy: vec![
+                BusyBlock {
+                    date: date("2025-03-05"),
+                    hours: None,
+                },
+                BusyBlock {
+                    date: date("2025-03-06"),
+                    hours: Some((time("10:00"), time("11:00"))),
+                },
+            ],
+            ..Schedule::default()
+        };
+
+        let dates =
+            schedule.available_dates(date("2025-02-20"), date("2025-03-09"), date("2025-03-02"));
+        assert_eq!(
+            dates,
+            vec![
+                date("2025-03-03"),
+                date("2025-03-06"),
+                date("2025-03-07"),
+                date("2025-03-08"),
+                date("2025-03-09"),
+            ]
+        );
+    }
+
+    #[test]
+    fn time_slots_are_empty_when_closed_or_open_across_midnight() {
+        let mut business_hours = open_week();
+        business_hours[0] = closed(0);
+        business_hours[5] = hours(5, "22:00", "02:00");
+        let schedule = Schedule {
+            business_hours,
+            ..Schedule::default()
+        };
+        assert!(schedule.time_slots(date("2025-03-02"), 60).is_empty());
+        assert!(schedule.time_slots(date("2025-03-07"), 60).is_empty());
+        assert!(schedule
+            .available_dates(date("2025-03-07"), date("2025-03-07"), date("2025-03-01"))
+            .is_empty());
+    }
+
+    #[test]
+    fn slots_keep_the_buffer_around_bookings() {
+        let schedule = Schedule {
+            business_hours: open_week(),
+            bookings: vec![Booking {
+                date: date("2025-03-03"),
+                start: Some(time("12:00")),
+                end: None,
+            }],
+            slot_settings: SlotSettings {
+                session_minutes: 60,
+                slot_minutes: 30,
+                buffer_minutes: 30,
+            },
+            ..Schedule::default()
+        };
+        let unavailable = schedule
+            .time_slots(date("2025-03-03"), 60)
+            .into_iter()
+            .filter(|slot| !slot.available)
+            .map(|slot| slot.start)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unavailable,
+            vec![
+                time("11:00"),
+                time("11:30"),
+                time("12:00"),
+                time("12:30"),
+                time("13:00")
+            ]
+        );
+    }
+
+    #[test]
+    fn slot_settings_bounds_and_sizes() {
+        assert!(SlotSettings::default().validate().is_ok());
+        let too_short = SlotSettings {
+            session_minutes: 10,
+            ..SlotSettings::default()
+        };
+        assert!(too_short.validate().is_err());
+
+        let settings = SlotSettings::default();
+        assert_eq!(settings.session_minutes_for(None), 60);
+        assert_eq!(settings.session_minutes_for(Some(-2.0)), 60);
+        assert_eq!(settings.session_minutes_for(Some(3.0)), 60);
+        assert_eq!(settings.session_minutes_for(Some(5.0)), 120);
+        assert_eq!(settings.session_minutes_for(Some(10.0)), 180);
+        assert_eq!(settings.session_minutes_for(Some(12.0)), 240);
+    }
+
+    #[test]
+    fn rrule_and_description() {
+        let mut weekends = rule(weekly(&[6, 0]), false);
+        weekends.ends_on = Some(date("2025-12-31"));
+        assert_eq!(
+            weekends.rrule(),
+            "FREQ=WEEKLY;INTERVAL=1;WKST=SU;BYDAY=SA,SU;UNTIL=20251231"
+        );
+        assert_eq!(weekends.recurrence.describe(), "Every Sunday, Saturday");
+        assert_eq!(
+            Recurrence::MonthlyWeekday {
+                weekday: 5,
+                nth: -1
+            }
+            .describe(),
+            "Last Friday of the month"
+        );
+
+        let holidays = rule(
+            Recurrence::Yearly {
+                dates: vec![(12, 25), (1, 1)],
+            },
+            false,
+        );
+        assert_eq!(holidays.series().len(), 2);
+        assert_eq!(
+            holidays.recurrence.describe(),
+            "Every year on December 25, January 1"
+        );
+    }
+}
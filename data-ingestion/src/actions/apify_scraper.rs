@@ -1,7 +1,9 @@
 use chrono::DateTime;
 use reqwest;
 use serde::{Deserialize, Deserializer, Serialize};
-use std::env;
+
+use crate::services::breakers::{self, APIFY, INSTAGRAM};
+use crate::services::costs;
 
 // Custom deserializer for timestamp that handles both ISO 8601 strings and i64 Unix timestamps
 mod timestamp_deserializer {
@@ -120,6 +122,7 @@ pub struct ApifyPost {
     pub shortcode: String,
     #[serde(rename = "displayUrl")]
     pub display_url: Option<String>,
+    pub caption: Option<String>,
     #[serde(deserialize_with = "timestamp_deserializer::deserialize")]
     pub timestamp: Option<i64>,
 }
@@ -128,8 +131,7 @@ pub async fn scrape_instagram_profile(
     username: &str,
     max_posts: i32,
 ) -> Result<Vec<ApifyPost>, Box<dyn std::error::Error>> {
-    let api_token =
-        env::var("APIFY_API_TOKEN").expect("APIFY_API_TOKEN environment variable must be set");
+    let api_token = crate::config::get().apify_api_token()?;
 
     let actor_id = "apify~instagram-scraper";
     let url = format!(
@@ -155,7 +157,11 @@ pub async fn scrape_instagram_profile(
         .timeout(std::time::Duration::from_secs(300))
         .build()?;
 
-    let response = client.post(&url).json(&input).send().await?;
+    costs::check_budget()?;
+    APIFY.check()?;
+    let response = client.post(&url).json(&input).send().await;
+    breakers::record_response(&APIFY, &response);
+    let response = response?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -191,9 +197,33 @@ pub async fn scrape_instagram_profile(
     Ok(posts)
 }
 
+/// Longest side of the thumbnails kept for Instagram privacy mode
+const PREVIEW_THUMBNAIL_SIZE: u32 = 480;
+
+/// A small JPEG of a downloaded post image with its dimensions, or `None`
+/// if the image can't be decoded
+pub fn make_preview_thumbnail(image_data: &[u8]) -> Option<(Vec<u8>, u32, u32)> {
+    use image::{DynamicImage, ImageFormat};
+
+    let image = image::load_from_memory(image_data).ok()?;
+    // JPEG has no alpha channel
+    let thumbnail = DynamicImage::ImageRgb8(
+        image
+            .thumbnail(PREVIEW_THUMBNAIL_SIZE, PREVIEW_THUMBNAIL_SIZE)
+            .to_rgb8(),
+    );
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    thumbnail.write_to(&mut bytes, ImageFormat::Jpeg).ok()?;
+
+    Some((bytes.into_inner(), thumbnail.width(), thumbnail.height()))
+}
+
 pub async fn download_image(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
-    let response = client.get(url).send().await?;
+    INSTAGRAM.check()?;
+    let response = client.get(url).send().await;
+    breakers::record_response(&INSTAGRAM, &response);
+    let response = response?;
 
     if !response.status().is_success() {
         return Err(format!("Failed to download image: {}", response.status()).into());
@@ -250,8 +280,7 @@ pub async fn lookup_shop_instagram(
     shop_name: &str,
     city: Option<&str>,
 ) -> Result<InstagramProfileInfo, Box<dyn std::error::Error + Send + Sync>> {
-    let api_token =
-        env::var("APIFY_API_TOKEN").expect("APIFY_API_TOKEN environment variable must be set");
+    let api_token = crate::config::get().apify_api_token()?;
 
     let actor_id = "apify~instagram-scraper";
     let url = format!(
@@ -279,7 +308,11 @@ pub async fn lookup_shop_instagram(
 
         println!("   Trying @{}...", username);
 
-        let response = match client.post(&url).json(&input).send().await {
+        costs::check_budget()?;
+        APIFY.check()?;
+        let response = client.post(&url).json(&input).send().await;
+        breakers::record_response(&APIFY, &response);
+        let response = match response {
             Ok(resp) => resp,
             Err(e) => {
                 println!("      ❌ Request failed: {}", e);
@@ -335,8 +368,7 @@ pub async fn lookup_shop_instagram(
 pub async fn get_instagram_profile_info(
     username: &str,
 ) -> Result<InstagramProfileInfo, Box<dyn std::error::Error + Send + Sync>> {
-    let api_token =
-        env::var("APIFY_API_TOKEN").expect("APIFY_API_TOKEN environment variable must be set");
+    let api_token = crate::config::get().apify_api_token()?;
 
     let actor_id = "apify~instagram-scraper";
     let url = format!(
@@ -359,7 +391,11 @@ pub async fn get_instagram_profile_info(
         .timeout(std::time::Duration::from_secs(300))
         .build()?;
 
-    let response = client.post(&url).json(&input).send().await?;
+    costs::check_budget()?;
+    APIFY.check()?;
+    let response = client.post(&url).json(&input).send().await;
+    breakers::record_response(&APIFY, &response);
+    let response = response?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -399,8 +435,7 @@ pub async fn get_instagram_profile_info(
 pub async fn search_instagram_artist(
     artist_name: &str,
 ) -> Result<InstagramProfileInfo, Box<dyn std::error::Error + Send + Sync>> {
-    let api_token =
-        env::var("APIFY_API_TOKEN").expect("APIFY_API_TOKEN environment variable must be set");
+    let api_token = crate::config::get().apify_api_token()?;
 
     let actor_id = "apify~instagram-scraper";
     let url = format!(
@@ -436,7 +471,11 @@ pub async fn search_instagram_artist(
             "searchType": "user"
         });
 
-        let response = match client.post(&url).json(&input).send().await {
+        costs::check_budget()?;
+        APIFY.check()?;
+        let response = client.post(&url).json(&input).send().await;
+        breakers::record_response(&APIFY, &response);
+        let response = match response {
             Ok(resp) => resp,
             Err(e) => {
                 println!("      ❌ Request failed: {}", e);
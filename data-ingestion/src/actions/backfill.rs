@@ -0,0 +1,209 @@
+//! Online backfills for large tables.
+//!
+//! Schema changes on big tables (artists_images, artists) roll out in stages so
+//! nothing holds a long table lock:
+//!
+//! 1. a migration adds the column as nullable, with no default rewrite, and
+//!    keeps new writes populated (trigger or application code);
+//! 2. `ACTION=BACKFILL BACKFILL_NAME=<name>` fills existing rows in primary
+//!    key chunks, each its own short transaction;
+//! 3. once the job shows `completed`, a follow-up migration adds constraints
+//!    or indexes that need the column filled.
+//!
+//! Each run is tracked as a `backfill` job on the `ingestion` queue of
+//! `background_jobs`; its `progress` holds the last processed id, so an
+//! interrupted or failed run resumes from there.
+//!
+//! Tuning, read by [`crate::config`]: `BACKFILL_BATCH_SIZE` (rows per chunk,
+//! default 1000) and `BACKFILL_PAUSE_MS` (sleep between chunks, default 100).
+
+use sqlx::{PgPool, Row};
+use std::env;
+use std::time::Instant;
+
+use crate::config;
+use crate::repository;
+
+/// A chunk gives up rather than queueing behind a conflicting lock; the
+/// run then fails and can simply be restarted
+const CHUNK_LOCK_TIMEOUT: &str = "5s";
+
+/// A registered backfill. `update_sql` updates the rows of `table` whose
+/// `id` is in `($1, $2]`; it is run once per chunk.
+struct Backfill {
+    name: &'static str,
+    table: &'static str,
+    update_sql: &'static str,
+}
+
+fn backfills() -> Vec<Backfill> {
+    vec![
+        // Re-derive search columns after search_normalize() changes
+        Backfill {
+            name: "artists_name_search",
+            table: "artists",
+            update_sql: "UPDATE artists SET name_search = search_normalize(name)
+                         WHERE id > $1 AND id <= $2
+                           AND name_search IS DISTINCT FROM search_normalize(name)",
+        },
+        Backfill {
+            name: "locations_search",
+            table: "locations",
+            update_sql: "UPDATE locations
+                         SET city_search = search_normalize(city),
+                             county_search = search_normalize(county)
+                         WHERE id > $1 AND id <= $2
+                           AND (city_search IS DISTINCT FROM search_normalize(city)
+                                OR county_search IS DISTINCT FROM search_normalize(county))",
+        },
+    ]
+}
+
+/// Runs (or resumes) the backfill named by `BACKFILL_NAME`.
+pub async fn run_backfill(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let available = backfills();
+    let name = env::var("BACKFILL_NAME").unwrap_or_default();
+    let Some(backfill) = available.iter().find(|backfill| backfill.name == name) else {
+        let names: Vec<&str> = available.iter().map(|backfill| backfill.name).collect();
+        return Err(format!("Set BACKFILL_NAME to one of: {}", names.join(", ")).into());
+    };
+
+    let config = &config::get().backfill;
+    let (batch_size, pause) = (config.batch_size, config.pause);
+
+    // Held on a dedicated connection for the whole run so two runs of the
+    // same backfill can't interleave
+    let mut lock_conn = pool.acquire().await?;
+    let locked: bool = sqlx::query("SELECT pg_try_advisory_lock(hashtext($1)) as locked")
+        .bind(format!("backfill:{}", backfill.name))
+        .fetch_one(&mut *lock_conn)
+        .await?
+        .get("locked");
+    if !locked {
+        return Err(format!("Backfill {} is already running", backfill.name).into());
+    }
+
+    // Rows added after this point are covered by stage 1
+    let max_id: i64 = sqlx::query(&format!(
+        "SELECT COALESCE(MAX(id), 0)::bigint as max_id FROM {}",
+        backfill.table
+    ))
+    .fetch_one(pool)
+    .await?
+    .get("max_id");
+
+    let (job_id, progress) =
+        match repository::find_unfinished_backfill_job(pool, backfill.name).await? {
+            Some(job) => job,
+            None => {
+                let payload = serde_json::json!({
+                    "name": backfill.name,
+                    "table": backfill.table,
+                });
+                (
+                    repository::create_job(pool, "backfill", &payload).await?,
+                    serde_json::Value::Null,
+                )
+            }
+        };
+
+    let mut last_id = progress["last_id"].as_i64().unwrap_or(0);
+    let mut rows_updated = progress["rows_updated"].as_i64().unwrap_or(0);
+    let mut chunks = progress["chunks"].as_i64().unwrap_or(0);
+
+    println!(
+        "🔁 Backfill {} (job {}): ids {}..={} in chunks of {}{}",
+        backfill.name,
+        job_id,
+        last_id + 1,
+        max_id,
+        batch_size,
+        if last_id > 0 { ", resuming" } else { "" }
+    );
+
+    let started = Instant::now();
+    let result: Result<(), Box<dyn std::error::Error>> = async {
+        while last_id < max_id {
+            let upper: Option<i64> = sqlx::query(&format!(
+                "SELECT MAX(id)::bigint as upper FROM (
+                     SELECT id FROM {} WHERE id > $1 AND id <= $2 ORDER BY id LIMIT $3
+                 ) chunk",
+                backfill.table
+            ))
+            .bind(last_id)
+            .bind(max_id)
+            .bind(batch_size)
+            .fetch_one(pool)
+            .await?
+            .get("upper");
+            let Some(upper) = upper else {
+                break;
+            };
+
+            let mut tx = pool.begin().await?;
+            sqlx::query(&format!(
+                "SET LOCAL lock_timeout = '{}'",
+                CHUNK_LOCK_TIMEOUT
+            ))
+            .execute(&mut *tx)
+            .await?;
+            let updated = sqlx::query(backfill.update_sql)
+                .bind(last_id)
+                .bind(upper)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            tx.commit().await?;
+
+            last_id = upper;
+            rows_updated += updated as i64;
+            chunks += 1;
+
+            let progress = serde_json::json!({
+                "last_id": last_id,
+                "max_id": max_id,
+                "rows_updated": rows_updated,
+                "chunks": chunks,
+                "percent": (last_id as f64 / max_id.max(1) as f64 * 100.0).round(),
+            });
+            repository::update_job_progress(pool, job_id, "running", &progress).await?;
+
+            if chunks % 50 == 0 {
+                println!(
+                    "   {} / {} ids, {} rows updated",
+                    last_id, max_id, rows_updated
+                );
+            }
+
+            tokio::time::sleep(pause).await;
+        }
+        Ok(())
+    }
+    .await;
+
+    match &result {
+        Ok(()) => {
+            repository::finish_job(pool, job_id, None).await?;
+            println!(
+                "✅ Backfill {} completed: {} rows updated in {:.1}s",
+                backfill.name,
+                rows_updated,
+                started.elapsed().as_secs_f64()
+            );
+        }
+        Err(e) => {
+            repository::finish_job(pool, job_id, Some(&e.to_string())).await?;
+            eprintln!(
+                "❌ Backfill {} failed after id {}: {} (rerun to resume)",
+                backfill.name, last_id, e
+            );
+        }
+    }
+
+    sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+        .bind(format!("backfill:{}", backfill.name))
+        .execute(&mut *lock_conn)
+        .await?;
+
+    result
+}
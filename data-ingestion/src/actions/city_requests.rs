@@ -0,0 +1,80 @@
+//! On-demand ingestion of cities users searched for but we have no shops in.
+//!
+//! The web app queues a `city_ingestion` job when someone picks such a city
+//! from autocomplete (payload: `city`, `state`, `lat`, `long`).
+//! `ACTION=CITY_REQUESTS` claims queued jobs and runs the Google Places search
+//! over a box around each city, the same way county ingestion does.
+//!
+//! Tuning: `CITY_REQUESTS_LIMIT` (jobs per run, default 25), read by
+//! [`crate::config`].
+
+use shared_types::CountyBoundary;
+use sqlx::PgPool;
+
+use crate::actions::google_api_ingestion::driver::process_county;
+use crate::config;
+use crate::repository;
+use crate::services::costs;
+
+/// Half the side of the search box, in degrees of latitude (about 20 km)
+const HALF_SIDE_DEGREES: f64 = 0.18;
+
+/// A box of roughly equal width and height centered on the city
+fn city_bounds(name: String, lat: f64, long: f64) -> CountyBoundary {
+    let long_span = HALF_SIDE_DEGREES / lat.to_radians().cos().max(0.2);
+    CountyBoundary {
+        name,
+        low_lat: lat - HALF_SIDE_DEGREES,
+        low_long: long - long_span,
+        high_lat: lat + HALF_SIDE_DEGREES,
+        high_long: long + long_span,
+        date_utc_last_ingested: None,
+    }
+}
+
+pub async fn ingest_requested_cities(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let limit = config::get().city_requests_limit;
+
+    let jobs = repository::claim_queued_jobs(pool, "city_ingestion", limit).await?;
+    if jobs.is_empty() {
+        println!("No requested cities to ingest.");
+        return Ok(());
+    }
+
+    for (index, (job_id, payload)) in jobs.iter().enumerate() {
+        if let Err(e) = costs::check_run_budget() {
+            let unstarted: Vec<i64> = jobs[index..].iter().map(|(job_id, _)| *job_id).collect();
+            println!(
+                "💸 Stopping: {}. {} requested cities left for the next run.",
+                e,
+                unstarted.len()
+            );
+            repository::requeue_jobs(pool, &unstarted).await?;
+            break;
+        }
+
+        let job_id = *job_id;
+        let city = payload["city"].as_str().unwrap_or_default().to_string();
+        let state = payload["state"].as_str().unwrap_or_default().to_string();
+        let (Some(lat), Some(long)) = (payload["lat"].as_f64(), payload["long"].as_f64()) else {
+            repository::finish_job(pool, job_id, Some("Missing coordinates")).await?;
+            continue;
+        };
+
+        println!("Processing requested city: {}, {}", city, state);
+        costs::set_location(Some(&city), Some(&state));
+
+        let bounds = city_bounds(format!("{}, {}", city, state), lat, long);
+        let error = process_county(pool, &bounds, 20, 10)
+            .await
+            .err()
+            .map(|e| e.to_string());
+        if let Some(error) = &error {
+            println!("Error processing {}, {}: {}", city, state, error);
+        }
+
+        repository::finish_job(pool, job_id, error.as_deref()).await?;
+    }
+
+    Ok(())
+}
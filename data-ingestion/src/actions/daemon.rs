@@ -0,0 +1,230 @@
+//! Long-running scheduler for the ingestion actions.
+//!
+//! `ACTION=DAEMON` keeps the process up and runs other actions on
+//! intervals instead of one action per invocation. An action's next run is
+//! due its interval after its last one finished, so a slow run never
+//! overlaps itself; after a restart the schedule picks up from the runs
+//! recorded in `ingestion_runs`. Costs and circuit breaker states are
+//! written after every run, as a one-shot invocation writes them on exit.
+//!
+//! `GOOGLE_API` is incremental here: each run searches a batch of the
+//! counties whose `date_utc_last_ingested` is oldest, so every county is
+//! refreshed about every `DAEMON_COUNTY_REFRESH_DAYS` without one run
+//! trying to cover the whole country.
+//!
+//! On SIGTERM or Ctrl-C no new runs are started, county ingestion stops
+//! after the county it's on, and the daemon exits once running actions
+//! finish or the grace period runs out.
+//!
+//! Tuning, read by [`crate::config`]:
+//! - `DAEMON_SCHEDULE`: `ACTION=INTERVAL` pairs, comma separated. Intervals
+//!   are `30m`, `6h`, `2d` or `@hourly`, `@daily`, `@weekly` (default
+//!   `CITY_REQUESTS=15m,GOOGLE_API=6h,REDDIT_RETRY_FAILED=12h,INTEGRITY_CHECK=@daily,DEDUP_LOCATIONS=@daily`)
+//! - `DAEMON_CONCURRENCY`: actions running at once (default 2). Spend of
+//!   runs that overlap is recorded against whichever finishes first; use 1
+//!   for exact per-run costs
+//! - `DAEMON_COUNTY_BATCH`: counties per `GOOGLE_API` run (default 100)
+//! - `DAEMON_COUNTY_REFRESH_DAYS`: days before a county is searched again
+//!   (default 160)
+//! - `DAEMON_SHUTDOWN_GRACE_SECS`: how long to wait for running actions on
+//!   shutdown (default 600)
+
+use chrono::Utc;
+use futures::stream::{FuturesUnordered, StreamExt};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::actions::google_api_ingestion::driver::ingest_stale_counties;
+use crate::actions::IngestAction;
+use crate::config::{self, DaemonConfig};
+use crate::repository;
+use crate::services::{breakers, costs};
+
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+/// Whether the daemon is shutting down, for long actions to stop at a safe
+/// point. Always false outside the daemon.
+pub fn shutdown_requested() -> bool {
+    SHUTDOWN.load(Ordering::Relaxed)
+}
+
+/// Resolves on SIGTERM or Ctrl-C
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// One scheduled run, then its costs and breaker states
+async fn run_scheduled(
+    pool: &PgPool,
+    config: &DaemonConfig,
+    action: IngestAction,
+) -> Result<(), String> {
+    costs::start_run(action.name());
+
+    let result = match action {
+        IngestAction::GoogleApi => {
+            ingest_stale_counties(pool, config.county_batch, config.county_refresh_days).await
+        }
+        action => action.run(pool).await,
+    }
+    .map_err(|e| e.to_string());
+
+    if let Err(e) = costs::flush_run_costs(pool, action.name()).await {
+        eprintln!("Failed to record ingestion costs: {}", e);
+    }
+    if let Err(e) = breakers::flush_breaker_states(pool).await {
+        eprintln!("Failed to record circuit breaker states: {}", e);
+    }
+
+    if action == IngestAction::GoogleApi {
+        match repository::count_stale_counties(pool, config.county_refresh_days).await {
+            Ok(stale) => println!("{} counties still due for a refresh", stale),
+            Err(e) => eprintln!("Failed to count stale counties: {}", e),
+        }
+    }
+
+    result
+}
+
+pub async fn run_daemon(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = &config::get().daemon;
+
+    // Pick the schedule up where the last process left it
+    let names: Vec<String> = config
+        .schedule
+        .iter()
+        .map(|scheduled| scheduled.action.name().to_string())
+        .collect();
+    let last_finished: HashMap<String, _> = repository::get_last_run_finished(pool, &names)
+        .await?
+        .into_iter()
+        .collect();
+    let started = Instant::now();
+    let mut next_due: Vec<Instant> = config
+        .schedule
+        .iter()
+        .map(|scheduled| {
+            let since_last = last_finished
+                .get(scheduled.action.name())
+                .and_then(|finished| (Utc::now() - *finished).to_std().ok());
+            match since_last {
+                Some(since_last) => started + scheduled.every.saturating_sub(since_last),
+                None => started,
+            }
+        })
+        .collect();
+
+    for (scheduled, due) in config.schedule.iter().zip(&next_due) {
+        println!(
+            "Scheduled {} every {} min, next run in {} min",
+            scheduled.action.name(),
+            scheduled.every.as_secs() / 60,
+            due.saturating_duration_since(started).as_secs() / 60
+        );
+    }
+
+    let mut running = FuturesUnordered::new();
+    let mut in_flight = vec![false; config.schedule.len()];
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    let mut grace_deadline: Option<Instant> = None;
+
+    loop {
+        if grace_deadline.is_none() {
+            let now = Instant::now();
+            for (index, scheduled) in config.schedule.iter().enumerate() {
+                if running.len() >= config.concurrency {
+                    break;
+                }
+                if in_flight[index] || next_due[index] > now {
+                    continue;
+                }
+                println!("▶️ Starting {}", scheduled.action.name());
+                in_flight[index] = true;
+                running.push(async move {
+                    let started_at = Instant::now();
+                    let result = run_scheduled(pool, config, scheduled.action).await;
+                    (index, started_at.elapsed(), result)
+                });
+            }
+        } else if running.is_empty() {
+            break;
+        }
+
+        // Sleep until the next waiting action is due or a run finishes
+        let next_wake = config
+            .schedule
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !in_flight[*index])
+            .map(|(index, _)| next_due[index])
+            .min()
+            .filter(|_| running.len() < config.concurrency)
+            .unwrap_or_else(|| Instant::now() + Duration::from_secs(60 * 60));
+
+        tokio::select! {
+            Some((index, elapsed, result)) = running.next(), if !running.is_empty() => {
+                let scheduled = &config.schedule[index];
+                match result {
+                    Ok(()) => println!(
+                        "✅ {} finished in {}s",
+                        scheduled.action.name(),
+                        elapsed.as_secs()
+                    ),
+                    Err(e) => eprintln!("❌ {} failed: {}", scheduled.action.name(), e),
+                }
+                in_flight[index] = false;
+                next_due[index] = Instant::now() + scheduled.every;
+            }
+            _ = tokio::time::sleep_until(next_wake), if grace_deadline.is_none() => {}
+            _ = &mut shutdown, if grace_deadline.is_none() => {
+                println!(
+                    "Shutting down, waiting up to {}s for {} running action(s)",
+                    config.shutdown_grace.as_secs(),
+                    running.len()
+                );
+                SHUTDOWN.store(true, Ordering::Relaxed);
+                grace_deadline = Some(Instant::now() + config.shutdown_grace);
+            }
+            _ = tokio::time::sleep_until(grace_deadline.unwrap_or_else(Instant::now)), if grace_deadline.is_some() => {
+                eprintln!("Grace period over, abandoning {} running action(s)", running.len());
+                break;
+            }
+        }
+    }
+
+    // Record what abandoned runs spent so far
+    if !running.is_empty() {
+        drop(running);
+        if let Err(e) = costs::flush_run_costs(pool, "DAEMON").await {
+            eprintln!("Failed to record ingestion costs: {}", e);
+        }
+        if let Err(e) = breakers::flush_breaker_states(pool).await {
+            eprintln!("Failed to record circuit breaker states: {}", e);
+        }
+    }
+    println!("Daemon stopped.");
+    Ok(())
+}
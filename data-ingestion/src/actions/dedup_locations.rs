@@ -0,0 +1,280 @@
+//! Merges near-duplicate shops.
+//!
+//! The same shop gets inserted by Google ingestion and again by the Reddit
+//! scraper's Google lookup under another place id (a moved or second
+//! listing for one studio), so it shows up twice with its artists split
+//! between the two. `ACTION=DEDUP_LOCATIONS` compares shops in the same
+//! state that are close together, scoring name and address similarity and
+//! proximity, and merges the pairs that score high enough.
+//!
+//! The shop kept is the claimed one, then the one with more artists, then
+//! the older one. Pairs where both are claimed are only reported, since
+//! their owners need to sort that out. Merging moves artists (and with them
+//! their images), shop photos, claims and scrape history over, and deletes
+//! the other shop; `location_merges` keeps its place id so ingestion doesn't
+//! add it back. A shop merged this run isn't compared again until the next.
+//!
+//! Tuning, read by [`crate::config`]:
+//! - `DEDUP_MIN_SCORE`: score from 0 to 1 a pair needs to be merged
+//!   (default 0.85)
+//! - `DEDUP_MAX_DISTANCE_M`: furthest apart two listings of one shop can be
+//!   (default 150)
+//!
+//! `DEDUP_DRY_RUN=true` prints the pairs that would be merged without
+//! merging them.
+
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::env;
+use strsim::jaro_winkler;
+
+use crate::actions::integrity_check::PLACEHOLDER_LOCATION_ID;
+use crate::config::{self, DedupConfig};
+use crate::repository::{self, DedupLocation};
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+const METERS_PER_DEGREE_LAT: f64 = 111_320.0;
+
+/// Words that say what kind of business it is rather than which one
+const GENERIC_NAME_WORDS: &[&str] = &[
+    "the",
+    "and",
+    "tattoo",
+    "tattoos",
+    "tattooing",
+    "studio",
+    "studios",
+    "shop",
+    "parlor",
+    "parlour",
+    "gallery",
+    "co",
+    "company",
+    "llc",
+    "inc",
+];
+
+const STREET_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("street", "st"),
+    ("avenue", "ave"),
+    ("boulevard", "blvd"),
+    ("road", "rd"),
+    ("drive", "dr"),
+    ("lane", "ln"),
+    ("court", "ct"),
+    ("place", "pl"),
+    ("highway", "hwy"),
+    ("parkway", "pkwy"),
+    ("north", "n"),
+    ("south", "s"),
+    ("east", "e"),
+    ("west", "w"),
+];
+
+const UNIT_WORDS: &[&str] = &["suite", "ste", "unit", "apt", "bldg", "floor", "fl"];
+
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace('&', " and ")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lowercased name without the generic words, so "Black Rose Tattoo Studio"
+/// and "Black Rose Tattoo" match
+fn normalize_name(name: &str) -> String {
+    let all = words(name);
+    let distinctive: Vec<&str> = all
+        .iter()
+        .map(String::as_str)
+        .filter(|word| !GENERIC_NAME_WORDS.contains(word))
+        .collect();
+    if distinctive.is_empty() {
+        all.join(" ")
+    } else {
+        distinctive.join(" ")
+    }
+}
+
+/// The street line of an address, with abbreviated street words and
+/// without the unit, so "123 North Main Street, Suite 4" and "123 N Main St"
+/// match
+fn normalize_address(address: &str) -> String {
+    let street = address.split(',').next().unwrap_or_default();
+    let mut normalized = Vec::new();
+    let mut after_unit = false;
+    for word in words(street) {
+        if UNIT_WORDS.contains(&word.as_str()) {
+            after_unit = true;
+            continue;
+        }
+        // The unit's number
+        if after_unit {
+            after_unit = false;
+            continue;
+        }
+        match STREET_ABBREVIATIONS.iter().find(|(long, _)| *long == word) {
+            Some((_, short)) => normalized.push(short.to_string()),
+            None => normalized.push(word),
+        }
+    }
+    normalized.join(" ")
+}
+
+fn distance_between(a: &DedupLocation, b: &DedupLocation) -> f64 {
+    let (lat_a, lat_b) = (a.lat.to_radians(), b.lat.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_long = (b.long - a.long).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_long / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * h.sqrt().asin()
+}
+
+/// How likely two shops `distance_m` apart are the same one, from 0 to 1:
+/// name similarity counts most, then address, then how close they are.
+/// Without both addresses, name and proximity decide.
+fn similarity(a: &DedupLocation, b: &DedupLocation, distance_m: f64, max_distance_m: f64) -> f64 {
+    let name = jaro_winkler(&normalize_name(&a.name), &normalize_name(&b.name));
+    let proximity = (1.0 - distance_m / max_distance_m).clamp(0.0, 1.0);
+
+    let addresses = a
+        .address
+        .as_deref()
+        .zip(b.address.as_deref())
+        .map(|(a, b)| (normalize_address(a), normalize_address(b)))
+        .filter(|(a, b)| !a.is_empty() && !b.is_empty());
+    match addresses {
+        Some((address_a, address_b)) => {
+            0.5 * name + 0.3 * jaro_winkler(&address_a, &address_b) + 0.2 * proximity
+        }
+        None => 0.7 * name + 0.3 * proximity,
+    }
+}
+
+struct Candidate<'a> {
+    keep: &'a DedupLocation,
+    merge: &'a DedupLocation,
+    score: f64,
+    distance_m: f64,
+}
+
+/// Which of a duplicate pair to keep, or `None` when both are claimed
+fn pick_survivor<'a>(
+    a: &'a DedupLocation,
+    b: &'a DedupLocation,
+) -> Option<(&'a DedupLocation, &'a DedupLocation)> {
+    if a.claimed && b.claimed {
+        return None;
+    }
+    let rank = |l: &DedupLocation| (l.claimed, l.artists, -l.id);
+    Some(if rank(a) >= rank(b) { (a, b) } else { (b, a) })
+}
+
+/// Pairs in one state scoring at least `min_score`, best first. Shops are
+/// sorted by latitude, so each is only compared with the ones after it
+/// until they're too far north.
+fn find_duplicates<'a>(locations: &'a [DedupLocation], config: &DedupConfig) -> Vec<Candidate<'a>> {
+    let max_lat_delta = config.max_distance_m / METERS_PER_DEGREE_LAT;
+    let mut candidates = Vec::new();
+
+    for (i, a) in locations.iter().enumerate() {
+        for b in &locations[i + 1..] {
+            if b.lat - a.lat > max_lat_delta {
+                break;
+            }
+            let distance_m = distance_between(a, b);
+            if distance_m > config.max_distance_m {
+                continue;
+            }
+            let score = similarity(a, b, distance_m, config.max_distance_m);
+            if score < config.min_score {
+                continue;
+            }
+
+            match pick_survivor(a, b) {
+                Some((keep, merge)) => candidates.push(Candidate {
+                    keep,
+                    merge,
+                    score,
+                    distance_m,
+                }),
+                None => println!(
+                    "   ⚠️  Both claimed, not merging: '{}' (#{}) and '{}' (#{}), score {:.2}",
+                    a.name, a.id, b.name, b.id, score
+                ),
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
+}
+
+pub async fn dedup_locations(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = &config::get().dedup;
+    let dry_run = env::var("DEDUP_DRY_RUN").is_ok_and(|v| v == "true");
+    println!(
+        "🔁 Deduplicating shops within {:.0}m scoring at least {:.2}{}",
+        config.max_distance_m,
+        config.min_score,
+        if dry_run { " (dry run)" } else { "" }
+    );
+
+    let mut merged = 0;
+    let mut artists_moved = 0;
+    for state in repository::get_location_states(pool).await? {
+        let locations =
+            repository::get_locations_for_dedup(pool, &state, PLACEHOLDER_LOCATION_ID).await?;
+        let candidates = find_duplicates(&locations, config);
+        if candidates.is_empty() {
+            continue;
+        }
+        println!("📍 {}: {} duplicate pair(s)", state, candidates.len());
+
+        // Shops already merged or kept this run; pairs with them are left
+        // for the next run, against the merged shop's new artist count
+        let mut touched: HashSet<i64> = HashSet::new();
+        for candidate in candidates {
+            let (keep, merge) = (candidate.keep, candidate.merge);
+            if touched.contains(&keep.id) || touched.contains(&merge.id) {
+                continue;
+            }
+            touched.insert(keep.id);
+            touched.insert(merge.id);
+            println!(
+                "   '{}' (#{}) ⬅ '{}' (#{}): score {:.2}, {:.0}m apart",
+                keep.name, keep.id, merge.name, merge.id, candidate.score, candidate.distance_m
+            );
+            if dry_run {
+                continue;
+            }
+
+            match repository::merge_locations(
+                pool,
+                keep.id,
+                merge.id,
+                candidate.score,
+                candidate.distance_m,
+            )
+            .await?
+            {
+                Some(result) => {
+                    merged += 1;
+                    artists_moved += result.artists_moved;
+                    println!(
+                        "      ✅ Merged, moving {} artist(s) and {} photo(s)",
+                        result.artists_moved, result.photos_moved
+                    );
+                }
+                None => println!("      ⏭️  One of them is gone, skipping"),
+            }
+        }
+    }
+
+    println!(
+        "✅ Merged {} duplicate shop(s), moving {} artist(s)",
+        merged, artists_moved
+    );
+    Ok(())
+}
@@ -4,11 +4,20 @@ use shared_types::CountyBoundary;
 use sqlx::PgPool;
 
 pub async fn ingest_google(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    ingest_stale_counties(pool, 3500, 160).await
+}
+
+/// Searches up to `county_limit` counties not ingested in the last
+/// `days_till_refetch` days, least recently ingested first. Stops between
+/// counties when the daemon is shutting down.
+pub async fn ingest_stale_counties(
+    pool: &PgPool,
+    county_limit: i16,
+    days_till_refetch: i16,
+) -> Result<(), Box<dyn std::error::Error>> {
     let limit_results_to: i8 = 20;
     let max_iter: i8 = 10;
 
-    let county_limit: i16 = 3500;
-    let days_till_refetch: i16 = 160;
     let county_boundaries: Vec<CountyBoundary> =
         fetch_county_boundaries(pool, county_limit, days_till_refetch)
             .await
@@ -20,7 +29,17 @@ pub async fn ingest_google(pool: &PgPool) -> Result<(), Box<dyn std::error::Erro
     }
 
     for county_boundary in county_boundaries {
+        if crate::actions::daemon::shutdown_requested() {
+            println!("Shutdown requested, leaving the remaining counties for the next run.");
+            break;
+        }
+        if let Err(e) = crate::services::costs::check_run_budget() {
+            println!("{}, leaving the remaining counties for the next run.", e);
+            break;
+        }
         println!("Processing county: {}", county_boundary.name);
+        // Places spend is tracked per county; it's the finest grain this job has
+        crate::services::costs::set_location(Some(&county_boundary.name), None);
 
         if let Err(e) = process_county(pool, &county_boundary, limit_results_to, max_iter).await {
             println!("Error processing county {}: {}", county_boundary.name, e);
@@ -31,7 +50,7 @@ pub async fn ingest_google(pool: &PgPool) -> Result<(), Box<dyn std::error::Erro
     Ok(())
 }
 
-async fn process_county(
+pub(crate) async fn process_county(
     pool: &PgPool,
     county_boundary: &CountyBoundary,
     limit_results_to: i8,
@@ -1,7 +1,6 @@
 use reqwest::{header::HeaderMap, Client};
 use serde_json::{json, Value};
 use shared_types::CountyBoundary;
-use std::env;
 
 fn make_body(county_boundary: &CountyBoundary, page_size: i8, page_token: Option<&str>) -> Value {
     let CountyBoundary {
@@ -38,7 +37,9 @@ fn make_body(county_boundary: &CountyBoundary, page_size: i8, page_token: Option
 }
 
 fn make_headers() -> HeaderMap {
-    let google_key: String = env::var("GOOGLE_PLACES_KEY").expect("Google key to be set");
+    let google_key = crate::config::get()
+        .google_places_api_key()
+        .unwrap_or_else(|e| panic!("{}", e));
 
     let mut headers: HeaderMap = HeaderMap::new();
     headers.insert(
@@ -0,0 +1,171 @@
+use sqlx::{PgPool, Row};
+use std::env;
+
+use crate::repository;
+
+/// Location row that artists get parked on when their shop couldn't be resolved.
+/// Artist signup also uses it until onboarding picks a shop.
+pub(crate) const PLACEHOLDER_LOCATION_ID: i64 = 1;
+
+/// A referential check that foreign keys don't cover.
+///
+/// `detect_sql` must return `entity_id BIGINT` and `details TEXT`. Repair
+/// statements are run in order inside one transaction with the offending ids
+/// bound as `$1::bigint[]`; checks without them are report-only.
+struct IntegrityCheck {
+    name: &'static str,
+    entity_table: &'static str,
+    detect_sql: String,
+    repair_sql: &'static [&'static str],
+}
+
+fn checks() -> Vec<IntegrityCheck> {
+    vec![
+        IntegrityCheck {
+            name: "artist_placeholder_location",
+            entity_table: "artists",
+            detect_sql: format!(
+                "SELECT a.id::bigint as entity_id,
+                        COALESCE(a.name, a.instagram_handle, 'unnamed artist') as details
+                 FROM artists a
+                 WHERE a.location_id = {}
+                   AND a.availability_status IS DISTINCT FROM 'pending_onboarding'",
+                PLACEHOLDER_LOCATION_ID
+            ),
+            // Needs a human to pick the right shop
+            repair_sql: &[],
+        },
+        IntegrityCheck {
+            name: "artist_missing_location",
+            entity_table: "artists",
+            detect_sql: format!(
+                "SELECT a.id::bigint as entity_id,
+                        'location_id ' || a.location_id as details
+                 FROM artists a
+                 LEFT JOIN locations l ON l.id = a.location_id
+                 WHERE l.id IS NULL AND a.location_id <> {}",
+                PLACEHOLDER_LOCATION_ID
+            ),
+            repair_sql: &[],
+        },
+        IntegrityCheck {
+            name: "image_without_artist",
+            entity_table: "artists_images",
+            detect_sql: "SELECT ai.id::bigint as entity_id,
+                                ai.short_code || ' (artist_id ' || ai.artist_id || ')' as details
+                         FROM artists_images ai
+                         LEFT JOIN artists a ON a.id = ai.artist_id
+                         WHERE a.id IS NULL"
+                .to_string(),
+            repair_sql: &[
+                "DELETE FROM artists_images_styles WHERE artists_images_id = ANY($1::bigint[])",
+                "DELETE FROM image_style_llm_recommendations WHERE artists_images_id = ANY($1::bigint[])",
+                "DELETE FROM user_favorites WHERE artists_images_id = ANY($1::bigint[])",
+                "DELETE FROM artists_images WHERE id = ANY($1::bigint[])",
+            ],
+        },
+        IntegrityCheck {
+            name: "image_style_without_image",
+            entity_table: "artists_images_styles",
+            detect_sql: "SELECT ais.id::bigint as entity_id,
+                                'artists_images_id ' || ais.artists_images_id as details
+                         FROM artists_images_styles ais
+                         LEFT JOIN artists_images ai ON ai.id = ais.artists_images_id
+                         WHERE ai.id IS NULL"
+                .to_string(),
+            repair_sql: &["DELETE FROM artists_images_styles WHERE id = ANY($1::bigint[])"],
+        },
+        IntegrityCheck {
+            name: "availability_without_artist",
+            entity_table: "artist_availability",
+            detect_sql: "SELECT av.id::bigint as entity_id,
+                                'artist_id ' || av.artist_id as details
+                         FROM artist_availability av
+                         LEFT JOIN artists a ON a.id = av.artist_id
+                         WHERE a.id IS NULL"
+                .to_string(),
+            repair_sql: &["DELETE FROM artist_availability WHERE id = ANY($1::bigint[])"],
+        },
+        IntegrityCheck {
+            name: "recurring_rule_without_artist",
+            entity_table: "recurring_rules",
+            detect_sql: "SELECT r.id::bigint as entity_id,
+                                'artist_id ' || r.artist_id as details
+                         FROM recurring_rules r
+                         LEFT JOIN artists a ON a.id = r.artist_id
+                         WHERE a.id IS NULL"
+                .to_string(),
+            repair_sql: &["DELETE FROM recurring_rules WHERE id = ANY($1::bigint[])"],
+        },
+        IntegrityCheck {
+            name: "location_unmatched_city",
+            entity_table: "locations",
+            detect_sql: "SELECT l.id::bigint as entity_id,
+                                COALESCE(l.city, 'no city') || ', ' || COALESCE(l.state, 'no state')
+                                    as details
+                         FROM locations l
+                         WHERE l.city_id IS NULL
+                           AND (l.is_person IS NULL OR l.is_person = 0)"
+                .to_string(),
+            // Needs the city added to the reference table, or the location fixed
+            repair_sql: &[],
+        },
+    ]
+}
+
+/// Finds orphaned rows, records them as data quality issues and, when
+/// `INTEGRITY_REPAIR=true`, applies the safe repairs.
+pub async fn check_integrity(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let repair = env::var("INTEGRITY_REPAIR")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false);
+
+    run_checks(pool, repair).await
+}
+
+/// Records every check's findings in `data_quality_issues`, repairing the
+/// repairable ones when `repair` is set
+pub async fn run_checks(pool: &PgPool, repair: bool) -> Result<(), Box<dyn std::error::Error>> {
+    println!(
+        "🔎 Running {} integrity checks{}",
+        checks().len(),
+        if repair { " with repairs" } else { "" }
+    );
+
+    for check in checks() {
+        let rows = sqlx::query(&check.detect_sql).fetch_all(pool).await?;
+
+        let found: Vec<(i64, Option<String>)> = rows
+            .iter()
+            .map(|row| (row.get("entity_id"), row.get("details")))
+            .collect();
+
+        let repairable = !check.repair_sql.is_empty();
+        repository::record_data_quality_issues(
+            pool,
+            check.name,
+            check.entity_table,
+            repairable,
+            &found,
+        )
+        .await?;
+
+        println!("   {}: {} issue(s)", check.name, found.len());
+
+        if !repair || !repairable || found.is_empty() {
+            continue;
+        }
+
+        let ids: Vec<i64> = found.iter().map(|(id, _)| *id).collect();
+        let mut tx = pool.begin().await?;
+        for statement in check.repair_sql {
+            sqlx::query(statement).bind(&ids).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        repository::resolve_data_quality_issues(pool, check.name, &ids, "repaired").await?;
+        println!("   ✅ Repaired {} {} row(s)", ids.len(), check.entity_table);
+    }
+
+    Ok(())
+}
@@ -1,5 +1,97 @@
 pub mod apify_scraper;
+pub mod backfill;
+pub mod city_requests;
+pub mod daemon;
+pub mod dedup_locations;
 pub mod google_api_ingestion;
+pub mod integrity_check;
+pub mod rebuild_derived;
+pub mod reddit_retry;
+pub mod refresh_portfolios;
 pub mod reddit_scraper;
 pub mod scraper;
 pub mod style_extraction;
+
+use sqlx::PgPool;
+
+/// A one-shot ingestion job, picked with `ACTION` or scheduled by the daemon
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IngestAction {
+    Scrape,
+    GoogleApi,
+    ExtractStyles,
+    RedditScraper,
+    IntegrityCheck,
+    Backfill,
+    CityRequests,
+    RebuildDerived,
+    RedditRetryFailed,
+    DedupLocations,
+    RefreshPortfolios,
+}
+
+impl IngestAction {
+    pub const ALL: [IngestAction; 11] = [
+        Self::Scrape,
+        Self::GoogleApi,
+        Self::ExtractStyles,
+        Self::RedditScraper,
+        Self::IntegrityCheck,
+        Self::Backfill,
+        Self::CityRequests,
+        Self::RebuildDerived,
+        Self::RedditRetryFailed,
+        Self::DedupLocations,
+        Self::RefreshPortfolios,
+    ];
+
+    pub fn parse(action: &str) -> Option<Self> {
+        match action {
+            "SCRAPE_HTML" => Some(Self::Scrape),
+            "GOOGLE_API" => Some(Self::GoogleApi),
+            "EXTRACT_STYLES" => Some(Self::ExtractStyles),
+            "REDDIT_SCRAPER" => Some(Self::RedditScraper),
+            "INTEGRITY_CHECK" => Some(Self::IntegrityCheck),
+            "BACKFILL" => Some(Self::Backfill),
+            "CITY_REQUESTS" => Some(Self::CityRequests),
+            "REBUILD_DERIVED" => Some(Self::RebuildDerived),
+            "REDDIT_RETRY_FAILED" => Some(Self::RedditRetryFailed),
+            "DEDUP_LOCATIONS" => Some(Self::DedupLocations),
+            "REFRESH_PORTFOLIOS" => Some(Self::RefreshPortfolios),
+            _ => None,
+        }
+    }
+
+    /// The `ACTION` value, also used to label the run's recorded costs
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Scrape => "SCRAPE_HTML",
+            Self::GoogleApi => "GOOGLE_API",
+            Self::ExtractStyles => "EXTRACT_STYLES",
+            Self::RedditScraper => "REDDIT_SCRAPER",
+            Self::IntegrityCheck => "INTEGRITY_CHECK",
+            Self::Backfill => "BACKFILL",
+            Self::CityRequests => "CITY_REQUESTS",
+            Self::RebuildDerived => "REBUILD_DERIVED",
+            Self::RedditRetryFailed => "REDDIT_RETRY_FAILED",
+            Self::DedupLocations => "DEDUP_LOCATIONS",
+            Self::RefreshPortfolios => "REFRESH_PORTFOLIOS",
+        }
+    }
+
+    pub async fn run(self, pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            Self::Scrape => scraper::scrape(pool).await,
+            Self::GoogleApi => google_api_ingestion::driver::ingest_google(pool).await,
+            Self::ExtractStyles => style_extraction::extract_styles(pool).await,
+            Self::RedditScraper => reddit_scraper::run_reddit_scraper(pool).await,
+            Self::IntegrityCheck => integrity_check::check_integrity(pool).await,
+            Self::Backfill => backfill::run_backfill(pool).await,
+            Self::CityRequests => city_requests::ingest_requested_cities(pool).await,
+            Self::RebuildDerived => rebuild_derived::rebuild_derived(pool).await,
+            Self::RedditRetryFailed => reddit_retry::retry_failed_pending(pool).await,
+            Self::DedupLocations => dedup_locations::dedup_locations(pool).await,
+            Self::RefreshPortfolios => refresh_portfolios::refresh_portfolios(pool).await,
+        }
+    }
+}
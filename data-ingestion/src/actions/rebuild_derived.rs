@@ -0,0 +1,267 @@
+//! Rebuilds every derived table and column from its source data.
+//!
+//! Derived data is normally kept current by triggers and application code,
+//! but can drift after a database restore, a bulk import that bypassed the
+//! app, or a change to how it's derived. `ACTION=REBUILD_DERIVED` recomputes
+//! all of it in dependency order, printing each step as it goes.
+//!
+//! Only one rebuild runs at a time (advisory lock). Each run is tracked as a
+//! `rebuild_derived` job on the `ingestion` queue of `background_jobs`, whose
+//! `progress` names the current step.
+//! `REBUILD_ONLY=step,step` limits a run to the named steps, still in
+//! dependency order.
+
+use sqlx::{PgPool, Row};
+use std::collections::HashSet;
+use std::env;
+use std::time::Instant;
+
+use crate::actions::integrity_check;
+use crate::repository;
+
+const LOCK_KEY: &str = "rebuild_derived";
+
+enum StepWork {
+    /// Statements run in order in one transaction
+    Sql(&'static [&'static str]),
+    /// Re-detects data quality issues, without repairing anything
+    IntegrityChecks,
+}
+
+struct Step {
+    name: &'static str,
+    description: &'static str,
+    /// Steps whose output this one reads
+    after: &'static [&'static str],
+    work: StepWork,
+}
+
+fn steps() -> Vec<Step> {
+    vec![
+        Step {
+            name: "search_columns",
+            description: "normalized search columns for artists, locations and cities",
+            after: &[],
+            work: StepWork::Sql(&[
+                "UPDATE artists SET name_search = search_normalize(name)
+                 WHERE name_search IS DISTINCT FROM search_normalize(name)",
+                "UPDATE locations
+                 SET city_search = search_normalize(city),
+                     county_search = search_normalize(county)
+                 WHERE city_search IS DISTINCT FROM search_normalize(city)
+                    OR county_search IS DISTINCT FROM search_normalize(county)",
+                "UPDATE cities SET city_search = search_normalize(city)
+                 WHERE city_search IS DISTINCT FROM search_normalize(city)",
+            ]),
+        },
+        Step {
+            name: "location_cities",
+            description: "canonical cities for locations",
+            after: &["search_columns"],
+            // Same matching as the insert trigger, for when the cities table
+            // has changed underneath existing locations
+            work: StepWork::Sql(&["UPDATE locations l
+                 SET city_id = m.city_id, city_match = m.method
+                 FROM locations src
+                 LEFT JOIN LATERAL match_city(src.city, src.state, src.lat, src.long) m ON true
+                 WHERE src.id = l.id
+                   AND (l.city_id, l.city_match) IS DISTINCT FROM (m.city_id, m.method)"]),
+        },
+        Step {
+            name: "response_times",
+            description: "first artist responses to booking requests",
+            after: &[],
+            // Same derivation as the migration that added the column
+            work: StepWork::Sql(&["UPDATE booking_requests br
+                 SET first_responded_at = COALESCE(
+                         (SELECT MIN(bm.created_at::timestamptz)
+                          FROM booking_messages bm
+                          WHERE bm.booking_request_id = br.id AND bm.sender_type = 'artist'),
+                         CASE WHEN br.status <> 'pending' THEN br.updated_at::timestamptz END
+                     )
+                 WHERE br.first_responded_at IS NULL"]),
+        },
+        Step {
+            name: "data_quality_issues",
+            description: "open data quality issues",
+            after: &["search_columns", "location_cities", "response_times"],
+            work: StepWork::IntegrityChecks,
+        },
+        Step {
+            name: "planner_statistics",
+            description: "query planner statistics for rebuilt and bulk-loaded tables",
+            after: &[
+                "search_columns",
+                "location_cities",
+                "response_times",
+                "data_quality_issues",
+            ],
+            work: StepWork::Sql(&[
+                "ANALYZE artists",
+                "ANALYZE artists_images",
+                "ANALYZE artists_images_styles",
+                "ANALYZE locations",
+                "ANALYZE cities",
+                "ANALYZE booking_requests",
+                "ANALYZE data_quality_issues",
+            ]),
+        },
+    ]
+}
+
+/// Orders steps so each runs after the steps it depends on
+fn dependency_order(steps: Vec<Step>) -> Result<Vec<Step>, String> {
+    let names: HashSet<&str> = steps.iter().map(|step| step.name).collect();
+    for step in &steps {
+        if let Some(missing) = step.after.iter().find(|dep| !names.contains(*dep)) {
+            return Err(format!("Step {} depends on unknown step {}", step.name, missing));
+        }
+    }
+
+    let mut remaining = steps;
+    let mut ordered: Vec<Step> = Vec::new();
+    while !remaining.is_empty() {
+        let done: HashSet<&str> = ordered.iter().map(|step| step.name).collect();
+        let Some(index) = remaining
+            .iter()
+            .position(|step| step.after.iter().all(|dep| done.contains(dep)))
+        else {
+            let names: Vec<&str> = remaining.iter().map(|step| step.name).collect();
+            return Err(format!("Dependency cycle between steps: {}", names.join(", ")));
+        };
+        ordered.push(remaining.remove(index));
+    }
+
+    Ok(ordered)
+}
+
+async fn run_step(pool: &PgPool, step: &Step) -> Result<u64, Box<dyn std::error::Error>> {
+    match step.work {
+        StepWork::Sql(statements) => {
+            let mut rows = 0;
+            let mut tx = pool.begin().await?;
+            for statement in statements {
+                rows += sqlx::query(statement)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+            }
+            tx.commit().await?;
+            Ok(rows)
+        }
+        StepWork::IntegrityChecks => {
+            integrity_check::run_checks(pool, false).await?;
+            Ok(0)
+        }
+    }
+}
+
+pub async fn rebuild_derived(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut steps = dependency_order(steps())?;
+
+    let only: Vec<String> = env::var("REBUILD_ONLY")
+        .unwrap_or_default()
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    if !only.is_empty() {
+        let names: Vec<&str> = steps.iter().map(|step| step.name).collect();
+        if let Some(unknown) = only.iter().find(|name| !names.contains(&name.as_str())) {
+            return Err(format!(
+                "Unknown step {}; steps are: {}",
+                unknown,
+                names.join(", ")
+            )
+            .into());
+        }
+        steps.retain(|step| only.iter().any(|name| name == step.name));
+    }
+
+    // Held on a dedicated connection for the whole run
+    let mut lock_conn = pool.acquire().await?;
+    let locked: bool = sqlx::query("SELECT pg_try_advisory_lock(hashtext($1)) as locked")
+        .bind(LOCK_KEY)
+        .fetch_one(&mut *lock_conn)
+        .await?
+        .get("locked");
+    if !locked {
+        return Err("A rebuild of derived tables is already running".into());
+    }
+
+    let step_names: Vec<&str> = steps.iter().map(|step| step.name).collect();
+    let job_id = repository::create_job(
+        pool,
+        "rebuild_derived",
+        &serde_json::json!({ "steps": step_names }),
+    )
+    .await?;
+
+    println!(
+        "🔁 Rebuilding derived data (job {}): {}",
+        job_id,
+        step_names.join(" → ")
+    );
+
+    let started = Instant::now();
+    let mut completed: Vec<serde_json::Value> = Vec::new();
+    let result: Result<(), Box<dyn std::error::Error>> = async {
+        for (i, step) in steps.iter().enumerate() {
+            let progress = serde_json::json!({
+                "step": step.name,
+                "completed": completed,
+                "total_steps": steps.len(),
+            });
+            repository::update_job_progress(pool, job_id, "running", &progress).await?;
+
+            println!(
+                "   [{}/{}] {}: {}",
+                i + 1,
+                steps.len(),
+                step.name,
+                step.description
+            );
+            let step_started = Instant::now();
+            let rows = run_step(pool, step)
+                .await
+                .map_err(|e| format!("step {} failed: {}", step.name, e))?;
+            let seconds = step_started.elapsed().as_secs_f64();
+            println!("         {} rows changed in {:.1}s", rows, seconds);
+
+            completed.push(serde_json::json!({
+                "name": step.name,
+                "rows": rows,
+                "seconds": (seconds * 10.0).round() / 10.0,
+            }));
+        }
+        Ok(())
+    }
+    .await;
+
+    let progress = serde_json::json!({
+        "completed": completed,
+        "total_steps": steps.len(),
+    });
+    repository::update_job_progress(pool, job_id, "running", &progress).await?;
+
+    match &result {
+        Ok(()) => {
+            repository::finish_job(pool, job_id, None).await?;
+            println!(
+                "✅ Rebuilt derived data in {:.1}s",
+                started.elapsed().as_secs_f64()
+            );
+        }
+        Err(e) => {
+            repository::finish_job(pool, job_id, Some(&e.to_string())).await?;
+            eprintln!("❌ Rebuild failed: {} (earlier steps were kept)", e);
+        }
+    }
+
+    sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+        .bind(LOCK_KEY)
+        .execute(&mut *lock_conn)
+        .await?;
+
+    result
+}
@@ -0,0 +1,196 @@
+//! Gives failed reddit_artists_pending records another chance.
+//!
+//! Processing a pending artist fails for reasons that are often transient,
+//! like an Apify timeout behind `artist_profile_not_found` or an OpenAI
+//! error behind `shop_not_found`. `ACTION=REDDIT_RETRY_FAILED` re-runs the
+//! artist-first processing for failed handles, waiting exponentially longer
+//! after each failed retry, then prints success rates by attempt number and
+//! by the reason records had failed.
+//!
+//! Tuning, read by [`crate::config`]:
+//! - `REDDIT_RETRY_REASONS`: failure reasons to retry, comma separated
+//!   (default `shop_not_found,artist_profile_not_found`; `all` for any)
+//! - `REDDIT_RETRY_MIN_AGE_HOURS`: only records that failed at least this
+//!   long ago (default 24)
+//! - `REDDIT_RETRY_MAX_AGE_DAYS`: skip records that failed longer ago
+//! - `REDDIT_RETRY_MAX_ATTEMPTS`: retries per record (default 4)
+//! - `REDDIT_RETRY_BACKOFF_HOURS`: wait after the first failed retry,
+//!   doubled after each one after that (default 12)
+//! - `REDDIT_RETRY_LIMIT`: records per run (default 50)
+//!
+//! `REDDIT_RETRY_REPORT_ONLY=true` prints the report without retrying.
+
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::env;
+
+use crate::actions::reddit_scraper::process_artist_from_pending;
+use crate::config::{self, RedditRetryConfig};
+use crate::repository::{self, RetryOutcomeCount};
+use crate::services::costs::{self, BudgetExceeded};
+
+/// Hours to wait after failed retry number `attempt` (1-based)
+fn backoff_after(attempt: i32, base_hours: f64) -> f64 {
+    base_hours * 2f64.powi(attempt.saturating_sub(1).min(16))
+}
+
+pub async fn retry_failed_pending(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = &config::get().reddit_retry;
+    let report_only = env::var("REDDIT_RETRY_REPORT_ONLY").is_ok_and(|v| v == "true" || v == "1");
+
+    if !report_only {
+        retry_batch(pool, config).await?;
+    }
+
+    print_report(&repository::get_retry_outcome_counts(pool).await?);
+    Ok(())
+}
+
+async fn retry_batch(
+    pool: &PgPool,
+    config: &RedditRetryConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let failed = repository::get_failed_pending_for_retry(
+        pool,
+        &config.reasons,
+        config.min_age_hours,
+        config.max_age_days,
+        config.max_attempts,
+        config.limit,
+    )
+    .await?;
+
+    if failed.is_empty() {
+        println!("No failed pending artists are due a retry.");
+        return Ok(());
+    }
+    println!("🔁 Retrying {} failed pending artists", failed.len());
+
+    let (mut succeeded, mut still_failed) = (0, 0);
+    for record in failed {
+        if let Err(e) = costs::check_run_budget() {
+            println!("💸 Stopping, retries left for the next run: {}", e);
+            break;
+        }
+        let attempt = record.retry_count + 1;
+        println!(
+            "\n🔁 @{} (attempt {}, failed with {})",
+            record.instagram_handle,
+            attempt,
+            record.failure_reason.as_deref().unwrap_or("unknown")
+        );
+        costs::set_location(Some(&record.city), Some(&record.state));
+
+        let result = process_artist_from_pending(
+            pool,
+            &record.instagram_handle,
+            &record.city,
+            &record.state,
+            0,
+        )
+        .await;
+        let processed = match result {
+            Ok(_) => true,
+            // Not the artist's fault, so it doesn't count as an attempt
+            Err(e) if e.downcast_ref::<BudgetExceeded>().is_some() => {
+                println!("   💸 {}, leaving it for the next run", e);
+                continue;
+            }
+            Err(e) => {
+                println!("   ❌ {}", e);
+                false
+            }
+        };
+
+        // Processing records its own outcome on the pending rows; errors it
+        // returns without doing so leave the record failed as it was
+        let (status, reason) =
+            repository::get_pending_artist_outcome(pool, &record.instagram_handle)
+                .await?
+                .unwrap_or_else(|| ("failed".to_string(), record.failure_reason.clone()));
+        let outcome = if processed && status == "success" {
+            succeeded += 1;
+            "success"
+        } else {
+            still_failed += 1;
+            "failed"
+        };
+        let failed = outcome == "failed";
+        let backoff_hours = failed.then(|| backoff_after(attempt, config.backoff_hours));
+
+        repository::record_pending_retry(
+            pool,
+            &record.instagram_handle,
+            attempt,
+            record.failure_reason.as_deref(),
+            outcome,
+            reason.as_deref().filter(|_| failed),
+            backoff_hours,
+        )
+        .await?;
+    }
+
+    println!(
+        "\n✅ Retries finished: {} succeeded, {} still failing",
+        succeeded, still_failed
+    );
+    Ok(())
+}
+
+fn success_rate(successes: i64, attempts: i64) -> f64 {
+    if attempts == 0 {
+        0.0
+    } else {
+        successes as f64 * 100.0 / attempts as f64
+    }
+}
+
+fn print_report(counts: &[RetryOutcomeCount]) {
+    if counts.is_empty() {
+        println!("\nNo retries recorded yet.");
+        return;
+    }
+
+    let mut by_attempt: BTreeMap<i32, (i64, i64)> = BTreeMap::new();
+    let mut by_reason: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    for count in counts {
+        let attempt = by_attempt.entry(count.attempt).or_default();
+        attempt.0 += count.attempts;
+        attempt.1 += count.successes;
+        let reason = by_reason
+            .entry(count.failure_reason.as_deref().unwrap_or("unknown"))
+            .or_default();
+        reason.0 += count.attempts;
+        reason.1 += count.successes;
+    }
+
+    println!("\n📊 Retry success rates by attempt");
+    println!(
+        "{:>8} {:>10} {:>10} {:>8}",
+        "attempt", "retries", "succeeded", "rate"
+    );
+    for (attempt, (attempts, successes)) in &by_attempt {
+        println!(
+            "{:>8} {:>10} {:>10} {:>7.1}%",
+            attempt,
+            attempts,
+            successes,
+            success_rate(*successes, *attempts)
+        );
+    }
+
+    println!("\n📊 Retry success rates by failure reason");
+    println!(
+        "{:<28} {:>10} {:>10} {:>8}",
+        "reason", "retries", "succeeded", "rate"
+    );
+    for (reason, (attempts, successes)) in &by_reason {
+        println!(
+            "{:<28} {:>10} {:>10} {:>7.1}%",
+            reason,
+            attempts,
+            successes,
+            success_rate(*successes, *attempts)
+        );
+    }
+}
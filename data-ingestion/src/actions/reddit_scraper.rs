@@ -1,9 +1,22 @@
 // Reddit Artist Discovery
 // Scrapes /r/tattoo subreddit to find artists and match them to existing shops
 // Also scrapes shop Instagram bios to discover additional artists
-
+//
+// Resumable: each post is marked in reddit_processed_posts once its artists
+// are extracted, and each city's progress through the phases is kept in a
+// `reddit_city` job, so a rerun after a failure skips the posts and phases
+// already done (and picks up unfinished cities first).
+//
+// `--dry-run` (or REDDIT_DRY_RUN=true) scrapes and extracts posts but only
+// logs the pending artists it would add and the artists it would process;
+// nothing is written and no checkpoints are recorded.
+
+use crate::config::RedditScraperConfig;
 use crate::repository::{self, CityStats, CityToScrape};
 use crate::services::apify::RedditPost;
+use crate::services::breakers::{self, OPENAI};
+use crate::services::costs;
+use crate::services::counters;
 use crate::services::google_places::{
     is_tattoo_shop, parse_places_to_locations, search_text_with_location, LocationBounds,
 };
@@ -20,35 +33,13 @@ use strsim::jaro_winkler;
 // Configuration
 // ============================================================================
 
+/// The checked tuning from [`crate::config`], with what this run was asked
+/// to do
 struct Config {
-    max_posts: i32,
-    min_images: i32,
-    rescrape_days: i16,
+    tuning: &'static RedditScraperConfig,
     city_filter: Option<String>,
     state_filter: Option<String>,
-    max_cities: Option<i16>,
-}
-
-fn load_config_from_env() -> Config {
-    Config {
-        max_posts: env::var("REDDIT_MAX_POSTS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(100),
-        min_images: env::var("REDDIT_MIN_IMAGES")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(1),
-        rescrape_days: env::var("REDDIT_RESCRAPE_DAYS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(30),
-        city_filter: env::var("REDDIT_CITY").ok(),
-        state_filter: env::var("REDDIT_STATE").ok(),
-        max_cities: env::var("REDDIT_MAX_CITIES")
-            .ok()
-            .and_then(|s| s.parse().ok()),
-    }
+    dry_run: bool,
 }
 
 // ============================================================================
@@ -74,18 +65,48 @@ const NUM_SHOP_THREADS: usize = 10;
 pub async fn run_reddit_scraper(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
     println!("🚀 Starting Reddit Artist Discovery (Shop-Centric)");
 
-    let config = load_config_from_env();
+    let config = Config {
+        tuning: &crate::config::get().reddit_scraper,
+        city_filter: env::var("REDDIT_CITY").ok(),
+        state_filter: env::var("REDDIT_STATE").ok(),
+        dry_run: env::args().any(|arg| arg == "--dry-run")
+            || env::var("REDDIT_DRY_RUN").is_ok_and(|v| v == "true"),
+    };
+    if config.dry_run {
+        println!("🧪 Dry run: nothing will be written");
+    }
     let cities = select_cities_to_scrape(pool, &config).await?;
 
     println!("📍 Selected {} cities to scrape", cities.len());
 
     for city in cities {
+        if let Err(e) = costs::check_run_budget() {
+            println!("💸 Stopping, cities left for the next run: {}", e);
+            break;
+        }
         println!("\n🌆 Processing: {}, {}", city.city, city.state);
+        costs::set_location(Some(&city.city), Some(&city.state));
 
-        match process_city_shop_centric(&city, pool, &config).await {
-            Ok(_) => println!("✅ Successfully processed {}, {}", city.city, city.state),
-            Err(e) => {
+        if config.dry_run {
+            if let Err(e) = process_city_shop_centric(&city, pool, &config, None).await {
                 eprintln!("❌ Failed to process {}, {}: {}", city.city, city.state, e);
+            }
+            continue;
+        }
+
+        let checkpoint = start_city_checkpoint(&city, pool).await?;
+        let job_id = checkpoint.job_id;
+        match process_city_shop_centric(&city, pool, &config, Some(checkpoint)).await {
+            Ok(_) => {
+                repository::finish_job(pool, job_id, None).await?;
+                println!("✅ Successfully processed {}, {}", city.city, city.state);
+            }
+            Err(e) => {
+                repository::finish_job(pool, job_id, Some(&e.to_string())).await?;
+                eprintln!(
+                    "❌ Failed to process {}, {}: {} (rerun to resume)",
+                    city.city, city.state, e
+                );
                 // Mark city as failed
                 let error_stats = CityStats {
                     posts_found: 0,
@@ -118,10 +139,10 @@ async fn select_cities_to_scrape(
 ) -> Result<Vec<CityToScrape>, Box<dyn std::error::Error>> {
     let cities = repository::get_cities_for_scrape(
         pool,
-        config.max_cities,
+        config.tuning.max_cities,
         config.city_filter.clone(),
         config.state_filter.clone(),
-        config.rescrape_days,
+        config.tuning.rescrape_days,
     )
     .await?;
 
@@ -132,64 +153,110 @@ async fn process_city_shop_centric(
     city: &CityToScrape,
     pool: &PgPool,
     config: &Config,
+    mut checkpoint: Option<CityCheckpoint>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // PHASE 1: Scrape Reddit → populate pending table
-    println!("📱 Phase 1: Scraping Reddit posts...");
-    let posts_found = scrape_reddit_to_pending(city, pool, config).await?;
-    println!("   Found {} posts", posts_found);
+    let done = |checkpoint: &Option<CityCheckpoint>, phase: &str| {
+        checkpoint
+            .as_ref()
+            .is_some_and(|checkpoint| checkpoint.is_done(phase))
+    };
 
-    // PHASE 2: Get pending artists with Instagram handles
-    println!("👤 Phase 2: Identifying artists with Instagram handles...");
-    let pending_artists =
-        repository::get_pending_artists_with_handles(pool, &city.city, &city.state).await?;
-    println!(
-        "   Found {} pending artists with IG handles to process",
-        pending_artists.len()
-    );
+    // PHASE 1: Scrape Reddit → populate pending table
+    let posts_found = match &checkpoint {
+        Some(checkpoint) if checkpoint.is_done("posts") => {
+            println!(
+                "📱 Phase 1: Already done ({} posts)",
+                checkpoint.posts_found
+            );
+            checkpoint.posts_found
+        }
+        _ => {
+            println!("📱 Phase 1: Scraping Reddit posts...");
+            let posts_found = scrape_reddit_to_pending(city, pool, config).await?;
+            println!("   Found {} posts", posts_found);
+            posts_found
+        }
+    };
+    if let Some(checkpoint) = checkpoint.as_mut() {
+        checkpoint.posts_found = posts_found;
+        checkpoint.complete("posts", pool).await?;
+    }
 
-    if pending_artists.is_empty() {
-        println!("   No artists with handles to process, marking city as complete");
-        let stats = CityStats {
-            posts_found,
-            artists_added: 0,
-            artists_updated: 0,
-            artists_pending: 0,
-            artists_added_from_shop_bios: 0,
-            shops_scraped: 0,
-        };
-        finalize_city_stats(city, &stats, pool).await?;
-        return Ok(());
+    if config.dry_run {
+        return log_dry_run_artists(city, pool).await;
     }
 
-    // PHASE 3: Process artists in parallel
-    println!(
-        "⚡ Phase 3: Processing artists with handles ({} threads)...",
-        NUM_SHOP_THREADS
-    );
-    let mut artist_stats = process_artists_parallel(pending_artists, pool).await?;
+    let mut artist_stats = checkpoint
+        .as_ref()
+        .map(|checkpoint| checkpoint.stats.clone())
+        .unwrap_or_default();
 
-    // PHASE 2.5: Get pending artists WITHOUT handles but WITH names
-    println!("🔍 Phase 2.5: Identifying artists without handles (name-based search)...");
-    let pending_artists_no_handles =
-        repository::get_pending_artists_without_handles(pool, &city.city, &city.state).await?;
-    println!(
-        "   Found {} pending artists without handles to search for",
-        pending_artists_no_handles.len()
-    );
+    if !done(&checkpoint, "handles") {
+        // PHASE 2: Get pending artists with Instagram handles
+        println!("👤 Phase 2: Identifying artists with Instagram handles...");
+        let pending_artists =
+            repository::get_pending_artists_with_handles(pool, &city.city, &city.state).await?;
+        println!(
+            "   Found {} pending artists with IG handles to process",
+            pending_artists.len()
+        );
+
+        if pending_artists.is_empty() {
+            println!("   No artists with handles to process, marking city as complete");
+            let stats = CityStats {
+                posts_found,
+                artists_added: 0,
+                artists_updated: 0,
+                artists_pending: 0,
+                artists_added_from_shop_bios: 0,
+                shops_scraped: 0,
+            };
+            finalize_city_stats(city, &stats, pool).await?;
+            return Ok(());
+        }
 
-    // PHASE 3.5: Search Instagram and process found artists
-    if !pending_artists_no_handles.is_empty() {
+        // PHASE 3: Process artists in parallel
         println!(
-            "🔎 Phase 3.5: Searching Instagram and processing artists ({} threads)...",
+            "⚡ Phase 3: Processing artists with handles ({} threads)...",
             NUM_SHOP_THREADS
         );
-        let search_stats = process_artists_via_search(pending_artists_no_handles, pool).await?;
+        let handle_stats = process_artists_parallel(pending_artists, pool).await?;
+        artist_stats.add(&handle_stats);
+
+        if let Some(checkpoint) = checkpoint.as_mut() {
+            checkpoint.stats = artist_stats.clone();
+            checkpoint.complete("handles", pool).await?;
+        }
+    } else {
+        println!("⚡ Phase 3: Already done");
+    }
+
+    if !done(&checkpoint, "names") {
+        // PHASE 2.5: Get pending artists WITHOUT handles but WITH names
+        println!("🔍 Phase 2.5: Identifying artists without handles (name-based search)...");
+        let pending_artists_no_handles =
+            repository::get_pending_artists_without_handles(pool, &city.city, &city.state).await?;
+        println!(
+            "   Found {} pending artists without handles to search for",
+            pending_artists_no_handles.len()
+        );
 
-        // Combine stats from both phases
-        artist_stats.shops_processed += search_stats.shops_processed;
-        artist_stats.artists_added += search_stats.artists_added;
-        artist_stats.artists_updated += search_stats.artists_updated;
-        artist_stats.artists_pending += search_stats.artists_pending;
+        // PHASE 3.5: Search Instagram and process found artists
+        if !pending_artists_no_handles.is_empty() {
+            println!(
+                "🔎 Phase 3.5: Searching Instagram and processing artists ({} threads)...",
+                NUM_SHOP_THREADS
+            );
+            let search_stats = process_artists_via_search(pending_artists_no_handles, pool).await?;
+
+            // Combine stats from both phases
+            artist_stats.add(&search_stats);
+        }
+
+        if let Some(checkpoint) = checkpoint.as_mut() {
+            checkpoint.stats = artist_stats.clone();
+            checkpoint.complete("names", pool).await?;
+        }
     }
 
     // PHASE 4: Update city stats
@@ -206,10 +273,128 @@ async fn process_city_shop_centric(
     Ok(())
 }
 
+/// Dry run stand-in for phases 2–4: lists the pending artists a real run
+/// would go on to process. Artists found in this run's posts aren't
+/// included, since they weren't stored.
+async fn log_dry_run_artists(
+    city: &CityToScrape,
+    pool: &PgPool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let with_handles =
+        repository::get_pending_artists_with_handles(pool, &city.city, &city.state).await?;
+    let without_handles =
+        repository::get_pending_artists_without_handles(pool, &city.city, &city.state).await?;
+
+    println!(
+        "🧪 Would process {} already-pending artists with IG handles and search Instagram for {} by name",
+        with_handles.len(),
+        without_handles.len()
+    );
+    for artist in &with_handles {
+        println!("   @{}", artist.instagram_handle);
+    }
+    for artist in &without_handles {
+        println!("   \"{}\"", artist.artist_name);
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Checkpoints
+// ============================================================================
+
+/// A city's phases in order, as recorded in its job's progress
+const PHASES: [&str; 3] = ["posts", "handles", "names"];
+
+/// Where a city's `reddit_city` job has got to
+struct CityCheckpoint {
+    job_id: i64,
+    completed_phase: Option<String>,
+    posts_found: i32,
+    /// Totals from the completed phases
+    stats: ShopProcessingStats,
+}
+
+impl CityCheckpoint {
+    fn from_progress(job_id: i64, progress: &Value) -> Self {
+        let number = |key: &str| progress[key].as_i64().unwrap_or(0) as i32;
+        CityCheckpoint {
+            job_id,
+            completed_phase: progress["phase"].as_str().map(str::to_string),
+            posts_found: number("posts_found"),
+            stats: ShopProcessingStats {
+                shops_processed: number("shops_processed"),
+                artists_added: number("artists_added"),
+                artists_updated: number("artists_updated"),
+                artists_pending: number("artists_pending"),
+            },
+        }
+    }
+
+    fn progress(&self) -> Value {
+        serde_json::json!({
+            "phase": self.completed_phase,
+            "posts_found": self.posts_found,
+            "shops_processed": self.stats.shops_processed,
+            "artists_added": self.stats.artists_added,
+            "artists_updated": self.stats.artists_updated,
+            "artists_pending": self.stats.artists_pending,
+        })
+    }
+
+    fn is_done(&self, phase: &str) -> bool {
+        let position = |phase: &str| PHASES.iter().position(|known| *known == phase);
+        match (
+            self.completed_phase.as_deref().and_then(position),
+            position(phase),
+        ) {
+            (Some(completed), Some(phase)) => phase <= completed,
+            _ => false,
+        }
+    }
+
+    /// Records `phase` as done, so a rerun starts after it
+    async fn complete(&mut self, phase: &str, pool: &PgPool) -> Result<(), sqlx::Error> {
+        if !self.is_done(phase) {
+            self.completed_phase = Some(phase.to_string());
+        }
+        repository::update_job_progress(pool, self.job_id, "running", &self.progress()).await
+    }
+}
+
+/// Picks up the city's unfinished job, or starts a new one
+async fn start_city_checkpoint(
+    city: &CityToScrape,
+    pool: &PgPool,
+) -> Result<CityCheckpoint, sqlx::Error> {
+    match repository::find_unfinished_reddit_city_job(pool, &city.city, &city.state).await? {
+        Some((job_id, progress)) => {
+            let checkpoint = CityCheckpoint::from_progress(job_id, &progress);
+            repository::update_job_progress(pool, job_id, "running", &checkpoint.progress())
+                .await?;
+            match &checkpoint.completed_phase {
+                Some(phase) => println!("⏩ Resuming after the {} phase (job {})", phase, job_id),
+                None => println!("⏩ Resuming (job {})", job_id),
+            }
+            Ok(checkpoint)
+        }
+        None => {
+            let payload = serde_json::json!({
+                "city": city.city,
+                "state": city.state,
+            });
+            let job_id = repository::create_job(pool, "reddit_city", &payload).await?;
+            Ok(CityCheckpoint::from_progress(job_id, &Value::Null))
+        }
+    }
+}
+
 // ============================================================================
 // Shop-Centric Helper Functions
 // ============================================================================
 
+#[derive(Clone, Default)]
 struct ShopProcessingStats {
     shops_processed: i32,
     artists_added: i32,
@@ -217,6 +402,15 @@ struct ShopProcessingStats {
     artists_pending: i32,
 }
 
+impl ShopProcessingStats {
+    fn add(&mut self, other: &ShopProcessingStats) {
+        self.shops_processed += other.shops_processed;
+        self.artists_added += other.artists_added;
+        self.artists_updated += other.artists_updated;
+        self.artists_pending += other.artists_pending;
+    }
+}
+
 async fn scrape_reddit_to_pending(
     city: &CityToScrape,
     pool: &PgPool,
@@ -227,12 +421,25 @@ async fn scrape_reddit_to_pending(
     let posts_found = posts.len() as i32;
 
     // Filter for posts with images
-    let posts_with_images = filter_posts_with_images(posts, config.min_images);
+    let posts_with_images = filter_posts_with_images(posts, config.tuning.min_images);
     println!("📸 Found {} posts with images", posts_with_images.len());
 
+    // Skip posts a previous run already extracted
+    let post_urls: Vec<String> = posts_with_images
+        .iter()
+        .filter_map(|post| post.url.clone())
+        .collect();
+    let processed = repository::get_processed_post_urls(pool, &post_urls).await?;
+    if !processed.is_empty() {
+        println!("⏩ Skipping {} posts already processed", processed.len());
+    }
+
     // Process each post and store in pending table
-    for post in posts_with_images {
-        match extract_and_store_pending(&post, city, pool).await {
+    let unprocessed = posts_with_images
+        .into_iter()
+        .filter(|post| !post.url.as_ref().is_some_and(|url| processed.contains(url)));
+    for post in unprocessed {
+        match extract_and_store_pending(&post, city, pool, config.dry_run).await {
             Ok(_) => {}
             Err(e) => {
                 let url = post.url.as_deref().unwrap_or("unknown");
@@ -248,19 +455,20 @@ async fn extract_and_store_pending(
     post: &RedditPost,
     city: &CityToScrape,
     pool: &PgPool,
+    dry_run: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Extract artist info with OpenAI
     let extracted_artists = extract_artist_info_with_openai(post).await?;
 
     let post_url = post.url.as_deref().unwrap_or("unknown");
 
-    if extracted_artists.is_empty() {
+    let pending_rows: Vec<repository::PendingArtistData> = if extracted_artists.is_empty() {
         // No info extracted - add to pending
         let title = post.title.as_deref().unwrap_or("");
         let body = post.body.as_deref().unwrap_or("");
         let post_context = format!("Title: {}\nBody: {}", title, body);
 
-        let pending = repository::PendingArtistData {
+        vec![repository::PendingArtistData {
             reddit_post_url: Some(post_url.to_string()),
             artist_name: None,
             instagram_handle: None,
@@ -269,50 +477,79 @@ async fn extract_and_store_pending(
             state: city.state.clone(),
             post_context: Some(post_context),
             match_type: "no_artist_info_extracted".to_string(),
-        };
+        }]
+    } else {
+        // Store each extracted artist in pending
+        extracted_artists
+            .into_iter()
+            .map(|artist_data| {
+                let context_parts: Vec<String> = vec![
+                    artist_data
+                        .artist_name
+                        .as_ref()
+                        .map(|n| format!("Name: {}", n)),
+                    artist_data
+                        .instagram
+                        .as_ref()
+                        .map(|ig| format!("Instagram: {}", ig)),
+                    artist_data.shop.as_ref().map(|s| format!("Shop: {}", s)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+
+                let post_context = if !context_parts.is_empty() {
+                    Some(context_parts.join(", "))
+                } else {
+                    None
+                };
+
+                repository::PendingArtistData {
+                    reddit_post_url: Some(post_url.to_string()),
+                    artist_name: artist_data.artist_name.clone(),
+                    instagram_handle: artist_data
+                        .instagram
+                        .as_ref()
+                        .map(|h| normalize_instagram_handle(h)),
+                    shop_name_mentioned: artist_data.shop.clone(),
+                    city: city.city.clone(),
+                    state: city.state.clone(),
+                    post_context,
+                    match_type: "pending".to_string(),
+                }
+            })
+            .collect()
+    };
 
-        repository::insert_reddit_artist_pending(pool, &pending).await?;
+    if dry_run {
+        for pending in &pending_rows {
+            println!(
+                "   🧪 Would add {} (name: {:?}, instagram: {:?}, shop: {:?}) from {}",
+                pending.match_type,
+                pending.artist_name,
+                pending.instagram_handle,
+                pending.shop_name_mentioned,
+                post_url
+            );
+        }
         return Ok(());
     }
 
-    // Store each extracted artist in pending
-    for artist_data in extracted_artists {
-        let context_parts: Vec<String> = vec![
-            artist_data
-                .artist_name
-                .as_ref()
-                .map(|n| format!("Name: {}", n)),
-            artist_data
-                .instagram
-                .as_ref()
-                .map(|ig| format!("Instagram: {}", ig)),
-            artist_data.shop.as_ref().map(|s| format!("Shop: {}", s)),
-        ]
-        .into_iter()
-        .flatten()
-        .collect();
-
-        let post_context = if !context_parts.is_empty() {
-            Some(context_parts.join(", "))
-        } else {
-            None
-        };
-
-        let pending = repository::PendingArtistData {
-            reddit_post_url: Some(post_url.to_string()),
-            artist_name: artist_data.artist_name.clone(),
-            instagram_handle: artist_data
-                .instagram
-                .as_ref()
-                .map(|h| normalize_instagram_handle(h)),
-            shop_name_mentioned: artist_data.shop.clone(),
-            city: city.city.clone(),
-            state: city.state.clone(),
-            post_context,
-            match_type: "pending".to_string(),
-        };
-
-        repository::insert_reddit_artist_pending(pool, &pending).await?;
+    for pending in &pending_rows {
+        repository::insert_reddit_artist_pending(pool, pending).await?;
+    }
+    counters::record_post_processed();
+
+    // Checkpoint the post so a rerun doesn't extract it again
+    if let Some(url) = post.url.as_deref() {
+        repository::mark_post_processed(
+            pool,
+            url,
+            &city.city,
+            &city.state,
+            pending_rows.len() as i32,
+        )
+        .await?;
     }
 
     Ok(())
@@ -595,6 +832,7 @@ async fn process_artist_handle(
         &instagram_url,
     )
     .await?;
+    counters::record_artist_added();
     println!("      ➕ Created artist (@{})", handle);
 
     Ok(ProcessResult::Added)
@@ -606,7 +844,8 @@ async fn call_apify_reddit_scraper(
     config: &Config,
 ) -> Result<Vec<RedditPost>, Box<dyn std::error::Error + Send + Sync>> {
     // Use centralized Apify service
-    let all_items = crate::services::apify::scrape_reddit_posts(city, config.max_posts).await?;
+    let all_items =
+        crate::services::apify::scrape_reddit_posts(city, config.tuning.max_posts).await?;
 
     let total_items = all_items.len();
 
@@ -705,7 +944,7 @@ fn filter_posts_with_images(posts: Vec<RedditPost>, min_images: i32) -> Vec<Redd
 async fn extract_artist_info_with_openai(
     post: &RedditPost,
 ) -> Result<Vec<ExtractedArtist>, Box<dyn std::error::Error>> {
-    let api_key = env::var("OPENAI_API_KEY")?;
+    let api_key = crate::config::get().openai_api_key()?;
 
     // Build context from post title and body
     let title = post.title.as_deref().unwrap_or("");
@@ -730,6 +969,8 @@ All fields are optional - include only what you can confidently extract."#,
         title, post_body
     );
 
+    costs::check_budget()?;
+    OPENAI.check()?;
     let client = reqwest::Client::new();
     let response = client
         .post("https://api.openai.com/v1/chat/completions")
@@ -744,9 +985,10 @@ All fields are optional - include only what you can confidently extract."#,
             "temperature": 0.3
         }))
         .send()
-        .await?;
+        .await;
+    breakers::record_response(&OPENAI, &response);
 
-    let response_json: serde_json::Value = response.json().await?;
+    let response_json: serde_json::Value = response?.json().await?;
 
     let content = response_json["choices"][0]["message"]["content"]
         .as_str()
@@ -767,7 +1009,7 @@ All fields are optional - include only what you can confidently extract."#,
 async fn extract_handles_from_bio(
     bio: &str,
 ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    let api_key = env::var("OPENAI_API_KEY")?;
+    let api_key = crate::config::get().openai_api_key()?;
 
     let prompt = format!(
         r#"Extract all Instagram handles mentioned in this tattoo shop's Instagram bio.
@@ -783,6 +1025,8 @@ If no handles found, return: []"#,
         bio
     );
 
+    costs::check_budget()?;
+    OPENAI.check()?;
     let client = reqwest::Client::new();
     let response = client
         .post("https://api.openai.com/v1/chat/completions")
@@ -797,9 +1041,10 @@ If no handles found, return: []"#,
             "temperature": 0.3
         }))
         .send()
-        .await?;
+        .await;
+    breakers::record_response(&OPENAI, &response);
 
-    let response_json: serde_json::Value = response.json().await?;
+    let response_json: serde_json::Value = response?.json().await?;
 
     let content = response_json["choices"][0]["message"]["content"]
         .as_str()
@@ -843,7 +1088,7 @@ If no handles found, return: []"#,
 async fn extract_shop_info_from_artist_bio(
     bio: &str,
 ) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
-    let api_key = env::var("OPENAI_API_KEY")?;
+    let api_key = crate::config::get().openai_api_key()?;
 
     let prompt = format!(
         r#"Extract the tattoo shop name and Instagram handle from this artist's Instagram bio.
@@ -870,6 +1115,8 @@ Return ONLY a JSON object with two fields:
         bio
     );
 
+    costs::check_budget()?;
+    OPENAI.check()?;
     let client = reqwest::Client::new();
     let response = client
         .post("https://api.openai.com/v1/chat/completions")
@@ -884,9 +1131,10 @@ Return ONLY a JSON object with two fields:
             "temperature": 0.3
         }))
         .send()
-        .await?;
+        .await;
+    breakers::record_response(&OPENAI, &response);
 
-    let response_json: serde_json::Value = response.json().await?;
+    let response_json: serde_json::Value = response?.json().await?;
 
     let content = response_json["choices"][0]["message"]["content"]
         .as_str()
@@ -923,7 +1171,7 @@ Return ONLY a JSON object with two fields:
 async fn extract_shop_names_from_fullname(
     full_name: &str,
 ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
-    let api_key = env::var("OPENAI_API_KEY")?;
+    let api_key = crate::config::get().openai_api_key()?;
 
     let prompt = format!(
         r#"Extract all potential tattoo shop names from this Instagram profile fullName field, including FUZZY VARIATIONS that might match database records.
@@ -955,6 +1203,8 @@ If no shop name can be extracted, return an empty array: []"#,
         full_name
     );
 
+    costs::check_budget()?;
+    OPENAI.check()?;
     let client = reqwest::Client::new();
     let response = client
         .post("https://api.openai.com/v1/chat/completions")
@@ -969,9 +1219,10 @@ If no shop name can be extracted, return an empty array: []"#,
             "temperature": 0.3
         }))
         .send()
-        .await?;
+        .await;
+    breakers::record_response(&OPENAI, &response);
 
-    let response_json: serde_json::Value = response.json().await?;
+    let response_json: serde_json::Value = response?.json().await?;
 
     let content = response_json["choices"][0]["message"]["content"]
         .as_str()
@@ -1108,13 +1359,9 @@ async fn lookup_and_create_shop_via_google(
         }
     }
 
-    // STEP 7: Query back to get the database id
-    let location_id = match sqlx::query_scalar::<_, i64>("SELECT id FROM locations WHERE _id = $1")
-        .bind(&location._id)
-        .fetch_one(pool)
-        .await
-    {
-        Ok(id) => {
+    // STEP 7: Query back to get the database id (or the shop it was merged into)
+    let location_id = match repository::find_location_by_place_id(pool, &location._id).await {
+        Ok(Some(id)) => {
             println!("      ✅ Created shop (location_id: {})", id);
             id
         }
@@ -1148,7 +1395,7 @@ async fn get_state_boundary(
 }
 
 /// Process a single artist from pending list - artist-first approach
-async fn process_artist_from_pending(
+pub(crate) async fn process_artist_from_pending(
     pool: &PgPool,
     artist_handle: &str,
     city: &str,
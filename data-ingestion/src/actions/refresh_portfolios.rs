@@ -0,0 +1,202 @@
+//! Keeps artists' Instagram portfolios current.
+//!
+//! Style extraction captures an artist's posts once. `ACTION=REFRESH_PORTFOLIOS`
+//! re-scrapes artists whose portfolio was last refreshed more than
+//! `PORTFOLIO_REFRESH_DAYS` ago (or never), saves posts that are new since,
+//! running style extraction on those alone, and marks saved posts that are
+//! gone from Instagram as removed so they stop being shown.
+//!
+//! The scrape only returns an artist's latest posts, so a saved post only
+//! counts as removed when it's newer than the oldest post scraped, unless
+//! the scrape came back with fewer posts than asked for and so covers the
+//! whole profile. A scrape with no posts at all (a private or renamed
+//! profile) removes nothing.
+//!
+//! Tuning, read by [`crate::config`]:
+//! - `PORTFOLIO_REFRESH_DAYS`: days between refreshes of an artist
+//!   (default 30)
+//! - `PORTFOLIO_REFRESH_LIMIT`: artists per run (default 25)
+//! - `PORTFOLIO_REFRESH_POSTS`: latest posts scraped per artist (default 12)
+//! - `STYLE_CONFIDENCE_THRESHOLD` and `VISION_BATCH_SIZE` as for
+//!   `EXTRACT_STYLES`
+
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+
+use crate::actions::apify_scraper::scrape_instagram_profile;
+use crate::actions::style_extraction::{download_posts, process_artist_posts, save_style_results};
+use crate::config::{self, Config};
+use crate::repository::{self, Artist};
+use crate::services::costs::{self, BudgetExceeded};
+
+#[derive(Default)]
+struct RefreshStats {
+    refreshed: usize,
+    failed: usize,
+    new_posts: usize,
+    removed_posts: u64,
+    restored_posts: u64,
+    api_cost: f64,
+}
+
+/// Refreshes one artist's portfolio, adding what it finds to `stats`
+async fn refresh_artist(
+    pool: &PgPool,
+    artist: &Artist,
+    ig_username: &str,
+    config: &Config,
+    available_styles: &HashMap<String, Vec<String>>,
+    stats: &mut RefreshStats,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let max_posts = config.portfolio_refresh.max_posts;
+    let posts = scrape_instagram_profile(ig_username, max_posts).await?;
+    if posts.is_empty() {
+        println!("   ⚠️  No posts scraped, leaving the portfolio as it is");
+        return Ok(());
+    }
+
+    let saved = repository::get_artist_posts(pool, artist.id).await?;
+    let scraped: HashSet<&str> = posts.iter().map(|post| post.shortcode.as_str()).collect();
+    let saved_codes: HashSet<&str> = saved.iter().map(|(code, _, _)| code.as_str()).collect();
+
+    // Saved posts inside the stretch of the profile the scrape covered
+    let covers_whole_profile = (posts.len() as i32) < max_posts;
+    let oldest_scraped = posts.iter().filter_map(|post| post.timestamp).min();
+    let removed: Vec<String> = saved
+        .iter()
+        .filter(|(code, _, removed)| !removed && !scraped.contains(code.as_str()))
+        .filter(|(_, post_date, _)| {
+            covers_whole_profile
+                || post_date
+                    .zip(oldest_scraped)
+                    .is_some_and(|(date, oldest)| date >= oldest)
+        })
+        .map(|(code, _, _)| code.clone())
+        .collect();
+    let restored: Vec<String> = saved
+        .iter()
+        .filter(|(code, _, removed)| *removed && scraped.contains(code.as_str()))
+        .map(|(code, _, _)| code.clone())
+        .collect();
+
+    if !removed.is_empty() {
+        stats.removed_posts +=
+            repository::set_artist_posts_removed(pool, artist.id, &removed, true).await?;
+    }
+    if !restored.is_empty() {
+        stats.restored_posts +=
+            repository::set_artist_posts_removed(pool, artist.id, &restored, false).await?;
+    }
+
+    let new_posts = download_posts(
+        pool,
+        posts
+            .iter()
+            .filter(|post| !saved_codes.contains(post.shortcode.as_str())),
+    )
+    .await;
+    println!(
+        "   📸 {} new, {} removed, {} back",
+        new_posts.len(),
+        removed.len(),
+        restored.len()
+    );
+    if new_posts.is_empty() {
+        return Ok(());
+    }
+
+    let style_extraction = &config.style_extraction;
+    let (style_results, api_cost) = process_artist_posts(
+        pool,
+        artist,
+        &new_posts,
+        style_extraction.vision_batch_size,
+        style_extraction.confidence_threshold,
+        available_styles,
+    )
+    .await?;
+    stats.new_posts += style_results.len();
+    stats.api_cost += api_cost;
+    save_style_results(
+        pool,
+        artist,
+        style_results,
+        style_extraction.confidence_threshold,
+    )
+    .await;
+
+    Ok(())
+}
+
+pub async fn refresh_portfolios(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = config::get();
+    config.openai_api_key()?;
+    config.apify_api_token()?;
+    let refresh = &config.portfolio_refresh;
+
+    let artists =
+        repository::get_artists_for_portfolio_refresh(pool, refresh.older_than_days, refresh.limit)
+            .await?;
+    if artists.is_empty() {
+        println!("🔍 No portfolios due a refresh.");
+        return Ok(());
+    }
+
+    let available_styles = repository::get_all_styles(pool).await?;
+    if available_styles.is_empty() {
+        println!("❌ No styles found in database. Please populate the styles table first.");
+        return Ok(());
+    }
+
+    println!(
+        "🔄 Refreshing {} portfolio(s) last refreshed over {} days ago",
+        artists.len(),
+        refresh.older_than_days
+    );
+
+    let mut stats = RefreshStats::default();
+    for artist in &artists {
+        costs::check_run_budget()?;
+
+        let Some(ig_username) = artist.ig_username.as_deref() else {
+            println!(
+                "⚠️  [{} - ID: {}] No valid Instagram username - skipping",
+                artist.name, artist.id
+            );
+            repository::mark_portfolio_refreshed(pool, artist.id).await?;
+            continue;
+        };
+        println!("📸 [{} - ID: {}] @{}", artist.name, artist.id, ig_username);
+
+        match refresh_artist(
+            pool,
+            artist,
+            ig_username,
+            config,
+            &available_styles,
+            &mut stats,
+        )
+        .await
+        {
+            Ok(()) => stats.refreshed += 1,
+            // Left due, to pick up when there's budget again
+            Err(e) if e.downcast_ref::<BudgetExceeded>().is_some() => return Err(e),
+            Err(e) => {
+                // Tried again next time it's due rather than every run
+                println!("   ❌ Refresh failed: {}", e);
+                stats.failed += 1;
+            }
+        }
+        repository::mark_portfolio_refreshed(pool, artist.id).await?;
+    }
+
+    println!("📈 Portfolio refresh results:");
+    println!("   • Artists refreshed: {}", stats.refreshed);
+    println!("   • Artists failed: {}", stats.failed);
+    println!("   • New posts saved: {}", stats.new_posts);
+    println!("   • Posts marked removed: {}", stats.removed_posts);
+    println!("   • Removed posts back: {}", stats.restored_posts);
+    println!("   • Style extraction cost: ${:.4}", stats.api_cost);
+
+    Ok(())
+}
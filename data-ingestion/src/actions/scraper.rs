@@ -14,7 +14,6 @@ use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
 use std::collections::HashSet;
-use std::env;
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
@@ -22,6 +21,11 @@ use std::sync::{
 use tokio;
 use url::Url;
 
+use crate::config::ScraperConfig;
+use crate::services::breakers::{self, OPENAI};
+use crate::services::costs;
+use crate::services::counters;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 #[serde(tag = "action")]
@@ -118,6 +122,7 @@ async fn persist_artist_and_styles(
         .await?;
 
         let artist_id: i64 = row.get("id");
+        counters::record_artist_added();
 
         if let Some(styles) = &artist.styles {
             for raw_style in styles {
@@ -306,7 +311,11 @@ async fn call_gpt_action(
         .max_completion_tokens(1000u32)
         .build()?;
 
-    let res = client.chat().create(req).await?;
+    costs::check_budget()?;
+    let res = breakers::call(&OPENAI, client.chat().create(req)).await?;
+    if let Some(usage) = &res.usage {
+        costs::record_openai_usage("o4-mini", usage.prompt_tokens, usage.completion_tokens);
+    }
     let text = res.choices[0].message.content.as_ref().unwrap();
 
     let action: GptAction = serde_json::from_str(text.trim())?;
@@ -365,7 +374,11 @@ async fn call_gpt_extract(
         .max_completion_tokens(1500u32)
         .build()?;
 
-    let res = client.chat().create(req).await?;
+    costs::check_budget()?;
+    let res = breakers::call(&OPENAI, client.chat().create(req)).await?;
+    if let Some(usage) = &res.usage {
+        costs::record_openai_usage("o4-mini", usage.prompt_tokens, usage.completion_tokens);
+    }
     let text = res.choices[0].message.content.as_ref().unwrap();
 
     let artists: Vec<Artist> = serde_json::from_str(text.trim())?;
@@ -374,10 +387,13 @@ async fn call_gpt_extract(
 }
 
 pub async fn scrape(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
-    let openai_key = env::var("OPENAI_API_KEY")?;
-    let num_threads: usize = env::var("NUM_THREADS").unwrap_or("12".into()).parse()?;
-    let max_scrapes: usize = env::var("MAX_SCRAPES").unwrap_or("100".into()).parse()?;
-    let max_page_visits: usize = env::var("MAX_PAGE_VISITS").unwrap_or("5".into()).parse()?;
+    let settings = crate::config::get();
+    let openai_key = settings.openai_api_key()?;
+    let ScraperConfig {
+        num_threads,
+        max_scrapes,
+        max_page_visits,
+    } = settings.scraper.clone();
 
     println!("🚀 Starting tattoo shop scraper");
     println!("📊 Configuration:");
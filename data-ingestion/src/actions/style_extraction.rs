@@ -3,7 +3,7 @@ use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::PgPool;
-use std::{collections::HashMap, env, sync::Arc};
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 
@@ -105,6 +105,7 @@ fn get_type_metadata(type_name: &str) -> StyleTypeMetadata {
 }
 
 use async_openai::{
+    config::OpenAIConfig,
     types::{
         ChatCompletionRequestMessage, ChatCompletionRequestMessageContentPartImage,
         ChatCompletionRequestMessageContentPartText, ChatCompletionRequestUserMessage,
@@ -117,20 +118,26 @@ use async_openai::{
 use crate::repository::{
     get_all_styles, get_artists_for_style_extraction, get_style_ids, insert_artist_image,
     insert_artist_image_styles, mark_artist_styles_extracted, mark_artist_styles_extraction_failed,
-    update_openai_api_costs, upsert_artist_styles, Artist,
+    update_openai_api_costs, upsert_artist_styles, upsert_instagram_media, Artist,
 };
 
-use super::apify_scraper::{download_image, scrape_instagram_profile};
+use crate::config::StyleExtractionConfig;
+use crate::services::breakers::OPENAI;
+use crate::services::counters;
+
+use super::apify_scraper::{
+    download_image, make_preview_thumbnail, scrape_instagram_profile, ApifyPost,
+};
 
 #[derive(Debug, Clone)]
-struct ProcessablePost {
+pub(crate) struct ProcessablePost {
     shortcode: String,
     image_data: Vec<u8>,
     timestamp: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct StyleResult {
+pub(crate) struct StyleResult {
     shortcode: String,
     styles: Vec<StyleConfidence>,
     timestamp: Option<i64>,
@@ -190,46 +197,17 @@ fn is_valid_style_name(style: &str) -> bool {
     valid_chars
 }
 
-fn calculate_gpt4o_cost(prompt_tokens: u32, completion_tokens: u32) -> f64 {
-    // GPT-4o pricing (as of latest known rates)
-    let input_cost_per_1k = 0.0025;
-    let output_cost_per_1k = 0.01;
-
-    let input_cost = (prompt_tokens as f64 / 1000.0) * input_cost_per_1k;
-    let output_cost = (completion_tokens as f64 / 1000.0) * output_cost_per_1k;
-
-    input_cost + output_cost
-}
-
 pub async fn extract_styles(pool: &PgPool) -> Result<(), Box<dyn std::error::Error>> {
-    env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY environment variable must be set");
-
-    let confidence_threshold: f64 = env::var("STYLE_CONFIDENCE_THRESHOLD")
-        .unwrap_or_else(|_| "0.9".to_string())
-        .parse()
-        .expect("STYLE_CONFIDENCE_THRESHOLD must be a valid number between 0 and 1");
-
-    let batch_size: usize = env::var("VISION_BATCH_SIZE")
-        .unwrap_or_else(|_| "8".to_string())
-        .parse()
-        .expect("VISION_BATCH_SIZE must be a valid number between 1 and 16");
-
-    let artist_limit: i16 = env::var("ARTIST_BATCH_LIMIT")
-        .unwrap_or_else(|_| "10".to_string())
-        .parse()
-        .expect("ARTIST_BATCH_LIMIT must be a valid number");
-
-    let concurrent_artists: usize = env::var("CONCURRENT_ARTISTS")
-        .unwrap_or_else(|_| "6".to_string()) // Increased from 3 to compensate for slower Apify requests
-        .parse()
-        .expect("CONCURRENT_ARTISTS must be a valid number");
-
-    env::var("APIFY_API_TOKEN").expect("APIFY_API_TOKEN environment variable must be set");
-
-    let max_posts: i32 = env::var("MAX_POSTS_PER_ARTIST")
-        .unwrap_or_else(|_| "10".to_string())
-        .parse()
-        .expect("MAX_POSTS_PER_ARTIST must be a valid number");
+    let settings = crate::config::get();
+    settings.openai_api_key()?;
+    settings.apify_api_token()?;
+    let StyleExtractionConfig {
+        confidence_threshold,
+        vision_batch_size: batch_size,
+        artist_batch_limit: artist_limit,
+        concurrent_artists,
+        max_posts_per_artist: max_posts,
+    } = settings.style_extraction.clone();
 
     let artists = get_artists_for_style_extraction(pool, artist_limit).await?;
 
@@ -431,28 +409,7 @@ async fn process_single_artist(
         return Ok((artist, 0, 0, 0.0));
     }
 
-    let mut processable_posts = Vec::new();
-    for post in apify_posts.iter().take(max_posts as usize) {
-        if let Some(display_url) = &post.display_url {
-            match download_image(display_url).await {
-                Ok(image_data) => {
-                    let shortcode = post.shortcode.clone();
-                    let timestamp = post.timestamp;
-                    processable_posts.push(ProcessablePost {
-                        shortcode,
-                        image_data,
-                        timestamp,
-                    });
-                }
-                Err(e) => {
-                    println!(
-                        "   ⚠️  Failed to download image for post {}: {}",
-                        post.shortcode, e
-                    );
-                }
-            }
-        }
-    }
+    let processable_posts = download_posts(pool, apify_posts.iter().take(max_posts as usize)).await;
 
     if processable_posts.is_empty() {
         println!("⚠️  No images could be downloaded for {}", artist.name);
@@ -505,88 +462,8 @@ async fn process_single_artist(
                 artist.name, artist.id, api_cost
             );
 
-            let mut all_artist_styles = HashMap::new();
-
-            for result in style_results {
-                let artist_image_id =
-                    insert_artist_image(pool, &result.shortcode, artist.id, result.timestamp).await;
-
-                match artist_image_id {
-                    Ok(artist_image_id) => {
-                        let style_names: Vec<String> = result
-                            .styles
-                            .iter()
-                            .filter(|s| s.confidence >= confidence_threshold)
-                            .map(|s| s.style.clone())
-                            .filter(|s| is_valid_style_name(s))
-                            .collect();
-
-                        if !style_names.is_empty() {
-                            let style_ids = get_style_ids(pool, &style_names).await;
-
-                            match style_ids {
-                                Ok(style_ids) => {
-                                    if !style_ids.is_empty() {
-                                        if let Err(e) = insert_artist_image_styles(
-                                            pool,
-                                            artist_image_id,
-                                            &style_ids,
-                                        )
-                                        .await
-                                        {
-                                            println!(
-                                                "Error saving styles for image {}: {}",
-                                                result.shortcode, e
-                                            );
-                                        }
-
-                                        for (name, id) in style_names.iter().zip(style_ids.iter()) {
-                                            all_artist_styles.insert(name.clone(), *id);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    println!(
-                                        "Error mapping styles to IDs for image {}: {}",
-                                        result.shortcode, e
-                                    );
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        println!(
-                            "Error inserting artist_image for {}: {}",
-                            result.shortcode, e
-                        );
-                    }
-                }
-            }
-
-            let styles_found = if !all_artist_styles.is_empty() {
-                let artist_style_ids: Vec<i64> = all_artist_styles.values().copied().collect();
-                if let Err(e) = upsert_artist_styles(pool, artist.id, &artist_style_ids).await {
-                    println!(
-                        "❌ Error saving artist-level styles for {}: {}",
-                        artist.name, e
-                    );
-                    0
-                } else {
-                    println!(
-                        "✅ [{} - ID: {}] Saved {} unique styles",
-                        artist.name,
-                        artist.id,
-                        artist_style_ids.len()
-                    );
-                    artist_style_ids.len()
-                }
-            } else {
-                println!(
-                    "ℹ️  [{} - ID: {}] No high-confidence styles found",
-                    artist.name, artist.id
-                );
-                0
-            };
+            let styles_found =
+                save_style_results(pool, &artist, style_results, confidence_threshold).await;
 
             if let Err(e) = mark_artist_styles_extracted(pool, artist.id).await {
                 println!(
@@ -625,7 +502,150 @@ async fn process_single_artist(
     }
 }
 
-async fn process_artist_posts(
+/// Downloads each post's image, saving its caption and preview thumbnail
+/// for Instagram privacy mode on the way. Posts whose image can't be
+/// downloaded are left out.
+pub(crate) async fn download_posts<'a>(
+    pool: &PgPool,
+    posts: impl IntoIterator<Item = &'a ApifyPost>,
+) -> Vec<ProcessablePost> {
+    let mut processable_posts = Vec::new();
+    for post in posts {
+        if let Some(display_url) = &post.display_url {
+            match download_image(display_url).await {
+                Ok(image_data) => {
+                    // Kept for Instagram privacy mode, which shows these
+                    // instead of the live embed
+                    let thumbnail = make_preview_thumbnail(&image_data);
+                    if let Err(e) = upsert_instagram_media(
+                        pool,
+                        &post.shortcode,
+                        post.caption.as_deref(),
+                        thumbnail
+                            .as_ref()
+                            .map(|(bytes, width, height)| (bytes.as_slice(), *width, *height)),
+                    )
+                    .await
+                    {
+                        println!(
+                            "   ⚠️  Failed to save preview for post {}: {}",
+                            post.shortcode, e
+                        );
+                    }
+
+                    let shortcode = post.shortcode.clone();
+                    let timestamp = post.timestamp;
+                    processable_posts.push(ProcessablePost {
+                        shortcode,
+                        image_data,
+                        timestamp,
+                    });
+                }
+                Err(e) => {
+                    println!(
+                        "   ⚠️  Failed to download image for post {}: {}",
+                        post.shortcode, e
+                    );
+                }
+            }
+        }
+    }
+
+    processable_posts
+}
+
+/// Saves images with the styles found in them above `confidence_threshold`
+/// and adds those styles to the artist, returning how many styles the
+/// artist got
+pub(crate) async fn save_style_results(
+    pool: &PgPool,
+    artist: &Artist,
+    style_results: Vec<StyleResult>,
+    confidence_threshold: f64,
+) -> usize {
+    let mut all_artist_styles = HashMap::new();
+
+    for result in style_results {
+        let artist_image_id =
+            insert_artist_image(pool, &result.shortcode, artist.id, result.timestamp).await;
+
+        match artist_image_id {
+            Ok(artist_image_id) => {
+                counters::record_post_processed();
+                let style_names: Vec<String> = result
+                    .styles
+                    .iter()
+                    .filter(|s| s.confidence >= confidence_threshold)
+                    .map(|s| s.style.clone())
+                    .filter(|s| is_valid_style_name(s))
+                    .collect();
+
+                if !style_names.is_empty() {
+                    let style_ids = get_style_ids(pool, &style_names).await;
+
+                    match style_ids {
+                        Ok(style_ids) => {
+                            if !style_ids.is_empty() {
+                                if let Err(e) =
+                                    insert_artist_image_styles(pool, artist_image_id, &style_ids)
+                                        .await
+                                {
+                                    println!(
+                                        "Error saving styles for image {}: {}",
+                                        result.shortcode, e
+                                    );
+                                }
+
+                                for (name, id) in style_names.iter().zip(style_ids.iter()) {
+                                    all_artist_styles.insert(name.clone(), *id);
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            println!(
+                                "Error mapping styles to IDs for image {}: {}",
+                                result.shortcode, e
+                            );
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                println!(
+                    "Error inserting artist_image for {}: {}",
+                    result.shortcode, e
+                );
+            }
+        }
+    }
+
+    if !all_artist_styles.is_empty() {
+        let artist_style_ids: Vec<i64> = all_artist_styles.values().copied().collect();
+        if let Err(e) = upsert_artist_styles(pool, artist.id, &artist_style_ids).await {
+            println!(
+                "❌ Error saving artist-level styles for {}: {}",
+                artist.name, e
+            );
+            0
+        } else {
+            println!(
+                "✅ [{} - ID: {}] Saved {} unique styles",
+                artist.name,
+                artist.id,
+                artist_style_ids.len()
+            );
+            artist_style_ids.len()
+        }
+    } else {
+        println!(
+            "ℹ️  [{} - ID: {}] No high-confidence styles found",
+            artist.name, artist.id
+        );
+        0
+    }
+}
+
+pub(crate) async fn process_artist_posts(
     pool: &PgPool,
     artist: &Artist,
     posts: &[ProcessablePost],
@@ -633,7 +653,9 @@ async fn process_artist_posts(
     confidence_threshold: f64,
     available_styles: &HashMap<String, Vec<String>>,
 ) -> Result<(Vec<StyleResult>, f64), Box<dyn std::error::Error>> {
-    let client = Client::new();
+    let client = Client::with_config(
+        OpenAIConfig::new().with_api_key(crate::config::get().openai_api_key()?),
+    );
     println!(
         "   🤖 Processing {} posts in batches of {} with OpenAI Vision API",
         posts.len(),
@@ -806,13 +828,38 @@ Return exactly {} objects (one per image, in order). Analyze all {} images now:"
                 }
             };
 
+            if let Err(exceeded) = crate::services::costs::check_budget() {
+                println!("  Skipping batch {}: {}", batch_idx + 1, exceeded);
+                return BatchResult {
+                    style_results: Vec::new(),
+                    api_cost: 0.0,
+                };
+            }
+            if let Err(open) = OPENAI.check() {
+                println!("  Skipping batch {}: {}", batch_idx + 1, open);
+                return BatchResult {
+                    style_results: Vec::new(),
+                    api_cost: 0.0,
+                };
+            }
+
             let timeout_duration = tokio::time::Duration::from_secs(90);
+            let result =
+                tokio::time::timeout(timeout_duration, (*client).chat().create(request)).await;
+            let succeeded = matches!(result, Ok(Ok(_)));
+            OPENAI.record(succeeded);
+            if !succeeded {
+                counters::record_api_error();
+            }
 
-            match tokio::time::timeout(timeout_duration, (*client).chat().create(request)).await {
+            match result {
                 Ok(Ok(response)) => {
                     let api_cost = if let Some(usage) = &response.usage {
-                        let cost =
-                            calculate_gpt4o_cost(usage.prompt_tokens, usage.completion_tokens);
+                        let cost = crate::services::costs::record_openai_usage(
+                            "gpt-4o",
+                            usage.prompt_tokens,
+                            usage.completion_tokens,
+                        );
                         println!(
                             "  Batch {} API cost: ${:.4} (tokens: {} prompt + {} completion)",
                             batch_idx + 1,
@@ -4,7 +4,6 @@
 
 use data_ingestion::actions::apify_scraper;
 use sqlx::{PgPool, Row};
-use std::env;
 
 #[derive(Debug)]
 struct ArtistToBackfill {
@@ -75,12 +74,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables
     dotenv::dotenv().ok();
 
-    // Get database URL
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let config = data_ingestion::config::init()?;
 
     // Connect to database
     println!("📦 Connecting to database...");
-    let pool = PgPool::connect(&database_url).await?;
+    let pool = PgPool::connect(&config.database_url).await?;
     println!("✅ Connected to database\n");
 
     // Get artists without names
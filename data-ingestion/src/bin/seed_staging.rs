@@ -0,0 +1,547 @@
+// Seed Staging
+// Copies the data for a few states from production into a staging database,
+// replacing personal details (names, emails, phones, messages) with fake
+// ones, so staging can be built from realistic data without exposing anyone.
+//
+// Rows keep their ids, so references between them still hold. Fake values
+// are derived from the real ones with a salted hash: an email becomes the
+// same fake email wherever it appears, and rerunning with the same salt
+// gives the same data. Artist profiles are public listings and are copied as
+// they are, apart from their email and phone. Uploads, payments, tokens and
+// logs aren't copied.
+//
+// Environment:
+//   SOURCE_DATABASE_URL   production, only read from, in one read-only snapshot
+//   DATABASE_URL          staging, already migrated; the copied tables are emptied first
+//   SEED_TARGET_DATABASE  staging's database name, confirming which database gets emptied
+//   SEED_STATES           states to copy, comma-separated, e.g. "Oregon,Washington"
+//   SEED_SALT             secret the fake values are derived with
+//   SEED_PASSWORD_HASH    bcrypt hash of the password every copied user signs in with
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use std::env;
+use std::error::Error;
+
+/// Rows per insert
+const BATCH_SIZE: usize = 500;
+/// Longest placeholder text, in words
+const MAX_TEXT_WORDS: usize = 80;
+
+const FIRST_NAMES: &[&str] = &[
+    "Alex", "Bailey", "Casey", "Dana", "Eden", "Finley", "Gray", "Harper", "Indy", "Jordan", "Kai",
+    "Logan", "Morgan", "Noel", "Oakley", "Parker", "Quinn", "Reese", "Sage", "Taylor", "Uma",
+    "Val", "Winter", "Yael",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Abbott",
+    "Brooks",
+    "Carver",
+    "Dalton",
+    "Ellis",
+    "Fischer",
+    "Garner",
+    "Hayes",
+    "Irwin",
+    "Jensen",
+    "Keller",
+    "Lowe",
+    "Mercer",
+    "Nolan",
+    "Owens",
+    "Pruitt",
+    "Quintero",
+    "Rhodes",
+    "Sutton",
+    "Tate",
+    "Underwood",
+    "Vance",
+    "Whitaker",
+    "York",
+];
+
+const WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "tattoo",
+    "linework",
+    "shading",
+    "color",
+    "sleeve",
+    "forearm",
+    "shoulder",
+    "small",
+    "large",
+    "session",
+    "design",
+    "flash",
+    "custom",
+    "fine",
+    "line",
+    "bold",
+    "traditional",
+    "floral",
+    "minimal",
+    "placement",
+    "size",
+    "idea",
+    "touch",
+    "up",
+    "cover",
+    "booking",
+    "time",
+    "next",
+    "week",
+    "thanks",
+    "please",
+    "would",
+    "like",
+    "maybe",
+    "around",
+];
+
+/// How a column's values are replaced. NULLs stay NULL.
+#[derive(Debug, Clone, Copy)]
+enum Fake {
+    FirstName,
+    LastName,
+    FullName,
+    Email,
+    Phone,
+    /// Placeholder text with about as many words
+    Text,
+    /// The `message` field of a JSON object
+    JsonMessage,
+    LicenseNumber,
+    /// `SEED_PASSWORD_HASH`
+    Password,
+    Null,
+}
+
+struct TablePlan {
+    table: &'static str,
+    /// Which production rows to copy, with the states bound as `$1`
+    filter: String,
+    fakes: &'static [(&'static str, Fake)],
+}
+
+impl TablePlan {
+    fn new(table: &'static str, filter: String, fakes: &'static [(&'static str, Fake)]) -> Self {
+        TablePlan {
+            table,
+            filter,
+            fakes,
+        }
+    }
+}
+
+/// The tables to copy, each after the tables it references
+fn plan() -> Vec<TablePlan> {
+    let locations = "SELECT id FROM locations WHERE state = ANY($1)".to_string();
+    let artists = format!(
+        "SELECT id FROM artists WHERE location_id IN ({})",
+        locations
+    );
+    let images = format!(
+        "SELECT id FROM artists_images WHERE artist_id IN ({})",
+        artists
+    );
+    let bookings = format!(
+        "SELECT id FROM booking_requests WHERE artist_id IN ({})",
+        artists
+    );
+    // Admins, plus everyone attached to the copied artists
+    let users = format!(
+        "SELECT id FROM users
+         WHERE role::text = 'admin'
+            OR artist_id IN ({artists})
+            OR id IN (SELECT client_user_id FROM booking_requests WHERE artist_id IN ({artists}))
+            OR id IN (SELECT user_id FROM artist_team_members WHERE artist_id IN ({artists}))
+            OR id IN (SELECT user_id FROM user_favorites WHERE artists_images_id IN ({images}))",
+        artists = artists,
+        images = images
+    );
+    let by = |column: &str, ids: &str| format!("{} IN ({})", column, ids);
+    let all = |table: &'static str| TablePlan::new(table, "TRUE".to_string(), &[]);
+
+    vec![
+        all("styles"),
+        all("style_aliases"),
+        all("questionnaire_questions"),
+        all("subscription_tiers"),
+        all("cities"),
+        TablePlan::new("locations", by("id", &locations), &[]),
+        TablePlan::new(
+            "artists",
+            by("id", &artists),
+            &[("email", Fake::Email), ("phone", Fake::Phone)],
+        ),
+        TablePlan::new(
+            "users",
+            by("id", &users),
+            &[
+                ("first_name", Fake::FirstName),
+                ("last_name", Fake::LastName),
+                ("email", Fake::Email),
+                ("phone", Fake::Phone),
+                ("password_hash", Fake::Password),
+            ],
+        ),
+        TablePlan::new("artists_images", by("id", &images), &[]),
+        TablePlan::new(
+            "instagram_media",
+            format!(
+                "short_code IN (SELECT short_code FROM artists_images WHERE id IN ({}))",
+                images
+            ),
+            &[],
+        ),
+        TablePlan::new("artists_styles", by("artist_id", &artists), &[]),
+        TablePlan::new(
+            "artists_images_styles",
+            by("artists_images_id", &images),
+            &[],
+        ),
+        TablePlan::new("artist_availability", by("artist_id", &artists), &[]),
+        TablePlan::new("business_hours", by("artist_id", &artists), &[]),
+        TablePlan::new("artist_questionnaires", by("artist_id", &artists), &[]),
+        TablePlan::new("artist_pricing", by("artist_id", &artists), &[]),
+        TablePlan::new("artist_style_pricing", by("artist_id", &artists), &[]),
+        TablePlan::new("appointment_settings", by("artist_id", &artists), &[]),
+        TablePlan::new("recurring_rules", by("artist_id", &artists), &[]),
+        TablePlan::new(
+            "artist_auto_response_settings",
+            by("artist_id", &artists),
+            &[],
+        ),
+        TablePlan::new("artist_auto_responses", by("artist_id", &artists), &[]),
+        TablePlan::new(
+            "artist_licenses",
+            by("artist_id", &artists),
+            &[
+                ("license_number", Fake::LicenseNumber),
+                ("document_key", Fake::Null),
+            ],
+        ),
+        TablePlan::new(
+            "artist_team_members",
+            by("artist_id", &artists),
+            &[("email", Fake::Email)],
+        ),
+        TablePlan::new(
+            "user_favorites",
+            format!(
+                "{} AND {}",
+                by("user_id", &users),
+                by("artists_images_id", &images)
+            ),
+            &[],
+        ),
+        TablePlan::new("favorite_collections", by("user_id", &users), &[]),
+        TablePlan::new(
+            "favorite_collection_items",
+            format!(
+                "collection_id IN (SELECT id FROM favorite_collections WHERE {}) AND {}",
+                by("user_id", &users),
+                by("artists_images_id", &images)
+            ),
+            &[],
+        ),
+        TablePlan::new(
+            "guest_spots",
+            by("artist_id", &artists),
+            // The host may work in a state that isn't copied
+            &[("host_artist_id", Fake::Null)],
+        ),
+        TablePlan::new(
+            "booking_requests",
+            by("id", &bookings),
+            &[
+                ("client_name", Fake::FullName),
+                ("client_email", Fake::Email),
+                ("client_phone", Fake::Phone),
+                ("tattoo_description", Fake::Text),
+                ("message_from_client", Fake::Text),
+                // Flash designs are uploads, which aren't copied
+                ("flash_design_id", Fake::Null),
+            ],
+        ),
+        TablePlan::new(
+            "booking_messages",
+            by("booking_request_id", &bookings),
+            &[("message", Fake::Text)],
+        ),
+        TablePlan::new(
+            "booking_questionnaire_responses",
+            by("booking_request_id", &bookings),
+            &[("response_text", Fake::Text), ("response_data", Fake::Null)],
+        ),
+        TablePlan::new(
+            "booking_status_history",
+            by("booking_request_id", &bookings),
+            &[("note", Fake::Text)],
+        ),
+        TablePlan::new(
+            "booking_events",
+            by("booking_request_id", &bookings),
+            &[("details", Fake::JsonMessage)],
+        ),
+    ]
+}
+
+struct Anonymizer {
+    salt: String,
+    password_hash: String,
+}
+
+impl Anonymizer {
+    /// A number derived from `value`, the same for the same value and salt
+    fn seed(&self, value: &str) -> u64 {
+        let digest = Sha256::new()
+            .chain_update(self.salt.as_bytes())
+            .chain_update(value.as_bytes())
+            .finalize();
+        u64::from_be_bytes(digest[..8].try_into().expect("digest is 32 bytes"))
+    }
+
+    fn pick(words: &[&'static str], seed: u64) -> &'static str {
+        words[(seed % words.len() as u64) as usize]
+    }
+
+    fn fake_text(&self, fake: Fake, value: &str) -> String {
+        match fake {
+            Fake::FirstName => Self::pick(FIRST_NAMES, self.seed(value.trim())).to_string(),
+            Fake::LastName => Self::pick(LAST_NAMES, self.seed(value.trim())).to_string(),
+            Fake::FullName => {
+                let seed = self.seed(value.trim());
+                format!(
+                    "{} {}",
+                    Self::pick(FIRST_NAMES, seed),
+                    Self::pick(LAST_NAMES, seed >> 32)
+                )
+            }
+            // Case-insensitive, as sign-in treats them
+            Fake::Email => format!(
+                "user{:010x}@example.com",
+                self.seed(&value.trim().to_lowercase()) & 0xff_ffff_ffff
+            ),
+            // 555-0100 to 555-0199 are reserved for fiction
+            Fake::Phone => {
+                let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+                format!("555-01{:02}", self.seed(&digits) % 100)
+            }
+            Fake::Text => {
+                let words = value.split_whitespace().count().clamp(1, MAX_TEXT_WORDS);
+                let mut seed = self.seed(value);
+                let mut text = (0..words)
+                    .map(|_| {
+                        // xorshift, so each word is picked from fresh bits
+                        seed ^= seed << 13;
+                        seed ^= seed >> 7;
+                        seed ^= seed << 17;
+                        Self::pick(WORDS, seed)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if let Some(first) = text.get_mut(..1) {
+                    first.make_ascii_uppercase();
+                }
+                text.push('.');
+                text
+            }
+            Fake::LicenseNumber => format!("STG-{:06}", self.seed(value) % 1_000_000),
+            Fake::JsonMessage | Fake::Password | Fake::Null => value.to_string(),
+        }
+    }
+
+    fn apply(&self, fake: Fake, value: &mut Value) {
+        match fake {
+            Fake::Null => *value = Value::Null,
+            Fake::Password => {
+                if value.is_string() {
+                    *value = Value::String(self.password_hash.clone());
+                }
+            }
+            Fake::JsonMessage => {
+                if let Some(message) = value.get_mut("message") {
+                    self.apply(Fake::Text, message);
+                }
+            }
+            _ => {
+                if let Some(text) = value.as_str() {
+                    *value = Value::String(self.fake_text(fake, text));
+                }
+            }
+        }
+    }
+}
+
+/// The table's columns that can be inserted into, empty when it doesn't exist
+async fn insertable_columns(
+    conn: &mut PgConnection,
+    table: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT column_name::text
+         FROM information_schema.columns
+         WHERE table_schema = 'public'
+           AND table_name = $1
+           AND is_generated = 'NEVER'
+         ORDER BY ordinal_position",
+    )
+    .bind(table)
+    .fetch_all(conn)
+    .await
+}
+
+/// Copies the table's rows, returning how many
+async fn copy_table(
+    source: &mut PgConnection,
+    target: &mut PgConnection,
+    table: &TablePlan,
+    states: &[String],
+    anonymizer: &Anonymizer,
+) -> Result<usize, Box<dyn Error>> {
+    let source_columns = insertable_columns(source, table.table).await?;
+    let target_columns = insertable_columns(target, table.table).await?;
+    // Columns only one side has are left to staging's defaults
+    let columns = target_columns
+        .iter()
+        .filter(|column| source_columns.contains(column))
+        .map(|column| format!("\"{}\"", column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if columns.is_empty() {
+        return Err(format!("{} is missing from one of the databases", table.table).into());
+    }
+
+    let rows: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT row_to_json(t)::text FROM {} t WHERE {}",
+        table.table, table.filter
+    ))
+    .bind(states)
+    .fetch_all(&mut *source)
+    .await?;
+
+    let insert = format!(
+        "INSERT INTO {table} ({columns}) OVERRIDING SYSTEM VALUE
+         SELECT {columns} FROM json_populate_recordset(NULL::{table}, $1::json)",
+        table = table.table,
+        columns = columns
+    );
+    for batch in rows.chunks(BATCH_SIZE) {
+        let mut records = Vec::with_capacity(batch.len());
+        for row in batch {
+            let mut record: Value = serde_json::from_str(row)?;
+            for (column, fake) in table.fakes {
+                if let Some(value) = record.get_mut(*column) {
+                    anonymizer.apply(*fake, value);
+                }
+            }
+            records.push(record);
+        }
+
+        sqlx::query(&insert)
+            .bind(Value::Array(records).to_string())
+            .execute(&mut *target)
+            .await?;
+    }
+
+    // New rows in staging continue after the copied ids
+    if target_columns.iter().any(|column| column == "id") {
+        sqlx::query(&format!(
+            "SELECT setval(pg_get_serial_sequence($1, 'id'), COALESCE(MAX(id), 0) + 1, false)
+             FROM {}",
+            table.table
+        ))
+        .bind(table.table)
+        .execute(&mut *target)
+        .await?;
+    }
+
+    Ok(rows.len())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    println!("🌱 Starting Staging Seed Script\n");
+
+    dotenv::dotenv().ok();
+
+    let source_url = env::var("SOURCE_DATABASE_URL").expect("SOURCE_DATABASE_URL must be set");
+    let target_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let target_database =
+        env::var("SEED_TARGET_DATABASE").expect("SEED_TARGET_DATABASE must be set");
+    let states: Vec<String> = env::var("SEED_STATES")
+        .expect("SEED_STATES must be set")
+        .split(',')
+        .map(|state| state.trim().to_string())
+        .filter(|state| !state.is_empty())
+        .collect();
+    let anonymizer = Anonymizer {
+        salt: env::var("SEED_SALT").expect("SEED_SALT must be set"),
+        password_hash: env::var("SEED_PASSWORD_HASH").expect("SEED_PASSWORD_HASH must be set"),
+    };
+
+    if source_url == target_url {
+        return Err("SOURCE_DATABASE_URL and DATABASE_URL are the same database".into());
+    }
+    if states.is_empty() {
+        return Err("SEED_STATES has no states".into());
+    }
+
+    println!("📦 Connecting to databases...");
+    let source_pool = PgPool::connect(&source_url).await?;
+    let target_pool = PgPool::connect(&target_url).await?;
+
+    let connected_to: String = sqlx::query_scalar("SELECT current_database()::text")
+        .fetch_one(&target_pool)
+        .await?;
+    if connected_to != target_database {
+        return Err(format!(
+            "DATABASE_URL points at {}, not SEED_TARGET_DATABASE ({})",
+            connected_to, target_database
+        )
+        .into());
+    }
+    println!("✅ Connected, seeding {}\n", connected_to);
+
+    // One snapshot, so rows copied from different tables agree
+    let mut source = source_pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *source)
+        .await?;
+    // Staging is only changed if every table copies
+    let mut target = target_pool.begin().await?;
+
+    let plan = plan();
+    let tables = plan
+        .iter()
+        .map(|table| table.table)
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!("🧹 Emptying copied tables in staging...");
+    sqlx::query(&format!("TRUNCATE {} CASCADE", tables))
+        .execute(&mut *target)
+        .await?;
+
+    println!("🔍 Copying data for {}...", states.join(", "));
+    let mut total = 0;
+    for table in &plan {
+        let copied = copy_table(&mut source, &mut target, table, &states, &anonymizer).await?;
+        println!("   📋 {:<34} {:>8} rows", table.table, copied);
+        total += copied;
+    }
+
+    target.commit().await?;
+    source.rollback().await?;
+
+    println!("\n🎉 Staging seeded with {} rows.", total);
+
+    Ok(())
+}
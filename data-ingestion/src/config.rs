@@ -0,0 +1,475 @@
+//! Settings shared by the ingestion actions, read from the environment once.
+//! `main` loads them with [`init`] before running anything, so a malformed
+//! value stops the run at startup with the variable's name instead of
+//! partway through. Everything else reads them through [`get`].
+//!
+//! API keys are optional here since most actions don't need all of them;
+//! their accessors fail with the variable's name when one is needed but
+//! not set. Each action's tuning is checked here too, whichever action
+//! runs; what an action is asked to do (`BACKFILL_NAME`, dry runs, the
+//! Reddit scraper's city and state filters) is read by that action.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::actions::IngestAction;
+
+const DEFAULT_SCHEDULE: &str = "CITY_REQUESTS=15m,GOOGLE_API=6h,REDDIT_RETRY_FAILED=12h,\
+     INTEGRITY_CHECK=@daily,DEDUP_LOCATIONS=@daily";
+const DEFAULT_RETRY_REASONS: &str = "shop_not_found,artist_profile_not_found";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Missing(&'static str),
+    Invalid {
+        name: String,
+        value: String,
+        expected: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Missing(name) => write!(f, "{} must be set", name),
+            ConfigError::Invalid {
+                name,
+                value,
+                expected,
+            } => write!(f, "{} is \"{}\", expected {}", name, value, expected),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// `SCRAPE_HTML`
+#[derive(Debug, Clone)]
+pub struct ScraperConfig {
+    /// `NUM_THREADS` (default 12)
+    pub num_threads: usize,
+    /// `MAX_SCRAPES` (default 100)
+    pub max_scrapes: usize,
+    /// `MAX_PAGE_VISITS` per location (default 5)
+    pub max_page_visits: usize,
+}
+
+/// `EXTRACT_STYLES`
+#[derive(Debug, Clone)]
+pub struct StyleExtractionConfig {
+    /// `STYLE_CONFIDENCE_THRESHOLD`, 0 to 1 (default 0.9)
+    pub confidence_threshold: f64,
+    /// `VISION_BATCH_SIZE`, images per vision request, 1 to 16 (default 8)
+    pub vision_batch_size: usize,
+    /// `ARTIST_BATCH_LIMIT` (default 10)
+    pub artist_batch_limit: i16,
+    /// `CONCURRENT_ARTISTS` (default 6)
+    pub concurrent_artists: usize,
+    /// `MAX_POSTS_PER_ARTIST` (default 10)
+    pub max_posts_per_artist: i32,
+}
+
+/// Spending caps in USD for one scope, see [`crate::services::costs`]
+#[derive(Debug, Clone, Default)]
+pub struct BudgetCaps {
+    /// `<prefix>`
+    pub default: Option<f64>,
+    /// `<prefix>_<ACTION>`, by action name
+    pub per_action: HashMap<&'static str, f64>,
+}
+
+impl BudgetCaps {
+    /// The action's own cap, falling back to the default
+    pub fn for_action(&self, action: Option<&str>) -> Option<f64> {
+        action
+            .and_then(|action| self.per_action.get(action).copied())
+            .or(self.default)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CostConfig {
+    /// `APIFY_USD_PER_CU`, for runs Apify doesn't report a price for
+    /// (default 0.40)
+    pub apify_usd_per_cu: f64,
+    /// `GOOGLE_PLACES_USD_PER_CALL` (default 0.032)
+    pub google_places_usd_per_call: f64,
+    /// `INGESTION_BUDGET_USD` and `INGESTION_BUDGET_USD_<ACTION>`
+    pub run_budget: BudgetCaps,
+    /// `INGESTION_CITY_BUDGET_USD` and `INGESTION_CITY_BUDGET_USD_<ACTION>`
+    pub city_budget: BudgetCaps,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduledAction {
+    pub action: IngestAction,
+    pub every: Duration,
+}
+
+/// `DAEMON`
+#[derive(Debug, Clone)]
+pub struct DaemonConfig {
+    /// `DAEMON_SCHEDULE`
+    pub schedule: Vec<ScheduledAction>,
+    /// `DAEMON_CONCURRENCY` (default 2)
+    pub concurrency: usize,
+    /// `DAEMON_COUNTY_BATCH` (default 100)
+    pub county_batch: i16,
+    /// `DAEMON_COUNTY_REFRESH_DAYS` (default 160)
+    pub county_refresh_days: i16,
+    /// `DAEMON_SHUTDOWN_GRACE_SECS` (default 600)
+    pub shutdown_grace: Duration,
+}
+
+/// `REDDIT_SCRAPER`
+#[derive(Debug, Clone)]
+pub struct RedditScraperConfig {
+    /// `REDDIT_MAX_POSTS` per city (default 100)
+    pub max_posts: i32,
+    /// `REDDIT_MIN_IMAGES` a post needs to be read (default 1)
+    pub min_images: i32,
+    /// `REDDIT_RESCRAPE_DAYS` before a city is scraped again (default 30)
+    pub rescrape_days: i16,
+    /// `REDDIT_MAX_CITIES` per run, all due cities when unset
+    pub max_cities: Option<i16>,
+}
+
+/// `REDDIT_RETRY_FAILED`
+#[derive(Debug, Clone)]
+pub struct RedditRetryConfig {
+    /// `REDDIT_RETRY_REASONS`, empty for any reason
+    pub reasons: Vec<String>,
+    /// `REDDIT_RETRY_MIN_AGE_HOURS` (default 24)
+    pub min_age_hours: i64,
+    /// `REDDIT_RETRY_MAX_AGE_DAYS`
+    pub max_age_days: Option<i64>,
+    /// `REDDIT_RETRY_MAX_ATTEMPTS` (default 4)
+    pub max_attempts: i32,
+    /// `REDDIT_RETRY_BACKOFF_HOURS` (default 12)
+    pub backoff_hours: f64,
+    /// `REDDIT_RETRY_LIMIT` (default 50)
+    pub limit: i64,
+}
+
+/// `REFRESH_PORTFOLIOS`
+#[derive(Debug, Clone)]
+pub struct PortfolioRefreshConfig {
+    /// `PORTFOLIO_REFRESH_DAYS` (default 30)
+    pub older_than_days: i32,
+    /// `PORTFOLIO_REFRESH_LIMIT` (default 25)
+    pub limit: i64,
+    /// `PORTFOLIO_REFRESH_POSTS` (default 12)
+    pub max_posts: i32,
+}
+
+/// `BACKFILL`
+#[derive(Debug, Clone)]
+pub struct BackfillConfig {
+    /// `BACKFILL_BATCH_SIZE`, rows per chunk (default 1000)
+    pub batch_size: i64,
+    /// `BACKFILL_PAUSE_MS`, sleep between chunks (default 100)
+    pub pause: Duration,
+}
+
+/// `DEDUP_LOCATIONS`
+#[derive(Debug, Clone)]
+pub struct DedupConfig {
+    /// `DEDUP_MIN_SCORE`, 0 to 1 (default 0.85)
+    pub min_score: f64,
+    /// `DEDUP_MAX_DISTANCE_M` (default 150)
+    pub max_distance_m: f64,
+}
+
+pub struct Config {
+    /// `DATABASE_URL`
+    pub database_url: String,
+    openai_api_key: Option<String>,
+    apify_api_token: Option<String>,
+    google_places_api_key: Option<String>,
+    pub scraper: ScraperConfig,
+    pub style_extraction: StyleExtractionConfig,
+    pub costs: CostConfig,
+    pub daemon: DaemonConfig,
+    pub reddit_scraper: RedditScraperConfig,
+    pub reddit_retry: RedditRetryConfig,
+    pub portfolio_refresh: PortfolioRefreshConfig,
+    pub backfill: BackfillConfig,
+    pub dedup: DedupConfig,
+    /// `CITY_REQUESTS_LIMIT`, jobs per `CITY_REQUESTS` run (default 25)
+    pub city_requests_limit: i64,
+}
+
+fn var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// `name` parsed and checked with `valid`, or `default` when it isn't set
+fn parse<T: FromStr>(
+    name: &str,
+    default: T,
+    expected: &str,
+    valid: impl Fn(&T) -> bool,
+) -> Result<T, ConfigError> {
+    match var(name) {
+        None => Ok(default),
+        Some(value) => match value.parse::<T>() {
+            Ok(parsed) if valid(&parsed) => Ok(parsed),
+            _ => Err(ConfigError::Invalid {
+                name: name.to_string(),
+                value,
+                expected: expected.to_string(),
+            }),
+        },
+    }
+}
+
+fn positive<T: FromStr + PartialOrd + Default>(name: &str, default: T) -> Result<T, ConfigError> {
+    parse(name, default, "a positive number", |v| *v > T::default())
+}
+
+/// A price or budget in USD
+fn usd(name: &str, default: f64) -> Result<f64, ConfigError> {
+    parse(name, default, "a dollar amount", |v: &f64| {
+        v.is_finite() && *v >= 0.0
+    })
+}
+
+fn budget_caps(prefix: &str) -> Result<BudgetCaps, ConfigError> {
+    let cap = |name: &str| var(name).map(|_| usd(name, 0.0)).transpose();
+
+    let mut per_action = HashMap::new();
+    for action in IngestAction::ALL {
+        if let Some(budget) = cap(&format!("{}_{}", prefix, action.name()))? {
+            per_action.insert(action.name(), budget);
+        }
+    }
+    Ok(BudgetCaps {
+        default: cap(prefix)?,
+        per_action,
+    })
+}
+
+/// `30m`, `6h`, `2d`, `@hourly`, `@daily` or `@weekly`
+fn parse_interval(interval: &str) -> Option<Duration> {
+    let interval = interval.trim();
+    let hours = match interval {
+        "@hourly" => 1,
+        "@daily" => 24,
+        "@weekly" => 24 * 7,
+        _ => {
+            let unit = interval.chars().last()?;
+            let count: u64 = interval[..interval.len() - unit.len_utf8()]
+                .parse()
+                .ok()
+                .filter(|count| *count > 0)?;
+            let minutes = match unit {
+                'm' => count,
+                'h' => count * 60,
+                'd' => count * 60 * 24,
+                _ => return None,
+            };
+            return Some(Duration::from_secs(minutes * 60));
+        }
+    };
+    Some(Duration::from_secs(hours * 60 * 60))
+}
+
+fn parse_schedule(schedule: &str) -> Result<Vec<ScheduledAction>, String> {
+    let mut parsed: Vec<ScheduledAction> = Vec::new();
+    for entry in schedule.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (action, interval) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("ACTION=INTERVAL pairs, not \"{}\"", entry))?;
+        let action = IngestAction::parse(action.trim())
+            .ok_or_else(|| format!("known actions, not \"{}\"", action.trim()))?;
+        let every = parse_interval(interval).ok_or_else(|| {
+            format!(
+                "intervals like 30m, 6h, 2d or @daily, not \"{}\" for {}",
+                interval.trim(),
+                action.name()
+            )
+        })?;
+        if parsed.iter().any(|scheduled| scheduled.action == action) {
+            return Err(format!(
+                "each action once, but {} is there twice",
+                action.name()
+            ));
+        }
+        parsed.push(ScheduledAction { action, every });
+    }
+
+    if parsed.is_empty() {
+        return Err("at least one ACTION=INTERVAL pair".to_string());
+    }
+    Ok(parsed)
+}
+
+fn daemon_config() -> Result<DaemonConfig, ConfigError> {
+    let schedule = var("DAEMON_SCHEDULE").unwrap_or_else(|| DEFAULT_SCHEDULE.to_string());
+
+    Ok(DaemonConfig {
+        schedule: parse_schedule(&schedule).map_err(|expected| ConfigError::Invalid {
+            name: "DAEMON_SCHEDULE".to_string(),
+            value: schedule.clone(),
+            expected,
+        })?,
+        concurrency: positive("DAEMON_CONCURRENCY", 2)?,
+        county_batch: positive("DAEMON_COUNTY_BATCH", 100)?,
+        county_refresh_days: positive("DAEMON_COUNTY_REFRESH_DAYS", 160)?,
+        shutdown_grace: Duration::from_secs(parse(
+            "DAEMON_SHUTDOWN_GRACE_SECS",
+            600,
+            "seconds",
+            |_| true,
+        )?),
+    })
+}
+
+fn reddit_retry_config() -> Result<RedditRetryConfig, ConfigError> {
+    let reasons = var("REDDIT_RETRY_REASONS").unwrap_or_else(|| DEFAULT_RETRY_REASONS.to_string());
+    let reasons = if reasons.eq_ignore_ascii_case("all") {
+        vec![]
+    } else {
+        reasons
+            .split(',')
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty())
+            .collect()
+    };
+
+    Ok(RedditRetryConfig {
+        reasons,
+        min_age_hours: parse("REDDIT_RETRY_MIN_AGE_HOURS", 24, "a number of hours", |v| {
+            *v >= 0
+        })?,
+        max_age_days: var("REDDIT_RETRY_MAX_AGE_DAYS")
+            .map(|_| positive("REDDIT_RETRY_MAX_AGE_DAYS", 1))
+            .transpose()?,
+        max_attempts: positive("REDDIT_RETRY_MAX_ATTEMPTS", 4)?,
+        backoff_hours: parse(
+            "REDDIT_RETRY_BACKOFF_HOURS",
+            12.0,
+            "a positive number",
+            |v: &f64| v.is_finite() && *v > 0.0,
+        )?,
+        limit: positive("REDDIT_RETRY_LIMIT", 50)?,
+    })
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Ok(Config {
+            database_url: var("DATABASE_URL").ok_or(ConfigError::Missing("DATABASE_URL"))?,
+            openai_api_key: var("OPENAI_API_KEY"),
+            apify_api_token: var("APIFY_API_TOKEN"),
+            // GOOGLE_PLACES_KEY is the name the Google ingestion first used
+            google_places_api_key: var("GOOGLE_PLACES_API_KEY")
+                .or_else(|| var("GOOGLE_PLACES_KEY")),
+            scraper: ScraperConfig {
+                num_threads: positive("NUM_THREADS", 12)?,
+                max_scrapes: positive("MAX_SCRAPES", 100)?,
+                max_page_visits: positive("MAX_PAGE_VISITS", 5)?,
+            },
+            style_extraction: StyleExtractionConfig {
+                confidence_threshold: parse(
+                    "STYLE_CONFIDENCE_THRESHOLD",
+                    0.9,
+                    "a number from 0 to 1",
+                    |v| (0.0..=1.0).contains(v),
+                )?,
+                vision_batch_size: parse("VISION_BATCH_SIZE", 8, "a number from 1 to 16", |v| {
+                    (1..=16).contains(v)
+                })?,
+                artist_batch_limit: positive("ARTIST_BATCH_LIMIT", 10)?,
+                // Higher than it needs to be for OpenAI, to make up for
+                // slower Apify requests
+                concurrent_artists: positive("CONCURRENT_ARTISTS", 6)?,
+                max_posts_per_artist: positive("MAX_POSTS_PER_ARTIST", 10)?,
+            },
+            costs: CostConfig {
+                apify_usd_per_cu: usd("APIFY_USD_PER_CU", 0.40)?,
+                google_places_usd_per_call: usd("GOOGLE_PLACES_USD_PER_CALL", 0.032)?,
+                run_budget: budget_caps("INGESTION_BUDGET_USD")?,
+                city_budget: budget_caps("INGESTION_CITY_BUDGET_USD")?,
+            },
+            daemon: daemon_config()?,
+            reddit_scraper: RedditScraperConfig {
+                max_posts: positive("REDDIT_MAX_POSTS", 100)?,
+                min_images: parse("REDDIT_MIN_IMAGES", 1, "a number of images", |v| *v >= 0)?,
+                rescrape_days: positive("REDDIT_RESCRAPE_DAYS", 30)?,
+                max_cities: var("REDDIT_MAX_CITIES")
+                    .map(|_| positive("REDDIT_MAX_CITIES", 1))
+                    .transpose()?,
+            },
+            reddit_retry: reddit_retry_config()?,
+            portfolio_refresh: PortfolioRefreshConfig {
+                older_than_days: positive("PORTFOLIO_REFRESH_DAYS", 30)?,
+                limit: positive("PORTFOLIO_REFRESH_LIMIT", 25)?,
+                max_posts: positive("PORTFOLIO_REFRESH_POSTS", 12)?,
+            },
+            backfill: BackfillConfig {
+                batch_size: positive("BACKFILL_BATCH_SIZE", 1000)?,
+                pause: Duration::from_millis(parse(
+                    "BACKFILL_PAUSE_MS",
+                    100,
+                    "milliseconds",
+                    |_| true,
+                )?),
+            },
+            dedup: DedupConfig {
+                min_score: parse("DEDUP_MIN_SCORE", 0.85, "a number from 0 to 1", |v| {
+                    (0.0..=1.0).contains(v)
+                })?,
+                max_distance_m: parse("DEDUP_MAX_DISTANCE_M", 150.0, "at least 1 meter", |v| {
+                    *v >= 1.0
+                })?,
+            },
+            city_requests_limit: positive("CITY_REQUESTS_LIMIT", 25)?,
+        })
+    }
+
+    pub fn openai_api_key(&self) -> Result<&str, ConfigError> {
+        self.openai_api_key
+            .as_deref()
+            .ok_or(ConfigError::Missing("OPENAI_API_KEY"))
+    }
+
+    pub fn apify_api_token(&self) -> Result<&str, ConfigError> {
+        self.apify_api_token
+            .as_deref()
+            .ok_or(ConfigError::Missing("APIFY_API_TOKEN"))
+    }
+
+    pub fn google_places_api_key(&self) -> Result<&str, ConfigError> {
+        self.google_places_api_key
+            .as_deref()
+            .ok_or(ConfigError::Missing("GOOGLE_PLACES_API_KEY"))
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Loads the configuration, for `main` to report a bad setting before it
+/// starts an action
+pub fn init() -> Result<&'static Config, ConfigError> {
+    if let Some(config) = CONFIG.get() {
+        return Ok(config);
+    }
+    let config = Config::from_env()?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+/// The configuration. Binaries that don't call [`init`] load it on first
+/// use and panic on a bad setting.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        Config::from_env().unwrap_or_else(|e| panic!("Invalid configuration: {}", e))
+    })
+}
@@ -2,5 +2,6 @@
 // This allows binaries in src/bin/ to use modules from this crate
 
 pub mod actions;
+pub mod config;
 pub mod repository;
 pub mod services;
@@ -4,44 +4,43 @@ use dotenv::dotenv;
 use sqlx::PgPool;
 
 pub mod actions;
+pub mod config;
 pub mod repository;
 pub mod services;
 
-enum IngestAction {
-    Scrape,
-    GoogleApi,
-    ExtractStyles,
-    RedditScraper,
-}
-
-impl IngestAction {
-    fn new(action: &str) -> Self {
-        match action {
-            "SCRAPE_HTML" => Self::Scrape,
-            "GOOGLE_API" => Self::GoogleApi,
-            "EXTRACT_STYLES" => Self::ExtractStyles,
-            "REDDIT_SCRAPER" => Self::RedditScraper,
-            _ => panic!("Invalid action"),
-        }
-    }
-}
+use actions::IngestAction;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
-    let action: String = env::var("ACTION").expect("Action to be set");
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let action: String = env::var("ACTION").map_err(|_| "ACTION must be set")?;
+    if action != "DAEMON" && IngestAction::parse(&action).is_none() {
+        return Err(format!("Unknown ACTION \"{}\"", action).into());
+    }
+    let config = config::init()?;
 
-    let pool = PgPool::connect(&database_url)
+    let pool = PgPool::connect(&config.database_url)
         .await
         .expect("Failed to connect to PostgreSQL");
 
-    match IngestAction::new(&action) {
-        IngestAction::Scrape => actions::scraper::scrape(&pool).await,
-        IngestAction::GoogleApi => {
-            actions::google_api_ingestion::driver::ingest_google(&pool).await
-        }
-        IngestAction::ExtractStyles => actions::style_extraction::extract_styles(&pool).await,
-        IngestAction::RedditScraper => actions::reddit_scraper::run_reddit_scraper(&pool).await,
+    // The daemon records costs and breaker states after each run it schedules
+    if action == "DAEMON" {
+        return actions::daemon::run_daemon(&pool).await;
     }
+
+    services::costs::start_run(&action);
+    let result = IngestAction::parse(&action)
+        .expect("ACTION was checked at startup")
+        .run(&pool)
+        .await;
+
+    // Record spend even when the run failed part way through
+    if let Err(e) = services::costs::flush_run_costs(&pool, &action).await {
+        eprintln!("Failed to record ingestion costs: {}", e);
+    }
+    if let Err(e) = services::breakers::flush_breaker_states(&pool).await {
+        eprintln!("Failed to record circuit breaker states: {}", e);
+    }
+
+    result
 }
@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
 use sqlx::{PgPool, Row};
+use std::collections::HashSet;
 
 use shared_types::{CountyBoundary, LocationInfo};
 
+/// Inserts or refreshes shops by Google place id, skipping places that were
+/// merged into another shop as duplicates
 pub async fn upsert_locations(
     pool: &PgPool,
     locations: &[LocationInfo],
@@ -25,7 +28,8 @@ pub async fn upsert_locations(
                             lat,
                             long
                         )
-                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13
+                WHERE NOT EXISTS (SELECT 1 FROM location_merges WHERE merged_place_id = $8)
                 ON CONFLICT (_id) DO UPDATE
                 SET
                     city = EXCLUDED.city,
@@ -82,6 +86,7 @@ pub async fn fetch_county_boundaries(
                 date_utc_last_ingested
             FROM county_boundaries
             WHERE date_utc_last_ingested IS NULL OR date_utc_last_ingested < $1
+            ORDER BY date_utc_last_ingested ASC NULLS FIRST
             LIMIT $2
         ",
     )
@@ -105,6 +110,22 @@ pub async fn fetch_county_boundaries(
     Ok(county_boundaries)
 }
 
+/// Counties not ingested in the last `days_till_refetch` days
+pub async fn count_stale_counties(
+    pool: &PgPool,
+    days_till_refetch: i16,
+) -> Result<i64, sqlx::Error> {
+    let date_cutoff: DateTime<Utc> = Utc::now() - chrono::Duration::days(days_till_refetch as i64);
+
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM county_boundaries
+         WHERE date_utc_last_ingested IS NULL OR date_utc_last_ingested < $1",
+    )
+    .bind(date_cutoff.timestamp())
+    .fetch_one(pool)
+    .await
+}
+
 pub async fn mark_county_ingested(
     pool: &PgPool,
     county_boundary: &CountyBoundary,
@@ -246,6 +267,102 @@ pub async fn get_artists_for_style_extraction(
     Ok(artists)
 }
 
+/// Artists due a portfolio refresh: style extraction has captured their
+/// Instagram posts, and they were last refreshed (or captured, if never
+/// refreshed) more than `older_than_days` ago. Longest waiting first.
+pub async fn get_artists_for_portfolio_refresh(
+    pool: &PgPool,
+    older_than_days: i32,
+    limit: i64,
+) -> Result<Vec<Artist>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT id, name, social_links, instagram_handle
+         FROM artists
+         WHERE styles_extracted = 1
+           AND (LOWER(social_links) LIKE '%instagram.com%' OR instagram_handle IS NOT NULL)
+           AND (portfolio_refreshed_at IS NULL
+                OR portfolio_refreshed_at < CURRENT_TIMESTAMP - make_interval(days => $1))
+         ORDER BY portfolio_refreshed_at NULLS FIRST, id
+         LIMIT $2",
+    )
+    .bind(older_than_days)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let social_links: Option<String> = row.get("social_links");
+            let instagram_handle: Option<String> = row.get("instagram_handle");
+            Artist {
+                id: row.get("id"),
+                name: row.get("name"),
+                ig_username: social_links
+                    .as_deref()
+                    .and_then(extract_instagram_username)
+                    .or(instagram_handle.filter(|handle| !handle.trim().is_empty())),
+            }
+        })
+        .collect())
+}
+
+/// An artist's saved posts as `(short_code, post_date, removed)`
+pub async fn get_artist_posts(
+    pool: &PgPool,
+    artist_id: i64,
+) -> Result<Vec<(String, Option<i64>, bool)>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT short_code, post_date, removed_at IS NOT NULL as removed
+         FROM artists_images
+         WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get("short_code"),
+                row.get("post_date"),
+                row.get("removed"),
+            )
+        })
+        .collect())
+}
+
+/// Marks posts that are gone from Instagram as removed, or brings back ones
+/// that turned up again
+pub async fn set_artist_posts_removed(
+    pool: &PgPool,
+    artist_id: i64,
+    short_codes: &[String],
+    removed: bool,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE artists_images
+         SET removed_at = CASE WHEN $3 THEN CURRENT_TIMESTAMP END
+         WHERE artist_id = $1 AND short_code = ANY($2) AND (removed_at IS NOT NULL) <> $3",
+    )
+    .bind(artist_id)
+    .bind(short_codes)
+    .bind(removed)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn mark_portfolio_refreshed(pool: &PgPool, artist_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE artists SET portfolio_refreshed_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(artist_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 pub async fn get_all_styles(
     pool: &PgPool,
 ) -> Result<std::collections::HashMap<String, Vec<String>>, sqlx::Error> {
@@ -347,6 +464,36 @@ pub async fn insert_artist_image(
     Ok(row.get("id"))
 }
 
+/// Saves a post's caption and preview thumbnail for Instagram privacy mode,
+/// replacing what was saved before
+pub async fn upsert_instagram_media(
+    pool: &PgPool,
+    short_code: &str,
+    caption: Option<&str>,
+    thumbnail: Option<(&[u8], u32, u32)>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO instagram_media
+             (short_code, caption, thumbnail, thumbnail_width, thumbnail_height)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (short_code) DO UPDATE
+         SET caption = EXCLUDED.caption,
+             thumbnail = COALESCE(EXCLUDED.thumbnail, instagram_media.thumbnail),
+             thumbnail_width = COALESCE(EXCLUDED.thumbnail_width, instagram_media.thumbnail_width),
+             thumbnail_height = COALESCE(EXCLUDED.thumbnail_height, instagram_media.thumbnail_height),
+             fetched_at = CURRENT_TIMESTAMP",
+    )
+    .bind(short_code)
+    .bind(caption)
+    .bind(thumbnail.map(|(bytes, _, _)| bytes))
+    .bind(thumbnail.map(|(_, width, _)| width as i32))
+    .bind(thumbnail.map(|(_, _, height)| height as i32))
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 pub async fn insert_artist_image_styles(
     pool: &PgPool,
     artist_image_id: i64,
@@ -436,6 +583,13 @@ pub async fn get_cities_for_scrape(
     state_filter: Option<String>,
     rescrape_days: i16,
 ) -> Result<Vec<CityToScrape>, sqlx::Error> {
+    // Cities a previous run stopped part way through
+    let resumable = "EXISTS (
+        SELECT 1 FROM background_jobs j
+        WHERE j.queue = 'ingestion' AND j.kind = 'reddit_city' AND j.status IN ('running', 'dead')
+          AND j.payload->>'city' = reddit_scrape_cities.city
+          AND j.payload->>'state' = reddit_scrape_cities.state
+    )";
     let mut query = String::from("SELECT city, state FROM reddit_scrape_cities WHERE 1=1");
 
     // Add filters
@@ -444,10 +598,16 @@ pub async fn get_cities_for_scrape(
     } else if state_filter.is_some() {
         query.push_str(" AND state = $1");
     } else {
-        // Get pending or stale cities
-        query.push_str(" AND (status = 'pending' OR last_scraped_at IS NULL OR last_scraped_at < NOW() - INTERVAL '1 day' * $1)");
+        // Get pending, stale or unfinished cities
+        query.push_str(&format!(
+            " AND (status = 'pending' OR last_scraped_at IS NULL OR last_scraped_at < NOW() - INTERVAL '1 day' * $1 OR {})",
+            resumable
+        ));
     }
 
+    // Finish what was started before taking on new cities
+    query.push_str(&format!(" ORDER BY {} DESC", resumable));
+
     if let Some(lim) = limit {
         query.push_str(&format!(" LIMIT {}", lim));
     }
@@ -548,6 +708,22 @@ pub async fn find_location_by_shop_and_city(
     Ok(result.map(|row| row.get("id")))
 }
 
+/// The shop with this Google place id, or the one it was merged into
+pub async fn find_location_by_place_id(
+    pool: &PgPool,
+    place_id: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COALESCE(
+            (SELECT id FROM locations WHERE _id = $1),
+            (SELECT survivor_location_id FROM location_merges WHERE merged_place_id = $1)
+         )",
+    )
+    .bind(place_id)
+    .fetch_one(pool)
+    .await
+}
+
 // --- Artist Lookups ---
 
 pub struct ArtistWithSocial {
@@ -619,7 +795,8 @@ pub async fn find_artist_by_name_at_location(
             "SELECT id, name, location_id, social_links, instagram_handle
              FROM artists
              WHERE location_id = $1
-               AND (LOWER(name) LIKE '%' || $2 || '%' OR LOWER(name) LIKE '%' || $3 || '%')
+               AND (name_search LIKE '%' || search_normalize($2) || '%'
+                    OR name_search LIKE '%' || search_normalize($3) || '%')
              LIMIT 1",
         )
         .bind(location_id)
@@ -632,7 +809,7 @@ pub async fn find_artist_by_name_at_location(
             "SELECT id, name, location_id, social_links, instagram_handle
              FROM artists
              WHERE location_id = $1
-               AND LOWER(name) LIKE '%' || $2 || '%'
+               AND name_search LIKE '%' || search_normalize($2) || '%'
              LIMIT 1",
         )
         .bind(location_id)
@@ -756,6 +933,71 @@ pub async fn insert_reddit_artist_pending(
     Ok(())
 }
 
+// --- Scrape Checkpoints ---
+
+/// The posts among `post_urls` that have already been extracted
+pub async fn get_processed_post_urls(
+    pool: &PgPool,
+    post_urls: &[String],
+) -> Result<HashSet<String>, sqlx::Error> {
+    let rows = sqlx::query("SELECT post_url FROM reddit_processed_posts WHERE post_url = ANY($1)")
+        .bind(post_urls)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().map(|row| row.get("post_url")).collect())
+}
+
+pub async fn mark_post_processed(
+    pool: &PgPool,
+    post_url: &str,
+    city: &str,
+    state: &str,
+    pending_rows: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO reddit_processed_posts (post_url, city, state, pending_rows)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (post_url) DO NOTHING",
+    )
+    .bind(post_url)
+    .bind(city)
+    .bind(state)
+    .bind(pending_rows)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Latest unfinished `reddit_city` job for a city, as `(job_id, progress)`
+pub async fn find_unfinished_reddit_city_job(
+    pool: &PgPool,
+    city: &str,
+    state: &str,
+) -> Result<Option<(i64, serde_json::Value)>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT id, progress::text as progress FROM background_jobs
+         WHERE queue = 'ingestion' AND kind = 'reddit_city'
+           AND payload->>'city' = $1 AND payload->>'state' = $2
+           AND status IN ('running', 'dead')
+         ORDER BY id DESC
+         LIMIT 1",
+    )
+    .bind(city)
+    .bind(state)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| {
+        let progress: String = row.get("progress");
+        (
+            row.get("id"),
+            serde_json::from_str(&progress).unwrap_or_default(),
+        )
+    }))
+}
+
 // --- New Shop-Centric Functions ---
 
 pub async fn find_location_by_shop_fuzzy(
@@ -826,8 +1068,8 @@ pub async fn find_artist_by_name_fuzzy(
          FROM artists
          WHERE location_id = $1
            AND (
-             LOWER(name) = $2
-             OR LOWER(name) LIKE '%' || $3 || '%'
+             name_search = search_normalize($2)
+             OR name_search LIKE '%' || search_normalize($3) || '%'
            )
          LIMIT 1",
     )
@@ -1097,3 +1339,690 @@ pub async fn update_pending_artist_handle(
 
     Ok(())
 }
+
+// --- Failed Pending Retries ---
+
+/// A handle whose pending rows failed processing, eligible for another try
+#[derive(Debug, Clone)]
+pub struct FailedPendingArtist {
+    pub instagram_handle: String,
+    pub city: String,
+    pub state: String,
+    pub failure_reason: Option<String>,
+    pub retry_count: i32,
+}
+
+/// Failed handles due another attempt: failed with one of `reasons` (any
+/// reason when empty), last touched between `min_age_hours` ago and
+/// `max_age_days` ago, under `max_attempts` retries and past their backoff.
+/// Handles retried least come first.
+pub async fn get_failed_pending_for_retry(
+    pool: &PgPool,
+    reasons: &[String],
+    min_age_hours: i64,
+    max_age_days: Option<i64>,
+    max_attempts: i32,
+    limit: i64,
+) -> Result<Vec<FailedPendingArtist>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT LTRIM(instagram_handle, '@') as instagram_handle,
+                MIN(city) as city,
+                MIN(state) as state,
+                MIN(shop_processing_status) as failure_reason,
+                MAX(retry_count) as retry_count
+         FROM reddit_artists_pending
+         WHERE status = 'failed'
+           AND instagram_handle IS NOT NULL
+           AND LTRIM(instagram_handle, '@') != ''
+           AND (cardinality($1::text[]) = 0 OR shop_processing_status = ANY($1))
+           AND updated_at <= NOW() - INTERVAL '1 hour' * $2
+           AND ($3::bigint IS NULL OR updated_at >= NOW() - INTERVAL '1 day' * $3)
+           AND retry_count < $4
+           AND (next_retry_at IS NULL OR next_retry_at <= NOW())
+         GROUP BY LTRIM(instagram_handle, '@')
+         ORDER BY MAX(retry_count), MIN(updated_at)
+         LIMIT $5",
+    )
+    .bind(reasons)
+    .bind(min_age_hours)
+    .bind(max_age_days)
+    .bind(max_attempts)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| FailedPendingArtist {
+            instagram_handle: row.get("instagram_handle"),
+            city: row.get("city"),
+            state: row.get("state"),
+            failure_reason: row.get("failure_reason"),
+            retry_count: row.get("retry_count"),
+        })
+        .collect())
+}
+
+/// Status and failure reason a handle's pending rows were left with
+pub async fn get_pending_artist_outcome(
+    pool: &PgPool,
+    instagram_handle: &str,
+) -> Result<Option<(String, Option<String>)>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT status, shop_processing_status
+         FROM reddit_artists_pending
+         WHERE instagram_handle = $1
+         ORDER BY updated_at DESC
+         LIMIT 1",
+    )
+    .bind(instagram_handle)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("status"), row.get("shop_processing_status"))))
+}
+
+/// Logs a retry and counts it against the handle's pending rows. A failed
+/// retry waits `backoff_hours` before the next one.
+pub async fn record_pending_retry(
+    pool: &PgPool,
+    instagram_handle: &str,
+    attempt: i32,
+    failure_reason: Option<&str>,
+    outcome: &str,
+    outcome_reason: Option<&str>,
+    backoff_hours: Option<f64>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO reddit_pending_retries
+         (instagram_handle, attempt, failure_reason, outcome, outcome_reason)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(instagram_handle)
+    .bind(attempt)
+    .bind(failure_reason)
+    .bind(outcome)
+    .bind(outcome_reason)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE reddit_artists_pending
+         SET retry_count = $2,
+             next_retry_at = NOW() + INTERVAL '1 hour' * $3
+         WHERE LTRIM(instagram_handle, '@') = $1",
+    )
+    .bind(instagram_handle)
+    .bind(attempt)
+    .bind(backoff_hours)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Retries grouped by attempt number and the reason the record had failed
+#[derive(Debug)]
+pub struct RetryOutcomeCount {
+    pub attempt: i32,
+    pub failure_reason: Option<String>,
+    pub attempts: i64,
+    pub successes: i64,
+}
+
+pub async fn get_retry_outcome_counts(
+    pool: &PgPool,
+) -> Result<Vec<RetryOutcomeCount>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT attempt,
+                failure_reason,
+                COUNT(*) as attempts,
+                COUNT(*) FILTER (WHERE outcome = 'success') as successes
+         FROM reddit_pending_retries
+         GROUP BY attempt, failure_reason
+         ORDER BY attempt, failure_reason",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RetryOutcomeCount {
+            attempt: row.get("attempt"),
+            failure_reason: row.get("failure_reason"),
+            attempts: row.get("attempts"),
+            successes: row.get("successes"),
+        })
+        .collect())
+}
+
+/// A finished run's spend and counters, for `ingestion_runs`
+pub struct IngestionRunRecord<'a> {
+    pub run_id: &'a str,
+    pub action: &'a str,
+    pub started_at: DateTime<Utc>,
+    pub budget_usd: Option<f64>,
+    pub city_budget_usd: Option<f64>,
+    pub total_cost_usd: f64,
+    pub api_calls: i64,
+    pub over_budget: bool,
+    /// Cities abandoned for going over `city_budget_usd`
+    pub aborted_locations: &'a [String],
+    pub counts: crate::services::counters::RunCounters,
+}
+
+pub async fn insert_ingestion_run(
+    pool: &PgPool,
+    run: &IngestionRunRecord<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO ingestion_runs
+         (run_id, action, started_at, budget_usd, city_budget_usd, total_cost_usd,
+          api_calls, over_budget, aborted_locations, posts_processed, artists_added,
+          api_errors)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+    )
+    .bind(run.run_id)
+    .bind(run.action)
+    .bind(run.started_at)
+    .bind(run.budget_usd)
+    .bind(run.city_budget_usd)
+    .bind(run.total_cost_usd)
+    .bind(run.api_calls)
+    .bind(run.over_budget)
+    .bind(run.aborted_locations)
+    .bind(run.counts.posts_processed)
+    .bind(run.counts.artists_added)
+    .bind(run.counts.api_errors)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// When each of these actions last finished a run, for the daemon to pick
+/// its schedule back up after a restart
+pub async fn get_last_run_finished(
+    pool: &PgPool,
+    actions: &[String],
+) -> Result<Vec<(String, DateTime<Utc>)>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT action, MAX(finished_at) as finished_at
+         FROM ingestion_runs
+         WHERE action = ANY($1)
+         GROUP BY action",
+    )
+    .bind(actions)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("action"), row.get("finished_at")))
+        .collect())
+}
+
+pub async fn insert_ingestion_cost(
+    pool: &PgPool,
+    run_id: &str,
+    source: &str,
+    city: Option<&str>,
+    state: Option<&str>,
+    totals: &crate::services::costs::CostTotals,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO ingestion_costs
+         (run_id, source, city, state, api_calls, compute_units,
+          prompt_tokens, completion_tokens, cost_usd)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    )
+    .bind(run_id)
+    .bind(source)
+    .bind(city)
+    .bind(state)
+    .bind(totals.api_calls)
+    .bind(totals.compute_units)
+    .bind(totals.prompt_tokens)
+    .bind(totals.completion_tokens)
+    .bind(totals.cost_usd)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Saves a breaker's state at the end of a run, for the web metrics endpoint
+pub async fn upsert_breaker_state(
+    pool: &PgPool,
+    snapshot: &shared_types::circuit_breaker::BreakerSnapshot,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO circuit_breaker_states
+         (service, name, state, failure_rate, calls, rejected_calls, times_opened)
+         VALUES ('ingestion', $1, $2, $3, $4, $5, $6)
+         ON CONFLICT (service, name) DO UPDATE SET
+            state = EXCLUDED.state,
+            failure_rate = EXCLUDED.failure_rate,
+            calls = EXCLUDED.calls,
+            rejected_calls = EXCLUDED.rejected_calls,
+            times_opened = EXCLUDED.times_opened,
+            updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(&snapshot.name)
+    .bind(snapshot.state.as_str())
+    .bind(snapshot.failure_rate)
+    .bind(snapshot.calls as i64)
+    .bind(snapshot.rejected_calls as i64)
+    .bind(snapshot.times_opened as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Surfaces an ingestion problem in the admin error log
+pub async fn log_ingestion_alert(
+    pool: &PgPool,
+    message: &str,
+    run_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO error_logs (error_type, error_level, error_message, additional_context)
+         VALUES ('ingestion', 'warn', $1, $2)",
+    )
+    .bind(message)
+    .bind(serde_json::json!({ "run_id": run_id }).to_string())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Upserts the open issues found by one integrity check and clears any open
+/// issues for that check that were not found this time.
+pub async fn record_data_quality_issues(
+    pool: &PgPool,
+    check_name: &str,
+    entity_table: &str,
+    repairable: bool,
+    issues: &[(i64, Option<String>)],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for (entity_id, details) in issues {
+        sqlx::query(
+            "INSERT INTO data_quality_issues
+             (check_name, entity_table, entity_id, details, repairable)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (check_name, entity_table, entity_id) WHERE resolved_at IS NULL
+             DO UPDATE SET details = EXCLUDED.details,
+                           repairable = EXCLUDED.repairable,
+                           last_seen_at = CURRENT_TIMESTAMP",
+        )
+        .bind(check_name)
+        .bind(entity_table)
+        .bind(entity_id)
+        .bind(details)
+        .bind(repairable)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let found_ids: Vec<i64> = issues.iter().map(|(id, _)| *id).collect();
+    sqlx::query(
+        "UPDATE data_quality_issues
+         SET resolved_at = CURRENT_TIMESTAMP, resolution = 'cleared'
+         WHERE check_name = $1 AND resolved_at IS NULL AND NOT (entity_id = ANY($2))",
+    )
+    .bind(check_name)
+    .bind(&found_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+pub async fn resolve_data_quality_issues(
+    pool: &PgPool,
+    check_name: &str,
+    entity_ids: &[i64],
+    resolution: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE data_quality_issues
+         SET resolved_at = CURRENT_TIMESTAMP, resolution = $3
+         WHERE check_name = $1 AND resolved_at IS NULL AND entity_id = ANY($2)",
+    )
+    .bind(check_name)
+    .bind(entity_ids)
+    .bind(resolution)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Latest unfinished backfill job for `name`, as `(job_id, progress)`.
+pub async fn find_unfinished_backfill_job(
+    pool: &PgPool,
+    name: &str,
+) -> Result<Option<(i64, serde_json::Value)>, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT id, progress::text as progress FROM background_jobs
+         WHERE queue = 'ingestion' AND kind = 'backfill' AND payload->>'name' = $1
+           AND status IN ('running', 'dead')
+         ORDER BY id DESC
+         LIMIT 1",
+    )
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| {
+        let progress: String = row.get("progress");
+        (
+            row.get("id"),
+            serde_json::from_str(&progress).unwrap_or_default(),
+        )
+    }))
+}
+
+/// Records a job this run is starting on the `ingestion` queue, which the
+/// web worker leaves alone
+pub async fn create_job(
+    pool: &PgPool,
+    kind: &str,
+    payload: &serde_json::Value,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        "INSERT INTO background_jobs
+             (queue, kind, status, payload, attempts, max_attempts, started_at)
+         VALUES ('ingestion', $1, 'running', $2::jsonb, 1, 1, CURRENT_TIMESTAMP)
+         RETURNING id",
+    )
+    .bind(kind)
+    .bind(payload.to_string())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+pub async fn update_job_progress(
+    pool: &PgPool,
+    job_id: i64,
+    status: &str,
+    progress: &serde_json::Value,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE background_jobs
+         SET status = $2, progress = $3::jsonb, last_error = NULL
+         WHERE id = $1",
+    )
+    .bind(job_id)
+    .bind(status)
+    .bind(progress.to_string())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks a job completed, or dead with `error`
+pub async fn finish_job(
+    pool: &PgPool,
+    job_id: i64,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE background_jobs
+         SET status = CASE WHEN $2::text IS NULL THEN 'completed' ELSE 'dead' END,
+             last_error = $2,
+             finished_at = CASE WHEN $2::text IS NULL THEN CURRENT_TIMESTAMP END
+         WHERE id = $1",
+    )
+    .bind(job_id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Puts claimed jobs back in the queue for a later run
+pub async fn requeue_jobs(pool: &PgPool, job_ids: &[i64]) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE background_jobs
+         SET status = 'queued', started_at = NULL, attempts = GREATEST(attempts - 1, 0)
+         WHERE id = ANY($1) AND status = 'running'",
+    )
+    .bind(job_ids)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves up to `limit` queued jobs of `kind` to running, oldest first, and
+/// returns them as `(job_id, payload)`. Concurrent runs skip each other's jobs.
+pub async fn claim_queued_jobs(
+    pool: &PgPool,
+    kind: &str,
+    limit: i64,
+) -> Result<Vec<(i64, serde_json::Value)>, sqlx::Error> {
+    let rows = sqlx::query(
+        "UPDATE background_jobs
+         SET status = 'running', started_at = CURRENT_TIMESTAMP, attempts = attempts + 1
+         WHERE id IN (
+             SELECT id FROM background_jobs
+             WHERE queue = 'ingestion' AND kind = $1 AND status = 'queued'
+             ORDER BY created_at
+             LIMIT $2
+             FOR UPDATE SKIP LOCKED
+         )
+         RETURNING id, payload::text as payload",
+    )
+    .bind(kind)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let payload: String = row.get("payload");
+            (
+                row.get("id"),
+                serde_json::from_str(&payload).unwrap_or_default(),
+            )
+        })
+        .collect())
+}
+
+// --- Location Dedup ---
+
+pub struct DedupLocation {
+    pub id: i64,
+    pub name: String,
+    pub address: Option<String>,
+    pub lat: f64,
+    pub long: f64,
+    pub claimed: bool,
+    pub artists: i64,
+}
+
+/// States with shops, for dedup to compare one state at a time
+pub async fn get_location_states(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT DISTINCT state FROM locations WHERE state IS NOT NULL ORDER BY state",
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Shops in `state` with coordinates, by latitude, leaving out `exclude_id`
+pub async fn get_locations_for_dedup(
+    pool: &PgPool,
+    state: &str,
+    exclude_id: i64,
+) -> Result<Vec<DedupLocation>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT l.id, l.name, l.address, l.lat::float8 as lat, l.long::float8 as long,
+                l.claimed_by IS NOT NULL as claimed,
+                (SELECT COUNT(*) FROM artists a WHERE a.location_id = l.id) as artists
+         FROM locations l
+         WHERE l.state = $1 AND l.id <> $2
+           AND l.name IS NOT NULL AND l.lat IS NOT NULL AND l.long IS NOT NULL
+         ORDER BY l.lat",
+    )
+    .bind(state)
+    .bind(exclude_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DedupLocation {
+            id: row.get("id"),
+            name: row.get("name"),
+            address: row.get("address"),
+            lat: row.get("lat"),
+            long: row.get("long"),
+            claimed: row.get("claimed"),
+            artists: row.get("artists"),
+        })
+        .collect())
+}
+
+pub struct LocationMerge {
+    pub artists_moved: i32,
+    pub photos_moved: i32,
+}
+
+/// Merges shop `merge_id` into `keep_id` in one transaction: its artists,
+/// photos, claims, guest spots and scrape history move over, details the
+/// kept shop is missing are filled in from it, and it's deleted with its
+/// place id recorded in `location_merges`. `None` if either shop is already
+/// gone.
+pub async fn merge_locations(
+    pool: &PgPool,
+    keep_id: i64,
+    merge_id: i64,
+    score: f64,
+    distance_m: f64,
+) -> Result<Option<LocationMerge>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let locked: Vec<i64> =
+        sqlx::query_scalar("SELECT id FROM locations WHERE id = ANY($1) FOR UPDATE")
+            .bind(vec![keep_id, merge_id])
+            .fetch_all(&mut *tx)
+            .await?;
+    if locked.len() != 2 {
+        tx.rollback().await?;
+        return Ok(None);
+    }
+
+    let artists_moved = sqlx::query("UPDATE artists SET location_id = $1 WHERE location_id = $2")
+        .bind(keep_id)
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i32;
+
+    // After the kept shop's own photos
+    let photos_moved = sqlx::query(
+        "UPDATE location_photos
+         SET location_id = $1,
+             position = position + (SELECT COALESCE(MAX(position) + 1, 0)
+                                    FROM location_photos WHERE location_id = $1)
+         WHERE location_id = $2",
+    )
+    .bind(keep_id)
+    .bind(merge_id)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected() as i32;
+
+    // A user with a pending claim on both keeps the one on the kept shop
+    sqlx::query(
+        "UPDATE shop_claims sc SET location_id = $1
+         WHERE sc.location_id = $2
+           AND NOT (sc.status = 'pending' AND EXISTS (
+               SELECT 1 FROM shop_claims k
+               WHERE k.location_id = $1 AND k.user_id = sc.user_id AND k.status = 'pending'
+           ))",
+    )
+    .bind(keep_id)
+    .bind(merge_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM shop_claims WHERE location_id = $1")
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE scrape_actions SET location_id = $1 WHERE location_id = $2")
+        .bind(keep_id)
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE guest_spots SET location_id = $1 WHERE location_id = $2")
+        .bind(keep_id)
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE locations k
+         SET website_uri = COALESCE(k.website_uri, m.website_uri),
+             postal_code = COALESCE(k.postal_code, m.postal_code),
+             description = COALESCE(k.description, m.description),
+             opening_hours = COALESCE(k.opening_hours, m.opening_hours),
+             claimed_at = CASE WHEN k.claimed_by IS NULL THEN m.claimed_at ELSE k.claimed_at END,
+             claimed_by = COALESCE(k.claimed_by, m.claimed_by)
+         FROM locations m
+         WHERE k.id = $1 AND m.id = $2",
+    )
+    .bind(keep_id)
+    .bind(merge_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE location_merges SET survivor_location_id = $1 WHERE survivor_location_id = $2",
+    )
+    .bind(keep_id)
+    .bind(merge_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO location_merges
+         (merged_location_id, merged_place_id, merged_name, merged_address,
+          survivor_location_id, score, distance_m, artists_moved, photos_moved)
+         SELECT id, _id, name, address, $1, $3, $4, $5, $6
+         FROM locations WHERE id = $2",
+    )
+    .bind(keep_id)
+    .bind(merge_id)
+    .bind(score)
+    .bind(distance_m)
+    .bind(artists_moved)
+    .bind(photos_moved)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM locations WHERE id = $1")
+        .bind(merge_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(Some(LocationMerge {
+        artists_moved,
+        photos_moved,
+    }))
+}
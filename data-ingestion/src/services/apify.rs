@@ -5,7 +5,9 @@ use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_json::json;
-use std::env;
+
+use super::breakers::{self, APIFY};
+use super::costs;
 
 // ============================================================================
 // Private Generic Apify Runners
@@ -19,7 +21,7 @@ async fn run_apify_sync<T: DeserializeOwned>(
     memory_mb: Option<u16>,
     timeout_secs: u64,
 ) -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>> {
-    let api_token = env::var("APIFY_API_TOKEN")?;
+    let api_token = crate::config::get().apify_api_token()?;
 
     let url = if let Some(mem) = memory_mb {
         format!(
@@ -37,7 +39,16 @@ async fn run_apify_sync<T: DeserializeOwned>(
         .timeout(std::time::Duration::from_secs(timeout_secs))
         .build()?;
 
-    let response = client.post(&url).json(&input).send().await?;
+    costs::check_budget()?;
+    APIFY.check()?;
+    let started = std::time::Instant::now();
+    let response = client.post(&url).json(&input).send().await;
+    breakers::record_response(&APIFY, &response);
+    let response = response?;
+
+    // run-sync doesn't return run stats, so estimate compute units (GB-hours)
+    let memory_gb = memory_mb.unwrap_or(1024) as f64 / 1024.0;
+    costs::record_apify_run(memory_gb * started.elapsed().as_secs_f64() / 3600.0, None);
 
     if !response.status().is_success() {
         let status = response.status();
@@ -76,6 +87,16 @@ struct ApifyRunData {
     #[serde(rename = "defaultDatasetId")]
     default_dataset_id: String,
     status: String,
+    #[serde(default)]
+    stats: Option<ApifyRunStats>,
+    #[serde(rename = "usageTotalUsd", default)]
+    usage_total_usd: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct ApifyRunStats {
+    #[serde(rename = "computeUnits", default)]
+    compute_units: Option<f64>,
 }
 
 async fn run_apify_async<T: DeserializeOwned>(
@@ -83,7 +104,7 @@ async fn run_apify_async<T: DeserializeOwned>(
     input: serde_json::Value,
     timeout_secs: u64,
 ) -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>> {
-    let api_token = env::var("APIFY_API_TOKEN")?;
+    let api_token = crate::config::get().apify_api_token()?;
     let client = Client::new();
 
     // Step 1: Start the run
@@ -93,7 +114,11 @@ async fn run_apify_async<T: DeserializeOwned>(
     );
 
     println!("🚀 Starting Apify run...");
-    let start_response = client.post(&start_url).json(&input).send().await?;
+    costs::check_budget()?;
+    APIFY.check()?;
+    let start_response = client.post(&start_url).json(&input).send().await;
+    breakers::record_response(&APIFY, &start_response);
+    let start_response = start_response?;
 
     if !start_response.status().is_success() {
         let error_text = start_response.text().await?;
@@ -124,6 +149,18 @@ async fn run_apify_async<T: DeserializeOwned>(
 
         println!("   Status after {}s: {}", elapsed, status);
 
+        if matches!(
+            status.as_str(),
+            "SUCCEEDED" | "FAILED" | "ABORTED" | "TIMED-OUT"
+        ) {
+            let compute_units = status_data
+                .data
+                .stats
+                .and_then(|s| s.compute_units)
+                .unwrap_or(0.0);
+            costs::record_apify_run(compute_units, status_data.data.usage_total_usd);
+        }
+
         match status.as_str() {
             "SUCCEEDED" => {
                 println!("✅ Run completed successfully!");
@@ -147,7 +184,10 @@ async fn run_apify_async<T: DeserializeOwned>(
     );
 
     println!("📥 Fetching results from dataset...");
-    let dataset_response = client.get(&dataset_url).send().await?;
+    APIFY.check()?;
+    let dataset_response = client.get(&dataset_url).send().await;
+    breakers::record_response(&APIFY, &dataset_response);
+    let dataset_response = dataset_response?;
     let response_text = dataset_response.text().await?;
 
     let results: Vec<T> = serde_json::from_str(&response_text).map_err(|e| {
@@ -0,0 +1,60 @@
+// External API Circuit Breakers
+// One breaker per external service, so an outage fails the rest of a run's
+// calls fast instead of each waiting out its timeout. Breaker states are
+// written to circuit_breaker_states when the run finishes, where the web
+// server's metrics endpoint reports them.
+
+use shared_types::circuit_breaker::{
+    is_outage_status, BreakerConfig, BreakerError, CircuitBreaker,
+};
+use sqlx::PgPool;
+use std::future::Future;
+
+use crate::repository::upsert_breaker_state;
+use crate::services::counters;
+
+pub static APIFY: CircuitBreaker = CircuitBreaker::new("apify", BreakerConfig::DEFAULT);
+pub static OPENAI: CircuitBreaker = CircuitBreaker::new("openai", BreakerConfig::DEFAULT);
+pub static GOOGLE_PLACES: CircuitBreaker =
+    CircuitBreaker::new("google_places", BreakerConfig::DEFAULT);
+/// Instagram's CDN, for post images
+pub static INSTAGRAM: CircuitBreaker = CircuitBreaker::new("instagram", BreakerConfig::DEFAULT);
+
+pub fn all() -> [&'static CircuitBreaker; 4] {
+    [&APIFY, &OPENAI, &GOOGLE_PLACES, &INSTAGRAM]
+}
+
+/// Records the outcome of a call admitted by `breaker.check()`
+pub fn record_response(
+    breaker: &CircuitBreaker,
+    response: &Result<reqwest::Response, reqwest::Error>,
+) {
+    let succeeded = matches!(response, Ok(r) if !is_outage_status(r.status().as_u16()));
+    breaker.record(succeeded);
+    if !succeeded {
+        counters::record_api_error();
+    }
+}
+
+/// `breaker.call`, counting a failed call towards the run's API errors
+pub async fn call<T, E>(
+    breaker: &CircuitBreaker,
+    call: impl Future<Output = Result<T, E>>,
+) -> Result<T, BreakerError<E>> {
+    let result = breaker.call(call).await;
+    if matches!(result, Err(BreakerError::Inner(_))) {
+        counters::record_api_error();
+    }
+    result
+}
+
+/// Writes every breaker that saw calls this run
+pub async fn flush_breaker_states(pool: &PgPool) -> Result<(), sqlx::Error> {
+    for breaker in all() {
+        let snapshot = breaker.snapshot();
+        if snapshot.calls > 0 || snapshot.rejected_calls > 0 {
+            upsert_breaker_state(pool, &snapshot).await?;
+        }
+    }
+    Ok(())
+}
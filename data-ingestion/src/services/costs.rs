@@ -0,0 +1,328 @@
+// External API Cost Tracking
+// Collects Apify, OpenAI and Google Places usage for the current run and
+// persists it to ingestion_runs / ingestion_costs when the run finishes.
+//
+// Budget caps are checked before every billable call:
+// - INGESTION_BUDGET_USD_<ACTION> (falling back to INGESTION_BUDGET_USD)
+//   caps the whole run; once it's spent the run stops
+// - INGESTION_CITY_BUDGET_USD_<ACTION> (falling back to
+//   INGESTION_CITY_BUDGET_USD) caps each city or county; once it's spent
+//   the rest of that city is abandoned and the run moves on to the next
+//
+// Prices and caps are read by crate::config.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::config;
+use crate::repository::{
+    insert_ingestion_cost, insert_ingestion_run, log_ingestion_alert, IngestionRunRecord,
+};
+use crate::services::counters;
+
+pub const SOURCE_APIFY: &str = "apify";
+pub const SOURCE_OPENAI: &str = "openai";
+pub const SOURCE_GOOGLE_PLACES: &str = "google_places";
+
+#[derive(Debug, Default, Clone)]
+pub struct CostTotals {
+    pub api_calls: i64,
+    pub compute_units: f64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+}
+
+type CostKey = (&'static str, Option<String>, Option<String>);
+
+struct CostTracker {
+    started_at: DateTime<Utc>,
+    action: Option<String>,
+    city: Option<String>,
+    state: Option<String>,
+    totals: HashMap<CostKey, CostTotals>,
+    /// Cities abandoned for going over their budget, as "City, ST"
+    aborted: Vec<String>,
+}
+
+static TRACKER: Lazy<Mutex<CostTracker>> = Lazy::new(|| {
+    Mutex::new(CostTracker {
+        started_at: Utc::now(),
+        action: None,
+        city: None,
+        state: None,
+        totals: HashMap::new(),
+        aborted: Vec::new(),
+    })
+});
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetScope {
+    Run,
+    City,
+}
+
+/// A budget cap was reached; returned in place of the call it would have
+/// paid for
+#[derive(Debug, Clone)]
+pub struct BudgetExceeded {
+    pub scope: BudgetScope,
+    pub spent_usd: f64,
+    pub budget_usd: f64,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scope = match self.scope {
+            BudgetScope::Run => "run",
+            BudgetScope::City => "city",
+        };
+        write!(
+            f,
+            "{} budget of ${:.2} used up (${:.4} spent)",
+            scope, self.budget_usd, self.spent_usd
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+fn location_label(city: Option<&str>, state: Option<&str>) -> String {
+    match (city, state) {
+        (Some(city), Some(state)) => format!("{}, {}", city, state),
+        (Some(city), None) => city.to_string(),
+        (None, _) => "(no location)".to_string(),
+    }
+}
+
+fn record(source: &'static str, update: impl FnOnce(&mut CostTotals)) {
+    let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+    let key = (source, tracker.city.clone(), tracker.state.clone());
+    update(tracker.totals.entry(key).or_default());
+}
+
+/// Starts a new run's clock and picks the action's budgets. The daemon
+/// records several runs per process.
+pub fn start_run(action: &str) {
+    let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+    tracker.started_at = Utc::now();
+    tracker.action = Some(action.to_string());
+    tracker.aborted.clear();
+}
+
+/// Errors once the run has spent its budget. Checked between cities so a
+/// run stops instead of failing every remaining city.
+pub fn check_run_budget() -> Result<(), BudgetExceeded> {
+    let tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(budget_usd) = config::get()
+        .costs
+        .run_budget
+        .for_action(tracker.action.as_deref())
+    else {
+        return Ok(());
+    };
+    let spent_usd: f64 = tracker.totals.values().map(|t| t.cost_usd).sum();
+    if spent_usd >= budget_usd {
+        return Err(BudgetExceeded {
+            scope: BudgetScope::Run,
+            spent_usd,
+            budget_usd,
+        });
+    }
+    Ok(())
+}
+
+/// Errors once the run or the current city has spent its budget. Call
+/// before every billable request.
+pub fn check_budget() -> Result<(), BudgetExceeded> {
+    check_run_budget()?;
+
+    let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(budget_usd) = config::get()
+        .costs
+        .city_budget
+        .for_action(tracker.action.as_deref())
+    else {
+        return Ok(());
+    };
+    let spent_usd: f64 = tracker
+        .totals
+        .iter()
+        .filter(|((_, city, state), _)| *city == tracker.city && *state == tracker.state)
+        .map(|(_, t)| t.cost_usd)
+        .sum();
+    if spent_usd >= budget_usd {
+        let label = location_label(tracker.city.as_deref(), tracker.state.as_deref());
+        if !tracker.aborted.contains(&label) {
+            eprintln!(
+                "💸 Abandoning {}: spent ${:.4} of its ${:.2} budget",
+                label, spent_usd, budget_usd
+            );
+            tracker.aborted.push(label);
+        }
+        return Err(BudgetExceeded {
+            scope: BudgetScope::City,
+            spent_usd,
+            budget_usd,
+        });
+    }
+    Ok(())
+}
+
+/// Attributes all following costs to this city/state until it is changed again
+pub fn set_location(city: Option<&str>, state: Option<&str>) {
+    let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+    tracker.city = city.map(str::to_string);
+    tracker.state = state.map(str::to_string);
+}
+
+/// Records one Apify actor run. `usage_usd` is used when Apify reports it,
+/// otherwise compute units are priced at APIFY_USD_PER_CU.
+pub fn record_apify_run(compute_units: f64, usage_usd: Option<f64>) {
+    let cost = usage_usd.unwrap_or_else(|| compute_units * config::get().costs.apify_usd_per_cu);
+    record(SOURCE_APIFY, |t| {
+        t.api_calls += 1;
+        t.compute_units += compute_units;
+        t.cost_usd += cost;
+    });
+}
+
+/// Records one OpenAI chat completion and returns its estimated cost
+pub fn record_openai_usage(model: &str, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+    // USD per 1K tokens (input, output)
+    let (input_per_1k, output_per_1k) = match model {
+        "gpt-4o-mini" => (0.00015, 0.0006),
+        "o4-mini" => (0.0011, 0.0044),
+        _ => (0.0025, 0.01),
+    };
+    let cost = (prompt_tokens as f64 / 1000.0) * input_per_1k
+        + (completion_tokens as f64 / 1000.0) * output_per_1k;
+
+    record(SOURCE_OPENAI, |t| {
+        t.api_calls += 1;
+        t.prompt_tokens += prompt_tokens as i64;
+        t.completion_tokens += completion_tokens as i64;
+        t.cost_usd += cost;
+    });
+    cost
+}
+
+/// Records one billable Google Places request
+pub fn record_google_places_call() {
+    let cost = config::get().costs.google_places_usd_per_call;
+    record(SOURCE_GOOGLE_PLACES, |t| {
+        t.api_calls += 1;
+        t.cost_usd += cost;
+    });
+}
+
+/// Prints what the run spent per source and in its most expensive cities
+fn print_summary(
+    run_id: &str,
+    totals: &HashMap<CostKey, CostTotals>,
+    budget: Option<f64>,
+    aborted: &[String],
+) {
+    let mut by_source: HashMap<&str, CostTotals> = HashMap::new();
+    let mut by_location: HashMap<String, f64> = HashMap::new();
+    for ((source, city, state), t) in totals {
+        let source_totals = by_source.entry(source).or_default();
+        source_totals.api_calls += t.api_calls;
+        source_totals.cost_usd += t.cost_usd;
+        *by_location
+            .entry(location_label(city.as_deref(), state.as_deref()))
+            .or_default() += t.cost_usd;
+    }
+    let total_cost: f64 = by_source.values().map(|t| t.cost_usd).sum();
+
+    println!("💰 Cost summary for run {}", run_id);
+    let mut sources: Vec<_> = by_source.into_iter().collect();
+    sources.sort_by(|a, b| b.1.cost_usd.total_cmp(&a.1.cost_usd));
+    for (source, t) in &sources {
+        println!(
+            "   {:<14} {:>6} calls  ${:.4}",
+            source, t.api_calls, t.cost_usd
+        );
+    }
+
+    let mut locations: Vec<_> = by_location.into_iter().collect();
+    locations.sort_by(|a, b| b.1.total_cmp(&a.1));
+    for (location, cost) in locations.iter().take(5) {
+        println!("   {:<30} ${:.4}", location, cost);
+    }
+
+    match budget {
+        Some(budget) => println!("   Total ${:.4} of ${:.2} budget", total_cost, budget),
+        None => println!("   Total ${:.4} (no budget set)", total_cost),
+    }
+    if !aborted.is_empty() {
+        println!(
+            "   Abandoned over their city budget: {}",
+            aborted.join("; ")
+        );
+    }
+}
+
+/// Writes the run's costs and counters, prints a summary and raises an
+/// alert if it went over budget.
+pub async fn flush_run_costs(pool: &PgPool, action: &str) -> Result<(), sqlx::Error> {
+    let (started_at, totals, aborted) = {
+        let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+        (
+            tracker.started_at,
+            std::mem::take(&mut tracker.totals),
+            std::mem::take(&mut tracker.aborted),
+        )
+    };
+
+    let counts = counters::take();
+
+    let total_cost: f64 = totals.values().map(|t| t.cost_usd).sum();
+    let api_calls: i64 = totals.values().map(|t| t.api_calls).sum();
+    let budget = budget_cap("INGESTION_BUDGET_USD", Some(action));
+    let city_budget = budget_cap("INGESTION_CITY_BUDGET_USD", Some(action));
+    let over_budget = budget.is_some_and(|b| total_cost > b);
+
+    let run_id = format!(
+        "{}-{}",
+        action.to_lowercase(),
+        started_at.timestamp_millis()
+    );
+
+    insert_ingestion_run(
+        pool,
+        &IngestionRunRecord {
+            run_id: &run_id,
+            action,
+            started_at,
+            budget_usd: budget,
+            city_budget_usd: city_budget,
+            total_cost_usd: total_cost,
+            api_calls,
+            over_budget,
+            aborted_locations: &aborted,
+            counts,
+        },
+    )
+    .await?;
+
+    for ((source, city, state), t) in &totals {
+        insert_ingestion_cost(pool, &run_id, source, city.as_deref(), state.as_deref(), t).await?;
+    }
+
+    print_summary(&run_id, &totals, budget, &aborted);
+
+    if let (true, Some(budget)) = (over_budget, budget) {
+        let message = format!(
+            "Ingestion run {} cost ${:.2}, exceeding its ${:.2} budget",
+            run_id, total_cost, budget
+        );
+        eprintln!("🚨 {}", message);
+        log_ingestion_alert(pool, &message, &run_id).await?;
+    }
+
+    Ok(())
+}
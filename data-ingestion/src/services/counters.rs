@@ -0,0 +1,43 @@
+// Ingestion Run Counters
+// What a run got through: posts processed, artists added and failed
+// external API calls. Saved with the run's ingestion_runs row when it
+// finishes, where the web server's /metrics endpoint totals them per action.
+// Like costs, counts from daemon runs that overlap go to whichever finishes
+// first.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+static POSTS_PROCESSED: AtomicI64 = AtomicI64::new(0);
+static ARTISTS_ADDED: AtomicI64 = AtomicI64::new(0);
+static API_ERRORS: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunCounters {
+    pub posts_processed: i64,
+    pub artists_added: i64,
+    pub api_errors: i64,
+}
+
+/// A post saved to an artist's portfolio or a Reddit post extracted
+pub fn record_post_processed() {
+    POSTS_PROCESSED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_artist_added() {
+    ARTISTS_ADDED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A call to Apify, OpenAI, Google Places or Instagram that failed or
+/// timed out
+pub fn record_api_error() {
+    API_ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The counts since the last call, starting the next run's from zero
+pub fn take() -> RunCounters {
+    RunCounters {
+        posts_processed: POSTS_PROCESSED.swap(0, Ordering::Relaxed),
+        artists_added: ARTISTS_ADDED.swap(0, Ordering::Relaxed),
+        api_errors: API_ERRORS.swap(0, Ordering::Relaxed),
+    }
+}
@@ -6,7 +6,9 @@ use reqwest::{header::HeaderMap, Client};
 use serde_json::{json, Value};
 use shared_types::LocationInfo;
 use std::collections::HashSet;
-use std::env;
+
+use super::breakers::{self, GOOGLE_PLACES};
+use super::costs;
 
 // Bounding box for geographic restriction
 #[derive(Debug, Clone)]
@@ -41,7 +43,7 @@ pub async fn search_text_with_location(
     bounds: &LocationBounds,
     page_size: i8,
 ) -> Result<Value, Box<dyn std::error::Error>> {
-    let api_key = env::var("GOOGLE_PLACES_API_KEY")?;
+    let api_key = crate::config::get().google_places_api_key()?;
     let url = "https://places.googleapis.com/v1/places:searchText";
 
     let body = json!({
@@ -71,7 +73,12 @@ pub async fn search_text_with_location(
     );
 
     let client = Client::new();
-    let response = client.post(url).headers(headers).json(&body).send().await?;
+    costs::check_budget()?;
+    GOOGLE_PLACES.check()?;
+    let response = client.post(url).headers(headers).json(&body).send().await;
+    breakers::record_response(&GOOGLE_PLACES, &response);
+    let response = response?;
+    costs::record_google_places_call();
 
     if !response.status().is_success() {
         let status = response.status();
@@ -91,7 +98,7 @@ pub async fn search_text_in_rectangle(
     page_size: i8,
     page_token: Option<&str>,
 ) -> Result<Value, Box<dyn std::error::Error>> {
-    let api_key = env::var("GOOGLE_PLACES_API_KEY")?;
+    let api_key = crate::config::get().google_places_api_key()?;
     let url = "https://places.googleapis.com/v1/places:searchText";
 
     let mut body = json!({
@@ -123,7 +130,12 @@ pub async fn search_text_in_rectangle(
     headers.insert("X-Goog-FieldMask", "nextPageToken,places.location,places.photos.heightPx,places.photos.widthPx,places.photos.authorAttributions.photoUri,places.displayName,places.formattedAddress,places.addressComponents,places.primaryType,places.primaryTypeDisplayName,places.id,places.nationalPhoneNumber,places.internationalPhoneNumber,places.rating,places.websiteUri,places.businessStatus,places.websiteUri".parse()?);
 
     let client = Client::new();
-    let response = client.post(url).headers(headers).json(&body).send().await?;
+    costs::check_budget()?;
+    GOOGLE_PLACES.check()?;
+    let response = client.post(url).headers(headers).json(&body).send().await;
+    breakers::record_response(&GOOGLE_PLACES, &response);
+    let response = response?;
+    costs::record_google_places_call();
 
     if !response.status().is_success() {
         let status = response.status();
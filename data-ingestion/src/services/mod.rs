@@ -1,2 +1,5 @@
 pub mod apify;
+pub mod breakers;
+pub mod costs;
+pub mod counters;
 pub mod google_places;
@@ -0,0 +1,75 @@
+-- Diacritics- and case-insensitive search columns for cities and artist names.
+--
+-- search_normalize() lowercases, strips accents, and folds apostrophes, dots and
+-- hyphens into spaces so that "Coeur d'Alene", "Cœur d’Alene" and "coeur dalene"
+-- all compare equal. The *_search columns are kept in sync by triggers.
+
+CREATE EXTENSION IF NOT EXISTS unaccent;
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE OR REPLACE FUNCTION search_normalize(value TEXT) RETURNS TEXT AS $$
+    SELECT btrim(regexp_replace(
+        regexp_replace(lower(public.unaccent('public.unaccent', coalesce(value, ''))), '[''’`.\-]+', ' ', 'g'),
+        '\s+', ' ', 'g'
+    ))
+$$ LANGUAGE sql IMMUTABLE PARALLEL SAFE;
+
+-- locations
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS city_search TEXT;
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS county_search TEXT;
+
+CREATE OR REPLACE FUNCTION locations_search_columns() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.city_search := search_normalize(NEW.city);
+    NEW.county_search := search_normalize(NEW.county);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS locations_search_columns ON locations;
+CREATE TRIGGER locations_search_columns
+    BEFORE INSERT OR UPDATE OF city, county ON locations
+    FOR EACH ROW EXECUTE FUNCTION locations_search_columns();
+
+UPDATE locations SET city_search = search_normalize(city), county_search = search_normalize(county);
+
+CREATE INDEX IF NOT EXISTS idx_locations_city_search ON locations USING gin (city_search gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_locations_county_search ON locations USING gin (county_search gin_trgm_ops);
+
+-- cities reference table
+ALTER TABLE cities ADD COLUMN IF NOT EXISTS city_search TEXT;
+
+CREATE OR REPLACE FUNCTION cities_search_columns() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.city_search := search_normalize(NEW.city);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS cities_search_columns ON cities;
+CREATE TRIGGER cities_search_columns
+    BEFORE INSERT OR UPDATE OF city ON cities
+    FOR EACH ROW EXECUTE FUNCTION cities_search_columns();
+
+UPDATE cities SET city_search = search_normalize(city);
+
+CREATE INDEX IF NOT EXISTS idx_cities_city_search ON cities USING gin (city_search gin_trgm_ops);
+
+-- artists
+ALTER TABLE artists ADD COLUMN IF NOT EXISTS name_search TEXT;
+
+CREATE OR REPLACE FUNCTION artists_search_columns() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.name_search := search_normalize(NEW.name);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS artists_search_columns ON artists;
+CREATE TRIGGER artists_search_columns
+    BEFORE INSERT OR UPDATE OF name ON artists
+    FOR EACH ROW EXECUTE FUNCTION artists_search_columns();
+
+UPDATE artists SET name_search = search_normalize(name);
+
+CREATE INDEX IF NOT EXISTS idx_artists_name_search ON artists USING gin (name_search gin_trgm_ops);
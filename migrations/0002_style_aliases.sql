@@ -0,0 +1,13 @@
+-- Records style names that were merged into another style so older references
+-- (saved quiz answers, bookmarked filter URLs) keep resolving to the survivor.
+
+CREATE TABLE IF NOT EXISTS style_aliases (
+    id BIGSERIAL PRIMARY KEY,
+    alias TEXT NOT NULL,
+    style_id BIGINT NOT NULL REFERENCES styles(id) ON DELETE CASCADE,
+    merged_by BIGINT,
+    merged_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_style_aliases_alias ON style_aliases (LOWER(alias));
+CREATE INDEX IF NOT EXISTS idx_style_aliases_style_id ON style_aliases (style_id);
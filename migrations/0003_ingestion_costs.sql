@@ -0,0 +1,29 @@
+-- Per-run external API spend for the data-ingestion jobs.
+
+CREATE TABLE IF NOT EXISTS ingestion_runs (
+    run_id TEXT PRIMARY KEY,
+    action TEXT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    budget_usd DOUBLE PRECISION,
+    total_cost_usd DOUBLE PRECISION NOT NULL DEFAULT 0,
+    over_budget BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+-- One row per (run, source, city, state). source is 'apify', 'openai' or 'google_places'.
+CREATE TABLE IF NOT EXISTS ingestion_costs (
+    id BIGSERIAL PRIMARY KEY,
+    run_id TEXT NOT NULL REFERENCES ingestion_runs(run_id) ON DELETE CASCADE,
+    source TEXT NOT NULL,
+    city TEXT,
+    state TEXT,
+    api_calls BIGINT NOT NULL DEFAULT 0,
+    compute_units DOUBLE PRECISION NOT NULL DEFAULT 0,
+    prompt_tokens BIGINT NOT NULL DEFAULT 0,
+    completion_tokens BIGINT NOT NULL DEFAULT 0,
+    cost_usd DOUBLE PRECISION NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_ingestion_costs_created_at ON ingestion_costs (created_at);
+CREATE INDEX IF NOT EXISTS idx_ingestion_costs_source_city ON ingestion_costs (source, state, city);
@@ -0,0 +1,58 @@
+-- Invoices for completed bookings, per-artist billing details and the payments ledger.
+
+CREATE TABLE IF NOT EXISTS artist_billing_settings (
+    artist_id INTEGER PRIMARY KEY REFERENCES artists(id) ON DELETE CASCADE,
+    business_name TEXT,
+    business_address TEXT,
+    tax_id TEXT,
+    tax_label TEXT NOT NULL DEFAULT 'Sales tax',
+    tax_rate DOUBLE PRECISION NOT NULL DEFAULT 0,
+    tax_inclusive BOOLEAN NOT NULL DEFAULT FALSE,
+    currency TEXT NOT NULL DEFAULT 'USD',
+    invoice_prefix TEXT NOT NULL DEFAULT 'INV',
+    next_invoice_number INTEGER NOT NULL DEFAULT 1,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Business and tax details are copied onto the invoice so later settings
+-- changes never alter an issued invoice.
+CREATE TABLE IF NOT EXISTS invoices (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id),
+    booking_request_id INTEGER NOT NULL UNIQUE REFERENCES booking_requests(id),
+    invoice_number INTEGER NOT NULL,
+    invoice_code TEXT NOT NULL,
+    client_name TEXT NOT NULL,
+    client_email TEXT NOT NULL,
+    description TEXT NOT NULL,
+    business_name TEXT,
+    business_address TEXT,
+    tax_id TEXT,
+    tax_label TEXT NOT NULL,
+    tax_rate DOUBLE PRECISION NOT NULL,
+    subtotal DOUBLE PRECISION NOT NULL,
+    tax_amount DOUBLE PRECISION NOT NULL,
+    total DOUBLE PRECISION NOT NULL,
+    currency TEXT NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (artist_id, invoice_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoices_client_email ON invoices (LOWER(client_email));
+
+-- Money owed and received per booking. 'charge' entries come from invoices,
+-- 'payment' and 'refund' entries from payment processing.
+CREATE TABLE IF NOT EXISTS payment_ledger (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id),
+    booking_request_id INTEGER REFERENCES booking_requests(id),
+    invoice_id BIGINT REFERENCES invoices(id),
+    entry_type TEXT NOT NULL CHECK (entry_type IN ('charge', 'payment', 'refund')),
+    amount DOUBLE PRECISION NOT NULL,
+    currency TEXT NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_ledger_booking ON payment_ledger (booking_request_id);
+CREATE INDEX IF NOT EXISTS idx_payment_ledger_artist ON payment_ledger (artist_id, created_at);
@@ -0,0 +1,21 @@
+-- Resumable (tus-style) uploads for booking reference images and portfolio images.
+
+CREATE TABLE IF NOT EXISTS uploads (
+    id TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('reference', 'portfolio')),
+    filename TEXT,
+    content_type TEXT,
+    total_size BIGINT NOT NULL,
+    upload_offset BIGINT NOT NULL DEFAULT 0,
+    expected_sha256 TEXT,
+    sha256 TEXT,
+    storage_path TEXT,
+    status TEXT NOT NULL DEFAULT 'in_progress'
+        CHECK (status IN ('in_progress', 'complete', 'aborted', 'expired')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_uploads_status_updated ON uploads (status, updated_at);
+CREATE INDEX IF NOT EXISTS idx_uploads_user ON uploads (user_id);
@@ -0,0 +1,21 @@
+-- Referential problems found by the data-ingestion INTEGRITY_CHECK job.
+-- One open row per (check, entity); rows are resolved when the problem is
+-- repaired or no longer detected.
+
+CREATE TABLE IF NOT EXISTS data_quality_issues (
+    id BIGSERIAL PRIMARY KEY,
+    check_name TEXT NOT NULL,
+    entity_table TEXT NOT NULL,
+    entity_id BIGINT NOT NULL,
+    details TEXT,
+    repairable BOOLEAN NOT NULL DEFAULT FALSE,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMPTZ,
+    resolution TEXT CHECK (resolution IN ('repaired', 'cleared'))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_data_quality_issues_open
+    ON data_quality_issues (check_name, entity_table, entity_id)
+    WHERE resolved_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_data_quality_issues_check ON data_quality_issues (check_name, resolved_at);
@@ -0,0 +1,19 @@
+-- Admin-managed overrides that pin a shop or artist to a fixed position in
+-- map/search listings and artist matching. A rule with no city applies to the
+-- whole state; with neither city nor state it applies everywhere.
+
+CREATE TABLE IF NOT EXISTS pinning_rules (
+    id BIGSERIAL PRIMARY KEY,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('location', 'artist')),
+    entity_id BIGINT NOT NULL,
+    scope_city TEXT,
+    scope_state TEXT,
+    position INTEGER NOT NULL CHECK (position >= 1),
+    note TEXT,
+    created_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_pinning_rules_scope
+    ON pinning_rules (entity_type, LOWER(scope_state), LOWER(scope_city));
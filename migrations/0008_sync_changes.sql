@@ -0,0 +1,67 @@
+-- Change log behind the delta sync API. Triggers append one row per insert,
+-- update or delete of an artist's bookings, booking messages and availability;
+-- `seq` is the cursor clients pass back. Deletes are kept as tombstones.
+
+CREATE TABLE IF NOT EXISTS sync_changes (
+    seq BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('booking', 'message', 'availability')),
+    entity_id BIGINT NOT NULL,
+    op TEXT NOT NULL CHECK (op IN ('upsert', 'delete')),
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_changes_artist_seq ON sync_changes (artist_id, seq);
+
+CREATE OR REPLACE FUNCTION log_sync_change() RETURNS TRIGGER AS $$
+DECLARE
+    rec RECORD;
+    change_artist_id INTEGER;
+    change_entity_type TEXT;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        rec := OLD;
+    ELSE
+        rec := NEW;
+    END IF;
+
+    IF TG_TABLE_NAME = 'booking_requests' THEN
+        change_entity_type := 'booking';
+        change_artist_id := rec.artist_id;
+    ELSIF TG_TABLE_NAME = 'booking_messages' THEN
+        change_entity_type := 'message';
+        SELECT artist_id INTO change_artist_id
+        FROM booking_requests WHERE id = rec.booking_request_id;
+    ELSE
+        change_entity_type := 'availability';
+        change_artist_id := rec.artist_id;
+    END IF;
+
+    IF change_artist_id IS NOT NULL THEN
+        INSERT INTO sync_changes (artist_id, entity_type, entity_id, op)
+        VALUES (
+            change_artist_id,
+            change_entity_type,
+            rec.id,
+            CASE WHEN TG_OP = 'DELETE' THEN 'delete' ELSE 'upsert' END
+        );
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS booking_requests_sync_change ON booking_requests;
+CREATE TRIGGER booking_requests_sync_change
+    AFTER INSERT OR UPDATE OR DELETE ON booking_requests
+    FOR EACH ROW EXECUTE FUNCTION log_sync_change();
+
+DROP TRIGGER IF EXISTS booking_messages_sync_change ON booking_messages;
+CREATE TRIGGER booking_messages_sync_change
+    AFTER INSERT OR UPDATE OR DELETE ON booking_messages
+    FOR EACH ROW EXECUTE FUNCTION log_sync_change();
+
+DROP TRIGGER IF EXISTS artist_availability_sync_change ON artist_availability;
+CREATE TRIGGER artist_availability_sync_change
+    AFTER INSERT OR UPDATE OR DELETE ON artist_availability
+    FOR EACH ROW EXECUTE FUNCTION log_sync_change();
@@ -0,0 +1,15 @@
+-- Admin-curated example images per style for the matching quiz. Images are
+-- stored by Instagram short code so the quiz is unaffected if the source post
+-- is later removed from an artist's gallery.
+
+CREATE TABLE IF NOT EXISTS style_starter_images (
+    id BIGSERIAL PRIMARY KEY,
+    style_id BIGINT NOT NULL REFERENCES styles(id) ON DELETE CASCADE,
+    short_code TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    curated_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (style_id, short_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_style_starter_images_style ON style_starter_images (style_id, position);
@@ -0,0 +1,40 @@
+-- Shop owners claiming their location. A claim is checked automatically where
+-- possible (account email on the shop's website domain) and approved by an
+-- admin; approval sets locations.claimed_by, which unlocks editing the shop's
+-- description, hours, photos and artist roster.
+
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS claimed_by BIGINT;
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ;
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS description TEXT;
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS opening_hours TEXT;
+
+CREATE TABLE IF NOT EXISTS shop_claims (
+    id BIGSERIAL PRIMARY KEY,
+    location_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    method TEXT NOT NULL CHECK (method IN ('email_domain', 'google_business')),
+    evidence TEXT,
+    email_domain_match BOOLEAN NOT NULL DEFAULT FALSE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    review_note TEXT,
+    reviewed_by BIGINT,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One open claim per user and shop
+CREATE UNIQUE INDEX IF NOT EXISTS idx_shop_claims_pending
+    ON shop_claims (location_id, user_id)
+    WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_shop_claims_status ON shop_claims (status, created_at);
+
+CREATE TABLE IF NOT EXISTS location_photos (
+    id BIGSERIAL PRIMARY KEY,
+    location_id BIGINT NOT NULL,
+    upload_id TEXT NOT NULL REFERENCES uploads(id),
+    position INTEGER NOT NULL DEFAULT 0,
+    added_by BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_location_photos_location ON location_photos (location_id, position);
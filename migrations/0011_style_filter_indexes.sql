@@ -0,0 +1,9 @@
+-- Indexes behind AND/OR style filtering: style -> images for the filter
+-- subqueries, image -> styles for nice-to-have ranking, and lower-cased names
+-- for filters keyed by style name.
+
+CREATE INDEX IF NOT EXISTS idx_artists_images_styles_style_image
+    ON artists_images_styles (style_id, artists_images_id);
+CREATE INDEX IF NOT EXISTS idx_artists_images_styles_image_style
+    ON artists_images_styles (artists_images_id, style_id);
+CREATE INDEX IF NOT EXISTS idx_styles_lower_name ON styles (LOWER(name));
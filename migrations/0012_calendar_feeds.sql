@@ -0,0 +1,14 @@
+-- Subscribable ICS feeds for artists. Each feed has its own secret token so a
+-- leaked bookings link can be revoked without breaking the availability one.
+
+CREATE TABLE IF NOT EXISTS calendar_feeds (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('bookings', 'availability')),
+    token TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_accessed_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendar_feeds_artist ON calendar_feeds (artist_id) WHERE revoked_at IS NULL;
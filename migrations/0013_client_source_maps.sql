@@ -0,0 +1,14 @@
+-- Source maps for the client JS/WASM bundle, uploaded per release so client
+-- error stacks can be symbolicated. error_logs records the build a client
+-- error came from and the readable stack once resolved.
+
+CREATE TABLE IF NOT EXISTS client_source_maps (
+    build_id TEXT NOT NULL,
+    file_name TEXT NOT NULL, -- bundle file the map belongs to, e.g. 'web.js'
+    source_map TEXT NOT NULL,
+    uploaded_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (build_id, file_name)
+);
+
+ALTER TABLE error_logs ADD COLUMN IF NOT EXISTS build_id TEXT;
+ALTER TABLE error_logs ADD COLUMN IF NOT EXISTS symbolicated_stack TEXT;
@@ -0,0 +1,28 @@
+-- Reference images attached to booking requests: consent captured at upload
+-- time, and attachments purged once the booking has been closed for the
+-- retention period.
+
+ALTER TABLE uploads ADD COLUMN IF NOT EXISTS consent_given_at TIMESTAMPTZ;
+ALTER TABLE uploads ADD COLUMN IF NOT EXISTS booking_request_id INTEGER
+    REFERENCES booking_requests(id) ON DELETE SET NULL;
+
+ALTER TABLE uploads DROP CONSTRAINT IF EXISTS uploads_status_check;
+ALTER TABLE uploads ADD CONSTRAINT uploads_status_check
+    CHECK (status IN ('in_progress', 'complete', 'aborted', 'expired', 'purged'));
+
+CREATE INDEX IF NOT EXISTS idx_uploads_booking_request
+    ON uploads (booking_request_id) WHERE booking_request_id IS NOT NULL;
+
+-- One row per deleted attachment; kept after the file and upload are gone
+CREATE TABLE IF NOT EXISTS attachment_deletions (
+    id BIGSERIAL PRIMARY KEY,
+    upload_id TEXT NOT NULL,
+    booking_request_id INTEGER,
+    user_id BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    booking_status TEXT,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachment_deletions_booking
+    ON attachment_deletions (booking_request_id);
@@ -0,0 +1,6 @@
+-- 'all' feeds carry bookings and availability in one calendar and are served
+-- at /api/artist/:id/calendar.ics?token=...
+
+ALTER TABLE calendar_feeds DROP CONSTRAINT IF EXISTS calendar_feeds_kind_check;
+ALTER TABLE calendar_feeds ADD CONSTRAINT calendar_feeds_kind_check
+    CHECK (kind IN ('bookings', 'availability', 'all'));
@@ -0,0 +1,16 @@
+-- Incident notices posted by admins for the public status page (external API
+-- outages, maintenance windows, ...).
+
+CREATE TABLE IF NOT EXISTS status_incidents (
+    id BIGSERIAL PRIMARY KEY,
+    subsystem TEXT NOT NULL,
+    severity TEXT NOT NULL CHECK (severity IN ('maintenance', 'degraded', 'outage')),
+    title TEXT NOT NULL,
+    message TEXT,
+    created_by BIGINT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_status_incidents_open ON status_incidents (started_at) WHERE resolved_at IS NULL;
@@ -0,0 +1,22 @@
+-- Named boards a client sorts their favorited images into. An image can sit
+-- on several boards; removing the favorite takes it off every board.
+
+CREATE TABLE IF NOT EXISTS favorite_collections (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_favorite_collections_user_name
+    ON favorite_collections (user_id, LOWER(name));
+
+CREATE TABLE IF NOT EXISTS favorite_collection_items (
+    collection_id BIGINT NOT NULL REFERENCES favorite_collections(id) ON DELETE CASCADE,
+    artists_images_id BIGINT NOT NULL,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (collection_id, artists_images_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_favorite_collection_items_image
+    ON favorite_collection_items (artists_images_id);
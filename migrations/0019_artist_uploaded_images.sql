@@ -0,0 +1,19 @@
+-- Portfolio images artists upload directly, alongside the scraped Instagram
+-- posts. Files live in object storage (see web/src/storage.rs) under
+-- storage_key, with a smaller JPEG at thumbnail_key for grids.
+
+CREATE TABLE IF NOT EXISTS artist_uploaded_images (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id BIGINT NOT NULL,
+    storage_key TEXT NOT NULL,
+    thumbnail_key TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    caption TEXT,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_artist_uploaded_images_artist
+    ON artist_uploaded_images (artist_id, position);
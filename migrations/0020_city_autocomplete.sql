@@ -0,0 +1,12 @@
+-- City autocomplete over the cities reference table, including cities we have
+-- no shops for yet. Picking one of those queues a 'city_ingestion' job that
+-- data-ingestion's CITY_REQUESTS action works off.
+
+ALTER TABLE cities ADD COLUMN IF NOT EXISTS population INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_cities_population ON cities (population DESC NULLS LAST);
+
+-- At most one open request per city
+CREATE UNIQUE INDEX IF NOT EXISTS idx_jobs_city_ingestion_open
+    ON jobs ((payload->>'state'), (payload->>'city'))
+    WHERE kind = 'city_ingestion' AND status IN ('queued', 'running');
@@ -0,0 +1,20 @@
+-- When an artist first responded to a booking request: accepting or
+-- declining it, suggesting another time, or sending a message. Feeds the
+-- "usually responds within" badge and the dashboard's SLA reminders.
+
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS first_responded_at TIMESTAMPTZ;
+
+-- Earliest artist message, falling back to the last update of requests that
+-- were answered without one
+UPDATE booking_requests br
+SET first_responded_at = COALESCE(
+        (SELECT MIN(bm.created_at::timestamptz)
+         FROM booking_messages bm
+         WHERE bm.booking_request_id = br.id AND bm.sender_type = 'artist'),
+        CASE WHEN br.status <> 'pending' THEN br.updated_at::timestamptz END
+    )
+WHERE br.first_responded_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_booking_requests_artist_pending
+    ON booking_requests (artist_id)
+    WHERE first_responded_at IS NULL;
@@ -0,0 +1,19 @@
+-- Artist subscriptions are paid through Stripe Checkout. Each tier maps to a
+-- recurring Stripe price; subscription rows remember the Stripe customer and
+-- subscription so webhooks can keep their status in sync.
+
+ALTER TABLE subscription_tiers ADD COLUMN IF NOT EXISTS stripe_price_id TEXT;
+
+ALTER TABLE artist_subscriptions ADD COLUMN IF NOT EXISTS stripe_customer_id TEXT;
+ALTER TABLE artist_subscriptions ADD COLUMN IF NOT EXISTS stripe_subscription_id TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_artist_subscriptions_stripe_subscription
+    ON artist_subscriptions (stripe_subscription_id)
+    WHERE stripe_subscription_id IS NOT NULL;
+
+-- Stripe delivers webhooks at least once; events already handled are skipped
+CREATE TABLE IF NOT EXISTS stripe_webhook_events (
+    id TEXT PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Deposits artists can require when approving a booking. deposit_status is
+-- NULL when no deposit is asked for; otherwise 'required' until the client
+-- pays through Stripe, then 'paid'. Payments also land in payment_ledger.
+
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS deposit_amount DOUBLE PRECISION;
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS deposit_status TEXT
+    CHECK (deposit_status IN ('required', 'paid', 'waived', 'refunded'));
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS deposit_payment_intent_id TEXT;
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS deposit_paid_at TIMESTAMPTZ;
@@ -0,0 +1,37 @@
+-- Connected payment accounts artists onboard so marketplace payments can be
+-- paid out to them, and the payouts made to those accounts. Both are kept in
+-- sync from the payment provider's webhooks.
+
+CREATE TABLE IF NOT EXISTS artist_payment_accounts (
+    artist_id INTEGER PRIMARY KEY REFERENCES artists(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL DEFAULT 'stripe',
+    account_id TEXT NOT NULL UNIQUE,
+    country TEXT NOT NULL,
+    default_currency TEXT NOT NULL,
+    charges_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    payouts_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    details_submitted BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Information the provider still needs before payouts can be enabled
+    requirements_due TEXT[] NOT NULL DEFAULT '{}',
+    payout_interval TEXT NOT NULL DEFAULT 'daily'
+        CHECK (payout_interval IN ('manual', 'daily', 'weekly', 'monthly')),
+    -- Weekday for weekly payouts ('monday'...), day of month for monthly ('1'-'31')
+    payout_anchor TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS payouts (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    provider_payout_id TEXT NOT NULL UNIQUE,
+    amount DOUBLE PRECISION NOT NULL,
+    currency TEXT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('pending', 'in_transit', 'paid', 'failed', 'canceled')),
+    arrival_date DATE,
+    failure_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_payouts_artist ON payouts (artist_id, created_at DESC);
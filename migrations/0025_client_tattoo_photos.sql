@@ -0,0 +1,29 @@
+-- Clients' own tattoo photos ("my tattoos"), stored like portfolio images
+-- (see web/src/storage.rs). Photos are private unless the client shares
+-- them: is_public publishes one on its own page crediting the artist, and
+-- healed_consent_at records the client's consent to the artist showing it
+-- in their healed-work collection. The artist is only ever taken from a
+-- completed booking of the client's, never entered by hand.
+
+CREATE TABLE IF NOT EXISTS client_tattoo_photos (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    booking_id INTEGER REFERENCES booking_requests(id) ON DELETE SET NULL,
+    artist_id INTEGER REFERENCES artists(id) ON DELETE SET NULL,
+    storage_key TEXT NOT NULL,
+    thumbnail_key TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    caption TEXT,
+    healed BOOLEAN NOT NULL DEFAULT FALSE,
+    is_public BOOLEAN NOT NULL DEFAULT FALSE,
+    healed_consent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (healed_consent_at IS NULL OR (healed AND artist_id IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_client_tattoo_photos_user
+    ON client_tattoo_photos (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_client_tattoo_photos_healed_work
+    ON client_tattoo_photos (artist_id, created_at DESC) WHERE healed_consent_at IS NOT NULL;
@@ -0,0 +1,13 @@
+-- Thumbnails and captions of Instagram posts, saved by media ingestion. In
+-- Instagram privacy mode these are served from our own domain in place of
+-- the live embed, so no request reaches Instagram until the visitor consents.
+
+CREATE TABLE IF NOT EXISTS instagram_media (
+    short_code TEXT PRIMARY KEY,
+    caption TEXT,
+    -- Small JPEG, a few tens of KB
+    thumbnail BYTEA,
+    thumbnail_width INTEGER,
+    thumbnail_height INTEGER,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,28 @@
+-- Assistants working on an artist's account. The artist invites someone by
+-- email with a set of permission scopes; the invite link carries a random
+-- code stored here only as a SHA-256 hash. Accepting links the invitee's own
+-- user account, which then gets the scoped access alongside its own.
+-- Removing a member sets revoked_at rather than deleting the row, so the
+-- history of who had access is kept.
+
+CREATE TABLE IF NOT EXISTS artist_team_members (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    -- Set once the invitation is accepted
+    user_id BIGINT REFERENCES users(id) ON DELETE CASCADE,
+    permissions TEXT[] NOT NULL DEFAULT '{}'
+        CHECK (permissions <@ ARRAY['messages', 'calendar', 'bookings', 'settings']),
+    invite_code_hash TEXT NOT NULL UNIQUE,
+    invite_expires_at TIMESTAMPTZ NOT NULL,
+    invited_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    accepted_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_artist_team_members_artist
+    ON artist_team_members (artist_id, invited_at DESC);
+
+-- An assistant works for one artist at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_artist_team_members_active_user
+    ON artist_team_members (user_id) WHERE user_id IS NOT NULL AND revoked_at IS NULL;
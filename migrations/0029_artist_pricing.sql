@@ -0,0 +1,21 @@
+-- What artists charge. An artist sets an hourly rate and a minimum charge,
+-- and may quote a price range per style they work in. Match results, shop
+-- pages and map pins derive their price ranges from these rows; artists
+-- without any pricing show "Contact for quote" and are never filtered out
+-- by a client's budget.
+
+CREATE TABLE IF NOT EXISTS artist_pricing (
+    artist_id INTEGER PRIMARY KEY REFERENCES artists(id) ON DELETE CASCADE,
+    hourly_rate DOUBLE PRECISION CHECK (hourly_rate >= 0),
+    minimum_charge DOUBLE PRECISION CHECK (minimum_charge >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS artist_style_pricing (
+    artist_id INTEGER NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    style_id BIGINT NOT NULL REFERENCES styles(id) ON DELETE CASCADE,
+    min_price DOUBLE PRECISION CHECK (min_price >= 0),
+    max_price DOUBLE PRECISION CHECK (max_price >= 0),
+    PRIMARY KEY (artist_id, style_id),
+    CHECK (min_price IS NULL OR max_price IS NULL OR min_price <= max_price)
+);
@@ -0,0 +1,24 @@
+-- Retries for reddit_artists_pending rows that failed processing. Reasons
+-- like shop_not_found and artist_profile_not_found are often transient
+-- (an Apify timeout, an OpenAI error), so ACTION=REDDIT_RETRY_FAILED gives
+-- them further attempts, backing off exponentially between failures. Every
+-- attempt is logged so success rates can be compared across retries.
+
+ALTER TABLE reddit_artists_pending
+    ADD COLUMN IF NOT EXISTS retry_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS next_retry_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS reddit_pending_retries (
+    id BIGSERIAL PRIMARY KEY,
+    instagram_handle TEXT NOT NULL,
+    -- 1 for the first retry after the original failure
+    attempt INTEGER NOT NULL,
+    -- Why the record had failed before this attempt
+    failure_reason TEXT,
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'failed')),
+    outcome_reason TEXT,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reddit_pending_retries_attempt
+    ON reddit_pending_retries (attempt, failure_reason);
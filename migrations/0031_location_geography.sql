@@ -0,0 +1,21 @@
+-- PostGIS geography for locations, so radius searches use a GiST index and
+-- exact distances. PostGIS isn't installed everywhere we run Postgres: where
+-- the extension can't be created this migration does nothing and radius
+-- searches fall back to the haversine formula over lat/long (see db/geo.rs).
+
+DO $$
+BEGIN
+    CREATE EXTENSION IF NOT EXISTS postgis;
+
+    ALTER TABLE locations
+        ADD COLUMN IF NOT EXISTS geog geography(Point, 4326)
+        GENERATED ALWAYS AS (
+            ST_SetSRID(ST_MakePoint(long::float8, lat::float8), 4326)::geography
+        ) STORED;
+
+    CREATE INDEX IF NOT EXISTS idx_locations_geog ON locations USING GIST (geog);
+EXCEPTION
+    WHEN OTHERS THEN
+        RAISE NOTICE 'PostGIS unavailable (%), radius searches will use haversine', SQLERRM;
+END
+$$;
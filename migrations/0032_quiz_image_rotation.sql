@@ -0,0 +1,26 @@
+-- Exposure and pick logging for the matching quiz's style images. Sessions are
+-- split between the static curated set and per-style rotation pools so their
+-- effect on which styles (and so which artists) get picked can be compared.
+
+CREATE TABLE IF NOT EXISTS quiz_image_exposures (
+    id BIGSERIAL PRIMARY KEY,
+    session_key TEXT NOT NULL,
+    variant TEXT NOT NULL CHECK (variant IN ('static', 'rotation')),
+    style_id BIGINT NOT NULL REFERENCES styles(id) ON DELETE CASCADE,
+    artist_id BIGINT,
+    short_code TEXT NOT NULL,
+    shown_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_quiz_image_exposures_shown ON quiz_image_exposures (shown_at);
+CREATE INDEX IF NOT EXISTS idx_quiz_image_exposures_artist ON quiz_image_exposures (style_id, artist_id, shown_at);
+
+CREATE TABLE IF NOT EXISTS quiz_style_picks (
+    id BIGSERIAL PRIMARY KEY,
+    session_key TEXT NOT NULL,
+    variant TEXT NOT NULL CHECK (variant IN ('static', 'rotation')),
+    style_id BIGINT NOT NULL REFERENCES styles(id) ON DELETE CASCADE,
+    picked_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_quiz_style_picks_picked ON quiz_style_picks (picked_at);
@@ -0,0 +1,5 @@
+-- A short bio artists write about themselves. Part of the profile
+-- completeness score, alongside photos, styles, pricing, hours and the
+-- booking questionnaire.
+
+ALTER TABLE artists ADD COLUMN IF NOT EXISTS bio TEXT;
@@ -0,0 +1,21 @@
+-- Self-serve email and phone changes. The new address gets a one-time code
+-- (and, for email, a link carrying it); the account only changes once the
+-- code comes back. Codes are stored hashed like refresh tokens.
+
+CREATE TABLE IF NOT EXISTS contact_change_requests (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    channel TEXT NOT NULL CHECK (channel IN ('email', 'phone')),
+    new_value TEXT NOT NULL,
+    code_hash TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    confirmed_at TIMESTAMPTZ,
+    cancelled_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_contact_change_requests_user
+    ON contact_change_requests (user_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_users_email_lower ON users (LOWER(email));
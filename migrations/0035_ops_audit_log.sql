@@ -0,0 +1,16 @@
+-- Account actions taken by operators through the ops CLI (src/bin/ops.rs),
+-- so routine fixes leave a trail instead of disappearing into psql history.
+
+CREATE TABLE IF NOT EXISTS ops_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    operator TEXT NOT NULL,
+    action TEXT NOT NULL,
+    target_user_id BIGINT,
+    target_email TEXT,
+    reason TEXT,
+    details TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_ops_audit_log_created ON ops_audit_log (created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_ops_audit_log_target ON ops_audit_log (target_user_id);
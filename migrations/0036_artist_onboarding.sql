@@ -0,0 +1,12 @@
+-- Artist onboarding. Signup creates the artist with a placeholder location
+-- and availability_status 'pending_onboarding'; the wizard records when the
+-- artist picked their real shop, and onboarding ends by setting the status
+-- to 'active'. Artists that never went through signup already have real
+-- shops.
+
+ALTER TABLE artists ADD COLUMN IF NOT EXISTS location_confirmed_at TIMESTAMPTZ;
+ALTER TABLE artists ADD COLUMN IF NOT EXISTS onboarded_at TIMESTAMPTZ;
+
+UPDATE artists SET location_confirmed_at = CURRENT_TIMESTAMP
+WHERE location_confirmed_at IS NULL
+  AND availability_status IS DISTINCT FROM 'pending_onboarding';
@@ -0,0 +1,71 @@
+-- What happened to a booking request and when, for the timeline on the
+-- booking details view: created, viewed by the artist, a suggested time,
+-- messages, accepted or declined, deposit paid. `details` holds the typed
+-- event (see BookingEventKind) as JSON.
+
+CREATE TABLE IF NOT EXISTS booking_events (
+    id BIGSERIAL PRIMARY KEY,
+    booking_request_id INTEGER NOT NULL,
+    event_type TEXT NOT NULL CHECK (event_type IN (
+        'created', 'viewed', 'time_suggested', 'message', 'accepted', 'declined', 'deposit_paid'
+    )),
+    actor TEXT NOT NULL CHECK (actor IN ('client', 'artist', 'system')),
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_events_booking
+    ON booking_events (booking_request_id, created_at, id);
+
+-- The artist first opening a request is the only view worth showing
+CREATE UNIQUE INDEX IF NOT EXISTS idx_booking_events_viewed_once
+    ON booking_events (booking_request_id)
+    WHERE event_type = 'viewed';
+
+-- Backfill what existing requests can tell us. Views and suggestions weren't
+-- recorded before now.
+INSERT INTO booking_events (booking_request_id, event_type, actor, details, created_at)
+SELECT br.id, 'created', 'client', '{"type": "created"}',
+       COALESCE(br.created_at::timestamptz, CURRENT_TIMESTAMP)
+FROM booking_requests br
+WHERE NOT EXISTS (SELECT 1 FROM booking_events be WHERE be.booking_request_id = br.id);
+
+INSERT INTO booking_events (booking_request_id, event_type, actor, details, created_at)
+SELECT bm.booking_request_id, 'message',
+       CASE WHEN bm.sender_type IN ('client', 'artist') THEN bm.sender_type ELSE 'system' END,
+       jsonb_build_object('type', 'message', 'sender_type', bm.sender_type, 'message', bm.message),
+       COALESCE(bm.created_at::timestamptz, CURRENT_TIMESTAMP)
+FROM booking_messages bm
+WHERE NOT EXISTS (
+    SELECT 1 FROM booking_events be
+    WHERE be.booking_request_id = bm.booking_request_id AND be.event_type = 'message'
+);
+
+INSERT INTO booking_events (booking_request_id, event_type, actor, details, created_at)
+SELECT br.id, br.status, 'artist',
+       CASE WHEN br.status = 'accepted'
+            THEN jsonb_build_object('type', 'accepted', 'deposit_amount', br.deposit_amount)
+            ELSE jsonb_build_object('type', 'declined', 'reason', br.decline_reason)
+       END,
+       COALESCE(br.first_responded_at, br.updated_at::timestamptz, CURRENT_TIMESTAMP)
+FROM (
+    SELECT id, CASE status WHEN 'approved' THEN 'accepted' ELSE status END as status,
+           deposit_amount, decline_reason, first_responded_at, updated_at
+    FROM booking_requests
+    WHERE status IN ('approved', 'declined')
+) br
+WHERE NOT EXISTS (
+    SELECT 1 FROM booking_events be
+    WHERE be.booking_request_id = br.id AND be.event_type IN ('accepted', 'declined')
+);
+
+INSERT INTO booking_events (booking_request_id, event_type, actor, details, created_at)
+SELECT br.id, 'deposit_paid', 'client',
+       jsonb_build_object('type', 'deposit_paid', 'amount', br.deposit_amount),
+       br.deposit_paid_at
+FROM booking_requests br
+WHERE br.deposit_paid_at IS NOT NULL
+  AND NOT EXISTS (
+      SELECT 1 FROM booking_events be
+      WHERE be.booking_request_id = br.id AND be.event_type = 'deposit_paid'
+  );
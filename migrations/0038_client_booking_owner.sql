@@ -0,0 +1,19 @@
+-- Ties booking requests to the signed-in client who made them, so the
+-- client dashboard lists a user's requests by account rather than by
+-- matching the typed-in email at read time. Requests made while signed out
+-- stay unlinked.
+
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS client_user_id BIGINT;
+
+-- Existing requests go to the account with the same email, the same match
+-- deposits and invoices already rely on
+UPDATE booking_requests br
+SET client_user_id = u.id
+FROM users u
+WHERE br.client_user_id IS NULL
+  AND LOWER(u.email) = LOWER(br.client_email)
+  AND u.role::text = 'client';
+
+CREATE INDEX IF NOT EXISTS idx_booking_requests_client_user
+    ON booking_requests (client_user_id, requested_date)
+    WHERE client_user_id IS NOT NULL;
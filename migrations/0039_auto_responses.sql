@@ -0,0 +1,31 @@
+-- Automatic first replies to new booking requests. An artist writes a
+-- default template and, optionally, one per booking type; the reply goes out
+-- `delay_minutes` after the request arrives unless the artist has responded
+-- by then.
+
+-- What the client is asking for, so replies (and artists) can tell a
+-- cover-up from a flash piece
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS booking_type TEXT NOT NULL DEFAULT 'custom'
+    CHECK (booking_type IN ('custom', 'flash', 'cover_up', 'touch_up', 'consultation'));
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS auto_response_due_at TIMESTAMPTZ;
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS auto_response_sent_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_booking_requests_auto_response_due
+    ON booking_requests (auto_response_due_at)
+    WHERE auto_response_due_at IS NOT NULL AND auto_response_sent_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS artist_auto_response_settings (
+    artist_id INTEGER PRIMARY KEY,
+    is_enabled BOOLEAN NOT NULL DEFAULT false,
+    delay_minutes INTEGER NOT NULL DEFAULT 0 CHECK (delay_minutes >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- booking_type is 'default' or one of booking_requests.booking_type
+CREATE TABLE IF NOT EXISTS artist_auto_responses (
+    artist_id INTEGER NOT NULL,
+    booking_type TEXT NOT NULL,
+    message TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (artist_id, booking_type)
+);
@@ -0,0 +1,38 @@
+-- Every change to a booking request's status, who made it and when.
+-- Transitions are checked in code (BookingStatus::next in shared-types);
+-- this is the record of the ones that happened.
+
+CREATE TABLE IF NOT EXISTS booking_status_history (
+    id BIGSERIAL PRIMARY KEY,
+    booking_request_id INTEGER NOT NULL,
+    -- NULL for the request being created
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    actor TEXT NOT NULL CHECK (actor IN ('client', 'artist', 'system')),
+    -- The signed-in user behind the change, if any
+    changed_by BIGINT,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_status_history_booking
+    ON booking_status_history (booking_request_id, created_at, id);
+
+-- Existing requests start with their creation and, if they've moved on, a
+-- single step to where they are now
+INSERT INTO booking_status_history (booking_request_id, from_status, to_status, actor, created_at)
+SELECT br.id, NULL, 'pending', 'client', COALESCE(br.created_at::timestamptz, CURRENT_TIMESTAMP)
+FROM booking_requests br
+WHERE NOT EXISTS (
+    SELECT 1 FROM booking_status_history h WHERE h.booking_request_id = br.id
+);
+
+INSERT INTO booking_status_history (booking_request_id, from_status, to_status, actor, note, created_at)
+SELECT br.id, 'pending', br.status, 'artist', br.decline_reason,
+       COALESCE(br.first_responded_at, br.updated_at::timestamptz, CURRENT_TIMESTAMP)
+FROM booking_requests br
+WHERE br.status <> 'pending'
+  AND NOT EXISTS (
+      SELECT 1 FROM booking_status_history h
+      WHERE h.booking_request_id = br.id AND h.from_status IS NOT NULL
+  );
@@ -0,0 +1,86 @@
+-- Ties each location to its canonical record in the cities reference table,
+-- so misspelled, differently accented or numeric `city` values scraped from
+-- Google and Instagram still roll up under one city. Queries that list or
+-- filter by city join through `locations.city_id`; the scraped `city` text is
+-- left as it was.
+
+-- Keyed even if the table was loaded without one
+ALTER TABLE cities ADD COLUMN IF NOT EXISTS id SERIAL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_cities_id ON cities (id);
+CREATE INDEX IF NOT EXISTS idx_cities_state_city_search ON cities (state_name, city_search);
+
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS city_id INTEGER REFERENCES cities (id);
+-- How the match was made: 'exact', 'fuzzy' or 'nearest'. NULL when nothing
+-- matched.
+ALTER TABLE locations ADD COLUMN IF NOT EXISTS city_match TEXT
+    CHECK (city_match IN ('exact', 'fuzzy', 'nearest'));
+
+CREATE INDEX IF NOT EXISTS idx_locations_city_id ON locations (city_id);
+
+-- Most similar name a fuzzy match accepts
+CREATE OR REPLACE FUNCTION city_match_threshold() RETURNS REAL AS $$
+    SELECT 0.6::real
+$$ LANGUAGE sql IMMUTABLE PARALLEL SAFE;
+
+-- The canonical city for a location, tried in order:
+--   exact    same normalized name in the same state
+--   fuzzy    closest name in the state by trigram similarity
+--   nearest  the closest city within 15 miles, for names that are numbers,
+--            blank or nothing like a real city
+-- Ties go to the larger city. Returns no row when nothing fits.
+CREATE OR REPLACE FUNCTION match_city(
+    loc_city TEXT, loc_state TEXT, loc_lat DOUBLE PRECISION, loc_long DOUBLE PRECISION
+)
+RETURNS TABLE (city_id INTEGER, method TEXT) AS $$
+    WITH candidates AS (
+        SELECT c.id, 'exact' as method, 0 as rank, 1.0::real as score, c.population
+        FROM cities c
+        WHERE c.state_name = loc_state AND c.city_search = search_normalize(loc_city)
+
+        UNION ALL
+
+        SELECT c.id, 'fuzzy', 1, similarity(c.city_search, search_normalize(loc_city)), c.population
+        FROM cities c
+        WHERE c.state_name = loc_state
+          AND search_normalize(loc_city) ~ '[a-z]'
+          AND similarity(c.city_search, search_normalize(loc_city)) >= city_match_threshold()
+
+        UNION ALL
+
+        SELECT c.id, 'nearest', 2,
+               -(((c.latitude - loc_lat) * 69.0) ^ 2
+                 + ((c.longitude - loc_long) * 69.0 * cos(radians(loc_lat))) ^ 2)::real,
+               c.population
+        FROM cities c
+        WHERE c.state_name = loc_state
+          AND loc_lat IS NOT NULL AND loc_long IS NOT NULL
+          AND c.latitude BETWEEN loc_lat - 15.0 / 69.0 AND loc_lat + 15.0 / 69.0
+          AND ((c.latitude - loc_lat) * 69.0) ^ 2
+              + ((c.longitude - loc_long) * 69.0 * cos(radians(loc_lat))) ^ 2 <= 15.0 ^ 2
+    )
+    SELECT id, method
+    FROM candidates
+    ORDER BY rank, score DESC, population DESC NULLS LAST
+    LIMIT 1
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION locations_city_id() RETURNS TRIGGER AS $$
+DECLARE
+    matched RECORD;
+BEGIN
+    SELECT * INTO matched FROM match_city(NEW.city, NEW.state, NEW.lat, NEW.long);
+    NEW.city_id := matched.city_id;
+    NEW.city_match := matched.method;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS locations_city_id ON locations;
+CREATE TRIGGER locations_city_id
+    BEFORE INSERT OR UPDATE OF city, state, lat, long ON locations
+    FOR EACH ROW EXECUTE FUNCTION locations_city_id();
+
+UPDATE locations l
+SET (city_id, city_match) = (
+    SELECT m.city_id, m.method FROM match_city(l.city, l.state, l.lat, l.long) m
+);
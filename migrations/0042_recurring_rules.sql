@@ -0,0 +1,153 @@
+-- One home for recurring availability. It used to be split between weekly
+-- rows in artist_availability flagged is_recurring, and a recurring_rules
+-- table that create_recurring_rule made on first use, whose free-text
+-- `pattern` the calendar and the booking checks each read their own way.
+-- recurring_rules now describes a rule in columns the availability crate
+-- evaluates (availability::Recurrence):
+--   weekly   `weekdays`, every `interval_weeks` weeks from `starts_on`
+--   monthly  `weekdays[1]` in week `week_of_month` (1-5, -1 for the last)
+--   yearly   `annual_dates` as 'MM-DD'
+-- Any rule can be limited to `starts_on`..`ends_on` and skip `except_dates`.
+
+CREATE TABLE IF NOT EXISTS recurring_rules (
+    id SERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    rule_type TEXT NOT NULL,
+    pattern TEXT NOT NULL,
+    action TEXT NOT NULL,
+    start_time TEXT,
+    end_time TEXT,
+    active BOOLEAN DEFAULT true,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS frequency TEXT;
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS weekdays INTEGER[] NOT NULL DEFAULT '{}';
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS interval_weeks INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS week_of_month INTEGER;
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS annual_dates TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS starts_on DATE;
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS ends_on DATE;
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS except_dates DATE[] NOT NULL DEFAULT '{}';
+ALTER TABLE recurring_rules ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
+
+-- Old rules stored what they matched as text: weekday numbers as JSON
+-- ("[0,6]") or weekday names, annual dates as "December 25, 01/01", and
+-- monthly rules as "1st Monday" or "last Friday". Patterns that can't be read
+-- ("15th of month") are kept switched off with no days, for the artist to
+-- redo or delete.
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'recurring_rules' AND column_name = 'pattern'
+    ) THEN
+        UPDATE recurring_rules
+        SET frequency = CASE rule_type
+                WHEN 'dates' THEN 'yearly'
+                WHEN 'monthly' THEN 'monthly'
+                ELSE 'weekly'
+            END,
+            weekdays = CASE
+                WHEN pattern ~ '^\s*\[' THEN ARRAY(
+                    SELECT DISTINCT d::int
+                    FROM jsonb_array_elements_text(pattern::jsonb) d
+                    WHERE d ~ '^[0-6]$'
+                    ORDER BY 1
+                )
+                ELSE ARRAY(
+                    SELECT (n.i - 1)::int
+                    FROM unnest(ARRAY['sunday', 'monday', 'tuesday', 'wednesday',
+                                      'thursday', 'friday', 'saturday'])
+                         WITH ORDINALITY AS n(day, i)
+                    WHERE lower(pattern) LIKE '%' || n.day || '%'
+                    ORDER BY n.i
+                )
+            END,
+            week_of_month = CASE
+                WHEN rule_type <> 'monthly' THEN NULL
+                WHEN lower(pattern) LIKE '%last%' THEN -1
+                ELSE substring(pattern FROM '(?:^|[^0-9])([1-5])(?:st|nd|rd|th)')::int
+            END,
+            annual_dates = CASE
+                WHEN rule_type <> 'dates' THEN '{}'
+                ELSE ARRAY(
+                    SELECT lpad(m::text, 2, '0') || '-' || lpad(d::text, 2, '0')
+                    FROM (
+                        SELECT CASE
+                                   WHEN part ~ '^\d{1,2}/\d{1,2}$'
+                                       THEN split_part(part, '/', 1)::int
+                                   ELSE array_position(
+                                       ARRAY['january', 'february', 'march', 'april', 'may', 'june',
+                                             'july', 'august', 'september', 'october', 'november',
+                                             'december'],
+                                       lower(split_part(part, ' ', 1)))
+                               END AS m,
+                               CASE
+                                   WHEN part ~ '^\d{1,2}/\d{1,2}$'
+                                       THEN split_part(part, '/', 2)::int
+                                   ELSE substring(part FROM '\s(\d{1,2})$')::int
+                               END AS d
+                        FROM (
+                            SELECT btrim(regexp_replace(part, '\s+', ' ', 'g')) AS part
+                            FROM unnest(string_to_array(pattern, ',')) part
+                        ) parts
+                    ) dates
+                    WHERE m BETWEEN 1 AND 12 AND d BETWEEN 1 AND 31
+                )
+            END
+        WHERE frequency IS NULL;
+
+        UPDATE recurring_rules
+        SET active = false
+        WHERE (frequency = 'weekly' AND weekdays = '{}')
+           OR (frequency = 'monthly' AND (weekdays = '{}' OR week_of_month IS NULL))
+           OR (frequency = 'yearly' AND annual_dates = '{}');
+
+        ALTER TABLE recurring_rules DROP COLUMN rule_type;
+        ALTER TABLE recurring_rules DROP COLUMN pattern;
+    END IF;
+END
+$$;
+
+-- Blank times meant all day; a lone start or end time never did anything
+UPDATE recurring_rules
+SET start_time = NULL, end_time = NULL
+WHERE COALESCE(start_time, '') = '' OR COALESCE(end_time, '') = '';
+
+UPDATE recurring_rules SET active = true WHERE active IS NULL;
+UPDATE recurring_rules SET action = 'blocked' WHERE action NOT IN ('available', 'blocked');
+
+-- Weekly rows from artist_availability become weekly rules. The column stays
+-- for older offline clients, but nothing writes recurring rows any more.
+INSERT INTO recurring_rules (artist_id, name, frequency, weekdays, action, start_time, end_time, active)
+SELECT av.artist_id,
+       (ARRAY['Sunday', 'Monday', 'Tuesday', 'Wednesday', 'Thursday', 'Friday', 'Saturday'])
+           [av.day_of_week + 1]
+           || CASE WHEN av.is_available THEN ' Available' ELSE ' Blocked' END,
+       'weekly',
+       ARRAY[av.day_of_week],
+       CASE WHEN av.is_available THEN 'available' ELSE 'blocked' END,
+       NULLIF(av.start_time, ''),
+       NULLIF(av.end_time, ''),
+       true
+FROM artist_availability av
+WHERE av.is_recurring = true AND av.day_of_week BETWEEN 0 AND 6;
+
+DELETE FROM artist_availability WHERE is_recurring = true;
+
+ALTER TABLE recurring_rules ALTER COLUMN frequency SET NOT NULL;
+ALTER TABLE recurring_rules ALTER COLUMN active SET NOT NULL;
+
+ALTER TABLE recurring_rules DROP CONSTRAINT IF EXISTS recurring_rules_shape;
+ALTER TABLE recurring_rules ADD CONSTRAINT recurring_rules_shape CHECK (
+    frequency IN ('weekly', 'monthly', 'yearly')
+    AND action IN ('available', 'blocked')
+    AND weekdays <@ ARRAY[0, 1, 2, 3, 4, 5, 6]
+    AND interval_weeks BETWEEN 1 AND 52
+    AND (week_of_month IS NULL OR week_of_month BETWEEN 1 AND 5 OR week_of_month = -1)
+    AND (ends_on IS NULL OR starts_on IS NULL OR ends_on >= starts_on)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recurring_rules_artist ON recurring_rules (artist_id, id);
@@ -0,0 +1,12 @@
+-- Dashboard hints a user has dismissed. The hints themselves are worked out
+-- from the artist's data on every load (utils::hints); only dismissals are
+-- stored. They're per user and per artist account, so an assistant hiding a
+-- hint doesn't hide it from the artist.
+
+CREATE TABLE IF NOT EXISTS dismissed_hints (
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    artist_id INTEGER NOT NULL,
+    hint_key TEXT NOT NULL,
+    dismissed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, artist_id, hint_key)
+);
@@ -0,0 +1,9 @@
+-- Finished uploads are now decoded and re-encoded before they're stored
+-- (web/src/image_processing.rs). Files that turn out not to be a readable
+-- image end as 'rejected', with the reason the client was given.
+
+ALTER TABLE uploads ADD COLUMN IF NOT EXISTS rejection_reason TEXT;
+
+ALTER TABLE uploads DROP CONSTRAINT IF EXISTS uploads_status_check;
+ALTER TABLE uploads ADD CONSTRAINT uploads_status_check
+    CHECK (status IN ('in_progress', 'complete', 'aborted', 'expired', 'purged', 'rejected'));
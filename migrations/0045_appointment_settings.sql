@@ -0,0 +1,11 @@
+-- How an artist's business hours are divided into bookable slots, set next to
+-- the hours on the settings page. Artists without a row get hour-long
+-- sessions starting on the hour with no buffer (availability::SlotSettings).
+
+CREATE TABLE IF NOT EXISTS appointment_settings (
+    artist_id INTEGER PRIMARY KEY,
+    session_minutes INTEGER NOT NULL DEFAULT 60 CHECK (session_minutes BETWEEN 15 AND 720),
+    slot_minutes INTEGER NOT NULL DEFAULT 60 CHECK (slot_minutes BETWEEN 5 AND 240),
+    buffer_minutes INTEGER NOT NULL DEFAULT 0 CHECK (buffer_minutes BETWEEN 0 AND 240),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,26 @@
+-- Exports too large to build during a request (an artist's bookings, admin
+-- data dumps) run as jobs: requested, picked up by the worker in
+-- web/src/exports.rs, polled for progress, then downloaded through a signed
+-- URL. Finished files are kept until `expires_at`, when the cleanup job
+-- deletes them and marks the job expired.
+
+CREATE TABLE IF NOT EXISTS export_jobs (
+    id TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- The artist account exported, for artist exports
+    artist_id INTEGER,
+    kind TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued'
+        CHECK (status IN ('queued', 'running', 'complete', 'failed', 'expired')),
+    total_rows BIGINT,
+    rows_written BIGINT NOT NULL DEFAULT 0,
+    storage_path TEXT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_export_jobs_user ON export_jobs (user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_export_jobs_status ON export_jobs (status, created_at);
@@ -0,0 +1,27 @@
+-- Short links (/s/{code}) for sharing artist profiles, shop pages and
+-- searches where a full URL is too long, like SMS. Links are canonical:
+-- each target has one code, however many times it's shared. Every visit is
+-- recorded with its referrer.
+
+CREATE TABLE IF NOT EXISTS short_links (
+    code TEXT PRIMARY KEY,
+    target_type TEXT NOT NULL CHECK (target_type IN ('artist', 'shop', 'search')),
+    -- The artist or shop id, or the search's path and query string
+    target TEXT NOT NULL,
+    created_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    click_count BIGINT NOT NULL DEFAULT 0,
+    last_clicked_at TIMESTAMPTZ,
+    UNIQUE (target_type, target)
+);
+
+CREATE TABLE IF NOT EXISTS short_link_clicks (
+    id BIGSERIAL PRIMARY KEY,
+    code TEXT NOT NULL REFERENCES short_links(code) ON DELETE CASCADE,
+    referrer TEXT,
+    -- The referrer's host, for grouping ("instagram.com", "t.co", ...)
+    referrer_host TEXT,
+    clicked_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_short_link_clicks_code ON short_link_clicks (code, clicked_at);
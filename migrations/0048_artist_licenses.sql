@@ -0,0 +1,38 @@
+-- Artists' tattoo licenses, one per issuing state. A new or edited license
+-- is pending until an admin checks it against its document, then verified
+-- or rejected. Verified, unexpired licenses are shown on the artist's
+-- profile when their state is in `license_display_states`. Expiry
+-- reminders go out from the hourly job in web/src/licensing.rs;
+-- `reminder_days_sent` is the last reminder sent (days before expiry), so
+-- each one goes out once.
+
+CREATE TABLE IF NOT EXISTS artist_licenses (
+    id SERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    license_number TEXT NOT NULL,
+    issuing_state TEXT NOT NULL,
+    expires_on DATE,
+    -- Photo or scan of the license, in object storage
+    document_key TEXT,
+    verification_status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (verification_status IN ('pending', 'verified', 'rejected')),
+    rejection_reason TEXT,
+    reviewed_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    reminder_days_sent INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (artist_id, issuing_state)
+);
+
+CREATE INDEX IF NOT EXISTS idx_artist_licenses_status
+    ON artist_licenses (verification_status, updated_at);
+CREATE INDEX IF NOT EXISTS idx_artist_licenses_expiry
+    ON artist_licenses (expires_on) WHERE expires_on IS NOT NULL;
+
+-- States whose rules require artists to show their license to clients.
+-- Kept by admins, since the rules change and differ between states.
+CREATE TABLE IF NOT EXISTS license_display_states (
+    state TEXT PRIMARY KEY,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
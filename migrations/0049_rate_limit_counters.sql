@@ -0,0 +1,18 @@
+-- Per-email counters behind rate limiting (web/src/rate_limit.rs): booking
+-- requests sent from one email address, and failed sign-ins that lock an
+-- email out. Per-IP limits are kept in memory by each instance. A counter
+-- covers the window starting at `window_started_at`; the next hit after the
+-- window ends starts a new one. Expired counters are deleted hourly.
+
+CREATE TABLE IF NOT EXISTS rate_limit_counters (
+    -- What's counted, e.g. 'booking_email' or 'login_failure'
+    scope TEXT NOT NULL,
+    -- Lower-cased email address
+    key TEXT NOT NULL,
+    hits INTEGER NOT NULL DEFAULT 1,
+    window_started_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (scope, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_counters_window
+    ON rate_limit_counters (window_started_at);
@@ -0,0 +1,15 @@
+-- Circuit breaker states reported by ingestion runs, one row per breaker,
+-- overwritten by the latest run that used it. The web server serves its own
+-- breakers from memory and these alongside them on /api/metrics.
+
+CREATE TABLE IF NOT EXISTS circuit_breaker_states (
+    service TEXT NOT NULL,
+    name TEXT NOT NULL,
+    state TEXT NOT NULL CHECK (state IN ('closed', 'open', 'half_open')),
+    failure_rate DOUBLE PRECISION NOT NULL DEFAULT 0,
+    calls BIGINT NOT NULL DEFAULT 0,
+    rejected_calls BIGINT NOT NULL DEFAULT 0,
+    times_opened BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (service, name)
+);
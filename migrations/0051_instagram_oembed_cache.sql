@@ -0,0 +1,16 @@
+-- Instagram oEmbed markup, cached so galleries don't wait on Instagram for
+-- every post on every page view. Entries older than the cache TTL are still
+-- served while a refresh runs in the background; an hourly job refreshes
+-- stale entries that are still being viewed and deletes ones that aren't
+-- (web/src/server_instagram.rs).
+
+CREATE TABLE IF NOT EXISTS instagram_oembed_cache (
+    short_code TEXT PRIMARY KEY,
+    html TEXT NOT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- Updated at most hourly, to keep page views read-only
+    last_served_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_instagram_oembed_cache_fetched
+    ON instagram_oembed_cache (fetched_at);
@@ -0,0 +1,14 @@
+-- Gallery images of Instagram posts, stored as WebP at a few widths under
+-- instagram/<short_code>/<width>.webp (web/src/instagram_images.rs) so
+-- galleries can show plain images instead of an embed per post. A row with
+-- no widths records a post whose image couldn't be found, retried after a
+-- day.
+
+CREATE TABLE IF NOT EXISTS instagram_images (
+    short_code TEXT PRIMARY KEY,
+    widths INTEGER[] NOT NULL DEFAULT '{}',
+    -- Where the image came from: 'instagram', or 'saved_thumbnail' for the
+    -- thumbnail media ingestion saved
+    source TEXT CHECK (source IN ('instagram', 'saved_thumbnail')),
+    checked_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
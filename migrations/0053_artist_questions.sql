@@ -0,0 +1,31 @@
+-- Public questions on artist profiles. Anyone can ask; a question that
+-- looks like spam is held for admins, anything else goes straight to the
+-- artist. Answered, approved questions are shown on the profile.
+--
+-- `status`: 'approved' (the artist sees it), 'held' (in the admin
+-- moderation queue, also where artists' spam reports land) or 'rejected'
+-- (removed as spam; kept so repeat askers can be spotted).
+
+CREATE TABLE IF NOT EXISTS artist_questions (
+    id SERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    asker_user_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    asker_name TEXT NOT NULL,
+    -- Where the answer notification goes; never shown
+    asker_email TEXT,
+    question TEXT NOT NULL,
+    answer TEXT,
+    answered_at TIMESTAMPTZ,
+    status TEXT NOT NULL DEFAULT 'approved'
+        CHECK (status IN ('approved', 'held', 'rejected')),
+    -- Why a held question was held
+    flag_reason TEXT,
+    moderated_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    moderated_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_artist_questions_artist
+    ON artist_questions (artist_id, created_at DESC) WHERE status = 'approved';
+CREATE INDEX IF NOT EXISTS idx_artist_questions_held
+    ON artist_questions (created_at) WHERE status = 'held';
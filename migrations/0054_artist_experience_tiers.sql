@@ -0,0 +1,14 @@
+-- Experience tier on artists, so shops can list apprentices and junior
+-- artists at lower price points. NULL means the artist hasn't said.
+--
+-- `experience_tier`: 'apprentice', 'junior' or 'senior'. Set by the artist
+-- in their settings or by the owner of their shop; used for the discovery
+-- filter, profile badges and to surface lower-priced artists to clients
+-- on a tight budget in matching.
+
+ALTER TABLE artists
+    ADD COLUMN IF NOT EXISTS experience_tier TEXT
+        CHECK (experience_tier IN ('apprentice', 'junior', 'senior'));
+
+CREATE INDEX IF NOT EXISTS idx_artists_experience_tier
+    ON artists (experience_tier) WHERE experience_tier IS NOT NULL;
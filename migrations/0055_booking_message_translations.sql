@@ -0,0 +1,17 @@
+-- Machine translation for booking messages, for clients who write in a
+-- language the artist doesn't. Off unless the artist turns it on.
+--
+-- `message` stays exactly what the sender wrote. `language` is the language
+-- it was detected as (ISO 639-1, NULL when translation was off or the
+-- provider couldn't tell); `translated_message` is the text in
+-- `translated_language` for the other side of the thread. Client messages
+-- are translated to English for the artist, and artist replies back into
+-- the language the client last wrote in.
+
+ALTER TABLE artists
+    ADD COLUMN IF NOT EXISTS auto_translate_messages BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE booking_messages
+    ADD COLUMN IF NOT EXISTS language TEXT,
+    ADD COLUMN IF NOT EXISTS translated_message TEXT,
+    ADD COLUMN IF NOT EXISTS translated_language TEXT;
@@ -0,0 +1,14 @@
+-- Reddit posts the scraper has already run through OpenAI extraction, so a
+-- rerun after a failure part way through a city (or a later rescrape that
+-- sees the same posts) doesn't pay for them again or add duplicate
+-- reddit_artists_pending rows. Progress through a city's phases is kept as
+-- a `reddit_city` row in `jobs`.
+
+CREATE TABLE IF NOT EXISTS reddit_processed_posts (
+    post_url TEXT PRIMARY KEY,
+    city TEXT NOT NULL,
+    state TEXT NOT NULL,
+    -- reddit_artists_pending rows the post produced
+    pending_rows INTEGER NOT NULL DEFAULT 0,
+    processed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,47 @@
+-- Cold storage for old bookings. Completed bookings past the retention age
+-- (`ARCHIVE_BOOKINGS_AFTER_YEARS`) are exported with their messages and
+-- events to gzipped JSON objects under `archives/bookings/` in storage. The
+-- booking row stays behind as a stub, pointing at its archive, with the
+-- client's free text cleared and its messages and events deleted. An artist
+-- can ask for one back and an admin approving the request restores it.
+
+CREATE TABLE IF NOT EXISTS booking_archives (
+    id BIGSERIAL PRIMARY KEY,
+    storage_key TEXT NOT NULL UNIQUE,
+    bookings INTEGER NOT NULL,
+    messages INTEGER NOT NULL,
+    events INTEGER NOT NULL,
+    -- Compressed size of the object
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS archive_id BIGINT
+    REFERENCES booking_archives(id);
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_booking_requests_archive
+    ON booking_requests (archive_id)
+    WHERE archive_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS booking_restore_requests (
+    id SERIAL PRIMARY KEY,
+    booking_request_id INTEGER NOT NULL REFERENCES booking_requests(id) ON DELETE CASCADE,
+    requested_by BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'rejected', 'restored', 'failed')),
+    reviewed_by BIGINT,
+    reviewed_at TIMESTAMPTZ,
+    -- Why restoring failed, for the admin to retry or look into
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One open request per booking
+CREATE UNIQUE INDEX IF NOT EXISTS idx_booking_restore_requests_pending
+    ON booking_restore_requests (booking_request_id)
+    WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_booking_restore_requests_status
+    ON booking_restore_requests (status, created_at);
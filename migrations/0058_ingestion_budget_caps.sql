@@ -0,0 +1,17 @@
+-- Budget enforcement for ingestion runs. Runs now stop once they've spent
+-- INGESTION_BUDGET_USD and abandon a city once it's spent
+-- INGESTION_CITY_BUDGET_USD; record the city cap, how many calls the run
+-- made and which cities it gave up on.
+
+ALTER TABLE ingestion_runs ADD COLUMN IF NOT EXISTS city_budget_usd DOUBLE PRECISION;
+ALTER TABLE ingestion_runs ADD COLUMN IF NOT EXISTS api_calls BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ingestion_runs ADD COLUMN IF NOT EXISTS aborted_locations TEXT[] NOT NULL DEFAULT '{}';
+
+UPDATE ingestion_runs r
+SET api_calls = c.api_calls
+FROM (
+    SELECT run_id, SUM(api_calls)::BIGINT as api_calls
+    FROM ingestion_costs
+    GROUP BY run_id
+) c
+WHERE c.run_id = r.run_id AND r.api_calls = 0;
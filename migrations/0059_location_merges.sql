@@ -0,0 +1,22 @@
+-- Near-duplicate shops merged by ACTION=DEDUP_LOCATIONS. The merged
+-- location is deleted once its artists, photos, claims and scrape history
+-- are moved to the survivor; its Google place id stays here so ingestion
+-- doesn't insert the shop again, and lookups of that place land on the
+-- survivor instead.
+
+CREATE TABLE IF NOT EXISTS location_merges (
+    id BIGSERIAL PRIMARY KEY,
+    merged_location_id BIGINT NOT NULL,
+    merged_place_id TEXT UNIQUE,
+    merged_name TEXT,
+    merged_address TEXT,
+    -- Kept pointing at the current survivor when that's merged in turn
+    survivor_location_id BIGINT NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    distance_m DOUBLE PRECISION NOT NULL,
+    artists_moved INTEGER NOT NULL DEFAULT 0,
+    photos_moved INTEGER NOT NULL DEFAULT 0,
+    merged_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_location_merges_survivor ON location_merges (survivor_location_id);
@@ -0,0 +1,12 @@
+-- Instagram portfolio refresh (ACTION=REFRESH_PORTFOLIOS): when each
+-- artist's posts were last re-scraped, and which saved posts have since
+-- been deleted from Instagram. Removed posts keep their row, styles and
+-- favorites, so they come back as they were if the post reappears, but
+-- they're no longer shown.
+
+ALTER TABLE artists ADD COLUMN IF NOT EXISTS portfolio_refreshed_at TIMESTAMPTZ;
+ALTER TABLE artists_images ADD COLUMN IF NOT EXISTS removed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_artists_portfolio_refreshed
+    ON artists (portfolio_refreshed_at NULLS FIRST, id)
+    WHERE styles_extracted = 1;
@@ -0,0 +1,64 @@
+-- Rescheduling and cancelling bookings. Every time the artist suggests
+-- another time, or the client counters one, it's a proposal here; the
+-- client answers the artist's through a signed link emailed to them, the
+-- artist answers the client's from the dashboard. Only a booking's latest
+-- proposal is open, a new one supersedes it. Accepting one moves the booking
+-- to that time.
+
+CREATE TABLE IF NOT EXISTS booking_reschedules (
+    id BIGSERIAL PRIMARY KEY,
+    booking_request_id INTEGER NOT NULL,
+    proposed_by TEXT NOT NULL CHECK (proposed_by IN ('client', 'artist')),
+    proposed_date TEXT NOT NULL,
+    proposed_start_time TEXT NOT NULL,
+    proposed_end_time TEXT,
+    status TEXT NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'accepted', 'declined', 'superseded')),
+    -- Why the other side declined, if they said
+    response_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    responded_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_booking_reschedules_booking
+    ON booking_reschedules (booking_request_id, created_at, id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_booking_reschedules_open
+    ON booking_reschedules (booking_request_id)
+    WHERE status = 'open';
+
+-- Times artists suggested before now are open proposals on bookings that
+-- are still going
+INSERT INTO booking_reschedules
+    (booking_request_id, proposed_by, proposed_date, proposed_start_time, proposed_end_time, created_at)
+SELECT br.id, 'artist', br.suggested_date, br.suggested_start_time, NULLIF(br.suggested_end_time, ''),
+       COALESCE(br.updated_at::timestamptz, CURRENT_TIMESTAMP)
+FROM booking_requests br
+WHERE br.suggested_date IS NOT NULL AND br.suggested_date <> ''
+  AND br.suggested_start_time IS NOT NULL AND br.suggested_start_time <> ''
+  AND br.status IN ('pending', 'needs_info', 'approved', 'confirmed')
+  AND NOT EXISTS (
+      SELECT 1 FROM booking_reschedules r WHERE r.booking_request_id = br.id
+  );
+
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ;
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS cancelled_by TEXT
+    CHECK (cancelled_by IN ('client', 'artist'));
+-- One of the cancellation reasons in utils/reschedule.rs
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS cancellation_reason TEXT;
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS cancellation_note TEXT;
+-- The client cancelled inside the artist's cancellation window, so any
+-- deposit they paid is the artist's to keep
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS late_cancellation BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- How many hours before an appointment the artist's clients can still
+-- cancel without losing their deposit. NULL uses
+-- BOOKING_CANCELLATION_WINDOW_HOURS.
+ALTER TABLE artists ADD COLUMN IF NOT EXISTS cancellation_window_hours INTEGER
+    CHECK (cancellation_window_hours >= 0);
+
+ALTER TABLE booking_events DROP CONSTRAINT IF EXISTS booking_events_event_type_check;
+ALTER TABLE booking_events ADD CONSTRAINT booking_events_event_type_check CHECK (event_type IN (
+    'created', 'viewed', 'time_suggested', 'message', 'accepted', 'declined', 'deposit_paid',
+    'time_accepted', 'time_declined', 'cancelled'
+));
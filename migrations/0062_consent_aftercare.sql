@@ -0,0 +1,38 @@
+-- Consent forms and aftercare instructions. Artists can write their own of
+-- each in markdown with {placeholders}; artists who haven't get the defaults
+-- in utils/documents.rs. Clients sign the consent form through their booking
+-- link, and the aftercare instructions are emailed to them when the booking
+-- is marked completed.
+
+CREATE TABLE IF NOT EXISTS artist_documents (
+    artist_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('consent', 'aftercare')),
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (artist_id, kind)
+);
+
+-- A client's signature on a booking's consent form. The form is kept as it
+-- was filled in when they signed, so later edits to the artist's template
+-- don't change what they agreed to.
+CREATE TABLE IF NOT EXISTS consent_signatures (
+    id BIGSERIAL PRIMARY KEY,
+    booking_request_id INTEGER NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    -- The name the client typed as their signature
+    typed_name TEXT NOT NULL,
+    ip_address TEXT,
+    user_agent TEXT,
+    signed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Set once the aftercare email has gone out, so it's only sent once
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS aftercare_sent_at TIMESTAMPTZ;
+
+ALTER TABLE booking_events DROP CONSTRAINT IF EXISTS booking_events_event_type_check;
+ALTER TABLE booking_events ADD CONSTRAINT booking_events_event_type_check CHECK (event_type IN (
+    'created', 'viewed', 'time_suggested', 'message', 'accepted', 'declined', 'deposit_paid',
+    'time_accepted', 'time_declined', 'cancelled', 'consent_signed'
+));
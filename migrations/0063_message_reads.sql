@@ -0,0 +1,21 @@
+-- How far each side of a booking's message thread has read, for the unread
+-- counts on the artist dashboard. A message is read once the other side's
+-- `last_read_message_id` reaches it, so opening a thread is a single upsert
+-- rather than an update per message.
+
+CREATE TABLE IF NOT EXISTS message_reads (
+    booking_request_id INTEGER NOT NULL,
+    reader TEXT NOT NULL CHECK (reader IN ('artist', 'client')),
+    last_read_message_id INTEGER NOT NULL,
+    read_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (booking_request_id, reader)
+);
+
+-- In existing threads, each side has read everything up to its own latest
+-- message
+INSERT INTO message_reads (booking_request_id, reader, last_read_message_id, read_at)
+SELECT booking_request_id, sender_type, MAX(id), MAX(created_at::timestamptz)
+FROM booking_messages
+WHERE sender_type IN ('artist', 'client')
+GROUP BY booking_request_id, sender_type
+ON CONFLICT (booking_request_id, reader) DO NOTHING;
@@ -0,0 +1,15 @@
+-- Keys for the public REST API under /api/v1, issued to third parties like
+-- booking widgets and partner sites with `ops create-api-key`. Only a hash
+-- of each key is stored; `key_prefix` is its first characters, so an
+-- operator can tell keys apart without the secret.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    -- Who the key was issued to, e.g. "Acme booking widget"
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
@@ -0,0 +1,8 @@
+-- The booking widget: a small booking form at /embed/booking/{artist_id}
+-- that artists put on their own website in an iframe.
+--
+-- `embed_allowed_origins` are the sites allowed to embed it, stored like
+-- "https://example.com". Empty lets any site embed it.
+
+ALTER TABLE artists
+    ADD COLUMN IF NOT EXISTS embed_allowed_origins TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,6 @@
+-- What each ingestion run got through, for the web server's /metrics
+-- endpoint to report throughput per action.
+
+ALTER TABLE ingestion_runs ADD COLUMN IF NOT EXISTS posts_processed BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ingestion_runs ADD COLUMN IF NOT EXISTS artists_added BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE ingestion_runs ADD COLUMN IF NOT EXISTS api_errors BIGINT NOT NULL DEFAULT 0;
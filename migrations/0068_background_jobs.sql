@@ -0,0 +1,30 @@
+-- Work the web server does outside of requests (sending notifications,
+-- refreshing Instagram embeds), queued by server fns and run by the worker
+-- in web/src/jobs.rs. A failed job is retried at `run_at` with exponential
+-- backoff until it has used `max_attempts`, then left as 'dead' for an
+-- operator to look into; set it back to 'queued' to run it again.
+
+CREATE TABLE IF NOT EXISTS background_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    -- At most one queued or running job per (kind, dedupe_key)
+    dedupe_key TEXT,
+    status TEXT NOT NULL DEFAULT 'queued'
+        CHECK (status IN ('queued', 'running', 'completed', 'dead')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    run_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_background_jobs_due
+    ON background_jobs (run_at) WHERE status = 'queued';
+CREATE INDEX IF NOT EXISTS idx_background_jobs_status
+    ON background_jobs (status, finished_at);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_background_jobs_dedupe
+    ON background_jobs (kind, dedupe_key)
+    WHERE dedupe_key IS NOT NULL AND status IN ('queued', 'running');
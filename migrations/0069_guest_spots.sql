@@ -0,0 +1,25 @@
+-- Guest spots: an artist working out of another shop for a few days. While
+-- a spot is on, map search shows the artist at that shop too, and booking
+-- requests for its dates are tagged with it so the artist knows which shop
+-- the appointment is at. `host_artist_id` is the artist at that shop who
+-- invited them, when there is one.
+
+CREATE TABLE IF NOT EXISTS guest_spots (
+    id SERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    location_id BIGINT NOT NULL,
+    host_artist_id INTEGER REFERENCES artists(id) ON DELETE SET NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    -- Shown to clients on the artist's profile
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (end_date >= start_date),
+    CHECK (host_artist_id IS DISTINCT FROM artist_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_guest_spots_artist ON guest_spots (artist_id, end_date);
+CREATE INDEX IF NOT EXISTS idx_guest_spots_location ON guest_spots (location_id, start_date, end_date);
+
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS guest_spot_id INTEGER
+    REFERENCES guest_spots(id) ON DELETE SET NULL;
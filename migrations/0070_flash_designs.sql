@@ -0,0 +1,41 @@
+-- Flash: pre-drawn designs an artist sells at a fixed price. Images are
+-- stored like portfolio images (see web/src/storage.rs). A repeatable
+-- design can be tattooed any number of times; a one-off is taken once a
+-- booking request claims it, and comes back if that request is declined or
+-- cancelled. Archived designs are hidden from clients but kept for the
+-- bookings that name them.
+
+CREATE TABLE IF NOT EXISTS flash_designs (
+    id SERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL REFERENCES artists(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    description TEXT,
+    image_key TEXT NOT NULL,
+    thumbnail_key TEXT NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    size_inches REAL CHECK (size_inches > 0),
+    price DOUBLE PRECISION NOT NULL CHECK (price >= 0),
+    repeatable BOOLEAN NOT NULL DEFAULT FALSE,
+    archived_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_flash_designs_artist ON flash_designs (artist_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_flash_designs_browse
+    ON flash_designs (created_at DESC) WHERE archived_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS flash_design_styles (
+    flash_design_id INTEGER NOT NULL REFERENCES flash_designs(id) ON DELETE CASCADE,
+    style_id BIGINT NOT NULL REFERENCES styles(id) ON DELETE CASCADE,
+    PRIMARY KEY (flash_design_id, style_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_flash_design_styles_style ON flash_design_styles (style_id);
+
+ALTER TABLE booking_requests ADD COLUMN IF NOT EXISTS flash_design_id INTEGER
+    REFERENCES flash_designs(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_booking_requests_flash_design
+    ON booking_requests (flash_design_id) WHERE flash_design_id IS NOT NULL;
@@ -0,0 +1,24 @@
+-- Accounts that run a shop. Owners manage the shop's profile, roster and who
+-- else is on the account; managers work its bookings. Both can see and
+-- respond to booking requests for every artist based at the shop, and see
+-- their calendars side by side on the shop dashboard.
+-- locations.claimed_by still records who claimed the shop; approving a claim
+-- also makes the claimant an owner here.
+
+CREATE TABLE IF NOT EXISTS shop_users (
+    location_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('owner', 'manager')),
+    added_by BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (location_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_shop_users_user ON shop_users (user_id);
+
+-- Shops claimed before this table existed
+INSERT INTO shop_users (location_id, user_id, role)
+SELECT l.id, l.claimed_by, 'owner'
+FROM locations l
+JOIN users u ON u.id = l.claimed_by
+ON CONFLICT (location_id, user_id) DO NOTHING;
@@ -0,0 +1,14 @@
+-- How often two styles are tagged on the same portfolio image, for "more
+-- like this" recommendations. Rebuilt in full by the refresh_style_cooccurrence
+-- background job; each style keeps only its closest related styles.
+-- similarity is the Jaccard index of the two styles' images: images tagged
+-- with both over images tagged with either.
+
+CREATE TABLE IF NOT EXISTS style_cooccurrence (
+    style_id BIGINT NOT NULL,
+    related_style_id BIGINT NOT NULL,
+    shared_images INTEGER NOT NULL,
+    similarity DOUBLE PRECISION NOT NULL,
+    refreshed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (style_id, related_style_id)
+);
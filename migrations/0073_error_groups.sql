@@ -0,0 +1,28 @@
+-- Triage for logged errors. Errors with the same fingerprint (a hash of the
+-- error type, the message with numbers blanked out and the top stack frame;
+-- see web/src/utils/error_triage.rs) form one group, which admins assign
+-- and mark resolved or ignored. A resolved group that happens again is
+-- opened again. Errors logged before this have no fingerprint and stay
+-- ungrouped.
+
+ALTER TABLE error_logs ADD COLUMN IF NOT EXISTS fingerprint TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_error_logs_fingerprint ON error_logs (fingerprint, timestamp);
+
+CREATE TABLE IF NOT EXISTS error_groups (
+    fingerprint TEXT PRIMARY KEY,
+    error_type TEXT NOT NULL,
+    error_level TEXT NOT NULL,
+    title TEXT NOT NULL, -- message of the latest occurrence
+    top_frame TEXT,
+    first_seen TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    occurrences BIGINT NOT NULL DEFAULT 1,
+    status TEXT NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'resolved', 'ignored')),
+    assigned_to BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    resolved_at TIMESTAMPTZ,
+    resolved_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    reopened_at TIMESTAMPTZ -- last time a resolved group happened again
+);
+
+CREATE INDEX IF NOT EXISTS idx_error_groups_status ON error_groups (status, last_seen DESC);
@@ -0,0 +1,15 @@
+-- Closing accounts. A user can deactivate their account, which blocks
+-- sign-in like an operator deactivating it, or ask for their data to be
+-- deleted, which deactivates it now and purges it once the grace period has
+-- passed: the purge job (web/src/server_account.rs) anonymizes the user row
+-- and the client details on their booking requests, blanks the messages they
+-- sent and deletes their favorites and tattoo photos. Reactivating an
+-- account before then cancels the deletion.
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deletion_requested_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS purged_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_deletion_requested
+    ON users (deletion_requested_at)
+    WHERE deletion_requested_at IS NOT NULL AND purged_at IS NULL;
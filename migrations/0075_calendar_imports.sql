@@ -0,0 +1,38 @@
+-- External calendars an artist imports busy time from, such as a Google
+-- Calendar's secret ICS address. A background job (web/src/calendar_imports.rs)
+-- fetches each one every half hour and replaces its blocks with the busy
+-- events in the coming months, which availability treats like bookings:
+-- no slot is offered while the artist is busy elsewhere.
+
+CREATE TABLE IF NOT EXISTS calendar_imports (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL,
+    -- The calendar's address; like a feed token, it's a credential
+    url TEXT NOT NULL,
+    -- IANA zone times without one are read in, e.g. 'America/Chicago'
+    timezone TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_attempted_at TIMESTAMPTZ,
+    last_synced_at TIMESTAMPTZ,
+    last_error TEXT,
+    busy_blocks INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (artist_id, url)
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendar_imports_attempted
+    ON calendar_imports (last_attempted_at NULLS FIRST);
+
+-- Busy time on a day, in the artist's own time; all day when the times are
+-- NULL
+CREATE TABLE IF NOT EXISTS calendar_import_blocks (
+    import_id BIGINT NOT NULL REFERENCES calendar_imports (id) ON DELETE CASCADE,
+    artist_id INTEGER NOT NULL,
+    block_date DATE NOT NULL,
+    start_time TEXT,
+    end_time TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_calendar_import_blocks_artist
+    ON calendar_import_blocks (artist_id, block_date);
+CREATE INDEX IF NOT EXISTS idx_calendar_import_blocks_import
+    ON calendar_import_blocks (import_id);
@@ -0,0 +1,32 @@
+-- Curating an artist's scraped portfolio. Artists group their images into
+-- albums, which visitors can filter the gallery by, pin a few highlights to
+-- the front of it and hide images they don't want shown. Hidden images keep
+-- their styles, favorites and albums, so unhiding one puts it back as it
+-- was.
+
+CREATE TABLE IF NOT EXISTS portfolio_albums (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (artist_id, name)
+);
+
+-- An image can be in several albums
+CREATE TABLE IF NOT EXISTS portfolio_album_images (
+    album_id BIGINT NOT NULL REFERENCES portfolio_albums (id) ON DELETE CASCADE,
+    image_id BIGINT NOT NULL,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (album_id, image_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_portfolio_album_images_image
+    ON portfolio_album_images (image_id);
+
+ALTER TABLE artists_images ADD COLUMN IF NOT EXISTS hidden_at TIMESTAMPTZ;
+-- 1-based place among the artist's pinned highlights, NULL when not pinned
+ALTER TABLE artists_images ADD COLUMN IF NOT EXISTS pinned_position INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_artists_images_pinned
+    ON artists_images (artist_id, pinned_position)
+    WHERE pinned_position IS NOT NULL;
@@ -0,0 +1,32 @@
+-- Profile content artists write for their public page, alongside their bio:
+-- FAQ entries, studio policies and links to their social accounts. The bio
+-- and policies are markdown (see utils/documents.rs); links are checked in
+-- utils/profile.rs before they're stored.
+
+CREATE TABLE IF NOT EXISTS artist_faqs (
+    id BIGSERIAL PRIMARY KEY,
+    artist_id INTEGER NOT NULL,
+    question TEXT NOT NULL,
+    answer TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_artist_faqs_artist ON artist_faqs (artist_id, position);
+
+CREATE TABLE IF NOT EXISTS artist_policies (
+    artist_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('deposit', 'cancellation', 'touch_up')),
+    body TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (artist_id, kind)
+);
+
+-- Links the artist manages themselves. The scraped artists.social_links is
+-- only shown when they haven't added any.
+CREATE TABLE IF NOT EXISTS artist_social_links (
+    artist_id INTEGER NOT NULL,
+    platform TEXT NOT NULL,
+    url TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (artist_id, platform)
+);
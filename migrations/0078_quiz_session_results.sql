@@ -0,0 +1,39 @@
+-- Persisted quiz sessions. A session is keyed by the same session key the
+-- quiz's style images are logged against (quiz_image_exposures), is linked
+-- to the client once they're signed in, and keeps the artists it matched so
+-- a returning client can reload their last results.
+
+ALTER TABLE client_quiz_sessions ADD COLUMN IF NOT EXISTS session_key TEXT;
+ALTER TABLE client_quiz_sessions ADD COLUMN IF NOT EXISTS user_id BIGINT;
+ALTER TABLE client_quiz_sessions ADD COLUMN IF NOT EXISTS states TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE client_quiz_sessions ADD COLUMN IF NOT EXISTS cities TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE client_quiz_sessions ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
+-- Set each time matches are stored for the session
+ALTER TABLE client_quiz_sessions ADD COLUMN IF NOT EXISTS matched_at TIMESTAMPTZ;
+
+-- The quiz no longer asks these
+ALTER TABLE client_quiz_sessions ALTER COLUMN body_placement DROP NOT NULL;
+ALTER TABLE client_quiz_sessions ALTER COLUMN pain_tolerance DROP NOT NULL;
+ALTER TABLE client_quiz_sessions ALTER COLUMN budget_min DROP NOT NULL;
+ALTER TABLE client_quiz_sessions ALTER COLUMN budget_max DROP NOT NULL;
+ALTER TABLE client_quiz_sessions ALTER COLUMN vibe_preference DROP NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_client_quiz_sessions_key
+    ON client_quiz_sessions (session_key)
+    WHERE session_key IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_client_quiz_sessions_user
+    ON client_quiz_sessions (user_id, updated_at DESC)
+    WHERE user_id IS NOT NULL;
+
+-- The session's matches in rank order, each a snapshot of the match as it
+-- was shown (server::MatchedArtist) so reloading doesn't rescore them.
+-- Replaced whenever the session's answers change.
+CREATE TABLE IF NOT EXISTS client_quiz_matches (
+    session_id BIGINT NOT NULL,
+    rank INTEGER NOT NULL,
+    artist_id BIGINT NOT NULL,
+    match_score INTEGER NOT NULL,
+    artist JSONB NOT NULL,
+    PRIMARY KEY (session_id, rank)
+);
@@ -0,0 +1,26 @@
+-- Weekly impression insights for artists. Raw view/click rows in
+-- client_match_impressions are kept for the retention period
+-- (IMPRESSION_RETENTION_DAYS), then folded into per-artist weekly totals
+-- and deleted a whole week at a time, so a week is only ever counted from
+-- one of the two tables.
+
+-- Rows logged before this column existed count as logged now
+ALTER TABLE client_match_impressions ADD COLUMN IF NOT EXISTS logged_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_client_match_impressions_logged
+    ON client_match_impressions (logged_at);
+
+CREATE INDEX IF NOT EXISTS idx_client_match_impressions_artist
+    ON client_match_impressions (artist_id, logged_at);
+
+CREATE TABLE IF NOT EXISTS artist_impression_weeks (
+    artist_id BIGINT NOT NULL,
+    -- Monday
+    week_start DATE NOT NULL,
+    views BIGINT NOT NULL DEFAULT 0,
+    clicks BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (artist_id, week_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_artist_impression_weeks_week
+    ON artist_impression_weeks (week_start);
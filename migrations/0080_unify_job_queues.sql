@@ -0,0 +1,47 @@
+-- One job queue. data-ingestion's jobs (backfills, Reddit city scrapes, city
+-- ingestion requests) move from `jobs` into `background_jobs` on their own
+-- 'ingestion' queue, which the web worker doesn't claim, and `progress`
+-- carries their resume point as before. Exports keep their own record for
+-- progress and downloads, renamed `exports`, and are run by `run_export`
+-- jobs. `job_schedules` holds when each piece of periodic work is next
+-- due, so only one server queues it when several are running.
+
+ALTER TABLE background_jobs
+    ADD COLUMN IF NOT EXISTS queue TEXT NOT NULL DEFAULT 'web',
+    ADD COLUMN IF NOT EXISTS progress JSONB NOT NULL DEFAULT '{}';
+
+INSERT INTO background_jobs (
+    queue, kind, payload, progress, dedupe_key, status, attempts, max_attempts,
+    run_at, last_error, created_at, started_at, finished_at
+)
+SELECT 'ingestion', kind, payload, progress,
+    CASE WHEN kind = 'city_ingestion'
+        THEN (payload->>'state') || '/' || (payload->>'city') END,
+    CASE WHEN status = 'failed' THEN 'dead' ELSE status END,
+    CASE WHEN started_at IS NULL THEN 0 ELSE 1 END, 1,
+    created_at, error, created_at, started_at, finished_at
+FROM jobs
+ORDER BY id;
+
+DROP TABLE jobs;
+
+CREATE INDEX IF NOT EXISTS idx_background_jobs_ingestion
+    ON background_jobs (kind, created_at) WHERE queue = 'ingestion';
+
+CREATE TABLE IF NOT EXISTS job_schedules (
+    kind TEXT PRIMARY KEY,
+    next_run_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE export_jobs RENAME TO exports;
+ALTER INDEX idx_export_jobs_user RENAME TO idx_exports_user;
+ALTER INDEX idx_export_jobs_status RENAME TO idx_exports_status;
+
+-- Exports a server was running are started again
+INSERT INTO background_jobs (kind, payload, dedupe_key, max_attempts)
+SELECT 'run_export', jsonb_build_object('export_id', id), id, 1
+FROM exports
+WHERE status IN ('queued', 'running')
+ORDER BY created_at;
+
+UPDATE exports SET status = 'queued', started_at = NULL WHERE status = 'running';
@@ -0,0 +1,277 @@
+//! Circuit breaker for calls to external APIs, shared by the web server and
+//! ingestion so an outage fails calls fast instead of every one waiting out
+//! its timeout.
+//!
+//! A breaker watches the outcome of its last calls. Once enough of them
+//! fail it opens and rejects calls for a cool-down; then it lets a few
+//! probe calls through (half-open). If they succeed it closes again, and if
+//! one fails it reopens for another cool-down.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BreakerConfig {
+    /// Recent calls the failure rate is measured over
+    pub window: usize,
+    /// Calls in the window before the breaker may open
+    pub min_calls: usize,
+    /// Failure rate (0 to 1) that opens the breaker
+    pub failure_rate: f64,
+    /// How long the breaker stays open before probing
+    pub cool_down: Duration,
+    /// Successful probes needed to close again
+    pub probes: u32,
+}
+
+impl BreakerConfig {
+    pub const DEFAULT: BreakerConfig = BreakerConfig {
+        window: 20,
+        min_calls: 5,
+        failure_rate: 0.5,
+        cool_down: Duration::from_secs(30),
+        probes: 2,
+    };
+}
+
+/// Whether an HTTP status means the service is having trouble, rather than
+/// the request being wrong: what a breaker should count as a failure
+pub fn is_outage_status(status: u16) -> bool {
+    status >= 500 || status == 429
+}
+
+/// The breaker rejected a call without making it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BreakerOpen {
+    pub name: &'static str,
+    /// Until probes are let through
+    pub retry_in: Duration,
+}
+
+impl std::fmt::Display for BreakerOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is unavailable (circuit open, retrying in {}s)",
+            self.name,
+            self.retry_in.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for BreakerOpen {}
+
+/// Error from [`CircuitBreaker::call`]: rejected, or the call's own error
+#[derive(Debug)]
+pub enum BreakerError<E> {
+    Open(BreakerOpen),
+    Inner(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for BreakerError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BreakerError::Open(open) => open.fmt(f),
+            BreakerError::Inner(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for BreakerError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BreakerError::Open(_) => None,
+            BreakerError::Inner(e) => Some(e),
+        }
+    }
+}
+
+/// A breaker's state for metrics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakerSnapshot {
+    pub name: String,
+    pub state: BreakerState,
+    /// Over the recent calls; 0 with none
+    pub failure_rate: f64,
+    pub recent_calls: usize,
+    /// Calls let through, since startup
+    pub calls: u64,
+    /// Calls rejected while open, since startup
+    pub rejected_calls: u64,
+    /// Times the breaker has opened, since startup
+    pub times_opened: u64,
+}
+
+struct Inner {
+    state: BreakerState,
+    /// Recent outcomes, true for a failure
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    /// When the probe in flight was let through, while half-open
+    probe_started_at: Option<Instant>,
+    /// Successful probes since going half-open
+    probes_succeeded: u32,
+    calls: u64,
+    rejected_calls: u64,
+    times_opened: u64,
+}
+
+pub struct CircuitBreaker {
+    name: &'static str,
+    config: BreakerConfig,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub const fn new(name: &'static str, config: BreakerConfig) -> Self {
+        CircuitBreaker {
+            name,
+            config,
+            inner: Mutex::new(Inner {
+                state: BreakerState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: None,
+                probe_started_at: None,
+                probes_succeeded: 0,
+                calls: 0,
+                rejected_calls: 0,
+                times_opened: 0,
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn open(&self, inner: &mut Inner) {
+        inner.state = BreakerState::Open;
+        inner.opened_at = Some(Instant::now());
+        inner.outcomes.clear();
+        inner.times_opened += 1;
+    }
+
+    /// Admits a call, or rejects it while the breaker is open. An admitted
+    /// call must be followed by [`record`](Self::record).
+    pub fn check(&self) -> Result<(), BreakerOpen> {
+        let mut inner = self.lock();
+        let cool_down = self.config.cool_down;
+
+        if inner.state == BreakerState::Open {
+            let since_opened = inner.opened_at.map(|at| at.elapsed()).unwrap_or(cool_down);
+            if since_opened < cool_down {
+                inner.rejected_calls += 1;
+                return Err(BreakerOpen {
+                    name: self.name,
+                    retry_in: cool_down - since_opened,
+                });
+            }
+            inner.state = BreakerState::HalfOpen;
+            inner.probe_started_at = None;
+            inner.probes_succeeded = 0;
+        }
+
+        if inner.state == BreakerState::HalfOpen {
+            // One probe at a time. A probe that never reported back stops
+            // counting after a cool-down, so it can't hold the breaker shut.
+            let probe_age = inner.probe_started_at.map(|at| at.elapsed());
+            if let Some(age) = probe_age.filter(|age| *age < cool_down) {
+                inner.rejected_calls += 1;
+                return Err(BreakerOpen {
+                    name: self.name,
+                    retry_in: cool_down - age,
+                });
+            }
+            inner.probe_started_at = Some(Instant::now());
+        }
+
+        inner.calls += 1;
+        Ok(())
+    }
+
+    /// Records the outcome of an admitted call
+    pub fn record(&self, success: bool) {
+        let mut inner = self.lock();
+
+        match inner.state {
+            BreakerState::HalfOpen if success => {
+                inner.probe_started_at = None;
+                inner.probes_succeeded += 1;
+                if inner.probes_succeeded >= self.config.probes {
+                    inner.state = BreakerState::Closed;
+                    inner.opened_at = None;
+                }
+            }
+            BreakerState::HalfOpen => self.open(&mut inner),
+            // A call admitted before the breaker opened
+            BreakerState::Open => {}
+            BreakerState::Closed => {
+                inner.outcomes.push_back(!success);
+                while inner.outcomes.len() > self.config.window {
+                    inner.outcomes.pop_front();
+                }
+                let failures = inner.outcomes.iter().filter(|failed| **failed).count();
+                if inner.outcomes.len() >= self.config.min_calls
+                    && failures as f64 >= self.config.failure_rate * inner.outcomes.len() as f64
+                {
+                    self.open(&mut inner);
+                }
+            }
+        }
+    }
+
+    /// Runs `call` through the breaker; an `Err` counts as a failure
+    pub async fn call<T, E, F>(&self, call: F) -> Result<T, BreakerError<E>>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        self.check().map_err(BreakerError::Open)?;
+        let result = call.await;
+        self.record(result.is_ok());
+        result.map_err(BreakerError::Inner)
+    }
+
+    pub fn snapshot(&self) -> BreakerSnapshot {
+        let inner = self.lock();
+        let failures = inner.outcomes.iter().filter(|failed| **failed).count();
+
+        BreakerSnapshot {
+            name: self.name.to_string(),
+            state: inner.state,
+            failure_rate: if inner.outcomes.is_empty() {
+                0.0
+            } else {
+                failures as f64 / inner.outcomes.len() as f64
+            },
+            recent_calls: inner.outcomes.len(),
+            calls: inner.calls,
+            rejected_calls: inner.rejected_calls,
+            times_opened: inner.times_opened,
+        }
+    }
+}
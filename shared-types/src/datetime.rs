@@ -0,0 +1,212 @@
+//! Dates and times as they travel between the client and server fns. Each
+//! type serializes to one format and refuses anything else, so a value
+//! can't drift into another format on its way through: [`Date`] is
+//! `YYYY-MM-DD`, [`Time`] is `HH:MM` on a 24-hour clock, and [`DateTimeUtc`]
+//! is RFC 3339 in UTC (`2025-03-14T18:30:00Z`).
+//!
+//! Booking and availability columns hold the same text, so `to_string()` is
+//! what gets stored and `parse` reads it back.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Timelike, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
+/// Deserializes a string with `parse`, naming the expected format on failure
+fn deserialize_with<'de, D, T>(
+    deserializer: D,
+    parse: fn(&str) -> Option<T>,
+    expected: &'static str,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    parse(&value)
+        .ok_or_else(|| de::Error::custom(format!("expected {}, got {:?}", expected, value)))
+}
+
+/// A calendar date, `YYYY-MM-DD`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(NaiveDate);
+
+impl Date {
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, day).map(Date)
+    }
+
+    /// Exactly `YYYY-MM-DD`
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.len() != 10 {
+            return None;
+        }
+        NaiveDate::parse_from_str(value, DATE_FORMAT).ok().map(Date)
+    }
+
+    pub fn naive(self) -> NaiveDate {
+        self.0
+    }
+}
+
+impl From<NaiveDate> for Date {
+    fn from(date: NaiveDate) -> Self {
+        Date(date)
+    }
+}
+
+impl From<Date> for NaiveDate {
+    fn from(date: Date) -> Self {
+        date.0
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format(DATE_FORMAT))
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with(deserializer, Date::parse, "a date as YYYY-MM-DD")
+    }
+}
+
+/// A time of day to the minute, `HH:MM`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time(NaiveTime);
+
+impl Time {
+    pub fn from_hm(hour: u32, minute: u32) -> Option<Self> {
+        NaiveTime::from_hms_opt(hour, minute, 0).map(Time)
+    }
+
+    /// Exactly `HH:MM`
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.len() != 5 {
+            return None;
+        }
+        NaiveTime::parse_from_str(value, TIME_FORMAT).ok().map(Time)
+    }
+
+    /// `HH:MM`, or `HH:MM:SS` as some stored rows and `<input type="time">`
+    /// have it, dropping the seconds
+    pub fn parse_lenient(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value.len() {
+            5 => Time::parse(value),
+            8 => NaiveTime::parse_from_str(value, "%H:%M:%S")
+                .ok()
+                .map(Time::from),
+            _ => None,
+        }
+    }
+
+    pub fn naive(self) -> NaiveTime {
+        self.0
+    }
+}
+
+/// Drops seconds
+impl From<NaiveTime> for Time {
+    fn from(time: NaiveTime) -> Self {
+        Time(time.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(time))
+    }
+}
+
+impl From<Time> for NaiveTime {
+    fn from(time: Time) -> Self {
+        time.0
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format(TIME_FORMAT))
+    }
+}
+
+impl Serialize for Time {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Time {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with(deserializer, Time::parse, "a time as HH:MM")
+    }
+}
+
+/// An instant, RFC 3339 in UTC to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTimeUtc(DateTime<Utc>);
+
+impl DateTimeUtc {
+    pub fn now() -> Self {
+        DateTimeUtc::from(Utc::now())
+    }
+
+    /// RFC 3339 with any offset, converted to UTC
+    pub fn parse(value: &str) -> Option<Self> {
+        DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|at| DateTimeUtc::from(at.with_timezone(&Utc)))
+    }
+
+    /// From a `TIMESTAMP` column, which holds UTC without saying so
+    pub fn from_naive_utc(at: NaiveDateTime) -> Self {
+        DateTimeUtc::from(at.and_utc())
+    }
+
+    pub fn date(self) -> Date {
+        Date(self.0.date_naive())
+    }
+
+    pub fn utc(self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Drops fractions of a second
+impl From<DateTime<Utc>> for DateTimeUtc {
+    fn from(at: DateTime<Utc>) -> Self {
+        DateTimeUtc(at.with_nanosecond(0).unwrap_or(at))
+    }
+}
+
+impl From<DateTimeUtc> for DateTime<Utc> {
+    fn from(at: DateTimeUtc) -> Self {
+        at.0
+    }
+}
+
+impl fmt::Display for DateTimeUtc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_rfc3339_opts(SecondsFormat::Secs, true))
+    }
+}
+
+impl Serialize for DateTimeUtc {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTimeUtc {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with(
+            deserializer,
+            DateTimeUtc::parse,
+            "a UTC date and time as RFC 3339",
+        )
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+pub mod circuit_breaker;
+pub mod datetime;
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct LocationInfo {
     pub id: i32,
@@ -18,6 +21,9 @@ pub struct LocationInfo {
     pub _id: String,
     pub has_artists: Option<bool>,
     pub artist_images_count: Option<i32>,
+    /// Set by radius searches: miles from the search center
+    #[serde(default)]
+    pub distance_miles: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -41,3 +47,250 @@ pub struct MapBounds {
     pub north_east: LatLong,
     pub south_west: LatLong,
 }
+
+/// "Within `miles` of `center`" for location searches. Results carry their
+/// distance and come nearest first.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RadiusSearch {
+    pub center: LatLong,
+    pub miles: f64,
+}
+
+impl RadiusSearch {
+    /// The box around the circle, used to prefilter before measuring
+    /// distances
+    pub fn bounds(&self) -> MapBounds {
+        const MILES_PER_DEGREE: f64 = 69.0;
+        let lat_span = self.miles / MILES_PER_DEGREE;
+        let long_span =
+            self.miles / (MILES_PER_DEGREE * self.center.lat.to_radians().cos().abs().max(0.01));
+        MapBounds {
+            north_east: LatLong {
+                lat: self.center.lat + lat_span,
+                long: self.center.long + long_span,
+            },
+            south_west: LatLong {
+                lat: self.center.lat - lat_span,
+                long: self.center.long - long_span,
+            },
+        }
+    }
+}
+
+/// Slim map marker: just enough to place a pin and label it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LocationPin {
+    pub id: i32,
+    pub name: String,
+    pub lat: f64,
+    pub long: f64,
+    pub artist_count: i32,
+    pub image_count: i32,
+    pub min_price: Option<f64>,
+}
+
+/// Slim gallery tile. Styles are sent as ids; clients already have the style
+/// list from the filter bar.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CompactImage {
+    pub id: i32,
+    pub short_code: String,
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    pub style_ids: Vec<i32>,
+    pub is_favorited: bool,
+}
+
+/// How must-have styles combine: `Any` keeps items with at least one of them
+/// (OR), `All` only items carrying every one (AND).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StyleMatchMode {
+    #[default]
+    Any,
+    All,
+}
+
+/// Style filter for galleries and artist matching, keyed by style id or name.
+/// Must-have styles narrow results according to `mode`; nice-to-have styles
+/// never exclude anything but rank items matching more of them first.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StyleFilter<T> {
+    #[serde(default = "Vec::new")]
+    pub must_have: Vec<T>,
+    #[serde(default = "Vec::new")]
+    pub nice_to_have: Vec<T>,
+    #[serde(default)]
+    pub mode: StyleMatchMode,
+}
+
+impl<T: PartialEq> StyleFilter<T> {
+    /// Items with any of `styles`, the behaviour of a plain style list.
+    pub fn any(styles: Vec<T>) -> Self {
+        Self {
+            must_have: styles,
+            nice_to_have: Vec::new(),
+            mode: StyleMatchMode::Any,
+        }
+    }
+
+    /// Only items with every one of `styles`.
+    pub fn all(styles: Vec<T>) -> Self {
+        Self {
+            must_have: styles,
+            nice_to_have: Vec::new(),
+            mode: StyleMatchMode::All,
+        }
+    }
+
+    /// No filtering, just ranking by `styles`.
+    pub fn preferred(styles: Vec<T>) -> Self {
+        Self {
+            must_have: Vec::new(),
+            nice_to_have: styles,
+            mode: StyleMatchMode::Any,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.must_have.is_empty() && self.nice_to_have.is_empty()
+    }
+
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> StyleFilter<U> {
+        StyleFilter {
+            must_have: self.must_have.into_iter().map(&f).collect(),
+            nice_to_have: self.nice_to_have.into_iter().map(&f).collect(),
+            mode: self.mode,
+        }
+    }
+
+    /// Drops duplicates, and nice-to-have styles that are already required
+    /// under `All` (they would add the same rank to every result).
+    pub fn normalized(self) -> Self {
+        fn dedup<T: PartialEq>(items: Vec<T>) -> Vec<T> {
+            let mut unique = Vec::with_capacity(items.len());
+            for item in items {
+                if !unique.contains(&item) {
+                    unique.push(item);
+                }
+            }
+            unique
+        }
+
+        let must_have = dedup(self.must_have);
+        let nice_to_have = dedup(self.nice_to_have)
+            .into_iter()
+            .filter(|style| self.mode == StyleMatchMode::Any || !must_have.contains(style))
+            .collect();
+
+        Self {
+            must_have,
+            nice_to_have,
+            mode: self.mode,
+        }
+    }
+}
+
+/// Where a booking request is in its lifecycle. Stored as text in
+/// `booking_requests.status`; accepted requests keep their older "approved"
+/// spelling so existing rows and queries still match.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingStatus {
+    Pending,
+    #[serde(rename = "approved")]
+    Accepted,
+    Declined,
+    /// The artist asked the client for more details before deciding
+    NeedsInfo,
+    /// Accepted and locked in, by the deposit being paid or by the artist
+    Confirmed,
+    Completed,
+    Cancelled,
+}
+
+impl BookingStatus {
+    pub const ALL: [BookingStatus; 7] = [
+        BookingStatus::Pending,
+        BookingStatus::Accepted,
+        BookingStatus::Declined,
+        BookingStatus::NeedsInfo,
+        BookingStatus::Confirmed,
+        BookingStatus::Completed,
+        BookingStatus::Cancelled,
+    ];
+
+    /// Value of the status column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookingStatus::Pending => "pending",
+            BookingStatus::Accepted => "approved",
+            BookingStatus::Declined => "declined",
+            BookingStatus::NeedsInfo => "needs_info",
+            BookingStatus::Confirmed => "confirmed",
+            BookingStatus::Completed => "completed",
+            BookingStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BookingStatus::Pending => "Pending",
+            BookingStatus::Accepted => "Approved",
+            BookingStatus::Declined => "Declined",
+            BookingStatus::NeedsInfo => "Needs Info",
+            BookingStatus::Confirmed => "Confirmed",
+            BookingStatus::Completed => "Completed",
+            BookingStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Statuses a booking can move to from this one: pending →
+    /// accepted/declined/needs info → confirmed → completed, with
+    /// cancellation possible until the booking is done.
+    pub fn next(self) -> &'static [BookingStatus] {
+        match self {
+            BookingStatus::Pending => &[
+                BookingStatus::Accepted,
+                BookingStatus::Declined,
+                BookingStatus::NeedsInfo,
+                BookingStatus::Cancelled,
+            ],
+            BookingStatus::NeedsInfo => &[
+                BookingStatus::Accepted,
+                BookingStatus::Declined,
+                BookingStatus::Cancelled,
+            ],
+            BookingStatus::Accepted => &[BookingStatus::Confirmed, BookingStatus::Cancelled],
+            BookingStatus::Confirmed => &[BookingStatus::Completed, BookingStatus::Cancelled],
+            BookingStatus::Declined | BookingStatus::Completed | BookingStatus::Cancelled => &[],
+        }
+    }
+
+    pub fn can_become(self, next: BookingStatus) -> bool {
+        self.next().contains(&next)
+    }
+
+    /// Declined, completed and cancelled bookings don't change again
+    pub fn is_final(self) -> bool {
+        self.next().is_empty()
+    }
+}
+
+impl std::fmt::Display for BookingStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Serialized JSON size of a response, used to log full vs compact payloads.
+pub fn payload_size<T: Serialize>(value: &T) -> usize {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0)
+}
@@ -0,0 +1,152 @@
+//! [`ApiError`], the error server fns return when the client needs to tell
+//! failures apart: a wrong password from a taken email, a missing booking
+//! from an expired session. Server fns return it as
+//! `ServerFnError<ApiError>`; views match on
+//! `ServerFnError::WrappedServerError` or show [`user_message`].
+//!
+//! Leptos carries the error to the client as its `Display` string and reads
+//! it back with `FromStr`, so each variant is written with a prefix that
+//! identifies it, e.g. "Invalid email: Enter a valid email address".
+
+use leptos::server_fn::error::ServerFnError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Shown for internal errors, whose details are for the logs
+const INTERNAL_MESSAGE: &str = "Something went wrong, please try again";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiError {
+    /// The thing asked for doesn't exist, or isn't visible to the caller
+    NotFound(String),
+    /// Not signed in, or not allowed to do this
+    Unauthorized(String),
+    /// A form value was rejected; `field` names the input it came from
+    Validation {
+        field: String,
+        message: String,
+    },
+    /// The request clashes with the current state, like an email already in
+    /// use or a booking already answered
+    Conflict(String),
+    /// Too many attempts; the client can try again after `retry_after_secs`
+    RateLimited {
+        retry_after_secs: u64,
+        message: String,
+    },
+    Internal(String),
+}
+
+impl ApiError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::Unauthorized(message.into())
+    }
+
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Validation {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::Conflict(message.into())
+    }
+
+    pub fn rate_limited(retry_after_secs: u64, message: impl Into<String>) -> Self {
+        Self::RateLimited {
+            retry_after_secs,
+            message: message.into(),
+        }
+    }
+
+    /// `context` says what failed, e.g. "Failed to load booking"
+    pub fn internal(context: &str, error: impl fmt::Display) -> Self {
+        Self::Internal(format!("{}: {}", context, error))
+    }
+
+    /// What to show the user. Internal details are left out.
+    pub fn message(&self) -> &str {
+        match self {
+            Self::NotFound(message)
+            | Self::Unauthorized(message)
+            | Self::Conflict(message)
+            | Self::Validation { message, .. }
+            | Self::RateLimited { message, .. } => message,
+            Self::Internal(_) => INTERNAL_MESSAGE,
+        }
+    }
+
+    /// The form field a validation error belongs to
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::Validation { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(message) => write!(f, "Not found: {}", message),
+            Self::Unauthorized(message) => write!(f, "Unauthorized: {}", message),
+            Self::Validation { field, message } => write!(f, "Invalid {}: {}", field, message),
+            Self::Conflict(message) => write!(f, "Conflict: {}", message),
+            Self::RateLimited {
+                retry_after_secs,
+                message,
+            } => write!(f, "Rate limited for {}s: {}", retry_after_secs, message),
+            Self::Internal(message) => write!(f, "Internal error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl FromStr for ApiError {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, message) = s.split_once(": ").ok_or(())?;
+        let message = message.to_string();
+        Ok(match kind {
+            "Not found" => Self::NotFound(message),
+            "Unauthorized" => Self::Unauthorized(message),
+            "Conflict" => Self::Conflict(message),
+            "Internal error" => Self::Internal(message),
+            _ if kind.starts_with("Rate limited for ") => Self::RateLimited {
+                retry_after_secs: kind
+                    .trim_start_matches("Rate limited for ")
+                    .trim_end_matches('s')
+                    .parse()
+                    .map_err(|_| ())?,
+                message,
+            },
+            _ => Self::Validation {
+                field: kind.strip_prefix("Invalid ").ok_or(())?.to_string(),
+                message,
+            },
+        })
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::internal("Database error", error)
+    }
+}
+
+/// What to show the user for a failed server fn call
+pub fn user_message(error: &ServerFnError<ApiError>) -> String {
+    match error {
+        ServerFnError::WrappedServerError(error) => error.message().to_string(),
+        _ => "Couldn't reach the server, please try again".to_string(),
+    }
+}
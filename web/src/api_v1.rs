@@ -0,0 +1,246 @@
+//! The public REST API, for third parties like booking widgets and partner
+//! sites that can't call server functions. Read-only and versioned under
+//! `/api/v1`:
+//! - `GET /locations` and `GET /locations/:id`, shops
+//! - `GET /artists`, `GET /artists/:id` and `GET /artists/:id/availability`
+//! - `GET /styles`
+//!
+//! Every endpoint but `GET /openapi.json`, the OpenAPI document generated
+//! from the handlers, needs an API key sent as `X-Api-Key` or
+//! `Authorization: Bearer`. Operators issue keys with `ops create-api-key`.
+//!
+//! Lists take `page` (from 1) and `per_page` (at most [`MAX_PER_PAGE`]) and
+//! return `{"data": [...], "pagination": {...}}`. Errors are
+//! `{"error": {"code", "message"}}` with a matching status.
+
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{IntoParams, Modify, OpenApi, ToSchema};
+
+use crate::api_error::ApiError;
+use crate::db::entities::{
+    PublicArtist, PublicAvailability, PublicAvailableDate, PublicLocation, PublicSlot, PublicStyle,
+};
+
+mod auth;
+mod handlers;
+
+pub use auth::issue_api_key;
+
+pub const DEFAULT_PER_PAGE: i64 = 25;
+pub const MAX_PER_PAGE: i64 = 100;
+
+/// Which page of a list to return
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Page number, from 1
+    page: Option<i64>,
+    /// Items per page, 25 by default and at most 100
+    per_page: Option<i64>,
+}
+
+impl PageParams {
+    /// `(page, per_page)`, defaulted and checked
+    fn resolve(&self) -> Result<(i64, i64), ApiError> {
+        let page = self.page.unwrap_or(1);
+        let per_page = self.per_page.unwrap_or(DEFAULT_PER_PAGE);
+        if page < 1 {
+            return Err(ApiError::validation("page", "Pages start at 1"));
+        }
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(ApiError::validation(
+                "per_page",
+                format!("per_page must be between 1 and {}", MAX_PER_PAGE),
+            ));
+        }
+        Ok((page, per_page))
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Pagination {
+    page: i64,
+    per_page: i64,
+    /// Items in all pages
+    total: i64,
+}
+
+/// One page of a list
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    LocationPage = Page<PublicLocation>,
+    ArtistPage = Page<PublicArtist>,
+    StylePage = Page<PublicStyle>
+)]
+pub struct Page<T> {
+    data: Vec<T>,
+    pagination: Pagination,
+}
+
+impl<T> Page<T> {
+    fn new(data: Vec<T>, page: i64, per_page: i64, total: i64) -> Self {
+        Self {
+            data,
+            pagination: Pagination {
+                page,
+                per_page,
+                total,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    error: ErrorDetail,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorDetail {
+    /// "not_found", "unauthorized", "invalid_request", "conflict",
+    /// "rate_limited" or "internal"
+    code: String,
+    message: String,
+    /// The parameter an invalid_request error is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<String>,
+}
+
+/// An [`ApiError`] as this API responds with it
+#[derive(Debug)]
+pub struct ApiV1Error(ApiError);
+
+impl From<ApiError> for ApiV1Error {
+    fn from(error: ApiError) -> Self {
+        Self(error)
+    }
+}
+
+impl From<sqlx::Error> for ApiV1Error {
+    fn from(error: sqlx::Error) -> Self {
+        Self(ApiError::internal("Database error", error))
+    }
+}
+
+impl IntoResponse for ApiV1Error {
+    fn into_response(self) -> Response {
+        let (status, code) = match &self.0 {
+            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
+            ApiError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "unauthorized"),
+            ApiError::Validation { .. } => (StatusCode::BAD_REQUEST, "invalid_request"),
+            ApiError::Conflict(_) => (StatusCode::CONFLICT, "conflict"),
+            ApiError::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, "rate_limited"),
+            ApiError::Internal(details) => {
+                tracing::error!("Public API error: {}", details);
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal")
+            }
+        };
+        let body = ErrorBody {
+            error: ErrorDetail {
+                code: code.to_string(),
+                message: self.0.message().to_string(),
+                field: self.0.field().map(str::to_string),
+            },
+        };
+
+        let mut response = (status, Json(body)).into_response();
+        if let ApiError::RateLimited {
+            retry_after_secs, ..
+        } = self.0
+        {
+            if let Ok(value) = HeaderValue::from_str(&retry_after_secs.to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+        }
+        response
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiV1Error>;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Tatteau API",
+        version = "1",
+        description = "Read-only access to tattoo shops, artists, styles and artist availability."
+    ),
+    paths(
+        handlers::list_locations,
+        handlers::get_location,
+        handlers::list_artists,
+        handlers::get_artist,
+        handlers::get_artist_availability,
+        handlers::list_styles,
+    ),
+    components(schemas(
+        PublicLocation,
+        PublicArtist,
+        PublicStyle,
+        PublicAvailability,
+        PublicAvailableDate,
+        PublicSlot,
+        LocationPage,
+        ArtistPage,
+        StylePage,
+        Pagination,
+        ErrorBody,
+        ErrorDetail,
+    )),
+    modifiers(&ApiKeySecurity),
+    tags(
+        (name = "locations", description = "Tattoo shops"),
+        (name = "artists", description = "Artists and when they can be booked"),
+        (name = "styles", description = "Tattoo styles artists work in"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Declares the two ways of sending an API key
+struct ApiKeySecurity;
+
+impl Modify for ApiKeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+async fn openapi_json() -> Response {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=3600")],
+        Json(ApiDoc::openapi()),
+    )
+        .into_response()
+}
+
+/// The `/api/v1` routes, to nest in the app's router
+pub fn router<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    let authenticated = Router::new()
+        .route("/locations", get(handlers::list_locations))
+        .route("/locations/:id", get(handlers::get_location))
+        .route("/artists", get(handlers::list_artists))
+        .route("/artists/:id", get(handlers::get_artist))
+        .route(
+            "/artists/:id/availability",
+            get(handlers::get_artist_availability),
+        )
+        .route("/styles", get(handlers::list_styles))
+        .layer(axum::middleware::from_fn(auth::require_api_key));
+
+    Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .merge(authenticated)
+}
@@ -0,0 +1,56 @@
+//! API keys. A key is shown once when it's issued; only its SHA-256 hash is
+//! stored, with its first few characters so operators can tell keys apart.
+
+use axum::extract::Request;
+use axum::http::{header, HeaderMap};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::ApiV1Error;
+use crate::api_error::ApiError;
+use crate::auth::{generate_token, hash_token};
+use crate::db::api_key_repository::{self, ApiKey};
+
+/// Issued keys start with this, so they're recognizable if they leak
+const KEY_PREFIX: &str = "tt_";
+/// Characters of a key kept to identify it
+const SHOWN_KEY_CHARS: usize = 10;
+
+/// Creates a key for `name`, returning it with the key itself
+pub async fn issue_api_key(name: &str) -> Result<(ApiKey, String), sqlx::Error> {
+    let key = format!("{}{}", KEY_PREFIX, generate_token());
+    let record =
+        api_key_repository::insert_key(name, &key[..SHOWN_KEY_CHARS], &hash_token(&key)).await?;
+    Ok((record, key))
+}
+
+/// The key sent as `X-Api-Key` or `Authorization: Bearer`
+fn presented_key(headers: &HeaderMap) -> Option<String> {
+    let header_value = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    header_value(header::HeaderName::from_static("x-api-key"))
+        .or_else(|| header_value(header::AUTHORIZATION)?.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+}
+
+/// Middleware turning away requests without a valid, unrevoked key
+pub async fn require_api_key(request: Request, next: Next) -> Response {
+    let Some(key) = presented_key(request.headers()) else {
+        return ApiV1Error::from(ApiError::unauthorized(
+            "Send your API key in the X-Api-Key header",
+        ))
+        .into_response();
+    };
+
+    match api_key_repository::use_key(&hash_token(&key)).await {
+        Ok(Some((key_id, name))) => {
+            tracing::debug!(key_id, name = %name, "Public API request");
+            next.run(request).await
+        }
+        Ok(None) => {
+            ApiV1Error::from(ApiError::unauthorized("Invalid or revoked API key")).into_response()
+        }
+        Err(e) => ApiV1Error::from(e).into_response(),
+    }
+}
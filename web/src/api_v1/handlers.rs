@@ -0,0 +1,237 @@
+use axum::extract::{Path, Query};
+use axum::Json;
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::{ApiResult, ErrorBody, Page, PageParams};
+use crate::api_error::ApiError;
+use crate::db::entities::{
+    PublicArtist, PublicAvailability, PublicAvailableDate, PublicLocation, PublicSlot, PublicStyle,
+};
+use crate::db::{availability_repository, public_api_repository};
+
+/// Days of availability returned when `end` isn't given
+const DEFAULT_AVAILABILITY_DAYS: i64 = 30;
+/// Longest range of availability one request can ask for
+const MAX_AVAILABILITY_DAYS: i64 = 92;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LocationFilter {
+    /// Only shops in this state, e.g. "Texas"
+    state: Option<String>,
+    /// Only shops in this city
+    city: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ArtistFilter {
+    /// Only artists at this shop
+    location_id: Option<i64>,
+    /// Only artists working in this style
+    style_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AvailabilityRange {
+    /// First date, YYYY-MM-DD; today by default
+    start: Option<String>,
+    /// Last date, YYYY-MM-DD; 30 days after `start` by default and at most 92
+    end: Option<String>,
+}
+
+fn parse_date(field: &str, value: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        ApiError::validation(field, format!("{} must be a date like 2025-06-14", field))
+    })
+}
+
+fn trimmed(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/locations",
+    tag = "locations",
+    params(PageParams, LocationFilter),
+    responses(
+        (status = 200, description = "Shops, in the order they were added", body = LocationPage),
+        (status = 400, description = "Invalid paging", body = ErrorBody),
+        (status = 401, description = "Missing or invalid API key", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+pub async fn list_locations(
+    Query(paging): Query<PageParams>,
+    Query(filter): Query<LocationFilter>,
+) -> ApiResult<Page<PublicLocation>> {
+    let (page, per_page) = paging.resolve()?;
+    let (locations, total) = public_api_repository::list_locations(
+        trimmed(&filter.state),
+        trimmed(&filter.city),
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+    Ok(Json(Page::new(locations, page, per_page, total)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/locations/{id}",
+    tag = "locations",
+    params(("id" = i64, Path, description = "Shop id")),
+    responses(
+        (status = 200, description = "The shop", body = PublicLocation),
+        (status = 401, description = "Missing or invalid API key", body = ErrorBody),
+        (status = 404, description = "No such shop", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+pub async fn get_location(Path(id): Path<i64>) -> ApiResult<PublicLocation> {
+    public_api_repository::get_location(id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("Location not found").into())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists",
+    tag = "artists",
+    params(PageParams, ArtistFilter),
+    responses(
+        (status = 200, description = "Artists, in the order they were added", body = ArtistPage),
+        (status = 400, description = "Invalid paging", body = ErrorBody),
+        (status = 401, description = "Missing or invalid API key", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+pub async fn list_artists(
+    Query(paging): Query<PageParams>,
+    Query(filter): Query<ArtistFilter>,
+) -> ApiResult<Page<PublicArtist>> {
+    let (page, per_page) = paging.resolve()?;
+    let (artists, total) = public_api_repository::list_artists(
+        filter.location_id,
+        filter.style_id,
+        per_page,
+        (page - 1) * per_page,
+    )
+    .await?;
+    Ok(Json(Page::new(artists, page, per_page, total)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists/{id}",
+    tag = "artists",
+    params(("id" = i64, Path, description = "Artist id")),
+    responses(
+        (status = 200, description = "The artist", body = PublicArtist),
+        (status = 401, description = "Missing or invalid API key", body = ErrorBody),
+        (status = 404, description = "No such artist", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+pub async fn get_artist(Path(id): Path<i64>) -> ApiResult<PublicArtist> {
+    public_api_repository::get_artist(id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("Artist not found").into())
+}
+
+/// Dates the artist can be booked on, none before today, each with the
+/// standard-length appointments still open that day
+#[utoipa::path(
+    get,
+    path = "/api/v1/artists/{id}/availability",
+    tag = "artists",
+    params(("id" = i64, Path, description = "Artist id"), AvailabilityRange),
+    responses(
+        (status = 200, description = "Bookable dates and times", body = PublicAvailability),
+        (status = 400, description = "Invalid date range", body = ErrorBody),
+        (status = 401, description = "Missing or invalid API key", body = ErrorBody),
+        (status = 404, description = "No such artist", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+pub async fn get_artist_availability(
+    Path(id): Path<i64>,
+    Query(range): Query<AvailabilityRange>,
+) -> ApiResult<PublicAvailability> {
+    let today = Utc::now().naive_utc().date();
+    let start = match trimmed(&range.start) {
+        Some(start) => parse_date("start", start)?,
+        None => today,
+    };
+    let end = match trimmed(&range.end) {
+        Some(end) => parse_date("end", end)?,
+        None => start + Duration::days(DEFAULT_AVAILABILITY_DAYS),
+    };
+    if end < start {
+        return Err(ApiError::validation("end", "end can't be before start").into());
+    }
+    if (end - start).num_days() > MAX_AVAILABILITY_DAYS {
+        return Err(ApiError::validation(
+            "end",
+            format!("Ask for at most {} days at a time", MAX_AVAILABILITY_DAYS),
+        )
+        .into());
+    }
+
+    let not_found = || ApiError::not_found("Artist not found");
+    let artist_id = i32::try_from(id).map_err(|_| not_found())?;
+    if public_api_repository::get_artist(id).await?.is_none() {
+        return Err(not_found().into());
+    }
+
+    let schedule = availability_repository::load_schedule(artist_id, start, end).await?;
+    let minutes = schedule.slot_settings.session_minutes;
+    let dates = schedule
+        .available_dates(start, end, today)
+        .into_iter()
+        .map(|date| PublicAvailableDate {
+            date: date.format("%Y-%m-%d").to_string(),
+            slots: schedule
+                .time_slots(date, minutes)
+                .into_iter()
+                .filter(|slot| slot.available)
+                .map(|slot| PublicSlot {
+                    start: slot.start.format("%H:%M").to_string(),
+                    end: slot.end.format("%H:%M").to_string(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(PublicAvailability {
+        artist_id: id,
+        start: start.format("%Y-%m-%d").to_string(),
+        end: end.format("%Y-%m-%d").to_string(),
+        dates,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/styles",
+    tag = "styles",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Styles, by name", body = StylePage),
+        (status = 400, description = "Invalid paging", body = ErrorBody),
+        (status = 401, description = "Missing or invalid API key", body = ErrorBody),
+    ),
+    security(("api_key" = []), ("bearer" = []))
+)]
+pub async fn list_styles(Query(paging): Query<PageParams>) -> ApiResult<Page<PublicStyle>> {
+    let (page, per_page) = paging.resolve()?;
+    let (styles, total) =
+        public_api_repository::list_styles(per_page, (page - 1) * per_page).await?;
+    Ok(Json(Page::new(styles, page, per_page, total)))
+}
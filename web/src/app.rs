@@ -7,29 +7,45 @@ use leptos_router::{
 use thaw::ssr::SSRMountStyleProvider;
 use thaw::*;
 
-use crate::components::{masonry_gallery::MasonryGallery, ArtistAuthGuard, ErrorBoundary, Navbar};
+use crate::components::{
+    masonry_gallery::MasonryGallery, provide_instagram_consent, ArtistAuthGuard, ErrorBoundary,
+    Navbar,
+};
+use crate::utils::auth::use_session_refresh;
+use crate::views::account::{AccountPage, VerifyContactPage};
+use crate::views::admin_artist_questions::AdminArtistQuestions;
+use crate::views::admin_booking_restores::AdminBookingRestores;
 use crate::views::admin_dashboard::AdminDashboard;
+use crate::views::admin_data_quality::AdminDataQuality;
+use crate::views::admin_errors::AdminErrors;
+use crate::views::admin_licenses::AdminLicenses;
 use crate::views::admin_login::AdminLoginPage;
 use crate::views::admin_validate_artists::AdminValidateArtists;
 use crate::views::admin_validate_posts::AdminValidatePosts;
 use crate::views::artist_dashboard::{
-    ArtistCalendar, ArtistHome, ArtistRecurring, ArtistRequests, ArtistSettings, BookingDetails,
-    QuestionnaireBuilder,
+    ArtistCalendar, ArtistHome, ArtistOnboarding, ArtistRecurring, ArtistRequests, ArtistSettings,
+    BookingDetails, QuestionnaireBuilder,
 };
 use crate::views::artist_highlight::ArtistHighlight;
 use crate::views::artist_login_prompt::ArtistLoginPrompt;
 use crate::views::auth::{LoginPage, SignupPage};
 use crate::views::booking::{ArtistBooking, ShopBooking};
 use crate::views::booking_confirmation::BookingConfirmation;
+use crate::views::client_dashboard::{BookingResponsePage, ClientBookingThread, ClientDashboard};
 use crate::views::favorites::FavoritesPage;
+use crate::views::flash::FlashPage;
 use crate::views::home::HomePage;
+use crate::views::image_detail::ImageDetailPage;
 use crate::views::map::map_wrapper::DiscoveryMap;
 use crate::views::match_results::MatchResults;
+use crate::views::my_tattoos::{MyTattoosPage, SharedTattooPage};
 use crate::views::not_found::NotFoundPage;
 use crate::views::quiz::GetMatchedQuiz;
 use crate::views::shop::Shop;
+use crate::views::shop_dashboard::ShopDashboardPage;
 use crate::views::styles::StylesShowcase;
 use crate::views::subscription_tiers::SubscriptionTiersPage;
+use crate::views::team_invite::TeamInvitePage;
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
@@ -51,8 +67,6 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
                     src="https://unpkg.com/leaflet@1.9.3/dist/leaflet.js"
                     defer
                 ></script>
-                // Add Instagram embed script
-                <script async defer src="//www.instagram.com/embed.js"></script>
                 <body>
                     <App/>
                 </body>
@@ -66,6 +80,10 @@ pub fn App() -> impl IntoView {
     // Provides context that manages stylesheets, titles, meta tags, etc.
     provide_meta_context();
 
+    // Renews the access token before it expires
+    use_session_refresh();
+    provide_instagram_consent();
+
     view! {
         // injects a stylesheet into the document <head>
         // id=leptos means cargo-leptos will hot-reload this stylesheet
@@ -89,8 +107,24 @@ pub fn App() -> impl IntoView {
                         <Route path=(StaticSegment("admin"), StaticSegment("dashboard")) view=AdminDashboard/>
                         <Route path=(StaticSegment("admin"), StaticSegment("validate-posts")) view=AdminValidatePosts/>
                         <Route path=(StaticSegment("admin"), StaticSegment("validate-artists")) view=AdminValidateArtists/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("data-quality")) view=AdminDataQuality/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("licenses")) view=AdminLicenses/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("questions")) view=AdminArtistQuestions/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("booking-restores")) view=AdminBookingRestores/>
+                        <Route path=(StaticSegment("admin"), StaticSegment("errors")) view=AdminErrors/>
+                        <Route path=StaticSegment("account") view=AccountPage/>
+                        <Route path=(StaticSegment("account"), StaticSegment("verify")) view=VerifyContactPage/>
+                        <Route path=StaticSegment("dashboard") view=ClientDashboard/>
+                        <Route path=(StaticSegment("dashboard"), StaticSegment("booking"), ParamSegment("id")) view=ClientBookingPage/>
+                        <Route path=(StaticSegment("booking"), ParamSegment("id"), StaticSegment("respond")) view=BookingResponsePage/>
                         <Route path=StaticSegment("explore") view=ExplorePage/>
                         <Route path=StaticSegment("favorites") view=FavoritesPage/>
+                        <Route path=StaticSegment("flash") view=FlashPage/>
+                        <Route path=(StaticSegment("image"), ParamSegment("id")) view=ImageDetailPage/>
+                        <Route path=StaticSegment("my-tattoos") view=MyTattoosPage/>
+                        <Route path=StaticSegment("shop-dashboard") view=ShopDashboardPage/>
+                        <Route path=(StaticSegment("tattoo"), ParamSegment("id")) view=SharedTattooPage/>
+                        <Route path=(StaticSegment("team"), StaticSegment("join"), ParamSegment("code")) view=TeamInvitePage/>
                         // <Route path=StaticSegment("artist-login-required") view=ArtistLoginPrompt/>
                         // <Route path=(StaticSegment("subscription"), StaticSegment("tiers")) view=SubscriptionTiersPage/>
                         <Route path=StaticSegment("match") view=GetMatchedQuiz/>
@@ -106,6 +140,7 @@ pub fn App() -> impl IntoView {
                         // <Route path=(StaticSegment("artist"), StaticSegment("dashboard"), StaticSegment("recurring")) view=ProtectedArtistRecurring/>
                         // <Route path=(StaticSegment("artist"), StaticSegment("dashboard"), StaticSegment("questionnaire")) view=ProtectedQuestionnaireBuilder/>
                         // <Route path=(StaticSegment("artist"), StaticSegment("dashboard"), StaticSegment("booking"), ParamSegment("id")) view=ProtectedBookingDetailsPage/>
+                        <Route path=(StaticSegment("artist"), StaticSegment("onboarding")) view=ProtectedArtistOnboarding/>
 
                         // Public artist profile pages (no authentication required)
                         <Route path=(StaticSegment("artist"), ParamSegment("id")) view=ArtistHighlight/>
@@ -165,6 +200,21 @@ fn BookingDetailsPage() -> impl IntoView {
     }
 }
 
+/// Renders one of the signed-in client's bookings
+#[component]
+fn ClientBookingPage() -> impl IntoView {
+    let params = leptos_router::hooks::use_params_map();
+    let id = params
+        .get_untracked()
+        .get("id")
+        .and_then(|id| id.parse::<i32>().ok())
+        .unwrap_or(0);
+
+    view! {
+        <ClientBookingThread booking_id=id />
+    }
+}
+
 // Protected artist dashboard components wrapped with authentication guards
 
 #[component]
@@ -176,6 +226,15 @@ fn ProtectedArtistHome() -> impl IntoView {
     }
 }
 
+#[component]
+fn ProtectedArtistOnboarding() -> impl IntoView {
+    view! {
+        <ArtistAuthGuard>
+            <ArtistOnboarding />
+        </ArtistAuthGuard>
+    }
+}
+
 #[component]
 fn ProtectedArtistCalendar() -> impl IntoView {
     view! {
@@ -0,0 +1,262 @@
+//! Cold storage for old bookings. With `ARCHIVE_BOOKINGS_AFTER_YEARS` set,
+//! the hourly `ArchiveOldBookings` job (see [`crate::jobs`]) moves completed
+//! bookings whose appointment was more than that many years ago out of the
+//! database: each batch is written with its messages and events to one gzipped JSON object under
+//! `archives/bookings/` in private storage (see [`crate::storage`]), and the
+//! bookings are left as stubs pointing at it, with the client's free text
+//! cleared. Point a lifecycle rule on that prefix at a cheaper storage class
+//! to keep them cheaply;
+//! they're only read back to restore a booking, which an artist asks for
+//! and an admin approves (see `server_archive`), or for a client's data
+//! export. Purging a client's account rewrites the archives holding their
+//! bookings with the client's details removed.
+//!
+//! Leaving the variable unset turns archiving off.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+use crate::db::archive_repository;
+use crate::storage::{private_storage, StorageError};
+
+/// Bookings written to one archive
+const BATCH_SIZE: i64 = 200;
+/// Archives written per hourly run, so catching up on a backlog is spread
+/// out
+const BATCHES_PER_RUN: usize = 5;
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("failed to compress archive: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed archive: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("booking {0} is missing from its archive")]
+    MissingFromArchive(i32),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    version: u32,
+    bookings: Vec<ArchivedBooking>,
+}
+
+/// A booking row as it was archived, with its messages and events
+#[derive(Serialize, Deserialize)]
+struct ArchivedBooking {
+    booking: serde_json::Value,
+    messages: Vec<serde_json::Value>,
+    events: Vec<serde_json::Value>,
+}
+
+fn ids<T: TryFrom<i64>>(rows: &[serde_json::Value]) -> Vec<T> {
+    rows.iter()
+        .filter_map(|row| row.get("id")?.as_i64())
+        .filter_map(|id| T::try_from(id).ok())
+        .collect()
+}
+
+fn compress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+fn decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(bytes).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+async fn read_archive(key: &str) -> Result<Archive, ArchiveError> {
+    Ok(serde_json::from_slice(&decompress(
+        &private_storage().get(key).await?,
+    )?)?)
+}
+
+fn is_client_booking(entry: &ArchivedBooking, user_id: i64) -> bool {
+    entry
+        .booking
+        .get("client_user_id")
+        .and_then(serde_json::Value::as_i64)
+        == Some(user_id)
+}
+
+/// Removes the client's details from an archived booking the way
+/// `account_repository::anonymize_client_bookings` does from a live one
+fn scrub_entry(entry: &mut ArchivedBooking, anonymized_email: &str) {
+    use serde_json::Value;
+
+    if let Some(booking) = entry.booking.as_object_mut() {
+        booking.insert("client_name".into(), "Deleted client".into());
+        booking.insert("client_email".into(), anonymized_email.into());
+        booking.insert("client_phone".into(), Value::Null);
+        booking.insert("message_from_client".into(), Value::Null);
+    }
+    for message in &mut entry.messages {
+        let Some(message) = message.as_object_mut() else {
+            continue;
+        };
+        if message.get("sender_type").and_then(Value::as_str) == Some("client") {
+            message.insert("message".into(), "[deleted]".into());
+            message.insert("translated_message".into(), Value::Null);
+        }
+    }
+}
+
+/// Rewrites every archive holding the client's bookings with their details
+/// removed, before their account is purged. Returns how many archives were
+/// rewritten.
+pub async fn scrub_client(user_id: i64) -> Result<usize, ArchiveError> {
+    let anonymized_email = crate::db::account_repository::anonymized_email(user_id);
+
+    let keys = archive_repository::get_client_archive_keys(user_id).await?;
+    for key in &keys {
+        let mut archive = read_archive(key).await?;
+        for entry in &mut archive.bookings {
+            if is_client_booking(entry, user_id) {
+                scrub_entry(entry, &anonymized_email);
+            }
+        }
+
+        let compressed = compress(&serde_json::to_vec(&archive)?)?;
+        let size_bytes = compressed.len() as i64;
+        private_storage()
+            .put(key, compressed, "application/gzip")
+            .await?;
+        archive_repository::update_archive_size(key, size_bytes).await?;
+    }
+
+    Ok(keys.len())
+}
+
+/// The client's archived bookings with their messages, for their data
+/// export, as `[{"booking": {...}, "messages": [...]}]`
+pub async fn client_archived_bookings(user_id: i64) -> Result<serde_json::Value, ArchiveError> {
+    let mut bookings = Vec::new();
+    for key in archive_repository::get_client_archive_keys(user_id).await? {
+        let archive = read_archive(&key).await?;
+        bookings.extend(
+            archive
+                .bookings
+                .into_iter()
+                .filter(|entry| is_client_booking(entry, user_id))
+                .map(|entry| {
+                    serde_json::json!({
+                        "booking": entry.booking,
+                        "messages": entry.messages,
+                    })
+                }),
+        );
+    }
+    Ok(bookings.into())
+}
+
+/// Archives one batch, returning how many bookings were archived
+async fn archive_batch(booking_ids: &[i32]) -> Result<usize, ArchiveError> {
+    let bookings = archive_repository::export_bookings(booking_ids)
+        .await?
+        .iter()
+        .map(|entry| serde_json::from_str::<ArchivedBooking>(entry))
+        .collect::<Result<Vec<_>, _>>()?;
+    let message_ids: Vec<i32> = bookings
+        .iter()
+        .flat_map(|booking| ids(&booking.messages))
+        .collect();
+    let event_ids: Vec<i64> = bookings
+        .iter()
+        .flat_map(|booking| ids(&booking.events))
+        .collect();
+
+    let archive = Archive {
+        version: ARCHIVE_VERSION,
+        bookings,
+    };
+    let compressed = compress(&serde_json::to_vec(&archive)?)?;
+    let size_bytes = compressed.len() as i64;
+
+    let key = format!("archives/bookings/{}.json.gz", uuid::Uuid::new_v4());
+    private_storage()
+        .put(&key, compressed, "application/gzip")
+        .await?;
+
+    let archived = match archive_repository::record_archive(
+        &key,
+        size_bytes,
+        booking_ids,
+        &message_ids,
+        &event_ids,
+    )
+    .await
+    {
+        Ok(archived) => archived,
+        Err(e) => {
+            if let Err(delete_error) = private_storage().delete(&key).await {
+                tracing::warn!(key, "Failed to delete unrecorded archive: {}", delete_error);
+            }
+            return Err(e.into());
+        }
+    };
+    // Another instance got to all of them first
+    if archived.is_empty() {
+        private_storage().delete(&key).await?;
+    }
+
+    Ok(archived.len())
+}
+
+/// Moves completed bookings past the retention age into archives. Returns
+/// how many were archived; nothing happens unless
+/// `ARCHIVE_BOOKINGS_AFTER_YEARS` is set.
+pub async fn archive_old_bookings() -> Result<usize, ArchiveError> {
+    let Some(years) = crate::config::config().archive_bookings_after_years else {
+        return Ok(0);
+    };
+
+    let mut archived = 0;
+    for _ in 0..BATCHES_PER_RUN {
+        let booking_ids = archive_repository::get_archivable_bookings(years, BATCH_SIZE).await?;
+        if booking_ids.is_empty() {
+            break;
+        }
+        archived += archive_batch(&booking_ids).await?;
+        if (booking_ids.len() as i64) < BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(archived)
+}
+
+/// Brings an archived booking back from its archive: its free text,
+/// messages and events. Restoring one that's already back does nothing.
+pub async fn restore_booking(booking_id: i32) -> Result<(), ArchiveError> {
+    let Some(key) = archive_repository::get_archive_key(booking_id).await? else {
+        return Ok(());
+    };
+
+    let archive = read_archive(&key).await?;
+    let archived = archive
+        .bookings
+        .into_iter()
+        .find(|entry| entry.booking.get("id") == Some(&booking_id.into()))
+        .ok_or(ArchiveError::MissingFromArchive(booking_id))?;
+
+    let restored = archive_repository::restore_booking(
+        booking_id,
+        &archived.booking.to_string(),
+        &serde_json::Value::from(archived.messages).to_string(),
+        &serde_json::Value::from(archived.events).to_string(),
+    )
+    .await?;
+    if restored {
+        tracing::info!(booking_id, key, "Restored archived booking");
+    }
+
+    Ok(())
+}
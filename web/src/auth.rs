@@ -0,0 +1,208 @@
+//! Session tokens. Signing in issues a short-lived JWT access token, sent
+//! with requests, and a long-lived refresh token that renews it. Refresh
+//! tokens are random strings stored server-side as SHA-256 hashes, so they
+//! can be revoked; each one is good for a single refresh, which rotates it.
+//! A token's successor is derived from it with the signing key, so tabs
+//! sharing one token that refresh at the same moment all get the same
+//! successor instead of one of them tripping reuse detection.
+//!
+//! Settings (see [`crate::config`]): `JWT_SECRET` (required in release
+//! builds), `ACCESS_TOKEN_TTL_MINUTES` (default 15) and
+//! `REFRESH_TOKEN_TTL_DAYS` (default 30).
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+use crate::db::refresh_token_repository;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("token generation failed: {0}")]
+    Token(#[from] jsonwebtoken::errors::Error),
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: usize,
+    /// "client", "artist" or "admin"
+    pub user_type: String,
+    pub user_id: i64,
+}
+
+/// A newly issued token pair
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub user_id: i64,
+    pub user_type: String,
+}
+
+struct AuthConfig {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    /// Also signs links, like export downloads
+    secret: Vec<u8>,
+    access_ttl: chrono::Duration,
+    refresh_ttl: chrono::Duration,
+}
+
+static CONFIG: OnceLock<AuthConfig> = OnceLock::new();
+
+impl AuthConfig {
+    fn new(config: &crate::config::Config) -> Self {
+        let secret = config.jwt_secret.as_bytes();
+
+        AuthConfig {
+            encoding_key: EncodingKey::from_secret(secret),
+            decoding_key: DecodingKey::from_secret(secret),
+            secret: secret.to_vec(),
+            access_ttl: config.access_token_ttl,
+            refresh_ttl: config.refresh_token_ttl,
+        }
+    }
+}
+
+fn config() -> &'static AuthConfig {
+    CONFIG.get_or_init(|| AuthConfig::new(crate::config::config()))
+}
+
+pub fn issue_access_token(user_id: i64, user_type: &str) -> Result<String, AuthError> {
+    let exp = (chrono::Utc::now() + config().access_ttl).timestamp() as usize;
+    let claims = Claims {
+        sub: user_id.to_string(),
+        exp,
+        user_type: user_type.to_string(),
+        user_id,
+    };
+
+    Ok(encode(&Header::default(), &claims, &config().encoding_key)?)
+}
+
+/// The claims of a valid, unexpired access token
+pub fn decode_access_token(token: &str) -> Option<Claims> {
+    decode::<Claims>(token, &config().decoding_key, &Validation::default())
+        .ok()
+        .map(|data| data.claims)
+}
+
+/// Opaque tokens, like refresh tokens, are stored as this hash
+pub(crate) fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// A random, URL-safe token
+pub(crate) fn generate_token() -> String {
+    let mut bytes = Vec::with_capacity(32);
+    bytes.extend_from_slice(uuid::Uuid::new_v4().as_bytes());
+    bytes.extend_from_slice(uuid::Uuid::new_v4().as_bytes());
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn signing_mac(message: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(&config().secret).expect("HMAC accepts keys of any length");
+    mac.update(message.as_bytes());
+    mac
+}
+
+/// A URL-safe HMAC-SHA256 signature of `message`, for links that must work
+/// without a session
+pub(crate) fn sign(message: &str) -> String {
+    URL_SAFE_NO_PAD.encode(signing_mac(message).finalize().into_bytes())
+}
+
+/// Whether `signature` is [`sign`] of `message`, compared in constant time
+pub(crate) fn verify_signature(message: &str, signature: &str) -> bool {
+    URL_SAFE_NO_PAD
+        .decode(signature)
+        .is_ok_and(|signature| signing_mac(message).verify_slice(&signature).is_ok())
+}
+
+/// Issues an access token and a stored refresh token for a signed-in user
+pub async fn start_session(user_id: i64, user_type: &str) -> Result<Session, AuthError> {
+    let refresh_token = generate_token();
+    let expires_at = chrono::Utc::now() + config().refresh_ttl;
+    refresh_token_repository::insert_token(
+        user_id,
+        &hash_token(&refresh_token),
+        expires_at,
+    )
+    .await?;
+
+    Ok(Session {
+        access_token: issue_access_token(user_id, user_type)?,
+        refresh_token,
+        user_id,
+        user_type: user_type.to_string(),
+    })
+}
+
+/// The refresh token that replaces `refresh_token` when it's rotated
+fn successor_token(refresh_token: &str) -> String {
+    sign(&format!("refresh-rotation:{}", refresh_token))
+}
+
+/// Exchanges a refresh token for a new session, revoking it. Returns `None`
+/// for unknown, expired or revoked tokens and for deactivated users. A
+/// token rotated within the last minute gets the successor it was rotated
+/// to, for other tabs that refreshed with it at the same time. A revoked
+/// token being presented again after that means it was copied, so every
+/// session of its user is revoked.
+pub async fn refresh_session(refresh_token: &str) -> Result<Option<Session>, AuthError> {
+    let token_hash = hash_token(refresh_token);
+    let successor = successor_token(refresh_token);
+    let successor_hash = hash_token(&successor);
+    let expires_at = chrono::Utc::now() + config().refresh_ttl;
+
+    let rotated =
+        refresh_token_repository::rotate_token(&token_hash, &successor_hash, expires_at).await?;
+    let rotated = match rotated {
+        Some(user_id) => Some(user_id),
+        None => {
+            refresh_token_repository::get_recently_rotated_user(&token_hash, &successor_hash)
+                .await?
+        }
+    };
+
+    let Some(user_id) = rotated else {
+        let reused = refresh_token_repository::get_revoked_token_user(&token_hash).await?;
+        if let Some(user_id) = reused {
+            let revoked = refresh_token_repository::revoke_all_for_user(user_id).await?;
+            tracing::warn!(
+                user_id,
+                revoked,
+                "Revoked refresh token reused; signed out all sessions"
+            );
+        }
+        return Ok(None);
+    };
+
+    match refresh_token_repository::get_active_user_role(user_id).await? {
+        Some(role) => Ok(Some(Session {
+            access_token: issue_access_token(user_id, &role)?,
+            refresh_token: successor,
+            user_id,
+            user_type: role,
+        })),
+        None => Ok(None),
+    }
+}
+
+/// Revokes a refresh token, returning the user it belonged to
+pub async fn revoke_refresh_token(refresh_token: &str) -> Result<Option<i64>, AuthError> {
+    Ok(refresh_token_repository::revoke_token(&hash_token(refresh_token)).await?)
+}
+
+/// Revokes every refresh token of the user, signing out all their devices
+/// once their access tokens expire
+pub async fn revoke_all_sessions(user_id: i64) -> Result<u64, AuthError> {
+    Ok(refresh_token_repository::revoke_all_for_user(user_id).await?)
+}
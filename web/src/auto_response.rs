@@ -0,0 +1,81 @@
+//! Sends artists' automatic first replies to new booking requests. Requests
+//! get an `auto_response_due_at` when they're submitted (see
+//! `submit_booking_request`); [`send_due_auto_responses`] runs every minute
+//! as a scheduled job (see `jobs`), and straight after submission for
+//! artists with no delay, from the `booking_created` hook (see `hooks`).
+//! A request the artist has answered by then is skipped.
+
+use crate::db::auto_response_repository::{self, DueAutoResponse};
+use crate::db::entities::BookingEventKind;
+use crate::utils::auto_response::{render, TemplateValues};
+use crate::utils::response_time::{response_window, WINDOW_DAYS};
+use crate::utils::timezone::{convert_to_12_hour_format, format_date_for_booking};
+
+/// Replies sent per run
+const BATCH_SIZE: i64 = 50;
+
+/// Sends every auto-response that's due, returning how many went out
+pub async fn send_due_auto_responses() -> Result<usize, sqlx::Error> {
+    let due = auto_response_repository::claim_due(BATCH_SIZE).await?;
+    let mut sent = 0;
+
+    for reply in due {
+        let booking_id = reply.booking_id;
+        match send(reply).await {
+            Ok(true) => sent += 1,
+            Ok(false) => {}
+            Err(e) => tracing::error!(booking_id, "Failed to send auto-response: {}", e),
+        }
+    }
+
+    Ok(sent)
+}
+
+async fn send(reply: DueAutoResponse) -> Result<bool, sqlx::Error> {
+    // The artist may have removed the template since the request came in
+    let Some(template) =
+        auto_response_repository::get_template(reply.artist_id, &reply.booking_type).await?
+    else {
+        return Ok(false);
+    };
+
+    let pricing = crate::db::pricing_repository::get_pricing(reply.artist_id).await?;
+    let response_time =
+        crate::db::response_time_repository::get_response_stats(&[reply.artist_id], WINDOW_DAYS)
+            .await?
+            .into_iter()
+            .next()
+            .and_then(|stats| response_window(stats.average_hours, stats.responded_count))
+            .map(str::to_string);
+
+    let message = render(
+        &template,
+        &TemplateValues {
+            client_name: reply.client_name,
+            artist_name: reply.artist_name.unwrap_or_default(),
+            booking_type: reply.booking_type,
+            requested_date: format_date_for_booking(&reply.requested_date),
+            requested_time: convert_to_12_hour_format(&reply.requested_start_time),
+            minimum_charge: pricing.minimum_charge,
+            hourly_rate: pricing.hourly_rate,
+            response_time,
+        },
+    );
+
+    let translation =
+        crate::translate::translate_booking_message(reply.booking_id, "artist", &message).await;
+    let sent =
+        auto_response_repository::insert_message(reply.booking_id, &message, &translation).await?;
+    crate::server::record_booking_event(
+        reply.booking_id,
+        "system",
+        BookingEventKind::Message {
+            sender_type: sent.sender_type.clone(),
+            message: sent.message.clone(),
+        },
+    )
+    .await;
+    crate::message_stream::publish(sent);
+
+    Ok(true)
+}
@@ -0,0 +1,284 @@
+// Booking Smoke Test
+// Drives the booking lifecycle against a running instance through the same
+// server-fn HTTP endpoints the browser uses: artist and client signup,
+// availability, booking request, artist approval and a message exchange.
+//
+// Usage: SMOKE_BASE_URL=https://tatteau.example cargo run --bin booking_smoke --features ssr
+//
+// Each run creates a throwaway artist, client and booking using
+// smoke+<timestamp>@example.com addresses.
+
+use leptos::server_fn::ServerFn;
+use serde::{de::DeserializeOwned, Serialize};
+use shared_types::datetime::{Date, Time};
+use shared_types::BookingStatus;
+use std::env;
+use std::time::Instant;
+use web::db::entities::{AvailabilityUpdate, BookingMessage, BookingRequest};
+use web::server::{
+    AuthResponse, BookingResponse, GetArtistAvailability, GetArtistIdFromJwtUserId,
+    GetBookingMessages, GetBookingRequestById, NewBookingMessage, NewBookingRequest,
+    RespondToBooking, SendBookingMessage, SetArtistAvailability, SignupUser, SubmitBookingRequest,
+};
+use web::server_client_dashboard::SendMyBookingMessage;
+use web::views::auth::SignupData;
+
+type SmokeResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+struct SmokeClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl SmokeClient {
+    /// Posts a server fn's arguments the way the browser client does
+    /// (url-encoded body) and decodes its JSON response.
+    async fn call<S>(&self, args: S) -> SmokeResult<S::Output>
+    where
+        S: ServerFn + Serialize,
+        S::Output: DeserializeOwned,
+    {
+        let url = format!("{}{}", self.base_url, S::PATH);
+        let response = self
+            .http
+            .post(&url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("Accept", "application/json")
+            .body(serde_qs::to_string(&args)?)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(format!("{} returned {}: {}", S::PATH, status, body).into());
+        }
+
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+async fn step<T>(
+    name: &str,
+    fut: impl std::future::Future<Output = SmokeResult<T>>,
+) -> SmokeResult<T> {
+    let started = Instant::now();
+    match fut.await {
+        Ok(value) => {
+            println!("✅ {} ({} ms)", name, started.elapsed().as_millis());
+            Ok(value)
+        }
+        Err(e) => {
+            println!("❌ {} ({} ms): {}", name, started.elapsed().as_millis(), e);
+            Err(e)
+        }
+    }
+}
+
+fn ensure(condition: bool, message: &str) -> SmokeResult<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(message.into())
+    }
+}
+
+async fn signup(client: &SmokeClient, email: &str, user_type: &str) -> SmokeResult<AuthResponse> {
+    let auth = client
+        .call(SignupUser {
+            signup_data: SignupData {
+                first_name: "Smoke".to_string(),
+                last_name: format!("Test {}", user_type),
+                email: email.to_string(),
+                phone: None,
+                password: format!("smoke-{}", chrono::Utc::now().timestamp_millis()),
+                user_type: user_type.to_string(),
+            },
+        })
+        .await?;
+
+    ensure(
+        auth.success && auth.token.is_some(),
+        &auth
+            .error
+            .clone()
+            .unwrap_or_else(|| "signup failed".to_string()),
+    )?;
+    Ok(auth)
+}
+
+async fn run(client: &SmokeClient) -> SmokeResult<()> {
+    let run_id = chrono::Utc::now().timestamp();
+    let artist_email = format!("smoke+artist{}@example.com", run_id);
+    let client_email = format!("smoke+client{}@example.com", run_id);
+    let booking_date = Date::from((chrono::Utc::now() + chrono::Duration::days(14)).date_naive());
+    let time = |hour| Time::from_hm(hour, 0).expect("hour of the day");
+
+    let artist_auth = step("artist signup", signup(client, &artist_email, "artist")).await?;
+    let artist_user_id = artist_auth
+        .user_id
+        .ok_or("artist signup returned no user id")?;
+    let artist_token = artist_auth
+        .token
+        .clone()
+        .ok_or("artist signup returned no token")?;
+
+    let artist_id = step("resolve artist id", async {
+        client
+            .call(GetArtistIdFromJwtUserId {
+                jwt_user_id: artist_user_id,
+            })
+            .await?
+            .ok_or_else(|| "no artist linked to new user".into())
+    })
+    .await?;
+
+    step("set availability", async {
+        client
+            .call(SetArtistAvailability {
+                availability: AvailabilityUpdate {
+                    artist_id,
+                    date: Some(booking_date),
+                    day_of_week: None,
+                    start_time: Some(time(10)),
+                    end_time: Some(time(16)),
+                    is_available: true,
+                    is_recurring: false,
+                },
+                token: artist_token.clone(),
+            })
+            .await
+    })
+    .await?;
+
+    step("fetch availability", async {
+        let slots = client
+            .call(GetArtistAvailability {
+                artist_id,
+                start_date: booking_date.to_string(),
+                end_date: booking_date.to_string(),
+            })
+            .await?;
+        ensure(!slots.is_empty(), "availability slot was not returned")
+    })
+    .await?;
+
+    let client_auth = step("client signup", signup(client, &client_email, "client")).await?;
+    let client_token = client_auth
+        .token
+        .clone()
+        .ok_or("client signup returned no token")?;
+
+    let booking_id = step("submit booking request", async {
+        client
+            .call(SubmitBookingRequest {
+                request: NewBookingRequest {
+                    artist_id,
+                    client_name: "Smoke Test client".to_string(),
+                    client_email: client_email.clone(),
+                    client_phone: None,
+                    tattoo_description: Some("Smoke test booking".to_string()),
+                    placement: Some("forearm".to_string()),
+                    size_inches: Some(3.0),
+                    requested_date: booking_date,
+                    requested_start_time: time(11),
+                    requested_end_time: Some(time(13)),
+                    message_from_client: Some("Automated smoke test".to_string()),
+                    allow_duplicate: false,
+                    reference_upload_ids: Vec::new(),
+                    booking_type: None,
+                    flash_design_id: None,
+                },
+                token: Some(client_token.clone()),
+            })
+            .await
+    })
+    .await?;
+
+    step("artist approves booking", async {
+        client
+            .call(RespondToBooking {
+                response: BookingResponse {
+                    booking_id,
+                    status: BookingStatus::Accepted,
+                    artist_response: Some("See you then".to_string()),
+                    estimated_price: Some(200.0),
+                    decline_reason: None,
+                    deposit_amount: None,
+                },
+                token: artist_token.clone(),
+            })
+            .await?;
+
+        let booking: BookingRequest = client
+            .call(GetBookingRequestById {
+                booking_id,
+                token: artist_token.clone(),
+            })
+            .await?;
+        ensure(
+            booking.status == BookingStatus::Accepted.as_str(),
+            &format!("booking status is {}", booking.status),
+        )
+    })
+    .await?;
+
+    step("exchange messages", async {
+        // Clients message through their own dashboard endpoint
+        client
+            .call(SendMyBookingMessage {
+                token: client_token.clone(),
+                booking_id,
+                message: "Can I bring a reference?".to_string(),
+            })
+            .await?;
+        client
+            .call(SendBookingMessage {
+                message_data: NewBookingMessage {
+                    booking_request_id: booking_id,
+                    sender_type: "artist".to_string(),
+                    message: "Yes, bring it along.".to_string(),
+                },
+                token: artist_token.clone(),
+            })
+            .await?;
+
+        let messages: Vec<BookingMessage> = client
+            .call(GetBookingMessages {
+                booking_request_id: booking_id,
+                token: artist_token.clone(),
+            })
+            .await?;
+        ensure(
+            messages.len() == 2,
+            &format!("expected 2 messages, got {}", messages.len()),
+        )
+    })
+    .await?;
+
+    println!(
+        "\nBooking {} for artist {} completed the full lifecycle",
+        booking_id, artist_id
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let base_url = env::var("SMOKE_BASE_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:3000".to_string())
+        .trim_end_matches('/')
+        .to_string();
+
+    println!("Running booking smoke test against {}\n", base_url);
+
+    let client = SmokeClient {
+        http: reqwest::Client::new(),
+        base_url,
+    };
+
+    if let Err(e) = run(&client).await {
+        eprintln!("\nSmoke test failed: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,669 @@
+// Load Test
+// Replays a realistic mix of map, gallery and booking traffic against a
+// running instance through the same server-fn HTTP endpoints the browser
+// uses, at a fixed request rate, then reports latency percentiles per
+// endpoint. Meant for checking the map caching and gallery pagination work
+// holds up, not for production: booking traffic creates real requests.
+//
+// Usage: LOAD_BASE_URL=https://staging.tatteau.example cargo run --release --bin load_test --features ssr
+//
+// Settings (environment):
+//   LOAD_BASE_URL        target, default http://127.0.0.1:3000
+//   LOAD_RPS             requests started per second, default 10
+//   LOAD_DURATION_SECS   how long to send for, default 60
+//   LOAD_USERS           simulated visitors whose sessions interleave, default 20
+//   LOAD_MIX             scenario weights, default "map=60,gallery=35,booking=5"
+//   LOAD_ARTIST_ID       artist that booking requests go to; booking traffic
+//                        is skipped without one
+//   LOAD_LOCATION_IDS    shops to page through, comma separated; default is
+//                        whatever the first map requests return
+//   LOAD_SEED            makes the generated traffic repeatable
+//   LOAD_MAX_P95_MS      exit non-zero if any endpoint's p95 is slower
+//   LOAD_DRY_RUN=true    print the first requests instead of sending them
+
+use leptos::server_fn::ServerFn;
+use serde::{de::DeserializeOwned, Serialize};
+use shared_types::datetime::Time;
+use shared_types::{LatLong, LocationPin, MapBounds};
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use web::server::{
+    FetchShopImagesCompact, FetchShopImagesPaginated, GetLocationPins, GetLocationsWithDetails,
+    NewBookingRequest, SubmitBookingRequest,
+};
+
+type LoadResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Where map sessions start: (state, city, lat, long)
+const MAP_CENTERS: &[(&str, &str, f64, f64)] = &[
+    ("Texas", "Austin", 30.2672, -97.7431),
+    ("California", "Los Angeles", 34.0522, -118.2437),
+    ("New York", "New York", 40.7128, -74.0060),
+    ("Illinois", "Chicago", 41.8781, -87.6298),
+    ("Washington", "Seattle", 47.6062, -122.3321),
+    ("Colorado", "Denver", 39.7392, -104.9903),
+    ("Florida", "Miami", 25.7617, -80.1918),
+    ("Oregon", "Portland", 45.5152, -122.6784),
+];
+
+/// Gallery page size, as the shop page requests it
+const PER_PAGE: i32 = 20;
+
+/// Small deterministic generator, so a seed reproduces a run's traffic
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        // xorshift64*
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in `[0, 1)`
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + self.unit() * (high - low)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        self.unit() < p
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Scenario {
+    Map,
+    Gallery,
+    Booking,
+}
+
+impl Scenario {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "map" => Some(Scenario::Map),
+            "gallery" => Some(Scenario::Gallery),
+            "booking" => Some(Scenario::Booking),
+            _ => None,
+        }
+    }
+}
+
+/// One HTTP call a simulated visitor makes
+#[derive(Clone, Debug)]
+enum Request {
+    MapDetails {
+        state: String,
+        city: String,
+        bounds: MapBounds,
+    },
+    MapPins {
+        state: String,
+        city: String,
+        bounds: MapBounds,
+    },
+    Gallery {
+        location_id: i32,
+        page: i32,
+        compact: bool,
+    },
+    Booking(Box<NewBookingRequest>),
+}
+
+impl Request {
+    /// Name latencies are reported under
+    fn endpoint(&self) -> &'static str {
+        match self {
+            Request::MapDetails { .. } => "map: locations_with_details",
+            Request::MapPins { .. } => "map: location_pins",
+            Request::Gallery { compact: false, .. } => "gallery: shop images",
+            Request::Gallery { compact: true, .. } => "gallery: shop images (compact)",
+            Request::Booking(_) => "booking: submit request",
+        }
+    }
+}
+
+struct Config {
+    base_url: String,
+    rps: f64,
+    duration: Duration,
+    users: usize,
+    mix: Vec<(Scenario, u32)>,
+    artist_id: Option<i32>,
+    location_ids: Vec<i32>,
+    seed: u64,
+    max_p95_ms: Option<u128>,
+    dry_run: bool,
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+impl Config {
+    fn from_env() -> LoadResult<Self> {
+        let mix_spec =
+            env::var("LOAD_MIX").unwrap_or_else(|_| "map=60,gallery=35,booking=5".into());
+        let mut mix = Vec::new();
+        for part in mix_spec.split(',').filter(|part| !part.trim().is_empty()) {
+            let (name, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("LOAD_MIX entry {:?} isn't name=weight", part))?;
+            let scenario = Scenario::parse(name.trim())
+                .ok_or_else(|| format!("Unknown scenario {:?} in LOAD_MIX", name.trim()))?;
+            mix.push((scenario, weight.trim().parse::<u32>()?));
+        }
+
+        let artist_id = env::var("LOAD_ARTIST_ID")
+            .ok()
+            .and_then(|id| id.trim().parse().ok());
+        if artist_id.is_none() && mix.iter().any(|(s, w)| *s == Scenario::Booking && *w > 0) {
+            println!("LOAD_ARTIST_ID not set, skipping booking traffic");
+            mix.retain(|(scenario, _)| *scenario != Scenario::Booking);
+        }
+        if mix.iter().all(|(_, weight)| *weight == 0) {
+            return Err("LOAD_MIX leaves nothing to send".into());
+        }
+
+        let rps: f64 = env_or("LOAD_RPS", 10.0);
+        if rps.is_nan() || rps <= 0.0 {
+            return Err("LOAD_RPS must be above 0".into());
+        }
+
+        Ok(Config {
+            base_url: env::var("LOAD_BASE_URL")
+                .unwrap_or_else(|_| "http://127.0.0.1:3000".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            rps,
+            duration: Duration::from_secs(env_or("LOAD_DURATION_SECS", 60)),
+            users: env_or("LOAD_USERS", 20usize).max(1),
+            mix,
+            artist_id,
+            location_ids: env::var("LOAD_LOCATION_IDS")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|id| id.trim().parse().ok())
+                .collect(),
+            seed: env_or("LOAD_SEED", chrono::Utc::now().timestamp_millis() as u64) | 1,
+            max_p95_ms: env::var("LOAD_MAX_P95_MS")
+                .ok()
+                .and_then(|ms| ms.trim().parse().ok()),
+            dry_run: env::var("LOAD_DRY_RUN").is_ok_and(|value| value == "true"),
+        })
+    }
+}
+
+/// Builds visitor sessions: a run of map pans and zooms around a city, a few
+/// pages of one shop's gallery, or a single booking request
+struct Generator {
+    rng: Rng,
+    mix: Vec<(Scenario, u32)>,
+    artist_id: Option<i32>,
+    location_ids: Vec<i32>,
+    bookings: u64,
+}
+
+impl Generator {
+    fn pick_scenario(&mut self) -> Scenario {
+        let total: u32 = self.mix.iter().map(|(_, weight)| weight).sum();
+        let mut roll = self.rng.below(total as usize) as u32;
+        for (scenario, weight) in &self.mix {
+            if roll < *weight {
+                return *scenario;
+            }
+            roll -= weight;
+        }
+        Scenario::Map
+    }
+
+    fn session(&mut self) -> Vec<Request> {
+        match self.pick_scenario() {
+            Scenario::Gallery if !self.location_ids.is_empty() => self.gallery_session(),
+            Scenario::Booking if self.artist_id.is_some() => vec![self.booking()],
+            _ => self.map_session(),
+        }
+    }
+
+    /// Lands on a city, then pans and zooms. Mobile visitors (about a third)
+    /// get the compact pins endpoint.
+    fn map_session(&mut self) -> Vec<Request> {
+        let (state, city, lat, long) = MAP_CENTERS[self.rng.below(MAP_CENTERS.len())];
+        let compact = self.rng.chance(0.35);
+        let mut center = LatLong { lat, long };
+        // Degrees of latitude shown, roughly a city-level zoom
+        let mut span = self.rng.range(0.08, 0.3);
+
+        let moves = 1 + self.rng.below(6);
+        (0..moves)
+            .map(|step| {
+                if step > 0 {
+                    if self.rng.chance(0.25) {
+                        span *= if self.rng.chance(0.5) { 0.5 } else { 2.0 };
+                        span = span.clamp(0.02, 2.0);
+                    } else {
+                        center.lat += self.rng.range(-0.4, 0.4) * span;
+                        center.long += self.rng.range(-0.4, 0.4) * span;
+                    }
+                }
+                let bounds = MapBounds {
+                    north_east: LatLong {
+                        lat: center.lat + span / 2.0,
+                        long: center.long + span,
+                    },
+                    south_west: LatLong {
+                        lat: center.lat - span / 2.0,
+                        long: center.long - span,
+                    },
+                };
+                let (state, city) = (state.to_string(), city.to_string());
+                if compact {
+                    Request::MapPins {
+                        state,
+                        city,
+                        bounds,
+                    }
+                } else {
+                    Request::MapDetails {
+                        state,
+                        city,
+                        bounds,
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Scrolls from the first page of a shop's gallery, stopping early more
+    /// often than not
+    fn gallery_session(&mut self) -> Vec<Request> {
+        let location_id = self.location_ids[self.rng.below(self.location_ids.len())];
+        let compact = self.rng.chance(0.35);
+        let mut pages = 1;
+        while pages < 8 && self.rng.chance(0.55) {
+            pages += 1;
+        }
+        (0..pages)
+            .map(|page| Request::Gallery {
+                location_id,
+                page,
+                compact,
+            })
+            .collect()
+    }
+
+    fn booking(&mut self) -> Request {
+        self.bookings += 1;
+        let date = chrono::Utc::now() + chrono::Duration::days(30 + self.rng.below(60) as i64);
+        let hour = 10 + self.rng.below(6);
+        Request::Booking(Box::new(NewBookingRequest {
+            artist_id: self.artist_id.unwrap_or_default(),
+            client_name: format!("Load Test {}", self.bookings),
+            client_email: format!(
+                "load+{}-{}@example.com",
+                self.rng.next() % 100_000,
+                self.bookings
+            ),
+            client_phone: None,
+            tattoo_description: Some("Load test booking".to_string()),
+            placement: Some("forearm".to_string()),
+            size_inches: Some(self.rng.range(2.0, 8.0) as f32),
+            requested_date: date.date_naive().into(),
+            requested_start_time: Time::from_hm(hour as u32, 0).expect("hour of the day"),
+            requested_end_time: Time::from_hm(hour as u32 + 2, 0),
+            message_from_client: Some("Automated load test".to_string()),
+            allow_duplicate: true,
+            reference_upload_ids: Vec::new(),
+            booking_type: None,
+            flash_design_id: None,
+        }))
+    }
+}
+
+struct LoadClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl LoadClient {
+    /// Posts a server fn's arguments url-encoded, as the browser client does
+    async fn post<S>(&self, args: S) -> LoadResult<S::Output>
+    where
+        S: ServerFn + Serialize,
+        S::Output: DeserializeOwned,
+    {
+        let url = format!("{}{}", self.base_url, S::PATH);
+        let request = self
+            .http
+            .post(&url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(serde_qs::to_string(&args)?);
+        Self::send::<S>(request).await
+    }
+
+    /// For server fns declared with `input = GetUrl`, which are what the HTTP
+    /// cache in front of the map endpoints sees
+    async fn get<S>(&self, args: S) -> LoadResult<S::Output>
+    where
+        S: ServerFn + Serialize,
+        S::Output: DeserializeOwned,
+    {
+        let url = format!(
+            "{}{}?{}",
+            self.base_url,
+            S::PATH,
+            serde_qs::to_string(&args)?
+        );
+        Self::send::<S>(self.http.get(&url)).await
+    }
+
+    async fn send<S>(request: reqwest::RequestBuilder) -> LoadResult<S::Output>
+    where
+        S: ServerFn,
+        S::Output: DeserializeOwned,
+    {
+        let response = request.header("Accept", "application/json").send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(format!("{} returned {}", S::PATH, status).into());
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    async fn execute(&self, request: Request) -> LoadResult<()> {
+        match request {
+            Request::MapDetails {
+                state,
+                city,
+                bounds,
+            } => {
+                self.get(GetLocationsWithDetails {
+                    state,
+                    city,
+                    bounds,
+                    style_filter: None,
+                    radius: None,
+                    experience_tiers: None,
+                })
+                .await?;
+            }
+            Request::MapPins {
+                state,
+                city,
+                bounds,
+            } => {
+                self.get(GetLocationPins {
+                    state,
+                    city,
+                    bounds,
+                    style_filter: None,
+                    radius: None,
+                    experience_tiers: None,
+                })
+                .await?;
+            }
+            Request::Gallery {
+                location_id,
+                page,
+                compact: true,
+            } => {
+                self.post(FetchShopImagesCompact {
+                    location_id,
+                    style_filter: None,
+                    page,
+                    per_page: PER_PAGE,
+                    token: None,
+                })
+                .await?;
+            }
+            Request::Gallery {
+                location_id,
+                page,
+                compact: false,
+            } => {
+                self.post(FetchShopImagesPaginated {
+                    location_id,
+                    style_filter: None,
+                    page,
+                    per_page: PER_PAGE,
+                    token: None,
+                })
+                .await?;
+            }
+            Request::Booking(request) => {
+                self.post(SubmitBookingRequest {
+                    request: *request,
+                    token: None,
+                })
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shops to page through when none were given: those around the first few
+/// map centers that have images
+async fn discover_locations(client: &LoadClient) -> Vec<i32> {
+    let mut ids = Vec::new();
+    for (state, city, lat, long) in MAP_CENTERS.iter().take(4) {
+        let pins: LoadResult<Vec<LocationPin>> = client
+            .get(GetLocationPins {
+                state: state.to_string(),
+                city: city.to_string(),
+                bounds: MapBounds {
+                    north_east: LatLong {
+                        lat: lat + 0.25,
+                        long: long + 0.5,
+                    },
+                    south_west: LatLong {
+                        lat: lat - 0.25,
+                        long: long - 0.5,
+                    },
+                },
+                style_filter: None,
+                radius: None,
+                experience_tiers: None,
+            })
+            .await;
+        match pins {
+            Ok(pins) => ids.extend(
+                pins.into_iter()
+                    .filter(|pin| pin.image_count > 0)
+                    .map(|pin| pin.id),
+            ),
+            Err(e) => println!("Couldn't load shops around {}: {}", city, e),
+        }
+    }
+    ids.truncate(200);
+    ids
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+struct Outcome {
+    endpoint: &'static str,
+    latency: Duration,
+    error: Option<String>,
+}
+
+/// Prints the per-endpoint table, returning the slowest p95
+fn report(outcomes: &[Outcome], elapsed: Duration) -> Duration {
+    let mut by_endpoint: BTreeMap<&str, (Vec<Duration>, usize)> = BTreeMap::new();
+    for outcome in outcomes {
+        let entry = by_endpoint.entry(outcome.endpoint).or_default();
+        entry.0.push(outcome.latency);
+        if outcome.error.is_some() {
+            entry.1 += 1;
+        }
+    }
+
+    println!(
+        "\n{:<34} {:>7} {:>7} {:>8} {:>8} {:>8} {:>8} {:>8}",
+        "endpoint", "count", "errors", "p50 ms", "p90 ms", "p95 ms", "p99 ms", "max ms"
+    );
+    let mut slowest_p95 = Duration::ZERO;
+    for (endpoint, (mut latencies, errors)) in by_endpoint {
+        latencies.sort();
+        let p95 = percentile(&latencies, 95.0);
+        slowest_p95 = slowest_p95.max(p95);
+        println!(
+            "{:<34} {:>7} {:>7} {:>8} {:>8} {:>8} {:>8} {:>8}",
+            endpoint,
+            latencies.len(),
+            errors,
+            percentile(&latencies, 50.0).as_millis(),
+            percentile(&latencies, 90.0).as_millis(),
+            p95.as_millis(),
+            percentile(&latencies, 99.0).as_millis(),
+            latencies.last().copied().unwrap_or_default().as_millis(),
+        );
+    }
+
+    let errors: Vec<&str> = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.error.as_deref())
+        .collect();
+    println!(
+        "\n{} requests in {:.1}s ({:.1} rps achieved), {} errors",
+        outcomes.len(),
+        elapsed.as_secs_f64(),
+        outcomes.len() as f64 / elapsed.as_secs_f64().max(0.001),
+        errors.len()
+    );
+    for error in errors.iter().take(5) {
+        println!("  {}", error);
+    }
+
+    slowest_p95
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid load test settings: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let client = Arc::new(LoadClient {
+        http: reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("HTTP client"),
+        base_url: config.base_url.clone(),
+    });
+
+    let mut location_ids = config.location_ids.clone();
+    if location_ids.is_empty()
+        && !config.dry_run
+        && config
+            .mix
+            .iter()
+            .any(|(s, w)| *s == Scenario::Gallery && *w > 0)
+    {
+        location_ids = discover_locations(&client).await;
+        if location_ids.is_empty() {
+            println!("No shops with images found, gallery traffic becomes map traffic");
+        }
+    }
+
+    let mut generator = Generator {
+        rng: Rng(config.seed),
+        mix: config.mix.clone(),
+        artist_id: config.artist_id,
+        location_ids,
+        bookings: 0,
+    };
+    let mut users: Vec<VecDeque<Request>> = vec![VecDeque::new(); config.users];
+    let total = (config.rps * config.duration.as_secs_f64()).ceil() as usize;
+
+    println!(
+        "Load test against {}: {} rps for {}s, {} users, seed {}\n",
+        config.base_url,
+        config.rps,
+        config.duration.as_secs(),
+        config.users,
+        config.seed
+    );
+
+    // Visitors take turns, each working through their current session
+    let mut next_request = move |tick: usize| {
+        let queue = &mut users[tick % config.users];
+        if queue.is_empty() {
+            queue.extend(generator.session());
+        }
+        queue
+            .pop_front()
+            .expect("sessions have at least one request")
+    };
+
+    if config.dry_run {
+        for tick in 0..total.min(50) {
+            println!("{:>4} {:?}", tick, next_request(tick));
+        }
+        return;
+    }
+
+    let started = Instant::now();
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / config.rps));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Burst);
+    let mut in_flight = Vec::with_capacity(total);
+    for tick in 0..total {
+        interval.tick().await;
+        let request = next_request(tick);
+        let client = client.clone();
+        in_flight.push(tokio::spawn(async move {
+            let endpoint = request.endpoint();
+            let sent = Instant::now();
+            let error = client.execute(request).await.err().map(|e| e.to_string());
+            Outcome {
+                endpoint,
+                latency: sent.elapsed(),
+                error,
+            }
+        }));
+    }
+
+    let mut outcomes = Vec::with_capacity(in_flight.len());
+    for handle in in_flight {
+        if let Ok(outcome) = handle.await {
+            outcomes.push(outcome);
+        }
+    }
+
+    let slowest_p95 = report(&outcomes, started.elapsed());
+    if let Some(budget) = config.max_p95_ms {
+        if slowest_p95.as_millis() > budget {
+            eprintln!(
+                "\np95 of {} ms is over the {} ms budget",
+                slowest_p95.as_millis(),
+                budget
+            );
+            std::process::exit(1);
+        }
+    }
+}
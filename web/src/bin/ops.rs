@@ -0,0 +1,333 @@
+// Ops
+// Routine account actions for operators, run straight against the database
+// instead of hand-written SQL. Every change is recorded in ops_audit_log
+// with the operator and reason.
+//
+// Usage: cargo run --bin ops --features ssr -- [--operator NAME] [--reason TEXT] <command>
+//
+// Commands:
+//   promote <email>                       make the user an admin
+//   set-role <email> <client|artist|admin>
+//   deactivate <email>                    block sign-in and end all sessions
+//   reactivate <email>                    also cancels a pending data deletion
+//   resend-verification <email> [channel] send a fresh code for a pending
+//                                         email or phone change
+//   audit [email] [limit]                 recent audit log entries
+//   create-api-key <name>                 issue a public API key, shown once
+//   revoke-api-key <id>
+//   api-keys                              list public API keys
+//
+// The operator defaults to $USER. Changes require a reason.
+
+use std::env;
+use web::db::ops_repository::{self, OpsUser, ROLES};
+use web::db::{account_repository, api_key_repository};
+
+type OpsResult<T> = Result<T, String>;
+
+const USAGE: &str = "Usage: ops [--operator NAME] [--reason TEXT] <command>
+
+Commands:
+  promote <email>
+  set-role <email> <client|artist|admin>
+  deactivate <email>
+  reactivate <email>
+  resend-verification <email> [email|phone]
+  audit [email] [limit]
+  create-api-key <name>
+  revoke-api-key <id>
+  api-keys";
+
+const DEFAULT_AUDIT_LIMIT: i64 = 50;
+
+struct Context {
+    operator: String,
+    reason: Option<String>,
+}
+
+impl Context {
+    fn reason(&self) -> OpsResult<&str> {
+        self.reason
+            .as_deref()
+            .ok_or_else(|| "--reason is required for changes".to_string())
+    }
+
+    async fn audit(&self, action: &str, user: &OpsUser, details: Option<&str>) -> OpsResult<()> {
+        ops_repository::insert_audit(
+            &self.operator,
+            action,
+            Some(user),
+            self.reason.as_deref(),
+            details,
+        )
+        .await
+        .map_err(|e| format!("Change made, but failed to write the audit log: {}", e))
+    }
+
+    /// Records a change that isn't to a user, like issuing an API key
+    async fn audit_untargeted(&self, action: &str, details: &str) -> OpsResult<()> {
+        ops_repository::insert_audit(
+            &self.operator,
+            action,
+            None,
+            self.reason.as_deref(),
+            Some(details),
+        )
+        .await
+        .map_err(|e| format!("Change made, but failed to write the audit log: {}", e))
+    }
+}
+
+async fn find_user(email: &str) -> OpsResult<OpsUser> {
+    ops_repository::find_user_by_email(email)
+        .await
+        .map_err(|e| format!("Failed to look up {}: {}", email, e))?
+        .ok_or_else(|| format!("No user with email {}", email))
+}
+
+async fn set_role(ctx: &Context, email: &str, role: &str) -> OpsResult<()> {
+    if !ROLES.contains(&role) {
+        return Err(format!(
+            "Unknown role {}; expected one of {}",
+            role,
+            ROLES.join(", ")
+        ));
+    }
+    ctx.reason()?;
+    let user = find_user(email).await?;
+    if user.role == role {
+        println!("{} is already {}", user.email, role);
+        return Ok(());
+    }
+
+    ops_repository::set_role(user.id, role)
+        .await
+        .map_err(|e| format!("Failed to update role: {}", e))?;
+    // Access tokens carry the role, so make the user sign in again
+    web::auth::revoke_all_sessions(user.id)
+        .await
+        .map_err(|e| format!("Failed to end sessions: {}", e))?;
+    ctx.audit(
+        "set_role",
+        &user,
+        Some(&format!("{} -> {}", user.role, role)),
+    )
+    .await?;
+
+    println!("✅ {} is now {} (was {})", user.email, role, user.role);
+    Ok(())
+}
+
+async fn set_active(ctx: &Context, email: &str, is_active: bool) -> OpsResult<()> {
+    ctx.reason()?;
+    let user = find_user(email).await?;
+    if user.is_active == is_active {
+        println!(
+            "{} is already {}",
+            user.email,
+            if is_active { "active" } else { "deactivated" }
+        );
+        return Ok(());
+    }
+
+    ops_repository::set_active(user.id, is_active)
+        .await
+        .map_err(|e| format!("Failed to update account: {}", e))?;
+
+    let details = if is_active {
+        None
+    } else {
+        let revoked = web::auth::revoke_all_sessions(user.id)
+            .await
+            .map_err(|e| format!("Failed to end sessions: {}", e))?;
+        Some(format!("revoked {} sessions", revoked))
+    };
+    let action = if is_active {
+        "reactivate"
+    } else {
+        "deactivate"
+    };
+    ctx.audit(action, &user, details.as_deref()).await?;
+
+    println!(
+        "✅ {} {}",
+        user.email,
+        if is_active {
+            "reactivated"
+        } else {
+            "deactivated"
+        }
+    );
+    Ok(())
+}
+
+async fn resend_verification(ctx: &Context, email: &str, channel: Option<&str>) -> OpsResult<()> {
+    let user = find_user(email).await?;
+    let pending = account_repository::get_pending_changes(user.id)
+        .await
+        .map_err(|e| format!("Failed to load pending changes: {}", e))?;
+
+    // Newest first; one per channel, since a new request replaces the old
+    let change = pending
+        .into_iter()
+        .find(|change| channel.is_none_or(|channel| change.channel == channel))
+        .ok_or_else(|| format!("{} has no pending contact change", user.email))?;
+
+    let sent =
+        web::server_account::send_contact_change_code(user.id, &change.channel, &change.new_value)
+            .await
+            .map_err(|e| e.to_string())?;
+    ctx.audit(
+        "resend_verification",
+        &user,
+        Some(&format!("{} {}", change.channel, change.new_value)),
+    )
+    .await?;
+
+    println!(
+        "✅ Sent a new code to {} (expires {})",
+        sent.new_value, sent.expires_at
+    );
+    Ok(())
+}
+
+async fn audit(email: Option<&str>, limit: i64) -> OpsResult<()> {
+    let user_id = match email {
+        Some(email) => Some(find_user(email).await?.id),
+        None => None,
+    };
+    let entries = ops_repository::list_audit(user_id, limit)
+        .await
+        .map_err(|e| format!("Failed to load audit log: {}", e))?;
+
+    for entry in entries {
+        println!(
+            "{}  {:<12} {:<20} {:<32} {}{}",
+            entry.created_at,
+            entry.operator,
+            entry.action,
+            entry.target_email.unwrap_or_default(),
+            entry.reason.unwrap_or_default(),
+            entry
+                .details
+                .map(|details| format!(" ({})", details))
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+async fn create_api_key(ctx: &Context, name: &str) -> OpsResult<()> {
+    ctx.reason()?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name the key after who it's for".to_string());
+    }
+
+    let (key, secret) = web::api_v1::issue_api_key(name)
+        .await
+        .map_err(|e| format!("Failed to create key: {}", e))?;
+    ctx.audit_untargeted("create_api_key", &format!("#{} {}", key.id, key.name))
+        .await?;
+
+    println!("✅ Created API key #{} for {}", key.id, key.name);
+    println!("{}", secret);
+    println!("This is the only time the key is shown; send it to them securely.");
+    Ok(())
+}
+
+async fn revoke_api_key(ctx: &Context, id: &str) -> OpsResult<()> {
+    let id = id
+        .parse::<i64>()
+        .map_err(|_| format!("Invalid key id: {}", id))?;
+    ctx.reason()?;
+
+    let key = api_key_repository::revoke_key(id)
+        .await
+        .map_err(|e| format!("Failed to revoke key: {}", e))?
+        .ok_or_else(|| format!("No active API key #{}", id))?;
+    ctx.audit_untargeted("revoke_api_key", &format!("#{} {}", key.id, key.name))
+        .await?;
+
+    println!("✅ Revoked API key #{} ({})", key.id, key.name);
+    Ok(())
+}
+
+async fn list_api_keys() -> OpsResult<()> {
+    let keys = api_key_repository::list_keys()
+        .await
+        .map_err(|e| format!("Failed to load API keys: {}", e))?;
+
+    for key in keys {
+        println!(
+            "#{:<5} {:<14} {:<32} created {}  last used {}{}",
+            key.id,
+            format!("{}...", key.key_prefix),
+            key.name,
+            key.created_at,
+            key.last_used_at.as_deref().unwrap_or("never"),
+            key.revoked_at
+                .map(|revoked_at| format!("  revoked {}", revoked_at))
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let mut operator = env::var("USER").ok().filter(|v| !v.is_empty());
+    let mut reason = None;
+    let mut args = Vec::new();
+    let mut raw = env::args().skip(1);
+    while let Some(arg) = raw.next() {
+        match arg.as_str() {
+            "--operator" => operator = raw.next(),
+            "--reason" => reason = raw.next(),
+            _ => args.push(arg),
+        }
+    }
+
+    let Some(operator) = operator else {
+        eprintln!("Set --operator (or $USER) so the audit log knows who ran this");
+        std::process::exit(2);
+    };
+    let ctx = Context { operator, reason };
+
+    if let Err(e) = web::db::pool::init_pool().await {
+        eprintln!("Failed to connect to database: {}", e);
+        std::process::exit(1);
+    }
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["promote", email] => set_role(&ctx, email, "admin").await,
+        ["set-role", email, role] => set_role(&ctx, email, role).await,
+        ["deactivate", email] => set_active(&ctx, email, false).await,
+        ["reactivate", email] => set_active(&ctx, email, true).await,
+        ["resend-verification", email] => resend_verification(&ctx, email, None).await,
+        ["resend-verification", email, channel] => {
+            resend_verification(&ctx, email, Some(channel)).await
+        }
+        ["audit"] => audit(None, DEFAULT_AUDIT_LIMIT).await,
+        ["audit", email] => audit(Some(email), DEFAULT_AUDIT_LIMIT).await,
+        ["audit", email, limit] => match limit.parse::<i64>() {
+            Ok(limit) if limit > 0 => audit(Some(email), limit).await,
+            _ => Err(format!("Invalid limit: {}", limit)),
+        },
+        ["create-api-key", name] => create_api_key(&ctx, name).await,
+        ["revoke-api-key", id] => revoke_api_key(&ctx, id).await,
+        ["api-keys"] => list_api_keys().await,
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+}
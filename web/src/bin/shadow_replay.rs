@@ -0,0 +1,179 @@
+// Shadow Replay
+// Replays production-shaped requests against repository query rewrites
+// before they're dark-launched or switched over. Each case samples real
+// inputs from the database, runs the original implementation and the
+// rewrite one after the other, and compares their results the way live
+// shadowing does (see src/db/shadow.rs).
+//
+// Usage: cargo run --bin shadow_replay --features ssr -- [requests per case]
+//
+// Prints each divergence and per-case latencies; exits non-zero if any
+// request diverged.
+
+use shared_types::StyleFilter;
+use std::env;
+use std::time::Duration;
+use web::db::repository;
+use web::db::shadow::{compare, Comparison, Outcome};
+
+type ReplayResult<T> = Result<T, sqlx::Error>;
+
+const DEFAULT_REQUESTS: usize = 200;
+const PER_PAGE: i32 = 20;
+
+/// Shop gallery requests: random locations with images, their first few
+/// pages, some with style filters and some signed in
+async fn replay_shop_images(requests: usize) -> ReplayResult<Vec<(String, Comparison)>> {
+    let pool = web::db::pool::get_pool();
+
+    let locations: Vec<(i32, i64)> = sqlx::query_as(
+        "SELECT a.location_id::int, COUNT(ai.id)
+         FROM artists a
+         JOIN artists_images ai ON ai.artist_id = a.id
+         GROUP BY a.location_id
+         ORDER BY random()
+         LIMIT $1",
+    )
+    .bind(requests as i64)
+    .fetch_all(pool)
+    .await?;
+    let users: Vec<i64> = sqlx::query_scalar(
+        "SELECT user_id::bigint
+         FROM (SELECT DISTINCT user_id FROM user_favorites) u
+         ORDER BY random()
+         LIMIT 50",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut results = Vec::new();
+    for (i, (location_id, image_count)) in locations.into_iter().enumerate() {
+        let pages = ((image_count as i32 + PER_PAGE - 1) / PER_PAGE).clamp(1, 3);
+        let page = i as i32 % pages;
+        // Every other request signed in, for the favorites join
+        let user_id = if i % 2 == 0 && !users.is_empty() {
+            Some(users[i % users.len()])
+        } else {
+            None
+        };
+
+        let style_filter = if i % 3 == 0 {
+            let style_ids: Vec<i32> = sqlx::query_scalar(
+                "SELECT DISTINCT ais.style_id::int
+                 FROM artists_images_styles ais
+                 JOIN artists_images ai ON ai.id = ais.artists_images_id
+                 JOIN artists a ON a.id = ai.artist_id
+                 WHERE a.location_id = $1
+                 LIMIT 2",
+            )
+            .bind(location_id)
+            .fetch_all(pool)
+            .await?;
+            (!style_ids.is_empty()).then(|| StyleFilter::any(style_ids))
+        } else {
+            None
+        };
+
+        let request = format!(
+            "location={} page={} user={:?} styles={:?}",
+            location_id, page, user_id, style_filter
+        );
+        let comparison = compare(
+            repository::get_shop_images_paginated(
+                location_id,
+                style_filter.clone(),
+                page,
+                PER_PAGE,
+                user_id,
+            ),
+            repository::get_shop_images_paginated_batched(
+                location_id,
+                style_filter,
+                page,
+                PER_PAGE,
+                user_id,
+            ),
+            repository::shop_images_key,
+        )
+        .await;
+        results.push((request, comparison));
+    }
+
+    Ok(results)
+}
+
+fn percentile(durations: &mut [Duration], p: f64) -> f64 {
+    if durations.is_empty() {
+        return 0.0;
+    }
+    durations.sort_unstable();
+    let index = ((durations.len() - 1) as f64 * p).round() as usize;
+    durations[index].as_secs_f64() * 1000.0
+}
+
+/// Prints the case's divergences and latencies, returning how many diverged
+fn report(case: &str, results: &[(String, Comparison)]) -> usize {
+    let mut diverged = 0;
+    for (request, comparison) in results {
+        if let Outcome::Diverged { primary, candidate } = &comparison.outcome {
+            diverged += 1;
+            println!("❌ {} {}", case, request);
+            println!("   original: {}", primary);
+            println!("   rewrite:  {}", candidate);
+        }
+    }
+
+    let mut primary: Vec<Duration> = results.iter().map(|(_, c)| c.primary).collect();
+    let mut candidate: Vec<Duration> = results.iter().map(|(_, c)| c.candidate).collect();
+    println!(
+        "{} {}: {} requests, {} diverged",
+        if diverged == 0 { "✅" } else { "❌" },
+        case,
+        results.len(),
+        diverged
+    );
+    println!(
+        "   original p50 {:.1} ms, p95 {:.1} ms",
+        percentile(&mut primary, 0.5),
+        percentile(&mut primary, 0.95)
+    );
+    println!(
+        "   rewrite  p50 {:.1} ms, p95 {:.1} ms",
+        percentile(&mut candidate, 0.5),
+        percentile(&mut candidate, 0.95)
+    );
+
+    diverged
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let requests = match env::args().nth(1).map(|arg| arg.parse::<usize>()) {
+        None => DEFAULT_REQUESTS,
+        Some(Ok(requests)) if requests > 0 => requests,
+        Some(_) => {
+            eprintln!("Usage: shadow_replay [requests per case]");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(e) = web::db::pool::init_pool().await {
+        eprintln!("Failed to connect to database: {}", e);
+        std::process::exit(1);
+    }
+
+    let mut diverged = 0;
+    match replay_shop_images(requests).await {
+        Ok(results) => diverged += report("shop_images_paginated", &results),
+        Err(e) => {
+            eprintln!("Failed to sample shop image requests: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    if diverged > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,70 @@
+// Source Map Upload
+// Stores the client bundle's source maps for a release, so client errors
+// logged with that build id get readable stack traces.
+//
+// Usage: cargo run --bin upload_source_maps --features ssr -- <build_id> [dir]
+//
+// Build the release with TATTEAU_BUILD_ID=<build_id> so the client reports
+// it. Every *.map file in `dir` (default target/site/pkg) is stored under the
+// name of the file it maps, e.g. web.js.map -> web.js.
+
+use std::env;
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let mut args = env::args().skip(1);
+    let Some(build_id) = args.next() else {
+        eprintln!("Usage: upload_source_maps <build_id> [dir]");
+        std::process::exit(2);
+    };
+    let dir = PathBuf::from(args.next().unwrap_or_else(|| "target/site/pkg".to_string()));
+
+    if let Err(e) = web::db::pool::init_pool().await {
+        eprintln!("Failed to connect to database: {}", e);
+        std::process::exit(1);
+    }
+
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", dir.display(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut uploaded = 0;
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(file_name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".map"))
+        else {
+            continue;
+        };
+
+        let result = match std::fs::read_to_string(&path) {
+            Ok(source_map) => {
+                web::db::source_map_repository::store_source_map(&build_id, file_name, &source_map)
+                    .await
+                    .map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        };
+
+        match result {
+            Ok(()) => {
+                println!("✅ {} -> {}", path.display(), file_name);
+                uploaded += 1;
+            }
+            Err(e) => {
+                eprintln!("❌ {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    println!("\nStored {} source maps for build {}", uploaded, build_id);
+}
@@ -0,0 +1,292 @@
+//! Busy time from artists' other calendars. An artist adds the secret ICS
+//! address of a calendar they keep elsewhere, like Google Calendar's
+//! "secret address in iCal format"; [`sync_due_imports`] fetches each one
+//! every [`SYNC_EVERY_MINUTES`] from the background job queue and stores its
+//! busy events for the next [`IMPORT_DAYS`] as blocks, which
+//! `load_schedule` hands to availability so no slot is offered while the
+//! artist is busy elsewhere.
+//!
+//! A calendar that can't be fetched or read keeps the busy time from its
+//! last good sync, with the error shown to the artist.
+//!
+//! Addresses are fetched on the artist's behalf, so every hop is checked:
+//! the host is resolved first, refused if any of its addresses is loopback,
+//! private, link-local or otherwise not on the public internet, and the
+//! connection is pinned to the checked address. Redirects are followed by
+//! hand, each one checked the same way.
+
+use chrono::Utc;
+use chrono_tz::Tz;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::db::calendar_import_repository::{self, DueImport};
+
+/// How far ahead busy time is imported
+pub const IMPORT_DAYS: i64 = 180;
+/// How often each calendar is fetched
+pub const SYNC_EVERY_MINUTES: i32 = 30;
+/// Calendars fetched per run
+const IMPORTS_PER_RUN: i64 = 20;
+/// Largest calendar accepted
+const MAX_CALENDAR_BYTES: usize = 5 * 1024 * 1024;
+/// Redirects followed before giving up
+const MAX_REDIRECTS: usize = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CalendarImportError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("calendar request failed: {0}")]
+    Fetch(#[from] reqwest::Error),
+    #[error("the calendar address answered {0}")]
+    Status(reqwest::StatusCode),
+    #[error("the calendar is larger than {} MB", MAX_CALENDAR_BYTES / (1024 * 1024))]
+    TooLarge,
+    #[error("the address isn't an iCalendar (.ics) file")]
+    NotCalendar,
+    #[error("unknown timezone {0}")]
+    Timezone(String),
+    #[error("{0} isn't a public calendar address")]
+    NotPublic(String),
+    #[error("the calendar address redirects too many times")]
+    TooManyRedirects,
+}
+
+/// The address to fetch for a calendar address an artist pastes in.
+/// `webcal://` addresses are fetched over https, and only public https
+/// hosts are allowed.
+pub fn normalize_calendar_url(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    let rest = match value.split_once("://") {
+        Some((scheme, rest)) if ["https", "webcal"].contains(&scheme.to_lowercase().as_str()) => {
+            rest
+        }
+        _ => return Err("Use the calendar's https:// or webcal:// address".to_string()),
+    };
+
+    let host = rest
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let name = host
+        .rsplit_once(':')
+        .map_or(host.as_str(), |(name, _)| name);
+    let is_ip = name.starts_with('[') || name.parse::<std::net::Ipv4Addr>().is_ok();
+    if name.is_empty() || !name.contains('.') || is_ip || name.ends_with(".localhost") {
+        return Err(format!("{} isn't a public calendar address", value));
+    }
+
+    Ok(format!("https://{}", rest))
+}
+
+pub fn parse_timezone(timezone: &str) -> Result<Tz, CalendarImportError> {
+    timezone
+        .trim()
+        .parse()
+        .map_err(|_| CalendarImportError::Timezone(timezone.to_string()))
+}
+
+/// Whether `ip` is a unicast address on the public internet
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b))
+                // Reserved, 240.0.0.0/4
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_unspecified()
+                    || ip.is_loopback()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// A client for one request to `url`, connecting only to an address of its
+/// host that was checked to be public
+async fn pinned_client(url: &reqwest::Url) -> Result<reqwest::Client, CalendarImportError> {
+    let not_public = || CalendarImportError::NotPublic(url.to_string());
+    if url.scheme() != "https" {
+        return Err(not_public());
+    }
+    let host = url.host_str().ok_or_else(not_public)?;
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| not_public())?
+        .collect();
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(addr.ip())) {
+        return Err(not_public());
+    }
+
+    Ok(reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve(host, addrs[0])
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?)
+}
+
+async fn fetch_calendar(url: &str) -> Result<String, CalendarImportError> {
+    let mut url =
+        reqwest::Url::parse(url).map_err(|_| CalendarImportError::NotPublic(url.to_string()))?;
+    let mut redirects = 0;
+    let mut response = loop {
+        let response = pinned_client(&url).await?.get(url.clone()).send().await?;
+        if !response.status().is_redirection() {
+            break response;
+        }
+        if redirects == MAX_REDIRECTS {
+            return Err(CalendarImportError::TooManyRedirects);
+        }
+        redirects += 1;
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| url.join(location).ok())
+            .ok_or(CalendarImportError::Status(response.status()))?;
+        url = location;
+    };
+    if !response.status().is_success() {
+        return Err(CalendarImportError::Status(response.status()));
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if bytes.len() + chunk.len() > MAX_CALENDAR_BYTES {
+            return Err(CalendarImportError::TooLarge);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let ics = String::from_utf8_lossy(&bytes).into_owned();
+    if !ics
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .starts_with("BEGIN:VCALENDAR")
+    {
+        return Err(CalendarImportError::NotCalendar);
+    }
+    Ok(ics)
+}
+
+/// Fetches a calendar and replaces its busy time, returning the number of
+/// blocks stored. A failure is recorded on the import.
+pub async fn sync_import(import: &DueImport) -> Result<usize, CalendarImportError> {
+    let result = async {
+        let zone = parse_timezone(&import.timezone)?;
+        let ics = fetch_calendar(&import.url).await?;
+
+        let today = Utc::now().with_timezone(&zone).date_naive();
+        let blocks = crate::utils::ics::busy_blocks(
+            &ics,
+            zone,
+            today,
+            today + chrono::Duration::days(IMPORT_DAYS),
+        );
+        calendar_import_repository::replace_busy_blocks(import.id, import.artist_id, &blocks)
+            .await?;
+        Ok::<_, CalendarImportError>(blocks.len())
+    }
+    .await;
+
+    if let Err(e) = &result {
+        calendar_import_repository::record_import_error(import.id, &e.to_string()).await?;
+    }
+    result
+}
+
+/// Syncs the calendars due a fetch, returning how many synced
+pub async fn sync_due_imports() -> Result<usize, CalendarImportError> {
+    let imports =
+        calendar_import_repository::claim_due_imports(SYNC_EVERY_MINUTES, IMPORTS_PER_RUN).await?;
+
+    let mut synced = 0;
+    for import in &imports {
+        match sync_import(import).await {
+            Ok(_) => synced += 1,
+            Err(e) => tracing::warn!(
+                "Failed to sync calendar import {} for artist {}: {}",
+                import.id,
+                import.artist_id,
+                e
+            ),
+        }
+    }
+    Ok(synced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn public(ip: &str) -> bool {
+        is_public_ip(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn public_addresses_are_allowed() {
+        assert!(public("142.250.72.14"));
+        assert!(public("1.1.1.1"));
+        assert!(public("2607:f8b0:4005:80c::200e"));
+    }
+
+    #[test]
+    fn internal_ipv4_addresses_are_refused() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "240.0.0.1",
+        ] {
+            assert!(!public(ip), "{} should be refused", ip);
+        }
+    }
+
+    #[test]
+    fn internal_ipv6_addresses_are_refused() {
+        for ip in [
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "ff02::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!public(ip), "{} should be refused", ip);
+        }
+    }
+
+    #[test]
+    fn only_https_and_webcal_hosts_are_accepted() {
+        assert_eq!(
+            normalize_calendar_url("webcal://calendar.example.com/a.ics").unwrap(),
+            "https://calendar.example.com/a.ics"
+        );
+        assert!(normalize_calendar_url("http://calendar.example.com/a.ics").is_err());
+        assert!(normalize_calendar_url("https://127.0.0.1/a.ics").is_err());
+        assert!(normalize_calendar_url("https://localhost/a.ics").is_err());
+    }
+}
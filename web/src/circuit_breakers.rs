@@ -0,0 +1,56 @@
+//! Circuit breakers around the external services the web server calls, so
+//! an outage fails requests fast with a clear error instead of each one
+//! waiting out its timeout. Their states are reported on `/metrics`,
+//! with the ones ingestion saves after each run.
+
+use shared_types::circuit_breaker::{is_outage_status, BreakerConfig, BreakerOpen, CircuitBreaker};
+
+pub static GOOGLE_PLACES: CircuitBreaker =
+    CircuitBreaker::new("google_places", BreakerConfig::DEFAULT);
+pub static STRIPE: CircuitBreaker = CircuitBreaker::new("stripe", BreakerConfig::DEFAULT);
+/// Instagram's oEmbed API, for live embeds
+pub static INSTAGRAM: CircuitBreaker = CircuitBreaker::new("instagram", BreakerConfig::DEFAULT);
+/// The notification webhook, for email and SMS
+pub static NOTIFY: CircuitBreaker = CircuitBreaker::new("notify", BreakerConfig::DEFAULT);
+/// S3-compatible storage, when configured
+pub static STORAGE: CircuitBreaker = CircuitBreaker::new("storage", BreakerConfig::DEFAULT);
+/// The machine translation provider for booking messages
+pub static TRANSLATE: CircuitBreaker = CircuitBreaker::new("translate", BreakerConfig::DEFAULT);
+/// The outside endpoint server hooks post events to, when configured
+pub static HOOKS_WEBHOOK: CircuitBreaker =
+    CircuitBreaker::new("hooks_webhook", BreakerConfig::DEFAULT);
+
+pub fn all() -> [&'static CircuitBreaker; 7] {
+    [
+        &GOOGLE_PLACES,
+        &STRIPE,
+        &INSTAGRAM,
+        &NOTIFY,
+        &STORAGE,
+        &TRANSLATE,
+        &HOOKS_WEBHOOK,
+    ]
+}
+
+/// Sends `request` through `breaker`, counting failed requests and outage
+/// statuses against it
+pub async fn send<E>(
+    breaker: &CircuitBreaker,
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, E>
+where
+    E: From<BreakerOpen> + From<reqwest::Error>,
+{
+    breaker.check()?;
+    let response = request.send().await;
+    record_response(breaker, &response);
+    Ok(response?)
+}
+
+/// Records the outcome of a request admitted by `breaker.check()`
+pub fn record_response(
+    breaker: &CircuitBreaker,
+    response: &Result<reqwest::Response, reqwest::Error>,
+) {
+    breaker.record(matches!(response, Ok(r) if !is_outage_status(r.status().as_u16())));
+}
@@ -1,4 +1,3 @@
-use crate::components::instagram_embed::process_instagram_embeds;
 use crate::components::instagram_posts_grid::{InstagramPostsGrid, PostWithArtist};
 use crate::db::entities::{ArtistImage, Style};
 use leptos::prelude::*;
@@ -15,16 +14,6 @@ pub fn ArtistMasonryGallery(
     instagram_posts: Vec<InstagramPost>,
     artist_styles: Vec<Style>,
 ) -> impl IntoView {
-    // Trigger Instagram embed processing when component mounts
-    Effect::new(move |_| {
-        set_timeout(
-            move || {
-                process_instagram_embeds();
-            },
-            std::time::Duration::from_millis(100),
-        );
-    });
-
     // Convert to PostWithArtist format
     let posts: Vec<PostWithArtist> = instagram_posts
         .into_iter()
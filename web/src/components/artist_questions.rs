@@ -0,0 +1,139 @@
+use crate::api_error::{user_message, ApiError};
+use crate::db::entities::ProfileQuestion;
+use crate::server_artist_questions::ask_artist_question;
+use crate::utils::artist_questions::MAX_QUESTION_CHARS;
+use crate::utils::auth::{get_auth_token, is_authenticated};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// The public Q&A on an artist's profile: the questions they've answered and
+/// a form for asking a new one
+#[component]
+pub fn ArtistQuestions(artist_id: i32, questions: Vec<ProfileQuestion>) -> impl IntoView {
+    let asker_name = RwSignal::new(String::new());
+    let asker_email = RwSignal::new(String::new());
+    let question = RwSignal::new(String::new());
+    let sending = RwSignal::new(false);
+    let sent = RwSignal::new(false);
+    let ask_error = RwSignal::new(None::<String>);
+    let field_error = RwSignal::new(None::<(String, String)>);
+
+    // Signed-in clients are asked as themselves. Read after hydration, since
+    // the server can't see the session.
+    let signed_in = RwSignal::new(false);
+    Effect::new(move |_| signed_in.set(is_authenticated()));
+
+    let field_hint = move |field: &'static str| {
+        move || {
+            field_error
+                .get()
+                .filter(|(name, _)| name == field)
+                .map(|(_, message)| view! { <p class="artist-questions-field-error">{message}</p> })
+        }
+    };
+
+    let ask = move |_| {
+        sending.set(true);
+        spawn_local(async move {
+            let result = ask_artist_question(
+                artist_id,
+                get_auth_token(),
+                asker_name.get_untracked(),
+                asker_email.get_untracked(),
+                question.get_untracked(),
+            )
+            .await;
+            match result {
+                Ok(()) => {
+                    question.set(String::new());
+                    ask_error.set(None);
+                    field_error.set(None);
+                    sent.set(true);
+                }
+                Err(ServerFnError::WrappedServerError(ApiError::Validation { field, message })) => {
+                    field_error.set(Some((field, message)));
+                }
+                Err(e) => ask_error.set(Some(user_message(&e))),
+            }
+            sending.set(false);
+        });
+    };
+
+    let has_questions = !questions.is_empty();
+
+    view! {
+        <div class="artist-highlight-portfolio-card artist-questions">
+            <h2 class="artist-highlight-portfolio-heading">"Questions & Answers"</h2>
+
+            {has_questions.then(|| view! {
+                <div class="artist-questions-list">
+                    {questions.into_iter().map(|q| view! {
+                        <div class="artist-questions-item">
+                            <p class="artist-questions-question">{q.question}</p>
+                            <p class="artist-questions-asker">{format!("Asked by {}", q.asker_name)}</p>
+                            <p class="artist-questions-answer">{q.answer}</p>
+                            <p class="artist-questions-date">{format!("Answered {}", q.answered_on)}</p>
+                        </div>
+                    }).collect_view()}
+                </div>
+            })}
+            {(!has_questions).then(|| view! {
+                <p class="artist-questions-empty">"No questions answered yet. Be the first to ask."</p>
+            })}
+
+            <div class="artist-questions-form">
+                <h3>"Ask a Question"</h3>
+                <p class="artist-questions-hint">
+                    "Pricing ballpark, styles, availability... Answers are shown here for everyone."
+                </p>
+
+                <Show when=move || sent.get()>
+                    <div class="success-message">"Thanks! You'll get an email when it's answered."</div>
+                </Show>
+                {move || ask_error.get().map(|error| view! {
+                    <div class="error-message">{error}</div>
+                })}
+
+                <Show when=move || !signed_in.get()>
+                    <div class="artist-questions-field">
+                        <label>"Your name"</label>
+                        <input
+                            type="text"
+                            maxlength="80"
+                            prop:value=move || asker_name.get()
+                            on:input=move |ev| asker_name.set(event_target_value(&ev))
+                        />
+                        {field_hint("asker_name")}
+                    </div>
+                    <div class="artist-questions-field">
+                        <label>"Your email"</label>
+                        <input
+                            type="email"
+                            prop:value=move || asker_email.get()
+                            on:input=move |ev| asker_email.set(event_target_value(&ev))
+                        />
+                        <p class="artist-questions-hint">"Only used to tell you when it's answered."</p>
+                        {field_hint("asker_email")}
+                    </div>
+                </Show>
+                <div class="artist-questions-field">
+                    <label>"Question"</label>
+                    <textarea
+                        rows="3"
+                        maxlength=MAX_QUESTION_CHARS.to_string()
+                        prop:value=move || question.get()
+                        on:input=move |ev| question.set(event_target_value(&ev))
+                    ></textarea>
+                    {field_hint("question")}
+                </div>
+                <button
+                    class="btn btn-primary"
+                    disabled=move || sending.get() || question.get().trim().is_empty()
+                    on:click=ask
+                >
+                    {move || if sending.get() { "Sending..." } else { "Ask" }}
+                </button>
+            </div>
+        </div>
+    }
+}
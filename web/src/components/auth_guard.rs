@@ -1,17 +1,31 @@
-use crate::utils::auth::get_authenticated_artist_id;
+use crate::server_team::get_team_access;
+use crate::utils::auth::{get_auth_token, get_authenticated_artist_id};
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_router::hooks::use_navigate;
 
-/// Hook to check artist authentication status using proper JWT validation
+/// Hook to check artist authentication status using proper JWT validation.
+/// Assistants on an artist's team are let in too; the server checks what
+/// they may do.
 pub fn use_artist_auth() -> (Signal<bool>, Signal<bool>) {
     let is_authenticated = RwSignal::new(false);
     let is_loading = RwSignal::new(true);
 
     Effect::new(move |_| {
         // Use the proper authentication function that validates JWT and checks user_type
-        let artist_id = get_authenticated_artist_id();
-        is_authenticated.set(artist_id.is_some());
-        is_loading.set(false);
+        if get_authenticated_artist_id().is_some() {
+            is_authenticated.set(true);
+            is_loading.set(false);
+        } else if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                let access = get_team_access(token).await.ok().flatten();
+                is_authenticated.set(access.is_some());
+                is_loading.set(false);
+            });
+        } else {
+            is_authenticated.set(false);
+            is_loading.set(false);
+        }
     });
 
     (is_authenticated.into(), is_loading.into())
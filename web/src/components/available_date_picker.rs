@@ -1,13 +1,17 @@
 use crate::server::get_available_dates;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
+use shared_types::datetime::Date;
 use thaw::*;
 
+/// Month calendar of the dates a client can book. With a `guest_spot_id`,
+/// only that guest spot's dates are offered.
 #[component]
 pub fn AvailableDatePicker(
     artist_id: RwSignal<Option<i32>>,
     selected_date: RwSignal<String>,
     on_date_selected: impl Fn(String) + 'static + Copy + Send + Sync,
+    #[prop(optional)] guest_spot_id: Option<Signal<Option<i32>>>,
 ) -> impl IntoView {
     let current_month_offset = RwSignal::new(0i32);
     let available_dates = RwSignal::new(Vec::<String>::new());
@@ -15,6 +19,7 @@ pub fn AvailableDatePicker(
 
     let fetch_available_dates = move || {
         if let Some(id) = artist_id.get() {
+            let guest_spot_id = guest_spot_id.and_then(|guest_spot_id| guest_spot_id.get());
             is_loading.set(true);
 
             spawn_local(async move {
@@ -23,17 +28,17 @@ pub fn AvailableDatePicker(
                 let month_offset = current_month_offset.get();
 
                 let (year, month) = calculate_month_offset(&today, month_offset);
-                let view_start = format!("{:04}-{:02}-01", year, month);
-
-                // Calculate last day of month
-                let next_month = if month == 12 { 1 } else { month + 1 };
-                let next_year = if month == 12 { year + 1 } else { year };
-                let view_end = format!("{:04}-{:02}-01", next_year, next_month);
-                let view_end = get_day_before(&view_end);
-
-                match get_available_dates(id, view_start.clone(), view_end).await {
+                let (Some(view_start), Some(view_end)) = (
+                    Date::from_ymd(year, month, 1),
+                    Date::from_ymd(year, month, get_days_in_month(year, month)),
+                ) else {
+                    is_loading.set(false);
+                    return;
+                };
+
+                match get_available_dates(id, view_start, view_end, guest_spot_id).await {
                     Ok(dates) => {
-                        available_dates.set(dates);
+                        available_dates.set(dates.iter().map(Date::to_string).collect());
                     }
                     Err(e) => {
                         leptos::logging::error!("Failed to fetch available dates: {}", e);
@@ -267,23 +272,6 @@ fn get_month_name(month: u32) -> &'static str {
     }
 }
 
-fn get_day_before(date: &str) -> String {
-    let parts: Vec<&str> = date.split('-').collect();
-    let year = parts[0].parse::<i32>().unwrap_or(2024);
-    let month = parts[1].parse::<u32>().unwrap_or(1);
-    let day = parts[2].parse::<u32>().unwrap_or(1);
-
-    if day > 1 {
-        format!("{:04}-{:02}-{:02}", year, month, day - 1)
-    } else if month > 1 {
-        let prev_month = month - 1;
-        let last_day = get_days_in_month(year, prev_month);
-        format!("{:04}-{:02}-{:02}", year, prev_month, last_day)
-    } else {
-        format!("{:04}-12-31", year - 1)
-    }
-}
-
 fn is_date_past(date: &str, today: &str) -> bool {
     date < today
 }
@@ -1,25 +1,51 @@
+use crate::api_error::user_message;
 use crate::components::{AvailableDatePicker, MultiStepQuestionnaire, TimeSlotPicker};
-use crate::db::entities::{ClientQuestionnaireSubmission, QuestionnaireResponse};
+use crate::db::entities::{
+    ClientQuestionnaireSubmission, FlashDesign, GuestSpot, QuestionnaireResponse,
+};
 use crate::server::{
     fetch_artist_data, get_artist_questionnaire_form, submit_booking_request,
     submit_questionnaire_responses, NewBookingRequest, TimeSlot,
 };
+use crate::utils::appointments::TATTOO_SIZES;
+use crate::utils::auth::get_auth_token;
+use crate::utils::auto_response::{BOOKING_TYPES, DEFAULT_BOOKING_TYPE};
 use leptos::prelude::*;
 use leptos::task::spawn_local;
 use leptos_router::hooks::use_navigate;
+use shared_types::datetime::Date;
 use std::collections::HashMap;
 use thaw::*;
 
+/// Booking request form for an artist. With a `guest_spot` set, only the
+/// dates of that guest spot can be picked. With a `flash_design` set, the
+/// request claims that design and is filled in from it.
 #[component]
 pub fn ClientBookingModal(
     show: RwSignal<bool>,
     artist_id: RwSignal<Option<i32>>,
     on_close: impl Fn() + 'static + Copy + Send + Sync,
+    #[prop(optional)] guest_spot: Option<RwSignal<Option<GuestSpot>>>,
+    #[prop(optional)] flash_design: Option<RwSignal<Option<FlashDesign>>>,
 ) -> impl IntoView {
+    let guest_spot_id =
+        Signal::derive(move || guest_spot.and_then(|spot| spot.get()).map(|spot| spot.id));
+    let flash = move || flash_design.and_then(|design| design.get());
+
     // Appointment form state (only time/date info - no contact details for authenticated users)
     let requested_date = RwSignal::new(String::new());
     let selected_time_slot = RwSignal::new(None::<TimeSlot>);
     let additional_message = RwSignal::new(String::new());
+    let booking_type = RwSignal::new(DEFAULT_BOOKING_TYPE.to_string());
+    let size_inches = RwSignal::new(None::<f64>);
+
+    // A claimed design sets the type and size, which pick the slot length
+    Effect::new(move |_| {
+        if let Some(design) = flash() {
+            booking_type.set("flash".to_string());
+            size_inches.set(design.size_inches.map(f64::from));
+        }
+    });
 
     // Questionnaire state
     let questionnaire_responses = RwSignal::new(HashMap::<i32, String>::new());
@@ -65,42 +91,47 @@ pub fn ClientBookingModal(
 
     let submit_booking = create_action(move |request: &NewBookingRequest| {
         let request = request.clone();
-        async move { submit_booking_request(request).await }
+        async move { submit_booking_request(request, get_auth_token()).await }
     });
 
     let handle_submit = move || {
         if let Some(id) = artist_id.get() {
+            let (Some(date), Some(slot)) =
+                (Date::parse(&requested_date.get()), selected_time_slot.get())
+            else {
+                submission_error.set(Some(
+                    "Pick a date and time for your appointment".to_string(),
+                ));
+                return;
+            };
             is_submitting.set(true);
             submission_error.set(None);
+            let flash_design = flash_design.and_then(|design| design.get_untracked());
 
-            let slot = selected_time_slot.get();
-            let (start_time, end_time) = if let Some(time_slot) = slot {
-                (
-                    time_slot.start_time.clone(),
-                    Some(time_slot.end_time.clone()),
-                )
-            } else {
-                (String::new(), None)
-            };
-
-            // For authenticated users, use placeholder values for required fields
-            // Real user data should come from authentication context
+            // Name and email are filled in from the signed-in account
             let request = NewBookingRequest {
                 artist_id: id,
-                client_name: "Authenticated User".to_string(), // TODO: Get from auth context
-                client_email: "user@example.com".to_string(),  // TODO: Get from auth context
+                client_name: String::new(),
+                client_email: String::new(),
                 client_phone: None,
-                tattoo_description: None, // Collected via questionnaire
-                placement: None,          // Collected via questionnaire
-                size_inches: None,        // Collected via questionnaire
-                requested_date: requested_date.get(),
-                requested_start_time: start_time,
-                requested_end_time: end_time,
+                // Otherwise collected via questionnaire
+                tattoo_description: flash_design
+                    .as_ref()
+                    .map(|design| format!("Flash: {}", design.title)),
+                placement: None, // Collected via questionnaire
+                size_inches: size_inches.get().map(|size| size as f32),
+                requested_date: date,
+                requested_start_time: slot.start_time,
+                requested_end_time: Some(slot.end_time),
                 message_from_client: if additional_message.get().trim().is_empty() {
                     None
                 } else {
                     Some(additional_message.get())
                 },
+                allow_duplicate: false,
+                reference_upload_ids: Vec::new(),
+                booking_type: Some(booking_type.get()),
+                flash_design_id: flash_design.map(|design| design.id),
             };
 
             submit_booking.dispatch(request);
@@ -149,6 +180,7 @@ pub fn ClientBookingModal(
                     requested_date.set(String::new());
                     selected_time_slot.set(None);
                     additional_message.set(String::new());
+                    booking_type.set(DEFAULT_BOOKING_TYPE.to_string());
                     questionnaire_responses.set(HashMap::new());
                     questionnaire_completed.set(false);
                     current_step.set(1);
@@ -168,7 +200,7 @@ pub fn ClientBookingModal(
                     is_submitting.set(false);
                 }
                 Err(e) => {
-                    submission_error.set(Some(format!("Failed to submit booking: {}", e)));
+                    submission_error.set(Some(user_message(&e)));
                     is_submitting.set(false);
                 }
             }
@@ -187,6 +219,7 @@ pub fn ClientBookingModal(
         requested_date.set(String::new());
         selected_time_slot.set(None);
         additional_message.set(String::new());
+        booking_type.set(DEFAULT_BOOKING_TYPE.to_string());
         questionnaire_responses.set(HashMap::new());
         questionnaire_completed.set(false);
         current_step.set(1);
@@ -316,12 +349,70 @@ pub fn ClientBookingModal(
                                 </Suspense>
 
                                 <div class="appointment-form-content">
+                                    {move || match flash() {
+                                        Some(design) => view! {
+                                            <div class="form-section booking-modal-flash">
+                                                <img src=design.thumbnail_url.clone() alt=design.title.clone() />
+                                                <div>
+                                                    <h4>{format!("Claiming \"{}\"", design.title)}</h4>
+                                                    <p>
+                                                        {format!("${:.2}", design.price)}
+                                                        {design.size_inches.map(|size| format!(" · {}\"", size))}
+                                                        {if design.repeatable { " · Repeatable" } else { " · One of a kind" }}
+                                                    </p>
+                                                </div>
+                                            </div>
+                                        }.into_any(),
+                                        None => view! {
+                                            <div class="form-section">
+                                                <h4>"What are you booking?"</h4>
+                                                <select
+                                                    class="booking-modal-booking-type"
+                                                    prop:value=move || booking_type.get()
+                                                    on:change=move |ev| booking_type.set(event_target_value(&ev))
+                                                >
+                                                    {BOOKING_TYPES.iter().map(|(value, label)| view! {
+                                                        <option value=*value>{*label}</option>
+                                                    }).collect_view()}
+                                                </select>
+                                            </div>
+
+                                            <div class="form-section">
+                                                <h4>"How big is the tattoo?"</h4>
+                                                <select
+                                                    class="booking-modal-booking-type"
+                                                    on:change=move |ev| {
+                                                        size_inches.set(event_target_value(&ev).parse().ok());
+                                                        selected_time_slot.set(None);
+                                                    }
+                                                >
+                                                    <option value="">"Not sure yet"</option>
+                                                    {TATTOO_SIZES.iter().map(|(label, inches)| view! {
+                                                        <option value=inches.to_string()>{*label}</option>
+                                                    }).collect_view()}
+                                                </select>
+                                            </div>
+                                        }.into_any(),
+                                    }}
+
                                     <div class="form-section">
                                         <h4>"Select a Date & Time"</h4>
                                         <p class="auth-note">"Choose your preferred appointment slot from the artist's available times"</p>
 
+                                        {move || guest_spot.and_then(|spot| spot.get()).map(|spot| view! {
+                                            <p class="booking-modal-guest-spot">
+                                                {format!(
+                                                    "Guest spot at {}, {} to {}",
+                                                    spot.shop_name.unwrap_or_else(|| "another shop".to_string()),
+                                                    spot.start_date,
+                                                    spot.end_date
+                                                )}
+                                            </p>
+                                        })}
+
                                         <AvailableDatePicker
                                             artist_id=artist_id
+                                            guest_spot_id=guest_spot_id
                                             selected_date=requested_date
                                             on_date_selected=move |date| {
                                                 requested_date.set(date);
@@ -336,6 +427,7 @@ pub fn ClientBookingModal(
                                                     <TimeSlotPicker
                                                         artist_id=artist_id
                                                         selected_date=requested_date
+                                                        size_inches=size_inches
                                                         selected_time_slot=selected_time_slot
                                                         on_slot_selected=move |slot| {
                                                             selected_time_slot.set(Some(slot));
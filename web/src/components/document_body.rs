@@ -0,0 +1,66 @@
+use crate::utils::documents::{parse_blocks, spans, Block};
+use leptos::prelude::*;
+
+/// A run of blocks rendered together, list items grouped into their list
+enum Group {
+    Heading(String),
+    Paragraph(String),
+    Bullets(Vec<String>),
+    Numbered(u32, Vec<String>),
+}
+
+fn group(blocks: Vec<Block>) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for block in blocks {
+        match (block, groups.last_mut()) {
+            (Block::Bullet(text), Some(Group::Bullets(items))) => items.push(text),
+            (Block::Numbered(_, text), Some(Group::Numbered(_, items))) => items.push(text),
+            (Block::Bullet(text), _) => groups.push(Group::Bullets(vec![text])),
+            (Block::Numbered(start, text), _) => groups.push(Group::Numbered(start, vec![text])),
+            (Block::Heading(text), _) => groups.push(Group::Heading(text)),
+            (Block::Paragraph(text), _) => groups.push(Group::Paragraph(text)),
+        }
+    }
+    groups
+}
+
+fn inline(text: &str) -> impl IntoView {
+    spans(text)
+        .into_iter()
+        .map(|(span, bold)| {
+            let span = span.to_string();
+            if bold {
+                view! { <strong>{span}</strong> }.into_any()
+            } else {
+                span.into_any()
+            }
+        })
+        .collect_view()
+}
+
+/// A consent form or aftercare instructions, rendered from their markdown
+/// (see `utils::documents`)
+#[component]
+pub fn DocumentBody(text: String) -> impl IntoView {
+    let groups = group(parse_blocks(&text))
+        .into_iter()
+        .map(|group| match group {
+            Group::Heading(text) => view! { <h3>{inline(&text)}</h3> }.into_any(),
+            Group::Paragraph(text) => view! { <p>{inline(&text)}</p> }.into_any(),
+            Group::Bullets(items) => view! {
+                <ul>
+                    {items.iter().map(|item| view! { <li>{inline(item)}</li> }).collect_view()}
+                </ul>
+            }
+            .into_any(),
+            Group::Numbered(start, items) => view! {
+                <ol start=start.to_string()>
+                    {items.iter().map(|item| view! { <li>{inline(item)}</li> }).collect_view()}
+                </ol>
+            }
+            .into_any(),
+        })
+        .collect_view();
+
+    view! { <div class="document-body">{groups}</div> }
+}
@@ -35,6 +35,7 @@ pub fn log_component_error(
             None, // User agent
             None, // Session ID
             Some(additional_context),
+            option_env!("TATTEAU_BUILD_ID").map(str::to_string),
         )
         .await;
     });
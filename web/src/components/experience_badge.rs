@@ -0,0 +1,15 @@
+use crate::utils::experience::tier_label;
+use leptos::prelude::*;
+
+/// Badge for an artist's experience tier, e.g. "Apprentice". Renders
+/// nothing when the tier isn't set.
+#[component]
+pub fn ExperienceBadge(tier: Option<String>) -> impl IntoView {
+    tier.map(|tier| {
+        view! {
+            <span class=format!("experience-badge experience-badge-{}", tier)>
+                {tier_label(&tier)}
+            </span>
+        }
+    })
+}
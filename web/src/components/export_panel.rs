@@ -0,0 +1,163 @@
+use crate::db::entities::ExportJob;
+use crate::server_exports::{get_export_job, get_export_jobs, request_export};
+use crate::utils::auth::get_auth_token;
+use crate::utils::export::label;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+fn is_active(job: &ExportJob) -> bool {
+    job.status == "queued" || job.status == "running"
+}
+
+/// Starts CSV exports of `kinds` (from `utils::export`) and lists the
+/// signed-in user's recent ones. Exports run in the background; the panel
+/// polls those in progress and shows a download link once they finish.
+#[component]
+pub fn ExportPanel(kinds: &'static [(&'static str, &'static str)]) -> impl IntoView {
+    let jobs = RwSignal::new(Vec::<ExportJob>::new());
+    let error = RwSignal::new(None::<String>);
+
+    let replace = move |job: ExportJob| {
+        jobs.update(|jobs| match jobs.iter_mut().find(|j| j.id == job.id) {
+            Some(existing) => *existing = job,
+            None => jobs.insert(0, job),
+        });
+    };
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                if let Ok(loaded) = get_export_jobs(token).await {
+                    jobs.set(
+                        loaded
+                            .into_iter()
+                            .filter(|job| kinds.iter().any(|(kind, _)| *kind == job.kind))
+                            .collect(),
+                    );
+                }
+            });
+        }
+    });
+
+    // Poll exports in progress every couple of seconds
+    Effect::new(move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            let poll = move || {
+                let Some(token) = get_auth_token() else {
+                    return;
+                };
+                let active: Vec<String> = jobs.with_untracked(|jobs| {
+                    jobs.iter()
+                        .filter(|job| is_active(job))
+                        .map(|job| job.id.clone())
+                        .collect()
+                });
+                for id in active {
+                    let token = token.clone();
+                    spawn_local(async move {
+                        if let Ok(job) = get_export_job(token, id).await {
+                            replace(job);
+                        }
+                    });
+                }
+            };
+            if let Ok(handle) = set_interval_with_handle(poll, std::time::Duration::from_secs(2)) {
+                on_cleanup(move || handle.clear());
+            }
+        }
+    });
+
+    let start = move |kind: &'static str| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        error.set(None);
+        spawn_local(async move {
+            match request_export(token, kind.to_string()).await {
+                Ok(job) => replace(job),
+                Err(e) => error.set(Some(format!("Couldn't start the export: {}", e))),
+            }
+        });
+    };
+
+    view! {
+        <div class="export-panel">
+            <div class="export-actions">
+                {kinds
+                    .iter()
+                    .map(|(kind, kind_label)| {
+                        let kind = *kind;
+                        let running = move || {
+                            jobs.with(|jobs| jobs.iter().any(|job| job.kind == kind && is_active(job)))
+                        };
+                        view! {
+                            <button
+                                class="btn btn-secondary"
+                                disabled=running
+                                on:click=move |_| start(kind)
+                            >
+                                {format!("Export {}", kind_label)}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+
+            {move || error.get().map(|message| view! { <p class="export-error">{message}</p> })}
+
+            <Show when=move || !jobs.get().is_empty()>
+                <ul class="export-jobs">
+                    <For
+                        each=move || jobs.get()
+                        key=|job| (job.id.clone(), job.status.clone(), job.progress)
+                        children=move |job| {
+                            let created = job.created_at.get(..16).unwrap_or_default().to_string();
+                            let detail = match job.status.as_str() {
+                                "queued" => view! { <span class="export-status">"Queued"</span> }.into_any(),
+                                "running" => view! {
+                                    <span class="export-progress">
+                                        <span
+                                            class="export-progress-bar"
+                                            style=format!("width: {}%", job.progress)
+                                        ></span>
+                                    </span>
+                                    <span class="export-status">{format!("{}%", job.progress)}</span>
+                                }
+                                .into_any(),
+                                "complete" => match job.download_url.clone() {
+                                    Some(url) => view! {
+                                        <a class="btn btn-primary" href=url download="">
+                                            {format!("Download ({} rows)", job.rows_written)}
+                                        </a>
+                                    }
+                                    .into_any(),
+                                    None => view! { <span class="export-status">"Complete"</span> }.into_any(),
+                                },
+                                "expired" => view! {
+                                    <span class="export-status">"Expired, export again to download"</span>
+                                }
+                                .into_any(),
+                                _ => view! {
+                                    <span class="export-status failed">
+                                        {job.error.clone().unwrap_or_else(|| "Failed".to_string())}
+                                    </span>
+                                }
+                                .into_any(),
+                            };
+                            view! {
+                                <li class=format!("export-job {}", job.status)>
+                                    <div class="export-job-info">
+                                        <strong>{label(&job.kind).to_string()}</strong>
+                                        <span class="export-job-date">{created}</span>
+                                    </div>
+                                    <div class="export-job-detail">{detail}</div>
+                                </li>
+                            }
+                        }
+                    />
+                </ul>
+            </Show>
+        </div>
+    }
+}
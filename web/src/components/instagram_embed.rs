@@ -36,11 +36,191 @@ impl InstagramEmbedSize {
     }
 }
 
+/// localStorage key holding the visitor's choice, "granted" or "denied"
+const CONSENT_KEY: &str = "tatteau_instagram_consent";
+
+/// The visitor's Instagram embed consent and whether, given it, live embeds
+/// may load. Provided once at the app root.
+#[derive(Clone, Copy)]
+pub struct InstagramConsent {
+    consent: RwSignal<Option<bool>>,
+    live: Resource<bool>,
+}
+
+impl InstagramConsent {
+    /// `None` until the server has answered
+    pub fn live(&self) -> Option<bool> {
+        self.live.get()
+    }
+
+    pub fn consent(&self) -> Option<bool> {
+        self.consent.get()
+    }
+
+    /// Records the visitor's choice, switching every embed on the page
+    pub fn set_consent(&self, granted: bool) {
+        #[cfg(feature = "hydrate")]
+        {
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn setItem(key: &str, value: &str);
+            }
+
+            setItem(CONSENT_KEY, if granted { "granted" } else { "denied" });
+        }
+        self.consent.set(Some(granted));
+    }
+}
+
+pub fn provide_instagram_consent() {
+    let consent = RwSignal::new(None::<bool>);
+
+    // Load the stored choice on mount
+    Effect::new(move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn getItem(key: &str) -> Option<String>;
+            }
+
+            match getItem(CONSENT_KEY).as_deref() {
+                Some("granted") => consent.set(Some(true)),
+                Some("denied") => consent.set(Some(false)),
+                _ => {}
+            }
+        }
+    });
+
+    let live = Resource::new(
+        move || consent.get(),
+        // Nothing loads from Instagram if the server can't be asked
+        |consent| async move {
+            crate::server_instagram::get_instagram_embeds_allowed(consent)
+                .await
+                .unwrap_or(false)
+        },
+    );
+
+    provide_context(InstagramConsent { consent, live });
+}
+
+/// Whether live embeds may load; pages without the app root's context
+/// always allow them
+pub fn use_instagram_live() -> Signal<Option<bool>> {
+    let consent = use_context::<InstagramConsent>();
+    Signal::derive(move || match consent {
+        Some(consent) => consent.live(),
+        None => Some(true),
+    })
+}
+
+/// Shows `children` (a live embed) once Instagram embeds are allowed, and the
+/// post's saved preview until then
+#[component]
+pub fn InstagramConsentGate(
+    short_code: String,
+    #[prop(optional, default=InstagramEmbedSize::default())] size: InstagramEmbedSize,
+    children: ChildrenFn,
+) -> impl IntoView {
+    let live = use_instagram_live();
+    let container_class = size.container_class();
+
+    move || match live.get() {
+        Some(true) => children().into_any(),
+        Some(false) => view! {
+            <InstagramPreviewCard short_code=short_code.clone() size=size.clone() />
+        }
+        .into_any(),
+        None => view! {
+            <div class={container_class}>
+                <div class="instagram-embed-loading-overlay">
+                    <div class="instagram-embed-loading-content">
+                        <div class="instagram-embed-loading-spinner"></div>
+                    </div>
+                </div>
+            </div>
+        }
+        .into_any(),
+    }
+}
+
+/// A post's saved thumbnail and caption, with a button that consents to
+/// loading Instagram embeds
+#[component]
+pub fn InstagramPreviewCard(
+    short_code: String,
+    #[prop(optional, default=InstagramEmbedSize::default())] size: InstagramEmbedSize,
+) -> impl IntoView {
+    let consent = use_context::<InstagramConsent>();
+    let permalink = format!("https://www.instagram.com/p/{}/", short_code);
+    let preview = Resource::new(
+        move || short_code.clone(),
+        |short_code| async move {
+            crate::server_instagram::get_instagram_preview(short_code)
+                .await
+                .ok()
+        },
+    );
+
+    view! {
+        <div class=format!("{} instagram-embed-preview", size.container_class())>
+            <Suspense fallback=|| ()>
+                {move || preview.get().flatten().map(|preview| view! {
+                    {preview.thumbnail_url.map(|url| view! {
+                        <img
+                            class="instagram-embed-preview__image"
+                            src=url
+                            width=preview.thumbnail_width
+                            height=preview.thumbnail_height
+                            alt=preview.caption.clone().unwrap_or_else(|| "Tattoo post".to_string())
+                            loading="lazy"
+                        />
+                    })}
+                    {preview.caption.map(|caption| view! {
+                        <p class="instagram-embed-preview__caption">{caption}</p>
+                    })}
+                })}
+            </Suspense>
+            <div class="instagram-embed-preview__actions">
+                {consent.map(|consent| view! {
+                    <button
+                        class="instagram-embed-preview__consent"
+                        on:click=move |_| consent.set_consent(true)
+                    >
+                        "Show Instagram posts"
+                    </button>
+                })}
+                <a href=permalink target="_blank" rel="noopener" class="instagram-embed-preview__link">
+                    "View on Instagram"
+                </a>
+            </div>
+            <p class="instagram-embed-preview__notice">
+                "Showing Instagram posts loads content from Instagram, which may set cookies."
+            </p>
+        </div>
+    }
+}
+
 #[component]
 pub fn InstagramEmbed(
     short_code: String,
     #[prop(optional, default=InstagramEmbedSize::default())] size: InstagramEmbedSize,
 ) -> impl IntoView {
+    let gate_short_code = short_code.clone();
+    let gate_size = size.clone();
+
+    view! {
+        <InstagramConsentGate short_code=gate_short_code size=gate_size>
+            <LiveInstagramEmbed short_code=short_code.clone() size=size.clone() />
+        </InstagramConsentGate>
+    }
+}
+
+#[component]
+fn LiveInstagramEmbed(short_code: String, size: InstagramEmbedSize) -> impl IntoView {
     // Use a unique ID for each component instance to ensure Effects run on every render
     // Use a simple counter-based approach that works in both SSR and client
     use std::sync::atomic::{AtomicU64, Ordering};
@@ -208,13 +388,26 @@ pub fn InstagramEmbed(
     }
 }
 
-// Helper function to trigger Instagram processing for all embeds
+// Helper function to trigger Instagram processing for all embeds. Loads
+// Instagram's script on first use, only once there are live embeds on the
+// page, so previews shown in privacy mode never load it.
 #[wasm_bindgen]
 pub fn process_instagram_embeds() {
     web_sys::js_sys::eval(
         r#"
         if (window.instgrm && window.instgrm.Embeds) {
             window.instgrm.Embeds.process();
+        } else if (document.querySelector('blockquote[data-instgrm-permalink]')
+                   && !document.querySelector('script[src*="instagram.com/embed.js"]')) {
+            const script = document.createElement('script');
+            script.src = 'https://www.instagram.com/embed.js';
+            script.async = true;
+            script.onload = () => {
+                if (window.instgrm && window.instgrm.Embeds) {
+                    window.instgrm.Embeds.process();
+                }
+            };
+            document.head.appendChild(script);
         }
     "#,
     )
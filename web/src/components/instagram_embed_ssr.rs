@@ -1,18 +1,35 @@
+use crate::components::instagram_embed::{InstagramConsent, InstagramPreviewCard};
 use crate::components::instagram_fallback_cta::InstagramFallbackCta;
 use crate::server::get_instagram_embed;
+use crate::server_instagram::InstagramEmbedContent;
 use leptos::prelude::*;
 
 #[component]
 pub fn InstagramEmbedSsr(short_code: String) -> impl IntoView {
     let short_code_for_resource = short_code.clone();
     let short_code_for_fallback = short_code.clone();
+    let consent = use_context::<InstagramConsent>();
 
-    // Create a resource to fetch the Instagram embed from the server
+    // Create a resource to fetch the Instagram embed from the server,
+    // refetched when the visitor changes their consent
     let embed_resource = Resource::new(
-        move || short_code_for_resource.clone(),
-        move |short_code| async move { get_instagram_embed(short_code).await },
+        move || {
+            (
+                short_code_for_resource.clone(),
+                consent.and_then(|consent| consent.consent()),
+            )
+        },
+        move |(short_code, consent)| async move { get_instagram_embed(short_code, consent).await },
     );
 
+    // Instagram's script isn't on every page, and markup set through
+    // inner_html doesn't run the one oEmbed includes
+    Effect::new(move |_| {
+        if let Some(Ok(InstagramEmbedContent::Live { .. })) = embed_resource.get() {
+            crate::components::instagram_embed::process_instagram_embeds();
+        }
+    });
+
     view! {
         <div class="instagram-embed-ssr-container">
             <Suspense fallback=move || {
@@ -29,13 +46,20 @@ pub fn InstagramEmbedSsr(short_code: String) -> impl IntoView {
             }>
                 {move || {
                     match embed_resource.get() {
-                        Some(Ok(html)) => {
+                        Some(Ok(InstagramEmbedContent::Live { html })) => {
                             view! {
                                 <div class="instagram-embed-ssr-success">
                                     <div inner_html={html}></div>
                                 </div>
                             }.into_any()
                         }
+                        Some(Ok(InstagramEmbedContent::Preview(preview))) => {
+                            view! {
+                                <div class="instagram-embed-ssr-success">
+                                    <InstagramPreviewCard short_code=preview.short_code />
+                                </div>
+                            }.into_any()
+                        }
                         Some(Err(_)) => {
                             view! {
                                 <div class="instagram-embed-ssr-error">
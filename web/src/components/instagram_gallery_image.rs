@@ -0,0 +1,44 @@
+use crate::server_instagram::GALLERY_IMAGE_WIDTHS;
+use leptos::prelude::*;
+
+/// A post's image for gallery grids: a plain `<img>` served from our own
+/// storage, linking to the post on Instagram. Much lighter than an embed,
+/// and it loads nothing from Instagram, so it needs no consent.
+#[component]
+pub fn InstagramGalleryImage(
+    short_code: String,
+    /// How wide the image is shown, for picking from `srcset`
+    #[prop(optional, into, default = "(min-width: 768px) 33vw, 100vw".to_string())]
+    sizes: String,
+) -> impl IntoView {
+    let failed = RwSignal::new(false);
+    let permalink = format!("https://www.instagram.com/p/{}/", short_code);
+    let src = move |width: u32| format!("/api/instagram/{}/image/{}", short_code, width);
+    let srcset = GALLERY_IMAGE_WIDTHS
+        .iter()
+        .map(|width| format!("{} {}w", src(*width), width))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let default_src = src(GALLERY_IMAGE_WIDTHS[1]);
+
+    view! {
+        <a href=permalink target="_blank" rel="noopener" class="instagram-gallery-image">
+            <Show
+                when=move || !failed.get()
+                fallback=|| view! {
+                    <span class="instagram-gallery-image-missing">"View on Instagram"</span>
+                }
+            >
+                <img
+                    src=default_src.clone()
+                    srcset=srcset.clone()
+                    sizes=sizes.clone()
+                    alt="Tattoo post on Instagram"
+                    loading="lazy"
+                    decoding="async"
+                    on:error=move |_| failed.set(true)
+                />
+            </Show>
+        </a>
+    }
+}
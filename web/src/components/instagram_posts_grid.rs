@@ -1,5 +1,5 @@
 use crate::components::favorite_button::FavoriteButton;
-use crate::components::instagram_embed::InstagramEmbed;
+use crate::components::instagram_gallery_image::InstagramGalleryImage;
 use crate::components::style_tag::StyleTag;
 use crate::components::style_tag_manager::StyleTagManager;
 use crate::db::entities::{Artist, ArtistImage, Style};
@@ -78,6 +78,11 @@ pub fn InstagramPostsGrid(
                                                 }).collect_view()
                                             }}
                                         </div>
+
+                                        <a href={format!("/image/{}", image_id)}
+                                           class="instagram-posts-grid-similar-link">
+                                            "More like this →"
+                                        </a>
                                     </div>
 
                                     // Admin style tag manager
@@ -95,7 +100,7 @@ pub fn InstagramPostsGrid(
                                     />
                                 </div>
                                 <div class="instagram-posts-grid-embed-container">
-                                    <InstagramEmbed short_code={short_code} />
+                                    <InstagramGalleryImage short_code={short_code} />
                                 </div>
 
                             </div>
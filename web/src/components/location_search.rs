@@ -3,9 +3,10 @@ use wasm_bindgen::JsCast;
 use web_sys::KeyboardEvent;
 
 use crate::{
-    db::entities::CityCoords,
+    db::entities::{CityCoords, PlaceSuggestion},
     db::search_repository::SearchResult,
     server::{get_search_suggestions, universal_search},
+    server_places::{autocomplete_places, select_place},
 };
 
 #[component]
@@ -20,6 +21,9 @@ where
     let search_input = RwSignal::new(String::new());
     let search_results = RwSignal::new(Vec::<SearchResult>::new());
     let suggestions = RwSignal::new(Vec::<String>::new());
+    // Reference-table cities we have no shops in yet
+    let new_places = RwSignal::new(Vec::<PlaceSuggestion>::new());
+    let search_notice = RwSignal::new(Option::<String>::None);
     let is_searching = RwSignal::new(false);
     let show_suggestions = RwSignal::new(false);
     let selected_index = RwSignal::new(0usize);
@@ -30,22 +34,35 @@ where
     let fetch_suggestions = move |query: String| {
         if query.len() < 2 {
             suggestions.set(Vec::new());
+            new_places.set(Vec::new());
             show_suggestions.set(false);
             return;
         }
 
+        let place_query = query.clone();
         spawn_local(async move {
             match get_search_suggestions(query).await {
                 Ok(sugg) => {
                     suggestions.set(sugg);
-                    show_suggestions.set(!suggestions.get().is_empty());
+                    show_suggestions
+                        .set(!suggestions.get().is_empty() || !new_places.get().is_empty());
                 }
                 Err(_) => {
                     suggestions.set(Vec::new());
-                    show_suggestions.set(false);
+                    show_suggestions.set(!new_places.get().is_empty());
                 }
             }
         });
+        spawn_local(async move {
+            let places = autocomplete_places(place_query)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|place| !place.has_locations)
+                .collect::<Vec<_>>();
+            new_places.set(places);
+            show_suggestions.set(!suggestions.get().is_empty() || !new_places.get().is_empty());
+        });
     };
 
     // Perform the actual search
@@ -145,6 +162,7 @@ where
         let value = event_target_value(&ev);
         search_input.set(value.clone());
         selected_index.set(0);
+        search_notice.set(None);
         fetch_suggestions(value);
     };
 
@@ -165,6 +183,35 @@ where
         show_suggestions.set(false);
     };
 
+    // Picking a city we don't cover yet: move the map there and queue it for ingestion
+    let handle_place_click = move |place: PlaceSuggestion| {
+        show_suggestions.set(false);
+        search_error.set(None);
+        search_input.set(format!("{}, {}", place.city, place.state));
+        city.set(place.city.clone());
+        state.set(place.state.clone());
+        on_location_selected(CityCoords {
+            city: place.city.clone(),
+            state: place.state.clone(),
+            lat: place.lat,
+            long: place.long,
+        });
+
+        spawn_local(async move {
+            let queued = select_place(place.city.clone(), place.state)
+                .await
+                .unwrap_or(false);
+            search_notice.set(Some(if queued {
+                format!(
+                    "We don't list shops in {} yet. We've queued it and will add them soon.",
+                    place.city
+                )
+            } else {
+                format!("We don't list shops in {} yet.", place.city)
+            }));
+        });
+    };
+
     // Effect to position the fixed dropdown
     Effect::new(move |_| {
         if show_suggestions.get() {
@@ -250,7 +297,9 @@ where
 
             // Suggestions dropdown - render using portal to escape stacking contexts
             <Portal>
-                {move || if show_suggestions.get() && !suggestions.get().is_empty() {
+                {move || if show_suggestions.get()
+                    && (!suggestions.get().is_empty() || !new_places.get().is_empty())
+                {
                     view! {
                         <div class="location-search-suggestions-portal">
                             {suggestions.get().into_iter().enumerate().map(|(idx, suggestion)| {
@@ -284,6 +333,30 @@ where
                                     </div>
                                 }
                             }).collect_view()}
+                            {move || {
+                                let places = new_places.get();
+                                (!places.is_empty()).then(|| view! {
+                                    <div class="location-search-suggestion-heading">"More cities"</div>
+                                    {places.into_iter().map(|place| {
+                                        let label = match (place.ambiguous, place.population) {
+                                            (true, Some(population)) => format!(
+                                                "{}, {} (pop. {})",
+                                                place.city, place.state, population
+                                            ),
+                                            _ => format!("{}, {}", place.city, place.state),
+                                        };
+                                        view! {
+                                            <div
+                                                class="location-search-suggestion-item location-search-suggestion-new"
+                                                on:mousedown=move |_| handle_place_click(place.clone())
+                                            >
+                                                <span class="location-search-suggestion-icon">"🏙"</span>
+                                                <span>{label}</span>
+                                            </div>
+                                        }
+                                    }).collect_view()}
+                                })
+                            }}
                         </div>
                     }.into_any()
                 } else {
@@ -302,6 +375,10 @@ where
                 view! { <></> }.into_any()
             }}
 
+            {move || search_notice.get().map(|notice| view! {
+                <div class="location-search-notice">{notice}</div>
+            })}
+
             // Quick location shortcuts
             <div class="location-search-quick-locations">
                 <span class="location-search-quick-label">"Quick access: "</span>
@@ -3,6 +3,7 @@ use wasm_bindgen::{closure::Closure, JsCast};
 use web_sys::window;
 
 // Import the entities from the db module
+use crate::components::instagram_gallery_image::InstagramGalleryImage;
 use crate::components::style_tag::StyleTag;
 use crate::components::style_tag_manager::StyleTagManager;
 use crate::db::entities::{ArtistImage, Style};
@@ -426,122 +427,6 @@ pub fn generate_sample_posts() -> Vec<InstagramPost> {
     ]
 }
 
-/// Client-only Instagram embed component that avoids hydration issues
-#[component]
-fn InstagramEmbed(post: InstagramPost) -> impl IntoView {
-    // Track if we're on the client
-    let (is_client, set_is_client) = signal(false);
-    let embed_container_ref = NodeRef::<leptos::html::Div>::new();
-
-    // Clone short_code for use in the closure
-    let short_code_for_effect = post.image.short_code.clone();
-
-    // Only run on client side
-    Effect::new(move |_| {
-        // This effect only runs on the client after hydration
-        set_is_client.set(true);
-
-        // Insert the Instagram embed after hydration is complete
-        if let Some(container) = embed_container_ref.get() {
-            if let Some(window) = window() {
-                if let Some(document) = window.document() {
-                    // Clear the container first
-                    container.set_inner_html("");
-
-                    // Create the blockquote element programmatically
-                    if let Ok(blockquote) = document.create_element("blockquote") {
-                        blockquote.set_class_name("masonry-gallery-instagram-media");
-                        blockquote.set_attribute("data-instgrm-captioned", "").ok();
-                        blockquote
-                            .set_attribute(
-                                "data-instgrm-permalink",
-                                &format!("https://www.instagram.com/p/{}/", short_code_for_effect),
-                            )
-                            .ok();
-                        blockquote.set_attribute("data-instgrm-version", "14").ok();
-
-                        // Set the style
-                        blockquote.set_attribute(
-                            "style",
-                            "background:#FFF; border:0; border-radius:3px; box-shadow:0 0 1px 0 rgba(0,0,0,0.5),0 1px 10px 0 rgba(0,0,0,0.15); margin: 1px; max-width:100%; min-width:326px; padding:0; width:99.375%; width:-webkit-calc(100% - 2px); width:calc(100% - 2px);"
-                        ).ok();
-
-                        // Add a link inside
-                        if let Ok(link) = document.create_element("a") {
-                            link.set_attribute(
-                                "href",
-                                &format!("https://www.instagram.com/p/{}/", short_code_for_effect),
-                            )
-                            .ok();
-                            link.set_attribute("target", "_blank").ok();
-                            link.set_text_content(Some("View this post on Instagram"));
-                            blockquote.append_child(&link).ok();
-                        }
-
-                        // Append to container
-                        container.append_child(&blockquote).ok();
-
-                        // Process Instagram embeds after a short delay
-                        let closure = Closure::wrap(Box::new(move || {
-                            // Use JavaScript to process Instagram embeds
-                            if let Some(window) = web_sys::window() {
-                                if let Some(document) = window.document() {
-                                    if let Ok(script) = document.create_element("script") {
-                                        script.set_text_content(Some(
-                                            "if (window.instgrm && window.instgrm.Embeds) { window.instgrm.Embeds.process(); }"
-                                        ));
-                                        if let Some(body) = document.body() {
-                                            body.append_child(&script).ok();
-                                            body.remove_child(&script).ok();
-                                        }
-                                    }
-                                }
-                            }
-                        }) as Box<dyn FnMut()>);
-
-                        window
-                            .set_timeout_with_callback_and_timeout_and_arguments_0(
-                                closure.as_ref().unchecked_ref(),
-                                100,
-                            )
-                            .ok();
-                        closure.forget();
-                    }
-                }
-            }
-        }
-    });
-
-    view! {
-        <div class="masonry-gallery-instagram-embed-container">
-            <Show
-                when=move || !is_client.get()
-                fallback=move || view! {
-                    // Client-side: Container for the Instagram embed
-                    <div node_ref=embed_container_ref class="masonry-gallery-instagram-wrapper"></div>
-                }
-            >
-                // Server-side: Render a placeholder
-                <div class="masonry-gallery-instagram-placeholder">
-                    <div class="masonry-gallery-placeholder-content">
-                        <svg width="50" height="50" viewBox="0 0 60 60" fill="#ccc">
-                            <path d="M30,0 C13.4314567,0 0,13.4314567 0,30 C0,46.5685433 13.4314567,60 30,60 C46.5685433,60 60,46.5685433 60,30 C60,13.4314567 46.5685433,0 30,0 Z M30,54 C16.745166,54 6,43.254834 6,30 C6,16.745166 16.745166,6 30,6 C43.254834,6 54,16.745166 54,30 C54,43.254834 43.254834,54 30,54 Z"></path>
-                        </svg>
-                        <p>"Loading Instagram post..."</p>
-                        <a
-                            href=format!("https://www.instagram.com/p/{}/", post.image.short_code)
-                            target="_blank"
-                            class="masonry-gallery-fallback-link"
-                        >
-                            "View on Instagram"
-                        </a>
-                    </div>
-                </div>
-            </Show>
-        </div>
-    }
-}
-
 #[component]
 pub fn MasonryGallery(
     #[prop(optional, default = Vec::new())] instagram_posts: Vec<InstagramPost>,
@@ -640,8 +525,7 @@ pub fn MasonryGallery(
                                         })
                                     />
 
-                                    // Use the client-only component
-                                    <InstagramEmbed post=post.clone()/>
+                                    <InstagramGalleryImage short_code=post.image.short_code.clone() />
                                 </div>
                             </div>
                         }
@@ -0,0 +1,33 @@
+use crate::db::entities::BookingMessage;
+use crate::utils::translation::{language_name, translation_for};
+use leptos::prelude::*;
+
+/// A booking message's text as `reader` ("artist" or "client") should see
+/// it: translated into their language when it was, with the original a
+/// click away
+#[component]
+pub fn MessageText(message: BookingMessage, reader: &'static str) -> impl IntoView {
+    let Some(translation) = translation_for(&message, reader).map(str::to_string) else {
+        return view! {
+            <div class="message-text">
+                <p>{message.message}</p>
+            </div>
+        }
+        .into_any();
+    };
+    let summary = match message.language.as_deref() {
+        Some(language) => format!("Translated from {}", language_name(language)),
+        None => "Translated".to_string(),
+    };
+
+    view! {
+        <div class="message-text">
+            <p>{translation}</p>
+            <details class="message-text-original">
+                <summary>{summary}</summary>
+                <p>{message.message}</p>
+            </details>
+        </div>
+    }
+    .into_any()
+}
@@ -1,21 +1,29 @@
 pub mod artist_cta;
 pub mod artist_masonry_gallery;
+pub mod artist_questions;
 pub mod auth_guard;
 pub mod available_date_picker;
 pub mod client_booking_modal;
+pub mod document_body;
 pub mod error;
 pub mod error_boundary;
 pub mod event_item;
+pub mod experience_badge;
+pub mod export_panel;
 pub mod favorite_button;
 pub mod instagram_embed;
 pub mod instagram_embed_ssr;
 pub mod instagram_fallback_cta;
+pub mod instagram_gallery_image;
 pub mod instagram_posts_grid;
 pub mod loading;
 pub mod location_search;
 pub mod masonry_gallery;
+pub mod message_text;
 pub mod multi_step_questionnaire;
 pub mod navbar;
+pub mod save_to_board;
+pub mod share_button;
 pub mod shop_masonry_gallery;
 pub mod style_tag;
 pub mod style_tag_manager;
@@ -27,19 +35,30 @@ pub mod time_slot_picker;
 // Re-export commonly used types
 pub use artist_cta::ArtistCTA;
 pub use artist_masonry_gallery::ArtistMasonryGallery;
+pub use artist_questions::ArtistQuestions;
 pub use auth_guard::ArtistAuthGuard;
 pub use available_date_picker::AvailableDatePicker;
 pub use client_booking_modal::ClientBookingModal;
+pub use document_body::DocumentBody;
 pub use error_boundary::{log_component_error, ErrorBoundary};
 pub use event_item::{EventItem, EventItemData};
+pub use experience_badge::ExperienceBadge;
+pub use export_panel::ExportPanel;
 pub use favorite_button::FavoriteButton;
-pub use instagram_embed::{process_instagram_embeds, InstagramEmbed};
+pub use instagram_embed::{
+    process_instagram_embeds, provide_instagram_consent, InstagramConsentGate, InstagramEmbed,
+    InstagramPreviewCard,
+};
 pub use instagram_embed_ssr::InstagramEmbedSsr;
 pub use instagram_fallback_cta::InstagramFallbackCta;
+pub use instagram_gallery_image::InstagramGalleryImage;
 pub use instagram_posts_grid::InstagramPostsGrid;
 pub use masonry_gallery::MasonryGallery;
+pub use message_text::MessageText;
 pub use multi_step_questionnaire::MultiStepQuestionnaire;
 pub use navbar::Navbar;
+pub use save_to_board::SaveToBoard;
+pub use share_button::ShareButton;
 pub use shop_masonry_gallery::ShopMasonryGallery;
 pub use style_tag::StyleTag;
 pub use style_tag_manager::StyleTagManager;
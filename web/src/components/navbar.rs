@@ -1,5 +1,10 @@
-use crate::utils::auth::is_authenticated;
+#[cfg(feature = "hydrate")]
+use crate::server::logout;
+use crate::server_shops::get_my_shops;
+use crate::utils::auth::{get_auth_token, is_authenticated};
 use leptos::prelude::*;
+#[cfg(feature = "hydrate")]
+use leptos::task::spawn_local;
 use leptos_router::components::A;
 
 #[component]
@@ -7,6 +12,19 @@ pub fn Navbar() -> impl IntoView {
     // Track authentication state reactively
     let is_logged_in = RwSignal::new(false);
 
+    // Whether the user runs a shop, for the shop dashboard link
+    let runs_shop = Resource::new(
+        move || is_logged_in.get(),
+        |logged_in| async move {
+            match get_auth_token().filter(|_| logged_in) {
+                Some(token) => get_my_shops(token)
+                    .await
+                    .is_ok_and(|shops| !shops.is_empty()),
+                None => false,
+            }
+        },
+    );
+
     // Track menu open state
     let is_menu_open = RwSignal::new(false);
 
@@ -36,21 +54,33 @@ pub fn Navbar() -> impl IntoView {
 
             #[wasm_bindgen]
             extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn getItem(key: &str) -> Option<String>;
                 #[wasm_bindgen(js_namespace = localStorage)]
                 fn removeItem(key: &str);
             }
 
-            // Remove the auth token
+            let refresh_token = getItem("tatteau_refresh_token");
+
+            // Remove the auth tokens
             removeItem("tatteau_auth_token");
+            removeItem("tatteau_refresh_token");
 
             // Update state
             is_logged_in.set(false);
             is_menu_open.set(false);
 
-            // Redirect to home page
-            if let Some(window) = web_sys::window() {
-                let _ = window.location().set_href("/");
-            }
+            spawn_local(async move {
+                // Revoke the refresh token so it can't be used again
+                if let Some(refresh_token) = refresh_token {
+                    let _ = logout(refresh_token, false).await;
+                }
+
+                // Redirect to home page
+                if let Some(window) = web_sys::window() {
+                    let _ = window.location().set_href("/");
+                }
+            });
         }
     };
 
@@ -88,19 +118,40 @@ pub fn Navbar() -> impl IntoView {
                     <A href="/match" attr:class="navbar__link" on:click=close_menu>
                         "Find My Match"
                     </A>
+                    <A href="/flash" attr:class="navbar__link" on:click=close_menu>
+                        "Flash"
+                    </A>
                     <A href="/favorites" attr:class="navbar__link" on:click=close_menu>
                         "Favorites"
                     </A>
+                    <A href="/my-tattoos" attr:class="navbar__link" on:click=close_menu>
+                        "My Tattoos"
+                    </A>
 
                     {move || {
                         if is_logged_in.get() {
                             view! {
-                                <button
-                                    class="navbar__link navbar__link--cta"
-                                    on:click=handle_logout
-                                >
-                                    "Log Out"
-                                </button>
+                                <>
+                                    <A href="/dashboard" attr:class="navbar__link" on:click=close_menu>
+                                        "Dashboard"
+                                    </A>
+                                    <Transition fallback=|| ()>
+                                        {move || runs_shop.get().unwrap_or(false).then(|| view! {
+                                            <A href="/shop-dashboard" attr:class="navbar__link" on:click=close_menu>
+                                                "My Shop"
+                                            </A>
+                                        })}
+                                    </Transition>
+                                    <A href="/account" attr:class="navbar__link" on:click=close_menu>
+                                        "Account"
+                                    </A>
+                                    <button
+                                        class="navbar__link navbar__link--cta"
+                                        on:click=handle_logout
+                                    >
+                                        "Log Out"
+                                    </button>
+                                </>
                             }.into_any()
                         } else {
                             view! {
@@ -0,0 +1,135 @@
+use crate::db::entities::FavoriteCollection;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Dropdown listing the user's boards with a checkbox each, saving the image
+/// to or removing it from a board as boxes are toggled.
+#[component]
+pub fn SaveToBoard(
+    /// The artist image ID to file
+    artists_images_id: i32,
+    /// The user's boards
+    collections: Signal<Vec<FavoriteCollection>>,
+    /// Called after the image was added to or removed from a board
+    on_changed: Callback<()>,
+) -> impl IntoView {
+    let is_open = RwSignal::new(false);
+    let saved_in = RwSignal::new(Vec::<i64>::new());
+    let is_loading = RwSignal::new(false);
+
+    let get_auth_token = move || -> Option<String> {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn getItem(key: &str) -> Option<String>;
+            }
+
+            getItem("tatteau_auth_token")
+        }
+        #[cfg(not(feature = "hydrate"))]
+        {
+            None
+        }
+    };
+
+    // Load which boards already hold the image the first time the menu opens
+    let loaded = RwSignal::new(false);
+    Effect::new(move |_| {
+        if !is_open.get() || loaded.get_untracked() {
+            return;
+        }
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        loaded.set(true);
+        spawn_local(async move {
+            use crate::server_favorites::get_image_collections;
+
+            match get_image_collections(token, artists_images_id as i64).await {
+                Ok(ids) => saved_in.set(ids),
+                Err(e) => leptos::logging::error!("Failed to load boards for image: {:?}", e),
+            }
+        });
+    });
+
+    let toggle_board = move |collection_id: i64| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let was_saved = saved_in.get_untracked().contains(&collection_id);
+        is_loading.set(true);
+
+        spawn_local(async move {
+            use crate::server_favorites::{add_to_collection, remove_from_collection};
+
+            let result = if was_saved {
+                remove_from_collection(token, collection_id, artists_images_id as i64).await
+            } else {
+                add_to_collection(token, collection_id, artists_images_id as i64).await
+            };
+
+            match result {
+                Ok(()) => {
+                    saved_in.update(|ids| {
+                        if was_saved {
+                            ids.retain(|id| *id != collection_id);
+                        } else {
+                            ids.push(collection_id);
+                        }
+                    });
+                    on_changed.run(());
+                }
+                Err(e) => leptos::logging::error!("Failed to update board: {:?}", e),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    view! {
+        <div class="save-to-board">
+            <button
+                class="save-to-board__toggle"
+                on:click=move |_| is_open.update(|open| *open = !*open)
+            >
+                "Save to board"
+            </button>
+
+            <Show when=move || is_open.get()>
+                <div class="save-to-board__menu">
+                    {move || {
+                        let boards = collections.get();
+                        if boards.is_empty() {
+                            view! {
+                                <p class="save-to-board__empty">"Create a board to start sorting your favorites"</p>
+                            }
+                            .into_any()
+                        } else {
+                            boards
+                                .into_iter()
+                                .map(|board| {
+                                    let board_id = board.id;
+                                    view! {
+                                        <label class="save-to-board__option">
+                                            <input
+                                                type="checkbox"
+                                                prop:checked=move || saved_in.get().contains(&board_id)
+                                                disabled=move || is_loading.get()
+                                                on:change=move |_| toggle_board(board_id)
+                                            />
+                                            <span>{board.name}</span>
+                                        </label>
+                                    }
+                                })
+                                .collect_view()
+                                .into_any()
+                        }
+                    }}
+                </div>
+            </Show>
+        </div>
+    }
+}
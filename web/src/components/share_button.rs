@@ -0,0 +1,59 @@
+use crate::server_short_links::create_short_link;
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Copies a short link to the clipboard. `target_type` and `target` are as
+/// for `create_short_link`: an artist or shop id, or a search path with its
+/// query string.
+#[component]
+pub fn ShareButton(
+    target_type: &'static str,
+    #[prop(into)] target: Signal<String>,
+    #[prop(optional, into)] class: String,
+) -> impl IntoView {
+    let link = RwSignal::new(None::<String>);
+    let failed = RwSignal::new(false);
+
+    let share = move |_| {
+        failed.set(false);
+        let target = target.get_untracked();
+        spawn_local(async move {
+            match create_short_link(target_type.to_string(), target, get_auth_token()).await {
+                Ok(url) => {
+                    #[cfg(feature = "hydrate")]
+                    {
+                        use wasm_bindgen::prelude::*;
+
+                        #[wasm_bindgen]
+                        extern "C" {
+                            #[wasm_bindgen(js_namespace = ["navigator", "clipboard"], js_name = writeText)]
+                            fn write_text(text: &str);
+                        }
+
+                        write_text(&url);
+                    }
+                    link.set(Some(url));
+                }
+                Err(_) => failed.set(true),
+            }
+        });
+    };
+
+    view! {
+        <div class=format!("share-button {}", class)>
+            <button class="share-button-action" on:click=share>
+                "🔗 Share"
+            </button>
+            {move || link.get().map(|url| view! {
+                <span class="share-button-link">
+                    "Copied "
+                    <input type="text" readonly value=url />
+                </span>
+            })}
+            <Show when=move || failed.get()>
+                <span class="share-button-error">"Couldn't create a link, try again"</span>
+            </Show>
+        </div>
+    }
+}
@@ -1,8 +1,9 @@
 use crate::components::favorite_button::FavoriteButton;
-use crate::components::instagram_embed::InstagramEmbed;
+use crate::components::instagram_gallery_image::InstagramGalleryImage;
+use crate::components::save_to_board::SaveToBoard;
 use crate::components::style_tag::StyleTag;
 use crate::components::style_tag_manager::StyleTagManager;
-use crate::db::entities::{Artist, ArtistImage, Style};
+use crate::db::entities::{Artist, ArtistImage, FavoriteCollection, Style};
 use leptos::prelude::*;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -17,25 +18,15 @@ pub struct ShopInstagramPost {
 pub fn ShopMasonryGallery(
     shop_posts: Vec<ShopInstagramPost>,
     all_styles: Vec<Style>,
+    /// The viewer's boards; when given, each post gets a "Save to board" menu
+    #[prop(optional)]
+    collections: Option<Signal<Vec<FavoriteCollection>>>,
+    /// Called after a post was added to or removed from a board
+    #[prop(optional)]
+    on_collections_changed: Option<Callback<()>>,
 ) -> impl IntoView {
     // Store posts in a signal so they can be updated when styles change
     let posts_signal = RwSignal::new(shop_posts);
-    // Process all Instagram embeds after component mounts
-    Effect::new(move |_| {
-        // Wait for all embeds to render, then process them all at once
-        set_timeout(
-            move || {
-                let _ = web_sys::js_sys::eval(
-                    r#"
-                    if (window.instgrm && window.instgrm.Embeds) {
-                        window.instgrm.Embeds.process();
-                    }
-                    "#,
-                );
-            },
-            std::time::Duration::from_millis(500),
-        );
-    });
 
     view! {
         <div class="shop-masonry-gallery__container">
@@ -100,7 +91,19 @@ pub fn ShopMasonryGallery(
                                         />
                                     </div>
 
-                                    <InstagramEmbed short_code={short_code} />
+                                    {collections.map(|collections| view! {
+                                        <SaveToBoard
+                                            artists_images_id=image_id
+                                            collections=collections
+                                            on_changed=Callback::new(move |_| {
+                                                if let Some(on_changed) = on_collections_changed {
+                                                    on_changed.run(());
+                                                }
+                                            })
+                                        />
+                                    })}
+
+                                    <InstagramGalleryImage short_code={short_code} />
                                 </div>
                             </div>
                         }
@@ -62,6 +62,7 @@ pub fn TattooGallery(
                 years_experience: None,
                 styles_extracted: None,
                 shop_validated: None,
+                experience_tier: None,
             };
 
             // Create ArtistImage from post data
@@ -155,6 +156,9 @@ pub fn TattooGallery(
                                                             years_experience: None,
                                                             min_price: None,
                                                             max_price: None,
+                                                            explanation: Default::default(),
+                                                            response_time_label: None,
+                                                            experience_tier: None,
                                                         };
                                                         callback.run(matched_artist);
                                                     }
@@ -177,6 +181,9 @@ pub fn TattooGallery(
                                                             years_experience: None,
                                                             min_price: None,
                                                             max_price: None,
+                                                            explanation: Default::default(),
+                                                            response_time_label: None,
+                                                            experience_tier: None,
                                                         };
                                                         callback.run(matched_artist);
                                                     }
@@ -1,28 +1,26 @@
 use crate::server::{get_available_time_slots, TimeSlot};
 use leptos::prelude::*;
+use shared_types::datetime::Date;
 use thaw::*;
 
 #[component]
 pub fn TimeSlotPicker(
     artist_id: RwSignal<Option<i32>>,
     selected_date: RwSignal<String>,
+    /// The tattoo's size, so slots are long enough for it
+    size_inches: RwSignal<Option<f64>>,
     selected_time_slot: RwSignal<Option<TimeSlot>>,
     on_slot_selected: impl Fn(TimeSlot) + 'static + Copy + Send + Sync,
 ) -> impl IntoView {
     let time_slots_resource = Resource::new(
-        move || (artist_id.get(), selected_date.get()),
-        move |(id_opt, date)| async move {
-            if let Some(id) = id_opt {
-                if id != 0 && !date.trim().is_empty() {
-                    get_available_time_slots(id, date)
-                        .await
-                        .ok()
-                        .unwrap_or_default()
-                } else {
-                    vec![]
-                }
-            } else {
-                vec![]
+        move || (artist_id.get(), selected_date.get(), size_inches.get()),
+        move |(id_opt, date, size)| async move {
+            match (id_opt.filter(|id| *id != 0), Date::parse(date.trim())) {
+                (Some(id), Some(date)) => get_available_time_slots(id, date, size)
+                    .await
+                    .ok()
+                    .unwrap_or_default(),
+                _ => vec![],
             }
         },
     );
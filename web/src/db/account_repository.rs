@@ -0,0 +1,507 @@
+#[cfg(feature = "ssr")]
+use super::entities::PendingContactChange;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Postgres, Row, Transaction};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What a contact change is checked against
+#[cfg(feature = "ssr")]
+pub struct UserContact {
+    pub email: String,
+    pub phone: Option<String>,
+    pub password_hash: String,
+    pub role: String,
+}
+
+/// A change request as stored, for checking a code against
+#[cfg(feature = "ssr")]
+pub struct ContactChangeRequest {
+    pub id: i64,
+    pub channel: String,
+    pub new_value: String,
+    pub code_hash: String,
+    pub attempts: i32,
+    /// Not yet confirmed, cancelled or expired
+    pub open: bool,
+}
+
+#[cfg(feature = "ssr")]
+const PENDING_SELECT: &str = "SELECT id, channel, new_value,
+        TO_CHAR(expires_at, 'YYYY-MM-DD HH24:MI') as expires_at
+     FROM contact_change_requests";
+
+#[cfg(feature = "ssr")]
+fn pending_from_row(row: &PgRow) -> PendingContactChange {
+    PendingContactChange {
+        id: row.get("id"),
+        channel: row.get("channel"),
+        new_value: row.get("new_value"),
+        expires_at: row.get("expires_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_user_contact(user_id: i64) -> DbResult<Option<UserContact>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT email, phone, password_hash, role::text as role
+         FROM users WHERE id = $1 AND is_active = true",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| UserContact {
+        email: row.get("email"),
+        phone: row.get("phone"),
+        password_hash: row.get("password_hash"),
+        role: row.get("role"),
+    }))
+}
+
+/// Changes still waiting on their code, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_pending_changes(user_id: i64) -> DbResult<Vec<PendingContactChange>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE user_id = $1
+              AND confirmed_at IS NULL
+              AND cancelled_at IS NULL
+              AND expires_at > CURRENT_TIMESTAMP
+            ORDER BY created_at DESC",
+        PENDING_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(pending_from_row).collect())
+}
+
+/// Whether another account already uses this email (case-insensitively) or
+/// phone number (ignoring formatting)
+#[cfg(feature = "ssr")]
+pub async fn is_contact_taken(channel: &str, value: &str, user_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let query = match channel {
+        "email" => {
+            "SELECT EXISTS (SELECT 1 FROM users WHERE LOWER(email) = LOWER($1) AND id <> $2)"
+        }
+        _ => {
+            "SELECT EXISTS (
+                SELECT 1 FROM users
+                WHERE regexp_replace(phone, '[^0-9+]', '', 'g') = $1 AND id <> $2
+            )"
+        }
+    };
+
+    sqlx::query_scalar(query)
+        .bind(value)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Change requests the user made in the last `hours`
+#[cfg(feature = "ssr")]
+pub async fn count_recent_requests(user_id: i64, hours: i32) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contact_change_requests
+         WHERE user_id = $1 AND created_at > NOW() - make_interval(hours => $2)",
+    )
+    .bind(user_id)
+    .bind(hours)
+    .fetch_one(pool)
+    .await
+}
+
+/// Records a change request, cancelling any earlier one for the same channel
+#[cfg(feature = "ssr")]
+pub async fn insert_change_request(
+    user_id: i64,
+    channel: &str,
+    new_value: &str,
+    code_hash: &str,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> DbResult<PendingContactChange> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE contact_change_requests SET cancelled_at = CURRENT_TIMESTAMP
+         WHERE user_id = $1 AND channel = $2
+           AND confirmed_at IS NULL AND cancelled_at IS NULL",
+    )
+    .bind(user_id)
+    .bind(channel)
+    .execute(&mut *tx)
+    .await?;
+
+    let row = sqlx::query(
+        "INSERT INTO contact_change_requests (user_id, channel, new_value, code_hash, expires_at)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING id, channel, new_value, TO_CHAR(expires_at, 'YYYY-MM-DD HH24:MI') as expires_at",
+    )
+    .bind(user_id)
+    .bind(channel)
+    .bind(new_value)
+    .bind(code_hash)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(pending_from_row(&row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_change_request(
+    request_id: i64,
+    user_id: i64,
+) -> DbResult<Option<ContactChangeRequest>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT id, channel, new_value, code_hash, attempts,
+                (confirmed_at IS NULL AND cancelled_at IS NULL
+                 AND expires_at > CURRENT_TIMESTAMP) as open
+         FROM contact_change_requests
+         WHERE id = $1 AND user_id = $2",
+    )
+    .bind(request_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| ContactChangeRequest {
+        id: row.get("id"),
+        channel: row.get("channel"),
+        new_value: row.get("new_value"),
+        code_hash: row.get("code_hash"),
+        attempts: row.get("attempts"),
+        open: row.get("open"),
+    }))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn record_failed_attempt(request_id: i64) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE contact_change_requests SET attempts = attempts + 1 WHERE id = $1")
+        .bind(request_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn cancel_change_request(request_id: i64, user_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE contact_change_requests SET cancelled_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND user_id = $2
+           AND confirmed_at IS NULL AND cancelled_at IS NULL",
+    )
+    .bind(request_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Applies a verified change to the account and to the linked artist's
+/// public contact field, where that still held the old value or was empty.
+/// Returns `false` if the address was taken in the meantime.
+#[cfg(feature = "ssr")]
+pub async fn apply_change(request: &ContactChangeRequest, user_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    // Serializes concurrent changes to the same address
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(format!("contact:{}", request.new_value.to_lowercase()))
+        .execute(&mut *tx)
+        .await?;
+
+    let (column, taken_query) = match request.channel.as_str() {
+        "email" => (
+            "email",
+            "SELECT EXISTS (SELECT 1 FROM users WHERE LOWER(email) = LOWER($1) AND id <> $2)",
+        ),
+        _ => (
+            "phone",
+            "SELECT EXISTS (
+                SELECT 1 FROM users
+                WHERE regexp_replace(phone, '[^0-9+]', '', 'g') = $1 AND id <> $2
+            )",
+        ),
+    };
+
+    let taken: bool = sqlx::query_scalar(taken_query)
+        .bind(&request.new_value)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    if taken {
+        return Ok(false);
+    }
+
+    let old_value: Option<String> =
+        sqlx::query_scalar(&format!("SELECT {} FROM users WHERE id = $1", column))
+            .bind(user_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    sqlx::query(&format!("UPDATE users SET {} = $2 WHERE id = $1", column))
+        .bind(user_id)
+        .bind(&request.new_value)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(&format!(
+        "UPDATE artists a SET {column} = $2
+         FROM users u
+         WHERE u.id = $1 AND u.role = 'artist' AND a.id = u.artist_id
+           AND (a.{column} IS NULL OR a.{column} = '' OR a.{column} = $3)",
+        column = column
+    ))
+    .bind(user_id)
+    .bind(&request.new_value)
+    .bind(old_value.unwrap_or_default())
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE contact_change_requests SET confirmed_at = CURRENT_TIMESTAMP WHERE id = $1",
+    )
+    .bind(request.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Blocks sign-in to the account, and with `delete` also queues its data
+/// for the purge. Returns `false` if it was already deactivated.
+#[cfg(feature = "ssr")]
+pub async fn deactivate_user(user_id: i64, delete: bool) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE users SET
+            is_active = false,
+            deactivated_at = CURRENT_TIMESTAMP,
+            deletion_requested_at = CASE WHEN $2 THEN CURRENT_TIMESTAMP END
+         WHERE id = $1 AND is_active = true",
+    )
+    .bind(user_id)
+    .bind(delete)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Everything kept about the user as a client, as one JSON object:
+/// `{"user": {...}, "bookings": [...], "messages": [...], "favorites": [...],
+/// "questions": [...]}`. Archived bookings' messages are in their archives.
+#[cfg(feature = "ssr")]
+pub async fn export_user_data(user_id: i64) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT jsonb_build_object(
+                    'user', to_jsonb(u) - 'password_hash',
+                    'bookings', COALESCE(
+                        (SELECT jsonb_agg(to_jsonb(br) ORDER BY br.id)
+                         FROM booking_requests br
+                         WHERE br.client_user_id = u.id),
+                        '[]'::jsonb),
+                    'messages', COALESCE(
+                        (SELECT jsonb_agg(to_jsonb(bm) ORDER BY bm.id)
+                         FROM booking_messages bm
+                         JOIN booking_requests br ON br.id = bm.booking_request_id
+                         WHERE br.client_user_id = u.id),
+                        '[]'::jsonb),
+                    'favorites', COALESCE(
+                        (SELECT jsonb_agg(jsonb_build_object(
+                                    'artists_images_id', uf.artists_images_id,
+                                    'short_code', ai.short_code,
+                                    'artist_name', a.name)
+                                ORDER BY uf.id)
+                         FROM user_favorites uf
+                         LEFT JOIN artists_images ai ON ai.id = uf.artists_images_id
+                         LEFT JOIN artists a ON a.id = ai.artist_id
+                         WHERE uf.user_id = u.id),
+                        '[]'::jsonb),
+                    'questions', COALESCE(
+                        (SELECT jsonb_agg(jsonb_build_object(
+                                    'artist_name', a.name,
+                                    'asker_name', q.asker_name,
+                                    'asker_email', q.asker_email,
+                                    'question', q.question,
+                                    'answer', q.answer,
+                                    'created_at', q.created_at)
+                                ORDER BY q.id)
+                         FROM artist_questions q
+                         LEFT JOIN artists a ON a.id = q.artist_id
+                         WHERE q.asker_user_id = u.id),
+                        '[]'::jsonb)
+                )::text
+         FROM users u
+         WHERE u.id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Accounts whose deletion was asked for more than `days` ago and that
+/// haven't been purged or reactivated since
+#[cfg(feature = "ssr")]
+pub async fn get_accounts_to_purge(days: i32, limit: i64) -> DbResult<Vec<i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT id FROM users
+         WHERE deletion_requested_at < NOW() - make_interval(days => $1)
+           AND purged_at IS NULL
+           AND is_active = false
+         ORDER BY deletion_requested_at
+         LIMIT $2",
+    )
+    .bind(days)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// The email a purged account is left with, also put on its bookings
+#[cfg(feature = "ssr")]
+pub fn anonymized_email(user_id: i64) -> String {
+    format!("deleted-{}@deleted.invalid", user_id)
+}
+
+/// Replaces the client details on a purged user's booking requests, or just
+/// on `booking_id`, and blanks the messages they sent. Archived copies are
+/// scrubbed the same way by `archive::scrub_client`.
+#[cfg(feature = "ssr")]
+pub(crate) async fn anonymize_client_bookings(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: i64,
+    booking_id: Option<i32>,
+) -> DbResult<()> {
+    sqlx::query(
+        "UPDATE booking_requests SET
+            client_name = 'Deleted client',
+            client_email = $2,
+            client_phone = NULL,
+            message_from_client = NULL
+         WHERE client_user_id = $1 AND ($3::int IS NULL OR id = $3)",
+    )
+    .bind(user_id)
+    .bind(anonymized_email(user_id))
+    .bind(booking_id)
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE booking_messages bm SET message = '[deleted]', translated_message = NULL
+         FROM booking_requests br
+         WHERE br.id = bm.booking_request_id
+           AND br.client_user_id = $1
+           AND ($2::int IS NULL OR br.id = $2)
+           AND bm.sender_type = 'client'",
+    )
+    .bind(user_id)
+    .bind(booking_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Purges an account whose deletion is due. The user row stays behind,
+/// anonymized, so bookings and invoices still add up; the client details on
+/// their booking requests are replaced, the messages they sent blanked, the
+/// contact details on their profile questions cleared and their favorites,
+/// sessions and tattoo photos deleted. Returns the storage keys of the
+/// deleted photos, or `None` if the account was reactivated or purged in
+/// the meantime.
+#[cfg(feature = "ssr")]
+pub async fn purge_user(user_id: i64) -> DbResult<Option<Vec<String>>> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let purged = sqlx::query(
+        "UPDATE users SET
+            email = $2,
+            first_name = 'Deleted',
+            last_name = 'User',
+            phone = NULL,
+            password_hash = '',
+            artist_id = NULL,
+            purged_at = CURRENT_TIMESTAMP
+         WHERE id = $1
+           AND deletion_requested_at IS NOT NULL
+           AND purged_at IS NULL
+           AND is_active = false",
+    )
+    .bind(user_id)
+    .bind(anonymized_email(user_id))
+    .execute(&mut *tx)
+    .await?;
+    if purged.rows_affected() == 0 {
+        tx.rollback().await?;
+        return Ok(None);
+    }
+
+    anonymize_client_bookings(&mut tx, user_id, None).await?;
+
+    sqlx::query(
+        "UPDATE artist_questions SET asker_name = 'Deleted user', asker_email = NULL
+         WHERE asker_user_id = $1",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    for table in [
+        "user_favorites",
+        "favorite_collections",
+        "refresh_tokens",
+        "contact_change_requests",
+    ] {
+        sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let photos = sqlx::query(
+        "DELETE FROM client_tattoo_photos WHERE user_id = $1
+         RETURNING storage_key, thumbnail_key",
+    )
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(
+        photos
+            .iter()
+            .flat_map(|row| [row.get("storage_key"), row.get("thumbnail_key")])
+            .collect(),
+    ))
+}
@@ -0,0 +1,108 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A public API key, without its secret
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    pub key_prefix: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+const KEY_SELECT: &str = "SELECT id, name, key_prefix,
+        TO_CHAR(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI') as created_at,
+        TO_CHAR(last_used_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI') as last_used_at,
+        TO_CHAR(revoked_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI') as revoked_at
+     FROM api_keys";
+
+#[cfg(feature = "ssr")]
+fn key_from_row(row: &sqlx::postgres::PgRow) -> ApiKey {
+    ApiKey {
+        id: row.get("id"),
+        name: row.get("name"),
+        key_prefix: row.get("key_prefix"),
+        created_at: row.get("created_at"),
+        last_used_at: row.get("last_used_at"),
+        revoked_at: row.get("revoked_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn insert_key(name: &str, key_prefix: &str, key_hash: &str) -> DbResult<ApiKey> {
+    let pool = crate::db::pool::get_pool();
+
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO api_keys (name, key_prefix, key_hash) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(name)
+    .bind(key_prefix)
+    .bind(key_hash)
+    .fetch_one(pool)
+    .await?;
+
+    let row = sqlx::query(&format!("{} WHERE id = $1", KEY_SELECT))
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(key_from_row(&row))
+}
+
+/// The unrevoked key with this hash, noting that it was used
+#[cfg(feature = "ssr")]
+pub async fn use_key(key_hash: &str) -> DbResult<Option<(i64, String)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "UPDATE api_keys SET last_used_at = CURRENT_TIMESTAMP
+         WHERE key_hash = $1 AND revoked_at IS NULL
+         RETURNING id, name",
+    )
+    .bind(key_hash)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("id"), row.get("name"))))
+}
+
+/// Every key, newest first
+#[cfg(feature = "ssr")]
+pub async fn list_keys() -> DbResult<Vec<ApiKey>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!("{} ORDER BY id DESC", KEY_SELECT))
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(key_from_row).collect())
+}
+
+/// The revoked key, `None` if there's no such key or it was already revoked
+#[cfg(feature = "ssr")]
+pub async fn revoke_key(id: i64) -> DbResult<Option<ApiKey>> {
+    let pool = crate::db::pool::get_pool();
+
+    let revoked = sqlx::query(
+        "UPDATE api_keys SET revoked_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND revoked_at IS NULL",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?
+    .rows_affected();
+    if revoked == 0 {
+        return Ok(None);
+    }
+
+    let row = sqlx::query(&format!("{} WHERE id = $1", KEY_SELECT))
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(Some(key_from_row(&row)))
+}
@@ -0,0 +1,402 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+use crate::db::entities::{BookingArchiveStatus, BookingRestoreRequest};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// The client's free text on a booking, cleared from archived stubs and
+/// put back from the archive on restore
+#[cfg(feature = "ssr")]
+const ARCHIVED_COLUMNS: [&str; 7] = [
+    "client_phone",
+    "tattoo_description",
+    "placement",
+    "reference_images",
+    "message_from_client",
+    "artist_response",
+    "decline_reason",
+];
+
+/// Completed bookings whose appointment was more than `years` ago and that
+/// haven't been archived yet, oldest first. Bookings of clients waiting to
+/// be purged stay until they are, so they're archived anonymized.
+#[cfg(feature = "ssr")]
+pub async fn get_archivable_bookings(years: i32, limit: i64) -> DbResult<Vec<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT id
+         FROM booking_requests
+         WHERE status = 'completed'
+           AND archive_id IS NULL
+           AND requested_date::date < CURRENT_DATE - make_interval(years => $1)
+           AND NOT EXISTS (
+               SELECT 1 FROM users u
+               WHERE u.id = booking_requests.client_user_id
+                 AND u.deletion_requested_at IS NOT NULL
+                 AND u.purged_at IS NULL
+           )
+         ORDER BY requested_date::date, id
+         LIMIT $2",
+    )
+    .bind(years)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Each booking as a JSON object of its row with its messages and events,
+/// `{"booking": {...}, "messages": [...], "events": [...]}`
+#[cfg(feature = "ssr")]
+pub async fn export_bookings(booking_ids: &[i32]) -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT jsonb_build_object(
+                    'booking', to_jsonb(br),
+                    'messages', COALESCE(
+                        (SELECT jsonb_agg(to_jsonb(bm) ORDER BY bm.id)
+                         FROM booking_messages bm
+                         WHERE bm.booking_request_id = br.id),
+                        '[]'::jsonb),
+                    'events', COALESCE(
+                        (SELECT jsonb_agg(to_jsonb(be) ORDER BY be.id)
+                         FROM booking_events be
+                         WHERE be.booking_request_id = br.id),
+                        '[]'::jsonb)
+                )::text
+         FROM booking_requests br
+         WHERE br.id = ANY($1)
+         ORDER BY br.id",
+    )
+    .bind(booking_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// Turns exported bookings into stubs pointing at the archive stored at
+/// `storage_key`: their free text is cleared and the exported messages and
+/// events deleted. Bookings another run archived in the meantime are left
+/// alone. Returns the bookings archived.
+#[cfg(feature = "ssr")]
+pub async fn record_archive(
+    storage_key: &str,
+    size_bytes: i64,
+    booking_ids: &[i32],
+    message_ids: &[i32],
+    event_ids: &[i64],
+) -> DbResult<Vec<i32>> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let archive_id: i64 = sqlx::query_scalar(
+        "INSERT INTO booking_archives (storage_key, bookings, messages, events, size_bytes)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING id",
+    )
+    .bind(storage_key)
+    .bind(booking_ids.len() as i32)
+    .bind(message_ids.len() as i32)
+    .bind(event_ids.len() as i32)
+    .bind(size_bytes)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let cleared = ARCHIVED_COLUMNS
+        .iter()
+        .map(|column| format!("{} = NULL", column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let archived: Vec<i32> = sqlx::query_scalar(&format!(
+        "UPDATE booking_requests
+         SET archive_id = $1, archived_at = CURRENT_TIMESTAMP, {}
+         WHERE id = ANY($2) AND archive_id IS NULL
+         RETURNING id",
+        cleared
+    ))
+    .bind(archive_id)
+    .bind(booking_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if archived.is_empty() {
+        // Nothing left to point at it
+        tx.rollback().await?;
+        return Ok(archived);
+    }
+
+    sqlx::query("DELETE FROM booking_messages WHERE id = ANY($1) AND booking_request_id = ANY($2)")
+        .bind(message_ids)
+        .bind(&archived)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM booking_events WHERE id = ANY($1) AND booking_request_id = ANY($2)")
+        .bind(event_ids)
+        .bind(&archived)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(archived)
+}
+
+/// Storage key of the archive an archived booking was moved to
+#[cfg(feature = "ssr")]
+pub async fn get_archive_key(booking_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT a.storage_key
+         FROM booking_requests br
+         JOIN booking_archives a ON a.id = br.archive_id
+         WHERE br.id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Storage keys of the archives holding the client's bookings
+#[cfg(feature = "ssr")]
+pub async fn get_client_archive_keys(user_id: i64) -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT DISTINCT a.storage_key
+         FROM booking_requests br
+         JOIN booking_archives a ON a.id = br.archive_id
+         WHERE br.client_user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Records the new size of an archive that was rewritten in place
+#[cfg(feature = "ssr")]
+pub async fn update_archive_size(storage_key: &str, size_bytes: i64) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE booking_archives SET size_bytes = $2 WHERE storage_key = $1")
+        .bind(storage_key)
+        .bind(size_bytes)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Puts an archived booking's free text, messages and events back from its
+/// archive entry. `false` if it had already been restored. A booking whose
+/// client has since been purged comes back anonymized, whatever the archive
+/// held.
+#[cfg(feature = "ssr")]
+pub async fn restore_booking(
+    booking_id: i32,
+    booking: &str,
+    messages: &str,
+    events: &str,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let restored = ARCHIVED_COLUMNS
+        .iter()
+        .map(|column| format!("{0} = archived.{0}", column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let result = sqlx::query(&format!(
+        "UPDATE booking_requests br
+         SET archive_id = NULL, archived_at = NULL, {}
+         FROM jsonb_populate_record(NULL::booking_requests, $2::jsonb) archived
+         WHERE br.id = $1 AND br.archive_id IS NOT NULL",
+        restored
+    ))
+    .bind(booking_id)
+    .bind(booking)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        tx.rollback().await?;
+        return Ok(false);
+    }
+
+    sqlx::query(
+        "INSERT INTO booking_messages
+         SELECT * FROM jsonb_populate_recordset(NULL::booking_messages, $1::jsonb)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(messages)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO booking_events
+         SELECT * FROM jsonb_populate_recordset(NULL::booking_events, $1::jsonb)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(events)
+    .execute(&mut *tx)
+    .await?;
+
+    let purged_client: Option<i64> = sqlx::query_scalar(
+        "SELECT u.id
+         FROM booking_requests br
+         JOIN users u ON u.id = br.client_user_id
+         WHERE br.id = $1 AND u.purged_at IS NOT NULL",
+    )
+    .bind(booking_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if let Some(user_id) = purged_client {
+        crate::db::account_repository::anonymize_client_bookings(
+            &mut tx,
+            user_id,
+            Some(booking_id),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// When a booking was archived and where its latest restore request is at,
+/// `None` if it isn't archived
+#[cfg(feature = "ssr")]
+pub async fn get_archive_status(booking_id: i32) -> DbResult<Option<BookingArchiveStatus>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT TO_CHAR(br.archived_at, 'YYYY-MM-DD') as archived_on,
+                (SELECT rr.status
+                 FROM booking_restore_requests rr
+                 WHERE rr.booking_request_id = br.id
+                 ORDER BY rr.created_at DESC, rr.id DESC
+                 LIMIT 1) as restore_status
+         FROM booking_requests br
+         WHERE br.id = $1 AND br.archive_id IS NOT NULL",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| BookingArchiveStatus {
+        archived_on: row.get("archived_on"),
+        restore_status: row.get("restore_status"),
+    }))
+}
+
+/// Asks for an archived booking back. `None` if it isn't archived or
+/// already has a request waiting.
+#[cfg(feature = "ssr")]
+pub async fn create_restore_request(
+    booking_id: i32,
+    requested_by: i64,
+    reason: &str,
+) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "INSERT INTO booking_restore_requests (booking_request_id, requested_by, reason)
+         SELECT id, $2, $3 FROM booking_requests WHERE id = $1 AND archive_id IS NOT NULL
+         ON CONFLICT (booking_request_id) WHERE status = 'pending' DO NOTHING
+         RETURNING id",
+    )
+    .bind(booking_id)
+    .bind(requested_by)
+    .bind(reason)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Restore requests with `status` for admins, oldest first
+#[cfg(feature = "ssr")]
+pub async fn get_restore_requests(
+    status: &str,
+    limit: i64,
+) -> DbResult<Vec<BookingRestoreRequest>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT rr.id, rr.booking_request_id, br.artist_id, a.name as artist_name,
+                br.client_name, br.requested_date::text as requested_date,
+                TO_CHAR(br.archived_at, 'YYYY-MM-DD') as archived_on,
+                rr.reason, rr.status, rr.error,
+                TO_CHAR(rr.created_at, 'YYYY-MM-DD HH24:MI') as requested_at
+         FROM booking_restore_requests rr
+         JOIN booking_requests br ON br.id = rr.booking_request_id
+         LEFT JOIN artists a ON a.id = br.artist_id
+         WHERE rr.status = $1
+         ORDER BY rr.created_at, rr.id
+         LIMIT $2",
+    )
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| BookingRestoreRequest {
+            id: row.get("id"),
+            booking_id: row.get("booking_request_id"),
+            artist_id: row.get("artist_id"),
+            artist_name: row.get("artist_name"),
+            client_name: row.get("client_name"),
+            requested_date: row.get("requested_date"),
+            archived_on: row.get("archived_on"),
+            reason: row.get("reason"),
+            status: row.get("status"),
+            error: row.get("error"),
+            requested_at: row.get("requested_at"),
+        })
+        .collect())
+}
+
+/// Records an admin's decision on a pending request, or their retry of a
+/// failed one, returning the booking to restore when approved. `None` if
+/// the request isn't open.
+#[cfg(feature = "ssr")]
+pub async fn review_restore_request(
+    request_id: i32,
+    approve: bool,
+    admin_id: i64,
+) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "UPDATE booking_restore_requests
+         SET status = CASE WHEN $2 THEN status ELSE 'rejected' END,
+             reviewed_by = $3, reviewed_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status IN ('pending', 'failed')
+         RETURNING booking_request_id",
+    )
+    .bind(request_id)
+    .bind(approve)
+    .bind(admin_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Marks an approved request restored, or failed with why
+#[cfg(feature = "ssr")]
+pub async fn finish_restore_request(request_id: i32, error: Option<&str>) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE booking_restore_requests
+         SET status = CASE WHEN $2::text IS NULL THEN 'restored' ELSE 'failed' END,
+             error = $2
+         WHERE id = $1",
+    )
+    .bind(request_id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+#[cfg(feature = "ssr")]
+use super::entities::{ArtistQuestion, ProfileQuestion};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What the artist is told about a question that reached them
+#[cfg(feature = "ssr")]
+pub struct NewQuestionNotice {
+    pub artist_id: i32,
+    /// The artist's sign-in email, or their profile email
+    pub artist_email: Option<String>,
+    pub asker_name: String,
+    pub question: String,
+}
+
+/// What the asker is told when their question is answered
+#[cfg(feature = "ssr")]
+pub struct AnswerNotice {
+    pub asker_email: Option<String>,
+    pub artist_name: Option<String>,
+    pub question: String,
+    /// False when an earlier answer was edited
+    pub first_answer: bool,
+}
+
+/// The flag on questions artists sent to the moderation queue
+#[cfg(feature = "ssr")]
+const ARTIST_REPORT_REASON: &str = "Reported by the artist";
+
+#[cfg(feature = "ssr")]
+const QUESTION_SELECT: &str = "SELECT q.id, q.artist_id, a.name as artist_name, q.asker_name,
+        q.question, q.answer, q.status, q.flag_reason,
+        TO_CHAR(q.created_at, 'YYYY-MM-DD HH24:MI') as asked_at,
+        TO_CHAR(q.answered_at, 'YYYY-MM-DD HH24:MI') as answered_at
+     FROM artist_questions q
+     JOIN artists a ON a.id = q.artist_id";
+
+#[cfg(feature = "ssr")]
+fn question_from_row(row: &PgRow) -> ArtistQuestion {
+    ArtistQuestion {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        asker_name: row.get("asker_name"),
+        question: row.get("question"),
+        answer: row.get("answer"),
+        status: row.get("status"),
+        flag_reason: row.get("flag_reason"),
+        asked_at: row.get("asked_at"),
+        answered_at: row.get("answered_at"),
+    }
+}
+
+/// Whether the artist exists and can be asked questions
+#[cfg(feature = "ssr")]
+pub async fn artist_exists(artist_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM artists WHERE id = $1)")
+        .bind(artist_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Saves a question, held for moderation when `flag_reason` is set.
+/// Returns its id.
+#[cfg(feature = "ssr")]
+pub async fn insert_question(
+    artist_id: i32,
+    asker_user_id: Option<i64>,
+    asker_name: &str,
+    asker_email: Option<&str>,
+    question: &str,
+    flag_reason: Option<&str>,
+) -> DbResult<i32> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "INSERT INTO artist_questions
+            (artist_id, asker_user_id, asker_name, asker_email, question, status, flag_reason)
+         VALUES ($1, $2, $3, $4, $5, CASE WHEN $6::TEXT IS NULL THEN 'approved' ELSE 'held' END, $6)
+         RETURNING id",
+    )
+    .bind(artist_id)
+    .bind(asker_user_id)
+    .bind(asker_name)
+    .bind(asker_email)
+    .bind(question)
+    .bind(flag_reason)
+    .fetch_one(pool)
+    .await
+}
+
+/// What to tell the artist about an approved, unanswered question; `None`
+/// otherwise
+#[cfg(feature = "ssr")]
+pub async fn get_new_question_notice(question_id: i32) -> DbResult<Option<NewQuestionNotice>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT q.artist_id, q.asker_name, q.question,
+                COALESCE(
+                    (SELECT u.email FROM users u
+                     WHERE u.artist_id = q.artist_id AND u.role = 'artist' AND u.is_active = true
+                     ORDER BY u.id LIMIT 1),
+                    a.email
+                ) as artist_email
+         FROM artist_questions q
+         JOIN artists a ON a.id = q.artist_id
+         WHERE q.id = $1 AND q.status = 'approved' AND q.answer IS NULL",
+    )
+    .bind(question_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| NewQuestionNotice {
+        artist_id: row.get("artist_id"),
+        artist_email: row.get("artist_email"),
+        asker_name: row.get("asker_name"),
+        question: row.get("question"),
+    }))
+}
+
+/// The artist's approved questions, unanswered first, then newest first
+#[cfg(feature = "ssr")]
+pub async fn get_artist_questions(artist_id: i32, limit: i64) -> DbResult<Vec<ArtistQuestion>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE q.artist_id = $1 AND q.status = 'approved'
+         ORDER BY q.answer IS NOT NULL, q.created_at DESC
+         LIMIT $2",
+        QUESTION_SELECT
+    ))
+    .bind(artist_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(question_from_row).collect())
+}
+
+/// The artist's answered, approved questions for their profile, most
+/// recently answered first
+#[cfg(feature = "ssr")]
+pub async fn get_profile_questions(artist_id: i32, limit: i64) -> DbResult<Vec<ProfileQuestion>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, asker_name, question, answer,
+                TO_CHAR(answered_at, 'YYYY-MM-DD') as answered_on
+         FROM artist_questions
+         WHERE artist_id = $1 AND status = 'approved' AND answer IS NOT NULL
+         ORDER BY answered_at DESC
+         LIMIT $2",
+    )
+    .bind(artist_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let asker_name: String = row.get("asker_name");
+            ProfileQuestion {
+                id: row.get("id"),
+                asker_name: crate::utils::artist_questions::public_name(&asker_name),
+                question: row.get("question"),
+                answer: row.get("answer"),
+                answered_on: row.get("answered_on"),
+            }
+        })
+        .collect())
+}
+
+/// Answers one of the artist's approved questions, or replaces the answer.
+/// `None` if it isn't theirs or isn't approved.
+#[cfg(feature = "ssr")]
+pub async fn answer_question(
+    artist_id: i32,
+    question_id: i32,
+    answer: &str,
+) -> DbResult<Option<AnswerNotice>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "UPDATE artist_questions q
+         SET answer = $3, answered_at = COALESCE(q.answered_at, CURRENT_TIMESTAMP)
+         FROM (SELECT id, answered_at IS NULL as first_answer
+               FROM artist_questions
+               WHERE id = $1 AND artist_id = $2 AND status = 'approved'
+               FOR UPDATE) previous
+         WHERE q.id = previous.id
+         RETURNING q.asker_email, q.question, previous.first_answer,
+                   (SELECT a.name FROM artists a WHERE a.id = q.artist_id) as artist_name",
+    )
+    .bind(question_id)
+    .bind(artist_id)
+    .bind(answer)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| AnswerNotice {
+        asker_email: row.get("asker_email"),
+        artist_name: row.get("artist_name"),
+        question: row.get("question"),
+        first_answer: row.get("first_answer"),
+    }))
+}
+
+/// Sends one of the artist's questions to the moderation queue, taking it
+/// off their profile. False if it isn't theirs or isn't approved.
+#[cfg(feature = "ssr")]
+pub async fn report_question(artist_id: i32, question_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_questions
+         SET status = 'held', flag_reason = $3
+         WHERE id = $1 AND artist_id = $2 AND status = 'approved'",
+    )
+    .bind(question_id)
+    .bind(artist_id)
+    .bind(ARTIST_REPORT_REASON)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Questions with the given status for admins, oldest first
+#[cfg(feature = "ssr")]
+pub async fn get_questions_by_status(status: &str, limit: i64) -> DbResult<Vec<ArtistQuestion>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE q.status = $1 ORDER BY q.created_at LIMIT $2",
+        QUESTION_SELECT
+    ))
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(question_from_row).collect())
+}
+
+/// Approves a question, sending it to the artist, or rejects it as spam.
+/// `None` if there's no such question; otherwise whether it's reaching the
+/// artist for the first time, so they should be told about it.
+#[cfg(feature = "ssr")]
+pub async fn moderate_question(
+    question_id: i32,
+    approve: bool,
+    admin_id: i64,
+) -> DbResult<Option<bool>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "UPDATE artist_questions q
+         SET status = CASE WHEN $2 THEN 'approved' ELSE 'rejected' END,
+             flag_reason = CASE WHEN $2 THEN NULL ELSE q.flag_reason END,
+             moderated_by = $3, moderated_at = CURRENT_TIMESTAMP
+         FROM (SELECT id, status, flag_reason
+               FROM artist_questions
+               WHERE id = $1
+               FOR UPDATE) previous
+         WHERE q.id = previous.id
+         RETURNING $2 AND previous.status <> 'approved'
+                   AND previous.flag_reason IS DISTINCT FROM $4",
+    )
+    .bind(question_id)
+    .bind(approve)
+    .bind(admin_id)
+    .bind(ARTIST_REPORT_REASON)
+    .fetch_optional(pool)
+    .await
+}
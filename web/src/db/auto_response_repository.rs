@@ -0,0 +1,248 @@
+#[cfg(feature = "ssr")]
+use super::entities::{AutoResponseSettings, AutoResponseTemplate, BookingMessage};
+#[cfg(feature = "ssr")]
+use crate::translate::MessageTranslation;
+#[cfg(feature = "ssr")]
+use sqlx::{Postgres, Row, Transaction};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A request whose auto-response is due, claimed for sending
+#[cfg(feature = "ssr")]
+pub struct DueAutoResponse {
+    pub booking_id: i32,
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    pub booking_type: String,
+    pub client_name: String,
+    pub requested_date: String,
+    pub requested_start_time: String,
+}
+
+/// The artist's settings and templates, disabled with no templates if they
+/// haven't set any up
+#[cfg(feature = "ssr")]
+pub async fn get_settings(artist_id: i32) -> DbResult<AutoResponseSettings> {
+    let pool = crate::db::pool::get_pool();
+
+    let settings = sqlx::query(
+        "SELECT is_enabled, delay_minutes FROM artist_auto_response_settings WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let templates = sqlx::query(
+        "SELECT booking_type, message
+         FROM artist_auto_responses
+         WHERE artist_id = $1
+         ORDER BY booking_type = 'default' DESC, booking_type",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(AutoResponseSettings {
+        enabled: settings
+            .as_ref()
+            .map(|row| row.get("is_enabled"))
+            .unwrap_or(false),
+        delay_minutes: settings
+            .as_ref()
+            .map(|row| row.get("delay_minutes"))
+            .unwrap_or(0),
+        templates: templates
+            .into_iter()
+            .map(|row| AutoResponseTemplate {
+                booking_type: row.get("booking_type"),
+                message: row.get("message"),
+            })
+            .collect(),
+    })
+}
+
+/// Replaces the artist's settings and templates. Templates left blank are
+/// dropped.
+#[cfg(feature = "ssr")]
+pub async fn save_settings(artist_id: i32, settings: &AutoResponseSettings) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO artist_auto_response_settings (artist_id, is_enabled, delay_minutes)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (artist_id) DO UPDATE SET
+             is_enabled = EXCLUDED.is_enabled,
+             delay_minutes = EXCLUDED.delay_minutes,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(artist_id)
+    .bind(settings.enabled)
+    .bind(settings.delay_minutes)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM artist_auto_responses WHERE artist_id = $1")
+        .bind(artist_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for template in settings
+        .templates
+        .iter()
+        .filter(|template| !template.message.trim().is_empty())
+    {
+        sqlx::query(
+            "INSERT INTO artist_auto_responses (artist_id, booking_type, message)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(artist_id)
+        .bind(&template.booking_type)
+        .bind(template.message.trim())
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Sets when a new request's auto-response goes out, if the artist has them
+/// switched on and a template covers the request's booking type. Returns the
+/// delay in minutes, or None when no reply is scheduled.
+#[cfg(feature = "ssr")]
+pub async fn schedule_in(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: i32,
+) -> DbResult<Option<i32>> {
+    let row = sqlx::query(
+        "UPDATE booking_requests br
+         SET auto_response_due_at =
+             br.created_at::timestamptz + make_interval(mins => s.delay_minutes)
+         FROM artist_auto_response_settings s
+         WHERE br.id = $1
+           AND s.artist_id = br.artist_id
+           AND s.is_enabled
+           AND EXISTS (
+               SELECT 1 FROM artist_auto_responses r
+               WHERE r.artist_id = br.artist_id
+                 AND r.booking_type IN (br.booking_type, 'default')
+           )
+         RETURNING s.delay_minutes",
+    )
+    .bind(booking_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(row.map(|row| row.get("delay_minutes")))
+}
+
+/// Marks up to `limit` due auto-responses as sent and returns them. Requests
+/// the artist has already answered, by message or otherwise, are skipped for
+/// good.
+#[cfg(feature = "ssr")]
+pub async fn claim_due(limit: i64) -> DbResult<Vec<DueAutoResponse>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "WITH due AS (
+             SELECT br.id
+             FROM booking_requests br
+             WHERE br.auto_response_due_at <= CURRENT_TIMESTAMP
+               AND br.auto_response_sent_at IS NULL
+             ORDER BY br.auto_response_due_at
+             LIMIT $1
+             FOR UPDATE SKIP LOCKED
+         )
+         UPDATE booking_requests br
+         SET auto_response_sent_at = CURRENT_TIMESTAMP
+         FROM due
+         WHERE br.id = due.id
+         RETURNING br.id, br.artist_id, br.booking_type, br.client_name,
+                   br.requested_date, br.requested_start_time,
+                   br.status = 'pending'
+                       AND br.first_responded_at IS NULL
+                       AND NOT EXISTS (
+                           SELECT 1 FROM booking_messages bm
+                           WHERE bm.booking_request_id = br.id AND bm.sender_type = 'artist'
+                       ) as unanswered,
+                   (SELECT a.name FROM artists a WHERE a.id = br.artist_id) as artist_name",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter(|row| row.get::<bool, _>("unanswered"))
+        .map(|row| DueAutoResponse {
+            booking_id: row.get("id"),
+            artist_id: row.get("artist_id"),
+            artist_name: row.get("artist_name"),
+            booking_type: row.get("booking_type"),
+            client_name: row.get("client_name"),
+            requested_date: row.get("requested_date"),
+            requested_start_time: row.get("requested_start_time"),
+        })
+        .collect())
+}
+
+/// The artist's template for a booking type, falling back to their default
+#[cfg(feature = "ssr")]
+pub async fn get_template(artist_id: i32, booking_type: &str) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT message
+         FROM artist_auto_responses
+         WHERE artist_id = $1 AND booking_type IN ($2, 'default')
+         ORDER BY booking_type = 'default'
+         LIMIT 1",
+    )
+    .bind(artist_id)
+    .bind(booking_type)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| row.get("message")))
+}
+
+/// Posts the reply to the booking's thread as the artist. Unlike a manual
+/// message it doesn't count as the artist's first response.
+#[cfg(feature = "ssr")]
+pub async fn insert_message(
+    booking_id: i32,
+    message: &str,
+    translation: &MessageTranslation,
+) -> DbResult<BookingMessage> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO booking_messages
+             (booking_request_id, sender_type, message,
+              language, translated_message, translated_language)
+         VALUES ($1, 'artist', $2, $3, $4, $5)
+         RETURNING id, booking_request_id, sender_type, message, created_at,
+                   language, translated_message, translated_language",
+    )
+    .bind(booking_id)
+    .bind(message)
+    .bind(&translation.language)
+    .bind(&translation.translated_message)
+    .bind(&translation.translated_language)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(BookingMessage {
+        id: row.get("id"),
+        booking_request_id: row.get("booking_request_id"),
+        sender_type: row.get("sender_type"),
+        message: row.get("message"),
+        created_at: row.get("created_at"),
+        language: row.get("language"),
+        translated_message: row.get("translated_message"),
+        translated_language: row.get("translated_language"),
+        is_read: false,
+    })
+}
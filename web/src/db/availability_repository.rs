@@ -0,0 +1,205 @@
+#[cfg(feature = "ssr")]
+use super::entities::AppointmentSettings;
+#[cfg(feature = "ssr")]
+use availability::{
+    parse_date, parse_time, Booking, BusinessHours, BusyBlock, Override, Schedule, SlotSettings,
+};
+#[cfg(feature = "ssr")]
+use chrono::NaiveDate;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// The artist's slot settings, or the defaults if they never saved any
+#[cfg(feature = "ssr")]
+pub async fn get_appointment_settings(artist_id: i32) -> DbResult<AppointmentSettings> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT session_minutes, slot_minutes, buffer_minutes
+         FROM appointment_settings
+         WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match row {
+        Some(row) => AppointmentSettings {
+            session_minutes: row.get("session_minutes"),
+            slot_minutes: row.get("slot_minutes"),
+            buffer_minutes: row.get("buffer_minutes"),
+        },
+        None => {
+            let defaults = SlotSettings::default();
+            AppointmentSettings {
+                session_minutes: defaults.session_minutes as i32,
+                slot_minutes: defaults.slot_minutes as i32,
+                buffer_minutes: defaults.buffer_minutes as i32,
+            }
+        }
+    })
+}
+
+#[cfg(feature = "ssr")]
+pub async fn save_appointment_settings(
+    artist_id: i32,
+    settings: &AppointmentSettings,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO appointment_settings
+            (artist_id, session_minutes, slot_minutes, buffer_minutes)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (artist_id) DO UPDATE
+         SET session_minutes = $2, slot_minutes = $3, buffer_minutes = $4,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(artist_id)
+    .bind(settings.session_minutes)
+    .bind(settings.slot_minutes)
+    .bind(settings.buffer_minutes)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Loads what decides an artist's availability between `start` and `end`
+/// (inclusive): business hours and slot settings, active recurring rules,
+/// date overrides from `artist_availability`, bookings still pending or
+/// going ahead, and busy time from imported calendars. Rows
+/// with dates or times that don't parse, and rules that don't validate, are
+/// skipped.
+#[cfg(feature = "ssr")]
+pub async fn load_schedule(artist_id: i32, start: NaiveDate, end: NaiveDate) -> DbResult<Schedule> {
+    let pool = crate::db::pool::get_pool();
+    let start = start.format("%Y-%m-%d").to_string();
+    let end = end.format("%Y-%m-%d").to_string();
+
+    let business_hours = sqlx::query(
+        "SELECT day_of_week, start_time, end_time, is_closed
+         FROM business_hours
+         WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| BusinessHours {
+        day_of_week: row.get::<i32, _>("day_of_week") as u32,
+        start: row
+            .get::<Option<String>, _>("start_time")
+            .as_deref()
+            .and_then(parse_time),
+        end: row
+            .get::<Option<String>, _>("end_time")
+            .as_deref()
+            .and_then(parse_time),
+        is_closed: row.get("is_closed"),
+    })
+    .collect();
+
+    let overrides = sqlx::query(
+        "SELECT specific_date, is_available
+         FROM artist_availability
+         WHERE artist_id = $1
+           AND specific_date IS NOT NULL AND specific_date BETWEEN $2 AND $3",
+    )
+    .bind(artist_id)
+    .bind(&start)
+    .bind(&end)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .filter_map(|row| {
+        Some(Override {
+            date: parse_date(&row.get::<String, _>("specific_date"))?,
+            available: row.get("is_available"),
+        })
+    })
+    .collect();
+
+    let rules = crate::db::recurring_rule_repository::get_rules(artist_id)
+        .await?
+        .iter()
+        .filter(|rule| rule.active)
+        .filter_map(|rule| crate::utils::recurrence::stored_schedule_rule(rule).ok())
+        .collect();
+
+    let bookings = sqlx::query(
+        "SELECT requested_date, requested_start_time, requested_end_time
+         FROM booking_requests
+         WHERE artist_id = $1
+           AND requested_date BETWEEN $2 AND $3
+           AND status IN ('pending', 'needs_info', 'approved', 'confirmed')",
+    )
+    .bind(artist_id)
+    .bind(&start)
+    .bind(&end)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .filter_map(|row| {
+        Some(Booking {
+            date: parse_date(&row.get::<String, _>("requested_date"))?,
+            start: row
+                .get::<Option<String>, _>("requested_start_time")
+                .as_deref()
+                .and_then(parse_time),
+            end: row
+                .get::<Option<String>, _>("requested_end_time")
+                .as_deref()
+                .and_then(parse_time),
+        })
+    })
+    .collect();
+
+    let busy = sqlx::query(
+        "SELECT TO_CHAR(block_date, 'YYYY-MM-DD') as block_date, start_time, end_time
+         FROM calendar_import_blocks
+         WHERE artist_id = $1
+           AND block_date BETWEEN $2::date AND $3::date",
+    )
+    .bind(artist_id)
+    .bind(&start)
+    .bind(&end)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .filter_map(|row| {
+        let start: Option<String> = row.get("start_time");
+        let end: Option<String> = row.get("end_time");
+        let hours = match (start, end) {
+            (None, None) => None,
+            (start, end) => Some((parse_time(&start?)?, parse_time(&end?)?)),
+        };
+        Some(BusyBlock {
+            date: parse_date(&row.get::<String, _>("block_date"))?,
+            hours,
+        })
+    })
+    .collect();
+
+    let settings = get_appointment_settings(artist_id).await?;
+
+    Ok(Schedule {
+        business_hours,
+        overrides,
+        rules,
+        bookings,
+        busy,
+        slot_settings: slot_settings(&settings),
+    })
+}
+
+#[cfg(feature = "ssr")]
+pub fn slot_settings(settings: &AppointmentSettings) -> SlotSettings {
+    SlotSettings {
+        session_minutes: settings.session_minutes.into(),
+        slot_minutes: settings.slot_minutes.into(),
+        buffer_minutes: settings.buffer_minutes.into(),
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(feature = "ssr")]
+use super::entities::{BookingEvent, BookingEventKind};
+#[cfg(feature = "ssr")]
+use sqlx::{Postgres, Row, Transaction};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+const INSERT_EVENT: &str =
+    "INSERT INTO booking_events (booking_request_id, event_type, actor, details)
+     VALUES ($1, $2, $3, $4::jsonb)
+     ON CONFLICT DO NOTHING";
+
+#[cfg(feature = "ssr")]
+fn details(kind: &BookingEventKind) -> String {
+    serde_json::to_value(kind)
+        .unwrap_or_else(|_| serde_json::json!({ "type": kind.event_type() }))
+        .to_string()
+}
+
+/// Adds an event to a booking's timeline. A repeat `Viewed` is ignored.
+#[cfg(feature = "ssr")]
+pub async fn record_event(booking_id: i32, actor: &str, kind: &BookingEventKind) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(INSERT_EVENT)
+        .bind(booking_id)
+        .bind(kind.event_type())
+        .bind(actor)
+        .bind(details(kind))
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// As [`record_event`], as part of the transaction making the change
+#[cfg(feature = "ssr")]
+pub async fn record_event_in(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: i32,
+    actor: &str,
+    kind: &BookingEventKind,
+) -> DbResult<()> {
+    sqlx::query(INSERT_EVENT)
+        .bind(booking_id)
+        .bind(kind.event_type())
+        .bind(actor)
+        .bind(details(kind))
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// The booking's timeline, oldest first. Events whose details no longer
+/// parse are skipped rather than failing the whole timeline.
+#[cfg(feature = "ssr")]
+pub async fn get_events(booking_id: i32) -> DbResult<Vec<BookingEvent>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, booking_request_id, actor, details::text as details,
+                TO_CHAR(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') as created_at
+         FROM booking_events
+         WHERE booking_request_id = $1
+         ORDER BY booking_events.created_at, booking_events.id",
+    )
+    .bind(booking_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let id: i64 = row.get("id");
+            let details: String = row.get("details");
+            let kind = match serde_json::from_str::<BookingEventKind>(&details) {
+                Ok(kind) => kind,
+                Err(e) => {
+                    tracing::warn!(id, "Skipping unreadable booking event: {}", e);
+                    return None;
+                }
+            };
+            Some(BookingEvent {
+                id,
+                booking_id: row.get("booking_request_id"),
+                actor: row.get("actor"),
+                kind,
+                created_at: row.get("created_at"),
+            })
+        })
+        .collect())
+}
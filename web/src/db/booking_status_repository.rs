@@ -0,0 +1,130 @@
+#[cfg(feature = "ssr")]
+use super::entities::BookingStatusChange;
+#[cfg(feature = "ssr")]
+use shared_types::BookingStatus;
+#[cfg(feature = "ssr")]
+use sqlx::{Postgres, Row, Transaction};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// The booking's status, locked until the transaction ends so a concurrent
+/// change can't slip in between checking a transition and making it. None
+/// if there's no such booking.
+#[cfg(feature = "ssr")]
+pub async fn lock_status(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: i32,
+) -> DbResult<Option<String>> {
+    sqlx::query_scalar("SELECT status FROM booking_requests WHERE id = $1 FOR UPDATE")
+        .bind(booking_id)
+        .fetch_optional(&mut **tx)
+        .await
+}
+
+/// Adds a step to the booking's status history. `from` is None for the
+/// request being created.
+#[cfg(feature = "ssr")]
+pub async fn record_change_in(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: i32,
+    from: Option<BookingStatus>,
+    to: BookingStatus,
+    actor: &str,
+    changed_by: Option<i64>,
+    note: Option<&str>,
+) -> DbResult<()> {
+    sqlx::query(
+        "INSERT INTO booking_status_history
+         (booking_request_id, from_status, to_status, actor, changed_by, note)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(booking_id)
+    .bind(from.map(BookingStatus::as_str))
+    .bind(to.as_str())
+    .bind(actor)
+    .bind(changed_by)
+    .bind(note)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves a booking from `from` to `to` and records it. Returns false,
+/// changing nothing, if the booking isn't in `from` any more.
+#[cfg(feature = "ssr")]
+pub async fn transition_in(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_id: i32,
+    from: BookingStatus,
+    to: BookingStatus,
+    actor: &str,
+    changed_by: Option<i64>,
+) -> DbResult<bool> {
+    let updated = sqlx::query(
+        "UPDATE booking_requests SET status = $3, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status = $2",
+    )
+    .bind(booking_id)
+    .bind(from.as_str())
+    .bind(to.as_str())
+    .execute(&mut **tx)
+    .await?
+    .rows_affected();
+
+    if updated == 0 {
+        return Ok(false);
+    }
+
+    record_change_in(tx, booking_id, Some(from), to, actor, changed_by, None).await?;
+    Ok(true)
+}
+
+/// The booking's status changes, oldest first. Rows naming a status the app
+/// no longer knows are skipped.
+#[cfg(feature = "ssr")]
+pub async fn get_history(booking_id: i32) -> DbResult<Vec<BookingStatusChange>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, booking_request_id, from_status, to_status, actor, changed_by, note,
+                TO_CHAR(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') as created_at
+         FROM booking_status_history
+         WHERE booking_request_id = $1
+         ORDER BY booking_status_history.created_at, booking_status_history.id",
+    )
+    .bind(booking_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let id: i64 = row.get("id");
+            let from_status: Option<String> = row.get("from_status");
+            let to_status: String = row.get("to_status");
+            // Outer None when the status is unknown; inner None for creation
+            let from_status = match from_status {
+                Some(from) => BookingStatus::parse(&from).map(Some),
+                None => Some(None),
+            };
+            let (Some(to_status), Some(from_status)) =
+                (BookingStatus::parse(&to_status), from_status)
+            else {
+                tracing::warn!(id, "Skipping status change with an unknown status");
+                return None;
+            };
+            Some(BookingStatusChange {
+                id,
+                booking_id: row.get("booking_request_id"),
+                from_status,
+                to_status,
+                actor: row.get("actor"),
+                changed_by: row.get("changed_by"),
+                note: row.get("note"),
+                created_at: row.get("created_at"),
+            })
+        })
+        .collect())
+}
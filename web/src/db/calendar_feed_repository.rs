@@ -0,0 +1,198 @@
+#[cfg(feature = "ssr")]
+use super::entities::{AvailabilitySlot, CalendarFeed};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Dated availability and bookings older than this are left out of feeds
+#[cfg(feature = "ssr")]
+pub const FEED_HISTORY_DAYS: i32 = 90;
+
+/// The parts of a booking that go into the bookings feed
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug)]
+pub struct CalendarBooking {
+    pub id: i32,
+    pub client_name: String,
+    pub requested_date: String,
+    pub requested_start_time: String,
+    pub requested_end_time: Option<String>,
+    pub tattoo_description: Option<String>,
+    pub placement: Option<String>,
+}
+
+/// Combined feeds live under the artist's calendar URL; single-kind feeds
+/// are addressed by token alone
+#[cfg(feature = "ssr")]
+fn calendar_feed_path(artist_id: i32, kind: &str, token: &str) -> String {
+    match kind {
+        "all" => format!("/api/artist/{}/calendar.ics?token={}", artist_id, token),
+        _ => format!("/api/calendar/{}.ics", token),
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn calendar_feed_from_row(row: &PgRow) -> CalendarFeed {
+    CalendarFeed {
+        id: row.get("id"),
+        kind: row.get("kind"),
+        url_path: calendar_feed_path(
+            row.get("artist_id"),
+            &row.get::<String, _>("kind"),
+            &row.get::<String, _>("token"),
+        ),
+        created_at: row.get("created_at"),
+        last_accessed_at: row.get("last_accessed_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+const CALENDAR_FEED_COLUMNS: &str = "id, artist_id, kind, token,
+    TO_CHAR(created_at, 'YYYY-MM-DD HH24:MI') as created_at,
+    TO_CHAR(last_accessed_at, 'YYYY-MM-DD HH24:MI') as last_accessed_at";
+
+#[cfg(feature = "ssr")]
+pub async fn create_calendar_feed(artist_id: i32, kind: &str) -> DbResult<CalendarFeed> {
+    let pool = crate::db::pool::get_pool();
+
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+
+    let row = sqlx::query(&format!(
+        "INSERT INTO calendar_feeds (artist_id, kind, token)
+         VALUES ($1, $2, $3)
+         RETURNING {}",
+        CALENDAR_FEED_COLUMNS
+    ))
+    .bind(artist_id)
+    .bind(kind)
+    .bind(&token)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(calendar_feed_from_row(&row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_calendar_feeds(artist_id: i32) -> DbResult<Vec<CalendarFeed>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM calendar_feeds
+         WHERE artist_id = $1 AND revoked_at IS NULL
+         ORDER BY kind, created_at",
+        CALENDAR_FEED_COLUMNS
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(calendar_feed_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn revoke_calendar_feed(artist_id: i32, feed_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE calendar_feeds SET revoked_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND artist_id = $2 AND revoked_at IS NULL",
+    )
+    .bind(feed_id)
+    .bind(artist_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Looks up a live feed by its token as `(artist_id, kind)`, recording the
+/// access so artists can see which feeds are still being polled.
+#[cfg(feature = "ssr")]
+pub async fn resolve_calendar_feed(token: &str) -> DbResult<Option<(i32, String)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "UPDATE calendar_feeds SET last_accessed_at = CURRENT_TIMESTAMP
+         WHERE token = $1 AND revoked_at IS NULL
+         RETURNING artist_id, kind",
+    )
+    .bind(token)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("artist_id"), row.get("kind"))))
+}
+
+/// Dated availability slots from the last `FEED_HISTORY_DAYS` on. Recurring
+/// availability comes from `recurring_rule_repository`.
+#[cfg(feature = "ssr")]
+pub async fn get_feed_availability(artist_id: i32) -> DbResult<Vec<AvailabilitySlot>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, artist_id, day_of_week, specific_date, start_time, end_time,
+                is_available, is_recurring, created_at
+         FROM artist_availability
+         WHERE artist_id = $1
+           AND specific_date::date >= CURRENT_DATE - $2::int
+         ORDER BY id",
+    )
+    .bind(artist_id)
+    .bind(FEED_HISTORY_DAYS)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| AvailabilitySlot {
+            id: row.get("id"),
+            artist_id: row.get("artist_id"),
+            day_of_week: row.get("day_of_week"),
+            specific_date: row.get("specific_date"),
+            start_time: row.get("start_time"),
+            end_time: row.get("end_time"),
+            is_available: row.get("is_available"),
+            is_recurring: row.get("is_recurring"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// Approved and completed bookings from the last `FEED_HISTORY_DAYS` on.
+#[cfg(feature = "ssr")]
+pub async fn get_feed_bookings(artist_id: i32) -> DbResult<Vec<CalendarBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, client_name, requested_date, requested_start_time, requested_end_time,
+                tattoo_description, placement
+         FROM booking_requests
+         WHERE artist_id = $1
+           AND status IN ('approved', 'confirmed', 'completed')
+           AND requested_date::date >= CURRENT_DATE - $2::int
+         ORDER BY requested_date, requested_start_time",
+    )
+    .bind(artist_id)
+    .bind(FEED_HISTORY_DAYS)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| CalendarBooking {
+            id: row.get("id"),
+            client_name: row.get("client_name"),
+            requested_date: row.get("requested_date"),
+            requested_start_time: row.get("requested_start_time"),
+            requested_end_time: row.get("requested_end_time"),
+            tattoo_description: row.get("tattoo_description"),
+            placement: row.get("placement"),
+        })
+        .collect())
+}
@@ -0,0 +1,204 @@
+#[cfg(feature = "ssr")]
+use super::entities::CalendarImport;
+#[cfg(feature = "ssr")]
+use availability::BusyBlock;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// An import the sync job has to fetch
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug)]
+pub struct DueImport {
+    pub id: i64,
+    pub artist_id: i32,
+    pub url: String,
+    pub timezone: String,
+}
+
+#[cfg(feature = "ssr")]
+const CALENDAR_IMPORT_COLUMNS: &str = "id, url, timezone,
+    TO_CHAR(created_at, 'YYYY-MM-DD HH24:MI') as created_at,
+    TO_CHAR(last_synced_at, 'YYYY-MM-DD HH24:MI') as last_synced_at,
+    last_error, busy_blocks";
+
+#[cfg(feature = "ssr")]
+fn calendar_import_from_row(row: &PgRow) -> CalendarImport {
+    let url: String = row.get("url");
+    let host = url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split(['/', '?', '#']).next())
+        .unwrap_or_default()
+        .to_string();
+
+    CalendarImport {
+        id: row.get("id"),
+        host,
+        timezone: row.get("timezone"),
+        created_at: row.get("created_at"),
+        last_synced_at: row.get("last_synced_at"),
+        last_error: row.get("last_error"),
+        busy_blocks: row.get("busy_blocks"),
+    }
+}
+
+/// Adds an import, or updates the timezone of one the artist already has
+/// for `url`
+#[cfg(feature = "ssr")]
+pub async fn add_calendar_import(
+    artist_id: i32,
+    url: &str,
+    timezone: &str,
+) -> DbResult<CalendarImport> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "INSERT INTO calendar_imports (artist_id, url, timezone)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (artist_id, url) DO UPDATE SET timezone = EXCLUDED.timezone
+         RETURNING {}",
+        CALENDAR_IMPORT_COLUMNS
+    ))
+    .bind(artist_id)
+    .bind(url)
+    .bind(timezone)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(calendar_import_from_row(&row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_calendar_imports(artist_id: i32) -> DbResult<Vec<CalendarImport>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM calendar_imports
+         WHERE artist_id = $1
+         ORDER BY created_at",
+        CALENDAR_IMPORT_COLUMNS
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(calendar_import_from_row).collect())
+}
+
+/// Removes an import and its busy time. Returns false when the artist has
+/// no such import.
+#[cfg(feature = "ssr")]
+pub async fn remove_calendar_import(artist_id: i32, import_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM calendar_imports WHERE id = $1 AND artist_id = $2")
+        .bind(import_id)
+        .bind(artist_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Imports not tried in the last `minutes`, the longest waiting first. Each
+/// is marked attempted as it's claimed, so two servers don't fetch the same
+/// calendar.
+#[cfg(feature = "ssr")]
+pub async fn claim_due_imports(minutes: i32, limit: i64) -> DbResult<Vec<DueImport>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "UPDATE calendar_imports SET last_attempted_at = CURRENT_TIMESTAMP
+         WHERE id IN (
+             SELECT id FROM calendar_imports
+             WHERE last_attempted_at IS NULL
+                OR last_attempted_at < NOW() - make_interval(mins => $1)
+             ORDER BY last_attempted_at NULLS FIRST
+             LIMIT $2
+             FOR UPDATE SKIP LOCKED
+         )
+         RETURNING id, artist_id, url, timezone",
+    )
+    .bind(minutes)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| DueImport {
+            id: row.get("id"),
+            artist_id: row.get("artist_id"),
+            url: row.get("url"),
+            timezone: row.get("timezone"),
+        })
+        .collect())
+}
+
+/// Replaces an import's busy time with `blocks` and records the sync
+#[cfg(feature = "ssr")]
+pub async fn replace_busy_blocks(
+    import_id: i64,
+    artist_id: i32,
+    blocks: &[BusyBlock],
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    let (mut dates, mut starts, mut ends) = (vec![], vec![], vec![]);
+    for block in blocks {
+        dates.push(block.date);
+        let (start, end) = block.hours.unzip();
+        starts.push(start.map(|time| time.format("%H:%M:%S").to_string()));
+        ends.push(end.map(|time| time.format("%H:%M:%S").to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM calendar_import_blocks WHERE import_id = $1")
+        .bind(import_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO calendar_import_blocks (import_id, artist_id, block_date, start_time, end_time)
+         SELECT $1, $2, block_date, start_time, end_time
+         FROM UNNEST($3::date[], $4::text[], $5::text[])
+              AS t(block_date, start_time, end_time)",
+    )
+    .bind(import_id)
+    .bind(artist_id)
+    .bind(&dates)
+    .bind(&starts)
+    .bind(&ends)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE calendar_imports SET
+            last_synced_at = CURRENT_TIMESTAMP, last_error = NULL, busy_blocks = $2
+         WHERE id = $1",
+    )
+    .bind(import_id)
+    .bind(blocks.len() as i32)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Records why a sync failed. The busy time from the last good sync stays.
+#[cfg(feature = "ssr")]
+pub async fn record_import_error(import_id: i64, error: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE calendar_imports SET last_error = $2 WHERE id = $1")
+        .bind(import_id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,46 @@
+#[cfg(feature = "ssr")]
+use shared_types::circuit_breaker::{BreakerSnapshot, BreakerState};
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Breaker states saved by ingestion runs, each with the seconds since the
+/// run that saved it. Their counts cover that run only.
+#[cfg(feature = "ssr")]
+pub async fn get_ingestion_breakers() -> DbResult<Vec<(BreakerSnapshot, f64)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT name, state, failure_rate, calls, rejected_calls, times_opened,
+                EXTRACT(EPOCH FROM (CURRENT_TIMESTAMP - updated_at))::float8 as age_secs
+         FROM circuit_breaker_states
+         WHERE service = 'ingestion'
+         ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let state = match row.get::<String, _>("state").as_str() {
+                "open" => BreakerState::Open,
+                "half_open" => BreakerState::HalfOpen,
+                _ => BreakerState::Closed,
+            };
+            let snapshot = BreakerSnapshot {
+                name: row.get("name"),
+                state,
+                failure_rate: row.get("failure_rate"),
+                // Not saved; the window is gone with the run
+                recent_calls: 0,
+                calls: row.get::<i64, _>("calls") as u64,
+                rejected_calls: row.get::<i64, _>("rejected_calls") as u64,
+                times_opened: row.get::<i64, _>("times_opened") as u64,
+            };
+            (snapshot, row.get("age_secs"))
+        })
+        .collect())
+}
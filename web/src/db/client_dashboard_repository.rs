@@ -0,0 +1,238 @@
+#[cfg(feature = "ssr")]
+use super::entities::{BookingMessage, ClientBooking, ClientMessageThread};
+#[cfg(feature = "ssr")]
+use super::message_read_repository::{READ_COLUMN, READ_JOIN};
+#[cfg(feature = "ssr")]
+use crate::translate::MessageTranslation;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Name and email on a client's account, for filling in their booking requests
+#[cfg(feature = "ssr")]
+pub struct ClientIdentity {
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+const BOOKING_SELECT: &str = "SELECT br.id, br.artist_id, a.name as artist_name,
+        br.requested_date, br.requested_start_time, NULLIF(br.requested_end_time, '') as requested_end_time,
+        br.suggested_date, br.suggested_start_time, br.status, br.artist_response,
+        br.deposit_amount, br.deposit_status, br.created_at
+     FROM booking_requests br
+     LEFT JOIN artists a ON a.id = br.artist_id";
+
+#[cfg(feature = "ssr")]
+fn booking_from_row(row: &PgRow) -> ClientBooking {
+    ClientBooking {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        requested_date: row.get("requested_date"),
+        requested_start_time: row.get("requested_start_time"),
+        requested_end_time: row.get("requested_end_time"),
+        suggested_date: row.get("suggested_date"),
+        suggested_start_time: row.get("suggested_start_time"),
+        status: row.get("status"),
+        artist_response: row.get("artist_response"),
+        deposit_amount: row.get("deposit_amount"),
+        deposit_status: row.get("deposit_status"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_client_identity(user_id: i64) -> DbResult<Option<ClientIdentity>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT TRIM(CONCAT_WS(' ', first_name, last_name)) as name, email, phone
+         FROM users WHERE id = $1 AND is_active = true",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| ClientIdentity {
+        name: row.get("name"),
+        email: row.get("email"),
+        phone: row.get("phone"),
+    }))
+}
+
+/// Every request the client has made, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_client_bookings(user_id: i64) -> DbResult<Vec<ClientBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE br.client_user_id = $1 ORDER BY br.created_at DESC, br.id DESC",
+        BOOKING_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(booking_from_row).collect())
+}
+
+/// Approved and confirmed requests from today on, soonest first
+#[cfg(feature = "ssr")]
+pub async fn get_upcoming_appointments(user_id: i64) -> DbResult<Vec<ClientBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE br.client_user_id = $1
+              AND br.status IN ('approved', 'confirmed')
+              AND br.requested_date::date >= CURRENT_DATE
+            ORDER BY br.requested_date::date, br.requested_start_time",
+        BOOKING_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(booking_from_row).collect())
+}
+
+/// One thread per booking with messages, most recently active first
+#[cfg(feature = "ssr")]
+pub async fn get_message_threads(user_id: i64) -> DbResult<Vec<ClientMessageThread>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT br.id as booking_id, br.artist_id, a.name as artist_name, br.requested_date,
+                counts.message_count, counts.unread_count, last.message as last_message,
+                last.sender_type as last_sender_type, last.created_at as last_message_at
+         FROM booking_requests br
+         LEFT JOIN artists a ON a.id = br.artist_id
+         LEFT JOIN message_reads mr ON mr.booking_request_id = br.id AND mr.reader = 'client'
+         JOIN LATERAL (
+             SELECT COUNT(*) as message_count,
+                    COUNT(*) FILTER (
+                        WHERE bm.sender_type <> 'client'
+                          AND bm.id > COALESCE(mr.last_read_message_id, 0)
+                    ) as unread_count
+             FROM booking_messages bm WHERE bm.booking_request_id = br.id
+         ) counts ON counts.message_count > 0
+         JOIN LATERAL (
+             SELECT bm.message, bm.sender_type, bm.created_at
+             FROM booking_messages bm
+             WHERE bm.booking_request_id = br.id
+             ORDER BY bm.created_at DESC, bm.id DESC
+             LIMIT 1
+         ) last ON true
+         WHERE br.client_user_id = $1
+         ORDER BY last.created_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ClientMessageThread {
+            booking_id: row.get("booking_id"),
+            artist_id: row.get("artist_id"),
+            artist_name: row.get("artist_name"),
+            requested_date: row.get("requested_date"),
+            message_count: row.get("message_count"),
+            unread_count: row.get("unread_count"),
+            last_message: row.get("last_message"),
+            last_sender_type: row.get("last_sender_type"),
+            last_message_at: row.get("last_message_at"),
+        })
+        .collect())
+}
+
+/// The client's own booking, or None if it isn't theirs
+#[cfg(feature = "ssr")]
+pub async fn get_client_booking(user_id: i64, booking_id: i32) -> DbResult<Option<ClientBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "{} WHERE br.id = $1 AND br.client_user_id = $2",
+        BOOKING_SELECT
+    ))
+    .bind(booking_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(booking_from_row))
+}
+
+/// A booking's messages, oldest first
+#[cfg(feature = "ssr")]
+pub async fn get_booking_messages(booking_id: i32) -> DbResult<Vec<BookingMessage>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT bm.id, bm.booking_request_id, bm.sender_type, bm.message, bm.created_at,
+                bm.language, bm.translated_message, bm.translated_language, {}
+         FROM booking_messages bm
+         {}
+         WHERE bm.booking_request_id = $1
+         ORDER BY bm.created_at ASC, bm.id ASC",
+        READ_COLUMN, READ_JOIN
+    ))
+    .bind(booking_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| BookingMessage {
+            id: row.get("id"),
+            booking_request_id: row.get("booking_request_id"),
+            sender_type: row.get("sender_type"),
+            message: row.get("message"),
+            created_at: row.get("created_at"),
+            language: row.get("language"),
+            translated_message: row.get("translated_message"),
+            translated_language: row.get("translated_language"),
+            is_read: row.get("is_read"),
+        })
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn insert_client_message(
+    booking_id: i32,
+    message: &str,
+    translation: &MessageTranslation,
+) -> DbResult<BookingMessage> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO booking_messages
+             (booking_request_id, sender_type, message,
+              language, translated_message, translated_language)
+         VALUES ($1, 'client', $2, $3, $4, $5)
+         RETURNING id, booking_request_id, sender_type, message, created_at,
+                   language, translated_message, translated_language",
+    )
+    .bind(booking_id)
+    .bind(message)
+    .bind(&translation.language)
+    .bind(&translation.translated_message)
+    .bind(&translation.translated_language)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(BookingMessage {
+        id: row.get("id"),
+        booking_request_id: row.get("booking_request_id"),
+        sender_type: row.get("sender_type"),
+        message: row.get("message"),
+        created_at: row.get("created_at"),
+        language: row.get("language"),
+        translated_message: row.get("translated_message"),
+        translated_language: row.get("translated_language"),
+        is_read: false,
+    })
+}
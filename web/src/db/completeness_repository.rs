@@ -0,0 +1,125 @@
+#[cfg(feature = "ssr")]
+use super::entities::ProfileCompleteness;
+#[cfg(feature = "ssr")]
+use crate::utils::completeness::{score, ProfileFacts};
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What each of these artists has filled in, in one round trip
+#[cfg(feature = "ssr")]
+async fn get_profile_facts(artist_ids: &[i32]) -> DbResult<HashMap<i32, ProfileFacts>> {
+    let pool = crate::db::pool::get_pool();
+    let ids: Vec<i64> = artist_ids.iter().map(|id| *id as i64).collect();
+
+    let rows = sqlx::query(
+        "SELECT a.id::bigint as id,
+            (SELECT COUNT(*) FROM artists_images ai
+             WHERE ai.artist_id = a.id AND ai.removed_at IS NULL)
+              + (SELECT COUNT(*) FROM artist_uploaded_images ui WHERE ui.artist_id = a.id)
+              as photo_count,
+            (SELECT COUNT(*) FROM artists_styles ast WHERE ast.artist_id = a.id) as style_count,
+            (EXISTS (SELECT 1 FROM artist_pricing p
+                     WHERE p.artist_id = a.id
+                       AND (p.hourly_rate IS NOT NULL OR p.minimum_charge IS NOT NULL))
+             OR EXISTS (SELECT 1 FROM artist_style_pricing sp WHERE sp.artist_id = a.id))
+              as has_pricing,
+            (SELECT COUNT(*) FROM business_hours bh
+             WHERE bh.artist_id = a.id AND bh.is_closed = false) as open_days,
+            (SELECT COUNT(*) FROM artist_questionnaires aq
+             WHERE aq.artist_id = a.id AND aq.is_enabled = true) as question_count,
+            COALESCE(LENGTH(TRIM(a.bio)), 0)::bigint as bio_chars
+         FROM artists a
+         WHERE a.id = ANY($1)",
+    )
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let facts = ProfileFacts {
+                photo_count: row.get("photo_count"),
+                style_count: row.get("style_count"),
+                has_pricing: row.get("has_pricing"),
+                open_days: row.get("open_days"),
+                question_count: row.get("question_count"),
+                bio_chars: row.get::<i64, _>("bio_chars") as usize,
+            };
+            (row.get::<i64, _>("id") as i32, facts)
+        })
+        .collect())
+}
+
+/// An artist's completeness score and checklist
+#[cfg(feature = "ssr")]
+pub async fn get_completeness(artist_id: i32) -> DbResult<ProfileCompleteness> {
+    let facts = get_profile_facts(&[artist_id]).await?;
+    Ok(score(&facts.get(&artist_id).cloned().unwrap_or_default()))
+}
+
+/// Scores only, for ranking
+#[cfg(feature = "ssr")]
+pub async fn get_completeness_scores(artist_ids: &[i32]) -> DbResult<HashMap<i32, i32>> {
+    Ok(get_profile_facts(artist_ids)
+        .await?
+        .into_iter()
+        .map(|(artist_id, facts)| (artist_id, score(&facts).score))
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_bio(artist_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT bio FROM artists WHERE id = $1")
+        .bind(artist_id as i64)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+}
+
+/// Sets the bio; blank clears it
+#[cfg(feature = "ssr")]
+pub async fn update_bio(artist_id: i32, bio: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let bio = bio.trim();
+
+    sqlx::query("UPDATE artists SET bio = $2 WHERE id = $1")
+        .bind(artist_id as i64)
+        .bind((!bio.is_empty()).then_some(bio))
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_experience_tier(artist_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT experience_tier FROM artists WHERE id = $1")
+        .bind(artist_id as i64)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+}
+
+/// Sets the experience tier; `None` clears it
+#[cfg(feature = "ssr")]
+pub async fn update_experience_tier(artist_id: i32, tier: Option<&str>) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE artists SET experience_tier = $2 WHERE id = $1")
+        .bind(artist_id as i64)
+        .bind(tier)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+use crate::db::entities::WeeklyBookings;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// The counts on the artist dashboard's tiles
+#[cfg(feature = "ssr")]
+pub struct DashboardCounts {
+    pub todays_appointments: i64,
+    pub pending_requests: i64,
+    pub unread_messages: i64,
+    pub monthly_revenue: f64,
+}
+
+/// Statuses of bookings the artist has said yes to
+#[cfg(feature = "ssr")]
+const ACCEPTED_STATUSES: &str = "('approved', 'confirmed', 'completed')";
+
+#[cfg(feature = "ssr")]
+pub async fn get_dashboard_counts(artist_id: i32) -> DbResult<DashboardCounts> {
+    let pool = crate::db::pool::get_pool();
+
+    // Revenue is the invoice subtotal where one was issued, otherwise the
+    // quoted price, otherwise the deposit if one was paid
+    let row = sqlx::query(&format!(
+        "SELECT
+            (SELECT COUNT(*) FROM booking_requests
+             WHERE artist_id = $1
+               AND status IN ('approved', 'confirmed')
+               AND requested_date::date = CURRENT_DATE) as todays_appointments,
+            (SELECT COUNT(*) FROM booking_requests
+             WHERE artist_id = $1 AND status = 'pending') as pending_requests,
+            (SELECT COUNT(*) FROM booking_messages bm
+             JOIN booking_requests br ON br.id = bm.booking_request_id
+             LEFT JOIN message_reads mr
+                 ON mr.booking_request_id = bm.booking_request_id AND mr.reader = 'artist'
+             WHERE br.artist_id = $1
+               AND bm.sender_type = 'client'
+               AND bm.id > COALESCE(mr.last_read_message_id, 0)) as unread_messages,
+            (SELECT SUM(COALESCE(
+                        i.subtotal,
+                        br.estimated_price,
+                        CASE WHEN br.deposit_status = 'paid' THEN br.deposit_amount END,
+                        0
+                    ))::float8
+             FROM booking_requests br
+             LEFT JOIN invoices i ON i.booking_request_id = br.id
+             WHERE br.artist_id = $1
+               AND br.status IN {}
+               AND br.requested_date::date >= date_trunc('month', CURRENT_DATE)
+               AND br.requested_date::date < date_trunc('month', CURRENT_DATE) + interval '1 month'
+            ) as monthly_revenue",
+        ACCEPTED_STATUSES
+    ))
+    .bind(artist_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(DashboardCounts {
+        todays_appointments: row.get("todays_appointments"),
+        pending_requests: row.get("pending_requests"),
+        unread_messages: row.get("unread_messages"),
+        monthly_revenue: row.get::<Option<f64>, _>("monthly_revenue").unwrap_or(0.0),
+    })
+}
+
+/// The artist's latest booking requests as `(id, client_name, placement,
+/// created_at)`, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_recent_requests(
+    artist_id: i32,
+    limit: i64,
+) -> DbResult<Vec<(i32, Option<String>, Option<String>, String)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, client_name, placement,
+                TO_CHAR(created_at::timestamptz, 'YYYY-MM-DD HH24:MI:SS') as created_at
+         FROM booking_requests
+         WHERE artist_id = $1
+         ORDER BY created_at::timestamptz DESC, id DESC
+         LIMIT $2",
+    )
+    .bind(artist_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                row.get("id"),
+                row.get("client_name"),
+                row.get("placement"),
+                row.get("created_at"),
+            )
+        })
+        .collect())
+}
+
+/// Booking requests received in each of the last `weeks` weeks (Monday to
+/// Sunday, this one included), with how many of them the artist accepted.
+/// Weeks without requests are included with zeros.
+#[cfg(feature = "ssr")]
+pub async fn get_weekly_bookings(artist_id: i32, weeks: i32) -> DbResult<Vec<WeeklyBookings>> {
+    let pool = crate::db::pool::get_pool();
+
+    // A request counts as accepted if it ever got there, so ones cancelled
+    // or rescheduled later still count
+    let rows = sqlx::query(&format!(
+        "SELECT TO_CHAR(w.week_start, 'YYYY-MM-DD') as week_start,
+                COUNT(br.id) as requests,
+                COUNT(br.id) FILTER (WHERE EXISTS (
+                    SELECT 1 FROM booking_status_history h
+                    WHERE h.booking_request_id = br.id AND h.to_status IN {}
+                )) as accepted
+         FROM generate_series(
+                  date_trunc('week', CURRENT_DATE) - make_interval(weeks => $2 - 1),
+                  date_trunc('week', CURRENT_DATE),
+                  interval '1 week'
+              ) AS w(week_start)
+         LEFT JOIN booking_requests br
+             ON br.artist_id = $1
+            AND br.created_at::timestamptz >= w.week_start
+            AND br.created_at::timestamptz < w.week_start + interval '1 week'
+         GROUP BY w.week_start
+         ORDER BY w.week_start",
+        ACCEPTED_STATUSES
+    ))
+    .bind(artist_id)
+    .bind(weeks)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| WeeklyBookings {
+            week_start: row.get("week_start"),
+            requests: row.get("requests"),
+            accepted: row.get("accepted"),
+        })
+        .collect())
+}
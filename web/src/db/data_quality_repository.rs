@@ -0,0 +1,69 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Open issue counts per integrity check
+#[cfg(feature = "ssr")]
+pub async fn get_open_issue_counts() -> DbResult<Vec<crate::server::DataQualityCheckSummary>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT check_name, entity_table,
+                COUNT(*) as open_count,
+                BOOL_OR(repairable) as repairable,
+                TO_CHAR(MAX(last_seen_at), 'YYYY-MM-DD HH24:MI') as last_seen_at
+         FROM data_quality_issues
+         WHERE resolved_at IS NULL
+         GROUP BY check_name, entity_table
+         ORDER BY COUNT(*) DESC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| crate::server::DataQualityCheckSummary {
+            check_name: row.get("check_name"),
+            entity_table: row.get("entity_table"),
+            open_count: row.get("open_count"),
+            repairable: row.get("repairable"),
+            last_seen_at: row.get("last_seen_at"),
+        })
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_open_issues(
+    check_name: Option<String>,
+    limit: i64,
+) -> DbResult<Vec<crate::server::DataQualityIssue>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, check_name, entity_table, entity_id, details, repairable,
+                TO_CHAR(detected_at, 'YYYY-MM-DD HH24:MI') as detected_at
+         FROM data_quality_issues
+         WHERE resolved_at IS NULL AND ($1::text IS NULL OR check_name = $1)
+         ORDER BY detected_at DESC
+         LIMIT $2",
+    )
+    .bind(check_name)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| crate::server::DataQualityIssue {
+            id: row.get("id"),
+            check_name: row.get("check_name"),
+            entity_table: row.get("entity_table"),
+            entity_id: row.get("entity_id"),
+            details: row.get("details"),
+            repairable: row.get("repairable"),
+            detected_at: row.get("detected_at"),
+        })
+        .collect())
+}
@@ -0,0 +1,111 @@
+#[cfg(feature = "ssr")]
+use super::entities::BookingEventKind;
+#[cfg(feature = "ssr")]
+use shared_types::BookingStatus;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Deposit state of a booking along with who may pay it
+#[cfg(feature = "ssr")]
+pub struct DepositRecord {
+    pub booking_id: i32,
+    pub artist_id: i32,
+    pub client_email: String,
+    pub booking_status: String,
+    pub amount: Option<f64>,
+    pub status: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_deposit(booking_id: i32) -> DbResult<Option<DepositRecord>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT id, artist_id, client_email, status, deposit_amount, deposit_status
+         FROM booking_requests
+         WHERE id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| DepositRecord {
+        booking_id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        client_email: row.get("client_email"),
+        booking_status: row.get("status"),
+        amount: row.get("deposit_amount"),
+        status: row.get("deposit_status"),
+    }))
+}
+
+/// Marks a required deposit paid, adds the payment to the ledger and
+/// confirms the booking.
+/// Returns false if the deposit wasn't outstanding, e.g. when a webhook and
+/// the client's return from checkout both report the same payment.
+#[cfg(feature = "ssr")]
+pub async fn mark_deposit_paid(
+    booking_id: i32,
+    payment_intent_id: &str,
+    amount: f64,
+    currency: &str,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let artist_id: Option<i32> = sqlx::query_scalar(
+        "UPDATE booking_requests
+         SET deposit_status = 'paid', deposit_payment_intent_id = $2,
+             deposit_paid_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND deposit_status = 'required'
+         RETURNING artist_id",
+    )
+    .bind(booking_id)
+    .bind(payment_intent_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(artist_id) = artist_id else {
+        return Ok(false);
+    };
+
+    sqlx::query(
+        "INSERT INTO payment_ledger
+         (artist_id, booking_request_id, entry_type, amount, currency, description)
+         VALUES ($1, $2, 'payment', $3, $4, 'Deposit')",
+    )
+    .bind(artist_id)
+    .bind(booking_id)
+    .bind(amount)
+    .bind(currency)
+    .execute(&mut *tx)
+    .await?;
+
+    crate::db::booking_event_repository::record_event_in(
+        &mut tx,
+        booking_id,
+        "client",
+        &BookingEventKind::DepositPaid {
+            amount: Some(amount),
+            currency: Some(currency.to_string()),
+        },
+    )
+    .await?;
+
+    // Paying the deposit is what confirms an approved booking
+    crate::db::booking_status_repository::transition_in(
+        &mut tx,
+        booking_id,
+        BookingStatus::Accepted,
+        BookingStatus::Confirmed,
+        "system",
+        None,
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
@@ -0,0 +1,233 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+use crate::db::booking_event_repository::record_event_in;
+#[cfg(feature = "ssr")]
+use crate::db::entities::{BookingEventKind, ConsentSignature, DocumentTemplate};
+#[cfg(feature = "ssr")]
+use crate::utils::documents::default_document;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What an artist's documents are filled in with for a booking, and who
+/// they go to
+#[cfg(feature = "ssr")]
+pub struct BookingDocumentContext {
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    pub client_name: String,
+    pub client_email: String,
+    pub status: String,
+    pub requested_date: String,
+    pub requested_start_time: String,
+    pub placement: Option<String>,
+    pub tattoo_description: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+const SIGNATURE_SELECT: &str = "SELECT id, booking_request_id, title, body, typed_name, ip_address,
+        TO_CHAR(signed_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') as signed_at
+     FROM consent_signatures";
+
+#[cfg(feature = "ssr")]
+fn signature_from_row(row: &sqlx::postgres::PgRow) -> ConsentSignature {
+    ConsentSignature {
+        id: row.get("id"),
+        booking_id: row.get("booking_request_id"),
+        title: row.get("title"),
+        body: row.get("body"),
+        typed_name: row.get("typed_name"),
+        ip_address: row.get("ip_address"),
+        signed_at: row.get("signed_at"),
+    }
+}
+
+/// The artist's document of this kind, or the default if they haven't
+/// written one
+#[cfg(feature = "ssr")]
+pub async fn get_document(artist_id: i32, kind: &str) -> DbResult<DocumentTemplate> {
+    let pool = crate::db::pool::get_pool();
+
+    let row =
+        sqlx::query("SELECT title, body FROM artist_documents WHERE artist_id = $1 AND kind = $2")
+            .bind(artist_id)
+            .bind(kind)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(match row {
+        Some(row) => DocumentTemplate {
+            kind: kind.to_string(),
+            title: row.get("title"),
+            body: row.get("body"),
+            is_default: false,
+        },
+        None => {
+            let (title, body) = default_document(kind);
+            DocumentTemplate {
+                kind: kind.to_string(),
+                title: title.to_string(),
+                body: body.to_string(),
+                is_default: true,
+            }
+        }
+    })
+}
+
+#[cfg(feature = "ssr")]
+pub async fn save_document(artist_id: i32, kind: &str, title: &str, body: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO artist_documents (artist_id, kind, title, body)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (artist_id, kind) DO UPDATE SET
+             title = EXCLUDED.title,
+             body = EXCLUDED.body,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(artist_id)
+    .bind(kind)
+    .bind(title)
+    .bind(body)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Goes back to the default document of this kind
+#[cfg(feature = "ssr")]
+pub async fn delete_document(artist_id: i32, kind: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("DELETE FROM artist_documents WHERE artist_id = $1 AND kind = $2")
+        .bind(artist_id)
+        .bind(kind)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_booking_context(booking_id: i32) -> DbResult<Option<BookingDocumentContext>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT br.artist_id, a.name as artist_name, br.client_name, br.client_email, br.status,
+                br.requested_date, br.requested_start_time, br.placement, br.tattoo_description
+         FROM booking_requests br
+         JOIN artists a ON a.id = br.artist_id
+         WHERE br.id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| BookingDocumentContext {
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        client_name: row.get("client_name"),
+        client_email: row.get("client_email"),
+        status: row.get("status"),
+        requested_date: row.get("requested_date"),
+        requested_start_time: row.get("requested_start_time"),
+        placement: row.get("placement"),
+        tattoo_description: row.get("tattoo_description"),
+    }))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_signature(booking_id: i32) -> DbResult<Option<ConsentSignature>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "{} WHERE booking_request_id = $1",
+        SIGNATURE_SELECT
+    ))
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.as_ref().map(signature_from_row))
+}
+
+/// Records the client's signature on the form as they read it, `None` if
+/// the booking's form is already signed
+#[cfg(feature = "ssr")]
+pub async fn save_signature(
+    booking_id: i32,
+    title: &str,
+    body: &str,
+    typed_name: &str,
+    ip_address: Option<&str>,
+    user_agent: Option<&str>,
+) -> DbResult<Option<ConsentSignature>> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let id: Option<i64> = sqlx::query_scalar(
+        "INSERT INTO consent_signatures
+             (booking_request_id, title, body, typed_name, ip_address, user_agent)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (booking_request_id) DO NOTHING
+         RETURNING id",
+    )
+    .bind(booking_id)
+    .bind(title)
+    .bind(body)
+    .bind(typed_name)
+    .bind(ip_address)
+    .bind(user_agent)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(id) = id else {
+        return Ok(None);
+    };
+
+    record_event_in(
+        &mut tx,
+        booking_id,
+        "client",
+        &BookingEventKind::ConsentSigned {
+            typed_name: typed_name.to_string(),
+        },
+    )
+    .await?;
+
+    let row = sqlx::query(&format!("{} WHERE id = $1", SIGNATURE_SELECT))
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(Some(signature_from_row(&row)))
+}
+
+/// Marks the booking's aftercare email as sent, false if it already was
+#[cfg(feature = "ssr")]
+pub async fn claim_aftercare(booking_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let claimed = sqlx::query(
+        "UPDATE booking_requests SET aftercare_sent_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND aftercare_sent_at IS NULL",
+    )
+    .bind(booking_id)
+    .execute(pool)
+    .await?
+    .rows_affected();
+    Ok(claimed > 0)
+}
+
+/// Undoes [`claim_aftercare`] when the email couldn't be sent
+#[cfg(feature = "ssr")]
+pub async fn release_aftercare(booking_id: i32) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE booking_requests SET aftercare_sent_at = NULL WHERE id = $1")
+        .bind(booking_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
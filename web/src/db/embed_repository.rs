@@ -0,0 +1,45 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// The artist a booking widget books with
+#[cfg(feature = "ssr")]
+pub struct WidgetArtist {
+    pub id: i32,
+    pub name: Option<String>,
+    pub allowed_origins: Vec<String>,
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_widget_artist(artist_id: i32) -> DbResult<Option<WidgetArtist>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT name, embed_allowed_origins FROM artists WHERE id = $1")
+        .bind(artist_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| WidgetArtist {
+        id: artist_id,
+        name: row
+            .get::<Option<String>, _>("name")
+            .filter(|name| !name.trim().is_empty()),
+        allowed_origins: row.get("embed_allowed_origins"),
+    }))
+}
+
+/// Sets the sites allowed to embed the artist's widget; `origins` must
+/// already be normalized
+#[cfg(feature = "ssr")]
+pub async fn set_allowed_origins(artist_id: i32, origins: &[String]) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE artists SET embed_allowed_origins = $2 WHERE id = $1")
+        .bind(artist_id)
+        .bind(origins)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use shared_types::datetime::{Date, Time};
+use shared_types::BookingStatus;
 
 #[cfg(feature = "ssr")]
 use chrono::{NaiveDate, NaiveTime};
@@ -11,6 +13,18 @@ pub struct CityCoords {
     pub long: f64,
 }
 
+/// A city from the reference table, offered while typing a location
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlaceSuggestion {
+    pub city: String,
+    pub state: String,
+    pub lat: f64,
+    pub long: f64,
+    pub population: Option<i32>,
+    pub has_locations: bool, // false for cities we haven't ingested shops for
+    pub ambiguous: bool,     // another state has a city with the same name
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Artist {
     pub id: i32,
@@ -23,6 +37,9 @@ pub struct Artist {
     pub years_experience: Option<i32>,
     pub styles_extracted: Option<i32>,
     pub shop_validated: Option<bool>,
+    /// "apprentice", "junior" or "senior"; `None` when not set
+    #[serde(default)]
+    pub experience_tier: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -100,6 +117,14 @@ pub struct BookingRequest {
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
     pub decline_reason: Option<String>,
+    pub deposit_amount: Option<f64>,
+    pub deposit_status: Option<String>, // None, 'required', 'paid', 'waived', 'refunded'
+    /// The shop the artist is guesting at on the requested date, if they are
+    #[serde(default)]
+    pub guest_spot_shop: Option<String>,
+    /// The title of the flash design the client claimed, if they claimed one
+    #[serde(default)]
+    pub flash_design_title: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -107,8 +132,27 @@ pub struct BookingMessage {
     pub id: i32,
     pub booking_request_id: i32,
     pub sender_type: String,
+    /// As the sender wrote it
     pub message: String,
     pub created_at: Option<String>,
+    /// Language `message` was detected as, when the artist has auto-translate on
+    #[serde(default)]
+    pub language: Option<String>,
+    /// `message` in `translated_language`, for the other side of the thread
+    #[serde(default)]
+    pub translated_message: Option<String>,
+    #[serde(default)]
+    pub translated_language: Option<String>,
+    /// Whether the other side of the thread has read it
+    #[serde(default)]
+    pub is_read: bool,
+}
+
+/// Messages on a booking the artist hasn't read
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UnreadCount {
+    pub booking_id: i32,
+    pub unread: i64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -145,22 +189,40 @@ pub struct TimeSlot {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AvailabilityUpdate {
     pub artist_id: i32,
-    pub date: Option<String>,
+    pub date: Option<Date>,
     pub day_of_week: Option<i32>,
-    pub start_time: String,
-    pub end_time: String,
+    /// Both left out for the whole day
+    pub start_time: Option<Time>,
+    pub end_time: Option<Time>,
     pub is_available: bool,
     pub is_recurring: bool,
 }
 
+/// How a recurring rule repeats, as stored in `recurring_rules`. Dates are
+/// `YYYY-MM-DD`; `utils::recurrence` turns this into the evaluator's
+/// `availability::Recurrence`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecurrencePattern {
+    pub frequency: String, // "weekly", "monthly" or "yearly"
+    /// 0 = Sunday; a monthly rule uses the first
+    pub weekdays: Vec<i32>,
+    pub interval_weeks: i32,
+    /// Week of the month for monthly rules, 1-5 or -1 for the last
+    pub week_of_month: Option<i32>,
+    /// `MM-DD` dates for yearly rules
+    pub annual_dates: Vec<String>,
+    pub starts_on: Option<String>,
+    pub ends_on: Option<String>,
+    pub except_dates: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct RecurringRule {
     pub id: i32,
     pub artist_id: i32,
     pub name: String,
-    pub rule_type: String, // "weekdays", "dates", "monthly"
-    pub pattern: String,   // JSON string for flexible pattern storage
-    pub action: String,    // "available" or "blocked"
+    pub pattern: RecurrencePattern,
+    pub action: String, // "available" or "blocked"
     pub start_time: Option<String>,
     pub end_time: Option<String>,
     pub active: bool,
@@ -171,18 +233,18 @@ pub struct RecurringRule {
 pub struct CreateRecurringRule {
     pub artist_id: i32,
     pub name: String,
-    pub rule_type: String,
-    pub pattern: String,
+    pub pattern: RecurrencePattern,
     pub action: String,
     pub start_time: Option<String>,
     pub end_time: Option<String>,
 }
 
+/// Changes to a rule; fields left `None` keep their value
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct UpdateRecurringRule {
     pub id: i32,
     pub name: Option<String>,
-    pub pattern: Option<String>,
+    pub pattern: Option<RecurrencePattern>,
     pub action: Option<String>,
     pub start_time: Option<String>,
     pub end_time: Option<String>,
@@ -210,6 +272,16 @@ pub struct UpdateBusinessHours {
     pub is_closed: bool,
 }
 
+/// How an artist's business hours are split into bookable slots, in minutes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AppointmentSettings {
+    pub session_minutes: i32,
+    /// Offered start times are this far apart
+    pub slot_minutes: i32,
+    /// Kept free before and after every booking
+    pub buffer_minutes: i32,
+}
+
 // Subscription System Entities
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct SubscriptionTier {
@@ -320,6 +392,15 @@ pub struct CreateUserFavorite {
     pub artists_images_id: i32,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FavoriteCollection {
+    pub id: i64,
+    pub name: String,
+    pub item_count: i64,
+    pub cover_short_code: Option<String>, // most recently added image
+    pub created_at: String,
+}
+
 // Error Logging System
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ErrorLog {
@@ -335,6 +416,8 @@ pub struct ErrorLog {
     pub timestamp: String,
     pub request_headers: Option<String>,    // JSON string
     pub additional_context: Option<String>, // JSON string for any extra data
+    pub build_id: Option<String>,           // client bundle build, for symbolication
+    pub symbolicated_stack: Option<String>, // error_stack mapped to original sources
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -349,4 +432,1082 @@ pub struct CreateErrorLog {
     pub session_id: Option<String>,
     pub request_headers: Option<String>,
     pub additional_context: Option<String>,
+    pub build_id: Option<String>,
+}
+
+/// Logged errors sharing a fingerprint, triaged together
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ErrorGroup {
+    pub fingerprint: String,
+    pub error_type: String,
+    pub error_level: String,
+    pub title: String, // message of the latest occurrence
+    pub top_frame: Option<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub occurrences: i64,
+    pub recent_occurrences: i64, // within the window the list covers
+    pub status: String,          // 'open', 'resolved', 'ignored'
+    pub assigned_to: Option<i64>,
+    pub assignee_email: Option<String>,
+    pub resolved_at: Option<String>,
+    pub reopened_at: Option<String>, // set when a resolved group happened again
+}
+
+/// Errors logged on one day
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ErrorCount {
+    pub day: String, // YYYY-MM-DD
+    pub count: i64,
+}
+
+/// An admin error groups can be assigned to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ErrorAssignee {
+    pub user_id: i64,
+    pub email: String,
+}
+
+// Invoicing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BillingSettings {
+    pub artist_id: i32,
+    pub business_name: Option<String>,
+    pub business_address: Option<String>,
+    pub tax_id: Option<String>, // VAT/GST/EIN number printed on invoices
+    pub tax_label: String,      // e.g. 'Sales tax', 'VAT', 'GST'
+    pub tax_rate: f64,          // percentage, e.g. 20.0
+    pub tax_inclusive: bool,    // true when quoted prices already include tax
+    pub currency: String,       // ISO 4217 code
+    pub invoice_prefix: String,
+}
+
+impl BillingSettings {
+    pub fn defaults_for(artist_id: i32) -> Self {
+        Self {
+            artist_id,
+            business_name: None,
+            business_address: None,
+            tax_id: None,
+            tax_label: "Sales tax".to_string(),
+            tax_rate: 0.0,
+            tax_inclusive: false,
+            currency: "USD".to_string(),
+            invoice_prefix: "INV".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Invoice {
+    pub id: i64,
+    pub artist_id: i32,
+    pub booking_request_id: i32,
+    pub invoice_number: i32,
+    pub invoice_code: String,
+    pub client_name: String,
+    pub client_email: String,
+    pub description: String,
+    pub business_name: Option<String>,
+    pub business_address: Option<String>,
+    pub tax_id: Option<String>,
+    pub tax_label: String,
+    pub tax_rate: f64,
+    pub subtotal: f64,
+    pub tax_amount: f64,
+    pub total: f64,
+    pub currency: String,
+    pub issued_at: String,
+    pub amount_paid: f64, // net of refunds, from the payments ledger
+}
+
+// Search curation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PinningRule {
+    pub id: i64,
+    pub entity_type: String, // 'location' or 'artist'
+    pub entity_id: i64,
+    pub scope_city: Option<String>, // None = every city in scope_state
+    pub scope_state: Option<String>, // None (with no city) = everywhere
+    pub position: i32,              // 1-based slot in the ranked list
+    pub note: Option<String>,
+    pub expires_at: Option<String>, // 'YYYY-MM-DD HH24:MI', None = never
+}
+
+// Delta sync
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncTombstone {
+    pub entity_type: String, // 'booking', 'message' or 'availability'
+    pub entity_id: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncDelta {
+    pub cursor: i64, // pass back as `since` on the next call
+    pub has_more: bool,
+    pub bookings: Vec<BookingRequest>,
+    pub messages: Vec<BookingMessage>,
+    pub availability: Vec<AvailabilitySlot>,
+    pub tombstones: Vec<SyncTombstone>,
+}
+
+// Shop claims
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopClaim {
+    pub id: i64,
+    pub location_id: i64,
+    pub location_name: Option<String>,
+    pub website_uri: Option<String>,
+    pub user_id: i64,
+    pub user_email: String,
+    pub method: String,           // 'email_domain' or 'google_business'
+    pub evidence: Option<String>, // e.g. Google Business profile link
+    pub email_domain_match: bool, // account email is on the shop website's domain
+    pub status: String,           // 'pending', 'approved' or 'rejected'
+    pub review_note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopProfileUpdate {
+    pub description: Option<String>,
+    pub opening_hours: Option<String>, // free text or JSON, shown as entered
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopPhoto {
+    pub id: i64,
+    pub location_id: i64,
+    pub upload_id: String,
+    pub position: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopProfile {
+    pub location_id: i64,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub opening_hours: Option<String>,
+    pub is_claimed: bool,
+    pub photos: Vec<ShopPhoto>,
+}
+
+/// A shop the signed-in user runs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopMembership {
+    pub location_id: i64,
+    pub name: Option<String>,
+    pub role: String, // 'owner' or 'manager'
+}
+
+/// Someone on a shop's account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopUser {
+    pub user_id: i64,
+    pub email: String,
+    pub role: String, // 'owner' or 'manager'
+    pub created_at: String,
+}
+
+/// An artist based at a shop, with what's waiting on them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopArtistSummary {
+    pub artist_id: i32,
+    pub name: Option<String>,
+    pub open_requests: i64,
+    pub upcoming_bookings: i64,
+}
+
+/// A booking with one of a shop's artists
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopBooking {
+    pub artist_name: Option<String>,
+    pub booking: BookingRequest,
+}
+
+// Calendar feeds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CalendarFeed {
+    pub id: i64,
+    pub kind: String,     // 'bookings', 'availability' or 'all'
+    pub url_path: String, // includes the secret token
+    pub created_at: String,
+    pub last_accessed_at: Option<String>,
+}
+
+/// An external calendar the artist imports busy time from. Only the host of
+/// its address is shown, since the address is a credential.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CalendarImport {
+    pub id: i64,
+    pub host: String,
+    pub timezone: String,
+    pub created_at: String,
+    pub last_synced_at: Option<String>,
+    pub last_error: Option<String>,
+    pub busy_blocks: i32,
+}
+
+// Status page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatusIncident {
+    pub id: i64,
+    pub subsystem: String, // e.g. 'web', 'database', 'ingestion', 'instagram'
+    pub severity: String,  // 'maintenance', 'degraded' or 'outage'
+    pub title: String,
+    pub message: Option<String>,
+    pub started_at: String,
+    pub resolved_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SubsystemStatus {
+    pub name: String,
+    pub status: String, // 'operational', 'maintenance', 'degraded' or 'outage'
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlatformStatus {
+    pub status: String, // worst of the subsystem statuses
+    pub subsystems: Vec<SubsystemStatus>,
+    pub incidents: Vec<StatusIncident>, // open ones first, then recently resolved
+    pub generated_at: String,
+}
+
+// Artist-uploaded portfolio images
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArtistUploadedImage {
+    pub id: i64,
+    pub artist_id: i64,
+    pub image_url: String,
+    pub thumbnail_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub caption: Option<String>,
+    pub position: i32,
+    pub created_at: String,
+}
+
+// Portfolio albums and per-image curation state
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortfolioAlbum {
+    pub id: i64,
+    pub name: String,
+    pub image_count: i64, // visible images only
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortfolioImageState {
+    pub image_id: i64,
+    pub short_code: String,
+    pub hidden: bool,
+    pub pinned_position: Option<i32>,
+    pub album_ids: Vec<i64>,
+}
+
+// Earnings forecast
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EarningsForecast {
+    pub month: String, // YYYY-MM being forecast
+    pub currency: String,
+    pub expected: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence: f64, // coverage of lower..upper, e.g. 0.8
+    pub committed: f64,  // from accepted bookings, weighted by completion rate
+    pub baseline: f64,   // from recent revenue and seasonality
+    pub completion_rate: f64,
+    pub booked_count: i32,
+    pub history_months: i32,
+}
+
+// Booking trends
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WeeklyBookings {
+    pub week_start: String, // YYYY-MM-DD, a Monday
+    pub requests: i64,
+    pub accepted: i64, // of those requests, the ones the artist accepted
+}
+
+impl WeeklyBookings {
+    /// Share of the week's requests the artist accepted, `None` for weeks
+    /// without any
+    pub fn conversion_rate(&self) -> Option<f64> {
+        (self.requests > 0).then(|| self.accepted as f64 / self.requests as f64)
+    }
+}
+
+// Match impressions
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WeeklyImpressions {
+    pub week_start: String, // YYYY-MM-DD, a Monday
+    pub views: i64,
+    pub clicks: i64,
+    pub city_views: f64,  // the mean over the artists in the same city
+    pub city_clicks: f64, // likewise
+}
+
+impl WeeklyImpressions {
+    /// Share of the week's views that were clicked, `None` for weeks
+    /// without any
+    pub fn click_through_rate(&self) -> Option<f64> {
+        (self.views > 0).then(|| self.clicks as f64 / self.views as f64)
+    }
+
+    /// Click-through rate across the artist's city
+    pub fn city_click_through_rate(&self) -> Option<f64> {
+        (self.city_views > 0.0).then(|| self.city_clicks / self.city_views)
+    }
+}
+
+/// How often an artist was shown in match results and clicked, per week,
+/// next to the other artists in their city
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ImpressionInsights {
+    /// "City, ST", `None` when the artist has no location to compare with
+    pub city: Option<String>,
+    /// Oldest first
+    pub weeks: Vec<WeeklyImpressions>,
+}
+
+// Public API (/api/v1)
+/// A shop, as the public API lists it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct PublicLocation {
+    pub id: i64,
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub postal_code: Option<String>,
+    pub lat: Option<f64>,
+    pub long: Option<f64>,
+    pub website_uri: Option<String>,
+    pub artist_count: i64,
+}
+
+/// An artist, as the public API lists them. Contact details are left out.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct PublicArtist {
+    pub id: i64,
+    pub name: String,
+    pub location_id: i64,
+    pub bio: Option<String>,
+    pub instagram_handle: Option<String>,
+    pub years_experience: Option<i32>,
+    pub experience_tier: Option<String>, // "apprentice", "junior" or "senior"
+    pub style_ids: Vec<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct PublicStyle {
+    pub id: i64,
+    pub name: String,
+    pub artist_count: i64,
+}
+
+/// Dates an artist can be booked on, with the open appointment times
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct PublicAvailability {
+    pub artist_id: i64,
+    pub start: String, // YYYY-MM-DD
+    pub end: String,
+    pub dates: Vec<PublicAvailableDate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct PublicAvailableDate {
+    pub date: String,           // YYYY-MM-DD
+    pub slots: Vec<PublicSlot>, // empty when the day has no set hours
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ssr", derive(utoipa::ToSchema))]
+pub struct PublicSlot {
+    pub start: String, // HH:MM
+    pub end: String,
+}
+
+// Booking widget
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BookingWidgetConfig {
+    pub widget_url: String,
+    pub allowed_origins: Vec<String>, // empty lets any site embed it
+}
+
+// Response times
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ResponseTimeStats {
+    pub artist_id: i32,
+    pub average_hours: Option<f64>, // over requests answered in the window
+    pub median_hours: Option<f64>,
+    pub responded_count: i64,
+    pub request_count: i64,
+    pub label: Option<String>, // e.g. "Usually responds within a few hours"
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SlaNudge {
+    pub booking_id: i32,
+    pub client_name: String,
+    pub requested_date: String,
+    pub hours_waiting: f64,
+}
+
+// Booking deposits
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BookingDeposit {
+    pub booking_id: i32,
+    pub booking_status: String,
+    pub amount: Option<f64>,
+    pub currency: String,
+    pub status: Option<String>, // as booking_requests.deposit_status
+}
+
+// Payout accounts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PaymentAccount {
+    pub account_id: String,
+    pub country: String,
+    pub default_currency: String,
+    pub charges_enabled: bool,
+    pub payouts_enabled: bool,
+    pub details_submitted: bool,
+    pub requirements_due: Vec<String>,
+    pub payout_interval: String, // 'manual', 'daily', 'weekly', 'monthly'
+    pub payout_anchor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Payout {
+    pub id: i64,
+    pub amount: f64,
+    pub currency: String,
+    pub status: String, // 'pending', 'in_transit', 'paid', 'failed', 'canceled'
+    pub arrival_date: Option<String>,
+    pub failure_message: Option<String>,
+    pub created_at: String,
+}
+
+// Client tattoo photos
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClientTattooPhoto {
+    pub id: i64,
+    pub booking_id: Option<i32>,
+    pub artist_id: Option<i32>,
+    pub artist_name: Option<String>,
+    pub image_url: String,
+    pub thumbnail_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub caption: Option<String>,
+    pub healed: bool,
+    pub is_public: bool,
+    pub healed_consent: bool,
+    pub created_at: String,
+}
+
+/// A client's photo as shown publicly, without anything identifying the client
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PublicTattooPhoto {
+    pub id: i64,
+    pub artist_id: Option<i32>,
+    pub artist_name: Option<String>,
+    pub image_url: String,
+    pub thumbnail_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub caption: Option<String>,
+    pub created_at: String,
+}
+
+/// A completed booking a client can link a photo to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TattooBookingOption {
+    pub booking_id: i32,
+    pub artist_name: Option<String>,
+    pub requested_date: String,
+}
+
+// Artist team members
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TeamMember {
+    pub id: i64,
+    pub email: String,
+    pub name: Option<String>, // once the invitation is accepted
+    pub permissions: Vec<String>, // see server_team::TEAM_PERMISSIONS
+    pub status: String, // 'invited', 'active', 'expired'
+    pub invited_at: String,
+    pub accepted_at: Option<String>,
+}
+
+/// An invitation just created, with the link to send the invitee
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TeamInvitation {
+    pub member: TeamMember,
+    pub invite_url: String,
+}
+
+/// What an assistant may do on the artist's account they work on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TeamAccess {
+    pub artist_id: i32,
+    pub artist_name: String,
+    pub permissions: Vec<String>,
+}
+
+// Artist pricing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ArtistPricing {
+    pub hourly_rate: Option<f64>,
+    pub minimum_charge: Option<f64>,
+    pub style_prices: Vec<StylePrice>,
+}
+
+/// The range an artist quotes for one of their styles; both bounds empty
+/// when they haven't priced it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StylePrice {
+    pub style_id: i32,
+    pub style_name: String,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+}
+
+// Profile completeness
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProfileCompleteness {
+    /// 0 to 100
+    pub score: i32,
+    pub items: Vec<CompletenessItem>,
+}
+
+/// One checklist entry. `delta` is how much finishing it would add to the
+/// score.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CompletenessItem {
+    pub key: String,
+    pub label: String,
+    pub hint: String,
+    pub link: String,
+    pub points: i32,
+    pub max_points: i32,
+    pub delta: i32,
+    pub complete: bool,
+}
+
+// Short links
+/// Visits to a short link from one referring site
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReferrerClicks {
+    /// `None` for visits with no referrer, like links opened from SMS
+    pub host: Option<String>,
+    pub clicks: i64,
+}
+
+/// An artist's profile short link and who has been opening it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShortLinkStats {
+    pub url: String,
+    pub clicks: i64,
+    /// Busiest referrers first
+    pub referrers: Vec<ReferrerClicks>,
+}
+
+// Export jobs
+/// An export as shown to whoever asked for it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExportJob {
+    pub id: String,
+    /// One of `utils::export::ARTIST_EXPORTS` or `ADMIN_EXPORTS`
+    pub kind: String,
+    /// "queued", "running", "complete", "failed" or "expired"
+    pub status: String,
+    /// 0-100
+    pub progress: i32,
+    pub rows_written: i64,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    /// Signed and short-lived, for complete jobs only
+    pub download_url: Option<String>,
+}
+
+// Dashboard hints
+/// Guidance shown on a dashboard page, worked out from the artist's data
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DashboardHint {
+    pub key: String,
+    /// "warning" when something stops clients booking, otherwise "tip"
+    pub severity: String,
+    pub title: String,
+    pub message: String,
+    pub link: String,
+    pub link_label: String,
+}
+
+// Account contact changes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AccountContact {
+    pub email: String,
+    pub phone: Option<String>,
+    pub pending: Vec<PendingContactChange>,
+}
+
+/// A change waiting for the code sent to the new address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingContactChange {
+    pub id: i64,
+    /// "email" or "phone"
+    pub channel: String,
+    pub new_value: String,
+    pub expires_at: String,
+}
+
+// Artist onboarding
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OnboardingShop {
+    pub location_id: i64,
+    pub name: String,
+    pub address: String,
+}
+
+/// A shop found on Google for the artist to pick
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShopCandidate {
+    pub place_id: String,
+    pub name: String,
+    pub address: String,
+}
+
+/// Where a newly signed-up artist is in onboarding. Each step is done once
+/// its data exists, so artists can leave and pick up where they stopped.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OnboardingStatus {
+    pub artist_id: i32,
+    pub shop: Option<OnboardingShop>,
+    pub style_ids: Vec<i32>,
+    pub has_pricing: bool,
+    pub open_days: i64,
+    pub photo_count: i64,
+    /// Onboarding is finished and the profile is live
+    pub completed: bool,
+}
+
+// Booking timeline
+/// Something that happened to a booking request. Stored as JSON in
+/// booking_events.details, tagged by `type` (the event_type column).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BookingEventKind {
+    Created,
+    /// The artist (or a team member) first opened the request
+    Viewed,
+    TimeSuggested {
+        date: String,
+        start_time: String,
+        #[serde(default)]
+        end_time: Option<String>,
+    },
+    Message {
+        sender_type: String,
+        message: String,
+    },
+    Accepted {
+        #[serde(default)]
+        deposit_amount: Option<f64>,
+    },
+    Declined {
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    DepositPaid {
+        #[serde(default)]
+        amount: Option<f64>,
+        #[serde(default)]
+        currency: Option<String>,
+    },
+    /// The other side agreed to a suggested time, which the booking moved to
+    TimeAccepted {
+        date: String,
+        start_time: String,
+        #[serde(default)]
+        end_time: Option<String>,
+    },
+    TimeDeclined {
+        #[serde(default)]
+        note: Option<String>,
+    },
+    Cancelled {
+        reason: String,
+        #[serde(default)]
+        note: Option<String>,
+        /// The client cancelled inside the artist's cancellation window
+        #[serde(default)]
+        late: bool,
+    },
+    /// The client signed the consent form, under this name
+    ConsentSigned {
+        typed_name: String,
+    },
+}
+
+impl BookingEventKind {
+    /// Value of the event_type column
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            BookingEventKind::Created => "created",
+            BookingEventKind::Viewed => "viewed",
+            BookingEventKind::TimeSuggested { .. } => "time_suggested",
+            BookingEventKind::Message { .. } => "message",
+            BookingEventKind::Accepted { .. } => "accepted",
+            BookingEventKind::Declined { .. } => "declined",
+            BookingEventKind::DepositPaid { .. } => "deposit_paid",
+            BookingEventKind::TimeAccepted { .. } => "time_accepted",
+            BookingEventKind::TimeDeclined { .. } => "time_declined",
+            BookingEventKind::Cancelled { .. } => "cancelled",
+            BookingEventKind::ConsentSigned { .. } => "consent_signed",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BookingEvent {
+    pub id: i64,
+    pub booking_id: i32,
+    /// "client", "artist" or "system"
+    pub actor: String,
+    pub kind: BookingEventKind,
+    pub created_at: String,
+}
+
+/// One step in a booking's status history (booking_status_history)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BookingStatusChange {
+    pub id: i64,
+    pub booking_id: i32,
+    /// None for the request being created
+    pub from_status: Option<BookingStatus>,
+    pub to_status: BookingStatus,
+    /// "client", "artist" or "system"
+    pub actor: String,
+    pub changed_by: Option<i64>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+// Client dashboard
+/// A booking request as its client sees it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClientBooking {
+    pub id: i32,
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    pub requested_date: String,
+    pub requested_start_time: String,
+    pub requested_end_time: Option<String>,
+    /// Another time the artist proposed, if any
+    pub suggested_date: Option<String>,
+    pub suggested_start_time: Option<String>,
+    pub status: String,
+    pub artist_response: Option<String>,
+    pub deposit_amount: Option<f64>,
+    pub deposit_status: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// The latest message on one of the client's bookings
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ClientMessageThread {
+    pub booking_id: i32,
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    pub requested_date: String,
+    pub message_count: i64,
+    /// Messages from the artist the client hasn't read
+    #[serde(default)]
+    pub unread_count: i64,
+    pub last_message: String,
+    pub last_sender_type: String,
+    pub last_message_at: Option<String>,
+}
+
+// Booking auto-responses
+/// An artist's automatic first reply to new booking requests
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AutoResponseSettings {
+    pub enabled: bool,
+    /// Minutes to hold the reply back, giving the artist a chance to answer
+    /// first
+    pub delay_minutes: i32,
+    pub templates: Vec<AutoResponseTemplate>,
+}
+
+/// The reply for one booking type, or `"default"` for the rest
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AutoResponseTemplate {
+    pub booking_type: String,
+    pub message: String,
+}
+
+// Artist licenses
+/// A license as its artist and admins see it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArtistLicense {
+    pub id: i32,
+    pub artist_id: i32,
+    /// For the admin review list
+    pub artist_name: Option<String>,
+    pub license_number: String,
+    pub issuing_state: String,
+    /// YYYY-MM-DD
+    pub expires_on: Option<String>,
+    /// Signed, short-lived link to the document
+    pub document_url: Option<String>,
+    /// One of `utils::licensing::VERIFICATION_STATUSES`
+    pub verification_status: String,
+    pub rejection_reason: Option<String>,
+    /// Whether the issuing state requires it on the artist's profile
+    pub display_required: bool,
+    pub updated_at: String,
+}
+
+/// A verified license as shown on the artist's profile
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProfileLicense {
+    pub license_number: String,
+    pub issuing_state: String,
+    pub expires_on: Option<String>,
+}
+
+// Artist Q&A
+/// A question on an artist's profile as the artist and admins see it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArtistQuestion {
+    pub id: i32,
+    pub artist_id: i32,
+    /// For the admin moderation queue
+    pub artist_name: Option<String>,
+    pub asker_name: String,
+    pub question: String,
+    pub answer: Option<String>,
+    /// One of `utils::artist_questions::QUESTION_STATUSES`
+    pub status: String,
+    pub flag_reason: Option<String>,
+    pub asked_at: String,
+    pub answered_at: Option<String>,
+}
+
+/// An answered question as shown on the artist's profile
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProfileQuestion {
+    pub id: i32,
+    /// The asker's first name
+    pub asker_name: String,
+    pub question: String,
+    pub answer: String,
+    /// YYYY-MM-DD
+    pub answered_on: String,
+}
+
+// Booking archives
+/// An archived booking's state as its artist sees it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BookingArchiveStatus {
+    /// YYYY-MM-DD
+    pub archived_on: String,
+    /// The latest restore request's status, one of
+    /// `utils::archive::RESTORE_STATUSES`
+    pub restore_status: Option<String>,
+}
+
+/// An artist's request to have an archived booking restored, for admins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BookingRestoreRequest {
+    pub id: i32,
+    pub booking_id: i32,
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    pub client_name: String,
+    pub requested_date: String,
+    /// YYYY-MM-DD, `None` once restored
+    pub archived_on: Option<String>,
+    pub reason: String,
+    /// One of `utils::archive::RESTORE_STATUSES`
+    pub status: String,
+    /// Why restoring failed
+    pub error: Option<String>,
+    pub requested_at: String,
+}
+
+// Booking reschedules
+/// Another time for a booking, suggested by the artist or countered by the
+/// client
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RescheduleProposal {
+    pub id: i64,
+    pub booking_id: i32,
+    /// "client" or "artist"
+    pub proposed_by: String,
+    pub date: String,
+    pub start_time: String,
+    pub end_time: Option<String>,
+    /// "open", "accepted", "declined" or "superseded"
+    pub status: String,
+    pub response_note: Option<String>,
+    pub created_at: String,
+}
+
+/// A booking as the client sees it through the link they were emailed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LinkedBooking {
+    pub id: i32,
+    pub artist_name: Option<String>,
+    pub client_name: String,
+    pub requested_date: String,
+    pub requested_start_time: String,
+    pub requested_end_time: Option<String>,
+    pub status: String,
+    pub deposit_amount: Option<f64>,
+    pub deposit_status: Option<String>,
+    /// The latest proposal, if it's still open
+    pub open_proposal: Option<RescheduleProposal>,
+    pub cancellation_window_hours: i32,
+    /// Cancelling now falls inside the cancellation window
+    pub late_to_cancel: bool,
+    /// Why it was cancelled (see `utils::reschedule`), once it is
+    pub cancellation_reason: Option<String>,
+}
+
+// Consent and aftercare
+/// An artist's consent form or aftercare instructions, theirs or the default
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DocumentTemplate {
+    /// "consent" or "aftercare"
+    pub kind: String,
+    pub title: String,
+    /// Markdown with `{variable}` placeholders (see `utils::documents`)
+    pub body: String,
+    /// The artist hasn't written their own
+    pub is_default: bool,
+}
+
+/// A client's signature on a booking's consent form
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ConsentSignature {
+    pub id: i64,
+    pub booking_id: i32,
+    pub title: String,
+    /// The form filled in, as the client read it
+    pub body: String,
+    pub typed_name: String,
+    pub ip_address: Option<String>,
+    /// UTC
+    pub signed_at: String,
+}
+
+/// A booking's consent form as the client sees it through their link
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ConsentForm {
+    pub booking_id: i32,
+    pub title: String,
+    /// Filled in for the booking, or as signed once it is
+    pub body: String,
+    pub signature: Option<ConsentSignature>,
+}
+
+// Guest spots
+/// Dates an artist is working out of another shop
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuestSpot {
+    pub id: i32,
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    pub location_id: i64,
+    pub shop_name: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    /// The artist at the shop who invited them
+    pub host_artist_id: Option<i32>,
+    pub host_artist_name: Option<String>,
+    /// YYYY-MM-DD, inclusive
+    pub start_date: String,
+    pub end_date: String,
+    pub note: Option<String>,
+}
+
+/// A shop an artist can pick for a guest spot
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GuestSpotShop {
+    pub location_id: i64,
+    pub name: String,
+    pub city: Option<String>,
+    pub state: Option<String>,
+}
+
+// Flash
+/// A pre-drawn design an artist sells at a fixed price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FlashDesign {
+    pub id: i32,
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    /// The artist's shop's city and state
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub image_url: String,
+    pub thumbnail_url: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_inches: Option<f32>,
+    pub price: f64,
+    /// Can be tattooed more than once; one-offs are taken by the first claim
+    pub repeatable: bool,
+    /// False for a one-off an open or finished booking has claimed
+    pub available: bool,
+    pub archived: bool,
+    pub style_ids: Vec<i32>,
+    pub style_names: Vec<String>,
+    pub created_at: String,
+}
+
+// Artist profile content
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArtistFaq {
+    pub question: String,
+    pub answer: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArtistPolicy {
+    /// One of `utils::profile::POLICY_KINDS`
+    pub kind: String,
+    /// Markdown (see `utils::documents`)
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SocialLink {
+    /// One of `utils::profile::SOCIAL_PLATFORMS`
+    pub platform: String,
+    pub url: String,
+}
+
+/// Everything an artist has written for their public page
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ArtistProfileContent {
+    /// Markdown (see `utils::documents`)
+    pub bio: Option<String>,
+    pub faqs: Vec<ArtistFaq>,
+    pub policies: Vec<ArtistPolicy>,
+    pub social_links: Vec<SocialLink>,
 }
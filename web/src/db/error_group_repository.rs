@@ -0,0 +1,198 @@
+#[cfg(feature = "ssr")]
+use super::entities::{ErrorAssignee, ErrorCount, ErrorGroup, ErrorLog};
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Error groups matching the dashboard's filters, those happening most in
+/// the last `days` first. Empty filters match everything; `search` matches
+/// the message or top frame.
+#[cfg(feature = "ssr")]
+pub async fn get_error_groups(
+    status: &str,
+    error_type: &str,
+    search: &str,
+    days: i32,
+    limit: i64,
+) -> DbResult<Vec<ErrorGroup>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT g.fingerprint, g.error_type, g.error_level, g.title, g.top_frame,
+            TO_CHAR(g.first_seen, 'YYYY-MM-DD HH24:MI') as first_seen,
+            TO_CHAR(g.last_seen, 'YYYY-MM-DD HH24:MI') as last_seen,
+            g.occurrences,
+            (SELECT COUNT(*) FROM error_logs el
+             WHERE el.fingerprint = g.fingerprint
+               AND el.timestamp::timestamp >= NOW() - make_interval(days => $4)) as recent_occurrences,
+            g.status, g.assigned_to, u.email as assignee_email,
+            TO_CHAR(g.resolved_at, 'YYYY-MM-DD HH24:MI') as resolved_at,
+            TO_CHAR(g.reopened_at, 'YYYY-MM-DD HH24:MI') as reopened_at
+         FROM error_groups g
+         LEFT JOIN users u ON u.id = g.assigned_to
+         WHERE ($1 = '' OR g.status = $1)
+           AND ($2 = '' OR g.error_type = $2)
+           AND ($3 = '' OR g.title ILIKE '%' || $3 || '%' OR g.top_frame ILIKE '%' || $3 || '%')
+           AND g.last_seen >= NOW() - make_interval(days => $4)
+         ORDER BY recent_occurrences DESC, g.last_seen DESC
+         LIMIT $5",
+    )
+    .bind(status)
+    .bind(error_type)
+    .bind(search.trim())
+    .bind(days)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ErrorGroup {
+            fingerprint: row.get("fingerprint"),
+            error_type: row.get("error_type"),
+            error_level: row.get("error_level"),
+            title: row.get("title"),
+            top_frame: row.get("top_frame"),
+            first_seen: row.get("first_seen"),
+            last_seen: row.get("last_seen"),
+            occurrences: row.get("occurrences"),
+            recent_occurrences: row.get("recent_occurrences"),
+            status: row.get("status"),
+            assigned_to: row.get("assigned_to"),
+            assignee_email: row.get("assignee_email"),
+            resolved_at: row.get("resolved_at"),
+            reopened_at: row.get("reopened_at"),
+        })
+        .collect())
+}
+
+/// Errors logged per day over the last `days`, oldest first with empty days
+/// included; for one group, or all errors when `fingerprint` is `None`
+#[cfg(feature = "ssr")]
+pub async fn get_error_counts(fingerprint: Option<&str>, days: i32) -> DbResult<Vec<ErrorCount>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT TO_CHAR(d.day, 'YYYY-MM-DD') as day, COUNT(el.id) as count
+         FROM generate_series(CURRENT_DATE - ($2 - 1), CURRENT_DATE, INTERVAL '1 day') as d(day)
+         LEFT JOIN error_logs el
+            ON el.timestamp::timestamp >= d.day
+           AND el.timestamp::timestamp < d.day + INTERVAL '1 day'
+           AND ($1::text IS NULL OR el.fingerprint = $1)
+         GROUP BY d.day
+         ORDER BY d.day",
+    )
+    .bind(fingerprint)
+    .bind(days)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ErrorCount {
+            day: row.get("day"),
+            count: row.get("count"),
+        })
+        .collect())
+}
+
+/// The group's latest occurrences, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_error_samples(fingerprint: &str, limit: i64) -> DbResult<Vec<ErrorLog>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, error_type, error_level, error_message, error_stack,
+               url_path, user_agent, user_id, session_id, timestamp,
+               request_headers, additional_context, build_id, symbolicated_stack
+         FROM error_logs
+         WHERE fingerprint = $1
+         ORDER BY timestamp DESC
+         LIMIT $2",
+    )
+    .bind(fingerprint)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ErrorLog {
+            id: row.get("id"),
+            error_type: row.get("error_type"),
+            error_level: row.get("error_level"),
+            error_message: row.get("error_message"),
+            error_stack: row.try_get("error_stack").ok(),
+            url_path: row.try_get("url_path").ok(),
+            user_agent: row.try_get("user_agent").ok(),
+            user_id: row.try_get("user_id").ok(),
+            session_id: row.try_get("session_id").ok(),
+            timestamp: row.get("timestamp"),
+            request_headers: row.try_get("request_headers").ok(),
+            additional_context: row.try_get("additional_context").ok(),
+            build_id: row.try_get("build_id").ok(),
+            symbolicated_stack: row.try_get("symbolicated_stack").ok(),
+        })
+        .collect())
+}
+
+/// Moves a group to `status`, recording who resolved it. Returns false when
+/// there's no such group.
+#[cfg(feature = "ssr")]
+pub async fn set_error_status(fingerprint: &str, status: &str, admin_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE error_groups SET
+            status = $2,
+            resolved_at = CASE WHEN $2 = 'resolved' THEN CURRENT_TIMESTAMP END,
+            resolved_by = CASE WHEN $2 = 'resolved' THEN $3 END
+         WHERE fingerprint = $1",
+    )
+    .bind(fingerprint)
+    .bind(status)
+    .bind(admin_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Assigns a group to an admin, or unassigns it. Returns false when there's
+/// no such group.
+#[cfg(feature = "ssr")]
+pub async fn assign_error_group(fingerprint: &str, assignee: Option<i64>) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("UPDATE error_groups SET assigned_to = $2 WHERE fingerprint = $1")
+        .bind(fingerprint)
+        .bind(assignee)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Active admins, who error groups can be assigned to
+#[cfg(feature = "ssr")]
+pub async fn get_assignees() -> DbResult<Vec<ErrorAssignee>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, email FROM users
+         WHERE role::text = 'admin' AND is_active = true
+         ORDER BY email",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ErrorAssignee {
+            user_id: row.get("id"),
+            email: row.get("email"),
+        })
+        .collect())
+}
@@ -0,0 +1,333 @@
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// An `exports` row
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug)]
+pub struct StoredExport {
+    pub id: String,
+    pub user_id: i64,
+    pub artist_id: Option<i32>,
+    pub kind: String,
+    pub status: String,
+    pub total_rows: Option<i64>,
+    pub rows_written: i64,
+    pub storage_path: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[cfg(feature = "ssr")]
+const EXPORT_COLUMNS: &str = "id, user_id, artist_id, kind, status, total_rows, rows_written,
+    storage_path, error, created_at::text as created_at, completed_at::text as completed_at,
+    expires_at";
+
+#[cfg(feature = "ssr")]
+fn export_from_row(row: &PgRow) -> StoredExport {
+    StoredExport {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        artist_id: row.get("artist_id"),
+        kind: row.get("kind"),
+        status: row.get("status"),
+        total_rows: row.get("total_rows"),
+        rows_written: row.get("rows_written"),
+        storage_path: row.get("storage_path"),
+        error: row.get("error"),
+        created_at: row.get("created_at"),
+        completed_at: row.get("completed_at"),
+        expires_at: row.get("expires_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn create_job(
+    id: &str,
+    user_id: i64,
+    artist_id: Option<i32>,
+    kind: &str,
+) -> DbResult<StoredExport> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "INSERT INTO exports (id, user_id, artist_id, kind)
+         VALUES ($1, $2, $3, $4)
+         RETURNING {}",
+        EXPORT_COLUMNS
+    ))
+    .bind(id)
+    .bind(user_id)
+    .bind(artist_id)
+    .bind(kind)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(export_from_row(&row))
+}
+
+/// The user's queued or running export of `kind`, if they already have one
+#[cfg(feature = "ssr")]
+pub async fn get_active_job(user_id: i64, kind: &str) -> DbResult<Option<StoredExport>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM exports
+         WHERE user_id = $1 AND kind = $2 AND status IN ('queued', 'running')
+         ORDER BY created_at DESC
+         LIMIT 1",
+        EXPORT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(kind)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(export_from_row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_job(id: &str) -> DbResult<Option<StoredExport>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM exports WHERE id = $1",
+        EXPORT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(export_from_row))
+}
+
+/// The user's most recent exports, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_jobs(user_id: i64, limit: i64) -> DbResult<Vec<StoredExport>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM exports
+         WHERE user_id = $1
+         ORDER BY created_at DESC
+         LIMIT $2",
+        EXPORT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(export_from_row).collect())
+}
+
+/// Marks a queued job as running and returns it. `None` if it's no longer
+/// queued; one left running by an interrupted run is started again.
+#[cfg(feature = "ssr")]
+pub async fn start_job(id: &str) -> DbResult<Option<StoredExport>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "UPDATE exports
+         SET status = 'running', started_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status IN ('queued', 'running')
+         RETURNING {}",
+        EXPORT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(export_from_row))
+}
+
+/// Rows `kind` will export. `artist_id` scopes artist exports and is
+/// ignored by admin ones.
+#[cfg(feature = "ssr")]
+pub async fn count_rows(kind: &str, artist_id: Option<i32>) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    match kind {
+        "bookings" => {
+            sqlx::query_scalar("SELECT COUNT(*) FROM booking_requests WHERE artist_id = $1")
+                .bind(artist_id)
+                .fetch_one(pool)
+                .await
+        }
+        "all_bookings" => {
+            sqlx::query_scalar("SELECT COUNT(*) FROM booking_requests")
+                .fetch_one(pool)
+                .await
+        }
+        _ => {
+            sqlx::query_scalar("SELECT COUNT(*) FROM artists")
+                .fetch_one(pool)
+                .await
+        }
+    }
+}
+
+/// The next `limit` rows of `kind` after `after_id`, as `(id, fields)` with
+/// fields in the order of `utils::export::headers`
+#[cfg(feature = "ssr")]
+pub async fn fetch_page(
+    kind: &str,
+    artist_id: Option<i32>,
+    after_id: i64,
+    limit: i64,
+) -> DbResult<Vec<(i64, Vec<String>)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let query = match kind {
+        "bookings" => {
+            "SELECT br.id::bigint as id, ARRAY[
+                 br.id::text, COALESCE(br.created_at::text, ''), COALESCE(br.requested_date, ''),
+                 COALESCE(br.requested_start_time, ''), COALESCE(br.requested_end_time, ''),
+                 br.status, COALESCE(br.booking_type, ''), COALESCE(br.client_name, ''),
+                 COALESCE(br.client_email, ''), COALESCE(br.client_phone, ''),
+                 COALESCE(br.placement, ''), COALESCE(br.size_inches::text, ''),
+                 COALESCE(br.tattoo_description, ''), COALESCE(br.estimated_price::text, ''),
+                 COALESCE(br.deposit_status, '')
+             ] as fields
+             FROM booking_requests br
+             WHERE br.id > $1 AND br.artist_id = $3
+             ORDER BY br.id
+             LIMIT $2"
+        }
+        "all_bookings" => {
+            "SELECT br.id::bigint as id, ARRAY[
+                 br.id::text, br.artist_id::text, COALESCE(a.name, ''),
+                 COALESCE(br.created_at::text, ''), COALESCE(br.requested_date, ''),
+                 COALESCE(br.requested_start_time, ''), br.status,
+                 COALESCE(br.booking_type, ''), COALESCE(br.client_name, ''),
+                 COALESCE(br.client_email, ''), COALESCE(br.estimated_price::text, ''),
+                 COALESCE(br.deposit_status, '')
+             ] as fields
+             FROM booking_requests br
+             LEFT JOIN artists a ON a.id = br.artist_id
+             WHERE br.id > $1
+             ORDER BY br.id
+             LIMIT $2"
+        }
+        _ => {
+            "SELECT a.id::bigint as id, ARRAY[
+                 a.id::text, COALESCE(a.name, ''), COALESCE(a.email, ''), COALESCE(a.phone, ''),
+                 COALESCE(a.instagram_handle, ''), COALESCE(a.years_experience::text, ''),
+                 COALESCE(l.name, ''), COALESCE(l.city, ''),
+                 COALESCE(a.shop_validated::text, '')
+             ] as fields
+             FROM artists a
+             LEFT JOIN locations l ON l.id = a.location_id
+             WHERE a.id > $1
+             ORDER BY a.id
+             LIMIT $2"
+        }
+    };
+
+    let mut query = sqlx::query(query).bind(after_id).bind(limit);
+    if kind == "bookings" {
+        query = query.bind(artist_id);
+    }
+    let rows = query.fetch_all(pool).await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("id"), row.get("fields")))
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_progress(id: &str, total_rows: i64, rows_written: i64) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE exports SET total_rows = $2, rows_written = $3 WHERE id = $1")
+        .bind(id)
+        .bind(total_rows)
+        .bind(rows_written)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Marks the job complete, with its file kept for `keep_hours`
+#[cfg(feature = "ssr")]
+pub async fn complete_job(
+    id: &str,
+    rows_written: i64,
+    storage_path: &str,
+    keep_hours: i64,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE exports
+         SET status = 'complete', rows_written = $2, storage_path = $3,
+             completed_at = CURRENT_TIMESTAMP,
+             expires_at = CURRENT_TIMESTAMP + make_interval(hours => $4::int)
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(rows_written)
+    .bind(storage_path)
+    .bind(keep_hours)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn fail_job(id: &str, error: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE exports
+         SET status = 'failed', error = $2, completed_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Marks complete jobs past their expiry as expired and returns their files
+/// for deletion
+#[cfg(feature = "ssr")]
+pub async fn expire_jobs() -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "UPDATE exports
+         SET status = 'expired'
+         WHERE status = 'complete' AND expires_at <= CURRENT_TIMESTAMP
+         RETURNING storage_path",
+    )
+    .fetch_all(pool)
+    .await
+    .map(|paths: Vec<Option<String>>| paths.into_iter().flatten().collect())
+}
+
+/// Fails jobs that have been running for over `max_minutes`, left behind by
+/// a server that stopped partway through
+#[cfg(feature = "ssr")]
+pub async fn fail_stalled_jobs(max_minutes: i32) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE exports
+         SET status = 'failed', error = 'The export was interrupted, please try again',
+             completed_at = CURRENT_TIMESTAMP
+         WHERE status = 'running'
+           AND started_at < CURRENT_TIMESTAMP - make_interval(mins => $1)",
+    )
+    .bind(max_minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
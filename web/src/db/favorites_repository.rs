@@ -1,4 +1,6 @@
-use super::entities::{Artist, ArtistImage, CreateUserFavorite, Style, UserFavorite};
+use super::entities::{
+    Artist, ArtistImage, CreateUserFavorite, FavoriteCollection, Style, UserFavorite,
+};
 #[cfg(feature = "ssr")]
 use sqlx::{PgPool, Row};
 
@@ -50,15 +52,30 @@ pub async fn add_favorite(user_id: i32, artists_images_id: i32) -> DbResult<i64>
 pub async fn remove_favorite(user_id: i32, artists_images_id: i32) -> DbResult<()> {
     let pool = crate::db::pool::get_pool();
 
+    let mut tx = pool.begin().await?;
+
     sqlx::query(
         "DELETE FROM user_favorites
          WHERE user_id = $1 AND artists_images_id = $2",
     )
     .bind(user_id)
     .bind(artists_images_id)
-    .execute(pool)
+    .execute(&mut *tx)
+    .await?;
+
+    // An unfavorited image comes off every board too
+    sqlx::query(
+        "DELETE FROM favorite_collection_items
+         WHERE artists_images_id = $2
+           AND collection_id IN (SELECT id FROM favorite_collections WHERE user_id = $1)",
+    )
+    .bind(user_id as i64)
+    .bind(artists_images_id as i64)
+    .execute(&mut *tx)
     .await?;
 
+    tx.commit().await?;
+
     Ok(())
 }
 
@@ -134,13 +151,31 @@ pub async fn toggle_favorite(user_id: i32, artists_images_id: i32) -> DbResult<b
 pub async fn get_user_favorites_with_details(
     user_id: i32,
 ) -> DbResult<Vec<FavoritePostWithDetails>> {
-    let pool = crate::db::pool::get_pool();
-
     // Get all favorited image IDs
     let image_ids = get_user_favorites(user_id).await?;
+    get_posts_with_details(&image_ids).await
+}
+
+/// The user's most recently saved favorites, for previews like the client
+/// dashboard
+#[cfg(feature = "ssr")]
+pub async fn get_recent_favorites_with_details(
+    user_id: i32,
+    limit: usize,
+) -> DbResult<Vec<FavoritePostWithDetails>> {
+    let mut image_ids = get_user_favorites(user_id).await?;
+    image_ids.truncate(limit);
+    get_posts_with_details(&image_ids).await
+}
+
+/// Loads image, artist and styles for each image id, keeping the given order
+/// and skipping images that no longer exist
+#[cfg(feature = "ssr")]
+async fn get_posts_with_details(image_ids: &[i32]) -> DbResult<Vec<FavoritePostWithDetails>> {
+    let pool = crate::db::pool::get_pool();
     let mut posts = Vec::new();
 
-    for image_id in image_ids {
+    for &image_id in image_ids {
         // Get image details
         let image_row = sqlx::query(
             "SELECT id, short_code, artist_id, post_date
@@ -161,7 +196,7 @@ pub async fn get_user_favorites_with_details(
 
             // Get artist details
             let artist_row = sqlx::query(
-                "SELECT id, name, location_id, social_links, instagram_handle, email, phone, years_experience, styles_extracted
+                "SELECT id, name, location_id, social_links, instagram_handle, email, phone, years_experience, styles_extracted, experience_tier
                  FROM artists WHERE id = $1",
             )
             .bind(image.artist_id)
@@ -185,6 +220,7 @@ pub async fn get_user_favorites_with_details(
                     .ok()
                     .map(|v| v as i32),
                 shop_validated: row.try_get("shop_validated").ok(),
+                experience_tier: row.try_get("experience_tier").ok().flatten(),
             });
 
             // Get styles for this image
@@ -216,3 +252,218 @@ pub async fn get_user_favorites_with_details(
 
     Ok(posts)
 }
+
+/// A user's boards with item counts, newest first
+#[cfg(feature = "ssr")]
+pub async fn list_collections(user_id: i64) -> DbResult<Vec<FavoriteCollection>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT fc.id, fc.name, TO_CHAR(fc.created_at, 'YYYY-MM-DD') as created_at,
+            COUNT(fci.artists_images_id) as item_count,
+            (SELECT ai.short_code
+             FROM favorite_collection_items latest
+             JOIN artists_images ai ON ai.id = latest.artists_images_id
+             WHERE latest.collection_id = fc.id
+             ORDER BY latest.added_at DESC
+             LIMIT 1) as cover_short_code
+         FROM favorite_collections fc
+         LEFT JOIN favorite_collection_items fci ON fci.collection_id = fc.id
+         WHERE fc.user_id = $1
+         GROUP BY fc.id
+         ORDER BY fc.created_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| FavoriteCollection {
+            id: row.get("id"),
+            name: row.get("name"),
+            item_count: row.get("item_count"),
+            cover_short_code: row.get("cover_short_code"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// Creates a board, returning `None` if the user already has one with that name
+#[cfg(feature = "ssr")]
+pub async fn create_collection(user_id: i64, name: &str) -> DbResult<Option<i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO favorite_collections (user_id, name)
+         VALUES ($1, $2)
+         ON CONFLICT (user_id, LOWER(name)) DO NOTHING
+         RETURNING id",
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| row.get("id")))
+}
+
+/// Renames a board the user owns. Returns false if it doesn't exist or the
+/// new name is already taken.
+#[cfg(feature = "ssr")]
+pub async fn rename_collection(user_id: i64, collection_id: i64, name: &str) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE favorite_collections SET name = $3
+         WHERE id = $2 AND user_id = $1
+           AND NOT EXISTS (
+               SELECT 1 FROM favorite_collections
+               WHERE user_id = $1 AND id <> $2 AND LOWER(name) = LOWER($3)
+           )",
+    )
+    .bind(user_id)
+    .bind(collection_id)
+    .bind(name)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes a board the user owns; the images stay favorited
+#[cfg(feature = "ssr")]
+pub async fn delete_collection(user_id: i64, collection_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM favorite_collections WHERE id = $1 AND user_id = $2")
+        .bind(collection_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Puts an image on a board the user owns, favoriting it first if needed.
+/// Returns false if the board isn't the user's.
+#[cfg(feature = "ssr")]
+pub async fn add_to_collection(
+    user_id: i64,
+    collection_id: i64,
+    artists_images_id: i64,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let owned = sqlx::query("SELECT 1 FROM favorite_collections WHERE id = $1 AND user_id = $2")
+        .bind(collection_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_some();
+    if !owned {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        "INSERT INTO user_favorites (user_id, artists_images_id)
+         VALUES ($1, $2)
+         ON CONFLICT (user_id, artists_images_id) DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(artists_images_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO favorite_collection_items (collection_id, artists_images_id)
+         VALUES ($1, $2)
+         ON CONFLICT (collection_id, artists_images_id) DO NOTHING",
+    )
+    .bind(collection_id)
+    .bind(artists_images_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Takes an image off a board the user owns; it stays favorited
+#[cfg(feature = "ssr")]
+pub async fn remove_from_collection(
+    user_id: i64,
+    collection_id: i64,
+    artists_images_id: i64,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "DELETE FROM favorite_collection_items
+         WHERE collection_id = $1 AND artists_images_id = $2
+           AND collection_id IN (SELECT id FROM favorite_collections WHERE user_id = $3)",
+    )
+    .bind(collection_id)
+    .bind(artists_images_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Ids of the user's boards that contain the image
+#[cfg(feature = "ssr")]
+pub async fn get_collections_for_image(user_id: i64, artists_images_id: i64) -> DbResult<Vec<i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT fci.collection_id
+         FROM favorite_collection_items fci
+         JOIN favorite_collections fc ON fc.id = fci.collection_id
+         WHERE fc.user_id = $1 AND fci.artists_images_id = $2",
+    )
+    .bind(user_id)
+    .bind(artists_images_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| row.get("collection_id")).collect())
+}
+
+/// Posts on one of the user's boards with full details, most recently added
+/// first. `None` if the board isn't the user's.
+#[cfg(feature = "ssr")]
+pub async fn get_collection_with_details(
+    user_id: i64,
+    collection_id: i64,
+) -> DbResult<Option<Vec<FavoritePostWithDetails>>> {
+    let pool = crate::db::pool::get_pool();
+
+    let owned = sqlx::query("SELECT 1 FROM favorite_collections WHERE id = $1 AND user_id = $2")
+        .bind(collection_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .is_some();
+    if !owned {
+        return Ok(None);
+    }
+
+    let rows = sqlx::query(
+        "SELECT artists_images_id FROM favorite_collection_items
+         WHERE collection_id = $1
+         ORDER BY added_at DESC",
+    )
+    .bind(collection_id)
+    .fetch_all(pool)
+    .await?;
+
+    let image_ids: Vec<i32> = rows
+        .into_iter()
+        .map(|row| row.get::<i64, _>("artists_images_id") as i32)
+        .collect();
+
+    get_posts_with_details(&image_ids).await.map(Some)
+}
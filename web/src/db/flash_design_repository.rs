@@ -0,0 +1,347 @@
+#[cfg(feature = "ssr")]
+use super::entities::FlashDesign;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Postgres, Row, Transaction};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A stored flash image before it has a row
+#[cfg(feature = "ssr")]
+pub struct NewFlashDesign {
+    pub artist_id: i32,
+    pub title: String,
+    pub description: Option<String>,
+    pub image_key: String,
+    pub thumbnail_key: String,
+    pub width: i32,
+    pub height: i32,
+    pub size_inches: Option<f32>,
+    pub price: f64,
+    pub repeatable: bool,
+    pub style_ids: Vec<i32>,
+}
+
+/// The details an artist can change after uploading
+#[cfg(feature = "ssr")]
+pub struct FlashDesignUpdate<'a> {
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub size_inches: Option<f32>,
+    pub price: f64,
+    pub repeatable: bool,
+    pub archived: bool,
+    pub style_ids: &'a [i32],
+}
+
+/// A one-off is taken while a booking that claimed it is still open or done
+#[cfg(feature = "ssr")]
+const AVAILABLE: &str = "(f.repeatable OR NOT EXISTS (
+        SELECT 1 FROM booking_requests b
+        WHERE b.flash_design_id = f.id AND b.status NOT IN ('declined', 'cancelled')
+     ))";
+
+#[cfg(feature = "ssr")]
+fn flash_select() -> String {
+    format!(
+        "SELECT f.id, f.artist_id, a.name as artist_name, l.city, l.state, f.title,
+            f.description, f.image_key, f.thumbnail_key, f.width, f.height, f.size_inches,
+            f.price, f.repeatable, {} as available, f.archived_at IS NOT NULL as archived,
+            ARRAY(SELECT s.id FROM flash_design_styles fs JOIN styles s ON s.id = fs.style_id
+                  WHERE fs.flash_design_id = f.id ORDER BY s.name) as style_ids,
+            ARRAY(SELECT s.name FROM flash_design_styles fs JOIN styles s ON s.id = fs.style_id
+                  WHERE fs.flash_design_id = f.id ORDER BY s.name) as style_names,
+            TO_CHAR(f.created_at, 'YYYY-MM-DD') as created_at
+         FROM flash_designs f
+         JOIN artists a ON a.id = f.artist_id
+         LEFT JOIN locations l ON l.id = a.location_id",
+        AVAILABLE
+    )
+}
+
+#[cfg(feature = "ssr")]
+fn flash_design_from_row(row: &PgRow) -> FlashDesign {
+    let storage = crate::storage::storage();
+    let image_key: String = row.get("image_key");
+    let thumbnail_key: String = row.get("thumbnail_key");
+
+    FlashDesign {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        city: row.get("city"),
+        state: row.get("state"),
+        title: row.get("title"),
+        description: row.get("description"),
+        image_url: storage.public_url(&image_key).unwrap_or_default(),
+        thumbnail_url: storage.public_url(&thumbnail_key).unwrap_or_default(),
+        width: row.get("width"),
+        height: row.get("height"),
+        size_inches: row.get("size_inches"),
+        price: row.get("price"),
+        repeatable: row.get("repeatable"),
+        available: row.get("available"),
+        archived: row.get("archived"),
+        style_ids: row
+            .get::<Vec<i64>, _>("style_ids")
+            .into_iter()
+            .map(|id| id as i32)
+            .collect(),
+        style_names: row.get("style_names"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Replaces a design's styles with those of `style_ids` that exist
+#[cfg(feature = "ssr")]
+async fn set_styles(
+    tx: &mut Transaction<'_, Postgres>,
+    flash_design_id: i32,
+    style_ids: &[i32],
+) -> DbResult<()> {
+    let style_ids: Vec<i64> = style_ids.iter().map(|&id| id as i64).collect();
+
+    sqlx::query("DELETE FROM flash_design_styles WHERE flash_design_id = $1")
+        .bind(flash_design_id)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query(
+        "INSERT INTO flash_design_styles (flash_design_id, style_id)
+         SELECT $1, id FROM styles WHERE id = ANY($2)",
+    )
+    .bind(flash_design_id)
+    .bind(&style_ids)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn insert_flash_design(design: &NewFlashDesign) -> DbResult<i32> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let id: i32 = sqlx::query_scalar(
+        "INSERT INTO flash_designs
+            (artist_id, title, description, image_key, thumbnail_key, width, height,
+             size_inches, price, repeatable)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+         RETURNING id",
+    )
+    .bind(design.artist_id)
+    .bind(&design.title)
+    .bind(&design.description)
+    .bind(&design.image_key)
+    .bind(&design.thumbnail_key)
+    .bind(design.width)
+    .bind(design.height)
+    .bind(design.size_inches)
+    .bind(design.price)
+    .bind(design.repeatable)
+    .fetch_one(&mut *tx)
+    .await?;
+    set_styles(&mut tx, id, &design.style_ids).await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Number of designs the artist has that aren't archived
+#[cfg(feature = "ssr")]
+pub async fn count_flash_designs(artist_id: i32) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM flash_designs WHERE artist_id = $1 AND archived_at IS NULL",
+    )
+    .bind(artist_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// All of the artist's designs, archived ones last, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_artist_flash_designs(artist_id: i32) -> DbResult<Vec<FlashDesign>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE f.artist_id = $1
+         ORDER BY f.archived_at IS NOT NULL, f.created_at DESC, f.id DESC",
+        flash_select()
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(flash_design_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_flash_design(flash_design_id: i32) -> DbResult<Option<FlashDesign>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE f.id = $1", flash_select()))
+        .bind(flash_design_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(flash_design_from_row))
+}
+
+/// Designs clients can claim, newest first: not archived, not taken, tagged
+/// with any of `style_ids` (all when empty), at a shop in `city` (any when
+/// `None`)
+#[cfg(feature = "ssr")]
+pub async fn browse_flash_designs(
+    style_ids: &[i32],
+    city: Option<&str>,
+    limit: i64,
+) -> DbResult<Vec<FlashDesign>> {
+    let pool = crate::db::pool::get_pool();
+    let style_ids: Vec<i64> = style_ids.iter().map(|&id| id as i64).collect();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE f.archived_at IS NULL AND {}
+           AND (cardinality($1::bigint[]) = 0 OR EXISTS (
+               SELECT 1 FROM flash_design_styles fs
+               WHERE fs.flash_design_id = f.id AND fs.style_id = ANY($1)
+           ))
+           AND ($2::text IS NULL OR LOWER(l.city) = LOWER($2))
+         ORDER BY f.created_at DESC, f.id DESC
+         LIMIT $3",
+        flash_select(),
+        AVAILABLE
+    ))
+    .bind(&style_ids)
+    .bind(city)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(flash_design_from_row).collect())
+}
+
+/// Cities with flash to claim, for the browse filter
+#[cfg(feature = "ssr")]
+pub async fn get_flash_cities() -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(&format!(
+        "SELECT DISTINCT l.city
+         FROM flash_designs f
+         JOIN artists a ON a.id = f.artist_id
+         JOIN locations l ON l.id = a.location_id
+         WHERE f.archived_at IS NULL AND {} AND l.city IS NOT NULL AND l.city <> ''
+         ORDER BY l.city",
+        AVAILABLE
+    ))
+    .fetch_all(pool)
+    .await
+}
+
+/// Updates one of the artist's designs. False if they have no such design.
+#[cfg(feature = "ssr")]
+pub async fn update_flash_design(
+    artist_id: i32,
+    flash_design_id: i32,
+    update: &FlashDesignUpdate<'_>,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        "UPDATE flash_designs SET
+            title = $3,
+            description = $4,
+            size_inches = $5,
+            price = $6,
+            repeatable = $7,
+            archived_at = CASE WHEN $8 THEN COALESCE(archived_at, CURRENT_TIMESTAMP) END,
+            updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND artist_id = $2",
+    )
+    .bind(flash_design_id)
+    .bind(artist_id)
+    .bind(update.title)
+    .bind(update.description)
+    .bind(update.size_inches)
+    .bind(update.price)
+    .bind(update.repeatable)
+    .bind(update.archived)
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    set_styles(&mut tx, flash_design_id, update.style_ids).await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Whether any booking request has claimed the design
+#[cfg(feature = "ssr")]
+pub async fn is_flash_design_booked(flash_design_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM booking_requests WHERE flash_design_id = $1)")
+        .bind(flash_design_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Deletes one of the artist's designs that no booking has claimed,
+/// returning its image and thumbnail keys
+#[cfg(feature = "ssr")]
+pub async fn delete_flash_design(
+    artist_id: i32,
+    flash_design_id: i32,
+) -> DbResult<Option<(String, String)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "DELETE FROM flash_designs
+         WHERE id = $1 AND artist_id = $2
+           AND NOT EXISTS (SELECT 1 FROM booking_requests WHERE flash_design_id = $1)
+         RETURNING image_key, thumbnail_key",
+    )
+    .bind(flash_design_id)
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("image_key"), row.get("thumbnail_key"))))
+}
+
+/// Locks the design for a booking request being created in `tx`, returning
+/// whether the request may claim it: the artist's, not archived, and either
+/// repeatable or not yet taken
+#[cfg(feature = "ssr")]
+pub async fn claim_in(
+    tx: &mut Transaction<'_, Postgres>,
+    artist_id: i32,
+    flash_design_id: i32,
+) -> DbResult<bool> {
+    // Held until the request is inserted, so two clients can't both claim a
+    // one-off
+    let locked = sqlx::query(
+        "SELECT id FROM flash_designs
+         WHERE id = $1 AND artist_id = $2 AND archived_at IS NULL
+         FOR UPDATE",
+    )
+    .bind(flash_design_id)
+    .bind(artist_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+    if locked.is_none() {
+        return Ok(false);
+    }
+
+    sqlx::query_scalar(&format!(
+        "SELECT {} FROM flash_designs f WHERE f.id = $1",
+        AVAILABLE
+    ))
+    .bind(flash_design_id)
+    .fetch_one(&mut **tx)
+    .await
+}
@@ -0,0 +1,94 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Revenue of completed bookings per calendar month as `(year, month,
+/// revenue)`, for the `months` before the current one. The invoice subtotal
+/// is used where one was issued, otherwise the quoted price. Months without
+/// revenue are missing.
+#[cfg(feature = "ssr")]
+pub async fn get_monthly_revenue(artist_id: i32, months: i32) -> DbResult<Vec<(i32, u32, f64)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT EXTRACT(YEAR FROM br.requested_date::date)::int as year,
+                EXTRACT(MONTH FROM br.requested_date::date)::int as month,
+                SUM(COALESCE(i.subtotal, br.estimated_price, 0))::float8 as revenue
+         FROM booking_requests br
+         LEFT JOIN invoices i ON i.booking_request_id = br.id
+         WHERE br.artist_id = $1
+           AND br.status = 'completed'
+           AND br.requested_date::date >= date_trunc('month', CURRENT_DATE) - make_interval(months => $2)
+           AND br.requested_date::date < date_trunc('month', CURRENT_DATE)
+         GROUP BY 1, 2
+         ORDER BY 1, 2",
+    )
+    .bind(artist_id)
+    .bind(months)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                row.get("year"),
+                row.get::<i32, _>("month") as u32,
+                row.get("revenue"),
+            )
+        })
+        .collect())
+}
+
+/// Past bookings from the last `months` that reached a final status, as
+/// `(completed, completed + cancelled)`
+#[cfg(feature = "ssr")]
+pub async fn get_booking_outcomes(artist_id: i32, months: i32) -> DbResult<(i64, i64)> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT COUNT(*) FILTER (WHERE status = 'completed') as completed,
+                COUNT(*) as closed
+         FROM booking_requests
+         WHERE artist_id = $1
+           AND status IN ('completed', 'cancelled')
+           AND requested_date::date >= CURRENT_DATE - make_interval(months => $2)
+           AND requested_date::date < CURRENT_DATE",
+    )
+    .bind(artist_id)
+    .bind(months)
+    .fetch_one(pool)
+    .await?;
+
+    Ok((row.get("completed"), row.get("closed")))
+}
+
+/// Prices of approved bookings dated in `[start, end)`. Bookings without a
+/// quote count at the artist's average completed price.
+#[cfg(feature = "ssr")]
+pub async fn get_booked_prices(artist_id: i32, start: &str, end: &str) -> DbResult<Vec<f64>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT COALESCE(
+                    br.estimated_price,
+                    (SELECT AVG(estimated_price) FROM booking_requests
+                     WHERE artist_id = $1 AND status = 'completed'),
+                    0
+                )::float8 as price
+         FROM booking_requests br
+         WHERE br.artist_id = $1
+           AND br.status IN ('approved', 'confirmed')
+           AND br.requested_date::date >= $2::date
+           AND br.requested_date::date < $3::date",
+    )
+    .bind(artist_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(|row| row.get("price")).collect())
+}
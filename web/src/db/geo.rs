@@ -0,0 +1,84 @@
+//! Radius filters over `locations l`. When PostGIS was available at
+//! migration time locations carry an indexed `geog` column and distances come
+//! from `ST_Distance`; otherwise they're computed with the haversine formula
+//! in plain SQL. Callers prefilter on `RadiusSearch::bounds` either way.
+
+#[cfg(feature = "ssr")]
+use sqlx::PgPool;
+#[cfg(feature = "ssr")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+const METERS_PER_MILE: f64 = 1609.344;
+#[cfg(feature = "ssr")]
+const EARTH_RADIUS_MILES: f64 = 3958.8;
+
+#[cfg(feature = "ssr")]
+static HAS_POSTGIS: OnceLock<bool> = OnceLock::new();
+
+/// Whether `locations.geog` exists, checked once per process
+#[cfg(feature = "ssr")]
+async fn has_postgis(pool: &PgPool) -> DbResult<bool> {
+    if let Some(has) = HAS_POSTGIS.get() {
+        return Ok(*has);
+    }
+
+    let has: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+            SELECT 1 FROM information_schema.columns
+            WHERE table_name = 'locations' AND column_name = 'geog'
+        )",
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(*HAS_POSTGIS.get_or_init(|| has))
+}
+
+/// SQL fragments for a radius search
+#[cfg(feature = "ssr")]
+pub struct RadiusSql {
+    /// Miles from the center, as a float8 expression
+    pub distance: String,
+    /// Condition keeping locations within the radius
+    pub filter: String,
+}
+
+/// Radius SQL binding the center's latitude at `$first`, its longitude at
+/// `$first + 1` and the radius in miles at `$first + 2`
+#[cfg(feature = "ssr")]
+pub async fn radius_sql(pool: &PgPool, first: usize) -> DbResult<RadiusSql> {
+    let (lat, long, miles) = (first, first + 1, first + 2);
+
+    if has_postgis(pool).await? {
+        let center = format!(
+            "ST_SetSRID(ST_MakePoint(${}::float8, ${}::float8), 4326)::geography",
+            long, lat
+        );
+        return Ok(RadiusSql {
+            distance: format!("(ST_Distance(l.geog, {}) / {})", center, METERS_PER_MILE),
+            filter: format!(
+                "ST_DWithin(l.geog, {}, ${}::float8 * {})",
+                center, miles, METERS_PER_MILE
+            ),
+        });
+    }
+
+    let distance = format!(
+        "({radius} * 2 * ASIN(LEAST(1, SQRT(
+            POWER(SIN(RADIANS(l.lat - ${lat}::float8) / 2), 2)
+            + COS(RADIANS(${lat}::float8)) * COS(RADIANS(l.lat))
+              * POWER(SIN(RADIANS(l.long - ${long}::float8) / 2), 2)
+        ))))",
+        radius = EARTH_RADIUS_MILES,
+        lat = lat,
+        long = long,
+    );
+    Ok(RadiusSql {
+        filter: format!("{} <= ${}::float8", distance, miles),
+        distance,
+    })
+}
@@ -0,0 +1,240 @@
+#[cfg(feature = "ssr")]
+use super::entities::{GuestSpot, GuestSpotShop};
+#[cfg(feature = "ssr")]
+use chrono::NaiveDate;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Artists and the shops they're at: each artist's own shop, plus any shop
+/// they're guesting at today with the spot's last day as `guest_until`. Map
+/// search joins this where it would join on `artists.location_id`.
+#[cfg(feature = "ssr")]
+pub const ARTIST_PLACEMENTS: &str = "(
+        SELECT a.id AS artist_id, a.location_id, NULL::date AS guest_until
+        FROM artists a
+        UNION ALL
+        SELECT g.artist_id, g.location_id, g.end_date
+        FROM guest_spots g
+        WHERE CURRENT_DATE BETWEEN g.start_date AND g.end_date
+     )";
+
+#[cfg(feature = "ssr")]
+const GUEST_SPOT_SELECT: &str = "SELECT g.id, g.artist_id, a.name as artist_name, g.location_id,
+        l.name as shop_name, l.city, l.state, g.host_artist_id, h.name as host_artist_name,
+        TO_CHAR(g.start_date, 'YYYY-MM-DD') as start_date,
+        TO_CHAR(g.end_date, 'YYYY-MM-DD') as end_date, g.note
+     FROM guest_spots g
+     JOIN artists a ON a.id = g.artist_id
+     LEFT JOIN locations l ON l.id = g.location_id
+     LEFT JOIN artists h ON h.id = g.host_artist_id";
+
+#[cfg(feature = "ssr")]
+fn guest_spot_from_row(row: &PgRow) -> GuestSpot {
+    GuestSpot {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        location_id: row.get("location_id"),
+        shop_name: row.get("shop_name"),
+        city: row.get("city"),
+        state: row.get("state"),
+        host_artist_id: row.get("host_artist_id"),
+        host_artist_name: row.get("host_artist_name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        note: row.get("note"),
+    }
+}
+
+/// The artist's guest spots that haven't ended, soonest first
+#[cfg(feature = "ssr")]
+pub async fn get_upcoming_guest_spots(artist_id: i32) -> DbResult<Vec<GuestSpot>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE g.artist_id = $1 AND g.end_date >= CURRENT_DATE
+         ORDER BY g.start_date, g.id",
+        GUEST_SPOT_SELECT
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(guest_spot_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_guest_spot(guest_spot_id: i32) -> DbResult<Option<GuestSpot>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE g.id = $1", GUEST_SPOT_SELECT))
+        .bind(guest_spot_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(guest_spot_from_row))
+}
+
+/// Adds a guest spot, returning its id, or `None` when it overlaps one the
+/// artist already has
+#[cfg(feature = "ssr")]
+pub async fn create_guest_spot(
+    artist_id: i32,
+    location_id: i64,
+    host_artist_id: Option<i32>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    note: Option<&str>,
+) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    // Serialize an artist's inserts so two overlapping spots can't both pass
+    // the check
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(format!("guest_spot:{}", artist_id))
+        .execute(&mut *tx)
+        .await?;
+
+    let id = sqlx::query_scalar(
+        "INSERT INTO guest_spots (artist_id, location_id, host_artist_id, start_date, end_date, note)
+         SELECT $1, $2, $3, $4, $5, $6
+         WHERE NOT EXISTS (
+             SELECT 1 FROM guest_spots
+             WHERE artist_id = $1 AND start_date <= $5 AND end_date >= $4
+         )
+         RETURNING id",
+    )
+    .bind(artist_id)
+    .bind(location_id)
+    .bind(host_artist_id)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(note)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Removes one of the artist's guest spots. False if they have no such spot.
+#[cfg(feature = "ssr")]
+pub async fn delete_guest_spot(artist_id: i32, guest_spot_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM guest_spots WHERE id = $1 AND artist_id = $2")
+        .bind(guest_spot_id)
+        .bind(artist_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Ids and first and last days of the artist's guest spots that overlap
+/// `start` to `end`
+#[cfg(feature = "ssr")]
+pub async fn get_guest_spot_ranges(
+    artist_id: i32,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> DbResult<Vec<(i32, NaiveDate, NaiveDate)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, start_date, end_date
+         FROM guest_spots
+         WHERE artist_id = $1 AND start_date <= $3 AND end_date >= $2",
+    )
+    .bind(artist_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("id"), row.get("start_date"), row.get("end_date")))
+        .collect())
+}
+
+/// Whether the artist's own shop is `location_id`
+#[cfg(feature = "ssr")]
+pub async fn works_at(artist_id: i32, location_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM artists WHERE id = $1 AND location_id = $2)")
+        .bind(artist_id as i64)
+        .bind(location_id)
+        .fetch_one(pool)
+        .await
+}
+
+/// Shops whose name contains `query`, for picking a guest spot's shop
+#[cfg(feature = "ssr")]
+pub async fn search_shops(query: &str, limit: i64) -> DbResult<Vec<GuestSpotShop>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, name, city, state
+         FROM locations
+         WHERE name ILIKE '%' || $1 || '%'
+           AND (is_person IS NULL OR is_person = 0)
+         ORDER BY name, city
+         LIMIT $2",
+    )
+    .bind(query)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| GuestSpotShop {
+            location_id: row.get("id"),
+            name: row.get("name"),
+            city: row.get("city"),
+            state: row.get("state"),
+        })
+        .collect())
+}
+
+/// Whether `location_id` is a shop that exists
+#[cfg(feature = "ssr")]
+pub async fn shop_exists(location_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM locations
+             WHERE id = $1 AND (is_person IS NULL OR is_person = 0)
+         )",
+    )
+    .bind(location_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// The artist's sign-in email, or their profile email
+#[cfg(feature = "ssr")]
+pub async fn get_artist_email(artist_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let email: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT COALESCE(
+                    (SELECT u.email FROM users u
+                     WHERE u.artist_id = a.id AND u.role = 'artist' AND u.is_active = true
+                     ORDER BY u.id LIMIT 1),
+                    a.email
+                )
+         FROM artists a
+         WHERE a.id = $1",
+    )
+    .bind(artist_id as i64)
+    .fetch_optional(pool)
+    .await?;
+    Ok(email.flatten().filter(|email| !email.is_empty()))
+}
@@ -0,0 +1,95 @@
+#[cfg(feature = "ssr")]
+use crate::utils::hints::HintFacts;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What the artist has set up, in one round trip
+#[cfg(feature = "ssr")]
+pub async fn get_hint_facts(artist_id: i32) -> DbResult<HintFacts> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT
+            (SELECT COUNT(*) FROM business_hours bh
+             WHERE bh.artist_id = $1 AND bh.is_closed = false) as open_days,
+            (SELECT COUNT(*) FROM artist_availability av
+             WHERE av.artist_id = $1 AND av.is_available = true
+               AND av.specific_date >= to_char(CURRENT_DATE, 'YYYY-MM-DD')) as upcoming_open_dates,
+            (SELECT COUNT(*) FROM artists_images ai
+               WHERE ai.artist_id = $1 AND ai.removed_at IS NULL)
+              + (SELECT COUNT(*) FROM artist_uploaded_images ui WHERE ui.artist_id = $1)
+              as photo_count,
+            (EXISTS (SELECT 1 FROM artist_pricing p
+                     WHERE p.artist_id = $1
+                       AND (p.hourly_rate IS NOT NULL OR p.minimum_charge IS NOT NULL))
+             OR EXISTS (SELECT 1 FROM artist_style_pricing sp WHERE sp.artist_id = $1))
+              as has_pricing,
+            (SELECT COUNT(*) FROM artist_questionnaires aq
+             WHERE aq.artist_id = $1 AND aq.is_enabled = true) as question_count,
+            (SELECT COUNT(*) FROM booking_requests br WHERE br.artist_id = $1) as request_count,
+            (SELECT COUNT(*) FROM booking_requests br
+             WHERE br.artist_id = $1 AND br.status = 'pending') as pending_requests,
+            COALESCE((SELECT s.is_enabled FROM artist_auto_response_settings s
+                      WHERE s.artist_id = $1), false) as auto_reply_enabled",
+    )
+    .bind(artist_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(HintFacts {
+        artist_id,
+        open_days: row.get("open_days"),
+        upcoming_open_dates: row.get("upcoming_open_dates"),
+        photo_count: row.get("photo_count"),
+        has_pricing: row.get("has_pricing"),
+        question_count: row.get("question_count"),
+        request_count: row.get("request_count"),
+        pending_requests: row.get("pending_requests"),
+        auto_reply_enabled: row.get("auto_reply_enabled"),
+    })
+}
+
+/// Keys of the hints this user has dismissed on the artist's account
+#[cfg(feature = "ssr")]
+pub async fn get_dismissed(user_id: i64, artist_id: i32) -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT hint_key FROM dismissed_hints WHERE user_id = $1 AND artist_id = $2")
+        .bind(user_id)
+        .bind(artist_id)
+        .fetch_all(pool)
+        .await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn dismiss(user_id: i64, artist_id: i32, hint_key: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO dismissed_hints (user_id, artist_id, hint_key)
+         VALUES ($1, $2, $3)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(artist_id)
+    .bind(hint_key)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Brings back every hint the user dismissed on the artist's account
+#[cfg(feature = "ssr")]
+pub async fn restore_all(user_id: i64, artist_id: i32) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("DELETE FROM dismissed_hints WHERE user_id = $1 AND artist_id = $2")
+        .bind(user_id)
+        .bind(artist_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
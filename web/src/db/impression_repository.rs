@@ -0,0 +1,142 @@
+#[cfg(feature = "ssr")]
+use crate::db::entities::WeeklyImpressions;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// "City, ST" of the artist's location
+#[cfg(feature = "ssr")]
+pub async fn get_artist_city(artist_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT l.city || ', ' || l.state
+         FROM artists a
+         JOIN locations l ON l.id = a.location_id
+         WHERE a.id = $1 AND l.city IS NOT NULL AND l.state IS NOT NULL",
+    )
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Views and clicks per week for the last `weeks` weeks, oldest first,
+/// with the means over the artists in the same city. Weeks still held as
+/// raw events are counted from those, older ones from the weekly totals.
+#[cfg(feature = "ssr")]
+pub async fn get_weekly_impressions(
+    artist_id: i32,
+    weeks: i32,
+) -> DbResult<Vec<WeeklyImpressions>> {
+    let pool = crate::db::pool::get_pool();
+
+    // Every artist in the city counts towards its mean, including ones who
+    // weren't shown that week
+    let rows = sqlx::query(
+        "WITH city_artists AS (
+             SELECT a.id
+             FROM artists a
+             JOIN locations l ON l.id = a.location_id
+             JOIN artists me ON me.id = $1
+             JOIN locations ml ON ml.id = me.location_id
+             WHERE l.city = ml.city AND l.state = ml.state
+         ),
+         counts AS (
+             SELECT artist_id, date_trunc('week', logged_at)::date as week_start,
+                    COUNT(*) FILTER (WHERE impression_type = 'view') as views,
+                    COUNT(*) FILTER (WHERE impression_type = 'click') as clicks
+             FROM client_match_impressions
+             WHERE logged_at >= date_trunc('week', CURRENT_DATE) - make_interval(weeks => $2 - 1)
+             GROUP BY 1, 2
+             UNION ALL
+             SELECT artist_id, week_start, views, clicks
+             FROM artist_impression_weeks
+             WHERE week_start >= (date_trunc('week', CURRENT_DATE) - make_interval(weeks => $2 - 1))::date
+         )
+         SELECT TO_CHAR(w.week_start, 'YYYY-MM-DD') as week_start,
+                COALESCE(SUM(c.views) FILTER (WHERE c.artist_id = $1), 0)::bigint as views,
+                COALESCE(SUM(c.clicks) FILTER (WHERE c.artist_id = $1), 0)::bigint as clicks,
+                COALESCE(SUM(c.views) FILTER (WHERE c.artist_id IN (SELECT id FROM city_artists)), 0)::float8
+                    / GREATEST((SELECT COUNT(*) FROM city_artists), 1) as city_views,
+                COALESCE(SUM(c.clicks) FILTER (WHERE c.artist_id IN (SELECT id FROM city_artists)), 0)::float8
+                    / GREATEST((SELECT COUNT(*) FROM city_artists), 1) as city_clicks
+         FROM generate_series(
+                  date_trunc('week', CURRENT_DATE) - make_interval(weeks => $2 - 1),
+                  date_trunc('week', CURRENT_DATE),
+                  interval '1 week'
+              ) AS w(week_start)
+         LEFT JOIN counts c ON c.week_start = w.week_start::date
+         GROUP BY w.week_start
+         ORDER BY w.week_start",
+    )
+    .bind(artist_id)
+    .bind(weeks)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| WeeklyImpressions {
+            week_start: row.get("week_start"),
+            views: row.get("views"),
+            clicks: row.get("clicks"),
+            city_views: row.get("city_views"),
+            city_clicks: row.get("city_clicks"),
+        })
+        .collect())
+}
+
+/// Folds raw impressions from weeks that ended more than `retention_days`
+/// ago into the weekly totals and deletes them, returning how many were
+/// folded. Whole weeks move at once so none is split across the tables.
+#[cfg(feature = "ssr")]
+pub async fn roll_up_impressions(retention_days: i32) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO artist_impression_weeks (artist_id, week_start, views, clicks)
+         SELECT artist_id, date_trunc('week', logged_at)::date,
+                COUNT(*) FILTER (WHERE impression_type = 'view'),
+                COUNT(*) FILTER (WHERE impression_type = 'click')
+         FROM client_match_impressions
+         WHERE logged_at < date_trunc('week', CURRENT_DATE - $1)
+         GROUP BY 1, 2
+         ON CONFLICT (artist_id, week_start) DO UPDATE
+         SET views = artist_impression_weeks.views + EXCLUDED.views,
+             clicks = artist_impression_weeks.clicks + EXCLUDED.clicks",
+    )
+    .bind(retention_days)
+    .execute(&mut *tx)
+    .await?;
+
+    let folded = sqlx::query(
+        "DELETE FROM client_match_impressions
+         WHERE logged_at < date_trunc('week', CURRENT_DATE - $1)",
+    )
+    .bind(retention_days)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    tx.commit().await?;
+    Ok(folded)
+}
+
+/// Deletes weekly totals older than `weeks` weeks, returning how many
+#[cfg(feature = "ssr")]
+pub async fn delete_old_weeks(weeks: i32) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "DELETE FROM artist_impression_weeks
+         WHERE week_start < (date_trunc('week', CURRENT_DATE) - make_interval(weeks => $1))::date",
+    )
+    .bind(weeks)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,122 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Daily external API spend per source and city over the last `days` days
+#[cfg(feature = "ssr")]
+pub async fn get_ingestion_cost_summary(
+    days: i32,
+) -> DbResult<Vec<crate::server::IngestionCostSummary>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT
+            TO_CHAR(DATE_TRUNC('day', created_at), 'YYYY-MM-DD') as day,
+            source,
+            city,
+            state,
+            SUM(api_calls)::BIGINT as api_calls,
+            SUM(compute_units) as compute_units,
+            SUM(prompt_tokens + completion_tokens)::BIGINT as tokens,
+            SUM(cost_usd) as cost_usd
+         FROM ingestion_costs
+         WHERE created_at >= CURRENT_TIMESTAMP - make_interval(days => $1)
+         GROUP BY DATE_TRUNC('day', created_at), source, city, state
+         ORDER BY DATE_TRUNC('day', created_at) DESC, cost_usd DESC",
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| crate::server::IngestionCostSummary {
+            day: row.get("day"),
+            source: row.get("source"),
+            city: row.get("city"),
+            state: row.get("state"),
+            api_calls: row.get("api_calls"),
+            compute_units: row.get("compute_units"),
+            tokens: row.get("tokens"),
+            cost_usd: row.get("cost_usd"),
+        })
+        .collect())
+}
+
+/// Most recent ingestion runs with their total spend and budget status
+#[cfg(feature = "ssr")]
+pub async fn get_recent_ingestion_runs(
+    limit: i64,
+) -> DbResult<Vec<crate::server::IngestionRunSummary>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT run_id, action,
+                TO_CHAR(started_at, 'YYYY-MM-DD HH24:MI') as started_at,
+                budget_usd, city_budget_usd, total_cost_usd, api_calls,
+                over_budget, aborted_locations
+         FROM ingestion_runs
+         ORDER BY started_at DESC
+         LIMIT $1",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| crate::server::IngestionRunSummary {
+            run_id: row.get("run_id"),
+            action: row.get("action"),
+            started_at: row.get("started_at"),
+            budget_usd: row.get("budget_usd"),
+            city_budget_usd: row.get("city_budget_usd"),
+            total_cost_usd: row.get("total_cost_usd"),
+            api_calls: row.get("api_calls"),
+            over_budget: row.get("over_budget"),
+            aborted_locations: row.get("aborted_locations"),
+        })
+        .collect())
+}
+
+/// What one ingestion action's recorded runs got through, in total
+#[cfg(feature = "ssr")]
+pub struct IngestionThroughput {
+    pub action: String,
+    pub runs: i64,
+    pub posts_processed: i64,
+    pub artists_added: i64,
+    pub api_errors: i64,
+}
+
+/// Totals per ingestion action over every recorded run, for `/metrics`
+#[cfg(feature = "ssr")]
+pub async fn get_ingestion_throughput() -> DbResult<Vec<IngestionThroughput>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT action,
+                COUNT(*) as runs,
+                SUM(posts_processed)::BIGINT as posts_processed,
+                SUM(artists_added)::BIGINT as artists_added,
+                SUM(api_errors)::BIGINT as api_errors
+         FROM ingestion_runs
+         GROUP BY action
+         ORDER BY action",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| IngestionThroughput {
+            action: row.get("action"),
+            runs: row.get("runs"),
+            posts_processed: row.get("posts_processed"),
+            artists_added: row.get("artists_added"),
+            api_errors: row.get("api_errors"),
+        })
+        .collect())
+}
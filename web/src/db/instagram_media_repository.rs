@@ -0,0 +1,238 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What media ingestion saved about a post
+#[cfg(feature = "ssr")]
+pub struct InstagramMedia {
+    pub caption: Option<String>,
+    pub has_thumbnail: bool,
+    pub thumbnail_width: Option<i32>,
+    pub thumbnail_height: Option<i32>,
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_media(short_code: &str) -> DbResult<Option<InstagramMedia>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT caption, thumbnail IS NOT NULL as has_thumbnail, thumbnail_width, thumbnail_height
+         FROM instagram_media
+         WHERE short_code = $1",
+    )
+    .bind(short_code)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| InstagramMedia {
+        caption: row.get("caption"),
+        has_thumbnail: row.get("has_thumbnail"),
+        thumbnail_width: row.get("thumbnail_width"),
+        thumbnail_height: row.get("thumbnail_height"),
+    }))
+}
+
+/// The saved JPEG thumbnail of a post
+#[cfg(feature = "ssr")]
+pub async fn get_thumbnail(short_code: &str) -> DbResult<Option<Vec<u8>>> {
+    let pool = crate::db::pool::get_pool();
+
+    let thumbnail: Option<Option<Vec<u8>>> =
+        sqlx::query_scalar("SELECT thumbnail FROM instagram_media WHERE short_code = $1")
+            .bind(short_code)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(thumbnail.flatten())
+}
+
+/// A post's cached oEmbed markup
+#[cfg(feature = "ssr")]
+pub struct CachedEmbed {
+    pub html: String,
+    /// Seconds since it was fetched
+    pub age_secs: f64,
+    /// Seconds since `last_served_at` was last updated
+    pub served_age_secs: f64,
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_cached_embed(short_code: &str) -> DbResult<Option<CachedEmbed>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT html,
+                EXTRACT(EPOCH FROM (CURRENT_TIMESTAMP - fetched_at))::float8 as age_secs,
+                EXTRACT(EPOCH FROM (CURRENT_TIMESTAMP - last_served_at))::float8 as served_age_secs
+         FROM instagram_oembed_cache
+         WHERE short_code = $1",
+    )
+    .bind(short_code)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| CachedEmbed {
+        html: row.get("html"),
+        age_secs: row.get("age_secs"),
+        served_age_secs: row.get("served_age_secs"),
+    }))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn save_cached_embed(short_code: &str, html: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO instagram_oembed_cache (short_code, html)
+         VALUES ($1, $2)
+         ON CONFLICT (short_code) DO UPDATE SET
+            html = EXCLUDED.html,
+            fetched_at = CURRENT_TIMESTAMP",
+    )
+    .bind(short_code)
+    .bind(html)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Marks a cached embed as just served
+#[cfg(feature = "ssr")]
+pub async fn touch_cached_embed(short_code: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE instagram_oembed_cache SET last_served_at = CURRENT_TIMESTAMP
+         WHERE short_code = $1",
+    )
+    .bind(short_code)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Short codes of embeds fetched over `stale_secs` ago and served within
+/// `served_within_secs`, oldest first
+#[cfg(feature = "ssr")]
+pub async fn get_embeds_to_refresh(
+    stale_secs: i64,
+    served_within_secs: i64,
+    limit: i64,
+) -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT short_code FROM instagram_oembed_cache
+         WHERE fetched_at <= CURRENT_TIMESTAMP - make_interval(secs => $1)
+           AND last_served_at > CURRENT_TIMESTAMP - make_interval(secs => $2)
+         ORDER BY fetched_at
+         LIMIT $3",
+    )
+    .bind(stale_secs as f64)
+    .bind(served_within_secs as f64)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Deletes embeds not served within `served_within_secs`, returning how many
+#[cfg(feature = "ssr")]
+pub async fn delete_unserved_embeds(served_within_secs: i64) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "DELETE FROM instagram_oembed_cache
+         WHERE last_served_at <= CURRENT_TIMESTAMP - make_interval(secs => $1)",
+    )
+    .bind(served_within_secs as f64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn delete_cached_embed(short_code: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("DELETE FROM instagram_oembed_cache WHERE short_code = $1")
+        .bind(short_code)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// A post's stored gallery image
+#[cfg(feature = "ssr")]
+pub struct StoredInstagramImage {
+    /// Widths stored; empty when no image could be found
+    pub widths: Vec<i32>,
+    /// Seconds since the image was stored or found missing
+    pub checked_age_secs: f64,
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_instagram_image(short_code: &str) -> DbResult<Option<StoredInstagramImage>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT widths,
+                EXTRACT(EPOCH FROM (CURRENT_TIMESTAMP - checked_at))::float8 as checked_age_secs
+         FROM instagram_images
+         WHERE short_code = $1",
+    )
+    .bind(short_code)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| StoredInstagramImage {
+        widths: row.get("widths"),
+        checked_age_secs: row.get("checked_age_secs"),
+    }))
+}
+
+/// Records a post's stored widths, or with none, that its image couldn't be
+/// found
+#[cfg(feature = "ssr")]
+pub async fn save_instagram_image(
+    short_code: &str,
+    widths: &[i32],
+    source: Option<&str>,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO instagram_images (short_code, widths, source)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (short_code) DO UPDATE SET
+            widths = EXCLUDED.widths,
+            source = EXCLUDED.source,
+            checked_at = CURRENT_TIMESTAMP",
+    )
+    .bind(short_code)
+    .bind(widths)
+    .bind(source)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Whether the post is in an artist's portfolio, or has media saved for it
+#[cfg(feature = "ssr")]
+pub async fn is_known_post(short_code: &str) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM artists_images WHERE short_code = $1)
+             OR EXISTS (SELECT 1 FROM instagram_media WHERE short_code = $1)",
+    )
+    .bind(short_code)
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,313 @@
+#[cfg(feature = "ssr")]
+use super::entities::{BillingSettings, Invoice};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+const INVOICE_COLUMNS: &str = "
+    i.id, i.artist_id, i.booking_request_id, i.invoice_number, i.invoice_code,
+    i.client_name, i.client_email, i.description, i.business_name, i.business_address,
+    i.tax_id, i.tax_label, i.tax_rate, i.subtotal, i.tax_amount, i.total, i.currency,
+    TO_CHAR(i.issued_at, 'YYYY-MM-DD') as issued_at,
+    COALESCE((
+        SELECT SUM(CASE WHEN pl.entry_type = 'payment' THEN pl.amount ELSE -pl.amount END)
+        FROM payment_ledger pl
+        WHERE pl.booking_request_id = i.booking_request_id
+          AND pl.entry_type IN ('payment', 'refund')
+    ), 0) as amount_paid";
+
+#[cfg(feature = "ssr")]
+fn invoice_from_row(row: &PgRow) -> Invoice {
+    Invoice {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        booking_request_id: row.get("booking_request_id"),
+        invoice_number: row.get("invoice_number"),
+        invoice_code: row.get("invoice_code"),
+        client_name: row.get("client_name"),
+        client_email: row.get("client_email"),
+        description: row.get("description"),
+        business_name: row.get("business_name"),
+        business_address: row.get("business_address"),
+        tax_id: row.get("tax_id"),
+        tax_label: row.get("tax_label"),
+        tax_rate: row.get("tax_rate"),
+        subtotal: row.get("subtotal"),
+        tax_amount: row.get("tax_amount"),
+        total: row.get("total"),
+        currency: row.get("currency"),
+        issued_at: row.get("issued_at"),
+        amount_paid: row.get("amount_paid"),
+    }
+}
+
+/// Splits a quoted price into (subtotal, tax, total), treating the price as
+/// tax-inclusive when the artist's region quotes prices that way (e.g. VAT).
+pub fn apply_tax(price: f64, tax_rate: f64, tax_inclusive: bool) -> (f64, f64, f64) {
+    let round = |v: f64| (v * 100.0).round() / 100.0;
+    if tax_inclusive {
+        let subtotal = round(price / (1.0 + tax_rate / 100.0));
+        (subtotal, round(price - subtotal), round(price))
+    } else {
+        let tax = round(price * tax_rate / 100.0);
+        (round(price), tax, round(price + tax))
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_billing_settings(artist_id: i32) -> DbResult<BillingSettings> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT artist_id, business_name, business_address, tax_id, tax_label,
+                tax_rate, tax_inclusive, currency, invoice_prefix
+         FROM artist_billing_settings
+         WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match row {
+        Some(row) => BillingSettings {
+            artist_id: row.get("artist_id"),
+            business_name: row.get("business_name"),
+            business_address: row.get("business_address"),
+            tax_id: row.get("tax_id"),
+            tax_label: row.get("tax_label"),
+            tax_rate: row.get("tax_rate"),
+            tax_inclusive: row.get("tax_inclusive"),
+            currency: row.get("currency"),
+            invoice_prefix: row.get("invoice_prefix"),
+        },
+        None => BillingSettings::defaults_for(artist_id),
+    })
+}
+
+#[cfg(feature = "ssr")]
+pub async fn upsert_billing_settings(settings: &BillingSettings) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO artist_billing_settings
+         (artist_id, business_name, business_address, tax_id, tax_label,
+          tax_rate, tax_inclusive, currency, invoice_prefix)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (artist_id) DO UPDATE SET
+            business_name = EXCLUDED.business_name,
+            business_address = EXCLUDED.business_address,
+            tax_id = EXCLUDED.tax_id,
+            tax_label = EXCLUDED.tax_label,
+            tax_rate = EXCLUDED.tax_rate,
+            tax_inclusive = EXCLUDED.tax_inclusive,
+            currency = EXCLUDED.currency,
+            invoice_prefix = EXCLUDED.invoice_prefix,
+            updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(settings.artist_id)
+    .bind(&settings.business_name)
+    .bind(&settings.business_address)
+    .bind(&settings.tax_id)
+    .bind(&settings.tax_label)
+    .bind(settings.tax_rate)
+    .bind(settings.tax_inclusive)
+    .bind(&settings.currency)
+    .bind(&settings.invoice_prefix)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Issues the invoice for a completed booking, or returns the existing one.
+///
+/// Numbers are allocated from the artist's billing settings row under a row
+/// lock, so each artist gets a gap-free sequence. The invoice total is posted
+/// to the payments ledger as a charge in the same transaction.
+#[cfg(feature = "ssr")]
+pub async fn create_invoice_for_booking(
+    artist_id: i32,
+    booking_request_id: i32,
+) -> DbResult<Option<Invoice>> {
+    let pool = crate::db::pool::get_pool();
+
+    if let Some(existing) = get_invoice_by_booking(booking_request_id).await? {
+        return Ok((existing.artist_id == artist_id).then_some(existing));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let Some(booking) = sqlx::query(
+        "SELECT client_name, client_email, tattoo_description, placement,
+                requested_date, estimated_price
+         FROM booking_requests
+         WHERE id = $1 AND artist_id = $2 AND status = 'completed'",
+    )
+    .bind(booking_request_id)
+    .bind(artist_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let Some(price) = booking.get::<Option<f64>, _>("estimated_price") else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "INSERT INTO artist_billing_settings (artist_id) VALUES ($1)
+         ON CONFLICT (artist_id) DO NOTHING",
+    )
+    .bind(artist_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let settings = sqlx::query(
+        "SELECT business_name, business_address, tax_id, tax_label, tax_rate,
+                tax_inclusive, currency, invoice_prefix, next_invoice_number
+         FROM artist_billing_settings
+         WHERE artist_id = $1
+         FOR UPDATE",
+    )
+    .bind(artist_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let invoice_number: i32 = settings.get("next_invoice_number");
+    let prefix: String = settings.get("invoice_prefix");
+    let invoice_code = format!("{}-{}-{:04}", prefix, artist_id, invoice_number);
+    let tax_rate: f64 = settings.get("tax_rate");
+    let (subtotal, tax_amount, total) = apply_tax(price, tax_rate, settings.get("tax_inclusive"));
+    let currency: String = settings.get("currency");
+
+    let description = {
+        let requested_date: String = booking.get("requested_date");
+        let mut parts = vec![format!("Tattoo session on {}", requested_date)];
+        if let Some(placement) = booking.get::<Option<String>, _>("placement") {
+            parts.push(format!("placement: {}", placement));
+        }
+        if let Some(desc) = booking.get::<Option<String>, _>("tattoo_description") {
+            parts.push(desc);
+        }
+        parts.join(" - ")
+    };
+
+    let invoice_id: i64 = sqlx::query(
+        "INSERT INTO invoices
+         (artist_id, booking_request_id, invoice_number, invoice_code, client_name,
+          client_email, description, business_name, business_address, tax_id,
+          tax_label, tax_rate, subtotal, tax_amount, total, currency)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+         RETURNING id",
+    )
+    .bind(artist_id)
+    .bind(booking_request_id)
+    .bind(invoice_number)
+    .bind(&invoice_code)
+    .bind(booking.get::<String, _>("client_name"))
+    .bind(booking.get::<String, _>("client_email"))
+    .bind(&description)
+    .bind(settings.get::<Option<String>, _>("business_name"))
+    .bind(settings.get::<Option<String>, _>("business_address"))
+    .bind(settings.get::<Option<String>, _>("tax_id"))
+    .bind(settings.get::<String, _>("tax_label"))
+    .bind(tax_rate)
+    .bind(subtotal)
+    .bind(tax_amount)
+    .bind(total)
+    .bind(&currency)
+    .fetch_one(&mut *tx)
+    .await?
+    .get("id");
+
+    sqlx::query(
+        "UPDATE artist_billing_settings
+         SET next_invoice_number = next_invoice_number + 1
+         WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO payment_ledger
+         (artist_id, booking_request_id, invoice_id, entry_type, amount, currency, description)
+         VALUES ($1, $2, $3, 'charge', $4, $5, $6)",
+    )
+    .bind(artist_id)
+    .bind(booking_request_id)
+    .bind(invoice_id)
+    .bind(total)
+    .bind(&currency)
+    .bind(format!("Invoice {}", invoice_code))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    get_invoice(invoice_id).await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_invoice(invoice_id: i64) -> DbResult<Option<Invoice>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM invoices i WHERE i.id = $1",
+        INVOICE_COLUMNS
+    ))
+    .bind(invoice_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(invoice_from_row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_invoice_by_booking(booking_request_id: i32) -> DbResult<Option<Invoice>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM invoices i WHERE i.booking_request_id = $1",
+        INVOICE_COLUMNS
+    ))
+    .bind(booking_request_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(invoice_from_row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_invoices_for_artist(artist_id: i32) -> DbResult<Vec<Invoice>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM invoices i WHERE i.artist_id = $1 ORDER BY i.invoice_number DESC",
+        INVOICE_COLUMNS
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(invoice_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_invoices_for_client(client_email: &str) -> DbResult<Vec<Invoice>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM invoices i WHERE LOWER(i.client_email) = LOWER($1) ORDER BY i.issued_at DESC",
+        INVOICE_COLUMNS
+    ))
+    .bind(client_email)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(invoice_from_row).collect())
+}
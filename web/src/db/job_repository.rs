@@ -0,0 +1,238 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A claimed `background_jobs` row from the web server's queue
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug)]
+pub struct StoredJob {
+    pub id: i64,
+    pub kind: String,
+    /// JSON
+    pub payload: String,
+    /// Including the run it was just claimed for
+    pub attempts: i32,
+    pub max_attempts: i32,
+}
+
+/// Queues a job to run at `run_at`, or now. Returns false when a job with
+/// the same `dedupe_key` is already queued or running.
+#[cfg(feature = "ssr")]
+pub async fn enqueue(
+    kind: &str,
+    payload: &str,
+    dedupe_key: Option<&str>,
+    max_attempts: i32,
+    run_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "INSERT INTO background_jobs (kind, payload, dedupe_key, max_attempts, run_at)
+         VALUES ($1, $2::jsonb, $3, $4, COALESCE($5, CURRENT_TIMESTAMP))
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(kind)
+    .bind(payload)
+    .bind(dedupe_key)
+    .bind(max_attempts)
+    .bind(run_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes queued jobs of `kind` whose dedupe key starts with `key_prefix`,
+/// returning how many. Jobs already running are left to finish.
+#[cfg(feature = "ssr")]
+pub async fn cancel_queued(kind: &str, key_prefix: &str) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "DELETE FROM background_jobs
+         WHERE kind = $1 AND status = 'queued' AND starts_with(dedupe_key, $2)",
+    )
+    .bind(kind)
+    .bind(key_prefix)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Marks up to `limit` due jobs as running, counting the attempt, and
+/// returns them, longest due first. Jobs another server already claimed are
+/// skipped.
+#[cfg(feature = "ssr")]
+pub async fn claim_due(limit: i64) -> DbResult<Vec<StoredJob>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "WITH due AS (
+             SELECT id AS due_id FROM background_jobs
+             WHERE queue = 'web' AND status = 'queued' AND run_at <= CURRENT_TIMESTAMP
+             ORDER BY run_at
+             LIMIT $1
+             FOR UPDATE SKIP LOCKED
+         )
+         UPDATE background_jobs
+         SET status = 'running', attempts = attempts + 1, started_at = CURRENT_TIMESTAMP
+         FROM due
+         WHERE id = due_id
+         RETURNING id, kind, payload::text as payload, attempts, max_attempts",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| StoredJob {
+            id: row.get("id"),
+            kind: row.get("kind"),
+            payload: row.get("payload"),
+            attempts: row.get("attempts"),
+            max_attempts: row.get("max_attempts"),
+        })
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn complete_job(id: i64) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE background_jobs
+         SET status = 'completed', last_error = NULL, finished_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Puts a failed job back in the queue to run again in `delay_secs`
+#[cfg(feature = "ssr")]
+pub async fn retry_job(id: i64, error: &str, delay_secs: i64) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE background_jobs
+         SET status = 'queued', last_error = $2,
+             run_at = CURRENT_TIMESTAMP + make_interval(secs => $3)
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(error)
+    .bind(delay_secs as f64)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Gives up on a job, leaving it for an operator to look into
+#[cfg(feature = "ssr")]
+pub async fn dead_letter_job(id: i64, error: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE background_jobs
+         SET status = 'dead', last_error = $2, finished_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Queues jobs left running longer than `max_minutes` (cut off by a
+/// restart) to run again. The interrupted run counts as an attempt.
+#[cfg(feature = "ssr")]
+pub async fn requeue_stalled_jobs(max_minutes: i32) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE background_jobs
+         SET status = CASE WHEN attempts >= max_attempts THEN 'dead' ELSE 'queued' END,
+             last_error = 'Interrupted',
+             run_at = CURRENT_TIMESTAMP,
+             finished_at = CASE WHEN attempts >= max_attempts THEN CURRENT_TIMESTAMP END
+         WHERE queue = 'web' AND status = 'running'
+           AND started_at < CURRENT_TIMESTAMP - make_interval(mins => $1)",
+    )
+    .bind(max_minutes)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Deletes completed jobs that finished more than `days` days ago. Dead
+/// jobs are kept, and so is ingestion's history, which its city request
+/// cooldown reads.
+#[cfg(feature = "ssr")]
+pub async fn delete_completed_jobs(days: i32) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "DELETE FROM background_jobs
+         WHERE queue = 'web' AND status = 'completed'
+           AND finished_at < CURRENT_TIMESTAMP - make_interval(days => $1)",
+    )
+    .bind(days)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Claims the next turn of the scheduled job `kind` if it's due, pushing
+/// the turn after it `every_secs` out. Only one server gets each turn.
+#[cfg(feature = "ssr")]
+pub async fn claim_schedule(kind: &str, every_secs: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let claimed = sqlx::query(
+        "INSERT INTO job_schedules (kind, next_run_at)
+         VALUES ($1, CURRENT_TIMESTAMP + make_interval(secs => $2))
+         ON CONFLICT (kind) DO UPDATE SET next_run_at = EXCLUDED.next_run_at
+         WHERE job_schedules.next_run_at <= CURRENT_TIMESTAMP
+         RETURNING kind",
+    )
+    .bind(kind)
+    .bind(every_secs as f64)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(claimed.is_some())
+}
+
+/// How many of the web queue's jobs are in each status, for `/metrics`
+#[cfg(feature = "ssr")]
+pub async fn count_jobs_by_status() -> DbResult<Vec<(String, i64)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT status, COUNT(*) as jobs
+         FROM background_jobs
+         WHERE queue = 'web'
+         GROUP BY status
+         ORDER BY status",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("status"), row.get("jobs")))
+        .collect())
+}
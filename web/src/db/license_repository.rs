@@ -0,0 +1,319 @@
+#[cfg(feature = "ssr")]
+use super::entities::{ArtistLicense, ProfileLicense};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A license whose expiry reminder may be due
+#[cfg(feature = "ssr")]
+pub struct ExpiringLicense {
+    pub id: i32,
+    pub artist_id: i32,
+    pub license_number: String,
+    pub issuing_state: String,
+    pub expires_on: String,
+    pub days_left: i32,
+    pub reminder_days_sent: Option<i32>,
+    /// The artist's sign-in email, or their profile email
+    pub email: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+const LICENSE_SELECT: &str = "SELECT l.id, l.artist_id, a.name as artist_name, l.license_number,
+        l.issuing_state, TO_CHAR(l.expires_on, 'YYYY-MM-DD') as expires_on, l.document_key,
+        l.verification_status, l.rejection_reason,
+        EXISTS (SELECT 1 FROM license_display_states d WHERE d.state = l.issuing_state)
+            as display_required,
+        TO_CHAR(l.updated_at, 'YYYY-MM-DD HH24:MI') as updated_at
+     FROM artist_licenses l
+     JOIN artists a ON a.id = l.artist_id";
+
+#[cfg(feature = "ssr")]
+fn license_from_row(row: &PgRow) -> ArtistLicense {
+    let document_key: Option<String> = row.get("document_key");
+
+    ArtistLicense {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        license_number: row.get("license_number"),
+        issuing_state: row.get("issuing_state"),
+        expires_on: row.get("expires_on"),
+        document_url: document_key.map(|_| crate::licensing::document_link(row.get("id"))),
+        verification_status: row.get("verification_status"),
+        rejection_reason: row.get("rejection_reason"),
+        display_required: row.get("display_required"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_licenses(artist_id: i32) -> DbResult<Vec<ArtistLicense>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE l.artist_id = $1 ORDER BY l.issuing_state",
+        LICENSE_SELECT
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(license_from_row).collect())
+}
+
+/// Adds the artist's license for `issuing_state`, or updates the one they
+/// have. A changed number or expiry date needs checking again, so it goes
+/// back to pending and its reminders start over.
+#[cfg(feature = "ssr")]
+pub async fn save_license(
+    artist_id: i32,
+    license_number: &str,
+    issuing_state: &str,
+    expires_on: Option<chrono::NaiveDate>,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO artist_licenses (artist_id, license_number, issuing_state, expires_on)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (artist_id, issuing_state) DO UPDATE SET
+            license_number = EXCLUDED.license_number,
+            expires_on = EXCLUDED.expires_on,
+            verification_status = 'pending',
+            rejection_reason = NULL,
+            reviewed_by = NULL,
+            reviewed_at = NULL,
+            reminder_days_sent = NULL,
+            updated_at = CURRENT_TIMESTAMP
+         WHERE (artist_licenses.license_number, artist_licenses.expires_on)
+            IS DISTINCT FROM (EXCLUDED.license_number, EXCLUDED.expires_on)",
+    )
+    .bind(artist_id)
+    .bind(license_number)
+    .bind(issuing_state)
+    .bind(expires_on)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Attaches a new document to the artist's license, sending it back for
+/// review. `None` if the license isn't theirs; otherwise the document it
+/// replaced, if any, for the caller to delete.
+#[cfg(feature = "ssr")]
+pub async fn set_document(
+    artist_id: i32,
+    license_id: i32,
+    document_key: &str,
+) -> DbResult<Option<Option<String>>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "UPDATE artist_licenses l
+         SET document_key = $3, verification_status = 'pending', rejection_reason = NULL,
+             reviewed_by = NULL, reviewed_at = NULL, updated_at = CURRENT_TIMESTAMP
+         FROM (SELECT id, document_key as previous_key
+               FROM artist_licenses
+               WHERE id = $1 AND artist_id = $2
+               FOR UPDATE) previous
+         WHERE l.id = previous.id
+         RETURNING previous.previous_key",
+    )
+    .bind(license_id)
+    .bind(artist_id)
+    .bind(document_key)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The license's document, if it has one
+#[cfg(feature = "ssr")]
+pub async fn get_document_key(license_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    Ok(
+        sqlx::query_scalar("SELECT document_key FROM artist_licenses WHERE id = $1")
+            .bind(license_id)
+            .fetch_optional(pool)
+            .await?
+            .flatten(),
+    )
+}
+
+/// Deletes the artist's license. `None` if it isn't theirs; otherwise its
+/// document, if any, for the caller to delete.
+#[cfg(feature = "ssr")]
+pub async fn delete_license(artist_id: i32, license_id: i32) -> DbResult<Option<Option<String>>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "DELETE FROM artist_licenses WHERE id = $1 AND artist_id = $2 RETURNING document_key",
+    )
+    .bind(license_id)
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Licenses with the given status for admins, longest waiting first
+#[cfg(feature = "ssr")]
+pub async fn get_licenses_by_status(status: &str, limit: i64) -> DbResult<Vec<ArtistLicense>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE l.verification_status = $1 ORDER BY l.updated_at ASC LIMIT $2",
+        LICENSE_SELECT
+    ))
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(license_from_row).collect())
+}
+
+/// Records an admin's decision. False if there's no such license.
+#[cfg(feature = "ssr")]
+pub async fn review_license(
+    license_id: i32,
+    approve: bool,
+    rejection_reason: Option<&str>,
+    reviewed_by: i64,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_licenses
+         SET verification_status = CASE WHEN $2 THEN 'verified' ELSE 'rejected' END,
+             rejection_reason = CASE WHEN $2 THEN NULL ELSE $3 END,
+             reviewed_by = $4, reviewed_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(license_id)
+    .bind(approve)
+    .bind(rejection_reason)
+    .bind(reviewed_by)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// The artist's verified, unexpired licenses from states that require them
+/// to be shown
+#[cfg(feature = "ssr")]
+pub async fn get_profile_licenses(artist_id: i32) -> DbResult<Vec<ProfileLicense>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT l.license_number, l.issuing_state,
+                TO_CHAR(l.expires_on, 'YYYY-MM-DD') as expires_on
+         FROM artist_licenses l
+         JOIN license_display_states d ON d.state = l.issuing_state
+         WHERE l.artist_id = $1
+           AND l.verification_status = 'verified'
+           AND (l.expires_on IS NULL OR l.expires_on >= CURRENT_DATE)
+         ORDER BY l.issuing_state",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ProfileLicense {
+            license_number: row.get("license_number"),
+            issuing_state: row.get("issuing_state"),
+            expires_on: row.get("expires_on"),
+        })
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_display_states() -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT state FROM license_display_states ORDER BY state")
+        .fetch_all(pool)
+        .await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_display_state(state: &str, required: bool) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    let query = if required {
+        "INSERT INTO license_display_states (state) VALUES ($1) ON CONFLICT DO NOTHING"
+    } else {
+        "DELETE FROM license_display_states WHERE state = $1"
+    };
+    sqlx::query(query).bind(state).execute(pool).await?;
+
+    Ok(())
+}
+
+/// Licenses expiring within `within_days` (or already expired) that haven't
+/// had their last reminder yet. Rejected licenses get none.
+#[cfg(feature = "ssr")]
+pub async fn get_expiring_licenses(within_days: i32, limit: i64) -> DbResult<Vec<ExpiringLicense>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT l.id, l.artist_id, l.license_number, l.issuing_state,
+                TO_CHAR(l.expires_on, 'YYYY-MM-DD') as expires_on,
+                (l.expires_on - CURRENT_DATE) as days_left, l.reminder_days_sent,
+                COALESCE(
+                    (SELECT u.email FROM users u
+                     WHERE u.artist_id = l.artist_id AND u.role = 'artist' AND u.is_active = true
+                     ORDER BY u.id LIMIT 1),
+                    a.email
+                ) as email
+         FROM artist_licenses l
+         JOIN artists a ON a.id = l.artist_id
+         WHERE l.expires_on <= CURRENT_DATE + $1
+           AND l.verification_status <> 'rejected'
+           AND (l.reminder_days_sent IS NULL OR l.reminder_days_sent > 0)
+         ORDER BY l.expires_on
+         LIMIT $2",
+    )
+    .bind(within_days)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ExpiringLicense {
+            id: row.get("id"),
+            artist_id: row.get("artist_id"),
+            license_number: row.get("license_number"),
+            issuing_state: row.get("issuing_state"),
+            expires_on: row.get("expires_on"),
+            days_left: row.get("days_left"),
+            reminder_days_sent: row.get("reminder_days_sent"),
+            email: row.get("email"),
+        })
+        .collect())
+}
+
+/// Claims the reminder `days` before expiry for sending. False if it, or a
+/// later one, has been sent already.
+#[cfg(feature = "ssr")]
+pub async fn mark_reminder_sent(license_id: i32, days: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_licenses SET reminder_days_sent = $2
+         WHERE id = $1 AND (reminder_days_sent IS NULL OR reminder_days_sent > $2)",
+    )
+    .bind(license_id)
+    .bind(days)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
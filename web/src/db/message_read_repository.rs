@@ -0,0 +1,65 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Join and column for whether the other side of the thread has read a
+/// message, for queries over `booking_messages bm`
+#[cfg(feature = "ssr")]
+pub const READ_JOIN: &str = "LEFT JOIN message_reads mr
+             ON mr.booking_request_id = bm.booking_request_id
+            AND mr.reader = CASE bm.sender_type WHEN 'client' THEN 'artist' ELSE 'client' END";
+
+#[cfg(feature = "ssr")]
+pub const READ_COLUMN: &str = "COALESCE(bm.id <= mr.last_read_message_id, false) as is_read";
+
+/// Marks everything in the booking's thread as read by `reader` ('artist' or
+/// 'client')
+#[cfg(feature = "ssr")]
+pub async fn mark_thread_read(booking_id: i32, reader: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO message_reads (booking_request_id, reader, last_read_message_id)
+         SELECT $1, $2, MAX(id) FROM booking_messages WHERE booking_request_id = $1
+         HAVING MAX(id) IS NOT NULL
+         ON CONFLICT (booking_request_id, reader) DO UPDATE SET
+             last_read_message_id = EXCLUDED.last_read_message_id,
+             read_at = CURRENT_TIMESTAMP
+         WHERE EXCLUDED.last_read_message_id > message_reads.last_read_message_id",
+    )
+    .bind(booking_id)
+    .bind(reader)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Client messages the artist hasn't read, per booking with any, as
+/// `(booking_id, unread)`
+#[cfg(feature = "ssr")]
+pub async fn get_unread_counts(artist_id: i32) -> DbResult<Vec<(i32, i64)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT bm.booking_request_id, COUNT(*) as unread
+         FROM booking_messages bm
+         JOIN booking_requests br ON br.id = bm.booking_request_id
+         LEFT JOIN message_reads mr
+             ON mr.booking_request_id = bm.booking_request_id AND mr.reader = 'artist'
+         WHERE br.artist_id = $1
+           AND bm.sender_type = 'client'
+           AND bm.id > COALESCE(mr.last_read_message_id, 0)
+         GROUP BY bm.booking_request_id
+         ORDER BY bm.booking_request_id",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("booking_request_id"), row.get("unread")))
+        .collect())
+}
@@ -1,5 +1,69 @@
+pub mod account_repository;
+pub mod api_key_repository;
+pub mod archive_repository;
+pub mod artist_question_repository;
+pub mod auto_response_repository;
+pub mod availability_repository;
+pub mod booking_event_repository;
+pub mod booking_status_repository;
+pub mod calendar_feed_repository;
+pub mod calendar_import_repository;
+pub mod circuit_breaker_repository;
+pub mod client_dashboard_repository;
+pub mod completeness_repository;
+pub mod dashboard_repository;
+pub mod data_quality_repository;
+pub mod deposit_repository;
+pub mod document_repository;
+pub mod embed_repository;
 pub mod entities;
+pub mod error_group_repository;
+pub mod export_repository;
 pub mod favorites_repository;
+pub mod flash_design_repository;
+pub mod forecast_repository;
+pub mod geo;
+pub mod guest_spot_repository;
+pub mod hint_repository;
+pub mod impression_repository;
+pub mod ingestion_cost_repository;
+pub mod instagram_media_repository;
+pub mod invoice_repository;
+pub mod job_repository;
+pub mod license_repository;
+pub mod message_read_repository;
+pub mod onboarding_repository;
+pub mod ops_repository;
+pub mod payout_repository;
+pub mod pinning_repository;
+pub mod place_repository;
 pub mod pool;
+pub mod portfolio_album_repository;
+pub mod pricing_repository;
+pub mod profile_content_repository;
+pub mod public_api_repository;
+pub mod quiz_session_repository;
+pub mod rate_limit_repository;
+pub mod recurring_rule_repository;
+pub mod refresh_token_repository;
+pub mod reminder_repository;
 pub mod repository;
+pub mod reschedule_repository;
+pub mod response_time_repository;
 pub mod search_repository;
+pub mod shadow;
+pub mod source_map_repository;
+pub mod shop_claim_repository;
+pub mod shop_user_repository;
+pub mod short_link_repository;
+pub mod similar_image_repository;
+pub mod starter_pack_repository;
+pub mod status_repository;
+pub mod style_merge_repository;
+pub mod subscription_repository;
+pub mod sync_repository;
+pub mod tattoo_photo_repository;
+pub mod team_repository;
+pub mod translation_repository;
+pub mod uploaded_image_repository;
+pub mod upload_repository;
@@ -0,0 +1,155 @@
+#[cfg(feature = "ssr")]
+use super::entities::{OnboardingShop, OnboardingStatus};
+#[cfg(feature = "ssr")]
+use shared_types::LocationInfo;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What the artist has set up so far, in one round trip
+#[cfg(feature = "ssr")]
+pub async fn get_onboarding_status(artist_id: i32) -> DbResult<OnboardingStatus> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT
+            a.location_confirmed_at IS NOT NULL as location_confirmed,
+            l.id::bigint as location_id,
+            l.name as location_name,
+            l.address as location_address,
+            ARRAY(SELECT ast.style_id::int FROM artists_styles ast
+                  WHERE ast.artist_id = a.id ORDER BY ast.style_id) as style_ids,
+            (EXISTS (SELECT 1 FROM artist_pricing p
+                     WHERE p.artist_id = a.id
+                       AND (p.hourly_rate IS NOT NULL OR p.minimum_charge IS NOT NULL))
+             OR EXISTS (SELECT 1 FROM artist_style_pricing sp WHERE sp.artist_id = a.id))
+              as has_pricing,
+            (SELECT COUNT(*) FROM business_hours bh
+             WHERE bh.artist_id = a.id AND bh.is_closed = false) as open_days,
+            (SELECT COUNT(*) FROM artist_uploaded_images ui WHERE ui.artist_id = a.id)
+              as photo_count,
+            a.availability_status IS DISTINCT FROM 'pending_onboarding' as completed
+         FROM artists a
+         LEFT JOIN locations l ON l.id = a.location_id
+         WHERE a.id = $1",
+    )
+    .bind(artist_id as i64)
+    .fetch_one(pool)
+    .await?;
+
+    let shop = if row.get("location_confirmed") {
+        row.get::<Option<i64>, _>("location_id")
+            .map(|location_id| OnboardingShop {
+                location_id,
+                name: row
+                    .get::<Option<String>, _>("location_name")
+                    .unwrap_or_default(),
+                address: row
+                    .get::<Option<String>, _>("location_address")
+                    .unwrap_or_default(),
+            })
+    } else {
+        None
+    };
+
+    Ok(OnboardingStatus {
+        artist_id,
+        shop,
+        style_ids: row.get("style_ids"),
+        has_pricing: row.get("has_pricing"),
+        open_days: row.get("open_days"),
+        photo_count: row.get("photo_count"),
+        completed: row.get("completed"),
+    })
+}
+
+/// Stores a shop found on Google or geocoded from an address, returning its
+/// id. Shops we already have, by place id, are left as they are.
+#[cfg(feature = "ssr")]
+pub async fn upsert_location(location: &LocationInfo) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "INSERT INTO locations (
+            city, county, state, country_code, postal_code, is_open, address,
+            _id, category, name, website_uri, lat, long
+         )
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+         ON CONFLICT (_id) DO UPDATE SET _id = EXCLUDED._id
+         RETURNING id::bigint",
+    )
+    .bind(&location.city)
+    .bind(&location.county)
+    .bind(&location.state)
+    .bind(&location.country_code)
+    .bind(&location.postal_code)
+    .bind(if location.is_open { 1i16 } else { 0i16 })
+    .bind(&location.address)
+    .bind(&location._id)
+    .bind(&location.category)
+    .bind(&location.name)
+    .bind(&location.website_uri)
+    .bind(location.lat)
+    .bind(location.long)
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_artist_location(artist_id: i32, location_id: i64) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE artists SET location_id = $2, location_confirmed_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(artist_id as i64)
+    .bind(location_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Replaces the artist's styles, ignoring ids that aren't styles. Returns
+/// how many were saved.
+#[cfg(feature = "ssr")]
+pub async fn set_artist_styles(artist_id: i32, style_ids: &[i32]) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM artists_styles WHERE artist_id = $1")
+        .bind(artist_id as i64)
+        .execute(&mut *tx)
+        .await?;
+
+    let inserted = sqlx::query(
+        "INSERT INTO artists_styles (artist_id, style_id)
+         SELECT $1, s.id FROM styles s WHERE s.id = ANY($2)",
+    )
+    .bind(artist_id as i64)
+    .bind(style_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(inserted.rows_affected())
+}
+
+/// Takes the artist live. Returns `false` if they already were.
+#[cfg(feature = "ssr")]
+pub async fn complete_onboarding(artist_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artists SET availability_status = 'active', onboarded_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND availability_status = 'pending_onboarding'",
+    )
+    .bind(artist_id as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
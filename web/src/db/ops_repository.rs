@@ -0,0 +1,142 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Roles `users.role` accepts
+#[cfg(feature = "ssr")]
+pub const ROLES: [&str; 3] = ["client", "artist", "admin"];
+
+#[cfg(feature = "ssr")]
+pub struct OpsUser {
+    pub id: i64,
+    pub email: String,
+    pub role: String,
+    pub is_active: bool,
+}
+
+#[cfg(feature = "ssr")]
+pub struct OpsAuditEntry {
+    pub created_at: String,
+    pub operator: String,
+    pub action: String,
+    pub target_email: Option<String>,
+    pub reason: Option<String>,
+    pub details: Option<String>,
+}
+
+/// Looks a user up by email, case-insensitively, active or not
+#[cfg(feature = "ssr")]
+pub async fn find_user_by_email(email: &str) -> DbResult<Option<OpsUser>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT id, email, role::text as role, is_active
+         FROM users
+         WHERE LOWER(email) = LOWER($1)",
+    )
+    .bind(email.trim())
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| OpsUser {
+        id: row.get("id"),
+        email: row.get("email"),
+        role: row.get("role"),
+        is_active: row.get("is_active"),
+    }))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_role(user_id: i64, role: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    // The role column is an enum, which a text parameter won't cast to
+    let query = match role {
+        "admin" => "UPDATE users SET role = 'admin' WHERE id = $1",
+        "artist" => "UPDATE users SET role = 'artist' WHERE id = $1",
+        "client" => "UPDATE users SET role = 'client' WHERE id = $1",
+        _ => return Err(sqlx::Error::Protocol(format!("unknown role: {}", role))),
+    };
+
+    sqlx::query(query).bind(user_id).execute(pool).await?;
+    Ok(())
+}
+
+/// Reactivating an account also cancels a data deletion the user asked for
+#[cfg(feature = "ssr")]
+pub async fn set_active(user_id: i64, is_active: bool) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE users SET
+            is_active = $2,
+            deactivated_at = CASE WHEN $2 THEN NULL ELSE CURRENT_TIMESTAMP END,
+            deletion_requested_at = CASE WHEN $2 THEN NULL ELSE deletion_requested_at END
+         WHERE id = $1",
+    )
+    .bind(user_id)
+    .bind(is_active)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn insert_audit(
+    operator: &str,
+    action: &str,
+    target: Option<&OpsUser>,
+    reason: Option<&str>,
+    details: Option<&str>,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO ops_audit_log (operator, action, target_user_id, target_email, reason, details)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(operator)
+    .bind(action)
+    .bind(target.map(|user| user.id))
+    .bind(target.map(|user| user.email.as_str()))
+    .bind(reason)
+    .bind(details)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Most recent entries first, optionally only those about one user
+#[cfg(feature = "ssr")]
+pub async fn list_audit(target_user_id: Option<i64>, limit: i64) -> DbResult<Vec<OpsAuditEntry>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT TO_CHAR(created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at,
+                operator, action, target_email, reason, details
+         FROM ops_audit_log
+         WHERE $1::bigint IS NULL OR target_user_id = $1
+         ORDER BY created_at DESC
+         LIMIT $2",
+    )
+    .bind(target_user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| OpsAuditEntry {
+            created_at: row.get("created_at"),
+            operator: row.get("operator"),
+            action: row.get("action"),
+            target_email: row.get("target_email"),
+            reason: row.get("reason"),
+            details: row.get("details"),
+        })
+        .collect())
+}
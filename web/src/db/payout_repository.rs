@@ -0,0 +1,201 @@
+#[cfg(feature = "ssr")]
+use super::entities::{PaymentAccount, Payout};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+const ACCOUNT_COLUMNS: &str = "artist_id, account_id, country, default_currency,
+    charges_enabled, payouts_enabled, details_submitted, requirements_due,
+    payout_interval, payout_anchor";
+
+#[cfg(feature = "ssr")]
+fn account_from_row(row: &PgRow) -> PaymentAccount {
+    PaymentAccount {
+        account_id: row.get("account_id"),
+        country: row.get("country"),
+        default_currency: row.get("default_currency"),
+        charges_enabled: row.get("charges_enabled"),
+        payouts_enabled: row.get("payouts_enabled"),
+        details_submitted: row.get("details_submitted"),
+        requirements_due: row.get("requirements_due"),
+        payout_interval: row.get("payout_interval"),
+        payout_anchor: row.get("payout_anchor"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_account(artist_id: i32) -> DbResult<Option<PaymentAccount>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM artist_payment_accounts WHERE artist_id = $1",
+        ACCOUNT_COLUMNS
+    ))
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(account_from_row))
+}
+
+/// The artist a connected account belongs to
+#[cfg(feature = "ssr")]
+pub async fn get_account_artist(account_id: &str) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT artist_id FROM artist_payment_accounts WHERE account_id = $1")
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn insert_account(
+    artist_id: i32,
+    provider: &str,
+    account_id: &str,
+    country: &str,
+    default_currency: &str,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO artist_payment_accounts
+            (artist_id, provider, account_id, country, default_currency)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(artist_id)
+    .bind(provider)
+    .bind(account_id)
+    .bind(country)
+    .bind(default_currency)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Stores the capabilities last reported for an account
+#[cfg(feature = "ssr")]
+pub async fn update_account_status(
+    account_id: &str,
+    charges_enabled: bool,
+    payouts_enabled: bool,
+    details_submitted: bool,
+    requirements_due: &[String],
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_payment_accounts
+         SET charges_enabled = $2, payouts_enabled = $3, details_submitted = $4,
+             requirements_due = $5, updated_at = CURRENT_TIMESTAMP
+         WHERE account_id = $1",
+    )
+    .bind(account_id)
+    .bind(charges_enabled)
+    .bind(payouts_enabled)
+    .bind(details_submitted)
+    .bind(requirements_due)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(feature = "ssr")]
+pub async fn update_payout_schedule(
+    artist_id: i32,
+    interval: &str,
+    anchor: Option<&str>,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE artist_payment_accounts
+         SET payout_interval = $2, payout_anchor = $3, updated_at = CURRENT_TIMESTAMP
+         WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .bind(interval)
+    .bind(anchor)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// A payout as reported by the provider
+#[cfg(feature = "ssr")]
+pub struct PayoutUpdate {
+    pub artist_id: i32,
+    pub provider_payout_id: String,
+    pub amount: f64,
+    pub currency: String,
+    pub status: String,
+    /// `YYYY-MM-DD`
+    pub arrival_date: Option<String>,
+    pub failure_message: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+pub async fn upsert_payout(payout: &PayoutUpdate) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO payouts
+            (artist_id, provider_payout_id, amount, currency, status, arrival_date, failure_message)
+         VALUES ($1, $2, $3, $4, $5, $6::date, $7)
+         ON CONFLICT (provider_payout_id) DO UPDATE
+         SET amount = EXCLUDED.amount, currency = EXCLUDED.currency, status = EXCLUDED.status,
+             arrival_date = EXCLUDED.arrival_date, failure_message = EXCLUDED.failure_message,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(payout.artist_id)
+    .bind(&payout.provider_payout_id)
+    .bind(payout.amount)
+    .bind(&payout.currency)
+    .bind(&payout.status)
+    .bind(&payout.arrival_date)
+    .bind(&payout.failure_message)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The artist's most recent payouts, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_payouts(artist_id: i32, limit: i64) -> DbResult<Vec<Payout>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, amount, currency, status,
+                TO_CHAR(arrival_date, 'YYYY-MM-DD') as arrival_date, failure_message,
+                TO_CHAR(created_at, 'YYYY-MM-DD') as created_at
+         FROM payouts
+         WHERE artist_id = $1
+         ORDER BY created_at DESC
+         LIMIT $2",
+    )
+    .bind(artist_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| Payout {
+            id: row.get("id"),
+            amount: row.get("amount"),
+            currency: row.get("currency"),
+            status: row.get("status"),
+            arrival_date: row.get("arrival_date"),
+            failure_message: row.get("failure_message"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
@@ -0,0 +1,138 @@
+#[cfg(feature = "ssr")]
+use super::entities::PinningRule;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, PgPool, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+fn pinning_rule_from_row(row: &PgRow) -> PinningRule {
+    PinningRule {
+        id: row.get("id"),
+        entity_type: row.get("entity_type"),
+        entity_id: row.get("entity_id"),
+        scope_city: row.get("scope_city"),
+        scope_state: row.get("scope_state"),
+        position: row.get("position"),
+        note: row.get("note"),
+        expires_at: row.get("expires_at"),
+    }
+}
+
+/// Unexpired pins for `entity_type` that apply to the given city/state, as
+/// `(entity_id, position)`. When an entity has several matching rules the
+/// most specific one (city over state over global) wins.
+#[cfg(feature = "ssr")]
+pub async fn get_active_pins(
+    pool: &PgPool,
+    entity_type: &str,
+    city: &str,
+    state: &str,
+) -> DbResult<Vec<(i64, i32)>> {
+    let rows = sqlx::query(
+        "SELECT DISTINCT ON (entity_id) entity_id, position
+         FROM pinning_rules
+         WHERE entity_type = $1
+           AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+           AND (scope_state IS NULL OR LOWER(scope_state) = LOWER($3))
+           AND (scope_city IS NULL OR LOWER(scope_city) = LOWER($2))
+         ORDER BY entity_id, (scope_city IS NOT NULL) DESC, (scope_state IS NOT NULL) DESC, position",
+    )
+    .bind(entity_type)
+    .bind(city)
+    .bind(state)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("entity_id"), row.get("position")))
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn list_pinning_rules() -> DbResult<Vec<PinningRule>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, entity_type, entity_id, scope_city, scope_state, position, note,
+                TO_CHAR(expires_at, 'YYYY-MM-DD HH24:MI') as expires_at
+         FROM pinning_rules
+         WHERE expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP
+         ORDER BY scope_state NULLS FIRST, scope_city NULLS FIRST, position",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(pinning_rule_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn create_pinning_rule(rule: &PinningRule, created_by: i64) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO pinning_rules
+         (entity_type, entity_id, scope_city, scope_state, position, note, created_by, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8::timestamptz)
+         RETURNING id",
+    )
+    .bind(&rule.entity_type)
+    .bind(rule.entity_id)
+    .bind(&rule.scope_city)
+    .bind(&rule.scope_state)
+    .bind(rule.position)
+    .bind(&rule.note)
+    .bind(created_by)
+    .bind(&rule.expires_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn delete_pinning_rule(rule_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM pinning_rules WHERE id = $1")
+        .bind(rule_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Moves pinned items into their 1-based positions, keeping the relative
+/// order of everything else. `mark` is called on each item that was pinned.
+/// Positions past the end of the list put the item last.
+pub fn apply_pins<T>(
+    items: &mut Vec<T>,
+    pins: &[(i64, i32)],
+    id_of: impl Fn(&T) -> i64,
+    mut mark: impl FnMut(&mut T, i32),
+) {
+    if pins.is_empty() {
+        return;
+    }
+
+    let mut pinned = Vec::new();
+    let mut index = 0;
+    while index < items.len() {
+        match pins.iter().find(|(id, _)| *id == id_of(&items[index])) {
+            Some((_, position)) => {
+                let mut item = items.remove(index);
+                mark(&mut item, *position);
+                pinned.push((*position, item));
+            }
+            None => index += 1,
+        }
+    }
+
+    pinned.sort_by_key(|(position, _)| *position);
+    for (position, item) in pinned {
+        let slot = (position.max(1) as usize - 1).min(items.len());
+        items.insert(slot, item);
+    }
+}
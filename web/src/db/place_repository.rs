@@ -0,0 +1,115 @@
+#[cfg(feature = "ssr")]
+use super::entities::PlaceSuggestion;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Cities whose name starts with `query`, optionally limited to states whose
+/// name starts with `state`. Exact name matches come first, then larger cities.
+#[cfg(feature = "ssr")]
+pub async fn autocomplete_cities(
+    query: &str,
+    state: Option<&str>,
+    limit: i64,
+) -> DbResult<Vec<PlaceSuggestion>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT DISTINCT ON (c.city_search, c.state_name)
+            c.city, c.state_name, c.latitude, c.longitude, c.population,
+            c.city_search = search_normalize($1) as exact,
+            EXISTS (
+                SELECT 1 FROM locations l
+                WHERE l.city_id = c.id
+                AND (l.is_person IS NULL OR l.is_person = 0)
+            ) as has_locations
+         FROM cities c
+         WHERE c.city_search LIKE search_normalize($1) || '%'
+         AND ($2::text IS NULL OR search_normalize(c.state_name) LIKE search_normalize($2) || '%')
+         ORDER BY c.city_search, c.state_name, c.population DESC NULLS LAST",
+    )
+    .bind(query)
+    .bind(state)
+    .fetch_all(pool)
+    .await?;
+
+    let mut ranked: Vec<(bool, PlaceSuggestion)> = rows
+        .into_iter()
+        .map(|row| {
+            (
+                row.get("exact"),
+                PlaceSuggestion {
+                    city: row.get("city"),
+                    state: row.get("state_name"),
+                    lat: row.try_get::<f32, _>("latitude").unwrap_or(0.0) as f64,
+                    long: row.try_get::<f32, _>("longitude").unwrap_or(0.0) as f64,
+                    population: row.get("population"),
+                    has_locations: row.get("has_locations"),
+                    ambiguous: false,
+                },
+            )
+        })
+        .collect();
+
+    ranked.sort_by(|(a_exact, a), (b_exact, b)| {
+        b_exact
+            .cmp(a_exact)
+            .then(b.population.unwrap_or(0).cmp(&a.population.unwrap_or(0)))
+            .then(a.city.len().cmp(&b.city.len()))
+    });
+    ranked.truncate(limit.max(0) as usize);
+
+    let mut suggestions: Vec<PlaceSuggestion> = ranked.into_iter().map(|(_, s)| s).collect();
+    let names: Vec<String> = suggestions.iter().map(|s| s.city.to_lowercase()).collect();
+    for suggestion in suggestions.iter_mut() {
+        let name = suggestion.city.to_lowercase();
+        suggestion.ambiguous = names.iter().filter(|n| **n == name).count() > 1;
+    }
+
+    Ok(suggestions)
+}
+
+/// Queues shop ingestion for a reference city we have no locations in.
+/// Returns false if the city is unknown, already covered, already queued, or
+/// was ingested within `cooldown_days`.
+#[cfg(feature = "ssr")]
+pub async fn enqueue_city_ingestion(
+    city: &str,
+    state: &str,
+    cooldown_days: i32,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "INSERT INTO background_jobs (queue, kind, payload, dedupe_key, max_attempts)
+         SELECT 'ingestion', 'city_ingestion',
+            jsonb_build_object('city', c.city, 'state', c.state_name,
+                               'lat', c.latitude, 'long', c.longitude),
+            c.state_name || '/' || c.city, 1
+         FROM cities c
+         WHERE c.city_search = search_normalize($1) AND c.state_name = $2
+         AND NOT EXISTS (
+             SELECT 1 FROM locations l
+             WHERE l.city_id = c.id
+         )
+         AND NOT EXISTS (
+             SELECT 1 FROM background_jobs j
+             WHERE j.queue = 'ingestion' AND j.kind = 'city_ingestion'
+             AND j.dedupe_key = c.state_name || '/' || c.city
+             AND j.status = 'completed'
+             AND j.finished_at > NOW() - make_interval(days => $3)
+         )
+         ORDER BY c.population DESC NULLS LAST
+         LIMIT 1
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(city)
+    .bind(state)
+    .bind(cooldown_days)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,207 @@
+#[cfg(feature = "ssr")]
+use super::entities::{PortfolioAlbum, PortfolioImageState};
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// An artist's albums in creation order, counting only images visitors can see
+#[cfg(feature = "ssr")]
+pub async fn get_albums(artist_id: i64) -> DbResult<Vec<PortfolioAlbum>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT pa.id, pa.name, TO_CHAR(pa.created_at, 'YYYY-MM-DD') as created_at,
+                COUNT(ai.id) as image_count
+         FROM portfolio_albums pa
+         LEFT JOIN portfolio_album_images pai ON pai.album_id = pa.id
+         LEFT JOIN artists_images ai ON ai.id = pai.image_id
+             AND ai.removed_at IS NULL AND ai.hidden_at IS NULL
+         WHERE pa.artist_id = $1
+         GROUP BY pa.id
+         ORDER BY pa.created_at, pa.id",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PortfolioAlbum {
+            id: row.get("id"),
+            name: row.get("name"),
+            image_count: row.get("image_count"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// Creates an album, or returns None when the artist already has one by that name
+#[cfg(feature = "ssr")]
+pub async fn create_album(artist_id: i64, name: &str) -> DbResult<Option<PortfolioAlbum>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO portfolio_albums (artist_id, name)
+         VALUES ($1, $2)
+         ON CONFLICT (artist_id, name) DO NOTHING
+         RETURNING id, name, TO_CHAR(created_at, 'YYYY-MM-DD') as created_at",
+    )
+    .bind(artist_id)
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| PortfolioAlbum {
+        id: row.get("id"),
+        name: row.get("name"),
+        image_count: 0,
+        created_at: row.get("created_at"),
+    }))
+}
+
+/// Deletes an album; its images stay in the portfolio
+#[cfg(feature = "ssr")]
+pub async fn delete_album(artist_id: i64, album_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM portfolio_albums WHERE id = $1 AND artist_id = $2")
+        .bind(album_id)
+        .bind(artist_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Adds images to an album. Images the artist doesn't own, or an album that
+/// isn't theirs, are silently skipped; returns how many were added.
+#[cfg(feature = "ssr")]
+pub async fn add_album_images(artist_id: i64, album_id: i64, image_ids: &[i64]) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "INSERT INTO portfolio_album_images (album_id, image_id)
+         SELECT pa.id, ai.id
+         FROM portfolio_albums pa
+         JOIN artists_images ai ON ai.artist_id = pa.artist_id AND ai.removed_at IS NULL
+         WHERE pa.id = $1 AND pa.artist_id = $2 AND ai.id = ANY($3)
+         ON CONFLICT (album_id, image_id) DO NOTHING",
+    )
+    .bind(album_id)
+    .bind(artist_id)
+    .bind(image_ids)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Takes images out of an album, returning how many were removed
+#[cfg(feature = "ssr")]
+pub async fn remove_album_images(
+    artist_id: i64,
+    album_id: i64,
+    image_ids: &[i64],
+) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "DELETE FROM portfolio_album_images pai
+         USING portfolio_albums pa
+         WHERE pa.id = pai.album_id AND pa.id = $1 AND pa.artist_id = $2
+           AND pai.image_id = ANY($3)",
+    )
+    .bind(album_id)
+    .bind(artist_id)
+    .bind(image_ids)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Replaces the artist's pinned highlights with `image_ids`, in that order.
+/// Removed and hidden images can't be pinned and are skipped; returns how
+/// many ended up pinned.
+#[cfg(feature = "ssr")]
+pub async fn set_pinned_images(artist_id: i64, image_ids: &[i64]) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE artists_images SET pinned_position = NULL
+         WHERE artist_id = $1 AND pinned_position IS NOT NULL",
+    )
+    .bind(artist_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let result = sqlx::query(
+        "UPDATE artists_images ai
+         SET pinned_position = pinned.position::int
+         FROM UNNEST($2::bigint[]) WITH ORDINALITY AS pinned(image_id, position)
+         WHERE ai.id = pinned.image_id AND ai.artist_id = $1
+           AND ai.removed_at IS NULL AND ai.hidden_at IS NULL",
+    )
+    .bind(artist_id)
+    .bind(image_ids)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Hides or unhides one of the artist's images. Hiding also unpins it.
+#[cfg(feature = "ssr")]
+pub async fn set_image_hidden(artist_id: i64, image_id: i64, hidden: bool) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artists_images
+         SET hidden_at = CASE WHEN $3 THEN COALESCE(hidden_at, CURRENT_TIMESTAMP) END,
+             pinned_position = CASE WHEN $3 THEN NULL ELSE pinned_position END
+         WHERE id = $1 AND artist_id = $2 AND removed_at IS NULL",
+    )
+    .bind(image_id)
+    .bind(artist_id)
+    .bind(hidden)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Every image in the artist's portfolio with its curation state, hidden ones
+/// included, pinned first and then newest first
+#[cfg(feature = "ssr")]
+pub async fn get_portfolio_images(artist_id: i64) -> DbResult<Vec<PortfolioImageState>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT ai.id, ai.short_code, ai.hidden_at IS NOT NULL as hidden, ai.pinned_position,
+                COALESCE(ARRAY_AGG(pai.album_id ORDER BY pai.album_id)
+                         FILTER (WHERE pai.album_id IS NOT NULL), '{}') as album_ids
+         FROM artists_images ai
+         LEFT JOIN portfolio_album_images pai ON pai.image_id = ai.id
+         WHERE ai.artist_id = $1 AND ai.removed_at IS NULL
+         GROUP BY ai.id
+         ORDER BY ai.pinned_position NULLS LAST, ai.id DESC",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| PortfolioImageState {
+            image_id: row.get("id"),
+            short_code: row.get("short_code"),
+            hidden: row.get("hidden"),
+            pinned_position: row.get("pinned_position"),
+            album_ids: row.get("album_ids"),
+        })
+        .collect())
+}
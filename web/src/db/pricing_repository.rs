@@ -0,0 +1,187 @@
+#[cfg(feature = "ssr")]
+use super::entities::{ArtistPricing, StylePrice};
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// An artist's pricing, listing every style they work in whether or not
+/// they've priced it yet
+#[cfg(feature = "ssr")]
+pub async fn get_pricing(artist_id: i32) -> DbResult<ArtistPricing> {
+    let pool = crate::db::pool::get_pool();
+
+    let rates =
+        sqlx::query("SELECT hourly_rate, minimum_charge FROM artist_pricing WHERE artist_id = $1")
+            .bind(artist_id)
+            .fetch_optional(pool)
+            .await?;
+
+    let style_rows = sqlx::query(
+        "SELECT s.id, s.name, sp.min_price, sp.max_price
+         FROM styles s
+         LEFT JOIN artist_style_pricing sp ON sp.style_id = s.id AND sp.artist_id = $1
+         WHERE sp.artist_id IS NOT NULL
+            OR EXISTS (
+                SELECT 1 FROM artists_styles ast
+                WHERE ast.style_id = s.id AND ast.artist_id = $1
+            )
+         ORDER BY s.name",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(ArtistPricing {
+        hourly_rate: rates.as_ref().and_then(|row| row.get("hourly_rate")),
+        minimum_charge: rates.as_ref().and_then(|row| row.get("minimum_charge")),
+        style_prices: style_rows
+            .iter()
+            .map(|row| StylePrice {
+                style_id: row.get::<i64, _>("id") as i32,
+                style_name: row.get("name"),
+                min_price: row.get("min_price"),
+                max_price: row.get("max_price"),
+            })
+            .collect(),
+    })
+}
+
+/// Replaces an artist's pricing. Styles with neither bound set are cleared.
+#[cfg(feature = "ssr")]
+pub async fn save_pricing(artist_id: i32, pricing: &ArtistPricing) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO artist_pricing (artist_id, hourly_rate, minimum_charge)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (artist_id) DO UPDATE
+         SET hourly_rate = EXCLUDED.hourly_rate,
+             minimum_charge = EXCLUDED.minimum_charge,
+             updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(artist_id)
+    .bind(pricing.hourly_rate)
+    .bind(pricing.minimum_charge)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM artist_style_pricing WHERE artist_id = $1")
+        .bind(artist_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for price in pricing
+        .style_prices
+        .iter()
+        .filter(|price| price.min_price.is_some() || price.max_price.is_some())
+    {
+        sqlx::query(
+            "INSERT INTO artist_style_pricing (artist_id, style_id, min_price, max_price)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(artist_id)
+        .bind(price.style_id as i64)
+        .bind(price.min_price)
+        .bind(price.max_price)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Pricing for each of these artists that has set any. Only priced styles
+/// are included.
+#[cfg(feature = "ssr")]
+pub async fn get_pricing_for_artists(artist_ids: &[i32]) -> DbResult<HashMap<i32, ArtistPricing>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rate_rows = sqlx::query(
+        "SELECT artist_id, hourly_rate, minimum_charge
+         FROM artist_pricing
+         WHERE artist_id = ANY($1)",
+    )
+    .bind(artist_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let style_rows = sqlx::query(
+        "SELECT sp.artist_id, s.id, s.name, sp.min_price, sp.max_price
+         FROM artist_style_pricing sp
+         JOIN styles s ON s.id = sp.style_id
+         WHERE sp.artist_id = ANY($1)
+         ORDER BY sp.artist_id, s.name",
+    )
+    .bind(artist_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut pricing: HashMap<i32, ArtistPricing> = HashMap::new();
+    for row in rate_rows {
+        let entry = pricing.entry(row.get("artist_id")).or_default();
+        entry.hourly_rate = row.get("hourly_rate");
+        entry.minimum_charge = row.get("minimum_charge");
+    }
+    for row in style_rows {
+        pricing
+            .entry(row.get("artist_id"))
+            .or_default()
+            .style_prices
+            .push(StylePrice {
+                style_id: row.get::<i64, _>("id") as i32,
+                style_name: row.get("name"),
+                min_price: row.get("min_price"),
+                max_price: row.get("max_price"),
+            });
+    }
+
+    Ok(pricing)
+}
+
+/// The range covering every priced artist at each of these locations
+#[cfg(feature = "ssr")]
+pub async fn get_location_price_ranges(
+    location_ids: &[i64],
+) -> DbResult<HashMap<i64, (Option<f64>, Option<f64>)>> {
+    use crate::utils::pricing::{merge_ranges, price_range};
+
+    let pool = crate::db::pool::get_pool();
+
+    let artist_rows = sqlx::query(
+        "SELECT a.id, a.location_id
+         FROM artists a
+         WHERE a.location_id = ANY($1)
+         AND (EXISTS (SELECT 1 FROM artist_pricing p WHERE p.artist_id = a.id)
+              OR EXISTS (SELECT 1 FROM artist_style_pricing sp WHERE sp.artist_id = a.id))",
+    )
+    .bind(location_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let artist_locations: Vec<(i32, i64)> = artist_rows
+        .iter()
+        .map(|row| (row.get::<i64, _>("id") as i32, row.get("location_id")))
+        .collect();
+    let artist_ids: Vec<i32> = artist_locations.iter().map(|(id, _)| *id).collect();
+    let pricing = get_pricing_for_artists(&artist_ids).await?;
+
+    let mut ranges: HashMap<i64, (Option<f64>, Option<f64>)> = HashMap::new();
+    for (artist_id, location_id) in artist_locations {
+        if let Some(artist_pricing) = pricing.get(&artist_id) {
+            let range = price_range(artist_pricing, &[]);
+            let merged = merge_ranges(
+                ranges.get(&location_id).copied().unwrap_or((None, None)),
+                range,
+            );
+            ranges.insert(location_id, merged);
+        }
+    }
+
+    Ok(ranges)
+}
@@ -0,0 +1,153 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+use crate::db::entities::{ArtistFaq, ArtistPolicy, ArtistProfileContent, SocialLink};
+#[cfg(feature = "ssr")]
+use crate::utils::profile::POLICY_KINDS;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// The artist's bio, FAQ, policies and links. Policies come in
+/// `POLICY_KINDS` order.
+#[cfg(feature = "ssr")]
+pub async fn get_profile_content(artist_id: i32) -> DbResult<ArtistProfileContent> {
+    let pool = crate::db::pool::get_pool();
+
+    let bio = crate::db::completeness_repository::get_bio(artist_id).await?;
+
+    let faqs = sqlx::query(
+        "SELECT question, answer FROM artist_faqs
+         WHERE artist_id = $1
+         ORDER BY position, id",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| ArtistFaq {
+        question: row.get("question"),
+        answer: row.get("answer"),
+    })
+    .collect();
+
+    let mut policies: Vec<ArtistPolicy> =
+        sqlx::query("SELECT kind, body FROM artist_policies WHERE artist_id = $1")
+            .bind(artist_id)
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| ArtistPolicy {
+                kind: row.get("kind"),
+                body: row.get("body"),
+            })
+            .collect();
+    policies.sort_by_key(|policy| {
+        POLICY_KINDS
+            .iter()
+            .position(|(kind, _)| *kind == policy.kind)
+    });
+
+    let social_links = sqlx::query(
+        "SELECT platform, url FROM artist_social_links
+         WHERE artist_id = $1
+         ORDER BY position",
+    )
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| SocialLink {
+        platform: row.get("platform"),
+        url: row.get("url"),
+    })
+    .collect();
+
+    Ok(ArtistProfileContent {
+        bio,
+        faqs,
+        policies,
+        social_links,
+    })
+}
+
+/// Replaces the artist's FAQ with `faqs`, in that order
+#[cfg(feature = "ssr")]
+pub async fn save_faqs(artist_id: i32, faqs: &[ArtistFaq]) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM artist_faqs WHERE artist_id = $1")
+        .bind(artist_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (position, faq) in faqs.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO artist_faqs (artist_id, question, answer, position)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(artist_id)
+        .bind(&faq.question)
+        .bind(&faq.answer)
+        .bind(position as i32)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Replaces the artist's policies; kinds left out are cleared
+#[cfg(feature = "ssr")]
+pub async fn save_policies(artist_id: i32, policies: &[ArtistPolicy]) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM artist_policies WHERE artist_id = $1")
+        .bind(artist_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for policy in policies {
+        sqlx::query("INSERT INTO artist_policies (artist_id, kind, body) VALUES ($1, $2, $3)")
+            .bind(artist_id)
+            .bind(&policy.kind)
+            .bind(&policy.body)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Replaces the artist's links with `links`, in that order
+#[cfg(feature = "ssr")]
+pub async fn save_social_links(artist_id: i32, links: &[SocialLink]) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM artist_social_links WHERE artist_id = $1")
+        .bind(artist_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (position, link) in links.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO artist_social_links (artist_id, platform, url, position)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(artist_id)
+        .bind(&link.platform)
+        .bind(&link.url)
+        .bind(position as i32)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,208 @@
+//! Queries behind the public REST API (`/api/v1`). Lists come back with the
+//! total matching count. Shops and artists are ordered by id so pages stay
+//! stable as rows are added.
+
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+use crate::db::entities::{PublicArtist, PublicLocation, PublicStyle};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Shops only, not the artists listed as locations of their own
+#[cfg(feature = "ssr")]
+const LOCATION_FILTER: &str = "(l.is_person IS NULL OR l.is_person = 0)
+       AND ($1::text IS NULL OR LOWER(l.state) = LOWER($1))
+       AND ($2::text IS NULL OR LOWER(l.city) = LOWER($2))
+       AND ($3::bigint IS NULL OR l.id = $3)";
+
+#[cfg(feature = "ssr")]
+const LOCATION_SELECT: &str = "SELECT l.id::bigint as id, l.name, l.address, l.city, l.state,
+        l.postal_code, l.lat::float8 as lat, l.long::float8 as long, l.website_uri,
+        (SELECT COUNT(*) FROM artists a
+         WHERE a.location_id = l.id AND a.name IS NOT NULL AND a.name != '') as artist_count
+     FROM locations l";
+
+/// Artists with a name at a shop
+#[cfg(feature = "ssr")]
+const ARTIST_FILTER: &str = "a.name IS NOT NULL AND a.name != ''
+       AND (l.is_person IS NULL OR l.is_person = 0)
+       AND ($1::bigint IS NULL OR a.location_id = $1)
+       AND ($2::bigint IS NULL OR EXISTS (
+           SELECT 1 FROM artists_styles ast WHERE ast.artist_id = a.id AND ast.style_id = $2
+       ))
+       AND ($3::bigint IS NULL OR a.id = $3)";
+
+#[cfg(feature = "ssr")]
+const ARTIST_SELECT: &str = "SELECT a.id::bigint as id, a.name,
+        a.location_id::bigint as location_id, a.bio, a.instagram_handle,
+        a.years_experience::int as years_experience, a.experience_tier,
+        ARRAY(SELECT ast.style_id::bigint FROM artists_styles ast
+              WHERE ast.artist_id = a.id ORDER BY ast.style_id) as style_ids
+     FROM artists a
+     JOIN locations l ON l.id = a.location_id";
+
+#[cfg(feature = "ssr")]
+fn location_from_row(row: &sqlx::postgres::PgRow) -> PublicLocation {
+    PublicLocation {
+        id: row.get("id"),
+        name: row.get("name"),
+        address: row.get("address"),
+        city: row.get("city"),
+        state: row.get("state"),
+        postal_code: row.get("postal_code"),
+        lat: row.get("lat"),
+        long: row.get("long"),
+        website_uri: row.get("website_uri"),
+        artist_count: row.get("artist_count"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn artist_from_row(row: &sqlx::postgres::PgRow) -> PublicArtist {
+    PublicArtist {
+        id: row.get("id"),
+        name: row.get("name"),
+        location_id: row.get("location_id"),
+        bio: row.get("bio"),
+        instagram_handle: row.get("instagram_handle"),
+        years_experience: row.get("years_experience"),
+        experience_tier: row.get("experience_tier"),
+        style_ids: row.get("style_ids"),
+    }
+}
+
+/// A page of shops, optionally in one state and city, and how many there
+/// are in all
+#[cfg(feature = "ssr")]
+pub async fn list_locations(
+    state: Option<&str>,
+    city: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> DbResult<(Vec<PublicLocation>, i64)> {
+    let pool = crate::db::pool::get_pool();
+
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM locations l WHERE {}",
+        LOCATION_FILTER
+    ))
+    .bind(state)
+    .bind(city)
+    .bind(None::<i64>)
+    .fetch_one(pool)
+    .await?;
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE {} ORDER BY l.id LIMIT $4 OFFSET $5",
+        LOCATION_SELECT, LOCATION_FILTER
+    ))
+    .bind(state)
+    .bind(city)
+    .bind(None::<i64>)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((rows.iter().map(location_from_row).collect(), total))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_location(id: i64) -> DbResult<Option<PublicLocation>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE {}", LOCATION_SELECT, LOCATION_FILTER))
+        .bind(None::<&str>)
+        .bind(None::<&str>)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.as_ref().map(location_from_row))
+}
+
+/// A page of artists, optionally at one shop or working in one style, and
+/// how many there are in all
+#[cfg(feature = "ssr")]
+pub async fn list_artists(
+    location_id: Option<i64>,
+    style_id: Option<i64>,
+    limit: i64,
+    offset: i64,
+) -> DbResult<(Vec<PublicArtist>, i64)> {
+    let pool = crate::db::pool::get_pool();
+
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM artists a JOIN locations l ON l.id = a.location_id WHERE {}",
+        ARTIST_FILTER
+    ))
+    .bind(location_id)
+    .bind(style_id)
+    .bind(None::<i64>)
+    .fetch_one(pool)
+    .await?;
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE {} ORDER BY a.id LIMIT $4 OFFSET $5",
+        ARTIST_SELECT, ARTIST_FILTER
+    ))
+    .bind(location_id)
+    .bind(style_id)
+    .bind(None::<i64>)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((rows.iter().map(artist_from_row).collect(), total))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_artist(id: i64) -> DbResult<Option<PublicArtist>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE {}", ARTIST_SELECT, ARTIST_FILTER))
+        .bind(None::<i64>)
+        .bind(None::<i64>)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.as_ref().map(artist_from_row))
+}
+
+/// A page of styles by name, with how many artists work in each, and how
+/// many styles there are in all
+#[cfg(feature = "ssr")]
+pub async fn list_styles(limit: i64, offset: i64) -> DbResult<(Vec<PublicStyle>, i64)> {
+    let pool = crate::db::pool::get_pool();
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM styles")
+        .fetch_one(pool)
+        .await?;
+
+    let rows = sqlx::query(
+        "SELECT s.id::bigint as id, s.name, COUNT(DISTINCT ast.artist_id) as artist_count
+         FROM styles s
+         LEFT JOIN artists_styles ast ON ast.style_id = s.id
+         GROUP BY s.id, s.name
+         ORDER BY s.name, s.id
+         LIMIT $1 OFFSET $2",
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((
+        rows.iter()
+            .map(|row| PublicStyle {
+                id: row.get("id"),
+                name: row.get("name"),
+                artist_count: row.get("artist_count"),
+            })
+            .collect(),
+        total,
+    ))
+}
@@ -0,0 +1,185 @@
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A quiz session as stored. Styles are kept comma-separated in
+/// `style_preference`, which the style merge preview searches.
+#[cfg(feature = "ssr")]
+pub struct StoredQuizSession {
+    pub id: i64,
+    pub session_key: String,
+    pub user_id: Option<i64>,
+    pub styles: Vec<String>,
+    pub states: Vec<String>,
+    pub cities: Vec<String>,
+    pub matched_at: Option<String>,
+}
+
+/// A stored match: the artist and their `server::MatchedArtist` as JSON
+#[cfg(feature = "ssr")]
+pub struct StoredQuizMatch {
+    pub artist_id: i64,
+    pub match_score: i32,
+    pub artist_json: String,
+}
+
+#[cfg(feature = "ssr")]
+const SESSION_SELECT: &str = "SELECT id, session_key, user_id, style_preference, states, cities,
+        TO_CHAR(matched_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') as matched_at
+     FROM client_quiz_sessions";
+
+#[cfg(feature = "ssr")]
+fn session_from_row(row: &PgRow) -> StoredQuizSession {
+    let style_preference: Option<String> = row.get("style_preference");
+
+    StoredQuizSession {
+        id: row.get("id"),
+        session_key: row.get("session_key"),
+        user_id: row.get("user_id"),
+        styles: style_preference
+            .unwrap_or_default()
+            .split(',')
+            .map(|style| style.trim().to_string())
+            .filter(|style| !style.is_empty())
+            .collect(),
+        states: row.get("states"),
+        cities: row.get("cities"),
+        matched_at: row.get("matched_at"),
+    }
+}
+
+/// Creates the session for `session_key`, or replaces its answers. A
+/// session's user is only ever set, never changed or cleared.
+#[cfg(feature = "ssr")]
+pub async fn upsert_session(
+    session_key: &str,
+    user_id: Option<i64>,
+    styles: &[String],
+    states: &[String],
+    cities: &[String],
+) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "INSERT INTO client_quiz_sessions (session_key, user_id, style_preference, states, cities)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (session_key) WHERE session_key IS NOT NULL DO UPDATE
+         SET user_id = COALESCE(client_quiz_sessions.user_id, EXCLUDED.user_id),
+             style_preference = EXCLUDED.style_preference,
+             states = EXCLUDED.states,
+             cities = EXCLUDED.cities,
+             updated_at = CURRENT_TIMESTAMP
+         RETURNING id",
+    )
+    .bind(session_key)
+    .bind(user_id)
+    .bind(styles.join(","))
+    .bind(states)
+    .bind(cities)
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_session_by_key(session_key: &str) -> DbResult<Option<StoredQuizSession>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE session_key = $1", SESSION_SELECT))
+        .bind(session_key)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(session_from_row))
+}
+
+/// The user's most recently updated session that has matches stored
+#[cfg(feature = "ssr")]
+pub async fn get_latest_session_for_user(user_id: i64) -> DbResult<Option<StoredQuizSession>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "{} WHERE user_id = $1 AND matched_at IS NOT NULL
+         ORDER BY updated_at DESC
+         LIMIT 1",
+        SESSION_SELECT
+    ))
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(session_from_row))
+}
+
+/// Links an anonymous session to the user who has now signed in
+#[cfg(feature = "ssr")]
+pub async fn link_session_user(session_id: i64, user_id: i64) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE client_quiz_sessions SET user_id = $2 WHERE id = $1 AND user_id IS NULL")
+        .bind(session_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Replaces the session's matches with `matches`, best first
+#[cfg(feature = "ssr")]
+pub async fn replace_matches(session_id: i64, matches: &[StoredQuizMatch]) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM client_quiz_matches WHERE session_id = $1")
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (rank, stored) in matches.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO client_quiz_matches (session_id, rank, artist_id, match_score, artist)
+             VALUES ($1, $2, $3, $4, $5::jsonb)",
+        )
+        .bind(session_id)
+        .bind(rank as i32 + 1)
+        .bind(stored.artist_id)
+        .bind(stored.match_score)
+        .bind(&stored.artist_json)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE client_quiz_sessions SET matched_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_matches(session_id: i64) -> DbResult<Vec<StoredQuizMatch>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT artist_id, match_score, artist::text as artist_json
+         FROM client_quiz_matches
+         WHERE session_id = $1
+         ORDER BY rank",
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| StoredQuizMatch {
+            artist_id: row.get("artist_id"),
+            match_score: row.get("match_score"),
+            artist_json: row.get("artist_json"),
+        })
+        .collect())
+}
@@ -0,0 +1,113 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Hits in a counter's current window
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Copy)]
+pub struct Counter {
+    pub hits: i32,
+    /// Seconds until the window ends
+    pub resets_in_secs: i64,
+}
+
+/// Counts a hit against `scope`/`key`, starting a new window of
+/// `window_secs` if the last one has ended. With `restart_window` every hit
+/// starts the window again, so it only ends after `window_secs` without one.
+#[cfg(feature = "ssr")]
+pub async fn hit(
+    scope: &str,
+    key: &str,
+    window_secs: i64,
+    restart_window: bool,
+) -> DbResult<Counter> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO rate_limit_counters (scope, key)
+         VALUES ($1, $2)
+         ON CONFLICT (scope, key) DO UPDATE SET
+            hits = CASE
+                WHEN rate_limit_counters.window_started_at
+                    <= CURRENT_TIMESTAMP - make_interval(secs => $3) THEN 1
+                ELSE rate_limit_counters.hits + 1
+            END,
+            window_started_at = CASE
+                WHEN $4 OR rate_limit_counters.window_started_at
+                    <= CURRENT_TIMESTAMP - make_interval(secs => $3) THEN CURRENT_TIMESTAMP
+                ELSE rate_limit_counters.window_started_at
+            END
+         RETURNING hits,
+            CEIL(EXTRACT(EPOCH FROM window_started_at + make_interval(secs => $3)
+                - CURRENT_TIMESTAMP))::BIGINT as resets_in_secs",
+    )
+    .bind(scope)
+    .bind(key)
+    .bind(window_secs as f64)
+    .bind(restart_window)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Counter {
+        hits: row.get("hits"),
+        resets_in_secs: row.get("resets_in_secs"),
+    })
+}
+
+/// The counter's current window, without counting a hit. `None` if it has
+/// ended or there isn't one.
+#[cfg(feature = "ssr")]
+pub async fn get_counter(scope: &str, key: &str, window_secs: i64) -> DbResult<Option<Counter>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT hits,
+            CEIL(EXTRACT(EPOCH FROM window_started_at + make_interval(secs => $3)
+                - CURRENT_TIMESTAMP))::BIGINT as resets_in_secs
+         FROM rate_limit_counters
+         WHERE scope = $1 AND key = $2
+           AND window_started_at > CURRENT_TIMESTAMP - make_interval(secs => $3)",
+    )
+    .bind(scope)
+    .bind(key)
+    .bind(window_secs as f64)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| Counter {
+        hits: row.get("hits"),
+        resets_in_secs: row.get("resets_in_secs"),
+    }))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn clear_counter(scope: &str, key: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("DELETE FROM rate_limit_counters WHERE scope = $1 AND key = $2")
+        .bind(scope)
+        .bind(key)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Deletes counters whose window started over `max_window_secs` ago, which
+/// have ended whatever their scope
+#[cfg(feature = "ssr")]
+pub async fn delete_expired_counters(max_window_secs: i64) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "DELETE FROM rate_limit_counters
+         WHERE window_started_at <= CURRENT_TIMESTAMP - make_interval(secs => $1)",
+    )
+    .bind(max_window_secs as f64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
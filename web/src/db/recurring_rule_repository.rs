@@ -0,0 +1,156 @@
+#[cfg(feature = "ssr")]
+use super::entities::{RecurrencePattern, RecurringRule};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+const RULE_COLUMNS: &str = "id, artist_id, name, frequency, weekdays, interval_weeks,
+    week_of_month, annual_dates, starts_on::text as starts_on, ends_on::text as ends_on,
+    except_dates::text[] as except_dates, action, start_time, end_time, active,
+    created_at::text as created_at";
+
+#[cfg(feature = "ssr")]
+fn rule_from_row(row: &PgRow) -> RecurringRule {
+    RecurringRule {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        name: row.get("name"),
+        pattern: RecurrencePattern {
+            frequency: row.get("frequency"),
+            weekdays: row.get("weekdays"),
+            interval_weeks: row.get("interval_weeks"),
+            week_of_month: row.get("week_of_month"),
+            annual_dates: row.get("annual_dates"),
+            starts_on: row.get("starts_on"),
+            ends_on: row.get("ends_on"),
+            except_dates: row.get("except_dates"),
+        },
+        action: row.get("action"),
+        start_time: row.get("start_time"),
+        end_time: row.get("end_time"),
+        active: row.get("active"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// All of an artist's rules, switched off ones included, oldest first
+#[cfg(feature = "ssr")]
+pub async fn get_rules(artist_id: i32) -> DbResult<Vec<RecurringRule>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM recurring_rules WHERE artist_id = $1 ORDER BY id",
+        RULE_COLUMNS
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(rule_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_rule(artist_id: i32, rule_id: i32) -> DbResult<Option<RecurringRule>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM recurring_rules WHERE id = $1 AND artist_id = $2",
+        RULE_COLUMNS
+    ))
+    .bind(rule_id)
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(rule_from_row))
+}
+
+/// Inserts a rule that has already been validated and returns its id
+#[cfg(feature = "ssr")]
+pub async fn create_rule(
+    artist_id: i32,
+    name: &str,
+    pattern: &RecurrencePattern,
+    action: &str,
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+) -> DbResult<i32> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO recurring_rules
+            (artist_id, name, frequency, weekdays, interval_weeks, week_of_month,
+             annual_dates, starts_on, ends_on, except_dates, action, start_time, end_time)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8::date, $9::date, $10::date[], $11, $12, $13)
+         RETURNING id",
+    )
+    .bind(artist_id)
+    .bind(name)
+    .bind(&pattern.frequency)
+    .bind(&pattern.weekdays)
+    .bind(pattern.interval_weeks)
+    .bind(pattern.week_of_month)
+    .bind(&pattern.annual_dates)
+    .bind(&pattern.starts_on)
+    .bind(&pattern.ends_on)
+    .bind(&pattern.except_dates)
+    .bind(action)
+    .bind(start_time)
+    .bind(end_time)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+/// Writes every field of an existing rule back. Returns false if the rule
+/// isn't the artist's.
+#[cfg(feature = "ssr")]
+pub async fn save_rule(rule: &RecurringRule) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+    let pattern = &rule.pattern;
+
+    let result = sqlx::query(
+        "UPDATE recurring_rules
+         SET name = $3, frequency = $4, weekdays = $5, interval_weeks = $6,
+             week_of_month = $7, annual_dates = $8, starts_on = $9::date,
+             ends_on = $10::date, except_dates = $11::date[], action = $12,
+             start_time = $13, end_time = $14, active = $15,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND artist_id = $2",
+    )
+    .bind(rule.id)
+    .bind(rule.artist_id)
+    .bind(&rule.name)
+    .bind(&pattern.frequency)
+    .bind(&pattern.weekdays)
+    .bind(pattern.interval_weeks)
+    .bind(pattern.week_of_month)
+    .bind(&pattern.annual_dates)
+    .bind(&pattern.starts_on)
+    .bind(&pattern.ends_on)
+    .bind(&pattern.except_dates)
+    .bind(&rule.action)
+    .bind(&rule.start_time)
+    .bind(&rule.end_time)
+    .bind(rule.active)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(feature = "ssr")]
+pub async fn delete_rule(artist_id: i32, rule_id: i32) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("DELETE FROM recurring_rules WHERE id = $1 AND artist_id = $2")
+        .bind(rule_id)
+        .bind(artist_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,166 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+pub async fn insert_token(
+    user_id: i64,
+    token_hash: &str,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO refresh_tokens (user_id, token_hash, expires_at)
+         VALUES ($1, $2, $3)",
+    )
+    .bind(user_id)
+    .bind(token_hash)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Revokes a live token in the same statement that checks it, so it can
+/// only be used once, and stores its successor in the same transaction.
+/// A concurrent rotation of the same token waits on the row lock and then
+/// finds the successor already there. Returns the token's user.
+#[cfg(feature = "ssr")]
+pub async fn rotate_token(
+    token_hash: &str,
+    successor_hash: &str,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> DbResult<Option<i64>> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let user_id: Option<i64> = sqlx::query_scalar(
+        "UPDATE refresh_tokens
+         SET revoked_at = CURRENT_TIMESTAMP
+         WHERE token_hash = $1 AND revoked_at IS NULL AND expires_at > CURRENT_TIMESTAMP
+         RETURNING user_id",
+    )
+    .bind(token_hash)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(user_id) = user_id {
+        sqlx::query(
+            "INSERT INTO refresh_tokens (user_id, token_hash, expires_at)
+             VALUES ($1, $2, $3)",
+        )
+        .bind(user_id)
+        .bind(successor_hash)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(user_id)
+}
+
+/// The user of a token rotated within the last minute whose successor is
+/// still live. Two tabs refreshing at once present the same token, and the
+/// later one is handed the same successor.
+#[cfg(feature = "ssr")]
+pub async fn get_recently_rotated_user(
+    token_hash: &str,
+    successor_hash: &str,
+) -> DbResult<Option<i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT rotated.user_id
+         FROM refresh_tokens rotated
+         JOIN refresh_tokens successor
+           ON successor.token_hash = $2 AND successor.user_id = rotated.user_id
+         WHERE rotated.token_hash = $1
+           AND rotated.revoked_at >= CURRENT_TIMESTAMP - INTERVAL '1 minute'
+           AND successor.revoked_at IS NULL
+           AND successor.expires_at > CURRENT_TIMESTAMP",
+    )
+    .bind(token_hash)
+    .bind(successor_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The user of an unexpired token revoked over a minute ago. Tokens revoked
+/// more recently are left alone, since two tabs refreshing at once present
+/// the same token.
+#[cfg(feature = "ssr")]
+pub async fn get_revoked_token_user(token_hash: &str) -> DbResult<Option<i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT user_id FROM refresh_tokens
+         WHERE token_hash = $1
+           AND revoked_at < CURRENT_TIMESTAMP - INTERVAL '1 minute'
+           AND expires_at > CURRENT_TIMESTAMP",
+    )
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Revokes a token, returning its user if it was live
+#[cfg(feature = "ssr")]
+pub async fn revoke_token(token_hash: &str) -> DbResult<Option<i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "UPDATE refresh_tokens
+         SET revoked_at = CURRENT_TIMESTAMP
+         WHERE token_hash = $1 AND revoked_at IS NULL
+         RETURNING user_id",
+    )
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn revoke_all_for_user(user_id: i64) -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE refresh_tokens
+         SET revoked_at = CURRENT_TIMESTAMP
+         WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Role of a user who can still sign in
+#[cfg(feature = "ssr")]
+pub async fn get_active_user_role(user_id: i64) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT role::text as role FROM users WHERE id = $1 AND is_active = true")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| row.get("role")))
+}
+
+/// Deletes tokens that have expired, which can no longer be used or reused
+#[cfg(feature = "ssr")]
+pub async fn delete_expired_tokens() -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM refresh_tokens WHERE expires_at <= CURRENT_TIMESTAMP")
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,75 @@
+#[cfg(feature = "ssr")]
+use super::entities::BookingMessage;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// What a booking reminder needs to know about the booking
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug)]
+pub struct ReminderBooking {
+    pub status: String,
+    pub requested_date: String,
+    pub requested_start_time: String,
+    pub artist_name: Option<String>,
+    pub client_name: String,
+    pub client_email: String,
+    pub client_phone: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_reminder_booking(booking_id: i32) -> DbResult<Option<ReminderBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT br.status, br.requested_date, br.requested_start_time, a.name as artist_name,
+                br.client_name, br.client_email, br.client_phone
+         FROM booking_requests br
+         JOIN artists a ON a.id = br.artist_id
+         WHERE br.id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| ReminderBooking {
+        status: row.get("status"),
+        requested_date: row.get("requested_date"),
+        requested_start_time: row.get("requested_start_time"),
+        artist_name: row.get("artist_name"),
+        client_name: row.get("client_name"),
+        client_email: row.get("client_email"),
+        client_phone: row.get("client_phone"),
+    }))
+}
+
+/// Posts a reminder to the booking's thread as a system message
+#[cfg(feature = "ssr")]
+pub async fn insert_reminder_message(booking_id: i32, message: &str) -> DbResult<BookingMessage> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO booking_messages (booking_request_id, sender_type, message)
+         VALUES ($1, 'system', $2)
+         RETURNING id, booking_request_id, sender_type, message, created_at,
+                   language, translated_message, translated_language",
+    )
+    .bind(booking_id)
+    .bind(message)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(BookingMessage {
+        id: row.get("id"),
+        booking_request_id: row.get("booking_request_id"),
+        sender_type: row.get("sender_type"),
+        message: row.get("message"),
+        created_at: row.get("created_at"),
+        language: row.get("language"),
+        translated_message: row.get("translated_message"),
+        translated_language: row.get("translated_language"),
+        is_read: false,
+    })
+}
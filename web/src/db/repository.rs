@@ -4,13 +4,21 @@ use super::entities::{
     QuestionnaireQuestion, Style,
 };
 #[cfg(feature = "ssr")]
+use super::guest_spot_repository::ARTIST_PLACEMENTS;
+#[cfg(feature = "ssr")]
 use serde_json;
-use shared_types::{LocationInfo, MapBounds};
+#[cfg(feature = "ssr")]
+use shared_types::datetime::{Date, Time};
+use shared_types::{LocationInfo, MapBounds, RadiusSearch};
+#[cfg(feature = "ssr")]
+use shared_types::{StyleFilter, StyleMatchMode};
 #[cfg(feature = "ssr")]
 use sqlx::{Executor, PgPool, Row};
 #[cfg(feature = "ssr")]
 type DbResult<T> = Result<T, sqlx::Error>;
 
+/// Cities in `state` that have shops, under their canonical names from the
+/// cities table. Locations no city was matched to are left out.
 #[cfg(feature = "ssr")]
 pub async fn get_cities_and_coords(state: String) -> DbResult<Vec<CityCoords>> {
     let pool = crate::db::pool::get_pool();
@@ -18,15 +26,16 @@ pub async fn get_cities_and_coords(state: String) -> DbResult<Vec<CityCoords>> {
     let rows = sqlx::query(
         "
             SELECT
-                city,
-                state,
-                AVG(lat) as lat,
-                AVG(long) as long
-            FROM locations
+                c.city,
+                c.state_name as state,
+                AVG(l.lat) as lat,
+                AVG(l.long) as long
+            FROM locations l
+            JOIN cities c ON c.id = l.city_id
             WHERE
-                state = $1
-            AND (is_person IS NULL OR is_person = 0)
-            GROUP BY city, state
+                c.state_name = $1
+            AND (l.is_person IS NULL OR l.is_person = 0)
+            GROUP BY c.id, c.city, c.state_name
         ",
     )
     .bind(&state)
@@ -41,7 +50,6 @@ pub async fn get_cities_and_coords(state: String) -> DbResult<Vec<CityCoords>> {
             lat: row.try_get::<f64, _>("lat").unwrap_or(0.0),
             long: row.try_get::<f64, _>("long").unwrap_or(0.0),
         })
-        .filter(|c| c.city.parse::<f64>().is_err())
         .collect();
 
     Ok(city_coords)
@@ -52,10 +60,25 @@ pub async fn query_locations(
     state: String,
     city: String,
     bounds: MapBounds,
+    radius: Option<RadiusSearch>,
 ) -> DbResult<Vec<LocationInfo>> {
     let pool = crate::db::pool::get_pool();
 
-    let rows = sqlx::query(
+    // A radius search prefilters on the circle's bounding box
+    let bounds = radius.as_ref().map(RadiusSearch::bounds).unwrap_or(bounds);
+    let (distance_column, radius_clause, order) = match &radius {
+        Some(_) => {
+            let radius_sql = crate::db::geo::radius_sql(pool, 5).await?;
+            (
+                radius_sql.distance,
+                format!("AND {}", radius_sql.filter),
+                "ORDER BY distance_miles",
+            )
+        }
+        None => ("NULL::float8".to_string(), String::new(), ""),
+    };
+
+    let query = format!(
         "
         SELECT
             l.id,
@@ -73,23 +96,34 @@ pub async fn query_locations(
             l.website_uri,
             l._id,
             CASE WHEN COUNT(DISTINCT a.id) > 0 THEN 1 ELSE 0 END as has_artists,
-            COUNT(DISTINCT ai.id) as artist_images_count
+            COUNT(DISTINCT ai.id) as artist_images_count,
+            {} as distance_miles
         FROM locations l
         LEFT JOIN artists a ON l.id = a.location_id
-        LEFT JOIN artists_images ai ON a.id = ai.artist_id
+        LEFT JOIN artists_images ai ON a.id = ai.artist_id AND ai.removed_at IS NULL
         WHERE
             l.lat BETWEEN $1 AND $2
             AND l.long BETWEEN $3 AND $4
             AND (l.is_person IS NULL OR l.is_person = 0)
+            {}
         GROUP BY l.id, l.name, l.lat, l.long, l.city, l.county, l.state, l.country_code, l.postal_code, l.is_open, l.address, l.category, l.website_uri, l._id
+        {}
     ",
-    )
-    .bind(bounds.south_west.lat)
-    .bind(bounds.north_east.lat)
-    .bind(bounds.south_west.long)
-    .bind(bounds.north_east.long)
-    .fetch_all(pool)
-    .await?;
+        distance_column, radius_clause, order
+    );
+
+    let mut locations_query = sqlx::query(&query)
+        .bind(bounds.south_west.lat)
+        .bind(bounds.north_east.lat)
+        .bind(bounds.south_west.long)
+        .bind(bounds.north_east.long);
+    if let Some(radius) = &radius {
+        locations_query = locations_query
+            .bind(radius.center.lat)
+            .bind(radius.center.long)
+            .bind(radius.miles);
+    }
+    let rows = locations_query.fetch_all(pool).await?;
 
     let locations: Vec<LocationInfo> = rows
         .into_iter()
@@ -117,6 +151,7 @@ pub async fn query_locations(
                 _id: row.get("_id"),
                 has_artists: Some(has_artists == 1),
                 artist_images_count: Some(artist_images_count as i32),
+                distance_miles: row.get("distance_miles"),
             }
         })
         .collect();
@@ -164,10 +199,10 @@ pub async fn get_city_coordinates(city_name: String) -> DbResult<CityCoords> {
                 latitude,
                 longitude
             FROM cities
-            WHERE LOWER(REPLACE(city, '''', ' ')) LIKE $1
+            WHERE city_search LIKE '%' || search_normalize($1) || '%'
         ",
     )
-    .bind(format!("%{}%", city_name))
+    .bind(city_name)
     .fetch_all(pool)
     .await?;
 
@@ -192,7 +227,7 @@ pub async fn get_artist_by_id(artist_id: i32) -> DbResult<Artist> {
     let pool = crate::db::pool::get_pool();
 
     let row = sqlx::query(
-        "SELECT id, name, location_id, social_links, instagram_handle, email, phone, years_experience, styles_extracted
+        "SELECT id, name, location_id, social_links, instagram_handle, email, phone, years_experience, styles_extracted, experience_tier
          FROM artists
          WHERE id = $1"
     )
@@ -217,6 +252,7 @@ pub async fn get_artist_by_id(artist_id: i32) -> DbResult<Artist> {
             .ok()
             .map(|v| v as i32),
         shop_validated: row.try_get("shop_validated").ok(),
+        experience_tier: row.try_get("experience_tier").ok().flatten(),
     })
 }
 
@@ -270,6 +306,35 @@ pub async fn get_artist_styles(artist_id: i32) -> DbResult<Vec<Style>> {
     Ok(styles)
 }
 
+/// Styles for each of these images, fetched in one query
+#[cfg(feature = "ssr")]
+pub async fn get_styles_for_images(
+    pool: &PgPool,
+    image_ids: &[i32],
+) -> DbResult<std::collections::HashMap<i32, Vec<Style>>> {
+    let style_rows = sqlx::query(
+        "SELECT ais.artists_images_id, s.id, s.name
+         FROM styles s
+         JOIN artists_images_styles ais ON s.id = ais.style_id
+         WHERE ais.artists_images_id = ANY($1)",
+    )
+    .bind(image_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut styles_by_image: std::collections::HashMap<i32, Vec<Style>> =
+        std::collections::HashMap::new();
+    for row in style_rows {
+        let image_id = row.try_get::<i64, _>("artists_images_id").unwrap_or(0) as i32;
+        styles_by_image.entry(image_id).or_default().push(Style {
+            id: row.try_get::<i64, _>("id").unwrap_or(0) as i32,
+            name: row.get("name"),
+        });
+    }
+
+    Ok(styles_by_image)
+}
+
 #[cfg(feature = "ssr")]
 pub async fn get_artist_images_with_styles(
     artist_id: i32,
@@ -280,47 +345,33 @@ pub async fn get_artist_images_with_styles(
     let image_rows = sqlx::query(
         "SELECT id, short_code, artist_id, post_date
          FROM artists_images
-         WHERE artist_id = $1",
+         WHERE artist_id = $1 AND removed_at IS NULL",
     )
     .bind(artist_id)
     .fetch_all(pool)
     .await?;
 
-    let mut result = Vec::new();
-
-    // For each image, get its styles
-    for image_row in image_rows {
-        let img = ArtistImage {
+    let images: Vec<ArtistImage> = image_rows
+        .into_iter()
+        .map(|image_row| ArtistImage {
             id: image_row.try_get::<i64, _>("id").unwrap_or(0) as i32,
             short_code: image_row.get("short_code"),
             artist_id: image_row.try_get::<i64, _>("artist_id").unwrap_or(0) as i32,
             post_date: image_row.try_get("post_date").ok(),
             validated: image_row.try_get("validated").ok(),
-        };
-        let img_id = img.id;
-
-        let style_rows = sqlx::query(
-            "SELECT s.id, s.name
-             FROM styles s
-             JOIN artists_images_styles ais ON s.id = ais.style_id
-             WHERE ais.artists_images_id = $1",
-        )
-        .bind(img_id)
-        .fetch_all(pool)
-        .await?;
-
-        let styles: Vec<Style> = style_rows
-            .into_iter()
-            .map(|row| Style {
-                id: row.try_get::<i64, _>("id").unwrap_or(0) as i32,
-                name: row.get("name"),
-            })
-            .collect();
+        })
+        .collect();
 
-        result.push((img, styles));
-    }
+    let image_ids: Vec<i32> = images.iter().map(|image| image.id).collect();
+    let mut styles_by_image = get_styles_for_images(pool, &image_ids).await?;
 
-    Ok(result)
+    Ok(images
+        .into_iter()
+        .map(|image| {
+            let styles = styles_by_image.remove(&image.id).unwrap_or_default();
+            (image, styles)
+        })
+        .collect())
 }
 
 #[cfg(feature = "ssr")]
@@ -353,7 +404,7 @@ pub async fn get_artists_by_location(location_id: i32) -> DbResult<Vec<Artist>>
     let pool = crate::db::pool::get_pool();
 
     let rows = sqlx::query(
-        "SELECT a.id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted
+        "SELECT a.id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted, a.experience_tier
          FROM artists a
          JOIN locations l ON a.location_id = l.id
          WHERE a.location_id = $1
@@ -384,6 +435,7 @@ pub async fn get_artists_by_location(location_id: i32) -> DbResult<Vec<Artist>>
                 .ok()
                 .map(|v| v as i32),
             shop_validated: row.try_get("shop_validated").ok(),
+            experience_tier: row.try_get("experience_tier").ok().flatten(),
         })
         .collect();
 
@@ -431,13 +483,14 @@ pub async fn get_all_images_with_styles_by_location(
     // Build query with conditional LEFT JOIN for user favorites
     let (query, has_user_id) = if let Some(uid) = user_id {
         (
-            format!("SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted,
+            format!("SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted, a.experience_tier,
                     CASE WHEN uf.id IS NOT NULL THEN TRUE ELSE FALSE END as is_favorited
              FROM artists_images ai
              JOIN artists a ON ai.artist_id = a.id
              JOIN locations l ON a.location_id = l.id
              LEFT JOIN user_favorites uf ON ai.id = uf.artists_images_id AND uf.user_id = {}
              WHERE a.location_id = $1
+             AND ai.removed_at IS NULL AND ai.hidden_at IS NULL
              AND (l.is_person IS NULL OR l.is_person = 0)
              AND a.name IS NOT NULL
              AND a.name != ''", uid),
@@ -445,12 +498,13 @@ pub async fn get_all_images_with_styles_by_location(
         )
     } else {
         (
-            "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted,
+            "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted, a.experience_tier,
                     FALSE as is_favorited
              FROM artists_images ai
              JOIN artists a ON ai.artist_id = a.id
              JOIN locations l ON a.location_id = l.id
              WHERE a.location_id = $1
+             AND ai.removed_at IS NULL AND ai.hidden_at IS NULL
              AND (l.is_person IS NULL OR l.is_person = 0)
              AND a.name IS NOT NULL
              AND a.name != ''".to_string(),
@@ -463,9 +517,7 @@ pub async fn get_all_images_with_styles_by_location(
         .fetch_all(pool)
         .await?;
 
-    let mut result = Vec::new();
-
-    // For each image, get its styles
+    let mut rows = Vec::new();
     for image_row in image_rows {
         let image = ArtistImage {
             id: image_row.try_get::<i64, _>("id").unwrap_or(0) as i32,
@@ -492,61 +544,23 @@ pub async fn get_all_images_with_styles_by_location(
                 .ok()
                 .map(|v| v as i32),
             shop_validated: image_row.try_get("shop_validated").ok(),
+            experience_tier: image_row.try_get("experience_tier").ok().flatten(),
         };
 
         let is_favorited: bool = image_row.get("is_favorited");
-        let img_id = image.id;
-
-        let style_rows = sqlx::query(
-            "SELECT s.id, s.name
-             FROM styles s
-             JOIN artists_images_styles ais ON s.id = ais.style_id
-             WHERE ais.artists_images_id = $1",
-        )
-        .bind(img_id)
-        .fetch_all(pool)
-        .await?;
-
-        let styles: Vec<Style> = style_rows
-            .into_iter()
-            .map(|row| Style {
-                id: row.try_get::<i64, _>("id").unwrap_or(0) as i32,
-                name: row.get("name"),
-            })
-            .collect();
-
-        result.push((image, styles, artist, is_favorited));
+        rows.push((image, artist, is_favorited));
     }
 
-    Ok(result)
-}
-
-#[cfg(feature = "ssr")]
-pub async fn save_quiz_session(
-    style_preference: String,
-    body_placement: String,
-    pain_tolerance: i32,
-    budget_min: f64,
-    budget_max: f64,
-    vibe_preference: String,
-) -> DbResult<i64> {
-    let pool = crate::db::pool::get_pool();
+    let image_ids: Vec<i32> = rows.iter().map(|(image, _, _)| image.id).collect();
+    let mut styles_by_image = get_styles_for_images(pool, &image_ids).await?;
 
-    let row = sqlx::query(
-        "INSERT INTO client_quiz_sessions (style_preference, body_placement, pain_tolerance, budget_min, budget_max, vibe_preference)
-         VALUES ($1, $2, $3, $4, $5, $6)
-         RETURNING id",
-    )
-    .bind(style_preference)
-    .bind(body_placement)
-    .bind(pain_tolerance)
-    .bind(budget_min)
-    .bind(budget_max)
-    .bind(vibe_preference)
-    .fetch_one(pool)
-    .await?;
-
-    Ok(row.get("id"))
+    Ok(rows
+        .into_iter()
+        .map(|(image, artist, is_favorited)| {
+            let styles = styles_by_image.remove(&image.id).unwrap_or_default();
+            (image, styles, artist, is_favorited)
+        })
+        .collect())
 }
 
 #[cfg(feature = "ssr")]
@@ -565,7 +579,8 @@ pub async fn get_location_stats_for_city(
          LEFT JOIN artists a ON l.id = a.location_id
          LEFT JOIN artists_styles ast ON a.id = ast.artist_id
          LEFT JOIN styles s ON ast.style_id = s.id
-         WHERE l.city = $1 AND l.state = $2
+         JOIN cities c ON c.id = l.city_id
+         WHERE c.city = $1 AND c.state_name = $2
          AND (l.is_person IS NULL OR l.is_person = 0)",
     )
     .bind(city)
@@ -680,7 +695,10 @@ pub async fn get_styles_by_location(
             let placeholders: Vec<String> = (0..city_list.len())
                 .map(|i| format!("${}", bind_idx + i))
                 .collect();
-            where_clauses.push(format!("l.city IN ({})", placeholders.join(",")));
+            where_clauses.push(format!(
+                "l.city_id IN (SELECT c.id FROM cities c WHERE c.city IN ({}))",
+                placeholders.join(",")
+            ));
         }
     }
 
@@ -703,7 +721,7 @@ pub async fn get_styles_by_location(
             COUNT(DISTINCT ai.id) as image_count
          FROM styles s
          {} JOIN artists_images_styles ais ON s.id = ais.style_id
-         {} JOIN artists_images ai ON ais.artists_images_id = ai.id
+         {} JOIN artists_images ai ON ais.artists_images_id = ai.id AND ai.removed_at IS NULL
          {} JOIN artists a ON ai.artist_id = a.id
          {} JOIN locations l ON a.location_id = l.id
          {}
@@ -760,90 +778,93 @@ pub async fn query_locations_with_details(
     city: String,
     bounds: MapBounds,
     style_filter: Option<Vec<i32>>,
+    radius: Option<RadiusSearch>,
+    experience_tiers: Option<Vec<String>>,
 ) -> DbResult<Vec<crate::server::EnhancedLocationInfo>> {
     let pool = crate::db::pool::get_pool();
 
-    // Build the query based on whether we have style filters
-    let (query, style_json) = if let Some(ref styles) = style_filter {
-        if !styles.is_empty() {
-            let json = serde_json::to_value(styles).unwrap();
-            (
-                "SELECT DISTINCT
-                    l.id, l.name, l.lat, l.long, l.city, l.county, l.state,
-                    l.country_code, l.postal_code, l.is_open, l.address,
-                    l.category, l.website_uri, l._id,
-                    COUNT(DISTINCT a.id) as artist_count,
-                    CASE WHEN COUNT(DISTINCT a.id) > 0 THEN 1 ELSE 0 END as has_artists,
-                    COUNT(DISTINCT ai.id) as artist_images_count
-                 FROM locations l
-                 LEFT JOIN artists a ON l.id = a.location_id
-                 LEFT JOIN artists_images ai ON a.id = ai.artist_id
-                 LEFT JOIN artists_styles ast ON a.id = ast.artist_id
-                 WHERE l.lat BETWEEN $1 AND $2
-                 AND l.long BETWEEN $3 AND $4
-                 AND (l.is_person IS NULL OR l.is_person = 0)
-                 AND ast.style_id = ANY($5::int[])
-                 GROUP BY l.id, l.name, l.lat, l.long, l.city, l.county, l.state, l.country_code, l.postal_code, l.is_open, l.address, l.category, l.website_uri, l._id",
-                Some(json)
-            )
-        } else {
+    // A radius search prefilters on the circle's bounding box
+    let bounds = radius.as_ref().map(RadiusSearch::bounds).unwrap_or(bounds);
+    let styles = style_filter.unwrap_or_default();
+    let tiers = experience_tiers.unwrap_or_default();
+
+    let mut next_bind = 5;
+    let (style_join, style_clause) = if styles.is_empty() {
+        ("", String::new())
+    } else {
+        let clause = format!("AND ast.style_id = ANY(${}::int[])", next_bind);
+        next_bind += 1;
+        ("LEFT JOIN artists_styles ast ON a.id = ast.artist_id", clause)
+    };
+    // Only shops with an artist in one of the tiers
+    let tier_clause = if tiers.is_empty() {
+        String::new()
+    } else {
+        let clause = format!("AND a.experience_tier = ANY(${}::text[])", next_bind);
+        next_bind += 1;
+        clause
+    };
+    let (distance_column, radius_clause, order) = match &radius {
+        Some(_) => {
+            let radius_sql = crate::db::geo::radius_sql(pool, next_bind).await?;
             (
-                "SELECT
-                    l.id, l.name, l.lat, l.long, l.city, l.county, l.state,
-                    l.country_code, l.postal_code, l.is_open, l.address,
-                    l.category, l.website_uri, l._id,
-                    COUNT(DISTINCT a.id) as artist_count,
-                    CASE WHEN COUNT(DISTINCT a.id) > 0 THEN 1 ELSE 0 END as has_artists,
-                    COUNT(DISTINCT ai.id) as artist_images_count
-                 FROM locations l
-                 LEFT JOIN artists a ON l.id = a.location_id
-                 LEFT JOIN artists_images ai ON a.id = ai.artist_id
-                 WHERE l.lat BETWEEN $1 AND $2
-                 AND l.long BETWEEN $3 AND $4
-                 AND (l.is_person IS NULL OR l.is_person = 0)
-                 GROUP BY l.id, l.name, l.lat, l.long, l.city, l.county, l.state, l.country_code, l.postal_code, l.is_open, l.address, l.category, l.website_uri, l._id",
-                None
+                radius_sql.distance,
+                format!("AND {}", radius_sql.filter),
+                "ORDER BY distance_miles",
             )
         }
-    } else {
-        (
-            "SELECT
-                l.id, l.name, l.lat, l.long, l.city, l.county, l.state,
-                l.country_code, l.postal_code, l.is_open, l.address,
-                l.category, l.website_uri, l._id,
-                COUNT(DISTINCT a.id) as artist_count,
-                CASE WHEN COUNT(DISTINCT a.id) > 0 THEN 1 ELSE 0 END as has_artists,
-                COUNT(DISTINCT ai.id) as artist_images_count
-             FROM locations l
-             LEFT JOIN artists a ON l.id = a.location_id
-             LEFT JOIN artists_images ai ON a.id = ai.artist_id
-             WHERE l.lat BETWEEN $1 AND $2
-             AND l.long BETWEEN $3 AND $4
-             AND (l.is_person IS NULL OR l.is_person = 0)
-             GROUP BY l.id, l.name, l.lat, l.long, l.city, l.county, l.state, l.country_code, l.postal_code, l.is_open, l.address, l.category, l.website_uri, l._id",
-            None
-        )
+        None => ("NULL::float8".to_string(), String::new(), ""),
     };
 
-    let location_rows = if let Some(json) = style_json {
-        let styles_vec: Vec<i32> = serde_json::from_value(json).unwrap();
-        sqlx::query(query)
-            .bind(bounds.south_west.lat)
-            .bind(bounds.north_east.lat)
-            .bind(bounds.south_west.long)
-            .bind(bounds.north_east.long)
-            .bind(&styles_vec)
-            .fetch_all(pool)
-            .await?
-    } else {
-        sqlx::query(query)
-            .bind(bounds.south_west.lat)
-            .bind(bounds.north_east.lat)
-            .bind(bounds.south_west.long)
-            .bind(bounds.north_east.long)
-            .fetch_all(pool)
-            .await?
-    };
+    let query = format!(
+        "SELECT
+            l.id, l.name, l.lat, l.long, l.city, l.county, l.state,
+            l.country_code, l.postal_code, l.is_open, l.address,
+            l.category, l.website_uri, l._id,
+            COUNT(DISTINCT a.id) as artist_count,
+            CASE WHEN COUNT(DISTINCT a.id) > 0 THEN 1 ELSE 0 END as has_artists,
+            COUNT(DISTINCT ai.id) as artist_images_count,
+            {} as distance_miles
+         FROM locations l
+         LEFT JOIN {} p ON l.id = p.location_id
+         LEFT JOIN artists a ON a.id = p.artist_id
+         LEFT JOIN artists_images ai ON a.id = ai.artist_id AND ai.removed_at IS NULL
+         {}
+         WHERE l.lat BETWEEN $1 AND $2
+         AND l.long BETWEEN $3 AND $4
+         AND (l.is_person IS NULL OR l.is_person = 0)
+         {}
+         {}
+         {}
+         GROUP BY l.id, l.name, l.lat, l.long, l.city, l.county, l.state, l.country_code, l.postal_code, l.is_open, l.address, l.category, l.website_uri, l._id
+         {}",
+        distance_column,
+        ARTIST_PLACEMENTS,
+        style_join,
+        style_clause,
+        tier_clause,
+        radius_clause,
+        order
+    );
+
+    let mut locations_query = sqlx::query(&query)
+        .bind(bounds.south_west.lat)
+        .bind(bounds.north_east.lat)
+        .bind(bounds.south_west.long)
+        .bind(bounds.north_east.long);
+    if !styles.is_empty() {
+        locations_query = locations_query.bind(&styles);
+    }
+    if !tiers.is_empty() {
+        locations_query = locations_query.bind(&tiers);
+    }
+    if let Some(radius) = &radius {
+        locations_query = locations_query
+            .bind(radius.center.lat)
+            .bind(radius.center.long)
+            .bind(radius.miles);
+    }
+    let location_rows = locations_query.fetch_all(pool).await?;
 
     // Extract location IDs for batch queries
     let location_ids: Vec<i64> = location_rows
@@ -857,13 +878,14 @@ pub async fn query_locations_with_details(
     }
 
     // Batch query: Get image counts for all locations
-    let image_count_rows = sqlx::query(
-        "SELECT a.location_id, COUNT(DISTINCT ai.id) as cnt
-         FROM artists a
-         LEFT JOIN artists_images ai ON a.id = ai.artist_id
-         WHERE a.location_id = ANY($1)
-         GROUP BY a.location_id",
-    )
+    let image_count_rows = sqlx::query(&format!(
+        "SELECT p.location_id, COUNT(DISTINCT ai.id) as cnt
+         FROM {} p
+         LEFT JOIN artists_images ai ON p.artist_id = ai.artist_id AND ai.removed_at IS NULL
+         WHERE p.location_id = ANY($1)
+         GROUP BY p.location_id",
+        ARTIST_PLACEMENTS
+    ))
     .bind(&location_ids)
     .fetch_all(pool)
     .await?;
@@ -876,14 +898,15 @@ pub async fn query_locations_with_details(
     }
 
     // Batch query: Get styles for all locations
-    let style_rows = sqlx::query(
-        "SELECT DISTINCT a.location_id, s.name
+    let style_rows = sqlx::query(&format!(
+        "SELECT DISTINCT p.location_id, s.name
          FROM styles s
          JOIN artists_styles ast ON s.id = ast.style_id
-         JOIN artists a ON ast.artist_id = a.id
-         WHERE a.location_id = ANY($1)
-         ORDER BY a.location_id, s.name",
-    )
+         JOIN {} p ON ast.artist_id = p.artist_id
+         WHERE p.location_id = ANY($1)
+         ORDER BY p.location_id, s.name",
+        ARTIST_PLACEMENTS
+    ))
     .bind(&location_ids)
     .fetch_all(pool)
     .await?;
@@ -899,29 +922,37 @@ pub async fn query_locations_with_details(
             .push(style_name);
     }
 
-    // Batch query: Get top 4 artists with their details for all locations
-    let artist_rows = sqlx::query(
+    // Batch query: Get top 4 artists with their details for all locations,
+    // those in the filtered tiers first
+    let artist_rows = sqlx::query(&format!(
         "WITH ranked_artists AS (
-             SELECT a.id, a.name, a.location_id,
+             SELECT a.id, a.name, p.location_id, a.experience_tier, p.guest_until,
                     (SELECT ai.short_code
                      FROM artists_images ai
-                     WHERE ai.artist_id = a.id
+                     WHERE ai.artist_id = a.id AND ai.removed_at IS NULL
                      LIMIT 1) as image_url,
                     (SELECT s.name
                      FROM styles s
                      JOIN artists_styles ast ON s.id = ast.style_id
                      WHERE ast.artist_id = a.id
                      LIMIT 1) as primary_style,
-                    ROW_NUMBER() OVER (PARTITION BY a.location_id ORDER BY a.name) as rn
-             FROM artists a
-             WHERE a.location_id = ANY($1)
+                    ROW_NUMBER() OVER (
+                        PARTITION BY p.location_id
+                        ORDER BY (a.experience_tier = ANY($2::text[])) IS TRUE DESC, a.name
+                    ) as rn
+             FROM {} p
+             JOIN artists a ON a.id = p.artist_id
+             WHERE p.location_id = ANY($1)
          )
-         SELECT id, name, location_id, image_url, primary_style
+         SELECT id, name, location_id, image_url, primary_style, experience_tier,
+                TO_CHAR(guest_until, 'YYYY-MM-DD') as guest_until
          FROM ranked_artists
          WHERE rn <= 4
          ORDER BY location_id, rn",
-    )
+        ARTIST_PLACEMENTS
+    ))
     .bind(&location_ids)
+    .bind(&tiers)
     .fetch_all(pool)
     .await?;
 
@@ -934,6 +965,8 @@ pub async fn query_locations_with_details(
             artist_name: row.get("name"),
             image_url: row.try_get("image_url").ok().flatten(),
             primary_style: row.try_get("primary_style").ok().flatten(),
+            experience_tier: row.try_get("experience_tier").ok().flatten(),
+            guest_until: row.get("guest_until"),
         };
         artists_map
             .entry(loc_id)
@@ -941,6 +974,10 @@ pub async fn query_locations_with_details(
             .push(artist);
     }
 
+    // Batch query: Get the price range quoted by each location's artists
+    let price_ranges =
+        crate::db::pricing_repository::get_location_price_ranges(&location_ids).await?;
+
     // Build results from location rows and batch query results
     let mut result = Vec::new();
     for location_row in location_rows {
@@ -970,12 +1007,17 @@ pub async fn query_locations_with_details(
             _id: location_row.get("_id"),
             has_artists: Some(has_artists_val == 1),
             artist_images_count: Some(artist_images_count as i32),
+            distance_miles: location_row.get("distance_miles"),
         };
 
         let artist_count: i64 = location_row.get("artist_count");
         let image_count = image_counts.get(&location_id).copied().unwrap_or(0);
         let styles = styles_map.get(&location_id).cloned().unwrap_or_default();
         let artists = artists_map.get(&location_id).cloned().unwrap_or_default();
+        let (min_price, max_price) = price_ranges
+            .get(&location_id)
+            .copied()
+            .unwrap_or((None, None));
 
         // Limit styles to 5
         let styles: Vec<String> = styles.into_iter().take(5).collect();
@@ -986,53 +1028,285 @@ pub async fn query_locations_with_details(
             image_count: image_count as i32,
             styles,
             artists,
-            min_price: None,
-            max_price: None,
+            min_price,
+            max_price,
+            pinned_position: None,
         });
     }
 
+    let pins =
+        crate::db::pinning_repository::get_active_pins(pool, "location", &city, &state).await?;
+    crate::db::pinning_repository::apply_pins(
+        &mut result,
+        &pins,
+        |info| info.location.id as i64,
+        |info, position| info.pinned_position = Some(position),
+    );
+
     Ok(result)
 }
 
+/// Matches returned by `query_matched_artists`
+#[cfg(feature = "ssr")]
+const MATCHES_RETURNED: usize = 10;
+
+/// Candidates fetched before price fit and tie-breaking are applied, so
+/// artists just below the cut on the SQL-side factors can still make it
+#[cfg(feature = "ssr")]
+const MATCH_CANDIDATES: usize = 30;
+
+/// Artist matching, scored by `utils::matching`. Style overlap, distance,
+/// popularity and recency are scored in SQL and rank the candidates; price
+/// fit needs the artist's quote for the requested styles, so it's added in
+/// Rust before the final ordering.
 #[cfg(feature = "ssr")]
 pub async fn query_matched_artists(
-    style_preferences: Vec<String>,
+    style_filter: StyleFilter<String>,
     location: String,
     price_range: Option<(f64, f64)>,
 ) -> DbResult<Vec<crate::server::MatchedArtist>> {
+    use crate::db::style_merge_repository::resolve_style_aliases;
+    use crate::utils::matching::{
+        MatchFactor, MatchWeights, MAX_DISTANCE_MILES, SAVES_FOR_FULL_RATING, STALE_WORK_DAYS,
+    };
+    use crate::utils::{experience, pricing};
+
     let pool = crate::db::pool::get_pool();
+    let weights = MatchWeights::get();
 
-    let rows = sqlx::query(
-        "SELECT DISTINCT
-            a.id,
-            a.name,
-            l.city,
-            l.state,
-            l.name as location_name,
-            a.years_experience,
-            COUNT(DISTINCT ai.id) as image_count
-        FROM artists a
-        LEFT JOIN locations l ON a.location_id = l.id
-        LEFT JOIN artists_images ai ON a.id = ai.artist_id
-        WHERE (l.is_person IS NULL OR l.is_person = 0)
-        AND a.name IS NOT NULL
-        AND a.name != ''
-        GROUP BY a.id, a.name, l.city, l.state, l.name, a.years_experience
-        ORDER BY image_count DESC, a.name ASC
-        LIMIT 10",
-    )
-    .fetch_all(pool)
-    .await?;
+    // Styles that were merged away may still arrive from saved quiz answers
+    let style_filter = StyleFilter {
+        must_have: resolve_style_aliases(pool, style_filter.must_have).await?,
+        nice_to_have: resolve_style_aliases(pool, style_filter.nice_to_have).await?,
+        mode: style_filter.mode,
+    }
+    .map(|name| name.to_lowercase())
+    .normalized();
+
+    // Every requested style counts towards the style overlap score
+    let style_preferences = style_preferences(&style_filter);
+
+    // Resolve the client's location so distance can be scored per match
+    let client_coords = if location.trim().is_empty() {
+        None
+    } else {
+        get_city_coordinates(location.trim().to_lowercase())
+            .await
+            .ok()
+    };
+
+    // Curated pins for the client's city always make the candidate list; with
+    // no location only global pins apply
+    let (pin_city, pin_state) = client_coords
+        .as_ref()
+        .map(|coords| (coords.city.as_str(), coords.state.as_str()))
+        .unwrap_or(("", ""));
+    let pins =
+        crate::db::pinning_repository::get_active_pins(pool, "artist", pin_city, pin_state).await?;
+    let pinned_ids: Vec<i64> = pins.iter().map(|(id, _)| *id).collect();
+
+    // Must-have styles restrict candidates to artists whose portfolio covers
+    // them; every requested style the artist has counts towards style overlap
+    let must_have = ARTIST_STYLE_NAMES.clauses(
+        "a.id",
+        StyleFilter {
+            nice_to_have: Vec::new(),
+            ..style_filter
+        },
+        2,
+    );
+    let style_clause = must_have.and_filter();
+    let mut next_bind = must_have.next_bind;
+    let (matched_styles, style_score) = if style_preferences.is_empty() {
+        ("ARRAY[]::text[]".to_string(), "NULL::float8".to_string())
+    } else {
+        let matched = ARTIST_STYLE_NAMES.matched_sql("a.id", next_bind);
+        let score = format!(
+            "cardinality(c.matched_styles)::float8 / cardinality(${}::text[])",
+            next_bind
+        );
+        next_bind += 1;
+        (matched, score)
+    };
+
+    // Artists without coordinates get no distance points when the client gave
+    // a location
+    let (distance, distance_score) = match client_coords {
+        Some(_) => {
+            let radius = crate::db::geo::radius_sql(pool, next_bind).await?;
+            (
+                radius.distance,
+                format!(
+                    "COALESCE(GREATEST(0, 1 - c.distance_miles / {}), 0)",
+                    MAX_DISTANCE_MILES
+                ),
+            )
+        }
+        None => ("NULL::float8".to_string(), "NULL::float8".to_string()),
+    };
+
+    // Clients on a tight budget see apprentices and junior artists ahead of
+    // equally matched senior artists
+    let budget_constrained = experience::is_budget_constrained(price_range);
+    let tier_rank = if budget_constrained {
+        experience::budget_rank_sql("a.experience_tier")
+    } else {
+        "0".to_string()
+    };
+
+    // `post_date` is a unix timestamp in seconds
+    let query = format!(
+        "WITH candidates AS (
+            SELECT
+                a.id,
+                a.name,
+                l.city,
+                l.state,
+                l.name as location_name,
+                a.years_experience,
+                a.experience_tier,
+                (SELECT COUNT(*) FROM artists_images ai
+                 WHERE ai.artist_id = a.id AND ai.removed_at IS NULL) as image_count,
+                (SELECT MAX(ai.post_date) FROM artists_images ai
+                 WHERE ai.artist_id = a.id AND ai.removed_at IS NULL) as last_post_date,
+                (SELECT COUNT(DISTINCT uf.user_id) FROM user_favorites uf
+                 JOIN artists_images ai ON ai.id = uf.artists_images_id
+                 WHERE ai.artist_id = a.id AND ai.removed_at IS NULL) as saves,
+                {matched_styles} as matched_styles,
+                {distance} as distance_miles,
+                {tier_rank} as tier_rank
+            FROM artists a
+            LEFT JOIN locations l ON a.location_id = l.id
+            WHERE (l.is_person IS NULL OR l.is_person = 0)
+            AND a.name IS NOT NULL
+            AND a.name != ''
+            {style_clause}
+        ),
+        scored AS (
+            SELECT
+                c.*,
+                {style_score} as style_score,
+                {distance_score} as distance_score,
+                LEAST(1, c.saves::float8 / {saves_for_full}) as rating_score,
+                COALESCE(GREATEST(0, 1 - (EXTRACT(EPOCH FROM NOW())::float8 - c.last_post_date)
+                    / ({stale_days} * 86400)), 0) as recency_score
+            FROM candidates c
+        )
+        SELECT
+            s.*,
+            ({w_style} * COALESCE(s.style_score, 0)
+                + {w_distance} * COALESCE(s.distance_score, 0)
+                + {w_rating} * s.rating_score
+                + {w_recency} * s.recency_score) as sql_score
+        FROM scored s
+        ORDER BY (s.id = ANY($1)) DESC, sql_score DESC, s.tier_rank DESC, s.image_count DESC, s.name ASC
+        LIMIT {limit}",
+        saves_for_full = SAVES_FOR_FULL_RATING,
+        stale_days = STALE_WORK_DAYS,
+        w_style = weights.style_overlap,
+        w_distance = weights.distance,
+        w_rating = weights.rating,
+        w_recency = weights.recency,
+        limit = MATCH_CANDIDATES,
+    );
+
+    let mut matched = sqlx::query(&query).bind(&pinned_ids);
+    for names in &must_have.binds {
+        matched = matched.bind(names);
+    }
+    if !style_preferences.is_empty() {
+        matched = matched.bind(&style_preferences);
+    }
+    if let Some(coords) = &client_coords {
+        matched = matched.bind(coords.lat).bind(coords.long);
+    }
+    let rows = matched.fetch_all(pool).await?;
+
+    let candidate_ids: Vec<i32> = rows
+        .iter()
+        .map(|row| row.get::<i64, _>("id") as i32)
+        .collect();
+    let artist_pricing =
+        crate::db::pricing_repository::get_pricing_for_artists(&candidate_ids).await?;
+    let completeness =
+        crate::db::completeness_repository::get_completeness_scores(&candidate_ids).await?;
+
+    struct Candidate {
+        row: sqlx::postgres::PgRow,
+        match_score: i32,
+        components: Vec<crate::server::ScoreComponent>,
+        price: (Option<f64>, Option<f64>),
+        within_budget: Option<bool>,
+        budget_tier: Option<String>,
+    }
+
+    let mut candidates: Vec<Candidate> = rows
+        .into_iter()
+        .map(|row| {
+            let artist_id: i64 = row.get("id");
+            let experience_tier: Option<String> = row.try_get("experience_tier").ok().flatten();
+
+            // Quote the requested styles where the artist priced them
+            let price = artist_pricing
+                .get(&(artist_id as i32))
+                .map(|quote| pricing::price_range(quote, &style_preferences))
+                .unwrap_or((None, None));
+            let within_budget =
+                price_range.and_then(|budget| pricing::within_budget(price, budget));
+
+            // The tier only counts for clients on a tight budget, and stands
+            // in for price fit when the artist hasn't set any pricing
+            let budget_tier = experience_tier.filter(|tier| {
+                budget_constrained && experience::is_lower_priced(Some(tier.as_str()))
+            });
+            let price_fit = price_range
+                .and_then(|budget| pricing::budget_fit(price, budget))
+                .or(budget_tier.as_ref().map(|_| 1.0));
+
+            let (match_score, components) = weights.score(&[
+                (MatchFactor::StyleOverlap, row.get("style_score")),
+                (MatchFactor::Distance, row.get("distance_score")),
+                (MatchFactor::PriceFit, price_fit),
+                (MatchFactor::Rating, Some(row.get("rating_score"))),
+                (MatchFactor::Recency, Some(row.get("recency_score"))),
+            ]);
+
+            Candidate {
+                row,
+                match_score,
+                components,
+                price,
+                within_budget,
+                budget_tier,
+            }
+        })
+        .collect();
+
+    // Profile completeness breaks ties between otherwise equal candidates
+    candidates.sort_by_cached_key(|candidate| {
+        let artist_id: i64 = candidate.row.get("id");
+        (
+            std::cmp::Reverse(pinned_ids.contains(&artist_id)),
+            std::cmp::Reverse(candidate.match_score),
+            std::cmp::Reverse(candidate.row.get::<i32, _>("tier_rank")),
+            std::cmp::Reverse(candidate.row.get::<i64, _>("image_count")),
+            std::cmp::Reverse(completeness.get(&(artist_id as i32)).copied().unwrap_or(0)),
+            candidate.row.get::<String, _>("name"),
+        )
+    });
+    candidates.truncate(MATCHES_RETURNED);
 
     let mut artists = Vec::new();
 
-    for row in rows {
+    for candidate in candidates {
+        let row = &candidate.row;
         let artist_id: i64 = row.get("id");
         let artist_name: String = row.get("name");
         let city: Option<String> = row.try_get("city").ok();
         let state: Option<String> = row.try_get("state").ok();
         let location_name: Option<String> = row.try_get("location_name").ok();
         let years_experience: Option<i32> = row.try_get("years_experience").ok();
+        let experience_tier: Option<String> = row.try_get("experience_tier").ok().flatten();
         let image_count: i64 = row.get("image_count");
 
         // Get styles for this artist
@@ -1045,8 +1319,20 @@ pub async fn query_matched_artists(
             .await
             .unwrap_or_default();
 
-        // Calculate match score based on style overlap and image count
-        let match_score = calculate_match_score(&styles, &style_preferences, image_count as i32);
+        // Matched styles come back lower-cased; show them as the artist lists them
+        let matched_styles = row
+            .get::<Vec<String>, _>("matched_styles")
+            .into_iter()
+            .map(|matched| {
+                styles
+                    .iter()
+                    .find(|style| style.eq_ignore_ascii_case(&matched))
+                    .cloned()
+                    .unwrap_or(matched)
+            })
+            .collect();
+
+        let (min_price, max_price) = candidate.price;
 
         artists.push(crate::server::MatchedArtist {
             id: artist_id,
@@ -1055,18 +1341,48 @@ pub async fn query_matched_artists(
             portfolio_images,
             avatar_url: None,
             years_experience,
-            min_price: Some(150.0),
-            max_price: Some(400.0),
+            min_price,
+            max_price,
             avg_rating: 4.2,
             image_count: image_count as i32,
-            match_score,
+            match_score: candidate.match_score,
+            explanation: crate::server::MatchExplanation {
+                matched_styles,
+                distance_miles: row.get("distance_miles"),
+                within_budget: candidate.within_budget,
+                components: candidate.components,
+                pinned_position: None,
+                budget_tier: candidate.budget_tier,
+            },
             city: city.unwrap_or_else(|| "Unknown".to_string()),
             state: state.unwrap_or_else(|| "Unknown".to_string()),
             location_name: location_name.unwrap_or_else(|| "Unknown Studio".to_string()),
             primary_style: styles.first().unwrap_or(&"Various".to_string()).clone(),
+            response_time_label: None,
+            experience_tier,
         });
     }
 
+    crate::db::pinning_repository::apply_pins(
+        &mut artists,
+        &pins,
+        |artist| artist.id,
+        |artist, position| artist.explanation.pinned_position = Some(position),
+    );
+
+    let artist_ids: Vec<i32> = artists.iter().map(|artist| artist.id as i32).collect();
+    let response_times = crate::db::response_time_repository::get_response_stats(
+        &artist_ids,
+        crate::utils::response_time::WINDOW_DAYS,
+    )
+    .await?;
+    for artist in artists.iter_mut() {
+        artist.response_time_label = response_times
+            .iter()
+            .find(|stats| stats.artist_id as i64 == artist.id)
+            .and_then(|stats| stats.label.clone());
+    }
+
     Ok(artists)
 }
 
@@ -1090,7 +1406,7 @@ async fn get_artist_styles_by_id(pool: &PgPool, artist_id: i64) -> DbResult<Vec<
 async fn get_artist_portfolio_images_by_id(pool: &PgPool, artist_id: i64) -> DbResult<Vec<String>> {
     let rows = sqlx::query(
         "SELECT short_code FROM artists_images
-         WHERE artist_id = $1
+         WHERE artist_id = $1 AND removed_at IS NULL
          ORDER BY id DESC
          LIMIT 4",
     )
@@ -1109,37 +1425,6 @@ async fn get_artist_portfolio_images_by_id(pool: &PgPool, artist_id: i64) -> DbR
     Ok(images)
 }
 
-#[cfg(feature = "ssr")]
-fn calculate_match_score(
-    artist_styles: &[String],
-    user_preferences: &[String],
-    image_count: i32,
-) -> i32 {
-    let mut score = 60; // Base score
-
-    // Add points for image count
-    score += std::cmp::min(20, image_count * 2);
-
-    // Add points for style matches
-    if !user_preferences.is_empty() {
-        let matches = artist_styles
-            .iter()
-            .filter(|style| {
-                user_preferences.iter().any(|pref| {
-                    style.to_lowercase().contains(&pref.to_lowercase())
-                        || pref.to_lowercase().contains(&style.to_lowercase())
-                })
-            })
-            .count();
-
-        score += (matches as f32 / user_preferences.len() as f32 * 20.0) as i32;
-    } else {
-        score += 10; // Slight bonus when no preferences (shows all artists)
-    }
-
-    score.max(50).min(95) // Ensure reasonable score range
-}
-
 #[cfg(feature = "ssr")]
 pub async fn get_coords_by_postal_code(postal_code: String) -> DbResult<CityCoords> {
     let pool = crate::db::pool::get_pool();
@@ -1215,6 +1500,7 @@ pub async fn get_location_with_artist_details(
         _id: location_row.get("_id"),
         has_artists: None,
         artist_images_count: None,
+        distance_miles: None,
     };
 
     // Get artists with their primary image and style
@@ -1222,13 +1508,14 @@ pub async fn get_location_with_artist_details(
         "SELECT DISTINCT a.id, a.name,
                (SELECT ai.short_code
                 FROM artists_images ai
-                WHERE ai.artist_id = a.id
+                WHERE ai.artist_id = a.id AND ai.removed_at IS NULL
                 LIMIT 1) as image_url,
                (SELECT s.name
                 FROM styles s
                 JOIN artists_styles ast ON s.id = ast.style_id
                 WHERE ast.artist_id = a.id
-                LIMIT 1) as primary_style
+                LIMIT 1) as primary_style,
+               a.experience_tier
         FROM artists a
         WHERE a.location_id = $1
         ORDER BY a.name
@@ -1245,6 +1532,8 @@ pub async fn get_location_with_artist_details(
             artist_name: row.get("name"),
             image_url: row.try_get("image_url").ok(),
             primary_style: row.try_get("primary_style").ok(),
+            experience_tier: row.try_get("experience_tier").ok().flatten(),
+            guest_until: None,
         })
         .collect();
 
@@ -1254,7 +1543,7 @@ pub async fn get_location_with_artist_details(
             COUNT(DISTINCT a.id) as artist_count,
             COUNT(DISTINCT ai.id) as image_count
         FROM artists a
-        LEFT JOIN artists_images ai ON a.id = ai.artist_id
+        LEFT JOIN artists_images ai ON a.id = ai.artist_id AND ai.removed_at IS NULL
         WHERE a.location_id = $1",
     )
     .bind(location_id)
@@ -1280,14 +1569,20 @@ pub async fn get_location_with_artist_details(
 
     let styles: Vec<String> = style_rows.into_iter().map(|row| row.get("name")).collect();
 
+    let (min_price, max_price) =
+        crate::db::pricing_repository::get_location_price_ranges(&[location_id as i64])
+            .await?
+            .remove(&(location_id as i64))
+            .unwrap_or((None, None));
+
     Ok(LocationDetailInfo {
         location,
         artist_count: artist_count as i32,
         image_count: image_count as i32,
         styles,
         artists,
-        min_price: None,
-        max_price: None,
+        min_price,
+        max_price,
         average_rating: None,
     })
 }
@@ -1373,53 +1668,19 @@ pub async fn get_artist_questionnaire(artist_id: i32) -> DbResult<ClientQuestion
 }
 
 // Artist Availability and Booking Conflict Functions
+
+/// Whether a booking at `requested_time` on `requested_date` is free of
+/// existing bookings and days the artist has blocked
 #[cfg(feature = "ssr")]
 pub async fn check_artist_availability(
     artist_id: i32,
-    requested_date: &str,
-    requested_time: &str,
+    requested_date: Date,
+    requested_time: Time,
 ) -> DbResult<bool> {
-    let pool = crate::db::pool::get_pool();
-
-    // Check for existing bookings at the requested time
-    let booking_row = sqlx::query(
-        "SELECT COUNT(*) as cnt
-        FROM bookings
-        WHERE artist_id = $1
-        AND booking_date = $2
-        AND status NOT IN ('cancelled', 'rejected')",
-    )
-    .bind(artist_id)
-    .bind(requested_date)
-    .fetch_one(pool)
-    .await?;
+    let date = requested_date.naive();
+    let schedule = crate::db::availability_repository::load_schedule(artist_id, date, date).await?;
 
-    let booking_conflicts: i64 = booking_row.get("cnt");
-
-    if booking_conflicts > 0 {
-        return Ok(false); // Time slot already booked
-    }
-
-    // Check for artist availability restrictions
-    let availability_row = sqlx::query(
-        "SELECT COUNT(*) as cnt
-        FROM artist_availability
-        WHERE artist_id = $1
-        AND (specific_date = $2 OR day_of_week = EXTRACT(DOW FROM $2::date)::text)
-        AND is_available = false",
-    )
-    .bind(artist_id)
-    .bind(requested_date)
-    .fetch_one(pool)
-    .await?;
-
-    let availability_blocks: i64 = availability_row.get("cnt");
-
-    if availability_blocks > 0 {
-        return Ok(false); // Time slot is blocked by artist
-    }
-
-    Ok(true) // Time slot appears to be available
+    Ok(!schedule.has_conflict(date, Some(requested_time.naive())))
 }
 
 #[cfg(feature = "ssr")]
@@ -1532,6 +1793,16 @@ pub async fn get_artist_id_from_user_id(user_id: i64) -> DbResult<Option<i32>> {
     Ok(result.and_then(|row| row.try_get("artist_id").ok()))
 }
 
+#[cfg(feature = "ssr")]
+pub async fn get_booking_artist_id(booking_id: i32) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT artist_id FROM booking_requests WHERE id = $1")
+        .bind(booking_id)
+        .fetch_optional(pool)
+        .await
+}
+
 #[cfg(feature = "ssr")]
 pub async fn delete_artist_question(artist_id: i32, question_id: i32) -> DbResult<()> {
     let pool = crate::db::pool::get_pool();
@@ -1601,16 +1872,42 @@ pub async fn get_booking_questionnaire_responses(
 }
 
 // Error Logging Functions
+
+/// Logs an error and counts it towards its group on the error dashboard
 #[cfg(feature = "ssr")]
 pub async fn log_error(error_data: CreateErrorLog) -> DbResult<i64> {
+    use crate::utils::error_triage;
+
     let pool = crate::db::pool::get_pool();
 
+    let stack = error_data.error_stack.as_deref();
+    let fingerprint =
+        error_triage::fingerprint(&error_data.error_type, &error_data.error_message, stack);
+    let top_frame = stack.and_then(error_triage::top_frame);
+
     let row = sqlx::query(
-        "INSERT INTO error_logs
-         (error_type, error_level, error_message, error_stack, url_path,
-          user_agent, user_id, session_id, request_headers, additional_context)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-         RETURNING id",
+        "WITH logged AS (
+            INSERT INTO error_logs
+            (error_type, error_level, error_message, error_stack, url_path,
+             user_agent, user_id, session_id, request_headers, additional_context, build_id,
+             fingerprint)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id
+         ),
+         grouped AS (
+            INSERT INTO error_groups (fingerprint, error_type, error_level, title, top_frame)
+            VALUES ($12, $1, $2, LEFT($3, 500), $13)
+            ON CONFLICT (fingerprint) DO UPDATE SET
+                error_level = EXCLUDED.error_level,
+                title = EXCLUDED.title,
+                last_seen = CURRENT_TIMESTAMP,
+                occurrences = error_groups.occurrences + 1,
+                status = CASE WHEN error_groups.status = 'resolved'
+                    THEN 'open' ELSE error_groups.status END,
+                reopened_at = CASE WHEN error_groups.status = 'resolved'
+                    THEN CURRENT_TIMESTAMP ELSE error_groups.reopened_at END
+         )
+         SELECT id FROM logged",
     )
     .bind(error_data.error_type)
     .bind(error_data.error_level)
@@ -1622,6 +1919,9 @@ pub async fn log_error(error_data: CreateErrorLog) -> DbResult<i64> {
     .bind(error_data.session_id)
     .bind(error_data.request_headers)
     .bind(error_data.additional_context)
+    .bind(error_data.build_id)
+    .bind(fingerprint)
+    .bind(top_frame)
     .fetch_one(pool)
     .await?;
 
@@ -1635,7 +1935,7 @@ pub async fn get_recent_errors(limit: i32) -> DbResult<Vec<ErrorLog>> {
     let rows = sqlx::query(
         "SELECT id, error_type, error_level, error_message, error_stack,
                url_path, user_agent, user_id, session_id, timestamp,
-               request_headers, additional_context
+               request_headers, additional_context, build_id, symbolicated_stack
         FROM error_logs
         ORDER BY timestamp DESC
         LIMIT $1",
@@ -1659,6 +1959,8 @@ pub async fn get_recent_errors(limit: i32) -> DbResult<Vec<ErrorLog>> {
             timestamp: row.get("timestamp"),
             request_headers: row.try_get("request_headers").ok(),
             additional_context: row.try_get("additional_context").ok(),
+            build_id: row.try_get("build_id").ok(),
+            symbolicated_stack: row.try_get("symbolicated_stack").ok(),
         })
         .collect();
 
@@ -1672,7 +1974,7 @@ pub async fn get_errors_by_type(error_type: String, limit: i32) -> DbResult<Vec<
     let rows = sqlx::query(
         "SELECT id, error_type, error_level, error_message, error_stack,
                url_path, user_agent, user_id, session_id, timestamp,
-               request_headers, additional_context
+               request_headers, additional_context, build_id, symbolicated_stack
         FROM error_logs
         WHERE error_type = $1
         ORDER BY timestamp DESC
@@ -1698,24 +2000,187 @@ pub async fn get_errors_by_type(error_type: String, limit: i32) -> DbResult<Vec<
             timestamp: row.get("timestamp"),
             request_headers: row.try_get("request_headers").ok(),
             additional_context: row.try_get("additional_context").ok(),
+            build_id: row.try_get("build_id").ok(),
+            symbolicated_stack: row.try_get("symbolicated_stack").ok(),
         })
         .collect();
 
     Ok(errors)
 }
 
-// Paginated and filtered image queries for shop pages
+/// The SQL a style filter adds to a query, built by [`StyleSource::clauses`]
 #[cfg(feature = "ssr")]
-pub async fn get_shop_images_paginated(
+#[derive(Debug, PartialEq)]
+pub(crate) struct StyleClauses<T> {
+    /// Predicate for the must-have styles, to AND into the WHERE clause
+    pub(crate) filter: Option<String>,
+    /// Rank by nice-to-have styles, to sort by descending
+    pub(crate) rank: Option<String>,
+    /// Style arrays to bind, in placeholder order
+    pub(crate) binds: Vec<Vec<T>>,
+    /// The first placeholder after the style arrays
+    pub(crate) next_bind: usize,
+}
+
+#[cfg(feature = "ssr")]
+impl<T> StyleClauses<T> {
+    /// `AND <filter>`, or nothing
+    pub(crate) fn and_filter(&self) -> String {
+        self.filter
+            .as_ref()
+            .map(|filter| format!("AND {}", filter))
+            .unwrap_or_default()
+    }
+
+    /// `<rank> DESC,` to lead an ORDER BY, or nothing
+    pub(crate) fn rank_order(&self) -> String {
+        self.rank
+            .as_ref()
+            .map(|rank| format!("{} DESC,", rank))
+            .unwrap_or_default()
+    }
+}
+
+/// Every style the filter mentions, must-have first, each once
+#[cfg(feature = "ssr")]
+pub(crate) fn style_preferences<T: PartialEq + Clone>(filter: &StyleFilter<T>) -> Vec<T> {
+    let mut preferences: Vec<T> = Vec::new();
+    for style in filter.must_have.iter().chain(filter.nice_to_have.iter()) {
+        if !preferences.contains(style) {
+            preferences.push(style.clone());
+        }
+    }
+    preferences
+}
+
+/// Where a style filter finds the styles of the rows it filters: a FROM
+/// clause yielding `owner` ids with a comparable style `key`.
+#[cfg(feature = "ssr")]
+pub(crate) struct StyleSource {
+    owner: &'static str,
+    from: &'static str,
+    key: &'static str,
+    array_type: &'static str,
+}
+
+/// Image styles by style id
+#[cfg(feature = "ssr")]
+pub(crate) const IMAGE_STYLE_IDS: StyleSource = StyleSource {
+    owner: "fais.artists_images_id",
+    from: "artists_images_styles fais",
+    key: "fais.style_id",
+    array_type: "int[]",
+};
+
+/// Image styles by lower-cased style name
+#[cfg(feature = "ssr")]
+pub(crate) const IMAGE_STYLE_NAMES: StyleSource = StyleSource {
+    owner: "fais.artists_images_id",
+    from: "artists_images_styles fais JOIN styles fs ON fs.id = fais.style_id",
+    key: "LOWER(fs.name)",
+    array_type: "text[]",
+};
+
+/// Styles across all of an artist's images, by lower-cased style name
+#[cfg(feature = "ssr")]
+pub(crate) const ARTIST_STYLE_NAMES: StyleSource = StyleSource {
+    owner: "fai.artist_id",
+    from: "artists_images fai
+           JOIN artists_images_styles fais ON fais.artists_images_id = fai.id
+           JOIN styles fs ON fs.id = fais.style_id",
+    key: "LOWER(fs.name)",
+    array_type: "text[]",
+};
+
+#[cfg(feature = "ssr")]
+impl StyleSource {
+    /// Predicate keeping `subject` ids that have the must-have styles bound
+    /// at `$bind`. Both modes resolve through a semi-join on the style key,
+    /// so the (style, image) index does the work; `All` additionally groups
+    /// and requires every distinct style to be present.
+    pub(crate) fn filter_sql(&self, subject: &str, mode: StyleMatchMode, bind: usize) -> String {
+        let matching = format!(
+            "SELECT {owner} FROM {from} WHERE {key} = ANY(${bind}::{ty})",
+            owner = self.owner,
+            from = self.from,
+            key = self.key,
+            ty = self.array_type,
+        );
+
+        match mode {
+            StyleMatchMode::Any => format!("{} IN ({})", subject, matching),
+            StyleMatchMode::All => format!(
+                "{} IN ({} GROUP BY {} HAVING COUNT(DISTINCT {}) = cardinality(${}::{}))",
+                subject, matching, self.owner, self.key, bind, self.array_type
+            ),
+        }
+    }
+
+    /// The filter and rank for `filter` over `subject`, with its style
+    /// arrays bound from `$first_bind`. The filter is normalized first, so
+    /// duplicates don't break `All`'s count, and empty lists add nothing.
+    pub(crate) fn clauses<T: PartialEq>(
+        &self,
+        subject: &str,
+        filter: StyleFilter<T>,
+        first_bind: usize,
+    ) -> StyleClauses<T> {
+        let filter = filter.normalized();
+        let mut clauses = StyleClauses {
+            filter: None,
+            rank: None,
+            binds: Vec::new(),
+            next_bind: first_bind,
+        };
+        if !filter.must_have.is_empty() {
+            clauses.filter = Some(self.filter_sql(subject, filter.mode, clauses.next_bind));
+            clauses.binds.push(filter.must_have);
+            clauses.next_bind += 1;
+        }
+        if !filter.nice_to_have.is_empty() {
+            clauses.rank = Some(self.rank_sql(subject, clauses.next_bind));
+            clauses.binds.push(filter.nice_to_have);
+            clauses.next_bind += 1;
+        }
+        clauses
+    }
+
+    /// Number of the nice-to-have styles bound at `$bind` that `subject` has,
+    /// for ranking.
+    pub(crate) fn rank_sql(&self, subject: &str, bind: usize) -> String {
+        format!(
+            "(SELECT COUNT(DISTINCT {key}) FROM {from} WHERE {owner} = {subject} AND {key} = ANY(${bind}::{ty}))",
+            owner = self.owner,
+            from = self.from,
+            key = self.key,
+            ty = self.array_type,
+        )
+    }
+
+    /// The styles bound at `$bind` that `subject` has, as a `text[]` of keys
+    pub(crate) fn matched_sql(&self, subject: &str, bind: usize) -> String {
+        format!(
+            "ARRAY(SELECT DISTINCT {key} FROM {from} WHERE {owner} = {subject} AND {key} = ANY(${bind}::{ty}))",
+            owner = self.owner,
+            from = self.from,
+            key = self.key,
+            ty = self.array_type,
+        )
+    }
+}
+
+/// A page of shop images with their artists, without styles, and the total
+/// number of matching images
+#[cfg(feature = "ssr")]
+async fn shop_image_rows(
     location_id: i32,
-    style_ids: Option<Vec<i32>>,
+    style_filter: Option<StyleFilter<i32>>,
     page: i32,
     per_page: i32,
     user_id: Option<i64>,
-) -> DbResult<(Vec<(ArtistImage, Vec<Style>, Artist, bool)>, i32)> {
+) -> DbResult<(Vec<(ArtistImage, Artist, bool)>, i32)> {
     let pool = crate::db::pool::get_pool();
 
-    // Build the WHERE clause for style filtering
     // Build the favorites JOIN clause based on user_id
     let favorites_join = if let Some(uid) = user_id {
         format!(
@@ -1732,148 +2197,70 @@ pub async fn get_shop_images_paginated(
         "FALSE as is_favorited"
     };
 
-    let (count_query, data_query) = if let Some(ref ids) = style_ids {
-        if !ids.is_empty() {
-            (
-                format!(
-                    "SELECT COUNT(DISTINCT ai.id)
-                     FROM artists_images ai
-                     JOIN artists a ON ai.artist_id = a.id
-                     JOIN locations l ON a.location_id = l.id
-                     WHERE a.location_id = $1
-                     AND (l.is_person IS NULL OR l.is_person = 0)
-                     AND a.name IS NOT NULL
-                     AND a.name != ''
-                     AND ai.id IN (SELECT ais.artists_images_id FROM artists_images_styles ais WHERE ais.style_id = ANY($2::int[]))"
-                ),
-                format!(
-                    "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted,
-                            {}
-                     FROM artists_images ai
-                     JOIN artists a ON ai.artist_id = a.id
-                     JOIN locations l ON a.location_id = l.id
-                     {}
-                     WHERE a.location_id = $1
-                     AND (l.is_person IS NULL OR l.is_person = 0)
-                     AND a.name IS NOT NULL
-                     AND a.name != ''
-                     AND ai.id IN (SELECT ais.artists_images_id FROM artists_images_styles ais WHERE ais.style_id = ANY($2::int[]))
-                     ORDER BY ai.id DESC
-                     LIMIT $3 OFFSET $4",
-                     is_favorited_select,
-                     favorites_join
-                )
-            )
-        } else {
-            (
-                "SELECT COUNT(DISTINCT ai.id)
-                 FROM artists_images ai
-                 JOIN artists a ON ai.artist_id = a.id
-                 JOIN locations l ON a.location_id = l.id
-                 WHERE a.location_id = $1
-                 AND (l.is_person IS NULL OR l.is_person = 0)
-                 AND a.name IS NOT NULL
-                 AND a.name != ''".to_string(),
-                format!(
-                    "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted,
-                            {}
-                     FROM artists_images ai
-                     JOIN artists a ON ai.artist_id = a.id
-                     JOIN locations l ON a.location_id = l.id
-                     {}
-                     WHERE a.location_id = $1
-                     AND (l.is_person IS NULL OR l.is_person = 0)
-                     AND a.name IS NOT NULL
-                     AND a.name != ''
-                     ORDER BY ai.id DESC
-                     LIMIT $2 OFFSET $3",
-                     is_favorited_select,
-                     favorites_join
-                )
-            )
-        }
-    } else {
-        (
-            "SELECT COUNT(DISTINCT ai.id)
-             FROM artists_images ai
-             JOIN artists a ON ai.artist_id = a.id
-             JOIN locations l ON a.location_id = l.id
-             WHERE a.location_id = $1
-             AND (l.is_person IS NULL OR l.is_person = 0)
-             AND a.name IS NOT NULL
-             AND a.name != ''".to_string(),
-            format!(
-                "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted,
-                        {}
-                 FROM artists_images ai
-                 JOIN artists a ON ai.artist_id = a.id
-                 JOIN locations l ON a.location_id = l.id
-                 {}
-                 WHERE a.location_id = $1
-                 AND (l.is_person IS NULL OR l.is_person = 0)
-                 AND a.name IS NOT NULL
-                 AND a.name != ''
-                 ORDER BY ai.id DESC
-                 LIMIT $2 OFFSET $3",
-                 is_favorited_select,
-                 favorites_join
-            )
-        )
-    };
+    // Style filtering: must-have styles become a WHERE predicate, nice-to-have
+    // styles an ORDER BY rank. $1 is the location; style arrays follow.
+    let styles = IMAGE_STYLE_IDS.clauses(
+        "ai.id",
+        style_filter.unwrap_or_else(|| StyleFilter::any(Vec::new())),
+        2,
+    );
+    let style_clause = styles.and_filter();
+    let rank_order = styles.rank_order();
+    let next_bind = styles.next_bind;
+
+    let base_where = format!(
+        "WHERE a.location_id = $1
+         AND ai.removed_at IS NULL AND ai.hidden_at IS NULL
+         AND (l.is_person IS NULL OR l.is_person = 0)
+         AND a.name IS NOT NULL
+         AND a.name != ''
+         {}",
+        style_clause
+    );
+
+    let count_query = format!(
+        "SELECT COUNT(DISTINCT ai.id)
+         FROM artists_images ai
+         JOIN artists a ON ai.artist_id = a.id
+         JOIN locations l ON a.location_id = l.id
+         {}",
+        base_where
+    );
+    let data_query = format!(
+        "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle, a.email, a.phone, a.years_experience, a.styles_extracted, a.experience_tier,
+                {}
+         FROM artists_images ai
+         JOIN artists a ON ai.artist_id = a.id
+         JOIN locations l ON a.location_id = l.id
+         {}
+         {}
+         ORDER BY {} ai.id DESC
+         LIMIT ${} OFFSET ${}",
+        is_favorited_select,
+        favorites_join,
+        base_where,
+        rank_order,
+        next_bind,
+        next_bind + 1
+    );
 
     // Get total count
-    let total_count: i64 = if let Some(ref ids) = style_ids {
-        if !ids.is_empty() {
-            sqlx::query(&count_query)
-                .bind(location_id)
-                .bind(ids)
-                .fetch_one(pool)
-                .await?
-                .try_get(0)?
-        } else {
-            sqlx::query(&count_query)
-                .bind(location_id)
-                .fetch_one(pool)
-                .await?
-                .try_get(0)?
-        }
-    } else {
-        sqlx::query(&count_query)
-            .bind(location_id)
-            .fetch_one(pool)
-            .await?
-            .try_get(0)?
-    };
+    // The count has no ORDER BY, so only the must-have array applies
+    let mut count = sqlx::query(&count_query).bind(location_id);
+    if styles.filter.is_some() {
+        count = count.bind(&styles.binds[0]);
+    }
+    let total_count: i64 = count.fetch_one(pool).await?.try_get(0)?;
 
     // Get paginated images
     let offset = page * per_page;
-    let image_rows = if let Some(ref ids) = style_ids {
-        if !ids.is_empty() {
-            sqlx::query(&data_query)
-                .bind(location_id)
-                .bind(ids)
-                .bind(per_page)
-                .bind(offset)
-                .fetch_all(pool)
-                .await?
-        } else {
-            sqlx::query(&data_query)
-                .bind(location_id)
-                .bind(per_page)
-                .bind(offset)
-                .fetch_all(pool)
-                .await?
-        }
-    } else {
-        sqlx::query(&data_query)
-            .bind(location_id)
-            .bind(per_page)
-            .bind(offset)
-            .fetch_all(pool)
-            .await?
-    };
+    let mut data = sqlx::query(&data_query).bind(location_id);
+    for ids in &styles.binds {
+        data = data.bind(ids);
+    }
+    let image_rows = data.bind(per_page).bind(offset).fetch_all(pool).await?;
 
-    let mut result: Vec<(ArtistImage, Vec<Style>, Artist, bool)> = vec![];
+    let mut result: Vec<(ArtistImage, Artist, bool)> = vec![];
 
     for image_row in image_rows {
         let image = ArtistImage {
@@ -1901,11 +2288,33 @@ pub async fn get_shop_images_paginated(
                 .ok()
                 .map(|v| v as i32),
             shop_validated: image_row.try_get("shop_validated").ok(),
+            experience_tier: image_row.try_get("experience_tier").ok().flatten(),
         };
 
         let is_favorited: bool = image_row.get("is_favorited");
-        let img_id = image.id;
+        result.push((image, artist, is_favorited));
+    }
 
+    Ok((result, total_count as i32))
+}
+
+// Paginated and filtered image queries for shop pages
+#[cfg(feature = "ssr")]
+pub async fn get_shop_images_paginated(
+    location_id: i32,
+    style_filter: Option<StyleFilter<i32>>,
+    page: i32,
+    per_page: i32,
+    user_id: Option<i64>,
+) -> DbResult<(Vec<(ArtistImage, Vec<Style>, Artist, bool)>, i32)> {
+    let pool = crate::db::pool::get_pool();
+
+    let (rows, total_count) =
+        shop_image_rows(location_id, style_filter, page, per_page, user_id).await?;
+
+    let mut result: Vec<(ArtistImage, Vec<Style>, Artist, bool)> = vec![];
+
+    for (image, artist, is_favorited) in rows {
         // Get styles for this image
         let style_rows = sqlx::query(
             "SELECT s.id, s.name
@@ -1913,7 +2322,7 @@ pub async fn get_shop_images_paginated(
              JOIN artists_images_styles ais ON s.id = ais.style_id
              WHERE ais.artists_images_id = $1",
         )
-        .bind(img_id)
+        .bind(image.id)
         .fetch_all(pool)
         .await?;
 
@@ -1928,147 +2337,140 @@ pub async fn get_shop_images_paginated(
         result.push((image, styles, artist, is_favorited));
     }
 
-    Ok((result, total_count as i32))
+    Ok((result, total_count))
+}
+
+/// Rewrite of [`get_shop_images_paginated`] that loads the page's styles in
+/// one query rather than one per image. Dark-launched through
+/// [`crate::db::shadow`] until it has run clean against the original.
+#[cfg(feature = "ssr")]
+pub async fn get_shop_images_paginated_batched(
+    location_id: i32,
+    style_filter: Option<StyleFilter<i32>>,
+    page: i32,
+    per_page: i32,
+    user_id: Option<i64>,
+) -> DbResult<(Vec<(ArtistImage, Vec<Style>, Artist, bool)>, i32)> {
+    let pool = crate::db::pool::get_pool();
+
+    let (rows, total_count) =
+        shop_image_rows(location_id, style_filter, page, per_page, user_id).await?;
+
+    let image_ids: Vec<i32> = rows.iter().map(|(image, _, _)| image.id).collect();
+    let styles_by_image = get_styles_for_images(pool, &image_ids).await?;
+
+    let result = rows
+        .into_iter()
+        .map(|(image, artist, is_favorited)| {
+            let styles = styles_by_image.get(&image.id).cloned().unwrap_or_default();
+            (image, styles, artist, is_favorited)
+        })
+        .collect();
+
+    Ok((result, total_count))
+}
+
+
+/// What shadow runs compare for shop image pages: the total, then each
+/// image's id, artist, favorite flag and style ids (style order is
+/// unspecified)
+#[cfg(feature = "ssr")]
+pub fn shop_images_key(
+    page: &(Vec<(ArtistImage, Vec<Style>, Artist, bool)>, i32),
+) -> (i32, Vec<(i32, i32, bool, Vec<i32>)>) {
+    let (images, total) = page;
+    let images = images
+        .iter()
+        .map(|(image, styles, artist, is_favorited)| {
+            let mut style_ids: Vec<i32> = styles.iter().map(|style| style.id).collect();
+            style_ids.sort_unstable();
+            (image.id, artist.id, *is_favorited, style_ids)
+        })
+        .collect();
+    (*total, images)
+}
+
+/// Shop image pages as served, with [`get_shop_images_paginated_batched`]
+/// shadowing [`get_shop_images_paginated`] on sampled requests
+#[cfg(feature = "ssr")]
+pub async fn get_shop_images_page(
+    location_id: i32,
+    style_filter: Option<StyleFilter<i32>>,
+    page: i32,
+    per_page: i32,
+    user_id: Option<i64>,
+) -> DbResult<(Vec<(ArtistImage, Vec<Style>, Artist, bool)>, i32)> {
+    let candidate_filter = style_filter.clone();
+    crate::db::shadow::shadow(
+        "shop_images_paginated",
+        get_shop_images_paginated(location_id, style_filter, page, per_page, user_id),
+        move || {
+            get_shop_images_paginated_batched(
+                location_id,
+                candidate_filter,
+                page,
+                per_page,
+                user_id,
+            )
+        },
+        shop_images_key,
+    )
+    .await
 }
 
 // Paginated and filtered image queries for artist pages
+
+/// A page of the artist's gallery: their pinned highlights first, in order,
+/// then the rest newest first, leaving out images they've hidden.
+/// `style_ids` narrows it to images tagged with any of the styles and
+/// `album_id` to one of their albums.
 #[cfg(feature = "ssr")]
 pub async fn get_artist_images_paginated(
     artist_id: i32,
     style_ids: Option<Vec<i32>>,
+    album_id: Option<i64>,
     page: i32,
     per_page: i32,
     user_id: Option<i64>,
 ) -> DbResult<(Vec<(ArtistImage, Vec<Style>, bool)>, i32)> {
     let pool = crate::db::pool::get_pool();
 
-    // Build the favorites JOIN clause based on user_id
-    let favorites_join = if let Some(uid) = user_id {
-        format!(
-            "LEFT JOIN user_favorites uf ON ai.id = uf.artists_images_id AND uf.user_id = {}",
-            uid
-        )
-    } else {
-        String::new()
-    };
-
-    let is_favorited_select = if user_id.is_some() {
-        "CASE WHEN uf.id IS NOT NULL THEN TRUE ELSE FALSE END as is_favorited"
-    } else {
-        "FALSE as is_favorited"
-    };
-
-    // Build the WHERE clause for style filtering
-    let (count_query, data_query) = if let Some(ref ids) = style_ids {
-        if !ids.is_empty() {
-            (
-                format!(
-                    "SELECT COUNT(DISTINCT ai.id)
-                     FROM artists_images ai
-                     WHERE ai.artist_id = $1
-                     AND ai.id IN (SELECT ais.artists_images_id FROM artists_images_styles ais WHERE ais.style_id = ANY($2::int[]))"
-                ),
-                format!(
-                    "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date,
-                            {}
-                     FROM artists_images ai
-                     {}
-                     WHERE ai.artist_id = $1
-                     AND ai.id IN (SELECT ais.artists_images_id FROM artists_images_styles ais WHERE ais.style_id = ANY($2::int[]))
-                     ORDER BY ai.id DESC
-                     LIMIT $3 OFFSET $4",
-                     is_favorited_select,
-                     favorites_join
-                )
-            )
-        } else {
-            (
-                "SELECT COUNT(DISTINCT ai.id)
-                 FROM artists_images ai
-                 WHERE ai.artist_id = $1"
-                    .to_string(),
-                format!(
-                    "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date,
-                            {}
-                     FROM artists_images ai
-                     {}
-                     WHERE ai.artist_id = $1
-                     ORDER BY ai.id DESC
-                     LIMIT $2 OFFSET $3",
-                    is_favorited_select, favorites_join
-                ),
-            )
-        }
-    } else {
-        (
-            "SELECT COUNT(DISTINCT ai.id)
-             FROM artists_images ai
-             WHERE ai.artist_id = $1"
-                .to_string(),
-            format!(
-                "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date,
-                        {}
-                 FROM artists_images ai
-                 {}
-                 WHERE ai.artist_id = $1
-                 ORDER BY ai.id DESC
-                 LIMIT $2 OFFSET $3",
-                is_favorited_select, favorites_join
-            ),
-        )
-    };
-
-    // Get total count
-    let total_count: i64 = if let Some(ref ids) = style_ids {
-        if !ids.is_empty() {
-            sqlx::query(&count_query)
-                .bind(artist_id)
-                .bind(ids)
-                .fetch_one(pool)
-                .await?
-                .try_get(0)?
-        } else {
-            sqlx::query(&count_query)
-                .bind(artist_id)
-                .fetch_one(pool)
-                .await?
-                .try_get(0)?
-        }
-    } else {
-        sqlx::query(&count_query)
-            .bind(artist_id)
-            .fetch_one(pool)
-            .await?
-            .try_get(0)?
-    };
+    let style_ids = style_ids.filter(|ids| !ids.is_empty());
+    let filters = "WHERE ai.artist_id = $1 AND ai.removed_at IS NULL AND ai.hidden_at IS NULL
+         AND ($2::int[] IS NULL OR ai.id IN
+             (SELECT ais.artists_images_id FROM artists_images_styles ais
+              WHERE ais.style_id = ANY($2::int[])))
+         AND ($3::bigint IS NULL OR ai.id IN
+             (SELECT pai.image_id FROM portfolio_album_images pai WHERE pai.album_id = $3))";
+
+    let total_count: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM artists_images ai {}",
+        filters
+    ))
+    .bind(artist_id)
+    .bind(&style_ids)
+    .bind(album_id)
+    .fetch_one(pool)
+    .await?;
 
-    // Get paginated images
-    let offset = page * per_page;
-    let image_rows = if let Some(ref ids) = style_ids {
-        if !ids.is_empty() {
-            sqlx::query(&data_query)
-                .bind(artist_id)
-                .bind(ids)
-                .bind(per_page)
-                .bind(offset)
-                .fetch_all(pool)
-                .await?
-        } else {
-            sqlx::query(&data_query)
-                .bind(artist_id)
-                .bind(per_page)
-                .bind(offset)
-                .fetch_all(pool)
-                .await?
-        }
-    } else {
-        sqlx::query(&data_query)
-            .bind(artist_id)
-            .bind(per_page)
-            .bind(offset)
-            .fetch_all(pool)
-            .await?
-    };
+    let image_rows = sqlx::query(&format!(
+        "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date,
+                EXISTS (SELECT 1 FROM user_favorites uf
+                        WHERE uf.artists_images_id = ai.id AND uf.user_id = $6) as is_favorited
+         FROM artists_images ai
+         {}
+         ORDER BY ai.pinned_position NULLS LAST, ai.id DESC
+         LIMIT $4 OFFSET $5",
+        filters
+    ))
+    .bind(artist_id)
+    .bind(&style_ids)
+    .bind(album_id)
+    .bind(per_page)
+    .bind(page * per_page)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
 
     let mut result: Vec<(ArtistImage, Vec<Style>, bool)> = vec![];
 
@@ -2108,3 +2510,152 @@ pub async fn get_artist_images_paginated(
 
     Ok((result, total_count as i32))
 }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn filter(must_have: &[i32], nice_to_have: &[i32], mode: StyleMatchMode) -> StyleFilter<i32> {
+        StyleFilter {
+            must_have: must_have.to_vec(),
+            nice_to_have: nice_to_have.to_vec(),
+            mode,
+        }
+    }
+
+    fn clauses(must_have: &[i32], nice_to_have: &[i32], mode: StyleMatchMode) -> StyleClauses<i32> {
+        IMAGE_STYLE_IDS.clauses("ai.id", filter(must_have, nice_to_have, mode), 2)
+    }
+
+    const ANY_FILTER: &str = "ai.id IN (SELECT fais.artists_images_id FROM artists_images_styles fais WHERE fais.style_id = ANY($2::int[]))";
+    const ALL_FILTER: &str = "ai.id IN (SELECT fais.artists_images_id FROM artists_images_styles fais WHERE fais.style_id = ANY($2::int[]) GROUP BY fais.artists_images_id HAVING COUNT(DISTINCT fais.style_id) = cardinality($2::int[]))";
+
+    fn rank(bind: usize) -> String {
+        format!(
+            "(SELECT COUNT(DISTINCT fais.style_id) FROM artists_images_styles fais WHERE fais.artists_images_id = ai.id AND fais.style_id = ANY(${}::int[]))",
+            bind
+        )
+    }
+
+    #[test]
+    fn neither_list_adds_nothing() {
+        for mode in [StyleMatchMode::Any, StyleMatchMode::All] {
+            let styles = clauses(&[], &[], mode);
+            assert_eq!(
+                styles,
+                StyleClauses {
+                    filter: None,
+                    rank: None,
+                    binds: Vec::new(),
+                    next_bind: 2,
+                }
+            );
+            assert_eq!(styles.and_filter(), "");
+            assert_eq!(styles.rank_order(), "");
+        }
+    }
+
+    #[test]
+    fn must_have_only_filters_without_ranking() {
+        let any = clauses(&[1, 2], &[], StyleMatchMode::Any);
+        assert_eq!(any.filter.as_deref(), Some(ANY_FILTER));
+        assert_eq!(any.rank, None);
+        assert_eq!(any.binds, vec![vec![1, 2]]);
+        assert_eq!(any.next_bind, 3);
+        assert_eq!(any.and_filter(), format!("AND {}", ANY_FILTER));
+
+        let all = clauses(&[1, 2], &[], StyleMatchMode::All);
+        assert_eq!(all.filter.as_deref(), Some(ALL_FILTER));
+        assert_eq!(all.rank, None);
+        assert_eq!(all.binds, vec![vec![1, 2]]);
+        assert_eq!(all.next_bind, 3);
+    }
+
+    #[test]
+    fn nice_to_have_only_ranks_without_filtering() {
+        for mode in [StyleMatchMode::Any, StyleMatchMode::All] {
+            let styles = clauses(&[], &[4, 5], mode);
+            assert_eq!(styles.filter, None);
+            assert_eq!(styles.rank, Some(rank(2)));
+            assert_eq!(styles.rank_order(), format!("{} DESC,", rank(2)));
+            assert_eq!(styles.binds, vec![vec![4, 5]]);
+            assert_eq!(styles.next_bind, 3);
+        }
+    }
+
+    #[test]
+    fn both_lists_bind_must_have_first() {
+        let any = clauses(&[1], &[4, 5], StyleMatchMode::Any);
+        assert_eq!(any.filter.as_deref(), Some(ANY_FILTER));
+        assert_eq!(any.rank, Some(rank(3)));
+        assert_eq!(any.binds, vec![vec![1], vec![4, 5]]);
+        assert_eq!(any.next_bind, 4);
+
+        let all = clauses(&[1, 2], &[4], StyleMatchMode::All);
+        assert_eq!(all.filter.as_deref(), Some(ALL_FILTER));
+        assert_eq!(all.rank, Some(rank(3)));
+        assert_eq!(all.binds, vec![vec![1, 2], vec![4]]);
+        assert_eq!(all.next_bind, 4);
+    }
+
+    #[test]
+    fn binds_start_where_the_query_says() {
+        let styles = IMAGE_STYLE_IDS.clauses("ai.id", filter(&[1], &[2], StyleMatchMode::Any), 7);
+        assert!(styles.filter.unwrap().contains("$7::int[]"));
+        assert_eq!(styles.rank, Some(rank(8)));
+        assert_eq!(styles.next_bind, 9);
+    }
+
+    #[test]
+    fn duplicate_ids_are_bound_once() {
+        // `All` compares a distinct count with the array's length, so a
+        // repeated id would otherwise match nothing
+        let all = clauses(&[3, 3, 5, 3], &[6, 6], StyleMatchMode::All);
+        assert_eq!(all.binds, vec![vec![3, 5], vec![6]]);
+
+        let any = clauses(&[3, 3], &[], StyleMatchMode::Any);
+        assert_eq!(any.binds, vec![vec![3]]);
+    }
+
+    #[test]
+    fn must_have_repeated_as_nice_to_have() {
+        // Under `All` every result has it, so it can't rank anything
+        let all = clauses(&[1, 2], &[2, 3], StyleMatchMode::All);
+        assert_eq!(all.binds, vec![vec![1, 2], vec![3]]);
+
+        let all_repeated = clauses(&[1, 2], &[2, 1], StyleMatchMode::All);
+        assert_eq!(all_repeated.rank, None);
+        assert_eq!(all_repeated.binds, vec![vec![1, 2]]);
+        assert_eq!(all_repeated.next_bind, 3);
+
+        // Under `Any` a result may have only the other must-have styles
+        let any = clauses(&[1, 2], &[2, 3], StyleMatchMode::Any);
+        assert_eq!(any.rank, Some(rank(3)));
+        assert_eq!(any.binds, vec![vec![1, 2], vec![2, 3]]);
+    }
+
+    #[test]
+    fn artist_names_filter_through_their_images() {
+        let styles =
+            ARTIST_STYLE_NAMES.clauses("a.id", StyleFilter::all(vec!["blackwork".to_string()]), 2);
+        let filter = styles.filter.unwrap();
+        assert!(filter.starts_with("a.id IN (SELECT fai.artist_id FROM artists_images fai"));
+        assert!(
+            filter.ends_with("HAVING COUNT(DISTINCT LOWER(fs.name)) = cardinality($2::text[]))")
+        );
+        assert_eq!(styles.binds, vec![vec!["blackwork".to_string()]]);
+    }
+
+    #[test]
+    fn style_preferences_cover_both_lists_once() {
+        assert!(style_preferences(&filter(&[], &[], StyleMatchMode::Any)).is_empty());
+        assert_eq!(
+            style_preferences(&filter(&[2, 1, 2], &[3, 1], StyleMatchMode::All)),
+            vec![2, 1, 3]
+        );
+        assert_eq!(
+            style_preferences(&filter(&[], &[3, 3], StyleMatchMode::Any)),
+            vec![3]
+        );
+    }
+}
@@ -0,0 +1,501 @@
+#[cfg(feature = "ssr")]
+use shared_types::datetime::{Date, Time};
+#[cfg(feature = "ssr")]
+use shared_types::BookingStatus;
+#[cfg(feature = "ssr")]
+use sqlx::{Postgres, Row, Transaction};
+
+#[cfg(feature = "ssr")]
+use crate::db::booking_event_repository::record_event_in;
+#[cfg(feature = "ssr")]
+use crate::db::booking_status_repository::{lock_status, record_change_in, transition_in};
+#[cfg(feature = "ssr")]
+use crate::db::entities::{BookingEventKind, LinkedBooking, RescheduleProposal};
+#[cfg(feature = "ssr")]
+use crate::utils::reschedule::is_late_cancellation;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Who to tell about a change to a booking
+#[cfg(feature = "ssr")]
+pub struct BookingContacts {
+    pub artist_id: i32,
+    pub artist_name: Option<String>,
+    /// The artist's sign-in email, or their profile email
+    pub artist_email: Option<String>,
+    pub client_name: String,
+    pub client_email: String,
+}
+
+/// How answering a proposal went
+#[cfg(feature = "ssr")]
+pub enum ProposalResponse {
+    /// There's no such open proposal from the other side, or the booking
+    /// isn't going ahead any more
+    NotOpen,
+    Declined,
+    /// The booking moved to the proposed time, and on to `status` if
+    /// agreeing on it took the booking a step further
+    Accepted {
+        status: Option<BookingStatus>,
+    },
+}
+
+#[cfg(feature = "ssr")]
+const PROPOSAL_SELECT: &str = "SELECT id, booking_request_id, proposed_by, proposed_date,
+        proposed_start_time, proposed_end_time, status, response_note,
+        TO_CHAR(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') as created_at
+     FROM booking_reschedules";
+
+#[cfg(feature = "ssr")]
+fn proposal_from_row(row: &sqlx::postgres::PgRow) -> RescheduleProposal {
+    RescheduleProposal {
+        id: row.get("id"),
+        booking_id: row.get("booking_request_id"),
+        proposed_by: row.get("proposed_by"),
+        date: row.get("proposed_date"),
+        start_time: row.get("proposed_start_time"),
+        end_time: row.get("proposed_end_time"),
+        status: row.get("status"),
+        response_note: row.get("response_note"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Hours from now until an appointment, in server time like the rest of the
+/// booking dates. `None` when the booking's date or time doesn't parse.
+#[cfg(feature = "ssr")]
+fn hours_until(date: &str, start_time: &str) -> Option<f64> {
+    let at = Date::parse(date)?
+        .naive()
+        .and_time(Time::parse_lenient(start_time)?.naive());
+    Some((at - chrono::Local::now().naive_local()).num_minutes() as f64 / 60.0)
+}
+
+/// Whether the booking is still going ahead, so can be rescheduled or
+/// cancelled
+#[cfg(feature = "ssr")]
+fn is_active(status: &str) -> Option<BookingStatus> {
+    BookingStatus::parse(status).filter(|status| status.can_become(BookingStatus::Cancelled))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_booking_contacts(booking_id: i32) -> DbResult<Option<BookingContacts>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT br.artist_id, a.name as artist_name, br.client_name, br.client_email,
+                COALESCE(
+                    (SELECT u.email FROM users u
+                     WHERE u.artist_id = br.artist_id AND u.role = 'artist' AND u.is_active = true
+                     ORDER BY u.id LIMIT 1),
+                    a.email
+                ) as artist_email
+         FROM booking_requests br
+         JOIN artists a ON a.id = br.artist_id
+         WHERE br.id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| BookingContacts {
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        artist_email: row.get("artist_email"),
+        client_name: row.get("client_name"),
+        client_email: row.get("client_email"),
+    }))
+}
+
+/// The artist's own cancellation window, `None` if they use the default
+#[cfg(feature = "ssr")]
+pub async fn get_cancellation_window(artist_id: i32) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT cancellation_window_hours FROM artists WHERE id = $1")
+        .bind(artist_id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+}
+
+/// Sets the artist's cancellation window; `None` goes back to the default
+#[cfg(feature = "ssr")]
+pub async fn set_cancellation_window(artist_id: i32, hours: Option<i32>) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE artists SET cancellation_window_hours = $2 WHERE id = $1")
+        .bind(artist_id)
+        .bind(hours)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// The booking's latest proposal, if it's still open
+#[cfg(feature = "ssr")]
+pub async fn get_open_proposal(booking_id: i32) -> DbResult<Option<RescheduleProposal>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "{} WHERE booking_request_id = $1 AND status = 'open'",
+        PROPOSAL_SELECT
+    ))
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(proposal_from_row))
+}
+
+/// The booking for the client's link, with the artist's window when they
+/// haven't set one of their own
+#[cfg(feature = "ssr")]
+pub async fn get_linked_booking(
+    booking_id: i32,
+    default_window_hours: i32,
+) -> DbResult<Option<LinkedBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let Some(row) = sqlx::query(
+        "SELECT br.id, a.name as artist_name, br.client_name, br.requested_date,
+                br.requested_start_time, NULLIF(br.requested_end_time, '') as requested_end_time,
+                br.status, br.deposit_amount, br.deposit_status, br.cancellation_reason,
+                a.cancellation_window_hours
+         FROM booking_requests br
+         JOIN artists a ON a.id = br.artist_id
+         WHERE br.id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let requested_date: String = row.get("requested_date");
+    let requested_start_time: String = row.get("requested_start_time");
+    let window_hours = row
+        .get::<Option<i32>, _>("cancellation_window_hours")
+        .unwrap_or(default_window_hours);
+    let late_to_cancel = hours_until(&requested_date, &requested_start_time)
+        .is_some_and(|hours| is_late_cancellation(hours, window_hours));
+
+    Ok(Some(LinkedBooking {
+        id: row.get("id"),
+        artist_name: row.get("artist_name"),
+        client_name: row.get("client_name"),
+        requested_date,
+        requested_start_time,
+        requested_end_time: row.get("requested_end_time"),
+        status: row.get("status"),
+        deposit_amount: row.get("deposit_amount"),
+        deposit_status: row.get("deposit_status"),
+        open_proposal: get_open_proposal(booking_id).await?,
+        cancellation_window_hours: window_hours,
+        late_to_cancel,
+        cancellation_reason: row.get("cancellation_reason"),
+    }))
+}
+
+/// Closes the booking's open proposal, if there is one
+#[cfg(feature = "ssr")]
+async fn supersede_open_in(tx: &mut Transaction<'_, Postgres>, booking_id: i32) -> DbResult<()> {
+    sqlx::query(
+        "UPDATE booking_reschedules SET status = 'superseded', responded_at = CURRENT_TIMESTAMP
+         WHERE booking_request_id = $1 AND status = 'open'",
+    )
+    .bind(booking_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Proposes another time for a booking, superseding any open proposal, and
+/// records it on the timeline. An artist's proposal is also the booking's
+/// suggested time. Returns `None`, changing nothing, if the booking isn't
+/// going ahead any more.
+#[cfg(feature = "ssr")]
+pub async fn propose_time(
+    booking_id: i32,
+    proposed_by: &str,
+    date: Date,
+    start_time: Time,
+    end_time: Option<Time>,
+) -> DbResult<Option<RescheduleProposal>> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let status = lock_status(&mut tx, booking_id).await?.unwrap_or_default();
+    if is_active(&status).is_none() {
+        return Ok(None);
+    }
+
+    supersede_open_in(&mut tx, booking_id).await?;
+    let row = sqlx::query(
+        "INSERT INTO booking_reschedules
+         (booking_request_id, proposed_by, proposed_date, proposed_start_time, proposed_end_time)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING id, booking_request_id, proposed_by, proposed_date, proposed_start_time,
+                   proposed_end_time, status, response_note,
+                   TO_CHAR(created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS') as created_at",
+    )
+    .bind(booking_id)
+    .bind(proposed_by)
+    .bind(date.to_string())
+    .bind(start_time.to_string())
+    .bind(end_time.map(|time| time.to_string()))
+    .fetch_one(&mut *tx)
+    .await?;
+    let proposal = proposal_from_row(&row);
+
+    if proposed_by == "artist" {
+        sqlx::query(
+            "UPDATE booking_requests
+             SET suggested_date = $2, suggested_start_time = $3, suggested_end_time = $4,
+                 updated_at = CURRENT_TIMESTAMP,
+                 first_responded_at = COALESCE(first_responded_at, CURRENT_TIMESTAMP)
+             WHERE id = $1",
+        )
+        .bind(booking_id)
+        .bind(&proposal.date)
+        .bind(&proposal.start_time)
+        .bind(&proposal.end_time)
+        .execute(&mut *tx)
+        .await?;
+    } else {
+        // The artist's suggestion was countered
+        sqlx::query(
+            "UPDATE booking_requests
+             SET suggested_date = NULL, suggested_start_time = NULL, suggested_end_time = NULL,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1",
+        )
+        .bind(booking_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    record_event_in(
+        &mut tx,
+        booking_id,
+        proposed_by,
+        &BookingEventKind::TimeSuggested {
+            date: proposal.date.clone(),
+            start_time: proposal.start_time.clone(),
+            end_time: proposal.end_time.clone(),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(proposal))
+}
+
+/// Accepts or declines the open proposal `proposal_id` on behalf of
+/// `responder` ("client" or "artist"), who can only answer the other side's.
+/// Accepting moves the booking to the proposed time; a pending booking is
+/// then approved, and an approved one with no deposit outstanding confirmed.
+#[cfg(feature = "ssr")]
+pub async fn respond_to_proposal(
+    booking_id: i32,
+    proposal_id: i64,
+    responder: &str,
+    changed_by: Option<i64>,
+    accept: bool,
+    note: Option<&str>,
+) -> DbResult<ProposalResponse> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let status = lock_status(&mut tx, booking_id).await?.unwrap_or_default();
+    let Some(status) = is_active(&status) else {
+        return Ok(ProposalResponse::NotOpen);
+    };
+
+    let Some(row) = sqlx::query(&format!(
+        "{} WHERE id = $1 AND booking_request_id = $2 AND status = 'open' AND proposed_by <> $3
+         FOR UPDATE",
+        PROPOSAL_SELECT
+    ))
+    .bind(proposal_id)
+    .bind(booking_id)
+    .bind(responder)
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(ProposalResponse::NotOpen);
+    };
+    let proposal = proposal_from_row(&row);
+
+    sqlx::query(
+        "UPDATE booking_reschedules
+         SET status = $2, response_note = $3, responded_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(proposal_id)
+    .bind(if accept { "accepted" } else { "declined" })
+    .bind(note)
+    .execute(&mut *tx)
+    .await?;
+
+    if !accept {
+        // The artist's suggestion no longer stands
+        sqlx::query(
+            "UPDATE booking_requests
+             SET suggested_date = NULL, suggested_start_time = NULL, suggested_end_time = NULL,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = $1",
+        )
+        .bind(booking_id)
+        .execute(&mut *tx)
+        .await?;
+        record_event_in(
+            &mut tx,
+            booking_id,
+            responder,
+            &BookingEventKind::TimeDeclined {
+                note: note.map(str::to_string),
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+        return Ok(ProposalResponse::Declined);
+    }
+
+    let deposit_status: Option<String> = sqlx::query_scalar(
+        "UPDATE booking_requests
+         SET requested_date = $2, requested_start_time = $3, requested_end_time = $4,
+             suggested_date = NULL, suggested_start_time = NULL, suggested_end_time = NULL,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1
+         RETURNING deposit_status",
+    )
+    .bind(booking_id)
+    .bind(&proposal.date)
+    .bind(&proposal.start_time)
+    .bind(proposal.end_time.as_deref().unwrap_or_default())
+    .fetch_one(&mut *tx)
+    .await?;
+    record_event_in(
+        &mut tx,
+        booking_id,
+        responder,
+        &BookingEventKind::TimeAccepted {
+            date: proposal.date,
+            start_time: proposal.start_time,
+            end_time: proposal.end_time,
+        },
+    )
+    .await?;
+
+    // Agreeing on a time is agreeing to the booking; a required deposit
+    // still has to be paid before it's confirmed
+    let next = match status {
+        BookingStatus::Pending | BookingStatus::NeedsInfo => Some(BookingStatus::Accepted),
+        BookingStatus::Accepted if deposit_status.as_deref() != Some("required") => {
+            Some(BookingStatus::Confirmed)
+        }
+        _ => None,
+    };
+    let mut moved_to = None;
+    if let Some(next) = next {
+        if transition_in(&mut tx, booking_id, status, next, responder, changed_by).await? {
+            moved_to = Some(next);
+        }
+    }
+
+    tx.commit().await?;
+    Ok(ProposalResponse::Accepted { status: moved_to })
+}
+
+/// Cancels a booking for `cancelled_by` ("client" or "artist"), closing any
+/// open proposal. A client cancelling inside the cancellation window (the
+/// artist's own, or `default_window_hours`) is a late cancellation. Returns
+/// whether it was late, or Err with the status the booking is stuck in when
+/// it can't be cancelled.
+#[cfg(feature = "ssr")]
+pub async fn cancel_booking(
+    booking_id: i32,
+    cancelled_by: &str,
+    changed_by: Option<i64>,
+    reason: &str,
+    note: Option<&str>,
+    default_window_hours: i32,
+) -> DbResult<Result<bool, String>> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let current = lock_status(&mut tx, booking_id).await?.unwrap_or_default();
+    let Some(from) = is_active(&current) else {
+        return Ok(Err(current));
+    };
+
+    let row = sqlx::query(
+        "SELECT br.requested_date, br.requested_start_time, a.cancellation_window_hours
+         FROM booking_requests br
+         JOIN artists a ON a.id = br.artist_id
+         WHERE br.id = $1",
+    )
+    .bind(booking_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let requested_date: String = row.get("requested_date");
+    let requested_start_time: String = row.get("requested_start_time");
+    let window_hours = row
+        .get::<Option<i32>, _>("cancellation_window_hours")
+        .unwrap_or(default_window_hours);
+    // Only approved bookings have an appointment to be late for
+    let late = cancelled_by == "client"
+        && matches!(from, BookingStatus::Accepted | BookingStatus::Confirmed)
+        && hours_until(&requested_date, &requested_start_time)
+            .is_some_and(|hours| is_late_cancellation(hours, window_hours));
+
+    sqlx::query(
+        "UPDATE booking_requests
+         SET status = $2, cancelled_at = CURRENT_TIMESTAMP, cancelled_by = $3,
+             cancellation_reason = $4, cancellation_note = $5, late_cancellation = $6,
+             suggested_date = NULL, suggested_start_time = NULL, suggested_end_time = NULL,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(booking_id)
+    .bind(BookingStatus::Cancelled.as_str())
+    .bind(cancelled_by)
+    .bind(reason)
+    .bind(note)
+    .bind(late)
+    .execute(&mut *tx)
+    .await?;
+
+    record_change_in(
+        &mut tx,
+        booking_id,
+        Some(from),
+        BookingStatus::Cancelled,
+        cancelled_by,
+        changed_by,
+        Some(note.unwrap_or(reason)),
+    )
+    .await?;
+    supersede_open_in(&mut tx, booking_id).await?;
+    record_event_in(
+        &mut tx,
+        booking_id,
+        cancelled_by,
+        &BookingEventKind::Cancelled {
+            reason: reason.to_string(),
+            note: note.map(str::to_string),
+            late,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(Ok(late))
+}
@@ -0,0 +1,113 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+use crate::db::entities::{ResponseTimeStats, SlaNudge};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Rolling response figures for each of `artist_ids` over requests created in
+/// the last `window_days`. Artists without requests in the window are
+/// missing from the result.
+#[cfg(feature = "ssr")]
+pub async fn get_response_stats(
+    artist_ids: &[i32],
+    window_days: i32,
+) -> DbResult<Vec<ResponseTimeStats>> {
+    use crate::utils::response_time::response_label;
+
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "WITH answered AS (
+             SELECT artist_id,
+                    first_responded_at,
+                    GREATEST(
+                        EXTRACT(EPOCH FROM (first_responded_at - created_at::timestamptz)) / 3600.0,
+                        0
+                    )::float8 as hours
+             FROM booking_requests
+             WHERE artist_id = ANY($1)
+               AND created_at::timestamptz >= CURRENT_TIMESTAMP - make_interval(days => $2)
+         )
+         SELECT artist_id,
+                (AVG(hours) FILTER (WHERE first_responded_at IS NOT NULL))::float8 as average_hours,
+                (PERCENTILE_CONT(0.5) WITHIN GROUP (ORDER BY hours)
+                    FILTER (WHERE first_responded_at IS NOT NULL))::float8 as median_hours,
+                COUNT(first_responded_at) as responded_count,
+                COUNT(*) as request_count
+         FROM answered
+         GROUP BY artist_id",
+    )
+    .bind(artist_ids)
+    .bind(window_days)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let average_hours: Option<f64> = row.get("average_hours");
+            let responded_count: i64 = row.get("responded_count");
+            ResponseTimeStats {
+                artist_id: row.get("artist_id"),
+                average_hours,
+                median_hours: row.get("median_hours"),
+                responded_count,
+                request_count: row.get("request_count"),
+                label: response_label(average_hours, responded_count),
+            }
+        })
+        .collect())
+}
+
+/// Pending requests the artist hasn't responded to that have waited at least
+/// `after_hours`, longest waiting first
+#[cfg(feature = "ssr")]
+pub async fn get_unanswered_requests(artist_id: i32, after_hours: f64) -> DbResult<Vec<SlaNudge>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, client_name, requested_date,
+                (EXTRACT(EPOCH FROM (CURRENT_TIMESTAMP - created_at::timestamptz)) / 3600.0)::float8
+                    as hours_waiting
+         FROM booking_requests
+         WHERE artist_id = $1
+           AND status = 'pending'
+           AND first_responded_at IS NULL
+           AND created_at::timestamptz <= CURRENT_TIMESTAMP - make_interval(secs => $2 * 3600)
+         ORDER BY created_at::timestamptz",
+    )
+    .bind(artist_id)
+    .bind(after_hours)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SlaNudge {
+            booking_id: row.get("id"),
+            client_name: row.get("client_name"),
+            requested_date: row.get("requested_date"),
+            hours_waiting: row.get("hours_waiting"),
+        })
+        .collect())
+}
+
+/// Stamps the artist's first response to a request; later calls are no-ops
+#[cfg(feature = "ssr")]
+pub async fn record_first_response(booking_id: i32) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE booking_requests
+         SET first_responded_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND first_responded_at IS NULL",
+    )
+    .bind(booking_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -83,22 +83,23 @@ pub async fn universal_location_search(query: String) -> Result<Vec<SearchResult
 
         sqlx::query(
             "SELECT
-                l.city, l.state, l.county, l.postal_code,
+                COALESCE(c.city, l.city) as city, l.state, l.county, l.postal_code,
                 AVG(l.lat) as lat, AVG(l.long) as long,
                 COUNT(DISTINCT a.id) as artist_count,
                 COUNT(DISTINCT l.id) as shop_count
              FROM locations l
+             LEFT JOIN cities c ON c.id = l.city_id
              LEFT JOIN artists a ON l.id = a.location_id
-             WHERE LOWER(l.city) LIKE $1
+             WHERE COALESCE(c.city_search, l.city_search) LIKE '%' || search_normalize($1) || '%'
              AND (LOWER(l.state) = $2 OR LOWER(l.state) LIKE $3 OR LOWER(l.state) LIKE $4)
              AND (l.is_person IS NULL OR l.is_person = 0)
-             GROUP BY l.city, l.state, l.county, l.postal_code
+             GROUP BY COALESCE(c.city, l.city), l.state, l.county, l.postal_code
              ORDER BY
-                CASE WHEN LOWER(l.city) = $5 THEN 0 ELSE 1 END,
+                CASE WHEN MIN(COALESCE(c.city_search, l.city_search)) = search_normalize($5) THEN 0 ELSE 1 END,
                 COUNT(DISTINCT a.id) DESC
              LIMIT 10",
         )
-        .bind(format!("%{}%", city_search))
+        .bind(&city_search)
         .bind(&state_exact_pattern)
         .bind(&state_prefix_pattern)
         .bind(&state_fuzzy_pattern)
@@ -109,21 +110,22 @@ pub async fn universal_location_search(query: String) -> Result<Vec<SearchResult
         // Search across all states
         sqlx::query(
             "SELECT
-                l.city, l.state, l.county, l.postal_code,
+                COALESCE(c.city, l.city) as city, l.state, l.county, l.postal_code,
                 AVG(l.lat) as lat, AVG(l.long) as long,
                 COUNT(DISTINCT a.id) as artist_count,
                 COUNT(DISTINCT l.id) as shop_count
              FROM locations l
+             LEFT JOIN cities c ON c.id = l.city_id
              LEFT JOIN artists a ON l.id = a.location_id
-             WHERE LOWER(l.city) LIKE $1
+             WHERE COALESCE(c.city_search, l.city_search) LIKE '%' || search_normalize($1) || '%'
              AND (l.is_person IS NULL OR l.is_person = 0)
-             GROUP BY l.city, l.state, l.county, l.postal_code
+             GROUP BY COALESCE(c.city, l.city), l.state, l.county, l.postal_code
              ORDER BY
-                CASE WHEN LOWER(l.city) = $2 THEN 0 ELSE 1 END,
+                CASE WHEN MIN(COALESCE(c.city_search, l.city_search)) = search_normalize($2) THEN 0 ELSE 1 END,
                 COUNT(DISTINCT a.id) DESC
              LIMIT 10",
         )
-        .bind(format!("%{}%", city_search))
+        .bind(&city_search)
         .bind(&city_search)
         .fetch_all(pool)
         .await?
@@ -161,13 +163,13 @@ pub async fn universal_location_search(query: String) -> Result<Vec<SearchResult
             COUNT(DISTINCT l.id) as shop_count
          FROM locations l
          LEFT JOIN artists a ON l.id = a.location_id
-         WHERE LOWER(l.county) LIKE $1
+         WHERE l.county_search LIKE '%' || search_normalize($1) || '%'
          AND (l.is_person IS NULL OR l.is_person = 0)
          GROUP BY l.city, l.state, l.county, l.postal_code
          ORDER BY COUNT(DISTINCT a.id) DESC
          LIMIT 5",
     )
-    .bind(format!("%{}%", normalized_query))
+    .bind(&normalized_query)
     .fetch_all(pool)
     .await?;
 
@@ -215,18 +217,16 @@ pub async fn get_search_suggestions(
     };
 
     // 1. Get city suggestions (highest priority)
-    let city_pattern = format!("{}%", normalized_query);
     let city_suggestions = sqlx::query(
         "SELECT DISTINCT city || ', ' || state as suggestion,
-                CASE WHEN LOWER(city) = $2 THEN 0 ELSE 1 END as priority,
+                CASE WHEN city_search = search_normalize($1) THEN 0 ELSE 1 END as priority,
                 LENGTH(city) as city_length
          FROM locations
-         WHERE LOWER(city) LIKE $1
+         WHERE city_search LIKE search_normalize($1) || '%'
          AND (is_person IS NULL OR is_person = 0)
          ORDER BY priority, city_length
-         LIMIT $3",
+         LIMIT $2",
     )
-    .bind(&city_pattern)
     .bind(&normalized_query)
     .bind(limit as i32)
     .fetch_all(pool)
@@ -276,20 +276,19 @@ pub async fn get_search_suggestions(
 
     // 3. Get county suggestions
     if suggestions.len() < limit {
-        let county_pattern = format!("{}%", normalized_query);
         let remaining = (limit - suggestions.len()) as i32;
 
         let county_suggestions = sqlx::query(
             "SELECT DISTINCT county || ' County, ' || state as suggestion,
                     LENGTH(county) as county_length
              FROM locations
-             WHERE LOWER(county) LIKE $1
+             WHERE county_search LIKE search_normalize($1) || '%'
              AND county IS NOT NULL
              AND (is_person IS NULL OR is_person = 0)
              ORDER BY county_length
              LIMIT $2",
         )
-        .bind(&county_pattern)
+        .bind(&normalized_query)
         .bind(remaining)
         .fetch_all(pool)
         .await?;
@@ -0,0 +1,238 @@
+//! Dark launching for repository query rewrites.
+//!
+//! [`shadow`] serves the original implementation and, for a sample of
+//! requests, also runs the rewrite in the background and compares the two.
+//! The rewrite never affects the response. Divergent results (including a
+//! rewrite that fails) are logged at warn level with both sides, each run's
+//! latencies at debug level, and every [`SUMMARY_EVERY`] runs of a query an
+//! info summary gives its divergence count and average latencies.
+//!
+//! [`compare`] runs both implementations in the foreground, for replaying
+//! production-shaped requests offline (see `src/bin/shadow_replay.rs`).
+//!
+//! `SHADOW_SAMPLE_RATE` is the share of requests shadowed, from 0 (the
+//! default, off) to 1.
+
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+#[cfg(feature = "ssr")]
+use std::fmt::Debug;
+#[cfg(feature = "ssr")]
+use std::future::Future;
+#[cfg(feature = "ssr")]
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+#[cfg(feature = "ssr")]
+use std::sync::{Mutex, OnceLock};
+#[cfg(feature = "ssr")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Shadow runs allowed at once, so a slow rewrite can't drain the pool
+#[cfg(feature = "ssr")]
+const MAX_IN_FLIGHT: usize = 4;
+/// Runs of a query between summary logs
+#[cfg(feature = "ssr")]
+const SUMMARY_EVERY: u64 = 100;
+/// Longest rendering of a result included in divergence logs
+#[cfg(feature = "ssr")]
+const MAX_DETAIL_CHARS: usize = 2000;
+
+#[cfg(feature = "ssr")]
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "ssr")]
+static REQUESTS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "ssr")]
+static SAMPLE_EVERY: OnceLock<Option<u64>> = OnceLock::new();
+#[cfg(feature = "ssr")]
+static STATS: OnceLock<Mutex<HashMap<&'static str, QueryStats>>> = OnceLock::new();
+
+#[cfg(feature = "ssr")]
+#[derive(Default)]
+struct QueryStats {
+    runs: u64,
+    divergences: u64,
+    primary: Duration,
+    candidate: Duration,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Match,
+    /// Renderings of each side's result, or its error
+    Diverged { primary: String, candidate: String },
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub outcome: Outcome,
+    pub primary: Duration,
+    pub candidate: Duration,
+}
+
+/// Every how many requests one is shadowed, `None` when shadowing is off
+#[cfg(feature = "ssr")]
+fn sample_every() -> Option<u64> {
+    *SAMPLE_EVERY.get_or_init(|| {
+        let rate = crate::config::config().shadow_sample_rate;
+        (rate > 0.0).then(|| (1.0 / rate).round() as u64)
+    })
+}
+
+#[cfg(feature = "ssr")]
+fn sampled() -> bool {
+    sample_every().is_some_and(|every| REQUESTS.fetch_add(1, Ordering::Relaxed) % every == 0)
+}
+
+#[cfg(feature = "ssr")]
+fn describe<T, K: Debug>(result: &DbResult<T>, key: fn(&T) -> K) -> String {
+    let mut rendered = match result {
+        Ok(value) => format!("{:?}", key(value)),
+        Err(e) => format!("error: {}", e),
+    };
+    if rendered.len() > MAX_DETAIL_CHARS {
+        let mut end = MAX_DETAIL_CHARS;
+        while !rendered.is_char_boundary(end) {
+            end -= 1;
+        }
+        rendered.truncate(end);
+        rendered.push_str("...");
+    }
+    rendered
+}
+
+/// Compares the parts of each result picked out by `key`. Both sides
+/// failing counts as a match.
+#[cfg(feature = "ssr")]
+fn outcome<T, K: PartialEq + Debug>(
+    primary: &DbResult<T>,
+    candidate: &DbResult<T>,
+    key: fn(&T) -> K,
+) -> Outcome {
+    match (primary, candidate) {
+        (Ok(p), Ok(c)) if key(p) == key(c) => Outcome::Match,
+        (Err(_), Err(_)) => Outcome::Match,
+        _ => Outcome::Diverged {
+            primary: describe(primary, key),
+            candidate: describe(candidate, key),
+        },
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn record(query: &'static str, comparison: &Comparison) {
+    let primary_ms = comparison.primary.as_secs_f64() * 1000.0;
+    let candidate_ms = comparison.candidate.as_secs_f64() * 1000.0;
+    match &comparison.outcome {
+        Outcome::Match => {
+            tracing::debug!(query, primary_ms, candidate_ms, "shadow query matched");
+        }
+        Outcome::Diverged { primary, candidate } => {
+            tracing::warn!(
+                query,
+                primary_ms,
+                candidate_ms,
+                primary = %primary,
+                candidate = %candidate,
+                "shadow query diverged"
+            );
+        }
+    }
+
+    let Ok(mut stats) = STATS.get_or_init(Default::default).lock() else {
+        return;
+    };
+    let entry = stats.entry(query).or_default();
+    entry.runs += 1;
+    entry.divergences += u64::from(comparison.outcome != Outcome::Match);
+    entry.primary += comparison.primary;
+    entry.candidate += comparison.candidate;
+
+    if entry.runs % SUMMARY_EVERY == 0 {
+        let runs = entry.runs as f64;
+        tracing::info!(
+            query,
+            runs = entry.runs,
+            divergences = entry.divergences,
+            avg_primary_ms = entry.primary.as_secs_f64() * 1000.0 / runs,
+            avg_candidate_ms = entry.candidate.as_secs_f64() * 1000.0 / runs,
+            "shadow query summary"
+        );
+    }
+}
+
+/// Serves `primary`, and for sampled requests also runs `candidate` in the
+/// background once the primary has succeeded, comparing the parts of the
+/// results picked out by `key`. `candidate` is only called when sampled, so
+/// it can clone the arguments it needs.
+#[cfg(feature = "ssr")]
+pub async fn shadow<T, K, P, C>(
+    query: &'static str,
+    primary: P,
+    candidate: impl FnOnce() -> C,
+    key: fn(&T) -> K,
+) -> DbResult<T>
+where
+    T: Clone + Send + 'static,
+    K: PartialEq + Debug + 'static,
+    P: Future<Output = DbResult<T>>,
+    C: Future<Output = DbResult<T>> + Send + 'static,
+{
+    if !sampled() {
+        return primary.await;
+    }
+
+    let started = Instant::now();
+    let result = primary.await;
+    let primary_elapsed = started.elapsed();
+
+    let Ok(value) = &result else {
+        return result;
+    };
+    if IN_FLIGHT.fetch_add(1, Ordering::Relaxed) >= MAX_IN_FLIGHT {
+        IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+        return result;
+    }
+
+    let expected = Ok(value.clone());
+    let candidate = candidate();
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let actual = candidate.await;
+        let comparison = Comparison {
+            outcome: outcome(&expected, &actual, key),
+            primary: primary_elapsed,
+            candidate: started.elapsed(),
+        };
+        IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+        record(query, &comparison);
+    });
+
+    result
+}
+
+/// Runs `primary` then `candidate` and compares the parts of the results
+/// picked out by `key`. One after the other so their latencies are
+/// comparable.
+#[cfg(feature = "ssr")]
+pub async fn compare<T, K: PartialEq + Debug>(
+    primary: impl Future<Output = DbResult<T>>,
+    candidate: impl Future<Output = DbResult<T>>,
+    key: fn(&T) -> K,
+) -> Comparison {
+    let started = Instant::now();
+    let expected = primary.await;
+    let primary_elapsed = started.elapsed();
+
+    let started = Instant::now();
+    let actual = candidate.await;
+
+    Comparison {
+        outcome: outcome(&expected, &actual, key),
+        primary: primary_elapsed,
+        candidate: started.elapsed(),
+    }
+}
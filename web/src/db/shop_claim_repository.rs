@@ -0,0 +1,382 @@
+#[cfg(feature = "ssr")]
+use super::entities::{ShopClaim, ShopPhoto, ShopProfile, ShopProfileUpdate};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Location new artist accounts are parked on until they pick a shop; artists
+/// removed from a shop's roster go back here.
+#[cfg(feature = "ssr")]
+pub const PLACEHOLDER_LOCATION_ID: i64 = 1;
+
+#[cfg(feature = "ssr")]
+const SHOP_CLAIM_SELECT: &str =
+    "SELECT sc.id, sc.location_id, l.name as location_name, l.website_uri,
+        sc.user_id, u.email as user_email, sc.method, sc.evidence, sc.email_domain_match,
+        sc.status, sc.review_note, TO_CHAR(sc.created_at, 'YYYY-MM-DD HH24:MI') as created_at
+     FROM shop_claims sc
+     JOIN users u ON u.id = sc.user_id
+     LEFT JOIN locations l ON l.id = sc.location_id";
+
+#[cfg(feature = "ssr")]
+fn shop_claim_from_row(row: &PgRow) -> ShopClaim {
+    ShopClaim {
+        id: row.get("id"),
+        location_id: row.get("location_id"),
+        location_name: row.get("location_name"),
+        website_uri: row.get("website_uri"),
+        user_id: row.get("user_id"),
+        user_email: row.get("user_email"),
+        method: row.get("method"),
+        evidence: row.get("evidence"),
+        email_domain_match: row.get("email_domain_match"),
+        status: row.get("status"),
+        review_note: row.get("review_note"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// The shop's website and current owner, or `None` if the location doesn't exist.
+#[cfg(feature = "ssr")]
+pub async fn get_location_claim_info(
+    location_id: i64,
+) -> DbResult<Option<(Option<String>, Option<i64>)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT website_uri, claimed_by FROM locations WHERE id = $1")
+        .bind(location_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| (row.get("website_uri"), row.get("claimed_by"))))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_user_email(user_id: i64) -> DbResult<String> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT email FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.get("email"))
+}
+
+/// Records a pending claim. Returns the existing claim's id if this user
+/// already has one open for the location.
+#[cfg(feature = "ssr")]
+pub async fn create_shop_claim(
+    location_id: i64,
+    user_id: i64,
+    method: &str,
+    evidence: Option<&str>,
+    email_domain_match: bool,
+) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    let existing = sqlx::query(
+        "SELECT id FROM shop_claims
+         WHERE location_id = $1 AND user_id = $2 AND status = 'pending'",
+    )
+    .bind(location_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    if let Some(row) = existing {
+        return Ok(row.get("id"));
+    }
+
+    let row = sqlx::query(
+        "INSERT INTO shop_claims (location_id, user_id, method, evidence, email_domain_match)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING id",
+    )
+    .bind(location_id)
+    .bind(user_id)
+    .bind(method)
+    .bind(evidence)
+    .bind(email_domain_match)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_pending_shop_claims() -> DbResult<Vec<ShopClaim>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE sc.status = 'pending' ORDER BY sc.email_domain_match DESC, sc.created_at",
+        SHOP_CLAIM_SELECT
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(shop_claim_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_user_shop_claims(user_id: i64) -> DbResult<Vec<ShopClaim>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE sc.user_id = $1 ORDER BY sc.created_at DESC",
+        SHOP_CLAIM_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(shop_claim_from_row).collect())
+}
+
+/// Approves or rejects a pending claim. Approving makes the claimant the
+/// shop's owner, adds them to its account as an owner and rejects any other
+/// open claims on the same shop; it fails with `RowNotFound` if the shop
+/// already belongs to someone else.
+#[cfg(feature = "ssr")]
+pub async fn review_shop_claim(
+    claim_id: i64,
+    approve: bool,
+    note: Option<&str>,
+    reviewed_by: i64,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let claim = sqlx::query(
+        "UPDATE shop_claims
+         SET status = $2, review_note = $3, reviewed_by = $4, reviewed_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND status = 'pending'
+         RETURNING location_id, user_id",
+    )
+    .bind(claim_id)
+    .bind(if approve { "approved" } else { "rejected" })
+    .bind(note)
+    .bind(reviewed_by)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(sqlx::Error::RowNotFound)?;
+
+    if approve {
+        let location_id: i64 = claim.get("location_id");
+        let user_id: i64 = claim.get("user_id");
+
+        let claimed = sqlx::query(
+            "UPDATE locations SET claimed_by = $2, claimed_at = CURRENT_TIMESTAMP
+             WHERE id = $1 AND (claimed_by IS NULL OR claimed_by = $2)",
+        )
+        .bind(location_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if claimed.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        sqlx::query(
+            "INSERT INTO shop_users (location_id, user_id, role, added_by)
+             VALUES ($1, $2, 'owner', $3)
+             ON CONFLICT (location_id, user_id) DO UPDATE SET role = 'owner'",
+        )
+        .bind(location_id)
+        .bind(user_id)
+        .bind(reviewed_by)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE shop_claims
+             SET status = 'rejected', review_note = 'Shop claimed by another owner',
+                 reviewed_by = $3, reviewed_at = CURRENT_TIMESTAMP
+             WHERE location_id = $1 AND id <> $2 AND status = 'pending'",
+        )
+        .bind(location_id)
+        .bind(claim_id)
+        .bind(reviewed_by)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Whether the user is one of the shop's owners (see `shop_user_repository`)
+#[cfg(feature = "ssr")]
+pub async fn is_shop_owner(location_id: i64, user_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT 1 as owned FROM shop_users
+         WHERE location_id = $1 AND user_id = $2 AND role = 'owner'",
+    )
+    .bind(location_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.is_some())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_shop_profile(location_id: i64) -> DbResult<ShopProfile> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT id, name, description, opening_hours, claimed_by IS NOT NULL as is_claimed
+         FROM locations WHERE id = $1",
+    )
+    .bind(location_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(ShopProfile {
+        location_id: row.get("id"),
+        name: row.get("name"),
+        description: row.get("description"),
+        opening_hours: row.get("opening_hours"),
+        is_claimed: row.get("is_claimed"),
+        photos: get_shop_photos(location_id).await?,
+    })
+}
+
+/// Overwrites the owner-editable fields; `None` clears a field.
+#[cfg(feature = "ssr")]
+pub async fn update_shop_profile(location_id: i64, update: &ShopProfileUpdate) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE locations SET description = $2, opening_hours = $3 WHERE id = $1")
+        .bind(location_id)
+        .bind(&update.description)
+        .bind(&update.opening_hours)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_shop_photos(location_id: i64) -> DbResult<Vec<ShopPhoto>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT id, location_id, upload_id, position
+         FROM location_photos
+         WHERE location_id = $1
+         ORDER BY position, id",
+    )
+    .bind(location_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ShopPhoto {
+            id: row.get("id"),
+            location_id: row.get("location_id"),
+            upload_id: row.get("upload_id"),
+            position: row.get("position"),
+        })
+        .collect())
+}
+
+/// Attaches a finished portfolio upload owned by `user_id` to the end of the
+/// shop's gallery. Fails with `RowNotFound` if the upload doesn't qualify.
+#[cfg(feature = "ssr")]
+pub async fn add_shop_photo(location_id: i64, upload_id: &str, user_id: i64) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO location_photos (location_id, upload_id, position, added_by)
+         SELECT $1, u.id,
+                COALESCE((SELECT MAX(position) + 1 FROM location_photos WHERE location_id = $1), 0),
+                $3
+         FROM uploads u
+         WHERE u.id = $2 AND u.user_id = $3 AND u.kind = 'portfolio' AND u.status = 'complete'
+         RETURNING id",
+    )
+    .bind(location_id)
+    .bind(upload_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(sqlx::Error::RowNotFound)?;
+
+    Ok(row.get("id"))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn remove_shop_photo(location_id: i64, photo_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM location_photos WHERE id = $1 AND location_id = $2")
+        .bind(photo_id)
+        .bind(location_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Moves an artist onto the shop's roster. Only artists not yet affiliated
+/// with a shop (still on the placeholder location) can be added, so owners
+/// can't pull artists away from other shops.
+#[cfg(feature = "ssr")]
+pub async fn add_artist_to_shop(location_id: i64, artist_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artists SET location_id = $1
+         WHERE id = $2 AND (location_id = $3 OR location_id IS NULL)",
+    )
+    .bind(location_id)
+    .bind(artist_id)
+    .bind(PLACEHOLDER_LOCATION_ID)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(feature = "ssr")]
+pub async fn remove_artist_from_shop(location_id: i64, artist_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result =
+        sqlx::query("UPDATE artists SET location_id = $3 WHERE id = $2 AND location_id = $1")
+            .bind(location_id)
+            .bind(artist_id)
+            .bind(PLACEHOLDER_LOCATION_ID)
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Sets the experience tier of an artist on the shop's roster; `None`
+/// clears it. False if the artist isn't at this shop.
+#[cfg(feature = "ssr")]
+pub async fn set_artist_experience_tier(
+    location_id: i64,
+    artist_id: i64,
+    tier: Option<&str>,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result =
+        sqlx::query("UPDATE artists SET experience_tier = $3 WHERE id = $2 AND location_id = $1")
+            .bind(location_id)
+            .bind(artist_id)
+            .bind(tier)
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected() > 0)
+}
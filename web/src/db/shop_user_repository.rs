@@ -0,0 +1,268 @@
+#[cfg(feature = "ssr")]
+use super::entities::{BookingRequest, ShopArtistSummary, ShopBooking, ShopMembership, ShopUser};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Statuses of requests still waiting on the artist
+#[cfg(feature = "ssr")]
+const OPEN_STATUSES: &str = "('pending', 'needs_info')";
+/// Statuses of bookings that hold a spot on the calendar
+#[cfg(feature = "ssr")]
+const BOOKED_STATUSES: &str = "('approved', 'confirmed', 'completed')";
+
+#[cfg(feature = "ssr")]
+const SHOP_BOOKING_SELECT: &str =
+    "SELECT b.id, b.artist_id, a.name as artist_name, b.client_name, b.client_email,
+        b.client_phone, b.requested_date, b.requested_start_time, b.requested_end_time,
+        b.tattoo_description, b.placement, b.size_inches, b.reference_images,
+        b.message_from_client, b.status, b.artist_response, b.estimated_price,
+        b.created_at, b.updated_at, b.decline_reason, b.deposit_amount, b.deposit_status,
+        (SELECT l.name FROM guest_spots g JOIN locations l ON l.id = g.location_id
+         WHERE g.id = b.guest_spot_id) as guest_spot_shop,
+        (SELECT f.title FROM flash_designs f WHERE f.id = b.flash_design_id) as flash_design_title
+     FROM booking_requests b
+     JOIN artists a ON a.id = b.artist_id";
+
+#[cfg(feature = "ssr")]
+fn shop_booking_from_row(row: &PgRow) -> ShopBooking {
+    ShopBooking {
+        artist_name: row.get("artist_name"),
+        booking: BookingRequest {
+            id: row.get("id"),
+            artist_id: row.get("artist_id"),
+            client_name: row.get("client_name"),
+            client_email: row.get("client_email"),
+            client_phone: row.get("client_phone"),
+            requested_date: row.get("requested_date"),
+            requested_start_time: row.get("requested_start_time"),
+            requested_end_time: row.get("requested_end_time"),
+            tattoo_description: row.get("tattoo_description"),
+            placement: row.get("placement"),
+            size_inches: row.get("size_inches"),
+            reference_images: row.get("reference_images"),
+            message_from_client: row.get("message_from_client"),
+            status: row.get("status"),
+            artist_response: row.get("artist_response"),
+            estimated_price: row.get("estimated_price"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            decline_reason: row.get("decline_reason"),
+            deposit_amount: row.get("deposit_amount"),
+            deposit_status: row.get("deposit_status"),
+            guest_spot_shop: row.get("guest_spot_shop"),
+            flash_design_title: row.get("flash_design_title"),
+        },
+    }
+}
+
+/// Shops the user is on the account of, by name
+#[cfg(feature = "ssr")]
+pub async fn get_user_shops(user_id: i64) -> DbResult<Vec<ShopMembership>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT su.location_id, l.name, su.role
+         FROM shop_users su
+         LEFT JOIN locations l ON l.id = su.location_id
+         WHERE su.user_id = $1
+         ORDER BY l.name, su.location_id",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ShopMembership {
+            location_id: row.get("location_id"),
+            name: row.get("name"),
+            role: row.get("role"),
+        })
+        .collect())
+}
+
+/// The user's role at the shop, if they're on its account
+#[cfg(feature = "ssr")]
+pub async fn get_role(location_id: i64, user_id: i64) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT role FROM shop_users WHERE location_id = $1 AND user_id = $2")
+        .bind(location_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Whether the user is on the account of the shop the artist is based at
+#[cfg(feature = "ssr")]
+pub async fn manages_artist(user_id: i64, artist_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT EXISTS (
+            SELECT 1 FROM shop_users su
+            JOIN artists a ON a.location_id = su.location_id
+            WHERE su.user_id = $1 AND a.id = $2
+         )",
+    )
+    .bind(user_id)
+    .bind(artist_id as i64)
+    .fetch_one(pool)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_shop_users(location_id: i64) -> DbResult<Vec<ShopUser>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT su.user_id, u.email, su.role,
+            TO_CHAR(su.created_at, 'YYYY-MM-DD') as created_at
+         FROM shop_users su
+         JOIN users u ON u.id = su.user_id
+         WHERE su.location_id = $1
+         ORDER BY su.role DESC, su.created_at",
+    )
+    .bind(location_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ShopUser {
+            user_id: row.get("user_id"),
+            email: row.get("email"),
+            role: row.get("role"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// The account with this email, matched case-insensitively
+#[cfg(feature = "ssr")]
+pub async fn find_user_id_by_email(email: &str) -> DbResult<Option<i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT id FROM users WHERE LOWER(email) = LOWER($1)")
+        .bind(email.trim())
+        .fetch_optional(pool)
+        .await
+}
+
+/// Puts the user on the shop's account, or changes their role if they're
+/// already on it
+#[cfg(feature = "ssr")]
+pub async fn add_shop_user(
+    location_id: i64,
+    user_id: i64,
+    role: &str,
+    added_by: i64,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO shop_users (location_id, user_id, role, added_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (location_id, user_id) DO UPDATE SET role = EXCLUDED.role",
+    )
+    .bind(location_id)
+    .bind(user_id)
+    .bind(role)
+    .bind(added_by)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn remove_shop_user(location_id: i64, user_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query("DELETE FROM shop_users WHERE location_id = $1 AND user_id = $2")
+        .bind(location_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Artists based at the shop, with their open requests and bookings from
+/// today on
+#[cfg(feature = "ssr")]
+pub async fn get_shop_artists(location_id: i64) -> DbResult<Vec<ShopArtistSummary>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT a.id::int as artist_id, a.name,
+            (SELECT COUNT(*) FROM booking_requests b
+             WHERE b.artist_id = a.id AND b.status IN {}) as open_requests,
+            (SELECT COUNT(*) FROM booking_requests b
+             WHERE b.artist_id = a.id AND b.status IN {}
+               AND b.requested_date >= TO_CHAR(CURRENT_DATE, 'YYYY-MM-DD')) as upcoming_bookings
+         FROM artists a
+         WHERE a.location_id = $1
+         ORDER BY a.name, a.id",
+        OPEN_STATUSES, BOOKED_STATUSES
+    ))
+    .bind(location_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ShopArtistSummary {
+            artist_id: row.get("artist_id"),
+            name: row.get("name"),
+            open_requests: row.get("open_requests"),
+            upcoming_bookings: row.get("upcoming_bookings"),
+        })
+        .collect())
+}
+
+/// Requests waiting on any of the shop's artists, oldest first so the
+/// longest-waiting clients are answered first
+#[cfg(feature = "ssr")]
+pub async fn get_shop_open_requests(location_id: i64) -> DbResult<Vec<ShopBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE a.location_id = $1 AND b.status IN {}
+         ORDER BY b.created_at, b.id",
+        SHOP_BOOKING_SELECT, OPEN_STATUSES
+    ))
+    .bind(location_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(shop_booking_from_row).collect())
+}
+
+/// Booked sessions across the shop's artists between two `YYYY-MM-DD`
+/// dates, inclusive, in time order
+#[cfg(feature = "ssr")]
+pub async fn get_shop_calendar(
+    location_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> DbResult<Vec<ShopBooking>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE a.location_id = $1 AND b.status IN {}
+           AND b.requested_date BETWEEN $2 AND $3
+         ORDER BY b.requested_date, b.requested_start_time, a.name",
+        SHOP_BOOKING_SELECT, BOOKED_STATUSES
+    ))
+    .bind(location_id)
+    .bind(start_date)
+    .bind(end_date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(shop_booking_from_row).collect())
+}
@@ -0,0 +1,136 @@
+#[cfg(feature = "ssr")]
+use super::entities::ReferrerClicks;
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A link's destination, as stored
+#[cfg(feature = "ssr")]
+pub struct ShortLinkTarget {
+    pub target_type: String,
+    pub target: String,
+}
+
+/// The target's code if it already has one
+#[cfg(feature = "ssr")]
+pub async fn get_code(target_type: &str, target: &str) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar("SELECT code FROM short_links WHERE target_type = $1 AND target = $2")
+        .bind(target_type)
+        .bind(target)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Stores `code` for the target. Returns the code the target ends up with,
+/// which is another one if someone shared it first, or `None` if `code` is
+/// already taken by a different target.
+#[cfg(feature = "ssr")]
+pub async fn insert_link(
+    code: &str,
+    target_type: &str,
+    target: &str,
+    created_by: Option<i64>,
+) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let inserted: Option<String> = sqlx::query_scalar(
+        "INSERT INTO short_links (code, target_type, target, created_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT DO NOTHING
+         RETURNING code",
+    )
+    .bind(code)
+    .bind(target_type)
+    .bind(target)
+    .bind(created_by)
+    .fetch_optional(pool)
+    .await?;
+
+    match inserted {
+        Some(code) => Ok(Some(code)),
+        None => get_code(target_type, target).await,
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn resolve(code: &str) -> DbResult<Option<ShortLinkTarget>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT target_type, target FROM short_links WHERE code = $1")
+        .bind(code)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| ShortLinkTarget {
+        target_type: row.get("target_type"),
+        target: row.get("target"),
+    }))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn record_click(
+    code: &str,
+    referrer: Option<&str>,
+    referrer_host: Option<&str>,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO short_link_clicks (code, referrer, referrer_host) VALUES ($1, $2, $3)",
+    )
+    .bind(code)
+    .bind(referrer)
+    .bind(referrer_host)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE short_links
+         SET click_count = click_count + 1, last_clicked_at = CURRENT_TIMESTAMP
+         WHERE code = $1",
+    )
+    .bind(code)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// Total visits to the link and its `limit` busiest referrers
+#[cfg(feature = "ssr")]
+pub async fn get_clicks(code: &str, limit: i64) -> DbResult<(i64, Vec<ReferrerClicks>)> {
+    let pool = crate::db::pool::get_pool();
+
+    let clicks: i64 = sqlx::query_scalar("SELECT click_count FROM short_links WHERE code = $1")
+        .bind(code)
+        .fetch_optional(pool)
+        .await?
+        .unwrap_or(0);
+
+    let rows = sqlx::query(
+        "SELECT referrer_host, COUNT(*) as clicks
+         FROM short_link_clicks
+         WHERE code = $1
+         GROUP BY referrer_host
+         ORDER BY clicks DESC, referrer_host
+         LIMIT $2",
+    )
+    .bind(code)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok((
+        clicks,
+        rows.iter()
+            .map(|row| ReferrerClicks {
+                host: row.get("referrer_host"),
+                clicks: row.get("clicks"),
+            })
+            .collect(),
+    ))
+}
@@ -0,0 +1,248 @@
+#[cfg(feature = "ssr")]
+use super::entities::{Artist, ArtistImage, Style};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Images two styles must share before they count as related
+#[cfg(feature = "ssr")]
+const MIN_SHARED_IMAGES: i64 = 3;
+/// Related styles kept per style
+#[cfg(feature = "ssr")]
+const RELATED_PER_STYLE: i64 = 10;
+/// A related style counts for at most this much of a shared tag
+#[cfg(feature = "ssr")]
+const RELATED_STYLE_WEIGHT: f64 = 0.5;
+/// Added for images by artists in the same city as the viewed image; less
+/// than one shared tag, so it only breaks ties between equally close work
+#[cfg(feature = "ssr")]
+const SAME_CITY_BONUS: f64 = 0.75;
+/// Recommendations from any one artist, so a prolific artist can't fill
+/// the list; the viewed image's own artist gets fewer
+#[cfg(feature = "ssr")]
+const MAX_PER_ARTIST: i64 = 2;
+#[cfg(feature = "ssr")]
+const MAX_FROM_SAME_ARTIST: i64 = 1;
+
+#[cfg(feature = "ssr")]
+const IMAGE_SELECT: &str =
+    "ai.id::int as id, ai.short_code, ai.artist_id::int as artist_id, ai.post_date, ai.validated,
+     a.name, a.location_id::int as location_id, a.social_links, a.instagram_handle, a.email,
+     a.phone, a.years_experience::int as years_experience,
+     a.styles_extracted::int as styles_extracted, a.shop_validated, a.experience_tier";
+
+#[cfg(feature = "ssr")]
+fn image_from_row(row: &PgRow) -> (ArtistImage, Artist, bool) {
+    let image = ArtistImage {
+        id: row.get("id"),
+        short_code: row.get("short_code"),
+        artist_id: row.get("artist_id"),
+        post_date: row.try_get("post_date").ok(),
+        validated: row.try_get("validated").ok(),
+    };
+    let artist = Artist {
+        id: row.get("artist_id"),
+        name: row.get("name"),
+        location_id: row.try_get("location_id").unwrap_or(0),
+        social_links: row.get("social_links"),
+        instagram_handle: row.get("instagram_handle"),
+        email: row.get("email"),
+        phone: row.get("phone"),
+        years_experience: row.get("years_experience"),
+        styles_extracted: row.get("styles_extracted"),
+        shop_validated: row.try_get("shop_validated").ok(),
+        experience_tier: row.get("experience_tier"),
+    };
+    (image, artist, row.get("is_favorited"))
+}
+
+/// Attaches each image's style tags
+#[cfg(feature = "ssr")]
+async fn with_styles(
+    images: Vec<(ArtistImage, Artist, bool)>,
+) -> DbResult<Vec<(ArtistImage, Vec<Style>, Artist, bool)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let image_ids: Vec<i32> = images.iter().map(|(image, _, _)| image.id).collect();
+    let styles_by_image = super::repository::get_styles_for_images(pool, &image_ids).await?;
+
+    Ok(images
+        .into_iter()
+        .map(|(image, artist, is_favorited)| {
+            let styles = styles_by_image.get(&image.id).cloned().unwrap_or_default();
+            (image, styles, artist, is_favorited)
+        })
+        .collect())
+}
+
+/// Rebuilds `style_cooccurrence` from the current style tags, returning how
+/// many related style pairs it holds
+#[cfg(feature = "ssr")]
+pub async fn refresh_style_cooccurrence() -> DbResult<u64> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM style_cooccurrence")
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query(
+        "WITH tagged AS (
+            SELECT DISTINCT ais.artists_images_id, ais.style_id
+            FROM artists_images_styles ais
+            JOIN artists_images ai ON ai.id = ais.artists_images_id
+            WHERE ai.removed_at IS NULL
+         ),
+         totals AS (
+            SELECT style_id, COUNT(*) as images FROM tagged GROUP BY style_id
+         ),
+         pairs AS (
+            SELECT a.style_id, b.style_id as related_style_id, COUNT(*) as shared_images
+            FROM tagged a
+            JOIN tagged b ON b.artists_images_id = a.artists_images_id AND b.style_id <> a.style_id
+            GROUP BY a.style_id, b.style_id
+            HAVING COUNT(*) >= $1
+         ),
+         ranked AS (
+            SELECT p.style_id, p.related_style_id, p.shared_images,
+                p.shared_images::float8 / (ta.images + tb.images - p.shared_images) as similarity
+            FROM pairs p
+            JOIN totals ta ON ta.style_id = p.style_id
+            JOIN totals tb ON tb.style_id = p.related_style_id
+         )
+         INSERT INTO style_cooccurrence (style_id, related_style_id, shared_images, similarity)
+         SELECT style_id, related_style_id, shared_images, similarity
+         FROM (
+            SELECT r.*, ROW_NUMBER() OVER (
+                PARTITION BY r.style_id ORDER BY r.similarity DESC, r.related_style_id
+            ) as position
+            FROM ranked r
+         ) kept
+         WHERE position <= $2",
+    )
+    .bind(MIN_SHARED_IMAGES)
+    .bind(RELATED_PER_STYLE)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// A portfolio image with its artist and tags, unless it was removed
+#[cfg(feature = "ssr")]
+pub async fn get_image(
+    image_id: i32,
+    user_id: Option<i64>,
+) -> DbResult<Option<(ArtistImage, Vec<Style>, Artist, bool)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {},
+            EXISTS (SELECT 1 FROM user_favorites uf
+                    WHERE uf.artists_images_id = ai.id AND uf.user_id = $2) as is_favorited
+         FROM artists_images ai
+         JOIN artists a ON a.id = ai.artist_id
+         WHERE ai.id = $1 AND ai.removed_at IS NULL",
+        IMAGE_SELECT
+    ))
+    .bind(image_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    Ok(with_styles(vec![image_from_row(&row)]).await?.pop())
+}
+
+/// Work similar to the image: other images ranked by the style tags they
+/// share with it, with styles that often appear alongside its tags counting
+/// for part of a tag, then by being in the same city. Each artist appears at
+/// most `MAX_PER_ARTIST` times.
+#[cfg(feature = "ssr")]
+pub async fn get_similar_images(
+    image_id: i32,
+    user_id: Option<i64>,
+    limit: i64,
+) -> DbResult<Vec<(ArtistImage, Vec<Style>, Artist, bool)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "WITH source AS (
+            SELECT ai.artist_id, l.city, l.state
+            FROM artists_images ai
+            JOIN artists a ON a.id = ai.artist_id
+            LEFT JOIN locations l ON l.id = a.location_id
+            WHERE ai.id = $1
+         ),
+         source_styles AS (
+            SELECT DISTINCT style_id FROM artists_images_styles WHERE artists_images_id = $1
+         ),
+         related AS (
+            SELECT style_id, MAX(weight) as weight
+            FROM (
+                SELECT style_id, 1.0::float8 as weight FROM source_styles
+                UNION ALL
+                SELECT sc.related_style_id, sc.similarity * {related_weight}
+                FROM style_cooccurrence sc
+                JOIN source_styles ss ON ss.style_id = sc.style_id
+            ) weights
+            GROUP BY style_id
+         ),
+         style_scores AS (
+            SELECT ais.artists_images_id, SUM(r.weight) as style_score
+            FROM (SELECT DISTINCT artists_images_id, style_id FROM artists_images_styles) ais
+            JOIN related r ON r.style_id = ais.style_id
+            WHERE ais.artists_images_id <> $1
+            GROUP BY ais.artists_images_id
+         ),
+         scored AS (
+            SELECT ai.id as scored_id, ai.artist_id as scored_artist_id,
+                s.style_score
+                    + CASE WHEN l.city = src.city AND l.state = src.state
+                        THEN {same_city_bonus} ELSE 0 END as score,
+                ai.artist_id = src.artist_id as same_artist
+            FROM style_scores s
+            JOIN artists_images ai ON ai.id = s.artists_images_id
+            JOIN artists a ON a.id = ai.artist_id
+            LEFT JOIN locations l ON l.id = a.location_id
+            CROSS JOIN source src
+            WHERE ai.removed_at IS NULL AND ai.hidden_at IS NULL
+            AND (l.is_person IS NULL OR l.is_person = 0)
+            AND a.name IS NOT NULL
+            AND a.name != ''
+         ),
+         ranked AS (
+            SELECT scored.*, ROW_NUMBER() OVER (
+                PARTITION BY scored_artist_id ORDER BY score DESC, scored_id DESC
+            ) as artist_position
+            FROM scored
+         )
+         SELECT {select},
+            EXISTS (SELECT 1 FROM user_favorites uf
+                    WHERE uf.artists_images_id = ai.id AND uf.user_id = $2) as is_favorited
+         FROM ranked r
+         JOIN artists_images ai ON ai.id = r.scored_id
+         JOIN artists a ON a.id = ai.artist_id
+         WHERE r.artist_position <= CASE WHEN r.same_artist
+            THEN {max_same_artist} ELSE {max_per_artist} END
+         ORDER BY r.score DESC, ai.id DESC
+         LIMIT $3",
+        related_weight = RELATED_STYLE_WEIGHT,
+        same_city_bonus = SAME_CITY_BONUS,
+        select = IMAGE_SELECT,
+        max_same_artist = MAX_FROM_SAME_ARTIST,
+        max_per_artist = MAX_PER_ARTIST,
+    ))
+    .bind(image_id)
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    with_styles(rows.iter().map(image_from_row).collect()).await
+}
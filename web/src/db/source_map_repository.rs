@@ -0,0 +1,152 @@
+#[cfg(feature = "ssr")]
+use crate::utils::source_map::{frame_location, SourceMap};
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+#[cfg(feature = "ssr")]
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Parsed maps kept in memory; errors cluster on the latest build, so a
+/// handful covers nearly every lookup
+#[cfg(feature = "ssr")]
+const MAX_CACHED_MAPS: usize = 8;
+
+/// `None` records a map that isn't stored, so frames from files without maps
+/// don't hit the database every time
+#[cfg(feature = "ssr")]
+type MapCache = HashMap<(String, String), Option<Arc<SourceMap>>>;
+
+#[cfg(feature = "ssr")]
+static CACHE: OnceLock<RwLock<MapCache>> = OnceLock::new();
+
+/// Stores (or replaces) the source map for one bundle file of a build
+#[cfg(feature = "ssr")]
+pub async fn store_source_map(build_id: &str, file_name: &str, source_map: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO client_source_maps (build_id, file_name, source_map)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (build_id, file_name)
+         DO UPDATE SET source_map = EXCLUDED.source_map, uploaded_at = CURRENT_TIMESTAMP",
+    )
+    .bind(build_id)
+    .bind(file_name)
+    .bind(source_map)
+    .execute(pool)
+    .await?;
+
+    if let Some(cache) = CACHE.get() {
+        cache
+            .write()
+            .unwrap()
+            .remove(&(build_id.to_string(), file_name.to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+async fn load_source_map(build_id: &str, file_name: &str) -> DbResult<Option<Arc<SourceMap>>> {
+    let key = (build_id.to_string(), file_name.to_string());
+    let cache = CACHE.get_or_init(|| RwLock::new(HashMap::new()));
+
+    if let Some(cached) = cache.read().unwrap().get(&key) {
+        return Ok(cached.clone());
+    }
+
+    let pool = crate::db::pool::get_pool();
+    let row = sqlx::query(
+        "SELECT source_map FROM client_source_maps WHERE build_id = $1 AND file_name = $2",
+    )
+    .bind(build_id)
+    .bind(file_name)
+    .fetch_optional(pool)
+    .await?;
+
+    let source_map = row.and_then(|row| {
+        let json: String = row.get("source_map");
+        match SourceMap::parse(&json) {
+            Ok(map) => Some(Arc::new(map)),
+            Err(e) => {
+                tracing::warn!("Unusable source map {}/{}: {}", build_id, file_name, e);
+                None
+            }
+        }
+    });
+
+    let mut cache = cache.write().unwrap();
+    if cache.len() >= MAX_CACHED_MAPS {
+        cache.clear();
+    }
+    cache.insert(key, source_map.clone());
+
+    Ok(source_map)
+}
+
+/// Rewrites each frame of a minified client stack to its original source
+/// position. Frames that can't be resolved are kept as they are; returns
+/// `None` when nothing resolved (e.g. no maps for the build).
+#[cfg(feature = "ssr")]
+pub async fn symbolicate_stack(build_id: &str, stack: &str) -> DbResult<Option<String>> {
+    let mut resolved_any = false;
+    let mut lines = Vec::new();
+
+    for frame in stack.lines() {
+        let resolved = match frame_location(frame) {
+            Some((file_name, line, column)) => load_source_map(build_id, &file_name)
+                .await?
+                .and_then(|map| map.lookup(line, column)),
+            None => None,
+        };
+
+        match resolved {
+            Some(position) => {
+                resolved_any = true;
+                lines.push(format!(
+                    "    at {} ({}:{}:{})",
+                    position.name.as_deref().unwrap_or("<anonymous>"),
+                    position.source,
+                    position.line,
+                    position.column
+                ));
+            }
+            None => lines.push(frame.to_string()),
+        }
+    }
+
+    Ok(resolved_any.then(|| lines.join("\n")))
+}
+
+/// Symbolicates a logged error's stack with its build's maps and saves the
+/// result. Returns the readable stack, if one could be produced.
+#[cfg(feature = "ssr")]
+pub async fn symbolicate_error_log(error_id: i64) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT build_id, error_stack FROM error_logs WHERE id = $1")
+        .bind(error_id)
+        .fetch_one(pool)
+        .await?;
+
+    let build_id: Option<String> = row.get("build_id");
+    let stack: Option<String> = row.get("error_stack");
+    let (Some(build_id), Some(stack)) = (build_id, stack) else {
+        return Ok(None);
+    };
+
+    let symbolicated = symbolicate_stack(&build_id, &stack).await?;
+    if symbolicated.is_some() {
+        sqlx::query("UPDATE error_logs SET symbolicated_stack = $2 WHERE id = $1")
+            .bind(error_id)
+            .bind(&symbolicated)
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(symbolicated)
+}
@@ -0,0 +1,527 @@
+#[cfg(feature = "ssr")]
+use crate::server::{
+    QuizImageVariant, QuizVariantReport, StarterImage, StylePickShare, StyleStarterPack,
+};
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+#[cfg(feature = "ssr")]
+use std::sync::{OnceLock, RwLock};
+#[cfg(feature = "ssr")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Images shown per style in the quiz
+#[cfg(feature = "ssr")]
+pub const IMAGES_PER_STYLE: usize = 6;
+
+/// Packs change only through admin curation or ingestion, so they're served
+/// from memory and rebuilt at most this often
+#[cfg(feature = "ssr")]
+const CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+#[cfg(feature = "ssr")]
+static CACHE: OnceLock<RwLock<Option<(Instant, Vec<StyleStarterPack>)>>> = OnceLock::new();
+
+/// Most recent validated images kept per artist in a style's rotation pool
+#[cfg(feature = "ssr")]
+const ROTATION_IMAGES_PER_ARTIST: i64 = 3;
+
+/// How far back exposures count when balancing the rotation
+#[cfg(feature = "ssr")]
+const EXPOSURE_WINDOW_DAYS: i32 = 30;
+
+/// Candidate images per style, rebuilt on the same schedule as the packs
+#[cfg(feature = "ssr")]
+static ROTATION_POOLS: OnceLock<RwLock<Option<(Instant, RotationPools)>>> = OnceLock::new();
+
+#[cfg(feature = "ssr")]
+type RotationPools = Vec<(i64, String, Vec<StarterImage>)>;
+
+/// Instagram's media redirect at the medium (~320px) size the quiz tiles use
+#[cfg(feature = "ssr")]
+fn quiz_image_url(short_code: &str) -> String {
+    format!("https://www.instagram.com/p/{}/media/?size=m", short_code)
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_starter_packs() -> DbResult<Vec<StyleStarterPack>> {
+    let cache = CACHE.get_or_init(|| RwLock::new(None));
+
+    if let Some((built_at, packs)) = cache.read().unwrap().as_ref() {
+        if built_at.elapsed() < CACHE_TTL {
+            return Ok(packs.clone());
+        }
+    }
+
+    let packs = build_starter_packs().await?;
+    *cache.write().unwrap() = Some((Instant::now(), packs.clone()));
+    Ok(packs)
+}
+
+/// Drops the cached packs so the next request sees curation changes
+#[cfg(feature = "ssr")]
+pub fn invalidate_starter_packs() {
+    if let Some(cache) = CACHE.get() {
+        *cache.write().unwrap() = None;
+    }
+    if let Some(pools) = ROTATION_POOLS.get() {
+        *pools.write().unwrap() = None;
+    }
+}
+
+/// Curated images first, topped up with recent validated images (one per
+/// artist) for styles that haven't been fully curated.
+#[cfg(feature = "ssr")]
+async fn build_starter_packs() -> DbResult<Vec<StyleStarterPack>> {
+    let pool = crate::db::pool::get_pool();
+
+    let mut images: HashMap<i64, Vec<StarterImage>> = HashMap::new();
+
+    let curated_rows = sqlx::query(
+        "SELECT ssi.style_id, ssi.short_code,
+                (SELECT ai.artist_id::bigint FROM artists_images ai
+                 WHERE ai.short_code = ssi.short_code LIMIT 1) as artist_id
+         FROM style_starter_images ssi
+         ORDER BY ssi.style_id, ssi.position, ssi.id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for row in curated_rows {
+        let short_code: String = row.get("short_code");
+        images
+            .entry(row.get("style_id"))
+            .or_default()
+            .push(StarterImage {
+                image_url: quiz_image_url(&short_code),
+                short_code,
+                artist_id: row.get("artist_id"),
+                curated: true,
+            });
+    }
+
+    let fallback_rows = sqlx::query(
+        "WITH per_artist AS (
+            SELECT ais.style_id, ai.short_code, ai.post_date, ai.id, ai.artist_id,
+                   ROW_NUMBER() OVER (
+                       PARTITION BY ais.style_id, ai.artist_id
+                       ORDER BY ai.post_date DESC NULLS LAST, ai.id DESC
+                   ) as artist_rank
+            FROM artists_images_styles ais
+            JOIN artists_images ai ON ai.id = ais.artists_images_id
+            WHERE ai.validated = true AND ai.removed_at IS NULL AND ai.hidden_at IS NULL
+         ),
+         ranked AS (
+            SELECT style_id, short_code, artist_id,
+                   ROW_NUMBER() OVER (
+                       PARTITION BY style_id
+                       ORDER BY post_date DESC NULLS LAST, id DESC
+                   ) as style_rank
+            FROM per_artist
+            WHERE artist_rank = 1
+         )
+         SELECT style_id::bigint as style_id, short_code, artist_id::bigint as artist_id
+         FROM ranked
+         WHERE style_rank <= $1
+         ORDER BY style_id, style_rank",
+    )
+    .bind(IMAGES_PER_STYLE as i64)
+    .fetch_all(pool)
+    .await?;
+
+    for row in fallback_rows {
+        let style_images = images.entry(row.get("style_id")).or_default();
+        let short_code: String = row.get("short_code");
+        if style_images.len() < IMAGES_PER_STYLE
+            && !style_images
+                .iter()
+                .any(|image| image.short_code == short_code)
+        {
+            style_images.push(StarterImage {
+                image_url: quiz_image_url(&short_code),
+                short_code,
+                artist_id: row.get("artist_id"),
+                curated: false,
+            });
+        }
+    }
+
+    let style_rows = sqlx::query("SELECT id::bigint as id, name FROM styles ORDER BY name")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(style_rows
+        .into_iter()
+        .filter_map(|row| {
+            let style_id: i64 = row.get("id");
+            let mut style_images = images.remove(&style_id)?;
+            style_images.truncate(IMAGES_PER_STYLE);
+            Some(StyleStarterPack {
+                style_id,
+                style_name: row.get("name"),
+                images: style_images,
+            })
+        })
+        .collect())
+}
+
+/// Replaces the curated images for a style, in display order
+#[cfg(feature = "ssr")]
+pub async fn set_curated_images(
+    style_id: i64,
+    short_codes: &[String],
+    curated_by: i64,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM style_starter_images WHERE style_id = $1")
+        .bind(style_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (position, short_code) in short_codes.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO style_starter_images (style_id, short_code, position, curated_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (style_id, short_code) DO NOTHING",
+        )
+        .bind(style_id)
+        .bind(short_code)
+        .bind(position as i32)
+        .bind(curated_by)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    invalidate_starter_packs();
+    Ok(())
+}
+
+/// FNV-1a over the parts, so a session lands in the same variant and gets the
+/// same tie-breaks whichever server answers
+#[cfg(feature = "ssr")]
+fn stable_hash(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+/// Percent of quiz sessions shown the rotation pools, from
+/// `QUIZ_ROTATION_PERCENT` (default 50)
+#[cfg(feature = "ssr")]
+fn rotation_percent() -> u64 {
+    crate::config::config().quiz_rotation_percent
+}
+
+/// The image set a quiz session is shown, fixed for the session
+#[cfg(feature = "ssr")]
+pub fn variant_for_session(session_key: &str) -> QuizImageVariant {
+    if stable_hash(&["quiz-variant", session_key]) % 100 < rotation_percent() {
+        QuizImageVariant::Rotation
+    } else {
+        QuizImageVariant::Static
+    }
+}
+
+#[cfg(feature = "ssr")]
+async fn get_rotation_pools() -> DbResult<RotationPools> {
+    let cache = ROTATION_POOLS.get_or_init(|| RwLock::new(None));
+
+    if let Some((built_at, pools)) = cache.read().unwrap().as_ref() {
+        if built_at.elapsed() < CACHE_TTL {
+            return Ok(pools.clone());
+        }
+    }
+
+    let pools = build_rotation_pools().await?;
+    *cache.write().unwrap() = Some((Instant::now(), pools.clone()));
+    Ok(pools)
+}
+
+/// Each artist's few most recent validated images in every style they work
+/// in, grouped by artist within each style
+#[cfg(feature = "ssr")]
+async fn build_rotation_pools() -> DbResult<RotationPools> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "WITH per_artist AS (
+            SELECT ais.style_id, ai.short_code, ai.artist_id,
+                   ROW_NUMBER() OVER (
+                       PARTITION BY ais.style_id, ai.artist_id
+                       ORDER BY ai.post_date DESC NULLS LAST, ai.id DESC
+                   ) as artist_rank
+            FROM artists_images_styles ais
+            JOIN artists_images ai ON ai.id = ais.artists_images_id
+            WHERE ai.validated = true AND ai.removed_at IS NULL AND ai.hidden_at IS NULL
+         )
+         SELECT pa.style_id::bigint as style_id, s.name, pa.short_code,
+                pa.artist_id::bigint as artist_id
+         FROM per_artist pa
+         JOIN styles s ON s.id = pa.style_id
+         WHERE pa.artist_rank <= $1
+         ORDER BY s.name, pa.style_id, pa.artist_id, pa.artist_rank",
+    )
+    .bind(ROTATION_IMAGES_PER_ARTIST)
+    .fetch_all(pool)
+    .await?;
+
+    let mut pools: RotationPools = Vec::new();
+    for row in rows {
+        let style_id: i64 = row.get("style_id");
+        if pools.last().map(|(id, _, _)| *id) != Some(style_id) {
+            pools.push((style_id, row.get("name"), Vec::new()));
+        }
+        let short_code: String = row.get("short_code");
+        if let Some((_, _, images)) = pools.last_mut() {
+            images.push(StarterImage {
+                image_url: quiz_image_url(&short_code),
+                short_code,
+                artist_id: row.get("artist_id"),
+                curated: false,
+            });
+        }
+    }
+
+    Ok(pools)
+}
+
+/// Rotation exposures per (style, artist) over the balancing window. The
+/// static arm isn't counted, so it can't shape what the rotation arm sees.
+#[cfg(feature = "ssr")]
+async fn get_rotation_exposures() -> DbResult<HashMap<(i64, i64), i64>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT style_id, artist_id, COUNT(*) as exposures
+         FROM quiz_image_exposures
+         WHERE variant = 'rotation'
+           AND artist_id IS NOT NULL
+           AND shown_at > NOW() - make_interval(days => $1)
+         GROUP BY style_id, artist_id",
+    )
+    .bind(EXPOSURE_WINDOW_DAYS)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            (
+                (row.get("style_id"), row.get("artist_id")),
+                row.get("exposures"),
+            )
+        })
+        .collect())
+}
+
+/// Packs drawn from the rotation pools: per style, the least exposed artists
+/// lately (ties broken per session) with one of their recent images each.
+#[cfg(feature = "ssr")]
+pub async fn get_rotation_packs(session_key: &str) -> DbResult<Vec<StyleStarterPack>> {
+    let pools = get_rotation_pools().await?;
+    let exposures = get_rotation_exposures().await?;
+
+    Ok(pools
+        .into_iter()
+        .map(|(style_id, style_name, candidates)| {
+            let mut by_artist: Vec<(i64, Vec<StarterImage>)> = Vec::new();
+            for image in candidates {
+                let artist_id = image.artist_id.unwrap_or_default();
+                match by_artist.last_mut() {
+                    Some((id, images)) if *id == artist_id => images.push(image),
+                    _ => by_artist.push((artist_id, vec![image])),
+                }
+            }
+
+            let style_key = style_id.to_string();
+            by_artist.sort_by_cached_key(|(artist_id, _)| {
+                (
+                    exposures.get(&(style_id, *artist_id)).copied().unwrap_or(0),
+                    stable_hash(&[session_key, &style_key, &artist_id.to_string()]),
+                )
+            });
+
+            let images = by_artist
+                .into_iter()
+                .take(IMAGES_PER_STYLE)
+                .map(|(_, mut images)| {
+                    let pick = stable_hash(&[session_key, &style_key]) as usize % images.len();
+                    images.swap_remove(pick)
+                })
+                .collect();
+
+            StyleStarterPack {
+                style_id,
+                style_name,
+                images,
+            }
+        })
+        .collect())
+}
+
+/// Logs the images a quiz session was shown
+#[cfg(feature = "ssr")]
+pub async fn record_exposures(
+    session_key: &str,
+    variant: QuizImageVariant,
+    packs: &[StyleStarterPack],
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    let images = packs
+        .iter()
+        .flat_map(|pack| pack.images.iter().map(move |image| (pack.style_id, image)));
+    let (mut style_ids, mut artist_ids, mut short_codes) = (vec![], vec![], vec![]);
+    for (style_id, image) in images {
+        style_ids.push(style_id);
+        artist_ids.push(image.artist_id);
+        short_codes.push(image.short_code.clone());
+    }
+    if style_ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "INSERT INTO quiz_image_exposures (session_key, variant, style_id, artist_id, short_code)
+         SELECT $1, $2, style_id, artist_id, short_code
+         FROM UNNEST($3::bigint[], $4::bigint[], $5::text[])
+              AS t(style_id, artist_id, short_code)",
+    )
+    .bind(session_key)
+    .bind(variant.as_str())
+    .bind(&style_ids)
+    .bind(&artist_ids)
+    .bind(&short_codes)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Logs the styles a quiz session went on to search with
+#[cfg(feature = "ssr")]
+pub async fn record_style_picks(
+    session_key: &str,
+    variant: QuizImageVariant,
+    style_ids: &[i64],
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO quiz_style_picks (session_key, variant, style_id)
+         SELECT $1, $2, style_id
+         FROM UNNEST($3::bigint[]) AS t(style_id)
+         WHERE EXISTS (SELECT 1 FROM styles s WHERE s.id = t.style_id)",
+    )
+    .bind(session_key)
+    .bind(variant.as_str())
+    .bind(style_ids)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Exposure spread and style pick rates per variant over the last `days`
+#[cfg(feature = "ssr")]
+pub async fn get_variant_reports(days: i32) -> DbResult<Vec<QuizVariantReport>> {
+    let pool = crate::db::pool::get_pool();
+
+    let exposure_rows = sqlx::query(
+        "WITH recent AS (
+            SELECT * FROM quiz_image_exposures
+            WHERE shown_at > NOW() - make_interval(days => $1)
+         ),
+         per_artist AS (
+            SELECT variant, artist_id, COUNT(*) as exposures
+            FROM recent
+            WHERE artist_id IS NOT NULL
+            GROUP BY variant, artist_id
+         ),
+         deciles AS (
+            SELECT variant, exposures,
+                   NTILE(10) OVER (PARTITION BY variant ORDER BY exposures DESC) as decile
+            FROM per_artist
+         ),
+         top_share AS (
+            SELECT variant,
+                   COALESCE(
+                       SUM(exposures) FILTER (WHERE decile = 1)::float8
+                           / NULLIF(SUM(exposures), 0),
+                       0
+                   ) as top_decile_share
+            FROM deciles
+            GROUP BY variant
+         )
+         SELECT r.variant,
+                COUNT(DISTINCT r.session_key) as sessions,
+                COUNT(*) as exposures,
+                COUNT(DISTINCT r.artist_id) as artists_shown,
+                COALESCE(MAX(t.top_decile_share), 0) as top_decile_share
+         FROM recent r
+         LEFT JOIN top_share t ON t.variant = r.variant
+         GROUP BY r.variant
+         ORDER BY r.variant",
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await?;
+
+    let pick_rows = sqlx::query(
+        "SELECT p.variant, p.style_id::bigint as style_id, s.name, COUNT(*) as picks,
+                COUNT(*)::float8 / NULLIF(
+                    (SELECT COUNT(DISTINCT p2.session_key) FROM quiz_style_picks p2
+                     WHERE p2.variant = p.variant
+                       AND p2.picked_at > NOW() - make_interval(days => $1)),
+                    0
+                ) as pick_rate
+         FROM quiz_style_picks p
+         JOIN styles s ON s.id = p.style_id
+         WHERE p.picked_at > NOW() - make_interval(days => $1)
+         GROUP BY p.variant, p.style_id, s.name
+         ORDER BY p.variant, picks DESC, s.name",
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await?;
+
+    let mut picks: HashMap<String, Vec<StylePickShare>> = HashMap::new();
+    for row in pick_rows {
+        picks
+            .entry(row.get("variant"))
+            .or_default()
+            .push(StylePickShare {
+                style_id: row.get("style_id"),
+                style_name: row.get("name"),
+                picks: row.get("picks"),
+                pick_rate: row.get::<Option<f64>, _>("pick_rate").unwrap_or(0.0),
+            });
+    }
+
+    Ok(exposure_rows
+        .into_iter()
+        .map(|row| {
+            let variant: String = row.get("variant");
+            QuizVariantReport {
+                variant: QuizImageVariant::from_db(&variant),
+                sessions: row.get("sessions"),
+                exposures: row.get("exposures"),
+                artists_shown: row.get("artists_shown"),
+                top_decile_share: row.get("top_decile_share"),
+                style_picks: picks.remove(&variant).unwrap_or_default(),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "ssr")]
+use super::entities::StatusIncident;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+const INCIDENT_COLUMNS: &str = "id, subsystem, severity, title, message,
+    TO_CHAR(started_at, 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') as started_at,
+    TO_CHAR(resolved_at, 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') as resolved_at";
+
+#[cfg(feature = "ssr")]
+fn incident_from_row(row: &PgRow) -> StatusIncident {
+    StatusIncident {
+        id: row.get("id"),
+        subsystem: row.get("subsystem"),
+        severity: row.get("severity"),
+        title: row.get("title"),
+        message: row.get("message"),
+        started_at: row.get("started_at"),
+        resolved_at: row.get("resolved_at"),
+    }
+}
+
+/// Round-trip time of a trivial query, in milliseconds
+#[cfg(feature = "ssr")]
+pub async fn ping_database() -> DbResult<u128> {
+    let pool = crate::db::pool::get_pool();
+    let started = std::time::Instant::now();
+
+    sqlx::query("SELECT 1").execute(pool).await?;
+
+    Ok(started.elapsed().as_millis())
+}
+
+/// The latest finished data-ingestion run as `(action, hours since it finished)`
+#[cfg(feature = "ssr")]
+pub async fn get_last_ingestion_run() -> DbResult<Option<(String, f64)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT action,
+                (EXTRACT(EPOCH FROM (CURRENT_TIMESTAMP - finished_at)) / 3600)::float8 as hours_ago
+         FROM ingestion_runs
+         ORDER BY finished_at DESC
+         LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("action"), row.get("hours_ago"))))
+}
+
+/// Open incidents, then those resolved in the last `resolved_days`
+#[cfg(feature = "ssr")]
+pub async fn get_status_incidents(resolved_days: i32) -> DbResult<Vec<StatusIncident>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM status_incidents
+         WHERE resolved_at IS NULL
+            OR resolved_at > CURRENT_TIMESTAMP - make_interval(days => $1)
+         ORDER BY resolved_at IS NOT NULL, started_at DESC",
+        INCIDENT_COLUMNS
+    ))
+    .bind(resolved_days)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(incident_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn create_status_incident(
+    subsystem: &str,
+    severity: &str,
+    title: &str,
+    message: Option<&str>,
+    created_by: i64,
+) -> DbResult<StatusIncident> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "INSERT INTO status_incidents (subsystem, severity, title, message, created_by)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING {}",
+        INCIDENT_COLUMNS
+    ))
+    .bind(subsystem)
+    .bind(severity)
+    .bind(title)
+    .bind(message)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(incident_from_row(&row))
+}
+
+/// Changes an open incident's severity and/or message as it develops
+#[cfg(feature = "ssr")]
+pub async fn update_status_incident(
+    incident_id: i64,
+    severity: Option<&str>,
+    message: Option<&str>,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE status_incidents
+         SET severity = COALESCE($2, severity),
+             message = COALESCE($3, message),
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND resolved_at IS NULL",
+    )
+    .bind(incident_id)
+    .bind(severity)
+    .bind(message)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(feature = "ssr")]
+pub async fn resolve_status_incident(incident_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE status_incidents
+         SET resolved_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1 AND resolved_at IS NULL",
+    )
+    .bind(incident_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,232 @@
+#[cfg(feature = "ssr")]
+use sqlx::{PgPool, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+async fn get_style_name(pool: &PgPool, style_id: i64) -> DbResult<String> {
+    let row = sqlx::query("SELECT name FROM styles WHERE id = $1")
+        .bind(style_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.get("name"))
+}
+
+/// Counts everything that would change if `source_style_id` were merged into
+/// `target_style_id`, without modifying any rows.
+#[cfg(feature = "ssr")]
+pub async fn preview_style_merge(
+    source_style_id: i64,
+    target_style_id: i64,
+) -> DbResult<crate::server::StyleMergePreview> {
+    let pool = crate::db::pool::get_pool();
+
+    let source_name = get_style_name(pool, source_style_id).await?;
+    let target_name = get_style_name(pool, target_style_id).await?;
+
+    let row = sqlx::query(
+        "SELECT
+            (SELECT COUNT(DISTINCT artist_id) FROM artists_styles WHERE style_id = $1) as affected_artists,
+            (SELECT COUNT(DISTINCT artist_id) FROM artists_styles
+             WHERE style_id = $1
+               AND artist_id IN (SELECT artist_id FROM artists_styles WHERE style_id = $2)) as overlapping_artists,
+            (SELECT COUNT(DISTINCT artists_images_id) FROM artists_images_styles WHERE style_id = $1) as affected_images,
+            (SELECT COUNT(DISTINCT artists_images_id) FROM artists_images_styles
+             WHERE style_id = $1
+               AND artists_images_id IN (SELECT artists_images_id FROM artists_images_styles WHERE style_id = $2)) as overlapping_images,
+            (SELECT COUNT(*) FROM client_quiz_sessions
+             WHERE LOWER(style_preference) LIKE '%' || LOWER($3) || '%') as quiz_filter_uses,
+            (SELECT COUNT(*) FROM style_aliases WHERE style_id = $1) as existing_aliases",
+    )
+    .bind(source_style_id)
+    .bind(target_style_id)
+    .bind(&source_name)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(crate::server::StyleMergePreview {
+        source_style_id,
+        source_name,
+        target_style_id,
+        target_name,
+        affected_artists: row.get::<i64, _>("affected_artists") as i32,
+        overlapping_artists: row.get::<i64, _>("overlapping_artists") as i32,
+        affected_images: row.get::<i64, _>("affected_images") as i32,
+        overlapping_images: row.get::<i64, _>("overlapping_images") as i32,
+        quiz_filter_uses: row.get::<i64, _>("quiz_filter_uses") as i32,
+        existing_aliases: row.get::<i64, _>("existing_aliases") as i32,
+    })
+}
+
+/// Merges `source_style_id` into `target_style_id` in a single transaction.
+///
+/// Artist, image and flash tags are re-pointed at the target (duplicates are
+/// dropped), the source name and any aliases it already carried are recorded
+/// against the target, and the source style is deleted.
+#[cfg(feature = "ssr")]
+pub async fn merge_styles(
+    source_style_id: i64,
+    target_style_id: i64,
+    merged_by: i64,
+) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let source_name: String = sqlx::query("SELECT name FROM styles WHERE id = $1 FOR UPDATE")
+        .bind(source_style_id)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("name");
+
+    // Lock the target too so it can't be merged away concurrently
+    sqlx::query("SELECT id FROM styles WHERE id = $1 FOR UPDATE")
+        .bind(target_style_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE artists_styles SET style_id = $2
+         WHERE style_id = $1
+           AND artist_id NOT IN (SELECT artist_id FROM artists_styles WHERE style_id = $2)",
+    )
+    .bind(source_style_id)
+    .bind(target_style_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM artists_styles WHERE style_id = $1")
+        .bind(source_style_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE artists_images_styles SET style_id = $2
+         WHERE style_id = $1
+           AND artists_images_id NOT IN
+               (SELECT artists_images_id FROM artists_images_styles WHERE style_id = $2)",
+    )
+    .bind(source_style_id)
+    .bind(target_style_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM artists_images_styles WHERE style_id = $1")
+        .bind(source_style_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE image_style_llm_recommendations SET style_id = $2
+         WHERE style_id = $1
+           AND artists_images_id NOT IN
+               (SELECT artists_images_id FROM image_style_llm_recommendations WHERE style_id = $2)",
+    )
+    .bind(source_style_id)
+    .bind(target_style_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM image_style_llm_recommendations WHERE style_id = $1")
+        .bind(source_style_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "UPDATE flash_design_styles SET style_id = $2
+         WHERE style_id = $1
+           AND flash_design_id NOT IN
+               (SELECT flash_design_id FROM flash_design_styles WHERE style_id = $2)",
+    )
+    .bind(source_style_id)
+    .bind(target_style_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Keep curated quiz examples; the cascade would otherwise drop them
+    sqlx::query(
+        "UPDATE style_starter_images SET style_id = $2
+         WHERE style_id = $1
+           AND short_code NOT IN
+               (SELECT short_code FROM style_starter_images WHERE style_id = $2)",
+    )
+    .bind(source_style_id)
+    .bind(target_style_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Quiz logs follow the style so variant comparisons survive the merge
+    for table in ["quiz_image_exposures", "quiz_style_picks"] {
+        sqlx::query(&format!(
+            "UPDATE {} SET style_id = $2 WHERE style_id = $1",
+            table
+        ))
+        .bind(source_style_id)
+        .bind(target_style_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Carry over aliases from earlier merges before the source row goes away
+    sqlx::query("UPDATE style_aliases SET style_id = $2 WHERE style_id = $1")
+        .bind(source_style_id)
+        .bind(target_style_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO style_aliases (alias, style_id, merged_by)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (LOWER(alias)) DO UPDATE SET
+            style_id = EXCLUDED.style_id,
+            merged_by = EXCLUDED.merged_by,
+            merged_at = CURRENT_TIMESTAMP",
+    )
+    .bind(&source_name)
+    .bind(target_style_id)
+    .bind(merged_by)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM styles WHERE id = $1")
+        .bind(source_style_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Maps any merged-away style names to the name of the style they were merged into.
+#[cfg(feature = "ssr")]
+pub async fn resolve_style_aliases(pool: &PgPool, names: Vec<String>) -> DbResult<Vec<String>> {
+    if names.is_empty() {
+        return Ok(names);
+    }
+
+    let rows = sqlx::query(
+        "SELECT LOWER(sa.alias) as alias, s.name
+         FROM style_aliases sa
+         JOIN styles s ON sa.style_id = s.id
+         WHERE LOWER(sa.alias) = ANY($1)",
+    )
+    .bind(names.iter().map(|n| n.to_lowercase()).collect::<Vec<_>>())
+    .fetch_all(pool)
+    .await?;
+
+    let mut resolved = Vec::with_capacity(names.len());
+    for name in names {
+        let canonical = rows
+            .iter()
+            .find(|row| row.get::<String, _>("alias") == name.to_lowercase())
+            .map(|row| row.get::<String, _>("name"))
+            .unwrap_or(name);
+        if !resolved.contains(&canonical) {
+            resolved.push(canonical);
+        }
+    }
+
+    Ok(resolved)
+}
@@ -0,0 +1,164 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// The Stripe price a tier is billed with, `None` for unknown tiers and
+/// tiers not set up in Stripe
+#[cfg(feature = "ssr")]
+pub async fn get_tier_price_id(tier_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let price_id = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT stripe_price_id FROM subscription_tiers WHERE id = $1",
+    )
+    .bind(tier_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(price_id.flatten())
+}
+
+/// The artist's customer id with the payment provider and whether a paid
+/// subscription of theirs is currently active
+#[cfg(feature = "ssr")]
+pub async fn get_billing_customer(artist_id: i32) -> DbResult<(Option<String>, bool)> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT MAX(stripe_customer_id) as customer_id,
+                COALESCE(BOOL_OR(status = 'active' AND stripe_subscription_id IS NOT NULL), FALSE)
+                    as has_active
+         FROM artist_subscriptions
+         WHERE artist_id = $1",
+    )
+    .bind(artist_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok((row.get("customer_id"), row.get("has_active")))
+}
+
+/// Records a webhook event, returning false if it was already recorded
+#[cfg(feature = "ssr")]
+pub async fn record_webhook_event(event_id: &str, event_type: &str) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "INSERT INTO stripe_webhook_events (id, event_type)
+         VALUES ($1, $2)
+         ON CONFLICT (id) DO NOTHING",
+    )
+    .bind(event_id)
+    .bind(event_type)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Forgets a recorded event so Stripe's retry is processed again
+#[cfg(feature = "ssr")]
+pub async fn forget_webhook_event(event_id: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("DELETE FROM stripe_webhook_events WHERE id = $1")
+        .bind(event_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// A paid invoice for an artist's subscription
+#[cfg(feature = "ssr")]
+pub struct SubscriptionPayment {
+    pub artist_id: i32,
+    pub tier_id: i32,
+    pub customer_id: String,
+    pub subscription_id: String,
+    /// End of the paid period, `YYYY-MM-DD HH:MM:SS` UTC
+    pub paid_until: String,
+}
+
+/// Marks the artist's subscription active after a payment, creating the row
+/// if the artist has none yet. Artists keep a single subscription row, so a
+/// new provider subscription takes over the artist's latest one.
+#[cfg(feature = "ssr")]
+pub async fn record_payment(payment: &SubscriptionPayment) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    let existing = sqlx::query(
+        "SELECT id FROM artist_subscriptions
+         WHERE stripe_subscription_id = $1 OR artist_id = $2
+         ORDER BY (stripe_subscription_id IS NOT DISTINCT FROM $1) DESC, id DESC
+         LIMIT 1
+         FOR UPDATE",
+    )
+    .bind(&payment.subscription_id)
+    .bind(payment.artist_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    match existing {
+        Some(row) => {
+            let id: i32 = row.get("id");
+            sqlx::query(
+                "UPDATE artist_subscriptions
+                 SET tier_id = $1, status = 'active', payment_method = 'stripe',
+                     stripe_customer_id = $2, stripe_subscription_id = $3,
+                     subscription_start = CASE WHEN stripe_subscription_id = $3
+                         THEN COALESCE(subscription_start, CURRENT_TIMESTAMP::text)
+                         ELSE CURRENT_TIMESTAMP::text END,
+                     subscription_end = NULL,
+                     last_payment = CURRENT_TIMESTAMP::text, next_payment = $4
+                 WHERE id = $5",
+            )
+            .bind(payment.tier_id)
+            .bind(&payment.customer_id)
+            .bind(&payment.subscription_id)
+            .bind(&payment.paid_until)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                "INSERT INTO artist_subscriptions
+                    (artist_id, tier_id, status, payment_method, stripe_customer_id,
+                     stripe_subscription_id, subscription_start, last_payment, next_payment)
+                 VALUES ($1, $2, 'active', 'stripe', $3, $4,
+                         CURRENT_TIMESTAMP::text, CURRENT_TIMESTAMP::text, $5)",
+            )
+            .bind(payment.artist_id)
+            .bind(payment.tier_id)
+            .bind(&payment.customer_id)
+            .bind(&payment.subscription_id)
+            .bind(&payment.paid_until)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Marks a provider subscription cancelled, returning false if it's unknown
+#[cfg(feature = "ssr")]
+pub async fn cancel_subscription(subscription_id: &str) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_subscriptions
+         SET status = 'cancelled', subscription_end = CURRENT_TIMESTAMP::text, next_payment = NULL
+         WHERE stripe_subscription_id = $1",
+    )
+    .bind(subscription_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
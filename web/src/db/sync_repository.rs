@@ -0,0 +1,237 @@
+#[cfg(feature = "ssr")]
+use super::entities::{AvailabilitySlot, BookingMessage, BookingRequest, SyncDelta, SyncTombstone};
+#[cfg(feature = "ssr")]
+use super::message_read_repository::{READ_COLUMN, READ_JOIN};
+#[cfg(feature = "ssr")]
+use sqlx::{PgPool, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Changes younger than this are held back for the next call, so rows from
+/// transactions that took a lower `seq` but haven't committed yet aren't
+/// skipped over.
+#[cfg(feature = "ssr")]
+const SETTLE_SECONDS: f64 = 2.0;
+
+#[cfg(feature = "ssr")]
+async fn load_bookings(
+    pool: &PgPool,
+    artist_id: i32,
+    ids: Option<&[i64]>,
+) -> DbResult<Vec<BookingRequest>> {
+    let rows = sqlx::query(
+        "SELECT id, artist_id, client_name, client_email, client_phone,
+                requested_date, requested_start_time, requested_end_time,
+                tattoo_description, placement, size_inches, reference_images,
+                message_from_client, status, artist_response, estimated_price,
+                created_at, updated_at, decline_reason, deposit_amount, deposit_status,
+                (SELECT l.name FROM guest_spots g JOIN locations l ON l.id = g.location_id
+                 WHERE g.id = booking_requests.guest_spot_id) as guest_spot_shop,
+                (SELECT f.title FROM flash_designs f
+                 WHERE f.id = booking_requests.flash_design_id) as flash_design_title
+         FROM booking_requests
+         WHERE artist_id = $1 AND ($2::bigint[] IS NULL OR id = ANY($2))
+         ORDER BY id",
+    )
+    .bind(artist_id)
+    .bind(ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| BookingRequest {
+            id: row.get("id"),
+            artist_id: row.get("artist_id"),
+            client_name: row.get("client_name"),
+            client_email: row.get("client_email"),
+            client_phone: row.get("client_phone"),
+            requested_date: row.get("requested_date"),
+            requested_start_time: row.get("requested_start_time"),
+            requested_end_time: row.get("requested_end_time"),
+            tattoo_description: row.get("tattoo_description"),
+            placement: row.get("placement"),
+            size_inches: row.get("size_inches"),
+            reference_images: row.get("reference_images"),
+            message_from_client: row.get("message_from_client"),
+            status: row.get("status"),
+            artist_response: row.get("artist_response"),
+            estimated_price: row.get("estimated_price"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            decline_reason: row.get("decline_reason"),
+            deposit_amount: row.get("deposit_amount"),
+            deposit_status: row.get("deposit_status"),
+            guest_spot_shop: row.get("guest_spot_shop"),
+            flash_design_title: row.get("flash_design_title"),
+        })
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+async fn load_messages(
+    pool: &PgPool,
+    artist_id: i32,
+    ids: Option<&[i64]>,
+) -> DbResult<Vec<BookingMessage>> {
+    let rows = sqlx::query(&format!(
+        "SELECT bm.id, bm.booking_request_id, bm.sender_type, bm.message, bm.created_at,
+                bm.language, bm.translated_message, bm.translated_language, {}
+         FROM booking_messages bm
+         JOIN booking_requests br ON br.id = bm.booking_request_id
+         {}
+         WHERE br.artist_id = $1 AND ($2::bigint[] IS NULL OR bm.id = ANY($2))
+         ORDER BY bm.id",
+        READ_COLUMN, READ_JOIN
+    ))
+    .bind(artist_id)
+    .bind(ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| BookingMessage {
+            id: row.get("id"),
+            booking_request_id: row.get("booking_request_id"),
+            sender_type: row.get("sender_type"),
+            message: row.get("message"),
+            created_at: row.get("created_at"),
+            language: row.get("language"),
+            translated_message: row.get("translated_message"),
+            translated_language: row.get("translated_language"),
+            is_read: row.get("is_read"),
+        })
+        .collect())
+}
+
+#[cfg(feature = "ssr")]
+async fn load_availability(
+    pool: &PgPool,
+    artist_id: i32,
+    ids: Option<&[i64]>,
+) -> DbResult<Vec<AvailabilitySlot>> {
+    let rows = sqlx::query(
+        "SELECT id, artist_id, day_of_week, specific_date, start_time, end_time,
+                is_available, is_recurring, created_at
+         FROM artist_availability
+         WHERE artist_id = $1 AND ($2::bigint[] IS NULL OR id = ANY($2))
+         ORDER BY id",
+    )
+    .bind(artist_id)
+    .bind(ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| AvailabilitySlot {
+            id: row.get("id"),
+            artist_id: row.get("artist_id"),
+            day_of_week: row.get("day_of_week"),
+            specific_date: row.get("specific_date"),
+            start_time: row.get("start_time"),
+            end_time: row.get("end_time"),
+            is_available: row.get("is_available"),
+            is_recurring: row.get("is_recurring"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+/// Everything the artist currently has, plus the cursor to sync from next.
+/// The cursor is read first, so a change racing the snapshot is sent again
+/// rather than lost.
+#[cfg(feature = "ssr")]
+pub async fn get_sync_snapshot(artist_id: i32) -> DbResult<SyncDelta> {
+    let pool = crate::db::pool::get_pool();
+
+    let cursor: i64 = sqlx::query("SELECT COALESCE(MAX(seq), 0) as seq FROM sync_changes")
+        .fetch_one(pool)
+        .await?
+        .get("seq");
+
+    Ok(SyncDelta {
+        cursor,
+        has_more: false,
+        bookings: load_bookings(pool, artist_id, None).await?,
+        messages: load_messages(pool, artist_id, None).await?,
+        availability: load_availability(pool, artist_id, None).await?,
+        tombstones: vec![],
+    })
+}
+
+/// Up to `limit` changes after `since`, collapsed to the latest state of each
+/// entity. Deleted entities come back as tombstones.
+#[cfg(feature = "ssr")]
+pub async fn get_sync_changes(artist_id: i32, since: i64, limit: i64) -> DbResult<SyncDelta> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT seq, entity_type, entity_id, op
+         FROM sync_changes
+         WHERE artist_id = $1 AND seq > $2
+           AND changed_at < CURRENT_TIMESTAMP - make_interval(secs => $4)
+         ORDER BY seq
+         LIMIT $3",
+    )
+    .bind(artist_id)
+    .bind(since)
+    .bind(limit)
+    .bind(SETTLE_SECONDS)
+    .fetch_all(pool)
+    .await?;
+
+    let has_more = rows.len() as i64 == limit;
+    let cursor = rows.last().map(|row| row.get("seq")).unwrap_or(since);
+
+    // Later changes to the same entity win
+    let mut latest: std::collections::HashMap<(String, i64), String> =
+        std::collections::HashMap::new();
+    for row in &rows {
+        latest.insert(
+            (row.get("entity_type"), row.get("entity_id")),
+            row.get("op"),
+        );
+    }
+
+    let mut upserts: std::collections::HashMap<&str, Vec<i64>> = std::collections::HashMap::new();
+    let mut tombstones = Vec::new();
+    for ((entity_type, entity_id), op) in &latest {
+        if op == "delete" {
+            tombstones.push(SyncTombstone {
+                entity_type: entity_type.clone(),
+                entity_id: *entity_id,
+            });
+        } else {
+            upserts
+                .entry(entity_type.as_str())
+                .or_default()
+                .push(*entity_id);
+        }
+    }
+    tombstones.sort_by(|a, b| (&a.entity_type, a.entity_id).cmp(&(&b.entity_type, b.entity_id)));
+
+    let bookings = match upserts.get("booking") {
+        Some(ids) => load_bookings(pool, artist_id, Some(ids)).await?,
+        None => vec![],
+    };
+    let messages = match upserts.get("message") {
+        Some(ids) => load_messages(pool, artist_id, Some(ids)).await?,
+        None => vec![],
+    };
+    let availability = match upserts.get("availability") {
+        Some(ids) => load_availability(pool, artist_id, Some(ids)).await?,
+        None => vec![],
+    };
+
+    Ok(SyncDelta {
+        cursor,
+        has_more,
+        bookings,
+        messages,
+        availability,
+        tombstones,
+    })
+}
@@ -0,0 +1,281 @@
+#[cfg(feature = "ssr")]
+use super::entities::{ClientTattooPhoto, PublicTattooPhoto, TattooBookingOption};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A stored photo before it has a row
+#[cfg(feature = "ssr")]
+pub struct NewTattooPhoto {
+    pub user_id: i64,
+    pub booking_id: Option<i32>,
+    pub artist_id: Option<i32>,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub caption: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+const PHOTO_SELECT: &str =
+    "SELECT p.id, p.booking_id, p.artist_id, a.name as artist_name, p.storage_key,
+        p.thumbnail_key, p.width, p.height, p.caption, p.healed, p.is_public,
+        p.healed_consent_at IS NOT NULL as healed_consent,
+        TO_CHAR(p.created_at, 'YYYY-MM-DD') as created_at
+     FROM client_tattoo_photos p
+     LEFT JOIN artists a ON a.id = p.artist_id";
+
+#[cfg(feature = "ssr")]
+fn photo_from_row(row: &PgRow) -> ClientTattooPhoto {
+    use crate::tattoo_photo_uploads::photo_link;
+
+    let id: i64 = row.get("id");
+
+    ClientTattooPhoto {
+        id,
+        booking_id: row.get("booking_id"),
+        artist_id: row.get("artist_id"),
+        artist_name: row.get("artist_name"),
+        image_url: photo_link(id, false),
+        thumbnail_url: photo_link(id, true),
+        width: row.get("width"),
+        height: row.get("height"),
+        caption: row.get("caption"),
+        healed: row.get("healed"),
+        is_public: row.get("is_public"),
+        healed_consent: row.get("healed_consent"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn public_photo_from_row(row: &PgRow) -> PublicTattooPhoto {
+    let photo = photo_from_row(row);
+
+    PublicTattooPhoto {
+        id: photo.id,
+        artist_id: photo.artist_id,
+        artist_name: photo.artist_name,
+        image_url: photo.image_url,
+        thumbnail_url: photo.thumbnail_url,
+        width: photo.width,
+        height: photo.height,
+        caption: photo.caption,
+        created_at: photo.created_at,
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn insert_photo(photo: &NewTattooPhoto) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO client_tattoo_photos
+            (user_id, booking_id, artist_id, storage_key, thumbnail_key, content_type,
+             width, height, caption)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+         RETURNING id",
+    )
+    .bind(photo.user_id)
+    .bind(photo.booking_id)
+    .bind(photo.artist_id)
+    .bind(&photo.storage_key)
+    .bind(&photo.thumbnail_key)
+    .bind(&photo.content_type)
+    .bind(photo.width)
+    .bind(photo.height)
+    .bind(&photo.caption)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+/// Number of photos the client has stored
+#[cfg(feature = "ssr")]
+pub async fn count_photos(user_id: i64) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT COUNT(*) as count FROM client_tattoo_photos WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.get("count"))
+}
+
+/// The client's photos, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_photos_for_user(user_id: i64) -> DbResult<Vec<ClientTattooPhoto>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE p.user_id = $1 ORDER BY p.created_at DESC, p.id DESC",
+        PHOTO_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(photo_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_photo(user_id: i64, photo_id: i64) -> DbResult<Option<ClientTattooPhoto>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE p.id = $1 AND p.user_id = $2", PHOTO_SELECT))
+        .bind(photo_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(photo_from_row))
+}
+
+/// A photo its owner has shared publicly
+#[cfg(feature = "ssr")]
+pub async fn get_public_photo(photo_id: i64) -> DbResult<Option<PublicTattooPhoto>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE p.id = $1 AND p.is_public", PHOTO_SELECT))
+        .bind(photo_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(public_photo_from_row))
+}
+
+/// Healed photos of the artist's work whose owners consented to them being
+/// shown, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_healed_work(artist_id: i32, limit: i64) -> DbResult<Vec<PublicTattooPhoto>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE p.artist_id = $1 AND p.healed AND p.healed_consent_at IS NOT NULL
+         ORDER BY p.created_at DESC, p.id DESC
+         LIMIT $2",
+        PHOTO_SELECT
+    ))
+    .bind(artist_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(public_photo_from_row).collect())
+}
+
+/// Updates the client's caption and sharing choices. Consent is
+/// timestamped when first given and cleared when withdrawn.
+#[cfg(feature = "ssr")]
+pub async fn update_photo(
+    user_id: i64,
+    photo_id: i64,
+    caption: Option<&str>,
+    healed: bool,
+    is_public: bool,
+    healed_consent: bool,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE client_tattoo_photos
+         SET caption = $3, healed = $4, is_public = $5,
+             healed_consent_at = CASE WHEN $6 THEN COALESCE(healed_consent_at, CURRENT_TIMESTAMP) END
+         WHERE id = $1 AND user_id = $2",
+    )
+    .bind(photo_id)
+    .bind(user_id)
+    .bind(caption)
+    .bind(healed)
+    .bind(is_public)
+    .bind(healed_consent)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// The photo's storage and thumbnail keys
+#[cfg(feature = "ssr")]
+pub async fn get_photo_keys(photo_id: i64) -> DbResult<Option<(String, String)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT storage_key, thumbnail_key FROM client_tattoo_photos
+         WHERE id = $1",
+    )
+    .bind(photo_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("storage_key"), row.get("thumbnail_key"))))
+}
+
+/// Removes the row, returning its storage and thumbnail keys so the caller
+/// can delete the files
+#[cfg(feature = "ssr")]
+pub async fn delete_photo(user_id: i64, photo_id: i64) -> DbResult<Option<(String, String)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "DELETE FROM client_tattoo_photos
+         WHERE id = $1 AND user_id = $2
+         RETURNING storage_key, thumbnail_key",
+    )
+    .bind(photo_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("storage_key"), row.get("thumbnail_key"))))
+}
+
+/// Completed bookings made with the client's email, newest first
+#[cfg(feature = "ssr")]
+pub async fn get_completed_bookings(client_email: &str) -> DbResult<Vec<TattooBookingOption>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT br.id, a.name as artist_name, br.requested_date
+         FROM booking_requests br
+         LEFT JOIN artists a ON a.id = br.artist_id
+         WHERE LOWER(br.client_email) = LOWER($1) AND br.status = 'completed'
+         ORDER BY br.requested_date DESC",
+    )
+    .bind(client_email)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TattooBookingOption {
+            booking_id: row.get("id"),
+            artist_name: row.get("artist_name"),
+            requested_date: row.get("requested_date"),
+        })
+        .collect())
+}
+
+/// The artist of one of the client's completed bookings
+#[cfg(feature = "ssr")]
+pub async fn get_completed_booking_artist(
+    client_email: &str,
+    booking_id: i32,
+) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "SELECT artist_id FROM booking_requests
+         WHERE id = $1 AND LOWER(client_email) = LOWER($2) AND status = 'completed'",
+    )
+    .bind(booking_id)
+    .bind(client_email)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,179 @@
+#[cfg(feature = "ssr")]
+use super::entities::{TeamAccess, TeamMember};
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[cfg(feature = "ssr")]
+const MEMBER_SELECT: &str = "SELECT m.id, m.email, m.permissions,
+        CASE WHEN u.id IS NOT NULL THEN CONCAT_WS(' ', u.first_name, u.last_name) END as name,
+        CASE
+            WHEN m.accepted_at IS NOT NULL THEN 'active'
+            WHEN m.invite_expires_at <= CURRENT_TIMESTAMP THEN 'expired'
+            ELSE 'invited'
+        END as status,
+        TO_CHAR(m.invited_at, 'YYYY-MM-DD') as invited_at,
+        TO_CHAR(m.accepted_at, 'YYYY-MM-DD') as accepted_at
+     FROM artist_team_members m
+     LEFT JOIN users u ON u.id = m.user_id";
+
+#[cfg(feature = "ssr")]
+fn member_from_row(row: &PgRow) -> TeamMember {
+    TeamMember {
+        id: row.get("id"),
+        email: row.get("email"),
+        name: row.get("name"),
+        permissions: row.get("permissions"),
+        status: row.get("status"),
+        invited_at: row.get("invited_at"),
+        accepted_at: row.get("accepted_at"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn insert_invitation(
+    artist_id: i32,
+    email: &str,
+    permissions: &[String],
+    invite_code_hash: &str,
+    invite_expires_at: chrono::DateTime<chrono::Utc>,
+) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "INSERT INTO artist_team_members
+            (artist_id, email, permissions, invite_code_hash, invite_expires_at)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING id",
+    )
+    .bind(artist_id)
+    .bind(email)
+    .bind(permissions)
+    .bind(invite_code_hash)
+    .bind(invite_expires_at)
+    .fetch_one(pool)
+    .await
+}
+
+/// Members and pending invitations, newest first
+#[cfg(feature = "ssr")]
+pub async fn list_members(artist_id: i32) -> DbResult<Vec<TeamMember>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE m.artist_id = $1 AND m.revoked_at IS NULL ORDER BY m.invited_at DESC",
+        MEMBER_SELECT
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(member_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_member(artist_id: i32, member_id: i64) -> DbResult<Option<TeamMember>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "{} WHERE m.artist_id = $1 AND m.id = $2 AND m.revoked_at IS NULL",
+        MEMBER_SELECT
+    ))
+    .bind(artist_id)
+    .bind(member_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(member_from_row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn update_permissions(
+    artist_id: i32,
+    member_id: i64,
+    permissions: &[String],
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_team_members SET permissions = $3
+         WHERE artist_id = $1 AND id = $2 AND revoked_at IS NULL",
+    )
+    .bind(artist_id)
+    .bind(member_id)
+    .bind(permissions)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes a member, or withdraws an invitation not yet accepted
+#[cfg(feature = "ssr")]
+pub async fn revoke_member(artist_id: i32, member_id: i64) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_team_members SET revoked_at = CURRENT_TIMESTAMP
+         WHERE artist_id = $1 AND id = $2 AND revoked_at IS NULL",
+    )
+    .bind(artist_id)
+    .bind(member_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Links a user to the live invitation with this code, if it was sent to
+/// their email. Returns the artist they joined.
+#[cfg(feature = "ssr")]
+pub async fn accept_invitation(
+    invite_code_hash: &str,
+    user_id: i64,
+    email: &str,
+) -> DbResult<Option<i32>> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query_scalar(
+        "UPDATE artist_team_members
+         SET user_id = $2, accepted_at = CURRENT_TIMESTAMP
+         WHERE invite_code_hash = $1
+           AND LOWER(email) = LOWER($3)
+           AND accepted_at IS NULL
+           AND revoked_at IS NULL
+           AND invite_expires_at > CURRENT_TIMESTAMP
+         RETURNING artist_id",
+    )
+    .bind(invite_code_hash)
+    .bind(user_id)
+    .bind(email)
+    .fetch_optional(pool)
+    .await
+}
+
+/// The artist account a user assists on, if any
+#[cfg(feature = "ssr")]
+pub async fn get_access(user_id: i64) -> DbResult<Option<TeamAccess>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT m.artist_id, a.name as artist_name, m.permissions
+         FROM artist_team_members m
+         JOIN artists a ON a.id = m.artist_id
+         WHERE m.user_id = $1 AND m.revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| TeamAccess {
+        artist_id: row.get("artist_id"),
+        artist_name: row
+            .get::<Option<String>, _>("artist_name")
+            .unwrap_or_default(),
+        permissions: row.get("permissions"),
+    }))
+}
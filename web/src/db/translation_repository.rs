@@ -0,0 +1,69 @@
+#[cfg(feature = "ssr")]
+use sqlx::Row;
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// Whether the artist has booking message translation turned on
+#[cfg(feature = "ssr")]
+pub async fn get_auto_translate(artist_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query("SELECT auto_translate_messages FROM artists WHERE id = $1")
+        .bind(artist_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.is_some_and(|row| row.get("auto_translate_messages")))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_auto_translate(artist_id: i32, enabled: bool) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE artists SET auto_translate_messages = $2 WHERE id = $1")
+        .bind(artist_id)
+        .bind(enabled)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Whether messages on a booking are translated, per its artist's setting
+#[cfg(feature = "ssr")]
+pub async fn is_enabled_for_booking(booking_id: i32) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT a.auto_translate_messages
+         FROM booking_requests br
+         JOIN artists a ON a.id = br.artist_id
+         WHERE br.id = $1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.is_some_and(|row| row.get("auto_translate_messages")))
+}
+
+/// The language the client most recently wrote in on a booking, if any of
+/// their messages had one detected
+#[cfg(feature = "ssr")]
+pub async fn get_client_language(booking_id: i32) -> DbResult<Option<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "SELECT language
+         FROM booking_messages
+         WHERE booking_request_id = $1 AND sender_type = 'client' AND language IS NOT NULL
+         ORDER BY created_at DESC, id DESC
+         LIMIT 1",
+    )
+    .bind(booking_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| row.get("language")))
+}
@@ -0,0 +1,301 @@
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Postgres, Row, Transaction};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct Upload {
+    pub id: String,
+    pub user_id: i64,
+    pub kind: String, // 'reference' or 'portfolio'
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub total_size: i64,
+    pub upload_offset: i64,
+    pub expected_sha256: Option<String>,
+    pub sha256: Option<String>,
+    pub storage_path: Option<String>,
+    pub status: String, // 'in_progress', 'complete', 'aborted', 'expired', 'purged', 'rejected'
+    /// Whether the uploader agreed to the image being stored (reference images)
+    pub consent_given: bool,
+}
+
+#[cfg(feature = "ssr")]
+const UPLOAD_COLUMNS: &str = "id, user_id, kind, filename, content_type, total_size,
+    upload_offset, expected_sha256, sha256, storage_path, status,
+    consent_given_at IS NOT NULL as consent_given";
+
+#[cfg(feature = "ssr")]
+fn upload_from_row(row: &PgRow) -> Upload {
+    Upload {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        kind: row.get("kind"),
+        filename: row.get("filename"),
+        content_type: row.get("content_type"),
+        total_size: row.get("total_size"),
+        upload_offset: row.get("upload_offset"),
+        expected_sha256: row.get("expected_sha256"),
+        sha256: row.get("sha256"),
+        storage_path: row.get("storage_path"),
+        status: row.get("status"),
+        consent_given: row.get("consent_given"),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub async fn create_upload(upload: &Upload) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "INSERT INTO uploads
+         (id, user_id, kind, filename, content_type, total_size, expected_sha256,
+          consent_given_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, CASE WHEN $8 THEN CURRENT_TIMESTAMP END)",
+    )
+    .bind(&upload.id)
+    .bind(upload.user_id)
+    .bind(&upload.kind)
+    .bind(&upload.filename)
+    .bind(&upload.content_type)
+    .bind(upload.total_size)
+    .bind(&upload.expected_sha256)
+    .bind(upload.consent_given)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_upload(id: &str) -> DbResult<Option<Upload>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM uploads WHERE id = $1",
+        UPLOAD_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(upload_from_row))
+}
+
+/// Loads an upload with a row lock so concurrent PATCH requests for the same
+/// upload are applied one at a time.
+#[cfg(feature = "ssr")]
+pub async fn lock_upload(tx: &mut Transaction<'_, Postgres>, id: &str) -> DbResult<Option<Upload>> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM uploads WHERE id = $1 FOR UPDATE",
+        UPLOAD_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(row.as_ref().map(upload_from_row))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_upload_offset(
+    tx: &mut Transaction<'_, Postgres>,
+    id: &str,
+    upload_offset: i64,
+) -> DbResult<()> {
+    sqlx::query(
+        "UPDATE uploads SET upload_offset = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+    )
+    .bind(id)
+    .bind(upload_offset)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn complete_upload(
+    tx: &mut Transaction<'_, Postgres>,
+    id: &str,
+    sha256: &str,
+    storage_path: &str,
+    content_type: &str,
+) -> DbResult<()> {
+    sqlx::query(
+        "UPDATE uploads
+         SET status = 'complete', sha256 = $2, storage_path = $3, content_type = $4,
+             updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(sha256)
+    .bind(storage_path)
+    .bind(content_type)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Ends an upload whose file turned out not to be a usable image
+#[cfg(feature = "ssr")]
+pub async fn reject_upload(
+    tx: &mut Transaction<'_, Postgres>,
+    id: &str,
+    reason: &str,
+) -> DbResult<()> {
+    sqlx::query(
+        "UPDATE uploads
+         SET status = 'rejected', rejection_reason = $2, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(reason)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn set_upload_status(id: &str, status: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query("UPDATE uploads SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(id)
+        .bind(status)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Marks in-progress uploads untouched for `max_idle_hours` as expired and
+/// returns their ids so the partial files can be removed.
+#[cfg(feature = "ssr")]
+pub async fn expire_abandoned_uploads(max_idle_hours: i32) -> DbResult<Vec<String>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "UPDATE uploads
+         SET status = 'expired', updated_at = CURRENT_TIMESTAMP
+         WHERE status = 'in_progress'
+           AND updated_at < CURRENT_TIMESTAMP - make_interval(hours => $1)
+         RETURNING id",
+    )
+    .bind(max_idle_hours)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|row| row.get("id")).collect())
+}
+
+/// Links completed, consented reference uploads to a new booking request.
+/// Uploads already attached elsewhere are left alone.
+#[cfg(feature = "ssr")]
+pub async fn attach_reference_uploads(
+    tx: &mut Transaction<'_, Postgres>,
+    booking_request_id: i32,
+    upload_ids: &[String],
+) -> DbResult<u64> {
+    if upload_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let result = sqlx::query(
+        "UPDATE uploads
+         SET booking_request_id = $1, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ANY($2)
+           AND kind = 'reference'
+           AND status = 'complete'
+           AND consent_given_at IS NOT NULL
+           AND booking_request_id IS NULL",
+    )
+    .bind(booking_request_id)
+    .bind(upload_ids)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// A stored attachment past its retention period
+#[cfg(feature = "ssr")]
+pub struct ExpiredAttachment {
+    pub upload_id: String,
+    pub user_id: i64,
+    pub booking_request_id: i32,
+    pub booking_status: String,
+    pub storage_path: Option<String>,
+}
+
+/// Reference uploads on bookings that were completed, declined or cancelled
+/// more than `retention_days` ago
+#[cfg(feature = "ssr")]
+pub async fn get_expired_attachments(
+    retention_days: i32,
+    limit: i64,
+) -> DbResult<Vec<ExpiredAttachment>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(
+        "SELECT u.id, u.user_id, u.booking_request_id, u.storage_path, br.status
+         FROM uploads u
+         JOIN booking_requests br ON br.id = u.booking_request_id
+         WHERE u.kind = 'reference'
+           AND u.status = 'complete'
+           AND br.status IN ('completed', 'declined', 'cancelled')
+           AND COALESCE(br.updated_at, br.created_at)::timestamp
+               < CURRENT_TIMESTAMP - make_interval(days => $1)
+         ORDER BY u.created_at
+         LIMIT $2",
+    )
+    .bind(retention_days)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ExpiredAttachment {
+            upload_id: row.get("id"),
+            user_id: row.get("user_id"),
+            booking_request_id: row.get("booking_request_id"),
+            booking_status: row.get("status"),
+            storage_path: row.get("storage_path"),
+        })
+        .collect())
+}
+
+/// Marks an attachment purged and records the deletion in the audit table
+#[cfg(feature = "ssr")]
+pub async fn record_attachment_purge(attachment: &ExpiredAttachment, reason: &str) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE uploads
+         SET status = 'purged', storage_path = NULL, updated_at = CURRENT_TIMESTAMP
+         WHERE id = $1",
+    )
+    .bind(&attachment.upload_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO attachment_deletions
+         (upload_id, booking_request_id, user_id, reason, booking_status)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(&attachment.upload_id)
+    .bind(attachment.booking_request_id)
+    .bind(attachment.user_id)
+    .bind(reason)
+    .bind(&attachment.booking_status)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
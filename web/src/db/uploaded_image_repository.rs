@@ -0,0 +1,172 @@
+#[cfg(feature = "ssr")]
+use super::entities::ArtistUploadedImage;
+#[cfg(feature = "ssr")]
+use sqlx::{postgres::PgRow, Row};
+
+#[cfg(feature = "ssr")]
+type DbResult<T> = Result<T, sqlx::Error>;
+
+/// A stored image before it has a row
+#[cfg(feature = "ssr")]
+pub struct NewUploadedImage {
+    pub artist_id: i64,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub caption: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+const UPLOADED_IMAGE_SELECT: &str =
+    "SELECT id, artist_id, storage_key, thumbnail_key, width, height, caption, position,
+        TO_CHAR(created_at, 'YYYY-MM-DD') as created_at
+     FROM artist_uploaded_images";
+
+#[cfg(feature = "ssr")]
+fn uploaded_image_from_row(row: &PgRow) -> ArtistUploadedImage {
+    let storage = crate::storage::storage();
+    let storage_key: String = row.get("storage_key");
+    let thumbnail_key: String = row.get("thumbnail_key");
+
+    ArtistUploadedImage {
+        id: row.get("id"),
+        artist_id: row.get("artist_id"),
+        image_url: storage.public_url(&storage_key).unwrap_or_default(),
+        thumbnail_url: storage.public_url(&thumbnail_key).unwrap_or_default(),
+        width: row.get("width"),
+        height: row.get("height"),
+        caption: row.get("caption"),
+        position: row.get("position"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Adds an image to the end of the artist's portfolio
+#[cfg(feature = "ssr")]
+pub async fn insert_uploaded_image(image: &NewUploadedImage) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "INSERT INTO artist_uploaded_images
+            (artist_id, storage_key, thumbnail_key, content_type, width, height, caption, position)
+         VALUES ($1, $2, $3, $4, $5, $6, $7,
+                 COALESCE((SELECT MAX(position) + 1 FROM artist_uploaded_images WHERE artist_id = $1), 0))
+         RETURNING id",
+    )
+    .bind(image.artist_id)
+    .bind(&image.storage_key)
+    .bind(&image.thumbnail_key)
+    .bind(&image.content_type)
+    .bind(image.width)
+    .bind(image.height)
+    .bind(&image.caption)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.get("id"))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_uploaded_images(artist_id: i64) -> DbResult<Vec<ArtistUploadedImage>> {
+    let pool = crate::db::pool::get_pool();
+
+    let rows = sqlx::query(&format!(
+        "{} WHERE artist_id = $1 ORDER BY position, id",
+        UPLOADED_IMAGE_SELECT
+    ))
+    .bind(artist_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(uploaded_image_from_row).collect())
+}
+
+#[cfg(feature = "ssr")]
+pub async fn get_uploaded_image(image_id: i64) -> DbResult<Option<ArtistUploadedImage>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(&format!("{} WHERE id = $1", UPLOADED_IMAGE_SELECT))
+        .bind(image_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.as_ref().map(uploaded_image_from_row))
+}
+
+/// Number of images the artist has uploaded
+#[cfg(feature = "ssr")]
+pub async fn count_uploaded_images(artist_id: i64) -> DbResult<i64> {
+    let pool = crate::db::pool::get_pool();
+
+    let row =
+        sqlx::query("SELECT COUNT(*) as count FROM artist_uploaded_images WHERE artist_id = $1")
+            .bind(artist_id)
+            .fetch_one(pool)
+            .await?;
+
+    Ok(row.get("count"))
+}
+
+#[cfg(feature = "ssr")]
+pub async fn update_caption(
+    artist_id: i64,
+    image_id: i64,
+    caption: Option<&str>,
+) -> DbResult<bool> {
+    let pool = crate::db::pool::get_pool();
+
+    let result = sqlx::query(
+        "UPDATE artist_uploaded_images SET caption = $3 WHERE id = $1 AND artist_id = $2",
+    )
+    .bind(image_id)
+    .bind(artist_id)
+    .bind(caption)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Sets positions to the order of `image_ids`; ids not owned by the artist
+/// are ignored
+#[cfg(feature = "ssr")]
+pub async fn reorder_uploaded_images(artist_id: i64, image_ids: &[i64]) -> DbResult<()> {
+    let pool = crate::db::pool::get_pool();
+
+    sqlx::query(
+        "UPDATE artist_uploaded_images aui
+         SET position = ordered.ord - 1
+         FROM UNNEST($2::BIGINT[]) WITH ORDINALITY AS ordered(id, ord)
+         WHERE aui.id = ordered.id AND aui.artist_id = $1",
+    )
+    .bind(artist_id)
+    .bind(image_ids)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes the row, returning its storage and thumbnail keys so the caller
+/// can delete the files
+#[cfg(feature = "ssr")]
+pub async fn delete_uploaded_image(
+    artist_id: i64,
+    image_id: i64,
+) -> DbResult<Option<(String, String)>> {
+    let pool = crate::db::pool::get_pool();
+
+    let row = sqlx::query(
+        "DELETE FROM artist_uploaded_images
+         WHERE id = $1 AND artist_id = $2
+         RETURNING storage_key, thumbnail_key",
+    )
+    .bind(image_id)
+    .bind(artist_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| (row.get("storage_key"), row.get("thumbnail_key"))))
+}
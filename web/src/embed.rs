@@ -0,0 +1,640 @@
+//! The booking widget at `/embed/booking/{artist_id}`: a small booking form
+//! artists show on their own sites in an iframe, with the embed code on
+//! their settings page. It's plain server-rendered HTML with its own styles,
+//! so neither the app's CSS nor the host page's reaches it, and it works
+//! without JavaScript:
+//! - times come from the artist's availability, grouped by date in one list
+//! - the artist's questionnaire follows, minus the contact questions the
+//!   form asks itself and the appointment question the time answers
+//! - the form posts back to the same address and goes through
+//!   `submit_booking_request` like a booking made in the app
+//!
+//! Once the request is sent, the page posts a [`COMPLETION_EVENT`] message
+//! to the page embedding it. Every response allows framing, and CORS, only
+//! from the sites the artist listed, or from any site if they listed none.
+
+use std::net::SocketAddr;
+
+use axum::extract::{ConnectInfo, Path};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Form;
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+
+use crate::api_error::user_message;
+use crate::db::embed_repository::{self, WidgetArtist};
+use crate::db::entities::{BookingQuestionnaireResponse, ClientQuestionnaireQuestion};
+use crate::server::{submit_booking_request, NewBookingRequest};
+use crate::utils::embed::COMPLETION_EVENT;
+use crate::utils::timezone::convert_to_12_hour_format;
+
+/// Days ahead the widget offers times for, from tomorrow
+const WIDGET_DAYS: i64 = 28;
+/// The system question asking for the appointment date (see
+/// `db::repository::get_artist_questionnaire`), answered by the chosen time
+const APPOINTMENT_QUESTION_ID: i32 = 6;
+const MAX_NAME_CHARS: usize = 120;
+/// Longest answer or message kept
+const MAX_TEXT_CHARS: usize = 2000;
+
+const WIDGET_CSS: &str = "\
+*{box-sizing:border-box}
+body{margin:0;font:15px/1.5 system-ui,-apple-system,'Segoe UI',Roboto,sans-serif;color:#1f2937;background:#fff}
+.widget{max-width:480px;margin:0 auto;padding:20px}
+h1{font-size:20px;margin:0 0 16px}
+label,fieldset{display:block;margin:0 0 14px;font-weight:600}
+fieldset{border:0;padding:0}
+legend{padding:0;margin-bottom:6px}
+.choice{display:flex;gap:8px;align-items:center;margin:4px 0;font-weight:400}
+input[type=text],input[type=email],input[type=tel],input[type=datetime-local],select,textarea{display:block;width:100%;margin-top:6px;padding:9px 10px;border:1px solid #d1d5db;border-radius:6px;font:inherit;font-weight:400;background:#fff}
+textarea{resize:vertical}
+.optional{color:#6b7280;font-weight:400}
+.error{background:#fef2f2;color:#b91c1c;padding:10px 12px;border-radius:6px;margin-bottom:14px}
+.notice{color:#4b5563}
+button{width:100%;padding:11px;border:0;border-radius:6px;background:#6366f1;color:#fff;font:inherit;font-weight:600;cursor:pointer}
+.powered{margin-top:16px;font-size:12px;color:#9ca3af;text-align:center}
+.powered a{color:inherit}";
+
+type Fields = Vec<(String, String)>;
+
+/// Open appointment times on each date that has any
+type Times = Vec<(NaiveDate, Vec<(NaiveTime, NaiveTime)>)>;
+
+pub fn widget_url(artist_id: i32) -> String {
+    format!(
+        "{}/embed/booking/{}",
+        crate::config::config().app_base_url,
+        artist_id
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+/// A submitted field, trimmed, empty if it wasn't sent
+fn field<'a>(fields: &'a Fields, name: &str) -> &'a str {
+    fields
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.trim())
+        .unwrap_or_default()
+}
+
+/// Every value sent for a field, like a group of checkboxes
+fn field_values<'a>(fields: &'a Fields, name: &str) -> Vec<&'a str> {
+    fields
+        .iter()
+        .filter(|(key, _)| key == name)
+        .map(|(_, value)| value.trim())
+        .collect()
+}
+
+fn artist_name(artist: &WidgetArtist) -> &str {
+    artist.name.as_deref().unwrap_or("your artist")
+}
+
+/// Adds the headers every widget response carries: framing allowed from
+/// the artist's sites (or anywhere), CORS for a request from one of them,
+/// and a CSP letting only the page's own nonce-tagged script run
+fn embed_headers(
+    mut response: Response,
+    allowed_origins: &[String],
+    request_headers: &HeaderMap,
+    nonce: Option<&str>,
+) -> Response {
+    let ancestors = if allowed_origins.is_empty() {
+        "*".to_string()
+    } else {
+        format!("'self' {}", allowed_origins.join(" "))
+    };
+    let script_src = nonce
+        .map(|nonce| format!("'nonce-{}'", nonce))
+        .unwrap_or_else(|| "'none'".to_string());
+    let policy = format!(
+        "default-src 'none'; style-src 'unsafe-inline'; script-src {}; \
+         form-action 'self'; base-uri 'none'; frame-ancestors {}",
+        script_src, ancestors
+    );
+
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&policy) {
+        headers.insert(header::CONTENT_SECURITY_POLICY, value);
+    }
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+
+    let origin = request_headers
+        .get(header::ORIGIN)
+        .and_then(|v| v.to_str().ok());
+    if allowed_origins.is_empty() {
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_ORIGIN,
+            HeaderValue::from_static("*"),
+        );
+    } else {
+        headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+        if let Some(origin) = origin.filter(|origin| allowed_origins.iter().any(|o| o == origin)) {
+            if let Ok(value) = HeaderValue::from_str(origin) {
+                headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+            }
+        }
+    }
+    response
+}
+
+fn page(title: &str, body: &str, script: Option<(&str, &str)>) -> String {
+    let script = script
+        .map(|(nonce, code)| format!("<script nonce=\"{}\">{}</script>", nonce, code))
+        .unwrap_or_default();
+    format!(
+        "<!DOCTYPE html>\
+         <html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>{}</title><style>{}</style></head>\
+         <body><main class=\"widget\">{}</main>{}</body></html>",
+        escape(title),
+        WIDGET_CSS,
+        body,
+        script
+    )
+}
+
+fn powered_by(artist_id: i32) -> String {
+    format!(
+        "<p class=\"powered\">Booking by <a href=\"{}/artist/{}\" target=\"_blank\" rel=\"noopener\">Tatteau</a></p>",
+        crate::config::config().app_base_url,
+        artist_id
+    )
+}
+
+/// A page for when there's no widget to show
+fn unavailable(status: StatusCode, message: &str) -> Response {
+    let body = format!("<p class=\"notice\">{}</p>", escape(message));
+    embed_headers(
+        (status, axum::response::Html(page("Booking", &body, None))).into_response(),
+        &[],
+        &HeaderMap::new(),
+        None,
+    )
+}
+
+async fn load_artist(artist_id: i32) -> Result<WidgetArtist, Response> {
+    match embed_repository::get_widget_artist(artist_id).await {
+        Ok(Some(artist)) => Ok(artist),
+        Ok(None) => Err(unavailable(
+            StatusCode::NOT_FOUND,
+            "This booking form isn't available.",
+        )),
+        Err(e) => {
+            tracing::error!(artist_id, "Failed to load booking widget artist: {}", e);
+            Err(unavailable(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Something went wrong, please try again.",
+            ))
+        }
+    }
+}
+
+/// Open appointments over the next [`WIDGET_DAYS`], standard session length
+async fn load_times(artist_id: i32) -> Result<Times, sqlx::Error> {
+    // Today's earlier hours may already be gone, so start tomorrow
+    let first = Utc::now().naive_utc().date() + Duration::days(1);
+    let last = first + Duration::days(WIDGET_DAYS - 1);
+    let schedule =
+        crate::db::availability_repository::load_schedule(artist_id, first, last).await?;
+    let minutes = schedule.slot_settings.session_minutes;
+
+    Ok(schedule
+        .available_dates(first, last, first)
+        .into_iter()
+        .map(|date| {
+            let slots = schedule
+                .time_slots(date, minutes)
+                .into_iter()
+                .filter(|slot| slot.available)
+                .map(|slot| (slot.start, slot.end))
+                .collect::<Vec<_>>();
+            (date, slots)
+        })
+        .filter(|(_, slots)| !slots.is_empty())
+        .collect())
+}
+
+/// The artist's questions the widget asks. Contact questions are left out,
+/// as in `MultiStepQuestionnaire`, since the form asks for those itself.
+async fn load_questions(artist_id: i32) -> Result<Vec<ClientQuestionnaireQuestion>, sqlx::Error> {
+    let form = crate::db::repository::get_artist_questionnaire(artist_id).await?;
+    Ok(form
+        .questions
+        .into_iter()
+        .filter(|question| question.id != APPOINTMENT_QUESTION_ID)
+        .filter(|question| {
+            let text = question.question_text.to_lowercase();
+            !text.contains("name") && !text.contains("email") && !text.contains("phone")
+        })
+        .collect())
+}
+
+fn question_html(question: &ClientQuestionnaireQuestion, fields: &Fields) -> String {
+    let name = format!("q{}", question.id);
+    let label = format!(
+        "{}{}",
+        escape(&question.question_text),
+        if question.is_required {
+            ""
+        } else {
+            " <span class=\"optional\">(optional)</span>"
+        }
+    );
+    let required = if question.is_required {
+        " required"
+    } else {
+        ""
+    };
+    let value = escape(field(fields, &name));
+
+    match question.question_type.as_str() {
+        "multiselect" => {
+            let selected = field_values(fields, &name);
+            let options = question
+                .options
+                .iter()
+                .map(|option| {
+                    format!(
+                        "<label class=\"choice\"><input type=\"checkbox\" name=\"{}\" value=\"{}\"{}> {}</label>",
+                        name,
+                        escape(option),
+                        if selected.contains(&option.as_str()) { " checked" } else { "" },
+                        escape(option)
+                    )
+                })
+                .collect::<String>();
+            format!("<fieldset><legend>{}</legend>{}</fieldset>", label, options)
+        }
+        "boolean" => {
+            let choice = |answer: &str, text: &str| {
+                format!(
+                    "<label class=\"choice\"><input type=\"radio\" name=\"{}\" value=\"{}\"{}{}> {}</label>",
+                    name,
+                    answer,
+                    required,
+                    if field(fields, &name) == answer { " checked" } else { "" },
+                    text
+                )
+            };
+            format!(
+                "<fieldset><legend>{}</legend>{}{}</fieldset>",
+                label,
+                choice("true", "Yes"),
+                choice("false", "No")
+            )
+        }
+        "text" => format!(
+            "<label>{}<textarea name=\"{}\" rows=\"3\" maxlength=\"{}\"{}>{}</textarea></label>",
+            label, name, MAX_TEXT_CHARS, required, value
+        ),
+        "datetime" => format!(
+            "<label>{}<input type=\"datetime-local\" name=\"{}\" value=\"{}\"{}></label>",
+            label, name, value, required
+        ),
+        _ => format!(
+            "<label>{}<input type=\"text\" name=\"{}\" value=\"{}\" maxlength=\"{}\"{}></label>",
+            label, name, value, MAX_TEXT_CHARS, required
+        ),
+    }
+}
+
+/// The answers given, or what's wrong with them. Multiselect answers are a
+/// JSON list, as the app sends them.
+fn answers(
+    questions: &[ClientQuestionnaireQuestion],
+    fields: &Fields,
+) -> Result<Vec<(i32, String)>, String> {
+    let mut answers = Vec::new();
+    for question in questions {
+        let name = format!("q{}", question.id);
+        let answer = match question.question_type.as_str() {
+            "multiselect" => {
+                let selected = field_values(fields, &name)
+                    .into_iter()
+                    .filter(|value| question.options.iter().any(|option| option == value))
+                    .collect::<Vec<_>>();
+                if selected.is_empty() {
+                    String::new()
+                } else {
+                    serde_json::to_string(&selected).unwrap_or_default()
+                }
+            }
+            "boolean" => match field(fields, &name) {
+                answer @ ("true" | "false") => answer.to_string(),
+                _ => String::new(),
+            },
+            _ => truncate(field(fields, &name), MAX_TEXT_CHARS),
+        };
+
+        if answer.is_empty() {
+            if question.is_required {
+                return Err(format!("Answer \"{}\"", question.question_text));
+            }
+            continue;
+        }
+        answers.push((question.id, answer));
+    }
+    Ok(answers)
+}
+
+fn form_page(
+    artist: &WidgetArtist,
+    times: &Times,
+    questions: &[ClientQuestionnaireQuestion],
+    fields: &Fields,
+    error: Option<&str>,
+) -> String {
+    let title = format!("Book with {}", artist_name(artist));
+    if times.is_empty() {
+        let body = format!(
+            "<h1>{}</h1><p class=\"notice\">{} has no open appointments in the next four weeks. Check back soon!</p>{}",
+            escape(&title),
+            escape(artist_name(artist)),
+            powered_by(artist.id)
+        );
+        return page(&title, &body, None);
+    }
+
+    let chosen = field(fields, "slot");
+    let slots = times
+        .iter()
+        .map(|(date, slots)| {
+            let options = slots
+                .iter()
+                .map(|(start, _)| {
+                    let value = format!("{}T{}", date.format("%Y-%m-%d"), start.format("%H:%M"));
+                    format!(
+                        "<option value=\"{}\"{}>{}</option>",
+                        value,
+                        if value == chosen { " selected" } else { "" },
+                        convert_to_12_hour_format(&start.format("%H:%M").to_string())
+                    )
+                })
+                .collect::<String>();
+            format!(
+                "<optgroup label=\"{}\">{}</optgroup>",
+                date.format("%A, %B %-d"),
+                options
+            )
+        })
+        .collect::<String>();
+    let questions = questions
+        .iter()
+        .map(|question| question_html(question, fields))
+        .collect::<String>();
+    let error = error
+        .map(|error| format!("<p class=\"error\" role=\"alert\">{}</p>", escape(error)))
+        .unwrap_or_default();
+
+    let body = format!(
+        "<h1>{title}</h1>{error}\
+         <form method=\"post\" action=\"/embed/booking/{id}\">\
+         <label>Your name<input type=\"text\" name=\"client_name\" value=\"{name}\" maxlength=\"{max_name}\" autocomplete=\"name\" required></label>\
+         <label>Email<input type=\"email\" name=\"client_email\" value=\"{email}\" autocomplete=\"email\" required></label>\
+         <label>Phone <span class=\"optional\">(optional)</span><input type=\"tel\" name=\"client_phone\" value=\"{phone}\" autocomplete=\"tel\"></label>\
+         <label>Appointment<select name=\"slot\" required><option value=\"\">Choose a time</option>{slots}</select></label>\
+         {questions}\
+         <label>Anything else? <span class=\"optional\">(optional)</span><textarea name=\"message\" rows=\"3\" maxlength=\"{max_text}\">{message}</textarea></label>\
+         <button type=\"submit\">Request Appointment</button>\
+         </form>{powered}",
+        title = escape(&title),
+        error = error,
+        id = artist.id,
+        name = escape(field(fields, "client_name")),
+        max_name = MAX_NAME_CHARS,
+        email = escape(field(fields, "client_email")),
+        phone = escape(field(fields, "client_phone")),
+        slots = slots,
+        questions = questions,
+        max_text = MAX_TEXT_CHARS,
+        message = escape(field(fields, "message")),
+        powered = powered_by(artist.id),
+    );
+    page(&title, &body, None)
+}
+
+/// Tells the page embedding the widget the request was sent. Only the
+/// artist's sites are told, when they listed any.
+fn completion_script(artist: &WidgetArtist, booking_id: i32) -> String {
+    let targets = if artist.allowed_origins.is_empty() {
+        vec!["*".to_string()]
+    } else {
+        artist.allowed_origins.clone()
+    };
+    format!(
+        "(function () {{\
+         if (window.parent === window) return;\
+         var message = {{ type: \"{}\", artistId: {}, bookingId: {} }};\
+         {}.forEach(function (origin) {{ window.parent.postMessage(message, origin); }});\
+         }})();",
+        COMPLETION_EVENT,
+        artist.id,
+        booking_id,
+        serde_json::to_string(&targets).unwrap_or_else(|_| "[]".to_string())
+    )
+}
+
+fn sent_page(
+    artist: &WidgetArtist,
+    booking_id: i32,
+    date: NaiveDate,
+    start: NaiveTime,
+    nonce: &str,
+) -> String {
+    let title = "Request sent";
+    let body = format!(
+        "<h1>{}</h1><p class=\"notice\">{} will get back to you about your appointment on {} at {}.</p>{}",
+        title,
+        escape(artist_name(artist)),
+        date.format("%A, %B %-d"),
+        convert_to_12_hour_format(&start.format("%H:%M").to_string()),
+        powered_by(artist.id)
+    );
+    page(
+        title,
+        &body,
+        Some((nonce, &completion_script(artist, booking_id))),
+    )
+}
+
+/// Sends the booking request, returning its id and time or what to fix
+async fn send_request(
+    artist: &WidgetArtist,
+    times: &Times,
+    questions: &[ClientQuestionnaireQuestion],
+    fields: &Fields,
+) -> Result<(i32, NaiveDate, NaiveTime), String> {
+    let client_name = field(fields, "client_name");
+    if client_name.is_empty() {
+        return Err("Enter your name".to_string());
+    }
+    if client_name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!(
+            "Your name can be at most {} characters",
+            MAX_NAME_CHARS
+        ));
+    }
+    let client_email = field(fields, "client_email");
+    if !client_email.contains('@') {
+        return Err(format!(
+            "Enter your email so {} can reach you",
+            artist_name(artist)
+        ));
+    }
+
+    let slot = field(fields, "slot");
+    if slot.is_empty() {
+        return Err("Choose a time for your appointment".to_string());
+    }
+    let (date, start, end) = slot
+        .split_once('T')
+        .and_then(|(date, time)| {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+            let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+            let (_, slots) = times.iter().find(|(open, _)| *open == date)?;
+            let (start, end) = slots.iter().find(|(start, _)| *start == time)?;
+            Some((date, *start, *end))
+        })
+        .ok_or_else(|| "That time was just taken, please choose another".to_string())?;
+
+    let answers = answers(questions, fields)?;
+    let message = truncate(field(fields, "message"), MAX_TEXT_CHARS);
+
+    let request = NewBookingRequest {
+        artist_id: artist.id,
+        client_name: client_name.to_string(),
+        client_email: client_email.to_string(),
+        client_phone: Some(field(fields, "client_phone"))
+            .filter(|phone| !phone.is_empty())
+            .map(str::to_string),
+        tattoo_description: None, // Collected via questionnaire
+        placement: None,          // Collected via questionnaire
+        size_inches: None,
+        requested_date: date.into(),
+        requested_start_time: start.into(),
+        requested_end_time: Some(end.into()),
+        message_from_client: Some(message).filter(|message| !message.is_empty()),
+        allow_duplicate: false,
+        reference_upload_ids: Vec::new(),
+        booking_type: None,
+        flash_design_id: None,
+    };
+    let booking_id = submit_booking_request(request, None)
+        .await
+        .map_err(|e| user_message(&e))?;
+
+    let responses = answers
+        .into_iter()
+        .map(|(question_id, answer)| BookingQuestionnaireResponse {
+            id: 0,
+            booking_request_id: booking_id,
+            question_id,
+            response_text: Some(answer),
+            response_data: None,
+            created_at: None,
+        })
+        .collect();
+    if let Err(e) = crate::db::repository::save_questionnaire_responses(booking_id, responses).await
+    {
+        tracing::error!(booking_id, "Failed to save booking widget answers: {}", e);
+    }
+
+    Ok((booking_id, date, start))
+}
+
+/// Loads what the form shows, or the page saying it couldn't be
+async fn load_form(artist_id: i32) -> Result<(Times, Vec<ClientQuestionnaireQuestion>), Response> {
+    match tokio::try_join!(load_times(artist_id), load_questions(artist_id)) {
+        Ok(loaded) => Ok(loaded),
+        Err(e) => {
+            tracing::error!(artist_id, "Failed to load booking widget: {}", e);
+            Err(unavailable(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Something went wrong, please try again.",
+            ))
+        }
+    }
+}
+
+/// The widget's booking form
+pub async fn booking_widget(Path(artist_id): Path<i32>, headers: HeaderMap) -> Response {
+    let artist = match load_artist(artist_id).await {
+        Ok(artist) => artist,
+        Err(response) => return response,
+    };
+    let (times, questions) = match load_form(artist_id).await {
+        Ok(loaded) => loaded,
+        Err(response) => return response,
+    };
+
+    let html = form_page(&artist, &times, &questions, &Vec::new(), None);
+    embed_headers(
+        axum::response::Html(html).into_response(),
+        &artist.allowed_origins,
+        &headers,
+        None,
+    )
+}
+
+/// Sends the widget's form, showing it again with what to fix if it can't
+pub async fn submit_booking_widget(
+    Path(artist_id): Path<i32>,
+    headers: HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    Form(fields): Form<Fields>,
+) -> Response {
+    let artist = match load_artist(artist_id).await {
+        Ok(artist) => artist,
+        Err(response) => return response,
+    };
+    let (times, questions) = match load_form(artist_id).await {
+        Ok(loaded) => loaded,
+        Err(response) => return response,
+    };
+
+    let ip = crate::rate_limit::client_ip(&headers, peer.map(|ConnectInfo(addr)| addr.ip()));
+    let limited = ip.and_then(|ip| crate::rate_limit::check_booking_ip(ip).err());
+    let result = match limited {
+        Some(error) => Err((StatusCode::TOO_MANY_REQUESTS, error.message().to_string())),
+        None => send_request(&artist, &times, &questions, &fields)
+            .await
+            .map_err(|message| (StatusCode::UNPROCESSABLE_ENTITY, message)),
+    };
+
+    match result {
+        Ok((booking_id, date, start)) => {
+            tracing::info!(booking_id, artist_id, "Booking request sent from widget");
+            let nonce = uuid::Uuid::new_v4().simple().to_string();
+            embed_headers(
+                axum::response::Html(sent_page(&artist, booking_id, date, start, &nonce))
+                    .into_response(),
+                &artist.allowed_origins,
+                &headers,
+                Some(&nonce),
+            )
+        }
+        Err((status, message)) => {
+            let html = form_page(&artist, &times, &questions, &fields, Some(&message));
+            embed_headers(
+                (status, axum::response::Html(html)).into_response(),
+                &artist.allowed_origins,
+                &headers,
+                None,
+            )
+        }
+    }
+}
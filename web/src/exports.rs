@@ -0,0 +1,207 @@
+//! Background CSV exports. `request_export` records the export in `exports`
+//! and queues a [`Job::RunExport`](crate::jobs::Job::RunExport), which calls
+//! [`run_export`]. Rows are written in batches to
+//! `UPLOAD_DIR/exports/<job id>.csv`, updating the export's progress after
+//! each batch, and the finished file is downloaded through a signed,
+//! short-lived link from [`job_view`]. Files are kept for
+//! `EXPORT_RETENTION_HOURS` (default 24), after which the hourly
+//! [`expire_exports`] deletes them.
+
+use axum::extract::{Path, Query};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+
+use crate::db::entities::ExportJob;
+use crate::db::export_repository::{self, StoredExport};
+use crate::utils::export::{csv_line, file_name, headers, progress};
+
+/// Rows read and written at a time
+const BATCH_SIZE: i64 = 500;
+/// How long a download link works for
+const DOWNLOAD_LINK_MINUTES: i64 = 15;
+/// Running jobs older than this were cut off by a restart
+const STALLED_AFTER_MINUTES: i32 = 60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExportError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("failed to write export file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+fn export_path(id: &str) -> PathBuf {
+    crate::uploads::upload_dir()
+        .join("exports")
+        .join(format!("{}.csv", id))
+}
+
+fn download_message(id: &str, expires: i64) -> String {
+    format!("export:{}:{}", id, expires)
+}
+
+/// The job as its owner sees it, with a fresh download link once complete
+pub fn job_view(job: &StoredExport) -> ExportJob {
+    let download_url = (job.status == "complete").then(|| {
+        let expires =
+            (chrono::Utc::now() + chrono::Duration::minutes(DOWNLOAD_LINK_MINUTES)).timestamp();
+        // The link can't outlive the file
+        let expires = job
+            .expires_at
+            .map_or(expires, |expires_at| expires.min(expires_at.timestamp()));
+        format!(
+            "/api/exports/{}/download?expires={}&signature={}",
+            job.id,
+            expires,
+            crate::auth::sign(&download_message(&job.id, expires))
+        )
+    });
+
+    ExportJob {
+        id: job.id.clone(),
+        kind: job.kind.clone(),
+        status: job.status.clone(),
+        progress: if job.status == "complete" {
+            100
+        } else {
+            progress(job.rows_written, job.total_rows)
+        },
+        rows_written: job.rows_written,
+        error: job.error.clone(),
+        created_at: job.created_at.clone(),
+        expires_at: job.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+        download_url,
+    }
+}
+
+/// Writes a queued export's file. An export that fails is marked failed
+/// for its owner to ask again.
+pub async fn run_export(id: &str) -> Result<(), ExportError> {
+    // Expired, or finished by an earlier run
+    let Some(job) = export_repository::start_job(id).await? else {
+        return Ok(());
+    };
+
+    if let Err(e) = run(&job).await {
+        let _ = tokio::fs::remove_file(export_path(&job.id)).await;
+        export_repository::fail_job(&job.id, "The export failed, please try again").await?;
+        return Err(e);
+    }
+    Ok(())
+}
+
+async fn run(job: &StoredExport) -> Result<(), ExportError> {
+    let total_rows = export_repository::count_rows(&job.kind, job.artist_id).await?;
+    export_repository::set_progress(&job.id, total_rows, 0).await?;
+
+    let path = export_path(&job.id);
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let mut file = tokio::fs::File::create(&path).await?;
+    file.write_all(csv_line(headers(&job.kind)).as_bytes())
+        .await?;
+
+    let mut rows_written = 0;
+    let mut after_id = 0;
+    loop {
+        let rows =
+            export_repository::fetch_page(&job.kind, job.artist_id, after_id, BATCH_SIZE).await?;
+        let Some((last_id, _)) = rows.last() else {
+            break;
+        };
+        after_id = *last_id;
+
+        let batch: String = rows.iter().map(|(_, fields)| csv_line(fields)).collect();
+        file.write_all(batch.as_bytes()).await?;
+        rows_written += rows.len() as i64;
+        // Rows added since the count was taken are exported too
+        export_repository::set_progress(&job.id, total_rows.max(rows_written), rows_written)
+            .await?;
+
+        if (rows.len() as i64) < BATCH_SIZE {
+            break;
+        }
+    }
+    file.flush().await?;
+
+    export_repository::complete_job(
+        &job.id,
+        rows_written,
+        &path.to_string_lossy(),
+        crate::config::config().export_retention_hours,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Deletes the files of exports past their retention and fails jobs left
+/// running by a restart. Returns how many files were deleted.
+pub async fn expire_exports() -> Result<usize, sqlx::Error> {
+    let stalled = export_repository::fail_stalled_jobs(STALLED_AFTER_MINUTES).await?;
+    if stalled > 0 {
+        tracing::warn!("Marked {} stalled exports as failed", stalled);
+    }
+
+    let paths = export_repository::expire_jobs().await?;
+    for path in &paths {
+        if let Err(e) = tokio::fs::remove_file(path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::error!("Failed to delete expired export {}: {}", path, e);
+            }
+        }
+    }
+    Ok(paths.len())
+}
+
+#[derive(Deserialize)]
+pub struct ExportDownloadParams {
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Serves a finished export to whoever holds a link from [`job_view`]
+pub async fn export_download_handler(
+    Path(id): Path<String>,
+    Query(params): Query<ExportDownloadParams>,
+) -> Response {
+    if params.expires < chrono::Utc::now().timestamp()
+        || !crate::auth::verify_signature(&download_message(&id, params.expires), &params.signature)
+    {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let job = match export_repository::get_job(&id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load export {}: {}", id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let Some(path) = job.storage_path.filter(|_| job.status == "complete") else {
+        return StatusCode::GONE.into_response();
+    };
+
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"{}\"",
+                        file_name(&job.kind, job.created_at.get(..10).unwrap_or_default())
+                    ),
+                ),
+                (header::CACHE_CONTROL, "private, no-store".to_string()),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => StatusCode::GONE.into_response(),
+    }
+}
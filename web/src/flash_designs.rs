@@ -0,0 +1,215 @@
+//! Artists' flash: pre-drawn designs sold at a fixed price.
+//!
+//! `POST /api/artist/flash` takes a `multipart/form-data` body with a `file`
+//! part (the design), a `title`, a `price`, and optionally a `description`,
+//! `size_inches`, `repeatable` (any value means yes) and one `style_id` part
+//! per style. Authentication and responses work as for portfolio uploads
+//! (see [`crate::portfolio_uploads`]), redirecting form posts to the settings
+//! page with `?flash=uploaded` or `?flash_error=<message>`. Everything else
+//! about a design is changed through `server_flash_designs`.
+
+use axum::extract::Multipart;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Json;
+
+use crate::api_error::ApiError;
+use crate::db::entities::FlashDesign;
+use crate::db::flash_design_repository::{self, NewFlashDesign};
+use crate::image_processing;
+use crate::portfolio_uploads::{extension, wants_json, UploadError};
+use crate::server_flash_designs::validate_details;
+use crate::server_team::{authorize_artist, TeamPermission};
+use crate::storage::storage;
+
+/// Largest image accepted
+pub const MAX_FLASH_IMAGE_BYTES: usize = 25 * 1024 * 1024;
+/// Designs an artist may have up at once; archived ones don't count
+const MAX_FLASH_DESIGNS: i64 = 200;
+const SETTINGS_PATH: &str = "/artist/dashboard/settings";
+
+async fn store_design(
+    headers: &HeaderMap,
+    mut multipart: Multipart,
+) -> Result<FlashDesign, UploadError> {
+    let mut file: Option<Vec<u8>> = None;
+    let mut title = String::new();
+    let mut description: Option<String> = None;
+    let mut size_inches: Option<String> = None;
+    let mut price: Option<String> = None;
+    let mut repeatable = false;
+    let mut style_ids: Vec<i32> = Vec::new();
+    let mut form_token: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| UploadError::new(StatusCode::BAD_REQUEST, "Malformed upload"))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                let bytes = field.bytes().await.map_err(|_| {
+                    UploadError::new(StatusCode::PAYLOAD_TOO_LARGE, "Image too large")
+                })?;
+                file = Some(bytes.to_vec());
+            }
+            "title" => title = field.text().await.unwrap_or_default(),
+            "description" => description = field.text().await.ok(),
+            "size_inches" => size_inches = field.text().await.ok(),
+            "price" => price = field.text().await.ok(),
+            "repeatable" => repeatable = true,
+            "style_id" => {
+                if let Some(id) = field
+                    .text()
+                    .await
+                    .ok()
+                    .and_then(|id| id.trim().parse().ok())
+                {
+                    style_ids.push(id);
+                }
+            }
+            "token" => form_token = field.text().await.ok(),
+            _ => {}
+        }
+    }
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or(form_token)
+        .ok_or_else(|| UploadError::new(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+    let artist_id = authorize_artist(&token, TeamPermission::Settings)
+        .await
+        .map_err(|e| match e {
+            ApiError::Unauthorized(message) => UploadError(StatusCode::FORBIDDEN, message),
+            e => {
+                tracing::error!("Failed to authorize flash upload: {}", e);
+                UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed")
+            }
+        })?;
+
+    let size_inches =
+        match size_inches.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(size) => Some(size.parse::<f32>().map_err(|_| {
+                UploadError::new(StatusCode::BAD_REQUEST, "Enter the size in inches")
+            })?),
+        };
+    let price = price
+        .as_deref()
+        .and_then(|price| price.trim().trim_start_matches('$').parse::<f64>().ok())
+        .ok_or_else(|| UploadError::new(StatusCode::BAD_REQUEST, "Enter a price"))?;
+    let (title, description) = validate_details(&title, description.as_deref(), size_inches, price)
+        .map_err(|e| UploadError(StatusCode::BAD_REQUEST, e.message().to_string()))?;
+
+    let file = file
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| UploadError::new(StatusCode::BAD_REQUEST, "No image selected"))?;
+    if file.len() > MAX_FLASH_IMAGE_BYTES {
+        return Err(UploadError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Image too large",
+        ));
+    }
+
+    let failed = |context: &str, e: sqlx::Error| {
+        tracing::error!("Failed to {}: {}", context, e);
+        UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed")
+    };
+
+    let existing = flash_design_repository::count_flash_designs(artist_id)
+        .await
+        .map_err(|e| failed("count flash designs", e))?;
+    if existing >= MAX_FLASH_DESIGNS {
+        return Err(UploadError(
+            StatusCode::CONFLICT,
+            format!(
+                "You can have at most {} flash designs up; archive some first",
+                MAX_FLASH_DESIGNS
+            ),
+        ));
+    }
+
+    // Decoding and re-encoding are CPU-bound
+    let processed = tokio::task::spawn_blocking(move || image_processing::process(&file))
+        .await
+        .map_err(|_| UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed"))??;
+    let content_type = processed.content_type;
+
+    let id = uuid::Uuid::new_v4();
+    let image_key = format!("flash/{}/{}.{}", artist_id, id, extension(content_type));
+    let thumbnail_key = format!("flash/{}/{}_thumb.jpg", artist_id, id);
+
+    let stored = async {
+        storage()
+            .put(&image_key, processed.bytes, content_type)
+            .await?;
+        storage()
+            .put(&thumbnail_key, processed.thumbnail, "image/jpeg")
+            .await
+    }
+    .await;
+    if let Err(e) = stored {
+        tracing::error!("Failed to store flash image: {}", e);
+        let _ = storage().delete(&image_key).await;
+        return Err(UploadError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store image",
+        ));
+    }
+
+    let new_design = NewFlashDesign {
+        artist_id,
+        title,
+        description,
+        image_key,
+        thumbnail_key,
+        width: processed.width as i32,
+        height: processed.height as i32,
+        size_inches,
+        price,
+        repeatable,
+        style_ids,
+    };
+
+    let saved = async {
+        let flash_design_id = flash_design_repository::insert_flash_design(&new_design).await?;
+        flash_design_repository::get_flash_design(flash_design_id).await
+    }
+    .await;
+
+    match saved {
+        Ok(Some(design)) => Ok(design),
+        other => {
+            if let Err(e) = other {
+                tracing::error!("Failed to record flash design: {}", e);
+            }
+            let _ = storage().delete(&new_design.image_key).await;
+            let _ = storage().delete(&new_design.thumbnail_key).await;
+            Err(UploadError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to save design",
+            ))
+        }
+    }
+}
+
+/// POST /api/artist/flash
+pub async fn upload_flash_design(headers: HeaderMap, multipart: Multipart) -> Response {
+    let json = wants_json(&headers);
+
+    match store_design(&headers, multipart).await {
+        Ok(design) if json => (StatusCode::CREATED, Json(design)).into_response(),
+        Ok(_) => Redirect::to(&format!("{}?flash=uploaded", SETTINGS_PATH)).into_response(),
+        Err(UploadError(status, message)) if json => (status, message).into_response(),
+        Err(UploadError(_, message)) => Redirect::to(&format!(
+            "{}?flash_error={}",
+            SETTINGS_PATH,
+            urlencoding::encode(&message)
+        ))
+        .into_response(),
+    }
+}
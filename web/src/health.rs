@@ -0,0 +1,79 @@
+//! Probes for whatever runs the server. `GET /healthz` answers as long as
+//! the process is serving requests, for restarting it when it isn't.
+//! `GET /readyz` also checks the database with a `SELECT 1`, and fails once
+//! shutdown starts, for routing traffic only to instances that can take it.
+//!
+//! On SIGTERM or Ctrl+C the server stops accepting connections and lets
+//! requests in flight finish, for up to `SHUTDOWN_GRACE_SECS` (default 25)
+//! since message streams stay open until cut off, then closes the pool.
+
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Longer than this and the database counts as unavailable
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+fn probe(status: StatusCode, body: &'static str) -> Response {
+    (status, [(header::CACHE_CONTROL, "no-store")], body).into_response()
+}
+
+pub async fn healthz() -> Response {
+    probe(StatusCode::OK, "ok")
+}
+
+pub async fn readyz() -> Response {
+    if SHUTTING_DOWN.load(Ordering::Relaxed) {
+        return probe(StatusCode::SERVICE_UNAVAILABLE, "shutting down");
+    }
+
+    let check = sqlx::query("SELECT 1").execute(crate::db::pool::get_pool());
+    match tokio::time::timeout(READY_TIMEOUT, check).await {
+        Ok(Ok(_)) => probe(StatusCode::OK, "ready"),
+        Ok(Err(e)) => {
+            tracing::warn!("Readiness check failed: {}", e);
+            probe(StatusCode::SERVICE_UNAVAILABLE, "database unavailable")
+        }
+        Err(_) => {
+            tracing::warn!("Readiness check timed out");
+            probe(StatusCode::SERVICE_UNAVAILABLE, "database unavailable")
+        }
+    }
+}
+
+/// Resolves on SIGTERM or Ctrl+C, after marking the server not ready
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    SHUTTING_DOWN.store(true, Ordering::Relaxed);
+    tracing::info!("Shutting down, finishing requests in flight");
+}
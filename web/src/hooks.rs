@@ -0,0 +1,299 @@
+//! Server-side extension points. Server fns emit an event once a change is
+//! saved (a booking request comes in or is completed, an artist's profile
+//! goes live, an admin retags an image), and the subsystems that react to it
+//! subscribe here instead of being called from each server fn. New behavior
+//! is a new subscriber in [`builtin`]; the server fns don't change.
+//!
+//! Every subscriber runs in its own task, so a slow or failing one never
+//! holds up or fails the request that emitted the event; failures are logged
+//! and counted on `/metrics`.
+//!
+//! Set `HOOKS_WEBHOOK_URL` to also POST every event as JSON,
+//! `{"event": "booking_created", "data": {...}}`, to an outside endpoint,
+//! with `HOOKS_WEBHOOK_TOKEN` as a bearer token if set.
+
+use futures::future::BoxFuture;
+use serde::Serialize;
+use shared_types::circuit_breaker::BreakerOpen;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use crate::circuit_breakers::{self, HOOKS_WEBHOOK};
+
+/// What a subscriber returns; the error is only logged
+pub type HookResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// A booking request was submitted
+#[derive(Debug, Clone, Serialize)]
+pub struct BookingCreated {
+    pub booking_id: i32,
+    pub artist_id: i32,
+    /// The artist has an auto-response that should go out now
+    pub auto_response_due: bool,
+}
+
+/// The artist marked a booking completed
+#[derive(Debug, Clone, Serialize)]
+pub struct BookingCompleted {
+    pub booking_id: i32,
+    pub artist_id: i32,
+}
+
+/// An artist finished onboarding and their profile went live
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtistPublished {
+    pub artist_id: i32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagChange {
+    Added,
+    Removed,
+}
+
+/// An admin added or removed a style tag on an image
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageTagged {
+    pub image_id: i64,
+    pub style_id: i64,
+    pub change: TagChange,
+    pub tagged_by: i64,
+}
+
+type Handler<E> = Box<dyn Fn(E) -> BoxFuture<'static, HookResult> + Send + Sync>;
+
+struct Subscriber<E> {
+    name: &'static str,
+    handler: Handler<E>,
+}
+
+impl<E> Subscriber<E> {
+    fn new<F, Fut>(name: &'static str, handler: F) -> Self
+    where
+        F: Fn(E) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HookResult> + Send + 'static,
+    {
+        Self {
+            name,
+            handler: Box::new(move |event| Box::pin(handler(event))),
+        }
+    }
+}
+
+/// How often an event was emitted and how many of its subscribers failed,
+/// since startup
+struct Counts {
+    emitted: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl Counts {
+    const fn new() -> Self {
+        Self {
+            emitted: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+        }
+    }
+}
+
+/// The subscribers to each event
+#[derive(Default)]
+struct Hooks {
+    booking_created: Vec<Subscriber<BookingCreated>>,
+    booking_completed: Vec<Subscriber<BookingCompleted>>,
+    artist_published: Vec<Subscriber<ArtistPublished>>,
+    image_tagged: Vec<Subscriber<ImageTagged>>,
+}
+
+impl Hooks {
+    fn on_booking_created<F, Fut>(&mut self, name: &'static str, handler: F) -> &mut Self
+    where
+        F: Fn(BookingCreated) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HookResult> + Send + 'static,
+    {
+        self.booking_created.push(Subscriber::new(name, handler));
+        self
+    }
+
+    fn on_booking_completed<F, Fut>(&mut self, name: &'static str, handler: F) -> &mut Self
+    where
+        F: Fn(BookingCompleted) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HookResult> + Send + 'static,
+    {
+        self.booking_completed.push(Subscriber::new(name, handler));
+        self
+    }
+
+    fn on_artist_published<F, Fut>(&mut self, name: &'static str, handler: F) -> &mut Self
+    where
+        F: Fn(ArtistPublished) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HookResult> + Send + 'static,
+    {
+        self.artist_published.push(Subscriber::new(name, handler));
+        self
+    }
+
+    fn on_image_tagged<F, Fut>(&mut self, name: &'static str, handler: F) -> &mut Self
+    where
+        F: Fn(ImageTagged) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HookResult> + Send + 'static,
+    {
+        self.image_tagged.push(Subscriber::new(name, handler));
+        self
+    }
+}
+
+static HOOKS: OnceLock<Hooks> = OnceLock::new();
+static BOOKING_CREATED: Counts = Counts::new();
+static BOOKING_COMPLETED: Counts = Counts::new();
+static ARTIST_PUBLISHED: Counts = Counts::new();
+static IMAGE_TAGGED: Counts = Counts::new();
+
+fn hooks() -> &'static Hooks {
+    HOOKS.get_or_init(builtin)
+}
+
+/// The subsystems subscribed to each event
+fn builtin() -> Hooks {
+    let mut hooks = Hooks::default();
+
+    hooks.on_booking_created("auto_response", |event| async move {
+        if event.auto_response_due {
+            crate::auto_response::send_due_auto_responses().await?;
+        }
+        Ok(())
+    });
+    hooks.on_booking_completed("aftercare", |event| {
+        crate::server_documents::send_aftercare(event.booking_id)
+    });
+    hooks.on_image_tagged("starter_packs", |_| async {
+        // Validated images feed the quiz's starter packs
+        crate::db::starter_pack_repository::invalidate_starter_packs();
+        Ok(())
+    });
+
+    if webhook().is_some() {
+        hooks
+            .on_booking_created("webhook", |event| post_webhook("booking_created", event))
+            .on_booking_completed("webhook", |event| post_webhook("booking_completed", event))
+            .on_artist_published("webhook", |event| post_webhook("artist_published", event))
+            .on_image_tagged("webhook", |event| post_webhook("image_tagged", event));
+    }
+
+    hooks
+}
+
+fn dispatch<E: Clone + Send + 'static>(
+    event_name: &'static str,
+    subscribers: &'static [Subscriber<E>],
+    counts: &'static Counts,
+    event: E,
+) {
+    counts.emitted.fetch_add(1, Ordering::Relaxed);
+    for subscriber in subscribers {
+        let name = subscriber.name;
+        let hook = (subscriber.handler)(event.clone());
+        tokio::spawn(async move {
+            if let Err(e) = hook.await {
+                counts.failed.fetch_add(1, Ordering::Relaxed);
+                tracing::error!(event = event_name, subscriber = name, "Hook failed: {}", e);
+            }
+        });
+    }
+}
+
+pub fn booking_created(event: BookingCreated) {
+    dispatch(
+        "booking_created",
+        &hooks().booking_created,
+        &BOOKING_CREATED,
+        event,
+    );
+}
+
+pub fn booking_completed(event: BookingCompleted) {
+    dispatch(
+        "booking_completed",
+        &hooks().booking_completed,
+        &BOOKING_COMPLETED,
+        event,
+    );
+}
+
+pub fn artist_published(event: ArtistPublished) {
+    dispatch(
+        "artist_published",
+        &hooks().artist_published,
+        &ARTIST_PUBLISHED,
+        event,
+    );
+}
+
+pub fn image_tagged(event: ImageTagged) {
+    dispatch("image_tagged", &hooks().image_tagged, &IMAGE_TAGGED, event);
+}
+
+/// `(event, emitted, failed subscribers)` since startup, for `/metrics`
+pub fn event_counts() -> [(&'static str, u64, u64); 4] {
+    [
+        ("booking_created", &BOOKING_CREATED),
+        ("booking_completed", &BOOKING_COMPLETED),
+        ("artist_published", &ARTIST_PUBLISHED),
+        ("image_tagged", &IMAGE_TAGGED),
+    ]
+    .map(|(event, counts)| {
+        (
+            event,
+            counts.emitted.load(Ordering::Relaxed),
+            counts.failed.load(Ordering::Relaxed),
+        )
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+enum WebhookError {
+    #[error("hook webhook request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("hook webhook returned {0}")]
+    Status(u16),
+    #[error(transparent)]
+    Unavailable(#[from] BreakerOpen),
+}
+
+/// Where events are POSTed, built from the environment by [`crate::config`]
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub token: Option<String>,
+}
+
+fn webhook() -> Option<&'static WebhookConfig> {
+    crate::config::config().hooks_webhook.as_ref()
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<E> {
+    event: &'static str,
+    data: E,
+}
+
+async fn post_webhook<E: Serialize>(event: &'static str, data: E) -> HookResult {
+    let Some(config) = webhook() else {
+        return Ok(());
+    };
+
+    let mut request = reqwest::Client::new()
+        .post(&config.url)
+        .json(&WebhookPayload { event, data });
+    if let Some(token) = config.token.as_deref() {
+        request = request.bearer_auth(token);
+    }
+
+    let response = circuit_breakers::send::<WebhookError>(&HOOKS_WEBHOOK, request).await?;
+    if !response.status().is_success() {
+        return Err(WebhookError::Status(response.status().as_u16()).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Conditional GETs for the heaviest read endpoints, so a map pan back over
+//! an area already loaded costs a 304 instead of the full payload again.
+//!
+//! The server fns listed in `ETAG_PATHS` are served over GET at fixed paths.
+//! Their responses get a weak ETag hashed from the body and
+//! `Cache-Control: private, no-cache`, which makes the browser revalidate
+//! with `If-None-Match` before reusing its copy. Galleries take the caller's
+//! token and stay POST; like everything else they are only compressed.
+
+use axum::body::Body;
+use axum::extract::Request;
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sha2::{Digest, Sha256};
+
+/// Server fn paths that answer conditional GETs
+pub const ETAG_PATHS: [&str; 3] = [
+    "/api/locations",
+    "/api/locations_with_details",
+    "/api/location_pins",
+];
+
+fn etag_for(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    // Weak, since compression changes the bytes actually sent
+    format!("W/\"{}\"", hex)
+}
+
+/// Whether `If-None-Match` lists `etag`, comparing weakly
+fn matches_if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+/// Middleware adding ETags to `ETAG_PATHS` responses and answering 304 when
+/// the client already has the current body
+pub async fn conditional_get(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET || !ETAG_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let request_headers = request.headers().clone();
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for ETag: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let etag = etag_for(&bytes);
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(header::ETAG, value);
+    }
+    parts.headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-cache"),
+    );
+
+    if matches_if_none_match(&request_headers, &etag) {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.headers.remove(header::CONTENT_TYPE);
+        return Response::from_parts(parts, Body::empty());
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
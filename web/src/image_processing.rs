@@ -0,0 +1,261 @@
+//! Cleans up uploaded images before they're stored. Every upload is decoded
+//! and re-encoded, which:
+//!
+//! - transcodes HEIC (iPhone photos) and WebP to JPEG, or to PNG when the
+//!   image has transparency, so every browser can show it
+//! - drops EXIF and other metadata, GPS position included, since nothing
+//!   from the original file is carried over
+//! - applies the EXIF orientation first, so photos taken sideways aren't
+//!   stored sideways once the tag is gone
+//!
+//! Files that aren't an image, are cut short or decode to something absurdly
+//! large are rejected with an [`ImageError`].
+
+use axum::http::StatusCode;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+use std::io::Cursor;
+
+/// Widest or tallest image accepted, in pixels
+const MAX_DIMENSION: u32 = 12_000;
+/// Most pixels accepted, so a small file can't decode to gigabytes
+const MAX_PIXELS: u64 = 50_000_000;
+const JPEG_QUALITY: u8 = 88;
+/// Longest edge of generated thumbnails, in pixels
+const THUMBNAIL_SIZE: u32 = 480;
+/// Lossy quality of gallery WebPs, out of 100
+const WEBP_QUALITY: f32 = 80.0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImageError {
+    #[error("Only JPEG, PNG, WebP and HEIC images are supported")]
+    Unsupported,
+    #[error("The image could not be read")]
+    Corrupt,
+    #[error("Images can be at most {MAX_DIMENSION} pixels on each side")]
+    TooLarge,
+    #[error("Failed to process image")]
+    Encode,
+}
+
+impl ImageError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ImageError::Unsupported => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ImageError::Corrupt => StatusCode::UNPROCESSABLE_ENTITY,
+            ImageError::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ImageError::Encode => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Formats accepted as uploads
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SourceFormat {
+    Jpeg,
+    Png,
+    WebP,
+    Heic,
+}
+
+/// An upload ready to store: re-encoded without metadata, upright, with a
+/// JPEG thumbnail
+pub struct ProcessedImage {
+    /// "image/jpeg" or "image/png"
+    pub content_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+/// HEIC files are ISO media files whose `ftyp` box lists a HEIF brand. AVIF
+/// uses the same container, so the generic `mif1`/`msf1` brands alone don't
+/// count.
+fn is_heic(bytes: &[u8]) -> bool {
+    if bytes.len() < 16 || &bytes[4..8] != b"ftyp" {
+        return false;
+    }
+    let box_len = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+    let ftyp = &bytes[8..box_len.clamp(16, bytes.len())];
+    // Major brand, minor version, then compatible brands
+    ftyp.chunks_exact(4)
+        .enumerate()
+        .filter(|(i, _)| *i != 1)
+        .any(|(_, brand)| {
+            matches!(
+                brand,
+                b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx"
+            )
+        })
+}
+
+/// The upload's format, from its content rather than the name or the type
+/// the client claimed
+pub fn sniff(bytes: &[u8]) -> Result<SourceFormat, ImageError> {
+    if is_heic(bytes) {
+        return Ok(SourceFormat::Heic);
+    }
+    match image::guess_format(bytes) {
+        Ok(ImageFormat::Jpeg) => Ok(SourceFormat::Jpeg),
+        Ok(ImageFormat::Png) => Ok(SourceFormat::Png),
+        Ok(ImageFormat::WebP) => Ok(SourceFormat::WebP),
+        _ => Err(ImageError::Unsupported),
+    }
+}
+
+fn check_size(width: u32, height: u32) -> Result<(), ImageError> {
+    if width == 0 || height == 0 {
+        return Err(ImageError::Corrupt);
+    }
+    if width > MAX_DIMENSION
+        || height > MAX_DIMENSION
+        || u64::from(width) * u64::from(height) > MAX_PIXELS
+    {
+        return Err(ImageError::TooLarge);
+    }
+    Ok(())
+}
+
+/// JPEG, PNG or WebP, turned upright by its EXIF orientation
+fn decode_raster(bytes: &[u8], format: ImageFormat) -> Result<DynamicImage, ImageError> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+    let mut decoder = reader.into_decoder().map_err(|_| ImageError::Corrupt)?;
+
+    let (width, height) = decoder.dimensions();
+    check_size(width, height)?;
+    // A missing or unreadable orientation tag just means upright
+    let orientation = decoder
+        .orientation()
+        .unwrap_or(image::metadata::Orientation::NoTransforms);
+
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| match e {
+        image::ImageError::Limits(_) => ImageError::TooLarge,
+        _ => ImageError::Corrupt,
+    })?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// The primary image of a HEIC file. libheif applies the file's rotation and
+/// mirroring while decoding.
+fn decode_heic(bytes: &[u8]) -> Result<DynamicImage, ImageError> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let context = HeifContext::read_from_bytes(bytes).map_err(|_| ImageError::Corrupt)?;
+    let handle = context
+        .primary_image_handle()
+        .map_err(|_| ImageError::Corrupt)?;
+    check_size(handle.width(), handle.height())?;
+
+    let has_alpha = handle.has_alpha_channel();
+    let chroma = if has_alpha {
+        RgbChroma::Rgba
+    } else {
+        RgbChroma::Rgb
+    };
+    let decoded = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(chroma), None)
+        .map_err(|_| ImageError::Corrupt)?;
+    let plane = decoded.planes().interleaved.ok_or(ImageError::Corrupt)?;
+
+    // Rows can be padded past width * channels
+    let channels = if has_alpha { 4 } else { 3 };
+    let row_len = plane.width as usize * channels;
+    let pixels: Vec<u8> = plane
+        .data
+        .chunks(plane.stride)
+        .take(plane.height as usize)
+        .flat_map(|row| row.get(..row_len).unwrap_or(row).iter().copied())
+        .collect();
+
+    let image = if has_alpha {
+        image::RgbaImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgba8)
+    } else {
+        image::RgbImage::from_raw(plane.width, plane.height, pixels).map(DynamicImage::ImageRgb8)
+    };
+    image.ok_or(ImageError::Corrupt)
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>, ImageError> {
+    let mut bytes = Cursor::new(Vec::new());
+    // JPEG has no alpha channel
+    DynamicImage::ImageRgb8(image.to_rgb8())
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY))
+        .map_err(|_| ImageError::Encode)?;
+    Ok(bytes.into_inner())
+}
+
+/// Decodes, validates and re-encodes an upload, see the module docs
+pub fn process(bytes: &[u8]) -> Result<ProcessedImage, ImageError> {
+    let image = match sniff(bytes)? {
+        SourceFormat::Heic => decode_heic(bytes)?,
+        SourceFormat::Jpeg => decode_raster(bytes, ImageFormat::Jpeg)?,
+        SourceFormat::Png => decode_raster(bytes, ImageFormat::Png)?,
+        SourceFormat::WebP => decode_raster(bytes, ImageFormat::WebP)?,
+    };
+
+    // Only keep PNG for images that actually use transparency
+    let transparent =
+        image.color().has_alpha() && image.to_rgba8().pixels().any(|pixel| pixel.0[3] < u8::MAX);
+    let (content_type, bytes) = if transparent {
+        let mut png = Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, ImageFormat::Png)
+            .map_err(|_| ImageError::Encode)?;
+        ("image/png", png.into_inner())
+    } else {
+        ("image/jpeg", encode_jpeg(&image)?)
+    };
+
+    Ok(ProcessedImage {
+        content_type,
+        width: image.width(),
+        height: image.height(),
+        bytes,
+        thumbnail: encode_jpeg(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE))?,
+    })
+}
+
+/// An image scaled to fit a width, encoded as WebP
+pub struct WebpVariant {
+    /// The width asked for; the image is narrower if the original was
+    pub max_width: u32,
+    pub bytes: Vec<u8>,
+}
+
+/// Decodes a JPEG, PNG or WebP image and encodes it as WebP scaled down to
+/// each of `widths`. Images are never scaled up.
+pub fn webp_variants(bytes: &[u8], widths: &[u32]) -> Result<Vec<WebpVariant>, ImageError> {
+    let format = match sniff(bytes)? {
+        SourceFormat::Jpeg => ImageFormat::Jpeg,
+        SourceFormat::Png => ImageFormat::Png,
+        SourceFormat::WebP => ImageFormat::WebP,
+        SourceFormat::Heic => return Err(ImageError::Unsupported),
+    };
+    let image = decode_raster(bytes, format)?;
+
+    widths
+        .iter()
+        .map(|&max_width| {
+            let scaled = if image.width() > max_width {
+                image.resize(max_width, image.height(), FilterType::CatmullRom)
+            } else {
+                image.clone()
+            };
+            let rgb = scaled.to_rgb8();
+            let encoded =
+                webp::Encoder::from_rgb(&rgb, rgb.width(), rgb.height()).encode(WEBP_QUALITY);
+            Ok(WebpVariant {
+                max_width,
+                bytes: encoded.to_vec(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,185 @@
+//! Gallery images for Instagram posts, served from our own storage so
+//! galleries can show plain `<img>` tags instead of loading an embed per
+//! post.
+//!
+//! `GET /api/instagram/:short_code/image/:width` redirects to a WebP of the
+//! post's image at the smallest of [`GALLERY_IMAGE_WIDTHS`] at least
+//! `width` wide. The first request for a post fetches its image from
+//! Instagram, falling back to the thumbnail media ingestion saved, and
+//! stores every width; after that it's only the redirect. Stored images
+//! never change, so they're served with long cache headers, from a CDN when
+//! `S3_PUBLIC_URL` points at one.
+//!
+//! Only posts we know of are fetched, and a post whose image can't be found
+//! isn't tried again for a day.
+
+use axum::extract::Path;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use crate::circuit_breakers::{self, INSTAGRAM};
+use crate::db::instagram_media_repository::{
+    get_instagram_image, get_thumbnail, is_known_post, save_instagram_image,
+};
+use crate::image_processing::{self, ImageError};
+use crate::server_instagram::{valid_short_code, GALLERY_IMAGE_WIDTHS};
+use crate::storage::{storage, StorageError};
+
+/// Largest image accepted from Instagram
+const MAX_SOURCE_BYTES: usize = 15 * 1024 * 1024;
+/// How long a post whose image couldn't be found is left alone
+const MISSING_RETRY_SECS: f64 = 24.0 * 3600.0;
+
+#[derive(Debug, thiserror::Error)]
+enum ImageProxyError {
+    #[error("database error: {0}")]
+    Db(#[from] sqlx::Error),
+    #[error("storing image failed: {0}")]
+    Storage(#[from] StorageError),
+    #[error("encoding image failed: {0}")]
+    Image(#[from] ImageError),
+    #[error("image request failed: {0}")]
+    Fetch(#[from] reqwest::Error),
+    #[error(transparent)]
+    Unavailable(#[from] shared_types::circuit_breaker::BreakerOpen),
+}
+
+fn image_key(short_code: &str, width: u32) -> String {
+    format!("instagram/{}/{}.webp", short_code, width)
+}
+
+/// The post's full-size image from Instagram, `None` if Instagram won't
+/// serve it
+async fn fetch_source_image(short_code: &str) -> Result<Option<Vec<u8>>, ImageProxyError> {
+    let request = reqwest::Client::new()
+        .get(format!(
+            "https://www.instagram.com/p/{}/media/?size=l",
+            short_code
+        ))
+        .timeout(std::time::Duration::from_secs(15));
+
+    let response = circuit_breakers::send::<ImageProxyError>(&INSTAGRAM, request).await?;
+    // Without a session Instagram may answer with a login page instead
+    let is_image = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("image/"));
+    if !response.status().is_success() || !is_image {
+        return Ok(None);
+    }
+
+    let bytes = response.bytes().await?;
+    Ok((bytes.len() <= MAX_SOURCE_BYTES).then(|| bytes.to_vec()))
+}
+
+/// Fetches the post's image and stores it at every width, returning the
+/// widths stored; none when no image could be found
+async fn store_image(short_code: &str) -> Result<Vec<i32>, ImageProxyError> {
+    let (fetched, fetch_error) = match fetch_source_image(short_code).await {
+        Ok(fetched) => (fetched, None),
+        Err(e) => {
+            tracing::warn!("Failed to fetch image for {}: {}", short_code, e);
+            (None, Some(e))
+        }
+    };
+    let source = match fetched {
+        Some(bytes) => Some(("instagram", bytes)),
+        None => get_thumbnail(short_code)
+            .await?
+            .map(|bytes| ("saved_thumbnail", bytes)),
+    };
+    let Some((source, bytes)) = source else {
+        // Only recorded missing when Instagram answered
+        if let Some(e) = fetch_error {
+            return Err(e);
+        }
+        save_instagram_image(short_code, &[], None).await?;
+        return Ok(vec![]);
+    };
+
+    // Decoding and encoding are CPU-bound
+    let variants = tokio::task::spawn_blocking(move || {
+        image_processing::webp_variants(&bytes, &GALLERY_IMAGE_WIDTHS)
+    })
+    .await
+    .map_err(|_| ImageError::Encode)??;
+
+    for variant in variants {
+        storage()
+            .put(
+                &image_key(short_code, variant.max_width),
+                variant.bytes,
+                "image/webp",
+            )
+            .await?;
+    }
+
+    let widths: Vec<i32> = GALLERY_IMAGE_WIDTHS.iter().map(|w| *w as i32).collect();
+    save_instagram_image(short_code, &widths, Some(source)).await?;
+    Ok(widths)
+}
+
+fn not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        [(header::CACHE_CONTROL, "public, max-age=3600")],
+    )
+        .into_response()
+}
+
+/// GET /api/instagram/:short_code/image/:width
+pub async fn instagram_image_handler(Path((short_code, width)): Path<(String, u32)>) -> Response {
+    if !valid_short_code(&short_code) {
+        return not_found();
+    }
+    let width = GALLERY_IMAGE_WIDTHS
+        .iter()
+        .copied()
+        .find(|w| *w >= width)
+        .unwrap_or(GALLERY_IMAGE_WIDTHS[GALLERY_IMAGE_WIDTHS.len() - 1]);
+
+    let stored = match get_instagram_image(&short_code).await {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::error!("Failed to load image for {}: {}", short_code, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let widths = match stored {
+        Some(stored) if !stored.widths.is_empty() => stored.widths,
+        Some(stored) if stored.checked_age_secs < MISSING_RETRY_SECS => return not_found(),
+        _ => {
+            match is_known_post(&short_code).await {
+                Ok(true) => {}
+                Ok(false) => return not_found(),
+                Err(e) => {
+                    tracing::error!("Failed to look up post {}: {}", short_code, e);
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+            }
+            match store_image(&short_code).await {
+                Ok(widths) => widths,
+                Err(e) => {
+                    tracing::error!("Failed to store image for {}: {}", short_code, e);
+                    return StatusCode::SERVICE_UNAVAILABLE.into_response();
+                }
+            }
+        }
+    };
+
+    let url = match storage().public_url(&image_key(&short_code, width)) {
+        Some(url) if widths.contains(&(width as i32)) => url,
+        _ => return not_found(),
+    };
+
+    (
+        StatusCode::FOUND,
+        [
+            (header::LOCATION, url),
+            (header::CACHE_CONTROL, "public, max-age=86400".to_string()),
+        ],
+    )
+        .into_response()
+}
@@ -0,0 +1,422 @@
+//! Background jobs. Work that shouldn't hold up a server fn (sending a
+//! notification, refreshing an Instagram embed, running an export) is
+//! queued as a [`Job`] in `background_jobs` with [`enqueue`] and run by
+//! [`run_worker`], which `main.rs` spawns. Every server runs a worker; a job
+//! is only ever claimed by one of them. Ingestion's jobs share the table on
+//! their own queue, which this worker leaves alone.
+//!
+//! A job can also be queued for later with [`enqueue_at`], like a booking
+//! reminder, and dropped with [`cancel_queued`] if it's no longer wanted.
+//! Periodic work is listed in [`SCHEDULE`] and queued by [`run_scheduler`],
+//! which every server runs too; `job_schedules` makes sure only one of them
+//! queues each task when it comes due.
+//!
+//! A job that fails is retried after 30 seconds, doubling with each attempt
+//! up to an hour, until it has used its attempts; then it's left `dead` in
+//! the table for an operator to look into. Jobs cut off by a restart are
+//! requeued by the scheduler.
+//!
+//! A new kind of work is a new [`Job`] variant and its arm in [`Job::run`],
+//! plus an entry in [`SCHEDULE`] if it runs on a timer.
+
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::db::job_repository::{self, StoredJob};
+use crate::notify::{self, Message};
+
+/// Jobs claimed at a time; they run concurrently
+const JOBS_PER_POLL: i64 = 10;
+/// How often the worker looks for due jobs when it isn't woken
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 60 * 60;
+/// Running jobs older than this were cut off by a restart
+const STALLED_AFTER_MINUTES: i32 = 15;
+/// How long completed jobs are kept
+const KEEP_COMPLETED_DAYS: i32 = 7;
+/// How often the scheduler checks for due tasks
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(10);
+const EVERY_MINUTE: Duration = Duration::from_secs(60);
+const HOURLY: Duration = Duration::from_secs(60 * 60);
+
+/// Periodic work, and how often it's queued
+pub const SCHEDULE: &[(Job, Duration)] = &[
+    // Replies to booking requests whose auto-response delay has passed
+    (Job::SendAutoResponses, EVERY_MINUTE),
+    (Job::SyncCalendarImports, EVERY_MINUTE),
+    // Resumable uploads that were never finished
+    (Job::CleanupAbandonedUploads, HOURLY),
+    // Booking attachments past `ATTACHMENT_RETENTION_DAYS`
+    (Job::PurgeExpiredAttachments, HOURLY),
+    (Job::DeleteExpiredRefreshTokens, HOURLY),
+    // Export files past `EXPORT_RETENTION_HOURS`
+    (Job::ExpireExports, HOURLY),
+    (Job::DeleteExpiredRateLimitCounters, HOURLY),
+    (Job::SendLicenseReminders, HOURLY),
+    (Job::RefreshCachedInstagramEmbeds, HOURLY),
+    // Bookings older than `ARCHIVE_BOOKINGS_AFTER_YEARS`
+    (Job::ArchiveOldBookings, HOURLY),
+    (Job::DeleteCompletedJobs, HOURLY),
+    (Job::RefreshStyleCooccurrence, HOURLY),
+    // Accounts whose deletion grace period is over
+    (Job::PurgeDeletedAccounts, HOURLY),
+    // Impressions past `IMPRESSION_RETENTION_DAYS`
+    (Job::RollUpImpressions, HOURLY),
+];
+
+/// What a job returns; the error is recorded on the job
+pub type JobResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum Job {
+    /// An email or SMS
+    SendNotification(Message),
+    /// Re-fetches a cached Instagram embed
+    RefreshInstagramEmbed { short_code: String },
+    /// Rebuilds the style co-occurrence behind "more like this"
+    RefreshStyleCooccurrence,
+    /// Purges accounts whose data deletion is due
+    PurgeDeletedAccounts,
+    /// Fetches the imported calendars due a sync
+    SyncCalendarImports,
+    /// Folds old match impressions into weekly totals
+    RollUpImpressions,
+    /// Sends the booking auto-responses that are due
+    SendAutoResponses,
+    /// Expires resumable uploads that were never finished
+    CleanupAbandonedUploads,
+    /// Deletes booking attachments past their retention
+    PurgeExpiredAttachments,
+    /// Deletes refresh tokens past their expiry
+    DeleteExpiredRefreshTokens,
+    /// Deletes export files past their retention
+    ExpireExports,
+    /// Deletes rate limit counters whose window has ended
+    DeleteExpiredRateLimitCounters,
+    /// Emails artists whose licenses are about to expire
+    SendLicenseReminders,
+    /// Re-fetches cached Instagram embeds that are due a refresh
+    RefreshCachedInstagramEmbeds,
+    /// Moves old completed bookings to the archive
+    ArchiveOldBookings,
+    /// Deletes old completed jobs from this queue
+    DeleteCompletedJobs,
+    /// Writes a queued export's file
+    RunExport { export_id: String },
+    /// Reminds a client of their appointment, `appointment` (`YYYY-MM-DD
+    /// HH:MM`) being the time it was queued for
+    SendBookingReminder {
+        booking_id: i32,
+        hours_before: i32,
+        appointment: String,
+    },
+}
+
+impl Job {
+    /// The variant's name in snake case, as stored in `kind`
+    fn kind(&self) -> Result<String, serde_json::Error> {
+        let value = serde_json::to_value(self)?;
+        Ok(value["kind"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// Jobs with the same key aren't queued twice
+    fn dedupe_key(&self) -> Option<String> {
+        match self {
+            Job::SendNotification(_) => None,
+            Job::RefreshInstagramEmbed { short_code } => Some(short_code.clone()),
+            Job::SendBookingReminder {
+                booking_id,
+                hours_before,
+                ..
+            } => Some(crate::reminders::reminder_key(*booking_id, *hours_before)),
+            Job::RunExport { export_id } => Some(export_id.clone()),
+            // Scheduled work, one of each at a time
+            _ => self.kind().ok(),
+        }
+    }
+
+    fn max_attempts(&self) -> i32 {
+        match self {
+            Job::SendNotification(_) | Job::SendBookingReminder { .. } => 5,
+            // A failed export is marked failed for its owner to ask again
+            Job::RunExport { .. } => 1,
+            _ => 3,
+        }
+    }
+
+    async fn run(self) -> JobResult {
+        match self {
+            Job::SendNotification(message) => notify::send(&message).await?,
+            Job::RefreshInstagramEmbed { short_code } => {
+                crate::server_instagram::refresh_embed(&short_code).await?
+            }
+            Job::RefreshStyleCooccurrence => {
+                let pairs =
+                    crate::db::similar_image_repository::refresh_style_cooccurrence().await?;
+                tracing::info!(pairs, "Refreshed style co-occurrence");
+            }
+            Job::PurgeDeletedAccounts => {
+                let purged = crate::server_account::purge_deleted_accounts().await?;
+                if purged > 0 {
+                    tracing::info!(purged, "Purged deleted accounts");
+                }
+            }
+            Job::SyncCalendarImports => {
+                let synced = crate::calendar_imports::sync_due_imports().await?;
+                if synced > 0 {
+                    tracing::info!(synced, "Synced imported calendars");
+                }
+            }
+            Job::RollUpImpressions => {
+                let folded = crate::server_insights::roll_up_impressions().await?;
+                if folded > 0 {
+                    tracing::info!(folded, "Rolled up match impressions");
+                }
+            }
+            Job::SendBookingReminder {
+                booking_id,
+                hours_before,
+                appointment,
+            } => crate::reminders::send_reminder(booking_id, hours_before, &appointment).await?,
+            Job::SendAutoResponses => {
+                let sent = crate::auto_response::send_due_auto_responses().await?;
+                if sent > 0 {
+                    tracing::info!(sent, "Sent booking auto-responses");
+                }
+            }
+            Job::CleanupAbandonedUploads => {
+                let expired = crate::uploads::cleanup_abandoned_uploads().await?;
+                if expired > 0 {
+                    tracing::info!(expired, "Expired abandoned uploads");
+                }
+            }
+            Job::PurgeExpiredAttachments => {
+                let purged = crate::uploads::purge_expired_attachments().await?;
+                if purged > 0 {
+                    tracing::info!(purged, "Purged expired booking attachments");
+                }
+            }
+            Job::DeleteExpiredRefreshTokens => {
+                let deleted = crate::db::refresh_token_repository::delete_expired_tokens().await?;
+                if deleted > 0 {
+                    tracing::info!(deleted, "Deleted expired refresh tokens");
+                }
+            }
+            Job::ExpireExports => {
+                let deleted = crate::exports::expire_exports().await?;
+                if deleted > 0 {
+                    tracing::info!(deleted, "Deleted expired exports");
+                }
+            }
+            Job::DeleteExpiredRateLimitCounters => {
+                let deleted = crate::rate_limit::delete_expired_counters().await?;
+                if deleted > 0 {
+                    tracing::info!(deleted, "Deleted expired rate limit counters");
+                }
+            }
+            Job::SendLicenseReminders => {
+                let sent = crate::licensing::send_expiry_reminders().await?;
+                if sent > 0 {
+                    tracing::info!(sent, "Sent license expiry reminders");
+                }
+            }
+            Job::RefreshCachedInstagramEmbeds => {
+                let refreshed = crate::server_instagram::refresh_cached_embeds().await?;
+                if refreshed > 0 {
+                    tracing::info!(refreshed, "Refreshed cached Instagram embeds");
+                }
+            }
+            Job::ArchiveOldBookings => {
+                let archived = crate::archive::archive_old_bookings().await?;
+                if archived > 0 {
+                    tracing::info!(archived, "Archived old bookings");
+                }
+            }
+            Job::DeleteCompletedJobs => {
+                let deleted = job_repository::delete_completed_jobs(KEEP_COMPLETED_DAYS).await?;
+                if deleted > 0 {
+                    tracing::info!(deleted, "Deleted completed background jobs");
+                }
+            }
+            Job::RunExport { export_id } => crate::exports::run_export(&export_id).await?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JobError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("invalid job payload: {0}")]
+    Payload(#[from] serde_json::Error),
+}
+
+fn wake() -> &'static Notify {
+    static WAKE: OnceLock<Notify> = OnceLock::new();
+    WAKE.get_or_init(Notify::new)
+}
+
+/// Queues a job and wakes this server's worker. Returns false when the
+/// same job is already queued.
+pub async fn enqueue(job: Job) -> Result<bool, JobError> {
+    let queued = store(&job, None).await?;
+    if queued {
+        wake().notify_one();
+    }
+    Ok(queued)
+}
+
+/// Queues a job to run at `run_at`. Returns false when the same job is
+/// already queued.
+pub async fn enqueue_at(job: Job, run_at: DateTime<Utc>) -> Result<bool, JobError> {
+    store(&job, Some(run_at)).await
+}
+
+async fn store(job: &Job, run_at: Option<DateTime<Utc>>) -> Result<bool, JobError> {
+    let value = serde_json::to_value(job)?;
+
+    Ok(job_repository::enqueue(
+        &job.kind()?,
+        &value["payload"].to_string(),
+        job.dedupe_key().as_deref(),
+        job.max_attempts(),
+        run_at,
+    )
+    .await?)
+}
+
+/// Drops queued jobs of `kind` (the variant's name in snake case) whose
+/// dedupe key starts with `key_prefix`, returning how many
+pub async fn cancel_queued(kind: &str, key_prefix: &str) -> Result<u64, JobError> {
+    Ok(job_repository::cancel_queued(kind, key_prefix).await?)
+}
+
+fn decode(stored: &StoredJob) -> Result<Job, serde_json::Error> {
+    let payload: serde_json::Value = serde_json::from_str(&stored.payload)?;
+    serde_json::from_value(serde_json::json!({ "kind": stored.kind, "payload": payload }))
+}
+
+/// Seconds to wait before retrying a job that has failed `attempts` times
+fn backoff_secs(attempts: i32) -> i64 {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    (BASE_BACKOFF_SECS << doublings).min(MAX_BACKOFF_SECS)
+}
+
+/// Runs one claimed job and records how it went, returning whether it
+/// succeeded
+async fn run_claimed(stored: StoredJob) -> Result<bool, sqlx::Error> {
+    let result = match decode(&stored) {
+        Ok(job) => job.run().await,
+        // A kind this version doesn't know, or a payload it can't read;
+        // retrying won't help
+        Err(e) => {
+            tracing::error!(job_id = stored.id, kind = %stored.kind, "Unreadable job: {}", e);
+            job_repository::dead_letter_job(stored.id, &e.to_string()).await?;
+            return Ok(false);
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            job_repository::complete_job(stored.id).await?;
+            Ok(true)
+        }
+        Err(e) if stored.attempts >= stored.max_attempts => {
+            tracing::error!(
+                job_id = stored.id,
+                kind = %stored.kind,
+                attempts = stored.attempts,
+                "Job failed for the last time: {}",
+                e
+            );
+            job_repository::dead_letter_job(stored.id, &e.to_string()).await?;
+            Ok(false)
+        }
+        Err(e) => {
+            let delay = backoff_secs(stored.attempts);
+            tracing::warn!(
+                job_id = stored.id,
+                kind = %stored.kind,
+                attempts = stored.attempts,
+                "Job failed, retrying in {}s: {}",
+                delay,
+                e
+            );
+            job_repository::retry_job(stored.id, &e.to_string(), delay).await?;
+            Ok(false)
+        }
+    }
+}
+
+/// Runs due jobs, returning how many succeeded
+pub async fn run_due_jobs() -> Result<usize, sqlx::Error> {
+    let jobs = job_repository::claim_due(JOBS_PER_POLL).await?;
+    let mut succeeded = 0;
+    for result in join_all(jobs.into_iter().map(run_claimed)).await {
+        if result? {
+            succeeded += 1;
+        }
+    }
+    Ok(succeeded)
+}
+
+/// Runs jobs as they come due, for as long as the server is up
+pub async fn run_worker() {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = wake().notified() => {}
+        }
+        // Keep going while there's a backlog
+        loop {
+            match run_due_jobs().await {
+                Ok(0) => break,
+                Ok(count) => tracing::debug!("Finished {} background jobs", count),
+                Err(e) => {
+                    tracing::error!("Running background jobs failed: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Queues the [`SCHEDULE`]d work that has come due, for as long as the
+/// server is up
+pub async fn run_scheduler() {
+    let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        // Done here rather than as a job, since a stalled queue couldn't run
+        // it
+        match job_repository::requeue_stalled_jobs(STALLED_AFTER_MINUTES).await {
+            Ok(0) => {}
+            Ok(stalled) => tracing::warn!("Requeued {} interrupted background jobs", stalled),
+            Err(e) => tracing::error!("Requeuing interrupted background jobs failed: {}", e),
+        }
+
+        for (job, every) in SCHEDULE {
+            if let Err(e) = queue_if_due(job, *every).await {
+                tracing::error!(?job, "Queuing scheduled job failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Queues `job` if its turn has come, claiming the turn so no other server
+/// queues it as well
+async fn queue_if_due(job: &Job, every: Duration) -> Result<(), JobError> {
+    if job_repository::claim_schedule(&job.kind()?, every.as_secs() as i64).await? {
+        enqueue(job.clone()).await?;
+    }
+    Ok(())
+}
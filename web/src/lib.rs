@@ -1,10 +1,103 @@
 #![recursion_limit = "512"]
 
+pub mod api_error;
+#[cfg(feature = "ssr")]
+pub mod api_v1;
 pub mod app;
+#[cfg(feature = "ssr")]
+pub mod archive;
+#[cfg(feature = "ssr")]
+pub mod auth;
+#[cfg(feature = "ssr")]
+pub mod auto_response;
+#[cfg(feature = "ssr")]
+pub mod calendar_imports;
+#[cfg(feature = "ssr")]
+pub mod circuit_breakers;
 pub mod components;
+#[cfg(feature = "ssr")]
+pub mod config;
 pub mod db;
+#[cfg(feature = "ssr")]
+pub mod embed;
+#[cfg(feature = "ssr")]
+pub mod exports;
+#[cfg(feature = "ssr")]
+pub mod flash_designs;
+#[cfg(feature = "ssr")]
+pub mod health;
+#[cfg(feature = "ssr")]
+pub mod hooks;
+#[cfg(feature = "ssr")]
+pub mod http_cache;
+#[cfg(feature = "ssr")]
+pub mod image_processing;
+#[cfg(feature = "ssr")]
+pub mod instagram_images;
+#[cfg(feature = "ssr")]
+pub mod jobs;
+#[cfg(feature = "ssr")]
+pub mod licensing;
+#[cfg(feature = "ssr")]
+pub mod message_stream;
+#[cfg(feature = "ssr")]
+pub mod metrics;
+#[cfg(feature = "ssr")]
+pub mod notify;
+#[cfg(feature = "ssr")]
+pub mod places_api;
+#[cfg(feature = "ssr")]
+pub mod portfolio_uploads;
+#[cfg(feature = "ssr")]
+pub mod rate_limit;
+#[cfg(feature = "ssr")]
+pub mod reminders;
 pub mod server;
+pub mod server_account;
+pub mod server_archive;
+pub mod server_artist_questions;
+pub mod server_auto_response;
+pub mod server_calendar;
+pub mod server_client_dashboard;
+pub mod server_completeness;
+pub mod server_documents;
+pub mod server_embed;
+pub mod server_errors;
+pub mod server_exports;
 pub mod server_favorites;
+pub mod server_flash_designs;
+pub mod server_forecast;
+pub mod server_guest_spots;
+pub mod server_hints;
+pub mod server_insights;
+pub mod server_instagram;
+pub mod server_invoices;
+pub mod server_licenses;
+pub mod server_onboarding;
+pub mod server_places;
+pub mod server_portfolio;
+pub mod server_pricing;
+pub mod server_profile;
+pub mod server_quiz;
+pub mod server_reschedule;
+pub mod server_response_time;
+pub mod server_shops;
+pub mod server_short_links;
+pub mod server_status;
+pub mod server_sync;
+pub mod server_tattoo_photos;
+pub mod server_team;
+pub mod server_translation;
+#[cfg(feature = "ssr")]
+pub mod short_links;
+#[cfg(feature = "ssr")]
+pub mod storage;
+#[cfg(feature = "ssr")]
+pub mod tattoo_photo_uploads;
+#[cfg(feature = "ssr")]
+pub mod translate;
+#[cfg(feature = "ssr")]
+pub mod uploads;
 pub mod utils;
 pub mod views;
 
@@ -0,0 +1,255 @@
+//! Artist license documents and expiry reminders.
+//!
+//! `POST /api/artist/license-document` takes a `multipart/form-data` body
+//! with a `file` part (a photo or scan of the license) and the `license_id`
+//! it belongs to. Authentication and responses work as for portfolio
+//! uploads (see [`crate::portfolio_uploads`]), redirecting form posts to the
+//! settings page with `?license=uploaded` or `?license_error=<message>`. A
+//! new document sends the license back for review.
+//!
+//! Documents are kept in private storage. The artist and admins see them
+//! through [`document_link`]s, signed links to
+//! `GET /api/licenses/:id/document` that stop working after
+//! [`DOCUMENT_LINK_MINUTES`].
+//!
+//! [`send_expiry_reminders`] runs hourly as a scheduled job (see
+//! [`crate::jobs`]) and emails artists
+//! `utils::licensing::REMINDER_DAYS` before a license expires.
+
+use axum::extract::{Multipart, Path, Query};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+
+use crate::api_error::ApiError;
+use crate::db::license_repository::{self, ExpiringLicense};
+use crate::image_processing;
+use crate::notify::{self, Channel, Message};
+use crate::portfolio_uploads::{extension, serve_private_media, wants_json, UploadError};
+use crate::server_team::{authorize_artist, TeamPermission};
+use crate::storage::private_storage;
+use crate::utils::licensing::{reminder_due, REMINDER_DAYS};
+
+/// Largest document accepted
+pub const MAX_LICENSE_DOCUMENT_BYTES: usize = 25 * 1024 * 1024;
+const SETTINGS_PATH: &str = "/artist/dashboard/settings";
+/// Reminders sent per run
+const REMINDER_BATCH_SIZE: i64 = 200;
+/// How long a document link works for
+pub const DOCUMENT_LINK_MINUTES: i64 = 60;
+
+fn document_link_message(license_id: i32, expires: i64) -> String {
+    format!("license-document:{}:{}", license_id, expires)
+}
+
+/// A short-lived link to a license's document, for listings shown only to
+/// the artist and to admins
+pub fn document_link(license_id: i32) -> String {
+    let expires =
+        (chrono::Utc::now() + chrono::Duration::minutes(DOCUMENT_LINK_MINUTES)).timestamp();
+    format!(
+        "/api/licenses/{}/document?expires={}&signature={}",
+        license_id,
+        expires,
+        crate::auth::sign(&document_link_message(license_id, expires))
+    )
+}
+
+async fn store_document(headers: &HeaderMap, mut multipart: Multipart) -> Result<(), UploadError> {
+    let mut file: Option<Vec<u8>> = None;
+    let mut license_id: Option<String> = None;
+    let mut form_token: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| UploadError::new(StatusCode::BAD_REQUEST, "Malformed upload"))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                let bytes = field.bytes().await.map_err(|_| {
+                    UploadError::new(StatusCode::PAYLOAD_TOO_LARGE, "Document too large")
+                })?;
+                file = Some(bytes.to_vec());
+            }
+            "license_id" => license_id = field.text().await.ok(),
+            "token" => form_token = field.text().await.ok(),
+            _ => {}
+        }
+    }
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or(form_token)
+        .ok_or_else(|| UploadError::new(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+    let artist_id = authorize_artist(&token, TeamPermission::Settings)
+        .await
+        .map_err(|e| match e {
+            ApiError::Unauthorized(message) => UploadError(StatusCode::FORBIDDEN, message),
+            e => {
+                tracing::error!("Failed to authorize license upload: {}", e);
+                UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed")
+            }
+        })?;
+
+    let not_found = || UploadError::new(StatusCode::NOT_FOUND, "Unknown license");
+    let license_id = license_id
+        .as_deref()
+        .and_then(|id| id.trim().parse::<i32>().ok())
+        .ok_or_else(not_found)?;
+
+    let file = file
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| UploadError::new(StatusCode::BAD_REQUEST, "No document selected"))?;
+    if file.len() > MAX_LICENSE_DOCUMENT_BYTES {
+        return Err(UploadError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "Document too large",
+        ));
+    }
+
+    // Decoding and re-encoding are CPU-bound
+    let processed = tokio::task::spawn_blocking(move || image_processing::process(&file))
+        .await
+        .map_err(|_| UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed"))??;
+    let content_type = processed.content_type;
+
+    let document_key = format!(
+        "licenses/{}/{}.{}",
+        artist_id,
+        uuid::Uuid::new_v4(),
+        extension(content_type)
+    );
+    if let Err(e) = private_storage()
+        .put(&document_key, processed.bytes, content_type)
+        .await
+    {
+        tracing::error!("Failed to store license document: {}", e);
+        return Err(UploadError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store document",
+        ));
+    }
+
+    match license_repository::set_document(artist_id, license_id, &document_key).await {
+        Ok(Some(previous)) => {
+            if let Some(previous) = previous {
+                if let Err(e) = private_storage().delete(&previous).await {
+                    tracing::warn!("Failed to delete replaced license document: {}", e);
+                }
+            }
+            Ok(())
+        }
+        other => {
+            if let Err(e) = other {
+                tracing::error!("Failed to record license document: {}", e);
+            }
+            let _ = private_storage().delete(&document_key).await;
+            Err(not_found())
+        }
+    }
+}
+
+/// POST /api/artist/license-document
+pub async fn upload_license_document(headers: HeaderMap, multipart: Multipart) -> Response {
+    let json = wants_json(&headers);
+
+    match store_document(&headers, multipart).await {
+        Ok(()) if json => StatusCode::NO_CONTENT.into_response(),
+        Ok(()) => Redirect::to(&format!("{}?license=uploaded", SETTINGS_PATH)).into_response(),
+        Err(UploadError(status, message)) if json => (status, message).into_response(),
+        Err(UploadError(_, message)) => Redirect::to(&format!(
+            "{}?license_error={}",
+            SETTINGS_PATH,
+            urlencoding::encode(&message)
+        ))
+        .into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct DocumentLinkParams {
+    expires: i64,
+    signature: String,
+}
+
+/// GET /api/licenses/:id/document?expires=...&signature=... — serves the
+/// document to whoever holds a link from [`document_link`].
+pub async fn license_document_handler(
+    Path(license_id): Path<i32>,
+    Query(params): Query<DocumentLinkParams>,
+) -> Response {
+    if params.expires < chrono::Utc::now().timestamp()
+        || !crate::auth::verify_signature(
+            &document_link_message(license_id, params.expires),
+            &params.signature,
+        )
+    {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    match license_repository::get_document_key(license_id).await {
+        Ok(Some(key)) => serve_private_media(&key).await,
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load license {}: {}", license_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+fn reminder_message(license: &ExpiringLicense, email: &str) -> Message {
+    let when = match license.days_left {
+        days if days < 0 => format!("expired on {}", license.expires_on),
+        0 => "expires today".to_string(),
+        1 => "expires tomorrow".to_string(),
+        days => format!("expires in {} days, on {}", days, license.expires_on),
+    };
+
+    Message {
+        channel: Channel::Email,
+        to: email.to_string(),
+        subject: format!("Your {} tattoo license {}", license.issuing_state, when),
+        body: format!(
+            "Your {} tattoo license {} {}.\n\nOnce you've renewed it, update the expiry date \
+             and upload the new license in your Tatteau settings so it stays on your profile.",
+            license.issuing_state, license.license_number, when
+        ),
+    }
+}
+
+/// Sends the expiry reminders that are due, returning how many went out
+pub async fn send_expiry_reminders() -> Result<usize, sqlx::Error> {
+    let within_days = REMINDER_DAYS.iter().copied().max().unwrap_or_default();
+    let expiring =
+        license_repository::get_expiring_licenses(within_days, REMINDER_BATCH_SIZE).await?;
+    let mut sent = 0;
+
+    for license in expiring {
+        let Some(days) = reminder_due(license.days_left, license.reminder_days_sent) else {
+            continue;
+        };
+        let Some(email) = license.email.as_deref().filter(|email| !email.is_empty()) else {
+            continue;
+        };
+        // Claimed before sending so another instance doesn't send it too
+        if !license_repository::mark_reminder_sent(license.id, days).await? {
+            continue;
+        }
+
+        match notify::send(&reminder_message(&license, email)).await {
+            Ok(()) => sent += 1,
+            Err(e) => tracing::error!(
+                license_id = license.id,
+                artist_id = license.artist_id,
+                "Failed to send license expiry reminder: {}",
+                e
+            ),
+        }
+    }
+
+    Ok(sent)
+}
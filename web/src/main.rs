@@ -5,9 +5,10 @@ async fn main() {
     use leptos::logging::log;
     use leptos::prelude::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
+    use tower_http::compression::CompressionLayer;
     use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
     use tracing::Level;
-    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
     use web::app::*;
     use web::server::*;
 
@@ -18,49 +19,222 @@ async fn main() {
         .or_else(|_| dotenvy::dotenv())
         .ok();
 
-    // Initialize tracing
+    // Initialize tracing. The log filter only applies to log output, so
+    // query metrics still see sqlx's debug-level query events
     tracing_subscriber::registry()
         .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,web=debug".into()),
+            tracing_subscriber::fmt::layer().with_filter(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| "info,web=debug".into()),
+            ),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(web::metrics::query_duration_layer())
         .init();
 
     tracing::info!("Tracing initialized");
 
+    web::metrics::install_recorder();
+
+    // Fail fast on missing or malformed settings
+    if let Err(e) = web::config::init() {
+        tracing::error!("Invalid configuration: {}", e);
+        std::process::exit(1);
+    }
+
     // Initialize database pool
     web::db::pool::init_pool()
         .await
         .expect("Failed to initialize database pool");
     log!("Database pool initialized successfully");
 
+    web::db::pool::run_migrations()
+        .await
+        .expect("Failed to apply database migrations");
+
     let conf = get_configuration(None).unwrap();
     let addr = conf.leptos_options.site_addr;
     let leptos_options = conf.leptos_options;
     // Generate the list of routes in your Leptos App
     let routes = generate_route_list(App);
 
+    // Queues the periodic work in `jobs::SCHEDULE`
+    tokio::spawn(web::jobs::run_scheduler());
+
+    // Background jobs queued by server fns
+    tokio::spawn(web::jobs::run_worker());
+
     let app = Router::new()
+        .route(
+            "/api/artist/:id/calendar.ics",
+            axum::routing::get(web::server_calendar::artist_calendar_handler),
+        )
+        .route(
+            "/api/artist/flash",
+            axum::routing::post(web::flash_designs::upload_flash_design).layer(
+                axum::extract::DefaultBodyLimit::max(
+                    // Room for the other form fields
+                    web::flash_designs::MAX_FLASH_IMAGE_BYTES + 64 * 1024,
+                ),
+            ),
+        )
+        .route(
+            "/api/artist/license-document",
+            axum::routing::post(web::licensing::upload_license_document).layer(
+                axum::extract::DefaultBodyLimit::max(
+                    web::licensing::MAX_LICENSE_DOCUMENT_BYTES + 64 * 1024,
+                ),
+            ),
+        )
+        .route(
+            "/api/artist/portfolio",
+            axum::routing::post(web::portfolio_uploads::upload_portfolio_image).layer(
+                axum::extract::DefaultBodyLimit::max(
+                    // Room for the other form fields
+                    web::portfolio_uploads::MAX_PORTFOLIO_IMAGE_BYTES + 64 * 1024,
+                ),
+            ),
+        )
+        .route(
+            "/api/bookings/:id/messages/stream",
+            axum::routing::get(web::message_stream::booking_message_stream),
+        )
+        .route(
+            "/api/bookings/:id/consent.pdf",
+            axum::routing::get(web::server_documents::consent_pdf_handler),
+        )
+        .route(
+            "/api/calendar/:feed",
+            axum::routing::get(web::server_calendar::calendar_feed_handler),
+        )
+        .route(
+            "/api/exports/:id/download",
+            axum::routing::get(web::exports::export_download_handler),
+        )
+        .route(
+            "/api/instagram/:short_code/image/:width",
+            axum::routing::get(web::instagram_images::instagram_image_handler),
+        )
+        .route(
+            "/api/instagram/:short_code/thumbnail",
+            axum::routing::get(web::server_instagram::instagram_thumbnail_handler),
+        )
+        .route(
+            "/api/invoices/:id/pdf",
+            axum::routing::get(web::server_invoices::invoice_pdf_handler),
+        )
+        .route(
+            "/api/licenses/:id/document",
+            axum::routing::get(web::licensing::license_document_handler),
+        )
+        .route(
+            "/api/tattoo-photos/:id/:variant",
+            axum::routing::get(web::tattoo_photo_uploads::tattoo_photo_handler),
+        )
+        .route(
+            "/api/my-tattoos",
+            axum::routing::post(web::tattoo_photo_uploads::upload_tattoo_photo).layer(
+                axum::extract::DefaultBodyLimit::max(
+                    // Room for the other form fields
+                    web::tattoo_photo_uploads::MAX_TATTOO_PHOTO_BYTES + 64 * 1024,
+                ),
+            ),
+        )
+        .route(
+            "/api/status",
+            axum::routing::get(web::server_status::status_handler),
+        )
+        .route(
+            "/api/stripe/webhook",
+            axum::routing::post(web::server::payments::payment_webhook),
+        )
+        .route(
+            "/api/uploads",
+            axum::routing::post(web::uploads::create_upload).options(web::uploads::upload_options),
+        )
+        .route(
+            "/api/uploads/:id",
+            axum::routing::head(web::uploads::upload_status)
+                .patch(web::uploads::append_chunk)
+                .delete(web::uploads::abort_upload)
+                .layer(axum::extract::DefaultBodyLimit::max(
+                    web::uploads::MAX_CHUNK_BYTES,
+                )),
+        )
+        .route(
+            "/embed/booking/:artist_id",
+            axum::routing::get(web::embed::booking_widget).post(web::embed::submit_booking_widget),
+        )
+        .route(
+            "/media/*key",
+            axum::routing::get(web::portfolio_uploads::serve_media),
+        )
+        .route(
+            "/s/:code",
+            axum::routing::get(web::short_links::short_link_handler),
+        )
+        .nest("/api/v1", web::api_v1::router())
         .leptos_routes(&leptos_options, routes, {
             let leptos_options = leptos_options.clone();
             move || shell(leptos_options.clone())
         })
         .fallback(leptos_axum::file_and_error_handler(shell))
         .with_state(leptos_options)
+        .layer(axum::middleware::from_fn(
+            web::rate_limit::limit_public_endpoints,
+        ))
+        // ETags hash the uncompressed body, so compression goes outside them
+        .layer(axum::middleware::from_fn(web::http_cache::conditional_get))
+        .layer(CompressionLayer::new())
+        .layer(axum::middleware::from_fn(web::metrics::track_requests))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
                 .on_response(DefaultOnResponse::new().level(Level::INFO)),
+        )
+        // After the layers, so probes and scrapes aren't traced, counted or
+        // rate limited
+        .route("/healthz", axum::routing::get(web::health::healthz))
+        .route("/readyz", axum::routing::get(web::health::readyz))
+        .route(
+            "/metrics",
+            axum::routing::get(web::metrics::metrics_handler),
         );
 
     // run our app with hyper
     // `axum::Server` is a re-export of `hyper::Server`
     log!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
+    // The peer address keys the per-IP rate limits
+    let (signalled, shutdown_started) = tokio::sync::oneshot::channel::<()>();
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        web::health::shutdown_signal().await;
+        let _ = signalled.send(());
+    });
+    // Connections still open after the grace period, like message streams,
+    // are cut off
+    let grace_period_over = async move {
+        match shutdown_started.await {
+            Ok(()) => tokio::time::sleep(web::config::config().shutdown_grace).await,
+            Err(_) => std::future::pending().await,
+        }
+    };
+
+    tokio::select! {
+        result = std::future::IntoFuture::into_future(server) => {
+            if let Err(e) = result {
+                tracing::error!("Server error: {}", e);
+            }
+        }
+        _ = grace_period_over => {
+            tracing::warn!("Requests still in flight after the grace period, closing anyway");
+        }
+    }
+    web::db::pool::close_pool().await;
+    tracing::info!("Shut down");
 }
 
 #[cfg(not(feature = "ssr"))]
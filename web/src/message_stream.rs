@@ -0,0 +1,68 @@
+//! Server-sent events for booking messages, so an open booking thread shows
+//! new messages without polling:
+//!
+//! - `GET /api/bookings/:id/messages/stream` streams each new message of the
+//!   booking as a JSON `message` event
+//! - a `resync` event means messages may have been missed (slow consumer) and
+//!   the client should refetch the thread
+//!
+//! Messages are fanned out in-process from `send_booking_message`.
+
+use axum::extract::Path;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::stream::Stream;
+use std::convert::Infallible;
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+
+use crate::db::entities::BookingMessage;
+
+/// Messages buffered per subscriber before it is told to resync
+const CHANNEL_CAPACITY: usize = 256;
+
+static CHANNEL: OnceLock<broadcast::Sender<BookingMessage>> = OnceLock::new();
+
+fn channel() -> &'static broadcast::Sender<BookingMessage> {
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Pushes a newly stored message to everyone watching its booking
+pub fn publish(message: BookingMessage) {
+    // An error only means nobody is subscribed
+    let _ = channel().send(message);
+}
+
+/// GET /api/bookings/:id/messages/stream
+pub async fn booking_message_stream(
+    Path(booking_id): Path<i32>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = channel().subscribe();
+
+    let stream = futures::stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(message) if message.booking_request_id == booking_id => {
+                    match Event::default().event("message").json_data(&message) {
+                        Ok(event) => event,
+                        Err(e) => {
+                            tracing::error!(
+                                "Failed to encode booking message {}: {}",
+                                message.id,
+                                e
+                            );
+                            continue;
+                        }
+                    }
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    Event::default().event("resync").data("")
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+            return Some((Ok(event), receiver));
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
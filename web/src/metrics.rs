@@ -0,0 +1,429 @@
+//! `GET /metrics`: the server's metrics in the Prometheus text format, for
+//! scraping or a quick look when an external API is misbehaving.
+//!
+//! Reports:
+//! - every request by method, matched route and status, with its latency.
+//!   Server functions each have their own `/api/...` route, so this is
+//!   their latency too
+//! - how long database queries took, by statement kind and the span they
+//!   ran in (the server function's name for queries a server function
+//!   made), taken from the events sqlx logs for each query
+//! - this server's circuit breakers (`service="web"`, counted since
+//!   startup) and the ones the latest ingestion runs saved
+//!   (`service="ingestion"`, counted over the run that saved them)
+//! - how often each server hook fired and how many of its subscribers
+//!   failed (see `hooks`)
+//! - background jobs by status (see `jobs`); dead ones need looking into
+//! - posts processed, artists added and failed API calls per ingestion
+//!   action, totalled over the runs recorded in `ingestion_runs`
+//!
+//! Set `METRICS_TOKEN` to require it as a bearer token; without it the
+//! endpoint is open.
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use shared_types::circuit_breaker::{BreakerSnapshot, BreakerState};
+use std::fmt::Write;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::db::circuit_breaker_repository;
+use crate::db::ingestion_cost_repository::{self, IngestionThroughput};
+
+/// Histogram buckets for request and query durations, in seconds
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// How often histogram samples recorded since the last scrape are folded in
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The route label for requests no route matched, like static files
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Where sqlx logs each query it ran
+const QUERY_TARGET: &str = "sqlx::query";
+
+static PROMETHEUS: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the recorder request and query metrics go to. Call once at
+/// startup, from inside the runtime.
+pub fn install_recorder() {
+    let installed = PrometheusBuilder::new()
+        .set_buckets(DURATION_BUCKETS)
+        .and_then(|builder| builder.install_recorder());
+    match installed {
+        Ok(handle) => {
+            let upkeep = handle.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    upkeep.run_upkeep();
+                }
+            });
+            let _ = PROMETHEUS.set(handle);
+        }
+        Err(e) => tracing::error!("Metrics: failed to install the Prometheus recorder: {}", e),
+    }
+}
+
+/// Counts and times each request by the route it matched
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let method = request.method().to_string();
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    metrics::histogram!(
+        "tatteau_http_request_duration_seconds",
+        "method" => method.clone(),
+        "route" => route.clone()
+    )
+    .record(started.elapsed().as_secs_f64());
+    metrics::counter!(
+        "tatteau_http_requests_total",
+        "method" => method,
+        "route" => route,
+        "status" => response.status().as_u16().to_string()
+    )
+    .increment(1);
+    response
+}
+
+/// The fields of a sqlx query event this needs
+#[derive(Default)]
+struct QueryEvent {
+    operation: Option<&'static str>,
+    elapsed_secs: Option<f64>,
+}
+
+impl QueryEvent {
+    /// The statement's first keyword, from the summary sqlx logs
+    fn set_operation(&mut self, summary: &str) {
+        let keyword = summary
+            .trim_start_matches(|c: char| c == '"' || c.is_whitespace())
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        self.operation = Some(match keyword.as_str() {
+            "select" => "select",
+            "insert" => "insert",
+            "update" => "update",
+            "delete" => "delete",
+            "with" => "with",
+            _ => "other",
+        });
+    }
+}
+
+impl Visit for QueryEvent {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "summary" {
+            self.set_operation(value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "summary" {
+            self.set_operation(&format!("{:?}", value));
+        }
+    }
+}
+
+/// Records query durations from the events sqlx logs after each query
+struct QueryDurations;
+
+impl<S> Layer<S> for QueryDurations
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if event.metadata().target() != QUERY_TARGET {
+            return;
+        }
+        let mut query = QueryEvent::default();
+        event.record(&mut query);
+        let Some(elapsed_secs) = query.elapsed_secs else {
+            return;
+        };
+        let caller = ctx
+            .event_span(event)
+            .map(|span| span.name())
+            .unwrap_or("none");
+
+        metrics::histogram!(
+            "tatteau_db_query_duration_seconds",
+            "operation" => query.operation.unwrap_or("other"),
+            "caller" => caller
+        )
+        .record(elapsed_secs);
+    }
+}
+
+/// The tracing layer behind the query duration metrics. It sees sqlx's
+/// debug-level query events whatever `RUST_LOG` says, so give the log
+/// output its filter as a per-layer filter rather than a global one. Spans
+/// at info and above stay visible to it for the `caller` label.
+pub fn query_duration_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    QueryDurations.with_filter(
+        Targets::new()
+            .with_target(QUERY_TARGET, Level::DEBUG)
+            .with_default(Level::INFO),
+    )
+}
+
+fn state_value(state: BreakerState) -> u8 {
+    match state {
+        BreakerState::Closed => 0,
+        BreakerState::HalfOpen => 1,
+        BreakerState::Open => 2,
+    }
+}
+
+fn authorized(headers: &HeaderMap) -> bool {
+    let Some(expected) = &crate::config::config().metrics_token else {
+        return true;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        // Compared as signatures so the check takes the same time however
+        // much of the token matches
+        .is_some_and(|token| crate::auth::verify_signature(token, &crate::auth::sign(expected)))
+}
+
+/// One gauge with a line per breaker
+fn write_gauge(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    breakers: &[(&str, &BreakerSnapshot)],
+    value: impl Fn(&BreakerSnapshot) -> f64,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (service, snapshot) in breakers {
+        let _ = writeln!(
+            out,
+            "{}{{service=\"{}\",breaker=\"{}\"}} {}",
+            name,
+            service,
+            snapshot.name,
+            value(snapshot)
+        );
+    }
+}
+
+/// One counter with a line per ingestion action
+fn write_ingestion_counter(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    throughput: &[IngestionThroughput],
+    value: impl Fn(&IngestionThroughput) -> i64,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for row in throughput {
+        let _ = writeln!(out, "{}{{action=\"{}\"}} {}", name, row.action, value(row));
+    }
+}
+
+pub async fn metrics_handler(headers: HeaderMap) -> Response {
+    if !authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let web: Vec<BreakerSnapshot> = crate::circuit_breakers::all()
+        .iter()
+        .map(|breaker| breaker.snapshot())
+        .collect();
+    let ingestion = match circuit_breaker_repository::get_ingestion_breakers().await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Metrics: failed to load ingestion breakers: {}", e);
+            vec![]
+        }
+    };
+
+    let breakers: Vec<(&str, &BreakerSnapshot)> = web
+        .iter()
+        .map(|snapshot| ("web", snapshot))
+        .chain(
+            ingestion
+                .iter()
+                .map(|(snapshot, _)| ("ingestion", snapshot)),
+        )
+        .collect();
+
+    let throughput = match ingestion_cost_repository::get_ingestion_throughput().await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Metrics: failed to load ingestion throughput: {}", e);
+            vec![]
+        }
+    };
+
+    let jobs = match crate::db::job_repository::count_jobs_by_status().await {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Metrics: failed to count background jobs: {}", e);
+            vec![]
+        }
+    };
+
+    let mut out = PROMETHEUS
+        .get()
+        .map(|handle| handle.render())
+        .unwrap_or_default();
+    write_gauge(
+        &mut out,
+        "tatteau_circuit_breaker_state",
+        "Circuit breaker state (0 closed, 1 half-open, 2 open)",
+        &breakers,
+        |s| state_value(s.state) as f64,
+    );
+    write_gauge(
+        &mut out,
+        "tatteau_circuit_breaker_failure_rate",
+        "Failure rate over the breaker's recent calls",
+        &breakers,
+        |s| s.failure_rate,
+    );
+    write_gauge(
+        &mut out,
+        "tatteau_circuit_breaker_calls",
+        "Calls let through the breaker",
+        &breakers,
+        |s| s.calls as f64,
+    );
+    write_gauge(
+        &mut out,
+        "tatteau_circuit_breaker_rejected_calls",
+        "Calls rejected while the breaker was open",
+        &breakers,
+        |s| s.rejected_calls as f64,
+    );
+    write_gauge(
+        &mut out,
+        "tatteau_circuit_breaker_times_opened",
+        "Times the breaker has opened",
+        &breakers,
+        |s| s.times_opened as f64,
+    );
+
+    let _ = writeln!(
+        out,
+        "# HELP tatteau_circuit_breaker_report_age_seconds Seconds since an ingestion run saved the breaker"
+    );
+    let _ = writeln!(
+        out,
+        "# TYPE tatteau_circuit_breaker_report_age_seconds gauge"
+    );
+    for (snapshot, age_secs) in &ingestion {
+        let _ = writeln!(
+            out,
+            "tatteau_circuit_breaker_report_age_seconds{{service=\"ingestion\",breaker=\"{}\"}} {:.0}",
+            snapshot.name, age_secs
+        );
+    }
+
+    let hook_counts = crate::hooks::event_counts();
+    let _ = writeln!(out, "# HELP tatteau_hook_events Server hook events emitted");
+    let _ = writeln!(out, "# TYPE tatteau_hook_events counter");
+    for (event, emitted, _) in hook_counts {
+        let _ = writeln!(
+            out,
+            "tatteau_hook_events{{event=\"{}\"}} {}",
+            event, emitted
+        );
+    }
+    let _ = writeln!(
+        out,
+        "# HELP tatteau_hook_failures Hook subscribers that returned an error"
+    );
+    let _ = writeln!(out, "# TYPE tatteau_hook_failures counter");
+    for (event, _, failed) in hook_counts {
+        let _ = writeln!(
+            out,
+            "tatteau_hook_failures{{event=\"{}\"}} {}",
+            event, failed
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP tatteau_background_jobs Background jobs by status"
+    );
+    let _ = writeln!(out, "# TYPE tatteau_background_jobs gauge");
+    for (status, count) in &jobs {
+        let _ = writeln!(
+            out,
+            "tatteau_background_jobs{{status=\"{}\"}} {}",
+            status, count
+        );
+    }
+
+    write_ingestion_counter(
+        &mut out,
+        "tatteau_ingestion_runs_total",
+        "Ingestion runs recorded",
+        &throughput,
+        |t| t.runs,
+    );
+    write_ingestion_counter(
+        &mut out,
+        "tatteau_ingestion_posts_processed_total",
+        "Posts saved to portfolios or extracted from Reddit by ingestion runs",
+        &throughput,
+        |t| t.posts_processed,
+    );
+    write_ingestion_counter(
+        &mut out,
+        "tatteau_ingestion_artists_added_total",
+        "Artists ingestion runs added",
+        &throughput,
+        |t| t.artists_added,
+    );
+    write_ingestion_counter(
+        &mut out,
+        "tatteau_ingestion_api_errors_total",
+        "External API calls from ingestion runs that failed or timed out",
+        &throughput,
+        |t| t.api_errors,
+    );
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/plain; version=0.0.4"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        out,
+    )
+        .into_response()
+}
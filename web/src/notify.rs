@@ -0,0 +1,63 @@
+//! Outbound email and SMS. Messages are POSTed as JSON to
+//! `NOTIFY_WEBHOOK_URL`, which hands them to whichever provider is set up
+//! there (a Postmark/Twilio relay, a Zapier hook, ...), with
+//! `NOTIFY_WEBHOOK_TOKEN` as a bearer token if set. Without a webhook,
+//! messages are only logged, which is enough for local development.
+
+use serde::{Deserialize, Serialize};
+use shared_types::circuit_breaker::BreakerOpen;
+
+use crate::circuit_breakers::{self, NOTIFY};
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotifyError {
+    #[error("notification request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("notification webhook returned {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error(transparent)]
+    Unavailable(#[from] BreakerOpen),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Email,
+    Sms,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub channel: Channel,
+    pub to: String,
+    /// Unused for SMS
+    pub subject: String,
+    pub body: String,
+}
+
+pub async fn send(message: &Message) -> Result<(), NotifyError> {
+    let config = crate::config::config();
+    let Some(url) = config.notify_webhook_url.as_deref() else {
+        tracing::info!(
+            channel = ?message.channel,
+            to = %message.to,
+            "NOTIFY_WEBHOOK_URL is not set; not sending: {}",
+            message.body
+        );
+        return Ok(());
+    };
+
+    let mut request = reqwest::Client::new().post(url).json(message);
+    if let Some(token) = config.notify_webhook_token.as_deref() {
+        request = request.bearer_auth(token);
+    }
+
+    let response = circuit_breakers::send(&NOTIFY, request).await?;
+    if !response.status().is_success() {
+        return Err(NotifyError::Status {
+            status: response.status().as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Google Places and Geocoding lookups for artists setting up their shop
+//! during onboarding. Uses the same `GOOGLE_PLACES_API_KEY` as ingestion;
+//! places found here are stored like ingested ones, keyed by place id.
+
+use serde_json::{json, Value};
+use shared_types::circuit_breaker::BreakerOpen;
+use shared_types::LocationInfo;
+
+use crate::circuit_breakers::{self, GOOGLE_PLACES};
+
+const SEARCH_URL: &str = "https://places.googleapis.com/v1/places:searchText";
+const DETAILS_URL: &str = "https://places.googleapis.com/v1/places";
+const GEOCODE_URL: &str = "https://maps.googleapis.com/maps/api/geocode/json";
+const PLACE_FIELDS: &str = "id,displayName,formattedAddress,addressComponents,location,\
+    primaryType,websiteUri,businessStatus";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlacesError {
+    #[error("GOOGLE_PLACES_API_KEY is not set")]
+    NotConfigured,
+    #[error("places request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("places API returned {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("geocoding failed: {0}")]
+    Geocode(String),
+    #[error(transparent)]
+    Unavailable(#[from] BreakerOpen),
+}
+
+fn api_key() -> Result<&'static str, PlacesError> {
+    crate::config::config()
+        .google_places_api_key
+        .as_deref()
+        .ok_or(PlacesError::NotConfigured)
+}
+
+async fn json_or_error(response: reqwest::Response) -> Result<Value, PlacesError> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(PlacesError::Status {
+            status: status.as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        });
+    }
+    Ok(response.json().await?)
+}
+
+/// Address component by type, in either API's shape: Places uses
+/// `longText`/`shortText`, Geocoding `long_name`/`short_name`
+fn component(components: &[Value], type_name: &str, short: bool) -> String {
+    components
+        .iter()
+        .find(|c| {
+            c.get("types")
+                .and_then(Value::as_array)
+                .is_some_and(|types| types.iter().any(|t| t == type_name))
+        })
+        .and_then(|c| {
+            let keys = if short {
+                ["shortText", "short_name"]
+            } else {
+                ["longText", "long_name"]
+            };
+            keys.iter()
+                .find_map(|key| c.get(*key).and_then(Value::as_str))
+        })
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn str_field(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
+
+fn place_to_location(place: &Value) -> LocationInfo {
+    let components = place
+        .get("addressComponents")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    LocationInfo {
+        city: component(components, "locality", false),
+        county: component(components, "administrative_area_level_2", false),
+        state: component(components, "administrative_area_level_1", false),
+        country_code: component(components, "country", true),
+        postal_code: component(components, "postal_code", false),
+        is_open: str_field(&place["businessStatus"]) != "CLOSED_PERMANENTLY",
+        address: str_field(&place["formattedAddress"]),
+        _id: str_field(&place["id"]),
+        category: str_field(&place["primaryType"]),
+        name: str_field(&place["displayName"]["text"]),
+        website_uri: str_field(&place["websiteUri"]),
+        lat: place["location"]["latitude"].as_f64().unwrap_or(0.0),
+        long: place["location"]["longitude"].as_f64().unwrap_or(0.0),
+        id: -1,
+        ..Default::default()
+    }
+}
+
+/// Places matching a free-text query like "Black Rose Tattoo Portland"
+pub async fn search_places(query: &str, limit: i32) -> Result<Vec<LocationInfo>, PlacesError> {
+    let field_mask = PLACE_FIELDS
+        .split(',')
+        .map(|field| format!("places.{}", field))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let request = reqwest::Client::new()
+        .post(SEARCH_URL)
+        .header("X-Goog-Api-Key", api_key()?)
+        .header("X-Goog-FieldMask", field_mask)
+        .json(&json!({ "textQuery": query, "pageSize": limit }));
+    let response = circuit_breakers::send(&GOOGLE_PLACES, request).await?;
+    let body = json_or_error(response).await?;
+
+    Ok(body
+        .get("places")
+        .and_then(Value::as_array)
+        .map(|places| places.iter().map(place_to_location).collect())
+        .unwrap_or_default())
+}
+
+/// One place by its id, as returned by [`search_places`]
+pub async fn get_place(place_id: &str) -> Result<LocationInfo, PlacesError> {
+    let request = reqwest::Client::new()
+        .get(format!("{}/{}", DETAILS_URL, urlencoding::encode(place_id)))
+        .header("X-Goog-Api-Key", api_key()?)
+        .header("X-Goog-FieldMask", PLACE_FIELDS);
+    let response = circuit_breakers::send(&GOOGLE_PLACES, request).await?;
+
+    Ok(place_to_location(&json_or_error(response).await?))
+}
+
+/// Geocodes a street address for a shop that isn't on Google. The result
+/// has no place id; the caller assigns one.
+pub async fn geocode_address(name: &str, address: &str) -> Result<LocationInfo, PlacesError> {
+    let key = api_key()?;
+    let request = reqwest::Client::new()
+        .get(GEOCODE_URL)
+        .query(&[("address", address), ("key", key)]);
+    let response = circuit_breakers::send(&GOOGLE_PLACES, request).await?;
+    let body = json_or_error(response).await?;
+
+    match body["status"].as_str() {
+        Some("OK") => {}
+        Some("ZERO_RESULTS") => return Err(PlacesError::Geocode("address not found".to_string())),
+        other => {
+            return Err(PlacesError::Geocode(
+                other.unwrap_or("no status").to_string(),
+            ))
+        }
+    }
+
+    let result = &body["results"][0];
+    let components = result
+        .get("address_components")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    Ok(LocationInfo {
+        city: component(components, "locality", false),
+        county: component(components, "administrative_area_level_2", false),
+        state: component(components, "administrative_area_level_1", false),
+        country_code: component(components, "country", true),
+        postal_code: component(components, "postal_code", false),
+        is_open: true,
+        address: str_field(&result["formatted_address"]),
+        category: "tattoo_shop".to_string(),
+        name: name.to_string(),
+        lat: result["geometry"]["location"]["lat"]
+            .as_f64()
+            .unwrap_or(0.0),
+        long: result["geometry"]["location"]["lng"]
+            .as_f64()
+            .unwrap_or(0.0),
+        id: -1,
+        ..Default::default()
+    })
+}
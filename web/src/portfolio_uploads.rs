@@ -0,0 +1,259 @@
+//! Native portfolio images for artists.
+//!
+//! `POST /api/artist/portfolio` takes a `multipart/form-data` body with a
+//! `file` part and an optional `caption`. Artists authenticate with
+//! `Authorization: Bearer <token>`, or with a `token` part when the settings
+//! page posts a plain HTML form. The image is cleaned up by
+//! [`crate::image_processing`] (re-encoded upright, without EXIF), a JPEG
+//! thumbnail is generated, and both are written to object storage (see
+//! [`crate::storage`]).
+//!
+//! Clients asking for `application/json` get the new image back; form posts
+//! are redirected to the settings page (or, with `?return_to=onboarding`, the
+//! onboarding wizard) with `?portfolio=uploaded` or
+//! `?portfolio_error=<message>`.
+
+use axum::extract::{Multipart, Path, Query};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Json;
+
+use crate::db::uploaded_image_repository::{self, NewUploadedImage};
+use crate::image_processing::{self, ImageError};
+use crate::storage::{private_storage, storage};
+
+/// Largest image accepted
+pub const MAX_PORTFOLIO_IMAGE_BYTES: usize = 25 * 1024 * 1024;
+/// Uploaded images an artist may keep at once
+const MAX_PORTFOLIO_IMAGES: i64 = 200;
+const SETTINGS_PATH: &str = "/artist/dashboard/settings";
+const ONBOARDING_PATH: &str = "/artist/onboarding";
+
+#[derive(serde::Deserialize)]
+pub struct UploadParams {
+    return_to: Option<String>,
+}
+
+pub(crate) struct UploadError(pub(crate) StatusCode, pub(crate) String);
+
+impl UploadError {
+    pub(crate) fn new(status: StatusCode, message: &str) -> Self {
+        UploadError(status, message.to_string())
+    }
+}
+
+impl From<ImageError> for UploadError {
+    fn from(e: ImageError) -> Self {
+        UploadError(e.status(), e.to_string())
+    }
+}
+
+pub(crate) fn wants_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"))
+}
+
+pub(crate) fn extension(content_type: &str) -> &'static str {
+    match content_type {
+        "image/png" => "png",
+        "image/webp" => "webp",
+        _ => "jpg",
+    }
+}
+
+async fn store_upload(
+    headers: &HeaderMap,
+    mut multipart: Multipart,
+) -> Result<crate::db::entities::ArtistUploadedImage, UploadError> {
+    let mut file: Option<Vec<u8>> = None;
+    let mut caption: Option<String> = None;
+    let mut form_token: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| UploadError::new(StatusCode::BAD_REQUEST, "Malformed upload"))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                let bytes = field.bytes().await.map_err(|_| {
+                    UploadError::new(StatusCode::PAYLOAD_TOO_LARGE, "Image too large")
+                })?;
+                file = Some(bytes.to_vec());
+            }
+            "caption" => caption = field.text().await.ok(),
+            "token" => form_token = field.text().await.ok(),
+            _ => {}
+        }
+    }
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or(form_token)
+        .ok_or_else(|| UploadError::new(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+    let artist_id = crate::server_invoices::artist_id_from_token(&token)
+        .await
+        .map_err(|_| UploadError::new(StatusCode::FORBIDDEN, "Artist access required"))?
+        as i64;
+
+    let file = file
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| UploadError::new(StatusCode::BAD_REQUEST, "No image selected"))?;
+    if file.len() > MAX_PORTFOLIO_IMAGE_BYTES {
+        return Err(UploadError::new(StatusCode::PAYLOAD_TOO_LARGE, "Image too large"));
+    }
+
+    let existing = uploaded_image_repository::count_uploaded_images(artist_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to count portfolio images: {}", e);
+            UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed")
+        })?;
+    if existing >= MAX_PORTFOLIO_IMAGES {
+        return Err(UploadError(
+            StatusCode::CONFLICT,
+            format!("Portfolios are limited to {} images", MAX_PORTFOLIO_IMAGES),
+        ));
+    }
+
+    // Decoding and re-encoding are CPU-bound
+    let processed = tokio::task::spawn_blocking(move || image_processing::process(&file))
+        .await
+        .map_err(|_| UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed"))??;
+    let content_type = processed.content_type;
+
+    let id = uuid::Uuid::new_v4();
+    let storage_key = format!("portfolio/{}/{}.{}", artist_id, id, extension(content_type));
+    let thumbnail_key = format!("portfolio/{}/{}_thumb.jpg", artist_id, id);
+
+    let stored = async {
+        storage()
+            .put(&storage_key, processed.bytes, content_type)
+            .await?;
+        storage()
+            .put(&thumbnail_key, processed.thumbnail, "image/jpeg")
+            .await
+    }
+    .await;
+    if let Err(e) = stored {
+        tracing::error!("Failed to store portfolio image: {}", e);
+        let _ = storage().delete(&storage_key).await;
+        return Err(UploadError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store image",
+        ));
+    }
+
+    let new_image = NewUploadedImage {
+        artist_id,
+        storage_key,
+        thumbnail_key,
+        content_type: content_type.to_string(),
+        width: processed.width as i32,
+        height: processed.height as i32,
+        caption: caption
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty()),
+    };
+
+    let saved = async {
+        let image_id = uploaded_image_repository::insert_uploaded_image(&new_image).await?;
+        uploaded_image_repository::get_uploaded_image(image_id).await
+    }
+    .await;
+
+    match saved {
+        Ok(Some(image)) => Ok(image),
+        other => {
+            if let Err(e) = other {
+                tracing::error!("Failed to record portfolio image: {}", e);
+            }
+            let _ = storage().delete(&new_image.storage_key).await;
+            let _ = storage().delete(&new_image.thumbnail_key).await;
+            Err(UploadError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to save image",
+            ))
+        }
+    }
+}
+
+/// POST /api/artist/portfolio
+pub async fn upload_portfolio_image(
+    Query(params): Query<UploadParams>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Response {
+    let json = wants_json(&headers);
+    // Only known pages, so this can't be used as an open redirect
+    let return_path = match params.return_to.as_deref() {
+        Some("onboarding") => ONBOARDING_PATH,
+        _ => SETTINGS_PATH,
+    };
+
+    match store_upload(&headers, multipart).await {
+        Ok(image) if json => (StatusCode::CREATED, Json(image)).into_response(),
+        Ok(_) => Redirect::to(&format!("{}?portfolio=uploaded", return_path)).into_response(),
+        Err(UploadError(status, message)) if json => (status, message).into_response(),
+        Err(UploadError(_, message)) => Redirect::to(&format!(
+            "{}?portfolio_error={}",
+            return_path,
+            urlencoding::encode(&message)
+        ))
+        .into_response(),
+    }
+}
+
+/// Content type of a stored image, from the extension [`extension`] gave it
+fn media_content_type(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+/// GET /media/*key, for the local storage backend
+pub async fn serve_media(Path(key): Path<String>) -> Response {
+    let Some(path) = storage().local_path(&key) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, media_content_type(&key)),
+                // Keys are never reused, so the content never changes
+                (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Responds with an image from private storage, for handlers that have
+/// already checked the caller may see it
+pub(crate) async fn serve_private_media(key: &str) -> Response {
+    match private_storage().get(key).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, media_content_type(key)),
+                (header::CACHE_CONTROL, "private, no-store"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::warn!("Failed to read private media {}: {}", key, e);
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
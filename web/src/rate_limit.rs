@@ -0,0 +1,333 @@
+//! Rate limits on the unauthenticated endpoints anyone can call: signing
+//! in, signing up, sending a booking request and asking an artist a
+//! question.
+//!
+//! [`limit_public_endpoints`] counts requests per client IP in memory, so
+//! each instance enforces its own limit. Counters per email address live in
+//! Postgres, shared by every instance: booking requests sent from one
+//! address, questions asked from one address, and failed sign-ins, which lock the address out for a while
+//! after too many. Either way the client gets a 429 with `Retry-After`,
+//! carrying [`ApiError::RateLimited`] like any other server fn error.
+//!
+//! Settings, each a count per window: `RATE_LIMIT_LOGIN_PER_MINUTE`
+//! (default 10), `RATE_LIMIT_SIGNUP_PER_HOUR` (default 5),
+//! `RATE_LIMIT_BOOKING_PER_HOUR` (default 10),
+//! `RATE_LIMIT_QUESTION_PER_HOUR` (default 5) per IP;
+//! `RATE_LIMIT_BOOKING_PER_EMAIL_PER_DAY` (default 5),
+//! `RATE_LIMIT_QUESTION_PER_EMAIL_PER_DAY` (default 3); and
+//! `LOGIN_LOCKOUT_ATTEMPTS` (default 5) failed sign-ins lock an email out
+//! until `LOGIN_LOCKOUT_MINUTES` (default 15) pass without another. Behind a
+//! proxy, set `TRUST_PROXY_HEADERS=true` to key on `X-Forwarded-For`.
+
+use axum::extract::{ConnectInfo, Request};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use leptos::server_fn::error::{ServerFnError, ServerFnErrorSerde};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::api_error::ApiError;
+use crate::db::rate_limit_repository;
+
+/// Counter scopes in `rate_limit_counters`
+const BOOKING_EMAIL_SCOPE: &str = "booking_email";
+const QUESTION_EMAIL_SCOPE: &str = "question_email";
+const LOGIN_FAILURE_SCOPE: &str = "login_failure";
+/// IP windows kept before ended ones are swept out
+const MAX_TRACKED_WINDOWS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limit {
+    pub max: u32,
+    pub window: Duration,
+}
+
+/// Built from the environment by [`crate::config`]
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    pub login_per_ip: Limit,
+    pub signup_per_ip: Limit,
+    pub booking_per_ip: Limit,
+    pub booking_per_email: Limit,
+    pub question_per_ip: Limit,
+    pub question_per_email: Limit,
+    pub lockout_attempts: u32,
+    pub lockout: Duration,
+    pub trust_proxy_headers: bool,
+}
+
+fn config() -> &'static RateLimitConfig {
+    &crate::config::config().rate_limits
+}
+
+/// The endpoint behind a server fn path and its per-IP limit, if it has one
+fn ip_limit(path: &str) -> Option<(&'static str, Limit)> {
+    let config = config();
+    match path {
+        "/api/login_user" => Some(("login", config.login_per_ip)),
+        "/api/signup_user" => Some(("signup", config.signup_per_ip)),
+        "/api/submit_booking_request" => Some(("booking", config.booking_per_ip)),
+        "/api/ask_artist_question" => Some(("question", config.question_per_ip)),
+        _ => None,
+    }
+}
+
+/// "a minute", "12 minutes", "2 hours"
+fn wait_label(secs: u64) -> String {
+    match secs {
+        0..=60 => "a minute".to_string(),
+        61..=5400 => format!("{} minutes", secs.div_ceil(60)),
+        _ => format!("{} hours", secs.div_ceil(3600)),
+    }
+}
+
+struct Window {
+    ends: Instant,
+    hits: u32,
+}
+
+type WindowKey = (&'static str, IpAddr);
+
+static WINDOWS: OnceLock<Mutex<HashMap<WindowKey, Window>>> = OnceLock::new();
+
+/// Counts a request from `ip`. Past the limit, the seconds until the window
+/// ends.
+fn hit_ip(endpoint: &'static str, ip: IpAddr, limit: Limit) -> Result<(), u64> {
+    let windows = WINDOWS.get_or_init(Default::default);
+    let mut windows = windows.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+
+    if windows.len() >= MAX_TRACKED_WINDOWS {
+        windows.retain(|_, window| window.ends > now);
+    }
+
+    let window = windows.entry((endpoint, ip)).or_insert(Window {
+        ends: now + limit.window,
+        hits: 0,
+    });
+    if window.ends <= now {
+        window.ends = now + limit.window;
+        window.hits = 0;
+    }
+    window.hits += 1;
+
+    if window.hits > limit.max {
+        Err(window.ends.duration_since(now).as_secs().max(1))
+    } else {
+        Ok(())
+    }
+}
+
+/// The client's address: the first `X-Forwarded-For` hop when the proxy in
+/// front is trusted, else the peer
+pub(crate) fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+    if config().trust_proxy_headers {
+        let forwarded = headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .and_then(|v| v.trim().parse().ok());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+    peer
+}
+
+/// A 429 the server fn client reads back as `ApiError::RateLimited`
+fn too_many_requests(retry_after_secs: u64, message: String) -> Response {
+    let body = ServerFnError::WrappedServerError(ApiError::rate_limited(retry_after_secs, message))
+        .ser()
+        .unwrap_or_default();
+
+    let mut response = (StatusCode::TOO_MANY_REQUESTS, body).into_response();
+    if let Ok(value) = HeaderValue::from_str(&retry_after_secs.to_string()) {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
+}
+
+/// Middleware applying the per-IP limits
+pub async fn limit_public_endpoints(request: Request, next: Next) -> Response {
+    let Some((endpoint, limit)) = ip_limit(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let Some(ip) = client_ip(request.headers(), peer) else {
+        return next.run(request).await;
+    };
+
+    match hit_ip(endpoint, ip, limit) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after_secs) => {
+            tracing::warn!(%ip, endpoint, "Rate limit exceeded");
+            too_many_requests(
+                retry_after_secs,
+                format!(
+                    "Too many requests. Try again in {}.",
+                    wait_label(retry_after_secs)
+                ),
+            )
+        }
+    }
+}
+
+/// Counts a booking request from `ip` sent outside the server fn, like from
+/// the booking widget's form, under the same per-IP limit
+pub(crate) fn check_booking_ip(ip: IpAddr) -> Result<(), ApiError> {
+    hit_ip("booking", ip, config().booking_per_ip).map_err(|retry_after_secs| {
+        ApiError::rate_limited(
+            retry_after_secs,
+            format!(
+                "Too many requests. Try again in {}.",
+                wait_label(retry_after_secs)
+            ),
+        )
+    })
+}
+
+/// Sets the server fn's response to a 429 and returns the error to send
+fn rate_limited(retry_after_secs: u64, message: String) -> ApiError {
+    if let Some(response) = leptos::prelude::use_context::<leptos_axum::ResponseOptions>() {
+        response.set_status(StatusCode::TOO_MANY_REQUESTS);
+        if let Ok(value) = HeaderValue::from_str(&retry_after_secs.to_string()) {
+            response.insert_header(header::RETRY_AFTER, value);
+        }
+    }
+    ApiError::rate_limited(retry_after_secs, message)
+}
+
+fn email_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// Counts a booking request sent from `email`, refusing it past the daily
+/// limit
+pub async fn check_booking_email(email: &str) -> Result<(), ApiError> {
+    let limit = config().booking_per_email;
+    let counter = rate_limit_repository::hit(
+        BOOKING_EMAIL_SCOPE,
+        &email_key(email),
+        limit.window.as_secs() as i64,
+        false,
+    )
+    .await?;
+
+    if counter.hits as u32 > limit.max {
+        let retry_after = counter.resets_in_secs.max(1) as u64;
+        return Err(rate_limited(
+            retry_after,
+            format!(
+                "You've sent a lot of booking requests today. Try again in {}.",
+                wait_label(retry_after)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Counts a question asked from `email`, refusing it past the daily limit
+pub async fn check_question_email(email: &str) -> Result<(), ApiError> {
+    let limit = config().question_per_email;
+    let counter = rate_limit_repository::hit(
+        QUESTION_EMAIL_SCOPE,
+        &email_key(email),
+        limit.window.as_secs() as i64,
+        false,
+    )
+    .await?;
+
+    if counter.hits as u32 > limit.max {
+        let retry_after = counter.resets_in_secs.max(1) as u64;
+        return Err(rate_limited(
+            retry_after,
+            format!(
+                "You've asked a lot of questions today. Try again in {}.",
+                wait_label(retry_after)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn lockout_error(retry_after_secs: u64) -> ApiError {
+    rate_limited(
+        retry_after_secs,
+        format!(
+            "Too many failed sign-in attempts. Try again in {}.",
+            wait_label(retry_after_secs)
+        ),
+    )
+}
+
+/// Refuses a sign-in while `email` is locked out
+pub async fn check_login_lockout(email: &str) -> Result<(), ApiError> {
+    let config = config();
+    let counter = rate_limit_repository::get_counter(
+        LOGIN_FAILURE_SCOPE,
+        &email_key(email),
+        config.lockout.as_secs() as i64,
+    )
+    .await?;
+
+    match counter {
+        Some(counter) if counter.hits as u32 >= config.lockout_attempts => {
+            Err(lockout_error(counter.resets_in_secs.max(1) as u64))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Counts a failed sign-in for `email`. The error to return: the lockout
+/// once this one reaches the limit, else `invalid`.
+pub async fn record_failed_login(email: &str, invalid: ApiError) -> ApiError {
+    let config = config();
+    let counter = match rate_limit_repository::hit(
+        LOGIN_FAILURE_SCOPE,
+        &email_key(email),
+        config.lockout.as_secs() as i64,
+        true,
+    )
+    .await
+    {
+        Ok(counter) => counter,
+        Err(e) => {
+            tracing::error!("Failed to record failed sign-in: {}", e);
+            return invalid;
+        }
+    };
+
+    if counter.hits as u32 >= config.lockout_attempts {
+        tracing::warn!("Sign-ins locked out after {} failures", counter.hits);
+        lockout_error(counter.resets_in_secs.max(1) as u64)
+    } else {
+        invalid
+    }
+}
+
+/// Forgets failed sign-ins after a successful one
+pub async fn clear_failed_logins(email: &str) {
+    if let Err(e) =
+        rate_limit_repository::clear_counter(LOGIN_FAILURE_SCOPE, &email_key(email)).await
+    {
+        tracing::error!("Failed to clear failed sign-ins: {}", e);
+    }
+}
+
+/// Deletes per-email counters whose windows have ended, returning how many
+pub async fn delete_expired_counters() -> Result<u64, sqlx::Error> {
+    let config = config();
+    let longest = config
+        .booking_per_email
+        .window
+        .max(config.question_per_email.window)
+        .max(config.lockout);
+    rate_limit_repository::delete_expired_counters(longest.as_secs() as i64).await
+}
@@ -0,0 +1,186 @@
+//! Reminders before appointments. While a booking is confirmed, a
+//! [`Job::SendBookingReminder`] is queued for each of the reminder offsets,
+//! which remind the client by email, by SMS when they gave a phone number,
+//! and in the booking's thread. [`schedule_reminders`] runs after every
+//! change to a booking's status or time: it drops the reminders still
+//! queued and, if the booking is confirmed, queues them again for its
+//! current time, so a cancelled booking gets none and a moved one gets them
+//! for the new time.
+//!
+//! The offsets are `BOOKING_REMINDER_HOURS`, hours before the appointment
+//! separated by commas (default `72,24,2`); set it to `off` for no
+//! reminders. Appointment times are server time, like the rest of the
+//! booking dates.
+
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use shared_types::datetime::{Date, Time};
+use shared_types::BookingStatus;
+
+use crate::db::entities::BookingEventKind;
+use crate::db::reminder_repository::{self, ReminderBooking};
+use crate::jobs::{self, Job, JobError};
+use crate::notify::{Channel, Message};
+
+/// Kind of reminder jobs in the queue
+const REMINDER_JOB_KIND: &str = "send_booking_reminder";
+const APPOINTMENT_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReminderError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Jobs(#[from] JobError),
+}
+
+fn key_prefix(booking_id: i32) -> String {
+    format!("booking:{}:", booking_id)
+}
+
+/// Dedupe key of a booking's reminder, so each offset is queued once
+pub(crate) fn reminder_key(booking_id: i32, hours_before: i32) -> String {
+    format!("{}{}", key_prefix(booking_id), hours_before)
+}
+
+fn appointment_time(booking: &ReminderBooking) -> Option<NaiveDateTime> {
+    Some(
+        Date::parse(&booking.requested_date)?
+            .naive()
+            .and_time(Time::parse_lenient(&booking.requested_start_time)?.naive()),
+    )
+}
+
+/// "tomorrow", "in 3 days", "in 2 hours"
+fn lead_time(hours: i32) -> String {
+    match hours {
+        24 => "tomorrow".to_string(),
+        hours if hours % 24 == 0 => format!("in {} days", hours / 24),
+        1 => "in an hour".to_string(),
+        hours => format!("in {} hours", hours),
+    }
+}
+
+/// Drops the booking's queued reminders and, while it's confirmed, queues
+/// them for its current time. Failures are logged, since the change to the
+/// booking has been made either way.
+pub async fn schedule_reminders(booking_id: i32) {
+    match queue_reminders(booking_id).await {
+        Ok(0) => {}
+        Ok(queued) => tracing::debug!(booking_id, queued, "Queued booking reminders"),
+        Err(e) => tracing::error!(booking_id, "Failed to schedule booking reminders: {}", e),
+    }
+}
+
+async fn queue_reminders(booking_id: i32) -> Result<usize, ReminderError> {
+    jobs::cancel_queued(REMINDER_JOB_KIND, &key_prefix(booking_id)).await?;
+
+    let Some(booking) = reminder_repository::get_reminder_booking(booking_id).await? else {
+        return Ok(0);
+    };
+    if booking.status != BookingStatus::Confirmed.as_str() {
+        return Ok(0);
+    }
+    let Some(appointment) = appointment_time(&booking) else {
+        return Ok(0);
+    };
+
+    let now = Local::now();
+    let mut queued = 0;
+    for &hours_before in &crate::config::config().booking_reminder_hours {
+        let Some(run_at) = Local
+            .from_local_datetime(&(appointment - chrono::Duration::hours(hours_before.into())))
+            .earliest()
+            .filter(|run_at| *run_at > now)
+        else {
+            continue;
+        };
+
+        let job = Job::SendBookingReminder {
+            booking_id,
+            hours_before,
+            appointment: appointment.format(APPOINTMENT_FORMAT).to_string(),
+        };
+        if jobs::enqueue_at(job, run_at.with_timezone(&Utc)).await? {
+            queued += 1;
+        }
+    }
+    Ok(queued)
+}
+
+/// Sends a queued reminder, unless the booking has been moved or is no
+/// longer confirmed since it was queued
+pub async fn send_reminder(
+    booking_id: i32,
+    hours_before: i32,
+    appointment: &str,
+) -> Result<(), ReminderError> {
+    use crate::utils::timezone::{convert_to_12_hour_format, format_date_for_booking};
+
+    let Some(booking) = reminder_repository::get_reminder_booking(booking_id).await? else {
+        return Ok(());
+    };
+    let current =
+        appointment_time(&booking).map(|time| time.format(APPOINTMENT_FORMAT).to_string());
+    if booking.status != BookingStatus::Confirmed.as_str()
+        || current.as_deref() != Some(appointment)
+    {
+        return Ok(());
+    }
+
+    let artist = booking.artist_name.as_deref().unwrap_or("your artist");
+    let lead = lead_time(hours_before);
+    let when = format!(
+        "{} at {}",
+        format_date_for_booking(&booking.requested_date),
+        convert_to_12_hour_format(&booking.requested_start_time)
+    );
+    let link = crate::server_reschedule::response_link(booking_id);
+
+    let mut messages = vec![Message {
+        channel: Channel::Email,
+        to: booking.client_email.clone(),
+        subject: format!("Reminder: your tattoo with {} is {}", artist, lead),
+        body: format!(
+            "Hi {},\n\nThis is a reminder that your tattoo appointment with {} is {}, on {}.\n\n\
+             If you can't make it, you can suggest another time or cancel here: {}",
+            booking.client_name, artist, lead, when, link
+        ),
+    }];
+    if let Some(phone) = booking
+        .client_phone
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+    {
+        messages.push(Message {
+            channel: Channel::Sms,
+            to: phone.to_string(),
+            subject: String::new(),
+            body: format!(
+                "Reminder: your tattoo with {} is {}, {}. Can't make it? {}",
+                artist, lead, when, link
+            ),
+        });
+    }
+    for message in messages
+        .into_iter()
+        .filter(|message| !message.to.is_empty())
+    {
+        jobs::enqueue(Job::SendNotification(message)).await?;
+    }
+
+    let message = format!("Reminder: this appointment is {}, on {}.", lead, when);
+    let sent = reminder_repository::insert_reminder_message(booking_id, &message).await?;
+    crate::server::record_booking_event(
+        booking_id,
+        "system",
+        BookingEventKind::Message {
+            sender_type: sent.sender_type.clone(),
+            message: sent.message.clone(),
+        },
+    )
+    .await;
+    crate::message_stream::publish(sent);
+
+    tracing::info!(booking_id, hours_before, "Sent booking reminder");
+    Ok(())
+}
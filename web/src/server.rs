@@ -1,33 +1,38 @@
 use leptos::prelude::*;
 use leptos::server;
+use leptos::server_fn::codec::GetUrl;
+use shared_types::datetime::{Date, Time};
 use shared_types::LocationInfo;
 use shared_types::MapBounds;
+use shared_types::{BookingStatus, CompactImage, LocationPin, RadiusSearch, StyleFilter};
 
 #[cfg(feature = "ssr")]
 use tracing::instrument;
 
+pub mod payments;
+
+use crate::api_error::ApiError;
 use crate::db::entities::{
     Artist, ArtistImage, ArtistQuestionnaire, ArtistSubscription, AvailabilitySlot,
-    AvailabilityUpdate, BookingMessage, BookingQuestionnaireResponse, BookingRequest, CityCoords,
-    ClientQuestionnaireForm, ClientQuestionnaireSubmission, CreateErrorLog, ErrorLog, Location,
-    QuestionnaireQuestion, RecurringRule, Style, SubscriptionTier,
+    AvailabilityUpdate, BookingEvent, BookingMessage, BookingQuestionnaireResponse, BookingRequest,
+    BookingStatusChange, CityCoords, ClientQuestionnaireForm, ClientQuestionnaireSubmission,
+    CreateErrorLog, CreateRecurringRule, ErrorLog, Location, ProfileQuestion,
+    QuestionnaireQuestion, RecurringRule, Style, SubscriptionTier, UnreadCount,
+    UpdateRecurringRule, WeeklyBookings,
 };
 use crate::db::search_repository::SearchResult;
+use crate::server_instagram::InstagramEmbedContent;
+use crate::utils::matching::MatchFactor;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "ssr")]
-use chrono::{Datelike, NaiveDateTime, Utc};
+use chrono::{NaiveDateTime, Utc};
 
-#[derive(Deserialize)]
-struct InstagramOEmbedResponse {
-    html: String,
-    width: Option<u32>,
-    height: Option<u32>,
-    #[serde(rename = "type")]
-    embed_type: String,
-    version: String,
-    provider_name: String,
-}
+#[cfg(feature = "ssr")]
+use crate::db::entities::BookingEventKind;
+
+#[cfg(feature = "ssr")]
+use crate::server_team::{authorize_artist, authorize_artist_id, authorize_booking, TeamPermission};
 
 #[cfg(feature = "ssr")]
 use crate::db::repository::{
@@ -44,36 +49,37 @@ use crate::db::repository::{
 // Helper function to extract user_id from JWT token
 #[cfg(feature = "ssr")]
 fn extract_user_id_from_token(token: &str) -> Option<i64> {
-    use jsonwebtoken::{decode, DecodingKey, Validation};
+    crate::auth::decode_access_token(token).map(|claims| claims.user_id)
+}
 
-    #[derive(Debug, Serialize, Deserialize)]
-    struct Claims {
-        sub: String,
-        exp: usize,
-        user_type: String,
-        user_id: i64,
+/// Adds to a booking's timeline. Failures are logged rather than returned,
+/// since the change the event describes has already been made.
+#[cfg(feature = "ssr")]
+pub(crate) async fn record_booking_event(booking_id: i32, actor: &str, kind: BookingEventKind) {
+    if let Err(e) =
+        crate::db::booking_event_repository::record_event(booking_id, actor, &kind).await
+    {
+        tracing::warn!(
+            booking_id,
+            "Failed to record {} booking event: {}",
+            kind.event_type(),
+            e
+        );
     }
-
-    let secret = "tatteau-jwt-secret-key-change-in-production";
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
-    )
-    .ok()?;
-
-    Some(token_data.claims.user_id)
 }
 
+/// Locations inside `bounds`, or within `radius` of a point (nearest first)
+/// when one is given.
 #[cfg_attr(feature = "ssr", instrument(skip(bounds), err, level = "info"))]
-#[server]
+#[server(prefix = "/api", endpoint = "locations", input = GetUrl)]
 #[cfg_attr(feature = "ssr", instrument(skip(bounds), err, level = "info"))]
 pub async fn fetch_locations(
     state: String,
     city: String,
     bounds: MapBounds,
+    radius: Option<RadiusSearch>,
 ) -> Result<Vec<LocationInfo>, ServerFnError> {
-    match query_locations(state, city, bounds).await {
+    match query_locations(state, city, bounds, radius).await {
         Ok(locations) => Ok(locations),
         Err(e) => Err(ServerFnError::new(format!("Database error: {}", e))),
     }
@@ -154,11 +160,17 @@ fn get_geographic_center(cities: &[CityCoords]) -> Option<CityCoords> {
     })
 }
 
+/// Answered questions shown on an artist's profile
+#[cfg(feature = "ssr")]
+const PROFILE_QUESTIONS_LIMIT: i64 = 20;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct ArtistData {
     pub artist: Artist,
     pub location: Location,
     pub styles: Vec<Style>,
+    /// Answered questions from the artist's public Q&A, latest first
+    pub questions: Vec<ProfileQuestion>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
@@ -166,6 +178,9 @@ pub struct ShopData {
     pub location: Location,
     pub artists: Vec<Artist>,
     pub all_styles: Vec<Style>,
+    /// Range quoted across the shop's artists, from their pricing settings
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
 }
 
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
@@ -184,10 +199,18 @@ pub async fn fetch_artist_data(artist_id: i32) -> Result<ArtistData, ServerFnErr
         .await
         .map_err(|e| ServerFnError::new(format!("Failed to fetch styles: {}", e)))?;
 
+    let questions = crate::db::artist_question_repository::get_profile_questions(
+        artist_id,
+        PROFILE_QUESTIONS_LIMIT,
+    )
+    .await
+    .map_err(|e| ServerFnError::new(format!("Failed to fetch questions: {}", e)))?;
+
     Ok(ArtistData {
         artist,
         location,
         styles,
+        questions,
     })
 }
 
@@ -214,10 +237,19 @@ pub async fn fetch_shop_data(
             .await
             .map_err(|e| ServerFnError::new(format!("Failed to fetch styles: {}", e)))?;
 
+        let (min_price, max_price) =
+            crate::db::pricing_repository::get_location_price_ranges(&[location_id as i64])
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to fetch pricing: {}", e)))?
+                .remove(&(location_id as i64))
+                .unwrap_or((None, None));
+
         Ok(ShopData {
             location,
             artists,
             all_styles,
+            min_price,
+            max_price,
         })
     }
     #[cfg(not(feature = "ssr"))]
@@ -226,31 +258,35 @@ pub async fn fetch_shop_data(
             location: Location::default(),
             artists: vec![],
             all_styles: vec![],
+            min_price: None,
+            max_price: None,
         })
     }
 }
 
+/// Shop gallery page. `style_filter` must-have styles combine per its mode
+/// (OR by default); nice-to-have styles sort matching images first.
 #[cfg_attr(
     feature = "ssr",
-    instrument(skip(token, style_ids), err, level = "info")
+    instrument(skip(token, style_filter), err, level = "info")
 )]
 #[server]
 #[cfg_attr(
     feature = "ssr",
-    instrument(skip(style_ids, token), err, level = "info")
+    instrument(skip(style_filter, token), err, level = "info")
 )]
 pub async fn fetch_shop_images_paginated(
     location_id: i32,
-    style_ids: Option<Vec<i32>>,
+    style_filter: Option<StyleFilter<i32>>,
     page: i32,
     per_page: i32,
     token: Option<String>,
 ) -> Result<(Vec<(ArtistImage, Vec<Style>, Artist, bool)>, i32), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use crate::db::repository::get_shop_images_paginated;
+        use crate::db::repository::get_shop_images_page;
         let user_id = token.as_deref().and_then(extract_user_id_from_token);
-        match get_shop_images_paginated(location_id, style_ids, page, per_page, user_id).await {
+        match get_shop_images_page(location_id, style_filter, page, per_page, user_id).await {
             Ok(result) => Ok(result),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to fetch paginated shop images: {}",
@@ -264,6 +300,111 @@ pub async fn fetch_shop_images_paginated(
     }
 }
 
+/// Compact variant of `fetch_shop_images_paginated` for mobile galleries.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(style_filter, token), err, level = "info")
+)]
+pub async fn fetch_shop_images_compact(
+    location_id: i32,
+    style_filter: Option<StyleFilter<i32>>,
+    page: i32,
+    per_page: i32,
+    token: Option<String>,
+) -> Result<(Vec<CompactImage>, i32), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::repository::get_shop_images_page;
+        let user_id = token.as_deref().and_then(extract_user_id_from_token);
+        let (images, total) =
+            get_shop_images_page(location_id, style_filter, page, per_page, user_id)
+                .await
+                .map_err(|e| {
+                    ServerFnError::new(format!("Failed to fetch paginated shop images: {}", e))
+                })?;
+
+        let compact: Vec<CompactImage> = images
+            .iter()
+            .map(|(image, styles, artist, is_favorited)| CompactImage {
+                id: image.id,
+                short_code: image.short_code.clone(),
+                artist_id: artist.id,
+                artist_name: artist.name.clone(),
+                style_ids: styles.iter().map(|style| style.id).collect(),
+                is_favorited: *is_favorited,
+            })
+            .collect();
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            tracing::debug!(
+                full_bytes = shared_types::payload_size(&images),
+                compact_bytes = shared_types::payload_size(&compact),
+                count = compact.len(),
+                "shop images payload"
+            );
+        }
+        Ok((compact, total))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok((Vec::new(), 0))
+    }
+}
+
+/// Most recommendations shown under an image
+#[cfg(feature = "ssr")]
+const MAX_SIMILAR_IMAGES: i64 = 24;
+
+/// A portfolio image with its artist and style tags, for the image page;
+/// `None` when it doesn't exist or was removed
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_image_detail(
+    image_id: i32,
+    token: Option<String>,
+) -> Result<Option<(ArtistImage, Vec<Style>, Artist, bool)>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::similar_image_repository;
+        let user_id = token.as_deref().and_then(extract_user_id_from_token);
+        similar_image_repository::get_image(image_id, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to fetch image: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// "More like this": work sharing the image's style tags or styles often
+/// seen alongside them, closest first, favouring the same city and a mix
+/// of artists
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_similar_images(
+    image_id: i32,
+    limit: i64,
+    token: Option<String>,
+) -> Result<Vec<(ArtistImage, Vec<Style>, Artist, bool)>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::similar_image_repository;
+        let user_id = token.as_deref().and_then(extract_user_id_from_token);
+        similar_image_repository::get_similar_images(
+            image_id,
+            user_id,
+            limit.clamp(1, MAX_SIMILAR_IMAGES),
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to fetch similar images: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
 #[cfg_attr(
     feature = "ssr",
     instrument(skip(token, style_ids), err, level = "info")
@@ -276,6 +417,7 @@ pub async fn fetch_shop_images_paginated(
 pub async fn fetch_artist_images_paginated(
     artist_id: i32,
     style_ids: Option<Vec<i32>>,
+    album_id: Option<i64>,
     page: i32,
     per_page: i32,
     token: Option<String>,
@@ -284,7 +426,9 @@ pub async fn fetch_artist_images_paginated(
     {
         use crate::db::repository::get_artist_images_paginated;
         let user_id = token.as_deref().and_then(extract_user_id_from_token);
-        match get_artist_images_paginated(artist_id, style_ids, page, per_page, user_id).await {
+        match get_artist_images_paginated(artist_id, style_ids, album_id, page, per_page, user_id)
+            .await
+        {
             Ok(result) => Ok(result),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to fetch paginated artist images: {}",
@@ -294,7 +438,7 @@ pub async fn fetch_artist_images_paginated(
     }
     #[cfg(not(feature = "ssr"))]
     {
-        let _ = (artist_id, style_ids, page, per_page, token);
+        let _ = (artist_id, style_ids, album_id, page, per_page, token);
         Ok((Vec::new(), 0))
     }
 }
@@ -405,6 +549,10 @@ pub struct EnhancedLocationInfo {
     pub artists: Vec<ArtistThumbnail>,
     pub min_price: Option<f64>,
     pub max_price: Option<f64>,
+    /// Set when an admin pinning rule placed this shop, so curated results
+    /// can be told apart from organically ranked ones.
+    #[serde(default)]
+    pub pinned_position: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -413,6 +561,13 @@ pub struct ArtistThumbnail {
     pub artist_name: String,
     pub image_url: Option<String>,
     pub primary_style: Option<String>,
+    /// "apprentice", "junior" or "senior"; `None` when not set
+    #[serde(default)]
+    pub experience_tier: Option<String>,
+    /// Last day (YYYY-MM-DD) of the guest spot the artist is at this shop
+    /// for; `None` at their own shop
+    #[serde(default)]
+    pub guest_until: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -431,7 +586,7 @@ pub struct LocationDetailInfo {
     feature = "ssr",
     instrument(skip(bounds, style_filter), err, level = "info")
 )]
-#[server]
+#[server(prefix = "/api", endpoint = "locations_with_details", input = GetUrl)]
 #[cfg_attr(
     feature = "ssr",
     instrument(skip(bounds, style_filter), err, level = "info")
@@ -441,11 +596,22 @@ pub async fn get_locations_with_details(
     city: String,
     bounds: MapBounds,
     style_filter: Option<Vec<i32>>,
+    radius: Option<RadiusSearch>,
+    experience_tiers: Option<Vec<String>>,
 ) -> Result<Vec<EnhancedLocationInfo>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
         use crate::db::repository::query_locations_with_details;
-        match query_locations_with_details(state, city, bounds, style_filter).await {
+        match query_locations_with_details(
+            state,
+            city,
+            bounds,
+            style_filter,
+            radius,
+            experience_tiers,
+        )
+        .await
+        {
             Ok(locations) => Ok(locations),
             Err(e) => {
                 println!("{}", e.to_string());
@@ -462,6 +628,65 @@ pub async fn get_locations_with_details(
     }
 }
 
+impl From<&EnhancedLocationInfo> for LocationPin {
+    fn from(info: &EnhancedLocationInfo) -> Self {
+        LocationPin {
+            id: info.location.id,
+            name: info.location.name.clone(),
+            lat: info.location.lat,
+            long: info.location.long,
+            artist_count: info.artist_count,
+            image_count: info.image_count,
+            min_price: info.min_price,
+        }
+    }
+}
+
+/// Compact variant of `get_locations_with_details` for mobile map views.
+#[server(prefix = "/api", endpoint = "location_pins", input = GetUrl)]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(bounds, style_filter), err, level = "info")
+)]
+pub async fn get_location_pins(
+    state: String,
+    city: String,
+    bounds: MapBounds,
+    style_filter: Option<Vec<i32>>,
+    radius: Option<RadiusSearch>,
+    experience_tiers: Option<Vec<String>>,
+) -> Result<Vec<LocationPin>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::repository::query_locations_with_details;
+        let locations = query_locations_with_details(
+            state,
+            city,
+            bounds,
+            style_filter,
+            radius,
+            experience_tiers,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to fetch locations: {}", e)))?;
+
+        let pins: Vec<LocationPin> = locations.iter().map(LocationPin::from).collect();
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            tracing::debug!(
+                full_bytes = shared_types::payload_size(&locations),
+                compact_bytes = shared_types::payload_size(&pins),
+                count = pins.len(),
+                "location pins payload"
+            );
+        }
+        Ok(pins)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MatchedArtist {
     pub id: i64,
@@ -479,26 +704,112 @@ pub struct MatchedArtist {
     pub years_experience: Option<i32>,
     pub min_price: Option<f64>,
     pub max_price: Option<f64>,
+    pub explanation: MatchExplanation,
+    /// e.g. "Usually responds within a few hours", when there's enough history
+    #[serde(default)]
+    pub response_time_label: Option<String>,
+    /// "apprentice", "junior" or "senior"; `None` when not set
+    #[serde(default)]
+    pub experience_tier: Option<String>,
 }
 
-#[cfg_attr(
-    feature = "ssr",
-    instrument(skip(style_preferences), err, level = "info")
-)]
+/// A single contribution to an artist's match score.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ScoreComponent {
+    pub factor: String,
+    pub points: i32,
+    pub max_points: i32,
+}
+
+/// Why the scoring engine recommended an artist, returned alongside each match.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MatchExplanation {
+    pub matched_styles: Vec<String>,
+    pub distance_miles: Option<f64>,
+    pub within_budget: Option<bool>,
+    pub components: Vec<ScoreComponent>,
+    /// Set when an admin pinning rule placed this artist rather than the score
+    #[serde(default)]
+    pub pinned_position: Option<i32>,
+    /// The artist's tier when it lifted them for a client on a tight budget
+    #[serde(default)]
+    pub budget_tier: Option<String>,
+}
+
+impl MatchExplanation {
+    /// Short user-facing reasons, e.g. `["Japanese", "within 10 miles", "in budget"]`.
+    pub fn reasons(&self) -> Vec<String> {
+        let mut reasons = self.matched_styles.clone();
+
+        if let Some(distance) = self.distance_miles {
+            let reason = match [5.0, 10.0, 25.0, 50.0, 100.0]
+                .into_iter()
+                .find(|limit| distance <= *limit)
+            {
+                Some(limit) => format!("within {} miles", limit as i32),
+                None => format!("{:.0} miles away", distance),
+            };
+            reasons.push(reason);
+        }
+
+        if self.within_budget == Some(true) {
+            reasons.push("in budget".to_string());
+        }
+
+        match self.budget_tier.as_deref() {
+            Some("apprentice") => reasons.push("apprentice rates".to_string()),
+            Some("junior") => reasons.push("junior artist rates".to_string()),
+            _ => {}
+        }
+
+        // Factors without a reason of their own are mentioned when the artist
+        // earned most of their points
+        let strong = |factor| self.share(factor).is_some_and(|share| share >= 0.75);
+        if strong(MatchFactor::Recency) {
+            reasons.push("posting recent work".to_string());
+        }
+        if strong(MatchFactor::Rating) {
+            reasons.push("popular with clients".to_string());
+        }
+
+        reasons
+    }
+
+    /// The share of a factor's points the artist earned, when it was scored
+    pub fn share(&self, factor: MatchFactor) -> Option<f64> {
+        self.components
+            .iter()
+            .find(|component| component.factor == factor.as_str() && component.max_points > 0)
+            .map(|component| component.points as f64 / component.max_points as f64)
+    }
+
+    /// "Matched because: Japanese, within 10 miles, in budget", or `None` when
+    /// nothing specific contributed to the match.
+    pub fn summary(&self) -> Option<String> {
+        let reasons = self.reasons();
+        if reasons.is_empty() {
+            None
+        } else {
+            Some(format!("Matched because: {}", reasons.join(", ")))
+        }
+    }
+}
+
+/// Artist matching. Quiz style preferences go in `style_filter` as
+/// nice-to-have (`StyleFilter::preferred`); must-have styles restrict the
+/// candidates to artists whose portfolio covers them.
+#[cfg_attr(feature = "ssr", instrument(skip(style_filter), err, level = "info"))]
 #[server]
-#[cfg_attr(
-    feature = "ssr",
-    instrument(skip(style_preferences), err, level = "info")
-)]
+#[cfg_attr(feature = "ssr", instrument(skip(style_filter), err, level = "info"))]
 pub async fn get_matched_artists(
-    style_preferences: Vec<String>,
+    style_filter: StyleFilter<String>,
     location: String,
     price_range: Option<(f64, f64)>,
 ) -> Result<Vec<MatchedArtist>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
         use crate::db::repository::query_matched_artists;
-        match query_matched_artists(style_preferences, location, price_range).await {
+        match query_matched_artists(style_filter, location, price_range).await {
             Ok(artists) => Ok(artists),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to fetch matched artists: {}",
@@ -594,55 +905,26 @@ pub async fn get_search_suggestions(query: String) -> Result<Vec<String>, Server
     }
 }
 
+/// A post's oEmbed markup, or its saved preview while the visitor hasn't
+/// consented to Instagram embeds (see `server_instagram`)
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
 #[server]
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
-pub async fn get_instagram_embed(short_code: String) -> Result<String, ServerFnError> {
+pub async fn get_instagram_embed(
+    short_code: String,
+    consent: Option<bool>,
+) -> Result<InstagramEmbedContent, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        let url = format!(
-            "https://www.instagram.com/p/{}/oembed/?url=https://www.instagram.com/p/{}/",
-            short_code, short_code
-        );
+        use crate::server_instagram::{live_embeds_allowed, load_live_embed, load_preview};
 
-        let client = reqwest::Client::new();
-
-        match client.get(&url).send().await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    match response.json::<InstagramOEmbedResponse>().await {
-                        Ok(oembed_data) => {
-                            // Return the HTML embed code
-                            Ok(oembed_data.html)
-                        }
-                        Err(e) => {
-                            leptos::logging::log!("Failed to parse Instagram oEmbed JSON: {}", e);
-                            Err(ServerFnError::new(format!(
-                                "Failed to parse Instagram embed data for post: {}",
-                                short_code
-                            )))
-                        }
-                    }
-                } else {
-                    leptos::logging::log!(
-                        "Instagram oEmbed API returned error status: {} for post: {}",
-                        response.status(),
-                        short_code
-                    );
-                    Err(ServerFnError::new(format!(
-                        "Instagram post not found or not accessible: {}",
-                        short_code
-                    )))
-                }
-            }
-            Err(e) => {
-                leptos::logging::log!("HTTP request to Instagram oEmbed failed: {}", e);
-                Err(ServerFnError::new(format!(
-                    "Failed to fetch Instagram embed for post: {}",
-                    short_code
-                )))
-            }
+        if !live_embeds_allowed(consent) {
+            return load_preview(&short_code).await.map(InstagramEmbedContent::Preview);
         }
+
+        load_live_embed(&short_code)
+            .await
+            .map(|html| InstagramEmbedContent::Live { html })
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -656,9 +938,10 @@ pub async fn get_instagram_embed(short_code: String) -> Result<String, ServerFnE
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArtistDashboardData {
     pub todays_bookings: i32,
-    pub pending_sketches: i32,
+    pub pending_requests: i32,
     pub unread_messages: i32,
     pub monthly_revenue: f64,
+    pub currency: String,
     pub recent_bookings: Vec<RecentBooking>,
 }
 
@@ -670,104 +953,110 @@ pub struct RecentBooking {
     pub created_at: String,
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+/// Weeks of booking trends the dashboard charts by default, and the most
+/// that can be asked for
+pub const DEFAULT_TREND_WEEKS: i32 = 12;
+pub const MAX_TREND_WEEKS: i32 = 52;
+
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn get_artist_dashboard_data(
     artist_id: i32,
+    token: String,
 ) -> Result<ArtistDashboardData, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
-
-        async fn query_dashboard_data(artist_id: i32) -> Result<ArtistDashboardData, sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
-
-            // Get today's bookings count
-            let today = Utc::now().naive_utc().date();
-            let today_str = today.to_string();
-            let row = sqlx::query(
-                "SELECT COUNT(*) FROM bookings
-                 WHERE artist_id = $1 AND DATE(created_at) = $2",
-            )
-            .bind(artist_id)
-            .bind(&today_str)
-            .fetch_one(pool)
-            .await?;
-            let todays_bookings: i64 = row.get(0);
-
-            // Get pending sketch requests count (placeholder - would need sketch_requests table)
-            let pending_sketches = 3; // Placeholder
+        use crate::db::{dashboard_repository, invoice_repository};
 
-            // Get unread messages count (placeholder - would need messages table)
-            let unread_messages = 7; // Placeholder
+        authorize_artist_id(&token, artist_id, TeamPermission::Bookings).await?;
 
-            // Get monthly revenue (placeholder calculation)
-            let monthly_revenue = 1250.0; // Placeholder
-
-            // Get recent bookings
-            let rows = sqlx::query(
-                "SELECT b.id, b.client_name, b.placement, b.created_at
-                 FROM bookings b
-                 WHERE b.artist_id = $1
-                 ORDER BY b.created_at DESC
-                 LIMIT 5",
-            )
-            .bind(artist_id)
-            .fetch_all(pool)
-            .await?;
+        let db_error = |e: sqlx::Error| {
+            ServerFnError::new(format!("Failed to fetch artist dashboard data: {}", e))
+        };
 
-            let recent_bookings: Vec<RecentBooking> = rows
-                .iter()
-                .map(|row| {
-                    let created_at_str: String = row.get("created_at");
-                    // Try to parse the date string and format it, fallback to original if parsing fails
-                    let formatted_date = if let Ok(naive_date) =
-                        NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
-                    {
-                        naive_date.format("%B %d, %Y").to_string()
-                    } else {
-                        created_at_str.clone()
-                    };
-
-                    RecentBooking {
-                        id: row.get("id"),
-                        client_name: row.get("client_name"),
-                        placement: row.get("placement"),
-                        created_at: formatted_date,
-                    }
-                })
-                .collect();
+        let counts = dashboard_repository::get_dashboard_counts(artist_id)
+            .await
+            .map_err(db_error)?;
+        let currency = invoice_repository::get_billing_settings(artist_id)
+            .await
+            .map_err(db_error)?
+            .currency;
 
-            Ok(ArtistDashboardData {
-                todays_bookings: todays_bookings as i32,
-                pending_sketches,
-                unread_messages,
-                monthly_revenue,
-                recent_bookings,
+        let recent_bookings = dashboard_repository::get_recent_requests(artist_id, 5)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|(id, client_name, placement, created_at)| {
+                // Try to parse the date string and format it, fallback to original if parsing fails
+                let formatted_date =
+                    NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+                        .map(|date| date.format("%B %d, %Y").to_string())
+                        .unwrap_or(created_at);
+
+                RecentBooking {
+                    id,
+                    client_name,
+                    placement,
+                    created_at: formatted_date,
+                }
             })
-        }
+            .collect();
 
-        match query_dashboard_data(artist_id).await {
-            Ok(data) => Ok(data),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to fetch artist dashboard data: {}",
-                e
-            ))),
-        }
+        Ok(ArtistDashboardData {
+            todays_bookings: counts.todays_appointments as i32,
+            pending_requests: counts.pending_requests as i32,
+            unread_messages: counts.unread_messages as i32,
+            monthly_revenue: counts.monthly_revenue,
+            currency,
+            recent_bookings,
+        })
     }
     #[cfg(not(feature = "ssr"))]
     {
         // Return placeholder data for client-side
         Ok(ArtistDashboardData {
             todays_bookings: 0,
-            pending_sketches: 0,
+            pending_requests: 0,
             unread_messages: 0,
             monthly_revenue: 0.0,
+            currency: String::new(),
             recent_bookings: Vec::new(),
         })
     }
 }
 
+/// Booking requests and how many were accepted, per week for the last
+/// `weeks` weeks, oldest first, for the charts on the artist dashboard
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server(prefix = "/api", endpoint = "dashboard/booking-trends")]
+pub async fn get_booking_trends(
+    token: String,
+    weeks: i32,
+) -> Result<Vec<WeeklyBookings>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = authorize_artist(&token, TeamPermission::Bookings).await?;
+
+        if !(1..=MAX_TREND_WEEKS).contains(&weeks) {
+            return Err(ApiError::validation(
+                "weeks",
+                format!("Trends cover 1 to {} weeks", MAX_TREND_WEEKS),
+            )
+            .into());
+        }
+
+        Ok(
+            crate::db::dashboard_repository::get_weekly_bookings(artist_id, weeks)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load booking trends", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
 #[server]
 pub async fn log_match_impression(
@@ -779,6 +1068,14 @@ pub async fn log_match_impression(
     {
         use std::time::{SystemTime, UNIX_EPOCH};
 
+        // Only these are counted in the artist insights
+        if !matches!(impression_type.as_str(), "view" | "click") {
+            return Err(ServerFnError::new(format!(
+                "Unknown impression type {}",
+                impression_type
+            )));
+        }
+
         // Use session ID if available, otherwise create a temp session ID based on timestamp
         let session_id = session_id.unwrap_or_else(|| {
             SystemTime::now()
@@ -832,7 +1129,7 @@ pub async fn get_all_styles_with_counts() -> Result<Vec<StyleWithCount>, ServerF
             FROM styles s
             LEFT JOIN artists_styles ast ON s.id = ast.style_id
             LEFT JOIN artists a ON ast.artist_id = a.id
-            LEFT JOIN artists_images ai ON a.id = ai.artist_id
+            LEFT JOIN artists_images ai ON a.id = ai.artist_id AND ai.removed_at IS NULL
             GROUP BY s.id, s.name
             HAVING COUNT(DISTINCT ast.artist_id) > 0
             ORDER BY artist_count DESC, s.name ASC
@@ -895,49 +1192,162 @@ pub async fn get_all_styles_with_counts() -> Result<Vec<StyleWithCount>, ServerF
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct TattooPost {
-    pub id: i64,
+pub struct StarterImage {
     pub short_code: String,
-    pub artist_id: i64,
-    pub artist_name: String,
-    pub artist_instagram: Option<String>,
-    pub styles: Vec<String>,
-    pub is_favorited: bool,
+    pub image_url: String,
+    pub artist_id: Option<i64>,
+    pub curated: bool, // false when filled in automatically
 }
 
-#[cfg_attr(
-    feature = "ssr",
-    instrument(skip(token, cities, states), err, level = "info")
-)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StyleStarterPack {
+    pub style_id: i64,
+    pub style_name: String,
+    pub images: Vec<StarterImage>,
+}
+
+/// Which images a quiz session sees: the curated set topped up with recent
+/// work, or per-style pools rotated to balance exposure across artists
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizImageVariant {
+    Static,
+    Rotation,
+}
+
+impl QuizImageVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Static => "static",
+            Self::Rotation => "rotation",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "rotation" => Self::Rotation,
+            _ => Self::Static,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuizStarterPacks {
+    /// Keep and send back on later calls so the session stays in its variant
+    pub session_key: String,
+    pub variant: QuizImageVariant,
+    pub packs: Vec<StyleStarterPack>,
+}
+
+/// Representative images per style for the quiz's visual style picker. A new
+/// session key is issued when none is given; what's shown is logged against it.
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
 #[server]
-pub async fn get_tattoo_posts_by_style(
-    style_names: Vec<String>,
-    states: Option<Vec<String>>,
-    cities: Option<Vec<String>>,
-    token: Option<String>,
-) -> Result<Vec<TattooPost>, ServerFnError> {
+pub async fn get_style_starter_packs(
+    session_key: Option<String>,
+) -> Result<QuizStarterPacks, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
+        use crate::db::starter_pack_repository::{
+            get_rotation_packs, get_starter_packs, record_exposures, variant_for_session,
+        };
 
-        let pool = crate::db::pool::get_pool();
-        let user_id = token.as_deref().and_then(extract_user_id_from_token);
+        let session_key = session_key
+            .map(|key| key.trim().chars().take(64).collect::<String>())
+            .filter(|key| !key.is_empty())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let variant = variant_for_session(&session_key);
 
-        // Build WHERE clause based on filters
-        let mut where_clauses = Vec::new();
-        let mut bind_index = 1;
+        let packs = match variant {
+            QuizImageVariant::Static => get_starter_packs().await,
+            QuizImageVariant::Rotation => get_rotation_packs(&session_key).await,
+        }
+        .map_err(|e| ServerFnError::new(format!("Failed to load style examples: {}", e)))?;
 
-        // Add style filter - support multiple styles with IN clause
-        let style_placeholders = if !style_names.is_empty() {
-            let placeholders: Vec<String> = (0..style_names.len())
-                .map(|i| format!("${}", bind_index + i))
-                .collect();
-            bind_index += style_names.len();
-            where_clauses.push(format!("LOWER(s.name) IN ({})", placeholders.join(",")));
-            placeholders
-        } else {
-            vec![]
-        };
+        // The quiz works without the log, so a failed write isn't the visitor's problem
+        if let Err(e) = record_exposures(&session_key, variant, &packs).await {
+            tracing::warn!("Failed to log quiz image exposures: {}", e);
+        }
+
+        Ok(QuizStarterPacks {
+            session_key,
+            variant,
+            packs,
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Records the styles a quiz session searched with, for comparing variants
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[server]
+pub async fn record_quiz_style_picks(
+    session_key: String,
+    style_ids: Vec<i32>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::starter_pack_repository::{record_style_picks, variant_for_session};
+
+        if session_key.trim().is_empty() || style_ids.is_empty() {
+            return Ok(());
+        }
+        let style_ids: Vec<i64> = style_ids.into_iter().map(i64::from).collect();
+
+        record_style_picks(&session_key, variant_for_session(&session_key), &style_ids)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to record style picks: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TattooPost {
+    pub id: i64,
+    pub short_code: String,
+    pub artist_id: i64,
+    pub artist_name: String,
+    pub artist_instagram: Option<String>,
+    pub styles: Vec<String>,
+    pub is_favorited: bool,
+}
+
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(token, cities, states), err, level = "info")
+)]
+#[server]
+pub async fn get_tattoo_posts_by_style(
+    style_filter: StyleFilter<String>,
+    states: Option<Vec<String>>,
+    cities: Option<Vec<String>>,
+    token: Option<String>,
+) -> Result<Vec<TattooPost>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::repository::IMAGE_STYLE_NAMES;
+        use sqlx::Row;
+
+        let pool = crate::db::pool::get_pool();
+        let user_id = token.as_deref().and_then(extract_user_id_from_token);
+        // Must-have styles combine per the filter's mode; nice-to-have styles
+        // only affect ordering
+        let styles =
+            IMAGE_STYLE_NAMES.clauses("ai.id", style_filter.map(|name| name.to_lowercase()), 1);
+        let rank_order = styles.rank_order();
+        let mut bind_index = styles.next_bind;
+
+        // Build WHERE clause based on filters
+        // Posts since deleted from Instagram, or hidden by the artist, aren't
+        // shown
+        let mut where_clauses = vec!["ai.removed_at IS NULL AND ai.hidden_at IS NULL".to_string()];
+        where_clauses.extend(styles.filter.clone());
 
         // Add state filter if provided
         let state_placeholders = if let Some(state_list) = &states {
@@ -970,11 +1380,7 @@ pub async fn get_tattoo_posts_by_style(
             vec![]
         };
 
-        let where_clause = if !where_clauses.is_empty() {
-            format!("WHERE {}", where_clauses.join(" AND "))
-        } else {
-            String::new()
-        };
+        let where_clause = format!("WHERE {}", where_clauses.join(" AND "));
 
         let (favorites_join, favorites_select) = if let Some(uid) = user_id {
             (
@@ -987,7 +1393,7 @@ pub async fn get_tattoo_posts_by_style(
 
         let query = format!(
             "
-            SELECT DISTINCT
+            SELECT
                 ai.id,
                 ai.short_code,
                 ai.artist_id,
@@ -997,20 +1403,18 @@ pub async fn get_tattoo_posts_by_style(
             FROM artists_images ai
             JOIN artists a ON ai.artist_id = a.id
             JOIN locations l ON a.location_id = l.id
-            JOIN artists_images_styles ais ON ai.id = ais.artists_images_id
-            JOIN styles s ON ais.style_id = s.id
             {}
             {}
-            ORDER BY ai.id DESC
+            ORDER BY {} ai.id DESC
         ",
-            favorites_select, favorites_join, where_clause
+            favorites_select, favorites_join, where_clause, rank_order
         );
 
         let mut query_builder = sqlx::query(&query);
 
         // Bind style parameters
-        for style in &style_names {
-            query_builder = query_builder.bind(style.to_lowercase());
+        for names in &styles.binds {
+            query_builder = query_builder.bind(names);
         }
 
         // Bind state parameters
@@ -1032,6 +1436,15 @@ pub async fn get_tattoo_posts_by_style(
             .await
             .map_err(|e| ServerFnError::new(format!("Failed to query posts: {}", e)))?;
 
+        // Styles for every post in one query
+        let image_ids: Vec<i32> = rows
+            .iter()
+            .map(|row| row.get::<i64, _>("id") as i32)
+            .collect();
+        let mut styles_by_image = crate::db::repository::get_styles_for_images(pool, &image_ids)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to query styles: {}", e)))?;
+
         let mut posts = Vec::new();
         for row in rows {
             let image_id: i64 = row.get("id");
@@ -1041,21 +1454,12 @@ pub async fn get_tattoo_posts_by_style(
             let artist_instagram: Option<String> = row.get("artist_instagram");
             let is_favorited: bool = row.get("is_favorited");
 
-            // Get styles for this specific image
-            let style_rows = sqlx::query(
-                "
-                SELECT s.name
-                FROM styles s
-                JOIN artists_images_styles ais ON s.id = ais.style_id
-                WHERE ais.artists_images_id = $1
-            ",
-            )
-            .bind(image_id)
-            .fetch_all(pool)
-            .await
-            .map_err(|e| ServerFnError::new(format!("Failed to query styles: {}", e)))?;
-
-            let styles: Vec<String> = style_rows.iter().map(|row| row.get("name")).collect();
+            let styles: Vec<String> = styles_by_image
+                .remove(&(image_id as i32))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|style| style.name)
+                .collect();
 
             posts.push(TattooPost {
                 id: image_id,
@@ -1151,13 +1555,82 @@ pub async fn get_artist_availability(
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn set_artist_availability(
     availability: AvailabilityUpdate,
+    token: String,
 ) -> Result<(), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
+        authorize_artist_id(&token, availability.artist_id, TeamPermission::Calendar).await?;
+
+        // Weekly availability is a recurring rule now; artist_availability
+        // only holds single dates
+        if availability.is_recurring {
+            use crate::db::entities::RecurrencePattern;
+
+            let Some(day) = availability.day_of_week.filter(|day| (0..7).contains(day)) else {
+                return Err(ServerFnError::new(
+                    "Failed to set availability: a weekly slot needs a day of the week".to_string(),
+                ));
+            };
+            const DAY_NAMES: [&str; 7] = [
+                "Sunday",
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+            ];
+            let action = if availability.is_available {
+                "available"
+            } else {
+                "blocked"
+            };
+            let name = format!(
+                "{} {}",
+                DAY_NAMES[day as usize],
+                if availability.is_available {
+                    "Available"
+                } else {
+                    "Blocked"
+                }
+            );
+            let pattern = RecurrencePattern {
+                frequency: "weekly".to_string(),
+                weekdays: vec![day],
+                interval_weeks: 1,
+                week_of_month: None,
+                annual_dates: Vec::new(),
+                starts_on: None,
+                ends_on: None,
+                except_dates: Vec::new(),
+            };
+            let start_time = availability.start_time.map(|time| time.to_string());
+            let end_time = availability.end_time.map(|time| time.to_string());
+            validate_recurring_rule(
+                &name,
+                &pattern,
+                action,
+                start_time.as_deref(),
+                end_time.as_deref(),
+            )?;
+
+            return crate::db::recurring_rule_repository::create_rule(
+                availability.artist_id,
+                &name,
+                &pattern,
+                action,
+                start_time.as_deref(),
+                end_time.as_deref(),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| ServerFnError::new(format!("Failed to set availability: {}", e)));
+        }
+
         async fn update_availability(availability: AvailabilityUpdate) -> Result<(), sqlx::Error> {
             let pool = crate::db::pool::get_pool();
 
@@ -1170,9 +1643,9 @@ pub async fn set_artist_availability(
             )
             .bind(availability.artist_id)
             .bind(availability.day_of_week)
-            .bind(availability.date)
-            .bind(availability.start_time)
-            .bind(availability.end_time)
+            .bind(availability.date.map(|date| date.to_string()))
+            .bind(availability.start_time.map(|time| time.to_string()))
+            .bind(availability.end_time.map(|time| time.to_string()))
             .bind(availability.is_available)
             .bind(availability.is_recurring)
             .execute(pool)
@@ -1195,13 +1668,18 @@ pub async fn set_artist_availability(
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn get_booking_requests(artist_id: i32) -> Result<Vec<BookingRequest>, ServerFnError> {
+pub async fn get_booking_requests(
+    artist_id: i32,
+    token: String,
+) -> Result<Vec<BookingRequest>, ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
         use sqlx::Row;
 
+        authorize_artist_id(&token, artist_id, TeamPermission::Bookings).await?;
+
         async fn query_bookings(artist_id: i32) -> Result<Vec<BookingRequest>, sqlx::Error> {
             let pool = crate::db::pool::get_pool();
 
@@ -1211,7 +1689,11 @@ pub async fn get_booking_requests(artist_id: i32) -> Result<Vec<BookingRequest>,
                        requested_date, requested_start_time, requested_end_time,
                        tattoo_description, placement, size_inches, reference_images,
                        message_from_client, status, artist_response, estimated_price,
-                       created_at, updated_at, decline_reason
+                       created_at, updated_at, decline_reason, deposit_amount, deposit_status,
+                       (SELECT l.name FROM guest_spots g JOIN locations l ON l.id = g.location_id
+                        WHERE g.id = booking_requests.guest_spot_id) as guest_spot_shop,
+                       (SELECT f.title FROM flash_designs f
+                        WHERE f.id = booking_requests.flash_design_id) as flash_design_title
                 FROM booking_requests
                 WHERE artist_id = $1
                 ORDER BY created_at DESC
@@ -1243,19 +1725,19 @@ pub async fn get_booking_requests(artist_id: i32) -> Result<Vec<BookingRequest>,
                     created_at: row.get("created_at"),
                     updated_at: row.get("updated_at"),
                     decline_reason: row.get("decline_reason"),
+                    deposit_amount: row.get("deposit_amount"),
+                    deposit_status: row.get("deposit_status"),
+                    guest_spot_shop: row.get("guest_spot_shop"),
+                    flash_design_title: row.get("flash_design_title"),
                 })
                 .collect();
 
             Ok(bookings)
         }
 
-        match query_bookings(artist_id).await {
-            Ok(bookings) => Ok(bookings),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to get booking requests: {}",
-                e
-            ))),
-        }
+        Ok(query_bookings(artist_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to get booking requests", e))?)
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -1266,44 +1748,133 @@ pub async fn get_booking_requests(artist_id: i32) -> Result<Vec<BookingRequest>,
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BookingResponse {
     pub booking_id: i32,
-    pub status: String,
+    /// Must be one the booking can move to from where it is (see
+    /// `BookingStatus::next`)
+    pub status: BookingStatus,
     pub artist_response: Option<String>,
     pub estimated_price: Option<f64>,
     pub decline_reason: Option<String>,
+    /// Deposit the client must pay before an approved booking is confirmed
+    #[serde(default)]
+    pub deposit_amount: Option<f64>,
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn respond_to_booking(response: BookingResponse) -> Result<(), ServerFnError> {
+pub async fn respond_to_booking(
+    response: BookingResponse,
+    token: String,
+) -> Result<(), ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
-        async fn update_booking(response: BookingResponse) -> Result<(), sqlx::Error> {
+        use crate::db::booking_status_repository;
+
+        let artist_id =
+            authorize_booking(&token, response.booking_id, TeamPermission::Bookings).await?;
+
+        /// The status the booking moved from, or Err with the one it's stuck
+        /// in when the move isn't allowed
+        async fn update_booking(
+            response: BookingResponse,
+            changed_by: Option<i64>,
+        ) -> Result<Result<BookingStatus, String>, sqlx::Error> {
             let pool = crate::db::pool::get_pool();
+            let mut tx = pool.begin().await?;
+
+            let current = booking_status_repository::lock_status(&mut tx, response.booking_id)
+                .await?
+                .unwrap_or_default();
+            let from = match BookingStatus::parse(&current) {
+                Some(from) if from.can_become(response.status) => from,
+                _ => return Ok(Err(current)),
+            };
+
+            // A deposit only applies to approvals; one already paid is kept
+            let deposit_amount = response
+                .deposit_amount
+                .filter(|amount| *amount > 0.0 && response.status == BookingStatus::Accepted);
 
             sqlx::query(
                 "
                 UPDATE booking_requests
-                SET status = $1, artist_response = $2, estimated_price = $3, decline_reason = $4, updated_at = CURRENT_TIMESTAMP
+                SET status = $1, artist_response = $2, estimated_price = $3, decline_reason = $4, updated_at = CURRENT_TIMESTAMP,
+                    first_responded_at = COALESCE(first_responded_at, CURRENT_TIMESTAMP),
+                    deposit_amount = CASE WHEN deposit_status = 'paid' THEN deposit_amount ELSE $6 END,
+                    deposit_status = CASE
+                        WHEN deposit_status = 'paid' THEN deposit_status
+                        WHEN $6 IS NOT NULL THEN 'required'
+                        ELSE NULL
+                    END
                 WHERE id = $5
             ",
             )
-            .bind(response.status)
-            .bind(response.artist_response)
+            .bind(response.status.as_str())
+            .bind(&response.artist_response)
             .bind(response.estimated_price)
-            .bind(response.decline_reason)
+            .bind(&response.decline_reason)
             .bind(response.booking_id)
-            .execute(pool)
+            .bind(deposit_amount)
+            .execute(&mut *tx)
             .await?;
 
-            Ok(())
+            booking_status_repository::record_change_in(
+                &mut tx,
+                response.booking_id,
+                Some(from),
+                response.status,
+                "artist",
+                changed_by,
+                response
+                    .decline_reason
+                    .as_deref()
+                    .or(response.artist_response.as_deref()),
+            )
+            .await?;
+
+            tx.commit().await?;
+
+            Ok(Ok(from))
         }
 
-        match update_booking(response).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to respond to booking: {}",
-                e
-            ))),
+        if response.deposit_amount.is_some_and(|amount| amount < 0.0) {
+            return Err(ApiError::validation("deposit_amount", "Deposit can't be negative").into());
+        }
+
+        let booking_id = response.booking_id;
+        let status = response.status;
+        let event = match status {
+            BookingStatus::Accepted => Some(BookingEventKind::Accepted {
+                deposit_amount: response.deposit_amount.filter(|amount| *amount > 0.0),
+            }),
+            BookingStatus::Declined => Some(BookingEventKind::Declined {
+                reason: response.decline_reason.clone(),
+            }),
+            _ => None,
+        };
+
+        match update_booking(response, extract_user_id_from_token(&token)).await {
+            Ok(Ok(_)) => {
+                if let Some(event) = event {
+                    record_booking_event(booking_id, "artist", event).await;
+                }
+                crate::reminders::schedule_reminders(booking_id).await;
+                if status == BookingStatus::Completed {
+                    crate::hooks::booking_completed(crate::hooks::BookingCompleted {
+                        booking_id,
+                        artist_id,
+                    });
+                }
+                Ok(())
+            }
+            Ok(Err(current)) => Err(ApiError::conflict(format!(
+                "A booking that is {} can't be moved to {}",
+                BookingStatus::parse(&current)
+                    .map(|status| status.label().to_lowercase())
+                    .unwrap_or(current),
+                status.label().to_lowercase()
+            ))
+            .into()),
+            Err(e) => Err(ApiError::internal("Failed to respond to booking", e).into()),
         }
     }
     #[cfg(not(feature = "ssr"))]
@@ -1312,6 +1883,28 @@ pub async fn respond_to_booking(response: BookingResponse) -> Result<(), ServerF
     }
 }
 
+/// Every status a booking has been through, oldest first, with who moved
+/// it and why.
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn get_booking_status_history(
+    booking_id: i32,
+    token: String,
+) -> Result<Vec<BookingStatusChange>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+
+        crate::db::booking_status_repository::get_history(booking_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load status history: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NewBookingMessage {
     pub booking_request_id: i32,
@@ -1319,32 +1912,93 @@ pub struct NewBookingMessage {
     pub message: String,
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn send_booking_message(message_data: NewBookingMessage) -> Result<(), ServerFnError> {
+pub async fn send_booking_message(
+    message_data: NewBookingMessage,
+    token: String,
+) -> Result<(), ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
-        async fn insert_message(message_data: NewBookingMessage) -> Result<(), sqlx::Error> {
+        use sqlx::Row;
+
+        authorize_booking(&token, message_data.booking_request_id, TeamPermission::Messages).await?;
+        if message_data.sender_type != "artist" {
+            return Err(ApiError::unauthorized("Messages here are sent as the artist").into());
+        }
+        if message_data.message.trim().is_empty() {
+            return Err(ApiError::validation("message", "Write a message first").into());
+        }
+
+        async fn insert_message(
+            message_data: NewBookingMessage,
+            translation: crate::translate::MessageTranslation,
+        ) -> Result<BookingMessage, sqlx::Error> {
             let pool = crate::db::pool::get_pool();
 
-            sqlx::query(
+            let row = sqlx::query(
                 "
-                INSERT INTO booking_messages (booking_request_id, sender_type, message)
-                VALUES ($1, $2, $3)
+                INSERT INTO booking_messages
+                    (booking_request_id, sender_type, message,
+                     language, translated_message, translated_language)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING id, booking_request_id, sender_type, message, created_at,
+                          language, translated_message, translated_language
             ",
             )
             .bind(message_data.booking_request_id)
             .bind(message_data.sender_type)
             .bind(message_data.message)
-            .execute(pool)
+            .bind(translation.language)
+            .bind(translation.translated_message)
+            .bind(translation.translated_language)
+            .fetch_one(pool)
             .await?;
 
-            Ok(())
+            Ok(BookingMessage {
+                id: row.get("id"),
+                booking_request_id: row.get("booking_request_id"),
+                sender_type: row.get("sender_type"),
+                message: row.get("message"),
+                created_at: row.get("created_at"),
+                language: row.get("language"),
+                translated_message: row.get("translated_message"),
+                translated_language: row.get("translated_language"),
+                is_read: false,
+            })
         }
 
-        match insert_message(message_data).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(ServerFnError::new(format!("Failed to send message: {}", e))),
+        let translation = crate::translate::translate_booking_message(
+            message_data.booking_request_id,
+            &message_data.sender_type,
+            &message_data.message,
+        )
+        .await;
+
+        match insert_message(message_data, translation).await {
+            Ok(message) => {
+                if message.sender_type == "artist" {
+                    if let Err(e) = crate::db::response_time_repository::record_first_response(
+                        message.booking_request_id,
+                    )
+                    .await
+                    {
+                        tracing::warn!("Failed to record first response: {}", e);
+                    }
+                }
+                record_booking_event(
+                    message.booking_request_id,
+                    "artist",
+                    BookingEventKind::Message {
+                        sender_type: message.sender_type.clone(),
+                        message: message.message.clone(),
+                    },
+                )
+                .await;
+                crate::message_stream::publish(message);
+                Ok(())
+            }
+            Err(e) => Err(ApiError::internal("Failed to send message", e).into()),
         }
     }
     #[cfg(not(feature = "ssr"))]
@@ -1353,28 +2007,35 @@ pub async fn send_booking_message(message_data: NewBookingMessage) -> Result<(),
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn get_booking_messages(
     booking_request_id: i32,
-) -> Result<Vec<BookingMessage>, ServerFnError> {
+    token: String,
+) -> Result<Vec<BookingMessage>, ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
+        use crate::db::message_read_repository::{READ_COLUMN, READ_JOIN};
         use sqlx::Row;
 
+        authorize_booking(&token, booking_request_id, TeamPermission::Messages).await?;
+
         async fn query_messages(
             booking_request_id: i32,
         ) -> Result<Vec<BookingMessage>, sqlx::Error> {
             let pool = crate::db::pool::get_pool();
 
-            let rows = sqlx::query(
+            let rows = sqlx::query(&format!(
                 "
-                SELECT id, booking_request_id, sender_type, message, created_at
-                FROM booking_messages
-                WHERE booking_request_id = $1
-                ORDER BY created_at ASC
+                SELECT bm.id, bm.booking_request_id, bm.sender_type, bm.message, bm.created_at,
+                       bm.language, bm.translated_message, bm.translated_language, {}
+                FROM booking_messages bm
+                {}
+                WHERE bm.booking_request_id = $1
+                ORDER BY bm.created_at ASC
             ",
-            )
+                READ_COLUMN, READ_JOIN
+            ))
             .bind(booking_request_id)
             .fetch_all(pool)
             .await?;
@@ -1387,16 +2048,19 @@ pub async fn get_booking_messages(
                     sender_type: row.get("sender_type"),
                     message: row.get("message"),
                     created_at: row.get("created_at"),
+                    language: row.get("language"),
+                    translated_message: row.get("translated_message"),
+                    translated_language: row.get("translated_language"),
+                    is_read: row.get("is_read"),
                 })
                 .collect();
 
             Ok(messages)
         }
 
-        match query_messages(booking_request_id).await {
-            Ok(messages) => Ok(messages),
-            Err(e) => Err(ServerFnError::new(format!("Failed to get messages: {}", e))),
-        }
+        Ok(query_messages(booking_request_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to get messages", e))?)
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -1404,103 +2068,69 @@ pub async fn get_booking_messages(
     }
 }
 
-// Recurring Rule Server Functions
-
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+/// Marks the booking's message thread as read by the artist, for when they
+/// open it
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn get_recurring_rules(artist_id: i32) -> Result<Vec<RecurringRule>, ServerFnError> {
+pub async fn mark_thread_read(
+    booking_request_id: i32,
+    token: String,
+) -> Result<(), ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
+        authorize_booking(&token, booking_request_id, TeamPermission::Messages).await?;
 
-        async fn query_recurring_rules(artist_id: i32) -> Result<Vec<RecurringRule>, sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
+        crate::db::message_read_repository::mark_thread_read(booking_request_id, "artist")
+            .await
+            .map_err(|e| ApiError::internal("Failed to mark messages read", e))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
 
-            // Query artist_availability table for recurring rules
-            let rows = sqlx::query(
-                "
-                SELECT id, artist_id, day_of_week, start_time, end_time, is_available, created_at
-                FROM artist_availability
-                WHERE artist_id = $1 AND is_recurring = true
-                ORDER BY day_of_week, start_time
-            ",
-            )
-            .bind(artist_id)
-            .fetch_all(pool)
-            .await?;
+/// Client messages the artist hasn't read yet, per booking with any
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn get_unread_counts(
+    artist_id: i32,
+    token: String,
+) -> Result<Vec<UnreadCount>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        authorize_artist_id(&token, artist_id, TeamPermission::Messages).await?;
 
-            let rules: Vec<RecurringRule> = rows
-                .iter()
-                .map(|row| {
-                    let day_of_week: Option<i32> = row.get("day_of_week");
-                    let start_time: Option<String> = row.get("start_time");
-                    let end_time: Option<String> = row.get("end_time");
-                    let is_available: bool = row.get("is_available");
-
-                    // Map day_of_week to day name
-                    let day_name = match day_of_week {
-                        Some(0) => "Sunday",
-                        Some(1) => "Monday",
-                        Some(2) => "Tuesday",
-                        Some(3) => "Wednesday",
-                        Some(4) => "Thursday",
-                        Some(5) => "Friday",
-                        Some(6) => "Saturday",
-                        _ => "Unknown Day",
-                    };
-
-                    // Create descriptive name
-                    let name = if is_available {
-                        format!(
-                            "{} Available: {} - {}",
-                            day_name,
-                            start_time.as_deref().unwrap_or("00:00"),
-                            end_time.as_deref().unwrap_or("00:00")
-                        )
-                    } else {
-                        format!(
-                            "{} Blocked: {} - {}",
-                            day_name,
-                            start_time.as_deref().unwrap_or("00:00"),
-                            end_time.as_deref().unwrap_or("00:00")
-                        )
-                    };
-
-                    // Create pattern from day_of_week
-                    let pattern = match day_of_week {
-                        Some(day) => format!("[{}]", day),
-                        None => "[]".to_string(),
-                    };
-
-                    RecurringRule {
-                        id: row.get("id"),
-                        artist_id: row.get("artist_id"),
-                        name,
-                        rule_type: "weekdays".to_string(),
-                        pattern,
-                        action: if is_available {
-                            "available".to_string()
-                        } else {
-                            "blocked".to_string()
-                        },
-                        start_time,
-                        end_time,
-                        active: true, // All recurring rules are considered active
-                        created_at: row.get("created_at"),
-                    }
-                })
-                .collect();
+        let counts = crate::db::message_read_repository::get_unread_counts(artist_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to get unread counts", e))?;
+        Ok(counts
+            .into_iter()
+            .map(|(booking_id, unread)| UnreadCount { booking_id, unread })
+            .collect())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
 
-            Ok(rules)
-        }
+/// Everything that has happened to a booking, oldest first, for the
+/// timeline on the booking details view.
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn get_booking_timeline(
+    booking_id: i32,
+    token: String,
+) -> Result<Vec<BookingEvent>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
 
-        match query_recurring_rules(artist_id).await {
-            Ok(rules) => Ok(rules),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to get recurring rules: {}",
-                e
-            ))),
-        }
+        crate::db::booking_event_repository::get_events(booking_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get booking timeline: {}", e)))
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -1508,89 +2138,77 @@ pub async fn get_recurring_rules(artist_id: i32) -> Result<Vec<RecurringRule>, S
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+// Recurring Rule Server Functions
+
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn create_recurring_rule(
+pub async fn get_recurring_rules(
     artist_id: i32,
-    name: String,
-    rule_type: String,
-    pattern: String,
-    action: String,
-    start_time: Option<String>,
-    end_time: Option<String>,
-) -> Result<i32, ServerFnError> {
+    token: String,
+) -> Result<Vec<RecurringRule>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
+        authorize_artist_id(&token, artist_id, TeamPermission::Calendar).await?;
 
-        async fn insert_recurring_rule(
-            artist_id: i32,
-            name: String,
-            rule_type: String,
-            pattern: String,
-            action: String,
-            start_time: Option<String>,
-            end_time: Option<String>,
-        ) -> Result<i32, sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
+        crate::db::recurring_rule_repository::get_rules(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get recurring rules: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
 
-            // Create recurring_rules table if it doesn't exist (PostgreSQL syntax)
-            sqlx::query(
-                "
-                CREATE TABLE IF NOT EXISTS recurring_rules (
-                    id SERIAL PRIMARY KEY,
-                    artist_id INTEGER NOT NULL,
-                    name TEXT NOT NULL,
-                    rule_type TEXT NOT NULL,
-                    pattern TEXT NOT NULL,
-                    action TEXT NOT NULL,
-                    start_time TEXT,
-                    end_time TEXT,
-                    active BOOLEAN DEFAULT true,
-                    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-                )
-            ",
-            )
-            .execute(pool)
-            .await?;
+/// Checks a rule's name, action and pattern the way the evaluator will read
+/// it, so a rule that's saved is one that takes effect
+#[cfg(feature = "ssr")]
+fn validate_recurring_rule(
+    name: &str,
+    pattern: &crate::db::entities::RecurrencePattern,
+    action: &str,
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+) -> Result<(), ServerFnError> {
+    if name.trim().is_empty() {
+        return Err(ServerFnError::new("Give the rule a name".to_string()));
+    }
+    crate::utils::recurrence::schedule_rule(pattern, action, start_time, end_time)
+        .map(|_| ())
+        .map_err(ServerFnError::new)
+}
 
-            let row = sqlx::query(
-                "
-                INSERT INTO recurring_rules (artist_id, name, rule_type, pattern, action, start_time, end_time)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
-                RETURNING id
-            ",
-            )
-            .bind(artist_id)
-            .bind(name)
-            .bind(rule_type)
-            .bind(pattern)
-            .bind(action)
-            .bind(start_time.unwrap_or_default())
-            .bind(end_time.unwrap_or_default())
-            .fetch_one(pool)
-            .await?;
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn create_recurring_rule(
+    rule: CreateRecurringRule,
+    token: String,
+) -> Result<i32, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        authorize_artist_id(&token, rule.artist_id, TeamPermission::Calendar).await?;
 
-            Ok(row.get("id"))
-        }
+        // Blank times mean all day
+        let start_time = rule.start_time.as_deref().filter(|t| !t.trim().is_empty());
+        let end_time = rule.end_time.as_deref().filter(|t| !t.trim().is_empty());
+        validate_recurring_rule(
+            &rule.name,
+            &rule.pattern,
+            &rule.action,
+            start_time,
+            end_time,
+        )?;
 
-        match insert_recurring_rule(
-            artist_id,
-            name,
-            rule_type,
-            pattern.clone(),
-            action,
+        crate::db::recurring_rule_repository::create_rule(
+            rule.artist_id,
+            rule.name.trim(),
+            &rule.pattern,
+            &rule.action,
             start_time,
             end_time,
         )
         .await
-        {
-            Ok(id) => Ok(id),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to create recurring rule - Error: {} - Pattern: {} - Artist ID: {}",
-                e, pattern, artist_id
-            ))),
-        }
+        .map_err(|e| ServerFnError::new(format!("Failed to create recurring rule: {}", e)))
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -1598,103 +2216,57 @@ pub async fn create_recurring_rule(
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn update_recurring_rule(
-    id: i32,
-    name: Option<String>,
-    pattern: Option<String>,
-    action: Option<String>,
-    start_time: Option<String>,
-    end_time: Option<String>,
-    active: Option<bool>,
+    update: UpdateRecurringRule,
+    token: String,
 ) -> Result<(), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
-
-        async fn update_rule(
-            id: i32,
-            name: Option<String>,
-            pattern: Option<String>,
-            action: Option<String>,
-            start_time: Option<String>,
-            end_time: Option<String>,
-            active: Option<bool>,
-        ) -> Result<(), sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
-
-            let mut updates = Vec::new();
-            let mut param_count = 1;
-
-            if name.is_some() {
-                updates.push(format!("name = ${}", param_count));
-                param_count += 1;
-            }
-            if pattern.is_some() {
-                updates.push(format!("pattern = ${}", param_count));
-                param_count += 1;
-            }
-            if action.is_some() {
-                updates.push(format!("action = ${}", param_count));
-                param_count += 1;
-            }
-            if start_time.is_some() {
-                updates.push(format!("start_time = ${}", param_count));
-                param_count += 1;
-            }
-            if end_time.is_some() {
-                updates.push(format!("end_time = ${}", param_count));
-                param_count += 1;
-            }
-            if active.is_some() {
-                updates.push(format!("active = ${}", param_count));
-                param_count += 1;
-            }
-
-            if updates.is_empty() {
-                return Ok(());
-            }
+        use crate::db::recurring_rule_repository;
 
-            let sql = format!(
-                "UPDATE recurring_rules SET {} WHERE id = ${}",
-                updates.join(", "),
-                param_count
-            );
-
-            let mut query = sqlx::query(&sql);
+        let artist_id = authorize_artist(&token, TeamPermission::Calendar).await?;
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to update recurring rule: {}", e));
 
-            if let Some(n) = name {
-                query = query.bind(n);
-            }
-            if let Some(p) = pattern {
-                query = query.bind(p);
-            }
-            if let Some(a) = action {
-                query = query.bind(a);
-            }
-            if let Some(st) = start_time {
-                query = query.bind(st);
-            }
-            if let Some(et) = end_time {
-                query = query.bind(et);
-            }
-            if let Some(act) = active {
-                query = query.bind(act);
-            }
-            query = query.bind(id);
+        let Some(mut rule) = recurring_rule_repository::get_rule(artist_id, update.id)
+            .await
+            .map_err(db_error)?
+        else {
+            return Err(ServerFnError::new("Recurring rule not found".to_string()));
+        };
 
-            query.execute(pool).await?;
-            Ok(())
+        if let Some(name) = update.name {
+            rule.name = name.trim().to_string();
         }
-
-        match update_rule(id, name, pattern, action, start_time, end_time, active).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to update recurring rule: {}",
-                e
-            ))),
+        if let Some(pattern) = update.pattern {
+            rule.pattern = pattern;
         }
+        if let Some(action) = update.action {
+            rule.action = action;
+        }
+        if let Some(start_time) = update.start_time {
+            rule.start_time = Some(start_time).filter(|t| !t.trim().is_empty());
+        }
+        if let Some(end_time) = update.end_time {
+            rule.end_time = Some(end_time).filter(|t| !t.trim().is_empty());
+        }
+        if let Some(active) = update.active {
+            rule.active = active;
+        }
+        validate_recurring_rule(
+            &rule.name,
+            &rule.pattern,
+            &rule.action,
+            rule.start_time.as_deref(),
+            rule.end_time.as_deref(),
+        )?;
+
+        recurring_rule_repository::save_rule(&rule)
+            .await
+            .map_err(db_error)?;
+        Ok(())
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -1710,101 +2282,13 @@ pub async fn get_effective_availability(
 ) -> Result<bool, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use chrono::{Datelike, NaiveDate, Weekday};
-        use sqlx::Row;
-
-        async fn check_effective_availability(
-            artist_id: i32,
-            date: String,
-        ) -> Result<bool, sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
-
-            // First check if there's an explicit availability record for this date
-            let explicit_availability: Option<bool> = sqlx::query(
-                "SELECT is_available FROM availability_slots
-                WHERE artist_id = $1 AND specific_date = $2",
-            )
-            .bind(artist_id)
-            .bind(&date)
-            .fetch_optional(pool)
-            .await?
-            .map(|row| row.get("is_available"));
-
-            if let Some(is_available) = explicit_availability {
-                return Ok(is_available);
-            }
-
-            // No explicit record, check recurring rules
-            let parsed_date = match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
-                Ok(d) => d,
-                Err(_) => return Ok(true), // Default to available if date parsing fails
-            };
-
-            // Get all active recurring rules for this artist
-            let rules = sqlx::query(
-                "SELECT rule_type, pattern, action FROM recurring_rules
-                WHERE artist_id = $1 AND active = true",
-            )
-            .bind(artist_id)
-            .fetch_all(pool)
-            .await?;
-
-            for row in rules {
-                let rule_type: String = row.get("rule_type");
-                let pattern: String = row.get("pattern");
-                let action: String = row.get("action");
-
-                let matches = match rule_type.as_str() {
-                    "weekdays" => {
-                        let weekday_num = match parsed_date.weekday() {
-                            Weekday::Sun => 0,
-                            Weekday::Mon => 1,
-                            Weekday::Tue => 2,
-                            Weekday::Wed => 3,
-                            Weekday::Thu => 4,
-                            Weekday::Fri => 5,
-                            Weekday::Sat => 6,
-                        };
-                        let weekday_name = match weekday_num {
-                            0 => "Sunday",
-                            1 => "Monday",
-                            2 => "Tuesday",
-                            3 => "Wednesday",
-                            4 => "Thursday",
-                            5 => "Friday",
-                            6 => "Saturday",
-                            _ => "",
-                        };
-                        pattern.contains(weekday_name)
-                    }
-                    "dates" => {
-                        let month_day = parsed_date.format("%B %e").to_string().trim().to_string();
-                        pattern.contains(&month_day)
-                    }
-                    "monthly" => {
-                        // Simple pattern matching for common monthly patterns
-                        if pattern.contains("1st") && parsed_date.day() <= 7 {
-                            let first_weekday_of_month = parsed_date.with_day(1).unwrap().weekday();
-                            let target_day = parsed_date.weekday();
-                            (parsed_date.day() - 1) / 7 == 0 && first_weekday_of_month == target_day
-                        } else {
-                            false // More complex patterns would need more parsing
-                        }
-                    }
-                    _ => false,
-                };
-
-                if matches {
-                    return Ok(action == "available");
-                }
-            }
-
-            // Default to available if no rules match
-            Ok(true)
-        }
+        // Default to available if the date can't be parsed
+        let Some(date) = availability::parse_date(&date) else {
+            return Ok(true);
+        };
 
-        match check_effective_availability(artist_id, date).await {
-            Ok(is_available) => Ok(is_available),
+        match crate::db::availability_repository::load_schedule(artist_id, date, date).await {
+            Ok(schedule) => Ok(schedule.effective_availability(date)),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to check effective availability: {}",
                 e
@@ -1817,30 +2301,16 @@ pub async fn get_effective_availability(
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn delete_recurring_rule(rule_id: i32) -> Result<(), ServerFnError> {
+pub async fn delete_recurring_rule(rule_id: i32, token: String) -> Result<(), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
-
-        async fn delete_rule(rule_id: i32) -> Result<(), sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
-
-            sqlx::query("DELETE FROM recurring_rules WHERE id = $1")
-                .bind(rule_id)
-                .execute(pool)
-                .await?;
-            Ok(())
-        }
+        let artist_id = authorize_artist(&token, TeamPermission::Calendar).await?;
 
-        match delete_rule(rule_id).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to delete recurring rule: {}",
-                e
-            ))),
-        }
+        crate::db::recurring_rule_repository::delete_rule(artist_id, rule_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to delete recurring rule: {}", e)))
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -1848,13 +2318,18 @@ pub async fn delete_recurring_rule(rule_id: i32) -> Result<(), ServerFnError> {
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn get_booking_request_by_id(booking_id: i32) -> Result<BookingRequest, ServerFnError> {
+pub async fn get_booking_request_by_id(
+    booking_id: i32,
+    token: String,
+) -> Result<BookingRequest, ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
         use sqlx::Row;
 
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+
         async fn query_booking_by_id(booking_id: i32) -> Result<BookingRequest, sqlx::Error> {
             let pool = crate::db::pool::get_pool();
 
@@ -1863,7 +2338,11 @@ pub async fn get_booking_request_by_id(booking_id: i32) -> Result<BookingRequest
                        requested_date, requested_start_time, requested_end_time,
                        tattoo_description, placement, size_inches, reference_images,
                        message_from_client, status, artist_response, estimated_price,
-                       created_at, updated_at, decline_reason
+                       created_at, updated_at, decline_reason, deposit_amount, deposit_status,
+                       (SELECT l.name FROM guest_spots g JOIN locations l ON l.id = g.location_id
+                        WHERE g.id = booking_requests.guest_spot_id) as guest_spot_shop,
+                       (SELECT f.title FROM flash_designs f
+                        WHERE f.id = booking_requests.flash_design_id) as flash_design_title
                 FROM booking_requests
                 WHERE id = $1",
             )
@@ -1891,12 +2370,21 @@ pub async fn get_booking_request_by_id(booking_id: i32) -> Result<BookingRequest
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
                 decline_reason: row.get("decline_reason"),
+                deposit_amount: row.get("deposit_amount"),
+                deposit_status: row.get("deposit_status"),
+                guest_spot_shop: row.get("guest_spot_shop"),
+                flash_design_title: row.get("flash_design_title"),
             })
         }
 
         match query_booking_by_id(booking_id).await {
-            Ok(booking) => Ok(booking),
-            Err(e) => Err(ServerFnError::new(format!("Failed to get booking: {}", e))),
+            Ok(booking) => {
+                // Only the first view makes it onto the timeline
+                record_booking_event(booking_id, "artist", BookingEventKind::Viewed).await;
+                Ok(booking)
+            }
+            Err(sqlx::Error::RowNotFound) => Err(ApiError::not_found("Booking not found").into()),
+            Err(e) => Err(ApiError::internal("Failed to get booking", e).into()),
         }
     }
     #[cfg(not(feature = "ssr"))]
@@ -1950,15 +2438,37 @@ pub async fn get_business_hours(
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn update_business_hours(
     hours: Vec<crate::db::entities::UpdateBusinessHours>,
+    token: String,
 ) -> Result<(), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
         use sqlx::Row;
 
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        if hours.iter().any(|hour| hour.artist_id != artist_id) {
+            return Err(ServerFnError::new(
+                "Unauthorized: not your account".to_string(),
+            ));
+        }
+
+        let week = hours
+            .iter()
+            .map(|hour| availability::BusinessHours {
+                day_of_week: u32::try_from(hour.day_of_week).unwrap_or(u32::MAX),
+                start: hour
+                    .start_time
+                    .as_deref()
+                    .and_then(availability::parse_time),
+                end: hour.end_time.as_deref().and_then(availability::parse_time),
+                is_closed: hour.is_closed,
+            })
+            .collect::<Vec<_>>();
+        availability::validate_week(&week).map_err(ServerFnError::new)?;
+
         let pool = crate::db::pool::get_pool();
 
         for hour in hours {
@@ -1986,6 +2496,50 @@ pub async fn update_business_hours(
     }
 }
 
+/// Session length, time between offered start times and buffer between
+/// bookings the artist's time slots are worked out with
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[server]
+pub async fn get_appointment_settings(
+    artist_id: i32,
+) -> Result<crate::db::entities::AppointmentSettings, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::availability_repository::get_appointment_settings(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load appointment settings: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn update_appointment_settings(
+    settings: crate::db::entities::AppointmentSettings,
+    token: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::availability_repository::{save_appointment_settings, slot_settings};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        slot_settings(&settings)
+            .validate()
+            .map_err(ServerFnError::new)?;
+
+        save_appointment_settings(artist_id, &settings)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save appointment settings: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BookingHistoryEntry {
     pub id: i32,
@@ -1994,16 +2548,20 @@ pub struct BookingHistoryEntry {
     pub created_at: String,
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn get_client_booking_history(
     client_email: String,
+    token: String,
 ) -> Result<Vec<BookingHistoryEntry>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
         use sqlx::Row;
 
+        let artist_id = authorize_artist(&token, TeamPermission::Bookings).await?;
+
         async fn query_client_history(
+            artist_id: i32,
             client_email: String,
         ) -> Result<Vec<BookingHistoryEntry>, sqlx::Error> {
             let pool = crate::db::pool::get_pool();
@@ -2011,11 +2569,12 @@ pub async fn get_client_booking_history(
             let rows = sqlx::query(
                 "SELECT id, requested_date, status, created_at
                 FROM booking_requests
-                WHERE client_email = $1
+                WHERE client_email = $1 AND artist_id = $2
                 ORDER BY created_at DESC
                 LIMIT 10",
             )
             .bind(&client_email)
+            .bind(artist_id)
             .fetch_all(pool)
             .await?;
 
@@ -2034,7 +2593,7 @@ pub async fn get_client_booking_history(
             Ok(history)
         }
 
-        match query_client_history(client_email).await {
+        match query_client_history(artist_id, client_email).await {
             Ok(history) => Ok(history),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to get client history: {}",
@@ -2051,43 +2610,39 @@ pub async fn get_client_booking_history(
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct BookingSuggestion {
     pub booking_id: i32,
-    pub suggested_date: String,
-    pub suggested_start_time: String,
-    pub suggested_end_time: Option<String>,
+    pub suggested_date: Date,
+    pub suggested_start_time: Time,
+    pub suggested_end_time: Option<Time>,
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+/// Suggests another time for a booking. The client is emailed a link to
+/// accept it, decline it or counter (see `server_reschedule`).
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "debug"))]
 #[server]
-pub async fn suggest_booking_time(suggestion: BookingSuggestion) -> Result<(), ServerFnError> {
+pub async fn suggest_booking_time(
+    suggestion: BookingSuggestion,
+    token: String,
+) -> Result<(), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
-
-        async fn update_suggested_time(suggestion: BookingSuggestion) -> Result<(), sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
-
-            sqlx::query(
-                "UPDATE booking_requests
-                SET suggested_date = $1, suggested_start_time = $2, suggested_end_time = $3, updated_at = CURRENT_TIMESTAMP
-                WHERE id = $4"
-            )
-            .bind(suggestion.suggested_date)
-            .bind(suggestion.suggested_start_time)
-            .bind(suggestion.suggested_end_time)
-            .bind(suggestion.booking_id)
-            .execute(pool)
-            .await?;
+        authorize_booking(&token, suggestion.booking_id, TeamPermission::Bookings).await?;
 
-            Ok(())
-        }
+        let booking_id = suggestion.booking_id;
+        let proposal = crate::db::reschedule_repository::propose_time(
+            booking_id,
+            "artist",
+            suggestion.suggested_date,
+            suggestion.suggested_start_time,
+            suggestion.suggested_end_time,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to suggest booking time: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("This booking isn't going ahead any more".to_string()))?;
 
-        match update_suggested_time(suggestion).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to suggest booking time: {}",
-                e
-            ))),
-        }
+        tokio::spawn(async move {
+            crate::server_reschedule::notify_client_of_suggestion(booking_id, &proposal).await;
+        });
+        Ok(())
     }
     #[cfg(not(feature = "ssr"))]
     {
@@ -2104,29 +2659,150 @@ pub struct NewBookingRequest {
     pub tattoo_description: Option<String>,
     pub placement: Option<String>,
     pub size_inches: Option<f32>,
-    pub requested_date: String,
-    pub requested_start_time: String,
-    pub requested_end_time: Option<String>,
+    pub requested_date: Date,
+    pub requested_start_time: Time,
+    pub requested_end_time: Option<Time>,
     pub message_from_client: Option<String>,
+    /// Skip duplicate detection, for clients who really do want a second request
+    #[serde(default)]
+    pub allow_duplicate: bool,
+    /// Completed `reference` uploads (see `uploads.rs`) to attach
+    #[serde(default)]
+    pub reference_upload_ids: Vec<String>,
+    /// One of `utils::auto_response::BOOKING_TYPES`; custom when left out
+    #[serde(default)]
+    pub booking_type: Option<String>,
+    /// The artist's flash design the client is claiming
+    #[serde(default)]
+    pub flash_design_id: Option<i32>,
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
-#[server]
-pub async fn submit_booking_request(request: NewBookingRequest) -> Result<i32, ServerFnError> {
+/// Requests for the same artist from the same email within this many days of
+/// each other's date, created in the last 24 hours, count as duplicates when
+/// their details are this similar (pg_trgm similarity)
+#[cfg(feature = "ssr")]
+const DUPLICATE_BOOKING_DATE_WINDOW_DAYS: i32 = 3;
+#[cfg(feature = "ssr")]
+const DUPLICATE_BOOKING_SIMILARITY: f32 = 0.6;
+
+/// Creates a booking request and returns its id. A resubmission of a recent
+/// open request returns the existing id instead, unless `allow_duplicate` is set.
+/// Signed-in clients pass their token so the request shows on their dashboard;
+/// a name or email left blank is then taken from their account.
+/// A request for a date the artist is on a guest spot is tagged with it.
+/// A request claiming a flash design is a flash booking; a one-off design
+/// can only be claimed while no other open request has it.
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server(prefix = "/api", endpoint = "submit_booking_request")]
+pub async fn submit_booking_request(
+    request: NewBookingRequest,
+    token: Option<String>,
+) -> Result<i32, ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
+        use crate::utils::auto_response::{BOOKING_TYPES, DEFAULT_BOOKING_TYPE};
         use sqlx::Row;
 
-        async fn insert_booking_request(request: NewBookingRequest) -> Result<i32, sqlx::Error> {
+        /// The request's id, and whether its auto-response is due right away;
+        /// `None` when the flash design it claims isn't available
+        async fn insert_booking_request(
+            request: NewBookingRequest,
+            client_user_id: Option<i64>,
+        ) -> Result<Option<(i32, bool)>, sqlx::Error> {
             let pool = crate::db::pool::get_pool();
+            let mut tx = pool.begin().await?;
+            let reference_upload_ids = request.reference_upload_ids.clone();
+
+            let description = [
+                request.tattoo_description.as_deref(),
+                request.placement.as_deref(),
+                request.message_from_client.as_deref(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+            if !request.allow_duplicate {
+                // Serialize submissions per client and artist so a double submit
+                // can't race past the duplicate check
+                sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+                    .bind(format!(
+                        "booking:{}:{}",
+                        request.artist_id,
+                        request.client_email.to_lowercase()
+                    ))
+                    .execute(&mut *tx)
+                    .await?;
+
+                let existing = sqlx::query(
+                    "SELECT id
+                     FROM booking_requests
+                     WHERE artist_id = $1
+                       AND LOWER(client_email) = LOWER($2)
+                       AND status IN ('pending', 'needs_info', 'approved', 'confirmed')
+                       AND created_at::timestamp >= CURRENT_TIMESTAMP - INTERVAL '24 hours'
+                       AND ABS(requested_date::date - $3::date) <= $4
+                       AND (
+                           similarity(
+                               CONCAT_WS(' ', NULLIF(tattoo_description, ''), NULLIF(placement, ''),
+                                         NULLIF(message_from_client, '')),
+                               $5
+                           ) >= $6
+                           OR (
+                               CONCAT_WS(' ', NULLIF(tattoo_description, ''), NULLIF(placement, ''),
+                                         NULLIF(message_from_client, '')) = ''
+                               AND $5 = ''
+                               AND requested_start_time = $7
+                           )
+                       )
+                     ORDER BY created_at DESC
+                     LIMIT 1",
+                )
+                .bind(request.artist_id)
+                .bind(&request.client_email)
+                .bind(request.requested_date.to_string())
+                .bind(DUPLICATE_BOOKING_DATE_WINDOW_DAYS)
+                .bind(&description)
+                .bind(DUPLICATE_BOOKING_SIMILARITY)
+                .bind(request.requested_start_time.to_string())
+                .fetch_optional(&mut *tx)
+                .await?;
+
+                if let Some(existing) = existing {
+                    let booking_id: i32 = existing.get("id");
+                    tracing::info!(
+                        booking_id,
+                        artist_id = request.artist_id,
+                        "Duplicate booking request, returning existing"
+                    );
+                    return Ok(Some((booking_id, false)));
+                }
+            }
+
+            if let Some(flash_design_id) = request.flash_design_id {
+                let claimable = crate::db::flash_design_repository::claim_in(
+                    &mut tx,
+                    request.artist_id,
+                    flash_design_id,
+                )
+                .await?;
+                if !claimable {
+                    return Ok(None);
+                }
+            }
 
             let row = sqlx::query(
                 "INSERT INTO booking_requests (
                     artist_id, client_name, client_email, client_phone,
                     tattoo_description, placement, size_inches,
                     requested_date, requested_start_time, requested_end_time,
-                    message_from_client, status, created_at
-                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 'pending', CURRENT_TIMESTAMP)
+                    message_from_client, status, created_at, client_user_id, booking_type,
+                    guest_spot_id, flash_design_id
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 'pending', CURRENT_TIMESTAMP, $12, $13,
+                    (SELECT id FROM guest_spots
+                     WHERE artist_id = $1 AND $8::date BETWEEN start_date AND end_date
+                     LIMIT 1), $14)
                 RETURNING id"
             )
             .bind(request.artist_id)
@@ -2136,22 +2812,112 @@ pub async fn submit_booking_request(request: NewBookingRequest) -> Result<i32, S
             .bind(request.tattoo_description.unwrap_or_else(|| "".to_string()))
             .bind(request.placement.unwrap_or_else(|| "".to_string()))
             .bind(request.size_inches)
-            .bind(request.requested_date)
-            .bind(request.requested_start_time)
-            .bind(request.requested_end_time.unwrap_or_else(|| "".to_string()))
+            .bind(request.requested_date.to_string())
+            .bind(request.requested_start_time.to_string())
+            .bind(
+                request
+                    .requested_end_time
+                    .map(|time| time.to_string())
+                    .unwrap_or_default(),
+            )
             .bind(request.message_from_client.unwrap_or_else(|| "".to_string()))
-            .fetch_one(pool)
+            .bind(client_user_id)
+            .bind(request.booking_type.as_deref().unwrap_or(DEFAULT_BOOKING_TYPE))
+            .bind(request.flash_design_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            let booking_id: i32 = row.get("id");
+
+            crate::db::upload_repository::attach_reference_uploads(
+                &mut tx,
+                booking_id,
+                &reference_upload_ids,
+            )
             .await?;
 
-            Ok(row.get("id"))
+            crate::db::booking_event_repository::record_event_in(
+                &mut tx,
+                booking_id,
+                "client",
+                &BookingEventKind::Created,
+            )
+            .await?;
+            crate::db::booking_status_repository::record_change_in(
+                &mut tx,
+                booking_id,
+                None,
+                BookingStatus::Pending,
+                "client",
+                client_user_id,
+                None,
+            )
+            .await?;
+
+            let auto_response_delay =
+                crate::db::auto_response_repository::schedule_in(&mut tx, booking_id).await?;
+
+            tx.commit().await?;
+
+            Ok(Some((booking_id, auto_response_delay == Some(0))))
         }
 
-        match insert_booking_request(request).await {
-            Ok(booking_id) => Ok(booking_id),
-            Err(e) => Err(ServerFnError::new(format!(
-                "Failed to submit booking request: {}",
-                e
-            ))),
+        let mut request = request;
+        let client_user_id = match token.as_deref().filter(|token| !token.is_empty()) {
+            Some(token) => Some(
+                extract_user_id_from_token(token).ok_or_else(|| {
+                    ApiError::unauthorized("Session expired, please log in again")
+                })?,
+            ),
+            None => None,
+        };
+        if let Some(user_id) = client_user_id {
+            let account = crate::db::client_dashboard_repository::get_client_identity(user_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load account", e))?
+                .ok_or_else(|| ApiError::not_found("Account not found"))?;
+            if request.client_name.trim().is_empty() {
+                request.client_name = account.name;
+            }
+            if request.client_email.trim().is_empty() {
+                request.client_email = account.email;
+            }
+            if request.client_phone.is_none() {
+                request.client_phone = account.phone;
+            }
+        }
+        if request.client_email.trim().is_empty() {
+            return Err(ApiError::validation(
+                "client_email",
+                "Enter an email so the artist can reach you",
+            )
+            .into());
+        }
+        if request.flash_design_id.is_some() && request.booking_type.is_none() {
+            request.booking_type = Some("flash".to_string());
+        }
+        if let Some(booking_type) = request.booking_type.as_deref() {
+            if !BOOKING_TYPES.iter().any(|(value, _)| *value == booking_type) {
+                return Err(ApiError::validation(
+                    "booking_type",
+                    format!("Unknown booking type: {}", booking_type),
+                )
+                .into());
+            }
+        }
+        crate::rate_limit::check_booking_email(&request.client_email).await?;
+
+        let artist_id = request.artist_id;
+        match insert_booking_request(request, client_user_id).await {
+            Ok(Some((booking_id, auto_response_due))) => {
+                crate::hooks::booking_created(crate::hooks::BookingCreated {
+                    booking_id,
+                    artist_id,
+                    auto_response_due,
+                });
+                Ok(booking_id)
+            }
+            Ok(None) => Err(ApiError::conflict("That flash design isn't available anymore").into()),
+            Err(e) => Err(ApiError::internal("Failed to submit booking request", e).into()),
         }
     }
     #[cfg(not(feature = "ssr"))]
@@ -2164,12 +2930,12 @@ pub async fn submit_booking_request(request: NewBookingRequest) -> Result<i32, S
 #[server]
 pub async fn check_availability(
     artist_id: i32,
-    requested_date: String,
-    requested_time: String,
+    requested_date: Date,
+    requested_time: Time,
 ) -> Result<bool, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        match check_artist_availability(artist_id, &requested_date, &requested_time).await {
+        match check_artist_availability(artist_id, requested_date, requested_time).await {
             Ok(is_available) => Ok(is_available),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to check availability: {}",
@@ -2192,7 +2958,10 @@ use crate::views::auth::{LoginData, SignupData};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub success: bool,
+    /// Short-lived access token sent with requests
     pub token: Option<String>,
+    /// Renews the access token through `refresh_session`
+    pub refresh_token: Option<String>,
     pub user_type: Option<String>,
     pub user_id: Option<i64>,
     pub error: Option<String>,
@@ -2208,79 +2977,58 @@ pub struct UserInfo {
 }
 
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
-#[server]
-pub async fn login_user(login_data: LoginData) -> Result<AuthResponse, ServerFnError> {
+#[server(prefix = "/api", endpoint = "login_user")]
+pub async fn login_user(login_data: LoginData) -> Result<AuthResponse, ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
         use bcrypt::verify;
-        use jsonwebtoken::{encode, EncodingKey, Header};
         use sqlx::Row;
 
-        #[derive(Debug, Serialize, Deserialize)]
-        struct Claims {
-            sub: String,       // User ID
-            exp: usize,        // Expiration time
-            user_type: String, // "client", "artist", or "admin" (for backwards compatibility)
-            user_id: i64,
-        }
-
         let pool = crate::db::pool::get_pool();
+        let email = login_data.email.as_str();
+        // Every failure counts towards locking the email out, unknown or not
+        let invalid = move || async move {
+            crate::rate_limit::record_failed_login(
+                email,
+                ApiError::unauthorized("Invalid email or password"),
+            )
+            .await
+        };
+
+        crate::rate_limit::check_login_lockout(email).await?;
 
         // Query unified users table
-        let result = sqlx::query(
-            "SELECT id, password_hash, first_name, last_name, role::text as role
+        let Some(row) = sqlx::query(
+            "SELECT id, password_hash, role::text as role
              FROM users
              WHERE email = $1 AND is_active = true",
         )
-        .bind(&login_data.email)
+        .bind(email)
         .fetch_optional(pool)
         .await
-        .map_err(|e| ServerFnError::new(format!("Database error: {}", e)))?;
-
-        let (user_id, stored_password_hash, _first_name, _last_name, role) = match result {
-            Some(row) => (
-                row.get::<i64, _>("id"),
-                row.get::<String, _>("password_hash"),
-                row.get::<String, _>("first_name"),
-                row.get::<String, _>("last_name"),
-                row.get::<String, _>("role"),
-            ),
-            None => {
-                return Ok(AuthResponse {
-                    success: false,
-                    token: None,
-                    user_type: None,
-                    user_id: None,
-                    error: Some("Invalid email or password".to_string()),
-                })
-            }
+        .map_err(ApiError::from)?
+        else {
+            return Err(invalid().await.into());
         };
 
+        let user_id: i64 = row.get("id");
+        let stored_password_hash: String = row.get("password_hash");
+        let role: String = row.get("role");
+
         // Verify the user_type matches the role in database
         // Allow admins to log in regardless of which option they select
         if role != "admin" && role != login_data.user_type {
-            return Ok(AuthResponse {
-                success: false,
-                token: None,
-                user_type: None,
-                user_id: None,
-                error: Some("Invalid email or password".to_string()),
-            });
+            return Err(invalid().await.into());
         }
 
         // Verify password
         let password_valid = verify(&login_data.password, &stored_password_hash)
-            .map_err(|e| ServerFnError::new(format!("Password verification error: {}", e)))?;
+            .map_err(|e| ApiError::internal("Password verification error", e))?;
 
         if !password_valid {
-            return Ok(AuthResponse {
-                success: false,
-                token: None,
-                user_type: None,
-                user_id: None,
-                error: Some("Invalid email or password".to_string()),
-            });
+            return Err(invalid().await.into());
         }
+        crate::rate_limit::clear_failed_logins(email).await;
 
         // Update last_login
         let _ = sqlx::query("UPDATE users SET last_login = CURRENT_TIMESTAMP WHERE id = $1")
@@ -2288,31 +3036,14 @@ pub async fn login_user(login_data: LoginData) -> Result<AuthResponse, ServerFnE
             .execute(pool)
             .await;
 
-        // Create JWT token
-        let expiration = chrono::Utc::now()
-            .checked_add_signed(chrono::Duration::days(7))
-            .expect("valid timestamp")
-            .timestamp() as usize;
-
-        let claims = Claims {
-            sub: user_id.to_string(),
-            exp: expiration,
-            user_type: role.clone(),
-            user_id,
-        };
-
-        // Use a simple secret for now - in production this should be from environment
-        let secret = "tatteau-jwt-secret-key-change-in-production";
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(secret.as_ref()),
-        )
-        .map_err(|e| ServerFnError::new(format!("Token generation error: {}", e)))?;
+        let session = crate::auth::start_session(user_id, &role)
+            .await
+            .map_err(|e| ApiError::internal("Token generation error", e))?;
 
         Ok(AuthResponse {
             success: true,
-            token: Some(token),
+            token: Some(session.access_token),
+            refresh_token: Some(session.refresh_token),
             user_type: Some(role),
             user_id: Some(user_id),
             error: None,
@@ -2320,32 +3051,48 @@ pub async fn login_user(login_data: LoginData) -> Result<AuthResponse, ServerFnE
     }
     #[cfg(not(feature = "ssr"))]
     {
-        Ok(AuthResponse {
-            success: false,
-            token: None,
-            user_type: None,
-            user_id: None,
-            error: Some("Server-side only".to_string()),
-        })
+        Err(ServerFnError::new("Not available on client".to_string()))
     }
 }
 
+/// Checks a signup form, naming the first field that needs fixing
+#[cfg(feature = "ssr")]
+fn validate_signup(signup_data: &SignupData) -> Result<(), ApiError> {
+    if signup_data.first_name.trim().is_empty() {
+        return Err(ApiError::validation("first_name", "Enter your first name"));
+    }
+    if signup_data.last_name.trim().is_empty() {
+        return Err(ApiError::validation("last_name", "Enter your last name"));
+    }
+    let email = signup_data.email.trim();
+    match email.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && domain.contains('.') => {}
+        _ => return Err(ApiError::validation("email", "Enter a valid email address")),
+    }
+    if signup_data.password.len() < 8 {
+        return Err(ApiError::validation(
+            "password",
+            "Use at least 8 characters for your password",
+        ));
+    }
+    if signup_data.user_type != "client" && signup_data.user_type != "artist" {
+        return Err(ApiError::validation(
+            "user_type",
+            "Choose a client or artist account",
+        ));
+    }
+    Ok(())
+}
+
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
-#[server]
-pub async fn signup_user(signup_data: SignupData) -> Result<AuthResponse, ServerFnError> {
+#[server(prefix = "/api", endpoint = "signup_user")]
+pub async fn signup_user(signup_data: SignupData) -> Result<AuthResponse, ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
         use bcrypt::{hash, DEFAULT_COST};
-        use jsonwebtoken::{encode, EncodingKey, Header};
         use sqlx::Row;
 
-        #[derive(Debug, Serialize, Deserialize)]
-        struct Claims {
-            sub: String,
-            exp: usize,
-            user_type: String,
-            user_id: i64,
-        }
+        validate_signup(&signup_data)?;
 
         let pool = crate::db::pool::get_pool();
 
@@ -2354,21 +3101,15 @@ pub async fn signup_user(signup_data: SignupData) -> Result<AuthResponse, Server
             .bind(&signup_data.email)
             .fetch_one(pool)
             .await
-            .map_err(|e| ServerFnError::new(format!("Database query error: {}", e)))?;
+            .map_err(ApiError::from)?;
 
         if user_count > 0 {
-            return Ok(AuthResponse {
-                success: false,
-                token: None,
-                user_type: None,
-                user_id: None,
-                error: Some("Email already exists".to_string()),
-            });
+            return Err(ApiError::conflict("An account with this email already exists").into());
         }
 
         // Hash password
         let password_hash = hash(&signup_data.password, DEFAULT_COST)
-            .map_err(|e| ServerFnError::new(format!("Password hashing error: {}", e)))?;
+            .map_err(|e| ApiError::internal("Password hashing error", e))?;
 
         let user_id = if signup_data.user_type == "client" {
             // Insert into users table with client role
@@ -2384,7 +3125,7 @@ pub async fn signup_user(signup_data: SignupData) -> Result<AuthResponse, Server
             .bind(&password_hash)
             .fetch_one(pool)
             .await
-            .map_err(|e| ServerFnError::new(format!("Failed to create user account: {}", e)))?;
+            .map_err(|e| ApiError::internal("Failed to create user account", e))?;
 
             row.get::<i64, _>("id")
         } else {
@@ -2405,7 +3146,7 @@ pub async fn signup_user(signup_data: SignupData) -> Result<AuthResponse, Server
             .bind("pending_onboarding")
             .fetch_one(pool)
             .await
-            .map_err(|e| ServerFnError::new(format!("Failed to create artist record: {}", e)))?;
+            .map_err(|e| ApiError::internal("Failed to create artist record", e))?;
 
             let artist_id: i64 = artist_row.get("id");
 
@@ -2423,35 +3164,19 @@ pub async fn signup_user(signup_data: SignupData) -> Result<AuthResponse, Server
             .bind(artist_id)
             .fetch_one(pool)
             .await
-            .map_err(|e| ServerFnError::new(format!("Failed to create artist user account: {}", e)))?;
+            .map_err(|e| ApiError::internal("Failed to create artist user account", e))?;
 
             user_row.get::<i64, _>("id")
         };
 
-        // Create JWT token
-        let expiration = chrono::Utc::now()
-            .checked_add_signed(chrono::Duration::days(7))
-            .expect("valid timestamp")
-            .timestamp() as usize;
-
-        let claims = Claims {
-            sub: user_id.to_string(),
-            exp: expiration,
-            user_type: signup_data.user_type.clone(),
-            user_id,
-        };
-
-        let secret = "tatteau-jwt-secret-key-change-in-production";
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(secret.as_ref()),
-        )
-        .map_err(|e| ServerFnError::new(format!("Token generation error: {}", e)))?;
+        let session = crate::auth::start_session(user_id, &signup_data.user_type)
+            .await
+            .map_err(|e| ApiError::internal("Token generation error", e))?;
 
         Ok(AuthResponse {
             success: true,
-            token: Some(token),
+            token: Some(session.access_token),
+            refresh_token: Some(session.refresh_token),
             user_type: Some(signup_data.user_type),
             user_id: Some(user_id),
             error: None,
@@ -2459,70 +3184,100 @@ pub async fn signup_user(signup_data: SignupData) -> Result<AuthResponse, Server
     }
     #[cfg(not(feature = "ssr"))]
     {
-        Ok(AuthResponse {
-            success: false,
-            token: None,
-            user_type: None,
-            user_id: None,
-            error: Some("Server-side only".to_string()),
-        })
+        Err(ServerFnError::new("Not available on client".to_string()))
     }
 }
 
 #[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
-pub async fn verify_token(token: String) -> Result<Option<UserInfo>, ServerFnError> {
+pub async fn verify_token(token: String) -> Result<Option<UserInfo>, ServerFnError<ApiError>> {
     #[cfg(feature = "ssr")]
     {
-        use jsonwebtoken::{decode, DecodingKey, Validation};
         use sqlx::Row;
 
-        #[derive(Debug, Serialize, Deserialize)]
-        struct Claims {
-            sub: String,
-            exp: usize,
-            user_type: String,
-            user_id: i64,
-        }
+        let Some(claims) = crate::auth::decode_access_token(&token) else {
+            return Ok(None);
+        };
 
-        let secret = "tatteau-jwt-secret-key-change-in-production";
-        let token_data = decode::<Claims>(
-            &token,
-            &DecodingKey::from_secret(secret.as_ref()),
-            &Validation::default(),
-        );
+        let pool = crate::db::pool::get_pool();
+        let row = sqlx::query(
+            "SELECT id, first_name, last_name, email, role::text as role
+             FROM users
+             WHERE id = $1 AND is_active = true",
+        )
+        .bind(claims.user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
 
-        match token_data {
-            Ok(data) => {
-                let claims = data.claims;
-                let pool = crate::db::pool::get_pool();
+        Ok(row.map(|row| UserInfo {
+            id: row.get("id"),
+            first_name: row.get("first_name"),
+            last_name: row.get("last_name"),
+            email: row.get("email"),
+            user_type: row.get("role"),
+        }))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
 
-                let user_info = sqlx::query(
-                    "SELECT id, first_name, last_name, email, role::text as role
-                     FROM users
-                     WHERE id = $1 AND is_active = true",
-                )
-                .bind(claims.user_id)
-                .fetch_optional(pool)
-                .await
-                .ok()
-                .flatten()
-                .map(|row| UserInfo {
-                    id: row.get("id"),
-                    first_name: row.get("first_name"),
-                    last_name: row.get("last_name"),
-                    email: row.get("email"),
-                    user_type: row.get("role"),
-                });
+/// Exchanges a refresh token for a new access token and refresh token. The
+/// old refresh token stops working; an expired or revoked one is
+/// `ApiError::Unauthorized`.
+#[cfg_attr(feature = "ssr", instrument(skip(refresh_token), err, level = "info"))]
+#[server]
+pub async fn refresh_session(
+    refresh_token: String,
+) -> Result<AuthResponse, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        let session = crate::auth::refresh_session(&refresh_token)
+            .await
+            .map_err(|e| ApiError::internal("Failed to refresh session", e))?
+            .ok_or_else(|| ApiError::unauthorized("Session expired, please log in again"))?;
 
-                Ok(user_info)
-            }
-            Err(_) => Ok(None),
+        Ok(AuthResponse {
+            success: true,
+            token: Some(session.access_token),
+            refresh_token: Some(session.refresh_token),
+            user_type: Some(session.user_type),
+            user_id: Some(session.user_id),
+            error: None,
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Revokes the session's refresh token, or with `all_devices` every refresh
+/// token of its user. Access tokens already issued last until they expire.
+#[cfg_attr(feature = "ssr", instrument(skip(refresh_token), err, level = "info"))]
+#[server]
+pub async fn logout(
+    refresh_token: String,
+    all_devices: bool,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = crate::auth::revoke_refresh_token(&refresh_token)
+            .await
+            .map_err(|e| ApiError::internal("Failed to log out", e))?;
+
+        if let (true, Some(user_id)) = (all_devices, user_id) {
+            crate::auth::revoke_all_sessions(user_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to log out", e))?;
         }
+        Ok(())
     }
     #[cfg(not(feature = "ssr"))]
     {
-        Ok(None)
+        Ok(())
     }
 }
 
@@ -2566,41 +3321,6 @@ pub async fn get_subscription_tiers() -> Result<Vec<SubscriptionTier>, ServerFnE
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
-#[server]
-pub async fn create_artist_subscription(
-    artist_id: i32,
-    tier_id: i32,
-    status: String,
-    payment_method: Option<String>,
-) -> Result<i32, ServerFnError> {
-    #[cfg(feature = "ssr")]
-    {
-        use sqlx::Row;
-
-        let pool = crate::db::pool::get_pool();
-
-        let row = sqlx::query(
-            "INSERT INTO artist_subscriptions (artist_id, tier_id, status, payment_method, subscription_start, next_payment)
-             VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP + INTERVAL '1 month')
-             RETURNING id"
-        )
-        .bind(artist_id)
-        .bind(tier_id)
-        .bind(&status)
-        .bind(&payment_method)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| ServerFnError::new(format!("Subscription creation error: {}", e)))?;
-
-        Ok(row.get("id"))
-    }
-    #[cfg(not(feature = "ssr"))]
-    {
-        Ok(0)
-    }
-}
-
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
 #[server]
 pub async fn get_artist_subscription(
@@ -2740,14 +3460,17 @@ pub async fn get_artist_id_from_jwt_user_id(
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn update_artist_questionnaire_configuration(
     artist_id: i32,
     config: Vec<ArtistQuestionnaire>,
+    token: String,
 ) -> Result<(), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
+        authorize_artist_id(&token, artist_id, TeamPermission::Settings).await?;
+
         match update_artist_questionnaire_config(artist_id, config).await {
             Ok(()) => Ok(()),
             Err(e) => Err(ServerFnError::new(format!("Database error: {}", e))),
@@ -2759,14 +3482,17 @@ pub async fn update_artist_questionnaire_configuration(
     }
 }
 
-#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 #[server]
 pub async fn delete_artist_questionnaire_question(
     artist_id: i32,
     question_id: i32,
+    token: String,
 ) -> Result<(), ServerFnError> {
     #[cfg(feature = "ssr")]
     {
+        authorize_artist_id(&token, artist_id, TeamPermission::Settings).await?;
+
         match delete_artist_question(artist_id, question_id).await {
             Ok(()) => Ok(()),
             Err(e) => Err(ServerFnError::new(format!("Database error: {}", e))),
@@ -2840,9 +3566,11 @@ pub async fn log_client_error(
     user_agent: Option<String>,
     session_id: Option<String>,
     additional_context: Option<String>,
+    build_id: Option<String>,
 ) -> Result<i64, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
+        let symbolicate = build_id.is_some() && error_stack.is_some();
         let error_data = CreateErrorLog {
             error_type,
             error_level,
@@ -2854,10 +3582,23 @@ pub async fn log_client_error(
             session_id,
             request_headers: None, // TODO: Extract from request context
             additional_context,
+            build_id,
         };
 
         match log_error(error_data).await {
-            Ok(id) => Ok(id),
+            Ok(id) => {
+                // Resolve the minified stack in the background so logging stays cheap
+                if symbolicate {
+                    tokio::spawn(async move {
+                        if let Err(e) =
+                            crate::db::source_map_repository::symbolicate_error_log(id).await
+                        {
+                            tracing::warn!("Failed to symbolicate error {}: {}", id, e);
+                        }
+                    });
+                }
+                Ok(id)
+            }
             Err(e) => Err(ServerFnError::new(format!("Failed to log error: {}", e))),
         }
     }
@@ -2941,6 +3682,7 @@ pub async fn log_server_error(
             session_id: None,
             request_headers: None, // TODO: Extract from request context
             additional_context,
+            build_id: None,
         };
 
         match log_error(error_data).await {
@@ -2985,142 +3727,79 @@ pub async fn get_error_logs(
     }
 }
 
+/// Re-runs symbolication for a logged client error, e.g. after its build's
+/// source maps were uploaded late. Returns the readable stack if resolved.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn symbolicate_error_log(
+    error_id: i64,
+    token: String,
+) -> Result<Option<String>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (_user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        // Verify admin role
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        crate::db::source_map_repository::symbolicate_error_log(error_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to symbolicate error: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
 // Booking Availability Server Functions
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct TimeSlot {
-    pub start_time: String,
-    pub end_time: String,
+    pub start_time: Time,
+    pub end_time: Time,
     pub is_available: bool,
 }
 
+/// Dates a client can book between `start_date` and `end_date`. Booking
+/// through one of the artist's guest spots offers only that spot's dates;
+/// otherwise the dates they're away on a guest spot are left out.
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
 #[server]
 pub async fn get_available_dates(
     artist_id: i32,
-    start_date: String,
-    end_date: String,
-) -> Result<Vec<String>, ServerFnError> {
+    start_date: Date,
+    end_date: Date,
+    guest_spot_id: Option<i32>,
+) -> Result<Vec<Date>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
-
-        async fn query_available_dates(
-            artist_id: i32,
-            start_date: String,
-            end_date: String,
-        ) -> Result<Vec<String>, sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
-
-            // Get business hours for this artist
-            let business_hours_rows = sqlx::query(
-                "SELECT day_of_week, start_time, end_time, is_closed
-                 FROM business_hours
-                 WHERE artist_id = $1
-                 ORDER BY day_of_week",
-            )
-            .bind(artist_id)
-            .fetch_all(pool)
-            .await?;
-
-            let mut business_hours = std::collections::HashMap::new();
-            for row in business_hours_rows {
-                let day: i32 = row.get("day_of_week");
-                let start: Option<String> = row.get("start_time");
-                let end: Option<String> = row.get("end_time");
-                let closed: bool = row.get("is_closed");
-                business_hours.insert(day, (start, end, closed));
-            }
+        let (start, end) = (start_date.naive(), end_date.naive());
+        let today = Utc::now().naive_utc().date();
 
-            // Get specific availability overrides
-            let availability_rows = sqlx::query(
-                "SELECT specific_date, is_available
-                 FROM artist_availability
-                 WHERE artist_id = $1
-                   AND specific_date IS NOT NULL
-                   AND specific_date BETWEEN $2 AND $3",
-            )
-            .bind(artist_id)
-            .bind(&start_date)
-            .bind(&end_date)
-            .fetch_all(pool)
-            .await?;
-
-            let mut availability_overrides = std::collections::HashMap::new();
-            for row in availability_rows {
-                let date: String = row.get("specific_date");
-                let available: bool = row.get("is_available");
-                availability_overrides.insert(date, available);
-            }
-
-            // Get existing bookings in date range
-            let booking_rows = sqlx::query(
-                "SELECT requested_date
-                 FROM booking_requests
-                 WHERE artist_id = $1
-                   AND requested_date BETWEEN $2 AND $3
-                   AND status IN ('pending', 'approved')",
-            )
-            .bind(artist_id)
-            .bind(&start_date)
-            .bind(&end_date)
-            .fetch_all(pool)
-            .await?;
-
-            let mut booked_dates = std::collections::HashSet::new();
-            for row in booking_rows {
-                let date: String = row.get("requested_date");
-                booked_dates.insert(date);
-            }
-
-            // Generate available dates
-            let mut available_dates = Vec::new();
-            let start = chrono::NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-            let end = chrono::NaiveDate::parse_from_str(&end_date, "%Y-%m-%d")
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-            let today = Utc::now().naive_utc().date();
-
-            let mut current_date = start;
-            while current_date <= end {
-                let date_str = current_date.format("%Y-%m-%d").to_string();
-
-                // Skip past dates
-                if current_date < today {
-                    current_date += chrono::Duration::days(1);
-                    continue;
-                }
-
-                // Check for specific availability override
-                if let Some(&is_available) = availability_overrides.get(&date_str) {
-                    if is_available && !booked_dates.contains(&date_str) {
-                        available_dates.push(date_str);
-                    }
-                } else {
-                    // Check business hours for this day of week
-                    let day_of_week = current_date.weekday().num_days_from_sunday() as i32;
-
-                    if let Some((start_time, end_time, is_closed)) =
-                        business_hours.get(&day_of_week)
-                    {
-                        if !is_closed
-                            && start_time.is_some()
-                            && end_time.is_some()
-                            && !booked_dates.contains(&date_str)
-                        {
-                            available_dates.push(date_str);
-                        }
-                    }
-                }
-
-                current_date += chrono::Duration::days(1);
-            }
-
-            Ok(available_dates)
-        }
+        let guest_spots =
+            crate::db::guest_spot_repository::get_guest_spot_ranges(artist_id, start, end)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to get guest spots: {}", e)))?;
+        let guest_spot_on = |date: &chrono::NaiveDate| {
+            guest_spots
+                .iter()
+                .find(|(_, first, last)| first <= date && date <= last)
+                .map(|(id, _, _)| *id)
+        };
 
-        match query_available_dates(artist_id, start_date, end_date).await {
-            Ok(dates) => Ok(dates),
+        match crate::db::availability_repository::load_schedule(artist_id, start, end).await {
+            Ok(schedule) => Ok(schedule
+                .available_dates(start, end, today)
+                .into_iter()
+                .filter(|date| guest_spot_on(date) == guest_spot_id)
+                .map(Date::from)
+                .collect()),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to get available dates: {}",
                 e
@@ -3133,105 +3812,33 @@ pub async fn get_available_dates(
     }
 }
 
+/// Appointments a client can pick on `date`, long enough for a tattoo
+/// `size_inches` across when the size is known (see
+/// `availability::SlotSettings::session_minutes_for`)
 #[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
 #[server]
 pub async fn get_available_time_slots(
     artist_id: i32,
-    date: String,
+    date: Date,
+    size_inches: Option<f64>,
 ) -> Result<Vec<TimeSlot>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
-        use sqlx::Row;
-
-        async fn query_time_slots(
-            artist_id: i32,
-            date: String,
-        ) -> Result<Vec<TimeSlot>, sqlx::Error> {
-            let pool = crate::db::pool::get_pool();
-
-            // Parse the date to get day of week
-            let parsed_date = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-                .map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
-            let day_of_week = parsed_date.weekday().num_days_from_sunday() as i32;
-
-            // Get business hours for this day
-            let business_hours: Option<(String, String)> = sqlx::query(
-                "SELECT start_time, end_time
-                 FROM business_hours
-                 WHERE artist_id = $1 AND day_of_week = $2 AND is_closed = false",
-            )
-            .bind(artist_id)
-            .bind(day_of_week)
-            .fetch_optional(pool)
-            .await?
-            .map(|row| (row.get("start_time"), row.get("end_time")));
-
-            if business_hours.is_none() {
-                return Ok(vec![]);
-            }
-
-            let (start_time_str, end_time_str) = business_hours.unwrap();
-
-            // Check for existing bookings on this date
-            let booking_rows = sqlx::query(
-                "SELECT requested_start_time, requested_end_time
-                 FROM booking_requests
-                 WHERE artist_id = $1
-                   AND requested_date = $2
-                   AND status IN ('pending', 'approved')",
-            )
-            .bind(artist_id)
-            .bind(&date)
-            .fetch_all(pool)
-            .await?;
-
-            let mut booked_slots = Vec::new();
-            for row in booking_rows {
-                let start: String = row.get("requested_start_time");
-                let end: Option<String> = row.get("requested_end_time");
-                booked_slots.push((start, end));
-            }
-
-            // Generate time slots (hourly slots from business hours)
-            let mut time_slots = Vec::new();
-
-            // Parse start and end times
-            let start_hour = start_time_str
-                .split(':')
-                .next()
-                .unwrap_or("9")
-                .parse::<u32>()
-                .unwrap_or(9);
-            let end_hour = end_time_str
-                .split(':')
-                .next()
-                .unwrap_or("17")
-                .parse::<u32>()
-                .unwrap_or(17);
-
-            for hour in start_hour..end_hour {
-                let slot_start = format!("{:02}:00", hour);
-                let slot_end = format!("{:02}:00", hour + 1);
-
-                // Check if this slot is booked
-                let is_booked = booked_slots.iter().any(|(booked_start, booked_end)| {
-                    let booked_end_time = booked_end.as_ref().unwrap_or(booked_start);
-                    // Simple overlap check
-                    !(slot_end <= *booked_start || slot_start >= *booked_end_time)
-                });
-
-                time_slots.push(TimeSlot {
-                    start_time: slot_start,
-                    end_time: slot_end,
-                    is_available: !is_booked,
-                });
-            }
-
-            Ok(time_slots)
-        }
+        let date = date.naive();
 
-        match query_time_slots(artist_id, date).await {
-            Ok(slots) => Ok(slots),
+        match crate::db::availability_repository::load_schedule(artist_id, date, date).await {
+            Ok(schedule) => Ok(schedule
+                .time_slots(
+                    date,
+                    schedule.slot_settings.session_minutes_for(size_inches),
+                )
+                .into_iter()
+                .map(|slot| TimeSlot {
+                    start_time: slot.start.into(),
+                    end_time: slot.end.into(),
+                    is_available: slot.available,
+                })
+                .collect()),
             Err(e) => Err(ServerFnError::new(format!(
                 "Failed to get time slots: {}",
                 e
@@ -3250,26 +3857,8 @@ pub async fn get_available_time_slots(
 
 /// Helper function to extract user info from JWT token on server side
 #[cfg(feature = "ssr")]
-fn extract_user_from_token(token: &str) -> Option<(i64, String)> {
-    use jsonwebtoken::{decode, DecodingKey, Validation};
-
-    #[derive(Debug, Serialize, Deserialize)]
-    struct Claims {
-        sub: String,
-        exp: usize,
-        user_type: String,
-        user_id: i64,
-    }
-
-    let secret = "tatteau-jwt-secret-key-change-in-production";
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
-    )
-    .ok()?;
-
-    Some((token_data.claims.user_id, token_data.claims.user_type))
+pub(crate) fn extract_user_from_token(token: &str) -> Option<(i64, String)> {
+    crate::auth::decode_access_token(token).map(|claims| (claims.user_id, claims.user_type))
 }
 
 /// Adds a style tag to an image (admin only)
@@ -3320,6 +3909,12 @@ pub async fn add_style_to_image(
                     .bind(image_id)
                     .execute(pool)
                     .await;
+                crate::hooks::image_tagged(crate::hooks::ImageTagged {
+                    image_id,
+                    style_id,
+                    change: crate::hooks::TagChange::Added,
+                    tagged_by: user_id,
+                });
                 Ok(())
             }
             Err(e) => Err(ServerFnError::new(format!(
@@ -3345,7 +3940,7 @@ pub async fn remove_style_from_image(
     #[cfg(feature = "ssr")]
     {
         // Verify admin role
-        let (_user_id, user_type) = extract_user_from_token(&token)
+        let (user_id, user_type) = extract_user_from_token(&token)
             .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
 
         if user_type != "admin" {
@@ -3373,6 +3968,12 @@ pub async fn remove_style_from_image(
                     .bind(image_id)
                     .execute(pool)
                     .await;
+                crate::hooks::image_tagged(crate::hooks::ImageTagged {
+                    image_id,
+                    style_id,
+                    change: crate::hooks::TagChange::Removed,
+                    tagged_by: user_id,
+                });
                 Ok(())
             }
             Err(e) => Err(ServerFnError::new(format!(
@@ -3500,6 +4101,424 @@ pub async fn get_image_style_metadata(image_id: i64) -> Result<ImageStyleMetadat
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StyleMergePreview {
+    pub source_style_id: i64,
+    pub source_name: String,
+    pub target_style_id: i64,
+    pub target_name: String,
+    pub affected_artists: i32,
+    /// Artists already tagged with both styles; their source tag is dropped
+    pub overlapping_artists: i32,
+    pub affected_images: i32,
+    /// Images already tagged with both styles; their source tag is dropped
+    pub overlapping_images: i32,
+    pub quiz_filter_uses: i32,
+    pub existing_aliases: i32,
+}
+
+/// Previews the impact of merging one style into another (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn preview_style_merge(
+    source_style_id: i64,
+    target_style_id: i64,
+    token: String,
+) -> Result<StyleMergePreview, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        // Verify admin role
+        let (_user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        if source_style_id == target_style_id {
+            return Err(ServerFnError::new(
+                "Cannot merge a style into itself".to_string(),
+            ));
+        }
+
+        crate::db::style_merge_repository::preview_style_merge(source_style_id, target_style_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to preview style merge: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Merges one style into another, keeping the old name as an alias (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn merge_styles(
+    source_style_id: i64,
+    target_style_id: i64,
+    token: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        // Verify admin role
+        let (user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        if source_style_id == target_style_id {
+            return Err(ServerFnError::new(
+                "Cannot merge a style into itself".to_string(),
+            ));
+        }
+
+        crate::db::style_merge_repository::merge_styles(source_style_id, target_style_id, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to merge styles: {}", e)))?;
+
+        crate::db::starter_pack_repository::invalidate_starter_packs();
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IngestionCostSummary {
+    pub day: String,
+    pub source: String,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub api_calls: i64,
+    pub compute_units: f64,
+    pub tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IngestionRunSummary {
+    pub run_id: String,
+    pub action: String,
+    pub started_at: String,
+    pub budget_usd: Option<f64>,
+    /// Cap on what one city may spend
+    pub city_budget_usd: Option<f64>,
+    pub total_cost_usd: f64,
+    pub api_calls: i64,
+    pub over_budget: bool,
+    /// Cities the run gave up on for going over `city_budget_usd`
+    pub aborted_locations: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IngestionCostReport {
+    pub daily: Vec<IngestionCostSummary>,
+    pub recent_runs: Vec<IngestionRunSummary>,
+}
+
+/// Summarizes scraping spend per source per city over time (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn get_ingestion_cost_report(
+    days: i32,
+    token: String,
+) -> Result<IngestionCostReport, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::ingestion_cost_repository::{
+            get_ingestion_cost_summary, get_recent_ingestion_runs,
+        };
+
+        // Verify admin role
+        let (_user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        let daily = get_ingestion_cost_summary(days.clamp(1, 365))
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to fetch ingestion costs: {}", e)))?;
+        let recent_runs = get_recent_ingestion_runs(50)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to fetch ingestion runs: {}", e)))?;
+
+        Ok(IngestionCostReport { daily, recent_runs })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DataQualityCheckSummary {
+    pub check_name: String,
+    pub entity_table: String,
+    pub open_count: i64,
+    pub repairable: bool,
+    pub last_seen_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DataQualityIssue {
+    pub id: i64,
+    pub check_name: String,
+    pub entity_table: String,
+    pub entity_id: i64,
+    pub details: Option<String>,
+    pub repairable: bool,
+    pub detected_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DataQualityReport {
+    pub checks: Vec<DataQualityCheckSummary>,
+    pub issues: Vec<DataQualityIssue>,
+}
+
+/// Open issues found by the INTEGRITY_CHECK ingestion job (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn get_data_quality_report(
+    check_name: Option<String>,
+    token: String,
+) -> Result<DataQualityReport, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::data_quality_repository::{get_open_issue_counts, get_open_issues};
+
+        // Verify admin role
+        let (_user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        let checks = get_open_issue_counts().await.map_err(|e| {
+            ServerFnError::new(format!("Failed to fetch data quality checks: {}", e))
+        })?;
+        let issues = get_open_issues(check_name, 100).await.map_err(|e| {
+            ServerFnError::new(format!("Failed to fetch data quality issues: {}", e))
+        })?;
+
+        Ok(DataQualityReport { checks, issues })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Active search/matching pins (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn list_pinning_rules(
+    token: String,
+) -> Result<Vec<crate::db::entities::PinningRule>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        // Verify admin role
+        let (_user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        crate::db::pinning_repository::list_pinning_rules()
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to fetch pinning rules: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Pins a shop or artist to a fixed position in listings for a city, state or
+/// everywhere, optionally until `expires_at` (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn create_pinning_rule(
+    rule: crate::db::entities::PinningRule,
+    token: String,
+) -> Result<i64, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        // Verify admin role
+        let (user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        if rule.entity_type != "location" && rule.entity_type != "artist" {
+            return Err(ServerFnError::new(
+                "Entity type must be 'location' or 'artist'".to_string(),
+            ));
+        }
+        if rule.position < 1 {
+            return Err(ServerFnError::new(
+                "Position must be 1 or greater".to_string(),
+            ));
+        }
+        if rule.scope_city.is_some() && rule.scope_state.is_none() {
+            return Err(ServerFnError::new(
+                "A city scope also needs a state".to_string(),
+            ));
+        }
+
+        crate::db::pinning_repository::create_pinning_rule(&rule, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to create pinning rule: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn delete_pinning_rule(rule_id: i64, token: String) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        // Verify admin role
+        let (_user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        crate::db::pinning_repository::delete_pinning_rule(rule_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to delete pinning rule: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Replaces the curated quiz examples for a style; an empty list hands the
+/// style back to automatic selection (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn set_style_starter_images(
+    style_id: i64,
+    short_codes: Vec<String>,
+    token: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::starter_pack_repository::{set_curated_images, IMAGES_PER_STYLE};
+
+        // Verify admin role
+        let (user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        let short_codes: Vec<String> = short_codes
+            .into_iter()
+            .map(|code| code.trim().to_string())
+            .filter(|code| !code.is_empty())
+            .collect();
+        if short_codes.len() > IMAGES_PER_STYLE {
+            return Err(ServerFnError::new(format!(
+                "At most {} images can be curated per style",
+                IMAGES_PER_STYLE
+            )));
+        }
+
+        set_curated_images(style_id, &short_codes, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save style examples: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StylePickShare {
+    pub style_id: i64,
+    pub style_name: String,
+    pub picks: i64,
+    /// Picks per session that picked anything
+    pub pick_rate: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuizVariantReport {
+    pub variant: QuizImageVariant,
+    pub sessions: i64,
+    pub exposures: i64,
+    pub artists_shown: i64,
+    /// Share of exposures that went to the most shown tenth of artists
+    pub top_decile_share: f64,
+    pub style_picks: Vec<StylePickShare>,
+}
+
+/// How evenly each quiz image variant spreads exposure across artists and
+/// which styles its sessions go on to pick (admin only)
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+#[server]
+pub async fn get_quiz_image_report(
+    days: i32,
+    token: String,
+) -> Result<Vec<QuizVariantReport>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        // Verify admin role
+        let (_user_id, user_type) = extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        crate::db::starter_pack_repository::get_variant_reports(days.clamp(1, 365))
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to fetch quiz image report: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
 // Admin Post Validation
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PostValidationData {
@@ -3548,7 +4567,7 @@ pub async fn get_non_validated_posts(
         let offset = page * page_size;
 
         // Get total count of non-validated posts
-        let total_count: i64 = sqlx::query("SELECT COUNT(*) as count FROM artists_images WHERE validated = FALSE OR validated IS NULL")
+        let total_count: i64 = sqlx::query("SELECT COUNT(*) as count FROM artists_images WHERE (validated = FALSE OR validated IS NULL) AND removed_at IS NULL")
             .fetch_one(pool)
             .await
             .map_err(|e| ServerFnError::new(format!("Failed to count non-validated posts: {}", e)))?
@@ -3559,7 +4578,7 @@ pub async fn get_non_validated_posts(
             "SELECT ai.id, ai.short_code, ai.artist_id, a.name as artist_name
              FROM artists_images ai
              LEFT JOIN artists a ON ai.artist_id = a.id
-             WHERE ai.validated = FALSE OR ai.validated IS NULL
+             WHERE (ai.validated = FALSE OR ai.validated IS NULL) AND ai.removed_at IS NULL
              ORDER BY ai.id ASC
              LIMIT $1 OFFSET $2"
         )
@@ -3640,7 +4659,7 @@ pub async fn get_unvalidated_posts_for_gallery(
         let offset = page * page_size;
 
         // Get total count
-        let total_count: i64 = sqlx::query("SELECT COUNT(*) as count FROM artists_images WHERE validated = FALSE OR validated IS NULL")
+        let total_count: i64 = sqlx::query("SELECT COUNT(*) as count FROM artists_images WHERE (validated = FALSE OR validated IS NULL) AND removed_at IS NULL")
             .fetch_one(pool)
             .await
             .map_err(|e| ServerFnError::new(format!("Failed to count: {}", e)))?
@@ -3650,10 +4669,11 @@ pub async fn get_unvalidated_posts_for_gallery(
         let rows = sqlx::query(
             "SELECT ai.id, ai.short_code, ai.artist_id, ai.post_date, ai.validated,
                     a.id as a_id, a.name, a.location_id, a.social_links, a.instagram_handle,
-                    a.email, a.phone, a.years_experience, a.styles_extracted, a.shop_validated
+                    a.email, a.phone, a.years_experience, a.styles_extracted, a.shop_validated,
+                    a.experience_tier
              FROM artists_images ai
              LEFT JOIN artists a ON ai.artist_id = a.id
-             WHERE ai.validated = FALSE OR ai.validated IS NULL
+             WHERE (ai.validated = FALSE OR ai.validated IS NULL) AND ai.removed_at IS NULL
              ORDER BY ai.id DESC
              LIMIT $1 OFFSET $2"
         )
@@ -3685,6 +4705,7 @@ pub async fn get_unvalidated_posts_for_gallery(
                     years_experience: row.try_get::<i64, _>("years_experience").ok().map(|v| v as i32),
                     styles_extracted: row.try_get::<i64, _>("styles_extracted").ok().map(|v| v as i32),
                     shop_validated: row.try_get("shop_validated").ok(),
+                    experience_tier: row.try_get("experience_tier").ok().flatten(),
                 })
             } else {
                 None
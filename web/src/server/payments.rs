@@ -0,0 +1,747 @@
+//! Payments: artist subscriptions, booking deposits and artist payouts.
+//!
+//! Artists pick a tier and are sent to the provider's hosted checkout, which
+//! creates the customer and subscription. Clients pay booking deposits
+//! through a one-off checkout. Artists onboard a connected account through
+//! the provider's hosted onboarding so payouts can be made to them, and
+//! choose how often they're paid out.
+//!
+//! The provider reports back through `POST /api/stripe/webhook`; events are
+//! translated into [`PaymentEvent`]s:
+//! - a paid subscription invoice activates the subscription and moves
+//!   `next_payment` to the end of the paid period
+//! - a deleted subscription is marked cancelled
+//! - a completed checkout records a paid deposit, in case the client never
+//!   makes it back to the confirmation page
+//! - account and payout updates keep `artist_payment_accounts` and `payouts`
+//!   in sync
+//!
+//! Everything provider-specific lives behind [`PaymentProvider`]; Stripe is
+//! the only implementation (see `stripe.rs` for its settings). `APP_BASE_URL`
+//! is used for the return URLs.
+
+use leptos::prelude::*;
+
+use crate::db::entities::{BookingDeposit, PaymentAccount, Payout};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+#[cfg(feature = "ssr")]
+mod stripe;
+
+#[cfg(feature = "ssr")]
+use crate::db::subscription_repository::SubscriptionPayment;
+#[cfg(feature = "ssr")]
+use std::future::Future;
+
+/// Payout schedules artists can choose from
+pub const PAYOUT_INTERVALS: [&str; 4] = ["manual", "daily", "weekly", "monthly"];
+pub const PAYOUT_WEEKDAYS: [&str; 5] = ["monday", "tuesday", "wednesday", "thursday", "friday"];
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum PaymentError {
+    #[error("payments are not configured")]
+    NotConfigured,
+    #[error("payment provider request failed: {0}")]
+    Request(String),
+    #[error("payment provider returned {status}: {message}")]
+    Provider { status: u16, message: String },
+    #[error("invalid webhook: {0}")]
+    InvalidWebhook(&'static str),
+    #[error(transparent)]
+    Unavailable(#[from] shared_types::circuit_breaker::BreakerOpen),
+}
+
+/// Logs the provider error and returns one that's safe to show users
+#[cfg(feature = "ssr")]
+fn payment_error(e: PaymentError) -> ServerFnError {
+    match e {
+        PaymentError::NotConfigured => {
+            ServerFnError::new("Payments are not configured".to_string())
+        }
+        PaymentError::Unavailable(e) => {
+            tracing::warn!("{}", e);
+            ServerFnError::new(
+                "Payments are temporarily unavailable. Please try again in a few minutes."
+                    .to_string(),
+            )
+        }
+        e => {
+            tracing::error!("Payment provider error: {}", e);
+            ServerFnError::new("Payment provider error".to_string())
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) struct SubscriptionCheckout<'a> {
+    pub artist_id: i32,
+    pub tier_id: i32,
+    pub price_id: &'a str,
+    pub customer_id: Option<&'a str>,
+    pub success_url: &'a str,
+    pub cancel_url: &'a str,
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) struct DepositCheckout<'a> {
+    pub booking_id: i32,
+    pub amount: f64,
+    pub currency: &'a str,
+    pub client_email: &'a str,
+    /// Gets the provider's checkout id appended as `&deposit_session=`
+    pub success_url: &'a str,
+    pub cancel_url: &'a str,
+}
+
+/// A one-off checkout and the payment made through it
+#[cfg(feature = "ssr")]
+pub(crate) struct CheckoutPayment {
+    pub booking_id: Option<i32>,
+    pub paid: bool,
+    pub payment_id: String,
+    pub amount: f64,
+    pub currency: String,
+}
+
+/// Capabilities of a connected account
+#[cfg(feature = "ssr")]
+pub(crate) struct AccountStatus {
+    pub account_id: String,
+    pub charges_enabled: bool,
+    pub payouts_enabled: bool,
+    pub details_submitted: bool,
+    pub requirements_due: Vec<String>,
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) struct PayoutStatus {
+    pub account_id: String,
+    pub payout_id: String,
+    pub amount: f64,
+    pub currency: String,
+    pub status: String,
+    /// `YYYY-MM-DD`
+    pub arrival_date: Option<String>,
+    pub failure_message: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) enum PaymentEvent {
+    SubscriptionPaid(SubscriptionPayment),
+    SubscriptionCancelled { subscription_id: String },
+    CheckoutCompleted(CheckoutPayment),
+    AccountUpdated(AccountStatus),
+    PayoutUpdated(PayoutStatus),
+    /// Events we don't act on
+    Other,
+}
+
+/// A verified webhook delivery
+#[cfg(feature = "ssr")]
+pub(crate) struct WebhookEvent {
+    pub id: String,
+    pub kind: String,
+    pub event: PaymentEvent,
+}
+
+/// A payment provider: hosted checkout, connected accounts for payouts and
+/// signed webhooks
+#[cfg(feature = "ssr")]
+pub(crate) trait PaymentProvider {
+    /// Stored with connected accounts
+    fn name(&self) -> &'static str;
+
+    /// Returns the URL of a checkout for a recurring subscription
+    fn subscription_checkout(
+        &self,
+        checkout: SubscriptionCheckout<'_>,
+    ) -> impl Future<Output = Result<String, PaymentError>> + Send;
+
+    /// Returns the URL of a checkout for a booking deposit
+    fn deposit_checkout(
+        &self,
+        checkout: DepositCheckout<'_>,
+    ) -> impl Future<Output = Result<String, PaymentError>> + Send;
+
+    fn get_checkout_payment(
+        &self,
+        checkout_id: &str,
+    ) -> impl Future<Output = Result<CheckoutPayment, PaymentError>> + Send;
+
+    /// Creates a connected account, returning its id
+    fn create_account(
+        &self,
+        country: &str,
+        currency: &str,
+        email: Option<&str>,
+    ) -> impl Future<Output = Result<String, PaymentError>> + Send;
+
+    /// Returns the URL of the provider's onboarding for an account. The
+    /// refresh URL is used when the link has expired.
+    fn onboarding_link(
+        &self,
+        account_id: &str,
+        return_url: &str,
+        refresh_url: &str,
+    ) -> impl Future<Output = Result<String, PaymentError>> + Send;
+
+    fn account_status(
+        &self,
+        account_id: &str,
+    ) -> impl Future<Output = Result<AccountStatus, PaymentError>> + Send;
+
+    /// `anchor` is a weekday for weekly payouts, a day of the month for
+    /// monthly ones
+    fn set_payout_schedule(
+        &self,
+        account_id: &str,
+        interval: &str,
+        anchor: Option<&str>,
+    ) -> impl Future<Output = Result<(), PaymentError>> + Send;
+
+    /// Verifies a webhook delivery and translates its event
+    fn parse_webhook(
+        &self,
+        headers: &axum::http::HeaderMap,
+        body: &[u8],
+    ) -> Result<WebhookEvent, PaymentError>;
+}
+
+#[cfg(feature = "ssr")]
+static PROVIDER: std::sync::OnceLock<stripe::StripeProvider> = std::sync::OnceLock::new();
+
+/// The configured payment provider, built from [`crate::config`] on first use
+#[cfg(feature = "ssr")]
+fn provider() -> &'static impl PaymentProvider {
+    PROVIDER.get_or_init(|| stripe::StripeProvider::new(crate::config::config()))
+}
+
+/// Starts a checkout for the signed-in artist's subscription and returns
+/// the URL to send them to.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn create_subscription_checkout(
+    token: String,
+    tier_id: i32,
+) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::subscription_repository;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to start checkout: {}", e));
+        let price_id = subscription_repository::get_tier_price_id(tier_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServerFnError::new("This plan can't be purchased".to_string()))?;
+        let (customer_id, has_active) = subscription_repository::get_billing_customer(artist_id)
+            .await
+            .map_err(db_error)?;
+        if has_active {
+            return Err(ServerFnError::new(
+                "You already have an active subscription".to_string(),
+            ));
+        }
+
+        let base_url = &crate::config::config().app_base_url;
+        let success_url = format!("{}/artist/dashboard?subscription=success", base_url);
+        let cancel_url = format!("{}/subscription/tiers?subscription=cancelled", base_url);
+        provider()
+            .subscription_checkout(SubscriptionCheckout {
+                artist_id,
+                tier_id,
+                price_id: &price_id,
+                customer_id: customer_id.as_deref(),
+                success_url: &success_url,
+                cancel_url: &cancel_url,
+            })
+            .await
+            .map_err(payment_error)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The booking's deposit, for its client or artist
+#[cfg(feature = "ssr")]
+async fn authorized_deposit(
+    token: &str,
+    booking_id: i32,
+) -> Result<crate::db::deposit_repository::DepositRecord, ServerFnError> {
+    let deposit = crate::db::deposit_repository::get_deposit(booking_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to load booking: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("Booking not found".to_string()))?;
+
+    let allowed = match crate::server_invoices::artist_id_from_token(token).await {
+        Ok(artist_id) => artist_id == deposit.artist_id,
+        Err(_) => crate::server_invoices::client_email_from_token(token)
+            .await
+            .is_ok_and(|email| email.eq_ignore_ascii_case(&deposit.client_email)),
+    };
+    if !allowed {
+        return Err(ServerFnError::new("Booking not found".to_string()));
+    }
+
+    Ok(deposit)
+}
+
+#[cfg(feature = "ssr")]
+async fn deposit_view(
+    deposit: crate::db::deposit_repository::DepositRecord,
+) -> Result<BookingDeposit, ServerFnError> {
+    let currency = crate::db::invoice_repository::get_billing_settings(deposit.artist_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to load billing settings: {}", e)))?
+        .currency;
+
+    Ok(BookingDeposit {
+        booking_id: deposit.booking_id,
+        booking_status: deposit.booking_status,
+        amount: deposit.amount,
+        currency,
+        status: deposit.status,
+    })
+}
+
+/// Deposit state of a booking, for its client or artist.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_booking_deposit(
+    token: String,
+    booking_id: i32,
+) -> Result<BookingDeposit, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        deposit_view(authorized_deposit(&token, booking_id).await?).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Creates the payment for a booking's outstanding deposit and returns the
+/// checkout URL the client pays at. The checkout creates the underlying
+/// payment intent, tagged with the booking id.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn create_deposit_payment_intent(
+    token: String,
+    booking_id: i32,
+) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let deposit = authorized_deposit(&token, booking_id).await?;
+        let (Some(amount), Some("required")) = (deposit.amount, deposit.status.as_deref()) else {
+            return Err(ServerFnError::new("No deposit is due for this booking".to_string()));
+        };
+        if deposit.booking_status != "approved" {
+            return Err(ServerFnError::new(
+                "Deposits can only be paid on approved bookings".to_string(),
+            ));
+        }
+        let client_email = deposit.client_email.clone();
+        let currency = deposit_view(deposit).await?.currency;
+
+        let base_url = &crate::config::config().app_base_url;
+        let return_url = format!("{}/booking/confirmation?booking_id={}", base_url, booking_id);
+        provider()
+            .deposit_checkout(DepositCheckout {
+                booking_id,
+                amount,
+                currency: &currency,
+                client_email: &client_email,
+                success_url: &return_url,
+                cancel_url: &return_url,
+            })
+            .await
+            .map_err(payment_error)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Records a paid deposit from a completed checkout, returning whether the
+/// checkout was for this booking's deposit and has been paid
+#[cfg(feature = "ssr")]
+async fn record_deposit_checkout(
+    booking_id: i32,
+    payment: &CheckoutPayment,
+) -> Result<bool, sqlx::Error> {
+    if payment.booking_id != Some(booking_id) || !payment.paid {
+        return Ok(false);
+    }
+
+    let newly_paid = crate::db::deposit_repository::mark_deposit_paid(
+        booking_id,
+        &payment.payment_id,
+        payment.amount,
+        &payment.currency,
+    )
+    .await?;
+    if newly_paid {
+        crate::reminders::schedule_reminders(booking_id).await;
+    }
+    Ok(true)
+}
+
+/// Confirms the deposit payment when the client returns from checkout, so
+/// the booking shows as paid without waiting for the webhook.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn record_deposit_payment(
+    token: String,
+    booking_id: i32,
+    session_id: String,
+) -> Result<BookingDeposit, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        authorized_deposit(&token, booking_id).await?;
+
+        let payment = provider()
+            .get_checkout_payment(&session_id)
+            .await
+            .map_err(payment_error)?;
+
+        if !record_deposit_checkout(booking_id, &payment)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to record deposit: {}", e)))?
+        {
+            return Err(ServerFnError::new(
+                "The deposit payment hasn't completed".to_string(),
+            ));
+        }
+
+        deposit_view(authorized_deposit(&token, booking_id).await?).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The signed-in artist's payout account, if they've started onboarding.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_payment_account(token: String) -> Result<Option<PaymentAccount>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::payout_repository::get_account(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load payout account: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Creates the artist's payout account on first use and returns the URL of
+/// the provider's onboarding for it. `country` is a two-letter ISO code;
+/// the account is paid out in the artist's billing currency.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn start_payout_onboarding(
+    token: String,
+    country: String,
+) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::payout_repository;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to start onboarding: {}", e));
+        let account_id = match payout_repository::get_account(artist_id)
+            .await
+            .map_err(db_error)?
+        {
+            Some(account) => account.account_id,
+            None => {
+                let country = country.trim().to_ascii_uppercase();
+                if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(ServerFnError::new("Choose your country".to_string()));
+                }
+                let currency = crate::db::invoice_repository::get_billing_settings(artist_id)
+                    .await
+                    .map_err(db_error)?
+                    .currency;
+                let email = crate::server_invoices::client_email_from_token(&token)
+                    .await
+                    .ok();
+
+                let account_id = provider()
+                    .create_account(&country, &currency, email.as_deref())
+                    .await
+                    .map_err(payment_error)?;
+                payout_repository::insert_account(
+                    artist_id,
+                    provider().name(),
+                    &account_id,
+                    &country,
+                    &currency,
+                )
+                .await
+                .map_err(db_error)?;
+                account_id
+            }
+        };
+
+        let settings_url = format!(
+            "{}/artist/dashboard/settings",
+            crate::config::config().app_base_url
+        );
+        provider()
+            .onboarding_link(
+                &account_id,
+                &format!("{}?payouts=returned", settings_url),
+                &format!("{}?payouts=refresh", settings_url),
+            )
+            .await
+            .map_err(payment_error)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Re-checks the payout account's capabilities with the provider, for when
+/// the artist returns from onboarding before the webhook arrives.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn refresh_payment_account(
+    token: String,
+) -> Result<Option<PaymentAccount>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::payout_repository;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to load payout account: {}", e));
+        let Some(account) = payout_repository::get_account(artist_id)
+            .await
+            .map_err(db_error)?
+        else {
+            return Ok(None);
+        };
+
+        let status = provider()
+            .account_status(&account.account_id)
+            .await
+            .map_err(payment_error)?;
+        payout_repository::update_account_status(
+            &status.account_id,
+            status.charges_enabled,
+            status.payouts_enabled,
+            status.details_submitted,
+            &status.requirements_due,
+        )
+        .await
+        .map_err(db_error)?;
+
+        payout_repository::get_account(artist_id)
+            .await
+            .map_err(db_error)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Sets how often the artist is paid out. `anchor` is a weekday
+/// ("monday"...) for weekly payouts and a day of the month ("1"-"31") for
+/// monthly ones; it's ignored otherwise.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_payout_schedule(
+    token: String,
+    interval: String,
+    anchor: Option<String>,
+) -> Result<PaymentAccount, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::payout_repository;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        if !PAYOUT_INTERVALS.contains(&interval.as_str()) {
+            return Err(ServerFnError::new("Unknown payout schedule".to_string()));
+        }
+        let anchor = match interval.as_str() {
+            "weekly" => match anchor.map(|a| a.to_ascii_lowercase()) {
+                Some(day) if PAYOUT_WEEKDAYS.contains(&day.as_str()) => Some(day),
+                _ => return Err(ServerFnError::new("Choose a weekday".to_string())),
+            },
+            "monthly" => match anchor.as_deref().map(str::parse::<u8>) {
+                Some(Ok(day @ 1..=31)) => Some(day.to_string()),
+                _ => return Err(ServerFnError::new("Choose a day of the month".to_string())),
+            },
+            _ => None,
+        };
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to update payouts: {}", e));
+        let account = payout_repository::get_account(artist_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServerFnError::new("Set up payouts first".to_string()))?;
+
+        provider()
+            .set_payout_schedule(&account.account_id, &interval, anchor.as_deref())
+            .await
+            .map_err(payment_error)?;
+        payout_repository::update_payout_schedule(artist_id, &interval, anchor.as_deref())
+            .await
+            .map_err(db_error)?;
+
+        payout_repository::get_account(artist_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServerFnError::new("Set up payouts first".to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The signed-in artist's recent payouts, newest first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_payouts(token: String) -> Result<Vec<Payout>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::payout_repository::get_payouts(artist_id, 20)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load payouts: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[cfg(feature = "ssr")]
+async fn handle_event(event: PaymentEvent) -> Result<(), sqlx::Error> {
+    use crate::db::{payout_repository, subscription_repository};
+
+    match event {
+        PaymentEvent::SubscriptionPaid(payment) => {
+            subscription_repository::record_payment(&payment).await
+        }
+        PaymentEvent::SubscriptionCancelled { subscription_id } => {
+            if !subscription_repository::cancel_subscription(&subscription_id).await? {
+                tracing::warn!("Cancelled subscription {} is unknown", subscription_id);
+            }
+            Ok(())
+        }
+        PaymentEvent::CheckoutCompleted(payment) => match payment.booking_id {
+            Some(booking_id) => record_deposit_checkout(booking_id, &payment)
+                .await
+                .map(|_| ()),
+            None => Ok(()),
+        },
+        PaymentEvent::AccountUpdated(status) => {
+            if !payout_repository::update_account_status(
+                &status.account_id,
+                status.charges_enabled,
+                status.payouts_enabled,
+                status.details_submitted,
+                &status.requirements_due,
+            )
+            .await?
+            {
+                tracing::warn!("Updated payment account {} is unknown", status.account_id);
+            }
+            Ok(())
+        }
+        PaymentEvent::PayoutUpdated(payout) => {
+            let Some(artist_id) = payout_repository::get_account_artist(&payout.account_id).await?
+            else {
+                tracing::warn!("Payout {} for unknown account {}", payout.payout_id, payout.account_id);
+                return Ok(());
+            };
+
+            payout_repository::upsert_payout(&payout_repository::PayoutUpdate {
+                artist_id,
+                provider_payout_id: payout.payout_id,
+                amount: payout.amount,
+                currency: payout.currency,
+                status: payout.status,
+                arrival_date: payout.arrival_date,
+                failure_message: payout.failure_message,
+            })
+            .await
+        }
+        PaymentEvent::Other => Ok(()),
+    }
+}
+
+/// POST /api/stripe/webhook
+#[cfg(feature = "ssr")]
+pub async fn payment_webhook(
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> axum::http::StatusCode {
+    use crate::db::subscription_repository;
+    use axum::http::StatusCode;
+
+    let webhook = match provider().parse_webhook(&headers, &body) {
+        Ok(webhook) => webhook,
+        Err(PaymentError::NotConfigured) => {
+            tracing::error!("Payment webhook received but no webhook secret is set");
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+        Err(e) => {
+            tracing::warn!("Rejected payment webhook: {}", e);
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    match subscription_repository::record_webhook_event(&webhook.id, &webhook.kind).await {
+        Ok(true) => {}
+        // Already handled
+        Ok(false) => return StatusCode::OK,
+        Err(e) => {
+            tracing::error!("Failed to record payment event {}: {}", webhook.id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    }
+
+    match handle_event(webhook.event).await {
+        Ok(()) => StatusCode::OK,
+        Err(e) => {
+            tracing::error!(
+                "Failed to handle payment event {} ({}): {}",
+                webhook.id,
+                webhook.kind,
+                e
+            );
+            // Let the provider's retry run it again
+            let _ = subscription_repository::forget_webhook_event(&webhook.id).await;
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
@@ -0,0 +1,440 @@
+//! Stripe implementation of [`PaymentProvider`]: Checkout for subscriptions
+//! and deposits, Connect Express accounts for payouts.
+//!
+//! Settings: `STRIPE_SECRET_KEY`, `STRIPE_WEBHOOK_SECRET` (signing secret of
+//! the account's webhook endpoint) and `STRIPE_CONNECT_WEBHOOK_SECRET`
+//! (signing secret of the Connect endpoint, which delivers events from
+//! artists' accounts to the same URL).
+
+use axum::http::HeaderMap;
+use serde_json::Value;
+
+use super::{
+    AccountStatus, CheckoutPayment, DepositCheckout, PaymentError, PaymentEvent,
+    PaymentProvider, PayoutStatus, SubscriptionCheckout, WebhookEvent,
+};
+use crate::circuit_breakers::{self, STRIPE};
+use crate::db::subscription_repository::SubscriptionPayment;
+use crate::utils::money::{from_minor_units, to_minor_units};
+
+const STRIPE_API: &str = "https://api.stripe.com/v1";
+/// Oldest webhook timestamp accepted, against replayed deliveries
+const WEBHOOK_TOLERANCE_SECS: i64 = 5 * 60;
+
+pub(crate) struct StripeProvider {
+    secret_key: Option<String>,
+    webhook_secrets: Vec<String>,
+}
+
+impl StripeProvider {
+    pub fn new(config: &crate::config::Config) -> Self {
+        StripeProvider {
+            secret_key: config.stripe_secret_key.clone(),
+            webhook_secrets: config.stripe_webhook_secrets.clone(),
+        }
+    }
+
+    /// Calls the Stripe API with a form-encoded body (ignored for GET)
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<Value, PaymentError> {
+        let secret_key = self.secret_key.as_ref().ok_or(PaymentError::NotConfigured)?;
+
+        let mut request = reqwest::Client::new()
+            .request(method, format!("{}{}", STRIPE_API, path))
+            .bearer_auth(secret_key);
+        if !form.is_empty() {
+            request = request.form(form);
+        }
+        STRIPE.check()?;
+        let response = request.send().await;
+        circuit_breakers::record_response(&STRIPE, &response);
+        let response = response.map_err(|e| PaymentError::Request(e.to_string()))?;
+
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| PaymentError::Request(e.to_string()))?;
+        if !status.is_success() {
+            return Err(PaymentError::Provider {
+                status: status.as_u16(),
+                message: body["error"]["message"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+
+        Ok(body)
+    }
+}
+
+/// Stripe ids are alphanumeric with underscores; anything else is never
+/// put into a request path
+fn valid_id(id: &str) -> Result<&str, PaymentError> {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(id)
+    } else {
+        Err(PaymentError::Request("invalid id".to_string()))
+    }
+}
+
+fn url_of(object: &Value) -> Result<String, PaymentError> {
+    object["url"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| PaymentError::Request("response has no URL".to_string()))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Checks a `Stripe-Signature` header (`t=<unix time>,v1=<hex hmac>,...`)
+/// against the raw request body
+fn verify_signature(header: &str, payload: &[u8], secret: &str, now: i64) -> bool {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.extend(decode_hex(value)),
+            _ => {}
+        }
+    }
+
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+    if (now - timestamp).abs() > WEBHOOK_TOLERANCE_SECS {
+        return false;
+    }
+
+    signatures.iter().any(|signature| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(payload);
+        mac.verify_slice(signature).is_ok()
+    })
+}
+
+fn metadata_id(metadata: &Value, key: &str) -> Option<i32> {
+    metadata[key].as_str()?.parse().ok()
+}
+
+fn checkout_payment(session: &Value) -> CheckoutPayment {
+    let currency = session["currency"]
+        .as_str()
+        .unwrap_or("usd")
+        .to_ascii_uppercase();
+
+    CheckoutPayment {
+        booking_id: metadata_id(&session["metadata"], "booking_id"),
+        paid: session["payment_status"].as_str() == Some("paid"),
+        payment_id: session["payment_intent"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        amount: from_minor_units(session["amount_total"].as_i64().unwrap_or(0), &currency),
+        currency,
+    }
+}
+
+/// Payouts need both the account-level flag and an active transfers
+/// capability
+fn account_status(account: &Value) -> AccountStatus {
+    let transfers_active = account["capabilities"]["transfers"].as_str() == Some("active");
+
+    AccountStatus {
+        account_id: account["id"].as_str().unwrap_or_default().to_string(),
+        charges_enabled: account["charges_enabled"].as_bool().unwrap_or(false),
+        payouts_enabled: account["payouts_enabled"].as_bool().unwrap_or(false) && transfers_active,
+        details_submitted: account["details_submitted"].as_bool().unwrap_or(false),
+        requirements_due: account["requirements"]["currently_due"]
+            .as_array()
+            .map(|due| {
+                due.iter()
+                    .filter_map(|item| item.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Subscription id and metadata of an invoice; newer API versions moved
+/// them under `parent`
+fn invoice_subscription(invoice: &Value) -> (Option<&str>, &Value) {
+    let details = &invoice["parent"]["subscription_details"];
+    if details.is_object() {
+        (details["subscription"].as_str(), &details["metadata"])
+    } else {
+        (
+            invoice["subscription"].as_str(),
+            &invoice["subscription_details"]["metadata"],
+        )
+    }
+}
+
+fn invoice_paid(invoice: &Value) -> PaymentEvent {
+    let (Some(subscription_id), metadata) = invoice_subscription(invoice) else {
+        // One-off invoices aren't subscriptions
+        return PaymentEvent::Other;
+    };
+    let (Some(artist_id), Some(tier_id), Some(customer_id)) = (
+        metadata_id(metadata, "artist_id"),
+        metadata_id(metadata, "tier_id"),
+        invoice["customer"].as_str(),
+    ) else {
+        tracing::warn!(
+            "Paid invoice for subscription {} without artist metadata",
+            subscription_id
+        );
+        return PaymentEvent::Other;
+    };
+
+    let period_end = invoice["lines"]["data"][0]["period"]["end"]
+        .as_i64()
+        .or_else(|| invoice["period_end"].as_i64())
+        .and_then(|end| chrono::DateTime::from_timestamp(end, 0))
+        .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::days(30));
+
+    PaymentEvent::SubscriptionPaid(SubscriptionPayment {
+        artist_id,
+        tier_id,
+        customer_id: customer_id.to_string(),
+        subscription_id: subscription_id.to_string(),
+        paid_until: period_end.format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+}
+
+fn payout_updated(account_id: Option<&str>, payout: &Value) -> PaymentEvent {
+    let (Some(account_id), Some(payout_id)) = (account_id, payout["id"].as_str()) else {
+        // Payouts of the platform's own balance
+        return PaymentEvent::Other;
+    };
+    let currency = payout["currency"]
+        .as_str()
+        .unwrap_or("usd")
+        .to_ascii_uppercase();
+
+    PaymentEvent::PayoutUpdated(PayoutStatus {
+        account_id: account_id.to_string(),
+        payout_id: payout_id.to_string(),
+        amount: from_minor_units(payout["amount"].as_i64().unwrap_or(0), &currency),
+        currency,
+        status: payout["status"].as_str().unwrap_or("pending").to_string(),
+        arrival_date: payout["arrival_date"]
+            .as_i64()
+            .and_then(|date| chrono::DateTime::from_timestamp(date, 0))
+            .map(|date| date.format("%Y-%m-%d").to_string()),
+        failure_message: payout["failure_message"].as_str().map(str::to_string),
+    })
+}
+
+impl PaymentProvider for StripeProvider {
+    fn name(&self) -> &'static str {
+        "stripe"
+    }
+
+    async fn subscription_checkout(
+        &self,
+        checkout: SubscriptionCheckout<'_>,
+    ) -> Result<String, PaymentError> {
+        let artist_id = checkout.artist_id.to_string();
+        let tier_id = checkout.tier_id.to_string();
+        let mut form = vec![
+            ("mode", "subscription"),
+            ("line_items[0][price]", checkout.price_id),
+            ("line_items[0][quantity]", "1"),
+            ("success_url", checkout.success_url),
+            ("cancel_url", checkout.cancel_url),
+            ("client_reference_id", artist_id.as_str()),
+            ("subscription_data[metadata][artist_id]", artist_id.as_str()),
+            ("subscription_data[metadata][tier_id]", tier_id.as_str()),
+        ];
+        if let Some(customer_id) = checkout.customer_id {
+            form.push(("customer", customer_id));
+        }
+
+        let session = self
+            .request(reqwest::Method::POST, "/checkout/sessions", &form)
+            .await?;
+        url_of(&session)
+    }
+
+    async fn deposit_checkout(
+        &self,
+        checkout: DepositCheckout<'_>,
+    ) -> Result<String, PaymentError> {
+        let booking_id = checkout.booking_id.to_string();
+        let unit_amount = to_minor_units(checkout.amount, checkout.currency).to_string();
+        let currency = checkout.currency.to_ascii_lowercase();
+        let product_name = format!("Deposit for booking #{}", checkout.booking_id);
+        // Stripe fills in the session id on the way back
+        let success_url = format!(
+            "{}&deposit_session={{CHECKOUT_SESSION_ID}}",
+            checkout.success_url
+        );
+        let form = [
+            ("mode", "payment"),
+            ("line_items[0][quantity]", "1"),
+            ("line_items[0][price_data][currency]", currency.as_str()),
+            ("line_items[0][price_data][unit_amount]", unit_amount.as_str()),
+            (
+                "line_items[0][price_data][product_data][name]",
+                product_name.as_str(),
+            ),
+            ("customer_email", checkout.client_email),
+            ("success_url", success_url.as_str()),
+            ("cancel_url", checkout.cancel_url),
+            ("metadata[booking_id]", booking_id.as_str()),
+            ("payment_intent_data[metadata][booking_id]", booking_id.as_str()),
+        ];
+
+        let session = self
+            .request(reqwest::Method::POST, "/checkout/sessions", &form)
+            .await?;
+        url_of(&session)
+    }
+
+    async fn get_checkout_payment(&self, checkout_id: &str) -> Result<CheckoutPayment, PaymentError> {
+        let path = format!("/checkout/sessions/{}", valid_id(checkout_id)?);
+        let session = self.request(reqwest::Method::GET, &path, &[]).await?;
+        Ok(checkout_payment(&session))
+    }
+
+    async fn create_account(
+        &self,
+        country: &str,
+        currency: &str,
+        email: Option<&str>,
+    ) -> Result<String, PaymentError> {
+        let currency = currency.to_ascii_lowercase();
+        let mut form = vec![
+            ("type", "express"),
+            ("country", country),
+            ("default_currency", currency.as_str()),
+            ("capabilities[card_payments][requested]", "true"),
+            ("capabilities[transfers][requested]", "true"),
+        ];
+        if let Some(email) = email {
+            form.push(("email", email));
+        }
+
+        let account = self
+            .request(reqwest::Method::POST, "/accounts", &form)
+            .await?;
+        account["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| PaymentError::Request("account has no id".to_string()))
+    }
+
+    async fn onboarding_link(
+        &self,
+        account_id: &str,
+        return_url: &str,
+        refresh_url: &str,
+    ) -> Result<String, PaymentError> {
+        let form = [
+            ("account", valid_id(account_id)?),
+            ("type", "account_onboarding"),
+            ("return_url", return_url),
+            ("refresh_url", refresh_url),
+        ];
+
+        let link = self
+            .request(reqwest::Method::POST, "/account_links", &form)
+            .await?;
+        url_of(&link)
+    }
+
+    async fn account_status(&self, account_id: &str) -> Result<AccountStatus, PaymentError> {
+        let path = format!("/accounts/{}", valid_id(account_id)?);
+        let account = self.request(reqwest::Method::GET, &path, &[]).await?;
+        Ok(account_status(&account))
+    }
+
+    async fn set_payout_schedule(
+        &self,
+        account_id: &str,
+        interval: &str,
+        anchor: Option<&str>,
+    ) -> Result<(), PaymentError> {
+        let path = format!("/accounts/{}", valid_id(account_id)?);
+        let mut form = vec![("settings[payouts][schedule][interval]", interval)];
+        match (interval, anchor) {
+            ("weekly", Some(anchor)) => {
+                form.push(("settings[payouts][schedule][weekly_anchor]", anchor))
+            }
+            ("monthly", Some(anchor)) => {
+                form.push(("settings[payouts][schedule][monthly_anchor]", anchor))
+            }
+            _ => {}
+        }
+
+        self.request(reqwest::Method::POST, &path, &form).await?;
+        Ok(())
+    }
+
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<WebhookEvent, PaymentError> {
+        if self.webhook_secrets.is_empty() {
+            return Err(PaymentError::NotConfigured);
+        }
+        let signature = headers
+            .get("stripe-signature")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let now = chrono::Utc::now().timestamp();
+        if !self
+            .webhook_secrets
+            .iter()
+            .any(|secret| verify_signature(signature, body, secret, now))
+        {
+            return Err(PaymentError::InvalidWebhook("bad signature"));
+        }
+
+        let event: Value =
+            serde_json::from_slice(body).map_err(|_| PaymentError::InvalidWebhook("bad JSON"))?;
+        let (Some(id), Some(kind)) = (event["id"].as_str(), event["type"].as_str()) else {
+            return Err(PaymentError::InvalidWebhook("missing id or type"));
+        };
+
+        let object = &event["data"]["object"];
+        let payment_event = match kind {
+            "invoice.paid" => invoice_paid(object),
+            "customer.subscription.deleted" => match object["id"].as_str() {
+                Some(subscription_id) => PaymentEvent::SubscriptionCancelled {
+                    subscription_id: subscription_id.to_string(),
+                },
+                None => PaymentEvent::Other,
+            },
+            "checkout.session.completed" => PaymentEvent::CheckoutCompleted(checkout_payment(object)),
+            "account.updated" => PaymentEvent::AccountUpdated(account_status(object)),
+            "payout.created" | "payout.updated" | "payout.paid" | "payout.failed"
+            | "payout.canceled" => payout_updated(event["account"].as_str(), object),
+            _ => PaymentEvent::Other,
+        };
+
+        Ok(WebhookEvent {
+            id: id.to_string(),
+            kind: kind.to_string(),
+            event: payment_event,
+        })
+    }
+}
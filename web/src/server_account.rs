@@ -0,0 +1,517 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{AccountContact, PendingContactChange};
+use crate::server::AuthResponse;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// How long a verification code stays valid
+#[cfg(feature = "ssr")]
+const CODE_TTL_MINUTES: i64 = 30;
+
+/// Days between asking for an account's data to be deleted and the purge;
+/// reactivating the account in the meantime cancels it
+pub const DELETION_GRACE_DAYS: i32 = 30;
+
+/// Accounts purged per run of the purge job
+#[cfg(feature = "ssr")]
+const ACCOUNTS_PURGED_PER_RUN: i64 = 100;
+
+/// Wrong codes allowed before a change request has to be started over
+#[cfg(feature = "ssr")]
+const MAX_CODE_ATTEMPTS: i32 = 5;
+
+/// Change requests a user can start per hour, each of which sends a message
+#[cfg(feature = "ssr")]
+const MAX_REQUESTS_PER_HOUR: i64 = 5;
+
+/// Checks and normalizes a new email address or phone number. Phone numbers
+/// keep only digits and a leading `+`, the form uniqueness is checked in.
+#[cfg(feature = "ssr")]
+fn normalize_contact(channel: &str, value: &str) -> Result<String, ServerFnError> {
+    let value = value.trim();
+    match channel {
+        "email" => {
+            let valid = value.len() <= 254
+                && !value.contains(char::is_whitespace)
+                && value.split_once('@').is_some_and(|(local, domain)| {
+                    !local.is_empty() && domain.contains('.') && !domain.ends_with('.')
+                });
+            if valid {
+                Ok(value.to_lowercase())
+            } else {
+                Err(ServerFnError::new(
+                    "Enter a valid email address".to_string(),
+                ))
+            }
+        }
+        "phone" => {
+            let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+            if !(10..=15).contains(&digits.len()) {
+                return Err(ServerFnError::new("Enter a valid phone number".to_string()));
+            }
+            Ok(if value.starts_with('+') {
+                format!("+{}", digits)
+            } else {
+                digits
+            })
+        }
+        _ => Err(ServerFnError::new(format!(
+            "Unknown contact channel: {}",
+            channel
+        ))),
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn user_id_from_token(token: &str) -> Result<i64, ServerFnError> {
+    crate::auth::decode_access_token(token)
+        .map(|claims| claims.user_id)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))
+}
+
+/// The user's account, once `password` is checked against it
+#[cfg(feature = "ssr")]
+async fn verify_password(
+    user_id: i64,
+    password: &str,
+) -> Result<crate::db::account_repository::UserContact, ServerFnError> {
+    let user = crate::db::account_repository::get_user_contact(user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to load account: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("Account not found".to_string()))?;
+
+    let password_valid = bcrypt::verify(password, &user.password_hash)
+        .map_err(|e| ServerFnError::new(format!("Password verification error: {}", e)))?;
+    if !password_valid {
+        return Err(ServerFnError::new("Incorrect password".to_string()));
+    }
+    Ok(user)
+}
+
+/// Records a change request and sends its code to the new address,
+/// replacing any earlier code for the same channel. Also used by the ops
+/// CLI to re-send codes.
+#[cfg(feature = "ssr")]
+pub async fn send_contact_change_code(
+    user_id: i64,
+    channel: &str,
+    new_value: &str,
+) -> Result<PendingContactChange, ServerFnError> {
+    use crate::db::account_repository;
+    use crate::notify::{self, Channel, Message};
+
+    let code = format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000);
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(CODE_TTL_MINUTES);
+
+    let pending = account_repository::insert_change_request(
+        user_id,
+        channel,
+        new_value,
+        &crate::auth::hash_token(&code),
+        expires_at,
+    )
+    .await
+    .map_err(|e| ServerFnError::new(format!("Failed to save change request: {}", e)))?;
+
+    let verification = if channel == "email" {
+        Message {
+            channel: Channel::Email,
+            to: new_value.to_string(),
+            subject: "Confirm your new Tatteau email".to_string(),
+            body: format!(
+                "Your Tatteau verification code is {}. Or confirm with this link: \
+                 {}/account/verify?request={}&code={}\n\nIt expires in {} minutes.",
+                code,
+                crate::config::config().app_base_url,
+                pending.id,
+                code,
+                CODE_TTL_MINUTES
+            ),
+        }
+    } else {
+        Message {
+            channel: Channel::Sms,
+            to: new_value.to_string(),
+            subject: String::new(),
+            body: format!(
+                "Your Tatteau verification code is {}. It expires in {} minutes.",
+                code, CODE_TTL_MINUTES
+            ),
+        }
+    };
+
+    notify::send(&verification)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to send verification code: {}", e)))?;
+
+    Ok(pending)
+}
+
+/// The signed-in user's email and phone, with any changes waiting on a code.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_account_contact(token: String) -> Result<AccountContact, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::account_repository;
+
+        let user_id = user_id_from_token(&token)?;
+
+        let user = account_repository::get_user_contact(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load account: {}", e)))?
+            .ok_or_else(|| ServerFnError::new("Account not found".to_string()))?;
+
+        let pending = account_repository::get_pending_changes(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load pending changes: {}", e)))?;
+
+        Ok(AccountContact {
+            email: user.email,
+            phone: user.phone,
+            pending,
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Starts changing the signed-in user's email or phone. A code goes to the
+/// new address (for email, with a link carrying it) and nothing changes
+/// until it's confirmed; the current email is told a change was requested.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(token, password), err, level = "info")
+)]
+pub async fn request_contact_change(
+    token: String,
+    channel: String,
+    new_value: String,
+    password: String,
+) -> Result<PendingContactChange, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::account_repository;
+        use crate::jobs::{enqueue, Job};
+        use crate::notify::{Channel, Message};
+
+        let user_id = user_id_from_token(&token)?;
+        let new_value = normalize_contact(&channel, &new_value)?;
+
+        let user = verify_password(user_id, &password).await?;
+
+        let current = match channel.as_str() {
+            "email" => Some(user.email.to_lowercase()),
+            _ => user
+                .phone
+                .as_deref()
+                .and_then(|p| normalize_contact("phone", p).ok()),
+        };
+        if current.as_deref() == Some(new_value.as_str()) {
+            return Err(ServerFnError::new(format!(
+                "That is already your {}",
+                channel
+            )));
+        }
+
+        let taken = account_repository::is_contact_taken(&channel, &new_value, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to check {}: {}", channel, e)))?;
+        if taken {
+            return Err(ServerFnError::new(format!(
+                "That {} is already used by another account",
+                channel
+            )));
+        }
+
+        let recent = account_repository::count_recent_requests(user_id, 1)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to check recent requests: {}", e)))?;
+        if recent >= MAX_REQUESTS_PER_HOUR {
+            return Err(ServerFnError::new(
+                "Too many change requests. Try again in an hour.".to_string(),
+            ));
+        }
+
+        let pending = send_contact_change_code(user_id, &channel, &new_value).await?;
+
+        let heads_up = Message {
+            channel: Channel::Email,
+            to: user.email,
+            subject: format!("Your Tatteau {} is being changed", channel),
+            body: format!(
+                "Someone signed in to your account asked to change its {} to {}. \
+                 If this wasn't you, change your password.",
+                channel, new_value
+            ),
+        };
+        if let Err(e) = enqueue(Job::SendNotification(heads_up)).await {
+            tracing::error!(user_id, "Failed to queue contact change notice: {}", e);
+        }
+
+        Ok(pending)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Confirms a change with the code sent to the new address and applies it.
+/// Every other session is signed out; the returned token pair replaces the
+/// caller's, so its claims reflect the account as it is now.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, code), err, level = "info"))]
+pub async fn confirm_contact_change(
+    token: String,
+    request_id: i64,
+    code: String,
+) -> Result<AuthResponse, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::account_repository;
+
+        let user_id = user_id_from_token(&token)?;
+        let failed = |error: &str| AuthResponse {
+            success: false,
+            token: None,
+            refresh_token: None,
+            user_type: None,
+            user_id: None,
+            error: Some(error.to_string()),
+        };
+
+        let request = account_repository::get_change_request(request_id, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load change request: {}", e)))?;
+        let Some(request) = request.filter(|r| r.open) else {
+            return Ok(failed(
+                "This change has expired or was cancelled. Request a new code.",
+            ));
+        };
+
+        if request.attempts >= MAX_CODE_ATTEMPTS {
+            return Ok(failed("Too many wrong codes. Request a new code."));
+        }
+
+        if crate::auth::hash_token(code.trim()) != request.code_hash {
+            account_repository::record_failed_attempt(request.id)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to record attempt: {}", e)))?;
+            return Ok(failed("That code is incorrect"));
+        }
+
+        let applied = account_repository::apply_change(&request, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to apply change: {}", e)))?;
+        if !applied {
+            return Ok(failed(&format!(
+                "That {} is already used by another account",
+                request.channel
+            )));
+        }
+
+        let role = crate::db::refresh_token_repository::get_active_user_role(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load account: {}", e)))?
+            .ok_or_else(|| ServerFnError::new("Account not found".to_string()))?;
+
+        crate::auth::revoke_all_sessions(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to sign out sessions: {}", e)))?;
+        let session = crate::auth::start_session(user_id, &role)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Token generation error: {}", e)))?;
+
+        Ok(AuthResponse {
+            success: true,
+            token: Some(session.access_token),
+            refresh_token: Some(session.refresh_token),
+            user_type: Some(role),
+            user_id: Some(user_id),
+            error: None,
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Withdraws a change that hasn't been confirmed yet.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn cancel_contact_change(token: String, request_id: i64) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = user_id_from_token(&token)?;
+
+        crate::db::account_repository::cancel_change_request(request_id, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to cancel change: {}", e)))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Purges the accounts whose deletion is due, scrubbing their archived
+/// bookings first, and deletes their tattoo photos' files, returning how
+/// many were purged. An account whose archives can't be rewritten is left
+/// for the next run. Run by the purge job.
+#[cfg(feature = "ssr")]
+pub async fn purge_deleted_accounts() -> Result<usize, sqlx::Error> {
+    use crate::db::account_repository;
+    use crate::storage::private_storage;
+
+    let due =
+        account_repository::get_accounts_to_purge(DELETION_GRACE_DAYS, ACCOUNTS_PURGED_PER_RUN)
+            .await?;
+
+    let mut purged = 0;
+    for user_id in due {
+        if let Err(e) = crate::archive::scrub_client(user_id).await {
+            tracing::error!(user_id, "Failed to scrub archived bookings: {}", e);
+            continue;
+        }
+        let Some(keys) = account_repository::purge_user(user_id).await? else {
+            continue;
+        };
+        for key in keys {
+            if let Err(e) = private_storage().delete(&key).await {
+                tracing::warn!(user_id, "Failed to delete stored photo {}: {}", key, e);
+            }
+        }
+        tracing::info!(user_id, "Purged deleted account");
+        purged += 1;
+    }
+    Ok(purged)
+}
+
+/// Closes the signed-in user's account: they're signed out everywhere and
+/// can't sign in again until an operator reactivates it. Their data is kept.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(token, password), err, level = "info")
+)]
+pub async fn deactivate_account(token: String, password: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = user_id_from_token(&token)?;
+        close_account(user_id, &password, false).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Closes the signed-in user's account like [`deactivate_account`] and
+/// deletes their data after `DELETION_GRACE_DAYS`: their name and contact
+/// details, on the account and on their booking requests, the messages they
+/// sent, their favorites and their tattoo photos. Artists keep the bookings
+/// themselves, with the client shown as deleted.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(token, password), err, level = "info")
+)]
+pub async fn delete_my_data(token: String, password: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = user_id_from_token(&token)?;
+        close_account(user_id, &password, true).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[cfg(feature = "ssr")]
+async fn close_account(user_id: i64, password: &str, delete: bool) -> Result<(), ServerFnError> {
+    use crate::jobs::{enqueue, Job};
+    use crate::notify::{Channel, Message};
+
+    let user = verify_password(user_id, password).await?;
+
+    let closed = crate::db::account_repository::deactivate_user(user_id, delete)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to close account: {}", e)))?;
+    if !closed {
+        return Err(ServerFnError::new("Account not found".to_string()));
+    }
+
+    crate::auth::revoke_all_sessions(user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to sign out sessions: {}", e)))?;
+
+    let body = if delete {
+        format!(
+            "Your Tatteau account is closed and your data will be deleted in {} days. \
+             To keep your account, contact us before then.",
+            DELETION_GRACE_DAYS
+        )
+    } else {
+        "Your Tatteau account is closed. To reopen it, contact us.".to_string()
+    };
+    let notice = Message {
+        channel: Channel::Email,
+        to: user.email,
+        subject: "Your Tatteau account is closed".to_string(),
+        body,
+    };
+    if let Err(e) = enqueue(Job::SendNotification(notice)).await {
+        tracing::error!(user_id, "Failed to queue account closure notice: {}", e);
+    }
+
+    tracing::info!(user_id, delete, "Account closed");
+    Ok(())
+}
+
+/// Everything kept about the signed-in user as a client, as a JSON document
+/// to download: their account, booking requests, the messages on them,
+/// their favorites and the questions they asked artists, with bookings moved
+/// to the archive under `archived_bookings`.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn export_my_data(token: String) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = user_id_from_token(&token)?;
+
+        let data = crate::db::account_repository::export_user_data(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to export data: {}", e)))?
+            .ok_or_else(|| ServerFnError::new("Account not found".to_string()))?;
+
+        // Indented for reading; the database returns it on one line
+        let mut data: serde_json::Value = serde_json::from_str(&data)
+            .map_err(|e| ServerFnError::new(format!("Failed to export data: {}", e)))?;
+        let archived = crate::archive::client_archived_bookings(user_id)
+            .await
+            .map_err(|e| {
+                ServerFnError::new(format!("Failed to export archived bookings: {}", e))
+            })?;
+        if let Some(data) = data.as_object_mut() {
+            data.insert("archived_bookings".to_string(), archived);
+        }
+        serde_json::to_string_pretty(&data)
+            .map_err(|e| ServerFnError::new(format!("Failed to export data: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
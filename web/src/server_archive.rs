@@ -0,0 +1,166 @@
+//! Getting archived bookings back. Old completed bookings are moved to cold
+//! storage by `archive`, leaving stubs; the booking's artist can ask for one
+//! back with a reason, and an admin approving the request restores it from
+//! the archive there and then.
+
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::{BookingArchiveStatus, BookingRestoreRequest};
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Restore requests listed for admins
+#[cfg(feature = "ssr")]
+const LIST_SIZE: i64 = 100;
+
+/// The signed-in admin's user id
+#[cfg(feature = "ssr")]
+fn authorize_admin(token: &str) -> Result<i64, ApiError> {
+    match crate::server::extract_user_from_token(token) {
+        Some((user_id, user_type)) if user_type == "admin" => Ok(user_id),
+        Some(_) => Err(ApiError::unauthorized("Admin access required")),
+        None => Err(ApiError::unauthorized("Invalid or expired token")),
+    }
+}
+
+/// Whether one of the signed-in artist's bookings is archived, and where a
+/// request to restore it is at. `None` for bookings that aren't archived.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_booking_archive_status(
+    token: String,
+    booking_id: i32,
+) -> Result<Option<BookingArchiveStatus>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_booking, TeamPermission};
+
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+
+        Ok(
+            crate::db::archive_repository::get_archive_status(booking_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load archive status", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// Asks an admin to restore one of the signed-in artist's archived
+/// bookings, saying why it's needed.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, reason), err, level = "info"))]
+pub async fn request_booking_restore(
+    token: String,
+    booking_id: i32,
+    reason: String,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::archive_repository;
+        use crate::server_team::{authorize_booking, TeamPermission};
+        use crate::utils::archive::normalize_reason;
+
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+        let (user_id, _) = crate::server::extract_user_from_token(&token)
+            .ok_or_else(|| ApiError::unauthorized("Invalid or expired token"))?;
+        let reason = normalize_reason(&reason).map_err(|e| ApiError::validation("reason", e))?;
+
+        if archive_repository::get_archive_status(booking_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load archive status", e))?
+            .is_none()
+        {
+            return Err(ApiError::conflict("This booking isn't archived").into());
+        }
+
+        let request_id = archive_repository::create_restore_request(booking_id, user_id, &reason)
+            .await
+            .map_err(|e| ApiError::internal("Failed to save restore request", e))?
+            .ok_or_else(|| ApiError::conflict("A restore has already been requested"))?;
+
+        tracing::info!(booking_id, request_id, "Booking restore requested");
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Restore requests with `status` ("pending", "failed", "restored" or
+/// "rejected") for admins, oldest first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_booking_restore_requests(
+    token: String,
+    status: String,
+) -> Result<Vec<BookingRestoreRequest>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::archive::RESTORE_STATUSES;
+
+        authorize_admin(&token)?;
+        if !RESTORE_STATUSES.contains(&status.as_str()) {
+            return Err(ApiError::validation("status", "Unknown restore status").into());
+        }
+
+        Ok(
+            crate::db::archive_repository::get_restore_requests(&status, LIST_SIZE)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load restore requests", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Approves a pending restore request, or retries a failed one, restoring
+/// the booking from its archive; or rejects it. Returns the request's new
+/// status.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn review_booking_restore(
+    token: String,
+    request_id: i32,
+    approve: bool,
+) -> Result<String, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::archive_repository;
+
+        let admin_id = authorize_admin(&token)?;
+
+        let booking_id = archive_repository::review_restore_request(request_id, approve, admin_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to review restore request", e))?
+            .ok_or_else(|| ApiError::not_found("Restore request not found"))?;
+        if !approve {
+            return Ok("rejected".to_string());
+        }
+
+        let (status, error) = match crate::archive::restore_booking(booking_id).await {
+            Ok(()) => ("restored", None),
+            Err(e) => {
+                tracing::error!(booking_id, request_id, "Failed to restore booking: {}", e);
+                ("failed", Some(e.to_string()))
+            }
+        };
+        archive_repository::finish_restore_request(request_id, error.as_deref())
+            .await
+            .map_err(|e| ApiError::internal("Failed to update restore request", e))?;
+
+        Ok(status.to_string())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,323 @@
+//! Public questions on artist profiles. Anyone can ask, signed in or with a
+//! name and email; questions that look like spam are held for admins and
+//! the rest go straight to the artist, who's emailed about each one. The
+//! artist's answers are shown on their profile and emailed to the asker.
+
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::ArtistQuestion;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Questions listed for an artist or an admin queue
+#[cfg(feature = "ssr")]
+const LIST_SIZE: i64 = 100;
+
+/// The signed-in admin's user id
+#[cfg(feature = "ssr")]
+fn authorize_admin(token: &str) -> Result<i64, ApiError> {
+    match crate::server::extract_user_from_token(token) {
+        Some((user_id, user_type)) if user_type == "admin" => Ok(user_id),
+        Some(_) => Err(ApiError::unauthorized("Admin access required")),
+        None => Err(ApiError::unauthorized("Invalid or expired token")),
+    }
+}
+
+/// Queues an email to the artist about a question that's reached them
+#[cfg(feature = "ssr")]
+async fn notify_artist(question_id: i32) {
+    use crate::jobs::{enqueue, Job};
+    use crate::notify::{Channel, Message};
+
+    let notice =
+        match crate::db::artist_question_repository::get_new_question_notice(question_id).await {
+            Ok(Some(notice)) => notice,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!(question_id, "Failed to load question to notify: {}", e);
+                return;
+            }
+        };
+    let Some(email) = notice.artist_email.filter(|email| !email.is_empty()) else {
+        return;
+    };
+
+    let message = Message {
+        channel: Channel::Email,
+        to: email,
+        subject: format!("{} asked you a question on Tatteau", notice.asker_name),
+        body: format!(
+            "{} asked:\n\n\"{}\"\n\nAnswer it in your settings at {}/artist/dashboard/settings. \
+             Answered questions are shown on your profile.",
+            notice.asker_name,
+            notice.question,
+            crate::config::config().app_base_url
+        ),
+    };
+    if let Err(e) = enqueue(Job::SendNotification(message)).await {
+        tracing::error!(
+            artist_id = notice.artist_id,
+            "Failed to queue question notification: {}",
+            e
+        );
+    }
+}
+
+/// Asks an artist a question. Signed-in clients are asked as themselves;
+/// anyone else gives a name and an email for the answer to go to.
+#[server(prefix = "/api", endpoint = "ask_artist_question")]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(token, asker_email, question), err, level = "info")
+)]
+pub async fn ask_artist_question(
+    artist_id: i32,
+    token: Option<String>,
+    asker_name: String,
+    asker_email: String,
+    question: String,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::artist_question_repository;
+        use crate::utils::artist_questions::{normalize_name, normalize_question, spam_reason};
+
+        let question =
+            normalize_question(&question).map_err(|e| ApiError::validation("question", e))?;
+
+        let asker_user_id = token
+            .as_deref()
+            .and_then(crate::server::extract_user_from_token)
+            .map(|(user_id, _)| user_id);
+        let (asker_name, asker_email) = match asker_user_id {
+            Some(user_id) => {
+                let account = crate::db::client_dashboard_repository::get_client_identity(user_id)
+                    .await
+                    .map_err(|e| ApiError::internal("Failed to load account", e))?
+                    .ok_or_else(|| ApiError::not_found("Account not found"))?;
+                let name = normalize_name(&account.name)
+                    .or_else(|_| normalize_name(&asker_name))
+                    .map_err(|e| ApiError::validation("asker_name", e))?;
+                (name, account.email)
+            }
+            None => {
+                let name = normalize_name(&asker_name)
+                    .map_err(|e| ApiError::validation("asker_name", e))?;
+                let email = asker_email.trim().to_lowercase();
+                if !email.contains('@') || email.len() > 254 {
+                    return Err(ApiError::validation(
+                        "asker_email",
+                        "Enter an email so you hear back when it's answered",
+                    )
+                    .into());
+                }
+                (name, email)
+            }
+        };
+
+        if !artist_question_repository::artist_exists(artist_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load artist", e))?
+        {
+            return Err(ApiError::not_found("Artist not found").into());
+        }
+        crate::rate_limit::check_question_email(&asker_email).await?;
+
+        let flag_reason = spam_reason(&question);
+        let question_id = artist_question_repository::insert_question(
+            artist_id,
+            asker_user_id,
+            &asker_name,
+            Some(&asker_email),
+            &question,
+            flag_reason,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to save question", e))?;
+
+        if flag_reason.is_some() {
+            tracing::info!(question_id, flag_reason, "Question held for moderation");
+        } else {
+            notify_artist(question_id).await;
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The questions clients have asked the signed-in artist, unanswered first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_questions(
+    token: String,
+) -> Result<Vec<ArtistQuestion>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Messages).await?;
+
+        Ok(
+            crate::db::artist_question_repository::get_artist_questions(artist_id, LIST_SIZE)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load questions", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Answers a question on the signed-in artist's profile, or edits the
+/// answer. The asker is emailed the first answer.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, answer), err, level = "info"))]
+pub async fn answer_artist_question(
+    token: String,
+    question_id: i32,
+    answer: String,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::jobs::{enqueue, Job};
+        use crate::notify::{Channel, Message};
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::artist_questions::normalize_answer;
+
+        let artist_id = authorize_artist(&token, TeamPermission::Messages).await?;
+        let answer = normalize_answer(&answer).map_err(|e| ApiError::validation("answer", e))?;
+
+        let notice =
+            crate::db::artist_question_repository::answer_question(artist_id, question_id, &answer)
+                .await
+                .map_err(|e| ApiError::internal("Failed to save answer", e))?
+                .ok_or_else(|| ApiError::not_found("Question not found"))?;
+
+        if let Some(email) = notice
+            .asker_email
+            .filter(|email| notice.first_answer && !email.is_empty())
+        {
+            let artist_name = notice
+                .artist_name
+                .unwrap_or_else(|| "The artist".to_string());
+            let message = Message {
+                channel: Channel::Email,
+                to: email,
+                subject: format!("{} answered your question", artist_name),
+                body: format!(
+                    "You asked:\n\n\"{}\"\n\n{} answered:\n\n\"{}\"\n\n\
+                     See their profile at {}/artist/{}.",
+                    notice.question,
+                    artist_name,
+                    answer,
+                    crate::config::config().app_base_url,
+                    artist_id
+                ),
+            };
+            if let Err(e) = enqueue(Job::SendNotification(message)).await {
+                tracing::error!(question_id, "Failed to queue answer notification: {}", e);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Reports a question on the signed-in artist's profile as spam, taking it
+/// off their list and profile until an admin looks at it.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn report_artist_question(
+    token: String,
+    question_id: i32,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Messages).await?;
+
+        let reported =
+            crate::db::artist_question_repository::report_question(artist_id, question_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to report question", e))?;
+        if !reported {
+            return Err(ApiError::not_found("Question not found").into());
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Questions with `status` ("approved", "held" or "rejected") for admins,
+/// oldest first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_questions_for_moderation(
+    token: String,
+    status: String,
+) -> Result<Vec<ArtistQuestion>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::artist_questions::QUESTION_STATUSES;
+
+        authorize_admin(&token)?;
+        if !QUESTION_STATUSES.contains(&status.as_str()) {
+            return Err(ApiError::validation("status", "Unknown question status").into());
+        }
+
+        Ok(
+            crate::db::artist_question_repository::get_questions_by_status(&status, LIST_SIZE)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load questions", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Approves a question, sending it on to the artist, or rejects it as spam.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn moderate_artist_question(
+    token: String,
+    question_id: i32,
+    approve: bool,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        let admin_id = authorize_admin(&token)?;
+
+        let newly_approved = crate::db::artist_question_repository::moderate_question(
+            question_id,
+            approve,
+            admin_id,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to moderate question", e))?
+        .ok_or_else(|| ApiError::not_found("Question not found"))?;
+
+        if newly_approved {
+            notify_artist(question_id).await;
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,115 @@
+use leptos::prelude::*;
+
+use crate::db::entities::AutoResponseSettings;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// The signed-in artist's auto-response settings and templates
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_auto_response_settings(
+    token: String,
+) -> Result<AutoResponseSettings, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::auto_response_repository::get_settings(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load auto-responses: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Replaces the signed-in artist's auto-response settings. Requests already
+/// submitted keep the schedule they were given.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, settings), err, level = "info"))]
+pub async fn update_auto_response_settings(
+    token: String,
+    settings: AutoResponseSettings,
+) -> Result<AutoResponseSettings, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::auto_response::{
+            booking_type_label, unknown_variables, BOOKING_TYPES, DEFAULT_VARIANT,
+            MAX_DELAY_MINUTES, MAX_TEMPLATE_LEN,
+        };
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        if !(0..=MAX_DELAY_MINUTES).contains(&settings.delay_minutes) {
+            return Err(ServerFnError::new(format!(
+                "The delay must be between 0 and {} minutes",
+                MAX_DELAY_MINUTES
+            )));
+        }
+
+        let mut seen = Vec::new();
+        for template in &settings.templates {
+            let booking_type = template.booking_type.as_str();
+            if booking_type != DEFAULT_VARIANT
+                && !BOOKING_TYPES.iter().any(|(value, _)| *value == booking_type)
+            {
+                return Err(ServerFnError::new(format!(
+                    "Unknown booking type: {}",
+                    booking_type
+                )));
+            }
+            if seen.contains(&booking_type) {
+                return Err(ServerFnError::new(format!(
+                    "More than one reply for {}",
+                    booking_type_label(booking_type)
+                )));
+            }
+            seen.push(booking_type);
+
+            if template.message.chars().count() > MAX_TEMPLATE_LEN {
+                return Err(ServerFnError::new(format!(
+                    "Replies can be at most {} characters",
+                    MAX_TEMPLATE_LEN
+                )));
+            }
+            if let Some(variable) = unknown_variables(&template.message).first() {
+                return Err(ServerFnError::new(format!(
+                    "Unknown placeholder {{{}}}",
+                    variable
+                )));
+            }
+        }
+
+        if settings.enabled
+            && settings
+                .templates
+                .iter()
+                .all(|template| template.message.trim().is_empty())
+        {
+            return Err(ServerFnError::new(
+                "Write a reply before switching auto-responses on".to_string(),
+            ));
+        }
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to save auto-responses: {}", e));
+        crate::db::auto_response_repository::save_settings(artist_id, &settings)
+            .await
+            .map_err(db_error)?;
+        crate::db::auto_response_repository::get_settings(artist_id)
+            .await
+            .map_err(db_error)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
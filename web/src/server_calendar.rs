@@ -0,0 +1,418 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{CalendarFeed, CalendarImport};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+#[cfg(feature = "ssr")]
+use crate::db::{
+    calendar_feed_repository::CalendarBooking,
+    entities::{AvailabilitySlot, RecurringRule},
+};
+#[cfg(feature = "ssr")]
+use crate::utils::ics::{IcsEvent, IcsTime};
+
+/// Length of a booking in the feed when the request gave no end time
+#[cfg(feature = "ssr")]
+const DEFAULT_BOOKING_HOURS: i64 = 2;
+
+#[cfg(feature = "ssr")]
+fn parse_time(time: &str) -> Option<chrono::NaiveTime> {
+    chrono::NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| chrono::NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()
+}
+
+/// Timed event on `date` when both times parse, otherwise an all-day event
+#[cfg(feature = "ssr")]
+fn event_times(
+    date: chrono::NaiveDate,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> (IcsTime, IcsTime) {
+    match (start.and_then(parse_time), end.and_then(parse_time)) {
+        (Some(start), Some(end)) if end > start => (
+            IcsTime::DateTime(date.and_time(start)),
+            IcsTime::DateTime(date.and_time(end)),
+        ),
+        _ => (
+            IcsTime::Date(date),
+            IcsTime::Date(date + chrono::Duration::days(1)),
+        ),
+    }
+}
+
+/// Available slots are free time, blocked slots show as busy
+#[cfg(feature = "ssr")]
+fn availability_event(slot: &AvailabilitySlot) -> Option<IcsEvent> {
+    let date =
+        chrono::NaiveDate::parse_from_str(slot.specific_date.as_deref()?, "%Y-%m-%d").ok()?;
+    let (start, end) = event_times(date, slot.start_time.as_deref(), slot.end_time.as_deref());
+
+    Some(IcsEvent {
+        uid: format!("availability-{}@tatteau", slot.id),
+        summary: if slot.is_available {
+            "Available for bookings".to_string()
+        } else {
+            "Blocked".to_string()
+        },
+        description: None,
+        start,
+        end,
+        rrule: None,
+        exceptions: Vec::new(),
+        busy: !slot.is_available,
+    })
+}
+
+/// One repeating event per RRULE series of an active rule, starting from its
+/// first occurrence in the feed's window
+#[cfg(feature = "ssr")]
+fn rule_events(rule: &RecurringRule) -> Vec<IcsEvent> {
+    use crate::db::calendar_feed_repository::FEED_HISTORY_DAYS;
+
+    let Ok(schedule_rule) = crate::utils::recurrence::stored_schedule_rule(rule) else {
+        return Vec::new();
+    };
+    let from =
+        chrono::Local::now().date_naive() - chrono::Duration::days(i64::from(FEED_HISTORY_DAYS));
+
+    schedule_rule
+        .series()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, series)| {
+            let date = series.next_occurrence(from)?;
+            let (start, end) =
+                event_times(date, rule.start_time.as_deref(), rule.end_time.as_deref());
+            Some(IcsEvent {
+                uid: format!("rule-{}-{}@tatteau", rule.id, index),
+                summary: rule.name.clone(),
+                description: None,
+                start,
+                end,
+                rrule: Some(series.rrule()),
+                exceptions: series.exceptions.clone(),
+                busy: !series.available,
+            })
+        })
+        .collect()
+}
+
+#[cfg(feature = "ssr")]
+fn booking_event(booking: &CalendarBooking) -> Option<IcsEvent> {
+    let date = chrono::NaiveDate::parse_from_str(&booking.requested_date, "%Y-%m-%d").ok()?;
+    let end_time = match booking.requested_end_time.as_deref() {
+        Some(end) => Some(end.to_string()),
+        None => parse_time(&booking.requested_start_time).map(|start| {
+            (start + chrono::Duration::hours(DEFAULT_BOOKING_HOURS))
+                .format("%H:%M")
+                .to_string()
+        }),
+    };
+    let (start, end) = event_times(
+        date,
+        Some(&booking.requested_start_time),
+        end_time.as_deref(),
+    );
+
+    let description = [
+        booking
+            .placement
+            .as_ref()
+            .map(|p| format!("Placement: {}", p)),
+        booking.tattoo_description.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    Some(IcsEvent {
+        uid: format!("booking-{}@tatteau", booking.id),
+        summary: format!("Tattoo: {}", booking.client_name),
+        description: (!description.is_empty()).then_some(description),
+        start,
+        end,
+        rrule: None,
+        exceptions: Vec::new(),
+        busy: true,
+    })
+}
+
+/// Renders the feed of `kind` for an artist as an ICS response
+#[cfg(feature = "ssr")]
+async fn feed_response(artist_id: i32, kind: &str) -> axum::response::Response {
+    use crate::db::calendar_feed_repository;
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+
+    let mut events: Vec<IcsEvent> = Vec::new();
+
+    if kind != "availability" {
+        match calendar_feed_repository::get_feed_bookings(artist_id).await {
+            Ok(bookings) => events.extend(bookings.iter().filter_map(booking_event)),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load bookings feed for artist {}: {}",
+                    artist_id,
+                    e
+                );
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+    }
+    if kind != "bookings" {
+        match calendar_feed_repository::get_feed_availability(artist_id).await {
+            Ok(slots) => events.extend(slots.iter().filter_map(availability_event)),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load availability feed for artist {}: {}",
+                    artist_id,
+                    e
+                );
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+        match crate::db::recurring_rule_repository::get_rules(artist_id).await {
+            Ok(rules) => events.extend(
+                rules
+                    .iter()
+                    .filter(|rule| rule.active)
+                    .flat_map(rule_events),
+            ),
+            Err(e) => {
+                tracing::error!(
+                    "Failed to load recurring rules feed for artist {}: {}",
+                    artist_id,
+                    e
+                );
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+    }
+
+    let name = match kind {
+        "availability" => "tatteau availability",
+        "bookings" => "tatteau bookings",
+        _ => "tatteau calendar",
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+            (header::CACHE_CONTROL, "private, max-age=300"),
+        ],
+        crate::utils::ics::render_calendar(name, &events),
+    )
+        .into_response()
+}
+
+/// GET /api/calendar/:token.ics — the feed's secret token is the only
+/// credential, so calendar apps can poll it without logging in.
+#[cfg(feature = "ssr")]
+pub async fn calendar_feed_handler(
+    axum::extract::Path(feed): axum::extract::Path<String>,
+) -> axum::response::Response {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    let token = feed.trim_end_matches(".ics");
+    match crate::db::calendar_feed_repository::resolve_calendar_feed(token).await {
+        Ok(Some((artist_id, kind))) => feed_response(artist_id, &kind).await,
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to resolve calendar feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+#[derive(serde::Deserialize)]
+pub struct ArtistCalendarQuery {
+    token: String,
+}
+
+/// GET /api/artist/:id/calendar.ics?token=… — approved bookings and
+/// availability in one calendar. The token must be a live feed of that artist.
+#[cfg(feature = "ssr")]
+pub async fn artist_calendar_handler(
+    axum::extract::Path(artist_id): axum::extract::Path<i32>,
+    axum::extract::Query(query): axum::extract::Query<ArtistCalendarQuery>,
+) -> axum::response::Response {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    match crate::db::calendar_feed_repository::resolve_calendar_feed(&query.token).await {
+        Ok(Some((feed_artist_id, kind))) if feed_artist_id == artist_id => {
+            feed_response(artist_id, &kind).await
+        }
+        Ok(_) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to resolve calendar feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Creates a new subscribable feed of `kind` (`bookings`, `availability`, or
+/// `all` for both in one calendar).
+/// An artist can hold several feeds of each kind, each revocable on its own.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn create_calendar_feed(
+    token: String,
+    kind: String,
+) -> Result<CalendarFeed, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        if !["bookings", "availability", "all"].contains(&kind.as_str()) {
+            return Err(ServerFnError::new(format!(
+                "Unknown calendar feed type: {}",
+                kind
+            )));
+        }
+
+        crate::db::calendar_feed_repository::create_calendar_feed(artist_id, &kind)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to create calendar feed: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_calendar_feeds(token: String) -> Result<Vec<CalendarFeed>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::calendar_feed_repository::get_calendar_feeds(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load calendar feeds: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn revoke_calendar_feed(token: String, feed_id: i64) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::calendar_feed_repository::revoke_calendar_feed(artist_id, feed_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to revoke calendar feed: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Imports busy time from another calendar at `url` (its secret ICS
+/// address), reading times without a zone in `timezone`, an IANA name like
+/// `America/Chicago`. The calendar is fetched straight away, so an address
+/// that doesn't work is reported now; after that it's kept in sync in the
+/// background.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, url), err, level = "info"))]
+pub async fn add_calendar_import(
+    token: String,
+    url: String,
+    timezone: String,
+) -> Result<CalendarImport, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::calendar_imports;
+        use crate::db::calendar_import_repository::{self, DueImport};
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let url = calendar_imports::normalize_calendar_url(&url).map_err(ServerFnError::new)?;
+        let timezone = calendar_imports::parse_timezone(&timezone)
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .name()
+            .to_string();
+
+        let import = calendar_import_repository::add_calendar_import(artist_id, &url, &timezone)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to add calendar: {}", e)))?;
+
+        let due = DueImport {
+            id: import.id,
+            artist_id,
+            url,
+            timezone,
+        };
+        if let Err(e) = calendar_imports::sync_import(&due).await {
+            calendar_import_repository::remove_calendar_import(artist_id, import.id)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to add calendar: {}", e)))?;
+            return Err(ServerFnError::new(format!(
+                "Couldn't import that calendar: {}",
+                e
+            )));
+        }
+
+        calendar_import_repository::get_calendar_imports(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load calendars: {}", e)))?
+            .into_iter()
+            .find(|added| added.id == import.id)
+            .ok_or_else(|| ServerFnError::new("Calendar not found".to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_calendar_imports(token: String) -> Result<Vec<CalendarImport>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::calendar_import_repository::get_calendar_imports(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load calendars: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Stops importing a calendar and frees the time it had blocked
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn remove_calendar_import(token: String, import_id: i64) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::calendar_import_repository::remove_calendar_import(artist_id, import_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to remove calendar: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,199 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{BookingMessage, ClientBooking, ClientMessageThread};
+use crate::db::favorites_repository::FavoritePostWithDetails;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Most favorites previewed on the dashboard
+#[cfg(feature = "ssr")]
+const MAX_DASHBOARD_FAVORITES: usize = 12;
+
+/// Longest message a client can send
+#[cfg(feature = "ssr")]
+const MAX_MESSAGE_LEN: usize = 2000;
+
+#[cfg(feature = "ssr")]
+fn client_user_id(token: &str) -> Result<i64, ServerFnError> {
+    crate::auth::decode_access_token(token)
+        .map(|claims| claims.user_id)
+        .ok_or_else(|| ServerFnError::new("Invalid token".to_string()))
+}
+
+/// Loads the booking if it belongs to the signed-in client
+#[cfg(feature = "ssr")]
+async fn own_booking(token: &str, booking_id: i32) -> Result<ClientBooking, ServerFnError> {
+    let user_id = client_user_id(token)?;
+
+    crate::db::client_dashboard_repository::get_client_booking(user_id, booking_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to load booking: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("Booking not found".to_string()))
+}
+
+/// Booking requests the signed-in client has made, newest first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_booking_requests(token: String) -> Result<Vec<ClientBooking>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = client_user_id(&token)?;
+
+        crate::db::client_dashboard_repository::get_client_bookings(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get booking requests: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// The signed-in client's approved bookings from today on, soonest first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_upcoming_appointments(
+    token: String,
+) -> Result<Vec<ClientBooking>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = client_user_id(&token)?;
+
+        crate::db::client_dashboard_repository::get_upcoming_appointments(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get appointments: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// A thread per booking that has messages, most recently active first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_message_threads(
+    token: String,
+) -> Result<Vec<ClientMessageThread>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = client_user_id(&token)?;
+
+        crate::db::client_dashboard_repository::get_message_threads(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get messages: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// One of the client's bookings with its messages.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_booking_thread(
+    token: String,
+    booking_id: i32,
+) -> Result<(ClientBooking, Vec<BookingMessage>), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let booking = own_booking(&token, booking_id).await?;
+
+        let messages = crate::db::client_dashboard_repository::get_booking_messages(booking_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get messages: {}", e)))?;
+
+        if let Err(e) =
+            crate::db::message_read_repository::mark_thread_read(booking_id, "client").await
+        {
+            tracing::warn!("Failed to mark messages read: {}", e);
+        }
+        Ok((booking, messages))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Sends a message to the artist on one of the client's bookings.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, message), err, level = "info"))]
+pub async fn send_my_booking_message(
+    token: String,
+    booking_id: i32,
+    message: String,
+) -> Result<BookingMessage, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::entities::BookingEventKind;
+
+        own_booking(&token, booking_id).await?;
+
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(ServerFnError::new("Message can't be empty".to_string()));
+        }
+        if message.chars().count() > MAX_MESSAGE_LEN {
+            return Err(ServerFnError::new(format!(
+                "Messages must be at most {} characters",
+                MAX_MESSAGE_LEN
+            )));
+        }
+
+        let translation =
+            crate::translate::translate_booking_message(booking_id, "client", message).await;
+        let sent = crate::db::client_dashboard_repository::insert_client_message(
+            booking_id,
+            message,
+            &translation,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to send message: {}", e)))?;
+
+        crate::server::record_booking_event(
+            booking_id,
+            "client",
+            BookingEventKind::Message {
+                sender_type: sent.sender_type.clone(),
+                message: sent.message.clone(),
+            },
+        )
+        .await;
+        crate::message_stream::publish(sent.clone());
+
+        Ok(sent)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The client's most recently saved favorites, for the dashboard preview.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_recent_favorites(
+    token: String,
+) -> Result<Vec<FavoritePostWithDetails>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = client_user_id(&token)?;
+
+        crate::db::favorites_repository::get_recent_favorites_with_details(
+            user_id as i32,
+            MAX_DASHBOARD_FAVORITES,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to get favorites: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,126 @@
+use leptos::prelude::*;
+
+use crate::db::entities::ProfileCompleteness;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Longest bio accepted, in characters
+pub const MAX_BIO_CHARS: usize = 1000;
+
+/// The signed-in artist's profile completeness score with a checklist of
+/// what's left, biggest gains first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_profile_completeness(token: String) -> Result<ProfileCompleteness, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::completeness_repository::get_completeness(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load profile completeness: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The signed-in artist's bio.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_artist_bio(token: String) -> Result<Option<String>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::completeness_repository::get_bio(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load bio: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// Sets the signed-in artist's bio; blank clears it.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, bio), err, level = "info"))]
+pub async fn update_artist_bio(token: String, bio: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        if bio.trim().chars().count() > MAX_BIO_CHARS {
+            return Err(ServerFnError::new(format!(
+                "Bios can be at most {} characters",
+                MAX_BIO_CHARS
+            )));
+        }
+
+        crate::db::completeness_repository::update_bio(artist_id, &bio)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save bio: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The signed-in artist's experience tier ("apprentice", "junior" or
+/// "senior"), if they've set one.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_artist_experience_tier(token: String) -> Result<Option<String>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::completeness_repository::get_experience_tier(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load experience tier: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// Sets the signed-in artist's experience tier; blank clears it.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_artist_experience_tier(
+    token: String,
+    tier: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::experience::normalize_tier;
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        let tier = normalize_tier(&tier).map_err(ServerFnError::new)?;
+
+        crate::db::completeness_repository::update_experience_tier(artist_id, tier.as_deref())
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save experience tier: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,446 @@
+//! Consent forms and aftercare instructions. Each artist has one of each,
+//! their own or the default. The client reads and signs the consent form on
+//! the page their booking link opens, by typing their name; the signature
+//! keeps the form as they read it along with when, and from which IP
+//! address and browser, they signed. The signed form downloads as a PDF
+//! for the artist and the client. The aftercare instructions are emailed to
+//! the client once, when the artist marks the booking completed.
+
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::{ConsentForm, ConsentSignature, DocumentTemplate};
+
+#[cfg(feature = "ssr")]
+use crate::db::document_repository::{self, BookingDocumentContext};
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+#[cfg(feature = "ssr")]
+fn validate_kind(kind: &str) -> Result<(), ApiError> {
+    use crate::utils::documents::DOCUMENT_KINDS;
+
+    if !DOCUMENT_KINDS.iter().any(|(value, _)| *value == kind) {
+        return Err(ApiError::validation("kind", "Unknown document"));
+    }
+    Ok(())
+}
+
+/// What the artist's documents are filled in with for the booking
+#[cfg(feature = "ssr")]
+fn document_values(context: &BookingDocumentContext) -> crate::utils::documents::DocumentValues {
+    use crate::utils::timezone::{convert_to_12_hour_format, format_date_for_booking};
+
+    crate::utils::documents::DocumentValues {
+        client_name: context.client_name.clone(),
+        artist_name: context
+            .artist_name
+            .clone()
+            .unwrap_or_else(|| "your artist".to_string()),
+        appointment_date: format_date_for_booking(&context.requested_date),
+        appointment_time: convert_to_12_hour_format(&context.requested_start_time),
+        placement: context.placement.clone(),
+        description: context.tattoo_description.clone(),
+    }
+}
+
+#[cfg(feature = "ssr")]
+async fn load_context(booking_id: i32) -> Result<BookingDocumentContext, ApiError> {
+    document_repository::get_booking_context(booking_id)
+        .await
+        .map_err(|e| ApiError::internal("Failed to load booking", e))?
+        .ok_or_else(|| ApiError::not_found("Booking not found"))
+}
+
+/// The client's IP address and browser, as best the request tells
+#[cfg(feature = "ssr")]
+async fn request_origin() -> (Option<String>, Option<String>) {
+    use axum::extract::ConnectInfo;
+    use axum::http::{header, HeaderMap};
+    use std::net::SocketAddr;
+
+    let Ok((headers, peer)) =
+        leptos_axum::extract::<(HeaderMap, Option<ConnectInfo<SocketAddr>>)>().await
+    else {
+        return (None, None);
+    };
+    let ip = crate::rate_limit::client_ip(&headers, peer.map(|ConnectInfo(addr)| addr.ip()));
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(500).collect());
+    (ip.map(|ip| ip.to_string()), user_agent)
+}
+
+/// The signed-in artist's document of this kind, theirs or the default.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_document_template(
+    token: String,
+    kind: String,
+) -> Result<DocumentTemplate, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        validate_kind(&kind)?;
+
+        Ok(document_repository::get_document(artist_id, &kind)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load document", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Saves the signed-in artist's own document of this kind. Consent forms
+/// already signed keep what the client signed.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, body), err, level = "info"))]
+pub async fn save_document_template(
+    token: String,
+    kind: String,
+    title: String,
+    body: String,
+) -> Result<DocumentTemplate, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::documents::{unknown_variables, MAX_DOCUMENT_CHARS, MAX_TITLE_CHARS};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        validate_kind(&kind)?;
+
+        let title = title.trim();
+        let body = body.trim();
+        if title.is_empty() {
+            return Err(ApiError::validation("title", "Give the document a title").into());
+        }
+        if title.chars().count() > MAX_TITLE_CHARS {
+            return Err(ApiError::validation(
+                "title",
+                format!("Titles can be at most {} characters", MAX_TITLE_CHARS),
+            )
+            .into());
+        }
+        if body.is_empty() {
+            return Err(ApiError::validation("body", "Write the document first").into());
+        }
+        if body.chars().count() > MAX_DOCUMENT_CHARS {
+            return Err(ApiError::validation(
+                "body",
+                format!("Documents can be at most {} characters", MAX_DOCUMENT_CHARS),
+            )
+            .into());
+        }
+        if let Some(variable) = unknown_variables(body).first() {
+            return Err(ApiError::validation(
+                "body",
+                format!("Unknown placeholder {{{}}}", variable),
+            )
+            .into());
+        }
+
+        let db_error = |e: sqlx::Error| ApiError::internal("Failed to save document", e);
+        document_repository::save_document(artist_id, &kind, title, body)
+            .await
+            .map_err(db_error)?;
+        Ok(document_repository::get_document(artist_id, &kind)
+            .await
+            .map_err(db_error)?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Goes back to the default document of this kind, returning it.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn reset_document_template(
+    token: String,
+    kind: String,
+) -> Result<DocumentTemplate, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        validate_kind(&kind)?;
+
+        let db_error = |e: sqlx::Error| ApiError::internal("Failed to reset document", e);
+        document_repository::delete_document(artist_id, &kind)
+            .await
+            .map_err(db_error)?;
+        Ok(document_repository::get_document(artist_id, &kind)
+            .await
+            .map_err(db_error)?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The booking's consent form for the client behind the link: as they
+/// signed it, or filled in for them to sign.
+#[server(prefix = "/api", endpoint = "get_consent_form")]
+#[cfg_attr(feature = "ssr", instrument(skip(link_token), err, level = "info"))]
+pub async fn get_consent_form(
+    booking_id: i32,
+    link_token: String,
+) -> Result<ConsentForm, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::documents::{render, CONSENT};
+
+        crate::server_reschedule::verify_link(booking_id, &link_token)?;
+
+        let signature = document_repository::get_signature(booking_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load consent form", e))?;
+        if let Some(signature) = signature {
+            return Ok(ConsentForm {
+                booking_id,
+                title: signature.title.clone(),
+                body: signature.body.clone(),
+                signature: Some(signature),
+            });
+        }
+
+        let context = load_context(booking_id).await?;
+        let template = document_repository::get_document(context.artist_id, CONSENT)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load consent form", e))?;
+        Ok(ConsentForm {
+            booking_id,
+            title: template.title,
+            body: render(&template.body, &document_values(&context)),
+            signature: None,
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Signs the booking's consent form with the name the client typed,
+/// recording the form as it reads now along with their IP address and
+/// browser. A form can only be signed once.
+#[server(prefix = "/api", endpoint = "sign_consent_form")]
+#[cfg_attr(feature = "ssr", instrument(skip(link_token), err, level = "info"))]
+pub async fn sign_consent_form(
+    booking_id: i32,
+    link_token: String,
+    typed_name: String,
+    agreed: bool,
+) -> Result<ConsentSignature, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::documents::{normalize_typed_name, render, CONSENT};
+        use shared_types::BookingStatus;
+
+        crate::server_reschedule::verify_link(booking_id, &link_token)?;
+        if !agreed {
+            return Err(ApiError::validation(
+                "agreed",
+                "Tick the box to confirm you've read and agree to the form",
+            )
+            .into());
+        }
+        let typed_name =
+            normalize_typed_name(&typed_name).map_err(|e| ApiError::validation("typed_name", e))?;
+
+        let context = load_context(booking_id).await?;
+        if matches!(
+            BookingStatus::parse(&context.status),
+            Some(BookingStatus::Declined | BookingStatus::Cancelled)
+        ) {
+            return Err(ApiError::conflict("This booking isn't going ahead").into());
+        }
+
+        let template = document_repository::get_document(context.artist_id, CONSENT)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load consent form", e))?;
+        let body = render(&template.body, &document_values(&context));
+        let (ip_address, user_agent) = request_origin().await;
+
+        Ok(document_repository::save_signature(
+            booking_id,
+            &template.title,
+            &body,
+            &typed_name,
+            ip_address.as_deref(),
+            user_agent.as_deref(),
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to sign consent form", e))?
+        .ok_or_else(|| ApiError::conflict("This consent form has already been signed"))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The client's signature on one of the artist's bookings, if they've
+/// signed.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_booking_consent(
+    token: String,
+    booking_id: i32,
+) -> Result<Option<ConsentSignature>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_booking, TeamPermission};
+
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+
+        Ok(document_repository::get_signature(booking_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load consent form", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// Emails the client a link to read and sign the booking's consent form.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn send_consent_form(
+    token: String,
+    booking_id: i32,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::notify::{self, Channel, Message};
+        use crate::server_team::{authorize_booking, TeamPermission};
+
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+
+        let signed = document_repository::get_signature(booking_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load consent form", e))?;
+        if signed.is_some() {
+            return Err(
+                ApiError::conflict("The client has already signed the consent form").into(),
+            );
+        }
+
+        let context = load_context(booking_id).await?;
+        let artist = context.artist_name.as_deref().unwrap_or("Your artist");
+        let message = Message {
+            channel: Channel::Email,
+            to: context.client_email.clone(),
+            subject: format!("{} needs your consent before your tattoo", artist),
+            body: format!(
+                "Hi {},\n\nBefore your appointment, please read and sign {}'s consent form \
+                 here: {}",
+                context.client_name,
+                artist,
+                crate::server_reschedule::response_link(booking_id)
+            ),
+        };
+        notify::send(&message)
+            .await
+            .map_err(|e| ApiError::internal("Failed to email the consent form", e))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Emails the client the artist's aftercare instructions, unless they've
+/// already had them. Called when the booking is marked completed.
+#[cfg(feature = "ssr")]
+pub(crate) async fn send_aftercare(booking_id: i32) -> crate::hooks::HookResult {
+    use crate::notify::{self, Channel, Message};
+    use crate::utils::documents::{render, to_plain_text, AFTERCARE};
+
+    let Some(context) = document_repository::get_booking_context(booking_id).await? else {
+        return Ok(());
+    };
+    if context.client_email.is_empty() {
+        return Ok(());
+    }
+    let template = document_repository::get_document(context.artist_id, AFTERCARE).await?;
+    if !document_repository::claim_aftercare(booking_id).await? {
+        return Ok(());
+    }
+
+    let values = document_values(&context);
+    let message = Message {
+        channel: Channel::Email,
+        to: context.client_email.clone(),
+        subject: render(&template.title, &values),
+        body: to_plain_text(&render(&template.body, &values)),
+    };
+    if let Err(e) = notify::send(&message).await {
+        document_repository::release_aftercare(booking_id).await?;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+#[derive(serde::Deserialize)]
+pub struct ConsentPdfParams {
+    /// The artist's session token, or the client's booking link token
+    pub token: String,
+}
+
+/// The signed consent form as a PDF, for the booking's artist (and team) or
+/// the client through their link
+#[cfg(feature = "ssr")]
+pub async fn consent_pdf_handler(
+    axum::extract::Path(booking_id): axum::extract::Path<i32>,
+    axum::extract::Query(params): axum::extract::Query<ConsentPdfParams>,
+) -> axum::response::Response {
+    use crate::server_team::{authorize_booking, TeamPermission};
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+
+    let allowed = crate::server_reschedule::verify_link(booking_id, &params.token).is_ok()
+        || authorize_booking(&params.token, booking_id, TeamPermission::Bookings)
+            .await
+            .is_ok();
+    if !allowed {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let signature = match document_repository::get_signature(booking_id).await {
+        Ok(Some(signature)) => signature,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!(
+                "Failed to load consent form for booking {}: {}",
+                booking_id,
+                e
+            );
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let pdf = crate::utils::consent_pdf::render_consent_pdf(&signature);
+    (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"consent-{}.pdf\"", booking_id),
+            ),
+        ],
+        pdf,
+    )
+        .into_response()
+}
@@ -0,0 +1,84 @@
+//! Settings for the booking widget artists embed on their own sites (see
+//! `embed.rs`): its address, and which sites may show it.
+
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::BookingWidgetConfig;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+#[cfg(feature = "ssr")]
+async fn load_config(artist_id: i32) -> Result<BookingWidgetConfig, ApiError> {
+    let artist = crate::db::embed_repository::get_widget_artist(artist_id)
+        .await
+        .map_err(|e| ApiError::internal("Failed to load booking widget", e))?
+        .ok_or_else(|| ApiError::not_found("Artist not found"))?;
+
+    Ok(BookingWidgetConfig {
+        widget_url: crate::embed::widget_url(artist_id),
+        allowed_origins: artist.allowed_origins,
+    })
+}
+
+/// The signed-in artist's booking widget
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_booking_widget(
+    token: String,
+) -> Result<BookingWidgetConfig, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        Ok(load_config(artist_id).await?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Sets the sites allowed to embed the signed-in artist's booking widget,
+/// any site when `origins` is empty
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_booking_widget_origins(
+    token: String,
+    origins: Vec<String>,
+) -> Result<BookingWidgetConfig, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::embed::{normalize_origin, MAX_EMBED_ORIGINS};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let mut normalized = Vec::new();
+        for origin in origins.iter().filter(|origin| !origin.trim().is_empty()) {
+            let origin = normalize_origin(origin)
+                .map_err(|message| ApiError::validation("origins", message))?;
+            if !normalized.contains(&origin) {
+                normalized.push(origin);
+            }
+        }
+        if normalized.len() > MAX_EMBED_ORIGINS {
+            return Err(ApiError::validation(
+                "origins",
+                format!("Add at most {} sites", MAX_EMBED_ORIGINS),
+            )
+            .into());
+        }
+
+        crate::db::embed_repository::set_allowed_origins(artist_id, &normalized)
+            .await
+            .map_err(|e| ApiError::internal("Failed to save booking widget", e))?;
+        Ok(load_config(artist_id).await?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
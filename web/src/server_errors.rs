@@ -0,0 +1,220 @@
+//! The admin error dashboard. Logged errors are grouped by fingerprint (see
+//! `utils::error_triage`); admins see which groups are happening most,
+//! how often over time and their latest occurrences, assign them and mark
+//! them resolved or ignored.
+
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::{ErrorAssignee, ErrorCount, ErrorGroup, ErrorLog};
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Groups listed on the dashboard
+#[cfg(feature = "ssr")]
+const GROUPS_LISTED: i64 = 100;
+/// Latest occurrences shown for a group
+#[cfg(feature = "ssr")]
+const SAMPLES_SHOWN: i64 = 20;
+/// Longest window the dashboard covers
+#[cfg(feature = "ssr")]
+const MAX_DAYS: i32 = 90;
+
+/// The signed-in admin's user id
+#[cfg(feature = "ssr")]
+fn authorize_admin(token: &str) -> Result<i64, ApiError> {
+    match crate::server::extract_user_from_token(token) {
+        Some((user_id, user_type)) if user_type == "admin" => Ok(user_id),
+        Some(_) => Err(ApiError::unauthorized("Admin access required")),
+        None => Err(ApiError::unauthorized("Invalid or expired token")),
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn window_days(days: i32) -> Result<i32, ApiError> {
+    if !(1..=MAX_DAYS).contains(&days) {
+        return Err(ApiError::validation(
+            "days",
+            format!("Choose between 1 and {} days", MAX_DAYS),
+        ));
+    }
+    Ok(days)
+}
+
+/// Error groups seen in the last `days`, those happening most first.
+/// `status` and `error_type` may be empty to list every status or type.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_error_groups(
+    token: String,
+    status: String,
+    error_type: String,
+    search: String,
+    days: i32,
+) -> Result<Vec<ErrorGroup>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::error_triage::ERROR_STATUSES;
+
+        authorize_admin(&token)?;
+        let days = window_days(days)?;
+        if !status.is_empty() && !ERROR_STATUSES.contains(&status.as_str()) {
+            return Err(ApiError::validation("status", "Unknown error status").into());
+        }
+
+        Ok(crate::db::error_group_repository::get_error_groups(
+            &status,
+            &error_type,
+            &search,
+            days,
+            GROUPS_LISTED,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to load error groups", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Errors logged per day over the last `days`, for one group or for all
+/// errors when `fingerprint` is `None`
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_error_counts(
+    token: String,
+    fingerprint: Option<String>,
+    days: i32,
+) -> Result<Vec<ErrorCount>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        authorize_admin(&token)?;
+        let days = window_days(days)?;
+
+        Ok(
+            crate::db::error_group_repository::get_error_counts(fingerprint.as_deref(), days)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load error counts", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// A group's latest occurrences, newest first
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_error_samples(
+    token: String,
+    fingerprint: String,
+) -> Result<Vec<ErrorLog>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        authorize_admin(&token)?;
+
+        Ok(
+            crate::db::error_group_repository::get_error_samples(&fingerprint, SAMPLES_SHOWN)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load errors", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Marks a group open, resolved or ignored
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_error_status(
+    token: String,
+    fingerprint: String,
+    status: String,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::error_triage::ERROR_STATUSES;
+
+        let admin_id = authorize_admin(&token)?;
+        if !ERROR_STATUSES.contains(&status.as_str()) {
+            return Err(ApiError::validation("status", "Unknown error status").into());
+        }
+
+        let updated =
+            crate::db::error_group_repository::set_error_status(&fingerprint, &status, admin_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to update error group", e))?;
+        if !updated {
+            return Err(ApiError::not_found("Error group not found").into());
+        }
+        tracing::info!(fingerprint, status, admin_id, "Error group triaged");
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Assigns a group to an admin, or unassigns it with `None`
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn assign_error_group(
+    token: String,
+    fingerprint: String,
+    assignee: Option<i64>,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::error_group_repository;
+
+        authorize_admin(&token)?;
+        if let Some(assignee) = assignee {
+            let admins = error_group_repository::get_assignees()
+                .await
+                .map_err(|e| ApiError::internal("Failed to load admins", e))?;
+            if !admins.iter().any(|admin| admin.user_id == assignee) {
+                return Err(
+                    ApiError::validation("assignee", "Errors can only go to an admin").into(),
+                );
+            }
+        }
+
+        let updated = error_group_repository::assign_error_group(&fingerprint, assignee)
+            .await
+            .map_err(|e| ApiError::internal("Failed to assign error group", e))?;
+        if !updated {
+            return Err(ApiError::not_found("Error group not found").into());
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Admins error groups can be assigned to
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_error_assignees(
+    token: String,
+) -> Result<Vec<ErrorAssignee>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        authorize_admin(&token)?;
+
+        Ok(crate::db::error_group_repository::get_assignees()
+            .await
+            .map_err(|e| ApiError::internal("Failed to load admins", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,126 @@
+use leptos::prelude::*;
+
+use crate::db::entities::ExportJob;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Exports listed on the export panel
+#[cfg(feature = "ssr")]
+const RECENT_EXPORTS: i64 = 10;
+
+/// The signed-in user, and the artist account for artist exports. Admin
+/// exports need an admin; artist exports the Bookings permission.
+#[cfg(feature = "ssr")]
+async fn authorize_export(token: &str, kind: &str) -> Result<(i64, Option<i32>), ServerFnError> {
+    use crate::server_team::{authorize_artist, TeamPermission};
+    use crate::utils::export::is_admin_export;
+
+    let (user_id, user_type) = crate::server::extract_user_from_token(token)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+    if is_admin_export(kind) {
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+        return Ok((user_id, None));
+    }
+    let artist_id = authorize_artist(token, TeamPermission::Bookings).await?;
+    Ok((user_id, Some(artist_id)))
+}
+
+/// Queues an export and starts it in the background. Asking again while one
+/// of the same kind is still running returns that one.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn request_export(token: String, kind: String) -> Result<ExportJob, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::export_repository;
+        use crate::exports::job_view;
+        use crate::jobs::{enqueue, Job};
+        use crate::utils::export::is_export_kind;
+
+        if !is_export_kind(&kind) {
+            return Err(ServerFnError::new(format!("Unknown export: {}", kind)));
+        }
+        let (user_id, artist_id) = authorize_export(&token, &kind).await?;
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to start export: {}", e));
+        if let Some(job) = export_repository::get_active_job(user_id, &kind)
+            .await
+            .map_err(db_error)?
+        {
+            return Ok(job_view(&job));
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let job = export_repository::create_job(&id, user_id, artist_id, &kind)
+            .await
+            .map_err(db_error)?;
+
+        if let Err(e) = enqueue(Job::RunExport {
+            export_id: id.clone(),
+        })
+        .await
+        {
+            // Otherwise it would stay queued and block asking again
+            let _ = export_repository::fail_job(&id, "The export failed, please try again").await;
+            return Err(ServerFnError::new(format!("Failed to start export: {}", e)));
+        }
+
+        Ok(job_view(&job))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// One of the signed-in user's exports, polled for progress
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_export_job(token: String, id: String) -> Result<ExportJob, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (user_id, _) = crate::server::extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        crate::db::export_repository::get_job(&id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load export: {}", e)))?
+            .filter(|job| job.user_id == user_id)
+            .map(|job| crate::exports::job_view(&job))
+            .ok_or_else(|| ServerFnError::new("Export not found".to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The signed-in user's recent exports, newest first
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_export_jobs(token: String) -> Result<Vec<ExportJob>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (user_id, _) = crate::server::extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        let jobs = crate::db::export_repository::get_jobs(user_id, RECENT_EXPORTS)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load exports: {}", e)))?;
+        Ok(jobs.iter().map(crate::exports::job_view).collect())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
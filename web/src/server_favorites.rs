@@ -1,5 +1,7 @@
 use leptos::prelude::*;
-use serde::{Deserialize, Serialize};
+
+use crate::db::entities::FavoriteCollection;
+use crate::db::favorites_repository::FavoritePostWithDetails;
 
 #[cfg(feature = "ssr")]
 use leptos::server_fn::error::ServerFnError;
@@ -10,25 +12,28 @@ use tracing::instrument;
 // Helper function to extract user_id from JWT token
 #[cfg(feature = "ssr")]
 fn extract_user_id_from_token(token: &str) -> Result<i32, ServerFnError> {
-    use jsonwebtoken::{decode, DecodingKey, Validation};
-
-    #[derive(Debug, Serialize, Deserialize)]
-    struct Claims {
-        sub: String,
-        exp: usize,
-        user_type: String,
-        user_id: i32,
-    }
+    crate::auth::decode_access_token(token)
+        .map(|claims| claims.user_id as i32)
+        .ok_or_else(|| ServerFnError::new("Invalid token".to_string()))
+}
 
-    let secret = "tatteau-jwt-secret-key-change-in-production";
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
-    )
-    .map_err(|e| ServerFnError::new(format!("Invalid token: {}", e)))?;
+/// Longest board name accepted
+#[cfg(feature = "ssr")]
+const MAX_COLLECTION_NAME_LEN: usize = 60;
 
-    Ok(token_data.claims.user_id)
+#[cfg(feature = "ssr")]
+fn collection_name(name: &str) -> Result<String, ServerFnError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServerFnError::new("Board name can't be empty".to_string()));
+    }
+    if name.chars().count() > MAX_COLLECTION_NAME_LEN {
+        return Err(ServerFnError::new(format!(
+            "Board name must be at most {} characters",
+            MAX_COLLECTION_NAME_LEN
+        )));
+    }
+    Ok(name.to_string())
 }
 
 #[server]
@@ -122,7 +127,7 @@ pub async fn get_favorites_count(token: String) -> Result<i32, ServerFnError> {
 #[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
 pub async fn get_user_favorites_with_details(
     token: String,
-) -> Result<Vec<crate::db::favorites_repository::FavoritePostWithDetails>, ServerFnError> {
+) -> Result<Vec<FavoritePostWithDetails>, ServerFnError> {
     #[cfg(feature = "ssr")]
     {
         use crate::db::favorites_repository;
@@ -138,3 +143,251 @@ pub async fn get_user_favorites_with_details(
         Ok(vec![])
     }
 }
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn add_favorite(token: String, artists_images_id: i32) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        favorites_repository::add_favorite(user_id, artists_images_id)
+            .await
+            .map(|_| ())
+            .map_err(|e| ServerFnError::new(format!("Failed to add favorite: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Unfavorites an image, which also takes it off all of the user's boards.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn remove_favorite(token: String, artists_images_id: i32) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        favorites_repository::remove_favorite(user_id, artists_images_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to remove favorite: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Favorited posts with details, either all of them or those on one board.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn list_favorites(
+    token: String,
+    collection_id: Option<i64>,
+) -> Result<Vec<FavoritePostWithDetails>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        match collection_id {
+            Some(collection_id) => {
+                favorites_repository::get_collection_with_details(user_id as i64, collection_id)
+                    .await
+                    .map_err(|e| ServerFnError::new(format!("Failed to get board: {}", e)))?
+                    .ok_or_else(|| ServerFnError::new("Board not found".to_string()))
+            }
+            None => favorites_repository::get_user_favorites_with_details(user_id)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to get favorites: {}", e))),
+        }
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn list_collections(token: String) -> Result<Vec<FavoriteCollection>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        favorites_repository::list_collections(user_id as i64)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get boards: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn create_collection(token: String, name: String) -> Result<i64, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+        let name = collection_name(&name)?;
+
+        favorites_repository::create_collection(user_id as i64, &name)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to create board: {}", e)))?
+            .ok_or_else(|| ServerFnError::new(format!("You already have a board named \"{}\"", name)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(0)
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn rename_collection(
+    token: String,
+    collection_id: i64,
+    name: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+        let name = collection_name(&name)?;
+
+        let renamed = favorites_repository::rename_collection(user_id as i64, collection_id, &name)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to rename board: {}", e)))?;
+
+        if !renamed {
+            return Err(ServerFnError::new(
+                "Board not found or name already in use".to_string(),
+            ));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Deletes a board; its images stay in the user's favorites.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn delete_collection(token: String, collection_id: i64) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        let deleted = favorites_repository::delete_collection(user_id as i64, collection_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to delete board: {}", e)))?;
+
+        if !deleted {
+            return Err(ServerFnError::new("Board not found".to_string()));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Saves an image to a board, favoriting it if it isn't already.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn add_to_collection(
+    token: String,
+    collection_id: i64,
+    artists_images_id: i64,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        let added =
+            favorites_repository::add_to_collection(user_id as i64, collection_id, artists_images_id)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to save to board: {}", e)))?;
+
+        if !added {
+            return Err(ServerFnError::new("Board not found".to_string()));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn remove_from_collection(
+    token: String,
+    collection_id: i64,
+    artists_images_id: i64,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        favorites_repository::remove_from_collection(
+            user_id as i64,
+            collection_id,
+            artists_images_id,
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| ServerFnError::new(format!("Failed to remove from board: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Ids of the user's boards an image is saved to, for the "save to board" picker.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "debug"))]
+pub async fn get_image_collections(
+    token: String,
+    artists_images_id: i64,
+) -> Result<Vec<i64>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::favorites_repository;
+
+        let user_id = extract_user_id_from_token(&token)?;
+
+        favorites_repository::get_collections_for_image(user_id as i64, artists_images_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get boards: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,248 @@
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::FlashDesign;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Longest flash title
+pub const MAX_FLASH_TITLE_CHARS: usize = 80;
+/// Longest flash description
+pub const MAX_FLASH_DESCRIPTION_CHARS: usize = 500;
+/// Designs returned per browse
+#[cfg(feature = "ssr")]
+const BROWSE_LIMIT: i64 = 60;
+
+/// Checks a design's details, returning the trimmed title and description
+#[cfg(feature = "ssr")]
+pub(crate) fn validate_details(
+    title: &str,
+    description: Option<&str>,
+    size_inches: Option<f32>,
+    price: f64,
+) -> Result<(String, Option<String>), ApiError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(ApiError::validation("title", "Give the design a title"));
+    }
+    if title.chars().count() > MAX_FLASH_TITLE_CHARS {
+        return Err(ApiError::validation(
+            "title",
+            format!(
+                "The title can be at most {} characters",
+                MAX_FLASH_TITLE_CHARS
+            ),
+        ));
+    }
+    let description = description.map(str::trim).filter(|d| !d.is_empty());
+    if description.is_some_and(|d| d.chars().count() > MAX_FLASH_DESCRIPTION_CHARS) {
+        return Err(ApiError::validation(
+            "description",
+            format!(
+                "The description can be at most {} characters",
+                MAX_FLASH_DESCRIPTION_CHARS
+            ),
+        ));
+    }
+    if size_inches.is_some_and(|size| !size.is_finite() || size <= 0.0) {
+        return Err(ApiError::validation(
+            "size_inches",
+            "Enter the size in inches",
+        ));
+    }
+    if !price.is_finite() || price < 0.0 {
+        return Err(ApiError::validation("price", "Enter a price"));
+    }
+    Ok((title.to_string(), description.map(str::to_string)))
+}
+
+/// All of the signed-in artist's flash, archived designs last.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_flash_designs(
+    token: String,
+) -> Result<Vec<FlashDesign>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        Ok(
+            crate::db::flash_design_repository::get_artist_flash_designs(artist_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load flash", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// What an artist can change on a design after uploading it
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FlashDesignDetails {
+    pub title: String,
+    pub description: Option<String>,
+    pub size_inches: Option<f32>,
+    pub price: f64,
+    pub repeatable: bool,
+    /// Hidden from clients; bookings that claimed it keep it
+    pub archived: bool,
+    pub style_ids: Vec<i32>,
+}
+
+/// Updates one of the signed-in artist's designs.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, details), err, level = "info"))]
+pub async fn update_flash_design(
+    token: String,
+    flash_design_id: i32,
+    details: FlashDesignDetails,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::flash_design_repository::{self, FlashDesignUpdate};
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let (title, description) = validate_details(
+            &details.title,
+            details.description.as_deref(),
+            details.size_inches,
+            details.price,
+        )?;
+        let updated = flash_design_repository::update_flash_design(
+            artist_id,
+            flash_design_id,
+            &FlashDesignUpdate {
+                title: &title,
+                description: description.as_deref(),
+                size_inches: details.size_inches,
+                price: details.price,
+                repeatable: details.repeatable,
+                archived: details.archived,
+                style_ids: &details.style_ids,
+            },
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to update flash", e))?;
+        if !updated {
+            return Err(ApiError::not_found("Flash design not found").into());
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Deletes one of the signed-in artist's designs and its images. A design a
+/// booking has claimed can only be archived.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn delete_flash_design(
+    token: String,
+    flash_design_id: i32,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::flash_design_repository;
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::storage::storage;
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let keys = flash_design_repository::delete_flash_design(artist_id, flash_design_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to delete flash", e))?;
+        let Some((image_key, thumbnail_key)) = keys else {
+            let booked = flash_design_repository::is_flash_design_booked(flash_design_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to delete flash", e))?;
+            return Err(if booked {
+                ApiError::conflict("This design has bookings, so it can only be archived")
+            } else {
+                ApiError::not_found("Flash design not found")
+            }
+            .into());
+        };
+
+        // The row is gone, so a file left behind is only wasted space
+        for key in [image_key, thumbnail_key] {
+            if let Err(e) = storage().delete(&key).await {
+                tracing::warn!("Failed to delete stored flash {}: {}", key, e);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Flash clients can claim, newest first, in any of `style_ids` (all when
+/// empty) and at shops in `city` (anywhere when empty).
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn browse_flash(
+    style_ids: Vec<i32>,
+    city: String,
+) -> Result<Vec<FlashDesign>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        let city = city.trim();
+        Ok(crate::db::flash_design_repository::browse_flash_designs(
+            &style_ids,
+            Some(city).filter(|city| !city.is_empty()),
+            BROWSE_LIMIT,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to load flash", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Cities with flash to claim, for the browse filter.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_flash_cities() -> Result<Vec<String>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        Ok(crate::db::flash_design_repository::get_flash_cities()
+            .await
+            .map_err(|e| ApiError::internal("Failed to load cities", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// A design that isn't archived, for the claim link.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_flash_design(
+    flash_design_id: i32,
+) -> Result<FlashDesign, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::flash_design_repository::get_flash_design(flash_design_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load flash", e))?
+            .filter(|design| !design.archived)
+            .ok_or_else(|| ApiError::not_found("Flash design not found").into())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
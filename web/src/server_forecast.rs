@@ -0,0 +1,115 @@
+use leptos::prelude::*;
+
+use crate::db::entities::EarningsForecast;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Months of revenue history used for the baseline and seasonality
+#[cfg(feature = "ssr")]
+const HISTORY_MONTHS: i32 = 24;
+/// Months of finished bookings used for the completion rate
+#[cfg(feature = "ssr")]
+const OUTCOME_MONTHS: i32 = 12;
+
+/// Fills the gaps in `(year, month, revenue)` rows with zero-revenue months,
+/// from the first month with revenue up to the month before `current`
+#[cfg(feature = "ssr")]
+fn consecutive_months(
+    rows: &[(i32, u32, f64)],
+    current: chrono::NaiveDate,
+) -> Vec<crate::utils::forecast::MonthRevenue> {
+    use crate::utils::forecast::MonthRevenue;
+    use chrono::{Datelike, Months};
+
+    let Some(&(year, month, _)) = rows.first() else {
+        return Vec::new();
+    };
+    let Some(mut date) = chrono::NaiveDate::from_ymd_opt(year, month, 1) else {
+        return Vec::new();
+    };
+
+    let mut months = Vec::new();
+    while date < current {
+        let revenue = rows
+            .iter()
+            .find(|(y, m, _)| *y == date.year() && *m == date.month())
+            .map_or(0.0, |(_, _, revenue)| *revenue);
+        months.push(MonthRevenue {
+            month: date.month(),
+            revenue,
+        });
+        date = date + Months::new(1);
+    }
+    months
+}
+
+/// Projects next month's revenue with an interval, for the artist dashboard.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_earnings_forecast(token: String) -> Result<EarningsForecast, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::{forecast_repository, invoice_repository};
+        use crate::utils::forecast::{self, ForecastInputs};
+        use chrono::{Datelike, Months};
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to load forecast data: {}", e));
+
+        let today = chrono::Local::now().date_naive();
+        let this_month = today.with_day(1).unwrap_or(today);
+        let target = this_month + Months::new(1);
+        let after_target = target + Months::new(1);
+
+        let revenue = forecast_repository::get_monthly_revenue(artist_id, HISTORY_MONTHS)
+            .await
+            .map_err(db_error)?;
+        let (completed, closed) =
+            forecast_repository::get_booking_outcomes(artist_id, OUTCOME_MONTHS)
+                .await
+                .map_err(db_error)?;
+        let booked_prices = forecast_repository::get_booked_prices(
+            artist_id,
+            &target.format("%Y-%m-%d").to_string(),
+            &after_target.format("%Y-%m-%d").to_string(),
+        )
+        .await
+        .map_err(db_error)?;
+        let currency = invoice_repository::get_billing_settings(artist_id)
+            .await
+            .map_err(db_error)?
+            .currency;
+
+        let inputs = ForecastInputs {
+            history: consecutive_months(&revenue, this_month),
+            booked_prices,
+            completed: completed as u32,
+            closed: closed as u32,
+            target_month: target.month(),
+        };
+        let result = forecast::forecast(&inputs);
+
+        Ok(EarningsForecast {
+            month: target.format("%Y-%m").to_string(),
+            currency,
+            expected: result.expected,
+            lower: result.lower,
+            upper: result.upper,
+            confidence: forecast::CONFIDENCE,
+            committed: result.committed,
+            baseline: result.baseline,
+            completion_rate: result.completion_rate,
+            booked_count: inputs.booked_prices.len() as i32,
+            history_months: inputs.history.len() as i32,
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,274 @@
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::{GuestSpot, GuestSpotShop};
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Longest note kept on a guest spot
+pub const MAX_GUEST_SPOT_NOTE_CHARS: usize = 300;
+/// Longest guest spot, in days
+pub const MAX_GUEST_SPOT_DAYS: i64 = 90;
+/// Shops returned per search
+#[cfg(feature = "ssr")]
+const SHOP_SEARCH_LIMIT: i64 = 10;
+
+#[cfg(feature = "ssr")]
+fn parse_date(field: &str, date: &str) -> Result<chrono::NaiveDate, ApiError> {
+    chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| ApiError::validation(field, "Enter the date as YYYY-MM-DD"))
+}
+
+/// Queues an email to the artist who invited a guest, letting them know the
+/// spot is on the guest's profile
+#[cfg(feature = "ssr")]
+async fn notify_host(guest_spot_id: i32) {
+    use crate::jobs::{enqueue, Job};
+    use crate::notify::{Channel, Message};
+
+    let spot = match crate::db::guest_spot_repository::get_guest_spot(guest_spot_id).await {
+        Ok(Some(spot)) => spot,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!(guest_spot_id, "Failed to load guest spot to notify: {}", e);
+            return;
+        }
+    };
+    let Some(host_artist_id) = spot.host_artist_id else {
+        return;
+    };
+    let email = match crate::db::guest_spot_repository::get_artist_email(host_artist_id).await {
+        Ok(Some(email)) => email,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!(host_artist_id, "Failed to load host email: {}", e);
+            return;
+        }
+    };
+
+    let guest_name = spot.artist_name.unwrap_or_else(|| "An artist".to_string());
+    let message = Message {
+        channel: Channel::Email,
+        to: email,
+        subject: format!("{} listed a guest spot with you", guest_name),
+        body: format!(
+            "{} is guesting at {} from {} to {}, and listed you as their host. \
+             Clients can book them for those dates from {}/artist/{}.",
+            guest_name,
+            spot.shop_name.unwrap_or_else(|| "your shop".to_string()),
+            spot.start_date,
+            spot.end_date,
+            crate::config::config().app_base_url,
+            spot.artist_id
+        ),
+    };
+    if let Err(e) = enqueue(Job::SendNotification(message)).await {
+        tracing::error!(
+            guest_spot_id,
+            "Failed to queue guest spot notification: {}",
+            e
+        );
+    }
+}
+
+/// The signed-in artist's guest spots that haven't ended.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_guest_spots(token: String) -> Result<Vec<GuestSpot>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        Ok(
+            crate::db::guest_spot_repository::get_upcoming_guest_spots(artist_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load guest spots", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Adds a guest spot for the signed-in artist at another shop, from
+/// `start_date` to `end_date` (YYYY-MM-DD, inclusive). `host_artist_id` is
+/// the artist at that shop who invited them, if any; they're emailed about it.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, note), err, level = "info"))]
+pub async fn create_guest_spot(
+    token: String,
+    location_id: i64,
+    host_artist_id: Option<i32>,
+    start_date: String,
+    end_date: String,
+    note: String,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::guest_spot_repository;
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let start = parse_date("start_date", &start_date)?;
+        let end = parse_date("end_date", &end_date)?;
+        let today = chrono::Utc::now().date_naive();
+        if end < start {
+            return Err(
+                ApiError::validation("end_date", "The last day can't be before the first").into(),
+            );
+        }
+        if end < today {
+            return Err(ApiError::validation("end_date", "That guest spot is already over").into());
+        }
+        if (end - start).num_days() >= MAX_GUEST_SPOT_DAYS {
+            return Err(ApiError::validation(
+                "end_date",
+                format!(
+                    "A guest spot can be at most {} days long",
+                    MAX_GUEST_SPOT_DAYS
+                ),
+            )
+            .into());
+        }
+        let note = note.trim();
+        if note.chars().count() > MAX_GUEST_SPOT_NOTE_CHARS {
+            return Err(ApiError::validation(
+                "note",
+                format!(
+                    "The note can be at most {} characters",
+                    MAX_GUEST_SPOT_NOTE_CHARS
+                ),
+            )
+            .into());
+        }
+
+        let shop_exists = guest_spot_repository::shop_exists(location_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to check shop", e))?;
+        if !shop_exists {
+            return Err(ApiError::validation("location_id", "Choose a shop").into());
+        }
+        let own_shop = guest_spot_repository::works_at(artist_id, location_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to check shop", e))?;
+        if own_shop {
+            return Err(ApiError::validation("location_id", "That's your own shop").into());
+        }
+        if let Some(host_artist_id) = host_artist_id {
+            let host_works_there = guest_spot_repository::works_at(host_artist_id, location_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to check host", e))?;
+            if !host_works_there {
+                return Err(ApiError::validation(
+                    "host_artist_id",
+                    "Your host has to be an artist at that shop",
+                )
+                .into());
+            }
+        }
+
+        let guest_spot_id = guest_spot_repository::create_guest_spot(
+            artist_id,
+            location_id,
+            host_artist_id,
+            start,
+            end,
+            Some(note).filter(|note| !note.is_empty()),
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to save guest spot", e))?
+        .ok_or_else(|| ApiError::conflict("You already have a guest spot on those dates"))?;
+
+        if host_artist_id.is_some() {
+            notify_host(guest_spot_id).await;
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Removes one of the signed-in artist's guest spots. Bookings made for it
+/// stay, without the guest spot.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn delete_guest_spot(
+    token: String,
+    guest_spot_id: i32,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let deleted = crate::db::guest_spot_repository::delete_guest_spot(artist_id, guest_spot_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to delete guest spot", e))?;
+        if !deleted {
+            return Err(ApiError::not_found("Guest spot not found").into());
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Shops whose name contains `query`, for the signed-in artist to pick a
+/// guest spot's shop from.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn search_guest_spot_shops(
+    token: String,
+    query: String,
+) -> Result<Vec<GuestSpotShop>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let query = query.trim();
+        if query.chars().count() < 2 {
+            return Ok(vec![]);
+        }
+        Ok(
+            crate::db::guest_spot_repository::search_shops(query, SHOP_SEARCH_LIMIT)
+                .await
+                .map_err(|e| ApiError::internal("Failed to search shops", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// An artist's guest spots that haven't ended, for their profile.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_artist_guest_spots(
+    artist_id: i32,
+) -> Result<Vec<GuestSpot>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        Ok(
+            crate::db::guest_spot_repository::get_upcoming_guest_spots(artist_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load guest spots", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,110 @@
+use leptos::prelude::*;
+
+use crate::db::entities::DashboardHint;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// The signed-in user and the artist account they're acting on, checked
+/// against the permission the dashboard `page` needs
+#[cfg(feature = "ssr")]
+async fn authorize_hints(token: &str, page: &str) -> Result<(i64, i32), ServerFnError> {
+    use crate::server_team::{authorize_artist, TeamPermission};
+
+    let permission = match page {
+        "calendar" => TeamPermission::Calendar,
+        "requests" => TeamPermission::Bookings,
+        _ => TeamPermission::Settings,
+    };
+    let artist_id = authorize_artist(token, permission).await?;
+    let (user_id, _) = crate::server::extract_user_from_token(token)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+    Ok((user_id, artist_id))
+}
+
+/// Hints for one dashboard page ("home", "calendar", "requests" or
+/// "settings"), without the ones the signed-in user has dismissed
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_dashboard_hints(
+    token: String,
+    page: String,
+) -> Result<Vec<DashboardHint>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::hint_repository;
+        use crate::utils::hints::{hints_for_page, HINT_PAGES};
+
+        if !HINT_PAGES.contains(&page.as_str()) {
+            return Err(ServerFnError::new(format!("Unknown page: {}", page)));
+        }
+        let (user_id, artist_id) = authorize_hints(&token, &page).await?;
+
+        let db_error = |e: sqlx::Error| ServerFnError::new(format!("Failed to load hints: {}", e));
+        let facts = hint_repository::get_hint_facts(artist_id)
+            .await
+            .map_err(db_error)?;
+        let dismissed = hint_repository::get_dismissed(user_id, artist_id)
+            .await
+            .map_err(db_error)?;
+
+        Ok(hints_for_page(&facts, &page, &dismissed))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Hides a hint from the signed-in user on this artist account for good.
+/// `page` is the page it was dismissed from, so team members only need the
+/// permission that page does.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn dismiss_dashboard_hint(
+    token: String,
+    page: String,
+    key: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::hints::{HINT_KEYS, HINT_PAGES};
+
+        if !HINT_PAGES.contains(&page.as_str()) {
+            return Err(ServerFnError::new(format!("Unknown page: {}", page)));
+        }
+        if !HINT_KEYS.contains(&key.as_str()) {
+            return Err(ServerFnError::new(format!("Unknown hint: {}", key)));
+        }
+        let (user_id, artist_id) = authorize_hints(&token, &page).await?;
+
+        crate::db::hint_repository::dismiss(user_id, artist_id, &key)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to dismiss hint: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Brings back every hint the signed-in user has dismissed
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn restore_dashboard_hints(token: String) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (user_id, artist_id) = authorize_hints(&token, "settings").await?;
+
+        crate::db::hint_repository::restore_all(user_id, artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to restore hints: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
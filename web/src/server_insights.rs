@@ -0,0 +1,68 @@
+use leptos::prelude::*;
+
+use crate::db::entities::ImpressionInsights;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Weeks of weekly totals kept; at least as many as the charts can show
+#[cfg(feature = "ssr")]
+const WEEKLY_RETENTION_WEEKS: i32 = 104;
+
+/// Folds raw impressions past their retention into weekly totals and drops
+/// weekly totals too old to chart, returning how many raw events were
+/// folded. Run by the roll-up job.
+#[cfg(feature = "ssr")]
+pub async fn roll_up_impressions() -> Result<u64, sqlx::Error> {
+    use crate::db::impression_repository;
+
+    let folded = impression_repository::roll_up_impressions(
+        crate::config::config().impression_retention_days,
+    )
+    .await?;
+    impression_repository::delete_old_weeks(WEEKLY_RETENTION_WEEKS).await?;
+    Ok(folded)
+}
+
+/// How often the signed-in artist was shown in match results and clicked,
+/// per week for the last `weeks` weeks, next to their city's average.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_impression_insights(
+    token: String,
+    weeks: i32,
+) -> Result<ImpressionInsights, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::impression_repository;
+        use crate::server::MAX_TREND_WEEKS;
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Bookings).await?;
+
+        if !(1..=MAX_TREND_WEEKS).contains(&weeks) {
+            return Err(ServerFnError::new(format!(
+                "Insights cover 1 to {} weeks",
+                MAX_TREND_WEEKS
+            )));
+        }
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to load insights: {}", e));
+        let city = impression_repository::get_artist_city(artist_id)
+            .await
+            .map_err(db_error)?;
+        let weeks = impression_repository::get_weekly_impressions(artist_id, weeks)
+            .await
+            .map_err(db_error)?;
+
+        Ok(ImpressionInsights { city, weeks })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(ImpressionInsights::default())
+    }
+}
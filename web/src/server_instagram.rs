@@ -0,0 +1,318 @@
+//! Instagram privacy mode. Some regions require consent before Instagram's
+//! embed script may load, so until the visitor consents posts are shown from
+//! the thumbnail and caption media ingestion saved, served from our domain.
+//!
+//! Each request carries the visitor's choice as `consent`: `Some(true)`
+//! allows live embeds, `Some(false)` refuses them, and `None` (no choice
+//! yet) falls back to `INSTAGRAM_EMBED_DEFAULT`: `live` (the default) or
+//! `preview`.
+//!
+//! Live embeds are Instagram's oEmbed markup, cached in
+//! `instagram_oembed_cache` for `INSTAGRAM_EMBED_CACHE_HOURS` (default 24).
+//! Past that a cached embed is still served while a background job (see
+//! `jobs`) refreshes it, and an hourly job refreshes the ones still being viewed, so
+//! galleries don't wait on Instagram. When Instagram can't be reached the
+//! cached copy is served however old it is.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Widths gallery images are stored at, in pixels (see `instagram_images`).
+/// Instagram serves at most 1080.
+pub const GALLERY_IMAGE_WIDTHS: [u32; 3] = [320, 640, 1080];
+
+/// Stand-in for a live embed, built from saved media
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstagramPreview {
+    pub short_code: String,
+    pub permalink: String,
+    /// `None` when ingestion saved no thumbnail for the post
+    pub thumbnail_url: Option<String>,
+    pub thumbnail_width: Option<i32>,
+    pub thumbnail_height: Option<i32>,
+    pub caption: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InstagramEmbedContent {
+    /// Instagram's oEmbed markup, which loads its embed script
+    Live { html: String },
+    Preview(InstagramPreview),
+}
+
+/// Whether live embeds may load for a visitor with this consent
+#[cfg(feature = "ssr")]
+pub(crate) fn live_embeds_allowed(consent: Option<bool>) -> bool {
+    consent.unwrap_or(!crate::config::config().instagram_embed_preview_default)
+}
+
+/// Cached embeds older than this are fetched again before they're served
+#[cfg(feature = "ssr")]
+const MAX_STALE_EMBED_SECS: f64 = 30.0 * 24.0 * 3600.0;
+/// How often serving a cached embed updates its `last_served_at`
+#[cfg(feature = "ssr")]
+const SERVED_UPDATE_SECS: f64 = 3600.0;
+/// Cached embeds not served for this long are deleted rather than refreshed
+#[cfg(feature = "ssr")]
+const KEEP_UNSERVED_EMBED_SECS: i64 = 30 * 24 * 3600;
+/// Embeds refreshed per run of the hourly job
+#[cfg(feature = "ssr")]
+const EMBED_REFRESH_BATCH: i64 = 200;
+
+/// How long a fetched embed is served before it's refreshed
+#[cfg(feature = "ssr")]
+fn embed_cache_secs() -> f64 {
+    crate::config::config().instagram_embed_cache_hours * 3600.0
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) fn valid_short_code(short_code: &str) -> bool {
+    !short_code.is_empty()
+        && short_code.len() <= 64
+        && short_code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) async fn load_preview(short_code: &str) -> Result<InstagramPreview, ServerFnError> {
+    if !valid_short_code(short_code) {
+        return Err(ServerFnError::new("Invalid post".to_string()));
+    }
+
+    let media = crate::db::instagram_media_repository::get_media(short_code)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to get post preview: {}", e)))?;
+
+    Ok(InstagramPreview {
+        short_code: short_code.to_string(),
+        permalink: format!("https://www.instagram.com/p/{}/", short_code),
+        thumbnail_url: media
+            .as_ref()
+            .filter(|media| media.has_thumbnail)
+            .map(|_| format!("/api/instagram/{}/thumbnail", short_code)),
+        thumbnail_width: media.as_ref().and_then(|media| media.thumbnail_width),
+        thumbnail_height: media.as_ref().and_then(|media| media.thumbnail_height),
+        caption: media.and_then(|media| media.caption),
+    })
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum OEmbedError {
+    #[error("oEmbed request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("oEmbed returned {0}")]
+    Status(u16),
+    #[error(transparent)]
+    Unavailable(#[from] shared_types::circuit_breaker::BreakerOpen),
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Deserialize)]
+struct OEmbedResponse {
+    html: String,
+}
+
+/// Fetches a post's oEmbed markup from Instagram and caches it. A post
+/// that's gone is dropped from the cache.
+#[cfg(feature = "ssr")]
+async fn fetch_embed(short_code: &str) -> Result<String, OEmbedError> {
+    use crate::circuit_breakers::{self, INSTAGRAM};
+    use crate::db::instagram_media_repository::{delete_cached_embed, save_cached_embed};
+
+    let request = reqwest::Client::new()
+        .get(format!(
+            "https://www.instagram.com/p/{}/oembed/?url=https://www.instagram.com/p/{}/",
+            short_code, short_code
+        ))
+        .timeout(std::time::Duration::from_secs(10));
+    let response = circuit_breakers::send(&INSTAGRAM, request).await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        if status == reqwest::StatusCode::NOT_FOUND {
+            if let Err(e) = delete_cached_embed(short_code).await {
+                tracing::error!("Failed to drop cached embed for {}: {}", short_code, e);
+            }
+        }
+        return Err(OEmbedError::Status(status.as_u16()));
+    }
+
+    let html = response.json::<OEmbedResponse>().await?.html;
+    if let Err(e) = save_cached_embed(short_code, &html).await {
+        tracing::error!("Failed to cache embed for {}: {}", short_code, e);
+    }
+    Ok(html)
+}
+
+/// Refreshes a cached embed for the `RefreshInstagramEmbed` job. A post
+/// that's gone needs no retry, its cached embed is already dropped.
+#[cfg(feature = "ssr")]
+pub(crate) async fn refresh_embed(short_code: &str) -> Result<(), OEmbedError> {
+    match fetch_embed(short_code).await {
+        Ok(_) => Ok(()),
+        Err(OEmbedError::Status(404)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Queues a refresh of a cached embed, unless one is already queued
+#[cfg(feature = "ssr")]
+async fn refresh_in_background(short_code: &str) {
+    use crate::jobs::{enqueue, Job};
+
+    let job = Job::RefreshInstagramEmbed {
+        short_code: short_code.to_string(),
+    };
+    if let Err(e) = enqueue(job).await {
+        tracing::error!("Failed to queue embed refresh for {}: {}", short_code, e);
+    }
+}
+
+/// A post's oEmbed markup: cached while fresh, cached with a background
+/// refresh once stale, otherwise fetched now. If the fetch fails any cached
+/// copy is served instead.
+#[cfg(feature = "ssr")]
+pub(crate) async fn load_live_embed(short_code: &str) -> Result<String, ServerFnError> {
+    use crate::db::instagram_media_repository::{get_cached_embed, touch_cached_embed};
+
+    if !valid_short_code(short_code) {
+        return Err(ServerFnError::new("Invalid post".to_string()));
+    }
+
+    let cached = match get_cached_embed(short_code).await {
+        Ok(cached) => cached,
+        Err(e) => {
+            tracing::error!("Failed to load cached embed for {}: {}", short_code, e);
+            None
+        }
+    };
+
+    if let Some(cached) = &cached {
+        if cached.served_age_secs > SERVED_UPDATE_SECS {
+            if let Err(e) = touch_cached_embed(short_code).await {
+                tracing::error!("Failed to update cached embed for {}: {}", short_code, e);
+            }
+        }
+        if cached.age_secs < embed_cache_secs() {
+            return Ok(cached.html.clone());
+        }
+        if cached.age_secs < MAX_STALE_EMBED_SECS {
+            refresh_in_background(short_code).await;
+            return Ok(cached.html.clone());
+        }
+    }
+
+    match fetch_embed(short_code).await {
+        Ok(html) => Ok(html),
+        Err(OEmbedError::Status(status)) => {
+            tracing::warn!("Instagram oEmbed returned {} for {}", status, short_code);
+            Err(ServerFnError::new(format!(
+                "Instagram post not found or not accessible: {}",
+                short_code
+            )))
+        }
+        Err(e) => {
+            tracing::warn!("Failed to fetch embed for {}: {}", short_code, e);
+            match cached {
+                Some(cached) => Ok(cached.html),
+                None => Err(ServerFnError::new(format!(
+                    "Failed to fetch Instagram embed for post: {}",
+                    short_code
+                ))),
+            }
+        }
+    }
+}
+
+/// Deletes cached embeds no longer being viewed and refreshes stale ones
+/// that are, returning how many were refreshed
+#[cfg(feature = "ssr")]
+pub async fn refresh_cached_embeds() -> Result<usize, sqlx::Error> {
+    use crate::db::instagram_media_repository::{delete_unserved_embeds, get_embeds_to_refresh};
+
+    delete_unserved_embeds(KEEP_UNSERVED_EMBED_SECS).await?;
+    let short_codes = get_embeds_to_refresh(
+        embed_cache_secs() as i64,
+        KEEP_UNSERVED_EMBED_SECS,
+        EMBED_REFRESH_BATCH,
+    )
+    .await?;
+
+    let mut refreshed = 0;
+    for short_code in short_codes {
+        match fetch_embed(&short_code).await {
+            Ok(_) => refreshed += 1,
+            Err(OEmbedError::Unavailable(e)) => {
+                tracing::warn!("Stopped refreshing embeds: {}", e);
+                break;
+            }
+            Err(e) => tracing::warn!("Failed to refresh embed for {}: {}", short_code, e),
+        }
+    }
+    Ok(refreshed)
+}
+
+/// Whether the visitor's page may load live Instagram embeds. Public.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn get_instagram_embeds_allowed(consent: Option<bool>) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        Ok(live_embeds_allowed(consent))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(false)
+    }
+}
+
+/// The saved thumbnail and caption of a post. Public.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn get_instagram_preview(short_code: String) -> Result<InstagramPreview, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        load_preview(&short_code).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// GET /api/instagram/:short_code/thumbnail
+#[cfg(feature = "ssr")]
+pub async fn instagram_thumbnail_handler(
+    axum::extract::Path(short_code): axum::extract::Path<String>,
+) -> axum::response::Response {
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+
+    if !valid_short_code(&short_code) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match crate::db::instagram_media_repository::get_thumbnail(&short_code).await {
+        Ok(Some(thumbnail)) => (
+            [
+                (header::CONTENT_TYPE, "image/jpeg"),
+                (header::CACHE_CONTROL, "public, max-age=86400"),
+            ],
+            thumbnail,
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load thumbnail for {}: {}", short_code, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
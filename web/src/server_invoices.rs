@@ -0,0 +1,270 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{BillingSettings, Invoice};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+#[cfg(feature = "ssr")]
+pub(crate) async fn artist_id_from_token(token: &str) -> Result<i32, ServerFnError> {
+    let (user_id, user_type) = crate::server::extract_user_from_token(token)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+    if user_type != "artist" {
+        return Err(ServerFnError::new(
+            "Unauthorized: Artist access required".to_string(),
+        ));
+    }
+
+    crate::db::repository::get_artist_id_from_user_id(user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to resolve artist: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("No artist profile for this account".to_string()))
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) async fn client_email_from_token(token: &str) -> Result<String, ServerFnError> {
+    use sqlx::Row;
+
+    let (user_id, _user_type) = crate::server::extract_user_from_token(token)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+    let pool = crate::db::pool::get_pool();
+    let row = sqlx::query("SELECT email FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to load user: {}", e)))?
+        .ok_or_else(|| ServerFnError::new("User not found".to_string()))?;
+
+    Ok(row.get("email"))
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_billing_settings(token: String) -> Result<BillingSettings, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::invoice_repository;
+
+        let artist_id = artist_id_from_token(&token).await?;
+
+        invoice_repository::get_billing_settings(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load billing settings: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_billing_settings(
+    token: String,
+    settings: BillingSettings,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::invoice_repository;
+
+        let artist_id = artist_id_from_token(&token).await?;
+
+        if !(0.0..=100.0).contains(&settings.tax_rate) {
+            return Err(ServerFnError::new(
+                "Tax rate must be between 0 and 100".to_string(),
+            ));
+        }
+        if settings.currency.len() != 3 {
+            return Err(ServerFnError::new(
+                "Currency must be a 3-letter ISO code".to_string(),
+            ));
+        }
+
+        let settings = BillingSettings {
+            artist_id,
+            currency: settings.currency.to_uppercase(),
+            ..settings
+        };
+
+        invoice_repository::upsert_billing_settings(&settings)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save billing settings: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Issues an invoice for a completed booking. Calling it again returns the same invoice.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn create_invoice(
+    token: String,
+    booking_request_id: i32,
+) -> Result<Invoice, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::invoice_repository;
+
+        let artist_id = artist_id_from_token(&token).await?;
+
+        invoice_repository::create_invoice_for_booking(artist_id, booking_request_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to create invoice: {}", e)))?
+            .ok_or_else(|| {
+                ServerFnError::new(
+                    "Only completed bookings with a price can be invoiced".to_string(),
+                )
+            })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_artist_invoices(token: String) -> Result<Vec<Invoice>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::invoice_repository;
+
+        let artist_id = artist_id_from_token(&token).await?;
+
+        invoice_repository::get_invoices_for_artist(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load invoices: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_client_invoices(token: String) -> Result<Vec<Invoice>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::invoice_repository;
+
+        let email = client_email_from_token(&token).await?;
+
+        invoice_repository::get_invoices_for_client(&email)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load invoices: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// How long an invoice PDF link works for
+#[cfg(feature = "ssr")]
+const PDF_LINK_MINUTES: i64 = 15;
+
+#[cfg(feature = "ssr")]
+fn pdf_link_message(invoice_id: i64, expires: i64) -> String {
+    format!("invoice-pdf:{}:{}", invoice_id, expires)
+}
+
+/// A short-lived link to an invoice's PDF for the issuing artist or the
+/// billed client. The link carries no session token, so it can be opened in
+/// a new tab or handed to a download manager.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_invoice_pdf_link(token: String, invoice_id: i64) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        if crate::server::extract_user_from_token(&token).is_none() {
+            return Err(ServerFnError::new("Invalid or expired token".to_string()));
+        }
+
+        let invoice = crate::db::invoice_repository::get_invoice(invoice_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load invoice: {}", e)))?;
+        let allowed = match &invoice {
+            Some(invoice) => match artist_id_from_token(&token).await {
+                Ok(artist_id) => artist_id == invoice.artist_id,
+                Err(_) => client_email_from_token(&token)
+                    .await
+                    .is_ok_and(|email| email.eq_ignore_ascii_case(&invoice.client_email)),
+            },
+            None => false,
+        };
+        // Someone else's invoice looks the same as a missing one
+        if !allowed {
+            return Err(ServerFnError::new("Invoice not found".to_string()));
+        }
+
+        let expires =
+            (chrono::Utc::now() + chrono::Duration::minutes(PDF_LINK_MINUTES)).timestamp();
+        Ok(format!(
+            "/api/invoices/{}/pdf?expires={}&signature={}",
+            invoice_id,
+            expires,
+            crate::auth::sign(&pdf_link_message(invoice_id, expires))
+        ))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[cfg(feature = "ssr")]
+#[derive(serde::Deserialize)]
+pub struct InvoicePdfParams {
+    expires: i64,
+    signature: String,
+}
+
+/// GET /api/invoices/:id/pdf?expires=...&signature=... — serves the PDF to
+/// whoever holds a link from [`get_invoice_pdf_link`].
+#[cfg(feature = "ssr")]
+pub async fn invoice_pdf_handler(
+    axum::extract::Path(invoice_id): axum::extract::Path<i64>,
+    axum::extract::Query(params): axum::extract::Query<InvoicePdfParams>,
+) -> axum::response::Response {
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+
+    if params.expires < chrono::Utc::now().timestamp()
+        || !crate::auth::verify_signature(
+            &pdf_link_message(invoice_id, params.expires),
+            &params.signature,
+        )
+    {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let invoice = match crate::db::invoice_repository::get_invoice(invoice_id).await {
+        Ok(Some(invoice)) => invoice,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load invoice {}: {}", invoice_id, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let pdf = crate::utils::invoice_pdf::render_invoice_pdf(&invoice);
+    (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}.pdf\"", invoice.invoice_code),
+            ),
+        ],
+        pdf,
+    )
+        .into_response()
+}
@@ -0,0 +1,259 @@
+use leptos::prelude::*;
+
+use crate::api_error::ApiError;
+use crate::db::entities::{ArtistLicense, ProfileLicense};
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Licenses listed per status on the admin review page
+#[cfg(feature = "ssr")]
+const REVIEW_PAGE_SIZE: i64 = 100;
+/// Longest rejection reason kept
+#[cfg(feature = "ssr")]
+const MAX_REJECTION_REASON_CHARS: usize = 500;
+
+/// The signed-in admin's user id
+#[cfg(feature = "ssr")]
+fn authorize_admin(token: &str) -> Result<i64, ApiError> {
+    match crate::server::extract_user_from_token(token) {
+        Some((user_id, user_type)) if user_type == "admin" => Ok(user_id),
+        Some(_) => Err(ApiError::unauthorized("Admin access required")),
+        None => Err(ApiError::unauthorized("Invalid or expired token")),
+    }
+}
+
+/// The signed-in artist's licenses, by state.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_licenses(token: String) -> Result<Vec<ArtistLicense>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        Ok(crate::db::license_repository::get_licenses(artist_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load licenses", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Adds the signed-in artist's license for `issuing_state`, or updates it.
+/// `expires_on` is YYYY-MM-DD, blank for licenses that don't expire. A
+/// changed license goes back for review.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn save_license(
+    token: String,
+    license_number: String,
+    issuing_state: String,
+    expires_on: String,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::licensing::{normalize_license_number, normalize_state};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let license_number = normalize_license_number(&license_number)
+            .map_err(|e| ApiError::validation("license_number", e))?;
+        let issuing_state = normalize_state(&issuing_state)
+            .ok_or_else(|| ApiError::validation("issuing_state", "Choose the issuing state"))?;
+        let expires_on =
+            match expires_on.trim() {
+                "" => None,
+                date => Some(chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(
+                    |_| ApiError::validation("expires_on", "Enter the expiry date as YYYY-MM-DD"),
+                )?),
+            };
+
+        Ok(crate::db::license_repository::save_license(
+            artist_id,
+            &license_number,
+            issuing_state,
+            expires_on,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to save license", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Removes one of the signed-in artist's licenses and its document.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn delete_license(token: String, license_id: i32) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let document_key = crate::db::license_repository::delete_license(artist_id, license_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to delete license", e))?
+            .ok_or_else(|| ApiError::not_found("License not found"))?;
+        if let Some(key) = document_key {
+            if let Err(e) = crate::storage::private_storage().delete(&key).await {
+                tracing::warn!("Failed to delete license document: {}", e);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The licenses shown on an artist's profile: verified, unexpired and from a
+/// state that requires them to be shown.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_artist_licenses(
+    artist_id: i32,
+) -> Result<Vec<ProfileLicense>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        Ok(
+            crate::db::license_repository::get_profile_licenses(artist_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load licenses", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// States whose licenses are shown on artists' profiles.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_license_display_states() -> Result<Vec<String>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        Ok(crate::db::license_repository::get_display_states()
+            .await
+            .map_err(|e| ApiError::internal("Failed to load states", e))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Licenses with `status` ("pending", "verified" or "rejected") for admins,
+/// longest waiting first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_licenses_for_review(
+    token: String,
+    status: String,
+) -> Result<Vec<ArtistLicense>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::licensing::VERIFICATION_STATUSES;
+
+        authorize_admin(&token)?;
+        if !VERIFICATION_STATUSES.contains(&status.as_str()) {
+            return Err(ApiError::validation("status", "Unknown verification status").into());
+        }
+
+        Ok(
+            crate::db::license_repository::get_licenses_by_status(&status, REVIEW_PAGE_SIZE)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load licenses", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Verifies a license, or rejects it with a reason the artist is shown.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn review_license(
+    token: String,
+    license_id: i32,
+    approve: bool,
+    rejection_reason: Option<String>,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        let admin_id = authorize_admin(&token)?;
+
+        let rejection_reason = rejection_reason
+            .map(|reason| {
+                reason
+                    .trim()
+                    .chars()
+                    .take(MAX_REJECTION_REASON_CHARS)
+                    .collect::<String>()
+            })
+            .filter(|reason| !reason.is_empty());
+        if !approve && rejection_reason.is_none() {
+            return Err(ApiError::validation(
+                "rejection_reason",
+                "Say why the license was rejected",
+            )
+            .into());
+        }
+
+        let reviewed = crate::db::license_repository::review_license(
+            license_id,
+            approve,
+            rejection_reason.as_deref(),
+            admin_id,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to review license", e))?;
+        if !reviewed {
+            return Err(ApiError::not_found("License not found").into());
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Sets whether licenses from `state` are shown on artists' profiles.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_license_display_state(
+    token: String,
+    state: String,
+    required: bool,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::licensing::normalize_state;
+
+        authorize_admin(&token)?;
+        let state = normalize_state(&state)
+            .ok_or_else(|| ApiError::validation("state", "Unknown state"))?;
+
+        Ok(
+            crate::db::license_repository::set_display_state(state, required)
+                .await
+                .map_err(|e| ApiError::internal("Failed to update state", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
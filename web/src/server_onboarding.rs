@@ -0,0 +1,241 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{OnboardingShop, OnboardingStatus, ShopCandidate};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Shops offered per search
+#[cfg(feature = "ssr")]
+const SHOP_SEARCH_LIMIT: i32 = 8;
+
+/// Most styles an artist can list
+pub const MAX_ARTIST_STYLES: usize = 8;
+
+#[cfg(feature = "ssr")]
+async fn onboarding_artist(token: &str) -> Result<i32, ServerFnError> {
+    use crate::server_team::{authorize_artist, TeamPermission};
+
+    Ok(authorize_artist(token, TeamPermission::Settings).await?)
+}
+
+#[cfg(feature = "ssr")]
+async fn save_shop(
+    artist_id: i32,
+    location: shared_types::LocationInfo,
+) -> Result<OnboardingShop, ServerFnError> {
+    use crate::db::onboarding_repository;
+
+    let location_id = onboarding_repository::upsert_location(&location)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to save shop: {}", e)))?;
+    onboarding_repository::set_artist_location(artist_id, location_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to set shop: {}", e)))?;
+
+    Ok(OnboardingShop {
+        location_id,
+        name: location.name,
+        address: location.address,
+    })
+}
+
+/// Which onboarding steps the signed-in artist has done.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_onboarding_status(token: String) -> Result<OnboardingStatus, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = onboarding_artist(&token).await?;
+
+        crate::db::onboarding_repository::get_onboarding_status(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load onboarding: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Looks the artist's shop up on Google by name and city.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn search_onboarding_shops(
+    token: String,
+    query: String,
+) -> Result<Vec<ShopCandidate>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        onboarding_artist(&token).await?;
+        if query.trim().chars().count() < 3 {
+            return Ok(vec![]);
+        }
+
+        let places = crate::places_api::search_places(query.trim(), SHOP_SEARCH_LIMIT)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to search shops: {}", e)))?;
+
+        Ok(places
+            .into_iter()
+            .filter(|place| !place._id.is_empty())
+            .map(|place| ShopCandidate {
+                place_id: place._id,
+                name: place.name,
+                address: place.address,
+            })
+            .collect())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Sets the artist's shop to one picked from the search results.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_onboarding_shop(
+    token: String,
+    place_id: String,
+) -> Result<OnboardingShop, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = onboarding_artist(&token).await?;
+
+        let location = crate::places_api::get_place(&place_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load shop: {}", e)))?;
+        if location._id.is_empty() {
+            return Err(ServerFnError::new("Shop not found".to_string()));
+        }
+
+        save_shop(artist_id, location).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Sets the artist's shop from a typed-in address, for shops Google doesn't
+/// list (or private studios).
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_onboarding_shop_address(
+    token: String,
+    shop_name: String,
+    address: String,
+) -> Result<OnboardingShop, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = onboarding_artist(&token).await?;
+
+        let shop_name = shop_name.trim();
+        if shop_name.is_empty() || address.trim().is_empty() {
+            return Err(ServerFnError::new(
+                "Enter the shop name and address".to_string(),
+            ));
+        }
+
+        let mut location = crate::places_api::geocode_address(shop_name, address.trim())
+            .await
+            .map_err(|e| ServerFnError::new(format!("Couldn't find that address: {}", e)))?;
+        if location.city.is_empty() {
+            return Err(ServerFnError::new(
+                "Enter a full street address including the city".to_string(),
+            ));
+        }
+        // Not a Google place, so it gets an id of its own
+        location._id = format!("manual:{}", uuid::Uuid::new_v4());
+
+        save_shop(artist_id, location).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Replaces the styles the signed-in artist tattoos.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_onboarding_styles(
+    token: String,
+    style_ids: Vec<i32>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = onboarding_artist(&token).await?;
+
+        if style_ids.is_empty() {
+            return Err(ServerFnError::new("Pick at least one style".to_string()));
+        }
+        if style_ids.len() > MAX_ARTIST_STYLES {
+            return Err(ServerFnError::new(format!(
+                "Pick at most {} styles",
+                MAX_ARTIST_STYLES
+            )));
+        }
+
+        crate::db::onboarding_repository::set_artist_styles(artist_id, &style_ids)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save styles: {}", e)))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Finishes onboarding and takes the profile live. Needs a shop, styles and
+/// business hours; pricing and portfolio photos can be added later.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn complete_onboarding(token: String) -> Result<OnboardingStatus, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::onboarding_repository;
+
+        let artist_id = onboarding_artist(&token).await?;
+        let status = onboarding_repository::get_onboarding_status(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load onboarding: {}", e)))?;
+        if status.completed {
+            return Ok(status);
+        }
+
+        let missing: Vec<&str> = [
+            (status.shop.is_none(), "your shop"),
+            (status.style_ids.is_empty(), "your styles"),
+            (status.open_days == 0, "your business hours"),
+        ]
+        .into_iter()
+        .filter_map(|(missing, step)| missing.then_some(step))
+        .collect();
+        if !missing.is_empty() {
+            return Err(ServerFnError::new(format!(
+                "Set {} before finishing",
+                missing.join(", ")
+            )));
+        }
+
+        onboarding_repository::complete_onboarding(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to finish onboarding: {}", e)))?;
+        crate::hooks::artist_published(crate::hooks::ArtistPublished { artist_id });
+
+        Ok(OnboardingStatus {
+            completed: true,
+            ..status
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
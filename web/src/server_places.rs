@@ -0,0 +1,138 @@
+use leptos::prelude::*;
+
+use crate::db::entities::PlaceSuggestion;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Suggestions returned per keystroke
+#[cfg(feature = "ssr")]
+const PLACE_SUGGESTION_LIMIT: i64 = 8;
+/// Days before a city that was ingested and still has no shops can be queued again
+#[cfg(feature = "ssr")]
+const CITY_INGESTION_COOLDOWN_DAYS: i32 = 30;
+
+#[cfg(feature = "ssr")]
+const US_STATE_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("AL", "Alabama"),
+    ("AK", "Alaska"),
+    ("AZ", "Arizona"),
+    ("AR", "Arkansas"),
+    ("CA", "California"),
+    ("CO", "Colorado"),
+    ("CT", "Connecticut"),
+    ("DE", "Delaware"),
+    ("DC", "District of Columbia"),
+    ("FL", "Florida"),
+    ("GA", "Georgia"),
+    ("HI", "Hawaii"),
+    ("ID", "Idaho"),
+    ("IL", "Illinois"),
+    ("IN", "Indiana"),
+    ("IA", "Iowa"),
+    ("KS", "Kansas"),
+    ("KY", "Kentucky"),
+    ("LA", "Louisiana"),
+    ("ME", "Maine"),
+    ("MD", "Maryland"),
+    ("MA", "Massachusetts"),
+    ("MI", "Michigan"),
+    ("MN", "Minnesota"),
+    ("MS", "Mississippi"),
+    ("MO", "Missouri"),
+    ("MT", "Montana"),
+    ("NE", "Nebraska"),
+    ("NV", "Nevada"),
+    ("NH", "New Hampshire"),
+    ("NJ", "New Jersey"),
+    ("NM", "New Mexico"),
+    ("NY", "New York"),
+    ("NC", "North Carolina"),
+    ("ND", "North Dakota"),
+    ("OH", "Ohio"),
+    ("OK", "Oklahoma"),
+    ("OR", "Oregon"),
+    ("PA", "Pennsylvania"),
+    ("RI", "Rhode Island"),
+    ("SC", "South Carolina"),
+    ("SD", "South Dakota"),
+    ("TN", "Tennessee"),
+    ("TX", "Texas"),
+    ("UT", "Utah"),
+    ("VT", "Vermont"),
+    ("VA", "Virginia"),
+    ("WA", "Washington"),
+    ("WV", "West Virginia"),
+    ("WI", "Wisconsin"),
+    ("WY", "Wyoming"),
+];
+
+/// Splits "Portland, OR" into the city part and a state filter, expanding
+/// two-letter state codes to names
+#[cfg(feature = "ssr")]
+fn split_place_query(query: &str) -> (String, Option<String>) {
+    let Some((city, state)) = query.split_once(',') else {
+        return (query.trim().to_string(), None);
+    };
+
+    let state = state.trim();
+    let state = US_STATE_ABBREVIATIONS
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(state))
+        .map_or(state, |(_, name)| *name);
+
+    (
+        city.trim().to_string(),
+        (!state.is_empty()).then(|| state.to_string()),
+    )
+}
+
+/// City autocomplete over the cities reference table, so users can find
+/// cities we haven't scraped shops for yet. "City, ST" narrows by state.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn autocomplete_places(query: String) -> Result<Vec<PlaceSuggestion>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (city, state) = split_place_query(&query);
+        if city.chars().count() < 2 {
+            return Ok(vec![]);
+        }
+
+        crate::db::place_repository::autocomplete_cities(
+            &city,
+            state.as_deref(),
+            PLACE_SUGGESTION_LIMIT,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to get place suggestions: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Called when a user picks a suggested city. Queues shop ingestion if we
+/// don't cover it yet; returns whether a request was queued.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn select_place(city: String, state: String) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::place_repository::enqueue_city_ingestion(
+            &city,
+            &state,
+            CITY_INGESTION_COOLDOWN_DAYS,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to queue city: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(false)
+    }
+}
@@ -0,0 +1,404 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{ArtistUploadedImage, PortfolioAlbum, PortfolioImageState};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Longest caption accepted on an uploaded image
+#[cfg(feature = "ssr")]
+const MAX_CAPTION_LEN: usize = 500;
+
+/// Longest album name accepted
+#[cfg(feature = "ssr")]
+const MAX_ALBUM_NAME_LEN: usize = 60;
+
+/// Albums a single artist can have
+#[cfg(feature = "ssr")]
+const MAX_ALBUMS: usize = 30;
+
+/// Highlights an artist can pin to the front of their gallery
+#[cfg(feature = "ssr")]
+const MAX_PINNED_IMAGES: usize = 6;
+
+/// An artist's uploaded portfolio images in display order. Public, for profiles.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn get_artist_uploaded_images(
+    artist_id: i64,
+) -> Result<Vec<ArtistUploadedImage>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::uploaded_image_repository::get_uploaded_images(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get portfolio images: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// The caller's own uploaded images, for the settings page.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_uploaded_images(
+    token: String,
+) -> Result<Vec<ArtistUploadedImage>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::uploaded_image_repository::get_uploaded_images(artist_id as i64)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get portfolio images: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_uploaded_image_caption(
+    token: String,
+    image_id: i64,
+    caption: Option<String>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let caption = caption
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        if caption
+            .as_ref()
+            .is_some_and(|c| c.chars().count() > MAX_CAPTION_LEN)
+        {
+            return Err(ServerFnError::new(format!(
+                "Captions must be at most {} characters",
+                MAX_CAPTION_LEN
+            )));
+        }
+
+        let updated = crate::db::uploaded_image_repository::update_caption(
+            artist_id as i64,
+            image_id,
+            caption.as_deref(),
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to update caption: {}", e)))?;
+
+        if !updated {
+            return Err(ServerFnError::new("Image not found".to_string()));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Puts the caller's images in the given order.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn reorder_uploaded_images(
+    token: String,
+    image_ids: Vec<i64>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::uploaded_image_repository::reorder_uploaded_images(
+            artist_id as i64,
+            &image_ids,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to reorder images: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Removes an image from the caller's portfolio and deletes its files.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn delete_uploaded_image(token: String, image_id: i64) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::storage::storage;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let keys =
+            crate::db::uploaded_image_repository::delete_uploaded_image(artist_id as i64, image_id)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to delete image: {}", e)))?;
+
+        let Some((storage_key, thumbnail_key)) = keys else {
+            return Err(ServerFnError::new("Image not found".to_string()));
+        };
+
+        // The row is gone, so a file left behind is only wasted space
+        for key in [storage_key, thumbnail_key] {
+            if let Err(e) = storage().delete(&key).await {
+                tracing::warn!("Failed to delete stored image {}: {}", key, e);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// An artist's albums that have something to show. Public, for the gallery filter.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn get_artist_albums(artist_id: i64) -> Result<Vec<PortfolioAlbum>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let albums = crate::db::portfolio_album_repository::get_albums(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get albums: {}", e)))?;
+
+        Ok(albums
+            .into_iter()
+            .filter(|album| album.image_count > 0)
+            .collect())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// The caller's albums, empty ones included.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_albums(token: String) -> Result<Vec<PortfolioAlbum>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::portfolio_album_repository::get_albums(artist_id as i64)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get albums: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Every image in the caller's portfolio, hidden ones included, with the
+/// albums it's in and whether it's pinned.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_portfolio_images(
+    token: String,
+) -> Result<Vec<PortfolioImageState>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::portfolio_album_repository::get_portfolio_images(artist_id as i64)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get portfolio images: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn create_portfolio_album(
+    token: String,
+    name: String,
+) -> Result<PortfolioAlbum, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::portfolio_album_repository as albums;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await? as i64;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ServerFnError::new("Album name is required".to_string()));
+        }
+        if name.chars().count() > MAX_ALBUM_NAME_LEN {
+            return Err(ServerFnError::new(format!(
+                "Album names must be at most {} characters",
+                MAX_ALBUM_NAME_LEN
+            )));
+        }
+
+        let existing = albums::get_albums(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get albums: {}", e)))?;
+        if existing.len() >= MAX_ALBUMS {
+            return Err(ServerFnError::new(format!(
+                "You can have at most {} albums",
+                MAX_ALBUMS
+            )));
+        }
+
+        albums::create_album(artist_id, name)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to create album: {}", e)))?
+            .ok_or_else(|| {
+                ServerFnError::new("You already have an album with that name".to_string())
+            })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available".to_string()))
+    }
+}
+
+/// Deletes one of the caller's albums. The images in it stay in the portfolio.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn delete_portfolio_album(token: String, album_id: i64) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let deleted =
+            crate::db::portfolio_album_repository::delete_album(artist_id as i64, album_id)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to delete album: {}", e)))?;
+
+        if !deleted {
+            return Err(ServerFnError::new("Album not found".to_string()));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Adds (or with `in_album` false, removes) some of the caller's images to
+/// one of their albums. Returns how many images changed.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_album_images(
+    token: String,
+    album_id: i64,
+    image_ids: Vec<i64>,
+    in_album: bool,
+) -> Result<u64, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::portfolio_album_repository as albums;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await? as i64;
+
+        let owned = albums::get_albums(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get albums: {}", e)))?
+            .iter()
+            .any(|album| album.id == album_id);
+        if !owned {
+            return Err(ServerFnError::new("Album not found".to_string()));
+        }
+
+        let changed = if in_album {
+            albums::add_album_images(artist_id, album_id, &image_ids).await
+        } else {
+            albums::remove_album_images(artist_id, album_id, &image_ids).await
+        };
+        changed.map_err(|e| ServerFnError::new(format!("Failed to update album: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(0)
+    }
+}
+
+/// Replaces the caller's pinned highlights, in the order given. An empty
+/// list unpins everything.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_pinned_images(token: String, image_ids: Vec<i64>) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let mut seen = std::collections::HashSet::new();
+        let image_ids: Vec<i64> = image_ids
+            .into_iter()
+            .filter(|id| seen.insert(*id))
+            .collect();
+        if image_ids.len() > MAX_PINNED_IMAGES {
+            return Err(ServerFnError::new(format!(
+                "You can pin at most {} images",
+                MAX_PINNED_IMAGES
+            )));
+        }
+
+        let pinned =
+            crate::db::portfolio_album_repository::set_pinned_images(artist_id as i64, &image_ids)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to pin images: {}", e)))?;
+
+        if pinned < image_ids.len() as u64 {
+            tracing::info!(
+                requested = image_ids.len(),
+                pinned,
+                "Some images could not be pinned"
+            );
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Hides one of the caller's images from their public gallery, or shows it
+/// again. Hiding an image also unpins it.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_image_hidden(
+    token: String,
+    image_id: i64,
+    hidden: bool,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let updated = crate::db::portfolio_album_repository::set_image_hidden(
+            artist_id as i64,
+            image_id,
+            hidden,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to update image: {}", e)))?;
+
+        if !updated {
+            return Err(ServerFnError::new("Image not found".to_string()));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use leptos::prelude::*;
+
+use crate::db::entities::ArtistPricing;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// The signed-in artist's pricing, with a row for every style they work in.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_artist_pricing(token: String) -> Result<ArtistPricing, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::pricing_repository::get_pricing(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load pricing: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(ArtistPricing::default())
+    }
+}
+
+/// Replaces the signed-in artist's pricing. Styles left blank are unpriced.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_artist_pricing(
+    token: String,
+    pricing: ArtistPricing,
+) -> Result<ArtistPricing, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let amounts = [pricing.hourly_rate, pricing.minimum_charge]
+            .into_iter()
+            .chain(
+                pricing
+                    .style_prices
+                    .iter()
+                    .flat_map(|price| [price.min_price, price.max_price]),
+            )
+            .flatten();
+        for amount in amounts {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(ServerFnError::new("Prices can't be negative".to_string()));
+            }
+        }
+        if let Some(price) = pricing.style_prices.iter().find(|price| {
+            matches!((price.min_price, price.max_price), (Some(min), Some(max)) if min > max)
+        }) {
+            return Err(ServerFnError::new(format!(
+                "{}: the lowest price is above the highest",
+                price.style_name
+            )));
+        }
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to save pricing: {}", e));
+        crate::db::pricing_repository::save_pricing(artist_id, &pricing)
+            .await
+            .map_err(db_error)?;
+        crate::db::pricing_repository::get_pricing(artist_id)
+            .await
+            .map_err(db_error)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
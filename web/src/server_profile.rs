@@ -0,0 +1,209 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{ArtistFaq, ArtistPolicy, ArtistProfileContent, SocialLink};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// An artist's bio, FAQ, policies and links. Public, for their profile page.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn get_artist_profile_content(
+    artist_id: i32,
+) -> Result<ArtistProfileContent, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::profile_content_repository::get_profile_content(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load profile: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(ArtistProfileContent::default())
+    }
+}
+
+/// The signed-in artist's profile content, for the settings page.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_profile_content(token: String) -> Result<ArtistProfileContent, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::profile_content_repository::get_profile_content(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load profile: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(ArtistProfileContent::default())
+    }
+}
+
+/// Replaces the signed-in artist's FAQ. Entries left entirely blank are
+/// dropped.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, faqs), err, level = "info"))]
+pub async fn save_artist_faqs(token: String, faqs: Vec<ArtistFaq>) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::profile::{
+            MAX_FAQ_ANSWER_CHARS, MAX_FAQ_ENTRIES, MAX_FAQ_QUESTION_CHARS,
+        };
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let faqs: Vec<ArtistFaq> = faqs
+            .into_iter()
+            .map(|faq| ArtistFaq {
+                question: faq.question.trim().to_string(),
+                answer: faq.answer.trim().to_string(),
+            })
+            .filter(|faq| !faq.question.is_empty() || !faq.answer.is_empty())
+            .collect();
+
+        if faqs.len() > MAX_FAQ_ENTRIES {
+            return Err(ServerFnError::new(format!(
+                "You can have at most {} questions",
+                MAX_FAQ_ENTRIES
+            )));
+        }
+        for faq in &faqs {
+            if faq.question.is_empty() || faq.answer.is_empty() {
+                return Err(ServerFnError::new(
+                    "Each question needs an answer".to_string(),
+                ));
+            }
+            if faq.question.chars().count() > MAX_FAQ_QUESTION_CHARS {
+                return Err(ServerFnError::new(format!(
+                    "Questions can be at most {} characters",
+                    MAX_FAQ_QUESTION_CHARS
+                )));
+            }
+            if faq.answer.chars().count() > MAX_FAQ_ANSWER_CHARS {
+                return Err(ServerFnError::new(format!(
+                    "Answers can be at most {} characters",
+                    MAX_FAQ_ANSWER_CHARS
+                )));
+            }
+        }
+
+        crate::db::profile_content_repository::save_faqs(artist_id, &faqs)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save FAQ: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Replaces the signed-in artist's studio policies; a blank policy clears it.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(token, policies), err, level = "info")
+)]
+pub async fn save_artist_policies(
+    token: String,
+    policies: Vec<ArtistPolicy>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::profile::{policy_label, MAX_POLICY_CHARS, POLICY_KINDS};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let mut kept: Vec<ArtistPolicy> = Vec::new();
+        for policy in policies {
+            if !POLICY_KINDS.iter().any(|(kind, _)| *kind == policy.kind) {
+                return Err(ServerFnError::new(format!(
+                    "Unknown policy {}",
+                    policy.kind
+                )));
+            }
+            let body = policy.body.trim();
+            if body.chars().count() > MAX_POLICY_CHARS {
+                return Err(ServerFnError::new(format!(
+                    "{} can be at most {} characters",
+                    policy_label(&policy.kind),
+                    MAX_POLICY_CHARS
+                )));
+            }
+            // A kind sent twice keeps the last one
+            kept.retain(|existing| existing.kind != policy.kind);
+            if !body.is_empty() {
+                kept.push(ArtistPolicy {
+                    kind: policy.kind,
+                    body: body.to_string(),
+                });
+            }
+        }
+
+        crate::db::profile_content_repository::save_policies(artist_id, &kept)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save policies: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Replaces the signed-in artist's social links, in the order given. Links
+/// can be full addresses or, for the social platforms, just a handle; blank
+/// ones are dropped.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, links), err, level = "info"))]
+pub async fn save_artist_social_links(
+    token: String,
+    links: Vec<SocialLink>,
+) -> Result<Vec<SocialLink>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::profile::{normalize_social_link, platform_label};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        let mut kept: Vec<SocialLink> = Vec::new();
+        for link in links {
+            if link.url.trim().is_empty() {
+                continue;
+            }
+            if kept
+                .iter()
+                .any(|existing| existing.platform == link.platform)
+            {
+                return Err(ServerFnError::new(format!(
+                    "Only one {} link can be added",
+                    platform_label(&link.platform)
+                )));
+            }
+            let url =
+                normalize_social_link(&link.platform, &link.url).map_err(ServerFnError::new)?;
+            kept.push(SocialLink {
+                platform: link.platform,
+                url,
+            });
+        }
+
+        crate::db::profile_content_repository::save_social_links(artist_id, &kept)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save links: {}", e)))?;
+
+        Ok(kept)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
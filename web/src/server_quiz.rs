@@ -0,0 +1,275 @@
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::server::MatchedArtist;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Matches kept with a session, best first
+#[cfg(feature = "ssr")]
+const MAX_STORED_MATCHES: usize = 20;
+
+#[cfg(feature = "ssr")]
+const MAX_QUIZ_STYLES: usize = 20;
+
+#[cfg(feature = "ssr")]
+const MAX_QUIZ_CITIES: usize = 20;
+
+/// What a client picked in the quiz
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct QuizAnswers {
+    /// Style names
+    pub styles: Vec<String>,
+    pub states: Vec<String>,
+    pub cities: Vec<String>,
+}
+
+impl QuizAnswers {
+    /// The match results page for these answers
+    pub fn results_url(&self) -> String {
+        let mut query_parts = vec![format!(
+            "styles={}",
+            urlencoding::encode(&self.styles.join(","))
+        )];
+        if !self.states.is_empty() {
+            query_parts.push(format!(
+                "states={}",
+                urlencoding::encode(&self.states.join(","))
+            ));
+        }
+        if !self.cities.is_empty() {
+            query_parts.push(format!(
+                "cities={}",
+                urlencoding::encode(&self.cities.join(","))
+            ));
+        }
+        format!("/match/results?{}", query_parts.join("&"))
+    }
+}
+
+/// A saved quiz session with the artists it matched
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuizSession {
+    pub session_key: String,
+    pub answers: QuizAnswers,
+    /// Best first, as they were scored when the answers were saved
+    pub matches: Vec<MatchedArtist>,
+    /// UTC, `None` until matches have been stored
+    pub matched_at: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+fn caller_user_id(token: Option<&str>) -> Option<i64> {
+    token
+        .and_then(crate::server::extract_user_from_token)
+        .map(|(user_id, _)| user_id)
+}
+
+/// A session linked to a user is only theirs to see; an anonymous one
+/// belongs to whoever holds its key
+#[cfg(feature = "ssr")]
+fn can_access(
+    session: &crate::db::quiz_session_repository::StoredQuizSession,
+    user_id: Option<i64>,
+) -> bool {
+    session.user_id.is_none() || session.user_id == user_id
+}
+
+#[cfg(feature = "ssr")]
+async fn load_session(
+    session: crate::db::quiz_session_repository::StoredQuizSession,
+) -> Result<QuizSession, ServerFnError> {
+    let matches = crate::db::quiz_session_repository::get_matches(session.id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to load quiz results: {}", e)))?
+        .into_iter()
+        .filter_map(
+            |stored| match serde_json::from_str::<MatchedArtist>(&stored.artist_json) {
+                Ok(artist) => Some(artist),
+                Err(e) => {
+                    tracing::warn!(
+                        session_id = session.id,
+                        artist_id = stored.artist_id,
+                        "Skipping unreadable stored quiz match: {}",
+                        e
+                    );
+                    None
+                }
+            },
+        )
+        .collect();
+
+    Ok(QuizSession {
+        session_key: session.session_key,
+        answers: QuizAnswers {
+            styles: session.styles,
+            states: session.states,
+            cities: session.cities,
+        },
+        matches,
+        matched_at: session.matched_at,
+    })
+}
+
+/// Saves the quiz answers for `session_key` (the key `get_style_starter_packs`
+/// issued), creating the session the first time, then matches artists to
+/// them and stores the matches with the session. Signed-in callers have the
+/// session linked to them.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn save_quiz_session(
+    session_key: String,
+    answers: QuizAnswers,
+    token: Option<String>,
+) -> Result<QuizSession, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::quiz_session_repository::{self as sessions, StoredQuizMatch};
+        use shared_types::StyleFilter;
+
+        let session_key = session_key.trim();
+        if session_key.is_empty() || session_key.chars().count() > 64 {
+            return Err(ServerFnError::new("Invalid quiz session".to_string()));
+        }
+        let clean = |values: Vec<String>| -> Vec<String> {
+            values
+                .into_iter()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .collect()
+        };
+        let answers = QuizAnswers {
+            styles: clean(answers.styles),
+            states: clean(answers.states),
+            cities: clean(answers.cities),
+        };
+        if answers.styles.is_empty() {
+            return Err(ServerFnError::new("Pick at least one style".to_string()));
+        }
+        if answers.styles.len() > MAX_QUIZ_STYLES || answers.cities.len() > MAX_QUIZ_CITIES {
+            return Err(ServerFnError::new("Too many quiz answers".to_string()));
+        }
+
+        let user_id = caller_user_id(token.as_deref());
+        let existing = sessions::get_session_by_key(session_key)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load quiz session: {}", e)))?;
+        if existing
+            .as_ref()
+            .is_some_and(|session| !can_access(session, user_id))
+        {
+            return Err(ServerFnError::new("Quiz session not found".to_string()));
+        }
+
+        let session_id = sessions::upsert_session(
+            session_key,
+            user_id,
+            &answers.styles,
+            &answers.states,
+            &answers.cities,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to save quiz session: {}", e)))?;
+
+        // The first city picked anchors distance scoring
+        let location = answers.cities.first().cloned().unwrap_or_default();
+        let mut matches = crate::db::repository::query_matched_artists(
+            StyleFilter::preferred(answers.styles.clone()),
+            location,
+            None,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to match artists: {}", e)))?;
+        matches.truncate(MAX_STORED_MATCHES);
+
+        let stored: Vec<StoredQuizMatch> = matches
+            .iter()
+            .filter_map(|artist| {
+                serde_json::to_string(artist)
+                    .ok()
+                    .map(|artist_json| StoredQuizMatch {
+                        artist_id: artist.id,
+                        match_score: artist.match_score,
+                        artist_json,
+                    })
+            })
+            .collect();
+        sessions::replace_matches(session_id, &stored)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save quiz results: {}", e)))?;
+
+        let session = sessions::get_session_by_key(session_key)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load quiz session: {}", e)))?
+            .ok_or_else(|| ServerFnError::new("Quiz session not found".to_string()))?;
+        load_session(session).await
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The session for `session_key` with its stored matches, or `None` if there
+/// isn't one (or it's someone else's). A signed-in caller opening their
+/// anonymous session has it linked to them.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_quiz_session(
+    session_key: String,
+    token: Option<String>,
+) -> Result<Option<QuizSession>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::quiz_session_repository as sessions;
+
+        let user_id = caller_user_id(token.as_deref());
+        let Some(session) = sessions::get_session_by_key(session_key.trim())
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load quiz session: {}", e)))?
+            .filter(|session| can_access(session, user_id))
+        else {
+            return Ok(None);
+        };
+
+        if let (None, Some(user_id)) = (session.user_id, user_id) {
+            sessions::link_session_user(session.id, user_id)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Failed to save quiz session: {}", e)))?;
+        }
+
+        load_session(session).await.map(Some)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// The signed-in caller's most recent quiz results, from any device.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_last_quiz_session(token: String) -> Result<Option<QuizSession>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = caller_user_id(Some(&token))
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        let session = crate::db::quiz_session_repository::get_latest_session_for_user(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load quiz session: {}", e)))?;
+
+        match session {
+            Some(session) => load_session(session).await.map(Some),
+            None => Ok(None),
+        }
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
@@ -0,0 +1,626 @@
+//! Rescheduling and cancelling bookings. When the artist suggests another
+//! time, the client is emailed a link to a page where they can accept it,
+//! decline it or counter with a time of their own, or cancel. The link is
+//! signed, so it works without an account. The artist answers a client's
+//! counter from the dashboard. Accepting a time moves the booking to it and
+//! a step on: a pending booking is approved, an approved one with no deposit
+//! outstanding is confirmed.
+//!
+//! A client cancelling an approved booking less than the artist's
+//! cancellation window before the appointment loses their deposit. Artists
+//! can set their own window; `BOOKING_CANCELLATION_WINDOW_HOURS` (default
+//! 48) applies to the rest.
+
+use leptos::prelude::*;
+use shared_types::datetime::{Date, Time};
+
+use crate::api_error::ApiError;
+use crate::db::entities::{LinkedBooking, RescheduleProposal};
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// How long the link emailed to a client works for
+#[cfg(feature = "ssr")]
+const LINK_TTL_DAYS: i64 = 60;
+
+/// A time proposed for a booking
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TimeProposal {
+    pub date: Date,
+    pub start_time: Time,
+    pub end_time: Option<Time>,
+}
+
+#[cfg(feature = "ssr")]
+fn link_message(booking_id: i32, expires: i64) -> String {
+    format!("booking-response:{}:{}", booking_id, expires)
+}
+
+/// The link the client answers the artist with, `<expires>.<signature>` in
+/// its `token` parameter
+#[cfg(feature = "ssr")]
+pub(crate) fn response_link(booking_id: i32) -> String {
+    let expires = (chrono::Utc::now() + chrono::Duration::days(LINK_TTL_DAYS)).timestamp();
+    format!(
+        "{}/booking/{}/respond?token={}.{}",
+        crate::config::config().app_base_url,
+        booking_id,
+        expires,
+        crate::auth::sign(&link_message(booking_id, expires))
+    )
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) fn verify_link(booking_id: i32, link_token: &str) -> Result<(), ApiError> {
+    let valid = link_token
+        .split_once('.')
+        .and_then(|(expires, signature)| Some((expires.parse::<i64>().ok()?, signature)))
+        .is_some_and(|(expires, signature)| {
+            expires >= chrono::Utc::now().timestamp()
+                && crate::auth::verify_signature(&link_message(booking_id, expires), signature)
+        });
+    if !valid {
+        return Err(ApiError::unauthorized(
+            "This link has expired or isn't valid. Ask the artist for a new one.",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+fn validate_proposal(proposal: &TimeProposal) -> Result<(), ApiError> {
+    if proposal.date.naive() < chrono::Local::now().date_naive() {
+        return Err(ApiError::validation("date", "Pick a date from today on"));
+    }
+    if proposal
+        .end_time
+        .is_some_and(|end_time| end_time <= proposal.start_time)
+    {
+        return Err(ApiError::validation(
+            "end_time",
+            "The end time has to be after the start time",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks `reason` is one `reasons` offers
+#[cfg(feature = "ssr")]
+fn validate_reason(reason: &str, reasons: &[(&str, &str)]) -> Result<(), ApiError> {
+    if !reasons.iter().any(|(value, _)| *value == reason) {
+        return Err(ApiError::validation(
+            "reason",
+            "Pick a reason for cancelling",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+fn describe_time(date: &str, start_time: &str) -> String {
+    use crate::utils::timezone::{convert_to_12_hour_format, format_date_for_booking};
+
+    format!(
+        "{} at {}",
+        format_date_for_booking(date),
+        convert_to_12_hour_format(start_time)
+    )
+}
+
+/// Queues an email to whoever `to` picks out of the booking's contacts.
+/// Failures are logged, since the change has been made either way.
+#[cfg(feature = "ssr")]
+async fn notify(
+    booking_id: i32,
+    to: impl FnOnce(&crate::db::reschedule_repository::BookingContacts) -> Option<String>,
+    subject: impl FnOnce(&crate::db::reschedule_repository::BookingContacts) -> String,
+    body: impl FnOnce(&crate::db::reschedule_repository::BookingContacts) -> String,
+) {
+    use crate::jobs::{enqueue, Job};
+    use crate::notify::{Channel, Message};
+
+    let contacts = match crate::db::reschedule_repository::get_booking_contacts(booking_id).await {
+        Ok(Some(contacts)) => contacts,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!(booking_id, "Failed to load booking contacts: {}", e);
+            return;
+        }
+    };
+    let Some(email) = to(&contacts).filter(|email| !email.is_empty()) else {
+        return;
+    };
+
+    let message = Message {
+        channel: Channel::Email,
+        to: email,
+        subject: subject(&contacts),
+        body: body(&contacts),
+    };
+    if let Err(e) = enqueue(Job::SendNotification(message)).await {
+        tracing::error!(booking_id, "Failed to queue booking notification: {}", e);
+    }
+}
+
+/// Emails the client the artist's suggested time, with their link to answer
+#[cfg(feature = "ssr")]
+pub(crate) async fn notify_client_of_suggestion(booking_id: i32, proposal: &RescheduleProposal) {
+    let time = describe_time(&proposal.date, &proposal.start_time);
+    notify(
+        booking_id,
+        |contacts| Some(contacts.client_email.clone()),
+        |contacts| {
+            format!(
+                "{} suggested another time for your tattoo",
+                contacts.artist_name.as_deref().unwrap_or("Your artist")
+            )
+        },
+        |contacts| {
+            format!(
+                "Hi {},\n\n{} suggested {} for your booking. Accept it, suggest another time \
+                 or cancel here: {}",
+                contacts.client_name,
+                contacts.artist_name.as_deref().unwrap_or("Your artist"),
+                time,
+                response_link(booking_id)
+            )
+        },
+    )
+    .await;
+}
+
+/// The booking behind a client's link, with the time the artist suggested
+/// if it's still open.
+#[server(prefix = "/api", endpoint = "get_linked_booking")]
+#[cfg_attr(feature = "ssr", instrument(skip(link_token), err, level = "info"))]
+pub async fn get_linked_booking(
+    booking_id: i32,
+    link_token: String,
+) -> Result<LinkedBooking, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        verify_link(booking_id, &link_token)?;
+
+        Ok(crate::db::reschedule_repository::get_linked_booking(
+            booking_id,
+            crate::config::config().cancellation_window_hours,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to load booking", e))?
+        .ok_or_else(|| ApiError::not_found("Booking not found"))?)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Accepts the time the artist suggested, moving the booking to it.
+#[server(prefix = "/api", endpoint = "accept_suggested_time")]
+#[cfg_attr(feature = "ssr", instrument(skip(link_token), err, level = "info"))]
+pub async fn accept_suggested_time(
+    booking_id: i32,
+    link_token: String,
+    proposal_id: i64,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::reschedule_repository::{respond_to_proposal, ProposalResponse};
+
+        verify_link(booking_id, &link_token)?;
+
+        let response = respond_to_proposal(booking_id, proposal_id, "client", None, true, None)
+            .await
+            .map_err(|e| ApiError::internal("Failed to accept the time", e))?;
+        let ProposalResponse::Accepted { status } = response else {
+            return Err(ApiError::conflict("This time is no longer open").into());
+        };
+        crate::reminders::schedule_reminders(booking_id).await;
+
+        notify(
+            booking_id,
+            |contacts| contacts.artist_email.clone(),
+            |contacts| format!("{} accepted your suggested time", contacts.client_name),
+            |contacts| {
+                format!(
+                    "{} accepted the time you suggested, and the booking has moved to it{}. \
+                     See it at {}/artist/dashboard/booking/{}",
+                    contacts.client_name,
+                    status
+                        .map(|status| format!(" and is now {}", status.label().to_lowercase()))
+                        .unwrap_or_default(),
+                    crate::config::config().app_base_url,
+                    booking_id
+                )
+            },
+        )
+        .await;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Declines the time the artist suggested, optionally saying why and
+/// offering another time for the artist to answer.
+#[server(prefix = "/api", endpoint = "decline_suggested_time")]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(link_token, note), err, level = "info")
+)]
+pub async fn decline_suggested_time(
+    booking_id: i32,
+    link_token: String,
+    proposal_id: i64,
+    note: Option<String>,
+    counter: Option<TimeProposal>,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::reschedule_repository::{
+            propose_time, respond_to_proposal, ProposalResponse,
+        };
+        use crate::utils::reschedule::normalize_note;
+
+        verify_link(booking_id, &link_token)?;
+        let note = normalize_note(note.as_deref()).map_err(|e| ApiError::validation("note", e))?;
+        if let Some(counter) = &counter {
+            validate_proposal(counter)?;
+        }
+
+        let response = respond_to_proposal(
+            booking_id,
+            proposal_id,
+            "client",
+            None,
+            false,
+            note.as_deref(),
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to decline the time", e))?;
+        if let ProposalResponse::NotOpen = response {
+            return Err(ApiError::conflict("This time is no longer open").into());
+        }
+
+        let countered = match counter {
+            Some(counter) => propose_time(
+                booking_id,
+                "client",
+                counter.date,
+                counter.start_time,
+                counter.end_time,
+            )
+            .await
+            .map_err(|e| ApiError::internal("Failed to suggest another time", e))?,
+            None => None,
+        };
+
+        notify(
+            booking_id,
+            |contacts| contacts.artist_email.clone(),
+            |contacts| format!("{} can't make your suggested time", contacts.client_name),
+            |contacts| {
+                let mut body = format!("{} declined the time you suggested", contacts.client_name);
+                match &countered {
+                    Some(proposal) => body.push_str(&format!(
+                        " and asked for {} instead",
+                        describe_time(&proposal.date, &proposal.start_time)
+                    )),
+                    None => body.push('.'),
+                }
+                if let Some(note) = &note {
+                    body.push_str(&format!("\n\n\"{}\"", note));
+                }
+                body.push_str(&format!(
+                    "\n\nAnswer at {}/artist/dashboard/booking/{}",
+                    crate::config::config().app_base_url,
+                    booking_id
+                ));
+                body
+            },
+        )
+        .await;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Cancels the booking from the client's link with one of
+/// `CLIENT_CANCELLATION_REASONS`. Returns whether it was inside the artist's
+/// cancellation window, which loses the client their deposit.
+#[server(prefix = "/api", endpoint = "cancel_booking_by_link")]
+#[cfg_attr(
+    feature = "ssr",
+    instrument(skip(link_token, note), err, level = "info")
+)]
+pub async fn cancel_booking_by_link(
+    booking_id: i32,
+    link_token: String,
+    reason: String,
+    note: Option<String>,
+) -> Result<bool, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::reschedule::{
+            cancellation_reason_label, normalize_note, CLIENT_CANCELLATION_REASONS,
+        };
+
+        verify_link(booking_id, &link_token)?;
+        validate_reason(&reason, CLIENT_CANCELLATION_REASONS)?;
+        let note = normalize_note(note.as_deref()).map_err(|e| ApiError::validation("note", e))?;
+
+        let late = crate::db::reschedule_repository::cancel_booking(
+            booking_id,
+            "client",
+            None,
+            &reason,
+            note.as_deref(),
+            crate::config::config().cancellation_window_hours,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to cancel booking", e))?
+        .map_err(|_| ApiError::conflict("This booking can't be cancelled any more"))?;
+        crate::reminders::schedule_reminders(booking_id).await;
+
+        notify(
+            booking_id,
+            |contacts| contacts.artist_email.clone(),
+            |contacts| format!("{} cancelled their booking", contacts.client_name),
+            |contacts| {
+                format!(
+                    "{} cancelled their booking: {}.{}{}",
+                    contacts.client_name,
+                    cancellation_reason_label(&reason),
+                    note.as_deref()
+                        .map(|note| format!("\n\n\"{}\"", note))
+                        .unwrap_or_default(),
+                    if late {
+                        "\n\nThis was inside your cancellation window, so any deposit paid is \
+                         yours to keep."
+                    } else {
+                        ""
+                    }
+                )
+            },
+        )
+        .await;
+        Ok(late)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(false)
+    }
+}
+
+/// The open proposal on one of the artist's bookings, if any.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_open_reschedule(
+    token: String,
+    booking_id: i32,
+) -> Result<Option<RescheduleProposal>, ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_booking, TeamPermission};
+
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+
+        Ok(
+            crate::db::reschedule_repository::get_open_proposal(booking_id)
+                .await
+                .map_err(|e| ApiError::internal("Failed to load suggested time", e))?,
+        )
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// Accepts or declines a time the client countered with.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, note), err, level = "info"))]
+pub async fn respond_to_client_time(
+    token: String,
+    booking_id: i32,
+    proposal_id: i64,
+    accept: bool,
+    note: Option<String>,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::reschedule_repository::{respond_to_proposal, ProposalResponse};
+        use crate::server_team::{authorize_booking, TeamPermission};
+        use crate::utils::reschedule::normalize_note;
+
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+        let changed_by = crate::server::extract_user_from_token(&token).map(|(user_id, _)| user_id);
+        let note = normalize_note(note.as_deref()).map_err(|e| ApiError::validation("note", e))?;
+
+        let response = respond_to_proposal(
+            booking_id,
+            proposal_id,
+            "artist",
+            changed_by,
+            accept,
+            note.as_deref(),
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to answer the client's time", e))?;
+        if let ProposalResponse::NotOpen = response {
+            return Err(ApiError::conflict("This time is no longer open").into());
+        }
+        if accept {
+            crate::reminders::schedule_reminders(booking_id).await;
+        }
+
+        notify(
+            booking_id,
+            |contacts| Some(contacts.client_email.clone()),
+            |contacts| {
+                format!(
+                    "{} {} your suggested time",
+                    contacts.artist_name.as_deref().unwrap_or("Your artist"),
+                    if accept { "accepted" } else { "can't make" }
+                )
+            },
+            |contacts| {
+                let artist = contacts.artist_name.as_deref().unwrap_or("Your artist");
+                if accept {
+                    format!(
+                        "Hi {},\n\n{} accepted the time you suggested, and your booking has \
+                         moved to it.",
+                        contacts.client_name, artist
+                    )
+                } else {
+                    format!(
+                        "Hi {},\n\n{} can't make the time you suggested.{}\n\nSee your booking \
+                         or cancel it here: {}",
+                        contacts.client_name,
+                        artist,
+                        note.as_deref()
+                            .map(|note| format!("\n\n\"{}\"", note))
+                            .unwrap_or_default(),
+                        response_link(booking_id)
+                    )
+                }
+            },
+        )
+        .await;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// Cancels one of the artist's bookings with one of
+/// `ARTIST_CANCELLATION_REASONS`, letting the client know.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, note), err, level = "info"))]
+pub async fn cancel_booking(
+    token: String,
+    booking_id: i32,
+    reason: String,
+    note: Option<String>,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_booking, TeamPermission};
+        use crate::utils::reschedule::{
+            cancellation_reason_label, normalize_note, ARTIST_CANCELLATION_REASONS,
+        };
+
+        authorize_booking(&token, booking_id, TeamPermission::Bookings).await?;
+        let changed_by = crate::server::extract_user_from_token(&token).map(|(user_id, _)| user_id);
+        validate_reason(&reason, ARTIST_CANCELLATION_REASONS)?;
+        let note = normalize_note(note.as_deref()).map_err(|e| ApiError::validation("note", e))?;
+
+        crate::db::reschedule_repository::cancel_booking(
+            booking_id,
+            "artist",
+            changed_by,
+            &reason,
+            note.as_deref(),
+            crate::config::config().cancellation_window_hours,
+        )
+        .await
+        .map_err(|e| ApiError::internal("Failed to cancel booking", e))?
+        .map_err(|_| ApiError::conflict("This booking can't be cancelled any more"))?;
+        crate::reminders::schedule_reminders(booking_id).await;
+
+        notify(
+            booking_id,
+            |contacts| Some(contacts.client_email.clone()),
+            |contacts| {
+                format!(
+                    "{} cancelled your booking",
+                    contacts.artist_name.as_deref().unwrap_or("Your artist")
+                )
+            },
+            |contacts| {
+                format!(
+                    "Hi {},\n\n{} cancelled your booking: {}.{}",
+                    contacts.client_name,
+                    contacts.artist_name.as_deref().unwrap_or("Your artist"),
+                    cancellation_reason_label(&reason),
+                    note.as_deref()
+                        .map(|note| format!("\n\n\"{}\"", note))
+                        .unwrap_or_default()
+                )
+            },
+        )
+        .await;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// The signed-in artist's cancellation window in hours, theirs or the
+/// default, and whether it's their own.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_cancellation_window(
+    token: String,
+) -> Result<(i32, bool), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        let own = crate::db::reschedule_repository::get_cancellation_window(artist_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to load cancellation window", e))?;
+        Ok((own.unwrap_or_else(default_window_hours), own.is_some()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok((0, false))
+    }
+}
+
+/// Sets the signed-in artist's cancellation window in hours; `None` goes
+/// back to the default.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_cancellation_window(
+    token: String,
+    hours: Option<i32>,
+) -> Result<(), ServerFnError<ApiError>> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::utils::reschedule::MAX_CANCELLATION_WINDOW_HOURS;
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        if hours.is_some_and(|hours| !(0..=MAX_CANCELLATION_WINDOW_HOURS).contains(&hours)) {
+            return Err(ApiError::validation(
+                "hours",
+                format!(
+                    "Set a window from 0 to {} hours",
+                    MAX_CANCELLATION_WINDOW_HOURS
+                ),
+            )
+            .into());
+        }
+
+        crate::db::reschedule_repository::set_cancellation_window(artist_id, hours)
+            .await
+            .map_err(|e| ApiError::internal("Failed to save cancellation window", e))?;
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{ResponseTimeStats, SlaNudge};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Rolling response-time figures for an artist's public profile.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_artist_response_time(artist_id: i32) -> Result<ResponseTimeStats, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::response_time_repository;
+        use crate::utils::response_time::WINDOW_DAYS;
+
+        let stats = response_time_repository::get_response_stats(&[artist_id], WINDOW_DAYS)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load response times: {}", e)))?;
+
+        Ok(stats.into_iter().next().unwrap_or(ResponseTimeStats {
+            artist_id,
+            average_hours: None,
+            median_hours: None,
+            responded_count: 0,
+            request_count: 0,
+            label: None,
+        }))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Pending requests of the signed-in artist that are close to, or past, the
+/// response SLA.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_sla_nudges(token: String) -> Result<Vec<SlaNudge>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::response_time_repository;
+        use crate::utils::response_time::nudge_after_hours;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        response_time_repository::get_unanswered_requests(artist_id, nudge_after_hours())
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load pending requests: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
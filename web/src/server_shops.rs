@@ -0,0 +1,656 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{
+    ShopArtistSummary, ShopBooking, ShopClaim, ShopMembership, ShopProfile, ShopProfileUpdate,
+    ShopUser,
+};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Roles on a shop's account: owners manage the shop and its account,
+/// managers work the bookings of the shop's artists.
+pub const SHOP_ROLES: [&str; 2] = ["owner", "manager"];
+
+/// Longest stretch of the shop calendar fetched at once
+#[cfg(feature = "ssr")]
+const MAX_SHOP_CALENDAR_DAYS: i64 = 62;
+
+/// Website hosts that don't identify a single shop, so an email on the same
+/// domain proves nothing about ownership.
+#[cfg(feature = "ssr")]
+const SHARED_WEBSITE_HOSTS: &[&str] = &[
+    "instagram.com",
+    "facebook.com",
+    "linktr.ee",
+    "google.com",
+    "gmail.com",
+    "yahoo.com",
+    "outlook.com",
+    "hotmail.com",
+];
+
+/// Host of a website URL without scheme, port, path or a leading `www.`.
+#[cfg(feature = "ssr")]
+fn website_domain(website_uri: &str) -> Option<String> {
+    let without_scheme = website_uri
+        .trim()
+        .split("://")
+        .last()
+        .unwrap_or_default()
+        .to_lowercase();
+    let host = without_scheme
+        .split(['/', '?', '#', ':'])
+        .next()
+        .unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// Whether the account email is on the shop's own website domain.
+#[cfg(feature = "ssr")]
+fn email_matches_website(email: &str, website_uri: Option<&str>) -> bool {
+    let Some(domain) = website_uri.and_then(website_domain) else {
+        return false;
+    };
+    let email = email.trim().to_lowercase();
+    let Some((_, email_domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+
+    email_domain == domain && !SHARED_WEBSITE_HOSTS.contains(&domain.as_str())
+}
+
+#[cfg(feature = "ssr")]
+fn user_from_token(token: &str) -> Result<(i64, String), ServerFnError> {
+    crate::server::extract_user_from_token(token)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))
+}
+
+/// Resolves the caller and checks they own the shop (admins may act on any shop).
+#[cfg(feature = "ssr")]
+async fn shop_owner_from_token(token: &str, location_id: i64) -> Result<i64, ServerFnError> {
+    use crate::db::shop_claim_repository;
+
+    let (user_id, user_type) = user_from_token(token)?;
+    if user_type == "admin" {
+        return Ok(user_id);
+    }
+
+    let is_owner = shop_claim_repository::is_shop_owner(location_id, user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to check shop ownership: {}", e)))?;
+
+    if !is_owner {
+        return Err(ServerFnError::new(
+            "Unauthorized: You don't manage this shop".to_string(),
+        ));
+    }
+
+    Ok(user_id)
+}
+
+/// Resolves the caller and their role on the shop's account (admins act as
+/// owners of any shop).
+#[cfg(feature = "ssr")]
+async fn shop_user_from_token(
+    token: &str,
+    location_id: i64,
+) -> Result<(i64, String), ServerFnError> {
+    let (user_id, user_type) = user_from_token(token)?;
+    if user_type == "admin" {
+        return Ok((user_id, "owner".to_string()));
+    }
+
+    crate::db::shop_user_repository::get_role(location_id, user_id)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to check shop access: {}", e)))?
+        .map(|role| (user_id, role))
+        .ok_or_else(|| ServerFnError::new("Unauthorized: You don't manage this shop".to_string()))
+}
+
+/// Submits a claim on a shop. `method` is `email_domain` (the account email
+/// is on the shop's website domain, checked here) or `google_business`
+/// (`evidence` links the Google Business profile for an admin to verify).
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn submit_shop_claim(
+    token: String,
+    location_id: i64,
+    method: String,
+    evidence: Option<String>,
+) -> Result<ShopClaim, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::shop_claim_repository;
+
+        let (user_id, _user_type) = user_from_token(&token)?;
+
+        let evidence = evidence
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty());
+        match method.as_str() {
+            "email_domain" => {}
+            "google_business" if evidence.is_some() => {}
+            "google_business" => {
+                return Err(ServerFnError::new(
+                    "A Google Business profile link is required".to_string(),
+                ))
+            }
+            _ => {
+                return Err(ServerFnError::new(format!(
+                    "Unknown verification method: {}",
+                    method
+                )))
+            }
+        }
+
+        let (website_uri, claimed_by) = shop_claim_repository::get_location_claim_info(location_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load shop: {}", e)))?
+            .ok_or_else(|| ServerFnError::new("Shop not found".to_string()))?;
+
+        if claimed_by.is_some() {
+            return Err(ServerFnError::new(
+                "This shop has already been claimed".to_string(),
+            ));
+        }
+
+        let email = shop_claim_repository::get_user_email(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load user: {}", e)))?;
+        let email_domain_match = email_matches_website(&email, website_uri.as_deref());
+
+        if method == "email_domain" && !email_domain_match {
+            return Err(ServerFnError::new(
+                "Your account email isn't on this shop's website domain. Verify with Google Business instead.".to_string(),
+            ));
+        }
+
+        let claim_id = shop_claim_repository::create_shop_claim(
+            location_id,
+            user_id,
+            &method,
+            evidence.as_deref(),
+            email_domain_match,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to submit claim: {}", e)))?;
+
+        shop_claim_repository::get_user_shop_claims(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load claim: {}", e)))?
+            .into_iter()
+            .find(|claim| claim.id == claim_id)
+            .ok_or_else(|| ServerFnError::new("Claim not found".to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_shop_claims(token: String) -> Result<Vec<ShopClaim>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (user_id, _user_type) = user_from_token(&token)?;
+
+        crate::db::shop_claim_repository::get_user_shop_claims(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load claims: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_pending_shop_claims(token: String) -> Result<Vec<ShopClaim>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (_user_id, user_type) = user_from_token(&token)?;
+
+        // Verify admin role
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        crate::db::shop_claim_repository::get_pending_shop_claims()
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load claims: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn review_shop_claim(
+    token: String,
+    claim_id: i64,
+    approve: bool,
+    note: Option<String>,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (user_id, user_type) = user_from_token(&token)?;
+
+        // Verify admin role
+        if user_type != "admin" {
+            return Err(ServerFnError::new(
+                "Unauthorized: Admin access required".to_string(),
+            ));
+        }
+
+        crate::db::shop_claim_repository::review_shop_claim(
+            claim_id,
+            approve,
+            note.as_deref(),
+            user_id,
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ServerFnError::new(
+                "Claim is no longer pending or the shop already has an owner".to_string(),
+            ),
+            e => ServerFnError::new(format!("Failed to review claim: {}", e)),
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_shop_profile(location_id: i64) -> Result<ShopProfile, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::shop_claim_repository::get_shop_profile(location_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load shop: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_shop_profile(
+    token: String,
+    location_id: i64,
+    update: ShopProfileUpdate,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        shop_owner_from_token(&token, location_id).await?;
+
+        let update = ShopProfileUpdate {
+            description: update
+                .description
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            opening_hours: update
+                .opening_hours
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty()),
+        };
+
+        crate::db::shop_claim_repository::update_shop_profile(location_id, &update)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to update shop: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Adds a completed portfolio upload (see `/api/uploads`) to the shop's gallery.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn add_shop_photo(
+    token: String,
+    location_id: i64,
+    upload_id: String,
+) -> Result<i64, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = shop_owner_from_token(&token, location_id).await?;
+
+        crate::db::shop_claim_repository::add_shop_photo(location_id, &upload_id, user_id)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    ServerFnError::new("Upload not found or not finished".to_string())
+                }
+                e => ServerFnError::new(format!("Failed to add photo: {}", e)),
+            })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn remove_shop_photo(
+    token: String,
+    location_id: i64,
+    photo_id: i64,
+) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        shop_owner_from_token(&token, location_id).await?;
+
+        crate::db::shop_claim_repository::remove_shop_photo(location_id, photo_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to remove photo: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Adds an artist who isn't yet with any shop to this shop's roster.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn add_artist_to_shop(
+    token: String,
+    location_id: i64,
+    artist_id: i64,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        shop_owner_from_token(&token, location_id).await?;
+
+        let added = crate::db::shop_claim_repository::add_artist_to_shop(location_id, artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to add artist: {}", e)))?;
+
+        if !added {
+            return Err(ServerFnError::new(
+                "Artist not found or already listed at another shop".to_string(),
+            ));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn remove_artist_from_shop(
+    token: String,
+    location_id: i64,
+    artist_id: i64,
+) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        shop_owner_from_token(&token, location_id).await?;
+
+        crate::db::shop_claim_repository::remove_artist_from_shop(location_id, artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to remove artist: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Lists an artist on this shop's roster as an apprentice, junior or senior
+/// artist; blank clears it. Shops use this to offer apprentices at lower
+/// prices.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn set_shop_artist_experience_tier(
+    token: String,
+    location_id: i64,
+    artist_id: i64,
+    tier: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::experience::normalize_tier;
+
+        shop_owner_from_token(&token, location_id).await?;
+        let tier = normalize_tier(&tier).map_err(ServerFnError::new)?;
+
+        let updated = crate::db::shop_claim_repository::set_artist_experience_tier(
+            location_id,
+            artist_id,
+            tier.as_deref(),
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to save experience tier: {}", e)))?;
+
+        if !updated {
+            return Err(ServerFnError::new(
+                "Artist not found at this shop".to_string(),
+            ));
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Shops the signed-in user is on the account of, for the shop dashboard.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_shops(token: String) -> Result<Vec<ShopMembership>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (user_id, _user_type) = user_from_token(&token)?;
+
+        crate::db::shop_user_repository::get_user_shops(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load shops: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// The shop's artists with their open requests and upcoming bookings.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_shop_artists(
+    token: String,
+    location_id: i64,
+) -> Result<Vec<ShopArtistSummary>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        shop_user_from_token(&token, location_id).await?;
+
+        crate::db::shop_user_repository::get_shop_artists(location_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load artists: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Requests waiting on any of the shop's artists, oldest first. Shop users
+/// answer them like the artist would, through `respond_to_booking`.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_shop_requests(
+    token: String,
+    location_id: i64,
+) -> Result<Vec<ShopBooking>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        shop_user_from_token(&token, location_id).await?;
+
+        crate::db::shop_user_repository::get_shop_open_requests(location_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load requests: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Booked sessions across the shop's artists from `start_date` to
+/// `end_date` (`YYYY-MM-DD`, inclusive).
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_shop_calendar(
+    token: String,
+    location_id: i64,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<ShopBooking>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use chrono::NaiveDate;
+
+        shop_user_from_token(&token, location_id).await?;
+
+        let parse = |date: &str| {
+            NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                .map_err(|_| ServerFnError::new(format!("Invalid date: {}", date)))
+        };
+        let (start, end) = (parse(&start_date)?, parse(&end_date)?);
+        if end < start || (end - start).num_days() >= MAX_SHOP_CALENDAR_DAYS {
+            return Err(ServerFnError::new(format!(
+                "The calendar shows up to {} days at a time",
+                MAX_SHOP_CALENDAR_DAYS
+            )));
+        }
+
+        crate::db::shop_user_repository::get_shop_calendar(
+            location_id,
+            &start.format("%Y-%m-%d").to_string(),
+            &end.format("%Y-%m-%d").to_string(),
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to load calendar: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Everyone on the shop's account. Owners only.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_shop_users(
+    token: String,
+    location_id: i64,
+) -> Result<Vec<ShopUser>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        shop_owner_from_token(&token, location_id).await?;
+
+        crate::db::shop_user_repository::get_shop_users(location_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load shop users: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Puts an existing account on the shop's account as an owner or manager,
+/// or changes the role of someone already on it. Owners only.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, email), err, level = "info"))]
+pub async fn add_shop_user(
+    token: String,
+    location_id: i64,
+    email: String,
+    role: String,
+) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::shop_user_repository;
+
+        let owner_id = shop_owner_from_token(&token, location_id).await?;
+
+        let role = role.trim().to_ascii_lowercase();
+        if !SHOP_ROLES.contains(&role.as_str()) {
+            return Err(ServerFnError::new(format!("Unknown role: {}", role)));
+        }
+
+        let user_id = shop_user_repository::find_user_id_by_email(&email)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to look up account: {}", e)))?
+            .ok_or_else(|| {
+                ServerFnError::new(
+                    "No account uses that email; ask them to sign up first".to_string(),
+                )
+            })?;
+        if user_id == owner_id {
+            return Err(ServerFnError::new(
+                "You can't change your own role".to_string(),
+            ));
+        }
+
+        shop_user_repository::add_shop_user(location_id, user_id, &role, owner_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to add shop user: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Takes someone off the shop's account. Owners only, and not themselves, so
+/// a shop always keeps an owner.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn remove_shop_user(
+    token: String,
+    location_id: i64,
+    user_id: i64,
+) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let owner_id = shop_owner_from_token(&token, location_id).await?;
+        if user_id == owner_id {
+            return Err(ServerFnError::new(
+                "You can't remove yourself from the shop".to_string(),
+            ));
+        }
+
+        crate::db::shop_user_repository::remove_shop_user(location_id, user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to remove shop user: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,97 @@
+use leptos::prelude::*;
+
+use crate::db::entities::ShortLinkStats;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Referrers listed in profile link stats
+#[cfg(feature = "ssr")]
+const TOP_REFERRERS: i64 = 5;
+
+/// The short URL for an artist profile ("artist" and the artist's id), a
+/// shop page ("shop" and its id) or a search ("search" and its path with
+/// query string). Anyone can share; `token` only records who did.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn create_short_link(
+    target_type: String,
+    target: String,
+    token: Option<String>,
+) -> Result<String, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::utils::short_links::normalize_target;
+
+        let target = normalize_target(&target_type, &target).map_err(ServerFnError::new)?;
+
+        // Only link to profiles and shops that exist
+        let not_found = || ServerFnError::new(format!("No {} with id {}", target_type, target));
+        match target_type.as_str() {
+            "artist" => {
+                let id = target.parse().map_err(|_| not_found())?;
+                crate::db::repository::get_artist_by_id(id)
+                    .await
+                    .map_err(|_| not_found())?;
+            }
+            "shop" => {
+                let id = target.parse().map_err(|_| not_found())?;
+                crate::db::repository::get_location_by_id(id)
+                    .await
+                    .map_err(|_| not_found())?;
+            }
+            _ => {}
+        }
+
+        let created_by = token
+            .as_deref()
+            .and_then(crate::server::extract_user_from_token)
+            .map(|(user_id, _)| user_id);
+        crate::short_links::shorten(&target_type, &target, created_by)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to create link: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The artist's profile short link, created if needed, with its visits by
+/// referrer
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_profile_link_stats(token: String) -> Result<ShortLinkStats, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::short_link_repository;
+        use crate::server_team::{authorize_artist, TeamPermission};
+        use crate::short_links;
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+        let (user_id, _) = crate::server::extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to load profile link: {}", e));
+        let code = short_links::get_or_create_code("artist", &artist_id.to_string(), Some(user_id))
+            .await
+            .map_err(db_error)?;
+        let (clicks, referrers) = short_link_repository::get_clicks(&code, TOP_REFERRERS)
+            .await
+            .map_err(db_error)?;
+
+        Ok(ShortLinkStats {
+            url: short_links::short_url(&code),
+            clicks,
+            referrers,
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
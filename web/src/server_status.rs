@@ -0,0 +1,303 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{PlatformStatus, StatusIncident};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+#[cfg(feature = "ssr")]
+use crate::db::entities::SubsystemStatus;
+
+/// Ingestion is reported degraded when no run has finished for this long
+#[cfg(feature = "ssr")]
+const INGESTION_STALE_HOURS: f64 = 48.0;
+/// Database round trips slower than this are reported degraded
+#[cfg(feature = "ssr")]
+const SLOW_DATABASE_MS: u128 = 1000;
+/// How long resolved incidents stay listed
+#[cfg(feature = "ssr")]
+const RESOLVED_INCIDENT_DAYS: i32 = 7;
+
+#[cfg(feature = "ssr")]
+const SEVERITIES: &[&str] = &["maintenance", "degraded", "outage"];
+
+/// Higher is worse; 'operational' (or anything unknown) is 0
+#[cfg(feature = "ssr")]
+fn severity_rank(status: &str) -> usize {
+    SEVERITIES
+        .iter()
+        .position(|s| *s == status)
+        .map_or(0, |i| i + 1)
+}
+
+#[cfg(feature = "ssr")]
+fn subsystem(name: &str, status: &str, detail: Option<String>) -> SubsystemStatus {
+    SubsystemStatus {
+        name: name.to_string(),
+        status: status.to_string(),
+        detail,
+    }
+}
+
+/// Checks each subsystem and folds in the open incidents. Never fails: a
+/// subsystem that can't be checked is reported as such.
+#[cfg(feature = "ssr")]
+pub async fn build_platform_status() -> PlatformStatus {
+    use crate::db::status_repository;
+
+    let mut subsystems = vec![subsystem("web", "operational", None)];
+
+    let database_up = match status_repository::ping_database().await {
+        Ok(ms) if ms > SLOW_DATABASE_MS => {
+            subsystems.push(subsystem(
+                "database",
+                "degraded",
+                Some(format!("Slow responses ({} ms)", ms)),
+            ));
+            true
+        }
+        Ok(_) => {
+            subsystems.push(subsystem("database", "operational", None));
+            true
+        }
+        Err(e) => {
+            tracing::error!("Status check: database unreachable: {}", e);
+            subsystems.push(subsystem(
+                "database",
+                "outage",
+                Some("Database unreachable".to_string()),
+            ));
+            false
+        }
+    };
+
+    let mut incidents = Vec::new();
+    if database_up {
+        subsystems.push(match status_repository::get_last_ingestion_run().await {
+            Ok(Some((action, hours))) if hours > INGESTION_STALE_HOURS => subsystem(
+                "ingestion",
+                "degraded",
+                Some(format!(
+                    "Last run ({}) finished {:.0} hours ago",
+                    action, hours
+                )),
+            ),
+            Ok(Some((action, hours))) => subsystem(
+                "ingestion",
+                "operational",
+                Some(format!(
+                    "Last run ({}) finished {:.0} hours ago",
+                    action, hours
+                )),
+            ),
+            Ok(None) => subsystem(
+                "ingestion",
+                "degraded",
+                Some("No ingestion runs recorded".to_string()),
+            ),
+            Err(e) => {
+                tracing::error!("Status check: failed to load ingestion runs: {}", e);
+                subsystem(
+                    "ingestion",
+                    "degraded",
+                    Some("Status unavailable".to_string()),
+                )
+            }
+        });
+
+        match status_repository::get_status_incidents(RESOLVED_INCIDENT_DAYS).await {
+            Ok(loaded) => incidents = loaded,
+            Err(e) => tracing::error!("Status check: failed to load incidents: {}", e),
+        }
+    }
+
+    // An open circuit breaker means requests to that service are failing
+    for breaker in crate::circuit_breakers::all() {
+        let snapshot = breaker.snapshot();
+        if snapshot.state != shared_types::circuit_breaker::BreakerState::Closed {
+            subsystems.push(subsystem(
+                breaker.name(),
+                "degraded",
+                Some("Requests failing; retrying shortly".to_string()),
+            ));
+        }
+    }
+
+    // Open incidents raise their subsystem's status, adding subsystems that
+    // aren't checked directly (external APIs)
+    for incident in incidents.iter().filter(|i| i.resolved_at.is_none()) {
+        match subsystems.iter_mut().find(|s| s.name == incident.subsystem) {
+            Some(existing) => {
+                if severity_rank(&incident.severity) > severity_rank(&existing.status) {
+                    existing.status = incident.severity.clone();
+                    existing.detail = Some(incident.title.clone());
+                }
+            }
+            None => subsystems.push(subsystem(
+                &incident.subsystem,
+                &incident.severity,
+                Some(incident.title.clone()),
+            )),
+        }
+    }
+
+    let status = subsystems
+        .iter()
+        .map(|s| s.status.as_str())
+        .max_by_key(|status| severity_rank(status))
+        .unwrap_or("operational")
+        .to_string();
+
+    PlatformStatus {
+        status,
+        subsystems,
+        incidents,
+        generated_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    }
+}
+
+/// GET /api/status — public JSON summary for the status page. Always answers
+/// 200 while the web server is up; the body says what's wrong.
+#[cfg(feature = "ssr")]
+pub async fn status_handler() -> axum::response::Response {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    (
+        [(header::CACHE_CONTROL, "public, max-age=30")],
+        axum::Json(build_platform_status().await),
+    )
+        .into_response()
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "info"))]
+pub async fn get_platform_status() -> Result<PlatformStatus, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        Ok(build_platform_status().await)
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn admin_from_token(token: &str) -> Result<i64, ServerFnError> {
+    let (user_id, user_type) = crate::server::extract_user_from_token(token)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+    // Verify admin role
+    if user_type != "admin" {
+        return Err(ServerFnError::new(
+            "Unauthorized: Admin access required".to_string(),
+        ));
+    }
+
+    Ok(user_id)
+}
+
+#[cfg(feature = "ssr")]
+fn validate_severity(severity: &str) -> Result<(), ServerFnError> {
+    if SEVERITIES.contains(&severity) {
+        Ok(())
+    } else {
+        Err(ServerFnError::new(format!(
+            "Unknown severity: {}",
+            severity
+        )))
+    }
+}
+
+/// Posts an incident notice. `subsystem` is free-form so external services
+/// (e.g. `instagram`, `google_places`) can be named without a schema change.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn post_status_incident(
+    token: String,
+    subsystem: String,
+    severity: String,
+    title: String,
+    message: Option<String>,
+) -> Result<StatusIncident, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = admin_from_token(&token)?;
+        validate_severity(&severity)?;
+
+        let subsystem = subsystem.trim().to_lowercase();
+        let title = title.trim();
+        if subsystem.is_empty() || title.is_empty() {
+            return Err(ServerFnError::new(
+                "Subsystem and title are required".to_string(),
+            ));
+        }
+
+        crate::db::status_repository::create_status_incident(
+            &subsystem,
+            &severity,
+            title,
+            message.as_deref(),
+            user_id,
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to post incident: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_status_incident(
+    token: String,
+    incident_id: i64,
+    severity: Option<String>,
+    message: Option<String>,
+) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        admin_from_token(&token)?;
+        if let Some(severity) = &severity {
+            validate_severity(severity)?;
+        }
+
+        crate::db::status_repository::update_status_incident(
+            incident_id,
+            severity.as_deref(),
+            message.as_deref(),
+        )
+        .await
+        .map_err(|e| ServerFnError::new(format!("Failed to update incident: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn resolve_status_incident(
+    token: String,
+    incident_id: i64,
+) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        admin_from_token(&token)?;
+
+        crate::db::status_repository::resolve_status_incident(incident_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to resolve incident: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+
+use crate::db::entities::SyncDelta;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Largest page of changes returned by one sync call
+#[cfg(feature = "ssr")]
+const MAX_SYNC_CHANGES: i64 = 500;
+
+/// Delta sync for an artist's bookings, booking messages and availability.
+///
+/// Without `since` the full current state is returned along with a cursor.
+/// Pass the returned `cursor` as `since` to get only what changed after it,
+/// repeating while `has_more` is set. Served at a fixed `/api/sync` path so
+/// native clients don't depend on generated server fn URLs.
+#[server(prefix = "/api", endpoint = "sync")]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_sync_changes(
+    token: String,
+    since: Option<i64>,
+    limit: Option<i64>,
+) -> Result<SyncDelta, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::sync_repository;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let result = match since {
+            Some(since) => {
+                let limit = limit.unwrap_or(MAX_SYNC_CHANGES).clamp(1, MAX_SYNC_CHANGES);
+                sync_repository::get_sync_changes(artist_id, since, limit).await
+            }
+            None => sync_repository::get_sync_snapshot(artist_id).await,
+        };
+
+        result.map_err(|e| ServerFnError::new(format!("Failed to load changes: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,196 @@
+use leptos::prelude::*;
+
+use crate::db::entities::{ClientTattooPhoto, PublicTattooPhoto, TattooBookingOption};
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Longest caption accepted on a tattoo photo
+#[cfg(feature = "ssr")]
+const MAX_CAPTION_LEN: usize = 500;
+/// Healed-work photos shown on an artist's profile
+#[cfg(feature = "ssr")]
+const HEALED_WORK_LIMIT: i64 = 24;
+
+#[cfg(feature = "ssr")]
+fn user_id_from_token(token: &str) -> Result<i64, ServerFnError> {
+    crate::server::extract_user_from_token(token)
+        .map(|(user_id, _)| user_id)
+        .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))
+}
+
+/// The caller's tattoo photos, newest first.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_tattoo_photos(token: String) -> Result<Vec<ClientTattooPhoto>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let user_id = user_id_from_token(&token)?;
+
+        crate::db::tattoo_photo_repository::get_photos_for_user(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get tattoo photos: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// The caller's completed bookings, which photos can be linked to.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_my_tattoo_bookings(
+    token: String,
+) -> Result<Vec<TattooBookingOption>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let email = crate::server_invoices::client_email_from_token(&token).await?;
+
+        crate::db::tattoo_photo_repository::get_completed_bookings(&email)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get bookings: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Updates a photo's caption and sharing. `is_public` shares it on its own
+/// page crediting the artist; `healed_consent` allows the artist to show it
+/// as healed work, and needs a healed photo linked to a booking.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_tattoo_photo(
+    token: String,
+    photo_id: i64,
+    caption: Option<String>,
+    healed: bool,
+    is_public: bool,
+    healed_consent: bool,
+) -> Result<ClientTattooPhoto, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::tattoo_photo_repository;
+
+        let user_id = user_id_from_token(&token)?;
+
+        let caption = caption
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+        if caption
+            .as_ref()
+            .is_some_and(|c| c.chars().count() > MAX_CAPTION_LEN)
+        {
+            return Err(ServerFnError::new(format!(
+                "Captions must be at most {} characters",
+                MAX_CAPTION_LEN
+            )));
+        }
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to update photo: {}", e));
+        let photo = tattoo_photo_repository::get_photo(user_id, photo_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServerFnError::new("Photo not found".to_string()))?;
+        if healed_consent && (!healed || photo.artist_id.is_none()) {
+            return Err(ServerFnError::new(
+                "Only healed photos linked to a booking can be shared with the artist".to_string(),
+            ));
+        }
+
+        tattoo_photo_repository::update_photo(
+            user_id,
+            photo_id,
+            caption.as_deref(),
+            healed,
+            is_public,
+            healed_consent,
+        )
+        .await
+        .map_err(db_error)?;
+
+        tattoo_photo_repository::get_photo(user_id, photo_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServerFnError::new("Photo not found".to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// Removes one of the caller's photos and deletes its files.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn delete_tattoo_photo(token: String, photo_id: i64) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::storage::private_storage;
+
+        let user_id = user_id_from_token(&token)?;
+
+        let keys = crate::db::tattoo_photo_repository::delete_photo(user_id, photo_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to delete photo: {}", e)))?;
+
+        let Some((storage_key, thumbnail_key)) = keys else {
+            return Err(ServerFnError::new("Photo not found".to_string()));
+        };
+
+        // The row is gone, so a file left behind is only wasted space
+        for key in [storage_key, thumbnail_key] {
+            if let Err(e) = private_storage().delete(&key).await {
+                tracing::warn!("Failed to delete stored photo {}: {}", key, e);
+            }
+        }
+        Ok(())
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(())
+    }
+}
+
+/// A photo its owner has shared publicly. Public.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn get_shared_tattoo_photo(
+    photo_id: i64,
+) -> Result<Option<PublicTattooPhoto>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::tattoo_photo_repository::get_public_photo(photo_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get photo: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
+
+/// Clients' healed photos of an artist's work, shared with their consent.
+/// Public, for profiles.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(err, level = "debug"))]
+pub async fn get_artist_healed_work(
+    artist_id: i32,
+) -> Result<Vec<PublicTattooPhoto>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        crate::db::tattoo_photo_repository::get_healed_work(artist_id, HEALED_WORK_LIMIT)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get healed work: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,339 @@
+//! Assistants on an artist's account. An artist invites someone by email
+//! with permission scopes; the invitee signs in with their own account and
+//! accepts through the invite link, after which requests made with their
+//! token may act on the artist's account within those scopes.
+//!
+//! Server fns that act on an artist's account authorize through
+//! `authorize_artist` and its variants instead of requiring an artist token.
+//! Managing the team, billing and payouts stay with the artist.
+
+use leptos::prelude::*;
+
+use crate::db::entities::{TeamAccess, TeamInvitation, TeamMember};
+
+#[cfg(feature = "ssr")]
+use crate::api_error::ApiError;
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+pub const TEAM_PERMISSIONS: [&str; 4] = ["messages", "calendar", "bookings", "settings"];
+
+/// How long an invite link can be used
+#[cfg(feature = "ssr")]
+const INVITE_TTL_DAYS: i64 = 7;
+
+/// What an assistant may be allowed to do on an artist's account
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TeamPermission {
+    /// Read and send booking messages
+    Messages,
+    /// Manage availability and recurring rules
+    Calendar,
+    /// See booking requests and respond to them
+    Bookings,
+    /// Change business hours and the booking questionnaire
+    Settings,
+}
+
+#[cfg(feature = "ssr")]
+impl TeamPermission {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Messages => "messages",
+            Self::Calendar => "calendar",
+            Self::Bookings => "bookings",
+            Self::Settings => "settings",
+        }
+    }
+}
+
+/// The artist a request acts for: the artist's own account, or the account
+/// an assistant works on if they were given `permission`
+#[cfg(feature = "ssr")]
+pub(crate) async fn authorize_artist(
+    token: &str,
+    permission: TeamPermission,
+) -> Result<i32, ApiError> {
+    let (user_id, user_type) = crate::server::extract_user_from_token(token)
+        .ok_or_else(|| ApiError::unauthorized("Invalid or expired token"))?;
+
+    if user_type == "artist" {
+        return crate::db::repository::get_artist_id_from_user_id(user_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to resolve artist", e))?
+            .ok_or_else(|| ApiError::unauthorized("No artist profile for this account"));
+    }
+
+    let access = crate::db::team_repository::get_access(user_id)
+        .await
+        .map_err(|e| ApiError::internal("Failed to load team access", e))?;
+
+    match access {
+        Some(access) if access.permissions.iter().any(|p| p == permission.as_str()) => {
+            Ok(access.artist_id)
+        }
+        Some(_) => Err(ApiError::unauthorized(format!(
+            "{} permission required",
+            permission.as_str()
+        ))),
+        None => Err(ApiError::unauthorized("Artist access required")),
+    }
+}
+
+/// Checks the request may act for this artist
+#[cfg(feature = "ssr")]
+pub(crate) async fn authorize_artist_id(
+    token: &str,
+    artist_id: i32,
+    permission: TeamPermission,
+) -> Result<(), ApiError> {
+    if authorize_artist(token, permission).await? != artist_id {
+        return Err(ApiError::unauthorized("Not your account"));
+    }
+    Ok(())
+}
+
+/// Checks the request may act for the artist a booking was made with,
+/// returning that artist. Owners and managers of the shop the artist is
+/// based at may act on all of its bookings (see `shop_user_repository`).
+#[cfg(feature = "ssr")]
+pub(crate) async fn authorize_booking(
+    token: &str,
+    booking_id: i32,
+    permission: TeamPermission,
+) -> Result<i32, ApiError> {
+    let authorized = authorize_artist(token, permission).await;
+    if let Err(ApiError::Internal(_)) = authorized {
+        return authorized;
+    }
+
+    let booking_artist_id = crate::db::repository::get_booking_artist_id(booking_id)
+        .await
+        .map_err(|e| ApiError::internal("Failed to load booking", e))?;
+    let Some(booking_artist_id) = booking_artist_id else {
+        return authorized.and(Err(ApiError::not_found("Booking not found")));
+    };
+    if authorized.as_ref().ok() == Some(&booking_artist_id) {
+        return Ok(booking_artist_id);
+    }
+
+    if let Some((user_id, _)) = crate::server::extract_user_from_token(token) {
+        let manages = crate::db::shop_user_repository::manages_artist(user_id, booking_artist_id)
+            .await
+            .map_err(|e| ApiError::internal("Failed to check shop access", e))?;
+        if manages {
+            return Ok(booking_artist_id);
+        }
+    }
+
+    authorized.and(Err(ApiError::not_found("Booking not found")))
+}
+
+#[cfg(feature = "ssr")]
+fn validate_permissions(permissions: Vec<String>) -> Result<Vec<String>, ServerFnError> {
+    let mut permissions: Vec<String> = permissions
+        .into_iter()
+        .map(|p| p.trim().to_ascii_lowercase())
+        .collect();
+    if let Some(unknown) = permissions
+        .iter()
+        .find(|p| !TEAM_PERMISSIONS.contains(&p.as_str()))
+    {
+        return Err(ServerFnError::new(format!("Unknown permission: {}", unknown)));
+    }
+    permissions.sort();
+    permissions.dedup();
+    Ok(permissions)
+}
+
+/// Invites an assistant by email, returning the link to send them. Artists only.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn invite_team_member(
+    token: String,
+    email: String,
+    permissions: Vec<String>,
+) -> Result<TeamInvitation, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::team_repository;
+
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        let email = email.trim().to_string();
+        if !email.contains('@') {
+            return Err(ServerFnError::new("Enter a valid email".to_string()));
+        }
+        let permissions = validate_permissions(permissions)?;
+        if permissions.is_empty() {
+            return Err(ServerFnError::new(
+                "Choose at least one permission".to_string(),
+            ));
+        }
+
+        let code = crate::auth::generate_token();
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(INVITE_TTL_DAYS);
+        let db_error = |e: sqlx::Error| ServerFnError::new(format!("Failed to invite: {}", e));
+        let member_id = team_repository::insert_invitation(
+            artist_id,
+            &email,
+            &permissions,
+            &crate::auth::hash_token(&code),
+            expires_at,
+        )
+        .await
+        .map_err(db_error)?;
+        let member = team_repository::get_member(artist_id, member_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServerFnError::new("Failed to invite".to_string()))?;
+
+        Ok(TeamInvitation {
+            member,
+            invite_url: format!(
+                "{}/team/join/{}",
+                crate::config::config().app_base_url,
+                code
+            ),
+        })
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The artist's assistants and pending invitations. Artists only.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_team_members(token: String) -> Result<Vec<TeamMember>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::team_repository::list_members(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to get team: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(vec![])
+    }
+}
+
+/// Replaces an assistant's permissions. Artists only.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_team_member_permissions(
+    token: String,
+    member_id: i64,
+    permissions: Vec<String>,
+) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+        let permissions = validate_permissions(permissions)?;
+
+        crate::db::team_repository::update_permissions(artist_id, member_id, &permissions)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to update permissions: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(false)
+    }
+}
+
+/// Removes an assistant or withdraws an invitation. Artists only.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn remove_team_member(token: String, member_id: i64) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let artist_id = crate::server_invoices::artist_id_from_token(&token).await?;
+
+        crate::db::team_repository::revoke_member(artist_id, member_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to remove team member: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(false)
+    }
+}
+
+/// Joins the team of the invitation with this code, which must have been
+/// sent to the signed-in user's email.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token, code), err, level = "info"))]
+pub async fn accept_team_invitation(
+    token: String,
+    code: String,
+) -> Result<TeamAccess, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::db::team_repository;
+
+        let (user_id, user_type) = crate::server::extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+        if user_type != "client" {
+            return Err(ServerFnError::new(
+                "Sign in with a personal account to join a team".to_string(),
+            ));
+        }
+
+        let db_error =
+            |e: sqlx::Error| ServerFnError::new(format!("Failed to accept invitation: {}", e));
+        if team_repository::get_access(user_id)
+            .await
+            .map_err(db_error)?
+            .is_some()
+        {
+            return Err(ServerFnError::new(
+                "You're already on an artist's team".to_string(),
+            ));
+        }
+
+        let email = crate::server_invoices::client_email_from_token(&token).await?;
+        team_repository::accept_invitation(&crate::auth::hash_token(&code), user_id, &email)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| {
+                ServerFnError::new(
+                    "This invitation is invalid, expired, or for a different email".to_string(),
+                )
+            })?;
+
+        team_repository::get_access(user_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServerFnError::new("Failed to accept invitation".to_string()))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
+
+/// The artist account the signed-in user assists on, if any
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "debug"))]
+pub async fn get_team_access(token: String) -> Result<Option<TeamAccess>, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        let (user_id, _user_type) = crate::server::extract_user_from_token(&token)
+            .ok_or_else(|| ServerFnError::new("Invalid or expired token".to_string()))?;
+
+        crate::db::team_repository::get_access(user_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load team access: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(None)
+    }
+}
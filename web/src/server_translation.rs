@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+
+#[cfg(feature = "ssr")]
+use leptos::server_fn::error::ServerFnError;
+
+#[cfg(feature = "ssr")]
+use tracing::instrument;
+
+/// Whether the signed-in artist has booking message translation on
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn get_message_translation(token: String) -> Result<bool, ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::translation_repository::get_auto_translate(artist_id)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to load translation setting: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Ok(false)
+    }
+}
+
+/// Turns booking message translation on or off for the signed-in artist.
+/// Messages already sent keep whatever translation they got.
+#[server]
+#[cfg_attr(feature = "ssr", instrument(skip(token), err, level = "info"))]
+pub async fn update_message_translation(token: String, enabled: bool) -> Result<(), ServerFnError> {
+    #[cfg(feature = "ssr")]
+    {
+        use crate::server_team::{authorize_artist, TeamPermission};
+
+        let artist_id = authorize_artist(&token, TeamPermission::Settings).await?;
+
+        crate::db::translation_repository::set_auto_translate(artist_id, enabled)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Failed to save translation setting: {}", e)))
+    }
+    #[cfg(not(feature = "ssr"))]
+    {
+        Err(ServerFnError::new("Not available on client".to_string()))
+    }
+}
@@ -0,0 +1,87 @@
+//! Short links at `/s/{code}` for artist profiles, shop pages and searches.
+//! [`shorten`] gives a target its link, reusing the one it already has, for
+//! share buttons and for messages like SMS where every character counts.
+//! [`short_link_handler`] records each visit with its referrer and redirects
+//! to the target.
+
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+
+use crate::db::short_link_repository;
+use crate::utils::short_links::{code_from, is_code, referrer_host, target_path};
+
+/// Longest referrer kept
+const MAX_REFERRER_LENGTH: usize = 512;
+
+pub fn short_url(code: &str) -> String {
+    format!("{}/s/{}", crate::config::config().app_base_url, code)
+}
+
+/// The target's code, drawing a new one if it has none yet. The target
+/// must already be checked with `utils::short_links::normalize_target`;
+/// `created_by` is the signed-in user sharing it, if any.
+pub async fn get_or_create_code(
+    target_type: &str,
+    target: &str,
+    created_by: Option<i64>,
+) -> Result<String, sqlx::Error> {
+    if let Some(code) = short_link_repository::get_code(target_type, target).await? {
+        return Ok(code);
+    }
+    // Codes are random, so a taken one just means drawing another
+    loop {
+        let code = code_from(uuid::Uuid::new_v4().as_u128());
+        if let Some(code) =
+            short_link_repository::insert_link(&code, target_type, target, created_by).await?
+        {
+            return Ok(code);
+        }
+    }
+}
+
+/// The absolute short URL for a target, see [`get_or_create_code`]
+pub async fn shorten(
+    target_type: &str,
+    target: &str,
+    created_by: Option<i64>,
+) -> Result<String, sqlx::Error> {
+    Ok(short_url(
+        &get_or_create_code(target_type, target, created_by).await?,
+    ))
+}
+
+/// Redirects to the link's target. The visit is recorded in the background
+/// so the redirect isn't held up.
+pub async fn short_link_handler(Path(code): Path<String>, headers: HeaderMap) -> Response {
+    if !is_code(&code) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let link = match short_link_repository::resolve(&code).await {
+        Ok(Some(link)) => link,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to resolve short link {}: {}", code, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let referrer = headers
+        .get(header::REFERER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.chars().take(MAX_REFERRER_LENGTH).collect::<String>());
+    let destination = target_path(&link.target_type, &link.target);
+    tokio::spawn(async move {
+        let host = referrer.as_deref().and_then(referrer_host);
+        if let Err(e) =
+            short_link_repository::record_click(&code, referrer.as_deref(), host.as_deref()).await
+        {
+            tracing::error!("Failed to record short link click on {}: {}", code, e);
+        }
+    });
+
+    // Temporary, so browsers come back through here and every visit counts
+    Redirect::temporary(&destination).into_response()
+}
@@ -0,0 +1,282 @@
+//! Object storage for user media. `STORAGE_BACKEND=s3` stores objects in an
+//! S3-compatible bucket (AWS S3, Cloudflare R2, MinIO, ...); `local`, the
+//! default, keeps them on local disk under `UPLOAD_DIR/media` and serves
+//! them from `/media/*key`.
+//!
+//! S3 settings, read by [`crate::config`]: `S3_ENDPOINT` (default
+//! `https://s3.us-east-1.amazonaws.com`), `S3_BUCKET`, `S3_PRIVATE_BUCKET`,
+//! `S3_REGION` (default `us-east-1`), `S3_ACCESS_KEY_ID`,
+//! `S3_SECRET_ACCESS_KEY` and optionally `S3_PUBLIC_URL`, the base URL
+//! objects are publicly readable at (defaults to `<endpoint>/<bucket>`).
+//!
+//! Only keys under [`PUBLIC_PREFIXES`] are ever handed out as URLs or served
+//! from `/media`. Objects nobody should be able to fetch by URL, such as
+//! booking archives, license documents and clients' tattoo photos, go
+//! through [`private_storage`] instead: `UPLOAD_DIR/private` locally, which
+//! nothing serves, or the separate `S3_PRIVATE_BUCKET`, which must not be
+//! publicly readable.
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use shared_types::circuit_breaker::BreakerOpen;
+use std::path::PathBuf;
+
+use crate::circuit_breakers::{self, STORAGE};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("storage I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("storage request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("storage returned {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("invalid storage key")]
+    InvalidKey,
+    #[error(transparent)]
+    Unavailable(#[from] BreakerOpen),
+}
+
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub public_url: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Storage {
+    Local(PathBuf),
+    S3(S3Config),
+}
+
+/// Key prefixes of public media, the only objects with a URL
+pub const PUBLIC_PREFIXES: &[&str] = &["portfolio/", "flash/", "instagram/"];
+
+/// Storage that's never served directly, for objects that are read back by
+/// the server only
+#[derive(Debug, Clone)]
+pub struct PrivateStorage(pub Storage);
+
+/// The configured storage backend for public media
+pub fn storage() -> &'static Storage {
+    &crate::config::config().storage
+}
+
+/// The configured storage backend for private objects
+pub fn private_storage() -> &'static PrivateStorage {
+    &crate::config::config().private_storage
+}
+
+fn is_public_key(key: &str) -> bool {
+    valid_key(key) && PUBLIC_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+}
+
+/// Keys are generated by the server, but check anyway so a key can never
+/// escape the local media directory or the bucket prefix
+fn valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with('/')
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+}
+
+impl Storage {
+    /// URL a browser can load the object from, `None` for keys outside the
+    /// public media prefixes
+    pub fn public_url(&self, key: &str) -> Option<String> {
+        if !is_public_key(key) {
+            return None;
+        }
+        Some(match self {
+            Storage::Local(_) => format!("/media/{}", key),
+            Storage::S3(config) => format!("{}/{}", config.public_url, key),
+        })
+    }
+
+    /// Path of a locally stored public object, `None` for remote backends or
+    /// keys outside the public media prefixes
+    pub fn local_path(&self, key: &str) -> Option<PathBuf> {
+        match self {
+            Storage::Local(root) if is_public_key(key) => Some(root.join(key)),
+            _ => None,
+        }
+    }
+
+    pub async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), StorageError> {
+        if !valid_key(key) {
+            return Err(StorageError::InvalidKey);
+        }
+
+        match self {
+            Storage::Local(root) => {
+                let path = root.join(key);
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                // Write then rename so readers never see a half-written file
+                let partial = path.with_extension("partial");
+                tokio::fs::write(&partial, &bytes).await?;
+                tokio::fs::rename(&partial, &path).await?;
+                Ok(())
+            }
+            Storage::S3(config) => {
+                s3_request(config, reqwest::Method::PUT, key, bytes, Some(content_type)).await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Reads an object back
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        if !valid_key(key) {
+            return Err(StorageError::InvalidKey);
+        }
+
+        match self {
+            Storage::Local(root) => Ok(tokio::fs::read(root.join(key)).await?),
+            Storage::S3(config) => {
+                s3_request(config, reqwest::Method::GET, key, Vec::new(), None).await
+            }
+        }
+    }
+
+    /// Deletes an object; deleting one that doesn't exist is not an error
+    pub async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        if !valid_key(key) {
+            return Err(StorageError::InvalidKey);
+        }
+
+        match self {
+            Storage::Local(root) => match tokio::fs::remove_file(root.join(key)).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e.into()),
+            },
+            Storage::S3(config) => {
+                s3_request(config, reqwest::Method::DELETE, key, Vec::new(), None).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl PrivateStorage {
+    pub async fn put(
+        &self,
+        key: &str,
+        bytes: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), StorageError> {
+        self.0.put(key, bytes, content_type).await
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.0.get(key).await
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        self.0.delete(key).await
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Sends a path-style request signed with AWS Signature Version 4, returning
+/// the response body
+async fn s3_request(
+    config: &S3Config,
+    method: reqwest::Method,
+    key: &str,
+    body: Vec<u8>,
+    content_type: Option<&str>,
+) -> Result<Vec<u8>, StorageError> {
+    let url = reqwest::Url::parse(&format!("{}/{}/{}", config.endpoint, config.bucket, key))
+        .map_err(|_| StorageError::InvalidKey)?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(StorageError::InvalidKey),
+    };
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date_stamp = now.format("%Y%m%d").to_string();
+    let payload_hash = hex(&Sha256::digest(&body));
+
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+        method.as_str(),
+        url.path(),
+        host,
+        payload_hash,
+        amz_date,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date_stamp, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let date_key = hmac_sha256(
+        format!("AWS4{}", config.secret_access_key).as_bytes(),
+        &date_stamp,
+    );
+    let region_key = hmac_sha256(&date_key, &config.region);
+    let service_key = hmac_sha256(&region_key, "s3");
+    let signing_key = hmac_sha256(&service_key, "aws4_request");
+    let signature = hex(&hmac_sha256(&signing_key, &string_to_sign));
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+        config.access_key_id, scope, signature
+    );
+
+    let is_delete = method == reqwest::Method::DELETE;
+    let mut request = reqwest::Client::new()
+        .request(method, url)
+        .header("x-amz-date", amz_date)
+        .header("x-amz-content-sha256", payload_hash)
+        .header(reqwest::header::AUTHORIZATION, authorization);
+    if let Some(content_type) = content_type {
+        request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+    }
+
+    let response = circuit_breakers::send(&STORAGE, request.body(body)).await?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response.bytes().await?.to_vec());
+    }
+    if is_delete && status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+
+    Err(StorageError::Status {
+        status: status.as_u16(),
+        body: response.text().await.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,251 @@
+//! Clients' own tattoo photos ("my tattoos").
+//!
+//! `POST /api/my-tattoos` takes a `multipart/form-data` body with a `file`
+//! part, an optional `caption` and an optional `booking_id` naming one of
+//! the client's completed bookings, which credits that booking's artist.
+//! Authentication and responses work as for portfolio uploads (see
+//! [`crate::portfolio_uploads`]), redirecting form posts to `/my-tattoos`.
+//! New photos are private; sharing is chosen afterwards.
+//!
+//! Photos are kept in private storage whether shared or not, so un-sharing
+//! one takes effect at once. Every photo URL is a [`photo_link`], a signed
+//! link to `GET /api/tattoo-photos/:id/:variant` that stops working after
+//! [`PHOTO_LINK_MINUTES`], handed only to those allowed to see the photo.
+
+use axum::extract::{Multipart, Path, Query};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Json;
+
+use crate::db::tattoo_photo_repository::{self, NewTattooPhoto};
+use crate::image_processing;
+use crate::portfolio_uploads::{extension, serve_private_media, wants_json, UploadError};
+use crate::storage::private_storage;
+
+/// Largest photo accepted
+pub const MAX_TATTOO_PHOTO_BYTES: usize = 25 * 1024 * 1024;
+/// Photos a client may keep at once
+const MAX_TATTOO_PHOTOS: i64 = 100;
+const GALLERY_PATH: &str = "/my-tattoos";
+/// How long a photo link works for
+pub const PHOTO_LINK_MINUTES: i64 = 60;
+
+fn photo_link_message(photo_id: i64, variant: &str, expires: i64) -> String {
+    format!("tattoo-photo:{}:{}:{}", photo_id, variant, expires)
+}
+
+/// A short-lived link to a photo, or to its thumbnail
+pub fn photo_link(photo_id: i64, thumbnail: bool) -> String {
+    let variant = if thumbnail { "thumbnail" } else { "image" };
+    let expires = (chrono::Utc::now() + chrono::Duration::minutes(PHOTO_LINK_MINUTES)).timestamp();
+    format!(
+        "/api/tattoo-photos/{}/{}?expires={}&signature={}",
+        photo_id,
+        variant,
+        expires,
+        crate::auth::sign(&photo_link_message(photo_id, variant, expires))
+    )
+}
+
+async fn store_photo(
+    headers: &HeaderMap,
+    mut multipart: Multipart,
+) -> Result<crate::db::entities::ClientTattooPhoto, UploadError> {
+    let mut file: Option<Vec<u8>> = None;
+    let mut caption: Option<String> = None;
+    let mut booking_id: Option<String> = None;
+    let mut form_token: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| UploadError::new(StatusCode::BAD_REQUEST, "Malformed upload"))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "file" => {
+                let bytes = field.bytes().await.map_err(|_| {
+                    UploadError::new(StatusCode::PAYLOAD_TOO_LARGE, "Photo too large")
+                })?;
+                file = Some(bytes.to_vec());
+            }
+            "caption" => caption = field.text().await.ok(),
+            "booking_id" => booking_id = field.text().await.ok(),
+            "token" => form_token = field.text().await.ok(),
+            _ => {}
+        }
+    }
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or(form_token)
+        .ok_or_else(|| UploadError::new(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+    let (user_id, _) = crate::server::extract_user_from_token(&token)
+        .ok_or_else(|| UploadError::new(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+
+    let file = file
+        .filter(|bytes| !bytes.is_empty())
+        .ok_or_else(|| UploadError::new(StatusCode::BAD_REQUEST, "No photo selected"))?;
+    if file.len() > MAX_TATTOO_PHOTO_BYTES {
+        return Err(UploadError::new(StatusCode::PAYLOAD_TOO_LARGE, "Photo too large"));
+    }
+
+    let failed = |context: &str, e: sqlx::Error| {
+        tracing::error!("Failed to {}: {}", context, e);
+        UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed")
+    };
+
+    // Only a completed booking of the client's can credit an artist
+    let booking_id = match booking_id.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(id) => Some(id.parse::<i32>().map_err(|_| {
+            UploadError::new(StatusCode::BAD_REQUEST, "Unknown booking")
+        })?),
+    };
+    let artist_id = match booking_id {
+        Some(booking_id) => {
+            let email = crate::server_invoices::client_email_from_token(&token)
+                .await
+                .map_err(|_| UploadError::new(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+            let artist_id =
+                tattoo_photo_repository::get_completed_booking_artist(&email, booking_id)
+                    .await
+                    .map_err(|e| failed("look up booking", e))?
+                    .ok_or_else(|| UploadError::new(StatusCode::BAD_REQUEST, "Unknown booking"))?;
+            Some(artist_id)
+        }
+        None => None,
+    };
+
+    let existing = tattoo_photo_repository::count_photos(user_id)
+        .await
+        .map_err(|e| failed("count tattoo photos", e))?;
+    if existing >= MAX_TATTOO_PHOTOS {
+        return Err(UploadError(
+            StatusCode::CONFLICT,
+            format!("Galleries are limited to {} photos", MAX_TATTOO_PHOTOS),
+        ));
+    }
+
+    // Decoding and re-encoding are CPU-bound
+    let processed = tokio::task::spawn_blocking(move || image_processing::process(&file))
+        .await
+        .map_err(|_| UploadError::new(StatusCode::INTERNAL_SERVER_ERROR, "Upload failed"))??;
+    let content_type = processed.content_type;
+
+    let id = uuid::Uuid::new_v4();
+    let storage_key = format!("tattoos/{}/{}.{}", user_id, id, extension(content_type));
+    let thumbnail_key = format!("tattoos/{}/{}_thumb.jpg", user_id, id);
+
+    let stored = async {
+        private_storage()
+            .put(&storage_key, processed.bytes, content_type)
+            .await?;
+        private_storage()
+            .put(&thumbnail_key, processed.thumbnail, "image/jpeg")
+            .await
+    }
+    .await;
+    if let Err(e) = stored {
+        tracing::error!("Failed to store tattoo photo: {}", e);
+        let _ = private_storage().delete(&storage_key).await;
+        return Err(UploadError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store photo",
+        ));
+    }
+
+    let new_photo = NewTattooPhoto {
+        user_id,
+        booking_id,
+        artist_id,
+        storage_key,
+        thumbnail_key,
+        content_type: content_type.to_string(),
+        width: processed.width as i32,
+        height: processed.height as i32,
+        caption: caption
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty()),
+    };
+
+    let saved = async {
+        let photo_id = tattoo_photo_repository::insert_photo(&new_photo).await?;
+        tattoo_photo_repository::get_photo(user_id, photo_id).await
+    }
+    .await;
+
+    match saved {
+        Ok(Some(photo)) => Ok(photo),
+        other => {
+            if let Err(e) = other {
+                tracing::error!("Failed to record tattoo photo: {}", e);
+            }
+            let _ = private_storage().delete(&new_photo.storage_key).await;
+            let _ = private_storage().delete(&new_photo.thumbnail_key).await;
+            Err(UploadError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to save photo",
+            ))
+        }
+    }
+}
+
+/// POST /api/my-tattoos
+pub async fn upload_tattoo_photo(headers: HeaderMap, multipart: Multipart) -> Response {
+    let json = wants_json(&headers);
+
+    match store_photo(&headers, multipart).await {
+        Ok(photo) if json => (StatusCode::CREATED, Json(photo)).into_response(),
+        Ok(_) => Redirect::to(&format!("{}?photo=uploaded", GALLERY_PATH)).into_response(),
+        Err(UploadError(status, message)) if json => (status, message).into_response(),
+        Err(UploadError(_, message)) => Redirect::to(&format!(
+            "{}?photo_error={}",
+            GALLERY_PATH,
+            urlencoding::encode(&message)
+        ))
+        .into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct PhotoLinkParams {
+    expires: i64,
+    signature: String,
+}
+
+/// GET /api/tattoo-photos/:id/:variant?expires=...&signature=... — serves
+/// the photo (`image`) or its thumbnail (`thumbnail`) to whoever holds a
+/// link from [`photo_link`].
+pub async fn tattoo_photo_handler(
+    Path((photo_id, variant)): Path<(i64, String)>,
+    Query(params): Query<PhotoLinkParams>,
+) -> Response {
+    if params.expires < chrono::Utc::now().timestamp()
+        || !crate::auth::verify_signature(
+            &photo_link_message(photo_id, &variant, params.expires),
+            &params.signature,
+        )
+    {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    match tattoo_photo_repository::get_photo_keys(photo_id).await {
+        Ok(Some((storage_key, thumbnail_key))) => {
+            let key = if variant == "thumbnail" {
+                thumbnail_key
+            } else {
+                storage_key
+            };
+            serve_private_media(&key).await
+        }
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            tracing::error!("Failed to load tattoo photo {}: {}", photo_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Machine translation for booking messages, for artists who turn on
+//! auto-translate. The provider is picked with `TRANSLATE_PROVIDER`:
+//!
+//! - `libretranslate`: a LibreTranslate server at `TRANSLATE_API_URL`, with
+//!   `TRANSLATE_API_KEY` if it needs one
+//! - `deepl`: DeepL with `TRANSLATE_API_KEY` as the auth key;
+//!   `TRANSLATE_API_URL` defaults to the free API
+//!
+//! Without a provider nothing is translated and messages are stored as
+//! written. Translation never holds a message up: if the provider fails the
+//! message is stored untranslated and the failure logged.
+
+use serde::{Deserialize, Serialize};
+use shared_types::circuit_breaker::BreakerOpen;
+
+use crate::circuit_breakers::{self, TRANSLATE};
+use crate::utils::translation::{normalize_language, ARTIST_LANGUAGE};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TranslateError {
+    #[error("translation request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("translation provider returned {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("translation provider returned no translation")]
+    Empty,
+    #[error(transparent)]
+    Unavailable(#[from] BreakerOpen),
+}
+
+/// Built from the environment by [`crate::config`]
+#[derive(Debug, Clone)]
+pub enum Provider {
+    Disabled,
+    LibreTranslate {
+        url: String,
+        api_key: Option<String>,
+    },
+    DeepL {
+        url: String,
+        auth_key: String,
+    },
+}
+
+fn provider() -> &'static Provider {
+    &crate::config::config().translate_provider
+}
+
+/// A message in another language, and the language it was written in
+#[derive(Debug, Clone)]
+pub struct Translation {
+    pub text: String,
+    pub source_language: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LibreTranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct LibreTranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+    #[serde(rename = "detectedLanguage")]
+    detected_language: Option<LibreTranslateDetected>,
+}
+
+#[derive(Deserialize)]
+struct LibreTranslateDetected {
+    language: String,
+}
+
+#[derive(Serialize)]
+struct DeepLRequest<'a> {
+    text: [&'a str; 1],
+    target_lang: String,
+}
+
+#[derive(Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeepLTranslation {
+    detected_source_language: Option<String>,
+    text: String,
+}
+
+/// DeepL wants a regional variant for English and Portuguese targets
+fn deepl_target(language: &str) -> String {
+    match language {
+        "en" => "EN-US".to_string(),
+        "pt" => "PT-BR".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+async fn checked(response: reqwest::Response) -> Result<reqwest::Response, TranslateError> {
+    if !response.status().is_success() {
+        return Err(TranslateError::Status {
+            status: response.status().as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        });
+    }
+    Ok(response)
+}
+
+/// Translates `text` into `target` (ISO 639-1), detecting the language it's
+/// in. `None` when no provider is configured.
+pub async fn translate(text: &str, target: &str) -> Result<Option<Translation>, TranslateError> {
+    let client = reqwest::Client::new();
+
+    let translation = match provider() {
+        Provider::Disabled => return Ok(None),
+        Provider::LibreTranslate { url, api_key } => {
+            let request = client
+                .post(format!("{}/translate", url))
+                .json(&LibreTranslateRequest {
+                    q: text,
+                    source: "auto",
+                    target,
+                    format: "text",
+                    api_key: api_key.as_deref(),
+                });
+            let response = circuit_breakers::send(&TRANSLATE, request).await?;
+            let body: LibreTranslateResponse = checked(response).await?.json().await?;
+            Translation {
+                text: body.translated_text,
+                source_language: body.detected_language.map(|detected| detected.language),
+            }
+        }
+        Provider::DeepL { url, auth_key } => {
+            let request = client
+                .post(format!("{}/v2/translate", url))
+                .header("Authorization", format!("DeepL-Auth-Key {}", auth_key))
+                .json(&DeepLRequest {
+                    text: [text],
+                    target_lang: deepl_target(target),
+                });
+            let response = circuit_breakers::send(&TRANSLATE, request).await?;
+            let body: DeepLResponse = checked(response).await?.json().await?;
+            let translation = body
+                .translations
+                .into_iter()
+                .next()
+                .ok_or(TranslateError::Empty)?;
+            Translation {
+                text: translation.text,
+                source_language: translation.detected_source_language,
+            }
+        }
+    };
+
+    Ok(Some(Translation {
+        source_language: translation
+            .source_language
+            .as_deref()
+            .and_then(normalize_language),
+        ..translation
+    }))
+}
+
+/// What's stored alongside a new booking message
+#[derive(Debug, Clone, Default)]
+pub struct MessageTranslation {
+    pub language: Option<String>,
+    pub translated_message: Option<String>,
+    pub translated_language: Option<String>,
+}
+
+/// Translates a new message on a booking for the other side of the thread,
+/// if the booking's artist has auto-translate on: client messages into the
+/// artist's language, artist messages into the language the client last
+/// wrote in. Anything that goes wrong leaves the message untranslated.
+pub async fn translate_booking_message(
+    booking_id: i32,
+    sender_type: &str,
+    message: &str,
+) -> MessageTranslation {
+    use crate::db::translation_repository;
+
+    match translation_repository::is_enabled_for_booking(booking_id).await {
+        Ok(true) => {}
+        Ok(false) => return MessageTranslation::default(),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to check auto-translate for booking {}: {}",
+                booking_id,
+                e
+            );
+            return MessageTranslation::default();
+        }
+    }
+
+    let target = if sender_type == "client" {
+        ARTIST_LANGUAGE.to_string()
+    } else {
+        match translation_repository::get_client_language(booking_id).await {
+            Ok(Some(language)) if language != ARTIST_LANGUAGE => language,
+            Ok(_) => return MessageTranslation::default(),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to get client language for booking {}: {}",
+                    booking_id,
+                    e
+                );
+                return MessageTranslation::default();
+            }
+        }
+    };
+
+    let translation = match translate(message, &target).await {
+        Ok(Some(translation)) => translation,
+        Ok(None) => return MessageTranslation::default(),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to translate message on booking {}: {}",
+                booking_id,
+                e
+            );
+            return MessageTranslation::default();
+        }
+    };
+
+    // Already in the reader's language: just remember what it was written in
+    if translation.source_language.as_deref() == Some(target.as_str())
+        || translation.text.trim() == message.trim()
+    {
+        return MessageTranslation {
+            language: translation.source_language,
+            ..Default::default()
+        };
+    }
+
+    MessageTranslation {
+        language: translation.source_language,
+        translated_message: Some(translation.text),
+        translated_language: Some(target),
+    }
+}
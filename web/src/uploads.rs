@@ -0,0 +1,513 @@
+//! Resumable uploads for reference and portfolio images, following the tus 1.0
+//! core protocol plus its creation, checksum and termination extensions:
+//!
+//! - `POST /api/uploads` with `Upload-Length` and `Upload-Metadata` creates an upload
+//! - `HEAD /api/uploads/:id` reports how many bytes the server has (`Upload-Offset`)
+//! - `PATCH /api/uploads/:id` appends a chunk at `Upload-Offset`, optionally
+//!   verified with `Upload-Checksum: sha256 <base64 digest>`
+//! - `DELETE /api/uploads/:id` abandons an upload
+//!
+//! Reference images are personal photos, so creating one requires the
+//! `consent` metadata value to be `true`. Once attached to a booking they are
+//! kept until the booking has been closed for the retention period, then
+//! deleted by [`purge_expired_attachments`].
+//!
+//! All requests authenticate with `Authorization: Bearer <token>`. Chunks are
+//! appended to a partial file; once the last byte arrives the whole file is
+//! hashed, checked against the `sha256` metadata value if one was sent, and
+//! cleaned up by [`crate::image_processing`] (HEIC and WebP transcoded, EXIF
+//! and its location stripped) before it's stored. A file that isn't a
+//! readable image ends the upload as `rejected`.
+
+use axum::body::Bytes;
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+use crate::db::upload_repository::{self, Upload};
+use crate::image_processing::{self, ImageError};
+
+const TUS_VERSION: &str = "1.0.0";
+const MAX_UPLOAD_BYTES: i64 = 25 * 1024 * 1024;
+/// Largest single PATCH body accepted; clients on poor connections should send less
+pub const MAX_CHUNK_BYTES: usize = 8 * 1024 * 1024;
+const ALLOWED_CONTENT_TYPES: &[&str] = &["image/jpeg", "image/png", "image/webp", "image/heic"];
+/// In-progress uploads idle for longer than this are expired by the cleanup job
+pub const ABANDONED_AFTER_HOURS: i32 = 24;
+/// Attachments purged per run, so a backlog is worked off over several runs
+const PURGE_BATCH_SIZE: i64 = 500;
+
+pub(crate) fn upload_dir() -> PathBuf {
+    crate::config::config().upload_dir.clone()
+}
+
+fn partial_path(id: &str) -> PathBuf {
+    upload_dir().join("partial").join(id)
+}
+
+fn complete_path(upload: &Upload, content_type: &str) -> PathBuf {
+    upload_dir().join(&upload.kind).join(format!(
+        "{}.{}",
+        upload.id,
+        crate::portfolio_uploads::extension(content_type)
+    ))
+}
+
+fn tus_response(status: StatusCode) -> Response {
+    let mut response = status.into_response();
+    response
+        .headers_mut()
+        .insert("Tus-Resumable", HeaderValue::from_static(TUS_VERSION));
+    response
+}
+
+fn tus_error(status: StatusCode, message: &str) -> Response {
+    let mut response = (status, message.to_string()).into_response();
+    response
+        .headers_mut()
+        .insert("Tus-Resumable", HeaderValue::from_static(TUS_VERSION));
+    response
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Resolves the caller from the bearer token as (user_id, user_type)
+fn authenticate(headers: &HeaderMap) -> Option<(i64, String)> {
+    let token = header_str(headers, header::AUTHORIZATION.as_str())?.strip_prefix("Bearer ")?;
+    crate::server::extract_user_from_token(token)
+}
+
+/// Parses tus `Upload-Metadata`: comma-separated `key base64value` pairs
+fn parse_metadata(raw: &str) -> Vec<(String, String)> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let mut parts = pair.trim().splitn(2, ' ');
+            let key = parts.next()?.to_string();
+            let value = parts
+                .next()
+                .and_then(|v| BASE64.decode(v).ok())
+                .and_then(|v| String::from_utf8(v).ok())
+                .unwrap_or_default();
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Loads the upload and checks it belongs to the caller
+async fn owned_upload(id: &str, headers: &HeaderMap) -> Result<Upload, Response> {
+    let (user_id, _) = authenticate(headers)
+        .ok_or_else(|| tus_error(StatusCode::UNAUTHORIZED, "Invalid token"))?;
+
+    match upload_repository::get_upload(id).await {
+        Ok(Some(upload)) if upload.user_id == user_id => Ok(upload),
+        Ok(_) => Err(tus_response(StatusCode::NOT_FOUND)),
+        Err(e) => {
+            tracing::error!("Failed to load upload {}: {}", id, e);
+            Err(tus_response(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+    }
+}
+
+/// OPTIONS /api/uploads
+pub async fn upload_options() -> Response {
+    let mut response = tus_response(StatusCode::NO_CONTENT);
+    let headers = response.headers_mut();
+    headers.insert("Tus-Version", HeaderValue::from_static(TUS_VERSION));
+    headers.insert(
+        "Tus-Extension",
+        HeaderValue::from_static("creation,checksum,termination"),
+    );
+    headers.insert("Tus-Checksum-Algorithm", HeaderValue::from_static("sha256"));
+    headers.insert("Tus-Max-Size", HeaderValue::from(MAX_UPLOAD_BYTES));
+    response
+}
+
+/// POST /api/uploads
+pub async fn create_upload(headers: HeaderMap) -> Response {
+    let Some((user_id, user_type)) = authenticate(&headers) else {
+        return tus_error(StatusCode::UNAUTHORIZED, "Invalid token");
+    };
+
+    let Some(total_size) =
+        header_str(&headers, "Upload-Length").and_then(|v| v.parse::<i64>().ok())
+    else {
+        return tus_error(StatusCode::BAD_REQUEST, "Upload-Length is required");
+    };
+    if total_size <= 0 || total_size > MAX_UPLOAD_BYTES {
+        return tus_error(StatusCode::PAYLOAD_TOO_LARGE, "Upload too large");
+    }
+
+    let metadata = parse_metadata(header_str(&headers, "Upload-Metadata").unwrap_or(""));
+    let meta = |key: &str| {
+        metadata
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .filter(|v| !v.is_empty())
+    };
+
+    let kind = meta("kind").unwrap_or_else(|| "reference".to_string());
+    match kind.as_str() {
+        "reference" => {}
+        "portfolio" if user_type == "artist" => {}
+        "portfolio" => return tus_error(StatusCode::FORBIDDEN, "Artist access required"),
+        _ => return tus_error(StatusCode::BAD_REQUEST, "Unknown upload kind"),
+    }
+
+    let consent_given = meta("consent").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    if kind == "reference" && !consent_given {
+        return tus_error(
+            StatusCode::BAD_REQUEST,
+            "Consent to store reference images is required",
+        );
+    }
+
+    let content_type = meta("filetype");
+    if !content_type
+        .as_deref()
+        .is_some_and(|ct| ALLOWED_CONTENT_TYPES.contains(&ct))
+    {
+        return tus_error(StatusCode::UNSUPPORTED_MEDIA_TYPE, "Unsupported file type");
+    }
+
+    let upload = Upload {
+        id: uuid::Uuid::new_v4().to_string(),
+        user_id,
+        kind,
+        filename: meta("filename"),
+        content_type,
+        total_size,
+        upload_offset: 0,
+        expected_sha256: meta("sha256").map(|v| v.to_lowercase()),
+        sha256: None,
+        storage_path: None,
+        status: "in_progress".to_string(),
+        consent_given,
+    };
+
+    let partial = partial_path(&upload.id);
+    if let Some(parent) = partial.parent() {
+        if let Err(e) = tokio::fs::create_dir_all(parent).await {
+            tracing::error!("Failed to create upload directory: {}", e);
+            return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    if let Err(e) = tokio::fs::File::create(&partial).await {
+        tracing::error!("Failed to create partial upload file: {}", e);
+        return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    if let Err(e) = upload_repository::create_upload(&upload).await {
+        tracing::error!("Failed to record upload: {}", e);
+        let _ = tokio::fs::remove_file(&partial).await;
+        return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    let mut response = tus_response(StatusCode::CREATED);
+    if let Ok(location) = HeaderValue::from_str(&format!("/api/uploads/{}", upload.id)) {
+        response.headers_mut().insert(header::LOCATION, location);
+    }
+    response
+}
+
+/// HEAD /api/uploads/:id
+pub async fn upload_status(Path(id): Path<String>, headers: HeaderMap) -> Response {
+    let upload = match owned_upload(&id, &headers).await {
+        Ok(upload) => upload,
+        Err(response) => return response,
+    };
+    if upload.status == "aborted" || upload.status == "expired" {
+        return tus_response(StatusCode::GONE);
+    }
+
+    let mut response = tus_response(StatusCode::OK);
+    let response_headers = response.headers_mut();
+    response_headers.insert("Upload-Offset", HeaderValue::from(upload.upload_offset));
+    response_headers.insert("Upload-Length", HeaderValue::from(upload.total_size));
+    response_headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response
+}
+
+/// PATCH /api/uploads/:id
+pub async fn append_chunk(Path(id): Path<String>, headers: HeaderMap, body: Bytes) -> Response {
+    use tokio::io::AsyncWriteExt;
+
+    // Ownership check before taking the row lock
+    if let Err(response) = owned_upload(&id, &headers).await {
+        return response;
+    }
+
+    if header_str(&headers, header::CONTENT_TYPE.as_str())
+        != Some("application/offset+octet-stream")
+    {
+        return tus_error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Content-Type must be application/offset+octet-stream",
+        );
+    }
+    let Some(client_offset) =
+        header_str(&headers, "Upload-Offset").and_then(|v| v.parse::<i64>().ok())
+    else {
+        return tus_error(StatusCode::BAD_REQUEST, "Upload-Offset is required");
+    };
+
+    // Per-chunk integrity check (tus checksum extension)
+    if let Some(checksum) = header_str(&headers, "Upload-Checksum") {
+        let Some(expected) = checksum.strip_prefix("sha256 ") else {
+            return tus_error(StatusCode::BAD_REQUEST, "Unsupported checksum algorithm");
+        };
+        if BASE64.encode(Sha256::digest(&body)) != expected.trim() {
+            // 460 Checksum Mismatch, as defined by the tus checksum extension
+            return tus_error(
+                StatusCode::from_u16(460).unwrap_or(StatusCode::BAD_REQUEST),
+                "Checksum mismatch",
+            );
+        }
+    }
+
+    let pool = crate::db::pool::get_pool();
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            tracing::error!("Failed to start upload transaction: {}", e);
+            return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let upload = match upload_repository::lock_upload(&mut tx, &id).await {
+        Ok(Some(upload)) => upload,
+        Ok(None) => return tus_response(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to lock upload {}: {}", id, e);
+            return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if upload.status != "in_progress" {
+        return tus_response(StatusCode::GONE);
+    }
+    if client_offset != upload.upload_offset {
+        return tus_error(StatusCode::CONFLICT, "Upload-Offset does not match");
+    }
+    let new_offset = upload.upload_offset + body.len() as i64;
+    if new_offset > upload.total_size {
+        return tus_error(StatusCode::BAD_REQUEST, "Chunk exceeds Upload-Length");
+    }
+
+    let partial = partial_path(&id);
+    let write_result = async {
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&partial)
+            .await?;
+        // Truncate anything left over from an interrupted write before appending
+        file.set_len(upload.upload_offset as u64).await?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&partial)
+            .await?;
+        file.write_all(&body).await?;
+        file.sync_data().await
+    }
+    .await;
+    if let Err(e) = write_result {
+        tracing::error!("Failed to write chunk for upload {}: {}", id, e);
+        return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    let mut verified = true;
+    if new_offset == upload.total_size {
+        match finish_upload(&mut tx, &upload).await {
+            Ok(Finished::Stored) => {}
+            Ok(Finished::ChecksumMismatch) => verified = false,
+            Ok(Finished::Rejected(e)) => return reject(tx, &id, e).await,
+            Err(response) => return response,
+        }
+    }
+    // A file that fails verification is discarded and the client starts over
+    let new_offset = if verified { new_offset } else { 0 };
+
+    let saved = async {
+        upload_repository::set_upload_offset(&mut tx, &id, new_offset).await?;
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = saved {
+        tracing::error!("Failed to save offset for upload {}: {}", id, e);
+        return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    if !verified {
+        return tus_error(
+            StatusCode::from_u16(460).unwrap_or(StatusCode::BAD_REQUEST),
+            "File checksum mismatch, upload restarted",
+        );
+    }
+
+    let mut response = tus_response(StatusCode::NO_CONTENT);
+    response
+        .headers_mut()
+        .insert("Upload-Offset", HeaderValue::from(new_offset));
+    response
+}
+
+enum Finished {
+    Stored,
+    /// The file was truncated for the client to send again
+    ChecksumMismatch,
+    /// The file isn't an image we can store
+    Rejected(ImageError),
+}
+
+/// Ends an upload whose file isn't a usable image and tells the client why.
+/// Sending the same file again wouldn't help, so it's not restarted.
+async fn reject(
+    mut tx: sqlx::Transaction<'_, sqlx::Postgres>,
+    id: &str,
+    error: ImageError,
+) -> Response {
+    let saved = async {
+        upload_repository::reject_upload(&mut tx, id, &error.to_string()).await?;
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = saved {
+        tracing::error!("Failed to reject upload {}: {}", id, e);
+        return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    let _ = tokio::fs::remove_file(partial_path(id)).await;
+
+    tus_error(error.status(), &error.to_string())
+}
+
+/// Hashes the assembled file, verifies it against the client's checksum,
+/// cleans the image up and stores it in place of the partial file.
+async fn finish_upload(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    upload: &Upload,
+) -> Result<Finished, Response> {
+    let partial = partial_path(&upload.id);
+    let bytes = tokio::fs::read(&partial).await.map_err(|e| {
+        tracing::error!("Failed to read upload {}: {}", upload.id, e);
+        tus_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    let digest: String = Sha256::digest(&bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    if upload
+        .expected_sha256
+        .as_deref()
+        .is_some_and(|expected| expected != digest)
+    {
+        let _ = tokio::fs::File::create(&partial).await;
+        return Ok(Finished::ChecksumMismatch);
+    }
+
+    // Decoding and re-encoding are CPU-bound
+    let processed = tokio::task::spawn_blocking(move || image_processing::process(&bytes))
+        .await
+        .map_err(|_| tus_response(StatusCode::INTERNAL_SERVER_ERROR))?;
+    let processed = match processed {
+        Ok(processed) => processed,
+        Err(e) => return Ok(Finished::Rejected(e)),
+    };
+
+    let destination = complete_path(upload, processed.content_type);
+    let stored = async {
+        if let Some(parent) = destination.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&destination, &processed.bytes).await?;
+        tokio::fs::remove_file(&partial).await
+    }
+    .await;
+    if let Err(e) = stored {
+        tracing::error!("Failed to store upload {}: {}", upload.id, e);
+        return Err(tus_response(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    upload_repository::complete_upload(
+        tx,
+        &upload.id,
+        &digest,
+        &destination.to_string_lossy(),
+        processed.content_type,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to mark upload {} complete: {}", upload.id, e);
+        tus_response(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    Ok(Finished::Stored)
+}
+
+/// DELETE /api/uploads/:id
+pub async fn abort_upload(Path(id): Path<String>, headers: HeaderMap) -> Response {
+    let upload = match owned_upload(&id, &headers).await {
+        Ok(upload) => upload,
+        Err(response) => return response,
+    };
+    if upload.status != "in_progress" {
+        return tus_response(StatusCode::GONE);
+    }
+
+    if let Err(e) = upload_repository::set_upload_status(&id, "aborted").await {
+        tracing::error!("Failed to abort upload {}: {}", id, e);
+        return tus_response(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    let _ = tokio::fs::remove_file(partial_path(&id)).await;
+
+    tus_response(StatusCode::NO_CONTENT)
+}
+
+/// Expires uploads nobody has touched in a while and deletes their partial files.
+/// Run periodically from the server's background task.
+pub async fn cleanup_abandoned_uploads() -> Result<usize, sqlx::Error> {
+    let expired = upload_repository::expire_abandoned_uploads(ABANDONED_AFTER_HOURS).await?;
+    for id in &expired {
+        let _ = tokio::fs::remove_file(partial_path(id)).await;
+    }
+    Ok(expired.len())
+}
+
+/// Deletes reference images of bookings closed longer than the retention
+/// period, recording each deletion in `attachment_deletions`. Run
+/// periodically from the server's background task.
+pub async fn purge_expired_attachments() -> Result<usize, sqlx::Error> {
+    let expired = upload_repository::get_expired_attachments(
+        crate::config::config().attachment_retention_days,
+        PURGE_BATCH_SIZE,
+    )
+    .await?;
+
+    let mut purged = 0;
+    for attachment in &expired {
+        if let Some(path) = &attachment.storage_path {
+            match tokio::fs::remove_file(path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    // Leave the row as is so the next run retries
+                    tracing::error!(
+                        "Failed to delete attachment {}: {}",
+                        attachment.upload_id,
+                        e
+                    );
+                    continue;
+                }
+            }
+        }
+        upload_repository::record_attachment_purge(attachment, "retention").await?;
+        purged += 1;
+    }
+    Ok(purged)
+}
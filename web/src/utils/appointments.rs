@@ -0,0 +1,29 @@
+//! Choices offered when artists set how their days are split into
+//! appointments, and when clients say how big their tattoo is. The slot
+//! arithmetic itself is `availability::SlotSettings`.
+
+/// Tattoo sizes a client can pick, with the size in inches sent with the
+/// request
+pub const TATTOO_SIZES: &[(&str, f64)] = &[
+    ("Small (up to 3\")", 3.0),
+    ("Medium (3-6\")", 6.0),
+    ("Large (6-10\")", 10.0),
+    ("Extra large (over 10\")", 12.0),
+];
+
+/// Standard session lengths, in minutes
+pub const SESSION_LENGTHS: &[i32] = &[30, 60, 90, 120, 180, 240];
+/// Time between offered start times, in minutes
+pub const SLOT_INTERVALS: &[i32] = &[15, 30, 60, 120];
+/// Buffer kept around bookings, in minutes
+pub const BUFFER_LENGTHS: &[i32] = &[0, 10, 15, 30, 45, 60];
+
+/// e.g. "45 min", "1 hr", "1 hr 30 min"
+pub fn minutes_label(minutes: i32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "None".to_string(),
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} hr", h),
+        (h, m) => format!("{} hr {} min", h, m),
+    }
+}
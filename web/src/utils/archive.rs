@@ -0,0 +1,31 @@
+//! Restoring archived bookings. Completed bookings are moved to cold storage
+//! a few years after the appointment (see the `archive` module); an artist
+//! who needs one back asks with a reason and an admin approves it.
+
+pub const RESTORE_STATUSES: [&str; 4] = ["pending", "failed", "restored", "rejected"];
+
+pub const MAX_REASON_CHARS: usize = 500;
+
+/// The reason for a restore request as stored
+pub fn normalize_reason(reason: &str) -> Result<String, String> {
+    let reason = reason.split_whitespace().collect::<Vec<_>>().join(" ");
+    if reason.is_empty() {
+        return Err("Say why you need this booking back".to_string());
+    }
+    if reason.chars().count() > MAX_REASON_CHARS {
+        return Err(format!(
+            "Keep the reason under {} characters",
+            MAX_REASON_CHARS
+        ));
+    }
+    Ok(reason)
+}
+
+pub fn restore_status_label(status: &str) -> &'static str {
+    match status {
+        "failed" => "Failed",
+        "restored" => "Restored",
+        "rejected" => "Rejected",
+        _ => "Awaiting approval",
+    }
+}
@@ -0,0 +1,126 @@
+//! Rules for public questions on artist profiles: how long questions and
+//! answers may be and which questions are held for moderation. Questions
+//! are stored by `db::artist_question_repository`.
+
+pub const QUESTION_STATUSES: [&str; 3] = ["approved", "held", "rejected"];
+
+pub const MAX_QUESTION_CHARS: usize = 500;
+pub const MAX_ANSWER_CHARS: usize = 2000;
+const MIN_QUESTION_CHARS: usize = 10;
+const MAX_NAME_CHARS: usize = 80;
+
+/// Words that only show up in spam here
+const SPAM_TERMS: [&str; 8] = [
+    "crypto",
+    "bitcoin",
+    "forex",
+    "casino",
+    "viagra",
+    "seo service",
+    "backlinks",
+    "onlyfans",
+];
+
+/// Trimmed, inner whitespace runs collapsed to one space while keeping line
+/// breaks
+fn tidy(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The question as stored
+pub fn normalize_question(question: &str) -> Result<String, String> {
+    let question = tidy(question);
+    let chars = question.chars().count();
+    if chars < MIN_QUESTION_CHARS {
+        return Err("Ask a full question".to_string());
+    }
+    if chars > MAX_QUESTION_CHARS {
+        return Err(format!(
+            "Keep questions under {} characters",
+            MAX_QUESTION_CHARS
+        ));
+    }
+    Ok(question)
+}
+
+/// The answer as stored
+pub fn normalize_answer(answer: &str) -> Result<String, String> {
+    let answer = tidy(answer);
+    if answer.is_empty() {
+        return Err("Write an answer".to_string());
+    }
+    if answer.chars().count() > MAX_ANSWER_CHARS {
+        return Err(format!(
+            "Keep answers under {} characters",
+            MAX_ANSWER_CHARS
+        ));
+    }
+    Ok(answer)
+}
+
+/// The asker's name as stored
+pub fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err("Enter your name".to_string());
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err("That name is too long".to_string());
+    }
+    Ok(name)
+}
+
+/// Why a question should be held for moderation instead of going to the
+/// artist, if it should
+pub fn spam_reason(question: &str) -> Option<&'static str> {
+    let lower = question.to_lowercase();
+
+    if lower.contains("http://")
+        || lower.contains("https://")
+        || lower.contains("www.")
+        || lower.contains(".com/")
+    {
+        return Some("Contains a link");
+    }
+    if SPAM_TERMS.iter().any(|term| lower.contains(term)) {
+        return Some("Contains a spam term");
+    }
+
+    let letters: Vec<char> = question.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() >= 20 && letters.iter().all(|c| !c.is_lowercase()) {
+        return Some("All capitals");
+    }
+
+    // Long runs of one character, like "!!!!!!!!" or "aaaaaaaa"
+    let mut run = 0;
+    let mut previous = None;
+    for c in question.chars().filter(|c| !c.is_whitespace()) {
+        run = if Some(c) == previous { run + 1 } else { 1 };
+        if run >= 8 {
+            return Some("Repeated characters");
+        }
+        previous = Some(c);
+    }
+
+    None
+}
+
+/// What the profile shows of an asker: their first name
+pub fn public_name(name: &str) -> String {
+    name.split_whitespace()
+        .next()
+        .unwrap_or("Someone")
+        .to_string()
+}
+
+pub fn status_label(status: &str) -> &'static str {
+    match status {
+        "approved" => "Approved",
+        "rejected" => "Rejected",
+        _ => "Held for review",
+    }
+}
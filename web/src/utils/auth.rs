@@ -1,4 +1,9 @@
 use crate::server::get_artist_id_from_jwt_user_id;
+use crate::server_team::get_team_access;
+#[cfg(feature = "hydrate")]
+use crate::api_error::ApiError;
+#[cfg(feature = "hydrate")]
+use crate::server::refresh_session;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
 use serde::{Deserialize, Serialize};
@@ -56,7 +61,8 @@ fn decode_jwt_artist_id(token: &str) -> Option<i64> {
     None
 }
 
-/// Hook to get the authenticated artist ID reactively with proper server lookup
+/// Hook to get the authenticated artist ID reactively with proper server lookup.
+/// For an assistant this is the artist whose account they work on.
 pub fn use_authenticated_artist_id() -> Signal<Option<i32>> {
     let artist_id = RwSignal::new(None::<i32>);
 
@@ -72,6 +78,13 @@ pub fn use_authenticated_artist_id() -> Signal<Option<i32>> {
                         Err(_) => artist_id.set(None),
                     }
                 });
+            } else if let Some(token) = get_auth_token() {
+                spawn_local(async move {
+                    match get_team_access(token).await {
+                        Ok(Some(access)) => artist_id.set(Some(access.artist_id)),
+                        _ => artist_id.set(None),
+                    }
+                });
             } else {
                 artist_id.set(None);
             }
@@ -83,6 +96,26 @@ pub fn use_authenticated_artist_id() -> Signal<Option<i32>> {
     artist_id.into()
 }
 
+/// The stored access token, if signed in
+pub fn get_auth_token() -> Option<String> {
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = localStorage)]
+            fn getItem(key: &str) -> Option<String>;
+        }
+
+        getItem("tatteau_auth_token").filter(|token| !token.is_empty())
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        None
+    }
+}
+
 /// Checks if any user (client or artist) is authenticated
 /// Returns true if there's a valid JWT token in localStorage
 pub fn is_authenticated() -> bool {
@@ -163,6 +196,76 @@ pub fn is_admin() -> bool {
     false
 }
 
+/// Renew access tokens this long before they expire
+#[cfg(feature = "hydrate")]
+const REFRESH_MARGIN_MS: f64 = 2.0 * 60.0 * 1000.0;
+
+/// Keeps the session alive by renewing the short-lived access token with the
+/// stored refresh token shortly before it expires. Checks on mount and then
+/// once a minute; call once, from the app root.
+pub fn use_session_refresh() {
+    Effect::new(move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            refresh_session_if_expiring();
+            let _ = set_interval_with_handle(
+                refresh_session_if_expiring,
+                std::time::Duration::from_secs(60),
+            );
+        }
+    });
+}
+
+#[cfg(feature = "hydrate")]
+fn refresh_session_if_expiring() {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = localStorage)]
+        fn getItem(key: &str) -> Option<String>;
+        #[wasm_bindgen(js_namespace = localStorage)]
+        fn setItem(key: &str, value: &str);
+        #[wasm_bindgen(js_namespace = localStorage)]
+        fn removeItem(key: &str);
+        #[wasm_bindgen(js_namespace = Date)]
+        fn now() -> f64;
+    }
+
+    let Some(refresh_token) = getItem("tatteau_refresh_token").filter(|t| !t.is_empty()) else {
+        return;
+    };
+    let expiring = getItem("tatteau_auth_token")
+        .and_then(|token| decode_jwt_token(&token))
+        .map(|claims| claims.exp as f64 * 1000.0 - now() < REFRESH_MARGIN_MS)
+        .unwrap_or(true);
+    if !expiring {
+        return;
+    }
+
+    spawn_local(async move {
+        match refresh_session(refresh_token.clone()).await {
+            Ok(response) => {
+                if let (Some(token), Some(refresh_token)) =
+                    (response.token, response.refresh_token)
+                {
+                    setItem("tatteau_auth_token", &token);
+                    setItem("tatteau_refresh_token", &refresh_token);
+                }
+            }
+            Err(ServerFnError::WrappedServerError(ApiError::Unauthorized(_))) => {
+                // Another tab may have renewed the session in the meantime
+                if getItem("tatteau_refresh_token").as_deref() == Some(refresh_token.as_str()) {
+                    removeItem("tatteau_auth_token");
+                    removeItem("tatteau_refresh_token");
+                }
+            }
+            // Offline or server unavailable; try again on the next check
+            Err(_) => {}
+        }
+    });
+}
+
 /// Decodes JWT token and returns claims if valid
 fn decode_jwt_token(token: &str) -> Option<Claims> {
     let parts: Vec<&str> = token.split('.').collect();
@@ -0,0 +1,145 @@
+//! Automatic first replies to new booking requests. Artists write templates
+//! with `{variable}` placeholders, a default one and optionally one per
+//! booking type; `crate::auto_response` fills them in and sends the reply
+//! once the artist's delay passes without a manual response.
+
+/// Kinds of booking a client can ask for (booking_requests.booking_type),
+/// with their labels
+pub const BOOKING_TYPES: &[(&str, &str)] = &[
+    ("custom", "Custom piece"),
+    ("flash", "Flash design"),
+    ("cover_up", "Cover-up"),
+    ("touch_up", "Touch-up"),
+    ("consultation", "Consultation"),
+];
+
+/// Booking type new requests get when the client doesn't pick one
+pub const DEFAULT_BOOKING_TYPE: &str = "custom";
+
+/// Template used for booking types without a variant of their own
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// Placeholders a template can use, with what they stand for
+pub const TEMPLATE_VARIABLES: &[(&str, &str)] = &[
+    ("client_name", "The client's full name"),
+    ("client_first_name", "The client's first name"),
+    ("artist_name", "Your name as shown on your profile"),
+    ("booking_type", "What they asked for, e.g. \"Cover-up\""),
+    ("requested_date", "The date they asked for"),
+    ("requested_time", "The start time they asked for"),
+    ("minimum_charge", "Your minimum charge, e.g. \"$150\""),
+    ("hourly_rate", "Your hourly rate, e.g. \"$200\""),
+    ("response_time", "Your usual response time, e.g. \"a day\""),
+];
+
+/// Longest template accepted
+pub const MAX_TEMPLATE_LEN: usize = 1000;
+
+/// Longest an artist can hold the reply back for a manual one
+pub const MAX_DELAY_MINUTES: i32 = 24 * 60;
+
+pub fn booking_type_label(booking_type: &str) -> &str {
+    BOOKING_TYPES
+        .iter()
+        .find(|(value, _)| *value == booking_type)
+        .map(|(_, label)| *label)
+        .unwrap_or(booking_type)
+}
+
+/// What the placeholders are filled in with. Values the artist hasn't set
+/// (no minimum charge, too few requests for a response time) render empty.
+#[derive(Clone, Debug, Default)]
+pub struct TemplateValues {
+    pub client_name: String,
+    pub artist_name: String,
+    pub booking_type: String,
+    pub requested_date: String,
+    pub requested_time: String,
+    pub minimum_charge: Option<f64>,
+    pub hourly_rate: Option<f64>,
+    pub response_time: Option<String>,
+}
+
+impl TemplateValues {
+    fn get(&self, variable: &str) -> Option<String> {
+        let money = |amount: Option<f64>| {
+            amount
+                .map(|amount| format!("${}", amount))
+                .unwrap_or_default()
+        };
+
+        Some(match variable {
+            "client_name" => self.client_name.clone(),
+            "client_first_name" => self
+                .client_name
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            "artist_name" => self.artist_name.clone(),
+            "booking_type" => booking_type_label(&self.booking_type).to_string(),
+            "requested_date" => self.requested_date.clone(),
+            "requested_time" => self.requested_time.clone(),
+            "minimum_charge" => money(self.minimum_charge),
+            "hourly_rate" => money(self.hourly_rate),
+            "response_time" => self.response_time.clone().unwrap_or_default(),
+            _ => return None,
+        })
+    }
+}
+
+/// Calls `on_variable` for each `{name}` in the template, building the
+/// output from the literal text and whatever it returns (None keeps the
+/// placeholder as written)
+pub(crate) fn expand(
+    template: &str,
+    mut on_variable: impl FnMut(&str) -> Option<String>,
+) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end)
+                if after[..end]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && end > 0 =>
+            {
+                let name = &after[..end];
+                match on_variable(name) {
+                    Some(value) => output.push_str(&value),
+                    None => output.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Fills in a template. Unknown placeholders are left as written.
+pub fn render(template: &str, values: &TemplateValues) -> String {
+    expand(template, |name| values.get(name))
+}
+
+/// Placeholders in the template that aren't in [`TEMPLATE_VARIABLES`]
+pub fn unknown_variables(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    expand(template, |name| {
+        if !TEMPLATE_VARIABLES.iter().any(|(known, _)| *known == name)
+            && !unknown.iter().any(|seen| seen == name)
+        {
+            unknown.push(name.to_string());
+        }
+        None
+    });
+    unknown
+}
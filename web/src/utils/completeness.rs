@@ -0,0 +1,122 @@
+use crate::db::entities::{CompletenessItem, ProfileCompleteness};
+
+/// Photos that earn full marks
+pub const TARGET_PHOTOS: i64 = 12;
+/// Styles that earn full marks
+pub const TARGET_STYLES: i64 = 3;
+/// Bio length, in characters, that earns full marks
+pub const TARGET_BIO_CHARS: usize = 80;
+
+const SETTINGS_LINK: &str = "/artist/dashboard/settings";
+
+/// What the score is computed from
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProfileFacts {
+    pub photo_count: i64,
+    pub style_count: i64,
+    pub has_pricing: bool,
+    pub open_days: i64,
+    pub question_count: i64,
+    pub bio_chars: usize,
+}
+
+fn partial(max_points: i32, have: i64, target: i64) -> i32 {
+    (max_points as i64 * have.clamp(0, target) / target) as i32
+}
+
+fn item(
+    key: &str,
+    label: &str,
+    hint: String,
+    link: &str,
+    points: i32,
+    max_points: i32,
+) -> CompletenessItem {
+    CompletenessItem {
+        key: key.to_string(),
+        label: label.to_string(),
+        hint,
+        link: link.to_string(),
+        points,
+        max_points,
+        delta: max_points - points,
+        complete: points == max_points,
+    }
+}
+
+/// Scores a profile out of 100, listing the unfinished items first with the
+/// biggest gains leading
+pub fn score(facts: &ProfileFacts) -> ProfileCompleteness {
+    let bio_points = match facts.bio_chars {
+        0 => 0,
+        n if n < TARGET_BIO_CHARS => 7,
+        _ => 15,
+    };
+
+    let mut items = vec![
+        item(
+            "photos",
+            "Portfolio photos",
+            format!(
+                "{} of {} photos. Clients judge fit from your work first.",
+                facts.photo_count.min(TARGET_PHOTOS),
+                TARGET_PHOTOS
+            ),
+            SETTINGS_LINK,
+            partial(25, facts.photo_count, TARGET_PHOTOS),
+            25,
+        ),
+        item(
+            "styles",
+            "Styles",
+            format!(
+                "{} of {} styles tagged. Styles decide which quiz matches you appear in.",
+                facts.style_count.min(TARGET_STYLES),
+                TARGET_STYLES
+            ),
+            SETTINGS_LINK,
+            partial(15, facts.style_count, TARGET_STYLES),
+            15,
+        ),
+        item(
+            "pricing",
+            "Pricing",
+            "Set a minimum charge or style prices so clients can check their budget.".to_string(),
+            SETTINGS_LINK,
+            if facts.has_pricing { 15 } else { 0 },
+            15,
+        ),
+        item(
+            "hours",
+            "Business hours",
+            "Add the days you work so clients know when to book.".to_string(),
+            SETTINGS_LINK,
+            if facts.open_days > 0 { 15 } else { 0 },
+            15,
+        ),
+        item(
+            "questionnaire",
+            "Booking questionnaire",
+            "Choose the questions clients answer when they request a booking.".to_string(),
+            "/artist/dashboard/questionnaire",
+            if facts.question_count > 0 { 15 } else { 0 },
+            15,
+        ),
+        item(
+            "bio",
+            "Bio",
+            format!(
+                "A few sentences ({}+ characters) about you and your work.",
+                TARGET_BIO_CHARS
+            ),
+            SETTINGS_LINK,
+            bio_points,
+            15,
+        ),
+    ];
+
+    let score = items.iter().map(|item| item.points).sum();
+    items.sort_by_key(|item| (item.complete, -item.delta));
+
+    ProfileCompleteness { score, items }
+}
@@ -0,0 +1,165 @@
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+
+use crate::db::entities::ConsentSignature;
+use crate::utils::documents::{parse_blocks, plain, Block};
+use crate::utils::invoice_pdf::{win_ansi, wrap};
+
+const PAGE_WIDTH: f32 = 595.0; // A4
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+
+/// Characters of 11pt Helvetica that fit across the page
+const LINE_CHARS: usize = 90;
+
+/// Lays text out top to bottom, starting a new page when one fills up
+struct Pages {
+    pages: Vec<Content>,
+    y: f32,
+}
+
+impl Pages {
+    fn new() -> Self {
+        Self {
+            pages: vec![Content::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn make_room(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.pages.push(Content::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text(&mut self, font: &[u8], size: f32, indent: f32, text: &str) {
+        let encoded = win_ansi(text);
+        if let Some(content) = self.pages.last_mut() {
+            content
+                .begin_text()
+                .set_font(Name(font), size)
+                .next_line(MARGIN + indent, self.y)
+                .show(Str(&encoded))
+                .end_text();
+        }
+    }
+
+    fn line(&mut self, font: &[u8], size: f32, indent: f32, text: &str) {
+        self.make_room(size * 1.5);
+        self.text(font, size, indent, text);
+        self.y -= size * 1.5;
+    }
+
+    /// Wrapped text, with `marker` hanging in front of the first line
+    fn paragraph(&mut self, text: &str, marker: Option<&str>) {
+        let indent = if marker.is_some() { 16.0 } else { 0.0 };
+        let max_chars = if marker.is_some() {
+            LINE_CHARS - 4
+        } else {
+            LINE_CHARS
+        };
+        for (i, line) in wrap(&plain(text), max_chars).iter().enumerate() {
+            self.make_room(11.0 * 1.5);
+            if let Some(marker) = marker.filter(|_| i == 0) {
+                self.text(b"F1", 11.0, 0.0, marker);
+            }
+            self.line(b"F1", 11.0, indent, line);
+        }
+    }
+}
+
+/// Renders a signed consent form, the form as the client read it followed
+/// by their signature
+pub fn render_consent_pdf(signature: &ConsentSignature) -> Vec<u8> {
+    let mut pages = Pages::new();
+
+    pages.line(b"F2", 18.0, 0.0, &signature.title);
+    pages.y -= 6.0;
+
+    let mut previous_item = false;
+    for block in parse_blocks(&signature.body) {
+        let is_item = matches!(block, Block::Bullet(_) | Block::Numbered(..));
+        if !(previous_item && is_item) {
+            pages.y -= 6.0;
+        }
+        previous_item = is_item;
+
+        match block {
+            Block::Heading(text) => {
+                pages.make_room(13.0 * 1.5 + 11.0 * 1.5);
+                pages.line(b"F2", 13.0, 0.0, &plain(&text));
+            }
+            Block::Bullet(text) => pages.paragraph(&text, Some("-")),
+            Block::Numbered(number, text) => pages.paragraph(&text, Some(&format!("{}.", number))),
+            Block::Paragraph(text) => pages.paragraph(&text, None),
+        }
+    }
+
+    pages.y -= 24.0;
+    pages.make_room(16.0 * 1.5 + 11.0 * 1.5 * 3);
+    pages.line(b"F3", 16.0, 0.0, &signature.typed_name);
+    pages.line(
+        b"F1",
+        11.0,
+        0.0,
+        &format!("Signed by typing their name on {} UTC", signature.signed_at),
+    );
+    if let Some(ip_address) = &signature.ip_address {
+        pages.line(b"F1", 11.0, 0.0, &format!("From IP address {}", ip_address));
+    }
+    pages.line(
+        b"F1",
+        11.0,
+        0.0,
+        &format!(
+            "Booking #{}, signature #{}",
+            signature.booking_id, signature.id
+        ),
+    );
+
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let font_id = Ref::new(3);
+    let bold_font_id = Ref::new(4);
+    let italic_font_id = Ref::new(5);
+    // Each page and its content stream after those
+    let page_ids: Vec<(Ref, Ref)> = (0..pages.pages.len() as i32)
+        .map(|i| (Ref::new(6 + i * 2), Ref::new(7 + i * 2)))
+        .collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(page_ids.iter().map(|(page_id, _)| *page_id))
+        .count(page_ids.len() as i32);
+
+    for (content, (page_id, content_id)) in pages.pages.into_iter().zip(&page_ids) {
+        let mut page_writer = pdf.page(*page_id);
+        page_writer
+            .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(*content_id);
+        let mut resources = page_writer.resources();
+        let mut fonts = resources.fonts();
+        fonts.pair(Name(b"F1"), font_id);
+        fonts.pair(Name(b"F2"), bold_font_id);
+        fonts.pair(Name(b"F3"), italic_font_id);
+        drop(fonts);
+        drop(resources);
+        drop(page_writer);
+
+        pdf.stream(*content_id, &content.finish());
+    }
+
+    for (id, font) in [
+        (font_id, b"Helvetica".as_slice()),
+        (bold_font_id, b"Helvetica-Bold".as_slice()),
+        (italic_font_id, b"Helvetica-Oblique".as_slice()),
+    ] {
+        pdf.type1_font(id)
+            .base_font(Name(font))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+    }
+
+    pdf.finish()
+}
@@ -0,0 +1,258 @@
+//! Consent forms and aftercare instructions. Artists write each in a small
+//! subset of markdown (`#` headings, `-` and `1.` lists, `**bold**`) with
+//! `{variable}` placeholders, filled in per booking. Artists who haven't
+//! written their own get the defaults here.
+
+use crate::utils::auto_response::expand;
+
+/// Documents an artist can write (artist_documents.kind), with their labels
+pub const DOCUMENT_KINDS: &[(&str, &str)] = &[
+    ("consent", "Consent form"),
+    ("aftercare", "Aftercare instructions"),
+];
+
+pub const CONSENT: &str = "consent";
+pub const AFTERCARE: &str = "aftercare";
+
+/// Placeholders a document can use, with what they stand for
+pub const DOCUMENT_VARIABLES: &[(&str, &str)] = &[
+    ("client_name", "The client's full name"),
+    ("client_first_name", "The client's first name"),
+    ("artist_name", "Your name as shown on your profile"),
+    ("appointment_date", "The date of the appointment"),
+    ("appointment_time", "The time of the appointment"),
+    ("placement", "Where on the body the tattoo goes"),
+    ("description", "The client's description of the tattoo"),
+];
+
+pub const MAX_TITLE_CHARS: usize = 120;
+
+/// Longest document accepted
+pub const MAX_DOCUMENT_CHARS: usize = 20_000;
+
+pub const MAX_TYPED_NAME_CHARS: usize = 120;
+
+pub const DEFAULT_CONSENT_TITLE: &str = "Tattoo Consent Form";
+
+pub const DEFAULT_CONSENT_BODY: &str = "\
+I, {client_name}, consent to be tattooed by {artist_name} on {appointment_date}.
+
+## I confirm that
+
+- I am at least 18 years old and have shown valid photo ID.
+- I am not under the influence of alcohol or drugs.
+- I am not pregnant or nursing.
+- I have told my artist about any medical conditions, allergies or medication that could affect \
+the tattoo or my healing, including diabetes, heart conditions, hemophilia, skin conditions and \
+blood thinners.
+
+## I understand that
+
+- A tattoo is permanent, and removing or changing it later may be costly, painful or not fully \
+possible.
+- Variations in color and design may happen, and the tattoo may fade over time.
+- There is a risk of infection, scarring or allergic reaction, and following the aftercare \
+instructions is my responsibility.
+- My deposit is non-refundable unless my artist cancels.
+
+I have read and understood this form, and agree to the design and its placement.";
+
+pub const DEFAULT_AFTERCARE_TITLE: &str = "Caring for Your New Tattoo";
+
+pub const DEFAULT_AFTERCARE_BODY: &str = "\
+Hi {client_first_name}, thanks for coming in! Here's how to look after your new tattoo.
+
+## The first day
+
+1. Leave the wrap on for 2 to 4 hours, or as your artist told you.
+2. Wash your hands, then gently wash the tattoo with lukewarm water and fragrance-free soap.
+3. Pat it dry with a clean paper towel and let it air dry for a few minutes.
+4. Apply a thin layer of unscented aftercare ointment.
+
+## The next two to four weeks
+
+- Wash the tattoo two or three times a day and keep it lightly moisturized.
+- **Don't pick or scratch** the scabs or peeling skin.
+- Stay out of pools, hot tubs, the sea and baths until it has fully healed.
+- Keep it out of direct sunlight, and use sunscreen once it has healed.
+- Wear loose, clean clothing over it.
+
+If you notice spreading redness, swelling, pus or a fever, see a doctor.
+
+Questions? Just reply to {artist_name}.";
+
+/// What the placeholders are filled in with
+#[derive(Clone, Debug, Default)]
+pub struct DocumentValues {
+    pub client_name: String,
+    pub artist_name: String,
+    pub appointment_date: String,
+    pub appointment_time: String,
+    pub placement: Option<String>,
+    pub description: Option<String>,
+}
+
+impl DocumentValues {
+    fn get(&self, variable: &str) -> Option<String> {
+        Some(match variable {
+            "client_name" => self.client_name.clone(),
+            "client_first_name" => self
+                .client_name
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            "artist_name" => self.artist_name.clone(),
+            "appointment_date" => self.appointment_date.clone(),
+            "appointment_time" => self.appointment_time.clone(),
+            "placement" => self.placement.clone().unwrap_or_default(),
+            "description" => self.description.clone().unwrap_or_default(),
+            _ => return None,
+        })
+    }
+}
+
+pub fn document_kind_label(kind: &str) -> &str {
+    DOCUMENT_KINDS
+        .iter()
+        .find(|(value, _)| *value == kind)
+        .map(|(_, label)| *label)
+        .unwrap_or(kind)
+}
+
+/// The title and body an artist without their own document of this kind
+/// gets
+pub fn default_document(kind: &str) -> (&'static str, &'static str) {
+    if kind == AFTERCARE {
+        (DEFAULT_AFTERCARE_TITLE, DEFAULT_AFTERCARE_BODY)
+    } else {
+        (DEFAULT_CONSENT_TITLE, DEFAULT_CONSENT_BODY)
+    }
+}
+
+/// Fills in a document. Unknown placeholders are left as written.
+pub fn render(template: &str, values: &DocumentValues) -> String {
+    expand(template, |name| values.get(name))
+}
+
+/// Placeholders in the document that aren't in [`DOCUMENT_VARIABLES`]
+pub fn unknown_variables(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    expand(template, |name| {
+        if !DOCUMENT_VARIABLES.iter().any(|(known, _)| *known == name)
+            && !unknown.iter().any(|seen| seen == name)
+        {
+            unknown.push(name.to_string());
+        }
+        None
+    });
+    unknown
+}
+
+/// A block of a document
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    Heading(String),
+    Bullet(String),
+    Numbered(u32, String),
+    Paragraph(String),
+}
+
+/// Splits a document into blocks. Lines that run on join their paragraph
+/// or list item; a blank line ends it.
+pub fn parse_blocks(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut open = false;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            open = false;
+            continue;
+        }
+
+        let heading = line
+            .strip_prefix('#')
+            .map(|rest| rest.trim_start_matches('#'))
+            .filter(|rest| rest.starts_with(' '));
+        let bullet = line.strip_prefix("- ").or_else(|| line.strip_prefix("* "));
+        let numbered = line
+            .split_once(". ")
+            .and_then(|(number, rest)| Some((number.parse::<u32>().ok()?, rest)));
+
+        let block = if let Some(text) = heading {
+            Block::Heading(text.trim().to_string())
+        } else if let Some(text) = bullet {
+            Block::Bullet(text.trim().to_string())
+        } else if let Some((number, text)) = numbered {
+            Block::Numbered(number, text.trim().to_string())
+        } else {
+            match blocks.last_mut() {
+                Some(Block::Paragraph(text) | Block::Bullet(text) | Block::Numbered(_, text))
+                    if open =>
+                {
+                    text.push(' ');
+                    text.push_str(line);
+                    continue;
+                }
+                _ => Block::Paragraph(line.to_string()),
+            }
+        };
+        open = !matches!(block, Block::Heading(_));
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// A block's text in runs, `true` for the `**bold**` ones
+pub fn spans(text: &str) -> Vec<(&str, bool)> {
+    text.split("**")
+        .enumerate()
+        .filter(|(_, span)| !span.is_empty())
+        .map(|(i, span)| (span, i % 2 == 1))
+        .collect()
+}
+
+/// A block's text without the markup
+pub fn plain(text: &str) -> String {
+    text.replace("**", "")
+}
+
+/// The document as plain text, for email
+pub fn to_plain_text(text: &str) -> String {
+    let mut output = String::new();
+    let mut in_list = false;
+
+    for block in parse_blocks(text) {
+        let is_item = matches!(block, Block::Bullet(_) | Block::Numbered(..));
+        if !output.is_empty() {
+            output.push_str(if in_list && is_item { "\n" } else { "\n\n" });
+        }
+        in_list = is_item;
+
+        match block {
+            Block::Heading(text) => output.push_str(&plain(&text).to_uppercase()),
+            Block::Bullet(text) => output.push_str(&format!("- {}", plain(&text))),
+            Block::Numbered(number, text) => {
+                output.push_str(&format!("{}. {}", number, plain(&text)))
+            }
+            Block::Paragraph(text) => output.push_str(&plain(&text)),
+        }
+    }
+    output
+}
+
+/// The name a client typed to sign, tidied, or why it won't do
+pub fn normalize_typed_name(name: &str) -> Result<String, String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err("Type your full name to sign".to_string());
+    }
+    if name.chars().count() > MAX_TYPED_NAME_CHARS {
+        return Err(format!(
+            "Your name can be at most {} characters",
+            MAX_TYPED_NAME_CHARS
+        ));
+    }
+    Ok(name)
+}
@@ -0,0 +1,57 @@
+//! The booking widget artists embed on their own sites, served by
+//! `embed.rs` at `/embed/booking/{artist_id}`.
+
+/// Most sites an artist can allow to embed their widget
+pub const MAX_EMBED_ORIGINS: usize = 10;
+
+/// Height the embed code gives the widget's iframe
+pub const WIDGET_HEIGHT_PX: u32 = 760;
+
+/// `type` of the message the widget posts to the page embedding it once a
+/// booking request is sent, along with `artistId` and `bookingId`
+pub const COMPLETION_EVENT: &str = "tatteau:booking-submitted";
+
+/// The origin of a site an artist enters, like `https://example.com` for
+/// `Example.com/book`. Without a scheme, https is assumed.
+pub fn normalize_origin(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    let (scheme, rest) = match value.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_lowercase(), rest),
+        None => ("https".to_string(), value),
+    };
+    if scheme != "https" && scheme != "http" {
+        return Err(format!("{} isn't a website address", value));
+    }
+
+    let host = rest
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) => (name, Some(port)),
+        None => (host.as_str(), None),
+    };
+    let valid_name = !name.is_empty()
+        && (name == "localhost" || name.contains('.'))
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        });
+    let valid_port = port.is_none_or(|port| port.parse::<u16>().is_ok());
+    if !valid_name || !valid_port {
+        return Err(format!("{} isn't a website address", value));
+    }
+
+    Ok(format!("{}://{}", scheme, host))
+}
+
+/// The HTML an artist pastes into their site to show the widget
+pub fn embed_snippet(widget_url: &str) -> String {
+    format!(
+        "<iframe src=\"{}\" title=\"Book an appointment\" width=\"100%\" height=\"{}\" style=\"border:0;max-width:480px\" loading=\"lazy\"></iframe>",
+        widget_url, WIDGET_HEIGHT_PX
+    )
+}
@@ -0,0 +1,78 @@
+//! Grouping logged errors for triage. Every error gets a fingerprint when
+//! it's logged: a hash of its type, its message with numbers blanked out
+//! (ids, counts, line numbers) and the top frame of its stack. Errors
+//! sharing a fingerprint are one group on the admin error dashboard, where
+//! it's assigned to an admin and marked resolved or ignored.
+
+#[cfg(feature = "ssr")]
+use sha2::{Digest, Sha256};
+
+/// Triage states of an error group. A resolved group that happens again is
+/// opened again.
+pub const ERROR_STATUSES: [&str; 3] = ["open", "resolved", "ignored"];
+
+/// Longest top frame kept for display
+#[cfg(feature = "ssr")]
+const MAX_FRAME_LEN: usize = 300;
+
+pub fn status_label(status: &str) -> &'static str {
+    match status {
+        "open" => "Open",
+        "resolved" => "Resolved",
+        "ignored" => "Ignored",
+        _ => "Unknown",
+    }
+}
+
+/// The innermost frame of a stack: a JS `at …` line, a Firefox `fn@file`
+/// line or a numbered Rust backtrace line
+#[cfg(feature = "ssr")]
+pub fn top_frame(stack: &str) -> Option<String> {
+    stack
+        .lines()
+        .map(str::trim)
+        .find(|line| {
+            line.starts_with("at ")
+                || line.contains('@')
+                || line.split_once(": ").is_some_and(|(number, _)| {
+                    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+                })
+        })
+        .map(|line| line.chars().take(MAX_FRAME_LEN).collect())
+}
+
+/// Text with every run of digits replaced by `N`
+#[cfg(feature = "ssr")]
+fn blank_numbers(text: &str) -> String {
+    let mut blanked = String::with_capacity(text.len());
+    let mut in_number = false;
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            if !in_number {
+                blanked.push('N');
+            }
+            in_number = true;
+        } else {
+            blanked.push(c);
+            in_number = false;
+        }
+    }
+    blanked
+}
+
+/// The group an error belongs to, as 32 hex characters
+#[cfg(feature = "ssr")]
+pub fn fingerprint(error_type: &str, message: &str, stack: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(error_type.as_bytes());
+    hasher.update([0]);
+    hasher.update(blank_numbers(message.trim()).as_bytes());
+    hasher.update([0]);
+    if let Some(frame) = stack.and_then(top_frame) {
+        hasher.update(blank_numbers(&frame).as_bytes());
+    }
+    hasher.finalize()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
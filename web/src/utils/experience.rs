@@ -0,0 +1,54 @@
+//! Artist experience tiers. Shops list apprentices and junior artists at
+//! lower price points; the tier is stored on `artists.experience_tier`
+//! (`NULL` when the artist hasn't said) and drives the discovery filter,
+//! the badges on profiles, match cards and shop pages, and how matching
+//! ranks artists for clients on a tight budget.
+
+/// Tiers an artist can be listed under, least experienced first
+pub const EXPERIENCE_TIERS: [&str; 3] = ["apprentice", "junior", "senior"];
+
+/// A client's budget tops out at or below this (in dollars) when they're
+/// treated as budget-constrained and shown lower-priced tiers first
+pub const BUDGET_CONSTRAINED_MAX: f64 = 300.0;
+
+/// The stored form of a tier picked in a form; an empty pick clears it
+pub fn normalize_tier(tier: &str) -> Result<Option<String>, String> {
+    let tier = tier.trim().to_lowercase();
+    if tier.is_empty() {
+        return Ok(None);
+    }
+    if !EXPERIENCE_TIERS.contains(&tier.as_str()) {
+        return Err("Choose apprentice, junior or senior".to_string());
+    }
+    Ok(Some(tier))
+}
+
+/// Badge text for a tier, e.g. `"Apprentice"`
+pub fn tier_label(tier: &str) -> &'static str {
+    match tier {
+        "apprentice" => "Apprentice",
+        "junior" => "Junior Artist",
+        "senior" => "Senior Artist",
+        _ => "Artist",
+    }
+}
+
+/// Whether artists in this tier are usually booked at lower price points
+pub fn is_lower_priced(tier: Option<&str>) -> bool {
+    matches!(tier, Some("apprentice") | Some("junior"))
+}
+
+/// Whether a client's budget is tight enough that lower-priced tiers
+/// should be surfaced first
+pub fn is_budget_constrained(price_range: Option<(f64, f64)>) -> bool {
+    price_range.is_some_and(|(_, budget_max)| budget_max <= BUDGET_CONSTRAINED_MAX)
+}
+
+/// SQL ranking `column`'s tier for a budget-constrained client: apprentices
+/// first, then junior artists, then everyone else
+pub fn budget_rank_sql(column: &str) -> String {
+    format!(
+        "(CASE {} WHEN 'apprentice' THEN 2 WHEN 'junior' THEN 1 ELSE 0 END)",
+        column
+    )
+}
@@ -0,0 +1,125 @@
+//! What can be exported and how rows are written out as CSV. The jobs
+//! themselves run in `exports.rs`; the rows come from `db::export_repository`
+//! with columns in the order of [`headers`].
+
+/// Exports artists (and team members with the Bookings permission) can run
+pub const ARTIST_EXPORTS: &[(&str, &str)] = &[("bookings", "Booking requests")];
+
+/// Exports only admins can run
+pub const ADMIN_EXPORTS: &[(&str, &str)] = &[
+    ("all_bookings", "All booking requests"),
+    ("artists", "Artists"),
+];
+
+const BOOKING_HEADERS: &[&str] = &[
+    "id",
+    "created_at",
+    "requested_date",
+    "requested_start_time",
+    "requested_end_time",
+    "status",
+    "booking_type",
+    "client_name",
+    "client_email",
+    "client_phone",
+    "placement",
+    "size_inches",
+    "tattoo_description",
+    "estimated_price",
+    "deposit_status",
+];
+
+const ALL_BOOKING_HEADERS: &[&str] = &[
+    "id",
+    "artist_id",
+    "artist_name",
+    "created_at",
+    "requested_date",
+    "requested_start_time",
+    "status",
+    "booking_type",
+    "client_name",
+    "client_email",
+    "estimated_price",
+    "deposit_status",
+];
+
+const ARTIST_HEADERS: &[&str] = &[
+    "id",
+    "name",
+    "email",
+    "phone",
+    "instagram_handle",
+    "years_experience",
+    "shop",
+    "city",
+    "shop_validated",
+];
+
+pub fn is_export_kind(kind: &str) -> bool {
+    ARTIST_EXPORTS
+        .iter()
+        .chain(ADMIN_EXPORTS)
+        .any(|(value, _)| *value == kind)
+}
+
+pub fn is_admin_export(kind: &str) -> bool {
+    ADMIN_EXPORTS.iter().any(|(value, _)| *value == kind)
+}
+
+pub fn label(kind: &str) -> &str {
+    ARTIST_EXPORTS
+        .iter()
+        .chain(ADMIN_EXPORTS)
+        .find(|(value, _)| *value == kind)
+        .map(|(_, label)| *label)
+        .unwrap_or(kind)
+}
+
+pub fn headers(kind: &str) -> &'static [&'static str] {
+    match kind {
+        "all_bookings" => ALL_BOOKING_HEADERS,
+        "artists" => ARTIST_HEADERS,
+        _ => BOOKING_HEADERS,
+    }
+}
+
+/// One CSV field. Fields a spreadsheet would run as a formula get a leading
+/// apostrophe, since client-entered text ends up in these files.
+fn field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) && value.parse::<f64>().is_err() {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// A CSV line, newline included
+pub fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|value| field(value.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// Percent done; 0 until the row count is known
+pub fn progress(rows_written: i64, total_rows: Option<i64>) -> i32 {
+    match total_rows {
+        Some(0) => 100,
+        Some(total) => ((rows_written.min(total) * 100) / total) as i32,
+        None => 0,
+    }
+}
+
+/// e.g. "bookings-2025-06-01.csv"
+pub fn file_name(kind: &str, date: &str) -> String {
+    format!("{}-{}.csv", kind.replace('_', "-"), date)
+}
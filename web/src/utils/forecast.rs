@@ -0,0 +1,324 @@
+//! Next-month earnings forecast for the artist dashboard. Pure math over
+//! figures loaded by `db::forecast_repository`, kept apart from the database
+//! so the model can be checked on its own.
+//!
+//! Two estimates are combined:
+//! - committed: accepted bookings in the month, each weighted by the artist's
+//!   historical completion rate (a Bernoulli per booking)
+//! - baseline: the recent deseasonalized monthly revenue, scaled by the
+//!   target month's seasonality
+//!
+//! Bookings already on the calendar are part of a typical month, so the
+//! forecast is the larger of the two rather than their sum.
+
+/// z-score of the two-sided interval reported with the forecast
+const Z_SCORE: f64 = 1.2816;
+/// Coverage of the reported interval
+pub const CONFIDENCE: f64 = 0.8;
+/// Months averaged for the baseline level
+const RECENT_MONTHS: usize = 3;
+/// Relative spread assumed when there's too little history to measure one
+const FALLBACK_RELATIVE_SD: f64 = 0.5;
+
+/// Revenue of one past calendar month (`month` is 1-12)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MonthRevenue {
+    pub month: u32,
+    pub revenue: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForecastInputs {
+    /// Consecutive months, oldest first, ending with the last complete month
+    pub history: Vec<MonthRevenue>,
+    /// Prices of accepted bookings in the target month
+    pub booked_prices: Vec<f64>,
+    /// Past accepted bookings that were completed, out of those that reached
+    /// a final status
+    pub completed: u32,
+    pub closed: u32,
+    /// 1-12
+    pub target_month: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forecast {
+    pub expected: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub committed: f64,
+    pub baseline: f64,
+    pub completion_rate: f64,
+    pub seasonality: f64,
+}
+
+/// Share of accepted bookings that get completed, with Laplace smoothing so a
+/// new artist starts at 50% rather than 0% or 100%
+pub fn completion_rate(completed: u32, closed: u32) -> f64 {
+    let completed = completed.min(closed);
+    (completed as f64 + 1.0) / (closed as f64 + 2.0)
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn sample_sd(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values)?;
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Revenue in `month` relative to the average month. Each year of data for
+/// that month counts once against one pseudo-year at 1.0, so a single odd
+/// December doesn't swing the forecast.
+pub fn seasonality(history: &[MonthRevenue], month: u32) -> f64 {
+    let overall = mean(&history.iter().map(|m| m.revenue).collect::<Vec<_>>()).unwrap_or(0.0);
+    if overall <= 0.0 {
+        return 1.0;
+    }
+
+    let same_month: Vec<f64> = history
+        .iter()
+        .filter(|m| m.month == month)
+        .map(|m| m.revenue)
+        .collect();
+    match mean(&same_month) {
+        Some(same) => {
+            let n = same_month.len() as f64;
+            (n * (same / overall) + 1.0) / (n + 1.0)
+        }
+        None => 1.0,
+    }
+}
+
+pub fn forecast(inputs: &ForecastInputs) -> Forecast {
+    let rate = completion_rate(inputs.completed, inputs.closed);
+
+    let committed = rate * inputs.booked_prices.iter().sum::<f64>();
+    let committed_sd =
+        (rate * (1.0 - rate) * inputs.booked_prices.iter().map(|p| p * p).sum::<f64>()).sqrt();
+
+    // Remove each month's seasonality before averaging, then apply the
+    // target month's
+    let deseasonalized: Vec<f64> = inputs
+        .history
+        .iter()
+        .map(|m| m.revenue / seasonality(&inputs.history, m.month))
+        .collect();
+    let recent = &deseasonalized[deseasonalized.len().saturating_sub(RECENT_MONTHS)..];
+    let season = seasonality(&inputs.history, inputs.target_month);
+    let level = mean(recent).unwrap_or(0.0);
+    let baseline = level * season;
+    let baseline_sd = sample_sd(&deseasonalized).unwrap_or(level * FALLBACK_RELATIVE_SD) * season;
+
+    let (expected, sd) = if committed >= baseline {
+        (committed, committed_sd)
+    } else {
+        (baseline, baseline_sd)
+    };
+
+    // Whatever the month looks like, it won't fall far below what's booked
+    let floor = (committed - Z_SCORE * committed_sd).max(0.0);
+
+    Forecast {
+        expected,
+        lower: (expected - Z_SCORE * sd).max(floor),
+        upper: expected + Z_SCORE * sd,
+        committed,
+        baseline,
+        completion_rate: rate,
+        seasonality: season,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(revenues: &[(u32, f64)]) -> Vec<MonthRevenue> {
+        revenues
+            .iter()
+            .map(|&(month, revenue)| MonthRevenue { month, revenue })
+            .collect()
+    }
+
+    /// Two years of months at `level`, with December at `december`
+    fn two_years(level: f64, december: f64) -> Vec<MonthRevenue> {
+        (0..24)
+            .map(|i| {
+                let month = i % 12 + 1;
+                MonthRevenue {
+                    month,
+                    revenue: if month == 12 { december } else { level },
+                }
+            })
+            .collect()
+    }
+
+    fn inputs(history: Vec<MonthRevenue>, booked_prices: Vec<f64>) -> ForecastInputs {
+        ForecastInputs {
+            history,
+            booked_prices,
+            completed: 8,
+            closed: 10,
+            target_month: 1,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    fn assert_interval(forecast: &Forecast) {
+        assert!(forecast.lower >= 0.0, "{:?}", forecast);
+        assert!(forecast.lower <= forecast.expected, "{:?}", forecast);
+        assert!(forecast.expected <= forecast.upper, "{:?}", forecast);
+    }
+
+    #[test]
+    fn completion_rate_without_closed_bookings_is_even() {
+        assert_close(completion_rate(0, 0), 0.5);
+    }
+
+    #[test]
+    fn completion_rate_is_smoothed() {
+        assert_close(completion_rate(3, 3), 0.8);
+        assert_close(completion_rate(0, 3), 0.2);
+        assert_close(completion_rate(8, 10), 0.75);
+        // More completed than closed can't push the rate past the all-completed one
+        assert_close(completion_rate(5, 3), completion_rate(3, 3));
+    }
+
+    #[test]
+    fn seasonality_without_history_is_neutral() {
+        assert_close(seasonality(&[], 6), 1.0);
+        assert_close(seasonality(&history(&[(1, 0.0), (2, 0.0)]), 1), 1.0);
+    }
+
+    #[test]
+    fn seasonality_for_a_month_with_no_history_is_neutral() {
+        let history = history(&[(1, 100.0), (2, 300.0), (3, 200.0)]);
+        assert_close(seasonality(&history, 7), 1.0);
+    }
+
+    #[test]
+    fn seasonality_shrinks_towards_one() {
+        // One December at twice the average month
+        let one_year = history(&[(11, 100.0), (12, 200.0), (1, 0.0)]);
+        assert_close(seasonality(&one_year, 12), 1.5);
+
+        // Two busy Decembers count for more than one
+        let history = two_years(100.0, 300.0);
+        let overall = (22.0 * 100.0 + 2.0 * 300.0) / 24.0;
+        assert_close(
+            seasonality(&history, 12),
+            (2.0 * 300.0 / overall + 1.0) / 3.0,
+        );
+        assert!(seasonality(&history, 12) > seasonality(&one_year, 12));
+        assert!(seasonality(&history, 6) < 1.0);
+    }
+
+    #[test]
+    fn forecast_with_no_history_uses_bookings() {
+        let forecast = forecast(&inputs(Vec::new(), vec![200.0, 300.0]));
+        assert_close(forecast.baseline, 0.0);
+        assert_close(forecast.seasonality, 1.0);
+        assert_close(forecast.completion_rate, 0.75);
+        assert_close(forecast.committed, 375.0);
+        assert_close(forecast.expected, 375.0);
+        assert_interval(&forecast);
+    }
+
+    #[test]
+    fn forecast_with_nothing_at_all_is_zero() {
+        let forecast = forecast(&inputs(Vec::new(), Vec::new()));
+        assert_close(forecast.expected, 0.0);
+        assert_close(forecast.lower, 0.0);
+        assert_close(forecast.upper, 0.0);
+    }
+
+    #[test]
+    fn forecast_with_one_month_of_history_assumes_a_wide_spread() {
+        let forecast = forecast(&inputs(history(&[(12, 1000.0)]), Vec::new()));
+        assert_close(forecast.baseline, 1000.0);
+        assert_close(forecast.expected, 1000.0);
+        assert_close(
+            forecast.upper,
+            1000.0 + Z_SCORE * 1000.0 * FALLBACK_RELATIVE_SD,
+        );
+        assert_interval(&forecast);
+    }
+
+    #[test]
+    fn forecast_takes_the_larger_estimate() {
+        let history = two_years(1000.0, 1000.0);
+
+        let quiet = forecast(&inputs(history.clone(), vec![100.0]));
+        assert_close(quiet.expected, quiet.baseline);
+        assert!(quiet.committed < quiet.baseline);
+
+        let busy = forecast(&inputs(history, vec![1000.0, 1000.0]));
+        assert_close(busy.expected, busy.committed);
+        assert!(busy.committed > busy.baseline);
+        // Booked work keeps the low end up
+        assert!(busy.lower >= busy.committed - Z_SCORE * 1000.0);
+    }
+
+    #[test]
+    fn forecast_scales_the_baseline_by_the_target_month() {
+        let mut december = inputs(two_years(1000.0, 3000.0), Vec::new());
+        december.target_month = 12;
+        let december = forecast(&december);
+        let june = forecast(&ForecastInputs {
+            target_month: 6,
+            ..inputs(two_years(1000.0, 3000.0), Vec::new())
+        });
+        assert!(december.baseline > june.baseline);
+        assert!(december.seasonality > 1.0 && june.seasonality < 1.0);
+    }
+
+    #[test]
+    fn interval_contains_the_estimate_and_stays_positive() {
+        let histories = [
+            Vec::new(),
+            history(&[(3, 0.0)]),
+            history(&[(3, 50.0)]),
+            history(&[(3, -200.0), (4, 100.0)]),
+            history(&[(1, 10.0), (2, 5000.0), (3, 0.0), (4, 20.0)]),
+            two_years(1000.0, 4000.0),
+            two_years(0.0, 0.0),
+        ];
+        let bookings = [
+            Vec::new(),
+            vec![0.0],
+            vec![150.0],
+            vec![100.0, 2500.0, 400.0],
+        ];
+        for history in &histories {
+            for booked_prices in &bookings {
+                for (completed, closed) in [(0, 0), (0, 5), (5, 5), (3, 10)] {
+                    for target_month in [1, 3, 6, 12] {
+                        let forecast = forecast(&ForecastInputs {
+                            history: history.clone(),
+                            booked_prices: booked_prices.clone(),
+                            completed,
+                            closed,
+                            target_month,
+                        });
+                        assert_interval(&forecast);
+                    }
+                }
+            }
+        }
+    }
+}
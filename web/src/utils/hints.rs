@@ -0,0 +1,156 @@
+//! Empty-state and onboarding hints for the artist dashboard. The facts are
+//! loaded by `db::hint_repository`; this module decides which hints apply and
+//! on which page, so every view words the same gap the same way.
+
+use crate::db::entities::DashboardHint;
+
+/// Dashboard pages that show hints
+pub const HINT_PAGES: [&str; 4] = ["home", "calendar", "requests", "settings"];
+
+/// Every hint's key, for validating dismissals
+pub const HINT_KEYS: [&str; 6] = [
+    "no_availability",
+    "no_photos",
+    "no_pricing",
+    "no_requests",
+    "no_questionnaire",
+    "no_auto_reply",
+];
+
+const SETTINGS_LINK: &str = "/artist/dashboard/settings";
+const QUESTIONNAIRE_LINK: &str = "/artist/dashboard/questionnaire";
+
+/// What the hints are worked out from
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HintFacts {
+    pub artist_id: i32,
+    /// Days of the week with business hours
+    pub open_days: i64,
+    /// Dates from today on explicitly marked available
+    pub upcoming_open_dates: i64,
+    pub photo_count: i64,
+    pub has_pricing: bool,
+    pub question_count: i64,
+    pub request_count: i64,
+    pub pending_requests: i64,
+    pub auto_reply_enabled: bool,
+}
+
+struct Hint {
+    key: &'static str,
+    pages: &'static [&'static str],
+    severity: &'static str,
+    title: &'static str,
+    message: String,
+    link: String,
+    link_label: &'static str,
+}
+
+/// Every hint that applies to the artist, warnings first
+fn applicable(facts: &HintFacts) -> Vec<Hint> {
+    let mut hints = Vec::new();
+
+    if facts.open_days == 0 && facts.upcoming_open_dates == 0 {
+        hints.push(Hint {
+            key: "no_availability",
+            pages: &["home", "calendar"],
+            severity: "warning",
+            title: "No availability set — clients can't book you",
+            message: "Set your business hours or open specific dates on your calendar. Until \
+                      then no dates show as bookable."
+                .to_string(),
+            link: SETTINGS_LINK.to_string(),
+            link_label: "Set Business Hours",
+        });
+    }
+    if facts.photo_count == 0 {
+        hints.push(Hint {
+            key: "no_photos",
+            pages: &["home", "settings"],
+            severity: "warning",
+            title: "No portfolio photos yet",
+            message: "Clients judge fit from your work first, and profiles without photos \
+                      rarely get requests."
+                .to_string(),
+            link: SETTINGS_LINK.to_string(),
+            link_label: "Add Photos",
+        });
+    }
+    if !facts.has_pricing {
+        hints.push(Hint {
+            key: "no_pricing",
+            pages: &["home", "settings"],
+            severity: "tip",
+            title: "Add your rates",
+            message: "A minimum charge or hourly rate tells clients what to expect before \
+                      they ask."
+                .to_string(),
+            link: SETTINGS_LINK.to_string(),
+            link_label: "Set Pricing",
+        });
+    }
+    if facts.request_count == 0 {
+        hints.push(Hint {
+            key: "no_requests",
+            pages: &["requests"],
+            severity: "tip",
+            title: "No booking requests yet",
+            message: "Requests clients send from your profile land here. Share your profile \
+                      link to get the first one."
+                .to_string(),
+            link: format!("/artist/{}", facts.artist_id),
+            link_label: "View Your Profile",
+        });
+    }
+    if facts.question_count == 0 {
+        hints.push(Hint {
+            key: "no_questionnaire",
+            pages: &["requests", "settings"],
+            severity: "tip",
+            title: "Ask clients the right questions",
+            message: "A booking questionnaire collects placement, size and references with \
+                      each request, so you aren't chasing them by message."
+                .to_string(),
+            link: QUESTIONNAIRE_LINK.to_string(),
+            link_label: "Build Questionnaire",
+        });
+    }
+    if facts.pending_requests > 0 && !facts.auto_reply_enabled {
+        hints.push(Hint {
+            key: "no_auto_reply",
+            pages: &["requests"],
+            severity: "tip",
+            title: "Let clients know their request arrived",
+            message: format!(
+                "{} waiting on you. An auto-reply answers new requests straight away while \
+                 you get to them.",
+                match facts.pending_requests {
+                    1 => "1 request is".to_string(),
+                    n => format!("{} requests are", n),
+                }
+            ),
+            link: SETTINGS_LINK.to_string(),
+            link_label: "Set Up Auto-Reply",
+        });
+    }
+
+    hints.sort_by_key(|hint| hint.severity != "warning");
+    hints
+}
+
+/// The hints for `page`, leaving out those in `dismissed`
+pub fn hints_for_page(facts: &HintFacts, page: &str, dismissed: &[String]) -> Vec<DashboardHint> {
+    applicable(facts)
+        .into_iter()
+        .filter(|hint| hint.pages.contains(&page))
+        .filter(|hint| !dismissed.iter().any(|key| key == hint.key))
+        .map(|hint| DashboardHint {
+            key: hint.key.to_string(),
+            severity: hint.severity.to_string(),
+            title: hint.title.to_string(),
+            message: hint.message,
+            link: hint.link,
+            link_label: hint.link_label.to_string(),
+        })
+        .collect()
+}
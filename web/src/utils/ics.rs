@@ -0,0 +1,599 @@
+//! Calendars in iCalendar (RFC 5545) form: rendering the feeds artists
+//! subscribe to, and reading the busy time out of calendars they import.
+
+use availability::BusyBlock;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use std::collections::HashSet;
+
+/// Event start/end. Times are floating (no timezone), so calendar apps show
+/// them in the viewer's local time, the same way the artist entered them.
+pub enum IcsTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+pub struct IcsEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub start: IcsTime,
+    pub end: IcsTime,
+    /// e.g. `FREQ=WEEKLY;BYDAY=MO`
+    pub rrule: Option<String>,
+    /// Dates a recurring event skips
+    pub exceptions: Vec<NaiveDate>,
+    /// Whether the event should show as busy in free/busy views
+    pub busy: bool,
+}
+
+/// Escapes TEXT values (RFC 5545 3.3.11)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line to 75 octets, continuation lines starting with a space
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn time_property(name: &str, time: &IcsTime) -> String {
+    match time {
+        IcsTime::Date(date) => format!("{};VALUE=DATE:{}", name, date.format("%Y%m%d")),
+        IcsTime::DateTime(datetime) => format!("{}:{}", name, datetime.format("%Y%m%dT%H%M%S")),
+    }
+}
+
+pub fn render_calendar(name: &str, events: &[IcsEvent]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//tatteau//calendar feed//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "METHOD:PUBLISH");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(name)));
+
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", event.uid));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(&mut out, &time_property("DTSTART", &event.start));
+        push_line(&mut out, &time_property("DTEND", &event.end));
+        if let Some(rrule) = &event.rrule {
+            push_line(&mut out, &format!("RRULE:{}", rrule));
+        }
+        for date in &event.exceptions {
+            let exception = match &event.start {
+                IcsTime::Date(_) => IcsTime::Date(*date),
+                IcsTime::DateTime(start) => IcsTime::DateTime(date.and_time(start.time())),
+            };
+            push_line(&mut out, &time_property("EXDATE", &exception));
+        }
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(description) = &event.description {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape(description)));
+        }
+        push_line(
+            &mut out,
+            if event.busy {
+                "TRANSP:OPAQUE"
+            } else {
+                "TRANSP:TRANSPARENT"
+            },
+        );
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+// Importing
+
+/// A content line, unfolded: `NAME;PARAM=value:VALUE`
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Content lines with folded continuations joined back on
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')) {
+            Some(rest) => {
+                if let Some(last) = lines.last_mut() {
+                    last.push_str(rest);
+                }
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // The value starts after the first colon outside a quoted parameter
+    let mut quoted = false;
+    let (colon, _) = line.char_indices().find(|(_, c)| {
+        if *c == '"' {
+            quoted = !quoted;
+        }
+        *c == ':' && !quoted
+    })?;
+
+    let mut head = line[..colon].split(';');
+    let name = head.next()?.trim().to_ascii_uppercase();
+    let params = head
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: line[colon + 1..].trim().to_string(),
+    })
+}
+
+/// The zone a date-time is in
+#[derive(Clone, Copy)]
+enum Zone {
+    Utc,
+    Named(Tz),
+    /// No zone given, or one we don't know: taken as the artist's own
+    Floating,
+}
+
+/// `datetime` in `zone`, as wall-clock time in `local`
+fn to_local(datetime: NaiveDateTime, zone: Zone, local: Tz) -> NaiveDateTime {
+    match zone {
+        Zone::Utc => Utc
+            .from_utc_datetime(&datetime)
+            .with_timezone(&local)
+            .naive_local(),
+        Zone::Named(tz) => tz
+            .from_local_datetime(&datetime)
+            .earliest()
+            .map_or(datetime, |time| time.with_timezone(&local).naive_local()),
+        Zone::Floating => datetime,
+    }
+}
+
+/// A DATE or DATE-TIME value: `20250301`, `20250301T140000` (in its TZID,
+/// if any) or `20250301T140000Z`
+fn parse_time(value: &str, property: &Property) -> Option<(IcsTime, Zone)> {
+    let value = value.trim();
+    if property.param("VALUE") == Some("DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((IcsTime::Date(date), Zone::Floating));
+    }
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let datetime = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let zone = if utc {
+        Zone::Utc
+    } else {
+        property
+            .param("TZID")
+            .and_then(|tzid| tzid.parse().ok())
+            .map_or(Zone::Floating, Zone::Named)
+    };
+    Some((IcsTime::DateTime(datetime), zone))
+}
+
+/// An occurrence's start in the artist's zone, all-day ones at midnight, for
+/// matching exceptions and overrides against
+fn local_start(time: &IcsTime, zone: Zone, local: Tz) -> NaiveDateTime {
+    match time {
+        IcsTime::Date(date) => date.and_time(NaiveTime::MIN),
+        IcsTime::DateTime(datetime) => to_local(*datetime, zone, local),
+    }
+}
+
+/// A DURATION value such as `PT1H30M` or `P1D`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let rest = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match c {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    _ => Duration::seconds(n),
+                };
+            }
+            _ => return None,
+        }
+    }
+    Some(total)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The parts of an RRULE we expand. Rules using any other part only block
+/// their first occurrence.
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDate>,
+    /// BYDAY entries as `(nth, weekday)`, `nth` 0 for every one
+    weekdays: Vec<(i32, Weekday)>,
+    month_days: Vec<i32>,
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    Some(match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// `MO`, `2TU` or `-1FR`
+fn parse_by_day(value: &str) -> Option<(i32, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let weekday = parse_weekday(value.get(split..)?)?;
+    let nth = match value.get(..split)? {
+        "" => 0,
+        nth => nth.trim_start_matches('+').parse().ok()?,
+    };
+    Some((nth, weekday))
+}
+
+fn parse_rule(value: &str) -> Option<Rule> {
+    let mut frequency = None;
+    let mut rule = Rule {
+        frequency: Frequency::Daily,
+        interval: 1,
+        count: None,
+        until: None,
+        weekdays: Vec::new(),
+        month_days: Vec::new(),
+    };
+    for part in value.split(';').filter(|part| !part.is_empty()) {
+        let (key, value) = part.split_once('=')?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                frequency = Some(match value {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    "YEARLY" => Frequency::Yearly,
+                    _ => return None,
+                })
+            }
+            "INTERVAL" => rule.interval = value.parse().ok().filter(|n| *n > 0)?,
+            "COUNT" => rule.count = Some(value.parse().ok()?),
+            "UNTIL" => {
+                rule.until = Some(NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()?)
+            }
+            "BYDAY" => rule.weekdays = value.split(',').map(parse_by_day).collect::<Option<_>>()?,
+            "BYMONTHDAY" => {
+                rule.month_days = value
+                    .split(',')
+                    .map(|day| day.parse().ok())
+                    .collect::<Option<_>>()?
+            }
+            "WKST" => {}
+            _ => return None,
+        }
+    }
+    rule.frequency = frequency?;
+    Some(rule)
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = match date.month() {
+        12 => (date.year() + 1, 1),
+        month => (date.year(), month + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|next| next.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// Whether `date` is the `nth` of its weekday in the month, counting back
+/// from the end when negative
+fn is_nth_weekday(date: NaiveDate, nth: i32) -> bool {
+    match nth {
+        0 => true,
+        nth if nth > 0 => ((date.day() - 1) / 7 + 1) as i32 == nth,
+        nth => ((days_in_month(date) - date.day()) / 7 + 1) as i32 == -nth,
+    }
+}
+
+impl Rule {
+    /// Whether the rule, for an event first on `start`, falls on `date`
+    fn matches(&self, date: NaiveDate, start: NaiveDate) -> bool {
+        let interval = i64::from(self.interval);
+        let on_weekday = |weekdays: &[(i32, Weekday)]| {
+            weekdays
+                .iter()
+                .any(|(nth, weekday)| date.weekday() == *weekday && is_nth_weekday(date, *nth))
+        };
+        match self.frequency {
+            Frequency::Daily => {
+                (date - start).num_days() % interval == 0
+                    && (self.weekdays.is_empty() || on_weekday(&self.weekdays))
+            }
+            Frequency::Weekly => {
+                let week = |day: NaiveDate| {
+                    day - Duration::days(day.weekday().num_days_from_monday().into())
+                };
+                let on_day = if self.weekdays.is_empty() {
+                    date.weekday() == start.weekday()
+                } else {
+                    self.weekdays
+                        .iter()
+                        .any(|(_, weekday)| date.weekday() == *weekday)
+                };
+                on_day && (week(date) - week(start)).num_weeks() % interval == 0
+            }
+            Frequency::Monthly => {
+                let months = i64::from(date.year() - start.year()) * 12 + i64::from(date.month())
+                    - i64::from(start.month());
+                let on_day = if !self.weekdays.is_empty() {
+                    on_weekday(&self.weekdays)
+                } else if !self.month_days.is_empty() {
+                    let last = days_in_month(date) as i32;
+                    self.month_days.iter().any(|day| {
+                        let day = if *day < 0 { last + day + 1 } else { *day };
+                        day == date.day() as i32
+                    })
+                } else {
+                    date.day() == start.day()
+                };
+                on_day && months % interval == 0
+            }
+            Frequency::Yearly => {
+                date.month() == start.month()
+                    && date.day() == start.day()
+                    && i64::from(date.year() - start.year()) % interval == 0
+            }
+        }
+    }
+}
+
+/// An event read from an imported calendar
+struct ImportedEvent {
+    uid: String,
+    start: IcsTime,
+    zone: Zone,
+    /// How long each occurrence lasts
+    length: Duration,
+    rule: Option<Rule>,
+    /// Occurrence starts the event skips, in the artist's zone
+    exceptions: Vec<NaiveDateTime>,
+    /// For an event replacing one occurrence of a recurring event, that
+    /// occurrence's start in the artist's zone
+    recurrence_id: Option<NaiveDateTime>,
+    /// Cancelled events and ones marked free block nothing
+    busy: bool,
+}
+
+fn parse_event(properties: &[Property], local: Tz) -> Option<ImportedEvent> {
+    let find = |name: &str| properties.iter().find(|property| property.name == name);
+
+    let start_property = find("DTSTART")?;
+    let (start, zone) = parse_time(&start_property.value, start_property)?;
+    let end = find("DTEND").and_then(|property| {
+        let (end, end_zone) = parse_time(&property.value, property)?;
+        Some(local_start(&end, end_zone, local))
+    });
+    let length = match (end, &start) {
+        (Some(end), _) => end - local_start(&start, zone, local),
+        (None, _) if find("DURATION").is_some() => {
+            parse_duration(&find("DURATION")?.value).unwrap_or_else(Duration::zero)
+        }
+        (None, IcsTime::Date(_)) => Duration::days(1),
+        (None, IcsTime::DateTime(_)) => Duration::zero(),
+    };
+
+    let exceptions = properties
+        .iter()
+        .filter(|property| property.name == "EXDATE")
+        .flat_map(|property| {
+            property.value.split(',').filter_map(move |value| {
+                let (time, zone) = parse_time(value, property)?;
+                Some(local_start(&time, zone, local))
+            })
+        })
+        .collect();
+    let recurrence_id = find("RECURRENCE-ID").and_then(|property| {
+        let (time, zone) = parse_time(&property.value, property)?;
+        Some(local_start(&time, zone, local))
+    });
+
+    let cancelled = find("STATUS").is_some_and(|status| status.value == "CANCELLED");
+    let free = find("TRANSP").is_some_and(|transp| transp.value == "TRANSPARENT");
+
+    Some(ImportedEvent {
+        uid: find("UID").map(|uid| uid.value.clone()).unwrap_or_default(),
+        start,
+        zone,
+        length,
+        rule: find("RRULE").and_then(|rrule| parse_rule(&rrule.value)),
+        exceptions,
+        recurrence_id,
+        busy: !cancelled && !free,
+    })
+}
+
+fn parse_events(ics: &str, local: Tz) -> Vec<ImportedEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    // Components inside the event being read, such as alarms
+    let mut nested = 0;
+
+    for property in unfold(ics).iter().filter_map(|line| parse_property(line)) {
+        match property.name.as_str() {
+            "BEGIN" if current.is_none() => {
+                if property.value.eq_ignore_ascii_case("VEVENT") {
+                    current = Some(Vec::new());
+                }
+            }
+            "BEGIN" => nested += 1,
+            "END" if nested > 0 => nested -= 1,
+            "END" => {
+                if let Some(properties) = current.take() {
+                    events.extend(parse_event(&properties, local));
+                }
+            }
+            _ => {
+                if let (Some(properties), 0) = (current.as_mut(), nested) {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Starts of the event's occurrences up to `to`, in the event's own zone
+fn occurrence_starts(event: &ImportedEvent, to: NaiveDate) -> Vec<NaiveDateTime> {
+    let first = match event.start {
+        IcsTime::Date(date) => date.and_time(NaiveTime::MIN),
+        IcsTime::DateTime(datetime) => datetime,
+    };
+    let Some(rule) = &event.rule else {
+        return vec![first];
+    };
+
+    let last = rule.until.map_or(to, |until| until.min(to));
+    let mut starts = Vec::new();
+    let mut seen = 0;
+    for date in first.date().iter_days().take_while(|date| *date <= last) {
+        if !rule.matches(date, first.date()) {
+            continue;
+        }
+        seen += 1;
+        if rule.count.is_some_and(|count| seen > count) {
+            break;
+        }
+        starts.push(date.and_time(first.time()));
+    }
+    starts
+}
+
+/// The time an imported calendar has the artist busy on each day in
+/// `from..=to`, in the artist's zone `local`. Events marked free or
+/// cancelled are left out; recurring events are expanded, skipping their
+/// exceptions and the occurrences moved by an override.
+pub fn busy_blocks(ics: &str, local: Tz, from: NaiveDate, to: NaiveDate) -> Vec<BusyBlock> {
+    let events = parse_events(ics, local);
+    let overridden: HashSet<(&str, NaiveDateTime)> = events
+        .iter()
+        .filter_map(|event| Some((event.uid.as_str(), event.recurrence_id?)))
+        .collect();
+
+    let mut blocks = Vec::new();
+    for event in events.iter().filter(|event| event.busy) {
+        for start in occurrence_starts(event, to) {
+            let key = match event.start {
+                IcsTime::Date(_) => start,
+                IcsTime::DateTime(_) => to_local(start, event.zone, local),
+            };
+            let replaced =
+                event.recurrence_id.is_none() && overridden.contains(&(event.uid.as_str(), key));
+            if replaced || event.exceptions.contains(&key) {
+                continue;
+            }
+
+            let end = key + event.length;
+            match event.start {
+                IcsTime::Date(_) => {
+                    for date in key
+                        .date()
+                        .iter_days()
+                        .take_while(|date| date.and_time(NaiveTime::MIN) < end && *date <= to)
+                    {
+                        if date >= from {
+                            blocks.push(BusyBlock { date, hours: None });
+                        }
+                    }
+                }
+                IcsTime::DateTime(_) => {
+                    for date in key
+                        .date()
+                        .iter_days()
+                        .take_while(|date| *date <= end.date() && *date <= to)
+                    {
+                        let from_time = if date == key.date() {
+                            key.time()
+                        } else {
+                            NaiveTime::MIN
+                        };
+                        let to_time = if date == end.date() {
+                            end.time()
+                        } else {
+                            NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN)
+                        };
+                        if date >= from && to_time > from_time {
+                            blocks.push(BusyBlock {
+                                date,
+                                hours: Some((from_time, to_time)),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    blocks.sort_by_key(|block| (block.date, block.hours));
+    blocks.dedup();
+    blocks
+}
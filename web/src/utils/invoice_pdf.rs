@@ -0,0 +1,168 @@
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+
+use crate::db::entities::Invoice;
+use crate::utils::money::format_money;
+
+const PAGE_WIDTH: f32 = 595.0; // A4
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+
+/// Encodes text for the standard Helvetica font (WinAnsiEncoding), replacing
+/// characters it can't represent.
+pub(crate) fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '€' => 0x80,
+            '‘' | '’' => b'\'',
+            '“' | '”' => b'"',
+            '–' | '—' => b'-',
+            c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Naive word wrap, good enough for Helvetica at invoice sizes
+pub(crate) fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + word.len() + 1 > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+struct Page {
+    content: Content,
+    y: f32,
+}
+
+impl Page {
+    fn text(&mut self, font: &[u8], size: f32, x: f32, text: &str) {
+        let encoded = win_ansi(text);
+        self.content
+            .begin_text()
+            .set_font(Name(font), size)
+            .next_line(x, self.y)
+            .show(Str(&encoded))
+            .end_text();
+    }
+
+    fn line(&mut self, font: &[u8], size: f32, text: &str) {
+        self.text(font, size, MARGIN, text);
+        self.y -= size * 1.5;
+    }
+
+    fn row(&mut self, label: &str, value: &str, bold: bool) {
+        let font: &[u8] = if bold { b"F2" } else { b"F1" };
+        self.text(font, 11.0, PAGE_WIDTH - MARGIN - 220.0, label);
+        self.text(font, 11.0, PAGE_WIDTH - MARGIN - 90.0, value);
+        self.y -= 18.0;
+    }
+}
+
+/// Renders a single-page A4 invoice
+pub fn render_invoice_pdf(invoice: &Invoice) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let page_id = Ref::new(3);
+    let font_id = Ref::new(4);
+    let bold_font_id = Ref::new(5);
+    let content_id = Ref::new(6);
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids([page_id]).count(1);
+
+    let mut page_writer = pdf.page(page_id);
+    page_writer
+        .media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+        .parent(page_tree_id)
+        .contents(content_id);
+    let mut resources = page_writer.resources();
+    let mut fonts = resources.fonts();
+    fonts.pair(Name(b"F1"), font_id);
+    fonts.pair(Name(b"F2"), bold_font_id);
+    drop(fonts);
+    drop(resources);
+    drop(page_writer);
+
+    pdf.type1_font(font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_font_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let money = |amount: f64| format_money(amount, &invoice.currency);
+    let mut page = Page {
+        content: Content::new(),
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    page.line(b"F2", 22.0, "INVOICE");
+    page.line(
+        b"F1",
+        11.0,
+        &format!("Invoice number: {}", invoice.invoice_code),
+    );
+    page.line(b"F1", 11.0, &format!("Issued: {}", invoice.issued_at));
+    page.y -= 10.0;
+
+    // From
+    page.line(b"F2", 11.0, "From");
+    if let Some(name) = &invoice.business_name {
+        page.line(b"F1", 11.0, name);
+    }
+    if let Some(address) = &invoice.business_address {
+        for address_line in address.lines() {
+            page.line(b"F1", 11.0, address_line);
+        }
+    }
+    if let Some(tax_id) = &invoice.tax_id {
+        page.line(
+            b"F1",
+            11.0,
+            &format!("{} ID: {}", invoice.tax_label, tax_id),
+        );
+    }
+    page.y -= 10.0;
+
+    // Bill to
+    page.line(b"F2", 11.0, "Bill to");
+    page.line(b"F1", 11.0, &invoice.client_name);
+    page.line(b"F1", 11.0, &invoice.client_email);
+    page.y -= 10.0;
+
+    page.line(b"F2", 11.0, "Description");
+    for description_line in wrap(&invoice.description, 85) {
+        page.line(b"F1", 11.0, &description_line);
+    }
+    page.y -= 20.0;
+
+    page.row("Subtotal", &money(invoice.subtotal), false);
+    page.row(
+        &format!("{} ({:.2}%)", invoice.tax_label, invoice.tax_rate),
+        &money(invoice.tax_amount),
+        false,
+    );
+    page.row("Total", &money(invoice.total), true);
+    page.row("Paid", &money(invoice.amount_paid), false);
+    page.row(
+        "Balance due",
+        &money((invoice.total - invoice.amount_paid).max(0.0)),
+        true,
+    );
+
+    pdf.stream(content_id, &page.content.finish());
+    pdf.finish()
+}
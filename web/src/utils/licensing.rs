@@ -0,0 +1,124 @@
+//! Artist license rules: which states a license can be issued by, how
+//! license numbers are stored and when expiry reminders are due. Licenses
+//! are stored by `db::license_repository`; reminders are sent by
+//! `licensing.rs`.
+
+/// States (and DC) a license can be issued by, as stored in
+/// `locations.state`
+pub const US_STATES: [&str; 51] = [
+    "Alabama",
+    "Alaska",
+    "Arizona",
+    "Arkansas",
+    "California",
+    "Colorado",
+    "Connecticut",
+    "Delaware",
+    "District of Columbia",
+    "Florida",
+    "Georgia",
+    "Hawaii",
+    "Idaho",
+    "Illinois",
+    "Indiana",
+    "Iowa",
+    "Kansas",
+    "Kentucky",
+    "Louisiana",
+    "Maine",
+    "Maryland",
+    "Massachusetts",
+    "Michigan",
+    "Minnesota",
+    "Mississippi",
+    "Missouri",
+    "Montana",
+    "Nebraska",
+    "Nevada",
+    "New Hampshire",
+    "New Jersey",
+    "New Mexico",
+    "New York",
+    "North Carolina",
+    "North Dakota",
+    "Ohio",
+    "Oklahoma",
+    "Oregon",
+    "Pennsylvania",
+    "Rhode Island",
+    "South Carolina",
+    "South Dakota",
+    "Tennessee",
+    "Texas",
+    "Utah",
+    "Vermont",
+    "Virginia",
+    "Washington",
+    "West Virginia",
+    "Wisconsin",
+    "Wyoming",
+];
+
+pub const VERIFICATION_STATUSES: [&str; 3] = ["pending", "verified", "rejected"];
+
+/// Days before expiry that reminders go out, latest last. 0 is the day it
+/// expires.
+pub const REMINDER_DAYS: [i32; 3] = [30, 7, 0];
+
+/// Longest license number accepted
+const MAX_LICENSE_NUMBER_LENGTH: usize = 64;
+
+/// The issuing state as stored, matched case-insensitively
+pub fn normalize_state(state: &str) -> Option<&'static str> {
+    let state = state.trim();
+    US_STATES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(state))
+        .copied()
+}
+
+/// The license number as stored: trimmed, upper case, inner whitespace
+/// collapsed
+pub fn normalize_license_number(number: &str) -> Result<String, String> {
+    let number = number
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_uppercase();
+    if number.is_empty() {
+        return Err("Enter your license number".to_string());
+    }
+    if number.len() > MAX_LICENSE_NUMBER_LENGTH {
+        return Err("That license number is too long".to_string());
+    }
+    if !number
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '/' | '.'))
+    {
+        return Err("License numbers can only have letters, digits, spaces, - / and .".to_string());
+    }
+    Ok(number)
+}
+
+/// The reminder due for a license expiring in `days_left` days, given the
+/// last one sent. Only the nearest is sent, so a license added a week before
+/// it expires gets the 7 day reminder and not the 30 day one as well.
+pub fn reminder_due(days_left: i32, last_sent: Option<i32>) -> Option<i32> {
+    let due = REMINDER_DAYS
+        .iter()
+        .copied()
+        .filter(|days| days_left <= *days)
+        .min()?;
+    match last_sent {
+        Some(sent) if sent <= due => None,
+        _ => Some(due),
+    }
+}
+
+pub fn status_label(status: &str) -> &'static str {
+    match status {
+        "verified" => "Verified",
+        "rejected" => "Rejected",
+        _ => "Pending review",
+    }
+}
@@ -0,0 +1,135 @@
+//! Artist matching scores. `db::repository::query_matched_artists` scores
+//! each candidate on five factors, each between 0 and 1: how many of the
+//! client's styles the artist covers, how close they are, how well their
+//! pricing fits the budget, how popular their work is and how recently they
+//! posted. A factor only counts when it applies (no location given, no
+//! distance), and the match score is the weighted share of the applicable
+//! factors' points. Every factor's points come back in the explanation so
+//! the match card can show what the artist was matched on.
+//!
+//! Weights: `MATCH_WEIGHT_STYLE` (default 40), `MATCH_WEIGHT_DISTANCE`
+//! (default 20), `MATCH_WEIGHT_PRICE` (default 15), `MATCH_WEIGHT_RATING`
+//! (default 10) and `MATCH_WEIGHT_RECENCY` (default 15). A weight of 0 turns
+//! its factor off.
+
+/// Factors a match is scored on, as named in `ScoreComponent::factor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchFactor {
+    StyleOverlap,
+    Distance,
+    PriceFit,
+    Rating,
+    Recency,
+}
+
+impl MatchFactor {
+    pub const ALL: [MatchFactor; 5] = [
+        MatchFactor::StyleOverlap,
+        MatchFactor::Distance,
+        MatchFactor::PriceFit,
+        MatchFactor::Rating,
+        MatchFactor::Recency,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchFactor::StyleOverlap => "style_overlap",
+            MatchFactor::Distance => "distance",
+            MatchFactor::PriceFit => "price_fit",
+            MatchFactor::Rating => "rating",
+            MatchFactor::Recency => "recency",
+        }
+    }
+
+    pub fn parse(factor: &str) -> Option<MatchFactor> {
+        MatchFactor::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == factor)
+    }
+
+    /// Label for the score breakdown, e.g. `"Style match"`
+    pub fn label(&self) -> &'static str {
+        match self {
+            MatchFactor::StyleOverlap => "Style match",
+            MatchFactor::Distance => "Distance",
+            MatchFactor::PriceFit => "Price fit",
+            MatchFactor::Rating => "Popularity",
+            MatchFactor::Recency => "Recent work",
+        }
+    }
+}
+
+/// Distance at which the distance factor reaches 0
+#[cfg(feature = "ssr")]
+pub const MAX_DISTANCE_MILES: f64 = 100.0;
+
+/// Days since the artist's latest post at which the recency factor reaches 0
+#[cfg(feature = "ssr")]
+pub const STALE_WORK_DAYS: f64 = 365.0;
+
+/// Clients saving an artist's work for the full rating factor. There are no
+/// reviews yet, so saves stand in for ratings.
+#[cfg(feature = "ssr")]
+pub const SAVES_FOR_FULL_RATING: f64 = 25.0;
+
+/// How much each factor counts towards the match score
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Copy)]
+pub struct MatchWeights {
+    pub style_overlap: f64,
+    pub distance: f64,
+    pub price_fit: f64,
+    pub rating: f64,
+    pub recency: f64,
+}
+
+#[cfg(feature = "ssr")]
+impl MatchWeights {
+    /// Weights from the environment, read by [`crate::config`]
+    pub fn get() -> &'static MatchWeights {
+        &crate::config::config().match_weights
+    }
+
+    pub fn weight(&self, factor: MatchFactor) -> f64 {
+        match factor {
+            MatchFactor::StyleOverlap => self.style_overlap,
+            MatchFactor::Distance => self.distance,
+            MatchFactor::PriceFit => self.price_fit,
+            MatchFactor::Rating => self.rating,
+            MatchFactor::Recency => self.recency,
+        }
+    }
+
+    /// Match score out of 100 from the applicable factors' scores, with each
+    /// factor's points out of its weight
+    pub fn score(
+        &self,
+        factors: &[(MatchFactor, Option<f64>)],
+    ) -> (i32, Vec<crate::server::ScoreComponent>) {
+        let mut earned = 0.0;
+        let mut possible = 0.0;
+        let mut components = Vec::new();
+
+        for (factor, score) in factors {
+            let weight = self.weight(*factor);
+            let Some(score) = score.filter(|_| weight > 0.0) else {
+                continue;
+            };
+            let score = score.clamp(0.0, 1.0);
+            earned += weight * score;
+            possible += weight;
+            components.push(crate::server::ScoreComponent {
+                factor: factor.as_str().to_string(),
+                points: (weight * score).round() as i32,
+                max_points: weight.round() as i32,
+            });
+        }
+
+        let match_score = if possible > 0.0 {
+            (earned / possible * 100.0).round() as i32
+        } else {
+            0
+        };
+        (match_score, components)
+    }
+}
@@ -1,2 +1,32 @@
+pub mod appointments;
+pub mod archive;
+pub mod artist_questions;
 pub mod auth;
+pub mod auto_response;
+pub mod completeness;
+#[cfg(feature = "ssr")]
+pub mod consent_pdf;
+pub mod documents;
+pub mod embed;
+pub mod error_triage;
+pub mod experience;
+pub mod export;
+pub mod forecast;
+pub mod hints;
+#[cfg(feature = "ssr")]
+pub mod ics;
+#[cfg(feature = "ssr")]
+pub mod invoice_pdf;
+pub mod licensing;
+pub mod matching;
+pub mod money;
+pub mod pricing;
+pub mod profile;
+pub mod recurrence;
+pub mod reschedule;
+pub mod response_time;
+pub mod short_links;
+#[cfg(feature = "ssr")]
+pub mod source_map;
 pub mod timezone;
+pub mod translation;
@@ -0,0 +1,57 @@
+/// Formats an amount for display in the given ISO 4217 currency,
+/// using that currency's usual symbol placement and separators.
+pub fn format_money(amount: f64, currency: &str) -> String {
+    let negative = amount < 0.0;
+    let cents = (amount.abs() * 100.0).round() as u64;
+    let (whole, frac) = (cents / 100, cents % 100);
+
+    let (thousands_sep, decimal_sep) = match currency {
+        "EUR" => ('.', ','),
+        _ => (',', '.'),
+    };
+
+    let digits = whole.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(thousands_sep);
+        }
+        grouped.push(c);
+    }
+    let number = format!("{}{}{:02}", grouped, decimal_sep, frac);
+    let sign = if negative { "-" } else { "" };
+
+    match currency {
+        "USD" => format!("{}${}", sign, number),
+        "CAD" => format!("{}CA${}", sign, number),
+        "AUD" => format!("{}A${}", sign, number),
+        "GBP" => format!("{}£{}", sign, number),
+        "EUR" => format!("{}{} €", sign, number),
+        other => format!("{}{} {}", sign, number, other),
+    }
+}
+
+/// Currencies without a minor unit, per Stripe's list
+const ZERO_DECIMAL_CURRENCIES: [&str; 16] = [
+    "BIF", "CLP", "DJF", "GNF", "JPY", "KMF", "KRW", "MGA", "PYG", "RWF", "UGX", "VND", "VUV",
+    "XAF", "XOF", "XPF",
+];
+
+/// Amount in the currency's smallest unit (cents for USD), as payment
+/// processors expect it
+pub fn to_minor_units(amount: f64, currency: &str) -> i64 {
+    if ZERO_DECIMAL_CURRENCIES.contains(&currency.to_ascii_uppercase().as_str()) {
+        amount.round() as i64
+    } else {
+        (amount * 100.0).round() as i64
+    }
+}
+
+/// Inverse of [`to_minor_units`]
+pub fn from_minor_units(minor: i64, currency: &str) -> f64 {
+    if ZERO_DECIMAL_CURRENCIES.contains(&currency.to_ascii_uppercase().as_str()) {
+        minor as f64
+    } else {
+        minor as f64 / 100.0
+    }
+}
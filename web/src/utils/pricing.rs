@@ -0,0 +1,90 @@
+//! Price ranges shown to clients. Artists set an hourly rate, a minimum
+//! charge and optional per-style ranges (loaded by `db::pricing_repository`);
+//! this module turns them into the single range shown on match cards, shop
+//! pages and map pins, and checks it against a client's budget.
+
+use crate::db::entities::ArtistPricing;
+
+/// The range an artist quotes. When they priced any of `styles` only those
+/// count, otherwise all their priced styles do. The minimum charge is a
+/// floor, and the hourly rate stands in for the low end when nothing else
+/// is set. `None` bounds are unknown.
+pub fn price_range(pricing: &ArtistPricing, styles: &[String]) -> (Option<f64>, Option<f64>) {
+    let priced: Vec<_> = pricing
+        .style_prices
+        .iter()
+        .filter(|price| price.min_price.is_some() || price.max_price.is_some())
+        .collect();
+    let requested: Vec<_> = priced
+        .iter()
+        .copied()
+        .filter(|price| {
+            styles
+                .iter()
+                .any(|style| style.eq_ignore_ascii_case(&price.style_name))
+        })
+        .collect();
+    let quoted = if requested.is_empty() {
+        priced
+    } else {
+        requested
+    };
+
+    let style_min = quoted.iter().filter_map(|p| p.min_price).reduce(f64::min);
+    let style_max = quoted.iter().filter_map(|p| p.max_price).reduce(f64::max);
+
+    let min = match (style_min, pricing.minimum_charge) {
+        (Some(style_min), Some(minimum)) => Some(style_min.max(minimum)),
+        (style_min, minimum) => style_min.or(minimum).or(pricing.hourly_rate),
+    };
+    let max = match (min, style_max) {
+        (Some(min), Some(max)) => Some(max.max(min)),
+        (_, max) => max,
+    };
+    (min, max)
+}
+
+/// Widens a range to cover another, e.g. to summarize the artists at a shop
+pub fn merge_ranges(
+    a: (Option<f64>, Option<f64>),
+    b: (Option<f64>, Option<f64>),
+) -> (Option<f64>, Option<f64>) {
+    let lower = match (a.0, b.0) {
+        (Some(x), Some(y)) => Some(x.min(y)),
+        (x, y) => x.or(y),
+    };
+    let upper = match (a.1, b.1) {
+        (Some(x), Some(y)) => Some(x.max(y)),
+        (x, y) => x.or(y),
+    };
+    (lower, upper)
+}
+
+/// Whether a quoted range overlaps the client's budget; `None` when the
+/// artist hasn't set any pricing, so they are neither favoured nor dropped
+pub fn within_budget(
+    (min_price, max_price): (Option<f64>, Option<f64>),
+    (budget_min, budget_max): (f64, f64),
+) -> Option<bool> {
+    if min_price.is_none() && max_price.is_none() {
+        return None;
+    }
+    Some(min_price.unwrap_or(0.0) <= budget_max && max_price.unwrap_or(f64::INFINITY) >= budget_min)
+}
+
+/// How well a quoted range fits the client's budget, from 1 when it overlaps
+/// down to 0 when it's a whole budget's top away from it; `None` when the
+/// artist hasn't set any pricing
+pub fn budget_fit(
+    (min_price, max_price): (Option<f64>, Option<f64>),
+    (budget_min, budget_max): (f64, f64),
+) -> Option<f64> {
+    if within_budget((min_price, max_price), (budget_min, budget_max))? {
+        return Some(1.0);
+    }
+    let gap = match min_price {
+        Some(min_price) if min_price > budget_max => min_price - budget_max,
+        _ => budget_min - max_price.unwrap_or(budget_min),
+    };
+    Some((1.0 - gap / budget_max.max(1.0)).clamp(0.0, 1.0))
+}
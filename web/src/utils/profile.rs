@@ -0,0 +1,107 @@
+//! Profile content artists write for their public page: FAQ entries, studio
+//! policies and social links. The bio and policies use the same markdown
+//! subset as documents (see `utils::documents`) and are rendered with
+//! `DocumentBody`, so nothing artists write is ever inserted as HTML. Links
+//! are the one place raw input ends up in an attribute, so they're checked
+//! here before they're stored.
+
+/// Policies an artist can publish (artist_policies.kind), with their labels
+pub const POLICY_KINDS: &[(&str, &str)] = &[
+    ("deposit", "Deposits"),
+    ("cancellation", "Cancellations & rescheduling"),
+    ("touch_up", "Touch-ups"),
+];
+
+/// Longest policy accepted
+pub const MAX_POLICY_CHARS: usize = 2_000;
+
+pub const MAX_FAQ_ENTRIES: usize = 20;
+pub const MAX_FAQ_QUESTION_CHARS: usize = 200;
+pub const MAX_FAQ_ANSWER_CHARS: usize = 2_000;
+
+/// Platforms an artist can link to: key, label and the hosts its links live
+/// on. "website" takes any public https or http address.
+pub const SOCIAL_PLATFORMS: &[(&str, &str, &[&str])] = &[
+    ("instagram", "Instagram", &["instagram.com"]),
+    ("tiktok", "TikTok", &["tiktok.com"]),
+    ("facebook", "Facebook", &["facebook.com", "fb.com"]),
+    ("x", "X", &["x.com", "twitter.com"]),
+    ("youtube", "YouTube", &["youtube.com", "youtu.be"]),
+    ("website", "Website", &[]),
+];
+
+pub const MAX_LINK_CHARS: usize = 300;
+
+pub fn policy_label(kind: &str) -> &'static str {
+    POLICY_KINDS
+        .iter()
+        .find(|(key, _)| *key == kind)
+        .map_or("Policy", |(_, label)| label)
+}
+
+pub fn platform_label(platform: &str) -> &'static str {
+    SOCIAL_PLATFORMS
+        .iter()
+        .find(|(key, _, _)| *key == platform)
+        .map_or("Link", |(_, label, _)| label)
+}
+
+/// The link to store for what an artist typed for `platform`: a full
+/// address on one of the platform's hosts, or for the social platforms just
+/// their handle. Addresses without a scheme get https.
+pub fn normalize_social_link(platform: &str, value: &str) -> Result<String, String> {
+    let Some((_, label, hosts)) = SOCIAL_PLATFORMS.iter().find(|(key, _, _)| *key == platform)
+    else {
+        return Err(format!("Unknown platform {}", platform));
+    };
+
+    let value = value.trim();
+    if value.chars().count() > MAX_LINK_CHARS {
+        return Err(format!("{} link is too long", label));
+    }
+
+    // A bare handle: "@ink.by.jo", or "inkbyjo" without the @ when it
+    // can't be mistaken for an address
+    let handle = value.strip_prefix('@').unwrap_or(value);
+    let is_handle = !handle.is_empty()
+        && (value.starts_with('@') || !handle.contains('.'))
+        && handle
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !hosts.is_empty() && is_handle {
+        return Ok(match platform {
+            "tiktok" => format!("https://{}/@{}", hosts[0], handle),
+            _ => format!("https://{}/{}", hosts[0], handle),
+        });
+    }
+
+    let (scheme, rest) = match value.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_lowercase(), rest),
+        None => ("https".to_string(), value),
+    };
+    if scheme != "https" && scheme != "http" {
+        return Err(format!("{} link must be a web address", label));
+    }
+
+    let host = rest
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    if host.contains('@') || host.contains(':') {
+        return Err(format!("{} isn't a valid {} link", value, label));
+    }
+    let name = host.strip_prefix("www.").unwrap_or(&host);
+    let allowed = if hosts.is_empty() {
+        name.contains('.') && name.parse::<std::net::Ipv4Addr>().is_err()
+    } else {
+        hosts
+            .iter()
+            .any(|h| name == *h || name.ends_with(&format!(".{}", h)))
+    };
+    if !allowed {
+        return Err(format!("{} isn't a valid {} link", value, label));
+    }
+
+    Ok(format!("{}://{}", scheme, rest))
+}
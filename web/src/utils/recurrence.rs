@@ -0,0 +1,123 @@
+//! Recurring rules as stored (`db::entities::RecurringRule`) turned into the
+//! `availability` crate's evaluator, so the booking checks on the server and
+//! the artist's calendar decide which days a rule covers the same way.
+
+use availability::{parse_date, parse_time, Recurrence, RecurringRule};
+
+use crate::db::entities::{RecurrencePattern, RecurringRule as StoredRule};
+
+pub const FREQUENCIES: [(&str, &str); 3] = [
+    ("weekly", "Weekly"),
+    ("monthly", "Monthly"),
+    ("yearly", "Every year"),
+];
+
+pub const RULE_ACTIONS: [&str; 2] = ["available", "blocked"];
+
+fn weekday(day: i32) -> Result<u32, String> {
+    u32::try_from(day)
+        .ok()
+        .filter(|day| *day < 7)
+        .ok_or_else(|| "Days of the week run from 0 (Sunday) to 6".to_string())
+}
+
+/// `MM-DD`
+fn annual_date(value: &str) -> Result<(u32, u32), String> {
+    value
+        .split_once('-')
+        .and_then(|(month, day)| Some((month.parse().ok()?, day.parse().ok()?)))
+        .ok_or_else(|| format!("{} isn't a month and day", value))
+}
+
+pub fn recurrence(pattern: &RecurrencePattern) -> Result<Recurrence, String> {
+    match pattern.frequency.as_str() {
+        "weekly" => Ok(Recurrence::Weekly {
+            weekdays: pattern
+                .weekdays
+                .iter()
+                .map(|day| weekday(*day))
+                .collect::<Result<_, _>>()?,
+            interval: u32::try_from(pattern.interval_weeks).unwrap_or(0),
+        }),
+        "monthly" => Ok(Recurrence::MonthlyWeekday {
+            weekday: weekday(*pattern.weekdays.first().ok_or("Pick a day of the week")?)?,
+            nth: pattern.week_of_month.ok_or("Pick a week of the month")?,
+        }),
+        "yearly" => Ok(Recurrence::Yearly {
+            dates: pattern
+                .annual_dates
+                .iter()
+                .map(|value| annual_date(value))
+                .collect::<Result<_, _>>()?,
+        }),
+        other => Err(format!("Unknown frequency: {}", other)),
+    }
+}
+
+/// The evaluator's version of a rule, checked with
+/// [`RecurringRule::validate`]. Blank times mean all day.
+pub fn schedule_rule(
+    pattern: &RecurrencePattern,
+    action: &str,
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+) -> Result<RecurringRule, String> {
+    if !RULE_ACTIONS.contains(&action) {
+        return Err(format!("Unknown action: {}", action));
+    }
+    let date = |value: &str| parse_date(value).ok_or_else(|| format!("{} isn't a date", value));
+    let time = |value: Option<&str>| match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => parse_time(value)
+            .map(Some)
+            .ok_or_else(|| format!("{} isn't a time", value)),
+        None => Ok(None),
+    };
+
+    let rule = RecurringRule {
+        recurrence: recurrence(pattern)?,
+        starts_on: pattern.starts_on.as_deref().map(date).transpose()?,
+        ends_on: pattern.ends_on.as_deref().map(date).transpose()?,
+        exceptions: pattern
+            .except_dates
+            .iter()
+            .map(|value| date(value.as_str()))
+            .collect::<Result<_, _>>()?,
+        start: time(start_time)?,
+        end: time(end_time)?,
+        available: action == "available",
+    };
+    rule.validate()?;
+    Ok(rule)
+}
+
+/// [`schedule_rule`] for a stored rule
+pub fn stored_schedule_rule(rule: &StoredRule) -> Result<RecurringRule, String> {
+    schedule_rule(
+        &rule.pattern,
+        &rule.action,
+        rule.start_time.as_deref(),
+        rule.end_time.as_deref(),
+    )
+}
+
+/// e.g. "Every Saturday, Sunday from 2025-06-01, except 2 dates"
+pub fn describe(pattern: &RecurrencePattern) -> String {
+    let Ok(recurrence) = recurrence(pattern) else {
+        return "Incomplete pattern".to_string();
+    };
+    let mut description = recurrence.describe();
+    match (&pattern.starts_on, &pattern.ends_on) {
+        (Some(starts_on), Some(ends_on)) => {
+            description.push_str(&format!(", {} to {}", starts_on, ends_on))
+        }
+        (Some(starts_on), None) => description.push_str(&format!(" from {}", starts_on)),
+        (None, Some(ends_on)) => description.push_str(&format!(" until {}", ends_on)),
+        (None, None) => {}
+    }
+    match pattern.except_dates.len() {
+        0 => {}
+        1 => description.push_str(&format!(", except {}", pattern.except_dates[0])),
+        n => description.push_str(&format!(", except {} dates", n)),
+    }
+    description
+}
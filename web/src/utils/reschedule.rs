@@ -0,0 +1,64 @@
+//! Rescheduling and cancelling bookings. The artist suggests another time
+//! and the client accepts it, declines it or counters with one of their
+//! own; either side can cancel, and a client cancelling inside the artist's
+//! cancellation window loses their deposit.
+
+/// Reasons a client can give for cancelling
+/// (booking_requests.cancellation_reason), with their labels
+pub const CLIENT_CANCELLATION_REASONS: &[(&str, &str)] = &[
+    ("schedule_conflict", "Schedule conflict"),
+    ("changed_mind", "Changed my mind"),
+    ("illness", "Illness or emergency"),
+    ("cost", "Cost"),
+    ("found_another_artist", "Found another artist"),
+    ("other", "Other"),
+];
+
+/// Reasons an artist can give for cancelling, with their labels
+pub const ARTIST_CANCELLATION_REASONS: &[(&str, &str)] = &[
+    ("schedule_conflict", "Schedule conflict"),
+    ("illness", "Illness or emergency"),
+    ("artist_unavailable", "Artist unavailable"),
+    ("client_no_response", "Client stopped responding"),
+    ("other", "Other"),
+];
+
+/// Hours before an appointment a client can cancel without losing their
+/// deposit, for artists who haven't set their own
+pub const DEFAULT_CANCELLATION_WINDOW_HOURS: i32 = 48;
+
+/// Longest cancellation window an artist can set, two weeks
+pub const MAX_CANCELLATION_WINDOW_HOURS: i32 = 14 * 24;
+
+pub const MAX_NOTE_CHARS: usize = 500;
+
+pub fn cancellation_reason_label(reason: &str) -> &str {
+    CLIENT_CANCELLATION_REASONS
+        .iter()
+        .chain(ARTIST_CANCELLATION_REASONS)
+        .find(|(value, _)| *value == reason)
+        .map(|(_, label)| *label)
+        .unwrap_or(reason)
+}
+
+/// An optional note on a cancellation or a declined time as stored, `None`
+/// when it's blank
+pub fn normalize_note(note: Option<&str>) -> Result<Option<String>, String> {
+    let note = note
+        .map(|note| note.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|note| !note.is_empty());
+    if note
+        .as_ref()
+        .is_some_and(|note| note.chars().count() > MAX_NOTE_CHARS)
+    {
+        return Err(format!("Keep the note under {} characters", MAX_NOTE_CHARS));
+    }
+    Ok(note)
+}
+
+/// Whether cancelling `hours_until` hours before the appointment falls inside
+/// a `window_hours` cancellation window. An appointment already past counts
+/// as inside it.
+pub fn is_late_cancellation(hours_until: f64, window_hours: i32) -> bool {
+    hours_until < window_hours as f64
+}
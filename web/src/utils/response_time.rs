@@ -0,0 +1,54 @@
+//! How quickly artists answer booking requests. The figures are loaded by
+//! `db::response_time_repository`; this module turns them into the badge
+//! shown on profiles and match cards, and decides when a pending request is
+//! close enough to the SLA to nudge the artist.
+
+/// Days of booking requests the rolling figures cover
+pub const WINDOW_DAYS: i32 = 90;
+/// Answered requests needed before a badge is shown
+pub const MIN_SAMPLES: i64 = 3;
+/// Hours an artist has to respond to a new request
+pub const SLA_HOURS: f64 = 24.0;
+/// Share of the SLA after which the dashboard starts nudging
+pub const NUDGE_AT: f64 = 0.75;
+
+/// How long an artist usually takes to respond, e.g. `"a few hours"`, with
+/// the same cut-offs as [`response_label`]
+pub fn response_window(average_hours: Option<f64>, responded: i64) -> Option<&'static str> {
+    if responded < MIN_SAMPLES {
+        return None;
+    }
+
+    match average_hours? {
+        h if h <= 1.0 => Some("an hour"),
+        h if h <= 6.0 => Some("a few hours"),
+        h if h <= 24.0 => Some("a day"),
+        h if h <= 72.0 => Some("a few days"),
+        _ => None,
+    }
+}
+
+/// Badge text for an artist's average response time, `None` when there are
+/// too few responses to go on or the artist is slower than a few days
+pub fn response_label(average_hours: Option<f64>, responded: i64) -> Option<String> {
+    response_window(average_hours, responded)
+        .map(|within| format!("Usually responds within {}", within))
+}
+
+/// Hours a pending request can wait before the dashboard flags it
+pub fn nudge_after_hours() -> f64 {
+    SLA_HOURS * NUDGE_AT
+}
+
+/// Short text for the time left on a pending request, e.g. `"5h left"` or
+/// `"3h overdue"`
+pub fn time_left(hours_waiting: f64) -> String {
+    let remaining = SLA_HOURS - hours_waiting;
+    if remaining >= 1.0 {
+        format!("{}h left", remaining.floor() as i64)
+    } else if remaining > 0.0 {
+        "under an hour left".to_string()
+    } else {
+        format!("{}h overdue", (-remaining).ceil().max(1.0) as i64)
+    }
+}
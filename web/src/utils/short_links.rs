@@ -0,0 +1,81 @@
+//! Short link codes and what they point at. Links are stored by
+//! `db::short_link_repository` and resolved by `short_links.rs` at
+//! `/s/{code}`.
+
+pub const TARGET_TYPES: [&str; 3] = ["artist", "shop", "search"];
+
+/// Pages a search link can point at, with their query string
+pub const SEARCH_PATHS: [&str; 2] = ["/match/results", "/explore"];
+
+/// No 0/O or 1/l/I, so codes read out or typed from an SMS survive
+const CODE_ALPHABET: &[u8] = b"23456789abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+pub const CODE_LENGTH: usize = 7;
+/// Longest search path and query accepted
+const MAX_SEARCH_LENGTH: usize = 1024;
+
+/// A code from random bits
+pub fn code_from(mut bits: u128) -> String {
+    let base = CODE_ALPHABET.len() as u128;
+    (0..CODE_LENGTH)
+        .map(|_| {
+            let c = CODE_ALPHABET[(bits % base) as usize] as char;
+            bits /= base;
+            c
+        })
+        .collect()
+}
+
+/// Whether `code` could be a code, checked before looking it up
+pub fn is_code(code: &str) -> bool {
+    code.len() == CODE_LENGTH && code.bytes().all(|b| CODE_ALPHABET.contains(&b))
+}
+
+/// The stored form of a link's target: a positive id for artists and shops,
+/// a path on this site with its query string for searches
+pub fn normalize_target(target_type: &str, target: &str) -> Result<String, String> {
+    let target = target.trim();
+    match target_type {
+        "artist" | "shop" => target
+            .parse::<i64>()
+            .ok()
+            .filter(|id| *id > 0)
+            .map(|id| id.to_string())
+            .ok_or_else(|| format!("{} isn't a valid {} id", target, target_type)),
+        "search" => {
+            // Fragments never reach the server, so they aren't kept
+            let target = target.split('#').next().unwrap_or_default();
+            let path = target.split('?').next().unwrap_or_default();
+            if !SEARCH_PATHS.contains(&path) {
+                return Err("Only searches and match results can be shared".to_string());
+            }
+            if target.len() > MAX_SEARCH_LENGTH {
+                return Err("That search is too long to share".to_string());
+            }
+            Ok(target.trim_end_matches('?').to_string())
+        }
+        other => Err(format!("Unknown link type: {}", other)),
+    }
+}
+
+/// Where a stored link sends visitors
+pub fn target_path(target_type: &str, target: &str) -> String {
+    match target_type {
+        "artist" => format!("/artist/{}", target),
+        "shop" => format!("/shop/{}", target),
+        _ => target.to_string(),
+    }
+}
+
+/// "instagram.com" for "https://www.instagram.com/p/...". `None` for
+/// anything that isn't an http(s) URL.
+pub fn referrer_host(referrer: &str) -> Option<String> {
+    let rest = referrer
+        .strip_prefix("https://")
+        .or_else(|| referrer.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    // Drop any credentials and port
+    let host = host.rsplit('@').next()?.split(':').next()?;
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty()).then(|| host.to_string())
+}
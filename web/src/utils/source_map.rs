@@ -0,0 +1,185 @@
+//! Minimal source map (v3) reader used to symbolicate client error stacks.
+//! Handles the plain `mappings` format emitted for the JS glue and, when
+//! present, the WASM bundle (where the column is the byte offset on line 1).
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct RawSourceMap {
+    #[serde(default)]
+    sources: Vec<String>,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default, rename = "sourceRoot")]
+    source_root: Option<String>,
+    mappings: String,
+}
+
+#[derive(Clone, Copy)]
+struct Segment {
+    generated_column: u32,
+    source: u32,
+    line: u32,
+    column: u32,
+    name: Option<u32>,
+}
+
+/// Original position for a generated one; line and column are 1-based
+#[derive(Debug, PartialEq)]
+pub struct OriginalPosition {
+    pub source: String,
+    pub line: u32,
+    pub column: u32,
+    pub name: Option<String>,
+}
+
+pub struct SourceMap {
+    sources: Vec<String>,
+    names: Vec<String>,
+    lines: Vec<Vec<Segment>>,
+}
+
+fn base64_value(c: u8) -> Option<i64> {
+    match c {
+        b'A'..=b'Z' => Some((c - b'A') as i64),
+        b'a'..=b'z' => Some((c - b'a') as i64 + 26),
+        b'0'..=b'9' => Some((c - b'0') as i64 + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes a run of base64 VLQ values
+fn decode_vlq(segment: &str) -> Option<Vec<i64>> {
+    let mut values = Vec::new();
+    let mut value = 0i64;
+    let mut shift = 0;
+
+    for c in segment.bytes() {
+        let digit = base64_value(c)?;
+        value += (digit & 0b11111) << shift;
+        if digit & 0b100000 != 0 {
+            shift += 5;
+            if shift > 60 {
+                return None;
+            }
+        } else {
+            let negative = value & 1 == 1;
+            value >>= 1;
+            values.push(if negative { -value } else { value });
+            value = 0;
+            shift = 0;
+        }
+    }
+
+    (shift == 0).then_some(values)
+}
+
+impl SourceMap {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let raw: RawSourceMap =
+            serde_json::from_str(json).map_err(|e| format!("Invalid source map: {}", e))?;
+
+        let root = raw
+            .source_root
+            .filter(|root| !root.is_empty())
+            .map(|root| format!("{}/", root.trim_end_matches('/')));
+        let sources = raw
+            .sources
+            .into_iter()
+            .map(|source| match &root {
+                Some(root) => format!("{}{}", root, source),
+                None => source,
+            })
+            .collect();
+
+        // Source, line, column and name deltas carry over between lines;
+        // the generated column resets on every line
+        let (mut source, mut line, mut column, mut name) = (0i64, 0i64, 0i64, 0i64);
+        let mut lines = Vec::new();
+
+        for generated_line in raw.mappings.split(';') {
+            let mut generated_column = 0i64;
+            let mut segments = Vec::new();
+
+            for segment in generated_line.split(',').filter(|s| !s.is_empty()) {
+                let values = decode_vlq(segment)
+                    .ok_or_else(|| format!("Invalid mapping segment: {}", segment))?;
+                generated_column += values[0];
+
+                if values.len() >= 4 {
+                    source += values[1];
+                    line += values[2];
+                    column += values[3];
+                    let segment_name = (values.len() >= 5).then(|| {
+                        name += values[4];
+                        name as u32
+                    });
+                    segments.push(Segment {
+                        generated_column: generated_column as u32,
+                        source: source as u32,
+                        line: line as u32,
+                        column: column as u32,
+                        name: segment_name,
+                    });
+                }
+            }
+
+            lines.push(segments);
+        }
+
+        Ok(Self {
+            sources,
+            names: raw.names,
+            lines,
+        })
+    }
+
+    /// Looks up a 1-based generated line and column
+    pub fn lookup(&self, line: u32, column: u32) -> Option<OriginalPosition> {
+        let segments = self.lines.get(line.checked_sub(1)? as usize)?;
+        let column = column.saturating_sub(1);
+        let index = segments.partition_point(|s| s.generated_column <= column);
+        let segment = segments.get(index.checked_sub(1)?)?;
+
+        Some(OriginalPosition {
+            source: self.sources.get(segment.source as usize)?.clone(),
+            line: segment.line + 1,
+            column: segment.column + 1,
+            name: segment
+                .name
+                .and_then(|name| self.names.get(name as usize).cloned()),
+        })
+    }
+}
+
+/// A stack frame's location: the bundle file name (last URL segment) and a
+/// 1-based line/column. WASM frames (`…/web_bg.wasm:wasm-function[12]:0x1a2b`)
+/// map to line 1 with the byte offset as column.
+pub fn frame_location(frame: &str) -> Option<(String, u32, u32)> {
+    let frame = frame.trim().trim_end_matches(')');
+
+    let (url, line, column) = if let Some(index) = frame.find(":wasm-function[") {
+        let offset = frame[index..].rsplit_once(":0x")?.1;
+        (
+            &frame[..index],
+            1,
+            u32::from_str_radix(offset, 16).ok()? + 1,
+        )
+    } else {
+        let (rest, column) = frame.rsplit_once(':')?;
+        let (url, line) = rest.rsplit_once(':')?;
+        (url, line.parse().ok()?, column.parse().ok()?)
+    };
+
+    // Drop the "at fn (" / "fn@" prefix and any query string
+    let url = url
+        .rsplit(['(', '@', ' '])
+        .next()?
+        .split(['?', '#'])
+        .next()?;
+    let file = url.rsplit('/').next()?.to_string();
+
+    (!file.is_empty()).then_some((file, line, column))
+}
@@ -0,0 +1,48 @@
+//! Booking message translation. With an artist's auto-translate turned on,
+//! client messages are shown to them in English and their replies to the
+//! client in the language the client writes in; both sides can still see
+//! what was originally written.
+
+use crate::db::entities::BookingMessage;
+
+/// The language artists read and write in
+pub const ARTIST_LANGUAGE: &str = "en";
+
+/// Display name of an ISO 639-1 code, e.g. `"Spanish"` for `"es"`
+pub fn language_name(code: &str) -> String {
+    match code {
+        "en" => "English",
+        "es" => "Spanish",
+        "pt" => "Portuguese",
+        "fr" => "French",
+        "de" => "German",
+        "it" => "Italian",
+        "zh" => "Chinese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "vi" => "Vietnamese",
+        "tl" => "Tagalog",
+        "ru" => "Russian",
+        "ar" => "Arabic",
+        _ => return code.to_uppercase(),
+    }
+    .to_string()
+}
+
+/// Lowercased primary subtag of a provider's language code, so `"PT-BR"`
+/// and `"pt"` compare equal
+pub fn normalize_language(code: &str) -> Option<String> {
+    let primary = code.trim().split(['-', '_']).next()?.to_lowercase();
+    (primary.len() == 2 || primary.len() == 3)
+        .then_some(primary)
+        .filter(|primary| primary.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// The translation `reader` ("artist" or "client") should see instead of a
+/// message's original text, if it was translated for them
+pub fn translation_for<'a>(message: &'a BookingMessage, reader: &str) -> Option<&'a str> {
+    message
+        .translated_message
+        .as_deref()
+        .filter(|_| message.sender_type != reader)
+}
@@ -0,0 +1,493 @@
+use crate::db::entities::{AccountContact, PendingContactChange};
+use crate::server::AuthResponse;
+use crate::server_account::{
+    cancel_contact_change, confirm_contact_change, deactivate_account, delete_my_data,
+    export_my_data, get_account_contact, request_contact_change, DELETION_GRACE_DAYS,
+};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_query_map;
+
+/// Replaces the stored session with the one issued after a confirmed change
+fn store_session(response: &AuthResponse) {
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = localStorage)]
+            fn setItem(key: &str, value: &str);
+        }
+
+        if let (Some(token), Some(refresh_token)) = (&response.token, &response.refresh_token) {
+            setItem("tatteau_auth_token", token);
+            setItem("tatteau_refresh_token", refresh_token);
+        }
+    }
+    #[cfg(not(feature = "hydrate"))]
+    let _ = response;
+}
+
+/// Signs out locally once the account is closed; the server has already
+/// revoked every session
+fn clear_session() {
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = localStorage)]
+            fn removeItem(key: &str);
+        }
+
+        removeItem("tatteau_auth_token");
+        removeItem("tatteau_refresh_token");
+    }
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().set_href("/");
+    }
+}
+
+fn redirect_to_login(return_to: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window
+            .location()
+            .set_href(&format!("/login?redirect={}", return_to));
+    }
+}
+
+/// Where signed-in users change the email and phone on their account,
+/// download their data and close the account
+#[component]
+pub fn AccountPage() -> impl IntoView {
+    let contact = RwSignal::new(None::<AccountContact>);
+    let error = RwSignal::new(None::<String>);
+    let notice = RwSignal::new(None::<String>);
+
+    let load = move || {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            match get_account_contact(token).await {
+                Ok(loaded) => contact.set(Some(loaded)),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    Effect::new(move |_| {
+        if get_auth_token().is_none() {
+            redirect_to_login("/account");
+            return;
+        }
+        load();
+    });
+
+    let on_requested = Callback::new(move |change: PendingContactChange| {
+        error.set(None);
+        notice.set(Some(format!(
+            "We sent a code to {}. Enter it below to finish the change.",
+            change.new_value
+        )));
+        load();
+    });
+    let on_confirmed = Callback::new(move |message: String| {
+        error.set(None);
+        notice.set(Some(message));
+        load();
+    });
+    let on_error = Callback::new(move |message: String| {
+        notice.set(None);
+        error.set(Some(message));
+    });
+
+    view! {
+        <div class="account-page">
+            <div class="account-container">
+                <h1>"Account"</h1>
+
+                {move || error.get().map(|error| view! {
+                    <div class="error-message">{error}</div>
+                })}
+                {move || notice.get().map(|notice| view! {
+                    <div class="account-notice">{notice}</div>
+                })}
+
+                {move || contact.get().map(|contact| {
+                    let pending = contact.pending.clone();
+                    view! {
+                        <div class="account-card">
+                            <h2>"Contact details"</h2>
+                            <dl class="account-contact">
+                                <dt>"Email"</dt>
+                                <dd>{contact.email.clone()}</dd>
+                                <dt>"Phone"</dt>
+                                <dd>{contact.phone.clone().unwrap_or_else(|| "Not set".to_string())}</dd>
+                            </dl>
+                        </div>
+
+                        <Show when={
+                            let has_pending = !pending.is_empty();
+                            move || has_pending
+                        }>
+                            <div class="account-card">
+                                <h2>"Waiting for confirmation"</h2>
+                                <For
+                                    each={
+                                        let pending = pending.clone();
+                                        move || pending.clone()
+                                    }
+                                    key=|change| change.id
+                                    let:change
+                                >
+                                    <PendingChange
+                                        change=change
+                                        on_confirmed=on_confirmed
+                                        on_error=on_error
+                                    />
+                                </For>
+                            </div>
+                        </Show>
+
+                        <ChangeContactForm on_requested=on_requested on_error=on_error />
+                        <YourDataCard on_error=on_error />
+                        <CloseAccountForm on_error=on_error />
+                    }
+                })}
+            </div>
+        </div>
+    }
+}
+
+/// Starts a change; the new address gets a code and nothing changes until
+/// it's entered
+#[component]
+fn ChangeContactForm(
+    on_requested: Callback<PendingContactChange>,
+    on_error: Callback<String>,
+) -> impl IntoView {
+    let channel = RwSignal::new("email".to_string());
+    let new_value = RwSignal::new(String::new());
+    let password = RwSignal::new(String::new());
+    let sending = RwSignal::new(false);
+
+    let submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        sending.set(true);
+        spawn_local(async move {
+            match request_contact_change(
+                token,
+                channel.get_untracked(),
+                new_value.get_untracked(),
+                password.get_untracked(),
+            )
+            .await
+            {
+                Ok(change) => {
+                    new_value.set(String::new());
+                    password.set(String::new());
+                    on_requested.run(change);
+                }
+                Err(e) => on_error.run(e.to_string()),
+            }
+            sending.set(false);
+        });
+    };
+
+    view! {
+        <form class="account-card account-change-form" on:submit=submit>
+            <h2>"Change email or phone"</h2>
+            <div class="form-group">
+                <label>"What to change"</label>
+                <select on:change=move |ev| channel.set(event_target_value(&ev))>
+                    <option value="email" selected=move || channel.get() == "email">"Email"</option>
+                    <option value="phone" selected=move || channel.get() == "phone">"Phone"</option>
+                </select>
+            </div>
+            <div class="form-group">
+                <label>{move || if channel.get() == "email" { "New email" } else { "New phone number" }}</label>
+                <input
+                    type=move || if channel.get() == "email" { "email" } else { "tel" }
+                    required
+                    prop:value=move || new_value.get()
+                    on:input=move |ev| new_value.set(event_target_value(&ev))
+                />
+            </div>
+            <div class="form-group">
+                <label>"Current password"</label>
+                <input
+                    type="password"
+                    required
+                    autocomplete="current-password"
+                    prop:value=move || password.get()
+                    on:input=move |ev| password.set(event_target_value(&ev))
+                />
+            </div>
+            <button type="submit" class="btn btn-primary" disabled=move || sending.get()>
+                {move || if sending.get() { "Sending code..." } else { "Send Code" }}
+            </button>
+        </form>
+    }
+}
+
+/// Downloads everything kept about the user as a JSON file
+#[component]
+fn YourDataCard(on_error: Callback<String>) -> impl IntoView {
+    let download = RwSignal::new(None::<String>);
+    let exporting = RwSignal::new(false);
+
+    let export = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        exporting.set(true);
+        spawn_local(async move {
+            match export_my_data(token).await {
+                Ok(data) => download.set(Some(format!(
+                    "data:application/json;charset=utf-8,{}",
+                    urlencoding::encode(&data)
+                ))),
+                Err(e) => on_error.run(e.to_string()),
+            }
+            exporting.set(false);
+        });
+    };
+
+    view! {
+        <div class="account-card">
+            <h2>"Your data"</h2>
+            <p>"Download your account details, booking requests, messages and favorites as a JSON file."</p>
+            {move || match download.get() {
+                Some(href) => view! {
+                    <a class="btn btn-primary" href=href download="tatteau-data.json">"Save File"</a>
+                }
+                .into_any(),
+                None => view! {
+                    <button class="btn btn-secondary" disabled=move || exporting.get() on:click=export>
+                        {move || if exporting.get() { "Preparing..." } else { "Download My Data" }}
+                    </button>
+                }
+                .into_any(),
+            }}
+        </div>
+    }
+}
+
+/// Deactivates the account, or deactivates it and deletes its data after
+/// the grace period. Either needs the password and a second click.
+#[component]
+fn CloseAccountForm(on_error: Callback<String>) -> impl IntoView {
+    // Some(true) when deleting data, Some(false) when only deactivating
+    let closing = RwSignal::new(None::<bool>);
+    let password = RwSignal::new(String::new());
+    let working = RwSignal::new(false);
+
+    let submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let (Some(token), Some(delete)) = (get_auth_token(), closing.get_untracked()) else {
+            return;
+        };
+        working.set(true);
+        spawn_local(async move {
+            let result = if delete {
+                delete_my_data(token, password.get_untracked()).await
+            } else {
+                deactivate_account(token, password.get_untracked()).await
+            };
+            match result {
+                Ok(()) => clear_session(),
+                Err(e) => {
+                    on_error.run(e.to_string());
+                    working.set(false);
+                }
+            }
+        });
+    };
+
+    view! {
+        <form class="account-card account-close-form" on:submit=submit>
+            <h2>"Close account"</h2>
+            {move || match closing.get() {
+                None => view! {
+                    <p>"Deactivating signs you out everywhere and keeps your data. Deleting also erases your details, messages, favorites and tattoo photos."</p>
+                    <div class="account-close-actions">
+                        <button type="button" class="btn btn-secondary" on:click=move |_| closing.set(Some(false))>
+                            "Deactivate Account"
+                        </button>
+                        <button type="button" class="btn btn-outline-danger" on:click=move |_| closing.set(Some(true))>
+                            "Delete My Data"
+                        </button>
+                    </div>
+                }
+                .into_any(),
+                Some(delete) => view! {
+                    <p class="account-close-warning">
+                        {if delete {
+                            format!(
+                                "Your account closes now and your data is deleted in {} days. Contact us before then to keep it.",
+                                DELETION_GRACE_DAYS
+                            )
+                        } else {
+                            "Your account closes now. Contact us to reopen it.".to_string()
+                        }}
+                    </p>
+                    <div class="form-group">
+                        <label>"Current password"</label>
+                        <input
+                            type="password"
+                            required
+                            autocomplete="current-password"
+                            prop:value=move || password.get()
+                            on:input=move |ev| password.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <div class="account-close-actions">
+                        <button type="submit" class="btn btn-outline-danger" disabled=move || working.get()>
+                            {if delete { "Close & Delete My Data" } else { "Close My Account" }}
+                        </button>
+                        <button
+                            type="button"
+                            class="btn btn-secondary"
+                            disabled=move || working.get()
+                            on:click=move |_| {
+                                password.set(String::new());
+                                closing.set(None);
+                            }
+                        >
+                            "Cancel"
+                        </button>
+                    </div>
+                }
+                .into_any(),
+            }}
+        </form>
+    }
+}
+
+#[component]
+fn PendingChange(
+    change: PendingContactChange,
+    on_confirmed: Callback<String>,
+    on_error: Callback<String>,
+) -> impl IntoView {
+    let code = RwSignal::new(String::new());
+    let working = RwSignal::new(false);
+    let request_id = change.id;
+
+    let confirm = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        working.set(true);
+        spawn_local(async move {
+            match confirm_contact_change(token, request_id, code.get_untracked()).await {
+                Ok(response) if response.success => {
+                    store_session(&response);
+                    on_confirmed.run("Your contact details were updated.".to_string());
+                }
+                Ok(response) => on_error.run(
+                    response
+                        .error
+                        .unwrap_or_else(|| "Could not confirm the change".to_string()),
+                ),
+                Err(e) => on_error.run(e.to_string()),
+            }
+            working.set(false);
+        });
+    };
+
+    let cancel = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        working.set(true);
+        spawn_local(async move {
+            match cancel_contact_change(token, request_id).await {
+                Ok(()) => on_confirmed.run("The change was cancelled.".to_string()),
+                Err(e) => on_error.run(e.to_string()),
+            }
+            working.set(false);
+        });
+    };
+
+    view! {
+        <form class="account-pending-change" on:submit=confirm>
+            <p>
+                {format!(
+                    "New {}: {} (code expires {})",
+                    change.channel, change.new_value, change.expires_at
+                )}
+            </p>
+            <input
+                type="text"
+                inputmode="numeric"
+                maxlength="6"
+                placeholder="6-digit code"
+                prop:value=move || code.get()
+                on:input=move |ev| code.set(event_target_value(&ev))
+            />
+            <button type="submit" class="btn btn-primary" disabled=move || working.get()>
+                "Confirm"
+            </button>
+            <button type="button" class="btn btn-secondary" disabled=move || working.get() on:click=cancel>
+                "Cancel"
+            </button>
+        </form>
+    }
+}
+
+/// Landing page for the link in a verification email
+#[component]
+pub fn VerifyContactPage() -> impl IntoView {
+    let query = use_query_map();
+    let status = RwSignal::new(None::<Result<String, String>>);
+
+    Effect::new(move |_| {
+        let request_id = query.read().get("request").and_then(|id| id.parse::<i64>().ok());
+        let code = query.read().get("code").unwrap_or_default();
+        let Some(request_id) = request_id else {
+            status.set(Some(Err("This link is incomplete.".to_string())));
+            return;
+        };
+        let Some(token) = get_auth_token() else {
+            redirect_to_login(&format!("/account/verify?request={}&code={}", request_id, code));
+            return;
+        };
+        spawn_local(async move {
+            let result = match confirm_contact_change(token, request_id, code).await {
+                Ok(response) if response.success => {
+                    store_session(&response);
+                    Ok("Your email was updated.".to_string())
+                }
+                Ok(response) => Err(response
+                    .error
+                    .unwrap_or_else(|| "Could not confirm the change".to_string())),
+                Err(e) => Err(e.to_string()),
+            };
+            status.set(Some(result));
+        });
+    });
+
+    view! {
+        <div class="account-page">
+            <div class="account-container">
+                <div class="account-card">
+                    {move || match status.get() {
+                        None => view! { <p>"Confirming..."</p> }.into_any(),
+                        Some(Ok(message)) => view! { <p class="account-notice">{message}</p> }.into_any(),
+                        Some(Err(message)) => view! { <div class="error-message">{message}</div> }.into_any(),
+                    }}
+                    <a href="/account" class="btn btn-secondary">"Back to Account"</a>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,162 @@
+use crate::api_error::user_message;
+use crate::db::entities::ArtistQuestion;
+use crate::server_artist_questions::{get_questions_for_moderation, moderate_artist_question};
+use crate::utils::artist_questions::{status_label, QUESTION_STATUSES};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_navigate;
+
+#[component]
+pub fn AdminArtistQuestions() -> impl IntoView {
+    let navigate = use_navigate();
+    let status = RwSignal::new("held".to_string());
+    let questions = RwSignal::new(Vec::<ArtistQuestion>::new());
+    let loading = RwSignal::new(false);
+    let error_message = RwSignal::new(Option::<String>::None);
+
+    let fetch_questions = move || {
+        let Some(token) = get_auth_token() else {
+            error_message.set(Some("Not authenticated. Please log in.".to_string()));
+            return;
+        };
+
+        loading.set(true);
+        error_message.set(None);
+
+        spawn_local(async move {
+            match get_questions_for_moderation(token, status.get_untracked()).await {
+                Ok(result) => questions.set(result),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+            loading.set(false);
+        });
+    };
+
+    // Initial load
+    Effect::new(move |_| {
+        fetch_questions();
+    });
+
+    let select_status = move |selected: &'static str| {
+        status.set(selected.to_string());
+        fetch_questions();
+    };
+
+    let moderate = move |question_id: i32, approve: bool| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            match moderate_artist_question(token, question_id, approve).await {
+                Ok(()) => {
+                    questions.update(|list| list.retain(|question| question.id != question_id))
+                }
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+        });
+    };
+
+    view! {
+        <div class="admin-artist-questions">
+            <div class="admin-validate-header">
+                <button
+                    class="admin-back-button"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| navigate("/admin/dashboard", Default::default())
+                    }
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                        <polyline points="15 18 9 12 15 6"></polyline>
+                    </svg>
+                    "Back to Dashboard"
+                </button>
+                <h1>"Artist Q&A Moderation"</h1>
+                <p>"Questions held by the spam filter or reported by artists. Approved questions go to the artist; rejected ones are never shown."</p>
+            </div>
+
+            <Show when=move || error_message.get().is_some()>
+                <div class="admin-error-message">
+                    {move || error_message.get().unwrap_or_default()}
+                </div>
+            </Show>
+
+            <div class="admin-license-tabs">
+                {QUESTION_STATUSES.iter().map(|tab| {
+                    let tab = *tab;
+                    view! {
+                        <button
+                            class="admin-license-tab"
+                            class:selected=move || status.get() == tab
+                            on:click=move |_| select_status(tab)
+                        >
+                            {status_label(tab)}
+                        </button>
+                    }
+                }).collect_view()}
+            </div>
+
+            <Show
+                when=move || loading.get()
+                fallback=move || view! {
+                    <Show
+                        when=move || !questions.get().is_empty()
+                        fallback=|| view! {
+                            <div class="admin-empty-state">"No questions here"</div>
+                        }
+                    >
+                        <div class="admin-question-list">
+                            <For
+                                each=move || questions.get()
+                                key=|question| (question.id, question.status.clone())
+                                children=move |question: ArtistQuestion| {
+                                    let question_id = question.id;
+                                    let can_approve = question.status != "approved";
+                                    let can_reject = question.status != "rejected";
+                                    view! {
+                                        <div class="admin-question-card">
+                                            <div class="admin-question-info">
+                                                <h3>
+                                                    <a href=format!("/artist/{}", question.artist_id) target="_blank">
+                                                        {question.artist_name.clone().unwrap_or_else(|| "Unknown Artist".to_string())}
+                                                    </a>
+                                                </h3>
+                                                <p class="admin-question-text">{question.question.clone()}</p>
+                                                {question.answer.clone().map(|answer| view! {
+                                                    <p class="admin-question-answer">{format!("Answer: {}", answer)}</p>
+                                                })}
+                                                {question.flag_reason.clone().map(|reason| view! {
+                                                    <span class="admin-tag">{reason}</span>
+                                                })}
+                                                <p class="admin-artist-created">
+                                                    {format!("Asked by {} · {}", question.asker_name, question.asked_at)}
+                                                </p>
+                                            </div>
+                                            <div class="admin-question-actions">
+                                                <Show when=move || can_approve>
+                                                    <button class="btn btn-primary" on:click=move |_| moderate(question_id, true)>
+                                                        "Approve"
+                                                    </button>
+                                                </Show>
+                                                <Show when=move || can_reject>
+                                                    <button class="btn btn-outline-danger" on:click=move |_| moderate(question_id, false)>
+                                                        "Reject as Spam"
+                                                    </button>
+                                                </Show>
+                                            </div>
+                                        </div>
+                                    }
+                                }
+                            />
+                        </div>
+                    </Show>
+                }
+            >
+                <div class="admin-loading">
+                    <p>"Loading questions..."</p>
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,179 @@
+use crate::api_error::user_message;
+use crate::db::entities::BookingRestoreRequest;
+use crate::server_archive::{get_booking_restore_requests, review_booking_restore};
+use crate::utils::archive::{restore_status_label, RESTORE_STATUSES};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_navigate;
+
+#[component]
+pub fn AdminBookingRestores() -> impl IntoView {
+    let navigate = use_navigate();
+    let status = RwSignal::new("pending".to_string());
+    let requests = RwSignal::new(Vec::<BookingRestoreRequest>::new());
+    let loading = RwSignal::new(false);
+    let reviewing = RwSignal::new(Option::<i32>::None);
+    let error_message = RwSignal::new(Option::<String>::None);
+
+    let fetch_requests = move || {
+        let Some(token) = get_auth_token() else {
+            error_message.set(Some("Not authenticated. Please log in.".to_string()));
+            return;
+        };
+
+        loading.set(true);
+        error_message.set(None);
+
+        spawn_local(async move {
+            match get_booking_restore_requests(token, status.get_untracked()).await {
+                Ok(result) => requests.set(result),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+            loading.set(false);
+        });
+    };
+
+    // Initial load
+    Effect::new(move |_| {
+        fetch_requests();
+    });
+
+    let select_status = move |selected: &'static str| {
+        status.set(selected.to_string());
+        fetch_requests();
+    };
+
+    let review = move |request_id: i32, approve: bool| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        reviewing.set(Some(request_id));
+        spawn_local(async move {
+            match review_booking_restore(token, request_id, approve).await {
+                Ok(new_status) if new_status == "failed" => {
+                    error_message.set(Some(
+                        "Restoring the booking failed; it's under Failed to retry.".to_string(),
+                    ));
+                    requests.update(|list| list.retain(|request| request.id != request_id));
+                }
+                Ok(_) => requests.update(|list| list.retain(|request| request.id != request_id)),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+            reviewing.set(None);
+        });
+    };
+
+    view! {
+        <div class="admin-booking-restores">
+            <div class="admin-validate-header">
+                <button
+                    class="admin-back-button"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| navigate("/admin/dashboard", Default::default())
+                    }
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                        <polyline points="15 18 9 12 15 6"></polyline>
+                    </svg>
+                    "Back to Dashboard"
+                </button>
+                <h1>"Archived Booking Restores"</h1>
+                <p>"Artists asking for archived bookings back. Approving restores the booking's details, messages and timeline from cold storage."</p>
+            </div>
+
+            <Show when=move || error_message.get().is_some()>
+                <div class="admin-error-message">
+                    {move || error_message.get().unwrap_or_default()}
+                </div>
+            </Show>
+
+            <div class="admin-license-tabs">
+                {RESTORE_STATUSES.iter().map(|tab| {
+                    let tab = *tab;
+                    view! {
+                        <button
+                            class="admin-license-tab"
+                            class:selected=move || status.get() == tab
+                            on:click=move |_| select_status(tab)
+                        >
+                            {restore_status_label(tab)}
+                        </button>
+                    }
+                }).collect_view()}
+            </div>
+
+            <Show
+                when=move || loading.get()
+                fallback=move || view! {
+                    <Show
+                        when=move || !requests.get().is_empty()
+                        fallback=|| view! {
+                            <div class="admin-empty-state">"No restore requests here"</div>
+                        }
+                    >
+                        <div class="admin-question-list">
+                            <For
+                                each=move || requests.get()
+                                key=|request| (request.id, request.status.clone())
+                                children=move |request: BookingRestoreRequest| {
+                                    let request_id = request.id;
+                                    let open = request.status == "pending" || request.status == "failed";
+                                    let approve_label = if request.status == "failed" { "Retry Restore" } else { "Approve & Restore" };
+                                    let busy = move || reviewing.get() == Some(request_id);
+                                    view! {
+                                        <div class="admin-question-card">
+                                            <div class="admin-question-info">
+                                                <h3>
+                                                    <a href=format!("/artist/{}", request.artist_id) target="_blank">
+                                                        {request.artist_name.clone().unwrap_or_else(|| "Unknown Artist".to_string())}
+                                                    </a>
+                                                </h3>
+                                                <p class="admin-question-text">
+                                                    {format!("Booking #{} with {} on {}", request.booking_id, request.client_name, request.requested_date)}
+                                                </p>
+                                                <p class="admin-question-answer">{format!("Reason: {}", request.reason)}</p>
+                                                {request.error.clone().map(|error| view! {
+                                                    <span class="admin-tag">{error}</span>
+                                                })}
+                                                <p class="admin-artist-created">
+                                                    {match request.archived_on.clone() {
+                                                        Some(archived_on) => format!("Archived {} · requested {}", archived_on, request.requested_at),
+                                                        None => format!("Requested {}", request.requested_at),
+                                                    }}
+                                                </p>
+                                            </div>
+                                            <Show when=move || open>
+                                                <div class="admin-question-actions">
+                                                    <button
+                                                        class="btn btn-primary"
+                                                        disabled=busy
+                                                        on:click=move |_| review(request_id, true)
+                                                    >
+                                                        {move || if busy() { "Restoring..." } else { approve_label }}
+                                                    </button>
+                                                    <button
+                                                        class="btn btn-outline-danger"
+                                                        disabled=busy
+                                                        on:click=move |_| review(request_id, false)
+                                                    >
+                                                        "Reject"
+                                                    </button>
+                                                </div>
+                                            </Show>
+                                        </div>
+                                    }
+                                }
+                            />
+                        </div>
+                    </Show>
+                }
+            >
+                <div class="admin-loading">
+                    <p>"Loading restore requests..."</p>
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -2,6 +2,9 @@ use leptos::prelude::*;
 use leptos_router::hooks::use_navigate;
 use thaw::*;
 
+use crate::components::ExportPanel;
+use crate::utils::export::ADMIN_EXPORTS;
+
 #[component]
 pub fn AdminDashboard() -> impl IntoView {
     let navigate = use_navigate();
@@ -53,6 +56,112 @@ pub fn AdminDashboard() -> impl IntoView {
                     <h2>"Validate Artist Shops"</h2>
                     <p>"Verify artist shop assignments"</p>
                 </div>
+
+                <div
+                    class="admin-card"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| {
+                            navigate("/admin/data-quality", Default::default());
+                        }
+                    }
+                >
+                    <div class="admin-card-icon">
+                        <svg xmlns="http://www.w3.org/2000/svg" width="48" height="48" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <path d="M12 22s8-4 8-10V5l-8-3-8 3v7c0 6 8 10 8 10z"></path>
+                            <line x1="12" y1="8" x2="12" y2="12"></line>
+                            <line x1="12" y1="16" x2="12.01" y2="16"></line>
+                        </svg>
+                    </div>
+                    <h2>"Data Quality"</h2>
+                    <p>"Review orphaned and inconsistent records"</p>
+                </div>
+
+                <div
+                    class="admin-card"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| {
+                            navigate("/admin/licenses", Default::default());
+                        }
+                    }
+                >
+                    <div class="admin-card-icon">
+                        <svg xmlns="http://www.w3.org/2000/svg" width="48" height="48" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <rect x="2" y="5" width="20" height="14" rx="2" ry="2"></rect>
+                            <circle cx="8" cy="12" r="2"></circle>
+                            <line x1="13" y1="10" x2="18" y2="10"></line>
+                            <line x1="13" y1="14" x2="18" y2="14"></line>
+                        </svg>
+                    </div>
+                    <h2>"Artist Licenses"</h2>
+                    <p>"Verify license documents and choose where they're shown"</p>
+                </div>
+
+                <div
+                    class="admin-card"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| {
+                            navigate("/admin/questions", Default::default());
+                        }
+                    }
+                >
+                    <div class="admin-card-icon">
+                        <svg xmlns="http://www.w3.org/2000/svg" width="48" height="48" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <path d="M21 15a2 2 0 0 1-2 2H7l-4 4V5a2 2 0 0 1 2-2h14a2 2 0 0 1 2 2z"></path>
+                            <line x1="12" y1="13" x2="12" y2="13"></line>
+                            <path d="M10 8a2 2 0 1 1 2 2v1"></path>
+                        </svg>
+                    </div>
+                    <h2>"Artist Q&A"</h2>
+                    <p>"Moderate questions held as spam or reported by artists"</p>
+                </div>
+
+                <div
+                    class="admin-card"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| {
+                            navigate("/admin/booking-restores", Default::default());
+                        }
+                    }
+                >
+                    <div class="admin-card-icon">
+                        <svg xmlns="http://www.w3.org/2000/svg" width="48" height="48" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <polyline points="21 8 21 21 3 21 3 8"></polyline>
+                            <rect x="1" y="3" width="22" height="5"></rect>
+                            <line x1="10" y1="12" x2="14" y2="12"></line>
+                        </svg>
+                    </div>
+                    <h2>"Archived Bookings"</h2>
+                    <p>"Approve artists' requests to restore bookings from cold storage"</p>
+                </div>
+
+                <div
+                    class="admin-card"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| {
+                            navigate("/admin/errors", Default::default());
+                        }
+                    }
+                >
+                    <div class="admin-card-icon">
+                        <svg xmlns="http://www.w3.org/2000/svg" width="48" height="48" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <path d="M10.29 3.86L1.82 18a2 2 0 0 0 1.71 3h16.94a2 2 0 0 0 1.71-3L13.71 3.86a2 2 0 0 0-3.42 0z"></path>
+                            <line x1="12" y1="9" x2="12" y2="13"></line>
+                            <line x1="12" y1="17" x2="12.01" y2="17"></line>
+                        </svg>
+                    </div>
+                    <h2>"Errors"</h2>
+                    <p>"Triage logged errors grouped by cause, assign them and mark them resolved"</p>
+                </div>
+            </div>
+
+            <div class="admin-exports">
+                <h2>"Data Exports"</h2>
+                <ExportPanel kinds=ADMIN_EXPORTS />
             </div>
         </div>
     }
@@ -0,0 +1,176 @@
+use crate::server::{get_data_quality_report, DataQualityCheckSummary, DataQualityIssue};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_navigate;
+
+#[component]
+pub fn AdminDataQuality() -> impl IntoView {
+    let navigate = use_navigate();
+    let checks = RwSignal::new(Vec::<DataQualityCheckSummary>::new());
+    let issues = RwSignal::new(Vec::<DataQualityIssue>::new());
+    let selected_check = RwSignal::new(Option::<String>::None);
+    let loading = RwSignal::new(false);
+    let error_message = RwSignal::new(Option::<String>::None);
+
+    // Get auth token from localStorage
+    let get_token = move || -> Option<String> {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn getItem(key: &str) -> Option<String>;
+            }
+
+            getItem("tatteau_auth_token")
+        }
+
+        #[cfg(not(feature = "hydrate"))]
+        {
+            None
+        }
+    };
+
+    let fetch_report = move || {
+        let token = match get_token() {
+            Some(t) => t,
+            None => {
+                error_message.set(Some("Not authenticated. Please log in.".to_string()));
+                return;
+            }
+        };
+
+        loading.set(true);
+        error_message.set(None);
+
+        spawn_local(async move {
+            match get_data_quality_report(selected_check.get_untracked(), token).await {
+                Ok(report) => {
+                    checks.set(report.checks);
+                    issues.set(report.issues);
+                }
+                Err(e) => {
+                    error_message.set(Some(format!("Failed to fetch data quality report: {}", e)));
+                }
+            }
+            loading.set(false);
+        });
+    };
+
+    // Initial load
+    Effect::new(move |_| {
+        fetch_report();
+    });
+
+    let select_check = move |check_name: Option<String>| {
+        selected_check.set(check_name);
+        fetch_report();
+    };
+
+    view! {
+        <div class="admin-data-quality">
+            <div class="admin-validate-header">
+                <button
+                    class="admin-back-button"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| navigate("/admin/dashboard", Default::default())
+                    }
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                        <polyline points="15 18 9 12 15 6"></polyline>
+                    </svg>
+                    "Back to Dashboard"
+                </button>
+                <h1>"Data Quality"</h1>
+                <p>"Orphaned rows found by the INTEGRITY_CHECK ingestion job. Repairable issues are fixed when the job runs with INTEGRITY_REPAIR=true."</p>
+            </div>
+
+            <Show when=move || error_message.get().is_some()>
+                <div class="admin-error-message">
+                    {move || error_message.get().unwrap_or_default()}
+                </div>
+            </Show>
+
+            <div class="admin-data-quality-checks">
+                <For
+                    each=move || checks.get()
+                    key=|check| check.check_name.clone()
+                    children=move |check: DataQualityCheckSummary| {
+                        let check_name = check.check_name.clone();
+                        let is_selected = {
+                            let check_name = check_name.clone();
+                            move || selected_check.get().as_deref() == Some(check_name.as_str())
+                        };
+                        view! {
+                            <div
+                                class="admin-data-quality-check"
+                                class:selected=is_selected
+                                on:click=move |_| select_check(Some(check_name.clone()))
+                            >
+                                <h3>{check.check_name.clone()}</h3>
+                                <p class="admin-data-quality-count">{check.open_count}</p>
+                                <p>{check.entity_table.clone()}</p>
+                                <span class="admin-tag">
+                                    {if check.repairable { "Auto-repairable" } else { "Needs review" }}
+                                </span>
+                                <p class="admin-artist-created">"Last seen: " {check.last_seen_at.clone()}</p>
+                            </div>
+                        }
+                    }
+                />
+            </div>
+
+            <Show when=move || selected_check.get().is_some()>
+                <button class="admin-back-button" on:click=move |_| select_check(None)>
+                    "Show all checks"
+                </button>
+            </Show>
+
+            <Show
+                when=move || loading.get()
+                fallback=move || view! {
+                    <Show
+                        when=move || !issues.get().is_empty()
+                        fallback=|| view! {
+                            <div class="admin-empty-state">"No open data quality issues"</div>
+                        }
+                    >
+                        <table class="admin-data-quality-table">
+                            <thead>
+                                <tr>
+                                    <th>"Check"</th>
+                                    <th>"Row"</th>
+                                    <th>"Details"</th>
+                                    <th>"Detected"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                <For
+                                    each=move || issues.get()
+                                    key=|issue| issue.id
+                                    children=move |issue: DataQualityIssue| {
+                                        view! {
+                                            <tr>
+                                                <td>{issue.check_name}</td>
+                                                <td>{format!("{} #{}", issue.entity_table, issue.entity_id)}</td>
+                                                <td>{issue.details.unwrap_or_default()}</td>
+                                                <td>{issue.detected_at}</td>
+                                            </tr>
+                                        }
+                                    }
+                                />
+                            </tbody>
+                        </table>
+                    </Show>
+                }
+            >
+                <div class="admin-loading">
+                    <p>"Loading data quality report..."</p>
+                </div>
+            </Show>
+        </div>
+    }
+}
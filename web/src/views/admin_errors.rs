@@ -0,0 +1,408 @@
+use crate::api_error::user_message;
+use crate::db::entities::{ErrorAssignee, ErrorCount, ErrorGroup, ErrorLog};
+use crate::server_errors::{
+    assign_error_group, get_error_assignees, get_error_counts, get_error_groups, get_error_samples,
+    set_error_status,
+};
+use crate::utils::auth::get_auth_token;
+use crate::utils::error_triage::{status_label, ERROR_STATUSES};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_navigate;
+
+const ERROR_TYPES: [&str; 3] = ["client", "server", "database"];
+const WINDOWS: [(i32, &str); 4] = [
+    (1, "Last 24 hours"),
+    (7, "Last 7 days"),
+    (30, "Last 30 days"),
+    (90, "Last 90 days"),
+];
+
+/// Daily counts as bars scaled to the busiest day
+#[component]
+fn ErrorTrend(counts: Vec<ErrorCount>) -> impl IntoView {
+    let busiest = counts
+        .iter()
+        .map(|count| count.count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    view! {
+        <div class="admin-error-trend">
+            {counts.into_iter().map(|count| {
+                let height = count.count as f64 / busiest as f64 * 100.0;
+                view! {
+                    <div
+                        class="admin-error-trend-bar"
+                        style=format!("height: {:.0}%", height)
+                        title=format!("{}: {}", count.day, count.count)
+                    ></div>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
+
+/// A group's trend and latest occurrences, loaded when it's expanded
+#[component]
+fn ErrorGroupDetails(fingerprint: String, days: i32) -> impl IntoView {
+    let counts = RwSignal::new(Vec::<ErrorCount>::new());
+    let samples = RwSignal::new(Vec::<ErrorLog>::new());
+    let error_message = RwSignal::new(Option::<String>::None);
+
+    if let Some(token) = get_auth_token() {
+        spawn_local(async move {
+            let loaded =
+                match get_error_counts(token.clone(), Some(fingerprint.clone()), days).await {
+                    Ok(result) => {
+                        counts.set(result);
+                        get_error_samples(token, fingerprint).await
+                    }
+                    Err(e) => Err(e),
+                };
+            match loaded {
+                Ok(result) => samples.set(result),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+        });
+    }
+
+    view! {
+        <div class="admin-error-details">
+            {move || error_message.get().map(|message| view! {
+                <div class="admin-error-message">{message}</div>
+            })}
+            {move || view! { <ErrorTrend counts=counts.get() /> }}
+            <ul class="admin-error-samples">
+                <For
+                    each=move || samples.get()
+                    key=|sample| sample.id
+                    children=|sample: ErrorLog| view! {
+                        <li>
+                            <p class="admin-artist-created">
+                                {format!(
+                                    "{} · {}{}",
+                                    sample.timestamp,
+                                    sample.url_path.clone().unwrap_or_else(|| "unknown page".to_string()),
+                                    sample.user_id.map(|id| format!(" · user #{}", id)).unwrap_or_default(),
+                                )}
+                            </p>
+                            <p class="admin-question-text">{sample.error_message.clone()}</p>
+                            {sample.symbolicated_stack.clone().or(sample.error_stack.clone()).map(|stack| view! {
+                                <pre class="admin-error-stack">{stack}</pre>
+                            })}
+                        </li>
+                    }
+                />
+            </ul>
+        </div>
+    }
+}
+
+#[component]
+pub fn AdminErrors() -> impl IntoView {
+    let navigate = use_navigate();
+    let status = RwSignal::new("open".to_string());
+    let error_type = RwSignal::new(String::new());
+    let search = RwSignal::new(String::new());
+    let days = RwSignal::new(7);
+    let groups = RwSignal::new(Vec::<ErrorGroup>::new());
+    let counts = RwSignal::new(Vec::<ErrorCount>::new());
+    let assignees = RwSignal::new(Vec::<ErrorAssignee>::new());
+    let expanded = RwSignal::new(Option::<String>::None);
+    let updating = RwSignal::new(Option::<String>::None);
+    let loading = RwSignal::new(false);
+    let error_message = RwSignal::new(Option::<String>::None);
+
+    let fetch_groups = move || {
+        let Some(token) = get_auth_token() else {
+            error_message.set(Some("Not authenticated. Please log in.".to_string()));
+            return;
+        };
+
+        loading.set(true);
+        error_message.set(None);
+        expanded.set(None);
+
+        spawn_local(async move {
+            let window = days.get_untracked();
+            let loaded = match get_error_groups(
+                token.clone(),
+                status.get_untracked(),
+                error_type.get_untracked(),
+                search.get_untracked(),
+                window,
+            )
+            .await
+            {
+                Ok(result) => {
+                    groups.set(result);
+                    get_error_counts(token, None, window).await
+                }
+                Err(e) => Err(e),
+            };
+            match loaded {
+                Ok(result) => counts.set(result),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+            loading.set(false);
+        });
+    };
+
+    // Initial load
+    Effect::new(move |_| {
+        fetch_groups();
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                if let Ok(result) = get_error_assignees(token).await {
+                    assignees.set(result);
+                }
+            });
+        }
+    });
+
+    let select_status = move |selected: &'static str| {
+        status.set(selected.to_string());
+        fetch_groups();
+    };
+
+    let triage = move |fingerprint: String, new_status: &'static str| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        updating.set(Some(fingerprint.clone()));
+        spawn_local(async move {
+            match set_error_status(token, fingerprint.clone(), new_status.to_string()).await {
+                Ok(()) => fetch_groups(),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+            updating.set(None);
+        });
+    };
+
+    let assign = move |fingerprint: String, assignee: Option<i64>| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            match assign_error_group(token, fingerprint.clone(), assignee).await {
+                Ok(()) => groups.update(|list| {
+                    if let Some(group) = list.iter_mut().find(|g| g.fingerprint == fingerprint) {
+                        group.assigned_to = assignee;
+                    }
+                }),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+        });
+    };
+
+    view! {
+        <div class="admin-errors">
+            <div class="admin-validate-header">
+                <button
+                    class="admin-back-button"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| navigate("/admin/dashboard", Default::default())
+                    }
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                        <polyline points="15 18 9 12 15 6"></polyline>
+                    </svg>
+                    "Back to Dashboard"
+                </button>
+                <h1>"Errors"</h1>
+                <p>"Logged errors grouped by cause. Assign a group, then resolve it once it's fixed; it reopens if it happens again."</p>
+            </div>
+
+            <Show when=move || error_message.get().is_some()>
+                <div class="admin-error-message">
+                    {move || error_message.get().unwrap_or_default()}
+                </div>
+            </Show>
+
+            <div class="admin-license-tabs">
+                <button
+                    class="admin-license-tab"
+                    class:selected=move || status.get().is_empty()
+                    on:click=move |_| select_status("")
+                >
+                    "All"
+                </button>
+                {ERROR_STATUSES.iter().map(|tab| {
+                    let tab = *tab;
+                    view! {
+                        <button
+                            class="admin-license-tab"
+                            class:selected=move || status.get() == tab
+                            on:click=move |_| select_status(tab)
+                        >
+                            {status_label(tab)}
+                        </button>
+                    }
+                }).collect_view()}
+            </div>
+
+            <div class="admin-error-filters">
+                <select
+                    on:change=move |ev| {
+                        error_type.set(event_target_value(&ev));
+                        fetch_groups();
+                    }
+                >
+                    <option value="">"All types"</option>
+                    {ERROR_TYPES.iter().map(|error_type| view! {
+                        <option value=*error_type>{*error_type}</option>
+                    }).collect_view()}
+                </select>
+                <select
+                    on:change=move |ev| {
+                        if let Ok(selected) = event_target_value(&ev).parse() {
+                            days.set(selected);
+                            fetch_groups();
+                        }
+                    }
+                >
+                    {WINDOWS.iter().map(|(window, label)| view! {
+                        <option value=window.to_string() selected=move || days.get() == *window>
+                            {*label}
+                        </option>
+                    }).collect_view()}
+                </select>
+                <input
+                    type="search"
+                    placeholder="Search messages and frames"
+                    prop:value=move || search.get()
+                    on:input=move |ev| search.set(event_target_value(&ev))
+                    on:keydown=move |ev| {
+                        if ev.key() == "Enter" {
+                            fetch_groups();
+                        }
+                    }
+                />
+            </div>
+
+            <div class="admin-error-overview">
+                <h2>
+                    {move || format!(
+                        "{} errors logged",
+                        counts.get().iter().map(|count| count.count).sum::<i64>(),
+                    )}
+                </h2>
+                {move || view! { <ErrorTrend counts=counts.get() /> }}
+            </div>
+
+            <Show
+                when=move || loading.get()
+                fallback=move || view! {
+                    <Show
+                        when=move || !groups.get().is_empty()
+                        fallback=|| view! {
+                            <div class="admin-empty-state">"No errors here"</div>
+                        }
+                    >
+                        <div class="admin-question-list">
+                            <For
+                                each=move || groups.get()
+                                key=|group| (group.fingerprint.clone(), group.status.clone(), group.recent_occurrences)
+                                children=move |group: ErrorGroup| {
+                                    let fingerprint = group.fingerprint.clone();
+                                    let is_expanded = {
+                                        let fingerprint = fingerprint.clone();
+                                        move || expanded.get().as_deref() == Some(fingerprint.as_str())
+                                    };
+                                    let busy = {
+                                        let fingerprint = fingerprint.clone();
+                                        move || updating.get().as_deref() == Some(fingerprint.as_str())
+                                    };
+                                    let actions: Vec<(&'static str, &'static str)> = match group.status.as_str() {
+                                        "open" => vec![("resolved", "Mark Resolved"), ("ignored", "Ignore")],
+                                        _ => vec![("open", "Reopen")],
+                                    };
+                                    let assigned_to = group.assigned_to;
+                                    view! {
+                                        <div class="admin-question-card admin-error-group">
+                                            <div class="admin-question-info">
+                                                <h3>{group.title.clone()}</h3>
+                                                {group.top_frame.clone().map(|frame| view! {
+                                                    <p class="admin-error-frame">{frame}</p>
+                                                })}
+                                                <div class="admin-error-tags">
+                                                    <span class="admin-tag">{group.error_type.clone()}</span>
+                                                    <span class="admin-tag">{group.error_level.clone()}</span>
+                                                    <span class="admin-tag">{status_label(&group.status)}</span>
+                                                    {group.reopened_at.clone().map(|reopened_at| view! {
+                                                        <span class="admin-tag admin-tag-regressed" title=format!("Reopened {}", reopened_at)>
+                                                            "Regressed"
+                                                        </span>
+                                                    })}
+                                                </div>
+                                                <p class="admin-question-answer">
+                                                    {format!("{} in this window · {} total", group.recent_occurrences, group.occurrences)}
+                                                </p>
+                                                <p class="admin-artist-created">
+                                                    {format!("First seen {} · last seen {}", group.first_seen, group.last_seen)}
+                                                </p>
+                                                <button
+                                                    class="btn btn-link"
+                                                    on:click={
+                                                        let fingerprint = fingerprint.clone();
+                                                        let is_expanded = is_expanded.clone();
+                                                        move |_| expanded.set((!is_expanded()).then(|| fingerprint.clone()))
+                                                    }
+                                                >
+                                                    {
+                                                        let is_expanded = is_expanded.clone();
+                                                        move || if is_expanded() { "Hide occurrences" } else { "Show occurrences" }
+                                                    }
+                                                </button>
+                                                <Show when=is_expanded>
+                                                    <ErrorGroupDetails fingerprint=fingerprint.clone() days=days.get_untracked() />
+                                                </Show>
+                                            </div>
+                                            <div class="admin-question-actions">
+                                                <select
+                                                    on:change={
+                                                        let fingerprint = fingerprint.clone();
+                                                        move |ev| assign(fingerprint.clone(), event_target_value(&ev).parse().ok())
+                                                    }
+                                                >
+                                                    <option value="" selected=assigned_to.is_none()>"Unassigned"</option>
+                                                    {move || assignees.get().into_iter().map(|assignee| view! {
+                                                        <option
+                                                            value=assignee.user_id.to_string()
+                                                            selected=assigned_to == Some(assignee.user_id)
+                                                        >
+                                                            {assignee.email}
+                                                        </option>
+                                                    }).collect_view()}
+                                                </select>
+                                                {actions.into_iter().map(|(new_status, label)| {
+                                                    let fingerprint = fingerprint.clone();
+                                                    let busy = busy.clone();
+                                                    view! {
+                                                        <button
+                                                            class="btn btn-secondary"
+                                                            disabled=busy
+                                                            on:click=move |_| triage(fingerprint.clone(), new_status)
+                                                        >
+                                                            {label}
+                                                        </button>
+                                                    }
+                                                }).collect_view()}
+                                            </div>
+                                        </div>
+                                    }
+                                }
+                            />
+                        </div>
+                    </Show>
+                }
+            >
+                <div class="admin-loading">
+                    <p>"Loading errors..."</p>
+                </div>
+            </Show>
+        </div>
+    }
+}
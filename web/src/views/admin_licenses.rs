@@ -0,0 +1,223 @@
+use crate::api_error::user_message;
+use crate::db::entities::ArtistLicense;
+use crate::server_licenses::{
+    get_license_display_states, get_licenses_for_review, review_license, set_license_display_state,
+};
+use crate::utils::auth::get_auth_token;
+use crate::utils::licensing::{status_label, US_STATES, VERIFICATION_STATUSES};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_navigate;
+
+#[component]
+pub fn AdminLicenses() -> impl IntoView {
+    let navigate = use_navigate();
+    let status = RwSignal::new("pending".to_string());
+    let licenses = RwSignal::new(Vec::<ArtistLicense>::new());
+    let display_states = RwSignal::new(Vec::<String>::new());
+    let loading = RwSignal::new(false);
+    let error_message = RwSignal::new(Option::<String>::None);
+
+    let fetch_licenses = move || {
+        let Some(token) = get_auth_token() else {
+            error_message.set(Some("Not authenticated. Please log in.".to_string()));
+            return;
+        };
+
+        loading.set(true);
+        error_message.set(None);
+
+        spawn_local(async move {
+            match get_licenses_for_review(token, status.get_untracked()).await {
+                Ok(result) => licenses.set(result),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+            loading.set(false);
+        });
+    };
+
+    let fetch_display_states = move || {
+        spawn_local(async move {
+            match get_license_display_states().await {
+                Ok(states) => display_states.set(states),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+        });
+    };
+
+    // Initial load
+    Effect::new(move |_| {
+        fetch_licenses();
+        fetch_display_states();
+    });
+
+    let select_status = move |selected: &'static str| {
+        status.set(selected.to_string());
+        fetch_licenses();
+    };
+
+    let review = move |license_id: i32, approve: bool, reason: Option<String>| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            match review_license(token, license_id, approve, reason).await {
+                Ok(()) => licenses.update(|list| list.retain(|license| license.id != license_id)),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+        });
+    };
+
+    let toggle_display_state = move |state: &'static str, required: bool| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            match set_license_display_state(token, state.to_string(), required).await {
+                Ok(()) => fetch_display_states(),
+                Err(e) => error_message.set(Some(user_message(&e))),
+            }
+        });
+    };
+
+    view! {
+        <div class="admin-licenses">
+            <div class="admin-validate-header">
+                <button
+                    class="admin-back-button"
+                    on:click={
+                        let navigate = navigate.clone();
+                        move |_| navigate("/admin/dashboard", Default::default())
+                    }
+                >
+                    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                        <polyline points="15 18 9 12 15 6"></polyline>
+                    </svg>
+                    "Back to Dashboard"
+                </button>
+                <h1>"Artist Licenses"</h1>
+                <p>"Check each license against its document. Verified, unexpired licenses are shown on profiles in the states selected below."</p>
+            </div>
+
+            <Show when=move || error_message.get().is_some()>
+                <div class="admin-error-message">
+                    {move || error_message.get().unwrap_or_default()}
+                </div>
+            </Show>
+
+            <div class="admin-license-tabs">
+                {VERIFICATION_STATUSES.iter().map(|tab| {
+                    let tab = *tab;
+                    view! {
+                        <button
+                            class="admin-license-tab"
+                            class:selected=move || status.get() == tab
+                            on:click=move |_| select_status(tab)
+                        >
+                            {status_label(tab)}
+                        </button>
+                    }
+                }).collect_view()}
+            </div>
+
+            <Show
+                when=move || loading.get()
+                fallback=move || view! {
+                    <Show
+                        when=move || !licenses.get().is_empty()
+                        fallback=|| view! {
+                            <div class="admin-empty-state">"No licenses here"</div>
+                        }
+                    >
+                        <div class="admin-license-list">
+                            <For
+                                each=move || licenses.get()
+                                key=|license| (license.id, license.updated_at.clone())
+                                children=move |license: ArtistLicense| {
+                                    let license_id = license.id;
+                                    let reason = RwSignal::new(String::new());
+                                    let can_verify = license.verification_status != "verified";
+                                    let can_reject = license.verification_status != "rejected";
+                                    view! {
+                                        <div class="admin-license-card">
+                                            <div class="admin-license-info">
+                                                <h3>
+                                                    <a href=format!("/artist/{}", license.artist_id) target="_blank">
+                                                        {license.artist_name.clone().unwrap_or_else(|| "Unknown Artist".to_string())}
+                                                    </a>
+                                                </h3>
+                                                <p>{format!("{} · {}", license.issuing_state, license.license_number)}</p>
+                                                <p>{license.expires_on.clone().map(|date| format!("Expires {}", date)).unwrap_or_else(|| "No expiry date".to_string())}</p>
+                                                {license.display_required.then(|| view! {
+                                                    <span class="admin-tag">"Shown on profile"</span>
+                                                })}
+                                                {license.rejection_reason.clone().map(|reason| view! {
+                                                    <p class="admin-license-rejection">{format!("Rejected: {}", reason)}</p>
+                                                })}
+                                                <p class="admin-artist-created">"Updated: " {license.updated_at.clone()}</p>
+                                            </div>
+                                            <div class="admin-license-actions">
+                                                {match license.document_url.clone() {
+                                                    Some(url) => view! {
+                                                        <a href=url target="_blank" class="admin-license-document">"View document"</a>
+                                                    }.into_any(),
+                                                    None => view! {
+                                                        <span class="admin-license-document missing">"No document uploaded"</span>
+                                                    }.into_any(),
+                                                }}
+                                                <Show when=move || can_verify>
+                                                    <button class="btn btn-primary" on:click=move |_| review(license_id, true, None)>
+                                                        "Verify"
+                                                    </button>
+                                                </Show>
+                                                <Show when=move || can_reject>
+                                                    <input
+                                                        type="text"
+                                                        placeholder="Reason for rejecting"
+                                                        maxlength="500"
+                                                        prop:value=move || reason.get()
+                                                        on:input=move |ev| reason.set(event_target_value(&ev))
+                                                    />
+                                                    <button
+                                                        class="btn btn-outline-danger"
+                                                        on:click=move |_| review(license_id, false, Some(reason.get_untracked()))
+                                                    >
+                                                        "Reject"
+                                                    </button>
+                                                </Show>
+                                            </div>
+                                        </div>
+                                    }
+                                }
+                            />
+                        </div>
+                    </Show>
+                }
+            >
+                <div class="admin-loading">
+                    <p>"Loading licenses..."</p>
+                </div>
+            </Show>
+
+            <div class="admin-license-display-states">
+                <h2>"States requiring licenses on profiles"</h2>
+                <div class="admin-license-state-grid">
+                    {US_STATES.iter().map(|state| {
+                        let state = *state;
+                        let required = move || display_states.get().iter().any(|s| s == state);
+                        view! {
+                            <label class="admin-license-state">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=required
+                                    on:change=move |ev| toggle_display_state(state, event_target_checked(&ev))
+                                />
+                                <span>{state}</span>
+                            </label>
+                        }
+                    }).collect_view()}
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -1,3 +1,4 @@
+use crate::api_error::user_message;
 use crate::server::login_user;
 use crate::views::auth::LoginData;
 use leptos::{prelude::*, task::spawn_local};
@@ -41,6 +42,9 @@ pub fn AdminLoginPage() -> impl IntoView {
                                 }
 
                                 setItem("tatteau_auth_token", token);
+                                if let Some(refresh_token) = &auth_response.refresh_token {
+                                    setItem("tatteau_refresh_token", refresh_token);
+                                }
                             }
                         }
 
@@ -53,7 +57,7 @@ pub fn AdminLoginPage() -> impl IntoView {
                     }
                 }
                 Err(e) => {
-                    error_message.set(Some(format!("Login failed: {}", e)));
+                    error_message.set(Some(user_message(&e)));
                 }
             }
             loading.set(false);
@@ -0,0 +1,231 @@
+use crate::db::entities::{AutoResponseSettings, AutoResponseTemplate};
+use crate::server_auto_response::{get_auto_response_settings, update_auto_response_settings};
+use crate::utils::auth::get_auth_token;
+use crate::utils::auto_response::{
+    render, TemplateValues, BOOKING_TYPES, DEFAULT_VARIANT, MAX_DELAY_MINUTES, MAX_TEMPLATE_LEN,
+    TEMPLATE_VARIABLES,
+};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Values the preview fills the default reply in with
+fn preview_values() -> TemplateValues {
+    TemplateValues {
+        client_name: "Alex Rivera".to_string(),
+        artist_name: "You".to_string(),
+        booking_type: "custom".to_string(),
+        requested_date: "Friday, June 14".to_string(),
+        requested_time: "2:00 PM".to_string(),
+        minimum_charge: Some(150.0),
+        hourly_rate: Some(200.0),
+        response_time: Some("a day".to_string()),
+    }
+}
+
+/// Settings card where an artist writes the automatic first reply clients get
+/// when they send a booking request, with optional variants per booking type
+#[component]
+pub fn AutoReplySettings() -> impl IntoView {
+    let enabled = RwSignal::new(false);
+    let delay_minutes = RwSignal::new(String::from("0"));
+    let default_message = RwSignal::new(String::new());
+    // One editor per booking type, blank to use the default reply
+    let variants = BOOKING_TYPES
+        .iter()
+        .map(|(booking_type, label)| (*booking_type, *label, RwSignal::new(String::new())))
+        .collect::<Vec<_>>();
+    let saving = RwSignal::new(false);
+    let auto_reply_error = RwSignal::new(None::<String>);
+    let saved = RwSignal::new(false);
+
+    let load = {
+        let variants = variants.clone();
+        move |settings: AutoResponseSettings| {
+            enabled.set(settings.enabled);
+            delay_minutes.set(settings.delay_minutes.to_string());
+            let message_for = |booking_type: &str| {
+                settings
+                    .templates
+                    .iter()
+                    .find(|template| template.booking_type == booking_type)
+                    .map(|template| template.message.clone())
+                    .unwrap_or_default()
+            };
+            default_message.set(message_for(DEFAULT_VARIANT));
+            for (booking_type, _, message) in &variants {
+                message.set(message_for(booking_type));
+            }
+        }
+    };
+
+    Effect::new({
+        let load = load.clone();
+        move |_| {
+            let load = load.clone();
+            if let Some(token) = get_auth_token() {
+                spawn_local(async move {
+                    match get_auto_response_settings(token).await {
+                        Ok(settings) => load(settings),
+                        Err(e) => auto_reply_error.set(Some(e.to_string())),
+                    }
+                });
+            }
+        }
+    });
+
+    let collect_settings = {
+        let variants = variants.clone();
+        move || -> Result<AutoResponseSettings, String> {
+            let delay = delay_minutes.get_untracked();
+            let delay_minutes = delay
+                .trim()
+                .parse::<i32>()
+                .ok()
+                .filter(|minutes| (0..=MAX_DELAY_MINUTES).contains(minutes))
+                .ok_or_else(|| {
+                    format!("Delay must be a number of minutes up to {}", MAX_DELAY_MINUTES)
+                })?;
+
+            let templates = std::iter::once((DEFAULT_VARIANT, default_message))
+                .chain(
+                    variants
+                        .iter()
+                        .map(|(booking_type, _, message)| (*booking_type, *message)),
+                )
+                .map(|(booking_type, message)| AutoResponseTemplate {
+                    booking_type: booking_type.to_string(),
+                    message: message.get_untracked(),
+                })
+                .filter(|template| !template.message.trim().is_empty())
+                .collect();
+
+            Ok(AutoResponseSettings {
+                enabled: enabled.get_untracked(),
+                delay_minutes,
+                templates,
+            })
+        }
+    };
+
+    let save_auto_reply = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let settings = match collect_settings() {
+            Ok(settings) => settings,
+            Err(e) => {
+                auto_reply_error.set(Some(e));
+                return;
+            }
+        };
+        let load = load.clone();
+        saving.set(true);
+        saved.set(false);
+        spawn_local(async move {
+            match update_auto_response_settings(token, settings).await {
+                Ok(settings) => {
+                    load(settings);
+                    auto_reply_error.set(None);
+                    saved.set(true);
+                }
+                Err(e) => auto_reply_error.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="settings-card auto-reply-settings">
+            <h2>"Booking Auto-Reply"</h2>
+
+            {move || auto_reply_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <div class="setting-group">
+                <label class="setting-label">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || enabled.get()
+                        on:change=move |ev| enabled.set(event_target_checked(&ev))
+                    />
+                    <span>"Reply automatically to new booking requests"</span>
+                </label>
+                <p class="setting-description">
+                    "Sent as your first message in the request's thread. If you reply yourself before it goes out, it isn't sent."
+                </p>
+            </div>
+
+            <div class="setting-group">
+                <label class="setting-label">"Wait Before Sending"</label>
+                <div class="delay-input">
+                    <input
+                        type="number"
+                        min="0"
+                        max=MAX_DELAY_MINUTES.to_string()
+                        prop:value=move || delay_minutes.get()
+                        on:input=move |ev| delay_minutes.set(event_target_value(&ev))
+                    />
+                    <span>"minutes"</span>
+                </div>
+                <p class="setting-description">"0 sends it as soon as the request arrives"</p>
+            </div>
+
+            <div class="setting-group">
+                <label class="setting-label">"Reply"</label>
+                <textarea
+                    rows="4"
+                    maxlength=MAX_TEMPLATE_LEN.to_string()
+                    placeholder="Thanks {client_first_name}! I usually reply within {response_time}. My minimum is {minimum_charge}."
+                    prop:value=move || default_message.get()
+                    on:input=move |ev| default_message.set(event_target_value(&ev))
+                ></textarea>
+                {move || {
+                    let message = default_message.get();
+                    (!message.trim().is_empty()).then(|| view! {
+                        <p class="auto-reply-preview">{render(&message, &preview_values())}</p>
+                    })
+                }}
+                <p class="setting-description">"Placeholders you can use:"</p>
+                <ul class="auto-reply-variables">
+                    {TEMPLATE_VARIABLES.iter().map(|(name, description)| view! {
+                        <li>
+                            <code>{format!("{{{}}}", name)}</code>
+                            " "
+                            {*description}
+                        </li>
+                    }).collect_view()}
+                </ul>
+            </div>
+
+            <div class="setting-group">
+                <label class="setting-label">"Replies by Booking Type"</label>
+                <p class="setting-description">"Leave blank to send the reply above"</p>
+                {variants.into_iter().map(|(_, label, message)| view! {
+                    <div class="auto-reply-variant">
+                        <span class="auto-reply-variant-label">{label}</span>
+                        <textarea
+                            rows="3"
+                            maxlength=MAX_TEMPLATE_LEN.to_string()
+                            prop:value=move || message.get()
+                            on:input=move |ev| message.set(event_target_value(&ev))
+                        ></textarea>
+                    </div>
+                }).collect_view()}
+            </div>
+
+            <div class="setting-actions">
+                <button
+                    class="btn btn-primary"
+                    disabled=move || saving.get()
+                    on:click=save_auto_reply
+                >
+                    {move || if saving.get() { "Saving..." } else { "Save Auto-Reply" }}
+                </button>
+                <Show when=move || saved.get()>
+                    <span class="save-confirmation">"Saved"</span>
+                </Show>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,132 @@
+use crate::server_completeness::{
+    get_artist_bio, get_artist_experience_tier, update_artist_bio, update_artist_experience_tier,
+    MAX_BIO_CHARS,
+};
+use crate::utils::auth::get_auth_token;
+use crate::utils::completeness::TARGET_BIO_CHARS;
+use crate::utils::experience::{tier_label, EXPERIENCE_TIERS};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Settings card for the artist's bio and experience tier, shown on their
+/// public profile
+#[component]
+pub fn BioSettings() -> impl IntoView {
+    let bio = RwSignal::new(String::new());
+    let tier = RwSignal::new(String::new());
+    let saving = RwSignal::new(false);
+    let bio_error = RwSignal::new(None::<String>);
+    let saved = RwSignal::new(false);
+    let tier_saved = RwSignal::new(false);
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                match get_artist_bio(token.clone()).await {
+                    Ok(text) => bio.set(text.unwrap_or_default()),
+                    Err(e) => bio_error.set(Some(e.to_string())),
+                }
+                match get_artist_experience_tier(token).await {
+                    Ok(current) => tier.set(current.unwrap_or_default()),
+                    Err(e) => bio_error.set(Some(e.to_string())),
+                }
+            });
+        }
+    });
+
+    let save_tier = move |ev| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let selected = event_target_value(&ev);
+        tier.set(selected.clone());
+        tier_saved.set(false);
+        spawn_local(async move {
+            match update_artist_experience_tier(token, selected).await {
+                Ok(()) => {
+                    bio_error.set(None);
+                    tier_saved.set(true);
+                }
+                Err(e) => bio_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let save_bio = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        saving.set(true);
+        saved.set(false);
+        spawn_local(async move {
+            match update_artist_bio(token, bio.get_untracked()).await {
+                Ok(()) => {
+                    bio_error.set(None);
+                    saved.set(true);
+                }
+                Err(e) => bio_error.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="settings-card bio-settings">
+            <h2>"Bio"</h2>
+
+            {move || bio_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <div class="setting-group">
+                <textarea
+                    rows="5"
+                    maxlength=MAX_BIO_CHARS.to_string()
+                    placeholder="How you got started, what you love tattooing, what clients can expect..."
+                    prop:value=move || bio.get()
+                    on:input=move |ev| bio.set(event_target_value(&ev))
+                ></textarea>
+                <p class="setting-description">
+                    {move || {
+                        let chars = bio.get().trim().chars().count();
+                        if chars < TARGET_BIO_CHARS {
+                            format!("{} characters. Aim for at least {}.", chars, TARGET_BIO_CHARS)
+                        } else {
+                            format!("{} characters", chars)
+                        }
+                    }}
+                </p>
+            </div>
+
+            <div class="setting-group">
+                <label for="experience-tier">"Experience level"</label>
+                <select id="experience-tier" prop:value=move || tier.get() on:change=save_tier>
+                    <option value="">"Not specified"</option>
+                    {EXPERIENCE_TIERS.iter().map(|option| view! {
+                        <option value=*option>{tier_label(option)}</option>
+                    }).collect_view()}
+                </select>
+                <p class="setting-description">
+                    "Shown as a badge on your profile. Clients on a tight budget see apprentices "
+                    "and junior artists first."
+                    <Show when=move || tier_saved.get()>
+                        " Saved."
+                    </Show>
+                </p>
+            </div>
+
+            <div class="setting-actions">
+                <button
+                    class="btn btn-primary"
+                    disabled=move || saving.get()
+                    on:click=save_bio
+                >
+                    {move || if saving.get() { "Saving..." } else { "Save Bio" }}
+                </button>
+                <Show when=move || saved.get()>
+                    <span class="save-confirmation">"Saved"</span>
+                </Show>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,98 @@
+use crate::api_error::user_message;
+use crate::server_archive::{get_booking_archive_status, request_booking_restore};
+use crate::utils::archive::MAX_REASON_CHARS;
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Shown on an archived booking: its details, messages and timeline are in
+/// cold storage, and the artist can ask an admin to restore them
+#[component]
+pub fn BookingArchiveNotice(booking_id: i32) -> impl IntoView {
+    let status = RwSignal::new(None);
+    let reason = RwSignal::new(String::new());
+    let submitting = RwSignal::new(false);
+    let restore_error = RwSignal::new(None::<String>);
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                if let Ok(archived) = get_booking_archive_status(token, booking_id).await {
+                    status.set(archived);
+                }
+            });
+        }
+    });
+
+    let request_restore = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        submitting.set(true);
+        spawn_local(async move {
+            match request_booking_restore(token, booking_id, reason.get_untracked()).await {
+                Ok(()) => {
+                    restore_error.set(None);
+                    status.update(|status| {
+                        if let Some(status) = status {
+                            status.restore_status = Some("pending".to_string());
+                        }
+                    });
+                }
+                Err(e) => restore_error.set(Some(user_message(&e))),
+            }
+            submitting.set(false);
+        });
+    };
+
+    move || {
+        status.get().map(|archived| {
+            let restore = match archived.restore_status.as_deref() {
+                Some("pending") => view! {
+                    <p class="booking-details-archive-state">"Restore requested. An admin will review it shortly."</p>
+                }
+                .into_any(),
+                Some("failed") => view! {
+                    <p class="booking-details-archive-state">"Restoring this booking failed. An admin will retry it."</p>
+                }
+                .into_any(),
+                _ => view! {
+                    <div class="booking-details-archive-restore">
+                        {move || restore_error.get().map(|error| view! {
+                            <div class="error-message">{error}</div>
+                        })}
+                        <textarea
+                            class="booking-details-archive-reason-input"
+                            rows="3"
+                            placeholder="Why do you need this booking back? e.g. a touch-up or a dispute"
+                            maxlength=MAX_REASON_CHARS
+                            prop:value=move || reason.get()
+                            on:input=move |ev| reason.set(event_target_value(&ev))
+                        ></textarea>
+                        <button
+                            class="btn btn-secondary"
+                            disabled=move || submitting.get() || reason.get().trim().is_empty()
+                            on:click=request_restore
+                        >
+                            {move || if submitting.get() { "Requesting..." } else { "Request Restore" }}
+                        </button>
+                    </div>
+                }
+                .into_any(),
+            };
+
+            view! {
+                <div class="booking-details-archive-card">
+                    <h2>"Archived"</h2>
+                    <p>
+                        {format!(
+                            "This booking was archived on {}. Its details, messages and timeline were moved to cold storage.",
+                            archived.archived_on
+                        )}
+                    </p>
+                    {restore}
+                </div>
+            }
+        })
+    }
+}
@@ -1,18 +1,27 @@
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos::wasm_bindgen::JsCast;
 use thaw::*;
+use shared_types::datetime::{Date, Time};
+use shared_types::BookingStatus;
 use web_sys::HtmlInputElement;
 
-use crate::db::entities::{BookingMessage, BookingRequest};
+use crate::api_error::user_message;
+use crate::components::MessageText;
+use crate::db::entities::{BookingEvent, BookingEventKind, BookingMessage, BookingRequest};
 use crate::server::{
-    get_booking_messages, get_booking_request_by_id, get_client_booking_history,
-    respond_to_booking, send_booking_message, suggest_booking_time, BookingHistoryEntry,
-    BookingResponse, BookingSuggestion, NewBookingMessage,
+    get_booking_messages, get_booking_request_by_id, get_booking_timeline,
+    get_client_booking_history, mark_thread_read, respond_to_booking, send_booking_message, suggest_booking_time,
+    BookingHistoryEntry, BookingResponse, BookingSuggestion, NewBookingMessage,
 };
+use crate::utils::auth::get_auth_token;
+use crate::utils::reschedule::cancellation_reason_label;
 use crate::utils::timezone::{
     format_date_for_booking, format_datetime_for_booking, format_time_range_with_timezone,
     format_time_with_timezone, get_timezone_abbreviation,
 };
+use crate::views::artist_dashboard::booking_archive::BookingArchiveNotice;
+use crate::views::artist_dashboard::documents::BookingConsentCard;
 
 #[component]
 pub fn BookingDetails(booking_id: i32) -> impl IntoView {
@@ -22,15 +31,44 @@ pub fn BookingDetails(booking_id: i32) -> impl IntoView {
     // Fetch booking data from database
     let booking_resource = Resource::new(
         move || booking_id,
-        move |id| async move { get_booking_request_by_id(id).await },
+        move |id| async move {
+            get_booking_request_by_id(id, get_auth_token().unwrap_or_default()).await
+        },
     );
 
     // Fetch booking messages
     let messages_resource = Resource::new(
         move || booking_id,
-        move |id| async move { get_booking_messages(id).await },
+        move |id| async move {
+            get_booking_messages(id, get_auth_token().unwrap_or_default()).await
+        },
+    );
+
+    // Having the thread open reads it, including messages that arrive while
+    // it is
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = messages_resource.get() {
+            spawn_local(async move {
+                let _ = mark_thread_read(booking_id, get_auth_token().unwrap_or_default()).await;
+            });
+        }
+    });
+
+    // Timeline of everything that has happened to the request
+    let timeline_resource = Resource::new(
+        move || booking_id,
+        move |id| async move {
+            get_booking_timeline(id, get_auth_token().unwrap_or_default()).await
+        },
     );
 
+    // Pull the thread (and the timeline it feeds) again whenever the server
+    // pushes a new message
+    use_booking_message_stream(booking_id, move || {
+        messages_resource.refetch();
+        timeline_resource.refetch();
+    });
+
     // Client booking history resource - will be fetched when booking data is available
     let history_resource = Resource::new(
         move || {
@@ -40,7 +78,9 @@ pub fn BookingDetails(booking_id: i32) -> impl IntoView {
         },
         move |client_email| async move {
             match client_email {
-                Some(email) => get_client_booking_history(email).await,
+                Some(email) => {
+                    get_client_booking_history(email, get_auth_token().unwrap_or_default()).await
+                }
                 None => Ok(vec![]),
             }
         },
@@ -58,6 +98,8 @@ pub fn BookingDetails(booking_id: i32) -> impl IntoView {
                                 Ok(booking) => view! {
                                     <BookingOverviewCard booking=booking.clone() timezone=timezone />
 
+                                    <BookingArchiveNotice booking_id=booking.id />
+
                                     <BookingDescriptionCard
                                         description=booking.tattoo_description.clone()
                                     />
@@ -66,6 +108,24 @@ pub fn BookingDetails(booking_id: i32) -> impl IntoView {
                                         message=booking.message_from_client.clone()
                                     />
 
+                                    <BookingConsentCard
+                                        booking_id=booking.id
+                                        status=booking.status.clone()
+                                    />
+
+                                    <Suspense fallback=|| view! { <div>"Loading timeline..."</div> }>
+                                        {move || {
+                                            timeline_resource.get().map(|timeline_result| {
+                                                view! {
+                                                    <BookingTimelineCard
+                                                        events=timeline_result.unwrap_or_default()
+                                                        timezone=timezone
+                                                    />
+                                                }
+                                            })
+                                        }}
+                                    </Suspense>
+
                                     <Suspense fallback=|| view! { <div>"Loading history..."</div> }>
                                         {move || {
                                             history_resource.get().map(|history_result| {
@@ -110,7 +170,7 @@ pub fn BookingDetails(booking_id: i32) -> impl IntoView {
                                 }.into_any(),
                                 Err(e) => view! {
                                     <div class="booking-details-error-message">
-                                        {format!("Failed to load booking: {}", e)}
+                                        {user_message(&e)}
                                     </div>
                                 }.into_any(),
                             }
@@ -144,15 +204,18 @@ fn BookingOverviewCard(booking: BookingRequest, timezone: ReadSignal<String>) ->
     );
     let status_icon = match booking.status.as_str() {
         "pending" => "⏳",
+        "needs_info" => "❓",
         "approved" => "✅",
+        "confirmed" => "📅",
         "declined" => "❌",
+        "completed" => "🎨",
+        "cancelled" => "🚫",
         _ => "📋",
     };
-    let status_text = match booking.status.as_str() {
-        "pending" => "Pending Review",
-        "approved" => "Approved",
-        "declined" => "Declined",
-        _ => &booking.status,
+    let status_text = match BookingStatus::parse(&booking.status) {
+        Some(BookingStatus::Pending) => "Pending Review",
+        Some(status) => status.label(),
+        None => &booking.status,
     };
 
     view! {
@@ -189,6 +252,14 @@ fn BookingOverviewCard(booking: BookingRequest, timezone: ReadSignal<String>) ->
                     )
                 />
 
+                {booking.guest_spot_shop.as_ref().map(|shop| view! {
+                    <BookingOverviewItem label="Guest Spot" value=shop.clone() />
+                })}
+
+                {booking.flash_design_title.as_ref().map(|title| view! {
+                    <BookingOverviewItem label="Flash" value=title.clone() />
+                })}
+
                 {booking.requested_end_time.as_ref().map(|end_time| view! {
                     <BookingOverviewItem
                         label="Duration"
@@ -196,6 +267,18 @@ fn BookingOverviewCard(booking: BookingRequest, timezone: ReadSignal<String>) ->
                     />
                 })}
 
+                {booking.deposit_amount.map(|amount| {
+                    let state = match booking.deposit_status.as_deref() {
+                        Some("paid") => "paid",
+                        Some("waived") => "waived",
+                        Some("refunded") => "refunded",
+                        _ => "awaiting payment",
+                    };
+                    view! {
+                        <BookingOverviewItem label="Deposit" value=format!("${:.2} ({})", amount, state) />
+                    }
+                })}
+
                 <BookingOverviewItem
                     label="Submitted"
                     value=booking.created_at.as_ref()
@@ -245,6 +328,145 @@ fn BookingClientMessageCard(message: Option<String>) -> impl IntoView {
     })
 }
 
+#[component]
+fn BookingTimelineCard(events: Vec<BookingEvent>, timezone: ReadSignal<String>) -> impl IntoView {
+    view! {
+        <div class="booking-details-timeline-card">
+            <h2>"Timeline"</h2>
+            {if events.is_empty() {
+                view! {
+                    <div class="booking-details-no-timeline">"Nothing recorded yet."</div>
+                }.into_any()
+            } else {
+                view! {
+                    <ol class="booking-details-timeline">
+                        {events.into_iter().map(|event| view! {
+                            <BookingTimelineItem event=event timezone=timezone />
+                        }).collect_view()}
+                    </ol>
+                }.into_any()
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn BookingTimelineItem(event: BookingEvent, timezone: ReadSignal<String>) -> impl IntoView {
+    let client = event.actor == "client";
+    let (icon, title, detail) = match event.kind {
+        BookingEventKind::Created => ("📝", "Request submitted".to_string(), None),
+        BookingEventKind::Viewed => ("👀", "Viewed by the artist".to_string(), None),
+        BookingEventKind::TimeSuggested {
+            date,
+            start_time,
+            end_time,
+        } => (
+            "📅",
+            if client {
+                "Client suggested another time".to_string()
+            } else {
+                "New time suggested".to_string()
+            },
+            Some(format!(
+                "{} at {}",
+                format_date_for_booking(&date),
+                format_time_range_with_timezone(&start_time, end_time.as_deref(), timezone)
+            )),
+        ),
+        BookingEventKind::Message {
+            sender_type,
+            message,
+        } => (
+            "💬",
+            match sender_type.as_str() {
+                "client" => "Client sent a message".to_string(),
+                "artist" => "You sent a message".to_string(),
+                _ => "Message".to_string(),
+            },
+            Some(message),
+        ),
+        BookingEventKind::Accepted { deposit_amount } => (
+            "✅",
+            "Accepted".to_string(),
+            deposit_amount.map(|amount| format!("${:.2} deposit requested", amount)),
+        ),
+        BookingEventKind::Declined { reason } => ("❌", "Declined".to_string(), reason),
+        BookingEventKind::DepositPaid { amount, currency } => (
+            "💳",
+            match (amount, currency) {
+                (Some(amount), Some(currency)) => {
+                    format!("Deposit of {:.2} {} paid", amount, currency.to_uppercase())
+                }
+                (Some(amount), None) => format!("Deposit of ${:.2} paid", amount),
+                _ => "Deposit paid".to_string(),
+            },
+            None,
+        ),
+        BookingEventKind::TimeAccepted {
+            date,
+            start_time,
+            end_time,
+        } => (
+            "🗓️",
+            if client {
+                "Client accepted the new time".to_string()
+            } else {
+                "You accepted the client's time".to_string()
+            },
+            Some(format!(
+                "{} at {}",
+                format_date_for_booking(&date),
+                format_time_range_with_timezone(&start_time, end_time.as_deref(), timezone)
+            )),
+        ),
+        BookingEventKind::TimeDeclined { note } => (
+            "🚫",
+            if client {
+                "Client declined the new time".to_string()
+            } else {
+                "You declined the client's time".to_string()
+            },
+            note,
+        ),
+        BookingEventKind::Cancelled { reason, note, late } => (
+            "🛑",
+            match (client, late) {
+                (true, true) => "Cancelled by the client, inside your window".to_string(),
+                (true, false) => "Cancelled by the client".to_string(),
+                _ => "Cancelled".to_string(),
+            },
+            Some(match note {
+                Some(note) => format!("{}: {}", cancellation_reason_label(&reason), note),
+                None => cancellation_reason_label(&reason).to_string(),
+            }),
+        ),
+        BookingEventKind::ConsentSigned { typed_name } => (
+            "✍️",
+            "Client signed the consent form".to_string(),
+            Some(format!("Signed as {}", typed_name)),
+        ),
+    };
+
+    view! {
+        <li class=if client {
+            "booking-details-timeline-item booking-details-timeline-client"
+        } else {
+            "booking-details-timeline-item"
+        }>
+            <span class="booking-details-timeline-icon">{icon}</span>
+            <div class="booking-details-timeline-body">
+                <div class="booking-details-timeline-title">{title}</div>
+                {detail.map(|detail| view! {
+                    <div class="booking-details-timeline-detail">{detail}</div>
+                })}
+                <div class="booking-details-timeline-time">
+                    {format_datetime_for_booking(&event.created_at, timezone)}
+                </div>
+            </div>
+        </li>
+    }
+}
+
 #[component]
 fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
     let booking_id = booking.id;
@@ -253,6 +475,9 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
     let (show_decline_modal, set_show_decline_modal) = signal(false);
     let decline_reason = RwSignal::new("".to_string());
 
+    // Deposit to require when accepting, blank for none
+    let deposit_amount = RwSignal::new("".to_string());
+
     // State for suggest date/time modal
     let (show_suggest_modal, set_show_suggest_modal) = signal(false);
     let suggested_date = RwSignal::new("".to_string());
@@ -260,20 +485,27 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
     let suggested_end_time = RwSignal::new("".to_string());
 
     // Actions for status updates
-    let accept_action = Action::new(move |_: &()| {
+    let accept_action = Action::new(move |deposit: &Option<f64>| {
         let booking_id = booking_id;
+        let deposit = *deposit;
         async move {
+            let artist_response = match deposit {
+                Some(amount) => format!(
+                    "Your booking has been approved! Please pay the ${:.2} deposit to confirm your appointment.",
+                    amount
+                ),
+                None => "Your booking has been approved! Looking forward to working with you."
+                    .to_string(),
+            };
             let response = BookingResponse {
                 booking_id,
-                status: "approved".to_string(),
-                artist_response: Some(
-                    "Your booking has been approved! Looking forward to working with you."
-                        .to_string(),
-                ),
+                status: BookingStatus::Accepted,
+                artist_response: Some(artist_response),
                 estimated_price: None,
                 decline_reason: None,
+                deposit_amount: deposit,
             };
-            respond_to_booking(response).await
+            respond_to_booking(response, get_auth_token().unwrap_or_default()).await
         }
     });
 
@@ -283,25 +515,62 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
         async move {
             let response = BookingResponse {
                 booking_id,
-                status: "declined".to_string(),
+                status: BookingStatus::Declined,
                 artist_response: Some(
                     "Unfortunately, I'm not able to take on this booking at this time.".to_string(),
                 ),
                 estimated_price: None,
                 decline_reason: Some(reason),
+                deposit_amount: None,
+            };
+            respond_to_booking(response, get_auth_token().unwrap_or_default()).await
+        }
+    });
+
+    // Moves that need nothing but the new status: asking for more info,
+    // confirming, completing and cancelling
+    let status_action = Action::new(move |status: &BookingStatus| {
+        let booking_id = booking_id;
+        let status = *status;
+        async move {
+            let artist_response = match status {
+                BookingStatus::NeedsInfo => {
+                    "Thanks for your request! I need a few more details before I can confirm. I'll follow up in the messages below."
+                }
+                BookingStatus::Confirmed => "Your appointment is confirmed. See you then!",
+                BookingStatus::Completed => "Thanks for coming in! Hope you love your new tattoo.",
+                BookingStatus::Cancelled => "This booking has been cancelled.",
+                _ => "",
+            };
+            let response = BookingResponse {
+                booking_id,
+                status,
+                artist_response: Some(artist_response.to_string()),
+                estimated_price: None,
+                decline_reason: None,
+                deposit_amount: None,
             };
-            respond_to_booking(response).await
+            respond_to_booking(response, get_auth_token().unwrap_or_default()).await
         }
     });
 
     let suggest_time_action = Action::new(move |suggestion: &BookingSuggestion| {
         let suggestion = suggestion.clone();
-        async move { suggest_booking_time(suggestion).await }
+        async move {
+            suggest_booking_time(suggestion, get_auth_token().unwrap_or_default()).await
+        }
     });
 
     // Event handlers
     let accept_booking = move |_| {
-        accept_action.dispatch(());
+        let deposit = deposit_amount
+            .get()
+            .trim()
+            .trim_start_matches('$')
+            .parse::<f64>()
+            .ok()
+            .filter(|amount| *amount > 0.0);
+        accept_action.dispatch(deposit);
     };
 
     let decline_booking = move |_| {
@@ -327,20 +596,20 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
     };
 
     let confirm_suggest = move |_| {
-        let date = suggested_date.get().trim().to_string();
-        let start_time = suggested_start_time.get().trim().to_string();
-        let end_time = suggested_end_time.get().trim().to_string();
+        let date = Date::parse(suggested_date.get().trim());
+        let start_time = Time::parse_lenient(&suggested_start_time.get());
+        // The end time is optional, but not when it's filled in wrong
+        let end_time = match suggested_end_time.get().trim() {
+            "" => Some(None),
+            end_time => Time::parse_lenient(end_time).map(Some),
+        };
 
-        if !date.is_empty() && !start_time.is_empty() {
+        if let (Some(date), Some(start_time), Some(end_time)) = (date, start_time, end_time) {
             let suggestion = BookingSuggestion {
                 booking_id,
                 suggested_date: date,
                 suggested_start_time: start_time,
-                suggested_end_time: if end_time.is_empty() {
-                    None
-                } else {
-                    Some(end_time)
-                },
+                suggested_end_time: end_time,
             };
             suggest_time_action.dispatch(suggestion);
             set_show_suggest_modal.set(false);
@@ -364,9 +633,21 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
             <h2>"Actions"</h2>
             <div class="booking-details-actions-grid">
                 {move || {
-                    let current_status = &booking.status;
-                    if current_status == "pending" {
+                    let current_status = BookingStatus::parse(&booking.status);
+                    if matches!(current_status, Some(BookingStatus::Pending | BookingStatus::NeedsInfo)) {
                         view! {
+                            <div class="booking-details-deposit-input">
+                                <label for="booking-deposit">"Deposit (optional)"</label>
+                                <input
+                                    id="booking-deposit"
+                                    type="number"
+                                    min="0"
+                                    step="0.01"
+                                    placeholder="0.00"
+                                    prop:value=move || deposit_amount.get()
+                                    on:input=move |ev| deposit_amount.set(event_target_value(&ev))
+                                />
+                            </div>
                             <Button
                                 appearance=ButtonAppearance::Primary
                                 on_click=accept_booking
@@ -388,18 +669,36 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
                             >
                                 {move || if suggest_time_action.pending().get() { "Suggesting..." } else { "Suggest New Date/Time" }}
                             </Button>
+                            <Show when=move || current_status == Some(BookingStatus::Pending)>
+                                <Button
+                                    appearance=ButtonAppearance::Subtle
+                                    on_click=move |_| { status_action.dispatch(BookingStatus::NeedsInfo); }
+                                    disabled=status_action.pending().get()
+                                >
+                                    "Ask for More Info"
+                                </Button>
+                            </Show>
                         }.into_any()
-                    } else if current_status == "approved" {
+                    } else if let Some(status @ (BookingStatus::Accepted | BookingStatus::Confirmed)) = current_status {
+                        let (next, next_label) = if status == BookingStatus::Accepted {
+                            (BookingStatus::Confirmed, "Mark Confirmed")
+                        } else {
+                            (BookingStatus::Completed, "Mark Completed")
+                        };
                         view! {
                             <Button
-                                appearance=ButtonAppearance::Secondary
-                                on_click=decline_booking
-                                disabled=decline_action.pending().get()
+                                appearance=ButtonAppearance::Primary
+                                on_click=move |_| { status_action.dispatch(next); }
+                                disabled=status_action.pending().get()
                             >
-                                {move || if decline_action.pending().get() { "Declining..." } else { "Decline Booking" }}
+                                {next_label}
                             </Button>
-                            <Button appearance=ButtonAppearance::Subtle>
-                                "Send Message"
+                            <Button
+                                appearance=ButtonAppearance::Secondary
+                                on_click=move |_| { status_action.dispatch(BookingStatus::Cancelled); }
+                                disabled=status_action.pending().get()
+                            >
+                                "Cancel Booking"
                             </Button>
                             <Button appearance=ButtonAppearance::Subtle>
                                 "Reschedule"
@@ -428,7 +727,7 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
                             <div class="booking-details-success-message">"Booking accepted successfully!"</div>
                         }.into_any(),
                         Err(e) => view! {
-                            <div class="booking-details-error-message">{format!("Failed to accept booking: {}", e)}</div>
+                            <div class="booking-details-error-message">{format!("Couldn't accept booking: {}", user_message(&e))}</div>
                         }.into_any(),
                     }
                 })
@@ -441,7 +740,20 @@ fn BookingActionsCard(booking: BookingRequest) -> impl IntoView {
                             <div class="booking-details-success-message">"Booking declined successfully!"</div>
                         }.into_any(),
                         Err(e) => view! {
-                            <div class="booking-details-error-message">{format!("Failed to decline booking: {}", e)}</div>
+                            <div class="booking-details-error-message">{format!("Couldn't decline booking: {}", user_message(&e))}</div>
+                        }.into_any(),
+                    }
+                })
+            }}
+
+            {move || {
+                status_action.value().get().map(|result| {
+                    match result {
+                        Ok(_) => view! {
+                            <div class="booking-details-success-message">"Booking updated successfully!"</div>
+                        }.into_any(),
+                        Err(e) => view! {
+                            <div class="booking-details-error-message">{format!("Couldn't update booking: {}", user_message(&e))}</div>
                         }.into_any(),
                     }
                 })
@@ -607,9 +919,12 @@ fn BookingHistoryItem(item: BookingHistoryEntry, timezone: ReadSignal<String>) -
     );
     let status_icon = match item.status.as_str() {
         "pending" => "⏳",
+        "needs_info" => "❓",
         "approved" => "✅",
+        "confirmed" => "📅",
         "declined" => "❌",
         "completed" => "🎨",
+        "cancelled" => "🚫",
         _ => "📋",
     };
 
@@ -648,6 +963,36 @@ fn BookingHistoryItem(item: BookingHistoryEntry, timezone: ReadSignal<String>) -
     }
 }
 
+/// Subscribes to the booking's message stream (`/api/bookings/:id/messages/stream`)
+/// and calls `on_update` when a message arrives or the server asks for a resync.
+/// The connection is closed when the calling component is disposed.
+fn use_booking_message_stream(booking_id: i32, on_update: impl Fn() + 'static) {
+    #[cfg(feature = "hydrate")]
+    {
+        use leptos::wasm_bindgen::closure::Closure;
+
+        let url = format!("/api/bookings/{}/messages/stream", booking_id);
+        let Ok(source) = web_sys::EventSource::new(&url) else {
+            leptos::logging::error!("Failed to open message stream for booking {}", booking_id);
+            return;
+        };
+
+        let callback = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(move |_| on_update());
+        for event in ["message", "resync"] {
+            let _ = source.add_event_listener_with_callback(event, callback.as_ref().unchecked_ref());
+        }
+
+        let stream = StoredValue::new_local((source, callback));
+        on_cleanup(move || {
+            stream.try_with_value(|(source, _)| source.close());
+        });
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        let _ = (booking_id, on_update);
+    }
+}
+
 #[component]
 pub fn BookingMessagesCard(
     messages: Vec<BookingMessage>,
@@ -662,7 +1007,9 @@ pub fn BookingMessagesCard(
             sender_type: "artist".to_string(),
             message: message_content.clone(),
         };
-        async move { send_booking_message(message_data).await }
+        async move {
+            send_booking_message(message_data, get_auth_token().unwrap_or_default()).await
+        }
     });
 
     let send_message = move |_| {
@@ -726,10 +1073,10 @@ pub fn BookingMessagesCard(
                 send_action.value().get().map(|result| {
                     match result {
                         Ok(_) => view! {
-                            <div class="booking-details-success-message">"Message sent successfully! Refresh to see it in the thread."</div>
+                            <div class="booking-details-success-message">"Message sent!"</div>
                         }.into_any(),
                         Err(e) => view! {
-                            <div class="booking-details-error-message">{format!("Failed to send message: {}", e)}</div>
+                            <div class="booking-details-error-message">{format!("Couldn't send message: {}", user_message(&e))}</div>
                         }.into_any(),
                     }
                 })
@@ -749,6 +1096,7 @@ fn BookingMessageItem(message: BookingMessage, timezone: ReadSignal<String>) ->
         "artist" => "You",
         _ => "System",
     };
+    let read = message.sender_type == "artist" && message.is_read;
 
     view! {
         <div class=sender_class>
@@ -761,8 +1109,11 @@ fn BookingMessageItem(message: BookingMessage, timezone: ReadSignal<String>) ->
                 </span>
             </div>
             <div class="booking-details-message-content">
-                {message.message}
+                <MessageText message=message reader="artist" />
             </div>
+            <Show when=move || read>
+                <div class="booking-details-message-read">"Read"</div>
+            </Show>
         </div>
     }
 }
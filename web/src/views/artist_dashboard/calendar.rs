@@ -2,15 +2,18 @@ use crate::components::{EventItem, EventItemData, TimeBlock, TimeBlockData};
 use crate::db::entities::{AvailabilitySlot, AvailabilityUpdate, BookingRequest, RecurringRule};
 use crate::server::{
     get_artist_availability, get_booking_requests, get_business_hours, get_effective_availability,
-    get_recurring_rules, set_artist_availability,
+    get_recurring_rules, get_unread_counts, set_artist_availability,
 };
-use crate::utils::auth::use_authenticated_artist_id;
+use crate::server_calendar::create_calendar_feed;
+use crate::utils::auth::{get_auth_token, use_authenticated_artist_id};
+use crate::utils::recurrence::stored_schedule_rule;
 use crate::utils::timezone::{
     format_time_range_with_timezone, format_time_with_timezone, get_timezone_abbreviation,
 };
+use crate::views::artist_dashboard::hints::DashboardHints;
 use leptos::prelude::*;
 use leptos::task::spawn_local;
-use serde_json;
+use shared_types::datetime::{Date, Time};
 use thaw::*;
 
 // Use the TimeBlockData from components
@@ -101,7 +104,9 @@ pub fn ArtistCalendar() -> impl IntoView {
         move || artist_id.get(),
         move |id_opt| async move {
             match id_opt {
-                Some(id) => get_recurring_rules(id).await.unwrap_or_else(|_| vec![]),
+                Some(id) => get_recurring_rules(id, get_auth_token().unwrap_or_default())
+                    .await
+                    .unwrap_or_else(|_| vec![]),
                 None => vec![],
             }
         },
@@ -112,7 +117,22 @@ pub fn ArtistCalendar() -> impl IntoView {
         move || artist_id.get(),
         move |id_opt| async move {
             match id_opt {
-                Some(id) => get_booking_requests(id).await.unwrap_or_else(|_| vec![]),
+                Some(id) => get_booking_requests(id, get_auth_token().unwrap_or_default())
+                    .await
+                    .unwrap_or_else(|_| vec![]),
+                None => vec![],
+            }
+        },
+    );
+
+    // Unread client messages per booking, badged on the requests below
+    let unread_resource = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match id_opt {
+                Some(id) => get_unread_counts(id, get_auth_token().unwrap_or_default())
+                    .await
+                    .unwrap_or_default(),
                 None => vec![],
             }
         },
@@ -162,21 +182,31 @@ pub fn ArtistCalendar() -> impl IntoView {
     };
 
     let handle_save_availability = move || {
+        // Left blank for the whole day, but not filled in wrong
+        let parse_time = |value: String| match value.trim() {
+            "" => Some(None),
+            value => Time::parse_lenient(value).map(Some),
+        };
+        let (Some(start), Some(end)) = (parse_time(start_time.get()), parse_time(end_time.get()))
+        else {
+            return;
+        };
+
         if let Some((year, month, day)) = selected_date.get() {
             if let Some(id) = artist_id.get() {
                 spawn_local(async move {
-                    let date_str = format!("{}-{:02}-{:02}", year, month, day);
                     let update = AvailabilityUpdate {
                         artist_id: id,
-                        date: Some(date_str.clone()),
+                        date: Date::from_ymd(year, month, day),
                         day_of_week: None,
-                        start_time: start_time.get(),
-                        end_time: end_time.get(),
+                        start_time: start,
+                        end_time: end,
                         is_available: availability_mode.get() == "available",
                         is_recurring: false,
                     };
 
-                    if set_artist_availability(update).await.is_ok() {
+                    let token = get_auth_token().unwrap_or_default();
+                    if set_artist_availability(update, token).await.is_ok() {
                         show_availability_modal.set(false);
                         selected_date.set(None);
                         availability_resource.refetch();
@@ -186,6 +216,22 @@ pub fn ArtistCalendar() -> impl IntoView {
         }
     };
 
+    // Subscribable ICS link for Google / Apple Calendar
+    let subscribe_url = RwSignal::new(None::<String>);
+    let create_feed = Action::new(move |_: &()| async move {
+        create_calendar_feed(get_auth_token().unwrap_or_default(), "all".to_string()).await
+    });
+    Effect::new(move |_| match create_feed.value().get() {
+        Some(Ok(feed)) => {
+            let origin = web_sys::window()
+                .and_then(|window| window.location().origin().ok())
+                .unwrap_or_default();
+            subscribe_url.set(Some(format!("{}{}", origin, feed.url_path)));
+        }
+        Some(Err(e)) => leptos::logging::error!("Failed to create calendar feed: {}", e),
+        None => {}
+    });
+
     view! {
         <div class="artist-calendar">
             <div class="calendar-header">
@@ -194,6 +240,12 @@ pub fn ArtistCalendar() -> impl IntoView {
                     <Button on_click=move |_| show_sidebar.update(|s| *s = !*s)>
                         "Toggle Sidebar"
                     </Button>
+                    <Button
+                        on_click=move |_| { create_feed.dispatch(()); }
+                        disabled=Signal::derive(move || create_feed.pending().get())
+                    >
+                        "Sync to Calendar"
+                    </Button>
                     <Button>
                         <a href="/artist/dashboard/recurring" style="text-decoration: none; color: inherit;">
                             "Manage Recurring Rules"
@@ -202,6 +254,15 @@ pub fn ArtistCalendar() -> impl IntoView {
                 </div>
             </div>
 
+            {move || subscribe_url.get().map(|url| view! {
+                <div class="calendar-subscribe">
+                    <p>"Subscribe to this private link in Google Calendar or Apple Calendar:"</p>
+                    <input type="text" readonly=true value=url />
+                </div>
+            })}
+
+            <DashboardHints page="calendar" />
+
             <div class="calendar-note">
                 <p>"💡 Tip: Set your recurring availability patterns first, then use the calendar to override specific dates as needed."</p>
             </div>
@@ -235,6 +296,11 @@ pub fn ArtistCalendar() -> impl IntoView {
                                 let first_day_weekday = day_of_week(year, month, 1);
                                 let availability_data = availability_resource.get().unwrap_or_default();
                                 let recurring_rules = recurring_rules_resource.get().unwrap_or_default();
+                                let readable_rules = readable_rules(&recurring_rules);
+                                let rule_schedule = availability::Schedule {
+                                    rules: readable_rules.iter().map(|(_, rule)| rule.clone()).collect(),
+                                    ..Default::default()
+                                };
                                 let booking_requests = booking_requests_resource.get().unwrap_or_default();
                                 let mut days = Vec::new();
 
@@ -259,11 +325,15 @@ pub fn ArtistCalendar() -> impl IntoView {
                                         (a.day_of_week == Some(dow) && a.is_recurring)
                                     });
 
+                                    let date = availability::parse_date(&date_str);
+
                                     // Check if this day is blocked by FULL DAY recurring rules only
-                                    let blocked_by_full_day_rule = check_full_day_blocking_rules(&recurring_rules, year, month, day, dow);
+                                    let blocked_by_full_day_rule = date.is_some_and(|date| !rule_schedule.effective_availability(date));
 
                                     // Get time blocks for this day
-                                    let mut time_blocks = get_day_time_blocks(&recurring_rules, year, month, day, dow);
+                                    let mut time_blocks = date
+                                        .map(|date| get_day_time_blocks(&readable_rules, date))
+                                        .unwrap_or_default();
 
                                     // Add booking requests for this day
                                     let day_booking_requests = booking_requests.iter().filter(|req| {
@@ -442,6 +512,13 @@ pub fn ArtistCalendar() -> impl IntoView {
                                                         .map(|booking| {
                                                         let status_class = if booking.status == "accepted" { "booking-accepted" } else { "booking-pending" };
                                                         let booking_id = booking.id;
+                                                        let unread = unread_resource
+                                                            .get()
+                                                            .unwrap_or_default()
+                                                            .into_iter()
+                                                            .find(|count| count.booking_id == booking_id)
+                                                            .map(|count| count.unread)
+                                                            .unwrap_or(0);
                                                         let navigate_to_booking = move |_| {
                                                             if let Some(window) = web_sys::window() {
                                                                 let location = window.location();
@@ -450,7 +527,12 @@ pub fn ArtistCalendar() -> impl IntoView {
                                                         };
                                                         view! {
                                                             <div class=format!("booking-item {}", status_class) on:click=navigate_to_booking>
-                                                                <div class="booking-client">{booking.client_name.clone()}</div>
+                                                                <div class="booking-client">
+                                                                    {booking.client_name.clone()}
+                                                                    {(unread > 0).then(|| view! {
+                                                                        <span class="booking-unread">{format!("{} unread", unread)}</span>
+                                                                    })}
+                                                                </div>
                                                                 <div class="booking-date">{booking.requested_date.clone()}</div>
                                                                 <div class="booking-time">
                                                                     {format_time_range_with_timezone(&booking.requested_start_time, booking.requested_end_time.as_deref(), timezone_signal)}
@@ -576,134 +658,13 @@ pub fn ArtistCalendar() -> impl IntoView {
     }
 }
 
-// Helper function to check if a day is blocked by FULL DAY recurring rules only
-fn check_full_day_blocking_rules(
-    rules: &[RecurringRule],
-    year: i32,
-    month: u32,
-    day: u32,
-    dow: i32,
-) -> bool {
-    for rule in rules {
-        // Only check active rules that block availability AND have no specific times (full day)
-        if !rule.active
-            || rule.action != "blocked"
-            || rule.start_time.is_some()
-            || rule.end_time.is_some()
-        {
-            continue;
-        }
-
-        match rule.rule_type.as_str() {
-            "weekdays" => {
-                // Check if this day of week is in the blocked pattern
-                if let Ok(days) = serde_json::from_str::<Vec<i32>>(&rule.pattern) {
-                    if days.contains(&dow) {
-                        return true;
-                    }
-                }
-            }
-            "dates" => {
-                // Check if this month/day matches any annual date
-                let date_str = format!("{:02}/{:02}", month, day);
-                if rule.pattern.contains(&date_str) {
-                    return true;
-                }
-                // Also check full month name format
-                let month_names = [
-                    "January",
-                    "February",
-                    "March",
-                    "April",
-                    "May",
-                    "June",
-                    "July",
-                    "August",
-                    "September",
-                    "October",
-                    "November",
-                    "December",
-                ];
-                if month > 0 && month <= 12 {
-                    let month_day = format!("{} {}", month_names[(month - 1) as usize], day);
-                    if rule.pattern.contains(&month_day) {
-                        return true;
-                    }
-                }
-            }
-            "monthly" => {
-                // Check if this day of month matches the pattern
-                if rule.pattern.contains(&day.to_string()) {
-                    return true;
-                }
-            }
-            _ => {}
-        }
-    }
-    false
-}
-
-// Helper function to check if a day is blocked by recurring rules
-fn check_recurring_rules(
-    rules: &[RecurringRule],
-    year: i32,
-    month: u32,
-    day: u32,
-    dow: i32,
-) -> bool {
-    for rule in rules {
-        // Only check active rules that block availability
-        if !rule.active || rule.action != "blocked" {
-            continue;
-        }
-
-        match rule.rule_type.as_str() {
-            "weekdays" => {
-                // Check if this day of week is in the blocked pattern
-                if let Ok(days) = serde_json::from_str::<Vec<i32>>(&rule.pattern) {
-                    if days.contains(&dow) {
-                        return true;
-                    }
-                }
-            }
-            "dates" => {
-                // Check if this month/day matches any annual date
-                let date_str = format!("{:02}/{:02}", month, day);
-                if rule.pattern.contains(&date_str) {
-                    return true;
-                }
-                // Also check full month name format
-                let month_names = [
-                    "January",
-                    "February",
-                    "March",
-                    "April",
-                    "May",
-                    "June",
-                    "July",
-                    "August",
-                    "September",
-                    "October",
-                    "November",
-                    "December",
-                ];
-                if month > 0 && month <= 12 {
-                    let month_day = format!("{} {}", month_names[(month - 1) as usize], day);
-                    if rule.pattern.contains(&month_day) {
-                        return true;
-                    }
-                }
-            }
-            "monthly" => {
-                // Check if this day of month matches the pattern
-                if rule.pattern.contains(&day.to_string()) {
-                    return true;
-                }
-            }
-            _ => {}
-        }
-    }
-    false
+/// Active rules the shared evaluator can read, each with the rule it came from
+fn readable_rules(rules: &[RecurringRule]) -> Vec<(&RecurringRule, availability::RecurringRule)> {
+    rules
+        .iter()
+        .filter(|rule| rule.active)
+        .filter_map(|rule| Some((rule, stored_schedule_rule(rule).ok()?)))
+        .collect()
 }
 
 // Helper functions for date calculations
@@ -747,74 +708,19 @@ fn day_of_week(year: i32, month: u32, day: u32) -> u32 {
 
 // Helper function to get all applicable time blocks for a specific day
 fn get_day_time_blocks(
-    rules: &[RecurringRule],
-    _year: i32,
-    month: u32,
-    day: u32,
-    dow: i32,
+    rules: &[(&RecurringRule, availability::RecurringRule)],
+    date: chrono::NaiveDate,
 ) -> Vec<CalendarTimeBlock> {
-    let mut time_blocks = Vec::new();
-
-    for rule in rules {
-        // Only check active rules
-        if !rule.active {
-            continue;
-        }
-
-        let applies_to_day = match rule.rule_type.as_str() {
-            "weekdays" => {
-                if let Ok(days) = serde_json::from_str::<Vec<i32>>(&rule.pattern) {
-                    days.contains(&dow)
-                } else {
-                    false
-                }
-            }
-            "dates" => {
-                // Check if this month/day matches any annual date
-                let date_str = format!("{:02}/{:02}", month, day);
-                rule.pattern.contains(&date_str) || {
-                    // Also check full month name format
-                    let month_names = [
-                        "January",
-                        "February",
-                        "March",
-                        "April",
-                        "May",
-                        "June",
-                        "July",
-                        "August",
-                        "September",
-                        "October",
-                        "November",
-                        "December",
-                    ];
-                    if month > 0 && month <= 12 {
-                        let month_day = format!("{} {}", month_names[(month - 1) as usize], day);
-                        rule.pattern.contains(&month_day)
-                    } else {
-                        false
-                    }
-                }
-            }
-            "monthly" => {
-                // Check if this day of month matches the pattern
-                rule.pattern.contains(&day.to_string())
-            }
-            _ => false,
-        };
-
-        if applies_to_day {
-            // Add this rule as a time block
-            time_blocks.push(CalendarTimeBlock {
-                name: rule.name.clone(),
-                start_time: rule.start_time.clone(),
-                end_time: rule.end_time.clone(),
-                action: rule.action.clone(),
-                tattoo_description: None,
-                booking_id: None,
-            });
-        }
-    }
-
-    time_blocks
+    rules
+        .iter()
+        .filter(|(_, schedule_rule)| schedule_rule.matches(date))
+        .map(|(rule, _)| CalendarTimeBlock {
+            name: rule.name.clone(),
+            start_time: rule.start_time.clone(),
+            end_time: rule.end_time.clone(),
+            action: rule.action.clone(),
+            tattoo_description: None,
+            booking_id: None,
+        })
+        .collect()
 }
@@ -0,0 +1,280 @@
+use crate::api_error::user_message;
+use crate::components::DocumentBody;
+use crate::db::entities::DocumentTemplate;
+use crate::server_documents::{
+    get_booking_consent, get_document_template, reset_document_template, save_document_template,
+    send_consent_form,
+};
+use crate::utils::auth::get_auth_token;
+use crate::utils::documents::{
+    render, DocumentValues, DOCUMENT_KINDS, DOCUMENT_VARIABLES, MAX_DOCUMENT_CHARS, MAX_TITLE_CHARS,
+};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use shared_types::BookingStatus;
+
+/// Values the preview fills documents in with
+fn preview_values() -> DocumentValues {
+    DocumentValues {
+        client_name: "Alex Rivera".to_string(),
+        artist_name: "You".to_string(),
+        appointment_date: "Friday, June 14".to_string(),
+        appointment_time: "2:00 PM".to_string(),
+        placement: Some("Left forearm".to_string()),
+        description: Some("Fine-line botanical sleeve".to_string()),
+    }
+}
+
+/// Settings card where an artist writes the consent form clients sign and
+/// the aftercare instructions they're emailed after their appointment
+#[component]
+pub fn DocumentSettings() -> impl IntoView {
+    view! {
+        <div class="settings-card document-settings">
+            <h2>"Consent & Aftercare"</h2>
+            <p class="setting-description">
+                "Clients sign your consent form from their booking link, and get your aftercare instructions by email when you mark their booking completed. "
+                "Write headings with #, lists with - or 1. and bold with **."
+            </p>
+            {DOCUMENT_KINDS
+                .iter()
+                .map(|(kind, label)| view! { <DocumentEditor kind=*kind label=*label /> })
+                .collect_view()}
+            <p class="setting-description">"Placeholders you can use:"</p>
+            <ul class="document-variables">
+                {DOCUMENT_VARIABLES.iter().map(|(name, description)| view! {
+                    <li>
+                        <code>{format!("{{{}}}", name)}</code>
+                        " "
+                        {*description}
+                    </li>
+                }).collect_view()}
+            </ul>
+        </div>
+    }
+}
+
+#[component]
+fn DocumentEditor(kind: &'static str, label: &'static str) -> impl IntoView {
+    let title = RwSignal::new(String::new());
+    let body = RwSignal::new(String::new());
+    let is_default = RwSignal::new(true);
+    let previewing = RwSignal::new(false);
+    let saving = RwSignal::new(false);
+    let document_error = RwSignal::new(None::<String>);
+    let saved = RwSignal::new(false);
+
+    let load = move |document: DocumentTemplate| {
+        title.set(document.title);
+        body.set(document.body);
+        is_default.set(document.is_default);
+    };
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                match get_document_template(token, kind.to_string()).await {
+                    Ok(document) => load(document),
+                    Err(e) => document_error.set(Some(user_message(&e))),
+                }
+            });
+        }
+    });
+
+    let save = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        saving.set(true);
+        saved.set(false);
+        spawn_local(async move {
+            let result = save_document_template(
+                token,
+                kind.to_string(),
+                title.get_untracked(),
+                body.get_untracked(),
+            )
+            .await;
+            match result {
+                Ok(document) => {
+                    load(document);
+                    document_error.set(None);
+                    saved.set(true);
+                }
+                Err(e) => document_error.set(Some(user_message(&e))),
+            }
+            saving.set(false);
+        });
+    };
+
+    let reset = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        saving.set(true);
+        saved.set(false);
+        spawn_local(async move {
+            match reset_document_template(token, kind.to_string()).await {
+                Ok(document) => {
+                    load(document);
+                    document_error.set(None);
+                }
+                Err(e) => document_error.set(Some(user_message(&e))),
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="setting-group document-editor">
+            <label class="setting-label">{label}</label>
+            {move || document_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+            <Show when=move || is_default.get()>
+                <p class="setting-description">"You're using the default. Edit it to make it your own."</p>
+            </Show>
+            <input
+                type="text"
+                maxlength=MAX_TITLE_CHARS.to_string()
+                prop:value=move || title.get()
+                on:input=move |ev| title.set(event_target_value(&ev))
+            />
+            <Show
+                when=move || previewing.get()
+                fallback=move || view! {
+                    <textarea
+                        rows="12"
+                        maxlength=MAX_DOCUMENT_CHARS.to_string()
+                        prop:value=move || body.get()
+                        on:input=move |ev| body.set(event_target_value(&ev))
+                    ></textarea>
+                }
+            >
+                <div class="document-preview">
+                    <h3>{move || render(&title.get(), &preview_values())}</h3>
+                    {move || view! { <DocumentBody text=render(&body.get(), &preview_values()) /> }}
+                </div>
+            </Show>
+            <div class="setting-actions">
+                <button
+                    class="btn btn-primary"
+                    disabled=move || saving.get()
+                    on:click=save
+                >
+                    {move || if saving.get() { "Saving..." } else { "Save" }}
+                </button>
+                <button class="btn btn-secondary" on:click=move |_| previewing.update(|p| *p = !*p)>
+                    {move || if previewing.get() { "Edit" } else { "Preview" }}
+                </button>
+                <Show when=move || !is_default.get()>
+                    <button class="btn btn-secondary" disabled=move || saving.get() on:click=reset>
+                        "Use Default"
+                    </button>
+                </Show>
+                <Show when=move || saved.get()>
+                    <span class="save-confirmation">"Saved"</span>
+                </Show>
+            </div>
+        </div>
+    }
+}
+
+/// Whether the client has signed the booking's consent form, with the
+/// signed PDF once they have or a way to email them the form until then
+#[component]
+pub fn BookingConsentCard(booking_id: i32, status: String) -> impl IntoView {
+    let signature = RwSignal::new(None);
+    let loaded = RwSignal::new(false);
+    let sending = RwSignal::new(false);
+    let sent = RwSignal::new(false);
+    let consent_error = RwSignal::new(None::<String>);
+    // Only bookings still going ahead can be sent the form
+    let active = BookingStatus::parse(&status)
+        .is_some_and(|status| status.can_become(BookingStatus::Cancelled));
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                match get_booking_consent(token, booking_id).await {
+                    Ok(signed) => signature.set(signed),
+                    Err(e) => consent_error.set(Some(user_message(&e))),
+                }
+                loaded.set(true);
+            });
+        }
+    });
+
+    let send = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        sending.set(true);
+        spawn_local(async move {
+            match send_consent_form(token, booking_id).await {
+                Ok(()) => {
+                    consent_error.set(None);
+                    sent.set(true);
+                }
+                Err(e) => consent_error.set(Some(user_message(&e))),
+            }
+            sending.set(false);
+        });
+    };
+
+    move || {
+        loaded.get().then(|| {
+            let state = match signature.get() {
+                Some(signature) => {
+                    let pdf_link = format!(
+                        "/api/bookings/{}/consent.pdf?token={}",
+                        booking_id,
+                        get_auth_token().unwrap_or_default()
+                    );
+                    view! {
+                        <p>
+                            {format!(
+                                "Signed by {} on {} UTC{}.",
+                                signature.typed_name,
+                                signature.signed_at,
+                                signature
+                                    .ip_address
+                                    .map(|ip| format!(" from {}", ip))
+                                    .unwrap_or_default()
+                            )}
+                        </p>
+                        <a class="btn btn-secondary" href=pdf_link target="_blank" rel="noopener">
+                            "Download Signed PDF"
+                        </a>
+                    }
+                    .into_any()
+                }
+                None if active => view! {
+                    <p>"The client hasn't signed your consent form yet."</p>
+                    <button
+                        class="btn btn-secondary"
+                        disabled=move || sending.get()
+                        on:click=send
+                    >
+                        {move || if sending.get() { "Sending..." } else { "Email Consent Form" }}
+                    </button>
+                    <Show when=move || sent.get()>
+                        <span class="booking-details-consent-sent">"Sent"</span>
+                    </Show>
+                }
+                .into_any(),
+                None => view! { <p>"The client didn't sign a consent form."</p> }.into_any(),
+            };
+
+            view! {
+                <div class="booking-details-consent-card">
+                    <h2>"Consent Form"</h2>
+                    {move || consent_error.get().map(|error| view! {
+                        <div class="error-message">{error}</div>
+                    })}
+                    {state}
+                </div>
+            }
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use crate::api_error::user_message;
+use crate::db::entities::BookingWidgetConfig;
+use crate::server_embed::{get_booking_widget, update_booking_widget_origins};
+use crate::utils::auth::get_auth_token;
+use crate::utils::embed::{embed_snippet, COMPLETION_EVENT, MAX_EMBED_ORIGINS};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Settings card with the code for putting the booking widget on the
+/// artist's own website, and the sites allowed to show it
+#[component]
+pub fn BookingWidgetSettings() -> impl IntoView {
+    let widget_url = RwSignal::new(None::<String>);
+    let origins = RwSignal::new(String::new());
+    let saving = RwSignal::new(false);
+    let saved = RwSignal::new(false);
+    let copied = RwSignal::new(false);
+    let widget_error = RwSignal::new(None::<String>);
+
+    let load = move |config: BookingWidgetConfig| {
+        widget_url.set(Some(config.widget_url));
+        origins.set(config.allowed_origins.join("\n"));
+    };
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                match get_booking_widget(token).await {
+                    Ok(config) => load(config),
+                    Err(e) => widget_error.set(Some(user_message(&e))),
+                }
+            });
+        }
+    });
+
+    let snippet = move || widget_url.get().map(|url| embed_snippet(&url));
+
+    let copy = move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = ["navigator", "clipboard"], js_name = writeText)]
+                fn write_text(text: &str);
+            }
+
+            if let Some(snippet) = snippet() {
+                write_text(&snippet);
+            }
+        }
+        copied.set(true);
+    };
+
+    let save = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let list = origins
+            .get_untracked()
+            .lines()
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        saving.set(true);
+        saved.set(false);
+        spawn_local(async move {
+            match update_booking_widget_origins(token, list).await {
+                Ok(config) => {
+                    load(config);
+                    widget_error.set(None);
+                    saved.set(true);
+                }
+                Err(e) => widget_error.set(Some(user_message(&e))),
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="settings-card booking-widget-settings">
+            <h2>"Booking Widget"</h2>
+            <p class="setting-description">
+                "Let clients request appointments from your own website. Paste this code where the booking form should go; "
+                "it shows your open times and your questionnaire."
+            </p>
+            {move || widget_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+            {move || snippet().map(|snippet| view! {
+                <div class="setting-group">
+                    <label class="setting-label">"Embed code"</label>
+                    <textarea class="booking-widget-snippet" rows="3" readonly prop:value=snippet></textarea>
+                    <div class="setting-actions">
+                        <button class="btn btn-secondary" on:click=copy>"Copy Code"</button>
+                        <a class="btn btn-secondary" href=widget_url.get().unwrap_or_default() target="_blank" rel="noopener">
+                            "Open Widget"
+                        </a>
+                        <Show when=move || copied.get()>
+                            <span class="save-confirmation">"Copied"</span>
+                        </Show>
+                    </div>
+                </div>
+            })}
+            <div class="setting-group">
+                <label class="setting-label">"Sites that can show it"</label>
+                <p class="setting-description">
+                    {format!(
+                        "One per line, like https://yourstudio.com, up to {}. Leave empty to allow any site.",
+                        MAX_EMBED_ORIGINS
+                    )}
+                </p>
+                <textarea
+                    rows="3"
+                    placeholder="https://yourstudio.com"
+                    prop:value=move || origins.get()
+                    on:input=move |ev| origins.set(event_target_value(&ev))
+                ></textarea>
+                <div class="setting-actions">
+                    <button
+                        class="btn btn-primary"
+                        disabled=move || saving.get()
+                        on:click=save
+                    >
+                        {move || if saving.get() { "Saving..." } else { "Save" }}
+                    </button>
+                    <Show when=move || saved.get()>
+                        <span class="save-confirmation">"Saved"</span>
+                    </Show>
+                </div>
+            </div>
+            <p class="setting-description">
+                "Developers: when a request is sent, the widget posts a "
+                <code>{COMPLETION_EVENT}</code>
+                " message with the artistId and bookingId to your page."
+            </p>
+        </div>
+    }
+}
@@ -0,0 +1,357 @@
+use crate::api_error::{user_message, ApiError};
+use crate::db::entities::FlashDesign;
+use crate::server::get_all_styles_with_counts;
+use crate::server_flash_designs::{
+    delete_flash_design, get_my_flash_designs, update_flash_design, FlashDesignDetails,
+    MAX_FLASH_DESCRIPTION_CHARS, MAX_FLASH_TITLE_CHARS,
+};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_query_map;
+
+fn details_of(design: &FlashDesign) -> FlashDesignDetails {
+    FlashDesignDetails {
+        title: design.title.clone(),
+        description: design.description.clone(),
+        size_inches: design.size_inches,
+        price: design.price,
+        repeatable: design.repeatable,
+        archived: design.archived,
+        style_ids: design.style_ids.clone(),
+    }
+}
+
+/// Settings card for the artist's flash: pre-drawn designs clients can claim
+/// at a fixed price from the flash page
+#[component]
+pub fn FlashSettings() -> impl IntoView {
+    let flash_error = RwSignal::new(None::<String>);
+    let field_error = RwSignal::new(None::<(String, String)>);
+    let flash_version = RwSignal::new(0u32);
+    // The design being edited, as it will be saved
+    let editing = RwSignal::new(None::<(i32, FlashDesignDetails)>);
+    let saving = RwSignal::new(false);
+
+    // Plain form posts can't set headers, so the upload sends the token as a
+    // field
+    let upload_token = RwSignal::new(String::new());
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            upload_token.set(token);
+        }
+    });
+
+    let query = use_query_map();
+    let upload_notice = move || {
+        let query = query.get();
+        if let Some(error) = query.get("flash_error") {
+            Some(("error-message", format!("Upload failed: {}", error)))
+        } else if query.get("flash").as_deref() == Some("uploaded") {
+            Some((
+                "success-message",
+                "Design added. Clients can claim it from the flash page.".to_string(),
+            ))
+        } else {
+            None
+        }
+    };
+
+    let flash_resource = Resource::new(
+        move || flash_version.get(),
+        move |_| async move {
+            match get_auth_token() {
+                Some(token) => get_my_flash_designs(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+    let styles_resource = Resource::new(
+        || (),
+        |_| async move { get_all_styles_with_counts().await.unwrap_or_default() },
+    );
+
+    let on_result = move |result: Result<(), ServerFnError<ApiError>>| match result {
+        Ok(()) => {
+            flash_error.set(None);
+            field_error.set(None);
+            editing.set(None);
+            flash_version.update(|v| *v += 1);
+        }
+        Err(ServerFnError::WrappedServerError(ApiError::Validation { field, message })) => {
+            field_error.set(Some((field, message)));
+        }
+        Err(e) => flash_error.set(Some(user_message(&e))),
+    };
+
+    let field_hint = move |field: &'static str| {
+        move || {
+            field_error
+                .get()
+                .filter(|(name, _)| name == field)
+                .map(|(_, message)| view! { <p class="flash-field-error">{message}</p> })
+        }
+    };
+
+    let update = move |flash_design_id: i32, details: FlashDesignDetails| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        saving.set(true);
+        spawn_local(async move {
+            on_result(update_flash_design(token, flash_design_id, details).await);
+            saving.set(false);
+        });
+    };
+
+    let remove = move |flash_design_id: i32| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(delete_flash_design(token, flash_design_id).await);
+        });
+    };
+
+    let edit_field = move |change: fn(&mut FlashDesignDetails, String)| {
+        move |ev: leptos::ev::Event| {
+            let value = event_target_value(&ev);
+            editing.update(|editing| {
+                if let Some((_, details)) = editing {
+                    change(details, value);
+                }
+            });
+        }
+    };
+    let edited = move |get: fn(&FlashDesignDetails) -> String| {
+        move || {
+            editing
+                .get()
+                .map(|(_, details)| get(&details))
+                .unwrap_or_default()
+        }
+    };
+
+    view! {
+        <div class="settings-card flash-settings">
+            <h2>"Flash"</h2>
+            <p class="setting-description">
+                "Sell pre-drawn designs at a fixed price. Clients browse flash by style and city "
+                "and claim a design straight into a booking request. A one-off design is off the "
+                "page once someone claims it; a repeatable one stays up."
+            </p>
+
+            {move || upload_notice().map(|(class, message)| view! {
+                <div class=class>{message}</div>
+            })}
+            {move || flash_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <Suspense fallback=|| ()>
+                {move || flash_resource.get().map(|designs| {
+                    if designs.is_empty() {
+                        return view! {
+                            <p class="setting-description">"No flash yet."</p>
+                        }.into_any();
+                    }
+                    view! {
+                        <div class="flash-list">
+                            {designs.into_iter().map(|design| {
+                                let flash_design_id = design.id;
+                                let details = details_of(&design);
+                                let toggled = FlashDesignDetails {
+                                    archived: !design.archived,
+                                    ..details.clone()
+                                };
+                                let status = if design.archived {
+                                    "Archived"
+                                } else if !design.available {
+                                    "Claimed"
+                                } else {
+                                    "Available"
+                                };
+                                view! {
+                                    <div class="flash-item" class:flash-item-archived=design.archived>
+                                        <img src=design.thumbnail_url.clone() alt=design.title.clone() />
+                                        <div class="flash-item-body">
+                                            <div class="flash-item-header">
+                                                <strong>{design.title.clone()}</strong>
+                                                <span class=format!("flash-status flash-status-{}", status.to_lowercase())>
+                                                    {status}
+                                                </span>
+                                            </div>
+                                            <p class="flash-details">
+                                                {format!("${:.2}", design.price)}
+                                                {design.size_inches.map(|size| format!(" · {}\"", size))}
+                                                {if design.repeatable { " · Repeatable" } else { " · One-off" }}
+                                            </p>
+                                            {(!design.style_names.is_empty()).then(|| view! {
+                                                <p class="flash-styles">{design.style_names.join(", ")}</p>
+                                            })}
+                                            <div class="flash-item-actions">
+                                                <button
+                                                    class="btn btn-secondary"
+                                                    on:click=move |_| editing.set(Some((flash_design_id, details.clone())))
+                                                >
+                                                    "Edit"
+                                                </button>
+                                                <button
+                                                    class="btn btn-secondary"
+                                                    on:click=move |_| update(flash_design_id, toggled.clone())
+                                                >
+                                                    {if design.archived { "Unarchive" } else { "Archive" }}
+                                                </button>
+                                                <button class="btn btn-outline-danger" on:click=move |_| remove(flash_design_id)>
+                                                    "Delete"
+                                                </button>
+                                            </div>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect_view()}
+                        </div>
+                    }.into_any()
+                })}
+            </Suspense>
+
+            <Show when=move || editing.get().is_some()>
+                <div class="flash-form">
+                    <h3>"Edit Design"</h3>
+                    <div class="setting-group">
+                        <label>"Title"</label>
+                        <input
+                            type="text"
+                            maxlength=MAX_FLASH_TITLE_CHARS.to_string()
+                            prop:value=edited(|details| details.title.clone())
+                            on:input=edit_field(|details, value| details.title = value)
+                        />
+                        {field_hint("title")}
+                    </div>
+                    <div class="setting-group">
+                        <label>"Price ($)"</label>
+                        <input
+                            type="number"
+                            min="0"
+                            step="1"
+                            prop:value=edited(|details| details.price.to_string())
+                            on:input=edit_field(|details, value| details.price = value.parse().unwrap_or(-1.0))
+                        />
+                        {field_hint("price")}
+                    </div>
+                    <div class="setting-group">
+                        <label>"Size (inches)"</label>
+                        <input
+                            type="number"
+                            min="0"
+                            step="0.5"
+                            prop:value=edited(|details| details.size_inches.map(|size| size.to_string()).unwrap_or_default())
+                            on:input=edit_field(|details, value| details.size_inches = value.parse().ok())
+                        />
+                        {field_hint("size_inches")}
+                    </div>
+                    <div class="setting-group">
+                        <label>"Description"</label>
+                        <textarea
+                            rows="3"
+                            maxlength=MAX_FLASH_DESCRIPTION_CHARS.to_string()
+                            prop:value=edited(|details| details.description.clone().unwrap_or_default())
+                            on:input=edit_field(|details, value| details.description = Some(value))
+                        ></textarea>
+                        {field_hint("description")}
+                    </div>
+                    <div class="setting-group">
+                        <label class="flash-checkbox">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || editing.get().is_some_and(|(_, details)| details.repeatable)
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    editing.update(|editing| {
+                                        if let Some((_, details)) = editing {
+                                            details.repeatable = checked;
+                                        }
+                                    });
+                                }
+                            />
+                            "Repeatable (can be tattooed more than once)"
+                        </label>
+                    </div>
+                    <div class="setting-actions">
+                        <button class="btn btn-secondary" on:click=move |_| editing.set(None)>
+                            "Cancel"
+                        </button>
+                        <button
+                            class="btn btn-primary"
+                            disabled=move || saving.get()
+                            on:click=move |_| {
+                                if let Some((flash_design_id, details)) = editing.get_untracked() {
+                                    update(flash_design_id, details);
+                                }
+                            }
+                        >
+                            {move || if saving.get() { "Saving..." } else { "Save Design" }}
+                        </button>
+                    </div>
+                </div>
+            </Show>
+
+            <form
+                class="flash-form"
+                method="post"
+                action="/api/artist/flash"
+                enctype="multipart/form-data"
+            >
+                <h3>"Add a Design"</h3>
+                <input type="hidden" name="token" prop:value=move || upload_token.get() />
+                <div class="setting-group">
+                    <label>"Image"</label>
+                    <input type="file" name="file" accept="image/jpeg,image/png,image/webp,image/heic,.heic" required />
+                </div>
+                <div class="setting-group">
+                    <label>"Title"</label>
+                    <input type="text" name="title" maxlength=MAX_FLASH_TITLE_CHARS.to_string() required />
+                </div>
+                <div class="setting-group">
+                    <label>"Price ($)"</label>
+                    <input type="number" name="price" min="0" step="1" required />
+                </div>
+                <div class="setting-group">
+                    <label>"Size (inches)"</label>
+                    <input type="number" name="size_inches" min="0" step="0.5" />
+                </div>
+                <div class="setting-group">
+                    <label>"Description"</label>
+                    <textarea
+                        name="description"
+                        rows="3"
+                        maxlength=MAX_FLASH_DESCRIPTION_CHARS.to_string()
+                        placeholder="Colour options, where it sits best..."
+                    ></textarea>
+                </div>
+                <div class="setting-group">
+                    <label>"Styles"</label>
+                    <div class="flash-style-options">
+                        <Suspense fallback=|| ()>
+                            {move || styles_resource.get().map(|styles| styles.into_iter().map(|style| view! {
+                                <label class="flash-checkbox">
+                                    <input type="checkbox" name="style_id" value=style.id.to_string() />
+                                    {style.name}
+                                </label>
+                            }).collect_view())}
+                        </Suspense>
+                    </div>
+                </div>
+                <div class="setting-group">
+                    <label class="flash-checkbox">
+                        <input type="checkbox" name="repeatable" />
+                        "Repeatable (can be tattooed more than once)"
+                    </label>
+                </div>
+                <div class="setting-actions">
+                    <button type="submit" class="btn btn-primary">"Add Design"</button>
+                </div>
+            </form>
+        </div>
+    }
+}
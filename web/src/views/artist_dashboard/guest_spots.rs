@@ -0,0 +1,287 @@
+use crate::api_error::{user_message, ApiError};
+use crate::db::entities::GuestSpotShop;
+use crate::server::{get_location_details, ArtistThumbnail};
+use crate::server_guest_spots::{
+    create_guest_spot, delete_guest_spot, get_my_guest_spots, search_guest_spot_shops,
+    MAX_GUEST_SPOT_NOTE_CHARS,
+};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// "Name, City, State", leaving out what's missing
+fn shop_label(shop: &GuestSpotShop) -> String {
+    [
+        Some(shop.name.clone()),
+        shop.city.clone(),
+        shop.state.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Settings card for guest spots: dates the artist is working out of another
+/// shop, shown on their profile and bookable on their own
+#[component]
+pub fn GuestSpotSettings() -> impl IntoView {
+    let shop_query = RwSignal::new(String::new());
+    let shop_results = RwSignal::new(Vec::<GuestSpotShop>::new());
+    let shop = RwSignal::new(None::<GuestSpotShop>);
+    let shop_artists = RwSignal::new(Vec::<ArtistThumbnail>::new());
+    let host_artist_id = RwSignal::new(String::new());
+    let start_date = RwSignal::new(String::new());
+    let end_date = RwSignal::new(String::new());
+    let note = RwSignal::new(String::new());
+    let saving = RwSignal::new(false);
+    let guest_spot_error = RwSignal::new(None::<String>);
+    let field_error = RwSignal::new(None::<(String, String)>);
+    let guest_spots_version = RwSignal::new(0u32);
+
+    let guest_spots_resource = Resource::new(
+        move || guest_spots_version.get(),
+        move |_| async move {
+            match get_auth_token() {
+                Some(token) => get_my_guest_spots(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+
+    let on_result = move |result: Result<(), ServerFnError<ApiError>>| match result {
+        Ok(()) => {
+            guest_spot_error.set(None);
+            field_error.set(None);
+            guest_spots_version.update(|v| *v += 1);
+        }
+        Err(ServerFnError::WrappedServerError(ApiError::Validation { field, message })) => {
+            field_error.set(Some((field, message)));
+        }
+        Err(e) => guest_spot_error.set(Some(user_message(&e))),
+    };
+
+    let field_hint = move |field: &'static str| {
+        move || {
+            field_error
+                .get()
+                .filter(|(name, _)| name == field)
+                .map(|(_, message)| view! { <p class="guest-spot-field-error">{message}</p> })
+        }
+    };
+
+    let search = move |query: String| {
+        shop_query.set(query.clone());
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            let results = search_guest_spot_shops(token, query.clone())
+                .await
+                .unwrap_or_default();
+            // Only the latest search's results are shown
+            if shop_query.get_untracked() == query {
+                shop_results.set(results);
+            }
+        });
+    };
+
+    // The host is picked from the chosen shop's artists
+    let choose_shop = move |chosen: GuestSpotShop| {
+        let location_id = chosen.location_id;
+        shop.set(Some(chosen));
+        shop_results.set(vec![]);
+        shop_artists.set(vec![]);
+        host_artist_id.set(String::new());
+        spawn_local(async move {
+            if let Ok(details) = get_location_details(location_id as i32).await {
+                shop_artists.set(details.artists);
+            }
+        });
+    };
+
+    let save = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let Some(location_id) = shop.get_untracked().map(|shop| shop.location_id) else {
+            field_error.set(Some((
+                "location_id".to_string(),
+                "Choose a shop".to_string(),
+            )));
+            return;
+        };
+        saving.set(true);
+        spawn_local(async move {
+            let result = create_guest_spot(
+                token,
+                location_id,
+                host_artist_id.get_untracked().parse().ok(),
+                start_date.get_untracked(),
+                end_date.get_untracked(),
+                note.get_untracked(),
+            )
+            .await;
+            if result.is_ok() {
+                shop.set(None);
+                shop_query.set(String::new());
+                shop_artists.set(vec![]);
+                host_artist_id.set(String::new());
+                start_date.set(String::new());
+                end_date.set(String::new());
+                note.set(String::new());
+            }
+            on_result(result);
+            saving.set(false);
+        });
+    };
+
+    let remove = move |guest_spot_id: i32| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(delete_guest_spot(token, guest_spot_id).await);
+        });
+    };
+
+    view! {
+        <div class="settings-card guest-spot-settings">
+            <h2>"Guest Spots"</h2>
+            <p class="setting-description">
+                "Working out of another shop for a few days? Add it here. "
+                "It's shown on your profile, you show up at that shop on the map while you're there, "
+                "and clients can book a slot during it."
+            </p>
+
+            {move || guest_spot_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <Suspense fallback=|| ()>
+                {move || guest_spots_resource.get().map(|spots| {
+                    if spots.is_empty() {
+                        return view! {
+                            <p class="setting-description">"No upcoming guest spots."</p>
+                        }.into_any();
+                    }
+                    view! {
+                        <div class="guest-spot-list">
+                            {spots.into_iter().map(|spot| {
+                                let guest_spot_id = spot.id;
+                                view! {
+                                    <div class="guest-spot-item">
+                                        <div class="guest-spot-item-header">
+                                            <strong>{spot.shop_name.unwrap_or_else(|| "Unknown shop".to_string())}</strong>
+                                            <span>{format!("{} to {}", spot.start_date, spot.end_date)}</span>
+                                        </div>
+                                        <p class="guest-spot-details">
+                                            {[spot.city, spot.state].into_iter().flatten().collect::<Vec<_>>().join(", ")}
+                                            {spot.host_artist_name.map(|host| format!(" · Hosted by {}", host))}
+                                        </p>
+                                        {spot.note.map(|note| view! { <p class="guest-spot-note">{note}</p> })}
+                                        <div class="guest-spot-item-actions">
+                                            <button class="btn btn-outline-danger" on:click=move |_| remove(guest_spot_id)>
+                                                "Delete"
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect_view()}
+                        </div>
+                    }.into_any()
+                })}
+            </Suspense>
+
+            <div class="guest-spot-form">
+                <h3>"Add a Guest Spot"</h3>
+                <div class="setting-group">
+                    <label>"Shop"</label>
+                    {move || match shop.get() {
+                        Some(chosen) => view! {
+                            <div class="guest-spot-chosen-shop">
+                                <span>{shop_label(&chosen)}</span>
+                                <button class="btn btn-secondary" on:click=move |_| shop.set(None)>
+                                    "Change"
+                                </button>
+                            </div>
+                        }.into_any(),
+                        None => view! {
+                            <input
+                                type="search"
+                                placeholder="Search shops by name"
+                                prop:value=move || shop_query.get()
+                                on:input=move |ev| search(event_target_value(&ev))
+                            />
+                            <div class="guest-spot-shop-results">
+                                {move || shop_results.get().into_iter().map(|result| view! {
+                                    <button class="guest-spot-shop-result" on:click={
+                                        let result = result.clone();
+                                        move |_| choose_shop(result.clone())
+                                    }>
+                                        {shop_label(&result)}
+                                    </button>
+                                }).collect_view()}
+                            </div>
+                        }.into_any(),
+                    }}
+                    {field_hint("location_id")}
+                </div>
+                <Show when=move || !shop_artists.get().is_empty()>
+                    <div class="setting-group">
+                        <label>"Host"</label>
+                        <select
+                            prop:value=move || host_artist_id.get()
+                            on:change=move |ev| host_artist_id.set(event_target_value(&ev))
+                        >
+                            <option value="">"No host"</option>
+                            {move || shop_artists.get().into_iter().map(|artist| view! {
+                                <option value=artist.artist_id.to_string()>{artist.artist_name}</option>
+                            }).collect_view()}
+                        </select>
+                        <p class="setting-description">"The artist at the shop who invited you. We'll let them know."</p>
+                        {field_hint("host_artist_id")}
+                    </div>
+                </Show>
+                <div class="setting-group">
+                    <label>"First day"</label>
+                    <input
+                        type="date"
+                        prop:value=move || start_date.get()
+                        on:input=move |ev| start_date.set(event_target_value(&ev))
+                    />
+                    {field_hint("start_date")}
+                </div>
+                <div class="setting-group">
+                    <label>"Last day"</label>
+                    <input
+                        type="date"
+                        prop:value=move || end_date.get()
+                        on:input=move |ev| end_date.set(event_target_value(&ev))
+                    />
+                    {field_hint("end_date")}
+                </div>
+                <div class="setting-group">
+                    <label>"Note for clients"</label>
+                    <textarea
+                        rows="3"
+                        maxlength=MAX_GUEST_SPOT_NOTE_CHARS.to_string()
+                        placeholder="Flash available, walk-ins welcome..."
+                        prop:value=move || note.get()
+                        on:input=move |ev| note.set(event_target_value(&ev))
+                    ></textarea>
+                    {field_hint("note")}
+                </div>
+                <div class="setting-actions">
+                    <button
+                        class="btn btn-primary"
+                        disabled=move || saving.get()
+                        on:click=save
+                    >
+                        {move || if saving.get() { "Saving..." } else { "Add Guest Spot" }}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
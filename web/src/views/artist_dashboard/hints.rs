@@ -0,0 +1,65 @@
+use crate::db::entities::DashboardHint;
+use crate::server_hints::{dismiss_dashboard_hint, get_dashboard_hints};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Guidance for what the artist hasn't set up yet, worked out on the server
+/// for one dashboard `page`. Dismissed hints stay hidden for the signed-in
+/// user.
+#[component]
+pub fn DashboardHints(page: &'static str) -> impl IntoView {
+    let hints = RwSignal::new(Vec::<DashboardHint>::new());
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                if let Ok(loaded) = get_dashboard_hints(token, page.to_string()).await {
+                    hints.set(loaded);
+                }
+            });
+        }
+    });
+
+    let dismiss = move |key: String| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        hints.update(|hints| hints.retain(|hint| hint.key != key));
+        spawn_local(async move {
+            let _ = dismiss_dashboard_hint(token, page.to_string(), key).await;
+        });
+    };
+
+    view! {
+        <Show when=move || !hints.get().is_empty()>
+            <div class="dashboard-hints">
+                <For
+                    each=move || hints.get()
+                    key=|hint| hint.key.clone()
+                    children=move |hint| {
+                        let key = hint.key.clone();
+                        view! {
+                            <div class=format!("dashboard-hint {}", hint.severity)>
+                                <div class="hint-text">
+                                    <strong>{hint.title}</strong>
+                                    <p>{hint.message}</p>
+                                </div>
+                                <div class="hint-actions">
+                                    <a href=hint.link class="btn btn-secondary">{hint.link_label}</a>
+                                    <button
+                                        class="hint-dismiss"
+                                        title="Dismiss"
+                                        on:click=move |_| dismiss(key.clone())
+                                    >
+                                        "Dismiss"
+                                    </button>
+                                </div>
+                            </div>
+                        }
+                    }
+                />
+            </div>
+        </Show>
+    }
+}
@@ -2,8 +2,18 @@ use leptos::prelude::*;
 use leptos_router::components::A;
 
 use crate::{
-    components::loading::LoadingView, server::get_artist_dashboard_data,
-    utils::auth::use_authenticated_artist_id,
+    components::loading::LoadingView,
+    server::{get_artist_dashboard_data, get_booking_trends, DEFAULT_TREND_WEEKS},
+    server_completeness::get_profile_completeness,
+    server_forecast::get_earnings_forecast,
+    server_insights::get_impression_insights,
+    server_onboarding::get_onboarding_status,
+    server_response_time::get_sla_nudges,
+    server_short_links::get_profile_link_stats,
+    utils::auth::{get_auth_token, use_authenticated_artist_id},
+    utils::money::format_money,
+    utils::response_time::{time_left, SLA_HOURS},
+    views::artist_dashboard::hints::DashboardHints,
 };
 
 #[component]
@@ -15,7 +25,9 @@ pub fn ArtistHome() -> impl IntoView {
         move || artist_id.get(),
         move |id_opt| async move {
             match id_opt {
-                Some(id) => get_artist_dashboard_data(id).await,
+                Some(id) => {
+                    get_artist_dashboard_data(id, get_auth_token().unwrap_or_default()).await
+                }
                 None => Err(ServerFnError::new(
                     "No authenticated artist found".to_string(),
                 )),
@@ -23,6 +35,84 @@ pub fn ArtistHome() -> impl IntoView {
         },
     );
 
+    // Loaded separately so a slow forecast doesn't hold up the dashboard
+    let forecast = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_earnings_forecast(token).await.ok(),
+                _ => None,
+            }
+        },
+    );
+
+    let trends = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_booking_trends(token, DEFAULT_TREND_WEEKS)
+                    .await
+                    .unwrap_or_default(),
+                _ => vec![],
+            }
+        },
+    );
+
+    let insights = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_impression_insights(token, DEFAULT_TREND_WEEKS)
+                    .await
+                    .ok(),
+                _ => None,
+            }
+        },
+    );
+
+    let sla_nudges = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_sla_nudges(token).await.unwrap_or_default(),
+                _ => vec![],
+            }
+        },
+    );
+
+    let completeness = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_profile_completeness(token).await.ok(),
+                _ => None,
+            }
+        },
+    );
+
+    let profile_link = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_profile_link_stats(token).await.ok(),
+                _ => None,
+            }
+        },
+    );
+
+    // Artists who haven't finished setting up are sent back to it
+    let onboarding_pending = Resource::new(
+        move || artist_id.get(),
+        move |id_opt| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_onboarding_status(token)
+                    .await
+                    .is_ok_and(|status| !status.completed),
+                _ => false,
+            }
+        },
+    );
+
     view! {
         <div class="artist-dashboard-container">
             <div class="dashboard-header">
@@ -30,6 +120,17 @@ pub fn ArtistHome() -> impl IntoView {
                 <p class="dashboard-subtitle">"Welcome back! Here's what's happening with your bookings."</p>
             </div>
 
+            <DashboardHints page="home" />
+
+            <Suspense fallback=|| ()>
+                <Show when=move || onboarding_pending.get().unwrap_or(false)>
+                    <div class="onboarding-banner">
+                        <p>"Your profile isn't live yet. Finish setting up so clients can find you."</p>
+                        <A href="/artist/onboarding" attr:class="btn btn-primary">"Continue Setup"</A>
+                    </div>
+                </Show>
+            </Suspense>
+
             <Suspense fallback=move || view! {
                 <LoadingView message=Some("Loading your dashboard...".to_string()) />
             }>
@@ -48,8 +149,8 @@ pub fn ArtistHome() -> impl IntoView {
                                     />
 
                                     <DashboardTile
-                                        title="Sketch Requests".to_string()
-                                        value=data.pending_sketches.to_string()
+                                        title="Pending Requests".to_string()
+                                        value=data.pending_requests.to_string()
                                         subtitle="awaiting your response".to_string()
                                         color="orange".to_string()
                                         icon="✏️".to_string()
@@ -67,14 +168,224 @@ pub fn ArtistHome() -> impl IntoView {
 
                                     <DashboardTile
                                         title="This Month".to_string()
-                                        value=format!("${}", data.monthly_revenue)
-                                        subtitle="total revenue".to_string()
+                                        value=format_money(data.monthly_revenue, &data.currency)
+                                        subtitle="from accepted bookings".to_string()
                                         color="green".to_string()
                                         icon="💰".to_string()
                                         link="/artist/dashboard/calendar".to_string()
                                     />
+
+                                    <Suspense fallback=|| ()>
+                                        {move || forecast.get().flatten().map(|forecast| view! {
+                                            <DashboardTile
+                                                title="Next Month".to_string()
+                                                value=format_money(forecast.expected, &forecast.currency)
+                                                subtitle=format!(
+                                                    "forecast, {:.0}% range {} – {}",
+                                                    forecast.confidence * 100.0,
+                                                    format_money(forecast.lower, &forecast.currency),
+                                                    format_money(forecast.upper, &forecast.currency),
+                                                )
+                                                color="green".to_string()
+                                                icon="📈".to_string()
+                                                link="/artist/dashboard/calendar".to_string()
+                                            />
+                                        })}
+                                    </Suspense>
                                 </div>
 
+                                <Suspense fallback=|| ()>
+                                    {move || trends.get().filter(|weeks| weeks.iter().any(|week| week.requests > 0)).map(|weeks| view! {
+                                        <div class="recent-activity booking-trends">
+                                            <h2>"Booking trends"</h2>
+                                            <p class="booking-trends-subtitle">
+                                                {format!("Requests you received over the last {} weeks, and how many you accepted.", weeks.len())}
+                                            </p>
+                                            <div class="booking-trends-charts">
+                                                <TrendChart
+                                                    title="Requests per week"
+                                                    bars=weeks.iter().map(|week| TrendBar {
+                                                        label: week_label(&week.week_start),
+                                                        value: week.requests as f64,
+                                                        detail: format!("{} requests, {} accepted", week.requests, week.accepted),
+                                                    }).collect()
+                                                />
+                                                <TrendChart
+                                                    title="Conversion rate"
+                                                    bars=weeks.iter().map(|week| TrendBar {
+                                                        label: week_label(&week.week_start),
+                                                        value: week.conversion_rate().unwrap_or(0.0) * 100.0,
+                                                        detail: week
+                                                            .conversion_rate()
+                                                            .map(|rate| format!("{:.0}% accepted", rate * 100.0))
+                                                            .unwrap_or_else(|| "No requests".to_string()),
+                                                    }).collect()
+                                                    max=100.0
+                                                />
+                                            </div>
+                                        </div>
+                                    })}
+                                </Suspense>
+
+                                <Suspense fallback=|| ()>
+                                    {move || insights.get().flatten().filter(|insights| insights.weeks.iter().any(|week| week.views > 0)).map(|insights| {
+                                        let views: i64 = insights.weeks.iter().map(|week| week.views).sum();
+                                        let clicks: i64 = insights.weeks.iter().map(|week| week.clicks).sum();
+                                        let city_views: f64 = insights.weeks.iter().map(|week| week.city_views).sum();
+                                        let city_clicks: f64 = insights.weeks.iter().map(|week| week.city_clicks).sum();
+                                        let rate = (views > 0).then(|| clicks as f64 / views as f64);
+                                        let city_rate = (city_views > 0.0).then(|| city_clicks / city_views);
+                                        view! {
+                                            <div class="recent-activity insights">
+                                                <h2>"Insights"</h2>
+                                                <p class="insights-subtitle">
+                                                    {format!("How often you were shown in match results over the last {} weeks, and how often clients clicked through.", insights.weeks.len())}
+                                                </p>
+                                                <div class="insights-summary">
+                                                    <div class="insights-stat">
+                                                        <span class="insights-stat-value">{views}</span>
+                                                        <span class="insights-stat-label">"views"</span>
+                                                    </div>
+                                                    <div class="insights-stat">
+                                                        <span class="insights-stat-value">{clicks}</span>
+                                                        <span class="insights-stat-label">"clicks"</span>
+                                                    </div>
+                                                    <div class="insights-stat">
+                                                        <span class="insights-stat-value">{rate_label(rate)}</span>
+                                                        <span class="insights-stat-label">"click-through rate"</span>
+                                                    </div>
+                                                </div>
+                                                {insights.city.clone().map(|city| view! {
+                                                    <p class="insights-comparison">
+                                                        {format!(
+                                                            "The average artist in {} had {:.0} views and a {} click-through rate.",
+                                                            city,
+                                                            city_views,
+                                                            rate_label(city_rate),
+                                                        )}
+                                                    </p>
+                                                })}
+                                                <div class="insights-charts">
+                                                    <TrendChart
+                                                        title="Views per week"
+                                                        bars=insights.weeks.iter().map(|week| TrendBar {
+                                                            label: week_label(&week.week_start),
+                                                            value: week.views as f64,
+                                                            detail: format!("{} views, city average {:.1}", week.views, week.city_views),
+                                                        }).collect()
+                                                    />
+                                                    <TrendChart
+                                                        title="Click-through rate"
+                                                        bars=insights.weeks.iter().map(|week| TrendBar {
+                                                            label: week_label(&week.week_start),
+                                                            value: week.click_through_rate().unwrap_or(0.0) * 100.0,
+                                                            detail: format!(
+                                                                "{} clicked, city average {}",
+                                                                rate_label(week.click_through_rate()),
+                                                                rate_label(week.city_click_through_rate()),
+                                                            ),
+                                                        }).collect()
+                                                        max=100.0
+                                                    />
+                                                </div>
+                                            </div>
+                                        }
+                                    })}
+                                </Suspense>
+
+                                <Suspense fallback=|| ()>
+                                    {move || completeness.get().flatten().filter(|c| c.score < 100).map(|completeness| view! {
+                                        <div class="recent-activity profile-checklist">
+                                            <h2>"Complete your profile"</h2>
+                                            <div class="completeness-meter">
+                                                <div class="completeness-bar">
+                                                    <div
+                                                        class="completeness-fill"
+                                                        style=format!("width: {}%", completeness.score)
+                                                    ></div>
+                                                </div>
+                                                <span class="completeness-score">{format!("{}%", completeness.score)}</span>
+                                            </div>
+                                            <p class="profile-checklist-subtitle">
+                                                "Complete profiles rank ahead of otherwise equal matches."
+                                            </p>
+                                            <div class="activity-list">
+                                                {completeness.items.into_iter().filter(|item| !item.complete).map(|item| view! {
+                                                    <A href=item.link attr:class="checklist-item">
+                                                        <div class="activity-icon">"☐"</div>
+                                                        <div class="activity-content">
+                                                            <div class="activity-title">{item.label}</div>
+                                                            <div class="activity-subtitle">{item.hint}</div>
+                                                        </div>
+                                                        <span class="checklist-delta">{format!("+{}", item.delta)}</span>
+                                                    </A>
+                                                }).collect_view()}
+                                            </div>
+                                        </div>
+                                    })}
+                                </Suspense>
+
+                                <Suspense fallback=|| ()>
+                                    {move || sla_nudges.get().filter(|nudges| !nudges.is_empty()).map(|nudges| view! {
+                                        <div class="recent-activity sla-nudges">
+                                            <h2>"Waiting on you"</h2>
+                                            <p class="sla-nudges-subtitle">
+                                                {format!("Reply within {} hours to keep your response-time badge.", SLA_HOURS as i64)}
+                                            </p>
+                                            <div class="activity-list">
+                                                {nudges.into_iter().map(|nudge| {
+                                                    let overdue = nudge.hours_waiting >= SLA_HOURS;
+                                                    view! {
+                                                        <A href="/artist/dashboard/requests" attr:class="sla-nudge">
+                                                            <div class="activity-icon">{if overdue { "⏰" } else { "⏳" }}</div>
+                                                            <div class="activity-content">
+                                                                <div class="activity-title">
+                                                                    {format!("{} is waiting for a reply", nudge.client_name)}
+                                                                </div>
+                                                                <div class="activity-subtitle">
+                                                                    {format!("Requested {}", nudge.requested_date)}
+                                                                </div>
+                                                            </div>
+                                                            <span class=if overdue { "sla-nudge-time overdue" } else { "sla-nudge-time" }>
+                                                                {time_left(nudge.hours_waiting)}
+                                                            </span>
+                                                        </A>
+                                                    }
+                                                }).collect_view()}
+                                            </div>
+                                        </div>
+                                    })}
+                                </Suspense>
+
+                                <Suspense fallback=|| ()>
+                                    {move || profile_link.get().flatten().map(|stats| view! {
+                                        <div class="recent-activity profile-link">
+                                            <h2>"Your profile link"</h2>
+                                            <p class="profile-link-subtitle">
+                                                "Short enough for your bio and texts. Every visit through it is counted."
+                                            </p>
+                                            <input type="text" class="profile-link-url" readonly value=stats.url />
+                                            <div class="profile-link-stats">
+                                                <span class="profile-link-clicks">
+                                                    {match stats.clicks {
+                                                        1 => "1 visit".to_string(),
+                                                        n => format!("{} visits", n),
+                                                    }}
+                                                </span>
+                                                {stats.referrers.into_iter().map(|referrer| view! {
+                                                    <span class="profile-link-referrer">
+                                                        {format!(
+                                                            "{} · {}",
+                                                            referrer.host.unwrap_or_else(|| "Direct".to_string()),
+                                                            referrer.clicks,
+                                                        )}
+                                                    </span>
+                                                }).collect_view()}
+                                            </div>
+                                        </div>
+                                    })}
+                                </Suspense>
+
                                 <div class="recent-activity">
                                     <h2>"Recent Activity"</h2>
                                     <div class="activity-list">
@@ -174,3 +485,55 @@ fn DashboardTile(
         </A>
     }
 }
+
+/// One bar of a [`TrendChart`]
+struct TrendBar {
+    label: String,
+    value: f64,
+    detail: String, // shown on hover
+}
+
+/// "Jun 3" for the week starting June 3rd (`2024-06-03`)
+fn week_label(week_start: &str) -> String {
+    chrono::NaiveDate::parse_from_str(week_start, "%Y-%m-%d")
+        .map(|date| date.format("%b %-d").to_string())
+        .unwrap_or_else(|_| week_start.to_string())
+}
+
+/// "12%", or "–" without any views to click
+fn rate_label(rate: Option<f64>) -> String {
+    rate.map(|rate| format!("{:.0}%", rate * 100.0))
+        .unwrap_or_else(|| "–".to_string())
+}
+
+/// Bar chart of a weekly series. Bars are scaled to `max`, or to the
+/// tallest bar without one.
+#[component]
+fn TrendChart(
+    title: &'static str,
+    bars: Vec<TrendBar>,
+    #[prop(optional)] max: Option<f64>,
+) -> impl IntoView {
+    let max = max
+        .unwrap_or_else(|| bars.iter().map(|bar| bar.value).fold(0.0, f64::max))
+        .max(1.0);
+
+    view! {
+        <div class="trend-chart">
+            <h3>{title}</h3>
+            <div class="trend-chart-bars">
+                {bars.into_iter().map(|bar| view! {
+                    <div class="trend-chart-column" title=format!("{}: {}", bar.label, bar.detail)>
+                        <div class="trend-chart-track">
+                            <div
+                                class="trend-chart-bar"
+                                style=format!("height: {:.1}%", (bar.value / max * 100.0).clamp(0.0, 100.0))
+                            ></div>
+                        </div>
+                        <span class="trend-chart-label">{bar.label}</span>
+                    </div>
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,242 @@
+use crate::api_error::{user_message, ApiError};
+use crate::server_licenses::{delete_license, get_my_licenses, save_license};
+use crate::utils::auth::get_auth_token;
+use crate::utils::licensing::{status_label, US_STATES};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::use_query_map;
+
+/// Settings card for the artist's tattoo licenses: one per issuing state,
+/// each with a document for admins to verify it against
+#[component]
+pub fn LicenseSettings() -> impl IntoView {
+    let license_number = RwSignal::new(String::new());
+    let issuing_state = RwSignal::new(String::new());
+    let expires_on = RwSignal::new(String::new());
+    let saving = RwSignal::new(false);
+    let license_error = RwSignal::new(None::<String>);
+    let field_error = RwSignal::new(None::<(String, String)>);
+    let licenses_version = RwSignal::new(0u32);
+
+    // Plain form posts can't set headers, so the document upload sends the
+    // token as a field
+    let upload_token = RwSignal::new(String::new());
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            upload_token.set(token);
+        }
+    });
+
+    let query = use_query_map();
+    let upload_notice = move || {
+        let query = query.get();
+        if let Some(error) = query.get("license_error") {
+            Some(("error-message", format!("Upload failed: {}", error)))
+        } else if query.get("license").as_deref() == Some("uploaded") {
+            Some((
+                "success-message",
+                "Document uploaded. Your license will be reviewed shortly.".to_string(),
+            ))
+        } else {
+            None
+        }
+    };
+
+    let licenses_resource = Resource::new(
+        move || licenses_version.get(),
+        move |_| async move {
+            match get_auth_token() {
+                Some(token) => get_my_licenses(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+
+    let on_result = move |result: Result<(), ServerFnError<ApiError>>| match result {
+        Ok(()) => {
+            license_error.set(None);
+            field_error.set(None);
+            licenses_version.update(|v| *v += 1);
+        }
+        Err(ServerFnError::WrappedServerError(ApiError::Validation { field, message })) => {
+            field_error.set(Some((field, message)));
+        }
+        Err(e) => license_error.set(Some(user_message(&e))),
+    };
+
+    let field_hint = move |field: &'static str| {
+        move || {
+            field_error
+                .get()
+                .filter(|(name, _)| name == field)
+                .map(|(_, message)| view! { <p class="license-field-error">{message}</p> })
+        }
+    };
+
+    let save = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        saving.set(true);
+        spawn_local(async move {
+            let result = save_license(
+                token,
+                license_number.get_untracked(),
+                issuing_state.get_untracked(),
+                expires_on.get_untracked(),
+            )
+            .await;
+            if result.is_ok() {
+                license_number.set(String::new());
+                expires_on.set(String::new());
+            }
+            on_result(result);
+            saving.set(false);
+        });
+    };
+
+    let remove = move |license_id: i32| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(delete_license(token, license_id).await);
+        });
+    };
+
+    // Editing a state's license starts from what's on file
+    let edit = move |number: String, state: String, expiry: Option<String>| {
+        license_number.set(number);
+        issuing_state.set(state);
+        expires_on.set(expiry.unwrap_or_default());
+        field_error.set(None);
+    };
+
+    view! {
+        <div class="settings-card license-settings">
+            <h2>"Licenses"</h2>
+            <p class="setting-description">
+                "Add the tattoo license for each state you work in and upload a photo or scan of it. "
+                "Once verified, it's shown on your profile in states that require it. "
+                "We'll remind you before it expires."
+            </p>
+
+            {move || upload_notice().map(|(class, message)| view! {
+                <div class=class>{message}</div>
+            })}
+            {move || license_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <Suspense fallback=|| ()>
+                {move || licenses_resource.get().map(|licenses| {
+                    if licenses.is_empty() {
+                        return view! {
+                            <p class="setting-description">"No licenses added yet."</p>
+                        }.into_any();
+                    }
+                    view! {
+                        <div class="license-list">
+                            {licenses.into_iter().map(|license| {
+                                let license_id = license.id;
+                                let number = license.license_number.clone();
+                                let state = license.issuing_state.clone();
+                                let expiry = license.expires_on.clone();
+                                view! {
+                                    <div class="license-item">
+                                        <div class="license-item-header">
+                                            <strong>{license.issuing_state.clone()}</strong>
+                                            <span class=format!("license-status license-status-{}", license.verification_status)>
+                                                {status_label(&license.verification_status)}
+                                            </span>
+                                        </div>
+                                        <p class="license-details">
+                                            {format!("License {}", license.license_number)}
+                                            {license.expires_on.clone().map(|date| format!(" · Expires {}", date))}
+                                        </p>
+                                        {license.rejection_reason.clone().map(|reason| view! {
+                                            <p class="license-rejection">{format!("Not verified: {}", reason)}</p>
+                                        })}
+                                        {license.document_url.clone().map(|url| view! {
+                                            <a href=url target="_blank" class="license-document-link">"View document"</a>
+                                        })}
+                                        <form
+                                            class="license-document-form"
+                                            method="post"
+                                            action="/api/artist/license-document"
+                                            enctype="multipart/form-data"
+                                        >
+                                            <input type="hidden" name="token" prop:value=move || upload_token.get() />
+                                            <input type="hidden" name="license_id" value=license_id.to_string() />
+                                            <input type="file" name="file" accept="image/jpeg,image/png,image/webp,image/heic,.heic" required />
+                                            <button type="submit" class="btn btn-secondary">
+                                                {if license.document_url.is_some() { "Replace Document" } else { "Upload Document" }}
+                                            </button>
+                                        </form>
+                                        <div class="license-item-actions">
+                                            <button
+                                                class="btn btn-secondary"
+                                                on:click=move |_| edit(number.clone(), state.clone(), expiry.clone())
+                                            >
+                                                "Edit"
+                                            </button>
+                                            <button class="btn btn-outline-danger" on:click=move |_| remove(license_id)>
+                                                "Delete"
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect_view()}
+                        </div>
+                    }.into_any()
+                })}
+            </Suspense>
+
+            <div class="license-form">
+                <h3>"Add or Update a License"</h3>
+                <div class="setting-group">
+                    <label>"Issuing state"</label>
+                    <select
+                        prop:value=move || issuing_state.get()
+                        on:change=move |ev| issuing_state.set(event_target_value(&ev))
+                    >
+                        <option value="">"Choose a state"</option>
+                        {US_STATES.iter().map(|state| view! {
+                            <option value=*state>{*state}</option>
+                        }).collect_view()}
+                    </select>
+                    {field_hint("issuing_state")}
+                </div>
+                <div class="setting-group">
+                    <label>"License number"</label>
+                    <input
+                        type="text"
+                        maxlength="64"
+                        prop:value=move || license_number.get()
+                        on:input=move |ev| license_number.set(event_target_value(&ev))
+                    />
+                    {field_hint("license_number")}
+                </div>
+                <div class="setting-group">
+                    <label>"Expires on"</label>
+                    <input
+                        type="date"
+                        prop:value=move || expires_on.get()
+                        on:input=move |ev| expires_on.set(event_target_value(&ev))
+                    />
+                    <p class="setting-description">"Leave blank if your license doesn't expire."</p>
+                    {field_hint("expires_on")}
+                </div>
+                <div class="setting-actions">
+                    <button
+                        class="btn btn-primary"
+                        disabled=move || saving.get()
+                        on:click=save
+                    >
+                        {move || if saving.get() { "Saving..." } else { "Save License" }}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
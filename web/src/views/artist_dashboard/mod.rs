@@ -1,14 +1,30 @@
+pub mod auto_response;
+pub mod bio;
+pub mod booking_archive;
 pub mod booking_details;
 pub mod calendar;
+pub mod documents;
+pub mod embed;
+pub mod flash;
+pub mod guest_spots;
+pub mod hints;
 pub mod home;
+pub mod licenses;
+pub mod onboarding;
+pub mod pricing;
+pub mod profile_content;
+pub mod profile_questions;
 pub mod questionnaire;
 pub mod recurring;
 pub mod requests;
 pub mod settings;
+pub mod team;
+pub mod translation;
 
 pub use booking_details::BookingDetails;
 pub use calendar::ArtistCalendar;
 pub use home::ArtistHome;
+pub use onboarding::ArtistOnboarding;
 pub use questionnaire::QuestionnaireBuilder;
 pub use recurring::ArtistRecurring;
 pub use requests::ArtistRequests;
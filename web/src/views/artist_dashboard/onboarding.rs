@@ -0,0 +1,627 @@
+use crate::db::entities::{OnboardingStatus, ShopCandidate, UpdateBusinessHours};
+use crate::server::{get_available_styles, get_business_hours, update_business_hours};
+use crate::server_onboarding::{
+    complete_onboarding, get_onboarding_status, search_onboarding_shops, set_onboarding_shop,
+    set_onboarding_shop_address, set_onboarding_styles, MAX_ARTIST_STYLES,
+};
+use crate::utils::auth::get_auth_token;
+use crate::views::artist_dashboard::pricing::PricingSettings;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::components::A;
+use leptos_router::hooks::use_query_map;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Step {
+    Shop,
+    Styles,
+    Pricing,
+    Hours,
+    Portfolio,
+    Finish,
+}
+
+impl Step {
+    const ALL: [Step; 6] = [
+        Step::Shop,
+        Step::Styles,
+        Step::Pricing,
+        Step::Hours,
+        Step::Portfolio,
+        Step::Finish,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Step::Shop => "Shop",
+            Step::Styles => "Styles",
+            Step::Pricing => "Pricing",
+            Step::Hours => "Hours",
+            Step::Portfolio => "Portfolio",
+            Step::Finish => "Finish",
+        }
+    }
+
+    fn next(self) -> Step {
+        let index = Step::ALL.iter().position(|s| *s == self).unwrap_or(0);
+        Step::ALL[(index + 1).min(Step::ALL.len() - 1)]
+    }
+
+    fn done(self, status: &OnboardingStatus) -> bool {
+        match self {
+            Step::Shop => status.shop.is_some(),
+            Step::Styles => !status.style_ids.is_empty(),
+            Step::Pricing => status.has_pricing,
+            Step::Hours => status.open_days > 0,
+            Step::Portfolio => status.photo_count > 0,
+            Step::Finish => status.completed,
+        }
+    }
+
+    /// Where a returning artist picks up
+    fn first_open(status: &OnboardingStatus) -> Step {
+        Step::ALL
+            .into_iter()
+            .find(|step| !step.done(status))
+            .unwrap_or(Step::Finish)
+    }
+}
+
+/// Days in the order they're shown, with `business_hours.day_of_week`
+/// (0 = Sunday)
+const WEEK: [(&str, i32); 7] = [
+    ("Monday", 1),
+    ("Tuesday", 2),
+    ("Wednesday", 3),
+    ("Thursday", 4),
+    ("Friday", 5),
+    ("Saturday", 6),
+    ("Sunday", 0),
+];
+
+/// Step-by-step setup for artists who just signed up: shop, styles,
+/// pricing, hours and portfolio, then the profile goes live
+#[component]
+pub fn ArtistOnboarding() -> impl IntoView {
+    let status = RwSignal::new(None::<OnboardingStatus>);
+    let step = RwSignal::new(Step::Shop);
+    let error = RwSignal::new(None::<String>);
+    let query = use_query_map();
+
+    let reload = move |advance: bool| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            match get_onboarding_status(token).await {
+                Ok(loaded) => {
+                    if advance {
+                        step.update(|s| *s = s.next());
+                    }
+                    status.set(Some(loaded));
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    Effect::new(move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        // Portfolio uploads post a form and come back here
+        let from_upload = query.get_untracked().get("portfolio").is_some()
+            || query.get_untracked().get("portfolio_error").is_some();
+        spawn_local(async move {
+            match get_onboarding_status(token).await {
+                Ok(loaded) => {
+                    step.set(if from_upload {
+                        Step::Portfolio
+                    } else {
+                        Step::first_open(&loaded)
+                    });
+                    status.set(Some(loaded));
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    });
+
+    let on_saved = Callback::new(move |_: ()| {
+        error.set(None);
+        reload(true);
+    });
+    let on_error = Callback::new(move |message: String| error.set(Some(message)));
+
+    view! {
+        <div class="onboarding-page">
+            <div class="onboarding-container">
+                <h1>"Set up your artist profile"</h1>
+
+                <ol class="onboarding-steps">
+                    {Step::ALL.into_iter().map(|s| view! {
+                        <li
+                            class="onboarding-step"
+                            class:active=move || step.get() == s
+                            class:done=move || status.get().is_some_and(|status| s.done(&status))
+                            on:click=move |_| step.set(s)
+                        >
+                            {s.label()}
+                        </li>
+                    }).collect_view()}
+                </ol>
+
+                {move || error.get().map(|error| view! {
+                    <div class="error-message">{error}</div>
+                })}
+
+                {move || status.get().map(|current| match step.get() {
+                    Step::Shop => view! {
+                        <ShopStep status=current on_saved=on_saved on_error=on_error />
+                    }.into_any(),
+                    Step::Styles => view! {
+                        <StylesStep status=current on_saved=on_saved on_error=on_error />
+                    }.into_any(),
+                    Step::Pricing => view! {
+                        <div class="onboarding-card">
+                            <PricingSettings />
+                            <div class="onboarding-actions">
+                                <button class="btn btn-primary" on:click=move |_| on_saved.run(())>
+                                    "Continue"
+                                </button>
+                            </div>
+                        </div>
+                    }.into_any(),
+                    Step::Hours => view! {
+                        <HoursStep status=current on_saved=on_saved on_error=on_error />
+                    }.into_any(),
+                    Step::Portfolio => view! {
+                        <PortfolioStep status=current on_continue=on_saved />
+                    }.into_any(),
+                    Step::Finish => view! {
+                        <FinishStep status=current on_error=on_error />
+                    }.into_any(),
+                })}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ShopStep(
+    status: OnboardingStatus,
+    on_saved: Callback<()>,
+    on_error: Callback<String>,
+) -> impl IntoView {
+    let query = RwSignal::new(String::new());
+    let results = RwSignal::new(Vec::<ShopCandidate>::new());
+    let searched = RwSignal::new(false);
+    let manual = RwSignal::new(false);
+    let shop_name = RwSignal::new(String::new());
+    let address = RwSignal::new(String::new());
+    let working = RwSignal::new(false);
+
+    let search = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        working.set(true);
+        spawn_local(async move {
+            match search_onboarding_shops(token, query.get_untracked()).await {
+                Ok(found) => {
+                    results.set(found);
+                    searched.set(true);
+                }
+                Err(e) => on_error.run(e.to_string()),
+            }
+            working.set(false);
+        });
+    };
+
+    let pick = move |place_id: String| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        working.set(true);
+        spawn_local(async move {
+            match set_onboarding_shop(token, place_id).await {
+                Ok(_) => on_saved.run(()),
+                Err(e) => on_error.run(e.to_string()),
+            }
+            working.set(false);
+        });
+    };
+
+    let save_address = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        working.set(true);
+        spawn_local(async move {
+            match set_onboarding_shop_address(
+                token,
+                shop_name.get_untracked(),
+                address.get_untracked(),
+            )
+            .await
+            {
+                Ok(_) => on_saved.run(()),
+                Err(e) => on_error.run(e.to_string()),
+            }
+            working.set(false);
+        });
+    };
+
+    view! {
+        <div class="onboarding-card">
+            <h2>"Where do you work?"</h2>
+            {status.shop.map(|shop| view! {
+                <p class="onboarding-current">
+                    {format!("Current shop: {}, {}", shop.name, shop.address)}
+                </p>
+            })}
+
+            <Show
+                when=move || !manual.get()
+                fallback=move || view! {
+                    <form class="onboarding-form" on:submit=save_address>
+                        <input
+                            type="text"
+                            placeholder="Shop or studio name"
+                            required
+                            prop:value=move || shop_name.get()
+                            on:input=move |ev| shop_name.set(event_target_value(&ev))
+                        />
+                        <input
+                            type="text"
+                            placeholder="Street address, city, state"
+                            required
+                            prop:value=move || address.get()
+                            on:input=move |ev| address.set(event_target_value(&ev))
+                        />
+                        <button type="submit" class="btn btn-primary" disabled=move || working.get()>
+                            "Use This Address"
+                        </button>
+                        <button type="button" class="btn btn-link" on:click=move |_| manual.set(false)>
+                            "Search instead"
+                        </button>
+                    </form>
+                }
+            >
+                <form class="onboarding-form" on:submit=search>
+                    <input
+                        type="text"
+                        placeholder="Shop name and city, e.g. Black Rose Tattoo Portland"
+                        prop:value=move || query.get()
+                        on:input=move |ev| query.set(event_target_value(&ev))
+                    />
+                    <button type="submit" class="btn btn-primary" disabled=move || working.get()>
+                        "Search"
+                    </button>
+                </form>
+
+                <ul class="onboarding-results">
+                    {move || results.get().into_iter().map(|candidate| {
+                        let place_id = candidate.place_id.clone();
+                        view! {
+                            <li>
+                                <div>
+                                    <strong>{candidate.name}</strong>
+                                    <span>{candidate.address}</span>
+                                </div>
+                                <button
+                                    class="btn btn-secondary"
+                                    disabled=move || working.get()
+                                    on:click=move |_| pick(place_id.clone())
+                                >
+                                    "This is my shop"
+                                </button>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+                <Show when=move || searched.get() && results.get().is_empty()>
+                    <p>"No shops found."</p>
+                </Show>
+
+                <button type="button" class="btn btn-link" on:click=move |_| manual.set(true)>
+                    "My shop isn't listed; enter the address"
+                </button>
+            </Show>
+        </div>
+    }
+}
+
+#[component]
+fn StylesStep(
+    status: OnboardingStatus,
+    on_saved: Callback<()>,
+    on_error: Callback<String>,
+) -> impl IntoView {
+    let selected = RwSignal::new(status.style_ids.clone());
+    let saving = RwSignal::new(false);
+    let styles = Resource::new(|| (), |_| async move { get_available_styles().await });
+
+    let toggle = move |style_id: i32| {
+        selected.update(|ids| {
+            if let Some(index) = ids.iter().position(|id| *id == style_id) {
+                ids.remove(index);
+            } else if ids.len() < MAX_ARTIST_STYLES {
+                ids.push(style_id);
+            }
+        });
+    };
+
+    let save = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        saving.set(true);
+        spawn_local(async move {
+            match set_onboarding_styles(token, selected.get_untracked()).await {
+                Ok(()) => on_saved.run(()),
+                Err(e) => on_error.run(e.to_string()),
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="onboarding-card">
+            <h2>"What do you tattoo?"</h2>
+            <p>{format!("Pick up to {} styles. Clients are matched to you by these.", MAX_ARTIST_STYLES)}</p>
+            <Suspense fallback=|| view! { <p>"Loading styles..."</p> }>
+                {move || styles.get().map(|loaded| view! {
+                    <div class="onboarding-styles">
+                        {loaded.unwrap_or_default().into_iter().map(|style| {
+                            let style_id = style.id;
+                            view! {
+                                <button
+                                    type="button"
+                                    class="onboarding-style"
+                                    class:selected=move || selected.get().contains(&style_id)
+                                    on:click=move |_| toggle(style_id)
+                                >
+                                    {style.name}
+                                </button>
+                            }
+                        }).collect_view()}
+                    </div>
+                })}
+            </Suspense>
+            <div class="onboarding-actions">
+                <button
+                    class="btn btn-primary"
+                    disabled=move || saving.get() || selected.get().is_empty()
+                    on:click=save
+                >
+                    "Save Styles"
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn HoursStep(
+    status: OnboardingStatus,
+    on_saved: Callback<()>,
+    on_error: Callback<String>,
+) -> impl IntoView {
+    let artist_id = status.artist_id;
+    // Weekdays open 10 to 6 unless they've been set already
+    let days: Vec<_> = WEEK
+        .iter()
+        .map(|(name, day_of_week)| {
+            let weekend = *day_of_week == 0 || *day_of_week == 6;
+            (
+                *name,
+                *day_of_week,
+                RwSignal::new("10:00".to_string()),
+                RwSignal::new("18:00".to_string()),
+                RwSignal::new(weekend),
+            )
+        })
+        .collect();
+    let saving = RwSignal::new(false);
+
+    {
+        let days = days.clone();
+        Effect::new(move |_| {
+            let days = days.clone();
+            spawn_local(async move {
+                if let Ok(hours) = get_business_hours(artist_id).await {
+                    for hour in hours {
+                        if let Some((_, _, start, end, closed)) =
+                            days.iter().find(|day| day.1 == hour.day_of_week)
+                        {
+                            start.set(hour.start_time.unwrap_or_default());
+                            end.set(hour.end_time.unwrap_or_default());
+                            closed.set(hour.is_closed);
+                        }
+                    }
+                }
+            });
+        });
+    }
+
+    let save = {
+        let days = days.clone();
+        move |_| {
+            let Some(token) = get_auth_token() else {
+                return;
+            };
+            let hours: Vec<UpdateBusinessHours> = days
+                .iter()
+                .map(|(_, day_of_week, start, end, closed)| {
+                    let is_closed = closed.get_untracked();
+                    UpdateBusinessHours {
+                        artist_id,
+                        day_of_week: *day_of_week,
+                        start_time: (!is_closed).then(|| start.get_untracked()),
+                        end_time: (!is_closed).then(|| end.get_untracked()),
+                        is_closed,
+                    }
+                })
+                .collect();
+            saving.set(true);
+            spawn_local(async move {
+                match update_business_hours(hours, token).await {
+                    Ok(()) => on_saved.run(()),
+                    Err(e) => on_error.run(e.to_string()),
+                }
+                saving.set(false);
+            });
+        }
+    };
+
+    view! {
+        <div class="onboarding-card">
+            <h2>"When are you open?"</h2>
+            <div class="onboarding-hours">
+                {days.into_iter().map(|(name, _, start, end, closed)| view! {
+                    <div class="onboarding-hours-row">
+                        <span class="day">{name}</span>
+                        <input
+                            type="time"
+                            disabled=move || closed.get()
+                            prop:value=move || start.get()
+                            on:input=move |ev| start.set(event_target_value(&ev))
+                        />
+                        <input
+                            type="time"
+                            disabled=move || closed.get()
+                            prop:value=move || end.get()
+                            on:input=move |ev| end.set(event_target_value(&ev))
+                        />
+                        <label>
+                            <input
+                                type="checkbox"
+                                prop:checked=move || closed.get()
+                                on:change=move |ev| closed.set(event_target_checked(&ev))
+                            />
+                            "Closed"
+                        </label>
+                    </div>
+                }).collect_view()}
+            </div>
+            <div class="onboarding-actions">
+                <button class="btn btn-primary" disabled=move || saving.get() on:click=save>
+                    "Save Hours"
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn PortfolioStep(status: OnboardingStatus, on_continue: Callback<()>) -> impl IntoView {
+    // The upload posts a plain form, which carries the token itself
+    let upload_token = RwSignal::new(String::new());
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            upload_token.set(token);
+        }
+    });
+
+    let query = use_query_map();
+    let upload_notice = move || {
+        let query = query.get();
+        if let Some(error) = query.get("portfolio_error") {
+            Some(("error-message", format!("Upload failed: {}", error)))
+        } else if query.get("portfolio").as_deref() == Some("uploaded") {
+            Some((
+                "success-message",
+                "Image added to your portfolio".to_string(),
+            ))
+        } else {
+            None
+        }
+    };
+
+    view! {
+        <div class="onboarding-card">
+            <h2>"Show your work"</h2>
+            <p>
+                {format!(
+                    "{} photos uploaded. Add a few of your best pieces; you can add more from settings later.",
+                    status.photo_count
+                )}
+            </p>
+            {move || upload_notice().map(|(class, message)| view! {
+                <div class=class>{message}</div>
+            })}
+            <form
+                class="onboarding-form"
+                method="post"
+                action="/api/artist/portfolio?return_to=onboarding"
+                enctype="multipart/form-data"
+            >
+                <input type="hidden" name="token" prop:value=move || upload_token.get() />
+                <input type="file" name="file" accept="image/jpeg,image/png,image/webp,image/heic,.heic" required />
+                <input type="text" name="caption" placeholder="Caption (optional)" maxlength="500" />
+                <button type="submit" class="btn btn-secondary">"Upload Image"</button>
+            </form>
+            <div class="onboarding-actions">
+                <button class="btn btn-primary" on:click=move |_| on_continue.run(())>
+                    "Continue"
+                </button>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn FinishStep(status: OnboardingStatus, on_error: Callback<String>) -> impl IntoView {
+    let completed = RwSignal::new(status.completed);
+    let finishing = RwSignal::new(false);
+    let required_done =
+        Step::Shop.done(&status) && Step::Styles.done(&status) && Step::Hours.done(&status);
+
+    let finish = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        finishing.set(true);
+        spawn_local(async move {
+            match complete_onboarding(token).await {
+                Ok(done) => completed.set(done.completed),
+                Err(e) => on_error.run(e.to_string()),
+            }
+            finishing.set(false);
+        });
+    };
+
+    view! {
+        <div class="onboarding-card">
+            <Show
+                when=move || completed.get()
+                fallback=move || view! {
+                    <h2>"Ready to go live?"</h2>
+                    <p>
+                        {if required_done {
+                            "Your profile will show up in search and matching once you finish."
+                        } else {
+                            "Set your shop, styles and hours first. Pricing and photos can wait."
+                        }}
+                    </p>
+                    <div class="onboarding-actions">
+                        <button
+                            class="btn btn-primary"
+                            disabled=move || finishing.get() || !required_done
+                            on:click=finish
+                        >
+                            "Finish Setup"
+                        </button>
+                    </div>
+                }
+            >
+                <h2>"You're live"</h2>
+                <p>"Clients can now find and book you."</p>
+                <A href="/artist/dashboard" attr:class="btn btn-primary">"Go to Dashboard"</A>
+            </Show>
+        </div>
+    }
+}
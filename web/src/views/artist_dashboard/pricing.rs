@@ -0,0 +1,215 @@
+use crate::db::entities::{ArtistPricing, StylePrice};
+use crate::server_pricing::{get_artist_pricing, update_artist_pricing};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+fn amount_text(amount: Option<f64>) -> String {
+    amount.map(|a| format!("{}", a)).unwrap_or_default()
+}
+
+/// Blank is unset; anything else must be a number
+fn parse_amount(text: &str, field: &str) -> Result<Option<f64>, String> {
+    let text = text.trim().trim_start_matches('$');
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse::<f64>()
+        .map(Some)
+        .map_err(|_| format!("{} must be a number", field))
+}
+
+/// One editable row of the per-style price table
+#[derive(Clone)]
+struct StylePriceRow {
+    style_id: i32,
+    style_name: String,
+    min_price: RwSignal<String>,
+    max_price: RwSignal<String>,
+}
+
+/// Settings card where an artist sets what they charge. Match results, shop
+/// pages and map pins quote ranges from these.
+#[component]
+pub fn PricingSettings() -> impl IntoView {
+    let hourly_rate = RwSignal::new(String::new());
+    let minimum_charge = RwSignal::new(String::new());
+    let style_rows = RwSignal::new(Vec::<StylePriceRow>::new());
+    let saving = RwSignal::new(false);
+    let pricing_error = RwSignal::new(None::<String>);
+    let saved = RwSignal::new(false);
+
+    let load = move |pricing: ArtistPricing| {
+        hourly_rate.set(amount_text(pricing.hourly_rate));
+        minimum_charge.set(amount_text(pricing.minimum_charge));
+        style_rows.set(
+            pricing
+                .style_prices
+                .into_iter()
+                .map(|price| StylePriceRow {
+                    style_id: price.style_id,
+                    style_name: price.style_name,
+                    min_price: RwSignal::new(amount_text(price.min_price)),
+                    max_price: RwSignal::new(amount_text(price.max_price)),
+                })
+                .collect(),
+        );
+    };
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                match get_artist_pricing(token).await {
+                    Ok(pricing) => load(pricing),
+                    Err(e) => pricing_error.set(Some(e.to_string())),
+                }
+            });
+        }
+    });
+
+    let collect_pricing = move || -> Result<ArtistPricing, String> {
+        let style_prices = style_rows
+            .get_untracked()
+            .into_iter()
+            .map(|row| {
+                Ok(StylePrice {
+                    style_id: row.style_id,
+                    min_price: parse_amount(
+                        &row.min_price.get_untracked(),
+                        &format!("{} low price", row.style_name),
+                    )?,
+                    max_price: parse_amount(
+                        &row.max_price.get_untracked(),
+                        &format!("{} high price", row.style_name),
+                    )?,
+                    style_name: row.style_name,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(ArtistPricing {
+            hourly_rate: parse_amount(&hourly_rate.get_untracked(), "Hourly rate")?,
+            minimum_charge: parse_amount(&minimum_charge.get_untracked(), "Minimum charge")?,
+            style_prices,
+        })
+    };
+
+    let save_pricing = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let pricing = match collect_pricing() {
+            Ok(pricing) => pricing,
+            Err(e) => {
+                pricing_error.set(Some(e));
+                return;
+            }
+        };
+        saving.set(true);
+        saved.set(false);
+        spawn_local(async move {
+            match update_artist_pricing(token, pricing).await {
+                Ok(pricing) => {
+                    load(pricing);
+                    pricing_error.set(None);
+                    saved.set(true);
+                }
+                Err(e) => pricing_error.set(Some(e.to_string())),
+            }
+            saving.set(false);
+        });
+    };
+
+    view! {
+        <div class="settings-card pricing-settings">
+            <h2>"Pricing Configuration"</h2>
+
+            {move || pricing_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <div class="setting-group">
+                <label class="setting-label">"Hourly Rate"</label>
+                <div class="price-input">
+                    <span class="currency">"$"</span>
+                    <input
+                        type="text"
+                        inputmode="decimal"
+                        placeholder="200"
+                        prop:value=move || hourly_rate.get()
+                        on:input=move |ev| hourly_rate.set(event_target_value(&ev))
+                    />
+                </div>
+                <p class="setting-description">"Rate per hour for larger, custom pieces"</p>
+            </div>
+
+            <div class="setting-group">
+                <label class="setting-label">"Minimum Charge"</label>
+                <div class="price-input">
+                    <span class="currency">"$"</span>
+                    <input
+                        type="text"
+                        inputmode="decimal"
+                        placeholder="150"
+                        prop:value=move || minimum_charge.get()
+                        on:input=move |ev| minimum_charge.set(event_target_value(&ev))
+                    />
+                </div>
+                <p class="setting-description">"The least you charge for any tattoo, however small"</p>
+            </div>
+
+            {move || {
+                let rows = style_rows.get();
+                (!rows.is_empty()).then(|| view! {
+                    <div class="setting-group">
+                        <label class="setting-label">"Pricing by Style"</label>
+                        <p class="setting-description">
+                            "Typical range for a piece in each style. Clients looking for a style see its range; leave blank to quote from your minimum charge."
+                        </p>
+                        <div class="style-pricing">
+                            {rows.into_iter().map(|row| {
+                                let (min_price, max_price) = (row.min_price, row.max_price);
+                                view! {
+                                    <div class="style-price-row">
+                                        <span class="style-price-name">{row.style_name}</span>
+                                        <div class="price-input">
+                                            <span class="currency">"$"</span>
+                                            <input
+                                                type="text"
+                                                inputmode="decimal"
+                                                placeholder="From"
+                                                prop:value=move || min_price.get()
+                                                on:input=move |ev| min_price.set(event_target_value(&ev))
+                                            />
+                                            <span>"–"</span>
+                                            <input
+                                                type="text"
+                                                inputmode="decimal"
+                                                placeholder="To"
+                                                prop:value=move || max_price.get()
+                                                on:input=move |ev| max_price.set(event_target_value(&ev))
+                                            />
+                                        </div>
+                                    </div>
+                                }
+                            }).collect_view()}
+                        </div>
+                    </div>
+                })
+            }}
+
+            <div class="setting-actions">
+                <button
+                    class="btn btn-primary"
+                    disabled=move || saving.get()
+                    on:click=save_pricing
+                >
+                    {move || if saving.get() { "Saving..." } else { "Save Pricing" }}
+                </button>
+                <Show when=move || saved.get()>
+                    <span class="save-confirmation">"Saved"</span>
+                </Show>
+            </div>
+        </div>
+    }
+}
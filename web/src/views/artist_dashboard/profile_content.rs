@@ -0,0 +1,307 @@
+use crate::db::entities::{ArtistFaq, ArtistPolicy, SocialLink};
+use crate::server_profile::{
+    get_my_profile_content, save_artist_faqs, save_artist_policies, save_artist_social_links,
+};
+use crate::utils::auth::get_auth_token;
+use crate::utils::profile::{
+    MAX_FAQ_ANSWER_CHARS, MAX_FAQ_ENTRIES, MAX_FAQ_QUESTION_CHARS, MAX_LINK_CHARS,
+    MAX_POLICY_CHARS, POLICY_KINDS, SOCIAL_PLATFORMS,
+};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// An FAQ entry being edited, keyed so rows keep their inputs when others
+/// are removed
+#[derive(Clone, Copy)]
+struct FaqRow {
+    key: usize,
+    question: RwSignal<String>,
+    answer: RwSignal<String>,
+}
+
+/// Settings card for the links, studio policies and FAQ shown on the
+/// artist's public profile, under their bio
+#[component]
+pub fn ProfileContentSettings() -> impl IntoView {
+    let links: Vec<(&'static str, &'static str, RwSignal<String>)> = SOCIAL_PLATFORMS
+        .iter()
+        .map(|(platform, label, _)| (*platform, *label, RwSignal::new(String::new())))
+        .collect();
+    let policies: Vec<(&'static str, &'static str, RwSignal<String>)> = POLICY_KINDS
+        .iter()
+        .map(|(kind, label)| (*kind, *label, RwSignal::new(String::new())))
+        .collect();
+    let faqs = RwSignal::new(Vec::<FaqRow>::new());
+    let next_key = StoredValue::new(0usize);
+
+    let error = RwSignal::new(None::<String>);
+    let saving = RwSignal::new(None::<&'static str>);
+    let saved = RwSignal::new(None::<&'static str>);
+
+    let add_faq = move |question: String, answer: String| {
+        let key = next_key.get_value();
+        next_key.set_value(key + 1);
+        faqs.update(|rows| {
+            rows.push(FaqRow {
+                key,
+                question: RwSignal::new(question),
+                answer: RwSignal::new(answer),
+            })
+        });
+    };
+
+    {
+        let links = links.clone();
+        let policies = policies.clone();
+        Effect::new(move |_| {
+            let Some(token) = get_auth_token() else {
+                return;
+            };
+            let links = links.clone();
+            let policies = policies.clone();
+            spawn_local(async move {
+                match get_my_profile_content(token).await {
+                    Ok(content) => {
+                        for link in content.social_links {
+                            if let Some((_, _, url)) = links
+                                .iter()
+                                .find(|(platform, _, _)| *platform == link.platform)
+                            {
+                                url.set(link.url);
+                            }
+                        }
+                        for policy in content.policies {
+                            if let Some((_, _, body)) =
+                                policies.iter().find(|(kind, _, _)| *kind == policy.kind)
+                            {
+                                body.set(policy.body);
+                            }
+                        }
+                        faqs.set(Vec::new());
+                        for faq in content.faqs {
+                            add_faq(faq.question, faq.answer);
+                        }
+                    }
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        });
+    }
+
+    let save_links = {
+        let links = links.clone();
+        move || {
+            let Some(token) = get_auth_token() else {
+                return;
+            };
+            let to_save: Vec<SocialLink> = links
+                .iter()
+                .map(|(platform, _, url)| SocialLink {
+                    platform: platform.to_string(),
+                    url: url.get_untracked(),
+                })
+                .collect();
+            let links = links.clone();
+            saving.set(Some("links"));
+            saved.set(None);
+            spawn_local(async move {
+                match save_artist_social_links(token, to_save).await {
+                    Ok(kept) => {
+                        // Show the addresses handles were turned into
+                        for (platform, _, url) in &links {
+                            url.set(
+                                kept.iter()
+                                    .find(|link| link.platform == *platform)
+                                    .map(|link| link.url.clone())
+                                    .unwrap_or_default(),
+                            );
+                        }
+                        error.set(None);
+                        saved.set(Some("links"));
+                    }
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+                saving.set(None);
+            });
+        }
+    };
+
+    let save_policies = {
+        let policies = policies.clone();
+        move || {
+            let Some(token) = get_auth_token() else {
+                return;
+            };
+            let to_save: Vec<ArtistPolicy> = policies
+                .iter()
+                .map(|(kind, _, body)| ArtistPolicy {
+                    kind: kind.to_string(),
+                    body: body.get_untracked(),
+                })
+                .collect();
+            saving.set(Some("policies"));
+            saved.set(None);
+            spawn_local(async move {
+                match save_artist_policies(token, to_save).await {
+                    Ok(()) => {
+                        error.set(None);
+                        saved.set(Some("policies"));
+                    }
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+                saving.set(None);
+            });
+        }
+    };
+
+    let save_faqs = move || {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let to_save: Vec<ArtistFaq> = faqs
+            .get_untracked()
+            .iter()
+            .map(|row| ArtistFaq {
+                question: row.question.get_untracked(),
+                answer: row.answer.get_untracked(),
+            })
+            .collect();
+        saving.set(Some("faqs"));
+        saved.set(None);
+        spawn_local(async move {
+            match save_artist_faqs(token, to_save).await {
+                Ok(()) => {
+                    error.set(None);
+                    saved.set(Some("faqs"));
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            saving.set(None);
+        });
+    };
+
+    let save_button = move |section: &'static str, label: &'static str| {
+        view! {
+            <button class="btn btn-primary" type="submit" disabled=move || saving.get().is_some()>
+                {move || if saving.get() == Some(section) { "Saving..." } else { label }}
+            </button>
+            <Show when=move || saved.get() == Some(section)>
+                <span class="save-confirmation">"Saved"</span>
+            </Show>
+        }
+    };
+
+    view! {
+        <div class="settings-card profile-content-settings">
+            <h2>"Profile Details"</h2>
+            <p class="setting-description">
+                "Shown on your public profile under your bio. Your bio and policies can "
+                "use **bold**, - lists and # headings."
+            </p>
+
+            {move || error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <form
+                class="setting-group"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    save_links();
+                }
+            >
+                <label class="setting-label">"Links"</label>
+                {links.into_iter().map(|(platform, label, url)| view! {
+                    <div class="profile-content-link">
+                        <span>{label}</span>
+                        <input
+                            type="text"
+                            maxlength=MAX_LINK_CHARS.to_string()
+                            placeholder={if platform == "website" { "https://..." } else { "@handle or link" }}
+                            prop:value=move || url.get()
+                            on:input=move |ev| url.set(event_target_value(&ev))
+                        />
+                    </div>
+                }).collect_view()}
+                <div class="setting-actions">{save_button("links", "Save Links")}</div>
+            </form>
+
+            <form
+                class="setting-group"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    save_policies();
+                }
+            >
+                <label class="setting-label">"Studio policies"</label>
+                {policies.into_iter().map(|(kind, label, body)| view! {
+                    <div class="profile-content-policy">
+                        <span>{label}</span>
+                        <textarea
+                            rows="4"
+                            maxlength=MAX_POLICY_CHARS.to_string()
+                            placeholder={match kind {
+                                "deposit" => "How much, when it's due and whether it's refundable",
+                                "cancellation" => "How much notice you need and what happens to the deposit",
+                                _ => "Whether touch-ups are free and for how long",
+                            }}
+                            prop:value=move || body.get()
+                            on:input=move |ev| body.set(event_target_value(&ev))
+                        ></textarea>
+                    </div>
+                }).collect_view()}
+                <div class="setting-actions">{save_button("policies", "Save Policies")}</div>
+            </form>
+
+            <form
+                class="setting-group"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    save_faqs();
+                }
+            >
+                <label class="setting-label">"FAQ"</label>
+                <For
+                    each=move || faqs.get()
+                    key=|row| row.key
+                    children=move |row| view! {
+                        <div class="profile-content-faq">
+                            <input
+                                type="text"
+                                maxlength=MAX_FAQ_QUESTION_CHARS.to_string()
+                                placeholder="Question"
+                                prop:value=move || row.question.get()
+                                on:input=move |ev| row.question.set(event_target_value(&ev))
+                            />
+                            <textarea
+                                rows="3"
+                                maxlength=MAX_FAQ_ANSWER_CHARS.to_string()
+                                placeholder="Answer"
+                                prop:value=move || row.answer.get()
+                                on:input=move |ev| row.answer.set(event_target_value(&ev))
+                            ></textarea>
+                            <button
+                                class="btn btn-secondary"
+                                type="button"
+                                on:click=move |_| faqs.update(|rows| rows.retain(|r| r.key != row.key))
+                            >
+                                "Remove"
+                            </button>
+                        </div>
+                    }
+                />
+                <div class="setting-actions">
+                    <button
+                        class="btn btn-secondary"
+                        type="button"
+                        disabled=move || faqs.get().len() >= MAX_FAQ_ENTRIES
+                        on:click=move |_| add_faq(String::new(), String::new())
+                    >
+                        "Add Question"
+                    </button>
+                    {save_button("faqs", "Save FAQ")}
+                </div>
+            </form>
+        </div>
+    }
+}
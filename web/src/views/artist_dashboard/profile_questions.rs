@@ -0,0 +1,127 @@
+use crate::api_error::{user_message, ApiError};
+use crate::db::entities::ArtistQuestion;
+use crate::server_artist_questions::{
+    answer_artist_question, get_my_questions, report_artist_question,
+};
+use crate::utils::artist_questions::MAX_ANSWER_CHARS;
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Settings card for the questions clients ask on the artist's profile.
+/// Answered questions are shown on the profile; spam goes to the admins.
+#[component]
+pub fn ProfileQuestionSettings() -> impl IntoView {
+    let questions_error = RwSignal::new(None::<String>);
+    let field_error = RwSignal::new(None::<(i32, String)>);
+    let questions_version = RwSignal::new(0u32);
+
+    let questions_resource = Resource::new(
+        move || questions_version.get(),
+        move |_| async move {
+            match get_auth_token() {
+                Some(token) => get_my_questions(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+
+    let on_result =
+        move |question_id: i32, result: Result<(), ServerFnError<ApiError>>| match result {
+            Ok(()) => {
+                questions_error.set(None);
+                field_error.set(None);
+                questions_version.update(|v| *v += 1);
+            }
+            Err(ServerFnError::WrappedServerError(ApiError::Validation { message, .. })) => {
+                field_error.set(Some((question_id, message)));
+            }
+            Err(e) => questions_error.set(Some(user_message(&e))),
+        };
+
+    let answer = move |question_id: i32, text: String| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(
+                question_id,
+                answer_artist_question(token, question_id, text).await,
+            );
+        });
+    };
+
+    let report = move |question_id: i32| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(
+                question_id,
+                report_artist_question(token, question_id).await,
+            );
+        });
+    };
+
+    view! {
+        <div class="settings-card profile-question-settings">
+            <h2>"Questions from Clients"</h2>
+            <p class="setting-description">
+                "Questions people ask on your profile. Your answers are shown on your profile for everyone, "
+                "and the asker gets an email. Report anything that's spam and we'll take a look."
+            </p>
+
+            {move || questions_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <Suspense fallback=|| ()>
+                {move || questions_resource.get().map(|questions| {
+                    if questions.is_empty() {
+                        return view! {
+                            <p class="setting-description">"No questions yet."</p>
+                        }.into_any();
+                    }
+                    view! {
+                        <div class="profile-question-list">
+                            {questions.into_iter().map(|question: ArtistQuestion| {
+                                let question_id = question.id;
+                                let answered = question.answer.is_some();
+                                let draft = RwSignal::new(question.answer.clone().unwrap_or_default());
+                                view! {
+                                    <div class="profile-question-item" class:answered=answered>
+                                        <p class="profile-question-text">{question.question}</p>
+                                        <p class="profile-question-meta">
+                                            {format!("{} · {}", question.asker_name, question.asked_at)}
+                                        </p>
+                                        <textarea
+                                            rows="3"
+                                            maxlength=MAX_ANSWER_CHARS.to_string()
+                                            placeholder="Write your answer"
+                                            prop:value=move || draft.get()
+                                            on:input=move |ev| draft.set(event_target_value(&ev))
+                                        ></textarea>
+                                        {move || field_error.get()
+                                            .filter(|(id, _)| *id == question_id)
+                                            .map(|(_, message)| view! { <p class="profile-question-error">{message}</p> })}
+                                        <div class="profile-question-actions">
+                                            <button
+                                                class="btn btn-primary"
+                                                on:click=move |_| answer(question_id, draft.get_untracked())
+                                            >
+                                                {if answered { "Update Answer" } else { "Answer" }}
+                                            </button>
+                                            <button class="btn btn-outline-danger" on:click=move |_| report(question_id)>
+                                                "Report Spam"
+                                            </button>
+                                        </div>
+                                    </div>
+                                }
+                            }).collect_view()}
+                        </div>
+                    }.into_any()
+                })}
+            </Suspense>
+        </div>
+    }
+}
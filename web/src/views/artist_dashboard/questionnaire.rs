@@ -3,7 +3,7 @@ use crate::server::{
     delete_artist_questionnaire_question, get_artist_questionnaire_configuration,
     get_default_questions, update_artist_questionnaire_configuration,
 };
-use crate::utils::auth::use_authenticated_artist_id;
+use crate::utils::auth::{get_auth_token, use_authenticated_artist_id};
 use leptos::prelude::*;
 use leptos::task::spawn_local;
 use server_fn::ServerFnError;
@@ -95,7 +95,9 @@ fn InteractiveQuestionnaireBuilder(
                 set_is_saving.set(true);
                 set_save_message.set(None);
 
-                match update_artist_questionnaire_configuration(artist_id, config_clone).await {
+                let token = get_auth_token().unwrap_or_default();
+                match update_artist_questionnaire_configuration(artist_id, config_clone, token).await
+                {
                     Ok(_) => {
                         set_save_message.set(Some("Configuration saved successfully!".to_string()));
                         set_has_changes.set(false);
@@ -124,7 +126,8 @@ fn InteractiveQuestionnaireBuilder(
                 set_is_deleting.set(true);
                 set_save_message.set(None);
 
-                match delete_artist_questionnaire_question(artist_id, question_id).await {
+                let token = get_auth_token().unwrap_or_default();
+                match delete_artist_questionnaire_question(artist_id, question_id, token).await {
                     Ok(_) => {
                         // Remove from local state
                         set_current_config.update(|config| {
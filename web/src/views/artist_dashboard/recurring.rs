@@ -1,22 +1,62 @@
-use crate::db::entities::RecurringRule;
+use crate::db::entities::{
+    CreateRecurringRule, RecurrencePattern, RecurringRule, UpdateRecurringRule,
+};
 use crate::server::*;
-use crate::utils::auth::use_authenticated_artist_id;
+use crate::utils::auth::{get_auth_token, use_authenticated_artist_id};
+use crate::utils::recurrence::{describe, FREQUENCIES};
 use leptos::prelude::*;
-use serde_json;
 use thaw::*;
 
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+const WEEKS_OF_MONTH: [(i32, &str); 6] = [
+    (1, "1st"),
+    (2, "2nd"),
+    (3, "3rd"),
+    (4, "4th"),
+    (5, "5th"),
+    (-1, "Last"),
+];
+
+/// Comma separated values from a text input, blanks dropped
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn optional(value: String) -> Option<String> {
+    Some(value).filter(|value| !value.trim().is_empty())
+}
+
 #[component]
 pub fn ArtistRecurring() -> impl IntoView {
     let show_add_rule_modal = RwSignal::new(false);
-    let rule_type = RwSignal::new("weekdays".to_string());
+    let frequency = RwSignal::new("weekly".to_string());
     let rule_action = RwSignal::new("blocked".to_string()); // Default to blocked as per user request
     let rule_name = RwSignal::new("".to_string());
-    let rule_pattern = RwSignal::new("".to_string());
     let start_time = RwSignal::new("".to_string());
     let end_time = RwSignal::new("".to_string());
-    let selected_weekdays = RwSignal::new(Vec::<String>::new());
-    let selected_dates = RwSignal::new("".to_string());
-    let monthly_pattern = RwSignal::new("".to_string());
+    let selected_weekdays = RwSignal::new(Vec::<i32>::new());
+    let interval_weeks = RwSignal::new("1".to_string());
+    let week_of_month = RwSignal::new(1_i32);
+    let monthly_weekday = RwSignal::new(1_i32);
+    let annual_dates = RwSignal::new("".to_string());
+    let starts_on = RwSignal::new("".to_string());
+    let ends_on = RwSignal::new("".to_string());
+    let except_dates = RwSignal::new("".to_string());
+    let form_error = RwSignal::new(None::<String>);
 
     // Get authenticated artist ID from JWT token
     let artist_id = use_authenticated_artist_id();
@@ -26,137 +66,62 @@ pub fn ArtistRecurring() -> impl IntoView {
         move || artist_id.get(),
         move |id_opt| async move {
             match id_opt {
-                Some(id) => get_recurring_rules(id).await.unwrap_or_else(|_| Vec::new()),
+                Some(id) => get_recurring_rules(id, get_auth_token().unwrap_or_default())
+                    .await
+                    .unwrap_or_else(|_| Vec::new()),
                 None => Vec::new(),
             }
         },
     );
 
     // Action to create a new rule
-    let create_rule_action =
-        Action::new(
-            move |(
-                artist_id,
-                name,
-                rule_type,
-                pattern,
-                action,
-                start_time_param,
-                end_time_param,
-            ): &(
-                i32,
-                String,
-                String,
-                String,
-                String,
-                Option<String>,
-                Option<String>,
-            )| {
-                let (artist_id, name, rule_type, pattern, action, start_time_param, end_time_param) = (
-                    *artist_id,
-                    name.clone(),
-                    rule_type.clone(),
-                    pattern.clone(),
-                    action.clone(),
-                    start_time_param.clone(),
-                    end_time_param.clone(),
-                );
-                async move {
-                    match create_recurring_rule(
-                        artist_id,
-                        name,
-                        rule_type,
-                        pattern,
-                        action,
-                        start_time_param,
-                        end_time_param,
-                    )
-                    .await
-                    {
-                        Ok(_) => {
-                            show_add_rule_modal.set(false);
-                            rules_resource.refetch();
-                            // Reset form
-                            rule_name.set("".to_string());
-                            rule_pattern.set("".to_string());
-                            start_time.set("".to_string());
-                            end_time.set("".to_string());
-                            selected_weekdays.set(Vec::new());
-                            selected_dates.set("".to_string());
-                            monthly_pattern.set("".to_string());
-                        }
-                        Err(e) => {
-                            leptos::logging::error!("Failed to create rule: {}", e);
-                        }
-                    }
+    let create_rule_action = Action::new(move |rule: &CreateRecurringRule| {
+        let rule = rule.clone();
+        async move {
+            match create_recurring_rule(rule, get_auth_token().unwrap_or_default()).await {
+                Ok(_) => {
+                    show_add_rule_modal.set(false);
+                    rules_resource.refetch();
+                    // Reset form
+                    form_error.set(None);
+                    rule_name.set("".to_string());
+                    start_time.set("".to_string());
+                    end_time.set("".to_string());
+                    selected_weekdays.set(Vec::new());
+                    interval_weeks.set("1".to_string());
+                    annual_dates.set("".to_string());
+                    starts_on.set("".to_string());
+                    ends_on.set("".to_string());
+                    except_dates.set("".to_string());
                 }
-            },
-        );
+                Err(e) => {
+                    leptos::logging::error!("Failed to create rule: {}", e);
+                    form_error.set(Some(e.to_string()));
+                }
+            }
+        }
+    });
 
     // Action to update a rule
-    let update_rule_action = Action::new(
-        move |(
-            id,
-            name_param,
-            pattern_param,
-            action_param,
-            start_time_param,
-            end_time_param,
-            active_param,
-        ): &(
-            i32,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            Option<bool>,
-        )| {
-            let (
-                id,
-                name_param,
-                pattern_param,
-                action_param,
-                start_time_param,
-                end_time_param,
-                active_param,
-            ) = (
-                *id,
-                name_param.clone(),
-                pattern_param.clone(),
-                action_param.clone(),
-                start_time_param.clone(),
-                end_time_param.clone(),
-                *active_param,
-            );
-            async move {
-                match update_recurring_rule(
-                    id,
-                    name_param,
-                    pattern_param,
-                    action_param,
-                    start_time_param,
-                    end_time_param,
-                    active_param,
-                )
-                .await
-                {
-                    Ok(_) => {
-                        rules_resource.refetch();
-                    }
-                    Err(e) => {
-                        leptos::logging::error!("Failed to update rule: {}", e);
-                    }
+    let update_rule_action = Action::new(move |update: &UpdateRecurringRule| {
+        let update = update.clone();
+        async move {
+            match update_recurring_rule(update, get_auth_token().unwrap_or_default()).await {
+                Ok(_) => {
+                    rules_resource.refetch();
+                }
+                Err(e) => {
+                    leptos::logging::error!("Failed to update rule: {}", e);
                 }
             }
-        },
-    );
+        }
+    });
 
     // Action to delete a rule
     let delete_rule_action = Action::new(move |rule_id: &i32| {
         let rule_id = *rule_id;
         async move {
-            match delete_recurring_rule(rule_id).await {
+            match delete_recurring_rule(rule_id, get_auth_token().unwrap_or_default()).await {
                 Ok(_) => {
                     rules_resource.refetch();
                 }
@@ -168,65 +133,50 @@ pub fn ArtistRecurring() -> impl IntoView {
     });
 
     // Function to build pattern from form inputs
-    let build_pattern = move || -> String {
-        match rule_type.get().as_str() {
-            "weekdays" => {
-                let days = selected_weekdays.get();
-                if days.is_empty() {
-                    "[]".to_string()
-                } else {
-                    // Convert day names to numbers for JSON array
-                    let day_numbers: Vec<i32> = days
-                        .iter()
-                        .map(|day| match day.as_str() {
-                            "Sunday" => 0,
-                            "Monday" => 1,
-                            "Tuesday" => 2,
-                            "Wednesday" => 3,
-                            "Thursday" => 4,
-                            "Friday" => 5,
-                            "Saturday" => 6,
-                            _ => -1,
-                        })
-                        .filter(|&n| n != -1)
-                        .collect();
-
-                    // Create JSON array
-                    serde_json::to_string(&day_numbers).unwrap_or_else(|_| "[]".to_string())
-                }
-            }
-            "dates" => selected_dates.get(),
-            "monthly" => monthly_pattern.get(),
-            _ => "".to_string(),
+    let build_pattern = move || -> RecurrencePattern {
+        let frequency = frequency.get();
+        let mut weekdays = match frequency.as_str() {
+            "weekly" => selected_weekdays.get(),
+            "monthly" => vec![monthly_weekday.get()],
+            _ => Vec::new(),
+        };
+        weekdays.sort_unstable();
+        RecurrencePattern {
+            interval_weeks: if frequency == "weekly" {
+                interval_weeks.get().trim().parse().unwrap_or(0)
+            } else {
+                1
+            },
+            week_of_month: (frequency == "monthly").then(|| week_of_month.get()),
+            annual_dates: if frequency == "yearly" {
+                split_list(&annual_dates.get())
+            } else {
+                Vec::new()
+            },
+            weekdays,
+            frequency,
+            starts_on: optional(starts_on.get()),
+            ends_on: optional(ends_on.get()),
+            except_dates: split_list(&except_dates.get()),
         }
     };
 
     // Function to save the rule
     let save_rule = move |_| {
         if let Some(id) = artist_id.get() {
-            let pattern = build_pattern();
-            if rule_name.get().is_empty() || pattern.is_empty() {
-                leptos::logging::warn!("Rule name and pattern are required");
+            if rule_name.get().trim().is_empty() {
+                form_error.set(Some("Give the rule a name".to_string()));
                 return;
             }
 
-            create_rule_action.dispatch((
-                id,
-                rule_name.get(),
-                rule_type.get(),
-                pattern,
-                rule_action.get(),
-                if start_time.get().is_empty() {
-                    None
-                } else {
-                    Some(start_time.get())
-                },
-                if end_time.get().is_empty() {
-                    None
-                } else {
-                    Some(end_time.get())
-                },
-            ));
+            create_rule_action.dispatch(CreateRecurringRule {
+                artist_id: id,
+                name: rule_name.get(),
+                pattern: build_pattern(),
+                action: rule_action.get(),
+                start_time: optional(start_time.get()),
+                end_time: optional(end_time.get()),
+            });
         } else {
             leptos::logging::error!("No authenticated artist found");
         }
@@ -234,7 +184,15 @@ pub fn ArtistRecurring() -> impl IntoView {
 
     // Function to toggle rule active status
     let toggle_rule_active = move |rule: RecurringRule| {
-        update_rule_action.dispatch((rule.id, None, None, None, None, None, Some(!rule.active)));
+        update_rule_action.dispatch(UpdateRecurringRule {
+            id: rule.id,
+            name: None,
+            pattern: None,
+            action: None,
+            start_time: None,
+            end_time: None,
+            active: Some(!rule.active),
+        });
     };
 
     // Function to delete a rule
@@ -267,7 +225,7 @@ pub fn ArtistRecurring() -> impl IntoView {
                     <div class="rule-type-cards">
                         <div class="rule-type-card">
                             <h3>"📅 Weekday Patterns"</h3>
-                            <p>"Set availability for specific days of the week (e.g., no weekends, Tuesday mornings only)"</p>
+                            <p>"Set availability for specific days of the week (e.g., no weekends, every other Monday)"</p>
                         </div>
                         <div class="rule-type-card">
                             <h3>"🎉 Annual Dates"</h3>
@@ -275,7 +233,7 @@ pub fn ArtistRecurring() -> impl IntoView {
                         </div>
                         <div class="rule-type-card">
                             <h3>"🗓️ Monthly Patterns"</h3>
-                            <p>"Set rules based on monthly patterns (1st of month, last Friday, etc.)"</p>
+                            <p>"Set rules based on monthly patterns (1st Monday, last Friday, etc.)"</p>
                         </div>
                     </div>
                 </div>
@@ -305,7 +263,7 @@ pub fn ArtistRecurring() -> impl IntoView {
                                                         <div class="rule-info">
                                                             <h3>{rule_for_display.name.clone()}</h3>
                                                             <div class="rule-details">
-                                                                <span class="rule-pattern">{format_rule_pattern(&rule_for_display)}</span>
+                                                                <span class="rule-pattern">{describe(&rule_for_display.pattern)}</span>
                                                                 <span class="rule-action" class:blocked=move || rule_action == "blocked">
                                                                     {if rule_action == "blocked" { "🚫 Blocked" } else { "✅ Available" }}
                                                                 </span>
@@ -348,6 +306,10 @@ pub fn ArtistRecurring() -> impl IntoView {
 
                         <div class="modal-content">
                             <div class="add-rule-form">
+                                {move || form_error.get().map(|error| view! {
+                                    <div class="error-message">{error}</div>
+                                })}
+
                                 <div class="form-group">
                                     <label>"Rule Name"</label>
                                     <Input
@@ -361,20 +323,18 @@ pub fn ArtistRecurring() -> impl IntoView {
                                 </div>
 
                                 <div class="form-group">
-                                    <label>"Rule Type"</label>
+                                    <label>"Repeats"</label>
                                     <div class="radio-group">
-                                        <label class="radio-label">
-                                            <input type="radio" name="rule_type" value="weekdays" checked=move || rule_type.get() == "weekdays" on:change=move |_| rule_type.set("weekdays".to_string()) />
-                                            "Weekday Pattern"
-                                        </label>
-                                        <label class="radio-label">
-                                            <input type="radio" name="rule_type" value="dates" checked=move || rule_type.get() == "dates" on:change=move |_| rule_type.set("dates".to_string()) />
-                                            "Annual Dates"
-                                        </label>
-                                        <label class="radio-label">
-                                            <input type="radio" name="rule_type" value="monthly" checked=move || rule_type.get() == "monthly" on:change=move |_| rule_type.set("monthly".to_string()) />
-                                            "Monthly Pattern"
-                                        </label>
+                                        {FREQUENCIES.iter().map(|(value, label)| {
+                                            let value = value.to_string();
+                                            let checked_value = value.clone();
+                                            view! {
+                                                <label class="radio-label">
+                                                    <input type="radio" name="frequency" value=value.clone() checked=move || frequency.get() == checked_value on:change=move |_| frequency.set(value.clone()) />
+                                                    {*label}
+                                                </label>
+                                            }
+                                        }).collect::<Vec<_>>()}
                                     </div>
                                 </div>
 
@@ -393,47 +353,54 @@ pub fn ArtistRecurring() -> impl IntoView {
                                 </div>
 
                                 // Dynamic form based on rule type
-                                {move || match rule_type.get().as_str() {
-                                    "weekdays" => view! {
+                                {move || match frequency.get().as_str() {
+                                    "weekly" => view! {
                                         <div class="form-group">
                                             <label>"Select Days"</label>
                                             <div class="weekday-checkboxes">
-                                                {["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"].iter().map(|day| {
-                                                    let day_str = day.to_string();
-                                                    let day_clone = day_str.clone();
+                                                {WEEKDAYS.iter().enumerate().map(|(day, name)| {
+                                                    let day = day as i32;
                                                     view! {
                                                         <label class="checkbox-label">
                                                             <input
                                                                 type="checkbox"
+                                                                prop:checked=move || selected_weekdays.get().contains(&day)
                                                                 on:change=move |e| {
                                                                     let checked = event_target_checked(&e);
-                                                                    let mut current = selected_weekdays.get();
-                                                                    if checked {
-                                                                        if !current.contains(&day_clone) {
-                                                                            current.push(day_clone.clone());
+                                                                    selected_weekdays.update(|days| {
+                                                                        days.retain(|d| *d != day);
+                                                                        if checked {
+                                                                            days.push(day);
                                                                         }
-                                                                    } else {
-                                                                        current.retain(|d| d != &day_clone);
-                                                                    }
-                                                                    selected_weekdays.set(current);
+                                                                    });
                                                                 }
                                                             />
-                                                            {day_str}
+                                                            {*name}
                                                         </label>
                                                     }
                                                 }).collect::<Vec<_>>()}
                                             </div>
                                         </div>
+                                        <div class="form-group">
+                                            <label>"Every how many weeks"</label>
+                                            <input
+                                                type="number"
+                                                min="1"
+                                                max="52"
+                                                prop:value=move || interval_weeks.get()
+                                                on:input=move |e| interval_weeks.set(event_target_value(&e))
+                                            />
+                                        </div>
                                     }.into_any(),
-                                    "dates" => view! {
+                                    "yearly" => view! {
                                         <div class="form-group">
-                                            <label>"Annual Dates (comma separated)"</label>
+                                            <label>"Annual Dates (MM-DD, comma separated)"</label>
                                             <Input
-                                                placeholder="e.g., December 25, January 1"
-                                                value=selected_dates
+                                                placeholder="e.g., 12-25, 01-01"
+                                                value=annual_dates
                                                 on:input=move |e| {
                                                     let val = event_target_value(&e);
-                                                    selected_dates.set(val);
+                                                    annual_dates.set(val);
                                                 }
                                             />
                                         </div>
@@ -441,19 +408,66 @@ pub fn ArtistRecurring() -> impl IntoView {
                                     "monthly" => view! {
                                         <div class="form-group">
                                             <label>"Monthly Pattern"</label>
-                                            <Input
-                                                placeholder="e.g., 1st Monday, Last Friday, 15th of month"
-                                                value=monthly_pattern
-                                                on:input=move |e| {
-                                                    let val = event_target_value(&e);
-                                                    monthly_pattern.set(val);
-                                                }
-                                            />
+                                            <div class="monthly-pattern">
+                                                <select on:change=move |e| {
+                                                    if let Ok(week) = event_target_value(&e).parse() {
+                                                        week_of_month.set(week);
+                                                    }
+                                                }>
+                                                    {WEEKS_OF_MONTH.iter().map(|(week, label)| {
+                                                        let week = *week;
+                                                        view! {
+                                                            <option value=week.to_string() selected=move || week_of_month.get() == week>{*label}</option>
+                                                        }
+                                                    }).collect::<Vec<_>>()}
+                                                </select>
+                                                <select on:change=move |e| {
+                                                    if let Ok(day) = event_target_value(&e).parse() {
+                                                        monthly_weekday.set(day);
+                                                    }
+                                                }>
+                                                    {WEEKDAYS.iter().enumerate().map(|(day, name)| {
+                                                        let day = day as i32;
+                                                        view! {
+                                                            <option value=day.to_string() selected=move || monthly_weekday.get() == day>{*name}</option>
+                                                        }
+                                                    }).collect::<Vec<_>>()}
+                                                </select>
+                                                <span>"of the month"</span>
+                                            </div>
                                         </div>
                                     }.into_any(),
                                     _ => view! {}.into_any()
                                 }}
 
+                                <div class="form-group">
+                                    <label>"Applies From / Until (optional)"</label>
+                                    <div class="date-range">
+                                        <input
+                                            type="date"
+                                            prop:value=move || starts_on.get()
+                                            on:input=move |e| starts_on.set(event_target_value(&e))
+                                        />
+                                        <input
+                                            type="date"
+                                            prop:value=move || ends_on.get()
+                                            on:input=move |e| ends_on.set(event_target_value(&e))
+                                        />
+                                    </div>
+                                </div>
+
+                                <div class="form-group">
+                                    <label>"Skip These Dates (optional)"</label>
+                                    <Input
+                                        placeholder="e.g., 2025-07-04, 2025-11-27"
+                                        value=except_dates
+                                        on:input=move |e| {
+                                            let val = event_target_value(&e);
+                                            except_dates.set(val);
+                                        }
+                                    />
+                                </div>
+
                                 <div class="form-group">
                                     <label>"Start Time (optional)"</label>
                                     <Input
@@ -495,66 +509,6 @@ pub fn ArtistRecurring() -> impl IntoView {
     }
 }
 
-// Helper function to format rule pattern for human readability
-fn format_rule_pattern(rule: &RecurringRule) -> String {
-    match rule.rule_type.as_str() {
-        "weekdays" => {
-            if let Ok(days) = serde_json::from_str::<Vec<i32>>(&rule.pattern) {
-                let day_names: Vec<String> = days
-                    .iter()
-                    .map(|&day| {
-                        match day {
-                            0 => "Sunday",
-                            1 => "Monday",
-                            2 => "Tuesday",
-                            3 => "Wednesday",
-                            4 => "Thursday",
-                            5 => "Friday",
-                            6 => "Saturday",
-                            _ => "Unknown",
-                        }
-                        .to_string()
-                    })
-                    .collect();
-
-                if day_names.is_empty() {
-                    "No days selected".to_string()
-                } else if day_names.len() == 7 {
-                    "Every day".to_string()
-                } else if day_names == vec!["Monday", "Tuesday", "Wednesday", "Thursday", "Friday"]
-                {
-                    "Weekdays (Mon-Fri)".to_string()
-                } else if day_names == vec!["Saturday", "Sunday"]
-                    || day_names == vec!["Sunday", "Saturday"]
-                {
-                    "Weekends".to_string()
-                } else {
-                    day_names.join(", ")
-                }
-            } else {
-                "Invalid weekday pattern".to_string()
-            }
-        }
-        "dates" => {
-            let dates_str = rule.pattern.clone();
-            if dates_str.is_empty() {
-                "No dates specified".to_string()
-            } else {
-                format!("Annual dates: {}", dates_str)
-            }
-        }
-        "monthly" => {
-            let pattern = rule.pattern.clone();
-            if pattern.is_empty() {
-                "No monthly pattern specified".to_string()
-            } else {
-                format!("Monthly: {}", pattern)
-            }
-        }
-        _ => rule.pattern.clone(),
-    }
-}
-
 // Helper function to format rule time for human readability
 fn format_rule_time(rule: &RecurringRule) -> String {
     match (&rule.start_time, &rule.end_time) {
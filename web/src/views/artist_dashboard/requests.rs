@@ -1,6 +1,10 @@
 use leptos::prelude::*;
 use leptos_router::components::A;
 
+use crate::components::ExportPanel;
+use crate::utils::export::ARTIST_EXPORTS;
+use crate::views::artist_dashboard::hints::DashboardHints;
+
 #[component]
 pub fn ArtistRequests() -> impl IntoView {
     view! {
@@ -13,6 +17,10 @@ pub fn ArtistRequests() -> impl IntoView {
                 <p class="dashboard-subtitle">"Manage booking requests, sketches, and messages"</p>
             </div>
 
+            <DashboardHints page="requests" />
+
+            <ExportPanel kinds=ARTIST_EXPORTS />
+
             <div class="requests-tabs">
                 <div class="tab-buttons">
                     <button class="tab-button active">"Bookings (5)"</button>
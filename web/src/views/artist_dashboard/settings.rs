@@ -1,10 +1,37 @@
-use crate::db::entities::{BusinessHours, UpdateBusinessHours};
-use crate::server::{get_business_hours, update_business_hours};
-use crate::utils::auth::use_authenticated_artist_id;
+use crate::db::entities::{AppointmentSettings, BusinessHours, UpdateBusinessHours};
+use crate::server::payments::{
+    get_payment_account, get_payouts, refresh_payment_account, start_payout_onboarding,
+    update_payout_schedule, PAYOUT_INTERVALS, PAYOUT_WEEKDAYS,
+};
+use crate::server::{
+    get_appointment_settings, get_business_hours, update_appointment_settings,
+    update_business_hours,
+};
+use crate::server_portfolio::{
+    delete_uploaded_image, get_my_uploaded_images, reorder_uploaded_images,
+    update_uploaded_image_caption,
+};
+use crate::utils::appointments::{minutes_label, BUFFER_LENGTHS, SESSION_LENGTHS, SLOT_INTERVALS};
+use crate::utils::auth::{get_auth_token, get_authenticated_user, use_authenticated_artist_id};
+use crate::views::artist_dashboard::auto_response::AutoReplySettings;
+use crate::views::artist_dashboard::bio::BioSettings;
+use crate::views::artist_dashboard::documents::DocumentSettings;
+use crate::views::artist_dashboard::embed::BookingWidgetSettings;
+use crate::views::artist_dashboard::flash::FlashSettings;
+use crate::views::artist_dashboard::guest_spots::GuestSpotSettings;
+use crate::views::artist_dashboard::hints::DashboardHints;
+use crate::views::artist_dashboard::licenses::LicenseSettings;
+use crate::views::artist_dashboard::pricing::PricingSettings;
+use crate::views::artist_dashboard::profile_content::ProfileContentSettings;
+use crate::views::artist_dashboard::profile_questions::ProfileQuestionSettings;
+use crate::views::artist_dashboard::team::TeamSettings;
+use crate::views::artist_dashboard::translation::MessageTranslationSettings;
 use crate::utils::timezone::convert_to_12_hour_format;
 use leptos::ev::*;
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_router::components::A;
+use leptos_router::hooks::use_query_map;
 use thaw::*;
 
 #[component]
@@ -12,10 +39,7 @@ pub fn ArtistSettings() -> impl IntoView {
     // Get authenticated artist ID from JWT token
     let artist_id = use_authenticated_artist_id();
 
-    let auto_reply = RwSignal::new(true);
     let availability = RwSignal::new(true);
-    let base_price = RwSignal::new("150.0".to_string());
-    let hourly_rate = RwSignal::new("200.0".to_string());
 
     // Business hours state - initialize with default values
     let business_hours = RwSignal::new(vec![
@@ -92,7 +116,23 @@ pub fn ArtistSettings() -> impl IntoView {
         }
     });
 
-    // Save business hours action
+    // How the hours are split into appointments
+    let appointment_settings = RwSignal::new(AppointmentSettings {
+        session_minutes: 60,
+        slot_minutes: 60,
+        buffer_minutes: 0,
+    });
+    Effect::new(move |_| {
+        if let Some(id) = artist_id.get() {
+            spawn_local(async move {
+                if let Ok(settings) = get_appointment_settings(id).await {
+                    appointment_settings.set(settings);
+                }
+            });
+        }
+    });
+
+    // Save business hours action, with the appointment settings
     let save_hours_action = Action::new(move |_: &()| async move {
         if let Some(id) = artist_id.get() {
             let hours_to_save = business_hours
@@ -119,7 +159,9 @@ pub fn ArtistSettings() -> impl IntoView {
                 })
                 .collect::<Vec<_>>();
 
-            update_business_hours(hours_to_save).await
+            let token = get_auth_token().unwrap_or_default();
+            update_business_hours(hours_to_save, token.clone()).await?;
+            update_appointment_settings(appointment_settings.get_untracked(), token).await
         } else {
             Err(ServerFnError::new(
                 "No authenticated artist found".to_string(),
@@ -127,6 +169,169 @@ pub fn ArtistSettings() -> impl IntoView {
         }
     });
 
+    // Portfolio uploads post a plain form, which carries the token itself
+    let upload_token = RwSignal::new(String::new());
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            upload_token.set(token);
+        }
+    });
+
+    let query = use_query_map();
+    let upload_notice = move || {
+        let query = query.get();
+        if let Some(error) = query.get("portfolio_error") {
+            Some(("error-message", format!("Upload failed: {}", error)))
+        } else if query.get("portfolio").as_deref() == Some("uploaded") {
+            Some(("success-message", "Image added to your portfolio".to_string()))
+        } else {
+            None
+        }
+    };
+
+    let portfolio_version = RwSignal::new(0u32);
+    let portfolio_error = RwSignal::new(None::<String>);
+    let portfolio_resource = Resource::new(
+        move || (artist_id.get(), portfolio_version.get()),
+        move |(id_opt, _)| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_my_uploaded_images(token).await.unwrap_or_default(),
+                _ => vec![],
+            }
+        },
+    );
+
+    let on_portfolio_result = move |result: Result<(), ServerFnError>| match result {
+        Ok(()) => {
+            portfolio_error.set(None);
+            portfolio_version.update(|v| *v += 1);
+        }
+        Err(e) => portfolio_error.set(Some(e.to_string())),
+    };
+
+    let delete_image = move |image_id: i64| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_portfolio_result(delete_uploaded_image(token, image_id).await);
+        });
+    };
+
+    let save_caption = move |image_id: i64, caption: String| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_portfolio_result(update_uploaded_image_caption(token, image_id, Some(caption)).await);
+        });
+    };
+
+    // Swaps the image with its neighbour `offset` places away
+    let move_image = move |index: usize, offset: isize| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let mut ids: Vec<i64> = portfolio_resource
+            .get_untracked()
+            .unwrap_or_default()
+            .iter()
+            .map(|image| image.id)
+            .collect();
+        let Some(target) = index.checked_add_signed(offset).filter(|t| *t < ids.len()) else {
+            return;
+        };
+        ids.swap(index, target);
+        spawn_local(async move {
+            on_portfolio_result(reorder_uploaded_images(token, ids).await);
+        });
+    };
+
+    // Assistants see the settings they were given, but not the team itself
+    let is_owner = RwSignal::new(false);
+    Effect::new(move |_| {
+        let user_type = get_authenticated_user().map(|(_, user_type)| user_type);
+        is_owner.set(user_type.as_deref() == Some("artist"));
+    });
+
+    let payouts_version = RwSignal::new(0u32);
+    let payouts_error = RwSignal::new(None::<String>);
+    let payout_country = RwSignal::new("US".to_string());
+    let payout_interval = RwSignal::new("daily".to_string());
+    let payout_anchor = RwSignal::new(String::new());
+    let connecting = RwSignal::new(false);
+
+    let payout_account_resource = Resource::new(
+        move || (artist_id.get(), payouts_version.get()),
+        move |(id_opt, _)| async move {
+            let (Some(_), Some(token)) = (id_opt, get_auth_token()) else {
+                return None;
+            };
+            // Back from onboarding: check capabilities rather than wait for the webhook
+            let account = if query.get_untracked().get("payouts").as_deref() == Some("returned") {
+                refresh_payment_account(token).await
+            } else {
+                get_payment_account(token).await
+            };
+            account.ok().flatten()
+        },
+    );
+    let payouts_resource = Resource::new(
+        move || (artist_id.get(), payouts_version.get()),
+        move |(id_opt, _)| async move {
+            match (id_opt, get_auth_token()) {
+                (Some(_), Some(token)) => get_payouts(token).await.unwrap_or_default(),
+                _ => vec![],
+            }
+        },
+    );
+
+    Effect::new(move |_| {
+        if let Some(Some(account)) = payout_account_resource.get() {
+            payout_interval.set(account.payout_interval);
+            payout_anchor.set(account.payout_anchor.unwrap_or_default());
+        }
+    });
+
+    let connect_payouts = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let country = payout_country.get_untracked();
+        connecting.set(true);
+        payouts_error.set(None);
+        spawn_local(async move {
+            match start_payout_onboarding(token, country).await {
+                Ok(onboarding_url) => {
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.location().set_href(&onboarding_url);
+                    }
+                }
+                Err(e) => {
+                    payouts_error.set(Some(e.to_string()));
+                    connecting.set(false);
+                }
+            }
+        });
+    };
+
+    let save_payout_schedule = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let interval = payout_interval.get_untracked();
+        let anchor = Some(payout_anchor.get_untracked()).filter(|a| !a.is_empty());
+        spawn_local(async move {
+            match update_payout_schedule(token, interval, anchor).await {
+                Ok(_) => {
+                    payouts_error.set(None);
+                    payouts_version.update(|v| *v += 1);
+                }
+                Err(e) => payouts_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
     view! {
         <div class="artist-dashboard-container">
             <div class="dashboard-header">
@@ -137,7 +342,13 @@ pub fn ArtistSettings() -> impl IntoView {
                 <p class="dashboard-subtitle">"Configure your preferences and pricing"</p>
             </div>
 
+            <DashboardHints page="settings" />
+
             <div class="settings-grid">
+                <BioSettings />
+
+                <ProfileContentSettings />
+
                 <div class="settings-card">
                     <h2>"Availability Settings"</h2>
 
@@ -150,49 +361,25 @@ pub fn ArtistSettings() -> impl IntoView {
                         </label>
                         <p class="setting-description">"Turn off to stop receiving new booking requests"</p>
                     </div>
-
-                    <div class="setting-group">
-                        <label class="setting-label">
-                            <Switch
-                                checked=auto_reply
-                            />
-                            <span>"Auto-Reply to Messages"</span>
-                        </label>
-                        <p class="setting-description">"Automatically send a response to new client messages"</p>
-                    </div>
                 </div>
 
-                <div class="settings-card">
-                    <h2>"Pricing Configuration"</h2>
+                <PricingSettings />
 
-                    <div class="setting-group">
-                        <label class="setting-label">"Base Price (Small Tattoos)"</label>
-                        <div class="price-input">
-                            <span class="currency">"$"</span>
-                            <Input
-                                value=base_price
-                                placeholder="150"
-                            />
-                        </div>
-                        <p class="setting-description">"Starting price for small tattoos (2-3 inches)"</p>
-                    </div>
+                <AutoReplySettings />
 
-                    <div class="setting-group">
-                        <label class="setting-label">"Hourly Rate"</label>
-                        <div class="price-input">
-                            <span class="currency">"$"</span>
-                            <Input
-                                value=hourly_rate
-                                placeholder="200"
-                            />
-                        </div>
-                        <p class="setting-description">"Rate per hour for larger, custom pieces"</p>
-                    </div>
+                <DocumentSettings />
 
-                    <div class="setting-actions">
-                        <button class="btn btn-primary">"Save Pricing"</button>
-                    </div>
-                </div>
+                <BookingWidgetSettings />
+
+                <MessageTranslationSettings />
+
+                <FlashSettings />
+
+                <GuestSpotSettings />
+
+                <LicenseSettings />
+
+                <ProfileQuestionSettings />
 
                 <div class="settings-card">
                     <h2>"Business Hours"</h2>
@@ -244,6 +431,57 @@ pub fn ArtistSettings() -> impl IntoView {
                         }).collect_view()}
                     </div>
 
+                    <div class="appointment-settings">
+                        <label class="setting-label">
+                            <span>"Session length"</span>
+                            <select
+                                prop:value=move || appointment_settings.get().session_minutes.to_string()
+                                on:change=move |ev| {
+                                    if let Ok(minutes) = event_target_value(&ev).parse() {
+                                        appointment_settings.update(|s| s.session_minutes = minutes);
+                                    }
+                                }
+                            >
+                                {SESSION_LENGTHS.iter().map(|minutes| view! {
+                                    <option value=minutes.to_string()>{minutes_label(*minutes)}</option>
+                                }).collect_view()}
+                            </select>
+                        </label>
+                        <label class="setting-label">
+                            <span>"Start times every"</span>
+                            <select
+                                prop:value=move || appointment_settings.get().slot_minutes.to_string()
+                                on:change=move |ev| {
+                                    if let Ok(minutes) = event_target_value(&ev).parse() {
+                                        appointment_settings.update(|s| s.slot_minutes = minutes);
+                                    }
+                                }
+                            >
+                                {SLOT_INTERVALS.iter().map(|minutes| view! {
+                                    <option value=minutes.to_string()>{minutes_label(*minutes)}</option>
+                                }).collect_view()}
+                            </select>
+                        </label>
+                        <label class="setting-label">
+                            <span>"Buffer between appointments"</span>
+                            <select
+                                prop:value=move || appointment_settings.get().buffer_minutes.to_string()
+                                on:change=move |ev| {
+                                    if let Ok(minutes) = event_target_value(&ev).parse() {
+                                        appointment_settings.update(|s| s.buffer_minutes = minutes);
+                                    }
+                                }
+                            >
+                                {BUFFER_LENGTHS.iter().map(|minutes| view! {
+                                    <option value=minutes.to_string()>{minutes_label(*minutes)}</option>
+                                }).collect_view()}
+                            </select>
+                        </label>
+                        <p class="setting-description">
+                            "Larger tattoos are offered longer slots: two sessions for up to 6\", three up to 10\" and four beyond."
+                        </p>
+                    </div>
+
                     <div class="setting-actions">
                         <button
                             class="btn btn-primary"
@@ -275,6 +513,229 @@ pub fn ArtistSettings() -> impl IntoView {
                     }}
                 </div>
 
+                <div class="settings-card portfolio-settings">
+                    <h2>"Portfolio"</h2>
+                    <p class="setting-description">
+                        "Upload your own work to show alongside your Instagram posts. JPEG, PNG, WebP or HEIC, up to 25 MB."
+                    </p>
+
+                    {move || upload_notice().map(|(class, message)| view! {
+                        <div class=class>{message}</div>
+                    })}
+                    {move || portfolio_error.get().map(|error| view! {
+                        <div class="error-message">{error}</div>
+                    })}
+
+                    <form
+                        class="portfolio-upload-form"
+                        method="post"
+                        action="/api/artist/portfolio"
+                        enctype="multipart/form-data"
+                    >
+                        <input type="hidden" name="token" prop:value=move || upload_token.get() />
+                        <input type="file" name="file" accept="image/jpeg,image/png,image/webp,image/heic,.heic" required />
+                        <input type="text" name="caption" placeholder="Caption (optional)" maxlength="500" />
+                        <button type="submit" class="btn btn-primary">"Upload Image"</button>
+                    </form>
+
+                    <Suspense fallback=|| ()>
+                        {move || portfolio_resource.get().map(|images| {
+                            let count = images.len();
+                            view! {
+                                <div class="portfolio-grid">
+                                    {images.into_iter().enumerate().map(|(index, image)| {
+                                        let image_id = image.id;
+                                        let caption = RwSignal::new(image.caption.clone().unwrap_or_default());
+                                        view! {
+                                            <div class="portfolio-item">
+                                                <a href=image.image_url.clone() target="_blank">
+                                                    <img src=image.thumbnail_url.clone() alt=image.caption.clone().unwrap_or_default() loading="lazy" />
+                                                </a>
+                                                <input
+                                                    type="text"
+                                                    placeholder="Caption"
+                                                    maxlength="500"
+                                                    prop:value=move || caption.get()
+                                                    on:input=move |ev| caption.set(event_target_value(&ev))
+                                                    on:change=move |_| save_caption(image_id, caption.get_untracked())
+                                                />
+                                                <div class="portfolio-item-actions">
+                                                    <button
+                                                        class="btn btn-secondary"
+                                                        disabled=index == 0
+                                                        on:click=move |_| move_image(index, -1)
+                                                    >
+                                                        "←"
+                                                    </button>
+                                                    <button
+                                                        class="btn btn-secondary"
+                                                        disabled=index + 1 == count
+                                                        on:click=move |_| move_image(index, 1)
+                                                    >
+                                                        "→"
+                                                    </button>
+                                                    <button class="btn btn-outline-danger" on:click=move |_| delete_image(image_id)>
+                                                        "Delete"
+                                                    </button>
+                                                </div>
+                                            </div>
+                                        }
+                                    }).collect_view()}
+                                </div>
+                            }
+                        })}
+                    </Suspense>
+                </div>
+
+                <div class="settings-card payout-settings">
+                    <h2>"Payouts"</h2>
+                    <p class="setting-description">
+                        "Connect a payout account to receive deposits and payments from clients."
+                    </p>
+
+                    {move || payouts_error.get().map(|error| view! {
+                        <div class="error-message">{error}</div>
+                    })}
+
+                    <Suspense fallback=|| ()>
+                        {move || payout_account_resource.get().map(|account| match account {
+                            None => view! {
+                                <div class="setting-group">
+                                    <label class="setting-label">"Country"</label>
+                                    <input
+                                        type="text"
+                                        class="payout-country"
+                                        maxlength="2"
+                                        placeholder="US"
+                                        prop:value=move || payout_country.get()
+                                        on:input=move |ev| payout_country.set(event_target_value(&ev))
+                                    />
+                                    <p class="setting-description">"Two-letter code of the country your bank account is in"</p>
+                                </div>
+                                <div class="setting-actions">
+                                    <button
+                                        class="btn btn-primary"
+                                        disabled=move || connecting.get()
+                                        on:click=connect_payouts
+                                    >
+                                        {move || if connecting.get() { "Redirecting..." } else { "Set Up Payouts" }}
+                                    </button>
+                                </div>
+                            }
+                            .into_any(),
+                            Some(account) => {
+                                let (status_class, status_text) = if account.payouts_enabled {
+                                    ("payout-status active", "Payouts enabled")
+                                } else if account.details_submitted {
+                                    ("payout-status pending", "Under review")
+                                } else {
+                                    ("payout-status pending", "Setup incomplete")
+                                };
+                                let payouts_enabled = account.payouts_enabled;
+                                let requirements = account.requirements_due.clone();
+                                view! {
+                                    <div class="payout-account">
+                                        <span class=status_class>{status_text}</span>
+                                        <span class="payout-account-details">
+                                            {format!("{} · {}", account.country, account.default_currency)}
+                                        </span>
+                                    </div>
+
+                                    <Show when=move || !payouts_enabled>
+                                        <div class="setting-actions">
+                                            <button
+                                                class="btn btn-primary"
+                                                disabled=move || connecting.get()
+                                                on:click=connect_payouts
+                                            >
+                                                "Continue Setup"
+                                            </button>
+                                        </div>
+                                    </Show>
+                                    {(!requirements.is_empty()).then(|| view! {
+                                        <p class="setting-description">
+                                            {format!("Still needed: {}", requirements.join(", ").replace('_', " "))}
+                                        </p>
+                                    })}
+
+                                    <div class="setting-group payout-schedule">
+                                        <label class="setting-label">"Payout Schedule"</label>
+                                        <select
+                                            prop:value=move || payout_interval.get()
+                                            on:change=move |ev| {
+                                                payout_interval.set(event_target_value(&ev));
+                                                payout_anchor.set(String::new());
+                                            }
+                                        >
+                                            {PAYOUT_INTERVALS.iter().map(|interval| view! {
+                                                <option value=*interval>{*interval}</option>
+                                            }).collect_view()}
+                                        </select>
+                                        <Show when=move || payout_interval.get() == "weekly">
+                                            <select
+                                                prop:value=move || payout_anchor.get()
+                                                on:change=move |ev| payout_anchor.set(event_target_value(&ev))
+                                            >
+                                                <option value="">"Day of the week"</option>
+                                                {PAYOUT_WEEKDAYS.iter().map(|day| view! {
+                                                    <option value=*day>{*day}</option>
+                                                }).collect_view()}
+                                            </select>
+                                        </Show>
+                                        <Show when=move || payout_interval.get() == "monthly">
+                                            <input
+                                                type="number"
+                                                min="1"
+                                                max="31"
+                                                placeholder="Day of the month"
+                                                prop:value=move || payout_anchor.get()
+                                                on:input=move |ev| payout_anchor.set(event_target_value(&ev))
+                                            />
+                                        </Show>
+                                        <button class="btn btn-secondary" on:click=save_payout_schedule>
+                                            "Save Schedule"
+                                        </button>
+                                    </div>
+                                }
+                                .into_any()
+                            }
+                        })}
+                    </Suspense>
+
+                    <Suspense fallback=|| ()>
+                        {move || payouts_resource.get().map(|payouts| {
+                            (!payouts.is_empty()).then(|| view! {
+                                <table class="payouts-table">
+                                    <thead>
+                                        <tr>
+                                            <th>"Created"</th>
+                                            <th>"Amount"</th>
+                                            <th>"Status"</th>
+                                            <th>"Arrives"</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {payouts.into_iter().map(|payout| view! {
+                                            <tr title=payout.failure_message.clone().unwrap_or_default()>
+                                                <td>{payout.created_at}</td>
+                                                <td>{format!("{:.2} {}", payout.amount, payout.currency)}</td>
+                                                <td class=format!("payout-status {}", payout.status)>
+                                                    {payout.status.replace('_', " ")}
+                                                </td>
+                                                <td>{payout.arrival_date.unwrap_or_default()}</td>
+                                            </tr>
+                                        }).collect_view()}
+                                    </tbody>
+                                </table>
+                            })
+                        })}
+                    </Suspense>
+                </div>
+
+                <Show when=move || is_owner.get()>
+                    <TeamSettings />
+                </Show>
+
                 <div class="settings-card">
                     <h2>"Profile Settings"</h2>
 
@@ -283,10 +744,8 @@ pub fn ArtistSettings() -> impl IntoView {
                         <h3>"Advanced Settings Coming Soon"</h3>
                         <p>"Additional configuration options in development:"</p>
                         <ul class="feature-list">
-                            <li>"Portfolio management"</li>
                             <li>"Style specialization tags"</li>
                             <li>"Notification preferences"</li>
-                            <li>"Payment method setup"</li>
                             <li>"Social media integration"</li>
                         </ul>
                     </div>
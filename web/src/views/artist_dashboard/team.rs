@@ -0,0 +1,210 @@
+use crate::db::entities::TeamMember;
+use crate::server_team::{
+    get_team_members, invite_team_member, remove_team_member, update_team_member_permissions,
+    TEAM_PERMISSIONS,
+};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+fn permission_label(permission: &str) -> &'static str {
+    match permission {
+        "messages" => "Messages",
+        "calendar" => "Calendar",
+        "bookings" => "Bookings",
+        "settings" => "Settings",
+        _ => "Other",
+    }
+}
+
+/// Settings card where an artist invites assistants and sets what each may do
+#[component]
+pub fn TeamSettings() -> impl IntoView {
+    // Bumped whenever the team changes
+    let team_version = RwSignal::new(0u32);
+    let team_error = RwSignal::new(None::<String>);
+    let invite_email = RwSignal::new(String::new());
+    let invite_permissions = RwSignal::new(vec!["messages".to_string(), "calendar".to_string()]);
+    let invite_url = RwSignal::new(None::<String>);
+
+    let members_resource = Resource::new(
+        move || team_version.get(),
+        move |_| async move {
+            match get_auth_token() {
+                Some(token) => get_team_members(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+
+    let on_result = move |result: Result<(), ServerFnError>| match result {
+        Ok(()) => {
+            team_error.set(None);
+            team_version.update(|v| *v += 1);
+        }
+        Err(e) => team_error.set(Some(e.to_string())),
+    };
+
+    let send_invite = move |_| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let email = invite_email.get_untracked();
+        let permissions = invite_permissions.get_untracked();
+        spawn_local(async move {
+            match invite_team_member(token, email, permissions).await {
+                Ok(invitation) => {
+                    invite_email.set(String::new());
+                    invite_url.set(Some(invitation.invite_url));
+                    on_result(Ok(()));
+                }
+                Err(e) => on_result(Err(e)),
+            }
+        });
+    };
+
+    let save_permissions = move |member_id: i64, permissions: Vec<String>| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            let result = update_team_member_permissions(token, member_id, permissions).await;
+            on_result(result.map(|_| ()));
+        });
+    };
+
+    let remove_member = move |member_id: i64| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(remove_team_member(token, member_id).await.map(|_| ()));
+        });
+    };
+
+    view! {
+        <div class="settings-card team-settings">
+            <h2>"Team"</h2>
+            <p class="setting-description">
+                "Give an assistant access to parts of your account. They sign in with their own account; billing and payouts stay with you."
+            </p>
+
+            {move || team_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <div class="setting-group">
+                <label class="setting-label">"Invite by email"</label>
+                <input
+                    type="email"
+                    placeholder="assistant@example.com"
+                    prop:value=move || invite_email.get()
+                    on:input=move |ev| invite_email.set(event_target_value(&ev))
+                />
+                <div class="team-permissions">
+                    {TEAM_PERMISSIONS.iter().map(|permission| {
+                        let permission = permission.to_string();
+                        let checked_permission = permission.clone();
+                        view! {
+                            <label class="team-permission">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || invite_permissions.get().contains(&checked_permission)
+                                    on:change=move |ev| {
+                                        let checked = event_target_checked(&ev);
+                                        invite_permissions.update(|permissions| {
+                                            permissions.retain(|p| *p != permission);
+                                            if checked {
+                                                permissions.push(permission.clone());
+                                            }
+                                        });
+                                    }
+                                />
+                                {permission_label(&permission)}
+                            </label>
+                        }
+                    }).collect_view()}
+                </div>
+                <button class="btn btn-primary" on:click=send_invite>"Send Invite"</button>
+            </div>
+
+            {move || invite_url.get().map(|url| view! {
+                <div class="team-invite-link">
+                    <p class="setting-description">
+                        "Send this link to your assistant. It works once, for the invited email, within 7 days."
+                    </p>
+                    <input type="text" readonly prop:value=url />
+                </div>
+            })}
+
+            <Suspense fallback=|| ()>
+                {move || members_resource.get().map(|members| {
+                    (!members.is_empty()).then(|| view! {
+                        <ul class="team-members">
+                            {members.into_iter().map(|member| view! {
+                                <TeamMemberRow
+                                    member=member
+                                    on_save=save_permissions
+                                    on_remove=remove_member
+                                />
+                            }).collect_view()}
+                        </ul>
+                    })
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn TeamMemberRow(
+    member: TeamMember,
+    on_save: impl Fn(i64, Vec<String>) + Copy + Send + Sync + 'static,
+    on_remove: impl Fn(i64) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let member_id = member.id;
+    let permissions = RwSignal::new(member.permissions.clone());
+    let status_text = match member.status.as_str() {
+        "active" => "Active",
+        "expired" => "Invite expired",
+        _ => "Invited",
+    };
+
+    view! {
+        <li class="team-member">
+            <div class="team-member-details">
+                <strong>{member.name.clone().unwrap_or_else(|| member.email.clone())}</strong>
+                <span class="team-member-email">{member.email.clone()}</span>
+                <span class=format!("team-member-status {}", member.status)>{status_text}</span>
+            </div>
+            <div class="team-permissions">
+                {TEAM_PERMISSIONS.iter().map(|permission| {
+                    let permission = permission.to_string();
+                    let checked_permission = permission.clone();
+                    view! {
+                        <label class="team-permission">
+                            <input
+                                type="checkbox"
+                                prop:checked=move || permissions.get().contains(&checked_permission)
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    permissions.update(|permissions| {
+                                        permissions.retain(|p| *p != permission);
+                                        if checked {
+                                            permissions.push(permission.clone());
+                                        }
+                                    });
+                                    on_save(member_id, permissions.get_untracked());
+                                }
+                            />
+                            {permission_label(&permission)}
+                        </label>
+                    }
+                }).collect_view()}
+            </div>
+            <button class="btn btn-outline-danger" on:click=move |_| on_remove(member_id)>
+                {if member.status == "active" { "Remove" } else { "Cancel Invite" }}
+            </button>
+        </li>
+    }
+}
@@ -0,0 +1,65 @@
+use crate::server_translation::{get_message_translation, update_message_translation};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+
+/// Settings card for translating booking messages between the artist and
+/// clients who write in another language
+#[component]
+pub fn MessageTranslationSettings() -> impl IntoView {
+    let enabled = RwSignal::new(false);
+    let translation_error = RwSignal::new(None::<String>);
+
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            spawn_local(async move {
+                match get_message_translation(token).await {
+                    Ok(on) => enabled.set(on),
+                    Err(e) => translation_error.set(Some(e.to_string())),
+                }
+            });
+        }
+    });
+
+    let toggle = move |ev| {
+        let Some(token) = get_auth_token() else {
+            return;
+        };
+        let on = event_target_checked(&ev);
+        enabled.set(on);
+        spawn_local(async move {
+            match update_message_translation(token, on).await {
+                Ok(()) => translation_error.set(None),
+                Err(e) => {
+                    enabled.set(!on);
+                    translation_error.set(Some(e.to_string()));
+                }
+            }
+        });
+    };
+
+    view! {
+        <div class="settings-card message-translation-settings">
+            <h2>"Message Translation"</h2>
+
+            {move || translation_error.get().map(|error| view! {
+                <div class="error-message">{error}</div>
+            })}
+
+            <div class="setting-group">
+                <label class="setting-label">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || enabled.get()
+                        on:change=toggle
+                    />
+                    <span>"Translate booking messages automatically"</span>
+                </label>
+                <p class="setting-description">
+                    "Messages from clients who write in another language, like Spanish or Portuguese, are shown to you in English, "
+                    "and your replies are sent to them in their language. Both of you can still see the original."
+                </p>
+            </div>
+        </div>
+    }
+}
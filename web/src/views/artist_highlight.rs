@@ -5,10 +5,18 @@ use crate::{
     components::{
         artist_masonry_gallery::{ArtistMasonryGallery, InstagramPost},
         loading::LoadingView,
-        ClientBookingModal, StyleTag,
+        ArtistQuestions, ClientBookingModal, DocumentBody, ExperienceBadge, ShareButton, StyleTag,
     },
+    db::entities::GuestSpot,
     server::{fetch_artist_data, fetch_artist_images_paginated},
+    server_guest_spots::get_artist_guest_spots,
+    server_licenses::get_artist_licenses,
+    server_portfolio::get_artist_albums,
+    server_profile::get_artist_profile_content,
+    server_response_time::get_artist_response_time,
+    server_tattoo_photos::get_artist_healed_work,
     utils::auth::is_authenticated,
+    utils::profile::{platform_label, policy_label},
 };
 
 #[component]
@@ -27,6 +35,7 @@ pub fn ArtistHighlight() -> impl IntoView {
 
     // Parse style IDs and page from query params
     let selected_styles = RwSignal::new(Vec::<i32>::new());
+    let selected_album = RwSignal::new(None::<i64>);
     let current_page = RwSignal::new(0);
     let per_page = 10;
 
@@ -62,24 +71,91 @@ pub fn ArtistHighlight() -> impl IntoView {
         },
     );
 
+    let response_time = Resource::new(
+        move || artist_id.get(),
+        move |id| async move {
+            if id != 0 {
+                get_artist_response_time(id).await.ok().and_then(|stats| stats.label)
+            } else {
+                None
+            }
+        },
+    );
+
+    let healed_work = Resource::new(
+        move || artist_id.get(),
+        move |id| async move {
+            if id != 0 {
+                get_artist_healed_work(id).await.unwrap_or_default()
+            } else {
+                vec![]
+            }
+        },
+    );
+
+    let profile_content = Resource::new(
+        move || artist_id.get(),
+        move |id| async move {
+            if id != 0 {
+                get_artist_profile_content(id).await.unwrap_or_default()
+            } else {
+                Default::default()
+            }
+        },
+    );
+
+    let licenses = Resource::new(
+        move || artist_id.get(),
+        move |id| async move {
+            if id != 0 {
+                get_artist_licenses(id).await.unwrap_or_default()
+            } else {
+                vec![]
+            }
+        },
+    );
+
+    let guest_spots = Resource::new(
+        move || artist_id.get(),
+        move |id| async move {
+            if id != 0 {
+                get_artist_guest_spots(id).await.unwrap_or_default()
+            } else {
+                vec![]
+            }
+        },
+    );
+
+    let albums = Resource::new(
+        move || artist_id.get(),
+        move |id| async move {
+            if id != 0 {
+                get_artist_albums(id as i64).await.unwrap_or_default()
+            } else {
+                vec![]
+            }
+        },
+    );
+
     // Paginated images resource
     let paginated_images = Resource::new(
         move || {
             (
                 artist_id.get(),
                 selected_styles.get(),
+                selected_album.get(),
                 current_page.get(),
                 auth_token.get(),
             )
         },
-        move |(id, styles, page, token)| async move {
+        move |(id, styles, album, page, token)| async move {
             if id > 0 {
                 let style_filter = if styles.is_empty() {
                     None
                 } else {
                     Some(styles)
                 };
-                fetch_artist_images_paginated(id, style_filter, page, per_page, token)
+                fetch_artist_images_paginated(id, style_filter, album, page, per_page, token)
                     .await
                     .ok()
             } else {
@@ -91,6 +167,8 @@ pub fn ArtistHighlight() -> impl IntoView {
     // Modal state for booking
     let show_booking_modal = RwSignal::new(false);
     let booking_artist_id = RwSignal::new(None::<i32>);
+    // Set when booking a slot at one of the artist's guest spots
+    let booking_guest_spot = RwSignal::new(None::<GuestSpot>);
 
     // Update selected styles and page from query params
     Effect::new(move |_| {
@@ -107,6 +185,13 @@ pub fn ArtistHighlight() -> impl IntoView {
             selected_styles.set(Vec::new());
         }
 
+        // Parse album
+        selected_album.set(
+            query_map
+                .get("album")
+                .and_then(|album| album.parse::<i64>().ok()),
+        );
+
         // Parse page
         if let Some(page_str) = query_map.get("page") {
             if let Ok(page) = page_str.parse::<i32>() {
@@ -137,6 +222,28 @@ pub fn ArtistHighlight() -> impl IntoView {
         }
     });
 
+    // Open a guest spot's booking straight away (after signing in to book it)
+    Effect::new(move |_| {
+        let Some(spot_id) = query
+            .read()
+            .get("guest_spot")
+            .and_then(|id| id.parse::<i32>().ok())
+        else {
+            return;
+        };
+        let Some(spot) = guest_spots
+            .get()
+            .and_then(|spots| spots.into_iter().find(|spot| spot.id == spot_id))
+        else {
+            return;
+        };
+        if is_authenticated() {
+            booking_guest_spot.set(Some(spot));
+            booking_artist_id.set(Some(artist_id.get()));
+            show_booking_modal.set(true);
+        }
+    });
+
     view! {
         <div class="artist-highlight-container">
             <Suspense fallback=move || view! {
@@ -149,6 +256,8 @@ pub fn ArtistHighlight() -> impl IntoView {
                         artist_data.get().map(|data| {
                         data.map(|artist_data| {
                             let artist_styles_for_filter = artist_data.styles.clone();
+                            let profile_artist_id = artist_data.artist.id;
+                            let questions = artist_data.questions;
 
                             let artist_name = artist_data.artist.name.unwrap_or_else(|| "Unknown Artist".to_string());
                             let shop_name = artist_data.location.name.unwrap_or_else(|| "Unknown Shop".to_string());
@@ -164,12 +273,18 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                     <h1 class="artist-highlight-artist-name">
                                                         {artist_name.clone()}
                                                     </h1>
+                                                    <ExperienceBadge tier=artist_data.artist.experience_tier.clone() />
                                                     <div class="artist-highlight-shop-info">
                                                         <a href={format!("/shop/{}", artist_data.location.id)}
                                                            class="artist-highlight-shop-link">
                                                             {format!("🏪 {} • {}, {}", shop_name, city, state)}
                                                         </a>
                                                     </div>
+                                                    <Suspense fallback=|| ()>
+                                                        {move || response_time.get().flatten().map(|label| view! {
+                                                            <span class="response-time-badge">"⚡ " {label}</span>
+                                                        })}
+                                                    </Suspense>
                                                 </div>
 
                                                 <div class="artist-highlight-buttons-container">
@@ -180,6 +295,7 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                                let current_artist_id = artist_id.get();
                                                                // Check authentication first
                                                                if is_authenticated() {
+                                                                   booking_guest_spot.set(None);
                                                                    booking_artist_id.set(Some(current_artist_id));
                                                                    show_booking_modal.set(true);
                                                                } else {
@@ -194,14 +310,29 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                         "📅 Book Appointment"
                                                     </button>
 
-                                                    {artist_data.artist.social_links.and_then(|links| {
-                                                        (!links.is_empty()).then(|| view! {
-                                                            <a href={links} target="_blank"
-                                                               class="artist-highlight-social-button">
-                                                                "📱 Instagram"
-                                                            </a>
-                                                        })
-                                                    })}
+                                                    // The artist's own links, or the scraped one until they add some
+                                                    <Suspense fallback=|| ()>
+                                                        {
+                                                            let scraped_link = artist_data.artist.social_links.clone().filter(|links| !links.is_empty());
+                                                            move || profile_content.get().map(|content| {
+                                                                if content.social_links.is_empty() {
+                                                                    scraped_link.clone().map(|links| view! {
+                                                                        <a href={links} target="_blank"
+                                                                           class="artist-highlight-social-button">
+                                                                            "📱 Instagram"
+                                                                        </a>
+                                                                    }).into_any()
+                                                                } else {
+                                                                    content.social_links.into_iter().map(|link| view! {
+                                                                        <a href=link.url target="_blank" rel="noopener noreferrer"
+                                                                           class="artist-highlight-social-button">
+                                                                            {platform_label(&link.platform)}
+                                                                        </a>
+                                                                    }).collect_view().into_any()
+                                                                }
+                                                            })
+                                                        }
+                                                    </Suspense>
 
                                                     {artist_data.artist.email.and_then(|email| {
                                                         (!email.is_empty()).then(|| view! {
@@ -220,6 +351,12 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                             </a>
                                                         })
                                                     })}
+
+                                                    <ShareButton
+                                                        target_type="artist"
+                                                        target=Signal::derive(move || artist_id.get().to_string())
+                                                        class="artist-highlight-share"
+                                                    />
                                                 </div>
                                             </div>
                                         </div>
@@ -286,13 +423,177 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                         </div>
                                                     }
                                                 })}
+
+                                                // Upcoming guest spots, each bookable on its own dates
+                                                <Suspense fallback=|| ()>
+                                                    {
+                                                        let navigate = navigate.clone();
+                                                        move || {
+                                                            let navigate = navigate.clone();
+                                                            guest_spots.get().filter(|spots| !spots.is_empty()).map(|spots| view! {
+                                                                <div class="artist-highlight-card artist-highlight-guest-spots">
+                                                                    <h3 class="artist-highlight-card-heading">"Guest Spots"</h3>
+                                                                    {spots.into_iter().map(|spot| {
+                                                                        let navigate = navigate.clone();
+                                                                        let place = [spot.city.clone(), spot.state.clone()]
+                                                                            .into_iter()
+                                                                            .flatten()
+                                                                            .collect::<Vec<_>>()
+                                                                            .join(", ");
+                                                                        view! {
+                                                                            <div class="artist-highlight-guest-spot">
+                                                                                <a href={format!("/shop/{}", spot.location_id)} class="artist-highlight-guest-spot-shop">
+                                                                                    {spot.shop_name.clone().unwrap_or_else(|| "Guest shop".to_string())}
+                                                                                </a>
+                                                                                <span class="artist-highlight-guest-spot-place">{place}</span>
+                                                                                <span class="artist-highlight-guest-spot-dates">
+                                                                                    {format!("{} to {}", spot.start_date, spot.end_date)}
+                                                                                </span>
+                                                                                {spot.host_artist_name.clone().map(|host| view! {
+                                                                                    <span class="artist-highlight-guest-spot-host">{format!("Hosted by {}", host)}</span>
+                                                                                })}
+                                                                                {spot.note.clone().map(|note| view! {
+                                                                                    <p class="artist-highlight-guest-spot-note">{note}</p>
+                                                                                })}
+                                                                                <button
+                                                                                    class="artist-highlight-guest-spot-book"
+                                                                                    on:click=move |_| {
+                                                                                        let current_artist_id = artist_id.get();
+                                                                                        if is_authenticated() {
+                                                                                            booking_guest_spot.set(Some(spot.clone()));
+                                                                                            booking_artist_id.set(Some(current_artist_id));
+                                                                                            show_booking_modal.set(true);
+                                                                                        } else {
+                                                                                            let current_url = format!("/artist/{}?guest_spot={}", current_artist_id, spot.id);
+                                                                                            let login_url = format!("/login?return_url={}", urlencoding::encode(&current_url));
+                                                                                            navigate(&login_url, Default::default());
+                                                                                        }
+                                                                                    }
+                                                                                >
+                                                                                    "Book a Guest Spot Slot"
+                                                                                </button>
+                                                                            </div>
+                                                                        }
+                                                                    }).collect_view()}
+                                                                </div>
+                                                            })
+                                                        }
+                                                    }
+                                                </Suspense>
+
+                                                // Verified licenses, in states that require them to be shown
+                                                <Suspense fallback=|| ()>
+                                                    {move || licenses.get().filter(|licenses| !licenses.is_empty()).map(|licenses| view! {
+                                                        <div class="artist-highlight-card artist-highlight-licenses">
+                                                            <h3 class="artist-highlight-card-heading">"Licenses"</h3>
+                                                            {licenses.into_iter().map(|license| view! {
+                                                                <p class="artist-highlight-license">
+                                                                    <span class="artist-highlight-license-state">{license.issuing_state}</span>
+                                                                    {format!(" #{}", license.license_number)}
+                                                                    {license.expires_on.map(|date| view! {
+                                                                        <span class="artist-highlight-license-expiry">{format!("Expires {}", date)}</span>
+                                                                    })}
+                                                                </p>
+                                                            }).collect_view()}
+                                                        </div>
+                                                    })}
+                                                </Suspense>
                                             </div>
                                         </div>
 
+                                        // Bio, studio policies and FAQ the artist wrote
+                                        <Suspense fallback=|| ()>
+                                            {move || profile_content.get()
+                                                .filter(|content| content.bio.is_some() || !content.policies.is_empty() || !content.faqs.is_empty())
+                                                .map(|content| view! {
+                                                    <div class="artist-highlight-portfolio-card artist-highlight-about">
+                                                        <h2 class="artist-highlight-portfolio-heading">"About"</h2>
+                                                        {content.bio.map(|bio| view! { <DocumentBody text=bio /> })}
+
+                                                        {(!content.policies.is_empty()).then(|| view! {
+                                                            <div class="artist-highlight-policies">
+                                                                <h3 class="artist-highlight-card-heading">"Studio Policies"</h3>
+                                                                {content.policies.into_iter().map(|policy| view! {
+                                                                    <div class="artist-highlight-policy">
+                                                                        <h4>{policy_label(&policy.kind)}</h4>
+                                                                        <DocumentBody text=policy.body />
+                                                                    </div>
+                                                                }).collect_view()}
+                                                            </div>
+                                                        })}
+
+                                                        {(!content.faqs.is_empty()).then(|| view! {
+                                                            <div class="artist-highlight-faqs">
+                                                                <h3 class="artist-highlight-card-heading">"FAQ"</h3>
+                                                                {content.faqs.into_iter().map(|faq| view! {
+                                                                    <details class="artist-highlight-faq">
+                                                                        <summary>{faq.question}</summary>
+                                                                        <p>{faq.answer}</p>
+                                                                    </details>
+                                                                }).collect_view()}
+                                                            </div>
+                                                        })}
+                                                    </div>
+                                                })}
+                                        </Suspense>
+
                                         // Portfolio section with server-side filtering
                                         <div class="artist-highlight-portfolio-card">
                                             <h2 class="artist-highlight-portfolio-heading">"Portfolio"</h2>
 
+                                            // Album chips, when the artist has organized their work
+                                            {
+                                                let navigate = navigate.clone();
+                                                move || {
+                                                    let navigate = navigate.clone();
+                                                    albums.get().filter(|albums| !albums.is_empty()).map(|albums| view! {
+                                                        <div class="shop-masonry-gallery__filter-container">
+                                                            <div class="shop-masonry-gallery__filter-wrapper">
+                                                                <span class="shop-masonry-gallery__filter-label">"Albums:"</span>
+
+                                                                <button
+                                                                    on:click={
+                                                                        let navigate = navigate.clone();
+                                                                        move |_| {
+                                                                            selected_album.set(None);
+                                                                            let mut options = leptos_router::NavigateOptions::default();
+                                                                            options.scroll = false;
+                                                                            navigate(&gallery_url(artist_id.get(), None, &selected_styles.get(), 0), options);
+                                                                        }
+                                                                    }
+                                                                    class="shop-masonry-gallery__filter-button"
+                                                                    class:shop-masonry-gallery__filter-button--active=move || selected_album.get().is_none()
+                                                                    class:shop-masonry-gallery__filter-button--inactive=move || selected_album.get().is_some()
+                                                                >
+                                                                    "All work"
+                                                                </button>
+
+                                                                {albums.into_iter().map(|album| {
+                                                                    let album_id = album.id;
+                                                                    let navigate = navigate.clone();
+                                                                    view! {
+                                                                        <button
+                                                                            on:click=move |_| {
+                                                                                let album = (selected_album.get() != Some(album_id)).then_some(album_id);
+                                                                                selected_album.set(album);
+                                                                                let mut options = leptos_router::NavigateOptions::default();
+                                                                                options.scroll = false;
+                                                                                navigate(&gallery_url(artist_id.get(), album, &selected_styles.get(), 0), options);
+                                                                            }
+                                                                            class="shop-masonry-gallery__filter-button"
+                                                                            class:shop-masonry-gallery__filter-button--active=move || selected_album.get() == Some(album_id)
+                                                                            class:shop-masonry-gallery__filter-button--inactive=move || selected_album.get() != Some(album_id)
+                                                                        >
+                                                                            {format!("{} ({})", album.name, album.image_count)}
+                                                                        </button>
+                                                                    }
+                                                                }).collect_view()}
+                                                            </div>
+                                                        </div>
+                                                    })
+                                                }
+                                            }
+
                                             // Style filter chips
                                             <div class="shop-masonry-gallery__filter-container">
                                                 <div class="shop-masonry-gallery__filter-wrapper">
@@ -305,7 +606,7 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                                 selected_styles.set(Vec::new());
                                                                 let mut options = leptos_router::NavigateOptions::default();
                                                                 options.scroll = false;
-                                                                navigate(&format!("/artist/{}", artist_id.get()), options);
+                                                                navigate(&gallery_url(artist_id.get(), selected_album.get(), &[], 0), options);
                                                             }
                                                         }
                                                         class="shop-masonry-gallery__filter-button"
@@ -329,18 +630,9 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                                             *styles = vec![style_id];
                                                                         }
                                                                     });
-                                                                    let styles_str = selected_styles.get()
-                                                                        .iter()
-                                                                        .map(|id| id.to_string())
-                                                                        .collect::<Vec<_>>()
-                                                                        .join(",");
                                                                     let mut options = leptos_router::NavigateOptions::default();
                                                                     options.scroll = false;
-                                                                    if styles_str.is_empty() {
-                                                                        navigate(&format!("/artist/{}", artist_id.get()), options);
-                                                                    } else {
-                                                                        navigate(&format!("/artist/{}?styles={}", artist_id.get(), styles_str), options);
-                                                                    }
+                                                                    navigate(&gallery_url(artist_id.get(), selected_album.get(), &selected_styles.get(), 0), options);
                                                                 }
                                                                 class="shop-masonry-gallery__filter-button"
                                                                 class:shop-masonry-gallery__filter-button--active=move || selected_styles.get().contains(&style_id)
@@ -392,22 +684,7 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                                                     on:click=move |_| {
                                                                                         if current_page.get() > 0 {
                                                                                             let new_page = current_page.get() - 1;
-                                                                                            let styles_str = selected_styles.get()
-                                                                                                .iter()
-                                                                                                .map(|id| id.to_string())
-                                                                                                .collect::<Vec<_>>()
-                                                                                                .join(",");
-                                                                                            let mut url = format!("/artist/{}", artist_id.get());
-                                                                                            let mut params = vec![];
-                                                                                            if !styles_str.is_empty() {
-                                                                                                params.push(format!("styles={}", styles_str));
-                                                                                            }
-                                                                                            if new_page > 0 {
-                                                                                                params.push(format!("page={}", new_page));
-                                                                                            }
-                                                                                            if !params.is_empty() {
-                                                                                                url = format!("{}?{}", url, params.join("&"));
-                                                                                            }
+                                                                                            let url = gallery_url(artist_id.get(), selected_album.get(), &selected_styles.get(), new_page);
                                                                                             let mut options = leptos_router::NavigateOptions::default();
                                                                                             options.scroll = false;
                                                                                             navigate_prev(&url, options);
@@ -427,20 +704,7 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                                                     on:click=move |_| {
                                                                                         if current_page.get() < total - 1 {
                                                                                             let new_page = current_page.get() + 1;
-                                                                                            let styles_str = selected_styles.get()
-                                                                                                .iter()
-                                                                                                .map(|id| id.to_string())
-                                                                                                .collect::<Vec<_>>()
-                                                                                                .join(",");
-                                                                                            let mut url = format!("/artist/{}", artist_id.get());
-                                                                                            let mut params = vec![];
-                                                                                            if !styles_str.is_empty() {
-                                                                                                params.push(format!("styles={}", styles_str));
-                                                                                            }
-                                                                                            params.push(format!("page={}", new_page));
-                                                                                            if !params.is_empty() {
-                                                                                                url = format!("{}?{}", url, params.join("&"));
-                                                                                            }
+                                                                                            let url = gallery_url(artist_id.get(), selected_album.get(), &selected_styles.get(), new_page);
                                                                                             let mut options = leptos_router::NavigateOptions::default();
                                                                                             options.scroll = false;
                                                                                             navigate_next(&url, options);
@@ -463,6 +727,30 @@ pub fn ArtistHighlight() -> impl IntoView {
                                                 }}
                                             </Suspense>
                                         </div>
+
+                                        // Clients' healed photos, shared with their consent
+                                        <Suspense fallback=|| ()>
+                                            {move || healed_work.get().filter(|photos| !photos.is_empty()).map(|photos| view! {
+                                                <div class="artist-highlight-portfolio-card artist-highlight-healed-work">
+                                                    <h2 class="artist-highlight-portfolio-heading">"Healed Work"</h2>
+                                                    <p class="artist-highlight-healed-work-subtitle">"Shared by clients"</p>
+                                                    <div class="artist-highlight-healed-work-grid">
+                                                        {photos.into_iter().map(|photo| view! {
+                                                            <a href=format!("/tattoo/{}", photo.id) class="artist-highlight-healed-work-item">
+                                                                <img
+                                                                    src=photo.thumbnail_url
+                                                                    alt=photo.caption.unwrap_or_else(|| "Healed tattoo".to_string())
+                                                                    loading="lazy"
+                                                                />
+                                                            </a>
+                                                        }).collect_view()}
+                                                    </div>
+                                                </div>
+                                            })}
+                                        </Suspense>
+
+                                        // Public Q&A
+                                        <ArtistQuestions artist_id=profile_artist_id questions=questions />
                                     </div>
                                 </div>
                             }.into_any()
@@ -499,11 +787,38 @@ pub fn ArtistHighlight() -> impl IntoView {
             <ClientBookingModal
                 show=show_booking_modal
                 artist_id=booking_artist_id
+                guest_spot=booking_guest_spot
                 on_close=move || {
                     show_booking_modal.set(false);
                     booking_artist_id.set(None);
+                    booking_guest_spot.set(None);
                 }
             />
         </div>
     }
 }
+
+/// Gallery URL keeping the album, style and page filters in the query string
+fn gallery_url(artist_id: i32, album: Option<i64>, styles: &[i32], page: i32) -> String {
+    let mut params = vec![];
+    if let Some(album) = album {
+        params.push(format!("album={}", album));
+    }
+    if !styles.is_empty() {
+        let styles_str = styles
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        params.push(format!("styles={}", styles_str));
+    }
+    if page > 0 {
+        params.push(format!("page={}", page));
+    }
+
+    if params.is_empty() {
+        format!("/artist/{}", artist_id)
+    } else {
+        format!("/artist/{}?{}", artist_id, params.join("&"))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::api_error::{user_message, ApiError};
 use crate::server::{login_user, signup_user};
 use leptos::{prelude::*, task::spawn_local};
 use leptos_router::{
@@ -99,6 +100,9 @@ pub fn LoginPage() -> impl IntoView {
                                 }
 
                                 setItem("tatteau_auth_token", token);
+                                if let Some(refresh_token) = &auth_response.refresh_token {
+                                    setItem("tatteau_refresh_token", refresh_token);
+                                }
                             }
                         }
 
@@ -132,7 +136,7 @@ pub fn LoginPage() -> impl IntoView {
                     }
                 }
                 Err(e) => {
-                    error_message.set(Some(format!("Login failed: {}", e)));
+                    error_message.set(Some(user_message(&e)));
                 }
             }
             loading.set(false);
@@ -282,6 +286,7 @@ pub fn LoginPage() -> impl IntoView {
                                 }}
                             </button>
                         </div>
+                        {field_hint("password")}
                     </div>
 
                     {move || error_message.get().map(|msg| view! {
@@ -331,6 +336,16 @@ pub fn SignupPage() -> impl IntoView {
     let confirm_password_visible = RwSignal::new(false);
     let loading = RwSignal::new(false);
     let error_message = RwSignal::new(Option::<String>::None);
+    // The field the server rejected, and why
+    let field_error = RwSignal::new(Option::<(String, String)>::None);
+    let field_hint = move |field: &'static str| {
+        move || {
+            field_error
+                .get()
+                .filter(|(name, _)| name == field)
+                .map(|(_, message)| view! { <div class="auth-field-error">{message}</div> })
+        }
+    };
 
     let is_button_disabled = Memo::new(move |_| {
         first_name.get().is_empty()
@@ -343,6 +358,7 @@ pub fn SignupPage() -> impl IntoView {
     let submit_signup = move |_| {
         loading.set(true);
         error_message.set(None);
+        field_error.set(None);
 
         // Validate passwords match
         if password.get() != confirm_password.get() {
@@ -371,7 +387,7 @@ pub fn SignupPage() -> impl IntoView {
                         // Redirect to login page with success message
                         if let Some(user_type) = auth_response.user_type {
                             let login_url = if user_type == "artist" {
-                                "/login?success=signup&user_type=artist"
+                                "/login?success=signup&user_type=artist&redirect=/artist/onboarding"
                             } else {
                                 "/login?success=signup&user_type=client"
                             };
@@ -384,9 +400,13 @@ pub fn SignupPage() -> impl IntoView {
                         error_message.set(auth_response.error);
                     }
                 }
-                Err(e) => {
-                    error_message.set(Some(format!("Signup failed: {}", e)));
-                }
+                Err(e) => match e {
+                    // Shown next to the input it's about
+                    ServerFnError::WrappedServerError(ApiError::Validation { field, message }) => {
+                        field_error.set(Some((field, message)));
+                    }
+                    e => error_message.set(Some(user_message(&e))),
+                },
             }
             loading.set(false);
         });
@@ -471,6 +491,7 @@ pub fn SignupPage() -> impl IntoView {
                                 placeholder="First Name"
                                 value=first_name
                             />
+                            {field_hint("first_name")}
                         </div>
                         <div class="auth-form-group">
                             <Input
@@ -478,6 +499,7 @@ pub fn SignupPage() -> impl IntoView {
                                 placeholder="Last Name"
                                 value=last_name
                             />
+                            {field_hint("last_name")}
                         </div>
                     </div>
 
@@ -496,6 +518,7 @@ pub fn SignupPage() -> impl IntoView {
                                 value=email
                             />
                         </div>
+                        {field_hint("email")}
                     </div>
 
                     <div class="auth-form-group">
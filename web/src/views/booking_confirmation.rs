@@ -1,7 +1,13 @@
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_router::hooks::{use_navigate, use_query_map};
 use thaw::*;
 
+use crate::server::payments::{
+    create_deposit_payment_intent, get_booking_deposit, record_deposit_payment,
+};
+use crate::utils::money::format_money;
+
 #[component]
 pub fn BookingConfirmation() -> impl IntoView {
     let query = use_query_map();
@@ -53,6 +59,7 @@ pub fn BookingConfirmation() -> impl IntoView {
                                         "Save this reference number for your records"
                                     </p>
                                 </div>
+                                <DepositPanel booking_id=id />
                             }.into_any()
                         } else {
                             view! {}.into_any()
@@ -131,3 +138,105 @@ pub fn BookingConfirmation() -> impl IntoView {
         </div>
     }
 }
+
+/// Deposit due on an approved booking, shown to its signed-in client. On the
+/// way back from Stripe Checkout the payment is confirmed first.
+#[component]
+fn DepositPanel(booking_id: i32) -> impl IntoView {
+    let query = use_query_map();
+    let paying = RwSignal::new(false);
+    let pay_error = RwSignal::new(None::<String>);
+
+    let auth_token = RwSignal::new(None::<String>);
+
+    // Load token on mount
+    Effect::new(move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn getItem(key: &str) -> Option<String>;
+            }
+
+            auth_token.set(getItem("tatteau_auth_token"));
+        }
+    });
+
+    let deposit = Resource::new(
+        move || (auth_token.get(), query.get().get("deposit_session")),
+        move |(token, session_id)| async move {
+            let token = token?;
+            match session_id {
+                Some(session_id) => {
+                    match record_deposit_payment(token.clone(), booking_id, session_id).await {
+                        Ok(deposit) => Some(deposit),
+                        // Stripe may not have settled yet; show the current state
+                        Err(_) => get_booking_deposit(token, booking_id).await.ok(),
+                    }
+                }
+                None => get_booking_deposit(token, booking_id).await.ok(),
+            }
+        },
+    );
+
+    let pay_deposit = move |_| {
+        let Some(token) = auth_token.get_untracked() else {
+            return;
+        };
+        paying.set(true);
+        pay_error.set(None);
+        spawn_local(async move {
+            match create_deposit_payment_intent(token, booking_id).await {
+                Ok(checkout_url) => {
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.location().set_href(&checkout_url);
+                    }
+                }
+                Err(e) => {
+                    pay_error.set(Some(e.to_string()));
+                    paying.set(false);
+                }
+            }
+        });
+    };
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || deposit.get().flatten().and_then(|deposit| {
+                let amount = deposit.amount?;
+                let amount = format_money(amount, &deposit.currency);
+                let (title, note) = match deposit.status.as_deref() {
+                    Some("paid") => ("Deposit paid", format!("Your {} deposit has been received. Your appointment is confirmed.", amount)),
+                    Some("waived") => ("Deposit waived", "The artist has waived the deposit for this booking.".to_string()),
+                    Some("refunded") => ("Deposit refunded", format!("Your {} deposit has been refunded.", amount)),
+                    _ => ("Deposit due", format!("The artist approved your request. Pay the {} deposit to confirm your appointment.", amount)),
+                };
+                let due = deposit.status.as_deref() == Some("required")
+                    && deposit.booking_status == "approved";
+
+                Some(view! {
+                    <div class="booking-confirmation-deposit">
+                        <h2 class="booking-confirmation-section-title">{title}</h2>
+                        <p>{note}</p>
+                        <Show when=move || due>
+                            <Button
+                                appearance=ButtonAppearance::Primary
+                                loading=Signal::from(paying)
+                                disabled=Signal::from(paying)
+                                on_click=pay_deposit
+                            >
+                                "Pay Deposit"
+                            </Button>
+                        </Show>
+                        {move || pay_error.get().map(|error| view! {
+                            <p class="booking-confirmation-deposit-error">{error}</p>
+                        })}
+                    </div>
+                })
+            })}
+        </Suspense>
+    }
+}
@@ -0,0 +1,191 @@
+use super::home::{status_label, use_client_token};
+use crate::components::MessageText;
+use crate::db::entities::{BookingMessage, ClientBooking};
+use crate::server_client_dashboard::{get_my_booking_thread, send_my_booking_message};
+use crate::utils::timezone::{
+    format_date_for_booking, format_datetime_for_booking, format_time_range_with_timezone,
+    get_timezone_abbreviation,
+};
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+/// One of the client's bookings with its message thread
+#[component]
+pub fn ClientBookingThread(booking_id: i32) -> impl IntoView {
+    let timezone = get_timezone_abbreviation();
+    let auth_token = use_client_token(format!("/dashboard/booking/{}", booking_id));
+    // Bumped after sending so the thread reloads
+    let thread_version = RwSignal::new(0u32);
+    let new_message = RwSignal::new(String::new());
+
+    let thread_resource = Resource::new(
+        move || (auth_token.get(), thread_version.get()),
+        move |(token, _)| async move {
+            match token {
+                Some(token) => get_my_booking_thread(token, booking_id).await.map(Some),
+                None => Ok(None),
+            }
+        },
+    );
+
+    let send_action = Action::new(move |message: &String| {
+        let message = message.clone();
+        let token = auth_token.get_untracked().unwrap_or_default();
+        async move { send_my_booking_message(token, booking_id, message).await }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = send_action.value().get() {
+            new_message.set(String::new());
+            thread_version.update(|v| *v += 1);
+        }
+    });
+
+    let on_send = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let message = new_message.get_untracked();
+        if !message.trim().is_empty() {
+            send_action.dispatch(message);
+        }
+    };
+
+    view! {
+        <div class="client-dashboard">
+            <div class="client-dashboard__container">
+                <A href="/dashboard" attr:class="client-dashboard__back">"← Back to dashboard"</A>
+
+                <Suspense fallback=|| view! { <p class="client-dashboard__loading">"Loading booking..."</p> }>
+                    {move || thread_resource.get().map(|result| match result {
+                        Ok(Some((booking, messages))) => view! {
+                            <BookingSummary booking=booking timezone=timezone />
+                            <section class="client-dashboard__section">
+                                <h2>"Messages"</h2>
+                                <MessageList messages=messages timezone=timezone />
+                            </section>
+                        }.into_any(),
+                        Ok(None) => ().into_any(),
+                        Err(e) => view! { <p class="error-message">{e.to_string()}</p> }.into_any(),
+                    })}
+                </Suspense>
+
+                <form class="client-dashboard__reply" on:submit=on_send>
+                    <textarea
+                        placeholder="Write a message to the artist..."
+                        maxlength="2000"
+                        prop:value=move || new_message.get()
+                        on:input=move |ev| new_message.set(event_target_value(&ev))
+                    ></textarea>
+                    <button
+                        type="submit"
+                        class="client-dashboard__cta"
+                        disabled=move || send_action.pending().get()
+                    >
+                        {move || if send_action.pending().get() { "Sending..." } else { "Send" }}
+                    </button>
+                </form>
+                {move || send_action.value().get().and_then(|result| result.err()).map(|e| view! {
+                    <p class="error-message">{e.to_string()}</p>
+                })}
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn BookingSummary(booking: ClientBooking, timezone: ReadSignal<String>) -> impl IntoView {
+    let (icon, label) = status_label(&booking.status);
+    let deposit_due =
+        booking.status == "approved" && booking.deposit_status.as_deref() == Some("required");
+
+    view! {
+        <section class="client-dashboard__section client-dashboard__summary">
+            <div class="client-dashboard__section-header">
+                <h1>{booking.artist_name.clone().unwrap_or_else(|| "Your booking".to_string())}</h1>
+                <span class=format!("client-dashboard__status client-dashboard__status--{}", booking.status)>
+                    {format!("{} {}", icon, label)}
+                </span>
+            </div>
+            <p>
+                {format!(
+                    "{}, {}",
+                    format_date_for_booking(&booking.requested_date),
+                    format_time_range_with_timezone(
+                        &booking.requested_start_time,
+                        booking.requested_end_time.as_deref(),
+                        timezone
+                    )
+                )}
+            </p>
+            {booking.suggested_date.clone().filter(|date| !date.is_empty()).map(|date| view! {
+                <p class="client-dashboard__note">
+                    {format!(
+                        "The artist suggested {}{}",
+                        format_date_for_booking(&date),
+                        booking
+                            .suggested_start_time
+                            .as_deref()
+                            .map(|time| format!(", {}", format_time_range_with_timezone(time, None, timezone)))
+                            .unwrap_or_default()
+                    )}
+                </p>
+            })}
+            {booking.artist_response.clone().filter(|response| !response.is_empty()).map(|response| view! {
+                <blockquote class="client-dashboard__response">{response}</blockquote>
+            })}
+            {deposit_due.then(|| view! {
+                <a
+                    class="client-dashboard__cta"
+                    href=format!("/booking/confirmation?booking_id={}", booking.id)
+                >
+                    {match booking.deposit_amount {
+                        Some(amount) => format!("Pay ${:.2} deposit", amount),
+                        None => "Pay deposit".to_string(),
+                    }}
+                </a>
+            })}
+            <A href=format!("/artist/{}", booking.artist_id) attr:class="client-dashboard__link">
+                "View artist profile"
+            </A>
+        </section>
+    }
+}
+
+#[component]
+fn MessageList(messages: Vec<BookingMessage>, timezone: ReadSignal<String>) -> impl IntoView {
+    if messages.is_empty() {
+        return view! {
+            <p class="client-dashboard__empty">"No messages yet. Say hello to your artist below."</p>
+        }
+        .into_any();
+    }
+
+    view! {
+        <ul class="client-dashboard__messages">
+            {messages.into_iter().map(|message| {
+                let mine = message.sender_type == "client";
+                let read = mine && message.is_read;
+                view! {
+                    <li class=if mine {
+                        "client-dashboard__message client-dashboard__message--mine"
+                    } else {
+                        "client-dashboard__message"
+                    }>
+                        <div class="client-dashboard__message-header">
+                            <strong>{if mine { "You" } else { "Artist" }}</strong>
+                            {message.created_at.as_ref().map(|at| view! {
+                                <span class="client-dashboard__meta">
+                                    {format_datetime_for_booking(at, timezone)}
+                                </span>
+                            })}
+                        </div>
+                        <MessageText message=message reader="client" />
+                        <Show when=move || read>
+                            <span class="client-dashboard__read">"Read"</span>
+                        </Show>
+                    </li>
+                }
+            }).collect_view()}
+        </ul>
+    }
+    .into_any()
+}
@@ -0,0 +1,340 @@
+use crate::db::entities::{ClientBooking, ClientMessageThread};
+use crate::db::favorites_repository::FavoritePostWithDetails;
+use crate::server_client_dashboard::{
+    get_my_booking_requests, get_my_message_threads, get_my_recent_favorites,
+    get_my_upcoming_appointments,
+};
+use crate::utils::timezone::{
+    format_date_for_booking, format_datetime_for_booking, format_time_with_timezone,
+    get_timezone_abbreviation,
+};
+use leptos::prelude::*;
+use leptos_router::components::A;
+
+/// Label and icon for a booking status, as the client sees it
+pub(super) fn status_label(status: &str) -> (&'static str, &'static str) {
+    match status {
+        "pending" => ("⏳", "Waiting for the artist"),
+        "needs_info" => ("❓", "The artist needs more details"),
+        "approved" => ("✅", "Approved"),
+        "confirmed" => ("📅", "Confirmed"),
+        "declined" => ("❌", "Declined"),
+        "completed" => ("🎨", "Completed"),
+        "cancelled" => ("🚫", "Cancelled"),
+        _ => ("📋", "In progress"),
+    }
+}
+
+/// Loads the access token on mount, sending signed-out visitors to log in
+/// and come back to `redirect`
+pub(super) fn use_client_token(redirect: String) -> RwSignal<Option<String>> {
+    let auth_token = RwSignal::new(None::<String>);
+
+    Effect::new(move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            use crate::utils::auth::get_auth_token;
+
+            if let Some(token) = get_auth_token() {
+                auth_token.set(Some(token));
+            } else if let Some(window) = web_sys::window() {
+                let _ = window.location().set_href(&format!(
+                    "/login?redirect={}",
+                    urlencoding::encode(&redirect)
+                ));
+            }
+        }
+        #[cfg(not(feature = "hydrate"))]
+        {
+            let _ = &redirect;
+        }
+    });
+
+    auth_token
+}
+
+/// The client's dashboard: upcoming appointments, booking requests, message
+/// threads and saved favorites
+#[component]
+pub fn ClientDashboard() -> impl IntoView {
+    let timezone = get_timezone_abbreviation();
+    let auth_token = use_client_token("/dashboard".to_string());
+
+    let upcoming_resource = Resource::new(
+        move || auth_token.get(),
+        move |token| async move {
+            match token {
+                Some(token) => get_my_upcoming_appointments(token).await,
+                None => Ok(vec![]),
+            }
+        },
+    );
+    let requests_resource = Resource::new(
+        move || auth_token.get(),
+        move |token| async move {
+            match token {
+                Some(token) => get_my_booking_requests(token).await,
+                None => Ok(vec![]),
+            }
+        },
+    );
+    let threads_resource = Resource::new(
+        move || auth_token.get(),
+        move |token| async move {
+            match token {
+                Some(token) => get_my_message_threads(token).await,
+                None => Ok(vec![]),
+            }
+        },
+    );
+    let favorites_resource = Resource::new(
+        move || auth_token.get(),
+        move |token| async move {
+            match token {
+                Some(token) => get_my_recent_favorites(token).await,
+                None => Ok(vec![]),
+            }
+        },
+    );
+
+    view! {
+        <div class="client-dashboard">
+            <div class="client-dashboard__container">
+                <div class="client-dashboard__header">
+                    <h1>"My Dashboard"</h1>
+                    <p class="client-dashboard__subtitle">
+                        "Your appointments, requests, messages and saved inspiration in one place"
+                    </p>
+                </div>
+
+                <section class="client-dashboard__section">
+                    <h2>"Upcoming appointments"</h2>
+                    <Suspense fallback=|| view! { <p class="client-dashboard__loading">"Loading..."</p> }>
+                        {move || upcoming_resource.get().map(|result| match result {
+                            Ok(bookings) if !bookings.is_empty() => bookings
+                                .into_iter()
+                                .map(|booking| view! { <AppointmentCard booking=booking timezone=timezone /> })
+                                .collect_view()
+                                .into_any(),
+                            Ok(_) => view! {
+                                <p class="client-dashboard__empty">
+                                    "No upcoming appointments. Approved bookings will show up here."
+                                </p>
+                            }.into_any(),
+                            Err(e) => view! { <p class="error-message">{e.to_string()}</p> }.into_any(),
+                        })}
+                    </Suspense>
+                </section>
+
+                <section class="client-dashboard__section">
+                    <h2>"My booking requests"</h2>
+                    <Suspense fallback=|| view! { <p class="client-dashboard__loading">"Loading..."</p> }>
+                        {move || requests_resource.get().map(|result| match result {
+                            Ok(bookings) if !bookings.is_empty() => view! {
+                                <ul class="client-dashboard__list">
+                                    {bookings
+                                        .into_iter()
+                                        .map(|booking| view! { <BookingRequestRow booking=booking timezone=timezone /> })
+                                        .collect_view()}
+                                </ul>
+                            }.into_any(),
+                            Ok(_) => view! {
+                                <div class="client-dashboard__empty">
+                                    <p>"You haven't requested a booking yet."</p>
+                                    <A href="/match" attr:class="client-dashboard__cta">"Find an artist"</A>
+                                </div>
+                            }.into_any(),
+                            Err(e) => view! { <p class="error-message">{e.to_string()}</p> }.into_any(),
+                        })}
+                    </Suspense>
+                </section>
+
+                <section class="client-dashboard__section">
+                    <h2>"Messages"</h2>
+                    <Suspense fallback=|| view! { <p class="client-dashboard__loading">"Loading..."</p> }>
+                        {move || threads_resource.get().map(|result| match result {
+                            Ok(threads) if !threads.is_empty() => view! {
+                                <ul class="client-dashboard__list">
+                                    {threads
+                                        .into_iter()
+                                        .map(|thread| view! { <MessageThreadRow thread=thread timezone=timezone /> })
+                                        .collect_view()}
+                                </ul>
+                            }.into_any(),
+                            Ok(_) => view! {
+                                <p class="client-dashboard__empty">
+                                    "No messages yet. Artists' replies to your requests appear here."
+                                </p>
+                            }.into_any(),
+                            Err(e) => view! { <p class="error-message">{e.to_string()}</p> }.into_any(),
+                        })}
+                    </Suspense>
+                </section>
+
+                <section class="client-dashboard__section">
+                    <div class="client-dashboard__section-header">
+                        <h2>"Saved favorites"</h2>
+                        <A href="/favorites" attr:class="client-dashboard__link">"View all"</A>
+                    </div>
+                    <Suspense fallback=|| view! { <p class="client-dashboard__loading">"Loading..."</p> }>
+                        {move || favorites_resource.get().map(|result| match result {
+                            Ok(favorites) if !favorites.is_empty() => view! {
+                                <div class="client-dashboard__favorites">
+                                    {favorites
+                                        .into_iter()
+                                        .map(|favorite| view! { <FavoriteCard favorite=favorite /> })
+                                        .collect_view()}
+                                </div>
+                            }.into_any(),
+                            Ok(_) => view! {
+                                <div class="client-dashboard__empty">
+                                    <p>"Save tattoos you love and they'll show up here."</p>
+                                    <A href="/explore" attr:class="client-dashboard__cta">"Explore tattoos"</A>
+                                </div>
+                            }.into_any(),
+                            Err(e) => view! { <p class="error-message">{e.to_string()}</p> }.into_any(),
+                        })}
+                    </Suspense>
+                </section>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn AppointmentCard(booking: ClientBooking, timezone: ReadSignal<String>) -> impl IntoView {
+    let deposit_due = booking.deposit_status.as_deref() == Some("required");
+
+    view! {
+        <A href=format!("/dashboard/booking/{}", booking.id) attr:class="client-dashboard__appointment">
+            <div class="client-dashboard__appointment-date">
+                {format_date_for_booking(&booking.requested_date)}
+            </div>
+            <div class="client-dashboard__appointment-details">
+                <strong>{booking.artist_name.unwrap_or_else(|| "Your artist".to_string())}</strong>
+                <span>{format_time_with_timezone(&booking.requested_start_time, timezone)}</span>
+                {deposit_due.then(|| view! {
+                    <span class="client-dashboard__badge client-dashboard__badge--warning">
+                        {match booking.deposit_amount {
+                            Some(amount) => format!("${:.2} deposit due", amount),
+                            None => "Deposit due".to_string(),
+                        }}
+                    </span>
+                })}
+            </div>
+        </A>
+    }
+}
+
+#[component]
+fn BookingRequestRow(booking: ClientBooking, timezone: ReadSignal<String>) -> impl IntoView {
+    let (icon, label) = status_label(&booking.status);
+    let suggestion = booking
+        .suggested_date
+        .as_ref()
+        .filter(|date| !date.is_empty() && booking.status == "pending")
+        .map(|date| {
+            format!(
+                "Artist suggested {}{}",
+                format_date_for_booking(date),
+                booking
+                    .suggested_start_time
+                    .as_deref()
+                    .map(|time| format!(" at {}", format_time_with_timezone(time, timezone)))
+                    .unwrap_or_default()
+            )
+        });
+
+    view! {
+        <li class="client-dashboard__row">
+            <A href=format!("/dashboard/booking/{}", booking.id) attr:class="client-dashboard__row-link">
+                <div class="client-dashboard__row-main">
+                    <strong>{booking.artist_name.unwrap_or_else(|| "Artist".to_string())}</strong>
+                    <span>
+                        {format!(
+                            "{} at {}",
+                            format_date_for_booking(&booking.requested_date),
+                            format_time_with_timezone(&booking.requested_start_time, timezone)
+                        )}
+                    </span>
+                    {suggestion.map(|suggestion| view! {
+                        <span class="client-dashboard__note">{suggestion}</span>
+                    })}
+                </div>
+                <div class="client-dashboard__row-side">
+                    <span class=format!("client-dashboard__status client-dashboard__status--{}", booking.status)>
+                        {format!("{} {}", icon, label)}
+                    </span>
+                    {booking.created_at.as_ref().map(|created| view! {
+                        <span class="client-dashboard__meta">
+                            "Sent "{format_datetime_for_booking(created, timezone)}
+                        </span>
+                    })}
+                </div>
+            </A>
+        </li>
+    }
+}
+
+#[component]
+fn MessageThreadRow(thread: ClientMessageThread, timezone: ReadSignal<String>) -> impl IntoView {
+    let from_artist = thread.last_sender_type == "artist";
+    let unread = thread.unread_count;
+    let preview: String = thread.last_message.chars().take(120).collect();
+
+    view! {
+        <li class="client-dashboard__row">
+            <A href=format!("/dashboard/booking/{}", thread.booking_id) attr:class="client-dashboard__row-link">
+                <div class="client-dashboard__row-main">
+                    <strong>{thread.artist_name.unwrap_or_else(|| "Artist".to_string())}</strong>
+                    <span class="client-dashboard__note">
+                        {if from_artist { "" } else { "You: " }}{preview}
+                    </span>
+                </div>
+                <div class="client-dashboard__row-side">
+                    <Show when=move || unread > 0>
+                        <span class="client-dashboard__unread">{format!("{} new", unread)}</span>
+                    </Show>
+                    <span class="client-dashboard__meta">
+                        {format!("{} messages", thread.message_count)}
+                    </span>
+                    {thread.last_message_at.as_ref().map(|at| view! {
+                        <span class="client-dashboard__meta">
+                            {format_datetime_for_booking(at, timezone)}
+                        </span>
+                    })}
+                </div>
+            </A>
+        </li>
+    }
+}
+
+#[component]
+fn FavoriteCard(favorite: FavoritePostWithDetails) -> impl IntoView {
+    let styles = favorite
+        .styles
+        .iter()
+        .map(|style| style.name.clone())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    view! {
+        <div class="client-dashboard__favorite">
+            <a
+                href=format!("https://www.instagram.com/p/{}/", favorite.image.short_code)
+                target="_blank"
+                rel="noopener noreferrer"
+            >
+                "View post"
+            </a>
+            {favorite.artist.map(|artist| view! {
+                <A href=format!("/artist/{}", artist.id) attr:class="client-dashboard__favorite-artist">
+                    {artist.name.unwrap_or_else(|| "Artist".to_string())}
+                </A>
+            })}
+            {(!styles.is_empty()).then(|| view! {
+                <span class="client-dashboard__meta">{styles}</span>
+            })}
+        </div>
+    }
+}
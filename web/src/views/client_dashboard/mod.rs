@@ -0,0 +1,7 @@
+pub mod booking;
+pub mod home;
+pub mod respond;
+
+pub use booking::ClientBookingThread;
+pub use home::ClientDashboard;
+pub use respond::BookingResponsePage;
@@ -0,0 +1,511 @@
+use super::home::status_label;
+use crate::api_error::user_message;
+use crate::components::DocumentBody;
+use crate::db::entities::{ConsentForm, LinkedBooking, RescheduleProposal};
+use crate::server_documents::{get_consent_form, sign_consent_form};
+use crate::server_reschedule::{
+    accept_suggested_time, cancel_booking_by_link, decline_suggested_time, get_linked_booking,
+    TimeProposal,
+};
+use crate::utils::reschedule::CLIENT_CANCELLATION_REASONS;
+use crate::utils::timezone::{
+    format_date_for_booking, format_time_range_with_timezone, get_timezone_abbreviation,
+};
+use leptos::prelude::*;
+use leptos_router::hooks::{use_params_map, use_query_map};
+use shared_types::datetime::{Date, Time};
+
+/// Statuses a client can still reschedule or cancel from
+const ACTIVE_STATUSES: [&str; 4] = ["pending", "needs_info", "approved", "confirmed"];
+
+fn describe_proposal(proposal: &RescheduleProposal, timezone: ReadSignal<String>) -> String {
+    format!(
+        "{}, {}",
+        format_date_for_booking(&proposal.date),
+        format_time_range_with_timezone(
+            &proposal.start_time,
+            proposal.end_time.as_deref(),
+            timezone
+        )
+    )
+}
+
+/// Where a client answers the artist's suggested time, signs the consent
+/// form or cancels, through the signed link they were emailed; no account
+/// needed
+#[component]
+pub fn BookingResponsePage() -> impl IntoView {
+    let params = use_params_map();
+    let query = use_query_map();
+    let booking_id = move || {
+        params
+            .read()
+            .get("id")
+            .and_then(|id| id.parse::<i32>().ok())
+            .unwrap_or_default()
+    };
+    let link_token = move || query.read().get("token").unwrap_or_default();
+    let timezone = get_timezone_abbreviation();
+    // Bumped after each change so the booking reloads
+    let version = RwSignal::new(0u32);
+    let notice = RwSignal::new(None::<String>);
+
+    let booking = Resource::new(
+        move || (booking_id(), link_token(), version.get()),
+        |(booking_id, link_token, _)| async move {
+            get_linked_booking(booking_id, link_token)
+                .await
+                .map_err(|e| user_message(&e))
+        },
+    );
+
+    view! {
+        <div class="client-dashboard">
+            <div class="client-dashboard__container">
+                {move || notice.get().map(|notice| view! {
+                    <p class="client-dashboard__notice">{notice}</p>
+                })}
+                <Suspense fallback=|| view! { <p class="client-dashboard__loading">"Loading booking..."</p> }>
+                    {move || booking.get().map(|result| match result {
+                        Ok(booking) => view! {
+                            <LinkedBookingView
+                                booking=booking
+                                link_token=link_token()
+                                timezone=timezone
+                                version=version
+                                notice=notice
+                            />
+                        }.into_any(),
+                        Err(e) => view! { <p class="error-message">{e}</p> }.into_any(),
+                    })}
+                </Suspense>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn LinkedBookingView(
+    booking: LinkedBooking,
+    link_token: String,
+    timezone: ReadSignal<String>,
+    version: RwSignal<u32>,
+    notice: RwSignal<Option<String>>,
+) -> impl IntoView {
+    let (icon, label) = status_label(&booking.status);
+    let active = ACTIVE_STATUSES.contains(&booking.status.as_str());
+    let artist = booking
+        .artist_name
+        .clone()
+        .unwrap_or_else(|| "Your artist".to_string());
+
+    let proposal_view = booking
+        .open_proposal
+        .clone()
+        .filter(|_| active)
+        .map(|proposal| {
+            if proposal.proposed_by == "artist" {
+                view! {
+                    <SuggestedTime
+                        booking_id=booking.id
+                        link_token=link_token.clone()
+                        proposal=proposal
+                        artist=artist.clone()
+                        timezone=timezone
+                        version=version
+                        notice=notice
+                    />
+                }
+                .into_any()
+            } else {
+                view! {
+                    <section class="client-dashboard__section">
+                        <p class="client-dashboard__note">
+                            {format!(
+                                "You asked for {}. {} will get back to you.",
+                                describe_proposal(&proposal, timezone),
+                                artist
+                            )}
+                        </p>
+                    </section>
+                }
+                .into_any()
+            }
+        });
+
+    view! {
+        <section class="client-dashboard__section client-dashboard__summary">
+            <div class="client-dashboard__section-header">
+                <h1>{artist.clone()}</h1>
+                <span class=format!("client-dashboard__status client-dashboard__status--{}", booking.status)>
+                    {format!("{} {}", icon, label)}
+                </span>
+            </div>
+            <p>
+                {format!(
+                    "{}, {}",
+                    format_date_for_booking(&booking.requested_date),
+                    format_time_range_with_timezone(
+                        &booking.requested_start_time,
+                        booking.requested_end_time.as_deref(),
+                        timezone
+                    )
+                )}
+            </p>
+            {booking.cancellation_reason.as_deref().map(|reason| view! {
+                <p class="client-dashboard__note">
+                    {format!(
+                        "Cancelled: {}",
+                        crate::utils::reschedule::cancellation_reason_label(reason)
+                    )}
+                </p>
+            })}
+        </section>
+        {proposal_view}
+        {active.then(|| view! {
+            <ConsentFormSection booking_id=booking.id link_token=link_token.clone() />
+        })}
+        {active.then(|| view! {
+            <CancelBooking
+                booking=booking.clone()
+                link_token=link_token.clone()
+                version=version
+                notice=notice
+            />
+        })}
+    }
+}
+
+#[component]
+fn SuggestedTime(
+    booking_id: i32,
+    link_token: String,
+    proposal: RescheduleProposal,
+    artist: String,
+    timezone: ReadSignal<String>,
+    version: RwSignal<u32>,
+    notice: RwSignal<Option<String>>,
+) -> impl IntoView {
+    let proposal_id = proposal.id;
+    let declining = RwSignal::new(false);
+    let note = RwSignal::new(String::new());
+    let counter_date = RwSignal::new(String::new());
+    let counter_start = RwSignal::new(String::new());
+    let counter_end = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+
+    let accept_token = link_token.clone();
+    let accept = Action::new(move |_: &()| {
+        let link_token = accept_token.clone();
+        async move { accept_suggested_time(booking_id, link_token, proposal_id).await }
+    });
+    let decline = Action::new(
+        move |(note, counter): &(Option<String>, Option<TimeProposal>)| {
+            let (link_token, note, counter) = (link_token.clone(), note.clone(), counter.clone());
+            async move {
+                decline_suggested_time(booking_id, link_token, proposal_id, note, counter).await
+            }
+        },
+    );
+
+    Effect::new(move |_| match accept.value().get() {
+        Some(Ok(())) => {
+            notice.set(Some("You're booked in for the new time.".to_string()));
+            version.update(|v| *v += 1);
+        }
+        Some(Err(e)) => error.set(Some(user_message(&e))),
+        None => {}
+    });
+    Effect::new(move |_| match decline.value().get() {
+        Some(Ok(())) => {
+            notice.set(Some("The artist has been told.".to_string()));
+            version.update(|v| *v += 1);
+        }
+        Some(Err(e)) => error.set(Some(user_message(&e))),
+        None => {}
+    });
+
+    let on_decline = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let date = counter_date.get_untracked();
+        let counter = if date.trim().is_empty() {
+            None
+        } else {
+            let (Some(date), Some(start_time)) = (
+                Date::parse(date.trim()),
+                Time::parse_lenient(&counter_start.get_untracked()),
+            ) else {
+                error.set(Some("Pick a date and a start time".to_string()));
+                return;
+            };
+            let end_time = match counter_end.get_untracked().trim() {
+                "" => None,
+                end => match Time::parse_lenient(end) {
+                    Some(end) => Some(end),
+                    None => {
+                        error.set(Some("That end time isn't valid".to_string()));
+                        return;
+                    }
+                },
+            };
+            Some(TimeProposal {
+                date,
+                start_time,
+                end_time,
+            })
+        };
+        let note = Some(note.get_untracked()).filter(|note| !note.trim().is_empty());
+        error.set(None);
+        decline.dispatch((note, counter));
+    };
+
+    let pending = move || accept.pending().get() || decline.pending().get();
+
+    view! {
+        <section class="client-dashboard__section">
+            <h2>"A new time was suggested"</h2>
+            <p>{format!("{} suggested {}.", artist, describe_proposal(&proposal, timezone))}</p>
+            {move || error.get().map(|error| view! { <p class="error-message">{error}</p> })}
+            <Show
+                when=move || declining.get()
+                fallback=move || view! {
+                    <button
+                        class="client-dashboard__cta"
+                        disabled=pending
+                        on:click=move |_| {
+                            accept.dispatch(());
+                        }
+                    >
+                        "Accept this time"
+                    </button>
+                    <button
+                        class="client-dashboard__link client-dashboard__button-link"
+                        disabled=pending
+                        on:click=move |_| declining.set(true)
+                    >
+                        "I can't make it"
+                    </button>
+                }
+            >
+                <form class="client-dashboard__reply" on:submit=on_decline>
+                    <p class="client-dashboard__note">
+                        "Suggest a time that works for you, or leave it blank to just decline."
+                    </p>
+                    <div class="client-dashboard__time-inputs">
+                        <input
+                            type="date"
+                            prop:value=move || counter_date.get()
+                            on:input=move |ev| counter_date.set(event_target_value(&ev))
+                        />
+                        <input
+                            type="time"
+                            prop:value=move || counter_start.get()
+                            on:input=move |ev| counter_start.set(event_target_value(&ev))
+                        />
+                        <input
+                            type="time"
+                            placeholder="End (optional)"
+                            prop:value=move || counter_end.get()
+                            on:input=move |ev| counter_end.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <textarea
+                        placeholder="Anything the artist should know (optional)"
+                        maxlength="500"
+                        prop:value=move || note.get()
+                        on:input=move |ev| note.set(event_target_value(&ev))
+                    ></textarea>
+                    <button type="submit" class="client-dashboard__cta" disabled=pending>
+                        {move || if decline.pending().get() { "Sending..." } else { "Send" }}
+                    </button>
+                </form>
+            </Show>
+        </section>
+    }
+}
+
+#[component]
+fn CancelBooking(
+    booking: LinkedBooking,
+    link_token: String,
+    version: RwSignal<u32>,
+    notice: RwSignal<Option<String>>,
+) -> impl IntoView {
+    let booking_id = booking.id;
+    let reason = RwSignal::new(String::new());
+    let note = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+    let deposit_paid = booking.deposit_status.as_deref() == Some("paid");
+
+    let cancel = Action::new(move |(reason, note): &(String, Option<String>)| {
+        let (link_token, reason, note) = (link_token.clone(), reason.clone(), note.clone());
+        async move { cancel_booking_by_link(booking_id, link_token, reason, note).await }
+    });
+
+    Effect::new(move |_| match cancel.value().get() {
+        Some(Ok(late)) => {
+            notice.set(Some(if late && deposit_paid {
+                "Your booking was cancelled. It was inside the artist's cancellation window, so your deposit isn't refunded.".to_string()
+            } else {
+                "Your booking was cancelled.".to_string()
+            }));
+            version.update(|v| *v += 1);
+        }
+        Some(Err(e)) => error.set(Some(user_message(&e))),
+        None => {}
+    });
+
+    let on_cancel = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let reason = reason.get_untracked();
+        if reason.is_empty() {
+            error.set(Some("Pick a reason for cancelling".to_string()));
+            return;
+        }
+        let note = Some(note.get_untracked()).filter(|note| !note.trim().is_empty());
+        error.set(None);
+        cancel.dispatch((reason, note));
+    };
+
+    view! {
+        <section class="client-dashboard__section">
+            <h2>"Cancel booking"</h2>
+            {(booking.late_to_cancel && deposit_paid).then(|| view! {
+                <p class="client-dashboard__note">
+                    {format!(
+                        "It's less than {} hours until your appointment, so cancelling now means your deposit isn't refunded.",
+                        booking.cancellation_window_hours
+                    )}
+                </p>
+            })}
+            {move || error.get().map(|error| view! { <p class="error-message">{error}</p> })}
+            <form class="client-dashboard__reply" on:submit=on_cancel>
+                <select
+                    prop:value=move || reason.get()
+                    on:change=move |ev| reason.set(event_target_value(&ev))
+                >
+                    <option value="">"Why are you cancelling?"</option>
+                    {CLIENT_CANCELLATION_REASONS
+                        .iter()
+                        .map(|(value, label)| view! { <option value=*value>{*label}</option> })
+                        .collect_view()}
+                </select>
+                <textarea
+                    placeholder="Anything else the artist should know (optional)"
+                    maxlength="500"
+                    prop:value=move || note.get()
+                    on:input=move |ev| note.set(event_target_value(&ev))
+                ></textarea>
+                <button type="submit" class="client-dashboard__cta" disabled=move || cancel.pending().get()>
+                    {move || if cancel.pending().get() { "Cancelling..." } else { "Cancel booking" }}
+                </button>
+            </form>
+        </section>
+    }
+}
+
+/// The artist's consent form, to read and sign by typing a name, or as
+/// signed with a PDF copy
+#[component]
+fn ConsentFormSection(booking_id: i32, link_token: String) -> impl IntoView {
+    let typed_name = RwSignal::new(String::new());
+    let agreed = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+    let version = RwSignal::new(0u32);
+
+    let form = Resource::new(
+        {
+            let link_token = link_token.clone();
+            move || (link_token.clone(), version.get())
+        },
+        move |(link_token, _)| async move {
+            get_consent_form(booking_id, link_token)
+                .await
+                .map_err(|e| user_message(&e))
+        },
+    );
+
+    let pdf_link = format!(
+        "/api/bookings/{}/consent.pdf?token={}",
+        booking_id, link_token
+    );
+    let sign = Action::new(move |(typed_name, agreed): &(String, bool)| {
+        let (link_token, typed_name, agreed) = (link_token.clone(), typed_name.clone(), *agreed);
+        async move { sign_consent_form(booking_id, link_token, typed_name, agreed).await }
+    });
+
+    Effect::new(move |_| match sign.value().get() {
+        Some(Ok(_)) => {
+            error.set(None);
+            version.update(|v| *v += 1);
+        }
+        Some(Err(e)) => error.set(Some(user_message(&e))),
+        None => {}
+    });
+
+    let on_sign = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        error.set(None);
+        sign.dispatch((typed_name.get_untracked(), agreed.get_untracked()));
+    };
+
+    let signed_view = move |form: ConsentForm| {
+        let signature = form.signature.clone()?;
+        Some(view! {
+            <p class="client-dashboard__note">
+                {format!(
+                    "Signed by {} on {} UTC.",
+                    signature.typed_name, signature.signed_at
+                )}
+            </p>
+            <a class="client-dashboard__link" href=pdf_link.clone() target="_blank" rel="noopener">
+                "Download a copy (PDF)"
+            </a>
+        })
+    };
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || form.get().map(|result| match result {
+                Ok(form) => {
+                    let signed = signed_view(form.clone());
+                    let unsigned = form.signature.is_none();
+                    view! {
+                        <section class="client-dashboard__section client-dashboard__consent">
+                            <h2>{form.title.clone()}</h2>
+                            <div class="client-dashboard__consent-body">
+                                <DocumentBody text=form.body.clone() />
+                            </div>
+                            {signed}
+                            {unsigned.then(|| view! {
+                                {move || error.get().map(|error| view! { <p class="error-message">{error}</p> })}
+                                <form class="client-dashboard__reply" on:submit=on_sign>
+                                    <label class="client-dashboard__checkbox">
+                                        <input
+                                            type="checkbox"
+                                            prop:checked=move || agreed.get()
+                                            on:change=move |ev| agreed.set(event_target_checked(&ev))
+                                        />
+                                        "I've read this form and agree to it"
+                                    </label>
+                                    <input
+                                        type="text"
+                                        placeholder="Type your full name to sign"
+                                        maxlength="120"
+                                        prop:value=move || typed_name.get()
+                                        on:input=move |ev| typed_name.set(event_target_value(&ev))
+                                    />
+                                    <button type="submit" class="client-dashboard__cta" disabled=move || sign.pending().get()>
+                                        {move || if sign.pending().get() { "Signing..." } else { "Sign" }}
+                                    </button>
+                                </form>
+                            })}
+                        </section>
+                    }
+                    .into_any()
+                }
+                Err(e) => view! { <p class="error-message">{e}</p> }.into_any(),
+            })}
+        </Suspense>
+    }
+}
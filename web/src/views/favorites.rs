@@ -1,6 +1,9 @@
 use crate::components::shop_masonry_gallery::{ShopInstagramPost, ShopMasonryGallery};
-use crate::server_favorites::get_user_favorites_with_details;
+use crate::server_favorites::{
+    create_collection, delete_collection, list_collections, list_favorites, rename_collection,
+};
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_router::hooks::use_navigate;
 
 #[component]
@@ -10,6 +13,15 @@ pub fn FavoritesPage() -> impl IntoView {
     // Get the auth token from localStorage as a signal
     let auth_token = RwSignal::new(None::<String>);
 
+    // Board being viewed, None for all favorites
+    let selected_board = RwSignal::new(None::<i64>);
+    // Bumped whenever boards or their contents change
+    let boards_version = RwSignal::new(0u32);
+    let new_board_name = RwSignal::new(String::new());
+    let rename_value = RwSignal::new(String::new());
+    let is_renaming = RwSignal::new(false);
+    let board_error = RwSignal::new(None::<String>);
+
     // Load token on mount
     Effect::new(move |_| {
         #[cfg(feature = "hydrate")]
@@ -33,16 +45,100 @@ pub fn FavoritesPage() -> impl IntoView {
         }
     });
 
+    let boards_resource = Resource::new(
+        move || (auth_token.get(), boards_version.get()),
+        move |(token, _)| async move {
+            match token {
+                Some(token) => list_collections(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+    let boards = Signal::derive(move || boards_resource.get().unwrap_or_default());
+
     let favorites_resource = Resource::new(
-        move || auth_token.get(),
-        move |token| async move {
+        move || {
+            let board = selected_board.get();
+            // Only a board's own view changes when posts are filed
+            let version = board.map(|_| boards_version.get());
+            (auth_token.get(), board, version)
+        },
+        move |(token, board, _)| async move {
             match token {
-                Some(token) => get_user_favorites_with_details(token).await.ok(),
+                Some(token) => list_favorites(token, board).await.ok(),
                 None => None,
             }
         },
     );
 
+    let on_create_board = move |_| {
+        let Some(token) = auth_token.get_untracked() else {
+            return;
+        };
+        let name = new_board_name.get_untracked();
+        if name.trim().is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            match create_collection(token, name).await {
+                Ok(id) => {
+                    new_board_name.set(String::new());
+                    board_error.set(None);
+                    selected_board.set(Some(id));
+                    boards_version.update(|v| *v += 1);
+                }
+                Err(e) => board_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let on_rename_board = move |_| {
+        let (Some(token), Some(board_id)) =
+            (auth_token.get_untracked(), selected_board.get_untracked())
+        else {
+            return;
+        };
+        let name = rename_value.get_untracked();
+        spawn_local(async move {
+            match rename_collection(token, board_id, name).await {
+                Ok(()) => {
+                    is_renaming.set(false);
+                    board_error.set(None);
+                    boards_version.update(|v| *v += 1);
+                }
+                Err(e) => board_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let on_delete_board = move |_| {
+        let (Some(token), Some(board_id)) =
+            (auth_token.get_untracked(), selected_board.get_untracked())
+        else {
+            return;
+        };
+        spawn_local(async move {
+            match delete_collection(token, board_id).await {
+                Ok(()) => {
+                    board_error.set(None);
+                    selected_board.set(None);
+                    boards_version.update(|v| *v += 1);
+                }
+                Err(e) => board_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let selected_board_name = move || {
+        selected_board.get().and_then(|id| {
+            boards
+                .get()
+                .into_iter()
+                .find(|board| board.id == id)
+                .map(|board| board.name)
+        })
+    };
+
     view! {
         <div class="favorites-page">
             <div class="favorites-container">
@@ -51,6 +147,86 @@ pub fn FavoritesPage() -> impl IntoView {
                     <p class="favorites-subtitle">"Your saved tattoo inspiration"</p>
                 </div>
 
+                <div class="favorites-boards">
+                    <button
+                        class=move || if selected_board.get().is_none() { "board-chip active" } else { "board-chip" }
+                        on:click=move |_| {
+                            is_renaming.set(false);
+                            selected_board.set(None);
+                        }
+                    >
+                        "All favorites"
+                    </button>
+                    <For
+                        each=move || boards.get()
+                        key=|board| (board.id, board.name.clone(), board.item_count)
+                        children=move |board| {
+                            let board_id = board.id;
+                            view! {
+                                <button
+                                    class=move || if selected_board.get() == Some(board_id) { "board-chip active" } else { "board-chip" }
+                                    on:click=move |_| {
+                                        is_renaming.set(false);
+                                        selected_board.set(Some(board_id));
+                                    }
+                                >
+                                    {board.name}
+                                    <span class="board-chip__count">{board.item_count}</span>
+                                </button>
+                            }
+                        }
+                    />
+                    <div class="favorites-boards__new">
+                        <input
+                            type="text"
+                            placeholder="New board"
+                            maxlength="60"
+                            prop:value=move || new_board_name.get()
+                            on:input=move |ev| new_board_name.set(event_target_value(&ev))
+                        />
+                        <button class="board-create" on:click=on_create_board>"Create"</button>
+                    </div>
+                </div>
+
+                {move || board_error.get().map(|error| view! {
+                    <p class="favorites-boards__error">{error}</p>
+                })}
+
+                {move || selected_board_name().map(|name| view! {
+                    <div class="favorites-board-header">
+                        <Show
+                            when=move || is_renaming.get()
+                            fallback=move || {
+                                let name = name.clone();
+                                view! {
+                                    <h2>{name.clone()}</h2>
+                                    <button
+                                        class="board-action"
+                                        on:click=move |_| {
+                                            rename_value.set(name.clone());
+                                            is_renaming.set(true);
+                                        }
+                                    >
+                                        "Rename"
+                                    </button>
+                                    <button class="board-action board-action--danger" on:click=on_delete_board>
+                                        "Delete board"
+                                    </button>
+                                }
+                            }
+                        >
+                            <input
+                                type="text"
+                                maxlength="60"
+                                prop:value=move || rename_value.get()
+                                on:input=move |ev| rename_value.set(event_target_value(&ev))
+                            />
+                            <button class="board-action" on:click=on_rename_board>"Save"</button>
+                            <button class="board-action" on:click=move |_| is_renaming.set(false)>"Cancel"</button>
+                        </Show>
+                    </div>
+                })}
+
                 <Suspense fallback=move || {
                     view! {
                         <div class="favorites-loading">
@@ -80,7 +256,24 @@ pub fn FavoritesPage() -> impl IntoView {
 
                                     view! {
                                         <div class="favorites-grid-wrapper">
-                                            <ShopMasonryGallery shop_posts=posts all_styles=vec![] />
+                                            <ShopMasonryGallery
+                                                shop_posts=posts
+                                                all_styles=vec![]
+                                                collections=boards
+                                                on_collections_changed=Callback::new(move |_| {
+                                                    boards_version.update(|v| *v += 1);
+                                                })
+                                            />
+                                        </div>
+                                    }
+                                    .into_any()
+                                }
+                                _ if selected_board.get_untracked().is_some() => {
+                                    view! {
+                                        <div class="favorites-empty">
+                                            <div class="empty-icon">"📌"</div>
+                                            <h2>"This board is empty"</h2>
+                                            <p>"Use \"Save to board\" on any of your favorites to add it here."</p>
                                         </div>
                                     }
                                     .into_any()
@@ -0,0 +1,200 @@
+use crate::components::ClientBookingModal;
+use crate::db::entities::FlashDesign;
+use crate::server::get_all_styles_with_counts;
+use crate::server_flash_designs::{browse_flash, get_flash_cities, get_flash_design};
+use crate::utils::auth::is_authenticated;
+use leptos::prelude::*;
+use leptos_router::components::A;
+use leptos_router::hooks::{use_navigate, use_query_map};
+
+/// Flash clients can claim, filtered by style and city. Claiming a design
+/// opens a booking request for it; signed-out clients sign in first and come
+/// back to `/flash?claim=<id>`.
+#[component]
+pub fn FlashPage() -> impl IntoView {
+    let navigate = use_navigate();
+    let query = use_query_map();
+
+    let selected_styles = RwSignal::new(Vec::<i32>::new());
+    let city = RwSignal::new(String::new());
+
+    let show_booking_modal = RwSignal::new(false);
+    let booking_artist_id = RwSignal::new(None::<i32>);
+    let claiming = RwSignal::new(None::<FlashDesign>);
+
+    let styles_resource = Resource::new(
+        || (),
+        |_| async move { get_all_styles_with_counts().await.unwrap_or_default() },
+    );
+    let cities_resource = Resource::new(
+        || (),
+        |_| async move { get_flash_cities().await.unwrap_or_default() },
+    );
+    let flash_resource = Resource::new(
+        move || (selected_styles.get(), city.get()),
+        move |(style_ids, city)| async move { browse_flash(style_ids, city).await.unwrap_or_default() },
+    );
+
+    let open_claim = move |design: FlashDesign| {
+        booking_artist_id.set(Some(design.artist_id));
+        claiming.set(Some(design));
+        show_booking_modal.set(true);
+    };
+
+    let claim = move |design: FlashDesign| {
+        if is_authenticated() {
+            open_claim(design);
+        } else {
+            let return_url = format!("/flash?claim={}", design.id);
+            let login_url = format!("/login?return_url={}", urlencoding::encode(&return_url));
+            navigate(&login_url, Default::default());
+        }
+    };
+
+    // Back from signing in to claim a design
+    let claim_resource = Resource::new(
+        move || {
+            query
+                .read()
+                .get("claim")
+                .and_then(|id| id.parse::<i32>().ok())
+        },
+        |flash_design_id| async move {
+            match flash_design_id {
+                Some(id) => get_flash_design(id).await.ok(),
+                None => None,
+            }
+        },
+    );
+    Effect::new(move |_| {
+        if let Some(Some(design)) = claim_resource.get() {
+            if design.available && is_authenticated() {
+                open_claim(design);
+            }
+        }
+    });
+
+    let toggle_style = move |style_id: i32| {
+        selected_styles.update(|styles| {
+            if let Some(index) = styles.iter().position(|id| *id == style_id) {
+                styles.remove(index);
+            } else {
+                styles.push(style_id);
+            }
+        });
+    };
+
+    view! {
+        <div class="flash-page">
+            <div class="flash-page-container">
+                <div class="flash-page-header">
+                    <h1>"Flash"</h1>
+                    <p class="flash-page-subtitle">
+                        "Ready-to-tattoo designs at a fixed price. Claim one and pick a time with the artist."
+                    </p>
+                </div>
+
+                <div class="flash-filters">
+                    <select
+                        class="flash-city-filter"
+                        prop:value=move || city.get()
+                        on:change=move |ev| city.set(event_target_value(&ev))
+                    >
+                        <option value="">"Any city"</option>
+                        <Suspense fallback=|| ()>
+                            {move || cities_resource.get().map(|cities| cities.into_iter().map(|name| view! {
+                                <option value=name.clone()>{name.clone()}</option>
+                            }).collect_view())}
+                        </Suspense>
+                    </select>
+                    <div class="flash-style-filters">
+                        <Suspense fallback=|| ()>
+                            {move || styles_resource.get().map(|styles| styles.into_iter().map(|style| {
+                                let style_id = style.id;
+                                view! {
+                                    <button
+                                        class="flash-style-chip"
+                                        class:flash-style-chip-selected=move || selected_styles.get().contains(&style_id)
+                                        on:click=move |_| toggle_style(style_id)
+                                    >
+                                        {style.name}
+                                    </button>
+                                }
+                            }).collect_view())}
+                        </Suspense>
+                    </div>
+                </div>
+
+                <Suspense fallback=|| view! { <div class="loading">"Loading flash..."</div> }>
+                    {move || {
+                        let claim = claim.clone();
+                        flash_resource.get().map(move |designs| {
+                            if designs.is_empty() {
+                                return view! {
+                                    <p class="flash-empty">"No flash matches those filters yet."</p>
+                                }.into_any();
+                            }
+                            view! {
+                                <div class="flash-grid">
+                                    {designs.into_iter().map(|design| {
+                                        let location = [design.city.clone(), design.state.clone()]
+                                            .into_iter()
+                                            .flatten()
+                                            .collect::<Vec<_>>()
+                                            .join(", ");
+                                        let claimed = design.clone();
+                                        let claim = claim.clone();
+                                        view! {
+                                            <div class="flash-card">
+                                                <img src=design.thumbnail_url.clone() alt=design.title.clone() loading="lazy" />
+                                                <div class="flash-card-body">
+                                                    <div class="flash-card-header">
+                                                        <h3>{design.title.clone()}</h3>
+                                                        <span class="flash-card-price">{format!("${:.2}", design.price)}</span>
+                                                    </div>
+                                                    <p class="flash-card-artist">
+                                                        <A href=format!("/artist/{}", design.artist_id)>
+                                                            {design.artist_name.clone().unwrap_or_else(|| "Artist".to_string())}
+                                                        </A>
+                                                        {(!location.is_empty()).then(|| format!(" · {}", location))}
+                                                    </p>
+                                                    <p class="flash-card-details">
+                                                        {design.size_inches.map(|size| format!("{}\" · ", size))}
+                                                        {if design.repeatable { "Repeatable" } else { "One of a kind" }}
+                                                    </p>
+                                                    {design.description.clone().map(|description| view! {
+                                                        <p class="flash-card-description">{description}</p>
+                                                    })}
+                                                    {(!design.style_names.is_empty()).then(|| view! {
+                                                        <p class="flash-card-styles">{design.style_names.join(", ")}</p>
+                                                    })}
+                                                    <button
+                                                        class="flash-card-claim"
+                                                        on:click=move |_| claim(claimed.clone())
+                                                    >
+                                                        "Claim This Flash"
+                                                    </button>
+                                                </div>
+                                            </div>
+                                        }
+                                    }).collect_view()}
+                                </div>
+                            }.into_any()
+                        })
+                    }}
+                </Suspense>
+            </div>
+
+            <ClientBookingModal
+                show=show_booking_modal
+                artist_id=booking_artist_id
+                flash_design=claiming
+                on_close=move || {
+                    show_booking_modal.set(false);
+                    booking_artist_id.set(None);
+                    claiming.set(None);
+                }
+            />
+        </div>
+    }
+}
@@ -0,0 +1,114 @@
+use crate::components::favorite_button::FavoriteButton;
+use crate::components::instagram_gallery_image::InstagramGalleryImage;
+use crate::components::instagram_posts_grid::{InstagramPostsGrid, PostWithArtist};
+use crate::components::loading::LoadingView;
+use crate::components::style_tag::StyleTag;
+use crate::server::{get_image_detail, get_similar_images};
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos_router::components::A;
+use leptos_router::hooks::use_params_map;
+
+/// Recommendations loaded under an image
+const SIMILAR_IMAGES_SHOWN: i64 = 12;
+
+/// A single portfolio image with similar work from other artists below it
+#[component]
+pub fn ImageDetailPage() -> impl IntoView {
+    let params = use_params_map();
+    let image_id = Memo::new(move |_| {
+        params
+            .read()
+            .get("id")
+            .and_then(|id| id.parse::<i32>().ok())
+            .unwrap_or(0)
+    });
+
+    let image = Resource::new(
+        move || image_id.get(),
+        |id| async move { get_image_detail(id, get_auth_token()).await.ok().flatten() },
+    );
+    let similar = Resource::new(
+        move || image_id.get(),
+        |id| async move {
+            get_similar_images(id, SIMILAR_IMAGES_SHOWN, get_auth_token())
+                .await
+                .unwrap_or_default()
+        },
+    );
+
+    view! {
+        <div class="image-detail-page">
+            <Suspense fallback=move || view! {
+                <LoadingView message=Some("Loading tattoo...".to_string()) />
+            }>
+                {move || image.get().map(|image| match image {
+                    Some((image, styles, artist, is_favorited)) => view! {
+                        <div class="image-detail">
+                            <div class="image-detail__embed">
+                                <InstagramGalleryImage
+                                    short_code=image.short_code.clone()
+                                    sizes="(min-width: 768px) 50vw, 100vw"
+                                />
+                            </div>
+                            <div class="image-detail__info">
+                                <div class="image-detail__header">
+                                    <A href=format!("/artist/{}", artist.id)>
+                                        {artist.name.clone().unwrap_or_else(|| "Unknown Artist".to_string())}
+                                    </A>
+                                    <FavoriteButton artists_images_id=image.id is_favorited_initial=is_favorited />
+                                </div>
+                                <div class="image-detail__styles">
+                                    {styles.into_iter().map(|style| view! {
+                                        <StyleTag name=style.name />
+                                    }).collect_view()}
+                                </div>
+                            </div>
+                        </div>
+                    }
+                    .into_any(),
+                    None => view! {
+                        <div class="image-detail-missing">
+                            <h2>"This tattoo isn't available"</h2>
+                            <p>"It may have been removed from the artist's portfolio."</p>
+                        </div>
+                    }
+                    .into_any(),
+                })}
+            </Suspense>
+
+            <section class="image-detail-similar">
+                <h2>"More like this"</h2>
+                <Suspense fallback=|| view! { <div class="loading-spinner"></div> }>
+                    {move || similar.get().map(|images| {
+                        if images.is_empty() {
+                            view! {
+                                <p class="image-detail-similar__empty">
+                                    "No similar work yet."
+                                </p>
+                            }
+                            .into_any()
+                        } else {
+                            let posts: Vec<PostWithArtist> = images
+                                .into_iter()
+                                .map(|(image, styles, artist, is_favorited)| PostWithArtist {
+                                    image,
+                                    styles,
+                                    artist: Some(artist),
+                                    is_favorited,
+                                })
+                                .collect();
+                            view! {
+                                <InstagramPostsGrid
+                                    posts=posts
+                                    filter_id=format!("similar-{}", image_id.get_untracked())
+                                />
+                            }
+                            .into_any()
+                        }
+                    })}
+                </Suspense>
+            </section>
+        </div>
+    }
+}
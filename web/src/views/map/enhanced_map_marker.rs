@@ -1,3 +1,4 @@
+use crate::components::ExperienceBadge;
 use crate::server::EnhancedLocationInfo;
 use leptos::prelude::*;
 use leptos_leaflet::prelude::*;
@@ -131,6 +132,10 @@ pub fn EnhancedMapPopup(
                                         </div>
                                         <div class="artist-info">
                                             <span class="artist-name">{artist.artist_name}</span>
+                                            <ExperienceBadge tier=artist.experience_tier />
+                                            {artist.guest_until.map(|until| view! {
+                                                <span class="artist-guest-spot">{format!("Guest spot until {}", until)}</span>
+                                            })}
                                             {if let Some(style) = artist.primary_style {
                                                 view! { <span class="artist-style">{style}</span> }.into_any()
                                             } else {
@@ -22,6 +22,9 @@ pub fn MapRenderer(
     cities: Resource<Result<Vec<CityCoords>, ServerFnError>>,
     selected_styles: RwSignal<Vec<i32>>,
     map_bounds: RwSignal<MapBounds>,
+    /// Experience tiers to show shops for; every shop when empty or unset
+    #[prop(optional)]
+    selected_tiers: Option<RwSignal<Vec<String>>>,
 ) -> impl IntoView {
     let selected_city_coords = RwSignal::new(default_location.clone());
 
@@ -39,6 +42,7 @@ pub fn MapRenderer(
         let current_city = city.get();
         let current_bounds = map_bounds.get();
         let current_styles = selected_styles.get();
+        let current_tiers = selected_tiers.map(|tiers| tiers.get()).unwrap_or_default();
 
         // Only fetch if we have valid bounds (not the default 0,0)
         if current_bounds.north_east.lat == 0.0 && current_bounds.south_west.lat == 0.0 {
@@ -63,6 +67,12 @@ pub fn MapRenderer(
             } else {
                 Some(current_styles)
             },
+            None,
+            if current_tiers.is_empty() {
+                None
+            } else {
+                Some(current_tiers)
+            },
         )
         .await
     });
@@ -149,6 +149,8 @@ pub fn MapRendererV2(
             } else {
                 Some(current_styles)
             },
+            None,
+            None,
         )
         .await
     });
@@ -8,6 +8,7 @@ use crate::{
         get_available_styles, get_cities, get_location_stats, get_styles_in_bounds,
         search_by_postal_code, LocationStats, StyleWithCount,
     },
+    utils::experience::{tier_label, EXPERIENCE_TIERS},
     views::map::{
         drop_down_cities::DropDownCities, drop_down_states::DropDownStates,
         map_renderer::MapRenderer,
@@ -32,6 +33,7 @@ pub fn DiscoveryMap() -> impl IntoView {
 
     // New state for enhanced features
     let selected_styles = RwSignal::new(Vec::<i32>::new());
+    let selected_tiers = RwSignal::new(Vec::<String>::new());
     // Initialize sidebar as visible (not collapsed)
     let sidebar_collapsed = RwSignal::new(false);
     let map_center = RwSignal::new(default_location.clone());
@@ -75,6 +77,7 @@ pub fn DiscoveryMap() -> impl IntoView {
 
     let clear_filters = move |_ev: web_sys::MouseEvent| {
         selected_styles.set(Vec::new());
+        selected_tiers.set(Vec::new());
     };
 
     view! {
@@ -231,12 +234,41 @@ pub fn DiscoveryMap() -> impl IntoView {
                             </Suspense>
                         </div>
 
+                        // Experience filters; apprentices and junior artists
+                        // usually book at lower prices
+                        <div class="filter-section experience-filters">
+                            <h3>"Experience"</h3>
+                            <div class="explore-filter-chip-grid">
+                                {EXPERIENCE_TIERS.iter().map(|tier| {
+                                    let tier = *tier;
+                                    view! {
+                                        <button
+                                            class="explore-filter-chip"
+                                            class:explore-filter-chip-selected=move || selected_tiers.get().iter().any(|t| t == tier)
+                                            on:click=move |_| {
+                                                selected_tiers.update(|tiers| {
+                                                    if let Some(index) = tiers.iter().position(|t| t == tier) {
+                                                        tiers.remove(index);
+                                                    } else {
+                                                        tiers.push(tier.to_string());
+                                                    }
+                                                });
+                                            }
+                                        >
+                                            <span class="explore-filter-chip-name">{tier_label(tier)}</span>
+                                        </button>
+                                    }
+                                }).collect_view()}
+                            </div>
+                            <p class="explore-filter-hint">"Apprentices and junior artists often charge less."</p>
+                        </div>
+
                         <button
                             class="explore-clear-filters"
                             on:click=clear_filters
-                            disabled=move || selected_styles.get().is_empty()
+                            disabled=move || selected_styles.get().is_empty() && selected_tiers.get().is_empty()
                         >
-                            "Clear Style Filters"
+                            "Clear Filters"
                         </button>
                     </div>
                 </div>
@@ -251,6 +283,7 @@ pub fn DiscoveryMap() -> impl IntoView {
                         cities=cities
                         selected_styles=selected_styles
                         map_bounds=map_bounds
+                        selected_tiers=selected_tiers
                     />
 
                     // Map legend
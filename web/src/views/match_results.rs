@@ -1,22 +1,34 @@
 use leptos::prelude::*;
 use leptos::task::spawn_local;
 use leptos_router::components::A;
-use leptos_router::hooks::use_query_map;
+use leptos_router::hooks::{use_location, use_query_map};
+use shared_types::StyleFilter;
 use thaw::*;
 
 use crate::{
     components::{
         artist_cta::ArtistCTA,
+        experience_badge::ExperienceBadge,
         instagram_embed::{InstagramEmbed, InstagramEmbedSize},
         loading::LoadingView,
-        TattooGallery,
+        ShareButton, TattooGallery,
     },
-    server::{get_matched_artists, get_tattoo_posts_by_style, MatchedArtist, TattooPost},
+    server::{
+        get_matched_artists, get_tattoo_posts_by_style, log_match_impression, MatchedArtist,
+        TattooPost,
+    },
+    server_quiz::get_quiz_session,
+    utils::{auth::get_auth_token, matching::MatchFactor},
+    views::quiz::stored_quiz_session,
 };
 
+/// Quiz matches listed above the gallery
+const TOP_MATCHES_SHOWN: usize = 6;
+
 #[component]
 pub fn MatchResults() -> impl IntoView {
     let query_map = use_query_map();
+    let location = use_location();
 
     // Modal state
     let (show_modal, set_show_modal) = signal(false);
@@ -68,10 +80,53 @@ pub fn MatchResults() -> impl IntoView {
             #[cfg(not(feature = "hydrate"))]
             let token = None;
 
-            get_tattoo_posts_by_style(styles, states, cities, token).await
+            get_tattoo_posts_by_style(StyleFilter::any(styles), states, cities, token).await
         },
     );
 
+    // Artists the quiz matched, saved with this browser's quiz session. Only
+    // shown when the page is for the styles that session picked.
+    let quiz_matches = Resource::new(
+        move || query_map.get(),
+        move |query| async move {
+            let session_key = stored_quiz_session()?;
+            let session = get_quiz_session(session_key, get_auth_token())
+                .await
+                .ok()
+                .flatten()?;
+
+            let normalize = |styles: Vec<String>| {
+                let mut styles: Vec<String> = styles
+                    .iter()
+                    .map(|style| style.trim().to_lowercase())
+                    .filter(|style| !style.is_empty())
+                    .collect();
+                styles.sort();
+                styles
+            };
+            let requested = normalize(
+                query
+                    .get("styles")
+                    .map(|s| s.split(',').map(str::to_string).collect())
+                    .unwrap_or_default(),
+            );
+            (requested == normalize(session.answers.styles) && !session.matches.is_empty())
+                .then_some(session.matches)
+        },
+    );
+
+    // Counted towards the artists' insights: each top match shown is a view,
+    // opening one a click
+    Effect::new(move |_| {
+        if let Some(Some(matches)) = quiz_matches.get() {
+            for artist in matches.into_iter().take(TOP_MATCHES_SHOWN) {
+                spawn_local(async move {
+                    let _ = log_match_impression(None, artist.id, "view".to_string()).await;
+                });
+            }
+        }
+    });
+
     // Fetch full artist data when artist is clicked
     let load_artist_details = move |artist_id: i64| {
         spawn_local(async move {
@@ -92,6 +147,9 @@ pub fn MatchResults() -> impl IntoView {
                 years_experience: Some(5),
                 min_price: Some(150.0),
                 max_price: Some(400.0),
+                explanation: Default::default(),
+                response_time_label: None,
+                experience_tier: None,
             };
             set_selected_artist.set(Some(matched_artist));
             set_show_modal.set(true);
@@ -110,8 +168,53 @@ pub fn MatchResults() -> impl IntoView {
                 <p class="subtitle">
                     "Discover amazing tattoo work in your selected style"
                 </p>
+                <ShareButton
+                    target_type="search"
+                    target=Signal::derive(move || {
+                        let search = location.search.get();
+                        match search.trim_start_matches('?') {
+                            "" => location.pathname.get(),
+                            query => format!("{}?{}", location.pathname.get(), query),
+                        }
+                    })
+                />
             </div>
 
+            <Suspense fallback=|| ()>
+                {move || quiz_matches.get().flatten().map(|matches| view! {
+                    <div class="match-results-top-matches">
+                        <h2>"Your Top Matches"</h2>
+                        <div class="match-results-top-matches-list">
+                            {matches.into_iter().take(TOP_MATCHES_SHOWN).map(|artist| {
+                                let name = artist.name.clone();
+                                let place = format!("{}, {}", artist.city, artist.state);
+                                let score = format!("{}%", artist.match_score);
+                                let summary = artist.explanation.summary();
+                                view! {
+                                    <button
+                                        class="match-results-top-match"
+                                        on:click=move |_| {
+                                            let artist_id = artist.id;
+                                            spawn_local(async move {
+                                                let _ = log_match_impression(None, artist_id, "click".to_string()).await;
+                                            });
+                                            on_artist_click.run(artist.clone());
+                                        }
+                                    >
+                                        <span class="match-results-top-match-score">{score}</span>
+                                        <span class="match-results-top-match-name">{name}</span>
+                                        <span class="match-results-top-match-place">{place}</span>
+                                        {summary.map(|summary| view! {
+                                            <span class="match-results-top-match-reason">{summary}</span>
+                                        })}
+                                    </button>
+                                }
+                            }).collect_view()}
+                        </div>
+                    </div>
+                })}
+            </Suspense>
+
             <Suspense fallback=move || view! {
                 <LoadingView message=Some("Loading tattoo gallery...".to_string()) />
             }>
@@ -208,7 +311,11 @@ fn ArtistModalContent(artist: MatchedArtist) -> impl IntoView {
                     </div>
                     <div class="match-results-modal-artist-details">
                         <h2>{artist.name.clone()}</h2>
+                        <ExperienceBadge tier=artist.experience_tier.clone() />
                         <p>"📍 " {format!("{}, {}", artist.city, artist.state)}</p>
+                        {artist.response_time_label.clone().map(|label| view! {
+                            <span class="response-time-badge">"⚡ " {label}</span>
+                        })}
                     </div>
                     <div class="match-results-modal-match-score">
                         <div class="score">{format!("{}%", artist.match_score)}</div>
@@ -278,6 +385,7 @@ fn ArtistModalContent(artist: MatchedArtist) -> impl IntoView {
                             <div class="pricing-value">
                                 {match (artist.min_price, artist.max_price) {
                                     (Some(min), Some(max)) => format!("${} - ${}", min as i32, max as i32),
+                                    (Some(min), None) => format!("From ${}", min as i32),
                                     _ => "Contact for quote".to_string()
                                 }}
                             </div>
@@ -292,16 +400,47 @@ fn ArtistModalContent(artist: MatchedArtist) -> impl IntoView {
                         <span class="reason-icon">"✅"</span>
                         <span class="reason-title">"Why we matched you"</span>
                     </div>
-                    <div class="reason-text">
-                        "This artist specializes in " <strong>{artist.primary_style.clone()}</strong>
-                        " and " {
-                            match artist.years_experience {
-                                Some(0) => "is new to the platform".to_string(),
-                                Some(years) => format!("has {} years of experience", years),
-                                None => "has experience in this style".to_string()
-                            }
-                        } " with a " <strong>{format!("{:.1}⭐", artist.avg_rating)}</strong> " rating."
-                    </div>
+                    {match artist.explanation.summary() {
+                        Some(summary) => view! {
+                            <div class="reason-text">{summary}</div>
+                        }.into_any(),
+                        None => view! {
+                            <div class="reason-text">
+                                "This artist specializes in " <strong>{artist.primary_style.clone()}</strong>
+                                " and " {
+                                    match artist.years_experience {
+                                        Some(0) => "is new to the platform".to_string(),
+                                        Some(years) => format!("has {} years of experience", years),
+                                        None => "has experience in this style".to_string()
+                                    }
+                                } " with a " <strong>{format!("{:.1}⭐", artist.avg_rating)}</strong> " rating."
+                            </div>
+                        }.into_any(),
+                    }}
+                    <ul class="score-breakdown">
+                        {artist.explanation.components.iter().filter_map(|component| {
+                            let label = MatchFactor::parse(&component.factor)?.label();
+                            let percent = if component.max_points > 0 {
+                                component.points * 100 / component.max_points
+                            } else {
+                                0
+                            };
+                            Some(view! {
+                                <li class="score-breakdown-item">
+                                    <span class="score-breakdown-label">{label}</span>
+                                    <span class="score-breakdown-bar">
+                                        <span
+                                            class="score-breakdown-fill"
+                                            style=format!("width: {}%", percent)
+                                        ></span>
+                                    </span>
+                                    <span class="score-breakdown-points">
+                                        {format!("{}/{}", component.points, component.max_points)}
+                                    </span>
+                                </li>
+                            })
+                        }).collect_view()}
+                    </ul>
                 </div>
             </div>
 
@@ -1,4 +1,10 @@
+pub mod account;
+pub mod admin_artist_questions;
+pub mod admin_booking_restores;
 pub mod admin_dashboard;
+pub mod admin_data_quality;
+pub mod admin_errors;
+pub mod admin_licenses;
 pub mod admin_login;
 pub mod admin_validate_artists;
 pub mod admin_validate_posts;
@@ -8,13 +14,19 @@ pub mod artist_login_prompt;
 pub mod auth;
 pub mod booking;
 pub mod booking_confirmation;
+pub mod client_dashboard;
 pub mod favorites;
+pub mod flash;
 pub mod home;
+pub mod image_detail;
 pub mod instagram_demo;
 pub mod map;
 pub mod match_results;
+pub mod my_tattoos;
 pub mod not_found;
 pub mod quiz;
 pub mod shop;
+pub mod shop_dashboard;
 pub mod styles;
 pub mod subscription_tiers;
+pub mod team_invite;
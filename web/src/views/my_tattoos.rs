@@ -0,0 +1,327 @@
+use crate::db::entities::ClientTattooPhoto;
+use crate::server_tattoo_photos::{
+    delete_tattoo_photo, get_my_tattoo_bookings, get_my_tattoo_photos, get_shared_tattoo_photo,
+    update_tattoo_photo,
+};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::components::A;
+use leptos_router::hooks::{use_params_map, use_query_map};
+
+#[component]
+pub fn MyTattoosPage() -> impl IntoView {
+    let auth_token = RwSignal::new(None::<String>);
+    // Bumped whenever photos change
+    let photos_version = RwSignal::new(0u32);
+    let photo_error = RwSignal::new(None::<String>);
+
+    // Load token on mount
+    Effect::new(move |_| {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn getItem(key: &str) -> Option<String>;
+            }
+
+            if let Some(token) = getItem("tatteau_auth_token") {
+                auth_token.set(Some(token));
+            } else if let Some(window) = web_sys::window() {
+                let _ = window.location().set_href("/login?redirect=/my-tattoos");
+            }
+        }
+    });
+
+    let photos_resource = Resource::new(
+        move || (auth_token.get(), photos_version.get()),
+        move |(token, _)| async move {
+            match token {
+                Some(token) => get_my_tattoo_photos(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+    let bookings_resource = Resource::new(
+        move || auth_token.get(),
+        move |token| async move {
+            match token {
+                Some(token) => get_my_tattoo_bookings(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+
+    let query = use_query_map();
+    let upload_notice = move || {
+        let query = query.get();
+        if let Some(error) = query.get("photo_error") {
+            Some(("error-message", format!("Upload failed: {}", error)))
+        } else if query.get("photo").as_deref() == Some("uploaded") {
+            Some(("success-message", "Photo added to your gallery".to_string()))
+        } else {
+            None
+        }
+    };
+
+    let on_result = move |result: Result<(), ServerFnError>| match result {
+        Ok(()) => {
+            photo_error.set(None);
+            photos_version.update(|v| *v += 1);
+        }
+        Err(e) => photo_error.set(Some(e.to_string())),
+    };
+
+    let save_photo = move |photo: ClientTattooPhoto| {
+        let Some(token) = auth_token.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            let result = update_tattoo_photo(
+                token,
+                photo.id,
+                photo.caption,
+                photo.healed,
+                photo.is_public,
+                photo.healed_consent,
+            )
+            .await;
+            on_result(result.map(|_| ()));
+        });
+    };
+
+    let delete_photo = move |photo_id: i64| {
+        let Some(token) = auth_token.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(delete_tattoo_photo(token, photo_id).await);
+        });
+    };
+
+    view! {
+        <div class="my-tattoos-page">
+            <div class="my-tattoos-container">
+                <div class="my-tattoos-header">
+                    <h1>"My Tattoos"</h1>
+                    <p class="my-tattoos-subtitle">
+                        "A private gallery of your ink. Only you can see these photos unless you choose to share them."
+                    </p>
+                </div>
+
+                {move || upload_notice().map(|(class, message)| view! {
+                    <div class=class>{message}</div>
+                })}
+                {move || photo_error.get().map(|error| view! {
+                    <div class="error-message">{error}</div>
+                })}
+
+                <form
+                    class="my-tattoos-upload"
+                    method="post"
+                    action="/api/my-tattoos"
+                    enctype="multipart/form-data"
+                >
+                    <input type="hidden" name="token" prop:value=move || auth_token.get().unwrap_or_default() />
+                    <input type="file" name="file" accept="image/jpeg,image/png,image/webp,image/heic,.heic" required />
+                    <input type="text" name="caption" placeholder="Caption (optional)" maxlength="500" />
+                    <select name="booking_id">
+                        <option value="">"Not from a Tatteau booking"</option>
+                        <Suspense fallback=|| ()>
+                            {move || bookings_resource.get().map(|bookings| {
+                                bookings.into_iter().map(|booking| view! {
+                                    <option value=booking.booking_id.to_string()>
+                                        {format!(
+                                            "{} · {}",
+                                            booking.artist_name.unwrap_or_else(|| "Artist".to_string()),
+                                            booking.requested_date
+                                        )}
+                                    </option>
+                                }).collect_view()
+                            })}
+                        </Suspense>
+                    </select>
+                    <button type="submit" class="btn btn-primary">"Add Photo"</button>
+                </form>
+
+                <Suspense fallback=move || view! {
+                    <div class="my-tattoos-loading">
+                        <div class="loading-spinner"></div>
+                    </div>
+                }>
+                    {move || photos_resource.get().map(|photos| {
+                        if photos.is_empty() {
+                            return view! {
+                                <div class="my-tattoos-empty">
+                                    <h2>"No photos yet"</h2>
+                                    <p>"Add photos of your tattoos, fresh or healed, to keep them in one place."</p>
+                                </div>
+                            }
+                            .into_any();
+                        }
+
+                        view! {
+                            <div class="my-tattoos-grid">
+                                {photos.into_iter().map(|photo| view! {
+                                    <TattooPhotoCard photo=photo on_save=save_photo on_delete=delete_photo />
+                                }).collect_view()}
+                            </div>
+                        }
+                        .into_any()
+                    })}
+                </Suspense>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn TattooPhotoCard(
+    photo: ClientTattooPhoto,
+    on_save: impl Fn(ClientTattooPhoto) + Copy + Send + Sync + 'static,
+    on_delete: impl Fn(i64) + Copy + Send + Sync + 'static,
+) -> impl IntoView {
+    let photo_id = photo.id;
+    let has_artist = photo.artist_id.is_some();
+    let caption = RwSignal::new(photo.caption.clone().unwrap_or_default());
+    let healed = RwSignal::new(photo.healed);
+    let is_public = RwSignal::new(photo.is_public);
+    let healed_consent = RwSignal::new(photo.healed_consent);
+
+    let saved = StoredValue::new(photo.clone());
+    let save = move || {
+        let mut photo = saved.get_value();
+        photo.caption = Some(caption.get_untracked());
+        photo.healed = healed.get_untracked();
+        photo.is_public = is_public.get_untracked();
+        // Consent only applies to healed photos
+        photo.healed_consent = photo.healed && healed_consent.get_untracked();
+        on_save(photo);
+    };
+
+    view! {
+        <div class="my-tattoos-card">
+            <a href=photo.image_url.clone() target="_blank">
+                <img src=photo.thumbnail_url.clone() alt=photo.caption.clone().unwrap_or_default() loading="lazy" />
+            </a>
+            <input
+                type="text"
+                placeholder="Caption"
+                maxlength="500"
+                prop:value=move || caption.get()
+                on:input=move |ev| caption.set(event_target_value(&ev))
+                on:change=move |_| save()
+            />
+            {match (photo.artist_id, photo.artist_name.clone()) {
+                (Some(artist_id), name) => view! {
+                    <p class="my-tattoos-card__artist">
+                        "By "
+                        <A href=format!("/artist/{}", artist_id)>
+                            {name.unwrap_or_else(|| "your artist".to_string())}
+                        </A>
+                    </p>
+                }
+                .into_any(),
+                (None, _) => view! { <p class="my-tattoos-card__artist">{photo.created_at.clone()}</p> }.into_any(),
+            }}
+
+            <label class="my-tattoos-card__option">
+                <input
+                    type="checkbox"
+                    prop:checked=move || healed.get()
+                    on:change=move |ev| {
+                        healed.set(event_target_checked(&ev));
+                        save();
+                    }
+                />
+                "Healed"
+            </label>
+            <label class="my-tattoos-card__option">
+                <input
+                    type="checkbox"
+                    prop:checked=move || is_public.get()
+                    on:change=move |ev| {
+                        is_public.set(event_target_checked(&ev));
+                        save();
+                    }
+                />
+                "Share publicly"
+            </label>
+            <Show when=move || is_public.get()>
+                <A href=format!("/tattoo/{}", photo_id) attr:class="my-tattoos-card__link">
+                    "View shared page"
+                </A>
+            </Show>
+            <Show when=move || has_artist && healed.get()>
+                <label class="my-tattoos-card__option">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || healed_consent.get()
+                        on:change=move |ev| {
+                            healed_consent.set(event_target_checked(&ev));
+                            save();
+                        }
+                    />
+                    "Let my artist show this in their healed work"
+                </label>
+            </Show>
+
+            <button class="btn btn-outline-danger" on:click=move |_| on_delete(photo_id)>
+                "Delete"
+            </button>
+        </div>
+    }
+}
+
+/// A photo a client has shared publicly, crediting the artist
+#[component]
+pub fn SharedTattooPage() -> impl IntoView {
+    let params = use_params_map();
+    let photo_id = Memo::new(move |_| {
+        params
+            .read()
+            .get("id")
+            .and_then(|id| id.parse::<i64>().ok())
+            .unwrap_or(0)
+    });
+
+    let photo = Resource::new(
+        move || photo_id.get(),
+        |id| async move { get_shared_tattoo_photo(id).await.ok().flatten() },
+    );
+
+    view! {
+        <div class="shared-tattoo-page">
+            <Suspense fallback=|| view! { <div class="loading-spinner"></div> }>
+                {move || photo.get().map(|photo| match photo {
+                    Some(photo) => view! {
+                        <figure class="shared-tattoo">
+                            <img src=photo.image_url alt=photo.caption.clone().unwrap_or_default() />
+                            <figcaption>
+                                {photo.caption.map(|caption| view! { <p>{caption}</p> })}
+                                {photo.artist_id.map(|artist_id| view! {
+                                    <p class="shared-tattoo__credit">
+                                        "Tattoo by "
+                                        <A href=format!("/artist/{}", artist_id)>
+                                            {photo.artist_name.clone().unwrap_or_else(|| "this artist".to_string())}
+                                        </A>
+                                    </p>
+                                })}
+                            </figcaption>
+                        </figure>
+                    }
+                    .into_any(),
+                    None => view! {
+                        <div class="shared-tattoo-missing">
+                            <h2>"This photo isn't available"</h2>
+                            <p>"It may have been made private or removed."</p>
+                        </div>
+                    }
+                    .into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
@@ -1,15 +1,91 @@
 use crate::db::entities::CityCoords;
-use crate::server::{get_cities, get_states_list, get_styles_by_location_filter, StyleWithCount};
+use crate::server::{
+    get_cities, get_states_list, get_style_starter_packs, get_styles_by_location_filter,
+    record_quiz_style_picks, QuizStarterPacks, StarterImage, StyleWithCount,
+};
+use crate::server_quiz::{get_last_quiz_session, get_quiz_session, save_quiz_session, QuizAnswers};
+use crate::utils::auth::get_auth_token;
 use leptos::prelude::*;
+use leptos::task::spawn_local;
 use leptos_router::hooks::use_navigate;
 use std::collections::HashSet;
 use thaw::*;
 use thaw_utils::VecModel;
 
+/// localStorage key holding the visitor's quiz session, which fixes the
+/// example images variant they're shown
+const QUIZ_SESSION_KEY: &str = "tatteau_quiz_session";
+
+pub(crate) fn stored_quiz_session() -> Option<String> {
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = localStorage)]
+            fn getItem(key: &str) -> Option<String>;
+        }
+
+        getItem(QUIZ_SESSION_KEY)
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        None
+    }
+}
+
+fn store_quiz_session(_session_key: &str) {
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = localStorage)]
+            fn setItem(key: &str, value: &str);
+        }
+
+        setItem(QUIZ_SESSION_KEY, _session_key);
+    }
+}
+
 #[component]
 pub fn GetMatchedQuiz() -> impl IntoView {
     let navigate = use_navigate();
 
+    // Example images per style, loaded once on the client so the images shown
+    // are logged against the visitor's own session
+    let starter_packs = RwSignal::new(None::<QuizStarterPacks>);
+    Effect::new(move |_| {
+        spawn_local(async move {
+            let stored = stored_quiz_session();
+            match get_style_starter_packs(stored.clone()).await {
+                Ok(packs) => {
+                    if stored.as_deref() != Some(packs.session_key.as_str()) {
+                        store_quiz_session(&packs.session_key);
+                    }
+                    starter_packs.set(Some(packs));
+                }
+                Err(e) => leptos::logging::error!("Failed to load style examples: {:?}", e),
+            }
+        });
+    });
+    let style_images = move |style_id: i32| -> Vec<StarterImage> {
+        starter_packs.with(|packs| {
+            packs
+                .as_ref()
+                .and_then(|packs| {
+                    packs
+                        .packs
+                        .iter()
+                        .find(|pack| pack.style_id == style_id as i64)
+                })
+                .map(|pack| pack.images.clone())
+                .unwrap_or_default()
+        })
+    };
+
     // Location filters - state is single-select, cities are multi-select
     let selected_state = RwSignal::new(Option::<String>::None);
     let selected_cities = RwSignal::new(Vec::<String>::new());
@@ -57,7 +133,19 @@ pub fn GetMatchedQuiz() -> impl IntoView {
         },
     );
 
-    let on_submit = move |_| {
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let session_key = starter_packs.get_untracked().map(|packs| packs.session_key);
+        if let Some(session_key) = session_key.clone() {
+            let style_ids: Vec<i32> = selected_styles.get_untracked().into_iter().collect();
+            spawn_local(async move {
+                if let Err(e) = record_quiz_style_picks(session_key, style_ids).await {
+                    leptos::logging::error!("Failed to record style picks: {:?}", e);
+                }
+            });
+        }
+
         let styles_vec: Vec<String> = selected_styles
             .get()
             .into_iter()
@@ -68,30 +156,69 @@ pub fn GetMatchedQuiz() -> impl IntoView {
             })
             .collect();
 
-        let state_opt = selected_state.get();
-        let cities: Vec<String> = selected_cities.get();
+        let answers = QuizAnswers {
+            styles: styles_vec,
+            states: selected_state.get().into_iter().collect(),
+            cities: selected_cities.get(),
+        };
+        let navigate_url = answers.results_url();
 
-        // Build query parameters
-        let styles_param = styles_vec.join(",");
-        let mut query_parts = vec![format!("styles={}", urlencoding::encode(&styles_param))];
-
-        if let Some(state) = state_opt {
-            query_parts.push(format!("states={}", urlencoding::encode(&state)));
-        }
-
-        if !cities.is_empty() {
-            let cities_param = cities.join(",");
-            query_parts.push(format!("cities={}", urlencoding::encode(&cities_param)));
-        }
-
-        let navigate_url = format!("/match/results?{}", query_parts.join("&"));
-        navigate(&navigate_url, Default::default());
+        // Save the session so its matches can be reloaded, then show results
+        // whether or not that worked
+        let Some(session_key) = session_key else {
+            navigate(&navigate_url, Default::default());
+            return;
+        };
+        let navigate = navigate.clone();
+        spawn_local(async move {
+            if let Err(e) = save_quiz_session(session_key, answers, get_auth_token()).await {
+                leptos::logging::error!("Failed to save quiz session: {:?}", e);
+            }
+            navigate(&navigate_url, Default::default());
+        });
     };
 
+    // A returning client's last results: this browser's session, or for
+    // signed-in clients their latest from anywhere
+    let last_session = Resource::new(
+        || (),
+        |_| async move {
+            let token = get_auth_token();
+            if let Some(session_key) = stored_quiz_session() {
+                if let Ok(Some(session)) = get_quiz_session(session_key, token.clone()).await {
+                    if !session.matches.is_empty() {
+                        return Some(session);
+                    }
+                }
+            }
+            match token {
+                Some(token) => get_last_quiz_session(token).await.ok().flatten(),
+                None => None,
+            }
+        },
+    );
+
     view! {
         <div class="quiz-container">
             <h1>"Find Your Perfect Artist"</h1>
 
+            <Suspense fallback=|| ()>
+                {move || last_session.get().flatten().map(|session| view! {
+                    <div class="quiz-resume-banner">
+                        <span>
+                            {format!(
+                                "Welcome back! Your last quiz matched {} artist{}.",
+                                session.matches.len(),
+                                if session.matches.len() == 1 { "" } else { "s" },
+                            )}
+                        </span>
+                        <a href=session.answers.results_url() class="quiz-resume-link">
+                            "View your results"
+                        </a>
+                    </div>
+                })}
+            </Suspense>
+
             <div class="quiz-form-wrapper">
                 <form on:submit=on_submit>
                     // Location Filters Section
@@ -302,6 +429,21 @@ pub fn GetMatchedQuiz() -> impl IntoView {
                                                             />
                                                             <span class="quiz-style-name">{style_name}</span>
                                                             <span class="quiz-style-count">{format!("({})", artist_count)}</span>
+                                                            {move || {
+                                                                let images = style_images(style_id);
+                                                                (!images.is_empty()).then(|| view! {
+                                                                    <div class="quiz-style-examples">
+                                                                        {images.into_iter().map(|image| view! {
+                                                                            <img
+                                                                                class="quiz-style-example"
+                                                                                src=image.image_url
+                                                                                alt=""
+                                                                                loading="lazy"
+                                                                            />
+                                                                        }).collect_view()}
+                                                                    </div>
+                                                                })
+                                                            }}
                                                         </label>
                                                     }
                                                 }).collect_view()}
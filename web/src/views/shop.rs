@@ -1,10 +1,12 @@
 use leptos::prelude::*;
 use leptos_router::hooks::{use_params_map, use_query_map};
+use shared_types::StyleFilter;
 
 use crate::{
     components::{
         loading::LoadingView,
         shop_masonry_gallery::{ShopInstagramPost, ShopMasonryGallery},
+        ExperienceBadge, ShareButton,
     },
     db::entities::{Artist, Style},
     server::{fetch_shop_data, fetch_shop_images_paginated},
@@ -96,7 +98,7 @@ pub fn Shop() -> impl IntoView {
                 let style_filter = if styles.is_empty() {
                     None
                 } else {
-                    Some(styles)
+                    Some(StyleFilter::any(styles))
                 };
                 let token = get_auth_token();
                 fetch_shop_images_paginated(id, style_filter, page, per_page, token)
@@ -127,6 +129,12 @@ pub fn Shop() -> impl IntoView {
 
                             let address = shop_data.location.address.clone().unwrap_or_else(|| String::new());
                             let artists_clone = shop_data.artists.clone();
+                            let price_label = match (shop_data.min_price, shop_data.max_price) {
+                                (Some(min), Some(max)) => Some(format!("${} - ${}", min as i32, max as i32)),
+                                (Some(min), None) => Some(format!("From ${}", min as i32)),
+                                (None, Some(max)) => Some(format!("Up to ${}", max as i32)),
+                                (None, None) => None,
+                            };
 
                             // Create Google Maps directions URL
                             let directions_url = if !address.is_empty() {
@@ -152,6 +160,11 @@ pub fn Shop() -> impl IntoView {
                                                     <div class="shop-location-header">
                                                         {format!("{}, {}", city, state)}
                                                     </div>
+                                                    {price_label.map(|label| view! {
+                                                        <div class="shop-price-header">
+                                                            {label}
+                                                        </div>
+                                                    })}
                                                 </div>
 
                                                 <div class="shop-actions">
@@ -171,6 +184,11 @@ pub fn Shop() -> impl IntoView {
                                                             </a>
                                                         }
                                                     })}
+                                                    <ShareButton
+                                                        target_type="shop"
+                                                        target=Signal::derive(move || shop_id.get().to_string())
+                                                        class="shop-action-share"
+                                                    />
                                                 </div>
                                             </div>
                                         </div>
@@ -188,6 +206,7 @@ pub fn Shop() -> impl IntoView {
                                                                 <a href={format!("/artist/{}", artist.id)}
                                                                    class="shop-artist-chip">
                                                                     {artist_name}
+                                                                    <ExperienceBadge tier=artist.experience_tier />
                                                                 </a>
                                                             }
                                                         }).collect_view()}
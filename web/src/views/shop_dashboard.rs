@@ -0,0 +1,469 @@
+use crate::api_error::{user_message, ApiError};
+use crate::db::entities::{ShopBooking, ShopMembership};
+use crate::server::{respond_to_booking, BookingResponse};
+use crate::server_shops::{
+    add_shop_user, get_my_shops, get_shop_artists, get_shop_calendar, get_shop_requests,
+    get_shop_users, remove_shop_user, SHOP_ROLES,
+};
+use crate::utils::auth::get_auth_token;
+use crate::utils::timezone::{
+    format_date_for_booking, format_time_with_timezone, get_timezone_abbreviation,
+};
+use chrono::{Datelike, Duration, NaiveDate};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use shared_types::BookingStatus;
+
+/// Days shown at once on the shop calendar
+const CALENDAR_DAYS: i64 = 7;
+
+/// Monday of the week `date` is in
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// One account running several artists: the shop's open requests, answered
+/// for whichever artist they came in for, its artists' bookings week by week
+/// and, for owners, who else is on the account. `?shop=<location id>` picks
+/// the shop when the user runs more than one.
+#[component]
+pub fn ShopDashboardPage() -> impl IntoView {
+    let timezone = get_timezone_abbreviation();
+    let navigate = use_navigate();
+    let query = use_query_map();
+
+    let auth_token = RwSignal::new(None::<String>);
+    Effect::new(move |_| {
+        if let Some(token) = get_auth_token() {
+            auth_token.set(Some(token));
+        } else {
+            navigate(
+                &format!(
+                    "/login?return_url={}",
+                    urlencoding::encode("/shop-dashboard")
+                ),
+                Default::default(),
+            );
+        }
+    });
+
+    let shops_resource = Resource::new(
+        move || auth_token.get(),
+        |token| async move {
+            match token {
+                Some(token) => get_my_shops(token).await.unwrap_or_default(),
+                None => vec![],
+            }
+        },
+    );
+    // The shop from the query, or the first one the user runs
+    let shop = Memo::new(move |_| {
+        let shops = shops_resource.get().unwrap_or_default();
+        let wanted = query
+            .read()
+            .get("shop")
+            .and_then(|id| id.parse::<i64>().ok());
+        wanted
+            .and_then(|id| shops.iter().find(|shop| shop.location_id == id).cloned())
+            .or_else(|| shops.first().cloned())
+    });
+
+    let refresh = RwSignal::new(0u32);
+    let selected_artist = RwSignal::new(None::<i32>);
+    let calendar_start = RwSignal::new(None::<NaiveDate>);
+    // Today is the browser's; the server doesn't know the shop's timezone
+    Effect::new(move |_| {
+        calendar_start.set(Some(week_start(chrono::Local::now().date_naive())));
+    });
+
+    let artists_resource = Resource::new(
+        move || (auth_token.get(), shop.get(), refresh.get()),
+        |(token, shop, _)| async move {
+            match (token, shop) {
+                (Some(token), Some(shop)) => get_shop_artists(token, shop.location_id)
+                    .await
+                    .unwrap_or_default(),
+                _ => vec![],
+            }
+        },
+    );
+    let requests_resource = Resource::new(
+        move || (auth_token.get(), shop.get(), refresh.get()),
+        |(token, shop, _)| async move {
+            match (token, shop) {
+                (Some(token), Some(shop)) => get_shop_requests(token, shop.location_id).await,
+                _ => Ok(vec![]),
+            }
+        },
+    );
+    let calendar_resource = Resource::new(
+        move || {
+            (
+                auth_token.get(),
+                shop.get(),
+                calendar_start.get(),
+                refresh.get(),
+            )
+        },
+        |(token, shop, start, _)| async move {
+            match (token, shop, start) {
+                (Some(token), Some(shop), Some(start)) => {
+                    let end = start + Duration::days(CALENDAR_DAYS - 1);
+                    get_shop_calendar(
+                        token,
+                        shop.location_id,
+                        start.format("%Y-%m-%d").to_string(),
+                        end.format("%Y-%m-%d").to_string(),
+                    )
+                    .await
+                }
+                _ => Ok(vec![]),
+            }
+        },
+    );
+
+    let respond_error = RwSignal::new(None::<String>);
+    let on_response = move |result: Result<(), ServerFnError<ApiError>>| match result {
+        Ok(()) => {
+            respond_error.set(None);
+            refresh.update(|v| *v += 1);
+        }
+        Err(e) => respond_error.set(Some(user_message(&e))),
+    };
+    let respond = move |booking_id: i32, status: BookingStatus| {
+        let Some(token) = auth_token.get_untracked() else {
+            return;
+        };
+        let response = BookingResponse {
+            booking_id,
+            status,
+            artist_response: None,
+            estimated_price: None,
+            decline_reason: (status == BookingStatus::Declined)
+                .then(|| "The shop can't take this booking".to_string()),
+            deposit_amount: None,
+        };
+        spawn_local(async move {
+            on_response(respond_to_booking(response, token).await);
+        });
+    };
+
+    let shown = move |bookings: Vec<ShopBooking>| {
+        let artist_id = selected_artist.get();
+        bookings.into_iter().filter(move |booking| {
+            artist_id.is_none() || artist_id == Some(booking.booking.artist_id)
+        })
+    };
+
+    view! {
+        <div class="shop-dashboard">
+            <div class="shop-dashboard__container">
+                <Suspense fallback=|| view! { <p class="shop-dashboard__loading">"Loading..."</p> }>
+                    {move || shops_resource.get().map(|shops| {
+                        if shops.is_empty() {
+                            return view! {
+                                <div class="shop-dashboard__header">
+                                    <h1>"Shop Dashboard"</h1>
+                                    <p class="shop-dashboard__subtitle">
+                                        "You aren't on a shop's account yet. Claim your shop from its page, "
+                                        "or ask its owner to add you."
+                                    </p>
+                                </div>
+                            }.into_any();
+                        }
+                        view! { <ShopHeader shops=shops current=shop /> }.into_any()
+                    })}
+                </Suspense>
+
+                <Show when=move || shop.get().is_some()>
+                    <section class="shop-dashboard__section">
+                        <h2>"Artists"</h2>
+                        <Suspense fallback=|| view! { <p class="shop-dashboard__loading">"Loading..."</p> }>
+                            {move || artists_resource.get().map(|artists| view! {
+                                <div class="shop-dashboard__artists">
+                                    <button
+                                        class="shop-dashboard__artist"
+                                        class:shop-dashboard__artist--selected=move || selected_artist.get().is_none()
+                                        on:click=move |_| selected_artist.set(None)
+                                    >
+                                        <strong>"All artists"</strong>
+                                    </button>
+                                    {artists.into_iter().map(|artist| {
+                                        let artist_id = artist.artist_id;
+                                        view! {
+                                            <button
+                                                class="shop-dashboard__artist"
+                                                class:shop-dashboard__artist--selected=move || selected_artist.get() == Some(artist_id)
+                                                on:click=move |_| selected_artist.set(Some(artist_id))
+                                            >
+                                                <strong>{artist.name.unwrap_or_else(|| "Artist".to_string())}</strong>
+                                                <span>{format!("{} open · {} upcoming", artist.open_requests, artist.upcoming_bookings)}</span>
+                                            </button>
+                                        }
+                                    }).collect_view()}
+                                </div>
+                            })}
+                        </Suspense>
+                    </section>
+
+                    <section class="shop-dashboard__section">
+                        <h2>"Open requests"</h2>
+                        {move || respond_error.get().map(|error| view! {
+                            <div class="error-message">{error}</div>
+                        })}
+                        <Suspense fallback=|| view! { <p class="shop-dashboard__loading">"Loading..."</p> }>
+                            {move || requests_resource.get().map(|result| match result {
+                                Ok(requests) => {
+                                    let rows = shown(requests)
+                                        .map(|request| {
+                                            let booking_id = request.booking.id;
+                                            let status = BookingStatus::parse(&request.booking.status);
+                                            view! {
+                                                <li class="shop-dashboard__request">
+                                                    <div class="shop-dashboard__request-info">
+                                                        <strong>{request.booking.client_name.clone()}</strong>
+                                                        <span class="shop-dashboard__request-artist">
+                                                            {format!("for {}", request.artist_name.clone().unwrap_or_else(|| "Artist".to_string()))}
+                                                        </span>
+                                                        <span class="shop-dashboard__request-when">
+                                                            {format!(
+                                                                "{} at {}",
+                                                                format_date_for_booking(&request.booking.requested_date),
+                                                                format_time_with_timezone(&request.booking.requested_start_time, timezone),
+                                                            )}
+                                                        </span>
+                                                        {request.booking.tattoo_description.clone().map(|description| view! {
+                                                            <p class="shop-dashboard__request-description">{description}</p>
+                                                        })}
+                                                        {(status == Some(BookingStatus::NeedsInfo)).then(|| view! {
+                                                            <span class="shop-dashboard__request-status">"Waiting on the client"</span>
+                                                        })}
+                                                    </div>
+                                                    <div class="shop-dashboard__request-actions">
+                                                        <button
+                                                            class="btn btn-primary"
+                                                            on:click=move |_| respond(booking_id, BookingStatus::Accepted)
+                                                        >
+                                                            "Approve"
+                                                        </button>
+                                                        <button
+                                                            class="btn btn-outline-danger"
+                                                            on:click=move |_| respond(booking_id, BookingStatus::Declined)
+                                                        >
+                                                            "Decline"
+                                                        </button>
+                                                    </div>
+                                                </li>
+                                            }
+                                        })
+                                        .collect::<Vec<_>>();
+                                    if rows.is_empty() {
+                                        view! {
+                                            <p class="shop-dashboard__empty">"No requests waiting."</p>
+                                        }.into_any()
+                                    } else {
+                                        view! { <ul class="shop-dashboard__list">{rows}</ul> }.into_any()
+                                    }
+                                }
+                                Err(e) => view! { <p class="error-message">{e.to_string()}</p> }.into_any(),
+                            })}
+                        </Suspense>
+                    </section>
+
+                    <section class="shop-dashboard__section">
+                        <div class="shop-dashboard__section-header">
+                            <h2>"Calendar"</h2>
+                            <div class="shop-dashboard__week-nav">
+                                <button on:click=move |_| calendar_start.update(|start| {
+                                    *start = start.map(|start| start - Duration::days(CALENDAR_DAYS));
+                                })>"← Previous"</button>
+                                <span>
+                                    {move || calendar_start.get().map(|start| {
+                                        format!("Week of {}", format_date_for_booking(&start.format("%Y-%m-%d").to_string()))
+                                    })}
+                                </span>
+                                <button on:click=move |_| calendar_start.update(|start| {
+                                    *start = start.map(|start| start + Duration::days(CALENDAR_DAYS));
+                                })>"Next →"</button>
+                            </div>
+                        </div>
+                        <Suspense fallback=|| view! { <p class="shop-dashboard__loading">"Loading..."</p> }>
+                            {move || calendar_resource.get().map(|result| match result {
+                                Ok(bookings) => {
+                                    let bookings: Vec<ShopBooking> = shown(bookings).collect();
+                                    let start = calendar_start.get().unwrap_or_default();
+                                    view! {
+                                        <div class="shop-dashboard__calendar">
+                                            {(0..CALENDAR_DAYS).map(|offset| {
+                                                let date = (start + Duration::days(offset)).format("%Y-%m-%d").to_string();
+                                                let day: Vec<ShopBooking> = bookings
+                                                    .iter()
+                                                    .filter(|booking| booking.booking.requested_date == date)
+                                                    .cloned()
+                                                    .collect();
+                                                view! {
+                                                    <div class="shop-dashboard__day">
+                                                        <h3>{format_date_for_booking(&date)}</h3>
+                                                        {day.into_iter().map(|booking| view! {
+                                                            <div class="shop-dashboard__session">
+                                                                <span class="shop-dashboard__session-time">
+                                                                    {format_time_with_timezone(&booking.booking.requested_start_time, timezone)}
+                                                                </span>
+                                                                <strong>{booking.artist_name.unwrap_or_else(|| "Artist".to_string())}</strong>
+                                                                <span>{booking.booking.client_name}</span>
+                                                            </div>
+                                                        }).collect_view()}
+                                                    </div>
+                                                }
+                                            }).collect_view()}
+                                        </div>
+                                    }.into_any()
+                                }
+                                Err(e) => view! { <p class="error-message">{e.to_string()}</p> }.into_any(),
+                            })}
+                        </Suspense>
+                    </section>
+
+                    <Show when=move || shop.get().is_some_and(|shop| shop.role == "owner")>
+                        <ShopUsersSection auth_token=auth_token shop=shop />
+                    </Show>
+                </Show>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ShopHeader(shops: Vec<ShopMembership>, current: Memo<Option<ShopMembership>>) -> impl IntoView {
+    let navigate = use_navigate();
+    let many = shops.len() > 1;
+
+    view! {
+        <div class="shop-dashboard__header">
+            <h1>
+                {move || current.get().and_then(|shop| shop.name).unwrap_or_else(|| "Shop Dashboard".to_string())}
+            </h1>
+            <p class="shop-dashboard__subtitle">
+                "Requests and bookings for every artist at your shop"
+            </p>
+            {many.then(|| view! {
+                <select
+                    class="shop-dashboard__shop-select"
+                    prop:value=move || current.get().map(|shop| shop.location_id.to_string()).unwrap_or_default()
+                    on:change=move |ev| {
+                        navigate(
+                            &format!("/shop-dashboard?shop={}", event_target_value(&ev)),
+                            Default::default(),
+                        );
+                    }
+                >
+                    {shops.iter().map(|shop| view! {
+                        <option value=shop.location_id.to_string()>
+                            {shop.name.clone().unwrap_or_else(|| format!("Shop {}", shop.location_id))}
+                        </option>
+                    }).collect_view()}
+                </select>
+            })}
+        </div>
+    }
+}
+
+/// Who is on the shop's account, for owners to add and remove people
+#[component]
+fn ShopUsersSection(
+    auth_token: RwSignal<Option<String>>,
+    shop: Memo<Option<ShopMembership>>,
+) -> impl IntoView {
+    let version = RwSignal::new(0u32);
+    let email = RwSignal::new(String::new());
+    let role = RwSignal::new("manager".to_string());
+    let error = RwSignal::new(None::<String>);
+
+    let users_resource = Resource::new(
+        move || (auth_token.get(), shop.get(), version.get()),
+        |(token, shop, _)| async move {
+            match (token, shop) {
+                (Some(token), Some(shop)) => get_shop_users(token, shop.location_id)
+                    .await
+                    .unwrap_or_default(),
+                _ => vec![],
+            }
+        },
+    );
+
+    let on_result = move |result: Result<(), ServerFnError>| match result {
+        Ok(()) => {
+            error.set(None);
+            email.set(String::new());
+            version.update(|v| *v += 1);
+        }
+        Err(e) => error.set(Some(e.to_string())),
+    };
+
+    let add = move |_| {
+        let (Some(token), Some(shop)) = (auth_token.get_untracked(), shop.get_untracked()) else {
+            return;
+        };
+        let (email, role) = (email.get_untracked(), role.get_untracked());
+        spawn_local(async move {
+            on_result(add_shop_user(token, shop.location_id, email, role).await);
+        });
+    };
+
+    let remove = move |user_id: i64| {
+        let (Some(token), Some(shop)) = (auth_token.get_untracked(), shop.get_untracked()) else {
+            return;
+        };
+        spawn_local(async move {
+            on_result(
+                remove_shop_user(token, shop.location_id, user_id)
+                    .await
+                    .map(|_| ()),
+            );
+        });
+    };
+
+    view! {
+        <section class="shop-dashboard__section">
+            <h2>"Shop account"</h2>
+            <p class="shop-dashboard__hint">
+                "Managers can answer requests and see the calendar for every artist here. "
+                "Owners can also edit the shop and its roster, and add people."
+            </p>
+            {move || error.get().map(|error| view! { <div class="error-message">{error}</div> })}
+            <Suspense fallback=|| ()>
+                {move || users_resource.get().map(|users| view! {
+                    <ul class="shop-dashboard__list">
+                        {users.into_iter().map(|user| {
+                            let user_id = user.user_id;
+                            view! {
+                                <li class="shop-dashboard__user">
+                                    <span>{user.email}</span>
+                                    <span class="shop-dashboard__role">{user.role}</span>
+                                    <button class="btn btn-outline-danger" on:click=move |_| remove(user_id)>
+                                        "Remove"
+                                    </button>
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                })}
+            </Suspense>
+            <div class="shop-dashboard__add-user">
+                <input
+                    type="email"
+                    placeholder="Their account email"
+                    prop:value=move || email.get()
+                    on:input=move |ev| email.set(event_target_value(&ev))
+                />
+                <select prop:value=move || role.get() on:change=move |ev| role.set(event_target_value(&ev))>
+                    {SHOP_ROLES.iter().map(|name| view! {
+                        <option value=*name>{name.to_string()}</option>
+                    }).collect_view()}
+                </select>
+                <button class="btn btn-primary" on:click=add>"Add"</button>
+            </div>
+        </section>
+    }
+}
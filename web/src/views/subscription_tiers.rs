@@ -1,4 +1,4 @@
-use crate::server::{create_artist_subscription, get_subscription_tiers};
+use crate::server::{get_subscription_tiers, payments::create_subscription_checkout};
 use leptos::{prelude::*, task::spawn_local};
 use leptos_router::components::A;
 use serde::{Deserialize, Serialize};
@@ -33,8 +33,24 @@ pub fn SubscriptionTiersPage() -> impl IntoView {
     let error_message = RwSignal::new(Option::<String>::None);
     let success_message = RwSignal::new(Option::<String>::None);
 
-    // Mock artist ID for now - this would come from authentication context
-    let artist_id = 1; // TODO: Get from authentication context
+    let get_auth_token = move || -> Option<String> {
+        #[cfg(feature = "hydrate")]
+        {
+            use wasm_bindgen::prelude::*;
+
+            #[wasm_bindgen]
+            extern "C" {
+                #[wasm_bindgen(js_namespace = localStorage)]
+                fn getItem(key: &str) -> Option<String>;
+            }
+
+            getItem("tatteau_auth_token")
+        }
+        #[cfg(not(feature = "hydrate"))]
+        {
+            None
+        }
+    };
 
     let tiers_resource = Resource::new(
         move || {},
@@ -52,35 +68,27 @@ pub fn SubscriptionTiersPage() -> impl IntoView {
 
     let subscribe_action = move |_| {
         if let Some(tier_id) = selected_tier.get() {
+            let Some(token) = get_auth_token() else {
+                error_message.set(Some("Please log in to subscribe.".to_string()));
+                return;
+            };
             loading.set(true);
             error_message.set(None);
 
             spawn_local(async move {
-                match create_artist_subscription(
-                    artist_id,
-                    tier_id,
-                    "active".to_string(),
-                    Some("mock_payment".to_string()),
-                )
-                .await
-                {
-                    Ok(_) => {
-                        success_message.set(Some(
-                            "Subscription created successfully! Redirecting to artist dashboard..."
-                                .to_string(),
-                        ));
-
-                        // Redirect to artist dashboard immediately for now
-                        // TODO: Add proper delay redirect later
+                match create_subscription_checkout(token, tier_id).await {
+                    Ok(checkout_url) => {
+                        success_message.set(Some("Redirecting to secure checkout...".to_string()));
+
                         if let Some(window) = web_sys::window() {
-                            let _ = window.location().set_href("/artist/dashboard");
+                            let _ = window.location().set_href(&checkout_url);
                         }
                     }
                     Err(e) => {
                         error_message.set(Some(format!("Subscription failed: {}", e)));
+                        loading.set(false);
                     }
                 }
-                loading.set(false);
             });
         }
     };
@@ -156,7 +164,7 @@ pub fn SubscriptionTiersPage() -> impl IntoView {
             <Show when=move || selected_tier.get().is_some()>
                 <div class="subscription-tiers-actions">
                     <div class="subscription-tiers-payment-notice">
-                        <p>"Payment processing is not yet implemented. This is a placeholder for the subscription flow."</p>
+                        <p>"You'll be taken to Stripe to pay securely. Your plan renews monthly."</p>
                     </div>
 
                     <div class="subscription-tiers-action-buttons">
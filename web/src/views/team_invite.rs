@@ -0,0 +1,89 @@
+use crate::db::entities::TeamAccess;
+use crate::server_team::accept_team_invitation;
+use crate::utils::auth::get_auth_token;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_router::components::A;
+use leptos_router::hooks::use_params_map;
+
+/// Where an invited assistant accepts an artist's invitation
+#[component]
+pub fn TeamInvitePage() -> impl IntoView {
+    let params = use_params_map();
+    let code = move || params.read().get("code").unwrap_or_default();
+
+    let auth_token = RwSignal::new(None::<String>);
+    let accepting = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+    let joined = RwSignal::new(None::<TeamAccess>);
+
+    // Invitees sign in (or sign up) first, then come back here
+    Effect::new(move |_| match get_auth_token() {
+        Some(token) => auth_token.set(Some(token)),
+        None => {
+            if let Some(window) = web_sys::window() {
+                let redirect = format!("/team/join/{}", code());
+                let _ = window
+                    .location()
+                    .set_href(&format!("/login?redirect={}", redirect));
+            }
+        }
+    });
+
+    let accept = move |_| {
+        let Some(token) = auth_token.get_untracked() else {
+            return;
+        };
+        let code = code();
+        accepting.set(true);
+        spawn_local(async move {
+            match accept_team_invitation(token, code).await {
+                Ok(access) => {
+                    error.set(None);
+                    joined.set(Some(access));
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+            accepting.set(false);
+        });
+    };
+
+    view! {
+        <div class="team-invite-page">
+            <div class="team-invite-card">
+                {move || match joined.get() {
+                    Some(access) => view! {
+                        <h1>{format!("You're on {}'s team", access.artist_name)}</h1>
+                        <p>
+                            {format!(
+                                "You can help with: {}.",
+                                access.permissions.join(", ")
+                            )}
+                        </p>
+                        <A href="/artist/dashboard" attr:class="btn btn-primary">
+                            "Go to Dashboard"
+                        </A>
+                    }
+                    .into_any(),
+                    None => view! {
+                        <h1>"Join an artist's team"</h1>
+                        <p>
+                            "You've been invited to help manage an artist's account on Tatteau. Accept with the account for the email the invitation was sent to."
+                        </p>
+                        {move || error.get().map(|error| view! {
+                            <div class="error-message">{error}</div>
+                        })}
+                        <button
+                            class="btn btn-primary"
+                            disabled=move || accepting.get() || auth_token.get().is_none()
+                            on:click=accept
+                        >
+                            {move || if accepting.get() { "Joining..." } else { "Accept Invitation" }}
+                        </button>
+                    }
+                    .into_any(),
+                }}
+            </div>
+        </div>
+    }
+}